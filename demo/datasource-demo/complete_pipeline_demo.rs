@@ -427,6 +427,10 @@ fn stream_record_to_json_value(record: &StreamRecord) -> serde_json::Value {
             FieldValue::Null => serde_json::Value::Null,
             FieldValue::Date(d) => serde_json::Value::String(d.to_string()),
             FieldValue::Timestamp(ts) => serde_json::Value::String(ts.to_string()),
+            FieldValue::TimestampTz(ts) => serde_json::Value::String(ts.to_rfc3339()),
+            FieldValue::Bytes(_) | FieldValue::Uuid(_) | FieldValue::Time(_) => {
+                serde_json::Value::String(field_value.to_display_string())
+            }
             FieldValue::Decimal(d) => serde_json::Value::String(d.to_string()),
             FieldValue::Array(arr) => {
                 let json_arr: Vec<serde_json::Value> =
@@ -476,6 +480,10 @@ fn stream_record_field_to_json_value(field_value: &FieldValue) -> serde_json::Va
         FieldValue::Null => serde_json::Value::Null,
         FieldValue::Date(d) => serde_json::Value::String(d.to_string()),
         FieldValue::Timestamp(ts) => serde_json::Value::String(ts.to_string()),
+        FieldValue::TimestampTz(ts) => serde_json::Value::String(ts.to_rfc3339()),
+        FieldValue::Bytes(_) | FieldValue::Uuid(_) | FieldValue::Time(_) => {
+            serde_json::Value::String(field_value.to_display_string())
+        }
         FieldValue::Decimal(d) => serde_json::Value::String(d.to_string()),
         FieldValue::Array(arr) => {
             let json_arr: Vec<serde_json::Value> =
@@ -527,7 +535,7 @@ fn json_value_to_stream_record(json_value: &serde_json::Value) -> StreamRecord {
     }
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         timestamp: SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap()
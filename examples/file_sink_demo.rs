@@ -81,7 +81,7 @@ async fn demo_json_sink() -> Result<(), Box<dyn std::error::Error>> {
         );
 
         let record = StreamRecord {
-            fields,
+            fields: fields.into(),
             timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as i64,
             offset: i,
             partition: 0,
@@ -152,7 +152,7 @@ async fn demo_csv_sink() -> Result<(), Box<dyn std::error::Error>> {
         );
 
         let record = StreamRecord {
-            fields,
+            fields: fields.into(),
             timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as i64,
             offset: i,
             partition: 0,
@@ -224,7 +224,7 @@ async fn demo_batch_writing() -> Result<(), Box<dyn std::error::Error>> {
             );
 
             records.push(StreamRecord {
-                fields,
+                fields: fields.into(),
                 timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as i64,
                 offset: record_id,
                 partition: 0,
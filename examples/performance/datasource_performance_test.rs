@@ -71,7 +71,7 @@ impl TestMessage {
         );
 
         StreamRecord {
-            fields,
+            fields: fields.into(),
             timestamp: self.timestamp as i64,
             offset: self.id as i64,
             partition: 0,
@@ -304,13 +304,17 @@ async fn benchmark_schema_discovery() -> PerformanceStats {
         for record in &sample_records {
             for (field_name, field_value) in &record.fields {
                 let data_type = match field_value {
-                    FieldValue::Integer(_) => DataType::Integer,
+                    FieldValue::Integer(_) => DataType::BigInt,
                     FieldValue::Float(_) => DataType::Float,
                     FieldValue::String(_) => DataType::String,
                     FieldValue::Boolean(_) => DataType::Boolean,
                     FieldValue::ScaledInteger(_, _) => DataType::Float, // Represent as Float for now
-                    FieldValue::Date(_) => DataType::String, // Represent as String for now
+                    FieldValue::Date(_) => DataType::Date,
+                    FieldValue::Time(_) => DataType::Time,
+                    FieldValue::Bytes(_) => DataType::Bytes,
+                    FieldValue::Uuid(_) => DataType::Uuid,
                     FieldValue::Timestamp(_) => DataType::Timestamp,
+                    FieldValue::TimestampTz(_) => DataType::TimestampTz,
                     FieldValue::Decimal(_) => DataType::Float, // Represent as Float for now
                     FieldValue::Array(_) => DataType::Array(Box::new(DataType::String)), // Generic array
                     FieldValue::Map(_) => {
@@ -19,7 +19,7 @@ fn create_test_record(id: u64) -> StreamRecord {
     fields.insert("symbol".to_string(), FieldValue::String("AAPL".to_string()));
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         timestamp: chrono::Utc::now().timestamp_millis(),
         offset: id as i64,
         partition: 0,
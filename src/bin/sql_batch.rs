@@ -265,7 +265,7 @@ async fn generate_sample_data_for_query(
         );
 
         records.push(StreamRecord {
-            fields,
+            fields: fields.into(),
             timestamp: 1704110400 + (i as i64 * 3600),
             offset: i as i64,
             partition: 0,
@@ -3,34 +3,46 @@
 //! Comprehensive validation tool for FerrisStreams SQL queries and applications.
 //! Checks for:
 //! - SQL parsing correctness
+//! - Column references and types against the declared stream/table schemas
 //! - Missing source/sink configurations  
 //! - Configuration completeness
 //! - Syntax compatibility
 //! - Performance warnings
+//!
+//! With `--format`, `--check` or `--write` it formats the files instead, see
+//! [`format_script`].
 
 use ferrisstreams::ferris::sql::{
     ast::StreamingQuery,
     config::with_clause_parser::WithClauseParser,
+    error::diagnostics::{render_all, Diagnostic, Span},
+    formatter::format_script,
     query_analyzer::{
         DataSinkRequirement, DataSinkType, DataSourceRequirement, DataSourceType, QueryAnalyzer,
     },
+    semantic_analyzer::{SchemaCatalog, SemanticAnalyzer},
     StreamingSqlParser,
 };
 use log::info;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Detailed parsing error with location information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParsingError {
     pub message: String,
+    /// Zero-based line in the file
     pub line: usize,
+    /// Zero-based column (in characters) in the line
     pub column: usize,
+    /// Byte offset in the file
     pub position: usize,
-    pub context_lines: Vec<String>,
-    pub error_indicator: String,
+    /// Suggested fixes, such as "did you mean" notes
+    pub help: Vec<String>,
+    /// The error rendered with an excerpt of the file, the way rustc reports errors
+    pub rendered: String,
 }
 
 /// Validation result for a single SQL query
@@ -41,6 +53,7 @@ pub struct QueryValidationResult {
     pub start_line: usize,
     pub is_valid: bool,
     pub parsing_errors: Vec<ParsingError>,
+    pub semantic_errors: Vec<ParsingError>,
     pub configuration_errors: Vec<String>,
     pub warnings: Vec<String>,
     pub sources_found: Vec<String>,
@@ -133,19 +146,40 @@ impl SqlValidator {
     }
 
     /// Validate a single SQL query string
+    ///
+    /// Schemas declared by the query are added to `schema_catalog` for the queries after it.
     pub fn validate_query(
         &self,
         query: &str,
         query_index: usize,
-        start_line: usize,
-        full_content: &str,
+        schema_catalog: &mut SchemaCatalog,
     ) -> QueryValidationResult {
+        let parsed = self.parser.parse_with_diagnostics(query);
+        let span = Span::new(0, query.len());
+        self.validate_statement(parsed, query, span, query_index, "<query>", schema_catalog)
+    }
+
+    /// Validate a parsed statement spanning `span` of `source`
+    ///
+    /// Errors are reported with positions in `source`, which `origin` names.
+    fn validate_statement(
+        &self,
+        parsed: Result<StreamingQuery, Vec<Diagnostic>>,
+        source: &str,
+        span: Span,
+        query_index: usize,
+        origin: &str,
+        schema_catalog: &mut SchemaCatalog,
+    ) -> QueryValidationResult {
+        let query = &source[span.start..span.end];
+        let start_line = source[..span.start].matches('\n').count();
         let mut result = QueryValidationResult {
             query_text: query.to_string(),
             query_index,
             start_line,
             is_valid: true,
             parsing_errors: Vec::new(),
+            semantic_errors: Vec::new(),
             configuration_errors: Vec::new(),
             warnings: Vec::new(),
             sources_found: Vec::new(),
@@ -156,19 +190,31 @@ impl SqlValidator {
             performance_warnings: Vec::new(),
         };
 
-        // Step 1: Parse the SQL query
-        let parsed_query = match self.parser.parse(query) {
+        // Step 1: Parse the SQL query, keeping every error the parser recovered from
+        let parsed_query = match parsed {
             Ok(q) => q,
-            Err(e) => {
-                let parsing_error =
-                    self.create_parsing_error(&e.to_string(), query, start_line, full_content);
-                result.parsing_errors.push(parsing_error);
+            Err(diagnostics) => {
+                for diagnostic in &diagnostics {
+                    let parsing_error = self.create_parsing_error(diagnostic, source, origin);
+                    result.parsing_errors.push(parsing_error);
+                }
                 result.is_valid = false;
                 return result;
             }
         };
 
-        // Step 2: Analyze query structure
+        // Step 2: Resolve columns and check types against the known schemas
+        let semantic_analysis = SemanticAnalyzer::new(schema_catalog)
+            .with_sql(query)
+            .analyze(&parsed_query);
+        for error in &semantic_analysis.errors {
+            let diagnostic = error.to_diagnostic(query).offset(span.start);
+            let semantic_error = self.create_parsing_error(&diagnostic, source, origin);
+            result.semantic_errors.push(semantic_error);
+        }
+        schema_catalog.apply_statement(&parsed_query, &semantic_analysis);
+
+        // Step 3: Analyze query structure
         match self.analyzer.analyze(&parsed_query) {
             Ok(analysis) => {
                 // Extract sources and sinks
@@ -191,16 +237,19 @@ impl SqlValidator {
             }
         }
 
-        // Step 3: Check for syntax compatibility issues
+        // Step 4: Check for syntax compatibility issues
         self.check_syntax_compatibility(&parsed_query, &mut result);
 
-        // Step 4: Performance analysis
+        // Step 5: Performance analysis
         if self.check_performance {
             self.analyze_performance_implications(&parsed_query, &mut result);
         }
 
-        // Step 5: Final validation
-        if !result.parsing_errors.is_empty() || !result.configuration_errors.is_empty() {
+        // Step 6: Final validation
+        if !result.parsing_errors.is_empty()
+            || !result.semantic_errors.is_empty()
+            || !result.configuration_errors.is_empty()
+        {
             result.is_valid = false;
         }
 
@@ -213,123 +262,24 @@ impl SqlValidator {
         result
     }
 
-    /// Create a detailed parsing error with location information
+    /// Create an error report from a diagnostic located in `source`
     fn create_parsing_error(
         &self,
-        error_msg: &str,
-        query: &str,
-        query_start_line: usize,
-        full_content: &str,
+        diagnostic: &Diagnostic,
+        source: &str,
+        origin: &str,
     ) -> ParsingError {
-        let position = self.extract_position(error_msg).unwrap_or(0);
-        let (line, column) = self.position_to_line_column(query, position);
-        let absolute_line = query_start_line + line;
-
-        let context_lines =
-            self.get_error_context(full_content, absolute_line, column, position, query);
-        let error_indicator = self.create_error_indicator(query, position, column);
+        let position = diagnostic.span.map_or(0, |span| span.start);
+        let (line, column) = diagnostic.line_column(source).unwrap_or((1, 1));
 
         ParsingError {
-            message: error_msg.to_string(),
-            line: absolute_line,
-            column,
+            message: diagnostic.message.clone(),
+            line: line - 1,
+            column: column - 1,
             position,
-            context_lines,
-            error_indicator,
-        }
-    }
-
-    /// Extract position from error message
-    fn extract_position(&self, error_msg: &str) -> Option<usize> {
-        if let Some(pos_start) = error_msg.find("position ") {
-            let pos_str = &error_msg[pos_start + 9..];
-            if let Some(pos_end) = pos_str.find(":") {
-                pos_str[..pos_end].parse().ok()
-            } else {
-                pos_str.split_whitespace().next()?.parse().ok()
-            }
-        } else {
-            None
-        }
-    }
-
-    /// Convert character position to line and column
-    fn position_to_line_column(&self, text: &str, position: usize) -> (usize, usize) {
-        let mut line = 0;
-        let mut column = 0;
-        let mut current_pos = 0;
-
-        for ch in text.chars() {
-            if current_pos >= position {
-                break;
-            }
-            if ch == '\n' {
-                line += 1;
-                column = 0;
-            } else {
-                column += 1;
-            }
-            current_pos += ch.len_utf8();
-        }
-
-        (line, column)
-    }
-
-    /// Get context lines around the error
-    fn get_error_context(
-        &self,
-        content: &str,
-        error_line: usize,
-        error_column: usize,
-        _position: usize,
-        _query: &str,
-    ) -> Vec<String> {
-        let lines: Vec<&str> = content.lines().collect();
-        let start_line = error_line.saturating_sub(2);
-        let end_line = std::cmp::min(error_line + 3, lines.len());
-
-        let mut context = Vec::new();
-
-        for (i, line_content) in lines[start_line..end_line].iter().enumerate() {
-            let line_number = start_line + i + 1;
-            let prefix = if line_number == error_line + 1 {
-                format!("→{:4} ", line_number)
-            } else {
-                format!(" {:4} ", line_number)
-            };
-
-            context.push(format!("{}{}", prefix, line_content));
+            help: diagnostic.help.clone(),
+            rendered: diagnostic.render(source, origin),
         }
-
-        // Add error indicator line
-        if error_line < lines.len() {
-            let indicator_spaces = " ".repeat(6 + error_column);
-            let indicator = format!("{}^ ERROR HERE", indicator_spaces);
-            context.push(indicator);
-        }
-
-        context
-    }
-
-    /// Create visual error indicator
-    fn create_error_indicator(&self, query: &str, position: usize, _column: usize) -> String {
-        let chars: Vec<char> = query.chars().collect();
-        if position >= chars.len() {
-            return format!("Error at end of query (position {})", position);
-        }
-
-        let error_char = chars.get(position).unwrap_or(&' ');
-        let start = position.saturating_sub(20);
-        let end = if position + 20 < chars.len() {
-            position + 20
-        } else {
-            chars.len()
-        };
-
-        let before: String = chars[start..position].iter().collect();
-        let after: String = chars[position + 1..end].iter().collect();
-
-        format!("{}⟨{}⟩{}", before, error_char, after)
     }
 
     /// Validate source configurations
@@ -631,13 +581,21 @@ impl SqlValidator {
         // Extract application name from comments
         result.application_name = self.extract_application_name(&content);
 
-        // Split into individual queries with line tracking
-        let queries_with_lines = self.split_sql_queries_with_lines(&content);
-        result.total_queries = queries_with_lines.len();
-
-        // Validate each query
-        for (index, (query, start_line)) in queries_with_lines.iter().enumerate() {
-            let query_result = self.validate_query(query, index, *start_line, &content);
+        // Parse every statement, recovering from errors so that all of them are reported
+        let statements = self.parser.parse_script(&content);
+        result.total_queries = statements.len();
+
+        // Validate each query; earlier DDL describes the sources of later queries
+        let mut schema_catalog = SchemaCatalog::new();
+        for (index, statement) in statements.into_iter().enumerate() {
+            let query_result = self.validate_statement(
+                statement.result,
+                &content,
+                statement.span,
+                index,
+                &result.file_path,
+                &mut schema_catalog,
+            );
             if query_result.is_valid {
                 result.valid_queries += 1;
             }
@@ -668,58 +626,6 @@ impl SqlValidator {
         None
     }
 
-    /// Split SQL content into individual queries with line number tracking
-    fn split_sql_queries_with_lines(&self, content: &str) -> Vec<(String, usize)> {
-        let mut queries = Vec::new();
-        let mut current_query = String::new();
-        let mut query_start_line = 0;
-        let mut current_line = 0;
-        let mut in_query = false;
-
-        for line in content.lines() {
-            current_line += 1;
-            let trimmed = line.trim();
-
-            // Skip empty lines and full-line comments
-            if trimmed.is_empty() || trimmed.starts_with("--") {
-                continue;
-            }
-
-            // Mark the start of a new query
-            if !in_query {
-                query_start_line = current_line;
-                in_query = true;
-            }
-
-            current_query.push_str(line);
-            current_query.push('\n');
-
-            // Check for query termination
-            if trimmed.ends_with(";") || trimmed.contains("EMIT CHANGES") {
-                if !current_query.trim().is_empty() {
-                    queries.push((current_query.trim().to_string(), query_start_line - 1));
-                }
-                current_query.clear();
-                in_query = false;
-            }
-        }
-
-        // Add any remaining query
-        if !current_query.trim().is_empty() {
-            queries.push((current_query.trim().to_string(), query_start_line - 1));
-        }
-
-        queries
-    }
-
-    /// Split SQL content into individual queries (legacy method)
-    fn split_sql_queries(&self, content: &str) -> Vec<String> {
-        self.split_sql_queries_with_lines(content)
-            .into_iter()
-            .map(|(query, _line)| query)
-            .collect()
-    }
-
     /// Build configuration summary from all queries
     fn build_configuration_summary(
         &self,
@@ -810,19 +716,85 @@ impl SqlValidator {
 
     /// Validate multiple SQL files
     pub fn validate_directory(&self, dir_path: &Path) -> Vec<ApplicationValidationResult> {
-        let mut results = Vec::new();
+        sql_files(dir_path)
+            .iter()
+            .map(|path| self.validate_application(path))
+            .collect()
+    }
+}
 
-        if let Ok(entries) = fs::read_dir(dir_path) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.extension().and_then(|s| s.to_str()) == Some("sql") {
-                    results.push(self.validate_application(&path));
-                }
+/// The `.sql` files of a directory
+fn sql_files(dir_path: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    if let Ok(entries) = fs::read_dir(dir_path) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|s| s.to_str()) == Some("sql") {
+                files.push(path);
             }
         }
+    }
+    files
+}
 
-        results
+/// What to do with the formatted text of a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FormatMode {
+    /// Print it to stdout (`--format`)
+    Print,
+    /// List files whose text differs from it (`--check`)
+    Check,
+    /// Rewrite files in place (`--write`)
+    Write,
+}
+
+/// Format SQL files, returning whether all of them parsed (and, for `--check`, were
+/// already formatted)
+fn format_files(files: &[PathBuf], mode: FormatMode) -> bool {
+    let mut success = true;
+    for path in files {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("Failed to read {}: {}", path.display(), e);
+                success = false;
+                continue;
+            }
+        };
+
+        let formatted = match format_script(&content) {
+            Ok(formatted) => formatted,
+            Err(diagnostics) => {
+                eprintln!(
+                    "{}",
+                    render_all(&diagnostics, &content, &path.display().to_string())
+                );
+                success = false;
+                continue;
+            }
+        };
+
+        match mode {
+            FormatMode::Print => print!("{}", formatted),
+            FormatMode::Check => {
+                if formatted != content {
+                    println!("Would reformat: {}", path.display());
+                    success = false;
+                }
+            }
+            FormatMode::Write => {
+                if formatted != content {
+                    if let Err(e) = fs::write(path, &formatted) {
+                        eprintln!("Failed to write {}: {}", path.display(), e);
+                        success = false;
+                    } else {
+                        println!("Formatted: {}", path.display());
+                    }
+                }
+            }
+        }
     }
+    success
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -831,13 +803,37 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args: Vec<String> = std::env::args().collect();
     if args.len() < 2 {
         eprintln!(
-            "Usage: {} <sql_file_or_directory> [--strict] [--no-performance]",
+            "Usage: {} <sql_file_or_directory> [--strict] [--no-performance] [--format|--check|--write]",
             args[0]
         );
         std::process::exit(1);
     }
 
     let path = Path::new(&args[1]);
+
+    let format_mode = if args.contains(&"--write".to_string()) {
+        Some(FormatMode::Write)
+    } else if args.contains(&"--check".to_string()) {
+        Some(FormatMode::Check)
+    } else if args.contains(&"--format".to_string()) {
+        Some(FormatMode::Print)
+    } else {
+        None
+    };
+    if let Some(mode) = format_mode {
+        let files = if path.is_dir() {
+            sql_files(path)
+        } else if path.is_file() {
+            vec![path.to_path_buf()]
+        } else {
+            eprintln!("Path does not exist: {}", path.display());
+            std::process::exit(1);
+        };
+        if !format_files(&files, mode) {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
     let strict_mode = args.contains(&"--strict".to_string());
     let check_performance = !args.contains(&"--no-performance".to_string());
 
@@ -946,20 +942,12 @@ fn print_validation_result(result: &ApplicationValidationResult) {
 
             if !query_result.parsing_errors.is_empty() {
                 println!("  🚫 Parsing Errors:");
-                for error in &query_result.parsing_errors {
-                    println!(
-                        "    • {} (Line {}, Column {})",
-                        error.message,
-                        error.line + 1,
-                        error.column + 1
-                    );
-                    println!("      📍 Location in file:");
-                    for context_line in &error.context_lines {
-                        println!("        {}", context_line);
-                    }
-                    println!("      🔍 Error context: {}", error.error_indicator);
-                    println!();
-                }
+                print_errors(&query_result.parsing_errors);
+            }
+
+            if !query_result.semantic_errors.is_empty() {
+                println!("  🧭 Semantic Errors:");
+                print_errors(&query_result.semantic_errors);
             }
 
             if !query_result.configuration_errors.is_empty() {
@@ -1042,3 +1030,13 @@ fn print_validation_result(result: &ApplicationValidationResult) {
         }
     }
 }
+
+/// Print errors as rustc-style diagnostics, indented under their heading
+fn print_errors(errors: &[ParsingError]) {
+    for error in errors {
+        for line in error.rendered.lines() {
+            println!("    {}", line);
+        }
+        println!();
+    }
+}
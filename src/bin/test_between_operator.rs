@@ -1,4 +1,5 @@
 // Simple test binary for BETWEEN operator
+use ferrisstreams::ferris::sql::ast::NodeSpan;
 use ferrisstreams::ferris::sql::{
     ast::{Expr, LiteralValue},
     execution::{expression::evaluator::ExpressionEvaluator, types::FieldValue, StreamRecord},
@@ -33,10 +34,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let record = StreamRecord::new(fields);
 
     let expr = Expr::Between {
-        expr: Box::new(Expr::Column("value".to_string())),
-        low: Box::new(Expr::Literal(LiteralValue::Integer(10))),
-        high: Box::new(Expr::Literal(LiteralValue::Integer(100))),
+        expr: Box::new(Expr::Column("value".to_string(), NodeSpan::default())),
+        low: Box::new(Expr::Literal(
+            LiteralValue::Integer(10),
+            NodeSpan::default(),
+        )),
+        high: Box::new(Expr::Literal(
+            LiteralValue::Integer(100),
+            NodeSpan::default(),
+        )),
         negated: false,
+        span: NodeSpan::default(),
     };
 
     match ExpressionEvaluator::evaluate_expression(&expr, &record) {
@@ -47,10 +55,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Test 4: NOT BETWEEN evaluation
     println!("\n4. Testing NOT BETWEEN expression evaluation:");
     let expr_not = Expr::Between {
-        expr: Box::new(Expr::Column("value".to_string())),
-        low: Box::new(Expr::Literal(LiteralValue::Integer(10))),
-        high: Box::new(Expr::Literal(LiteralValue::Integer(100))),
+        expr: Box::new(Expr::Column("value".to_string(), NodeSpan::default())),
+        low: Box::new(Expr::Literal(
+            LiteralValue::Integer(10),
+            NodeSpan::default(),
+        )),
+        high: Box::new(Expr::Literal(
+            LiteralValue::Integer(100),
+            NodeSpan::default(),
+        )),
         negated: true,
+        span: NodeSpan::default(),
     };
 
     match ExpressionEvaluator::evaluate_expression(&expr_not, &record) {
@@ -65,10 +80,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let financial_record = StreamRecord::new(financial_fields);
 
     let financial_expr = Expr::Between {
-        expr: Box::new(Expr::Column("price".to_string())),
-        low: Box::new(Expr::Literal(LiteralValue::Decimal("50.00".to_string()))), // $50.00
-        high: Box::new(Expr::Literal(LiteralValue::Decimal("100.00".to_string()))), // $100.00
+        expr: Box::new(Expr::Column("price".to_string(), NodeSpan::default())),
+        low: Box::new(Expr::Literal(
+            LiteralValue::Decimal("50.00".to_string()),
+            NodeSpan::default(),
+        )), // $50.00
+        high: Box::new(Expr::Literal(
+            LiteralValue::Decimal("100.00".to_string()),
+            NodeSpan::default(),
+        )), // $100.00
         negated: false,
+        span: NodeSpan::default(),
     };
 
     match ExpressionEvaluator::evaluate_expression(&financial_expr, &financial_record) {
@@ -86,10 +108,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let string_record = StreamRecord::new(string_fields);
 
     let string_expr = Expr::Between {
-        expr: Box::new(Expr::Column("name".to_string())),
-        low: Box::new(Expr::Literal(LiteralValue::String("Alice".to_string()))),
-        high: Box::new(Expr::Literal(LiteralValue::String("David".to_string()))),
+        expr: Box::new(Expr::Column("name".to_string(), NodeSpan::default())),
+        low: Box::new(Expr::Literal(
+            LiteralValue::String("Alice".to_string()),
+            NodeSpan::default(),
+        )),
+        high: Box::new(Expr::Literal(
+            LiteralValue::String("David".to_string()),
+            NodeSpan::default(),
+        )),
         negated: false,
+        span: NodeSpan::default(),
     };
 
     match ExpressionEvaluator::evaluate_expression(&string_expr, &string_record) {
@@ -100,10 +129,20 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Test 7: Mixed type comparison
     println!("\n7. Testing BETWEEN with mixed types:");
     let mixed_expr = Expr::Between {
-        expr: Box::new(Expr::Literal(LiteralValue::Integer(75))),
-        low: Box::new(Expr::Literal(LiteralValue::Float(50.0))),
-        high: Box::new(Expr::Literal(LiteralValue::Float(100.0))),
+        expr: Box::new(Expr::Literal(
+            LiteralValue::Integer(75),
+            NodeSpan::default(),
+        )),
+        low: Box::new(Expr::Literal(
+            LiteralValue::Float(50.0),
+            NodeSpan::default(),
+        )),
+        high: Box::new(Expr::Literal(
+            LiteralValue::Float(100.0),
+            NodeSpan::default(),
+        )),
         negated: false,
+        span: NodeSpan::default(),
     };
 
     match ExpressionEvaluator::evaluate_expression(&mixed_expr, &record) {
@@ -118,10 +157,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let null_record = StreamRecord::new(null_fields);
 
     let null_expr = Expr::Between {
-        expr: Box::new(Expr::Column("value".to_string())),
-        low: Box::new(Expr::Literal(LiteralValue::Integer(10))),
-        high: Box::new(Expr::Literal(LiteralValue::Integer(100))),
+        expr: Box::new(Expr::Column("value".to_string(), NodeSpan::default())),
+        low: Box::new(Expr::Literal(
+            LiteralValue::Integer(10),
+            NodeSpan::default(),
+        )),
+        high: Box::new(Expr::Literal(
+            LiteralValue::Integer(100),
+            NodeSpan::default(),
+        )),
         negated: false,
+        span: NodeSpan::default(),
     };
 
     match ExpressionEvaluator::evaluate_expression(&null_expr, &null_record) {
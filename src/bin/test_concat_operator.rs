@@ -1,4 +1,5 @@
 // Simple test binary to verify || concatenation operator
+use ferrisstreams::ferris::sql::ast::NodeSpan;
 use ferrisstreams::ferris::sql::{
     ast::{BinaryOperator, Expr, LiteralValue, SelectField, StreamingQuery},
     execution::{expression::evaluator::ExpressionEvaluator, types::FieldValue, StreamRecord},
@@ -48,9 +49,16 @@ fn main() {
 
     // Test string || string
     let expr = Expr::BinaryOp {
-        left: Box::new(Expr::Literal(LiteralValue::String("Hello".to_string()))),
+        left: Box::new(Expr::Literal(
+            LiteralValue::String("Hello".to_string()),
+            NodeSpan::default(),
+        )),
         op: BinaryOperator::Concat,
-        right: Box::new(Expr::Literal(LiteralValue::String(" World".to_string()))),
+        right: Box::new(Expr::Literal(
+            LiteralValue::String(" World".to_string()),
+            NodeSpan::default(),
+        )),
+        span: NodeSpan::default(),
     };
 
     match ExpressionEvaluator::evaluate_expression_value(&expr, &record) {
@@ -77,9 +85,16 @@ fn main() {
     // Test 3: Mixed type concatenation
     println!("\n=== Test 3: Mixed Type Concatenation ===");
     let expr2 = Expr::BinaryOp {
-        left: Box::new(Expr::Literal(LiteralValue::String("Value: ".to_string()))),
+        left: Box::new(Expr::Literal(
+            LiteralValue::String("Value: ".to_string()),
+            NodeSpan::default(),
+        )),
         op: BinaryOperator::Concat,
-        right: Box::new(Expr::Literal(LiteralValue::Integer(42))),
+        right: Box::new(Expr::Literal(
+            LiteralValue::Integer(42),
+            NodeSpan::default(),
+        )),
+        span: NodeSpan::default(),
     };
 
     match ExpressionEvaluator::evaluate_expression_value(&expr2, &record) {
@@ -106,9 +121,16 @@ fn main() {
     // Test 4: User's specific example pattern
     println!("\n=== Test 4: User Example Pattern ===");
     let expr3 = Expr::BinaryOp {
-        left: Box::new(Expr::Literal(LiteralValue::String("exported_".to_string()))),
+        left: Box::new(Expr::Literal(
+            LiteralValue::String("exported_".to_string()),
+            NodeSpan::default(),
+        )),
         op: BinaryOperator::Concat,
-        right: Box::new(Expr::Literal(LiteralValue::Integer(1693910400))), // Mock timestamp
+        right: Box::new(Expr::Literal(
+            LiteralValue::Integer(1693910400),
+            NodeSpan::default(),
+        )),
+        span: NodeSpan::default(), // Mock timestamp
     };
 
     match ExpressionEvaluator::evaluate_expression_value(&expr3, &record) {
@@ -132,9 +154,13 @@ fn main() {
     // Test 5: NULL handling
     println!("\n=== Test 5: NULL Handling ===");
     let expr4 = Expr::BinaryOp {
-        left: Box::new(Expr::Literal(LiteralValue::String("Hello".to_string()))),
+        left: Box::new(Expr::Literal(
+            LiteralValue::String("Hello".to_string()),
+            NodeSpan::default(),
+        )),
         op: BinaryOperator::Concat,
-        right: Box::new(Expr::Literal(LiteralValue::Null)),
+        right: Box::new(Expr::Literal(LiteralValue::Null, NodeSpan::default())),
+        span: NodeSpan::default(),
     };
 
     match ExpressionEvaluator::evaluate_expression_value(&expr4, &record) {
@@ -37,14 +37,14 @@ impl MockKafkaReader {
 
         let records = vec![
             StreamRecord {
-                fields: fields1,
+                fields: fields1.into(),
                 headers: HashMap::new(),
                 timestamp: chrono::Utc::now().timestamp_millis(),
                 offset: 1001,
                 partition: 0,
             },
             StreamRecord {
-                fields: fields2,
+                fields: fields2.into(),
                 headers: HashMap::new(),
                 timestamp: chrono::Utc::now().timestamp_millis(),
                 offset: 1002,
@@ -208,7 +208,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         );
 
         let test_record = StreamRecord {
-            fields: test_record_fields,
+            fields: test_record_fields.into(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             offset: 1,
             partition: 0,
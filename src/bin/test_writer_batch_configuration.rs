@@ -236,7 +236,7 @@ fn create_test_records(count: usize) -> Vec<StreamRecord> {
         );
 
         records.push(StreamRecord {
-            fields,
+            fields: fields.into(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             offset: i as i64,
             partition: 0,
@@ -683,7 +683,13 @@ impl FileWriter {
                 let mut csv_row = Vec::new();
                 for value in record.fields.values() {
                     // Simple CSV serialization - in production use proper CSV library
-                    let value_str = format!("{:?}", value); // TODO: Proper CSV escaping
+                    // Bytes are written base64-encoded, UUIDs and times as canonical text
+                    let value_str = match value {
+                        FieldValue::Bytes(_) | FieldValue::Uuid(_) | FieldValue::Time(_) => {
+                            value.to_display_string()
+                        }
+                        _ => format!("{:?}", value), // TODO: Proper CSV escaping
+                    };
                     csv_row.push(value_str);
                 }
                 Ok(format!("{}\n", csv_row.join(&self.config.csv_delimiter)).into_bytes())
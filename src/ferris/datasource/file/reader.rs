@@ -1,9 +1,11 @@
 //! File Data Reader Implementation
 
-use crate::ferris::datasource::traits::DataReader;
+use crate::ferris::datasource::traits::{DataReader, PushdownFailure, ReaderPushdown};
 use crate::ferris::datasource::types::SourceOffset;
 use crate::ferris::datasource::{BatchConfig, BatchStrategy};
+use crate::ferris::serialization::json_codec::{is_json_object, parse_projected_json_object};
 use crate::ferris::sql::execution::types::{FieldValue, StreamRecord};
+use crate::ferris::sql::execution::RecordSchemaCache;
 use async_trait::async_trait;
 use std::collections::HashMap;
 use std::error::Error;
@@ -31,6 +33,12 @@ pub struct FileReader {
     csv_headers: Option<Vec<String>>, // Store CSV column headers
     // State for adaptive batching
     adaptive_state: FileAdaptiveBatchState,
+    // Projection and filter pushed down by the query optimizer
+    pushdown: Option<ReaderPushdown>,
+    // Records the pushed-down filter could not be evaluated on, not yet taken
+    pushdown_failures: Vec<PushdownFailure>,
+    // Layout shared by the records read, so field names are stored once per file shape
+    record_schemas: RecordSchemaCache,
 }
 
 /// State tracking for adaptive batch sizing in file reading
@@ -100,6 +108,9 @@ impl FileReader {
             eof_reached: false,
             csv_headers: None,
             adaptive_state: FileAdaptiveBatchState::new(initial_size),
+            pushdown: None,
+            pushdown_failures: Vec::new(),
+            record_schemas: RecordSchemaCache::new(),
         };
 
         reader.initialize_files().await?;
@@ -308,26 +319,29 @@ impl FileReader {
     fn parse_csv_line(&self, line: &str) -> Result<StreamRecord, Box<dyn Error + Send + Sync>> {
         let parsed_fields = self.parse_csv_fields(line)?;
 
-        let mut fields = HashMap::new();
+        let mut fields = self.record_schemas.builder();
 
         // Use header names if available, otherwise fall back to column indices
         for (i, field_value) in parsed_fields.iter().enumerate() {
-            let field_name = if let Some(ref headers) = self.csv_headers {
-                headers
-                    .get(i)
-                    .cloned()
-                    .unwrap_or_else(|| format!("column_{}", i))
-            } else {
-                format!("column_{}", i)
+            let generated_name;
+            let field_name = match self.csv_headers.as_ref().and_then(|headers| headers.get(i)) {
+                Some(header) => header.as_str(),
+                None => {
+                    generated_name = format!("column_{}", i);
+                    generated_name.as_str()
+                }
             };
+            if !self.keeps_field(field_name) {
+                continue;
+            }
 
             // Smart type inference with financial precision detection
-            let field_val = self.infer_field_type(&field_name, field_value);
-            fields.insert(field_name, field_val);
+            let field_val = self.infer_field_type(field_name, field_value);
+            fields.push(field_name, field_val);
         }
 
         Ok(StreamRecord {
-            fields,
+            fields: fields.finish(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             offset: self.current_position as i64,
             partition: 0,
@@ -468,11 +482,12 @@ impl FileReader {
                     }
 
                     // Parse JSON line
-                    let json_value: serde_json::Value = serde_json::from_str(line)
+                    let json_value = self
+                        .parse_json_line(line)
                         .map_err(|e| FileDataSourceError::JsonParseError(e.to_string()))?;
 
                     // Convert JSON to FieldValue map
-                    let mut fields = HashMap::new();
+                    let mut fields = self.record_schemas.builder();
                     match json_value {
                         serde_json::Value::Object(obj) => {
                             for (key, val) in obj {
@@ -488,7 +503,7 @@ impl FileReader {
                                     serde_json::Value::Null => FieldValue::Null,
                                     _ => FieldValue::String(val.to_string()),
                                 };
-                                fields.insert(key, field_val);
+                                fields.push(key, field_val);
                             }
                         }
                         _ => {
@@ -505,12 +520,12 @@ impl FileReader {
                                 serde_json::Value::Null => FieldValue::Null,
                                 _ => FieldValue::String(json_value.to_string()),
                             };
-                            fields.insert("data".to_string(), field_val);
+                            fields.push("data", field_val);
                         }
                     }
 
                     let record = StreamRecord {
-                        fields,
+                        fields: fields.finish(),
                         timestamp: chrono::Utc::now().timestamp_millis(),
                         offset: self.current_position as i64,
                         partition: 0,
@@ -577,29 +592,18 @@ impl FileReader {
 #[async_trait]
 impl DataReader for FileReader {
     async fn read(&mut self) -> Result<Vec<StreamRecord>, Box<dyn Error + Send + Sync>> {
-        if !self.batch_config.enable_batching {
-            return self.read_single().await;
-        }
+        loop {
+            let records = self.read_batch().await?;
+            let pushdown = match &self.pushdown {
+                Some(pushdown) if pushdown.filter.is_some() && !records.is_empty() => pushdown,
+                _ => return Ok(records),
+            };
 
-        match &self.batch_config.strategy {
-            BatchStrategy::FixedSize(size) => self.read_fixed_size(*size).await,
-            BatchStrategy::TimeWindow(duration) => self.read_time_window(*duration).await,
-            BatchStrategy::AdaptiveSize {
-                min_size,
-                max_size,
-                target_latency,
-            } => {
-                self.read_adaptive(*min_size, *max_size, *target_latency)
-                    .await
-            }
-            BatchStrategy::MemoryBased(max_bytes) => self.read_memory_based(*max_bytes).await,
-            BatchStrategy::LowLatency {
-                max_batch_size,
-                max_wait_time,
-                eager_processing,
-            } => {
-                self.read_low_latency(*max_batch_size, *max_wait_time, *eager_processing)
-                    .await
+            // Keep reading until a record passes or fails the pushed-down filter, or the
+            // data runs out
+            let records = pushdown.filter(&self.config.path, records, &mut self.pushdown_failures);
+            if !records.is_empty() || !self.pushdown_failures.is_empty() {
+                return Ok(records);
             }
         }
     }
@@ -612,8 +616,12 @@ impl DataReader for FileReader {
 
     async fn seek(&mut self, offset: SourceOffset) -> Result<(), Box<dyn Error + Send + Sync>> {
         // Extract position from SourceOffset
-        let position = match offset {
-            SourceOffset::File { byte_offset, .. } => byte_offset,
+        let (path, position, line_number) = match offset {
+            SourceOffset::File {
+                path,
+                byte_offset,
+                line_number,
+            } => (path, byte_offset, line_number),
             _ => {
                 return Err(Box::new(FileDataSourceError::InvalidPath(
                     "Invalid offset type for file source".to_string(),
@@ -621,16 +629,51 @@ impl DataReader for FileReader {
             }
         };
 
+        // A position in another file of a glob reopens that file first
+        if let Some(index) = self.file_list.iter().position(|file| *file == path) {
+            if index != self.current_file_index || self.current_file.is_none() {
+                self.current_file_index = index;
+                self.finished = false;
+                self.open_next_file().await?;
+            }
+        }
+
+        // The header row lies before any later position, so read it before skipping past it
+        if self.config.format == FileFormat::Csv && self.csv_headers.is_none() && position > 0 {
+            if let Some(reader) = self.current_file.as_mut() {
+                let mut header = String::new();
+                reader
+                    .read_line(&mut header)
+                    .map_err(|e| FileDataSourceError::IoError(e.to_string()))?;
+                self.csv_headers = Some(self.parse_csv_fields(header.trim())?);
+            }
+        }
+
         if let Some(reader) = self.current_file.as_mut() {
             reader
                 .seek(SeekFrom::Start(position))
                 .map_err(|e| FileDataSourceError::IoError(e.to_string()))?;
             self.current_position = position;
+            self.line_number = line_number as usize;
+            self.eof_reached = false;
         }
 
         Ok(())
     }
 
+    fn position(&mut self) -> Option<SourceOffset> {
+        if self.config.format == FileFormat::Json {
+            return None;
+        }
+        let path = self.file_list.get(self.current_file_index)?.clone();
+        let byte_offset = self.current_file.as_mut()?.stream_position().ok()?;
+        Some(SourceOffset::File {
+            path,
+            byte_offset,
+            line_number: self.line_number as u64,
+        })
+    }
+
     async fn has_more(&self) -> Result<bool, Box<dyn Error + Send + Sync>> {
         // If we have a max_records limit, check that first
         if let Some(max) = self.config.max_records {
@@ -661,9 +704,69 @@ impl DataReader for FileReader {
         // If not finished and not at EOF, we likely have more data
         Ok(!self.eof_reached || self.config.watch_for_changes)
     }
+
+    fn push_down(&mut self, pushdown: ReaderPushdown) -> bool {
+        self.pushdown = Some(pushdown);
+        true
+    }
+
+    fn take_pushdown_failures(&mut self) -> Vec<PushdownFailure> {
+        std::mem::take(&mut self.pushdown_failures)
+    }
 }
 
 impl FileReader {
+    /// Read a batch according to the configured batch strategy
+    async fn read_batch(&mut self) -> Result<Vec<StreamRecord>, Box<dyn Error + Send + Sync>> {
+        if !self.batch_config.enable_batching {
+            return self.read_single().await;
+        }
+
+        match &self.batch_config.strategy {
+            BatchStrategy::FixedSize(size) => self.read_fixed_size(*size).await,
+            BatchStrategy::TimeWindow(duration) => self.read_time_window(*duration).await,
+            BatchStrategy::AdaptiveSize {
+                min_size,
+                max_size,
+                target_latency,
+            } => {
+                self.read_adaptive(*min_size, *max_size, *target_latency)
+                    .await
+            }
+            BatchStrategy::MemoryBased(max_bytes) => self.read_memory_based(*max_bytes).await,
+            BatchStrategy::LowLatency {
+                max_batch_size,
+                max_wait_time,
+                eager_processing,
+            } => {
+                self.read_low_latency(*max_batch_size, *max_wait_time, *eager_processing)
+                    .await
+            }
+        }
+    }
+
+    /// Check if a field is referenced by the query (every field is without pushdown)
+    fn keeps_field(&self, name: &str) -> bool {
+        self.pushdown
+            .as_ref()
+            .map_or(true, |pushdown| pushdown.keeps_field(name))
+    }
+
+    /// Parse a JSON line, skipping object keys outside the pushed-down projection
+    fn parse_json_line(&self, line: &str) -> Result<serde_json::Value, serde_json::Error> {
+        match self
+            .pushdown
+            .as_ref()
+            .and_then(|pushdown| pushdown.projection.as_ref())
+        {
+            Some(projection) if is_json_object(line.as_bytes()) => {
+                parse_projected_json_object(line.as_bytes(), projection)
+                    .map(serde_json::Value::Object)
+            }
+            _ => serde_json::from_str(line),
+        }
+    }
+
     /// Read a single record (when batching is disabled)
     async fn read_single(&mut self) -> Result<Vec<StreamRecord>, Box<dyn Error + Send + Sync>> {
         if let Some(record) = self.read_one_record().await? {
@@ -729,12 +832,15 @@ impl FileReader {
             let chunk_buffer = String::with_capacity(buffer_size);
             let mut lines_in_chunk = 0;
 
-            while lines_in_chunk < batch_size && chunk_buffer.len() < buffer_size {
+            // Only the lines the batch still needs, so none are read past and dropped
+            let lines_wanted = batch_size - records.len();
+            while lines_in_chunk < lines_wanted && chunk_buffer.len() < buffer_size {
                 let mut line = String::new();
                 match reader.read_line(&mut line) {
                     Ok(0) => {
-                        // EOF reached
+                        // EOF reached, continue with the next file
                         self.eof_reached = true;
+                        self.current_file_index += 1;
                         self.open_next_file().await?;
                         break;
                     }
@@ -1079,8 +1185,6 @@ impl FileReader {
         // Parse CSV fields (simple split for now - could be enhanced with proper CSV parsing)
         let fields_raw: Vec<&str> = line.split(',').map(|f| f.trim()).collect();
 
-        let mut fields = HashMap::new();
-
         // Handle headers
         if self.csv_headers.is_none() && self.config.format == FileFormat::Csv {
             // First line is headers
@@ -1088,26 +1192,26 @@ impl FileReader {
             return Ok(None); // Skip header line
         }
 
-        // Use headers if available, otherwise generate field names
-        let headers = match &self.csv_headers {
-            Some(h) => h.clone(),
-            None => (0..fields_raw.len())
-                .map(|i| format!("field_{}", i))
-                .collect(),
-        };
-
-        // Create field map
+        // Create field map, using headers if available and generated names otherwise
+        let mut fields = self.record_schemas.builder();
         for (i, value) in fields_raw.iter().enumerate() {
-            let field_name = headers
-                .get(i)
-                .cloned()
-                .unwrap_or_else(|| format!("field_{}", i));
+            let generated_name;
+            let field_name = match self.csv_headers.as_ref().and_then(|headers| headers.get(i)) {
+                Some(header) => header.as_str(),
+                None => {
+                    generated_name = format!("field_{}", i);
+                    generated_name.as_str()
+                }
+            };
+            if !self.keeps_field(field_name) {
+                continue;
+            }
             let field_value = self.infer_field_type_simple(value);
-            fields.insert(field_name, field_value);
+            fields.push(field_name, field_value);
         }
 
         let record = StreamRecord {
-            fields,
+            fields: fields.finish(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             offset: self.current_position as i64,
             partition: 0,
@@ -1128,7 +1232,7 @@ impl FileReader {
         }
 
         // Parse JSON
-        let json_value: serde_json::Value = match serde_json::from_str(line) {
+        let json_value = match self.parse_json_line(line) {
             Ok(v) => v,
             Err(e) => {
                 eprintln!("Failed to parse JSON line: {}, error: {}", line, e);
@@ -1137,16 +1241,16 @@ impl FileReader {
         };
 
         // Convert JSON to fields map
-        let mut fields = HashMap::new();
+        let mut fields = self.record_schemas.builder();
         if let serde_json::Value::Object(obj) = json_value {
             for (key, value) in obj {
                 let field_value = self.json_value_to_field_value(&value);
-                fields.insert(key, field_value);
+                fields.push(key, field_value);
             }
         }
 
         let record = StreamRecord {
-            fields,
+            fields: fields.finish(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             offset: self.current_position as i64,
             partition: 0,
@@ -1214,10 +1318,14 @@ impl FileReader {
             FieldValue::Boolean(_) => 1,
             FieldValue::ScaledInteger(_, _) => 16,
             FieldValue::Timestamp(_) => 16,
+            FieldValue::TimestampTz(_) => 24,
             FieldValue::Date(_) => 8,
             FieldValue::Decimal(_) => 16,
             FieldValue::Null => 0,
             FieldValue::Interval { .. } => 16,
+            FieldValue::Bytes(bytes) => 24 + bytes.len(),
+            FieldValue::Uuid(_) => 16,
+            FieldValue::Time(_) => 8,
             FieldValue::Array(arr) => {
                 24 + arr
                     .iter()
@@ -349,7 +349,8 @@ impl DataSink for KafkaDataSink {
     }
 
     fn supports_upsert(&self) -> bool {
-        false // Kafka is append-only, upserts require application logic
+        // Keyed writes and tombstones only give upsert semantics on compacted topics
+        KafkaDataWriter::is_compacted_topic(&self.config)
     }
 
     fn metadata(&self) -> SinkMetadata {
@@ -357,7 +358,7 @@ impl DataSink for KafkaDataSink {
             sink_type: "kafka".to_string(),
             version: "1.0.0".to_string(),
             supports_transactions: true,
-            supports_upsert: false,
+            supports_upsert: self.supports_upsert(),
             supports_schema_evolution: true,
             capabilities: vec![
                 "exactly_once".to_string(),
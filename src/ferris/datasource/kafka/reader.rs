@@ -1,12 +1,15 @@
 //! Unified Kafka data reader implementation
 
-use crate::ferris::datasource::{BatchConfig, BatchStrategy, DataReader, SourceOffset};
+use crate::ferris::datasource::{
+    BatchConfig, BatchStrategy, DataReader, PushdownFailure, ReaderPushdown, SourceOffset,
+};
 use crate::ferris::kafka::{serialization::StringSerializer, KafkaConsumer};
 
 // Removed unused imports - AvroSerializer and ProtoSerializer don't exist
 // Using AvroCodec and ProtobufCodec directly instead
 use crate::ferris::serialization::{json_codec::JsonCodec, SerializationCodec};
 use crate::ferris::sql::execution::types::{FieldValue, StreamRecord};
+use crate::ferris::sql::execution::{RecordFields, RecordSchemaCache};
 use async_trait::async_trait;
 use chrono;
 use log::info;
@@ -19,12 +22,20 @@ pub use crate::ferris::kafka::serialization_format::SerializationFormat;
 
 /// Unified Kafka DataReader that handles all serialization formats
 pub struct KafkaDataReader {
-    consumer:
-        KafkaConsumer<String, HashMap<String, FieldValue>, StringSerializer, SerializationCodec>,
+    consumer: KafkaConsumer<String, RecordFields, StringSerializer, SerializationCodec>,
+    topic: String,
+    // Next offset to read from each partition, after the records handed out
+    positions: HashMap<i32, i64>,
     batch_config: BatchConfig,
     // State for adaptive batching
     current_batch_start: Option<Instant>,
     adaptive_state: AdaptiveBatchState,
+    // Projection and filter pushed down by the query optimizer
+    pushdown: Option<ReaderPushdown>,
+    // Records the pushed-down filter could not be evaluated on, not yet taken
+    pushdown_failures: Vec<PushdownFailure>,
+    // Layout of the records handed out: the decoded fields plus the message key
+    record_schemas: RecordSchemaCache,
 }
 
 /// State tracking for adaptive batch sizing
@@ -148,9 +159,14 @@ impl KafkaDataReader {
 
         Ok(Self {
             consumer,
+            topic,
+            positions: HashMap::new(),
             batch_config,
             current_batch_start: None,
             adaptive_state: AdaptiveBatchState::new(initial_size),
+            pushdown: None,
+            pushdown_failures: Vec::new(),
+            record_schemas: RecordSchemaCache::new(),
         })
     }
 
@@ -224,30 +240,17 @@ impl KafkaDataReader {
 #[async_trait]
 impl DataReader for KafkaDataReader {
     async fn read(&mut self) -> Result<Vec<StreamRecord>, Box<dyn Error + Send + Sync>> {
-        if !self.batch_config.enable_batching {
-            return self.read_single().await;
+        let records = self.read_batch().await?;
+        for record in &records {
+            self.positions.insert(record.partition, record.offset + 1);
         }
 
-        match &self.batch_config.strategy {
-            BatchStrategy::FixedSize(size) => self.read_fixed_size(*size).await,
-            BatchStrategy::TimeWindow(duration) => self.read_time_window(*duration).await,
-            BatchStrategy::AdaptiveSize {
-                min_size,
-                max_size,
-                target_latency,
-            } => {
-                self.read_adaptive(*min_size, *max_size, *target_latency)
-                    .await
-            }
-            BatchStrategy::MemoryBased(max_bytes) => self.read_memory_based(*max_bytes).await,
-            BatchStrategy::LowLatency {
-                max_batch_size,
-                max_wait_time,
-                eager_processing,
-            } => {
-                self.read_low_latency(*max_batch_size, *max_wait_time, *eager_processing)
-                    .await
+        // Records failing a pushed-down filter never reach the query
+        match &self.pushdown {
+            Some(pushdown) if pushdown.filter.is_some() => {
+                Ok(pushdown.filter(&self.topic, records, &mut self.pushdown_failures))
             }
+            _ => Ok(records),
         }
     }
 
@@ -258,18 +261,27 @@ impl DataReader for KafkaDataReader {
     }
 
     async fn seek(&mut self, offset: SourceOffset) -> Result<(), Box<dyn Error + Send + Sync>> {
-        match offset {
-            SourceOffset::Kafka {
-                partition: _,
-                offset: _,
-            } => {
-                // Note: ferris_streams KafkaConsumer doesn't directly expose seek functionality
-                // This would need to be implemented by accessing the underlying rdkafka consumer
-                // For now, return an error indicating this is not yet implemented
-                Err("Seek operation not yet implemented for ferris_streams KafkaConsumer".into())
-            }
-            _ => Err("Invalid offset type for Kafka source".into()),
+        let offsets = match offset {
+            SourceOffset::Kafka { partition, offset } => HashMap::from([(partition, offset)]),
+            SourceOffset::KafkaPartitions { offsets } => offsets,
+            _ => return Err("Invalid offset type for Kafka source".into()),
+        };
+        // Assigning the partitions directly takes them out of group rebalancing, so the
+        // reader stays on the offsets it resumed from
+        self.consumer
+            .assign_offsets(&self.topic, &offsets)
+            .map_err(|e| Box::new(e) as Box<dyn Error + Send + Sync>)?;
+        self.positions = offsets;
+        Ok(())
+    }
+
+    fn position(&mut self) -> Option<SourceOffset> {
+        if self.positions.is_empty() {
+            return None;
         }
+        Some(SourceOffset::KafkaPartitions {
+            offsets: self.positions.clone(),
+        })
     }
 
     async fn has_more(&self) -> Result<bool, Box<dyn Error + Send + Sync>> {
@@ -297,9 +309,51 @@ impl DataReader for KafkaDataReader {
     fn supports_transactions(&self) -> bool {
         true
     }
+
+    fn push_down(&mut self, pushdown: ReaderPushdown) -> bool {
+        // JSON skips unprojected keys while parsing; other formats drop them after decoding
+        self.consumer
+            .value_serializer_mut()
+            .set_projection(pushdown.projection.clone());
+        self.pushdown = Some(pushdown);
+        true
+    }
+
+    fn take_pushdown_failures(&mut self) -> Vec<PushdownFailure> {
+        std::mem::take(&mut self.pushdown_failures)
+    }
 }
 
 impl KafkaDataReader {
+    /// Read a batch according to the configured batch strategy
+    async fn read_batch(&mut self) -> Result<Vec<StreamRecord>, Box<dyn Error + Send + Sync>> {
+        if !self.batch_config.enable_batching {
+            return self.read_single().await;
+        }
+
+        match &self.batch_config.strategy {
+            BatchStrategy::FixedSize(size) => self.read_fixed_size(*size).await,
+            BatchStrategy::TimeWindow(duration) => self.read_time_window(*duration).await,
+            BatchStrategy::AdaptiveSize {
+                min_size,
+                max_size,
+                target_latency,
+            } => {
+                self.read_adaptive(*min_size, *max_size, *target_latency)
+                    .await
+            }
+            BatchStrategy::MemoryBased(max_bytes) => self.read_memory_based(*max_bytes).await,
+            BatchStrategy::LowLatency {
+                max_batch_size,
+                max_wait_time,
+                eager_processing,
+            } => {
+                self.read_low_latency(*max_batch_size, *max_wait_time, *eager_processing)
+                    .await
+            }
+        }
+    }
+
     /// Apply BatchConfig settings to Kafka consumer configuration
     fn apply_batch_config_to_consumer(
         consumer_config: &mut crate::ferris::kafka::consumer_config::ConsumerConfig,
@@ -629,15 +683,23 @@ impl KafkaDataReader {
     /// Helper method to create a StreamRecord from a Kafka message
     fn create_stream_record(
         &self,
-        mut message: crate::ferris::kafka::message::Message<String, HashMap<String, FieldValue>>,
+        mut message: crate::ferris::kafka::message::Message<String, RecordFields>,
     ) -> Result<StreamRecord, Box<dyn Error + Send + Sync>> {
-        let mut fields = message.take_value();
+        // The codec shares one layout across messages; adding the key to it record by
+        // record would copy that layout, so the reader keeps its own one with the key
+        let mut builder = self.record_schemas.builder();
+        builder.append(message.take_value());
 
         // Add message key to fields map if present
         if let Some(key) = message.take_key() {
-            fields.insert("key".to_string(), FieldValue::String(key));
+            builder.push("key", FieldValue::String(key));
         } else {
-            fields.insert("key".to_string(), FieldValue::Null);
+            builder.push("key", FieldValue::Null);
+        }
+        let mut fields = builder.finish();
+
+        if let Some(pushdown) = &self.pushdown {
+            pushdown.project(&mut fields);
         }
 
         Ok(StreamRecord {
@@ -660,10 +722,14 @@ impl KafkaDataReader {
             FieldValue::Boolean(_) => 1,
             FieldValue::ScaledInteger(_, _) => 16,
             FieldValue::Timestamp(_) => 16,
+            FieldValue::TimestampTz(_) => 24,
             FieldValue::Date(_) => 8,
             FieldValue::Decimal(_) => 16,
             FieldValue::Null => 0,
             FieldValue::Interval { .. } => 16,
+            FieldValue::Bytes(bytes) => 24 + bytes.len(),
+            FieldValue::Uuid(_) => 16,
+            FieldValue::Time(_) => 8,
             FieldValue::Array(arr) => {
                 24 + arr
                     .iter()
@@ -30,6 +30,7 @@ pub struct KafkaDataWriter {
     key_field: Option<String>, // Field name to use as message key
     avro_codec: Option<AvroCodec>,
     protobuf_codec: Option<ProtobufCodec>,
+    compacted: bool, // Whether the topic's cleanup.policy includes compaction
 }
 
 impl KafkaDataWriter {
@@ -79,7 +80,7 @@ impl KafkaDataWriter {
         // Extract schema based on format
         let schema = Self::extract_schema_from_properties(&format, properties)?;
 
-        Self::create_with_schema_validation_and_batch_config(
+        let mut writer = Self::create_with_schema_validation_and_batch_config(
             brokers,
             topic,
             format,
@@ -88,7 +89,9 @@ impl KafkaDataWriter {
             &HashMap::new(),
             None,
         )
-        .await
+        .await?;
+        writer.compacted = Self::is_compacted_topic(properties);
+        Ok(writer)
     }
 
     /// Create from HashMap properties with batch configuration optimizations
@@ -117,7 +120,7 @@ impl KafkaDataWriter {
         // Extract schema based on format
         let schema = Self::extract_schema_from_properties(&format, properties)?;
 
-        Self::create_with_schema_validation_and_batch_config(
+        let mut writer = Self::create_with_schema_validation_and_batch_config(
             brokers,
             topic,
             format,
@@ -126,7 +129,22 @@ impl KafkaDataWriter {
             properties,
             Some(batch_config),
         )
-        .await
+        .await?;
+        writer.compacted = Self::is_compacted_topic(properties);
+        Ok(writer)
+    }
+
+    /// Whether the configured `cleanup.policy` compacts the topic, so that a
+    /// keyed write replaces the previous value for that key.
+    pub fn is_compacted_topic(properties: &HashMap<String, String>) -> bool {
+        properties
+            .get("cleanup.policy")
+            .or_else(|| properties.get("topic.cleanup.policy"))
+            .is_some_and(|policy| {
+                policy
+                    .split(',')
+                    .any(|p| p.trim().eq_ignore_ascii_case("compact"))
+            })
     }
 
     /// Internal method with schema validation and batch configuration support
@@ -186,6 +204,7 @@ impl KafkaDataWriter {
             key_field: key_field.or(Some("key".to_string())), // Default to "key" field
             avro_codec,
             protobuf_codec,
+            compacted: false,
         };
 
         log::info!(
@@ -409,7 +428,7 @@ impl KafkaDataWriter {
         if let Some(codec) = &self.avro_codec {
             // Use proper Avro serialization with just the fields (no metadata)
             codec
-                .serialize(&record.fields)
+                .serialize_fields(&record.fields)
                 .map_err(|e| Box::new(e) as Box<dyn Error + Send + Sync>)
         } else {
             // Fallback to JSON if codec not available
@@ -425,7 +444,7 @@ impl KafkaDataWriter {
         if let Some(codec) = &self.protobuf_codec {
             // Use proper Protobuf serialization with just the fields (no metadata)
             codec
-                .serialize(&record.fields)
+                .serialize_fields(&record.fields)
                 .map_err(|e| Box::new(e) as Box<dyn Error + Send + Sync>)
         } else {
             // Fallback to JSON if codec not available
@@ -496,20 +515,19 @@ impl KafkaDataWriter {
             .map(|(k, v)| (k.clone(), v.as_bytes().to_vec()))
             .collect()
     }
-}
 
-#[async_trait]
-impl DataWriter for KafkaDataWriter {
-    async fn write(&mut self, record: StreamRecord) -> Result<(), Box<dyn Error + Send + Sync>> {
+    /// Serialize and send a record with an explicit message key
+    async fn send_record(
+        &mut self,
+        record: StreamRecord,
+        key: Option<String>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
         log::debug!(
             "KafkaDataWriter: Sending record to topic '{}', format={:?}",
             self.topic,
             self.format
         );
 
-        // Extract key for partitioning
-        let key = self.extract_key(&record);
-
         // Serialize payload based on format
         let payload = self.serialize_payload(&record)?;
 
@@ -559,6 +577,15 @@ impl DataWriter for KafkaDataWriter {
             }
         }
     }
+}
+
+#[async_trait]
+impl DataWriter for KafkaDataWriter {
+    async fn write(&mut self, record: StreamRecord) -> Result<(), Box<dyn Error + Send + Sync>> {
+        // Extract key for partitioning
+        let key = self.extract_key(&record);
+        self.send_record(record, key).await
+    }
 
     async fn write_batch(
         &mut self,
@@ -613,11 +640,11 @@ impl DataWriter for KafkaDataWriter {
 
     async fn update(
         &mut self,
-        _key: &str,
+        key: &str,
         record: StreamRecord,
     ) -> Result<(), Box<dyn Error + Send + Sync>> {
-        // Kafka doesn't have native update semantics - treat as write
-        self.write(record).await
+        // Keyed write: on a compacted topic the latest record per key wins
+        self.send_record(record, Some(key.to_string())).await
     }
 
     async fn delete(&mut self, key: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
@@ -723,4 +750,9 @@ impl DataWriter for KafkaDataWriter {
     fn supports_transactions(&self) -> bool {
         true
     }
+
+    fn supports_upsert(&self) -> bool {
+        // Keyed writes only replace earlier values once the log is compacted
+        self.compacted
+    }
 }
@@ -44,7 +44,7 @@ pub mod types;
 
 // Re-export core types from config.rs
 pub use config::{BatchConfig, BatchStrategy, FileFormat, SinkConfig, SourceConfig};
-pub use traits::{DataReader, DataSink, DataSource, DataWriter};
+pub use traits::{DataReader, DataSink, DataSource, DataWriter, PushdownFailure, ReaderPushdown};
 pub use types::{DataSourceError, SinkMetadata, SourceMetadata, SourceOffset};
 
 // Re-export specific implementations
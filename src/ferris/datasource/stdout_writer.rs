@@ -191,4 +191,8 @@ impl DataWriter for StdoutWriter {
     fn supports_transactions(&self) -> bool {
         false
     }
+
+    fn supports_upsert(&self) -> bool {
+        true
+    }
 }
@@ -5,8 +5,13 @@
 //! ETL, or any other data processing system.
 
 use crate::ferris::schema::Schema;
+use crate::ferris::sql::ast::Expr;
+use crate::ferris::sql::execution::expression::ExpressionEvaluator;
 use crate::ferris::sql::execution::types::StreamRecord; // TODO: Move this to generic module
+use crate::ferris::sql::execution::RecordFields;
+use crate::ferris::sql::SqlError;
 use async_trait::async_trait;
+use std::collections::HashSet;
 use std::error::Error;
 
 use super::config::{BatchConfig, SinkConfig, SourceConfig};
@@ -100,6 +105,13 @@ pub trait DataReader: Send + Sync + 'static {
     /// Seek to a specific position/offset (for sources that support it)
     async fn seek(&mut self, offset: SourceOffset) -> Result<(), Box<dyn Error + Send + Sync>>;
 
+    /// Position just after the last record read, for [`seek`](Self::seek) to return to
+    ///
+    /// Checkpoints record it. `None` for sources that cannot seek back to it.
+    fn position(&mut self) -> Option<SourceOffset> {
+        None
+    }
+
     /// Check if more data is available (non-blocking)
     async fn has_more(&self) -> Result<bool, Box<dyn Error + Send + Sync>>;
 
@@ -128,6 +140,107 @@ pub trait DataReader: Send + Sync + 'static {
     fn supports_transactions(&self) -> bool {
         false
     }
+
+    /// Accept work pushed down by the query optimizer: fields outside the projection are
+    /// not decoded and records failing the filter are not returned.
+    /// Returns false if the reader cannot apply it; the query then keeps its full WHERE clause
+    fn push_down(&mut self, _pushdown: ReaderPushdown) -> bool {
+        // Default implementation: pushdown not supported
+        false
+    }
+
+    /// Take the records the pushed-down filter could not be evaluated on since the last call
+    /// These are not returned by read(); the caller handles them as failed records
+    fn take_pushdown_failures(&mut self) -> Vec<PushdownFailure> {
+        // Default implementation: no pushed-down filter, so nothing fails
+        Vec::new()
+    }
+}
+
+/// Projection and filter a query lets its source readers apply while decoding
+#[derive(Debug, Clone, Default)]
+pub struct ReaderPushdown {
+    /// Top-level field names the query references; `None` keeps every field
+    pub projection: Option<HashSet<String>>,
+    /// Predicate a record must satisfy to be returned; `None` keeps every record
+    pub filter: Option<Expr>,
+}
+
+impl ReaderPushdown {
+    /// Check if there is anything for a reader to apply
+    pub fn is_empty(&self) -> bool {
+        self.projection.is_none() && self.filter.is_none()
+    }
+
+    /// Check if a top-level field has to be decoded
+    pub fn keeps_field(&self, name: &str) -> bool {
+        self.projection
+            .as_ref()
+            .map_or(true, |projection| projection.contains(name))
+    }
+
+    /// Drop the fields outside the projection
+    pub fn project(&self, fields: &mut RecordFields) {
+        if let Some(projection) = &self.projection {
+            fields.retain(|name, _| projection.contains(name));
+        }
+    }
+
+    /// Check a record against the filter
+    pub fn matches(&self, record: &StreamRecord) -> Result<bool, SqlError> {
+        match &self.filter {
+            Some(filter) => ExpressionEvaluator::evaluate_expression(filter, record),
+            None => Ok(true),
+        }
+    }
+
+    /// Filter a decoded batch, setting aside the records the filter fails on so they can
+    /// be handled as failed records instead of being dropped
+    pub fn filter(
+        &self,
+        source: &str,
+        records: Vec<StreamRecord>,
+        failures: &mut Vec<PushdownFailure>,
+    ) -> Vec<StreamRecord> {
+        let mut kept = Vec::with_capacity(records.len());
+        for record in records {
+            match self.matches(&record) {
+                Ok(true) => kept.push(record),
+                Ok(false) => {}
+                Err(error) => failures.push(PushdownFailure {
+                    source: source.to_string(),
+                    record,
+                    error,
+                }),
+            }
+        }
+        kept
+    }
+
+    /// Filter and project a decoded batch
+    pub fn apply(
+        &self,
+        source: &str,
+        records: Vec<StreamRecord>,
+        failures: &mut Vec<PushdownFailure>,
+    ) -> Vec<StreamRecord> {
+        let mut records = self.filter(source, records, failures);
+        for record in &mut records {
+            self.project(&mut record.fields);
+        }
+        records
+    }
+}
+
+/// A record a reader's pushed-down filter could not be evaluated on
+#[derive(Debug, Clone)]
+pub struct PushdownFailure {
+    /// Topic, file or other source the record was read from
+    pub source: String,
+    /// The record as it was decoded
+    pub record: StreamRecord,
+    /// Why the filter failed
+    pub error: SqlError,
 }
 
 /// Writer trait for publishing data to any sink
@@ -186,4 +299,10 @@ pub trait DataWriter: Send + Sync + 'static {
     fn supports_transactions(&self) -> bool {
         false
     }
+
+    /// Check if `update`/`delete` replace rows by key (vs append-only)
+    /// Tables with a PRIMARY KEY are written through `update` when this returns true
+    fn supports_upsert(&self) -> bool {
+        false
+    }
 }
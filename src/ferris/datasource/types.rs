@@ -11,6 +11,9 @@ use std::fmt;
 pub enum SourceOffset {
     /// Kafka offset (partition, offset)
     Kafka { partition: i32, offset: i64 },
+    /// Kafka offsets of a reader consuming several partitions: the next offset to read,
+    /// by partition
+    KafkaPartitions { offsets: HashMap<i32, i64> },
     /// File position (file path, byte offset, line number)
     File {
         path: String,
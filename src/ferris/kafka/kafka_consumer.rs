@@ -11,6 +11,8 @@ use rdkafka::consumer::{
 };
 use rdkafka::error::KafkaError;
 use rdkafka::message::Message as KafkaMessage;
+use rdkafka::{Offset, TopicPartitionList};
+use std::collections::HashMap;
 use std::marker::PhantomData;
 use std::time::Duration;
 
@@ -419,6 +421,25 @@ where
         &self.value_serializer
     }
 
+    /// Mutably access the value serializer, e.g. to reconfigure decoding
+    pub fn value_serializer_mut(&mut self) -> &mut VS {
+        &mut self.value_serializer
+    }
+
+    /// Read the given partitions of a topic from the given offsets, replacing the
+    /// partitions the consumer group assigned
+    pub fn assign_offsets(
+        &self,
+        topic: &str,
+        offsets: &HashMap<i32, i64>,
+    ) -> Result<(), KafkaError> {
+        let mut partitions = TopicPartitionList::new();
+        for (partition, offset) in offsets {
+            partitions.add_partition_offset(topic, *partition, Offset::Offset(*offset))?;
+        }
+        self.consumer.assign(&partitions)
+    }
+
     /// Get current consumer offsets for transaction coordination
     pub fn current_offsets(&self) -> Result<rdkafka::TopicPartitionList, KafkaError> {
         use rdkafka::consumer::Consumer;
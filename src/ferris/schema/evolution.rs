@@ -289,7 +289,7 @@ impl SchemaEvolution {
         }
 
         Ok(StreamRecord {
-            fields: new_fields,
+            fields: new_fields.into(),
             timestamp: record.timestamp,
             offset: record.offset,
             partition: record.partition,
@@ -444,7 +444,15 @@ impl SchemaEvolution {
             // Same types are always compatible
             (a, b) if a == b => true,
             // Number type promotions
-            (DataType::Integer, DataType::Float) => true,
+            (DataType::SmallInt, DataType::Integer | DataType::BigInt) => true,
+            (DataType::Integer, DataType::BigInt) | (DataType::BigInt, DataType::Integer) => true,
+            (DataType::SmallInt | DataType::Integer | DataType::BigInt, DataType::Float) => true,
+            (DataType::Real, DataType::Float) => true,
+            // Decimals may widen when neither integer nor fractional digits are lost
+            (DataType::Decimal(_), DataType::Decimal(None)) => true,
+            (DataType::Decimal(Some(a)), DataType::Decimal(Some(b))) => {
+                b.scale >= a.scale && b.precision - b.scale >= a.precision - a.scale
+            }
             // String conversions (if allowed)
             (_, DataType::String) if self.config.allow_type_changes => true,
             // Array and Map type compatibility (simplified)
@@ -4,7 +4,11 @@
 //! FerrisStreams schema system.
 
 use crate::ferris::sql::ast::DataType;
+use crate::ferris::sql::execution::decimal::RoundingMode;
+use crate::ferris::sql::execution::FieldValue;
+use base64::Engine;
 use std::collections::HashMap;
+use std::str::FromStr;
 
 /// A schema definition containing field definitions and metadata
 #[derive(Debug, Clone, PartialEq)]
@@ -132,9 +136,39 @@ impl Schema {
         match (value, data_type) {
             (serde_json::Value::Null, _) => true, // Null is always acceptable
             (serde_json::Value::Bool(_), DataType::Boolean) => true,
-            (serde_json::Value::Number(n), DataType::Integer) => n.is_i64(),
-            (serde_json::Value::Number(n), DataType::Float) => n.is_f64(),
+            (serde_json::Value::Number(n), DataType::SmallInt) => {
+                n.as_i64().is_some_and(|i| i16::try_from(i).is_ok())
+            }
+            (serde_json::Value::Number(n), DataType::Integer | DataType::BigInt) => n.is_i64(),
+            (serde_json::Value::Number(n), DataType::Float | DataType::Real) => n.is_f64(),
             (serde_json::Value::String(_), DataType::String) => true,
+            // Binary data travels through JSON as base64 text
+            (serde_json::Value::String(s), DataType::Bytes) => {
+                base64::engine::general_purpose::STANDARD.decode(s).is_ok()
+            }
+            (serde_json::Value::String(s), DataType::Uuid) => uuid::Uuid::parse_str(s).is_ok(),
+            (serde_json::Value::String(s), DataType::Date) => {
+                chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok()
+            }
+            (serde_json::Value::String(s), DataType::Time) => {
+                chrono::NaiveTime::parse_from_str(s, "%H:%M:%S%.f").is_ok()
+            }
+            (serde_json::Value::Number(_), DataType::Decimal(None)) => true,
+            (serde_json::Value::String(s), DataType::Decimal(None)) => {
+                rust_decimal::Decimal::from_str(s.trim()).is_ok()
+            }
+            // Declared DECIMAL(p, s) values must fit the precision once rounded to the scale
+            (
+                serde_json::Value::Number(_) | serde_json::Value::String(_),
+                DataType::Decimal(Some(spec)),
+            ) => {
+                let text = match value {
+                    serde_json::Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                spec.coerce(FieldValue::String(text), RoundingMode::default())
+                    .is_ok()
+            }
             (serde_json::Value::Number(n), DataType::Timestamp) => n.is_i64(),
             (serde_json::Value::Array(_), DataType::Array(_)) => true, // Simplified validation
             (serde_json::Value::Object(_), DataType::Map(_, _)) => true, // Simplified validation
@@ -2,14 +2,19 @@
 
 use crate::ferris::kafka::serialization::Serializer;
 use crate::ferris::serialization;
+use crate::ferris::serialization::helpers::{self, DecimalSchemaInfo};
 use crate::ferris::serialization::SerializationError;
 use crate::ferris::sql::execution::types::FieldValue;
+use crate::ferris::sql::execution::{RecordFields, RecordSchemaCache};
 use apache_avro::{types::Value as AvroValue, Reader, Schema as AvroSchema, Writer};
+use chrono::Timelike;
 use std::collections::HashMap;
 
 /// Avro codec for serializing/deserializing HashMap<String, FieldValue> using a schema
 pub struct AvroCodec {
     schema: AvroSchema,
+    /// Layout shared by the records decoded into `RecordFields`
+    record_schemas: RecordSchemaCache,
 }
 
 impl AvroCodec {
@@ -18,12 +23,15 @@ impl AvroCodec {
         let schema = AvroSchema::parse_str(schema_json)
             .map_err(|e| SerializationError::avro_error("Failed to parse Avro schema", e))?;
 
-        Ok(AvroCodec { schema })
+        Ok(Self::with_schema(schema))
     }
 
     /// Create a new AvroCodec with a pre-parsed schema
     pub fn with_schema(schema: AvroSchema) -> Self {
-        AvroCodec { schema }
+        AvroCodec {
+            schema,
+            record_schemas: RecordSchemaCache::new(),
+        }
     }
 
     /// Serialize a HashMap<String, FieldValue> to Avro bytes
@@ -31,7 +39,19 @@ impl AvroCodec {
         &self,
         record: &HashMap<String, FieldValue>,
     ) -> Result<Vec<u8>, SerializationError> {
-        // Convert HashMap to Avro Value
+        self.encode(record)
+    }
+
+    /// Serialize record fields to Avro bytes
+    pub fn serialize_fields(&self, fields: &RecordFields) -> Result<Vec<u8>, SerializationError> {
+        self.encode(fields)
+    }
+
+    fn encode<'a>(
+        &self,
+        record: impl IntoIterator<Item = (&'a String, &'a FieldValue)>,
+    ) -> Result<Vec<u8>, SerializationError> {
+        // Convert the fields to an Avro Value
         let avro_value = self.record_to_avro_value(record)?;
 
         // Create writer and serialize
@@ -50,6 +70,26 @@ impl AvroCodec {
         &self,
         bytes: &[u8],
     ) -> Result<HashMap<String, FieldValue>, SerializationError> {
+        let mut record = HashMap::new();
+        self.decode(bytes, |name, value| {
+            record.insert(name.to_string(), value);
+        })?;
+        Ok(record)
+    }
+
+    /// Deserialize Avro bytes straight into record fields sharing the codec's layout
+    pub fn deserialize_fields(&self, bytes: &[u8]) -> Result<RecordFields, SerializationError> {
+        let mut fields = self.record_schemas.builder();
+        self.decode(bytes, |name, value| fields.push(name, value))?;
+        Ok(fields.finish())
+    }
+
+    /// Decode the first record of Avro bytes, handing every field to `push`
+    fn decode(
+        &self,
+        bytes: &[u8],
+        push: impl FnMut(&str, FieldValue),
+    ) -> Result<(), SerializationError> {
         let mut reader = Reader::with_schema(&self.schema, bytes)
             .map_err(|e| SerializationError::avro_error("Failed to create Avro reader", e))?;
 
@@ -58,7 +98,7 @@ impl AvroCodec {
             let avro_value = record_result
                 .map_err(|e| SerializationError::avro_error("Failed to read Avro record", e))?;
 
-            self.avro_value_to_record(&avro_value)
+            self.avro_value_to_record(&avro_value, push)
         } else {
             Err(SerializationError::type_conversion_error(
                 "No records found in Avro data".to_string(),
@@ -75,20 +115,163 @@ impl AvroCodec {
     }
 
     /// Convert HashMap<String, FieldValue> to Avro Value
-    fn record_to_avro_value(
+    fn record_to_avro_value<'a>(
         &self,
-        record: &HashMap<String, FieldValue>,
+        record: impl IntoIterator<Item = (&'a String, &'a FieldValue)>,
     ) -> Result<AvroValue, SerializationError> {
         let mut avro_fields = Vec::new();
 
         for (key, field_value) in record {
-            let avro_value = self.field_value_to_avro(field_value)?;
+            let avro_value = match self.record_field_schema(key) {
+                Some(AvroSchema::Union(union)) => {
+                    self.field_value_to_avro_union(field_value, union)?
+                }
+                Some(field_schema) => {
+                    self.field_value_to_avro_for_schema(field_value, field_schema)?
+                }
+                None => self.field_value_to_avro(field_value)?,
+            };
             avro_fields.push((key.clone(), avro_value));
         }
 
         Ok(AvroValue::Record(avro_fields))
     }
 
+    /// Look up the schema of a top-level record field
+    fn record_field_schema(&self, field_name: &str) -> Option<&AvroSchema> {
+        let AvroSchema::Record(record) = &self.schema else {
+            return None;
+        };
+        record
+            .fields
+            .iter()
+            .find(|f| f.name == field_name)
+            .map(|f| &f.schema)
+    }
+
+    /// Look up the decimal logical type of a top-level field, including nullable unions
+    fn record_decimal_schema(
+        &self,
+        field_name: &str,
+    ) -> Option<&apache_avro::schema::DecimalSchema> {
+        match self.record_field_schema(field_name)? {
+            AvroSchema::Decimal(decimal) => Some(decimal),
+            AvroSchema::Union(union) => union.variants().iter().find_map(|variant| match variant {
+                AvroSchema::Decimal(decimal) => Some(decimal),
+                _ => None,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Convert a value for a union field (e.g. `["null", {"type": "long", ...}]`).
+    ///
+    /// Non-null values are converted for the first non-null branch and tagged with the
+    /// index of the branch they match; NULL is encoded by the writer itself.
+    fn field_value_to_avro_union(
+        &self,
+        field_value: &FieldValue,
+        union: &apache_avro::schema::UnionSchema,
+    ) -> Result<AvroValue, SerializationError> {
+        if matches!(field_value, FieldValue::Null) {
+            return Ok(AvroValue::Null);
+        }
+
+        let avro_value = match union
+            .variants()
+            .iter()
+            .find(|variant| !matches!(variant, AvroSchema::Null))
+        {
+            Some(branch) => self.field_value_to_avro_for_schema(field_value, branch)?,
+            None => self.field_value_to_avro(field_value)?,
+        };
+        match union.find_schema_with_known_schemata::<AvroSchema>(&avro_value, None, &None) {
+            Some((index, _)) => Ok(AvroValue::Union(index as u32, Box::new(avro_value))),
+            // Let the writer report the schema mismatch
+            None => Ok(avro_value),
+        }
+    }
+
+    /// Convert FieldValue to the Avro Value expected by a specific field schema.
+    ///
+    /// Logical types (uuid, date, time-millis/micros) and sized primitives (int, float,
+    /// fixed) need their own Avro value variants; everything else uses the schema-less
+    /// conversion.
+    fn field_value_to_avro_for_schema(
+        &self,
+        field_value: &FieldValue,
+        field_schema: &AvroSchema,
+    ) -> Result<AvroValue, SerializationError> {
+        let out_of_range = |target: &str| {
+            SerializationError::type_conversion_error(
+                format!(
+                    "Value {} is out of range for Avro {}",
+                    field_value.to_display_string(),
+                    target
+                ),
+                "FieldValue",
+                target,
+                None::<std::io::Error>,
+            )
+        };
+
+        match (field_value, field_schema) {
+            (FieldValue::Integer(i), AvroSchema::Int) => i32::try_from(*i)
+                .map(AvroValue::Int)
+                .map_err(|_| out_of_range("int")),
+            (FieldValue::Float(f), AvroSchema::Float) => Ok(AvroValue::Float(*f as f32)),
+            (
+                FieldValue::ScaledInteger(..)
+                | FieldValue::Integer(_)
+                | FieldValue::Float(_)
+                | FieldValue::Decimal(_),
+                AvroSchema::Decimal(decimal),
+            ) => {
+                // Decimals are always written at the schema's declared scale
+                let info = DecimalSchemaInfo {
+                    precision: decimal.precision as u32,
+                    scale: decimal.scale as u32,
+                    is_standard_logical_type: true,
+                };
+                let (value, scale) = helpers::rescale_to_decimal_schema(field_value, &info)?;
+                helpers::scaled_integer_to_avro_decimal_bytes(value, scale)
+            }
+            (FieldValue::Uuid(u), AvroSchema::Uuid) => Ok(AvroValue::Uuid(*u)),
+            (FieldValue::Bytes(bytes), AvroSchema::Fixed(fixed)) => {
+                Ok(AvroValue::Fixed(fixed.size, bytes.clone()))
+            }
+            (FieldValue::Date(date), AvroSchema::Date) => {
+                let epoch = chrono::NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
+                i32::try_from((*date - epoch).num_days())
+                    .map(AvroValue::Date)
+                    .map_err(|_| out_of_range("date"))
+            }
+            (FieldValue::Time(time), AvroSchema::TimeMillis) => {
+                let millis = time.num_seconds_from_midnight() as i32 * 1000
+                    + (time.nanosecond() / 1_000_000).min(999) as i32;
+                Ok(AvroValue::TimeMillis(millis))
+            }
+            (FieldValue::Time(time), AvroSchema::TimeMicros) => {
+                let micros = time.num_seconds_from_midnight() as i64 * 1_000_000
+                    + (time.nanosecond() / 1_000).min(999_999) as i64;
+                Ok(AvroValue::TimeMicros(micros))
+            }
+            (FieldValue::Timestamp(ts), AvroSchema::TimestampMillis) => {
+                Ok(AvroValue::TimestampMillis(ts.and_utc().timestamp_millis()))
+            }
+            (FieldValue::Timestamp(ts), AvroSchema::TimestampMicros) => {
+                Ok(AvroValue::TimestampMicros(ts.and_utc().timestamp_micros()))
+            }
+            (FieldValue::TimestampTz(ts), AvroSchema::TimestampMillis) => {
+                Ok(AvroValue::TimestampMillis(ts.timestamp_millis()))
+            }
+            (FieldValue::TimestampTz(ts), AvroSchema::TimestampMicros) => {
+                Ok(AvroValue::TimestampMicros(ts.timestamp_micros()))
+            }
+            _ => self.field_value_to_avro(field_value),
+        }
+    }
+
     /// Convert FieldValue to Avro Value
     fn field_value_to_avro(
         &self,
@@ -130,6 +313,12 @@ impl AvroCodec {
                     ts.format("%Y-%m-%dT%H:%M:%S%.3f").to_string(),
                 ))
             }
+            FieldValue::TimestampTz(ts) => {
+                // RFC 3339 keeps the UTC offset of the original zone
+                Ok(AvroValue::String(
+                    ts.to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+                ))
+            }
             FieldValue::Array(arr) => {
                 let mut avro_array = Vec::new();
                 for item in arr {
@@ -167,23 +356,28 @@ impl AvroCodec {
                 // Convert time interval to string representation
                 Ok(AvroValue::String(format!("{} {:?}", value, unit)))
             }
+            FieldValue::Bytes(bytes) => Ok(AvroValue::Bytes(bytes.clone())),
+            FieldValue::Uuid(_) | FieldValue::Time(_) => {
+                // Without a uuid / time logical type in the schema, use the string form
+                Ok(AvroValue::String(field_value.to_display_string()))
+            }
         }
     }
 
-    /// Convert Avro Value to HashMap<String, FieldValue>
+    /// Convert an Avro record Value field by field
     fn avro_value_to_record(
         &self,
         avro_value: &AvroValue,
-    ) -> Result<HashMap<String, FieldValue>, SerializationError> {
+        mut push: impl FnMut(&str, FieldValue),
+    ) -> Result<(), SerializationError> {
         match avro_value {
             AvroValue::Record(fields) => {
-                let mut record = HashMap::new();
                 for (key, value) in fields {
                     let field_value =
                         self.avro_value_to_field_value_with_context(value, Some(key))?;
-                    record.insert(key.clone(), field_value);
+                    push(key, field_value);
                 }
-                Ok(record)
+                Ok(())
             }
             _ => Err(SerializationError::SchemaError(
                 "Expected Avro record, got other type".to_string(),
@@ -238,17 +432,8 @@ impl AvroCodec {
                     }
                 }
 
-                // Otherwise, try to decode as ScaledInteger if it looks like decimal bytes
-                if bytes.len() == 8 {
-                    let value = i64::from_be_bytes(bytes.as_slice().try_into().unwrap());
-                    let scale = 2; // Default scale for unknown decimal bytes
-                    Ok(FieldValue::ScaledInteger(value, scale))
-                } else {
-                    // Convert to base64 string
-                    use base64::Engine;
-                    let b64_str = base64::engine::general_purpose::STANDARD.encode(bytes);
-                    Ok(FieldValue::String(b64_str))
-                }
+                // Plain bytes field - keep the raw bytes
+                Ok(FieldValue::Bytes(bytes.clone()))
             }
             AvroValue::Array(arr) => {
                 let mut field_values = Vec::new();
@@ -295,11 +480,56 @@ impl AvroCodec {
                     }
                 }
 
-                // Convert fixed bytes to base64 string
-                use base64::Engine;
-                let b64_str = base64::engine::general_purpose::STANDARD.encode(bytes);
-                Ok(FieldValue::String(b64_str))
+                // Plain fixed field - keep the raw bytes
+                Ok(FieldValue::Bytes(bytes.clone()))
+            }
+            AvroValue::Decimal(decimal) => {
+                let bytes: Vec<u8> = decimal.clone().try_into().map_err(|e| {
+                    SerializationError::type_conversion_error(
+                        "Failed to extract bytes from Avro decimal".to_string(),
+                        "Decimal",
+                        "bytes",
+                        Some(e),
+                    )
+                })?;
+                let decimal_schema = field_name
+                    .and_then(|name| self.record_decimal_schema(name))
+                    .ok_or_else(|| {
+                        SerializationError::SchemaError(
+                            "Avro decimal value without a decimal field schema".to_string(),
+                        )
+                    })?;
+                helpers::decode_avro_decimal_bytes_with_schema(
+                    &bytes,
+                    decimal_schema.precision as u32,
+                    decimal_schema.scale as u32,
+                )
             }
+            AvroValue::Uuid(uuid) => Ok(FieldValue::Uuid(*uuid)),
+            AvroValue::Date(days) => chrono::NaiveDate::from_ymd_opt(1970, 1, 1)
+                .and_then(|epoch| epoch.checked_add_signed(chrono::Duration::days(*days as i64)))
+                .map(FieldValue::Date)
+                .ok_or_else(|| {
+                    SerializationError::SchemaError(format!("Avro date {} is out of range", days))
+                }),
+            AvroValue::TimeMillis(millis) => Self::time_from_micros(*millis as i64 * 1000),
+            AvroValue::TimeMicros(micros) => Self::time_from_micros(*micros),
+            AvroValue::TimestampMillis(millis) => chrono::DateTime::from_timestamp_millis(*millis)
+                .map(|ts| FieldValue::Timestamp(ts.naive_utc()))
+                .ok_or_else(|| {
+                    SerializationError::SchemaError(format!(
+                        "Avro timestamp-millis {} is out of range",
+                        millis
+                    ))
+                }),
+            AvroValue::TimestampMicros(micros) => chrono::DateTime::from_timestamp_micros(*micros)
+                .map(|ts| FieldValue::Timestamp(ts.naive_utc()))
+                .ok_or_else(|| {
+                    SerializationError::SchemaError(format!(
+                        "Avro timestamp-micros {} is out of range",
+                        micros
+                    ))
+                }),
             _ => Err(SerializationError::SchemaError(format!(
                 "Unsupported Avro value type: {:?}",
                 avro_value
@@ -307,6 +537,24 @@ impl AvroCodec {
         }
     }
 
+    /// Convert an Avro time-of-day (microseconds since midnight) to FieldValue::Time
+    fn time_from_micros(micros: i64) -> Result<FieldValue, SerializationError> {
+        let seconds = micros.div_euclid(1_000_000);
+        let nanos = micros.rem_euclid(1_000_000) * 1000;
+        u32::try_from(seconds)
+            .ok()
+            .and_then(|secs| {
+                chrono::NaiveTime::from_num_seconds_from_midnight_opt(secs, nanos as u32)
+            })
+            .map(FieldValue::Time)
+            .ok_or_else(|| {
+                SerializationError::SchemaError(format!(
+                    "Avro time value {} is outside a single day",
+                    micros
+                ))
+            })
+    }
+
     /// Convert Avro Value to FieldValue (backwards compatibility)
     fn avro_value_to_field_value(
         &self,
@@ -1,6 +1,8 @@
 //! Helper functions for serialization conversions
 
 use super::{FieldValue, SerializationError};
+use crate::ferris::sql::ast::DecimalSpec;
+use crate::ferris::sql::execution::decimal::RoundingMode;
 use std::collections::HashMap;
 
 // JSON conversion helpers
@@ -85,6 +87,9 @@ pub fn field_value_to_json(
         FieldValue::Timestamp(ts) => Ok(serde_json::Value::String(
             ts.format("%Y-%m-%d %H:%M:%S%.3f").to_string(),
         )),
+        FieldValue::TimestampTz(ts) => Ok(serde_json::Value::String(
+            ts.to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        )),
         FieldValue::Decimal(dec) => Ok(serde_json::Value::String(dec.to_string())),
         FieldValue::ScaledInteger(value, scale) => {
             // Serialize as standard decimal string for cross-system compatibility
@@ -139,6 +144,10 @@ pub fn field_value_to_json(
             );
             Ok(serde_json::Value::Object(interval_obj))
         }
+        // Bytes are base64-encoded, UUIDs and times use their canonical text form
+        FieldValue::Bytes(_) | FieldValue::Uuid(_) | FieldValue::Time(_) => {
+            Ok(serde_json::Value::String(field_value.to_display_string()))
+        }
     }
 }
 
@@ -180,14 +189,8 @@ pub fn avro_value_to_field_value(
             if let Some(scaled_integer) = try_decode_avro_decimal_bytes(bytes) {
                 Ok(scaled_integer)
             } else {
-                // Not a decimal logical type - handle as generic bytes
-                // For now, convert to string representation for compatibility
-                Ok(FieldValue::String(
-                    bytes
-                        .iter()
-                        .map(|b| format!("{:02x}", b))
-                        .collect::<String>(),
-                ))
+                // Not a decimal logical type - keep the raw bytes
+                Ok(FieldValue::Bytes(bytes.clone()))
             }
         }
         Value::Fixed(_size, bytes) => {
@@ -196,15 +199,11 @@ pub fn avro_value_to_field_value(
             if let Some(scaled_integer) = try_decode_avro_decimal_bytes(bytes) {
                 Ok(scaled_integer)
             } else {
-                // Not a decimal logical type - handle as generic fixed bytes
-                Ok(FieldValue::String(
-                    bytes
-                        .iter()
-                        .map(|b| format!("{:02x}", b))
-                        .collect::<String>(),
-                ))
+                // Not a decimal logical type - keep the raw fixed bytes
+                Ok(FieldValue::Bytes(bytes.clone()))
             }
         }
+        Value::Uuid(uuid) => Ok(FieldValue::Uuid(*uuid)),
         Value::Array(arr) => {
             let field_arr: Result<Vec<_>, _> = arr.iter().map(avro_value_to_field_value).collect();
             Ok(FieldValue::Array(field_arr?))
@@ -249,6 +248,9 @@ pub fn field_value_to_avro(
         FieldValue::Timestamp(ts) => Ok(Value::String(
             ts.format("%Y-%m-%d %H:%M:%S%.3f").to_string(),
         )),
+        FieldValue::TimestampTz(ts) => Ok(Value::String(
+            ts.to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        )),
         FieldValue::Decimal(dec) => Ok(Value::String(dec.to_string())),
         FieldValue::ScaledInteger(value, scale) => {
             // For maximum compatibility, we use string representation
@@ -292,6 +294,9 @@ pub fn field_value_to_avro(
         FieldValue::Interval { value, unit } => {
             Ok(Value::String(format!("INTERVAL {} {:?}", value, unit)))
         }
+        FieldValue::Bytes(bytes) => Ok(Value::Bytes(bytes.clone())),
+        FieldValue::Uuid(uuid) => Ok(Value::Uuid(*uuid)),
+        FieldValue::Time(_) => Ok(Value::String(field_value.to_display_string())),
     }
 }
 
@@ -310,7 +315,8 @@ pub fn scaled_integer_to_avro_decimal_bytes(
 
     // For Apache Avro 0.20.0+, use Value::Decimal when schema is parsed as Schema::Decimal
     // This provides better compatibility with the standard decimal logical type
-    let decimal = apache_avro::Decimal::from(value.to_be_bytes().to_vec());
+    // Minimal two's complement bytes also fit fixed-size decimal schemas smaller than 8 bytes
+    let decimal = apache_avro::Decimal::from(encode_big_endian_signed(value));
     let avro_value = Value::Decimal(decimal);
     eprintln!("DEBUG: Created Avro value: Decimal(...) for standard logical type compatibility");
 
@@ -365,15 +371,22 @@ pub fn field_value_to_avro_with_decimal_schema(
     field_value: &FieldValue,
     decimal_info: Option<&DecimalSchemaInfo>,
 ) -> Result<apache_avro::types::Value, SerializationError> {
-    match field_value {
-        FieldValue::ScaledInteger(value, _scale) if decimal_info.is_some() => {
-            let info = decimal_info.unwrap();
+    match (field_value, decimal_info) {
+        (
+            FieldValue::ScaledInteger(..)
+            | FieldValue::Integer(_)
+            | FieldValue::Float(_)
+            | FieldValue::Decimal(_),
+            Some(info),
+        ) => {
+            // Settlement amounts must be written at the schema's scale, not the value's own
+            let (value, scale) = rescale_to_decimal_schema(field_value, info)?;
             if info.is_standard_logical_type {
                 // Use Value::Decimal for standard "logicalType": "decimal"
-                scaled_integer_to_avro_decimal_bytes(*value, *_scale)
+                scaled_integer_to_avro_decimal_bytes(value, scale)
             } else {
                 // Use Value::Bytes for custom properties (Flink compatibility)
-                scaled_integer_to_avro_bytes_custom(*value, *_scale)
+                scaled_integer_to_avro_bytes_custom(value, scale)
             }
         }
         _ => {
@@ -383,6 +396,38 @@ pub fn field_value_to_avro_with_decimal_schema(
     }
 }
 
+/// Coerce a numeric value to the precision and scale declared by an Avro decimal schema
+///
+/// Dropped digits are rounded HALF_UP; values needing more digits than the schema
+/// precision are rejected rather than written with a different scale.
+pub fn rescale_to_decimal_schema(
+    field_value: &FieldValue,
+    info: &DecimalSchemaInfo,
+) -> Result<(i64, u8), SerializationError> {
+    let spec = DecimalSpec {
+        precision: info.precision.min(u8::MAX as u32) as u8,
+        scale: info.scale.min(u8::MAX as u32) as u8,
+    };
+    match spec.coerce(field_value.clone(), RoundingMode::default()) {
+        Ok(FieldValue::ScaledInteger(value, scale)) => Ok((value, scale)),
+        Ok(other) => Err(SerializationError::type_conversion_error(
+            format!("Cannot write {} as an Avro decimal", other.type_name()),
+            "FieldValue",
+            "Avro decimal",
+            None::<std::io::Error>,
+        )),
+        Err(e) => Err(SerializationError::type_conversion_error(
+            format!(
+                "Cannot write value as Avro decimal({}, {}): {}",
+                info.precision, info.scale, e
+            ),
+            "FieldValue",
+            "Avro decimal",
+            Some(e),
+        )),
+    }
+}
+
 /// Schema-aware Avro to FieldValue conversion that respects decimal logical types
 /// This function takes schema information to properly decode decimal fields
 pub fn avro_value_to_field_value_with_schema(
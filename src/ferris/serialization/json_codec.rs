@@ -4,30 +4,104 @@ use crate::ferris::kafka::serialization::Serializer;
 use crate::ferris::serialization::helpers::json_to_field_value;
 use crate::ferris::serialization::SerializationError;
 use crate::ferris::sql::execution::types::FieldValue;
-use serde_json::Value;
-use std::collections::HashMap;
+use crate::ferris::sql::execution::{RecordFields, RecordSchemaCache};
+use serde::de::{DeserializeSeed, Deserializer, IgnoredAny, MapAccess, Visitor};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
 
 /// JSON codec that deserializes to HashMap<String, FieldValue>
 /// This enables unified consumer types across all serialization formats
-pub struct JsonCodec;
+pub struct JsonCodec {
+    /// Top-level keys to decode; values of other keys are skipped without being built
+    projection: Option<HashSet<String>>,
+    /// Layout shared by the records decoded into `RecordFields`
+    record_schemas: RecordSchemaCache,
+}
 
 impl JsonCodec {
     /// Create a new JsonCodec
     pub fn new() -> Self {
-        JsonCodec
+        JsonCodec {
+            projection: None,
+            record_schemas: RecordSchemaCache::new(),
+        }
+    }
+
+    /// Restrict deserialization to the given top-level keys (`None` decodes every key)
+    pub fn set_projection(&mut self, projection: Option<HashSet<String>>) {
+        self.projection = projection;
     }
 }
 
-impl Serializer<HashMap<String, FieldValue>> for JsonCodec {
-    /// Serialize HashMap<String, FieldValue> to JSON bytes
-    fn serialize(
-        &self,
-        value: &HashMap<String, FieldValue>,
+/// Parse a JSON object keeping only the keys in `projection`
+///
+/// Values of other keys are scanned but never materialized, which avoids allocating
+/// strings, arrays and nested objects the query does not reference.
+pub fn parse_projected_json_object(
+    bytes: &[u8],
+    projection: &HashSet<String>,
+) -> Result<Map<String, Value>, serde_json::Error> {
+    let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+    let object = ProjectedObject(projection).deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(object)
+}
+
+/// Check if the bytes hold a JSON object, so projection applies to them
+pub fn is_json_object(bytes: &[u8]) -> bool {
+    bytes.iter().find(|b| !b.is_ascii_whitespace()) == Some(&b'{')
+}
+
+struct ProjectedObject<'a>(&'a HashSet<String>);
+
+impl<'de, 'a> DeserializeSeed<'de> for ProjectedObject<'a> {
+    type Value = Map<String, Value>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, 'a> Visitor<'de> for ProjectedObject<'a> {
+    type Value = Map<String, Value>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a JSON object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut object = Map::new();
+        while let Some(key) = map.next_key::<String>()? {
+            if self.0.contains(&key) {
+                object.insert(key, map.next_value::<Value>()?);
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(object)
+    }
+}
+
+impl JsonCodec {
+    /// Serialize record fields to JSON bytes
+    pub fn serialize_fields(&self, fields: &RecordFields) -> Result<Vec<u8>, SerializationError> {
+        Self::encode(fields)
+    }
+
+    /// Deserialize JSON bytes straight into record fields sharing the codec's layout
+    pub fn deserialize_fields(&self, bytes: &[u8]) -> Result<RecordFields, SerializationError> {
+        let mut fields = self.record_schemas.builder();
+        self.decode(bytes, |name, value| fields.push(name, value))?;
+        Ok(fields.finish())
+    }
+
+    fn encode<'a>(
+        fields: impl IntoIterator<Item = (&'a String, &'a FieldValue)>,
     ) -> Result<Vec<u8>, SerializationError> {
-        // Convert HashMap<String, FieldValue> to serde_json::Value
+        // Convert the fields to serde_json::Value
         let mut json_obj = serde_json::Map::new();
 
-        for (key, field_value) in value {
+        for (key, field_value) in fields {
             let json_value = field_value_to_json_value(field_value);
             json_obj.insert(key.clone(), json_value);
         }
@@ -37,20 +111,33 @@ impl Serializer<HashMap<String, FieldValue>> for JsonCodec {
             .map_err(|e| SerializationError::json_error("Failed to serialize JSON", e))
     }
 
-    /// Deserialize JSON bytes to HashMap<String, FieldValue>
-    fn deserialize(&self, bytes: &[u8]) -> Result<HashMap<String, FieldValue>, SerializationError> {
-        // Store the original JSON string as JSON_PAYLOAD field
-        let json_payload = String::from_utf8(bytes.to_vec())
-            .map_err(|e| SerializationError::encoding_error("Invalid UTF-8 in JSON data", e))?;
-
-        // Parse JSON bytes to serde_json::Value
-        let json_value: Value = serde_json::from_slice(bytes)
-            .map_err(|e| SerializationError::json_error("Failed to parse JSON from bytes", e))?;
+    /// Decode JSON bytes, handing every field to `push`
+    fn decode(
+        &self,
+        bytes: &[u8],
+        mut push: impl FnMut(String, FieldValue),
+    ) -> Result<(), SerializationError> {
+        let keeps = |key: &str| {
+            self.projection
+                .as_ref()
+                .map_or(true, |projection| projection.contains(key))
+        };
 
-        let mut fields = HashMap::new();
+        // Add the original JSON string as JSON_PAYLOAD, unless projected away
+        if keeps("JSON_PAYLOAD") {
+            let json_payload = String::from_utf8(bytes.to_vec())
+                .map_err(|e| SerializationError::encoding_error("Invalid UTF-8 in JSON data", e))?;
+            push("JSON_PAYLOAD".to_string(), FieldValue::String(json_payload));
+        }
 
-        // Always add the original JSON string as JSON_PAYLOAD
-        fields.insert("JSON_PAYLOAD".to_string(), FieldValue::String(json_payload));
+        // Parse JSON bytes to serde_json::Value, skipping keys outside the projection
+        let json_value: Value = match &self.projection {
+            Some(projection) if is_json_object(bytes) => {
+                parse_projected_json_object(bytes, projection).map(Value::Object)
+            }
+            _ => serde_json::from_slice(bytes),
+        }
+        .map_err(|e| SerializationError::json_error("Failed to parse JSON from bytes", e))?;
 
         match json_value {
             Value::Object(obj) => {
@@ -64,7 +151,7 @@ impl Serializer<HashMap<String, FieldValue>> for JsonCodec {
                             Some(e),
                         )
                     })?;
-                    fields.insert(key, field_value);
+                    push(key, field_value);
                 }
             }
             _ => {
@@ -77,10 +164,29 @@ impl Serializer<HashMap<String, FieldValue>> for JsonCodec {
                         Some(e),
                     )
                 })?;
-                fields.insert("value".to_string(), field_value);
+                push("value".to_string(), field_value);
             }
         }
 
+        Ok(())
+    }
+}
+
+impl Serializer<HashMap<String, FieldValue>> for JsonCodec {
+    /// Serialize HashMap<String, FieldValue> to JSON bytes
+    fn serialize(
+        &self,
+        value: &HashMap<String, FieldValue>,
+    ) -> Result<Vec<u8>, SerializationError> {
+        Self::encode(value)
+    }
+
+    /// Deserialize JSON bytes to HashMap<String, FieldValue>
+    fn deserialize(&self, bytes: &[u8]) -> Result<HashMap<String, FieldValue>, SerializationError> {
+        let mut fields = HashMap::new();
+        self.decode(bytes, |name, value| {
+            fields.insert(name, value);
+        })?;
         Ok(fields)
     }
 }
@@ -120,6 +226,9 @@ fn field_value_to_json_value(field_value: &FieldValue) -> Value {
         FieldValue::Timestamp(datetime) => {
             Value::String(datetime.format("%Y-%m-%d %H:%M:%S%.3f").to_string())
         }
+        FieldValue::TimestampTz(datetime) => {
+            Value::String(datetime.to_rfc3339_opts(chrono::SecondsFormat::Millis, true))
+        }
         FieldValue::Decimal(decimal) => Value::String(decimal.to_string()),
         FieldValue::Array(array) => {
             let json_array: Vec<Value> = array.iter().map(field_value_to_json_value).collect();
@@ -149,6 +258,10 @@ fn field_value_to_json_value(field_value: &FieldValue) -> Value {
             json_obj.insert("unit".to_string(), Value::String(format!("{:?}", unit)));
             Value::Object(json_obj)
         }
+        // Bytes are base64-encoded, UUIDs and times use their canonical text form
+        FieldValue::Bytes(_) | FieldValue::Uuid(_) | FieldValue::Time(_) => {
+            Value::String(field_value.to_display_string())
+        }
     }
 }
 
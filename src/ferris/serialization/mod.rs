@@ -109,7 +109,8 @@ pub use json_codec::JsonCodec;
 
 // Serialization codec enum for Kafka integration
 use crate::ferris::kafka::serialization::Serializer;
-use std::collections::HashMap;
+use crate::ferris::sql::execution::RecordFields;
+use std::collections::{HashMap, HashSet};
 
 /// Unified serialization codec enum that implements Serializer trait
 /// This allows KafkaConsumer to work with any of our supported formats
@@ -140,6 +141,25 @@ impl Serializer<HashMap<String, FieldValue>> for SerializationCodec {
     }
 }
 
+/// Codecs fill `RecordFields` directly, so records decoded by one codec share a layout
+impl Serializer<RecordFields> for SerializationCodec {
+    fn serialize(&self, value: &RecordFields) -> Result<Vec<u8>, SerializationError> {
+        match self {
+            Self::Json(codec) => codec.serialize_fields(value),
+            Self::Avro(codec) => codec.serialize_fields(value),
+            Self::Protobuf(codec) => codec.serialize_fields(value),
+        }
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> Result<RecordFields, SerializationError> {
+        match self {
+            Self::Json(codec) => codec.deserialize_fields(bytes),
+            Self::Avro(codec) => codec.deserialize_fields(bytes),
+            Self::Protobuf(codec) => codec.deserialize_fields(bytes),
+        }
+    }
+}
+
 impl SerializationCodec {
     /// Get the format name for debugging/logging
     pub fn format_name(&self) -> &'static str {
@@ -149,6 +169,14 @@ impl SerializationCodec {
             Self::Protobuf(_) => "Protobuf",
         }
     }
+
+    /// Restrict decoding to the given top-level fields where the format allows skipping them
+    /// Avro and Protobuf decode whole messages; callers drop unprojected fields afterwards
+    pub fn set_projection(&mut self, projection: Option<HashSet<String>>) {
+        if let Self::Json(codec) = self {
+            codec.set_projection(projection);
+        }
+    }
 }
 
 // Re-export conversion helpers (used by external modules like kafka reader/writer)
@@ -6,6 +6,7 @@
 use crate::ferris::kafka::serialization::Serializer;
 use crate::ferris::serialization::SerializationError;
 use crate::ferris::sql::execution::types::FieldValue;
+use crate::ferris::sql::execution::{RecordFields, RecordSchemaCache};
 use prost::Message;
 use std::collections::HashMap;
 
@@ -40,6 +41,14 @@ pub enum FieldValueOneof {
     ArrayValue(ArrayMessage),
     #[prost(message, tag = "8")]
     MapValue(MapMessage),
+    #[prost(bytes = "vec", tag = "9")]
+    BytesValue(Vec<u8>),
+    /// Canonical hyphenated UUID text
+    #[prost(string, tag = "10")]
+    UuidValue(String),
+    /// Nanoseconds since midnight
+    #[prost(int64, tag = "11")]
+    TimeValue(i64),
 }
 
 /// Timestamp message
@@ -68,7 +77,7 @@ pub struct MapMessage {
 /// Main field message wrapper
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct FieldMessage {
-    #[prost(oneof = "FieldValueOneof", tags = "1,2,3,4,5,6,7,8")]
+    #[prost(oneof = "FieldValueOneof", tags = "1,2,3,4,5,6,7,8,9,10,11")]
     pub value: Option<FieldValueOneof>,
 }
 
@@ -88,6 +97,8 @@ pub struct ProtobufCodec {
     schema: String,
     /// Message type name from the schema to use for serialization
     message_type: String,
+    /// Layout shared by the records decoded into `RecordFields`
+    record_schemas: RecordSchemaCache,
 }
 
 // Note: No Default implementation since Protobuf REQUIRES a schema
@@ -143,6 +154,7 @@ impl ProtobufCodec {
         Ok(ProtobufCodec {
             schema: schema.to_string(),
             message_type: message_type.to_string(),
+            record_schemas: RecordSchemaCache::new(),
         })
     }
 
@@ -166,6 +178,9 @@ impl ProtobufCodec {
                     TimestampMessage timestamp_value = 6;
                     ArrayMessage array_value = 7;
                     MapMessage map_value = 8;
+                    bytes bytes_value = 9;
+                    string uuid_value = 10;
+                    int64 time_value = 11;
                 }
             }
             
@@ -191,6 +206,7 @@ impl ProtobufCodec {
         ProtobufCodec {
             schema: default_schema.to_string(),
             message_type: "RecordMessage".to_string(),
+            record_schemas: RecordSchemaCache::new(),
         }
     }
 
@@ -226,6 +242,18 @@ impl ProtobufCodec {
     pub fn serialize(
         &self,
         record: &HashMap<String, FieldValue>,
+    ) -> Result<Vec<u8>, SerializationError> {
+        self.encode(record)
+    }
+
+    /// Serialize record fields to protobuf bytes
+    pub fn serialize_fields(&self, fields: &RecordFields) -> Result<Vec<u8>, SerializationError> {
+        self.encode(fields)
+    }
+
+    fn encode<'a>(
+        &self,
+        record: impl IntoIterator<Item = (&'a String, &'a FieldValue)>,
     ) -> Result<Vec<u8>, SerializationError> {
         let mut proto_fields = HashMap::new();
 
@@ -251,17 +279,36 @@ impl ProtobufCodec {
         &self,
         bytes: &[u8],
     ) -> Result<HashMap<String, FieldValue>, SerializationError> {
+        let mut record = HashMap::new();
+        self.decode(bytes, |name, value| {
+            record.insert(name, value);
+        })?;
+        Ok(record)
+    }
+
+    /// Deserialize protobuf bytes straight into record fields sharing the codec's layout
+    pub fn deserialize_fields(&self, bytes: &[u8]) -> Result<RecordFields, SerializationError> {
+        let mut fields = self.record_schemas.builder();
+        self.decode(bytes, |name, value| fields.push(name, value))?;
+        Ok(fields.finish())
+    }
+
+    /// Decode protobuf bytes, handing every field to `push`
+    fn decode(
+        &self,
+        bytes: &[u8],
+        mut push: impl FnMut(String, FieldValue),
+    ) -> Result<(), SerializationError> {
         let record_msg = RecordMessage::decode(bytes).map_err(|e| {
             SerializationError::protobuf_error("Failed to decode protobuf message", e)
         })?;
 
-        let mut record = HashMap::new();
         for (key, proto_field) in record_msg.fields {
             let field_value = self.proto_to_field_value(&proto_field)?;
-            record.insert(key, field_value);
+            push(key, field_value);
         }
 
-        Ok(record)
+        Ok(())
     }
 
     /// Convert FieldValue to protobuf message
@@ -288,6 +335,10 @@ impl ProtobufCodec {
                     nanos: timestamp_nanos,
                 })
             }
+            FieldValue::TimestampTz(ts) => FieldValueOneof::TimestampValue(TimestampMessage {
+                seconds: ts.timestamp(),
+                nanos: ts.timestamp_subsec_nanos(),
+            }),
             FieldValue::Array(arr) => {
                 let mut proto_values = Vec::new();
                 for item in arr {
@@ -325,6 +376,14 @@ impl ProtobufCodec {
                 // Convert NaiveDate to string representation
                 FieldValueOneof::StringValue(date.format("%Y-%m-%d").to_string())
             }
+            FieldValue::Bytes(bytes) => FieldValueOneof::BytesValue(bytes.clone()),
+            FieldValue::Uuid(uuid) => FieldValueOneof::UuidValue(uuid.hyphenated().to_string()),
+            FieldValue::Time(time) => {
+                use chrono::Timelike;
+                let nanos = time.num_seconds_from_midnight() as i64 * 1_000_000_000
+                    + time.nanosecond() as i64;
+                FieldValueOneof::TimeValue(nanos)
+            }
             FieldValue::Decimal(decimal) => {
                 // Convert rust_decimal::Decimal to our DecimalMessage
                 let scale = decimal.scale();
@@ -394,6 +453,33 @@ impl ProtobufCodec {
                 }
                 Ok(FieldValue::Map(field_map))
             }
+            Some(FieldValueOneof::BytesValue(bytes)) => Ok(FieldValue::Bytes(bytes.clone())),
+            Some(FieldValueOneof::UuidValue(text)) => uuid::Uuid::parse_str(text)
+                .map(FieldValue::Uuid)
+                .map_err(|e| {
+                    SerializationError::type_conversion_error(
+                        format!("Invalid UUID '{}'", text),
+                        "ProtobufString",
+                        "Uuid",
+                        Some(e),
+                    )
+                }),
+            Some(FieldValueOneof::TimeValue(nanos)) => {
+                let secs = u32::try_from(nanos.div_euclid(1_000_000_000)).ok();
+                secs.and_then(|secs| {
+                    chrono::NaiveTime::from_num_seconds_from_midnight_opt(
+                        secs,
+                        nanos.rem_euclid(1_000_000_000) as u32,
+                    )
+                })
+                .map(FieldValue::Time)
+                .ok_or_else(|| {
+                    SerializationError::SchemaError(format!(
+                        "Time value {} is outside a single day",
+                        nanos
+                    ))
+                })
+            }
             None => Ok(FieldValue::Null),
         }
     }
@@ -6,12 +6,20 @@
 use crate::ferris::datasource::{
     file::{FileDataSource, FileSink},
     kafka::{KafkaDataSink, KafkaDataSource},
-    DataReader, DataSink, DataSource, DataWriter, SinkConfig, StdoutWriter,
+    DataReader, DataSink, DataSource, DataWriter, PushdownFailure, SinkConfig, StdoutWriter,
 };
+use crate::ferris::serialization::helpers::field_value_to_json;
 use crate::ferris::sql::{
+    error::recovery::{DeadLetterQueue, FailedMessage},
+    execution::processors::merge::{ChangeOperation, CHANGE_KEY_HEADER, CHANGE_OPERATION_HEADER},
+    execution::schema_enforcement::{EnforcementOutcome, StreamSchemaEnforcer},
+    execution::table_constraints::{
+        ConstraintOutcome, ConstraintViolationPolicy, TableConstraintEnforcer,
+    },
     execution::types::StreamRecord,
+    execution::{processors::ProcessorContext, savepoint::SharedSourcePositions, Checkpointer},
     query_analyzer::{DataSinkRequirement, DataSinkType, DataSourceRequirement, DataSourceType},
-    StreamExecutionEngine, StreamingQuery,
+    SqlError, StreamExecutionEngine, StreamingQuery,
 };
 use log::{debug, error, info, warn};
 use std::collections::HashMap;
@@ -131,6 +139,8 @@ pub struct JobProcessingConfig {
     pub log_progress: bool,
     /// Progress logging interval (in batches)
     pub progress_interval: u64,
+    /// Whether stateless SELECTs run over whole batches through the columnar kernels
+    pub columnar_execution: bool,
 }
 
 impl Default for JobProcessingConfig {
@@ -144,6 +154,7 @@ impl Default for JobProcessingConfig {
             retry_backoff: Duration::from_millis(1000),
             log_progress: true,
             progress_interval: 10,
+            columnar_execution: false,
         }
     }
 }
@@ -206,19 +217,74 @@ pub async fn process_batch_with_output(
     let mut error_details = Vec::new();
     let mut output_records = Vec::new();
 
+    // MERGE and UPSERT write their changelog records instead of the input
+    let writes_changelog = matches!(
+        query,
+        StreamingQuery::Merge { .. } | StreamingQuery::Upsert { .. }
+    );
+
+    // Stateless SELECTs run over the whole batch through the columnar kernels
+    match engine.lock().await.execute_batch(&batch) {
+        Ok(true) => {
+            return BatchProcessingResultWithOutput {
+                records_processed: batch_size,
+                records_failed: 0,
+                processing_time: batch_start.elapsed(),
+                batch_size,
+                error_details: Vec::new(),
+                output_records: batch,
+            };
+        }
+        Ok(false) => {}
+        // Part of the batch may have been emitted, so it is not executed again
+        Err(e) => {
+            warn!("Job '{}' failed to process batch: {:?}", job_name, e);
+            return BatchProcessingResultWithOutput {
+                records_processed: 0,
+                records_failed: batch_size,
+                processing_time: batch_start.elapsed(),
+                batch_size,
+                error_details: (0..batch_size)
+                    .map(|record_index| ProcessingError {
+                        record_index,
+                        error_message: format!("{:?}", e),
+                        recoverable: is_recoverable_error(&e),
+                    })
+                    .collect(),
+                output_records: Vec::new(),
+            };
+        }
+    }
+
     // Process each record individually to capture output and handle errors
     for (index, record) in batch.into_iter().enumerate() {
         let mut engine_lock = engine.lock().await;
 
         // Try to execute the record and capture the result
-        match engine_lock.execute_with_record(query, record.clone()).await {
-            Ok(()) => {
+        let outcome = if writes_changelog {
+            // Rows evicted by a state TTL go out with the change that swept them
+            engine_lock
+                .execute_with_output(query, &record)
+                .map(|change| {
+                    change
+                        .into_iter()
+                        .chain(engine_lock.take_evicted_records())
+                        .collect()
+                })
+        } else {
+            engine_lock
+                .execute_with_record(query, record.clone())
+                .await
+                .map(|()| vec![record])
+        };
+        match outcome {
+            Ok(records) => {
                 records_processed += 1;
                 // For now, we'll add the processed record to output
                 // TODO: This is a placeholder - ideally we'd capture the actual SQL output
                 // For simple SELECT * queries, the output would be the input record
                 // For more complex queries, we'd need the transformed result
-                output_records.push(record);
+                output_records.extend(records);
             }
             Err(e) => {
                 records_failed += 1;
@@ -252,6 +318,43 @@ fn is_recoverable_error(_error: &crate::ferris::sql::SqlError) -> bool {
     false
 }
 
+/// Checkpoint the job when one is due, or unconditionally with `force`
+///
+/// A failed checkpoint is logged and the job keeps running; the previous
+/// checkpoint stays the one to restore from.
+pub async fn checkpoint_job(
+    checkpointer: Option<&Checkpointer>,
+    engine: &Arc<Mutex<StreamExecutionEngine>>,
+    context: &ProcessorContext,
+    job_name: &str,
+    force: bool,
+) {
+    let Some(checkpointer) = checkpointer else {
+        return;
+    };
+    if !force && !checkpointer.is_due() {
+        return;
+    }
+    let engine = engine.lock().await;
+    match checkpointer.checkpoint(&engine, &context.source_positions) {
+        Ok(id) => debug!("Job '{}': wrote checkpoint {}", job_name, id),
+        Err(e) => warn!("Job '{}': checkpoint failed: {}", job_name, e),
+    }
+}
+
+/// Share the positions the sources were read up to with the job server, so a savepoint
+/// taken after the job stops resumes where it left off
+pub fn publish_source_positions(
+    positions: Option<&SharedSourcePositions>,
+    context: &ProcessorContext,
+) {
+    if let Some(positions) = positions {
+        if let Ok(mut positions) = positions.lock() {
+            positions.clone_from(&context.source_positions);
+        }
+    }
+}
+
 /// Log progress for a job
 pub fn log_job_progress(job_name: &str, stats: &JobExecutionStats) {
     let rps = stats.records_per_second();
@@ -712,7 +815,7 @@ pub async fn create_multi_sink_writers(
 /// Log comprehensive configuration details for a job
 pub fn log_job_configuration(job_name: &str, config: &JobProcessingConfig) {
     info!(
-        "Job '{}' configuration: use_transactions={}, failure_strategy={:?}, max_batch_size={}, batch_timeout={}ms, max_retries={}, retry_backoff={}ms, progress_interval={}, log_progress={}",
+        "Job '{}' configuration: use_transactions={}, failure_strategy={:?}, max_batch_size={}, batch_timeout={}ms, max_retries={}, retry_backoff={}ms, progress_interval={}, log_progress={}, columnar_execution={}",
         job_name,
         config.use_transactions,
         config.failure_strategy,
@@ -721,7 +824,8 @@ pub fn log_job_configuration(job_name: &str, config: &JobProcessingConfig) {
         config.max_retries,
         config.retry_backoff.as_millis(),
         config.progress_interval,
-        config.log_progress
+        config.log_progress,
+        config.columnar_execution
     );
 }
 
@@ -845,6 +949,308 @@ pub fn update_stats_from_batch_result(
     stats.update_from_batch(&stats_result);
 }
 
+/// Schema and constraint checks declared by a job's CREATE STREAM or CREATE TABLE statement
+#[derive(Debug, Default)]
+pub struct JobEnforcement {
+    /// Declared stream schema, applied to records read from sources
+    pub schema: Option<StreamSchemaEnforcer>,
+    /// Table constraints, applied to rows before they are written to sinks
+    pub table_constraints: Option<TableConstraintEnforcer>,
+}
+
+impl JobEnforcement {
+    pub fn from_query(query: &StreamingQuery) -> Result<Self, SqlError> {
+        Ok(Self {
+            schema: StreamSchemaEnforcer::from_query(query)?,
+            table_constraints: TableConstraintEnforcer::from_query(query)?,
+        })
+    }
+
+    /// Log which checks are active for the job
+    pub fn log(&self, job_name: &str) {
+        if let Some(schema) = &self.schema {
+            info!(
+                "Job '{}': Enforcing the declared schema of stream '{}' ({:?} policy)",
+                job_name,
+                schema.stream_name(),
+                schema.policy()
+            );
+        }
+        if let Some(constraints) = &self.table_constraints {
+            info!(
+                "Job '{}': Enforcing constraints of table '{}' (primary key: {:?}, {:?} policy)",
+                job_name,
+                constraints.table_name(),
+                constraints.primary_key(),
+                constraints.policy()
+            );
+        }
+    }
+}
+
+/// Records that passed schema or constraint enforcement
+#[derive(Debug)]
+pub struct EnforcedBatch {
+    pub records: Vec<StreamRecord>,
+    pub rejected: usize,
+}
+
+/// Check a batch read from a source against the declared stream schema
+///
+/// Rejected records are sent to the dead letter queue with the violation attached (or
+/// dropped with a warning when none is configured). Under the `fail` policy the first
+/// violation is returned as a [`SqlError::SchemaError`].
+pub async fn enforce_source_schema(
+    batch: Vec<StreamRecord>,
+    enforcer: Option<&StreamSchemaEnforcer>,
+    dead_letter_queue: Option<&DeadLetterQueue>,
+    job_name: &str,
+) -> Result<EnforcedBatch, SqlError> {
+    let Some(enforcer) = enforcer else {
+        return Ok(EnforcedBatch {
+            records: batch,
+            rejected: 0,
+        });
+    };
+
+    let mut records = Vec::with_capacity(batch.len());
+    let mut rejected = 0;
+    for record in batch {
+        match enforcer.enforce(record)? {
+            EnforcementOutcome::Accepted(record) => records.push(record),
+            EnforcementOutcome::Rejected { record, violation } => {
+                rejected += 1;
+                let reason = format!(
+                    "Record violates the schema of stream '{}': {}",
+                    enforcer.stream_name(),
+                    violation
+                );
+                let headers = [
+                    ("schema.violation.column", violation.column),
+                    ("schema.violation.reason", violation.reason),
+                ];
+                send_to_dead_letter_queue(
+                    dead_letter_queue,
+                    enforcer.stream_name(),
+                    record,
+                    reason,
+                    headers,
+                    job_name,
+                )
+                .await;
+            }
+        }
+    }
+
+    if rejected > 0 {
+        debug!(
+            "Job '{}': {} records rejected by the schema of stream '{}'",
+            job_name,
+            rejected,
+            enforcer.stream_name()
+        );
+    }
+    Ok(EnforcedBatch { records, rejected })
+}
+
+/// Handle the records a source's pushed-down filter could not be evaluated on
+///
+/// They are sent to the dead letter queue with the error attached (or dropped with a
+/// warning when none is configured). Returns how many there were, so callers can count
+/// them as failed records and let the failure strategy decide whether the batch commits,
+/// as it would if the WHERE clause had failed on them in the engine.
+pub async fn handle_pushdown_failures(
+    failures: Vec<PushdownFailure>,
+    dead_letter_queue: Option<&DeadLetterQueue>,
+    job_name: &str,
+) -> usize {
+    let failed = failures.len();
+    for failure in failures {
+        let reason = format!(
+            "Pushed-down filter failed on a record of '{}': {}",
+            failure.source, failure.error
+        );
+        let headers = [("pushdown.filter.error", failure.error.to_string())];
+        send_to_dead_letter_queue(
+            dead_letter_queue,
+            &failure.source,
+            failure.record,
+            reason,
+            headers,
+            job_name,
+        )
+        .await;
+    }
+    failed
+}
+
+/// Check the rows produced for a table against its PRIMARY KEY, NOT NULL and CHECK constraints
+///
+/// Violating rows are dropped under the `skip` policy and sent to the dead letter queue
+/// under `dlq`. Under the `fail` policy the first violation is returned as a
+/// [`SqlError::SchemaError`].
+pub async fn enforce_table_constraints(
+    rows: Vec<StreamRecord>,
+    enforcer: Option<&TableConstraintEnforcer>,
+    dead_letter_queue: Option<&DeadLetterQueue>,
+    job_name: &str,
+) -> Result<EnforcedBatch, SqlError> {
+    let Some(enforcer) = enforcer else {
+        return Ok(EnforcedBatch {
+            records: rows,
+            rejected: 0,
+        });
+    };
+
+    let mut records = Vec::with_capacity(rows.len());
+    let mut rejected = 0;
+    for record in rows {
+        match enforcer.enforce(record)? {
+            ConstraintOutcome::Accepted(record) => records.push(record),
+            ConstraintOutcome::Rejected { record, violation } => {
+                rejected += 1;
+                let reason = format!(
+                    "Row violates {} of table '{}'",
+                    violation,
+                    enforcer.table_name()
+                );
+                if enforcer.policy() == ConstraintViolationPolicy::Skip {
+                    warn!("Job '{}': {} (row skipped)", job_name, reason);
+                    continue;
+                }
+                let headers = [
+                    ("constraint.violation.name", violation.constraint),
+                    ("constraint.violation.reason", violation.reason),
+                ];
+                send_to_dead_letter_queue(
+                    dead_letter_queue,
+                    enforcer.table_name(),
+                    record,
+                    reason,
+                    headers,
+                    job_name,
+                )
+                .await;
+            }
+        }
+    }
+
+    if rejected > 0 {
+        debug!(
+            "Job '{}': {} rows rejected by the constraints of table '{}'",
+            job_name,
+            rejected,
+            enforcer.table_name()
+        );
+    }
+    Ok(EnforcedBatch { records, rejected })
+}
+
+/// Send a rejected record to the dead letter queue, or drop it with a warning if none is configured
+async fn send_to_dead_letter_queue<const N: usize>(
+    dead_letter_queue: Option<&DeadLetterQueue>,
+    source: &str,
+    record: StreamRecord,
+    reason: String,
+    violation_headers: [(&str, String); N],
+    job_name: &str,
+) {
+    let Some(dlq) = dead_letter_queue else {
+        warn!(
+            "Job '{}': {} (no dead letter queue configured, record dropped)",
+            job_name, reason
+        );
+        return;
+    };
+
+    let original_data = record
+        .fields
+        .iter()
+        .map(|(name, value)| {
+            field_value_to_json(value)
+                .map(|json| (name.clone(), json))
+                .map_err(|_| ())
+        })
+        .collect::<Result<serde_json::Map<_, _>, ()>>()
+        .map(|fields| serde_json::Value::Object(fields).to_string())
+        .unwrap_or_else(|_| format!("{:?}", record.fields));
+    let mut headers = record.headers;
+    for (name, value) in violation_headers {
+        headers.insert(name.to_string(), value);
+    }
+
+    let message = FailedMessage {
+        id: format!("{}-{}-{}", source, record.partition, record.offset),
+        original_data,
+        error_details: reason,
+        failed_at: Instant::now(),
+        retry_count: 0,
+        source_topic: Some(source.to_string()),
+        headers,
+    };
+    if let Err(e) = dlq.enqueue(message).await {
+        warn!(
+            "Job '{}': Failed to send rejected record to dead letter queue: {}",
+            job_name, e
+        );
+    }
+}
+
+/// Write output rows to a sink, upserting by PRIMARY KEY when the table declares one
+///
+/// Rows of keyed tables and MERGE/UPSERT changelog records (which carry an `upsert.key`
+/// header) go through [`DataWriter::update`] and [`DataWriter::delete`] on sinks that
+/// support upserts; other sinks receive a plain batch append.
+pub async fn write_output_records(
+    writer: &mut dyn DataWriter,
+    records: Vec<StreamRecord>,
+    table_constraints: Option<&TableConstraintEnforcer>,
+) -> DataSourceResult<()> {
+    let enforcer = table_constraints.filter(|c| !c.primary_key().is_empty());
+    let has_changelog = records
+        .iter()
+        .any(|r| r.headers.contains_key(CHANGE_KEY_HEADER));
+    if enforcer.is_none() && !has_changelog {
+        return writer.write_batch(records).await;
+    }
+    if !writer.supports_upsert() {
+        debug!(
+            "Sink does not support upserts, appending {} rows as changelog records",
+            records.len()
+        );
+        return writer.write_batch(records).await;
+    }
+
+    for record in records {
+        if let Some(key) = record.headers.get(CHANGE_KEY_HEADER).cloned() {
+            let is_delete = record
+                .headers
+                .get(CHANGE_OPERATION_HEADER)
+                .is_some_and(|op| op == ChangeOperation::Delete.as_str());
+            if is_delete {
+                writer.delete(&key).await?;
+            } else {
+                writer.update(&key, record).await?;
+            }
+        } else if let Some(enforcer) = enforcer {
+            // Constraint enforcement has already rejected rows with a NULL key
+            let key = enforcer.key_of(&record).unwrap_or_default();
+            writer.update(&key, record).await?;
+        } else {
+            writer.write(record).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Whether a batch error is a schema violation that must stop the job instead of retrying
+pub fn is_schema_violation(error: &(dyn std::error::Error + Send + Sync + 'static)) -> bool {
+    matches!(
+        error.downcast_ref::<SqlError>(),
+        Some(SqlError::SchemaError { .. })
+    )
+}
+
 /// Handle missing sink by creating a StdoutWriter and logging a warning
 pub fn ensure_sink_or_create_stdout(writer: &mut Option<Box<dyn DataWriter>>, job_name: &str) {
     if writer.is_none() {
@@ -3,8 +3,13 @@
 //! This module provides best-effort job processing without transactional semantics.
 //! It's optimized for throughput and simplicity, using basic commit/flush operations.
 
+use crate::ferris::datasource::SourceOffset;
 use crate::ferris::datasource::{DataReader, DataWriter};
 use crate::ferris::server::processors::common::*;
+use crate::ferris::sql::error::recovery::DeadLetterQueue;
+use crate::ferris::sql::execution::checkpoint::seek_sources;
+use crate::ferris::sql::execution::savepoint::SharedSourcePositions;
+use crate::ferris::sql::execution::Checkpointer;
 use crate::ferris::sql::{StreamExecutionEngine, StreamingQuery};
 use log::{debug, error, info, warn};
 use std::collections::HashMap;
@@ -15,11 +20,45 @@ use tokio::sync::{mpsc, Mutex};
 /// Simple (non-transactional) job processor
 pub struct SimpleJobProcessor {
     config: JobProcessingConfig,
+    dead_letter_queue: Option<Arc<DeadLetterQueue>>,
+    checkpointer: Option<Arc<Checkpointer>>,
+    source_positions: Option<SharedSourcePositions>,
+    start_offsets: HashMap<String, SourceOffset>,
 }
 
 impl SimpleJobProcessor {
     pub fn new(config: JobProcessingConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            dead_letter_queue: None,
+            checkpointer: None,
+            source_positions: None,
+            start_offsets: HashMap::new(),
+        }
+    }
+
+    /// Send records rejected by stream schema enforcement to a dead letter queue
+    pub fn with_dead_letter_queue(mut self, dead_letter_queue: Arc<DeadLetterQueue>) -> Self {
+        self.dead_letter_queue = Some(dead_letter_queue);
+        self
+    }
+
+    /// Take periodic checkpoints and resume the sources from the restored one
+    pub fn with_checkpointer(mut self, checkpointer: Arc<Checkpointer>) -> Self {
+        self.checkpointer = Some(checkpointer);
+        self
+    }
+
+    /// Publish the source positions after every batch, for savepoints of the job
+    pub fn with_source_positions(mut self, positions: SharedSourcePositions) -> Self {
+        self.source_positions = Some(positions);
+        self
+    }
+
+    /// Start reading the sources at these positions, e.g. those of a savepoint
+    pub fn with_start_offsets(mut self, offsets: HashMap<String, SourceOffset>) -> Self {
+        self.start_offsets = offsets;
+        self
     }
 
     /// Get reference to the job processing configuration
@@ -48,6 +87,14 @@ impl SimpleJobProcessor {
 
         // Log comprehensive configuration details
         log_job_configuration(&job_name, &self.config);
+        {
+            let mut engine = engine.lock().await;
+            engine.set_columnar_execution(self.config.columnar_execution);
+            engine.plan_query(&query);
+        }
+
+        let enforcement = JobEnforcement::from_query(&query)?;
+        enforcement.log(&job_name);
 
         // Create enhanced context with multiple sources and sinks
         let mut context =
@@ -61,6 +108,14 @@ impl SimpleJobProcessor {
             // Context is already prepared by engine.prepare_context() above
         }
 
+        // Continue reading where the restored checkpoint left off
+        if let Some(checkpointer) = &self.checkpointer {
+            checkpointer.resume_sources(&mut context).await;
+        }
+        if !self.start_offsets.is_empty() {
+            seek_sources(&mut context, self.start_offsets.clone()).await;
+        }
+
         loop {
             // Check for shutdown signal
             if shutdown_rx.try_recv().is_ok() {
@@ -70,7 +125,14 @@ impl SimpleJobProcessor {
 
             // Process from all sources
             match self
-                .process_multi_source_batch(&mut context, &engine, &query, &job_name, &mut stats)
+                .process_multi_source_batch(
+                    &mut context,
+                    &engine,
+                    &query,
+                    &enforcement,
+                    &job_name,
+                    &mut stats,
+                )
                 .await
             {
                 Ok(()) => {
@@ -79,8 +141,23 @@ impl SimpleJobProcessor {
                     {
                         log_job_progress(&job_name, &stats);
                     }
+                    publish_source_positions(self.source_positions.as_ref(), &context);
+                    checkpoint_job(
+                        self.checkpointer.as_deref(),
+                        &engine,
+                        &context,
+                        &job_name,
+                        false,
+                    )
+                    .await;
                 }
                 Err(e) => {
+                    if is_schema_violation(e.as_ref()) {
+                        error!("Job '{}' failed: {}", job_name, e);
+                        stats.batches_failed += 1;
+                        log_final_stats(&job_name, &stats);
+                        return Err(e);
+                    }
                     warn!(
                         "Job '{}' multi-source batch processing failed: {:?}",
                         job_name, e
@@ -99,6 +176,15 @@ impl SimpleJobProcessor {
             job_name
         );
 
+        checkpoint_job(
+            self.checkpointer.as_deref(),
+            &engine,
+            &context,
+            &job_name,
+            true,
+        )
+        .await;
+
         for source_name in context.list_sources() {
             if let Err(e) = context.commit_source(&source_name).await {
                 warn!(
@@ -145,10 +231,18 @@ impl SimpleJobProcessor {
 
         // Log comprehensive configuration details
         log_job_configuration(&job_name, &self.config);
+        {
+            let mut engine = engine.lock().await;
+            engine.set_columnar_execution(self.config.columnar_execution);
+            engine.plan_query(&query);
+        }
 
         // Log detailed information about the source and sink types
         log_datasource_info(&job_name, reader.as_ref(), writer.as_deref());
 
+        let enforcement = JobEnforcement::from_query(&query)?;
+        enforcement.log(&job_name);
+
         if reader.supports_transactions()
             || writer
                 .as_ref()
@@ -181,6 +275,7 @@ impl SimpleJobProcessor {
                     writer.as_deref_mut(),
                     &engine,
                     &query,
+                    &enforcement,
                     &job_name,
                     &mut stats,
                 )
@@ -195,6 +290,12 @@ impl SimpleJobProcessor {
                     }
                 }
                 Err(e) => {
+                    if is_schema_violation(e.as_ref()) {
+                        error!("Job '{}' failed: {}", job_name, e);
+                        stats.batches_failed += 1;
+                        log_final_stats(&job_name, &stats);
+                        return Err(e);
+                    }
                     warn!("Job '{}' batch processing failed: {:?}", job_name, e);
                     stats.batches_failed += 1;
 
@@ -223,6 +324,7 @@ impl SimpleJobProcessor {
         mut writer: Option<&mut dyn DataWriter>,
         engine: &Arc<Mutex<StreamExecutionEngine>>,
         query: &StreamingQuery,
+        enforcement: &JobEnforcement,
         job_name: &str,
         stats: &mut JobExecutionStats,
     ) -> DataSourceResult<()> {
@@ -230,7 +332,13 @@ impl SimpleJobProcessor {
 
         // Step 1: Read batch from datasource
         let batch = reader.read().await?;
-        if batch.is_empty() {
+        let pushdown_failed = handle_pushdown_failures(
+            reader.take_pushdown_failures(),
+            self.dead_letter_queue.as_deref(),
+            job_name,
+        )
+        .await;
+        if batch.is_empty() && pushdown_failed == 0 {
             debug!("Job '{}': No data available, waiting 100ms", job_name);
             tokio::time::sleep(Duration::from_millis(100)).await;
             return Ok(());
@@ -242,13 +350,36 @@ impl SimpleJobProcessor {
             batch.len()
         );
 
+        // Enforce the declared stream schema before records reach the query
+        let enforced = enforce_source_schema(
+            batch,
+            enforcement.schema.as_ref(),
+            self.dead_letter_queue.as_deref(),
+            job_name,
+        )
+        .await?;
+        stats.records_failed += enforced.rejected as u64;
+        let batch = enforced.records;
+
         // Step 2: Process batch through SQL engine and capture output
-        let batch_result = process_batch_with_output(batch, engine, query, job_name).await;
+        let mut batch_result = process_batch_with_output(batch, engine, query, job_name).await;
+        batch_result.records_failed += pushdown_failed;
         debug!(
             "Job '{}': SQL processing complete - {} records processed, {} failed",
             job_name, batch_result.records_processed, batch_result.records_failed
         );
 
+        // Enforce table constraints before rows reach the sink
+        let constrained = enforce_table_constraints(
+            std::mem::take(&mut batch_result.output_records),
+            enforcement.table_constraints.as_ref(),
+            self.dead_letter_queue.as_deref(),
+            job_name,
+        )
+        .await?;
+        stats.records_failed += constrained.rejected as u64;
+        batch_result.output_records = constrained.records;
+
         // Step 3: Handle results based on failure strategy
         let should_commit = should_commit_batch(
             self.config.failure_strategy,
@@ -266,7 +397,13 @@ impl SimpleJobProcessor {
                 );
 
                 // Attempt to write to sink with retry logic
-                match w.write_batch(batch_result.output_records.clone()).await {
+                match write_output_records(
+                    &mut **w,
+                    batch_result.output_records.clone(),
+                    enforcement.table_constraints.as_ref(),
+                )
+                .await
+                {
                     Ok(()) => {
                         debug!(
                             "Job '{}': Successfully wrote {} records to sink",
@@ -390,6 +527,7 @@ impl SimpleJobProcessor {
         context: &mut crate::ferris::sql::execution::processors::ProcessorContext,
         engine: &Arc<Mutex<StreamExecutionEngine>>,
         query: &StreamingQuery,
+        enforcement: &JobEnforcement,
         job_name: &str,
         stats: &mut JobExecutionStats,
     ) -> DataSourceResult<()> {
@@ -415,6 +553,12 @@ impl SimpleJobProcessor {
 
             // Read batch from current source
             let batch = context.read().await?;
+            total_records_failed += handle_pushdown_failures(
+                context.take_pushdown_failures(source_name),
+                self.dead_letter_queue.as_deref(),
+                job_name,
+            )
+            .await;
             if batch.is_empty() {
                 debug!(
                     "Job '{}': No data from source '{}', skipping",
@@ -430,10 +574,53 @@ impl SimpleJobProcessor {
                 source_name
             );
 
+            let enforced = enforce_source_schema(
+                batch,
+                enforcement.schema.as_ref(),
+                self.dead_letter_queue.as_deref(),
+                job_name,
+            )
+            .await?;
+            stats.records_failed += enforced.rejected as u64;
+            let batch = enforced.records;
+
             // Process batch through SQL engine using execute_with_sources
             {
                 let mut engine_lock = engine.lock().await;
 
+                // Stateless SELECTs run over the whole batch through the columnar kernels
+                match engine_lock.execute_batch(&batch) {
+                    Ok(true) => {
+                        total_records_processed += batch.len();
+                        continue;
+                    }
+                    Ok(false) => {}
+                    // Part of the batch may have been emitted, so it is not executed again
+                    Err(e) => {
+                        total_records_failed += batch.len();
+                        match self.config.failure_strategy {
+                            FailureStrategy::FailBatch | FailureStrategy::RetryWithBackoff => {
+                                error!(
+                                    "Job '{}': Batch processing failed (failing batch): {:?}",
+                                    job_name, e
+                                );
+                                return Err(format!(
+                                    "Batch failed due to batch processing error: {:?}",
+                                    e
+                                )
+                                .into());
+                            }
+                            FailureStrategy::LogAndContinue | FailureStrategy::SendToDLQ => {
+                                warn!(
+                                    "Job '{}': Batch processing failed (continuing): {:?}",
+                                    job_name, e
+                                );
+                                continue;
+                            }
+                        }
+                    }
+                }
+
                 for record in batch {
                     match engine_lock.execute_with_record(query, record).await {
                         Ok(()) => {
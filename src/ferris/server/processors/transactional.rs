@@ -3,8 +3,13 @@
 //! This module provides transactional job processing with exactly-once semantics.
 //! It uses datasource and sink transaction capabilities to ensure atomicity.
 
+use crate::ferris::datasource::SourceOffset;
 use crate::ferris::datasource::{DataReader, DataWriter};
 use crate::ferris::server::processors::common::*;
+use crate::ferris::sql::error::recovery::DeadLetterQueue;
+use crate::ferris::sql::execution::checkpoint::seek_sources;
+use crate::ferris::sql::execution::savepoint::SharedSourcePositions;
+use crate::ferris::sql::execution::Checkpointer;
 use crate::ferris::sql::{StreamExecutionEngine, StreamingQuery};
 use log::{debug, error, info, warn};
 use std::collections::HashMap;
@@ -15,11 +20,45 @@ use tokio::sync::{mpsc, Mutex};
 /// Transactional job processor
 pub struct TransactionalJobProcessor {
     config: JobProcessingConfig,
+    dead_letter_queue: Option<Arc<DeadLetterQueue>>,
+    checkpointer: Option<Arc<Checkpointer>>,
+    source_positions: Option<SharedSourcePositions>,
+    start_offsets: HashMap<String, SourceOffset>,
 }
 
 impl TransactionalJobProcessor {
     pub fn new(config: JobProcessingConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            dead_letter_queue: None,
+            checkpointer: None,
+            source_positions: None,
+            start_offsets: HashMap::new(),
+        }
+    }
+
+    /// Send records rejected by stream schema enforcement to a dead letter queue
+    pub fn with_dead_letter_queue(mut self, dead_letter_queue: Arc<DeadLetterQueue>) -> Self {
+        self.dead_letter_queue = Some(dead_letter_queue);
+        self
+    }
+
+    /// Take periodic checkpoints and resume the sources from the restored one
+    pub fn with_checkpointer(mut self, checkpointer: Arc<Checkpointer>) -> Self {
+        self.checkpointer = Some(checkpointer);
+        self
+    }
+
+    /// Publish the source positions after every batch, for savepoints of the job
+    pub fn with_source_positions(mut self, positions: SharedSourcePositions) -> Self {
+        self.source_positions = Some(positions);
+        self
+    }
+
+    /// Start reading the sources at these positions, e.g. those of a savepoint
+    pub fn with_start_offsets(mut self, offsets: HashMap<String, SourceOffset>) -> Self {
+        self.start_offsets = offsets;
+        self
     }
 
     /// Get reference to the job processing configuration
@@ -63,6 +102,14 @@ impl TransactionalJobProcessor {
 
         // Log comprehensive configuration details
         log_job_configuration(&job_name, &self.config);
+        {
+            let mut engine = engine.lock().await;
+            engine.set_columnar_execution(self.config.columnar_execution);
+            engine.plan_query(&query);
+        }
+
+        let enforcement = JobEnforcement::from_query(&query)?;
+        enforcement.log(&job_name);
 
         // Create enhanced context with multiple sources and sinks
         let mut context =
@@ -76,6 +123,14 @@ impl TransactionalJobProcessor {
             // Context is already prepared by engine.prepare_context() above
         }
 
+        // Continue reading where the restored checkpoint left off
+        if let Some(checkpointer) = &self.checkpointer {
+            checkpointer.resume_sources(&mut context).await;
+        }
+        if !self.start_offsets.is_empty() {
+            seek_sources(&mut context, self.start_offsets.clone()).await;
+        }
+
         loop {
             // Check for shutdown signal
             if shutdown_rx.try_recv().is_ok() {
@@ -89,6 +144,7 @@ impl TransactionalJobProcessor {
                     &mut context,
                     &engine,
                     &query,
+                    &enforcement,
                     &job_name,
                     &readers_support_tx,
                     &writers_support_tx,
@@ -102,8 +158,23 @@ impl TransactionalJobProcessor {
                     {
                         log_job_progress(&job_name, &stats);
                     }
+                    publish_source_positions(self.source_positions.as_ref(), &context);
+                    checkpoint_job(
+                        self.checkpointer.as_deref(),
+                        &engine,
+                        &context,
+                        &job_name,
+                        false,
+                    )
+                    .await;
                 }
                 Err(e) => {
+                    if is_schema_violation(e.as_ref()) {
+                        error!("Job '{}' failed: {}", job_name, e);
+                        stats.batches_failed += 1;
+                        log_final_stats(&job_name, &stats);
+                        return Err(e);
+                    }
                     warn!(
                         "Job '{}' transactional multi-source batch processing failed: {:?}",
                         job_name, e
@@ -122,6 +193,15 @@ impl TransactionalJobProcessor {
             job_name
         );
 
+        checkpoint_job(
+            self.checkpointer.as_deref(),
+            &engine,
+            &context,
+            &job_name,
+            true,
+        )
+        .await;
+
         for source_name in context.list_sources() {
             if let Err(e) = context.commit_source(&source_name).await {
                 warn!(
@@ -170,10 +250,18 @@ impl TransactionalJobProcessor {
 
         // Log comprehensive configuration details
         log_job_configuration(&job_name, &self.config);
+        {
+            let mut engine = engine.lock().await;
+            engine.set_columnar_execution(self.config.columnar_execution);
+            engine.plan_query(&query);
+        }
 
         // Log detailed information about the source and sink types
         log_datasource_info(&job_name, reader.as_ref(), writer.as_deref());
 
+        let enforcement = JobEnforcement::from_query(&query)?;
+        enforcement.log(&job_name);
+
         info!(
             "Job '{}' starting transactional processing (reader_tx: {}, writer_tx: {})",
             job_name, reader_supports_tx, writer_supports_tx
@@ -193,6 +281,7 @@ impl TransactionalJobProcessor {
                     writer.as_deref_mut(),
                     &engine,
                     &query,
+                    &enforcement,
                     &job_name,
                     reader_supports_tx,
                     writer_supports_tx,
@@ -209,6 +298,12 @@ impl TransactionalJobProcessor {
                     }
                 }
                 Err(e) => {
+                    if is_schema_violation(e.as_ref()) {
+                        error!("Job '{}' failed: {}", job_name, e);
+                        stats.batches_failed += 1;
+                        log_final_stats(&job_name, &stats);
+                        return Err(e);
+                    }
                     warn!("Job '{}' batch processing failed: {:?}", job_name, e);
                     stats.batches_failed += 1;
 
@@ -237,6 +332,7 @@ impl TransactionalJobProcessor {
         mut writer: Option<&mut dyn DataWriter>,
         engine: &Arc<Mutex<StreamExecutionEngine>>,
         query: &StreamingQuery,
+        enforcement: &JobEnforcement,
         job_name: &str,
         reader_supports_tx: bool,
         writer_supports_tx: bool,
@@ -279,7 +375,13 @@ impl TransactionalJobProcessor {
 
         // Step 2: Read batch from datasource
         let batch = reader.read().await?;
-        if batch.is_empty() {
+        let pushdown_failed = handle_pushdown_failures(
+            reader.take_pushdown_failures(),
+            self.dead_letter_queue.as_deref(),
+            job_name,
+        )
+        .await;
+        if batch.is_empty() && pushdown_failed == 0 {
             // No data available - abort any active transactions
             self.abort_transactions(reader, writer, reader_tx_active, writer_tx_active, job_name)
                 .await?;
@@ -287,8 +389,61 @@ impl TransactionalJobProcessor {
             return Ok(());
         }
 
+        // Enforce the declared stream schema before records reach the query
+        let batch = match enforce_source_schema(
+            batch,
+            enforcement.schema.as_ref(),
+            self.dead_letter_queue.as_deref(),
+            job_name,
+        )
+        .await
+        {
+            Ok(enforced) => {
+                stats.records_failed += enforced.rejected as u64;
+                enforced.records
+            }
+            Err(e) => {
+                self.abort_transactions(
+                    reader,
+                    writer,
+                    reader_tx_active,
+                    writer_tx_active,
+                    job_name,
+                )
+                .await?;
+                return Err(e.into());
+            }
+        };
+
         // Step 3: Process batch through SQL engine and capture output
-        let batch_result = process_batch_with_output(batch, engine, query, job_name).await;
+        let mut batch_result = process_batch_with_output(batch, engine, query, job_name).await;
+        batch_result.records_failed += pushdown_failed;
+
+        // Enforce table constraints before rows reach the sink
+        match enforce_table_constraints(
+            std::mem::take(&mut batch_result.output_records),
+            enforcement.table_constraints.as_ref(),
+            self.dead_letter_queue.as_deref(),
+            job_name,
+        )
+        .await
+        {
+            Ok(constrained) => {
+                stats.records_failed += constrained.rejected as u64;
+                batch_result.output_records = constrained.records;
+            }
+            Err(e) => {
+                self.abort_transactions(
+                    reader,
+                    writer,
+                    reader_tx_active,
+                    writer_tx_active,
+                    job_name,
+                )
+                .await?;
+                return Err(e.into());
+            }
+        }
 
         // Step 4: Handle results based on failure strategy
         let should_commit = should_commit_batch(
@@ -305,7 +460,13 @@ impl TransactionalJobProcessor {
                     job_name,
                     batch_result.output_records.len()
                 );
-                match w.write_batch(batch_result.output_records.clone()).await {
+                match write_output_records(
+                    &mut **w,
+                    batch_result.output_records.clone(),
+                    enforcement.table_constraints.as_ref(),
+                )
+                .await
+                {
                     Ok(()) => {
                         debug!(
                             "Job '{}': Successfully wrote {} records to sink",
@@ -504,6 +665,7 @@ impl TransactionalJobProcessor {
         context: &mut crate::ferris::sql::execution::processors::ProcessorContext,
         engine: &Arc<Mutex<StreamExecutionEngine>>,
         query: &StreamingQuery,
+        enforcement: &JobEnforcement,
         job_name: &str,
         readers_support_tx: &HashMap<String, bool>,
         writers_support_tx: &HashMap<String, bool>,
@@ -619,6 +781,12 @@ impl TransactionalJobProcessor {
             context.set_active_reader(source_name)?;
 
             let batch = context.read().await?;
+            total_records_failed += handle_pushdown_failures(
+                context.take_pushdown_failures(source_name),
+                self.dead_letter_queue.as_deref(),
+                job_name,
+            )
+            .await;
             if batch.is_empty() {
                 debug!(
                     "Job '{}': No data from source '{}', skipping",
@@ -634,10 +802,59 @@ impl TransactionalJobProcessor {
                 source_name
             );
 
+            let batch = match enforce_source_schema(
+                batch,
+                enforcement.schema.as_ref(),
+                self.dead_letter_queue.as_deref(),
+                job_name,
+            )
+            .await
+            {
+                Ok(enforced) => {
+                    stats.records_failed += enforced.rejected as u64;
+                    enforced.records
+                }
+                Err(e) => {
+                    self.abort_multi_source_transactions(
+                        context,
+                        &active_reader_transactions,
+                        &active_writer_transactions,
+                        job_name,
+                    )
+                    .await?;
+                    return Err(e.into());
+                }
+            };
+
             // Process batch through SQL engine
             {
                 let mut engine_lock = engine.lock().await;
 
+                // Stateless SELECTs run over the whole batch through the columnar kernels
+                match engine_lock.execute_batch(&batch) {
+                    Ok(true) => {
+                        total_records_processed += batch.len();
+                        continue;
+                    }
+                    Ok(false) => {}
+                    // Part of the batch may have been emitted, so it is not executed again
+                    Err(e) => {
+                        total_records_failed += batch.len();
+                        error!(
+                            "Job '{}': Batch processing failed within transaction: {:?}",
+                            job_name, e
+                        );
+                        if matches!(
+                            self.config.failure_strategy,
+                            FailureStrategy::FailBatch | FailureStrategy::RetryWithBackoff
+                        ) {
+                            processing_successful = false;
+                            break; // Exit source processing loop
+                        }
+                        continue;
+                    }
+                }
+
                 for record in batch {
                     match engine_lock.execute_with_record(query, record).await {
                         Ok(()) => {
@@ -5,17 +5,33 @@
 //! hardcoded Kafka-only processing.
 
 use crate::ferris::datasource::DataWriter;
+use crate::ferris::schema::Schema;
 use crate::ferris::server::processors::{
     create_multi_sink_writers, create_multi_source_readers, FailureStrategy, JobProcessingConfig,
     SimpleJobProcessor, TransactionalJobProcessor,
 };
 use crate::ferris::sql::{
-    ast::StreamingQuery, config::with_clause_parser::WithClauseParser,
-    execution::performance::PerformanceMonitor, query_analyzer::QueryAnalyzer, SqlApplication,
-    SqlError, SqlValidator, StreamExecutionEngine, StreamingSqlParser,
+    ast::{ObjectType, ShowResourceType, StreamingQuery},
+    config::with_clause_parser::WithClauseParser,
+    context::{plan_object_ddl, query_dependencies, CatalogChange},
+    execution::algorithms::hash_join::JoinStatistics,
+    execution::performance::PerformanceMonitor,
+    execution::savepoint::{
+        list_savepoints, SavepointSummary, SharedSourcePositions, SAVEPOINT_DIR_PROPERTY,
+    },
+    execution::subquery::{materialized_table, subquery_sources, LookupSource},
+    execution::{
+        CheckpointConfig, Checkpointer, FieldValue, MemoryBudgetConfig, MemoryMetrics, Savepoint,
+        StateBackendConfig, StateMetrics, StateTtlConfig,
+    },
+    formatter::format_query,
+    query_analyzer::QueryAnalyzer,
+    QueryOptimizer, SchemaCatalog, SemanticAnalyzer, SqlApplication, SqlError, SqlValidator,
+    StreamExecutionEngine, StreamingSqlParser,
 };
 use log::{debug, error, info, warn};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::{mpsc, Mutex, RwLock};
@@ -28,12 +44,26 @@ pub struct StreamJobServer {
     max_jobs: usize,
     job_counter: Arc<Mutex<u64>>,
     performance_monitor: Option<Arc<PerformanceMonitor>>,
+    /// Source schemas used to check queries before they are deployed
+    schema_catalog: Arc<RwLock<SchemaCatalog>>,
+    /// Join statistics by right source name, used to choose join strategies
+    join_statistics: Arc<RwLock<HashMap<String, JoinStatistics>>>,
+    /// Where savepoints go for jobs without a `savepoint.dir` property
+    savepoint_directory: Option<PathBuf>,
+    /// Properties of streams and tables set with ALTER ... SET
+    object_properties: Arc<RwLock<HashMap<String, HashMap<String, String>>>>,
+    /// Reference data the subqueries of every job can read
+    lookup_sources: Arc<RwLock<HashMap<String, Arc<dyn LookupSource>>>>,
 }
 
+/// How long `STOP JOB ... WITH SAVEPOINT` waits for the batch in progress
+const SAVEPOINT_STOP_TIMEOUT: Duration = Duration::from_secs(30);
+
 #[derive(Debug)]
 pub struct RunningJob {
     pub name: String,
     pub version: String,
+    /// Canonical text of the deployed query, see [`format_query`]
     pub query: String,
     pub topic: String,
     pub status: JobStatus,
@@ -42,6 +72,25 @@ pub struct RunningJob {
     pub execution_handle: JoinHandle<()>,
     pub shutdown_sender: mpsc::Sender<()>,
     pub metrics: JobMetrics,
+    /// What a savepoint of the job is taken from
+    pub(crate) state: JobState,
+}
+
+/// Engine, executed query and source positions of a running job
+pub(crate) struct JobState {
+    engine: Arc<Mutex<StreamExecutionEngine>>,
+    query: StreamingQuery,
+    source_positions: SharedSourcePositions,
+    savepoint_directory: Option<PathBuf>,
+}
+
+impl std::fmt::Debug for JobState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JobState")
+            .field("query", &format_query(&self.query))
+            .field("savepoint_directory", &self.savepoint_directory)
+            .finish_non_exhaustive()
+    }
 }
 
 #[derive(Clone, Debug, serde::Serialize)]
@@ -109,7 +158,107 @@ impl StreamJobServer {
             max_jobs,
             job_counter: Arc::new(Mutex::new(0)),
             performance_monitor,
+            schema_catalog: Arc::new(RwLock::new(SchemaCatalog::new())),
+            join_statistics: Arc::new(RwLock::new(HashMap::new())),
+            savepoint_directory: None,
+            object_properties: Arc::new(RwLock::new(HashMap::new())),
+            lookup_sources: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Write savepoints of jobs without a `savepoint.dir` property to `directory`
+    pub fn with_savepoint_directory(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.savepoint_directory = Some(directory.into());
+        self
+    }
+
+    /// Register the schema of a stream, table or source URI so deployed queries are
+    /// checked against it
+    pub async fn register_schema(&self, name: impl Into<String>, schema: Schema) {
+        self.schema_catalog
+            .write()
+            .await
+            .register_schema(name, schema);
+    }
+
+    /// Register cardinality and memory estimates for joins against a source, so deployed
+    /// queries pick a join strategy from them
+    pub async fn register_join_statistics(
+        &self,
+        right_source: impl Into<String>,
+        statistics: JoinStatistics,
+    ) {
+        self.join_statistics
+            .write()
+            .await
+            .insert(right_source.into(), statistics);
+    }
+
+    /// Register reference data that the subqueries of every job read as `FROM <name>`,
+    /// running jobs included
+    pub async fn register_lookup_source(
+        &self,
+        name: impl Into<String>,
+        source: Arc<dyn LookupSource>,
+    ) {
+        let name = name.into();
+        self.lookup_sources
+            .write()
+            .await
+            .insert(name.clone(), Arc::clone(&source));
+        let engines: Vec<_> = self
+            .jobs
+            .read()
+            .await
+            .values()
+            .map(|job| Arc::clone(&job.state.engine))
+            .collect();
+        for engine in engines {
+            engine
+                .lock()
+                .await
+                .register_lookup_source(name.clone(), Arc::clone(&source));
+        }
+    }
+
+    /// Fail when a subquery of a job reads a source its engine cannot resolve: neither a
+    /// lookup source of the server nor the table the job itself materializes
+    async fn check_subquery_sources(
+        &self,
+        query: &StreamingQuery,
+        lookup_sources: &HashMap<String, Arc<dyn LookupSource>>,
+    ) -> Result<(), SqlError> {
+        for source in subquery_sources(query) {
+            if lookup_sources.contains_key(&source)
+                || materialized_table(query) == Some(source.as_str())
+            {
+                continue;
+            }
+            let owner = self
+                .jobs
+                .read()
+                .await
+                .values()
+                .find(|job| materialized_table(&job.state.query) == Some(source.as_str()))
+                .map(|job| job.name.clone());
+            let message = match owner {
+                Some(job) => format!(
+                    "Subquery source '{}' is materialized by job '{}'; jobs only read their own \
+                     tables and the lookup sources registered with the server",
+                    source, job
+                ),
+                None => format!(
+                    "Subquery source '{}' is neither a table this job materializes nor a lookup \
+                     source registered with the server",
+                    source
+                ),
+            };
+            return Err(SqlError::ExecutionError {
+                message,
+                query: Some(format_query(query)),
+            });
         }
+        Ok(())
     }
 
     /// Get performance metrics (if monitoring is enabled)
@@ -235,6 +384,36 @@ impl StreamJobServer {
         // Parse and validate the query
         let parser = StreamingSqlParser::new();
         let parsed_query = parser.parse(&query)?;
+        let canonical_query = format_query(&parsed_query);
+
+        // Subqueries read tables of this job or lookup sources of the server
+        let lookup_sources = self.lookup_sources.read().await.clone();
+        self.check_subquery_sources(&parsed_query, &lookup_sources)
+            .await?;
+
+        // Resolve columns and check types against the known source schemas
+        let mut optimizer = {
+            let mut schema_catalog = self.schema_catalog.write().await;
+            let semantic_analysis = SemanticAnalyzer::new(&schema_catalog)
+                .with_sql(&query)
+                .analyze(&parsed_query)
+                .into_result()?;
+            let optimizer = QueryOptimizer::new().with_schema_catalog(schema_catalog.clone());
+            schema_catalog.apply_statement(&parsed_query, &semantic_analysis);
+            optimizer
+        };
+
+        // Rewrite the query for execution; readers get its projection and filter once created
+        for (right_source, statistics) in self.join_statistics.read().await.iter() {
+            optimizer = optimizer.with_join_statistics(right_source.clone(), statistics.clone());
+        }
+        let optimized = optimizer.optimize(&parsed_query);
+        if !optimized.applied_rules.is_empty() {
+            info!(
+                "Job '{}': query optimizer applied {:?}",
+                name, optimized.applied_rules
+            );
+        }
 
         // Analyze query to determine required resources
         let analyzer = QueryAnalyzer::new(self.base_group_id.clone());
@@ -255,6 +434,58 @@ impl StreamJobServer {
         // Create execution engine for this job with query-driven format
         let (output_sender, mut output_receiver) = mpsc::unbounded_channel();
         let mut execution_engine = StreamExecutionEngine::new(output_sender);
+        execution_engine.set_join_strategies(optimized.join_strategies.clone());
+        for (source_name, source) in lookup_sources {
+            execution_engine.register_lookup_source(source_name, source);
+        }
+
+        // Operator state goes to the backends the job's properties select
+        let properties = Self::get_query_properties(&parsed_query);
+        let state_config = StateBackendConfig::from_properties(&properties)?;
+        execution_engine.set_state_backends(state_config.open(&name)?);
+        if let Some(ttl) = StateTtlConfig::from_properties(&properties)? {
+            execution_engine.set_state_ttl(ttl)?;
+        }
+        // Buffering operators spill to disk rather than outgrow the job's memory budget
+        if let Some(budget) = MemoryBudgetConfig::from_properties(&properties)? {
+            execution_engine.set_memory_budget(budget.open(&name));
+        }
+
+        // START JOB ... FROM SAVEPOINT takes the state and source positions of the
+        // savepoint; otherwise resume from the latest checkpoint when the job takes them
+        let start_offsets = match &parsed_query {
+            StreamingQuery::StartJob {
+                savepoint: Some(path),
+                ..
+            } => {
+                let savepoint = Savepoint::read(Path::new(path))?;
+                savepoint.restore(&optimized.query, &mut execution_engine)?;
+                info!(
+                    "Job '{}' restored from savepoint '{}' of job '{}' version '{}'",
+                    name, path, savepoint.job, savepoint.version
+                );
+                Some(savepoint.source_offsets)
+            }
+            _ => None,
+        };
+        let checkpointer = match CheckpointConfig::from_properties(&properties)? {
+            Some(config) => {
+                let checkpointer = Checkpointer::new(config, &name)?;
+                if start_offsets.is_none() {
+                    if let Some(id) = checkpointer.restore(&mut execution_engine)? {
+                        info!("Job '{}' restored from checkpoint {}", name, id);
+                    }
+                }
+                Some(Arc::new(checkpointer))
+            }
+            None => None,
+        };
+        let start_offsets = start_offsets.unwrap_or_default();
+        let source_positions = SharedSourcePositions::default();
+        let savepoint_directory = properties
+            .get(SAVEPOINT_DIR_PROPERTY)
+            .map(PathBuf::from)
+            .or_else(|| self.savepoint_directory.clone());
 
         // Enable performance monitoring for this job if available
         if let Some(monitor) = &self.performance_monitor {
@@ -262,6 +493,12 @@ impl StreamJobServer {
         }
 
         let execution_engine = Arc::new(tokio::sync::Mutex::new(execution_engine));
+        let state = JobState {
+            engine: Arc::clone(&execution_engine),
+            query: optimized.query.clone(),
+            source_positions: Arc::clone(&source_positions),
+            savepoint_directory,
+        };
 
         // Clone data for the job task
         let job_name = name.clone();
@@ -313,13 +550,16 @@ impl StreamJobServer {
             )
             .await
             {
-                Ok(readers) => {
+                Ok(mut readers) => {
                     info!(
                         "Job '{}' successfully created {} data sources",
                         job_name,
                         readers.len()
                     );
 
+                    // Let the readers skip unreferenced fields and filtered-out records
+                    let parsed_query = optimized.push_down_into(&mut readers);
+
                     // Create all sinks
                     match create_multi_sink_writers(
                         &analysis.required_sinks,
@@ -366,7 +606,13 @@ impl StreamJobServer {
 
                             if use_transactions {
                                 info!("Job '{}' using transactional processor for multi-source processing", job_name);
-                                let processor = TransactionalJobProcessor::new(config);
+                                let mut processor = TransactionalJobProcessor::new(config)
+                                    .with_source_positions(Arc::clone(&source_positions))
+                                    .with_start_offsets(start_offsets.clone());
+                                if let Some(checkpointer) = &checkpointer {
+                                    processor =
+                                        processor.with_checkpointer(Arc::clone(checkpointer));
+                                }
 
                                 match processor
                                     .process_multi_job(
@@ -397,7 +643,13 @@ impl StreamJobServer {
                                     "Job '{}' using simple processor for multi-source processing",
                                     job_name
                                 );
-                                let processor = SimpleJobProcessor::new(config);
+                                let mut processor = SimpleJobProcessor::new(config)
+                                    .with_source_positions(Arc::clone(&source_positions))
+                                    .with_start_offsets(start_offsets.clone());
+                                if let Some(checkpointer) = &checkpointer {
+                                    processor =
+                                        processor.with_checkpointer(Arc::clone(checkpointer));
+                                }
 
                                 match processor
                                     .process_multi_job(
@@ -438,7 +690,12 @@ impl StreamJobServer {
 
                             // Still proceed with processing using simple processor
                             let config = Self::extract_job_config_from_query(&parsed_query);
-                            let processor = SimpleJobProcessor::new(config);
+                            let mut processor = SimpleJobProcessor::new(config)
+                                .with_source_positions(Arc::clone(&source_positions))
+                                .with_start_offsets(start_offsets);
+                            if let Some(checkpointer) = &checkpointer {
+                                processor = processor.with_checkpointer(Arc::clone(checkpointer));
+                            }
 
                             match processor
                                 .process_multi_job(
@@ -474,7 +731,7 @@ impl StreamJobServer {
         let job = RunningJob {
             name: name.clone(),
             version: version.clone(),
-            query: query.clone(),
+            query: canonical_query,
             topic: topic.clone(),
             status: JobStatus::Running,
             created_at: chrono::Utc::now(),
@@ -482,6 +739,7 @@ impl StreamJobServer {
             execution_handle,
             shutdown_sender,
             metrics: JobMetrics::default(),
+            state,
         };
 
         // Store the job
@@ -520,6 +778,252 @@ impl StreamJobServer {
         }
     }
 
+    /// Stop a job after the batch in progress and write a savepoint of its state
+    ///
+    /// Returns the path of the savepoint, for `START JOB ... FROM SAVEPOINT`.
+    pub async fn stop_job_with_savepoint(&self, name: &str) -> Result<PathBuf, SqlError> {
+        let job = {
+            let mut jobs = self.jobs.write().await;
+            let job = jobs.get(name).ok_or_else(|| SqlError::ExecutionError {
+                message: format!("Job '{}' not found", name),
+                query: None,
+            })?;
+            if job.state.savepoint_directory.is_none() {
+                return Err(SqlError::ConfigurationError {
+                    message: format!(
+                        "[SAVEPOINT-003] Job '{}' has no savepoint directory; set '{}' or start the server with one",
+                        name, SAVEPOINT_DIR_PROPERTY
+                    ),
+                });
+            }
+            jobs.remove(name).expect("job exists")
+        };
+        info!("Stopping job '{}' with savepoint", name);
+
+        // The job writes its last positions and checkpoint once it leaves its loop
+        if let Err(e) = job.shutdown_sender.try_send(()) {
+            warn!("Failed to send shutdown signal to job '{}': {:?}", name, e);
+        }
+        let mut execution_handle = job.execution_handle;
+        if tokio::time::timeout(SAVEPOINT_STOP_TIMEOUT, &mut execution_handle)
+            .await
+            .is_err()
+        {
+            execution_handle.abort();
+            return Err(SqlError::ExecutionError {
+                message: format!(
+                    "[SAVEPOINT-004] Job '{}' did not stop within {}s; it was aborted without a savepoint",
+                    name,
+                    SAVEPOINT_STOP_TIMEOUT.as_secs()
+                ),
+                query: None,
+            });
+        }
+
+        let source_positions = job
+            .state
+            .source_positions
+            .lock()
+            .map(|positions| positions.clone())
+            .unwrap_or_default();
+        let engine = job.state.engine.lock().await;
+        let savepoint = Savepoint::take(
+            name,
+            &job.version,
+            &job.state.query,
+            &engine,
+            &source_positions,
+        )?;
+        let directory = job.state.savepoint_directory.expect("checked above");
+        let path = savepoint.write(&directory)?;
+        info!("Stopped job '{}' with savepoint '{}'", name, path.display());
+        Ok(path)
+    }
+
+    /// Savepoints in the server's savepoint directory, of one job or of all
+    pub fn list_savepoints(&self, job: Option<&str>) -> Result<Vec<SavepointSummary>, SqlError> {
+        match &self.savepoint_directory {
+            Some(directory) => list_savepoints(directory, job),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Execute a job management statement against the jobs and savepoints of this server
+    ///
+    /// Handles `STOP JOB <name> [WITH SAVEPOINT]`, `DROP JOB`, `ALTER` and `DROP` of streams
+    /// and tables, and `SHOW SAVEPOINTS [<job>]`, which the execution engine of a single
+    /// query cannot carry out. ALTER and DROP are checked against the running jobs of the
+    /// server. Returns one row per result.
+    pub async fn execute_statement(
+        &self,
+        sql: &str,
+    ) -> Result<Vec<HashMap<String, FieldValue>>, SqlError> {
+        match StreamingSqlParser::new().parse(sql)? {
+            StreamingQuery::StopJob {
+                name, savepoint, ..
+            } => {
+                let mut row = HashMap::new();
+                if savepoint {
+                    let path = self.stop_job_with_savepoint(&name).await?;
+                    row.insert(
+                        "savepoint".to_string(),
+                        FieldValue::String(path.display().to_string()),
+                    );
+                } else {
+                    self.stop_job(&name).await?;
+                }
+                row.insert("job_name".to_string(), FieldValue::String(name));
+                row.insert(
+                    "status".to_string(),
+                    FieldValue::String("stopped".to_string()),
+                );
+                Ok(vec![row])
+            }
+            StreamingQuery::Drop {
+                object_type: ObjectType::Job,
+                name,
+                if_exists,
+                ..
+            } => {
+                if if_exists && !self.jobs.read().await.contains_key(&name) {
+                    return Ok(Vec::new());
+                }
+                self.stop_job(&name).await?;
+                Ok(vec![HashMap::from([
+                    ("job_name".to_string(), FieldValue::String(name)),
+                    (
+                        "status".to_string(),
+                        FieldValue::String("dropped".to_string()),
+                    ),
+                ])])
+            }
+            ddl @ (StreamingQuery::Alter { .. } | StreamingQuery::Drop { .. }) => {
+                self.apply_ddl(&ddl).await
+            }
+            StreamingQuery::Show {
+                resource_type: ShowResourceType::Savepoints { name },
+                ..
+            } => Ok(self
+                .list_savepoints(name.as_deref())?
+                .into_iter()
+                .map(|summary| {
+                    HashMap::from([
+                        (
+                            "path".to_string(),
+                            FieldValue::String(summary.path.display().to_string()),
+                        ),
+                        ("job".to_string(), FieldValue::String(summary.job)),
+                        ("version".to_string(), FieldValue::String(summary.version)),
+                        (
+                            "created_at".to_string(),
+                            FieldValue::Integer(summary.created_at),
+                        ),
+                        (
+                            "operators".to_string(),
+                            FieldValue::Array(
+                                summary
+                                    .operators
+                                    .into_iter()
+                                    .map(FieldValue::String)
+                                    .collect(),
+                            ),
+                        ),
+                    ])
+                })
+                .collect()),
+            _ => Err(SqlError::ExecutionError {
+                message:
+                    "Only STOP JOB, ALTER, DROP and SHOW SAVEPOINTS are executed by the job server"
+                        .to_string(),
+                query: Some(sql.to_string()),
+            }),
+        }
+    }
+
+    /// Properties set on a stream or table with ALTER ... SET
+    pub async fn object_properties(&self, name: &str) -> Option<HashMap<String, String>> {
+        self.object_properties.read().await.get(name).cloned()
+    }
+
+    /// Running jobs whose query reads from or writes to a stream or table
+    async fn dependent_jobs(&self, name: &str) -> Vec<String> {
+        let jobs = self.jobs.read().await;
+        let mut dependents: Vec<String> = jobs
+            .values()
+            .filter(|job| {
+                query_dependencies(&job.state.query)
+                    .iter()
+                    .any(|d| d == name)
+            })
+            .map(|job| job.name.clone())
+            .collect();
+        dependents.sort();
+        dependents
+    }
+
+    /// Apply an ALTER or DROP of a stream or table to the server catalog
+    ///
+    /// Refused while running jobs depend on the object, except for columns added under
+    /// the evolution rules of its schema.
+    async fn apply_ddl(
+        &self,
+        query: &StreamingQuery,
+    ) -> Result<Vec<HashMap<String, FieldValue>>, SqlError> {
+        let (StreamingQuery::Alter { name, .. } | StreamingQuery::Drop { name, .. }) = query else {
+            return plan_object_ddl(query, None, &[]).map(|_| Vec::new());
+        };
+        let dependent_jobs = self.dependent_jobs(name).await;
+        let mut schema_catalog = self.schema_catalog.write().await;
+        let status = match plan_object_ddl(query, schema_catalog.get(name), &dependent_jobs)? {
+            CatalogChange::Unchanged | CatalogChange::DropJob { .. } => "unchanged",
+            CatalogChange::ReplaceSchema { name, schema } => {
+                schema_catalog.register_schema(name, schema);
+                "altered"
+            }
+            CatalogChange::SetProperties { name, properties } => {
+                self.object_properties
+                    .write()
+                    .await
+                    .entry(name)
+                    .or_default()
+                    .extend(properties);
+                "altered"
+            }
+            CatalogChange::DropObject { name } => {
+                schema_catalog.remove(&name);
+                self.object_properties.write().await.remove(&name);
+                "dropped"
+            }
+        };
+        Ok(vec![HashMap::from([
+            ("name".to_string(), FieldValue::String(name.clone())),
+            ("status".to_string(), FieldValue::String(status.to_string())),
+        ])])
+    }
+
+    /// Keys held and evicted by the stateful operators of a job
+    pub async fn job_state_metrics(
+        &self,
+        name: &str,
+    ) -> Option<Result<Vec<StateMetrics>, SqlError>> {
+        let engine = {
+            let jobs = self.jobs.read().await;
+            Arc::clone(&jobs.get(name)?.state.engine)
+        };
+        let engine = engine.lock().await;
+        Some(engine.state_metrics())
+    }
+
+    /// Memory use and spilling of a job with a `memory.budget.mb`
+    pub async fn job_memory_metrics(&self, name: &str) -> Option<MemoryMetrics> {
+        let engine = {
+            let jobs = self.jobs.read().await;
+            Arc::clone(&jobs.get(name)?.state.engine)
+        };
+        let engine = engine.lock().await;
+        engine.memory_metrics()
+    }
+
     pub async fn pause_job(&self, name: &str) -> Result<(), SqlError> {
         let mut jobs = self.jobs.write().await;
         if let Some(job) = jobs.get_mut(name) {
@@ -589,7 +1093,8 @@ impl StreamJobServer {
 
         // Pre-deployment SQL validation to prevent runtime failures
         info!("Validating SQL application before deployment...");
-        let validator = SqlValidator::new();
+        let validator =
+            SqlValidator::new().with_schema_catalog(self.schema_catalog.read().await.clone());
 
         // Reconstruct the SQL content from the application statements for validation
         let sql_content = app
@@ -608,6 +1113,7 @@ impl StreamJobServer {
             );
             for query_result in &validation_result.query_results {
                 if !query_result.parsing_errors.is_empty()
+                    || !query_result.semantic_errors.is_empty()
                     || !query_result.configuration_errors.is_empty()
                 {
                     error!(
@@ -628,6 +1134,13 @@ impl StreamJobServer {
                         );
                     }
 
+                    for error in &query_result.semantic_errors {
+                        error!(
+                            "  Semantic Error: {} (line: {:?})",
+                            error.message, error.line
+                        );
+                    }
+
                     for error in &query_result.configuration_errors {
                         error!(
                             "  Configuration Error: {} (line: {:?})",
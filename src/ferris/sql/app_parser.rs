@@ -21,6 +21,7 @@ can be defined in .sql files with multiple statements and metadata.
 -- Description: Complete e-commerce data processing pipeline
 -- Author: Analytics Team
 -- Dependencies: kafka-orders, kafka-users, kafka-products
+-- Parameter: min_amount DECIMAL(10,2)
 
 -- Create base streams
 CREATE STREAM raw_orders AS
@@ -42,7 +43,7 @@ JOIN raw_users u ON o.customer_id = u.user_id;
 
 -- Deploy processing jobs
 START JOB high_value_orders AS
-SELECT * FROM enriched_orders WHERE amount > 1000
+SELECT * FROM enriched_orders WHERE amount > :min_amount
 WITH ('replicas' = '3');
 
 START JOB user_analytics AS
@@ -54,10 +55,20 @@ FROM enriched_orders
 GROUP BY customer_id
 WINDOW TUMBLING(1h);
 ```
+
+## Parameters
+
+`-- Parameter: <name> <TYPE>` declares a parameter that statements reference as `:name`.
+[`SqlApplication::bind_parameters`] checks values against the declared types and writes
+them into the statements before the application is deployed; a single statement is
+prepared with [`SqlApplication::prepare_statement`].
 */
 
-use crate::ferris::sql::ast::StreamSource;
-use crate::ferris::sql::{SqlError, StreamingQuery, StreamingSqlParser};
+use crate::ferris::sql::ast::{QueryParameter, StreamSource};
+use crate::ferris::sql::execution::PreparedStatement;
+use crate::ferris::sql::formatter::format_query;
+use crate::ferris::sql::semantic_analyzer::cast_target_type;
+use crate::ferris::sql::{FieldValue, SqlError, StreamingQuery, StreamingSqlParser};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -80,6 +91,16 @@ pub struct ApplicationMetadata {
     pub dependencies: Vec<String>,
     pub created_at: DateTime<Utc>,
     pub tags: HashMap<String, String>,
+    pub parameters: Vec<ParameterDeclaration>,
+}
+
+/// A parameter declared with `-- Parameter: <name> <TYPE>`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ParameterDeclaration {
+    /// Name statements reference the parameter by, as `:name`
+    pub name: String,
+    /// Declared type, such as `BIGINT` or `DECIMAL(10,2)`
+    pub data_type: String,
 }
 
 /// Individual SQL statement within an application
@@ -106,6 +127,84 @@ pub enum StatementType {
     Other(String),
 }
 
+impl SqlApplication {
+    /// Prepare a statement of the application, typing its parameters as declared
+    pub fn prepare_statement(
+        &self,
+        statement: &SqlStatement,
+    ) -> Result<PreparedStatement, SqlError> {
+        let query = StreamingSqlParser::new().parse(&statement.sql)?;
+        let mut prepared = PreparedStatement::new(query)?;
+        for parameter in prepared.parameters().to_vec() {
+            let declaration = match &parameter.parameter {
+                QueryParameter::Named(name) => {
+                    self.metadata.parameters.iter().find(|p| &p.name == name)
+                }
+                QueryParameter::Positional(_) => None,
+            };
+            let Some(declaration) = declaration else {
+                return Err(SqlError::parse_error(
+                    format!(
+                        "Parameter {} of statement {} is not declared, add '-- Parameter: <name> <TYPE>'",
+                        parameter.parameter, statement.id
+                    ),
+                    None,
+                ));
+            };
+            // Declared types were checked when the application was parsed
+            if let Some(data_type) = cast_target_type(&declaration.data_type) {
+                prepared = prepared.with_parameter_type(parameter.parameter, data_type);
+            }
+        }
+        Ok(prepared)
+    }
+
+    /// Bind a value to every declared parameter and write the values into the statements
+    pub fn bind_parameters(
+        &self,
+        values: &HashMap<String, FieldValue>,
+    ) -> Result<SqlApplication, SqlError> {
+        if let Some(missing) = self
+            .metadata
+            .parameters
+            .iter()
+            .find(|p| !values.contains_key(&p.name))
+        {
+            return Err(SqlError::execution_error(
+                format!("No value bound to parameter :{}", missing.name),
+                None,
+            ));
+        }
+        if let Some(unknown) = values
+            .keys()
+            .find(|name| !self.metadata.parameters.iter().any(|p| &p.name == *name))
+        {
+            return Err(SqlError::execution_error(
+                format!("Application has no parameter :{}", unknown),
+                None,
+            ));
+        }
+
+        let mut app = self.clone();
+        for statement in app.statements.iter_mut() {
+            let prepared = self.prepare_statement(statement)?;
+            if prepared.parameters().is_empty() {
+                continue;
+            }
+            let statement_values: HashMap<String, FieldValue> = prepared
+                .parameters()
+                .iter()
+                .filter_map(|p| match &p.parameter {
+                    QueryParameter::Named(name) => Some((name.clone(), values[name].clone())),
+                    QueryParameter::Positional(_) => None,
+                })
+                .collect();
+            statement.sql = format_query(&prepared.bind_named(&statement_values)?);
+        }
+        Ok(app)
+    }
+}
+
 /// Resources created or used by the application
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ApplicationResources {
@@ -153,6 +252,7 @@ impl SqlApplicationParser {
         let mut author = None;
         let mut dependencies = Vec::new();
         let mut tags = HashMap::new();
+        let mut parameters = Vec::new();
 
         for line in content.lines() {
             let line = line.trim();
@@ -178,6 +278,10 @@ impl SqlApplicationParser {
                 if let Some((key, value)) = tag_str.split_once(':') {
                     tags.insert(key.trim().to_string(), value.trim().to_string());
                 }
+            } else if line.starts_with("-- Parameter:") {
+                parameters.push(Self::parse_parameter_declaration(
+                    line.replace("-- Parameter:", "").trim(),
+                )?);
             }
         }
 
@@ -201,6 +305,33 @@ impl SqlApplicationParser {
             dependencies,
             created_at: Utc::now(),
             tags,
+            parameters,
+        })
+    }
+
+    /// Parse the `<name> <TYPE>` of a `-- Parameter:` comment
+    fn parse_parameter_declaration(declaration: &str) -> Result<ParameterDeclaration, SqlError> {
+        let invalid = |reason: &str| SqlError::ParseError {
+            message: format!(
+                "Invalid parameter declaration '-- Parameter: {}': {}",
+                declaration, reason
+            ),
+            position: None,
+        };
+        let (name, data_type) = declaration
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| invalid("expected '<name> <TYPE>'"))?;
+        let name = name.trim_start_matches(':');
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return Err(invalid("parameter names are identifiers"));
+        }
+        let data_type = data_type.trim();
+        if cast_target_type(data_type).is_none() {
+            return Err(invalid(&format!("unknown type {}", data_type)));
+        }
+        Ok(ParameterDeclaration {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
         })
     }
 
@@ -222,6 +353,7 @@ impl SqlApplicationParser {
                 || trimmed.starts_with("-- Author:")
                 || trimmed.starts_with("-- Dependencies:")
                 || trimmed.starts_with("-- Tag:")
+                || trimmed.starts_with("-- Parameter:")
             {
                 continue;
             }
@@ -47,11 +47,54 @@ The AST is designed to be:
 - **Composable**: Complex queries built from simple expressions
 - **Extensible**: Easy to add new streaming operations
 - **Type-Safe**: Full Rust type checking throughout
+
+Statements and expressions carry the [`NodeSpan`] of the text they were parsed from, which
+diagnostics point at.
 */
 
+use crate::ferris::sql::error::diagnostics::Span;
 use std::collections::HashMap;
 use std::time::Duration;
 
+/// Where a node was parsed from in the SQL text
+///
+/// Nodes built in code have no span. Spans take no part in comparisons, so a parsed query
+/// equals the same query built in code.
+#[derive(Clone, Copy, Default)]
+pub struct NodeSpan(Option<Span>);
+
+impl NodeSpan {
+    pub fn new(span: Span) -> Self {
+        Self(Some(span))
+    }
+
+    /// The span, unless the node was built in code
+    pub fn get(&self) -> Option<Span> {
+        self.0
+    }
+}
+
+impl From<Span> for NodeSpan {
+    fn from(span: Span) -> Self {
+        Self::new(span)
+    }
+}
+
+impl PartialEq for NodeSpan {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl std::fmt::Debug for NodeSpan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(span) => write!(f, "{}..{}", span.start, span.end),
+            None => write!(f, "_"),
+        }
+    }
+}
+
 /// Emission mode for streaming query results
 ///
 /// Controls when and how results are emitted from streaming queries.
@@ -77,13 +120,14 @@ pub enum EmitMode {
 /// # Examples
 ///
 /// ```rust,no_run
-/// use ferrisstreams::ferris::sql::ast::{StreamingQuery, SelectField, StreamSource};
+/// use ferrisstreams::ferris::sql::ast::{NodeSpan, StreamingQuery, SelectField, StreamSource};
 ///
 /// fn main() {
 ///     // SELECT query
 ///     let select_query = StreamingQuery::Select {
 ///         fields: vec![SelectField::Wildcard],
 ///         from: StreamSource::Stream("orders".to_string()),
+///         from_alias: None,
 ///         joins: None,
 ///         where_clause: None,
 ///         group_by: None,
@@ -92,7 +136,8 @@ pub enum EmitMode {
 ///         order_by: None,
 ///         limit: Some(100),
 ///         emit_mode: None,
-///         properties: None};
+///         properties: None,
+///         span: NodeSpan::default()};
 /// }
 /// ```
 #[derive(Debug, Clone, PartialEq)]
@@ -113,6 +158,8 @@ pub enum StreamingQuery {
         fields: Vec<SelectField>,
         /// Source stream or table
         from: StreamSource,
+        /// Optional alias for the source (`FROM orders o`)
+        from_alias: Option<String>,
         /// Optional JOIN clauses
         joins: Option<Vec<JoinClause>>,
         /// Optional WHERE clause for filtering
@@ -131,6 +178,8 @@ pub enum StreamingQuery {
         emit_mode: Option<EmitMode>,
         /// Optional WITH clause properties for configuration
         properties: Option<HashMap<String, String>>,
+        /// Where the statement was parsed from
+        span: NodeSpan,
     },
     /// CREATE STREAM AS SELECT statement for stream transformations.
     ///
@@ -148,6 +197,8 @@ pub enum StreamingQuery {
         properties: HashMap<String, String>,
         /// Emission mode for continuous query results
         emit_mode: Option<EmitMode>,
+        /// Where the statement was parsed from
+        span: NodeSpan,
     },
     /// CREATE STREAM AS SELECT ... INTO statement for source-to-sink streaming.
     ///
@@ -166,6 +217,8 @@ pub enum StreamingQuery {
         properties: ConfigProperties,
         /// Emission mode for continuous query results
         emit_mode: Option<EmitMode>,
+        /// Where the statement was parsed from
+        span: NodeSpan,
     },
     /// CREATE TABLE AS SELECT statement for materialized views.
     ///
@@ -177,12 +230,16 @@ pub enum StreamingQuery {
         name: String,
         /// Optional column definitions with types
         columns: Option<Vec<ColumnDef>>,
+        /// PRIMARY KEY and CHECK constraints declared with the columns
+        constraints: Vec<TableConstraint>,
         /// SELECT query that defines the table population
         as_select: Box<StreamingQuery>,
         /// Table properties (retention, compaction, etc.)
         properties: HashMap<String, String>,
         /// Emission mode for continuous query results
         emit_mode: Option<EmitMode>,
+        /// Where the statement was parsed from
+        span: NodeSpan,
     },
     /// CREATE TABLE AS SELECT ... INTO statement for source-to-sink table creation.
     ///
@@ -194,6 +251,8 @@ pub enum StreamingQuery {
         name: String,
         /// Optional column definitions with types
         columns: Option<Vec<ColumnDef>>,
+        /// PRIMARY KEY and CHECK constraints declared with the columns
+        constraints: Vec<TableConstraint>,
         /// SELECT query that defines the transformation
         as_select: Box<StreamingQuery>,
         /// Target sink specification
@@ -202,6 +261,36 @@ pub enum StreamingQuery {
         properties: ConfigProperties,
         /// Emission mode for continuous query results
         emit_mode: Option<EmitMode>,
+        /// Where the statement was parsed from
+        span: NodeSpan,
+    },
+    /// ALTER STREAM / ALTER TABLE statement for changing a registered object.
+    ///
+    /// Adds columns (subject to the schema evolution rules of the object) or
+    /// changes WITH properties without recreating the stream or table.
+    Alter {
+        /// Kind of object being altered (stream or table)
+        object_type: ObjectType,
+        /// Name of the stream or table
+        name: String,
+        /// Change to apply
+        operation: AlterOperation,
+        /// Where the statement was parsed from
+        span: NodeSpan,
+    },
+    /// DROP STREAM / DROP TABLE / DROP JOB statement.
+    ///
+    /// Removes a stream, table or job from the catalog. Streams and tables
+    /// cannot be dropped while running jobs depend on them.
+    Drop {
+        /// Kind of object being dropped
+        object_type: ObjectType,
+        /// Name of the object
+        name: String,
+        /// IF EXISTS: dropping a missing object is not an error
+        if_exists: bool,
+        /// Where the statement was parsed from
+        span: NodeSpan,
     },
     /// SHOW/LIST commands for discovering available resources.
     ///
@@ -213,6 +302,8 @@ pub enum StreamingQuery {
         resource_type: ShowResourceType,
         /// Optional pattern for filtering results
         pattern: Option<String>,
+        /// Where the statement was parsed from
+        span: NodeSpan,
     },
     /// START JOB command for initiating continuous job execution.
     ///
@@ -225,6 +316,10 @@ pub enum StreamingQuery {
         query: Box<StreamingQuery>,
         /// Optional properties for job execution
         properties: HashMap<String, String>,
+        /// FROM SAVEPOINT 'path': savepoint whose operator state the job starts with
+        savepoint: Option<String>,
+        /// Where the statement was parsed from
+        span: NodeSpan,
     },
     /// STOP JOB command for terminating running jobs.
     ///
@@ -235,6 +330,10 @@ pub enum StreamingQuery {
         name: String,
         /// Whether to force stop if graceful shutdown fails
         force: bool,
+        /// WITH SAVEPOINT: write a savepoint of the job's state once it has stopped
+        savepoint: bool,
+        /// Where the statement was parsed from
+        span: NodeSpan,
     },
     /// PAUSE JOB command for temporarily suspending job execution.
     ///
@@ -243,6 +342,8 @@ pub enum StreamingQuery {
     PauseJob {
         /// Name of the job to pause
         name: String,
+        /// Where the statement was parsed from
+        span: NodeSpan,
     },
     /// RESUME JOB command for restarting paused jobs.
     ///
@@ -251,6 +352,8 @@ pub enum StreamingQuery {
     ResumeJob {
         /// Name of the job to resume
         name: String,
+        /// Where the statement was parsed from
+        span: NodeSpan,
     },
     /// DEPLOY JOB command for versioned job deployment.
     ///
@@ -267,6 +370,8 @@ pub enum StreamingQuery {
         properties: HashMap<String, String>,
         /// Strategy for deployment (blue-green, canary, etc.)
         strategy: DeploymentStrategy,
+        /// Where the statement was parsed from
+        span: NodeSpan,
     },
     /// ROLLBACK JOB command for reverting to previous job version.
     ///
@@ -277,6 +382,8 @@ pub enum StreamingQuery {
         name: String,
         /// Target version to rollback to (optional - defaults to previous)
         target_version: Option<String>,
+        /// Where the statement was parsed from
+        span: NodeSpan,
     },
     /// INSERT INTO statement for adding records to streams/tables.
     ///
@@ -289,6 +396,8 @@ pub enum StreamingQuery {
         columns: Option<Vec<String>>,
         /// Source of data to insert
         source: InsertSource,
+        /// Where the statement was parsed from
+        span: NodeSpan,
     },
     /// UPDATE statement for modifying existing records.
     ///
@@ -301,6 +410,8 @@ pub enum StreamingQuery {
         assignments: Vec<(String, Expr)>,
         /// Optional WHERE clause to filter which records to update
         where_clause: Option<Expr>,
+        /// Where the statement was parsed from
+        span: NodeSpan,
     },
     /// DELETE statement for removing records.
     ///
@@ -311,6 +422,44 @@ pub enum StreamingQuery {
         table_name: String,
         /// Optional WHERE clause to filter which records to delete
         where_clause: Option<Expr>,
+        /// Where the statement was parsed from
+        span: NodeSpan,
+    },
+    /// MERGE INTO statement for keyed upserts and deletes driven by a source stream.
+    ///
+    /// Every source record is matched against the target by the equality conditions
+    /// in ON; the first WHEN clause that applies decides whether the target row is
+    /// updated, deleted or inserted.
+    Merge {
+        /// Target table or sink name
+        target: String,
+        /// Optional alias for the target
+        target_alias: Option<String>,
+        /// Source of the rows being merged
+        source: StreamSource,
+        /// Optional alias for the source
+        source_alias: Option<String>,
+        /// Join condition: equalities between target and source key columns
+        on: Expr,
+        /// WHEN [NOT] MATCHED clauses in declaration order
+        clauses: Vec<MergeClause>,
+        /// Optional WITH clause properties for source and sink configuration
+        properties: Option<HashMap<String, String>>,
+        /// Where the statement was parsed from
+        span: NodeSpan,
+    },
+    /// UPSERT INTO statement: insert rows or replace existing rows with the same key.
+    Upsert {
+        /// Target table or sink name
+        table_name: String,
+        /// Optional column names (as for INSERT INTO)
+        columns: Option<Vec<String>>,
+        /// Columns that identify a row: KEY (...) or the first listed column
+        key_columns: Vec<String>,
+        /// Source of data to upsert
+        source: InsertSource,
+        /// Where the statement was parsed from
+        span: NodeSpan,
     },
     /// UNION operation for combining result sets from multiple SELECT queries.
     ///
@@ -323,6 +472,8 @@ pub enum StreamingQuery {
         right: Box<StreamingQuery>,
         /// Whether to preserve duplicates (UNION ALL = true, UNION = false)
         all: bool,
+        /// Where the statement was parsed from
+        span: NodeSpan,
     },
 }
 
@@ -343,6 +494,31 @@ pub enum InsertSource {
     },
 }
 
+/// WHEN clause of a MERGE statement
+#[derive(Debug, Clone, PartialEq)]
+pub enum MergeClause {
+    /// WHEN MATCHED [AND condition] THEN UPDATE SET ... | DELETE
+    Matched {
+        condition: Option<Expr>,
+        action: MergeMatchedAction,
+    },
+    /// WHEN NOT MATCHED [AND condition] THEN INSERT (columns) VALUES (values)
+    NotMatched {
+        condition: Option<Expr>,
+        columns: Vec<String>,
+        values: Vec<Expr>,
+    },
+}
+
+/// Action applied to a matched target row
+#[derive(Debug, Clone, PartialEq)]
+pub enum MergeMatchedAction {
+    /// UPDATE SET column = expression, ...
+    Update { assignments: Vec<(String, Expr)> },
+    /// DELETE the target row
+    Delete,
+}
+
 /// Deployment strategies for versioned job deployments.
 ///
 /// Different strategies provide varying levels of safety and rollback capabilities
@@ -378,6 +554,37 @@ pub enum JobStatus {
     Deploying,
 }
 
+/// Kinds of catalog objects that can be altered or dropped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectType {
+    Stream,
+    Table,
+    Job,
+}
+
+impl std::fmt::Display for ObjectType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ObjectType::Stream => write!(f, "stream"),
+            ObjectType::Table => write!(f, "table"),
+            ObjectType::Job => write!(f, "job"),
+        }
+    }
+}
+
+/// Change applied by an ALTER STREAM / ALTER TABLE statement
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlterOperation {
+    /// ADD [COLUMN] [IF NOT EXISTS] name type [NOT NULL]
+    AddColumn {
+        column: ColumnDef,
+        /// IF NOT EXISTS: adding an existing column is a no-op
+        if_not_exists: bool,
+    },
+    /// SET ('key' = 'value', ...) merged into the WITH properties
+    SetProperties { properties: HashMap<String, String> },
+}
+
 /// Types of resources that can be shown with SHOW/LIST commands.
 ///
 /// Each resource type provides different metadata about the streaming
@@ -404,6 +611,8 @@ pub enum ShowResourceType {
     JobVersions { name: String },
     /// Show job metrics and performance statistics
     JobMetrics { name: Option<String> },
+    /// Show the savepoints of one job or of all jobs
+    Savepoints { name: Option<String> },
     /// Show partitions for a specific topic/stream
     Partitions { name: String },
     /// Describe the schema of a stream or table
@@ -480,6 +689,9 @@ pub enum WindowSpec {
     Tumbling {
         size: Duration,
         time_column: Option<String>,
+        /// IANA time zone the window boundaries are aligned to (UTC when `None`),
+        /// e.g. a 1-day window in `Asia/Tokyo` runs from local midnight to local midnight
+        time_zone: Option<String>,
     },
     /// Sliding window with advance interval
     Sliding {
@@ -551,36 +763,48 @@ pub enum FrameBound {
 #[derive(Debug, Clone, PartialEq)]
 pub enum Expr {
     /// Column reference
-    Column(String),
+    Column(String, NodeSpan),
     /// Literal values
-    Literal(LiteralValue),
+    Literal(LiteralValue, NodeSpan),
     /// Binary operations: expr op expr
     BinaryOp {
         left: Box<Expr>,
         op: BinaryOperator,
         right: Box<Expr>,
+        span: NodeSpan,
     },
     /// Unary operations: op expr
-    UnaryOp { op: UnaryOperator, expr: Box<Expr> },
+    UnaryOp {
+        op: UnaryOperator,
+        expr: Box<Expr>,
+        span: NodeSpan,
+    },
     /// Function calls: func_name(args...)
-    Function { name: String, args: Vec<Expr> },
+    Function {
+        name: String,
+        args: Vec<Expr>,
+        span: NodeSpan,
+    },
     /// Window functions like LAG(expr) OVER (PARTITION BY col ORDER BY col)
     WindowFunction {
         function_name: String,
         args: Vec<Expr>,
         over_clause: OverClause,
+        span: NodeSpan,
     },
     /// CASE expressions for conditional logic
     Case {
         when_clauses: Vec<(Expr, Expr)>, // (condition, result)
         else_clause: Option<Box<Expr>>,
+        span: NodeSpan,
     },
     /// List expressions for IN operators: (expr1, expr2, expr3)
-    List(Vec<Expr>),
+    List(Vec<Expr>, NodeSpan),
     /// Subquery expressions for complex SQL operations
     Subquery {
         query: Box<StreamingQuery>,
         subquery_type: SubqueryType,
+        span: NodeSpan,
     },
     /// BETWEEN expressions: expr BETWEEN low AND high
     Between {
@@ -588,7 +812,46 @@ pub enum Expr {
         low: Box<Expr>,
         high: Box<Expr>,
         negated: bool, // for NOT BETWEEN
+        span: NodeSpan,
+    },
+    /// Subscript access: items[0] (zero-based, negative counts from the end) or attributes['key']
+    Subscript {
+        expr: Box<Expr>,
+        index: Box<Expr>,
+        span: NodeSpan,
     },
+    /// Field access on a computed value: items[0].price
+    FieldAccess {
+        expr: Box<Expr>,
+        field: String,
+        span: NodeSpan,
+    },
+    /// Lambda for higher-order functions: x -> x * 2 or (acc, x) -> acc + x
+    Lambda {
+        params: Vec<String>,
+        body: Box<Expr>,
+        span: NodeSpan,
+    },
+    /// Placeholder for a value bound when a prepared statement is executed: $1 or :name
+    Parameter(QueryParameter, NodeSpan),
+}
+
+/// Placeholder of a parameterized query
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum QueryParameter {
+    /// `$1`, `$2`, ... numbered from 1
+    Positional(usize),
+    /// `:name`
+    Named(String),
+}
+
+impl std::fmt::Display for QueryParameter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QueryParameter::Positional(number) => write!(f, "${}", number),
+            QueryParameter::Named(name) => write!(f, ":{}", name),
+        }
+    }
 }
 
 /// Types of subquery expressions
@@ -635,6 +898,11 @@ pub enum TimeUnit {
     Minute,
     Hour,
     Day,
+    Week,
+    /// Calendar month - variable length, applied with calendar arithmetic on timestamps
+    Month,
+    /// Calendar year - variable length, applied with calendar arithmetic on timestamps
+    Year,
 }
 
 /// Binary operators
@@ -697,16 +965,60 @@ pub struct ColumnDef {
     pub properties: HashMap<String, String>,
 }
 
+/// Table constraint declared in CREATE TABLE column definitions
+///
+/// Column-level `PRIMARY KEY` and `CHECK (expr)` clauses are normalized into these
+/// table-level forms by the parser.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TableConstraint {
+    /// `[CONSTRAINT name] PRIMARY KEY (col, ...)` - the upsert key of the table
+    PrimaryKey {
+        name: Option<String>,
+        columns: Vec<String>,
+    },
+    /// `[CONSTRAINT name] CHECK (expr)` - evaluated on every row
+    Check { name: Option<String>, expr: Expr },
+}
+
+impl TableConstraint {
+    /// The declared constraint name, if any
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            TableConstraint::PrimaryKey { name, .. } | TableConstraint::Check { name, .. } => {
+                name.as_deref()
+            }
+        }
+    }
+}
+
 /// Data types supported in streaming SQL
 #[derive(Debug, Clone, PartialEq)]
 pub enum DataType {
+    /// 16-bit signed integer (SMALLINT)
+    SmallInt,
+    /// 64-bit signed integer (INT / INTEGER)
     Integer,
+    /// 64-bit signed integer (BIGINT)
+    BigInt,
+    /// Single-precision floating point (REAL)
+    Real,
     Float,
     String,
     Boolean,
+    /// Raw binary data (BYTES / VARBINARY)
+    Bytes,
+    /// 128-bit universally unique identifier
+    Uuid,
+    /// Calendar date without a time of day
+    Date,
+    /// Time of day without a date
+    Time,
     Timestamp,
-    /// High-precision decimal number for financial calculations
-    Decimal,
+    /// Timestamp with time zone (TIMESTAMPTZ / TIMESTAMP WITH TIME ZONE)
+    TimestampTz,
+    /// High-precision decimal number for financial calculations.
+    /// `DECIMAL(p, s)` fixes the precision and scale; bare `DECIMAL` keeps the value's own scale.
+    Decimal(Option<DecimalSpec>),
     /// Array of elements of a specific type
     Array(Box<DataType>),
     /// Map with key-value pairs of specific types
@@ -715,6 +1027,49 @@ pub enum DataType {
     Struct(Vec<StructField>),
 }
 
+impl std::fmt::Display for DataType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DataType::SmallInt => write!(f, "SMALLINT"),
+            DataType::Integer => write!(f, "INTEGER"),
+            DataType::BigInt => write!(f, "BIGINT"),
+            DataType::Real => write!(f, "REAL"),
+            DataType::Float => write!(f, "FLOAT"),
+            DataType::String => write!(f, "STRING"),
+            DataType::Boolean => write!(f, "BOOLEAN"),
+            DataType::Bytes => write!(f, "BYTES"),
+            DataType::Uuid => write!(f, "UUID"),
+            DataType::Date => write!(f, "DATE"),
+            DataType::Time => write!(f, "TIME"),
+            DataType::Timestamp => write!(f, "TIMESTAMP"),
+            DataType::TimestampTz => write!(f, "TIMESTAMPTZ"),
+            DataType::Decimal(None) => write!(f, "DECIMAL"),
+            DataType::Decimal(Some(spec)) => write!(f, "{}", spec),
+            DataType::Array(inner) => write!(f, "ARRAY({})", inner),
+            DataType::Map(key, value) => write!(f, "MAP({}, {})", key, value),
+            DataType::Struct(fields) => {
+                write!(f, "STRUCT(")?;
+                for (i, field) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{} {}", field.name, field.data_type)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+/// Declared precision and scale of a `DECIMAL(p, s)` type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecimalSpec {
+    /// Total number of significant digits
+    pub precision: u8,
+    /// Number of digits after the decimal point
+    pub scale: u8,
+}
+
 /// Field definition for STRUCT data types
 #[derive(Debug, Clone, PartialEq)]
 pub struct StructField {
@@ -791,6 +1146,59 @@ impl ConfigProperties {
 }
 
 impl StreamingQuery {
+    /// Where the statement was parsed from, if it was parsed
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            StreamingQuery::Select { span, .. }
+            | StreamingQuery::CreateStream { span, .. }
+            | StreamingQuery::CreateStreamInto { span, .. }
+            | StreamingQuery::CreateTable { span, .. }
+            | StreamingQuery::CreateTableInto { span, .. }
+            | StreamingQuery::Alter { span, .. }
+            | StreamingQuery::Drop { span, .. }
+            | StreamingQuery::Show { span, .. }
+            | StreamingQuery::StartJob { span, .. }
+            | StreamingQuery::StopJob { span, .. }
+            | StreamingQuery::PauseJob { span, .. }
+            | StreamingQuery::ResumeJob { span, .. }
+            | StreamingQuery::DeployJob { span, .. }
+            | StreamingQuery::RollbackJob { span, .. }
+            | StreamingQuery::InsertInto { span, .. }
+            | StreamingQuery::Update { span, .. }
+            | StreamingQuery::Delete { span, .. }
+            | StreamingQuery::Merge { span, .. }
+            | StreamingQuery::Upsert { span, .. }
+            | StreamingQuery::Union { span, .. } => span.get(),
+        }
+    }
+
+    /// The statement located at `location`
+    pub fn with_span(mut self, location: Span) -> Self {
+        match &mut self {
+            StreamingQuery::Select { span, .. }
+            | StreamingQuery::CreateStream { span, .. }
+            | StreamingQuery::CreateStreamInto { span, .. }
+            | StreamingQuery::CreateTable { span, .. }
+            | StreamingQuery::CreateTableInto { span, .. }
+            | StreamingQuery::Alter { span, .. }
+            | StreamingQuery::Drop { span, .. }
+            | StreamingQuery::Show { span, .. }
+            | StreamingQuery::StartJob { span, .. }
+            | StreamingQuery::StopJob { span, .. }
+            | StreamingQuery::PauseJob { span, .. }
+            | StreamingQuery::ResumeJob { span, .. }
+            | StreamingQuery::DeployJob { span, .. }
+            | StreamingQuery::RollbackJob { span, .. }
+            | StreamingQuery::InsertInto { span, .. }
+            | StreamingQuery::Update { span, .. }
+            | StreamingQuery::Delete { span, .. }
+            | StreamingQuery::Merge { span, .. }
+            | StreamingQuery::Upsert { span, .. }
+            | StreamingQuery::Union { span, .. } => *span = NodeSpan::new(location),
+        }
+        self
+    }
+
     /// Check if this query requires windowing
     pub fn has_window(&self) -> bool {
         match self {
@@ -799,7 +1207,9 @@ impl StreamingQuery {
             StreamingQuery::CreateTable { as_select, .. } => as_select.has_window(),
             StreamingQuery::CreateStreamInto { as_select, .. } => as_select.has_window(),
             StreamingQuery::CreateTableInto { as_select, .. } => as_select.has_window(),
-            StreamingQuery::Show { .. } => false, // SHOW commands don't use windows
+            StreamingQuery::Alter { .. } => false, // ALTER commands don't use windows
+            StreamingQuery::Drop { .. } => false,  // DROP commands don't use windows
+            StreamingQuery::Show { .. } => false,  // SHOW commands don't use windows
             StreamingQuery::StartJob { query, .. } => query.has_window(),
             StreamingQuery::StopJob { .. } => false, // STOP commands don't use windows
             StreamingQuery::PauseJob { .. } => false, // PAUSE commands don't use windows
@@ -809,6 +1219,8 @@ impl StreamingQuery {
             StreamingQuery::InsertInto { .. } => false,  // INSERT commands don't use windows
             StreamingQuery::Update { .. } => false,      // UPDATE commands don't use windows
             StreamingQuery::Delete { .. } => false,      // DELETE commands don't use windows
+            StreamingQuery::Merge { .. } => false,       // MERGE commands don't use windows
+            StreamingQuery::Upsert { .. } => false,      // UPSERT commands don't use windows
             StreamingQuery::Union { left, right, .. } => left.has_window() || right.has_window(),
         }
     }
@@ -841,7 +1253,9 @@ impl StreamingQuery {
             StreamingQuery::CreateTable { as_select, .. } => as_select.get_columns(),
             StreamingQuery::CreateStreamInto { as_select, .. } => as_select.get_columns(),
             StreamingQuery::CreateTableInto { as_select, .. } => as_select.get_columns(),
-            StreamingQuery::Show { .. } => Vec::new(), // SHOW commands don't reference columns
+            StreamingQuery::Alter { .. } => Vec::new(), // ALTER commands don't reference query columns
+            StreamingQuery::Drop { .. } => Vec::new(),  // DROP commands don't reference columns
+            StreamingQuery::Show { .. } => Vec::new(),  // SHOW commands don't reference columns
             StreamingQuery::StartJob { query, .. } => query.get_columns(),
             StreamingQuery::StopJob { .. } => Vec::new(), // STOP commands don't reference columns
             StreamingQuery::PauseJob { .. } => Vec::new(), // PAUSE commands don't reference columns
@@ -849,9 +1263,10 @@ impl StreamingQuery {
             StreamingQuery::DeployJob { query, .. } => query.get_columns(),
             StreamingQuery::RollbackJob { .. } => Vec::new(), // ROLLBACK commands don't reference columns
             StreamingQuery::InsertInto {
-                table_name: _,
-                columns,
-                source,
+                columns, source, ..
+            }
+            | StreamingQuery::Upsert {
+                columns, source, ..
             } => {
                 let mut cols = Vec::new();
                 // Add explicit column names if specified
@@ -879,6 +1294,7 @@ impl StreamingQuery {
                 table_name: _,
                 assignments,
                 where_clause,
+                ..
             } => {
                 let mut cols = Vec::new();
                 // Add columns from assignments
@@ -897,6 +1313,7 @@ impl StreamingQuery {
             StreamingQuery::Delete {
                 table_name: _,
                 where_clause,
+                ..
             } => {
                 let mut cols = Vec::new();
                 // Add columns from WHERE clause
@@ -905,6 +1322,34 @@ impl StreamingQuery {
                 }
                 cols
             }
+            StreamingQuery::Merge { on, clauses, .. } => {
+                let mut cols = on.get_columns();
+                for clause in clauses {
+                    match clause {
+                        MergeClause::Matched { condition, action } => {
+                            cols.extend(condition.iter().flat_map(|c| c.get_columns()));
+                            if let MergeMatchedAction::Update { assignments } = action {
+                                for (col_name, expr) in assignments {
+                                    cols.push(col_name.clone());
+                                    cols.extend(expr.get_columns());
+                                }
+                            }
+                        }
+                        MergeClause::NotMatched {
+                            condition,
+                            columns,
+                            values,
+                        } => {
+                            cols.extend(condition.iter().flat_map(|c| c.get_columns()));
+                            cols.extend(columns.iter().cloned());
+                            cols.extend(values.iter().flat_map(|v| v.get_columns()));
+                        }
+                    }
+                }
+                cols.sort();
+                cols.dedup();
+                cols
+            }
             StreamingQuery::Union { left, right, .. } => {
                 let mut columns = left.get_columns();
                 columns.extend(right.get_columns());
@@ -929,11 +1374,52 @@ impl SelectField {
 }
 
 impl Expr {
+    /// Where the expression was parsed from, if it was parsed
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            Expr::BinaryOp { span, .. }
+            | Expr::UnaryOp { span, .. }
+            | Expr::Function { span, .. }
+            | Expr::WindowFunction { span, .. }
+            | Expr::Case { span, .. }
+            | Expr::Subquery { span, .. }
+            | Expr::Between { span, .. }
+            | Expr::Subscript { span, .. }
+            | Expr::FieldAccess { span, .. }
+            | Expr::Lambda { span, .. }
+            | Expr::Column(_, span)
+            | Expr::Literal(_, span)
+            | Expr::List(_, span)
+            | Expr::Parameter(_, span) => span.get(),
+        }
+    }
+
+    /// The expression located at `location`
+    pub fn with_span(mut self, location: Span) -> Self {
+        match &mut self {
+            Expr::BinaryOp { span, .. }
+            | Expr::UnaryOp { span, .. }
+            | Expr::Function { span, .. }
+            | Expr::WindowFunction { span, .. }
+            | Expr::Case { span, .. }
+            | Expr::Subquery { span, .. }
+            | Expr::Between { span, .. }
+            | Expr::Subscript { span, .. }
+            | Expr::FieldAccess { span, .. }
+            | Expr::Lambda { span, .. }
+            | Expr::Column(_, span)
+            | Expr::Literal(_, span)
+            | Expr::List(_, span)
+            | Expr::Parameter(_, span) => *span = NodeSpan::new(location),
+        }
+        self
+    }
+
     /// Extract all column references from this expression
     pub fn get_columns(&self) -> Vec<String> {
         match self {
-            Expr::Column(name) => vec![name.clone()],
-            Expr::Literal(_) => Vec::new(),
+            Expr::Column(name, _) => vec![name.clone()],
+            Expr::Literal(_, _) => Vec::new(),
             Expr::BinaryOp { left, right, .. } => {
                 let mut columns = left.get_columns();
                 columns.extend(right.get_columns());
@@ -950,6 +1436,7 @@ impl Expr {
             Expr::Case {
                 when_clauses,
                 else_clause,
+                ..
             } => {
                 let mut columns = Vec::new();
                 for (condition, result) in when_clauses {
@@ -976,7 +1463,7 @@ impl Expr {
                 }
                 columns
             }
-            Expr::List(items) => {
+            Expr::List(items, _) => {
                 let mut columns = Vec::new();
                 for item in items {
                     columns.extend(item.get_columns());
@@ -996,6 +1483,20 @@ impl Expr {
                 columns.extend(high.get_columns());
                 columns
             }
+            Expr::Subscript { expr, index, .. } => {
+                let mut columns = expr.get_columns();
+                columns.extend(index.get_columns());
+                columns
+            }
+            Expr::FieldAccess { expr, .. } => expr.get_columns(),
+            Expr::Lambda { params, body, .. } => {
+                // Lambda parameters are bound locally and are not record columns
+                body.get_columns()
+                    .into_iter()
+                    .filter(|column| !params.contains(column))
+                    .collect()
+            }
+            Expr::Parameter(_, _) => Vec::new(),
         }
     }
 }
@@ -1019,6 +1520,8 @@ impl WindowSpec {
 
 impl TimeUnit {
     /// Convert to Duration
+    ///
+    /// Months and years have no fixed length; they are approximated as 30 and 365 days.
     pub fn to_duration(&self, value: i64) -> Duration {
         match self {
             TimeUnit::Millisecond => Duration::from_millis(value as u64),
@@ -1026,6 +1529,14 @@ impl TimeUnit {
             TimeUnit::Minute => Duration::from_secs(value as u64 * 60),
             TimeUnit::Hour => Duration::from_secs(value as u64 * 3600),
             TimeUnit::Day => Duration::from_secs(value as u64 * 86400),
+            TimeUnit::Week => Duration::from_secs(value as u64 * 7 * 86400),
+            TimeUnit::Month => Duration::from_secs(value as u64 * 30 * 86400),
+            TimeUnit::Year => Duration::from_secs(value as u64 * 365 * 86400),
         }
     }
+
+    /// Whether this unit is a calendar unit (month/year) rather than a fixed duration
+    pub fn is_calendar(&self) -> bool {
+        matches!(self, TimeUnit::Month | TimeUnit::Year)
+    }
 }
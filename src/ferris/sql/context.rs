@@ -1,21 +1,54 @@
-use crate::ferris::schema::{Schema, StreamHandle};
-use crate::ferris::sql::ast::StreamingQuery;
+use crate::ferris::schema::evolution::SchemaEvolution;
+use crate::ferris::schema::{CompatibilityMode, FieldDefinition, Schema, StreamHandle};
+use crate::ferris::sql::ast::{
+    AlterOperation, InsertSource, ObjectType, StreamSource, StreamingQuery,
+};
 use crate::ferris::sql::error::SqlError;
+use crate::ferris::sql::formatter::format_query;
+use crate::ferris::sql::optimizer::QueryOptimizer;
 use crate::ferris::sql::parser::StreamingSqlParser;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 pub struct StreamingSqlContext {
     registered_streams: HashMap<String, StreamHandle>,
     schemas: HashMap<String, Schema>,
+    /// Registered names that are tables rather than streams
+    tables: HashSet<String>,
+    /// WITH properties of registered streams and tables
+    properties: HashMap<String, HashMap<String, String>>,
+    /// Running jobs and the streams/tables they read from or write to
+    jobs: HashMap<String, Vec<String>>,
     parser: StreamingSqlParser,
 }
 
+/// Catalog change computed (and validated) for an ALTER or DROP statement
+pub enum CatalogChange {
+    Unchanged,
+    ReplaceSchema {
+        name: String,
+        schema: Schema,
+    },
+    SetProperties {
+        name: String,
+        properties: HashMap<String, String>,
+    },
+    DropObject {
+        name: String,
+    },
+    DropJob {
+        name: String,
+    },
+}
+
 impl StreamingSqlContext {
     pub fn new() -> Self {
         Self {
             registered_streams: HashMap::new(),
             schemas: HashMap::new(),
+            tables: HashSet::new(),
+            properties: HashMap::new(),
+            jobs: HashMap::new(),
             parser: StreamingSqlParser::new(),
         }
     }
@@ -48,6 +81,72 @@ impl StreamingSqlContext {
 
         self.registered_streams.remove(name);
         self.schemas.remove(name);
+        self.tables.remove(name);
+        self.properties.remove(name);
+        Ok(())
+    }
+
+    /// Register a table; tables share the namespace of streams
+    pub fn register_table(
+        &mut self,
+        name: String,
+        handle: StreamHandle,
+        schema: Schema,
+    ) -> Result<(), SqlError> {
+        self.register_stream(name.clone(), handle, schema)?;
+        self.tables.insert(name);
+        Ok(())
+    }
+
+    /// Register a running job together with the streams and tables its query uses
+    pub fn register_job(&mut self, name: String, query: &StreamingQuery) -> Result<(), SqlError> {
+        if self.jobs.contains_key(&name) {
+            return Err(SqlError::ExecutionError {
+                message: format!("Job '{}' already exists", name),
+                query: None,
+            });
+        }
+
+        self.jobs.insert(name, query_dependencies(query));
+        Ok(())
+    }
+
+    /// Names of the running jobs that read from or write to a stream or table
+    pub fn dependent_jobs(&self, name: &str) -> Vec<String> {
+        let mut jobs: Vec<String> = self
+            .jobs
+            .iter()
+            .filter(|(_, dependencies)| dependencies.iter().any(|d| d == name))
+            .map(|(job, _)| job.clone())
+            .collect();
+        jobs.sort();
+        jobs
+    }
+
+    /// Parse and apply an ALTER or DROP statement to the catalog
+    pub fn execute_ddl(&mut self, sql: &str) -> Result<(), SqlError> {
+        let query = self.parser.parse(sql)?;
+        self.apply_ddl(&query)
+    }
+
+    /// Apply a parsed ALTER or DROP statement to the catalog
+    ///
+    /// The statement is validated against the running jobs that depend on the object
+    /// and, for added columns, against the schema evolution rules of its schema.
+    pub fn apply_ddl(&mut self, query: &StreamingQuery) -> Result<(), SqlError> {
+        match self.plan_ddl(query)? {
+            CatalogChange::Unchanged => {}
+            CatalogChange::ReplaceSchema { name, schema } => {
+                self.schemas.insert(name, schema);
+            }
+            CatalogChange::SetProperties { name, properties } => {
+                self.properties.entry(name).or_default().extend(properties);
+            }
+            CatalogChange::DropObject { name } => self.unregister_stream(&name)?,
+            CatalogChange::DropJob { name } => {
+                self.jobs.remove(&name);
+            }
+        }
         Ok(())
     }
 
@@ -65,6 +164,74 @@ impl StreamingSqlContext {
         self.registered_streams.keys().cloned().collect()
     }
 
+    pub fn list_tables(&self) -> Vec<String> {
+        self.tables.iter().cloned().collect()
+    }
+
+    pub fn list_jobs(&self) -> Vec<String> {
+        self.jobs.keys().cloned().collect()
+    }
+
+    /// WITH properties of a stream or table (set with ALTER ... SET)
+    pub fn get_properties(&self, name: &str) -> Option<&HashMap<String, String>> {
+        self.properties.get(name)
+    }
+
+    fn plan_ddl(&self, query: &StreamingQuery) -> Result<CatalogChange, SqlError> {
+        match query {
+            StreamingQuery::Drop {
+                object_type: ObjectType::Job,
+                name,
+                if_exists,
+                ..
+            } => {
+                if self.jobs.contains_key(name) {
+                    Ok(CatalogChange::DropJob { name: name.clone() })
+                } else if *if_exists {
+                    Ok(CatalogChange::Unchanged)
+                } else {
+                    Err(SqlError::ExecutionError {
+                        message: format!("Job '{}' not found", name),
+                        query: None,
+                    })
+                }
+            }
+            StreamingQuery::Alter {
+                object_type, name, ..
+            }
+            | StreamingQuery::Drop {
+                object_type, name, ..
+            } => plan_object_ddl(
+                query,
+                self.lookup(*object_type, name)?,
+                &self.dependent_jobs(name),
+            ),
+            _ => Err(SqlError::ExecutionError {
+                message: "Expected an ALTER or DROP statement".to_string(),
+                query: None,
+            }),
+        }
+    }
+
+    /// Schema of a registered stream or table, checking that it is of the expected kind
+    fn lookup(&self, object_type: ObjectType, name: &str) -> Result<Option<&Schema>, SqlError> {
+        let Some(schema) = self.schemas.get(name) else {
+            return Ok(None);
+        };
+        let actual = if self.tables.contains(name) {
+            ObjectType::Table
+        } else {
+            ObjectType::Stream
+        };
+        if actual != object_type {
+            return Err(SqlError::StreamError {
+                stream_name: name.to_string(),
+                message: format!("'{}' is a {}, not a {}", name, actual, object_type),
+            });
+        }
+        Ok(Some(schema))
+    }
+
     fn validate_query(&self, query: &StreamingQuery) -> Result<(), SqlError> {
         match query {
             StreamingQuery::Select {
@@ -150,6 +317,10 @@ impl StreamingSqlContext {
                 // Validate the underlying SELECT query
                 self.validate_query(as_select)
             }
+            StreamingQuery::Alter { .. } | StreamingQuery::Drop { .. } => {
+                // Validate against dependent jobs and schema evolution without applying
+                self.plan_ddl(query).map(|_| ())
+            }
             StreamingQuery::Show { .. } => {
                 // SHOW commands don't need schema validation
                 Ok(())
@@ -182,6 +353,7 @@ impl StreamingSqlContext {
                 table_name,
                 columns: _,
                 source: _,
+                ..
             } => {
                 // Validate INSERT command - check table exists and types match
                 if !self.registered_streams.contains_key(table_name) {
@@ -199,6 +371,7 @@ impl StreamingSqlContext {
                 table_name,
                 assignments: _,
                 where_clause: _,
+                ..
             } => {
                 // Validate UPDATE command - check table exists
                 if !self.registered_streams.contains_key(table_name) {
@@ -215,6 +388,7 @@ impl StreamingSqlContext {
             StreamingQuery::Delete {
                 table_name,
                 where_clause: _,
+                ..
             } => {
                 // Validate DELETE command - check table exists
                 if !self.registered_streams.contains_key(table_name) {
@@ -227,6 +401,37 @@ impl StreamingSqlContext {
                 // - WHERE clause validation
                 Ok(())
             }
+            StreamingQuery::Merge { target, source, .. } => {
+                // Validate MERGE command - check target and source exist
+                if !self.registered_streams.contains_key(target) {
+                    return Err(SqlError::TableNotFound {
+                        table_name: target.clone(),
+                    });
+                }
+                if let crate::ferris::sql::ast::StreamSource::Stream(name) = source {
+                    if !self.registered_streams.contains_key(name) {
+                        return Err(SqlError::StreamError {
+                            stream_name: name.clone(),
+                            message: "Stream not found".to_string(),
+                        });
+                    }
+                }
+                Ok(())
+            }
+            StreamingQuery::Upsert {
+                table_name, source, ..
+            } => {
+                // Validate UPSERT command - check table exists and any SELECT source
+                if !self.registered_streams.contains_key(table_name) {
+                    return Err(SqlError::TableNotFound {
+                        table_name: table_name.clone(),
+                    });
+                }
+                match source {
+                    InsertSource::Select { query } => self.validate_query(query),
+                    InsertSource::Values { .. } => Ok(()),
+                }
+            }
             StreamingQuery::Union { left, right, .. } => {
                 // Validate both sides of the UNION
                 self.validate_query(left)?;
@@ -243,7 +448,7 @@ impl StreamingSqlContext {
         schema: &Schema,
     ) -> Result<(), SqlError> {
         match expr {
-            crate::ferris::sql::ast::Expr::Column(name) => {
+            crate::ferris::sql::ast::Expr::Column(name, _) => {
                 if !schema.has_field(name) {
                     return Err(SqlError::SchemaError {
                         message: "Column not found".to_string(),
@@ -252,7 +457,7 @@ impl StreamingSqlContext {
                 }
                 Ok(())
             }
-            crate::ferris::sql::ast::Expr::Literal(_) => Ok(()),
+            crate::ferris::sql::ast::Expr::Literal(_, _) => Ok(()),
             crate::ferris::sql::ast::Expr::BinaryOp { left, right, .. } => {
                 self.validate_expression(left, schema)?;
                 self.validate_expression(right, schema)
@@ -287,6 +492,10 @@ impl StreamingSqlContext {
             StreamingQuery::CreateTable { name, .. } => name,
             StreamingQuery::CreateStreamInto { name, .. } => name,
             StreamingQuery::CreateTableInto { name, .. } => name,
+            StreamingQuery::Alter { name, .. } | StreamingQuery::Drop { name, .. } => {
+                // Use the altered or dropped object as the stream identifier
+                name
+            }
             StreamingQuery::Show { .. } => {
                 // SHOW commands don't have a primary stream name
                 "system"
@@ -299,11 +508,11 @@ impl StreamingSqlContext {
                 // Use the job name as the stream identifier
                 name
             }
-            StreamingQuery::PauseJob { name } => {
+            StreamingQuery::PauseJob { name, .. } => {
                 // Use the job name as the stream identifier
                 name
             }
-            StreamingQuery::ResumeJob { name } => {
+            StreamingQuery::ResumeJob { name, .. } => {
                 // Use the job name as the stream identifier
                 name
             }
@@ -327,6 +536,14 @@ impl StreamingSqlContext {
                 // Use the table name as the stream identifier
                 table_name
             }
+            StreamingQuery::Merge { target, .. } => {
+                // Use the merge target as the stream identifier
+                target
+            }
+            StreamingQuery::Upsert { table_name, .. } => {
+                // Use the table name as the stream identifier
+                table_name
+            }
             StreamingQuery::Union { left, .. } => {
                 // For UNION, use the left side's stream name as primary identifier
                 match left.as_ref() {
@@ -359,6 +576,218 @@ impl StreamingSqlContext {
     }
 }
 
+/// Plan an ALTER or DROP of a stream or table
+///
+/// `schema` is the current schema of the object, `None` when it does not exist, and
+/// `dependent_jobs` the running jobs that read from or write to it. Properties cannot be
+/// changed and the object cannot be dropped while jobs depend on it; added columns must
+/// follow the evolution rules of the schema.
+pub fn plan_object_ddl(
+    query: &StreamingQuery,
+    schema: Option<&Schema>,
+    dependent_jobs: &[String],
+) -> Result<CatalogChange, SqlError> {
+    match query {
+        StreamingQuery::Alter {
+            object_type,
+            name,
+            operation,
+            ..
+        } => {
+            let schema = schema.ok_or_else(|| not_found(*object_type, name))?;
+            match operation {
+                AlterOperation::AddColumn {
+                    column,
+                    if_not_exists,
+                } => {
+                    if schema.has_field(&column.name) {
+                        if *if_not_exists {
+                            return Ok(CatalogChange::Unchanged);
+                        }
+                        return Err(SqlError::SchemaError {
+                            message: format!("Column already exists in {} '{}'", object_type, name),
+                            column: Some(column.name.clone()),
+                        });
+                    }
+
+                    let mut evolved = schema.clone();
+                    evolved.fields.push(FieldDefinition::new(
+                        column.name.clone(),
+                        column.data_type.clone(),
+                        column.nullable,
+                    ));
+                    evolved.metadata.updated_at = chrono::Utc::now().timestamp();
+
+                    // Running jobs keep reading with the old schema, so they need at
+                    // least backward compatibility even where the schema opts out
+                    let mode = match &schema.metadata.compatibility {
+                        CompatibilityMode::None if !dependent_jobs.is_empty() => {
+                            CompatibilityMode::Backward
+                        }
+                        mode => mode.clone(),
+                    };
+                    SchemaEvolution::new()
+                        .check_compatibility(schema, &evolved, &mode)
+                        .map_err(|e| SqlError::SchemaError {
+                            message: format!(
+                                "Cannot add column to {} '{}': {}",
+                                object_type, name, e
+                            ),
+                            column: Some(column.name.clone()),
+                        })?;
+
+                    Ok(CatalogChange::ReplaceSchema {
+                        name: name.clone(),
+                        schema: evolved,
+                    })
+                }
+                AlterOperation::SetProperties { properties } => {
+                    // Properties are bound when a job starts
+                    ensure_no_dependent_jobs("alter", *object_type, name, dependent_jobs)?;
+                    Ok(CatalogChange::SetProperties {
+                        name: name.clone(),
+                        properties: properties.clone(),
+                    })
+                }
+            }
+        }
+        StreamingQuery::Drop {
+            object_type,
+            name,
+            if_exists,
+            ..
+        } => {
+            if schema.is_none() {
+                return if *if_exists {
+                    Ok(CatalogChange::Unchanged)
+                } else {
+                    Err(not_found(*object_type, name))
+                };
+            }
+            ensure_no_dependent_jobs("drop", *object_type, name, dependent_jobs)?;
+            Ok(CatalogChange::DropObject { name: name.clone() })
+        }
+        _ => Err(SqlError::ExecutionError {
+            message: "Expected an ALTER or DROP of a stream or table".to_string(),
+            query: None,
+        }),
+    }
+}
+
+/// Streams and tables a query reads from or writes to, sorted
+pub fn query_dependencies(query: &StreamingQuery) -> Vec<String> {
+    let mut dependencies = Vec::new();
+    collect_dependencies(query, &mut dependencies);
+    dependencies.sort();
+    dependencies.dedup();
+    dependencies
+}
+
+fn ensure_no_dependent_jobs(
+    action: &str,
+    object_type: ObjectType,
+    name: &str,
+    jobs: &[String],
+) -> Result<(), SqlError> {
+    if jobs.is_empty() {
+        return Ok(());
+    }
+    Err(SqlError::ExecutionError {
+        message: format!(
+            "Cannot {} {} '{}' while running jobs depend on it: {}. Drop those jobs first",
+            action,
+            object_type,
+            name,
+            jobs.join(", ")
+        ),
+        query: None,
+    })
+}
+
+fn not_found(object_type: ObjectType, name: &str) -> SqlError {
+    match object_type {
+        ObjectType::Table => SqlError::TableNotFound {
+            table_name: name.to_string(),
+        },
+        _ => SqlError::StreamError {
+            stream_name: name.to_string(),
+            message: "Stream not found".to_string(),
+        },
+    }
+}
+
+/// Streams and tables a query reads from or writes to
+fn collect_dependencies(query: &StreamingQuery, dependencies: &mut Vec<String>) {
+    match query {
+        StreamingQuery::Select { from, joins, .. } => {
+            collect_source_dependencies(from, dependencies);
+            for join in joins.iter().flatten() {
+                collect_source_dependencies(&join.right_source, dependencies);
+            }
+        }
+        StreamingQuery::CreateStream {
+            name, as_select, ..
+        }
+        | StreamingQuery::CreateTable {
+            name, as_select, ..
+        } => {
+            dependencies.push(name.clone());
+            collect_dependencies(as_select, dependencies);
+        }
+        StreamingQuery::CreateStreamInto {
+            name,
+            as_select,
+            into_clause,
+            ..
+        }
+        | StreamingQuery::CreateTableInto {
+            name,
+            as_select,
+            into_clause,
+            ..
+        } => {
+            dependencies.push(name.clone());
+            dependencies.push(into_clause.sink_name.clone());
+            collect_dependencies(as_select, dependencies);
+        }
+        StreamingQuery::StartJob { query, .. } | StreamingQuery::DeployJob { query, .. } => {
+            collect_dependencies(query, dependencies);
+        }
+        StreamingQuery::InsertInto {
+            table_name, source, ..
+        }
+        | StreamingQuery::Upsert {
+            table_name, source, ..
+        } => {
+            dependencies.push(table_name.clone());
+            if let InsertSource::Select { query } = source {
+                collect_dependencies(query, dependencies);
+            }
+        }
+        StreamingQuery::Update { table_name, .. } | StreamingQuery::Delete { table_name, .. } => {
+            dependencies.push(table_name.clone());
+        }
+        StreamingQuery::Merge { target, source, .. } => {
+            dependencies.push(target.clone());
+            collect_source_dependencies(source, dependencies);
+        }
+        StreamingQuery::Union { left, right, .. } => {
+            collect_dependencies(left, dependencies);
+            collect_dependencies(right, dependencies);
+        }
+        _ => {}
+    }
+}
+
+fn collect_source_dependencies(source: &StreamSource, dependencies: &mut Vec<String>) {
+    match source {
+        StreamSource::Stream(name) | StreamSource::Table(name) | StreamSource::Uri(name) => {
+            dependencies.push(name.clone())
+        }
+        StreamSource::Subquery(subquery) => collect_dependencies(subquery, dependencies),
+    }
+}
+
 impl Default for StreamingSqlContext {
     fn default() -> Self {
         Self::new()
@@ -378,8 +807,20 @@ impl SqlQueryExecutor {
         self.context.execute_query(sql)
     }
 
+    /// Describe how a query would run: its canonical text and, when the optimizer
+    /// rewrites it, the rules that applied and the rewritten query
     pub fn explain(&self, sql: &str) -> Result<String, SqlError> {
         let query = self.context.parser.parse(sql)?;
-        Ok(format!("Execution plan for: {:#?}", query))
+        let mut plan = format!("Execution plan for:\n{}", format_query(&query));
+
+        let optimized = QueryOptimizer::new().optimize(&query);
+        if !optimized.applied_rules.is_empty() {
+            plan.push_str(&format!(
+                "\n\nOptimized ({:?}):\n{}",
+                optimized.applied_rules,
+                format_query(&optimized.query)
+            ));
+        }
+        Ok(plan)
     }
 }
@@ -97,6 +97,7 @@ Errors implement standard Rust error traits (`std::error::Error`, `Display`, `De
 for seamless integration with error handling libraries and frameworks.
 */
 
+pub mod diagnostics;
 pub mod recovery;
 
 // Re-export recovery types for easy access
@@ -506,91 +507,11 @@ impl SqlError {
 
     /// Suggest similar function names using fuzzy string matching
     fn suggest_similar_functions(unknown_func: &str) -> Vec<String> {
-        let available_functions = vec![
-            // Aggregate functions
-            "COUNT",
-            "SUM",
-            "AVG",
-            "MIN",
-            "MAX",
-            "APPROX_COUNT_DISTINCT",
-            "FIRST_VALUE",
-            "LAST_VALUE",
-            "LISTAGG",
-            "STRING_AGG",
-            "COUNT_DISTINCT",
-            "MEDIAN",
-            "STDDEV",
-            "STDDEV_SAMP",
-            "STDDEV_POP",
-            "VARIANCE",
-            "VAR_SAMP",
-            "VAR_POP",
-            // Header functions
-            "HEADER",
-            "HEADER_KEYS",
-            "HAS_HEADER",
-            "SET_HEADER",
-            "REMOVE_HEADER",
-            // Math functions
-            "ABS",
-            "ROUND",
-            "CEIL",
-            "CEILING",
-            "FLOOR",
-            "SQRT",
-            "POWER",
-            "POW",
-            "MOD",
-            // String functions
-            "UPPER",
-            "LOWER",
-            "SUBSTRING",
-            "REPLACE",
-            "TRIM",
-            "LTRIM",
-            "RTRIM",
-            "LENGTH",
-            "LEN",
-            "SPLIT",
-            "JOIN",
-            "LEFT",
-            "RIGHT",
-            "POSITION",
-            "CONCAT",
-            // JSON functions
-            "JSON_EXTRACT",
-            "JSON_VALUE",
-            // Conversion functions
-            "CAST",
-            "COALESCE",
-            "NULLIF",
-            // System functions
-            "TIMESTAMP",
-            "NOW",
-            "CURRENT_TIMESTAMP",
-            // Date/Time functions
-            "DATE_FORMAT",
-            "DATEDIFF",
-            "EXTRACT",
-            // Comparison functions
-            "LEAST",
-            "GREATEST",
-            // Advanced type functions
-            "ARRAY",
-            "STRUCT",
-            "MAP",
-            "ARRAY_LENGTH",
-            "ARRAY_CONTAINS",
-            "MAP_KEYS",
-            "MAP_VALUES",
-        ];
-
         let unknown_lower = unknown_func.to_lowercase();
         let mut suggestions = Vec::new();
 
         // Find functions with similar names using multiple strategies
-        for &func in &available_functions {
+        for &func in BUILTIN_FUNCTIONS {
             let func_lower = func.to_lowercase();
 
             // Strategy 1: Check for exact prefix/suffix matches
@@ -641,6 +562,136 @@ impl SqlError {
     }
 }
 
+/// Names of the built-in SQL functions, used to suggest fixes for misspelled calls
+pub(crate) const BUILTIN_FUNCTIONS: &[&str] = &[
+    // Aggregate functions
+    "COUNT",
+    "SUM",
+    "AVG",
+    "MIN",
+    "MAX",
+    "APPROX_COUNT_DISTINCT",
+    "FIRST_VALUE",
+    "LAST_VALUE",
+    "LISTAGG",
+    "STRING_AGG",
+    "COUNT_DISTINCT",
+    "MEDIAN",
+    "STDDEV",
+    "STDDEV_SAMP",
+    "STDDEV_POP",
+    "VARIANCE",
+    "VAR_SAMP",
+    "VAR_POP",
+    "VAR",
+    "FIRST",
+    "LAST",
+    "GROUP_CONCAT",
+    // Header functions
+    "HEADER",
+    "HEADER_KEYS",
+    "HAS_HEADER",
+    "SET_HEADER",
+    "REMOVE_HEADER",
+    // Math functions
+    "ABS",
+    "ROUND",
+    "CEIL",
+    "CEILING",
+    "FLOOR",
+    "SQRT",
+    "POWER",
+    "POW",
+    "MOD",
+    // String functions
+    "UPPER",
+    "LOWER",
+    "SUBSTRING",
+    "REPLACE",
+    "TRIM",
+    "LTRIM",
+    "RTRIM",
+    "LENGTH",
+    "LEN",
+    "SPLIT",
+    "JOIN",
+    "LEFT",
+    "RIGHT",
+    "POSITION",
+    "CONCAT",
+    // Regex, hashing and encoding functions
+    "REGEXP_LIKE",
+    "REGEXP_EXTRACT",
+    "REGEXP_REPLACE",
+    "REGEXP_SPLIT",
+    "MD5",
+    "SHA256",
+    "XXHASH64",
+    "BASE64_ENCODE",
+    "BASE64_DECODE",
+    "URL_DECODE",
+    "PARSE_URL",
+    "UUID",
+    // JSON functions
+    "JSON_EXTRACT",
+    "JSON_VALUE",
+    "JSON_QUERY",
+    "PARSE_JSON",
+    "TO_JSON",
+    "JSON_OBJECT",
+    "JSON_ARRAY",
+    "JSON_ARRAYAGG",
+    "JSON_OBJECTAGG",
+    // Conversion functions
+    "CAST",
+    "COALESCE",
+    "NULLIF",
+    // System functions
+    "TIMESTAMP",
+    "NOW",
+    "CURRENT_TIMESTAMP",
+    "UNIX_TIMESTAMP",
+    "FROM_UNIXTIME",
+    // Date/Time functions
+    "DATE_FORMAT",
+    "DATEDIFF",
+    "EXTRACT",
+    "CONVERT_TZ",
+    "TO_TIMESTAMP",
+    "TO_DATE",
+    "DATE_TRUNC",
+    "TIME_BUCKET",
+    "DATE_ADD",
+    "DATE_SUB",
+    // Comparison functions
+    "LEAST",
+    "GREATEST",
+    // Advanced type functions
+    "ARRAY",
+    "STRUCT",
+    "MAP",
+    "ARRAY_LENGTH",
+    "ARRAY_CONTAINS",
+    "MAP_KEYS",
+    "MAP_VALUES",
+    // Higher-order array functions
+    "TRANSFORM",
+    "FILTER",
+    "REDUCE",
+    "ARRAY_SORT",
+    "ARRAY_DISTINCT",
+    // Window functions
+    "ROW_NUMBER",
+    "RANK",
+    "DENSE_RANK",
+    "PERCENT_RANK",
+    "CUME_DIST",
+    "NTILE",
+    "LAG",
+    "LEAD",
+    "NTH_VALUE",
+];
+
 /// Result type for SQL operations
 pub type SqlResult<T> = Result<T, SqlError>;
 
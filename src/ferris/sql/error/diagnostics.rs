@@ -0,0 +1,265 @@
+//! Source Diagnostics
+//!
+//! Errors found in SQL text, located by a [`Span`] and rendered the way rustc renders its
+//! diagnostics: a header, the file location, the offending line and carets under the
+//! offending text, followed by help notes such as "did you mean" suggestions.
+//!
+//! ```text
+//! error: Unexpected 'FORM' after the end of the statement
+//!  --> app.sql:3:10
+//!   |
+//! 3 | SELECT * FORM orders
+//!   |          ^^^^
+//!   |
+//!   = help: did you mean `FROM`?
+//! ```
+
+use std::fmt;
+
+/// A byte range of SQL text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Span {
+    /// Byte offset of the first byte
+    pub start: usize,
+    /// Byte offset just past the last byte
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self {
+            start,
+            end: end.max(start),
+        }
+    }
+
+    /// Span a range of character positions, as the tokenizer reports them
+    pub fn from_char_range(text: &str, start: usize, end: usize) -> Self {
+        let byte_offset = |chars: usize| {
+            text.char_indices()
+                .nth(chars)
+                .map_or(text.len(), |(offset, _)| offset)
+        };
+        Self::new(byte_offset(start), byte_offset(end))
+    }
+
+    /// Shift the span by `offset` bytes, for text embedded in a larger source
+    pub fn offset(self, offset: usize) -> Self {
+        Self::new(self.start + offset, self.end + offset)
+    }
+
+    /// Whether the span covers no text
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// One-based line and column (in characters) of the start of the span
+    pub fn line_column(&self, text: &str) -> (usize, usize) {
+        let start = clamp_to_char_boundary(text, self.start);
+        let before = &text[..start];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+        (line, before[line_start..].chars().count() + 1)
+    }
+}
+
+/// How serious a diagnostic is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// A problem found in SQL text
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    /// The offending text, if it is known
+    pub span: Option<Span>,
+    /// Short note printed after the carets
+    pub label: Option<String>,
+    /// Notes printed below the excerpt, such as suggested fixes
+    pub help: Vec<String>,
+}
+
+impl Diagnostic {
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            message: message.into(),
+            span: None,
+            label: None,
+            help: Vec::new(),
+        }
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            ..Self::error(message)
+        }
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help.push(help.into());
+        self
+    }
+
+    /// Add a "did you mean" note for a replacement of the offending text
+    pub fn with_suggestion(self, suggestion: &str) -> Self {
+        self.with_help(format!("did you mean `{}`?", suggestion))
+    }
+
+    /// Shift the span by `offset` bytes, for text embedded in a larger source
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.span = self.span.map(|span| span.offset(offset));
+        self
+    }
+
+    /// One-based line and column of the diagnostic in `source`
+    pub fn line_column(&self, source: &str) -> Option<(usize, usize)> {
+        self.span.map(|span| span.line_column(source))
+    }
+
+    /// Render the diagnostic with an excerpt of `source`, which `origin` names
+    pub fn render(&self, source: &str, origin: &str) -> String {
+        let mut out = format!("{}: {}\n", self.severity, self.message);
+        let mut gutter = String::from(" ");
+
+        if let Some(span) = self.span {
+            let start = clamp_to_char_boundary(source, span.start);
+            let end = clamp_to_char_boundary(source, span.end);
+            let (line, column) = span.line_column(source);
+            let line_start = source[..start].rfind('\n').map_or(0, |newline| newline + 1);
+            let line_end = source[start..]
+                .find('\n')
+                .map_or(source.len(), |newline| start + newline);
+            let line_text = source[line_start..line_end].trim_end_matches('\r');
+
+            let number = line.to_string();
+            gutter = " ".repeat(number.len());
+            // Keep tabs so the carets line up with the excerpt
+            let indent: String = source[line_start..start]
+                .chars()
+                .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+                .collect();
+            let width = source[start..end.min(line_end).max(start)]
+                .chars()
+                .count()
+                .max(1);
+
+            out.push_str(&format!("{}--> {}:{}:{}\n", gutter, origin, line, column));
+            out.push_str(&format!("{} |\n", gutter));
+            out.push_str(&format!("{} | {}\n", number, line_text));
+            out.push_str(&format!("{} | {}{}", gutter, indent, "^".repeat(width)));
+            if let Some(label) = &self.label {
+                out.push_str(&format!(" {}", label));
+            }
+            out.push('\n');
+            if !self.help.is_empty() {
+                out.push_str(&format!("{} |\n", gutter));
+            }
+        }
+
+        for help in &self.help {
+            out.push_str(&format!("{} = help: {}\n", gutter, help));
+        }
+        out
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.severity, self.message)
+    }
+}
+
+/// Render several diagnostics of one source, separated by blank lines
+pub fn render_all(diagnostics: &[Diagnostic], source: &str, origin: &str) -> String {
+    diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.render(source, origin))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The candidate closest to a misspelled `word`, ignoring case
+///
+/// Candidates further than a third of the word's length (at least one edit) are not
+/// considered, and words of one or two characters are too short to tell what they were
+/// meant to be. Ties go to the earlier candidate.
+pub fn closest_match<'a>(
+    word: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let word = word.to_lowercase();
+    let length = word.chars().count();
+    if length < 3 {
+        return None;
+    }
+    let max_distance = (length / 3).max(1);
+    candidates
+        .into_iter()
+        .filter_map(|candidate| {
+            let distance = edit_distance(&word, &candidate.to_lowercase());
+            (distance > 0 && distance <= max_distance).then_some((distance, candidate))
+        })
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Edit distance counting insertions, deletions, substitutions and swaps of adjacent
+/// characters as one edit each
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = distance;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+fn clamp_to_char_boundary(text: &str, mut offset: usize) -> usize {
+    offset = offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
@@ -44,7 +44,7 @@ impl AccumulatorManager {
         expr: &Expr,
     ) -> Result<(), SqlError> {
         match expr {
-            Expr::Function { name, args } => {
+            Expr::Function { name, args, .. } => {
                 match name.to_uppercase().as_str() {
                     "COUNT" => {
                         // For COUNT(column), only count non-NULL values
@@ -205,6 +205,34 @@ impl AccumulatorManager {
                             }
                         }
                     }
+                    "JSON_ARRAYAGG" => {
+                        if let Some(arg) = args.first() {
+                            let value =
+                                ExpressionEvaluator::evaluate_expression_value(arg, record)?;
+                            accumulator
+                                .json_array_values
+                                .entry(field_name.to_string())
+                                .or_default()
+                                .push(value);
+                        }
+                    }
+                    "JSON_OBJECTAGG" => {
+                        if let [key_expr, value_expr] = args.as_slice() {
+                            // Rows with a NULL key are skipped
+                            let key =
+                                ExpressionEvaluator::evaluate_expression_value(key_expr, record)?;
+                            if !matches!(key, FieldValue::Null) {
+                                let value = ExpressionEvaluator::evaluate_expression_value(
+                                    value_expr, record,
+                                )?;
+                                accumulator
+                                    .json_object_entries
+                                    .entry(field_name.to_string())
+                                    .or_default()
+                                    .push((key.to_display_string(), value));
+                            }
+                        }
+                    }
                     _ => {
                         // For non-recognized aggregates, store as first/last
                         if let Some(arg) = args.first() {
@@ -249,6 +277,7 @@ impl AccumulatorManager {
             FieldValue::Boolean(b) => b.to_string(),
             FieldValue::Null => "NULL".to_string(),
             FieldValue::Timestamp(ts) => ts.to_string(),
+            FieldValue::TimestampTz(ts) => ts.to_rfc3339(),
             FieldValue::Array(arr) => {
                 format!(
                     "[{}]",
@@ -288,6 +317,9 @@ impl AccumulatorManager {
                 }
             }
             FieldValue::Interval { value, unit } => format!("{}_{:?}", value, unit),
+            FieldValue::Bytes(_) | FieldValue::Uuid(_) | FieldValue::Time(_) => {
+                value.to_display_string()
+            }
         }
     }
 
@@ -342,6 +374,8 @@ impl AccumulatorManager {
                         | "FIRST"
                         | "LAST"
                         | "STRING_AGG"
+                        | "JSON_ARRAYAGG"
+                        | "JSON_OBJECTAGG"
                 )
             }
             _ => false,
@@ -352,7 +386,7 @@ impl AccumulatorManager {
     #[doc(hidden)]
     pub fn generate_field_name(expr: &Expr) -> String {
         match expr {
-            Expr::Function { name, args } => {
+            Expr::Function { name, args, .. } => {
                 if args.is_empty() {
                     format!("{}(*)", name)
                 } else {
@@ -366,9 +400,9 @@ impl AccumulatorManager {
     /// Convert an expression to a string representation
     fn expr_to_string(expr: &Expr) -> String {
         match expr {
-            Expr::Column(name) => name.clone(),
-            Expr::Literal(val) => format!("{:?}", val),
-            Expr::Function { name, args } => {
+            Expr::Column(name, _) => name.clone(),
+            Expr::Literal(val, _) => format!("{:?}", val),
+            Expr::Function { name, args, .. } => {
                 if args.is_empty() {
                     format!("{}()", name)
                 } else {
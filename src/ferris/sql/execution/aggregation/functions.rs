@@ -43,6 +43,12 @@ impl AggregateFunctions {
                     "APPROX_COUNT_DISTINCT" => {
                         Self::compute_approx_count_distinct_aggregate(field_name, accumulator)
                     }
+                    "JSON_ARRAYAGG" => {
+                        Self::compute_json_arrayagg_aggregate(field_name, accumulator)
+                    }
+                    "JSON_OBJECTAGG" => {
+                        Self::compute_json_objectagg_aggregate(field_name, accumulator)
+                    }
                     _ => {
                         // Non-aggregate function - use first value
                         Ok(accumulator
@@ -214,7 +220,7 @@ impl AggregateFunctions {
             let separator = if let Expr::Function { args, .. } = expr {
                 if args.len() > 1 {
                     // Try to extract separator from second argument
-                    if let Expr::Literal(LiteralValue::String(sep)) = &args[1] {
+                    if let Expr::Literal(LiteralValue::String(sep), _) = &args[1] {
                         sep.as_str()
                     } else {
                         "," // Default if not a string literal
@@ -258,6 +264,30 @@ impl AggregateFunctions {
         }
     }
 
+    /// Compute JSON_ARRAYAGG aggregate value
+    fn compute_json_arrayagg_aggregate(
+        field_name: &str,
+        accumulator: &GroupAccumulator,
+    ) -> Result<FieldValue, SqlError> {
+        Ok(accumulator
+            .json_array_values
+            .get(field_name)
+            .map(|values| FieldValue::Array(values.clone()))
+            .unwrap_or(FieldValue::Null))
+    }
+
+    /// Compute JSON_OBJECTAGG aggregate value (later keys overwrite earlier ones)
+    fn compute_json_objectagg_aggregate(
+        field_name: &str,
+        accumulator: &GroupAccumulator,
+    ) -> Result<FieldValue, SqlError> {
+        Ok(accumulator
+            .json_object_entries
+            .get(field_name)
+            .map(|entries| FieldValue::Struct(entries.iter().cloned().collect()))
+            .unwrap_or(FieldValue::Null))
+    }
+
     /// Check if an expression is a valid aggregate function
     #[doc(hidden)]
     pub fn is_aggregate_function(expr: &Expr) -> bool {
@@ -278,6 +308,8 @@ impl AggregateFunctions {
                         | "LAST"
                         | "STRING_AGG"
                         | "GROUP_CONCAT"
+                        | "JSON_ARRAYAGG"
+                        | "JSON_OBJECTAGG"
                 )
             }
             _ => false,
@@ -301,6 +333,8 @@ impl AggregateFunctions {
             "LAST",
             "STRING_AGG",
             "GROUP_CONCAT",
+            "JSON_ARRAYAGG",
+            "JSON_OBJECTAGG",
         ]
     }
 }
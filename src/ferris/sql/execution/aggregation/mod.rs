@@ -29,7 +29,7 @@
 //!
 //! // Process a record through aggregation
 //! let record = StreamRecord {
-//!     fields: HashMap::new(),
+//!     fields: HashMap::new().into(),
 //!     timestamp: 0,
 //!     offset: 0,
 //!     partition: 0,
@@ -70,14 +70,15 @@ impl AggregationEngine {
         GroupAccumulator::new()
     }
 
-    /// Create a new GROUP BY state
+    /// Create a new GROUP BY state over `source`
     pub fn create_group_by_state(
         &self,
+        source: &crate::ferris::sql::ast::StreamSource,
         group_expressions: Vec<Expr>,
         select_fields: Vec<crate::ferris::sql::ast::SelectField>,
         having_clause: Option<Expr>,
     ) -> GroupByState {
-        GroupByState::new(group_expressions, select_fields, having_clause)
+        GroupByState::new(source, group_expressions, select_fields, having_clause)
     }
 }
 
@@ -40,6 +40,7 @@ impl GroupByStateManager {
             FieldValue::Null => "NULL".to_string(),
             FieldValue::Date(d) => d.format("%Y-%m-%d").to_string(),
             FieldValue::Timestamp(ts) => ts.format("%Y-%m-%d %H:%M:%S%.3f").to_string(),
+            FieldValue::TimestampTz(ts) => ts.to_rfc3339(),
             FieldValue::Decimal(dec) => dec.to_string(),
             FieldValue::ScaledInteger(value, scale) => {
                 // Format as decimal with appropriate precision for group key
@@ -86,6 +87,9 @@ impl GroupByStateManager {
             FieldValue::Interval { value, unit } => {
                 format!("INTERVAL:{}:{:?}", value, unit)
             }
+            FieldValue::Bytes(_) | FieldValue::Uuid(_) | FieldValue::Time(_) => {
+                value.to_display_string()
+            }
         }
     }
 
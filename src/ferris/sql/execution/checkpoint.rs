@@ -0,0 +1,516 @@
+//! Periodic checkpoints of operator state and source positions.
+//!
+//! A [`Checkpoint`] is a consistent snapshot of a job taken between two batches: the
+//! accumulators of every GROUP BY, the buffers of every window, the rows of MERGE/UPSERT
+//! targets, the contents of the job's [state backends](super::state) and the position each
+//! source was read up to. Joins read their right side from sources and buffer nothing, so
+//! they contribute no state.
+//!
+//! Checkpoints of a job are files `checkpoint-<id>.ckpt` in `<checkpoint.dir>/<job>`. Each is
+//! written to a temporary file, synced and renamed into place, so a crash while writing
+//! leaves the previous checkpoint as the latest one. A checksum guards against files damaged
+//! later; an unreadable checkpoint is skipped in favour of the one before it.
+//!
+//! When a job with checkpoints is deployed, its engine is restored from the latest checkpoint
+//! and its sources seek back to the recorded positions, so no record is counted twice or
+//! lost. Kafka sources record the next offset of every partition they read and are
+//! assigned those partitions at those offsets on restore. Sources that report no position
+//! resume from their committed offsets.
+//!
+//! ## Configuration
+//!
+//! | Property | Default | |
+//! |----------|---------|-|
+//! | `checkpoint.dir` | none | Root directory; checkpoints are off without it |
+//! | `checkpoint.interval` | `60s` | Time between checkpoints, e.g. `500ms`, `30s`, `5m` |
+//! | `checkpoint.retained` | `3` | Checkpoints kept per job, older ones are deleted |
+//!
+//! ```sql
+//! CREATE STREAM customer_totals AS
+//! SELECT customer_id, COUNT(*) AS orders FROM orders GROUP BY customer_id
+//! WITH ('checkpoint.dir' = '/var/lib/ferris/checkpoints', 'checkpoint.interval' = '30s');
+//! ```
+
+use super::internal::GroupAccumulator;
+use super::processors::ProcessorContext;
+use super::state::{StateCodec, StateEntry};
+use super::subquery::parse_interval;
+use super::{StreamExecutionEngine, StreamRecord};
+use crate::ferris::datasource::SourceOffset;
+use crate::ferris::sql::error::SqlError;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::hash::Hasher;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use twox_hash::XxHash64;
+
+/// Job property with the root directory of checkpoints; checkpoints are off without it
+pub const CHECKPOINT_DIR_PROPERTY: &str = "checkpoint.dir";
+
+/// Job property with the time between checkpoints
+pub const CHECKPOINT_INTERVAL_PROPERTY: &str = "checkpoint.interval";
+
+/// Job property with the number of checkpoints kept per job
+pub const CHECKPOINT_RETAINED_PROPERTY: &str = "checkpoint.retained";
+
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(60);
+const DEFAULT_RETAINED: usize = 3;
+
+const FILE_PREFIX: &str = "checkpoint-";
+const FILE_SUFFIX: &str = ".ckpt";
+const TEMP_SUFFIX: &str = ".tmp";
+const FORMAT_VERSION: u8 = 1;
+
+/// Magic, version and trailing checksum
+const FILE_OVERHEAD: usize = 4 + 1 + 8;
+
+/// Checkpoint settings of a job
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckpointConfig {
+    /// Root directory, holding one subdirectory per job
+    pub directory: PathBuf,
+    /// Time between checkpoints
+    pub interval: Duration,
+    /// Checkpoints kept per job
+    pub retained: usize,
+}
+
+impl CheckpointConfig {
+    /// Checkpoints under `directory` with the default interval and retention
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            interval: DEFAULT_INTERVAL,
+            retained: DEFAULT_RETAINED,
+        }
+    }
+
+    /// Read `checkpoint.dir`, `checkpoint.interval` and `checkpoint.retained` from job
+    /// properties; `None` when checkpoints are not enabled
+    pub fn from_properties(properties: &HashMap<String, String>) -> Result<Option<Self>, SqlError> {
+        let Some(directory) = properties.get(CHECKPOINT_DIR_PROPERTY) else {
+            if let Some(property) = [CHECKPOINT_INTERVAL_PROPERTY, CHECKPOINT_RETAINED_PROPERTY]
+                .into_iter()
+                .find(|property| properties.contains_key(*property))
+            {
+                return Err(SqlError::ConfigurationError {
+                    message: format!("'{}' requires '{}'", property, CHECKPOINT_DIR_PROPERTY),
+                });
+            }
+            return Ok(None);
+        };
+
+        let mut config = Self::new(directory);
+        if let Some(value) = properties.get(CHECKPOINT_INTERVAL_PROPERTY) {
+            config.interval = parse_interval(&value.trim().to_lowercase())
+                .filter(|interval| !interval.is_zero())
+                .ok_or_else(|| SqlError::ConfigurationError {
+                    message: format!(
+                        "Invalid {} '{}': expected a positive duration such as '30s'",
+                        CHECKPOINT_INTERVAL_PROPERTY, value
+                    ),
+                })?;
+        }
+        if let Some(value) = properties.get(CHECKPOINT_RETAINED_PROPERTY) {
+            config.retained = value
+                .trim()
+                .parse::<usize>()
+                .ok()
+                .filter(|retained| *retained > 0)
+                .ok_or_else(|| SqlError::ConfigurationError {
+                    message: format!(
+                        "Invalid {} '{}': expected a positive number",
+                        CHECKPOINT_RETAINED_PROPERTY, value
+                    ),
+                })?;
+        }
+        Ok(Some(config))
+    }
+
+    /// Checkpoint files of one job
+    pub fn store(&self, job: &str) -> CheckpointStore {
+        CheckpointStore::new(self.directory.join(job))
+    }
+}
+
+/// Buffered records of a window
+#[derive(Debug, Clone, Default)]
+pub struct WindowSnapshot {
+    pub buffer: Vec<StreamRecord>,
+    /// Timestamp of the last emission
+    pub last_emit: i64,
+}
+
+/// State of every operator of an engine, see
+/// [`StreamExecutionEngine::snapshot_operator_state`]
+#[derive(Debug, Clone, Default)]
+pub struct OperatorSnapshot {
+    /// GROUP BY accumulators by state namespace and group key
+    pub group_by: HashMap<String, Vec<(Vec<String>, GroupAccumulator)>>,
+    /// Window buffers by query ID
+    pub windows: HashMap<String, WindowSnapshot>,
+    /// Rows of MERGE/UPSERT targets by target name and upsert key
+    pub merge_targets: HashMap<String, HashMap<String, StreamRecord>>,
+    /// Entries of state backends by operator name and namespace
+    pub backends: HashMap<String, HashMap<String, Vec<StateEntry>>>,
+}
+
+/// Operator state and source positions of a job at one point in its input
+#[derive(Debug, Clone)]
+pub struct Checkpoint {
+    /// Increases with every checkpoint of a job
+    pub id: u64,
+    /// Creation time in milliseconds since the epoch
+    pub created_at: i64,
+    pub operators: OperatorSnapshot,
+    /// Position each source was read up to, by source name
+    pub source_offsets: HashMap<String, SourceOffset>,
+}
+
+impl StateCodec for WindowSnapshot {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.buffer.encode(out);
+        self.last_emit.encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, SqlError> {
+        Ok(Self {
+            buffer: Vec::decode(input)?,
+            last_emit: i64::decode(input)?,
+        })
+    }
+}
+
+impl StateCodec for OperatorSnapshot {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.group_by.encode(out);
+        self.windows.encode(out);
+        self.merge_targets.encode(out);
+        self.backends.encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, SqlError> {
+        Ok(Self {
+            group_by: HashMap::decode(input)?,
+            windows: HashMap::decode(input)?,
+            merge_targets: HashMap::decode(input)?,
+            backends: HashMap::decode(input)?,
+        })
+    }
+}
+
+impl StateCodec for Checkpoint {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.id.encode(out);
+        self.created_at.encode(out);
+        self.operators.encode(out);
+        self.source_offsets.encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, SqlError> {
+        Ok(Self {
+            id: u64::decode(input)?,
+            created_at: i64::decode(input)?,
+            operators: OperatorSnapshot::decode(input)?,
+            source_offsets: HashMap::decode(input)?,
+        })
+    }
+}
+
+fn checksum(bytes: &[u8]) -> u64 {
+    let mut hasher = XxHash64::with_seed(0);
+    hasher.write(bytes);
+    hasher.finish()
+}
+
+/// Layout of the files checkpoints and savepoints are stored in
+///
+/// A file holds a magic number, the format version, the encoded content and a checksum of
+/// all of it. It is written to a temporary file, synced and renamed into place.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct StateFile {
+    /// What the file holds, for error messages, e.g. `checkpoint`
+    pub kind: &'static str,
+    pub magic: &'static [u8; 4],
+}
+
+const CHECKPOINT_FILE: StateFile = StateFile {
+    kind: "checkpoint",
+    magic: b"FCKP",
+};
+
+impl StateFile {
+    fn io_error(&self, path: &Path, action: &str, error: std::io::Error) -> SqlError {
+        SqlError::ResourceError {
+            resource: self.kind.to_string(),
+            message: format!(
+                "Failed to {} {} '{}': {}",
+                action,
+                self.kind,
+                path.display(),
+                error
+            ),
+        }
+    }
+
+    fn corrupt(&self, path: &Path, message: impl Into<String>) -> SqlError {
+        SqlError::ExecutionError {
+            message: format!(
+                "[{}-001] Corrupt {} '{}': {}",
+                self.kind.to_uppercase(),
+                self.kind,
+                path.display(),
+                message.into()
+            ),
+            query: None,
+        }
+    }
+
+    /// Write `content` to `path`; it replaces nothing until it is complete on disk
+    pub fn write(&self, path: &Path, content: &impl StateCodec) -> Result<(), SqlError> {
+        let directory = path.parent().unwrap_or(Path::new("."));
+        fs::create_dir_all(directory)
+            .map_err(|e| self.io_error(directory, "create directory for", e))?;
+
+        let mut bytes = Vec::with_capacity(FILE_OVERHEAD);
+        bytes.extend_from_slice(self.magic);
+        bytes.push(FORMAT_VERSION);
+        content.encode(&mut bytes);
+        let sum = checksum(&bytes);
+        bytes.extend_from_slice(&sum.to_be_bytes());
+
+        let mut temp_path = path.to_path_buf().into_os_string();
+        temp_path.push(TEMP_SUFFIX);
+        let temp_path = PathBuf::from(temp_path);
+
+        let mut file =
+            File::create(&temp_path).map_err(|e| self.io_error(&temp_path, "create", e))?;
+        file.write_all(&bytes)
+            .and_then(|_| file.sync_all())
+            .map_err(|e| self.io_error(&temp_path, "write", e))?;
+        fs::rename(&temp_path, path).map_err(|e| self.io_error(path, "rename", e))?;
+        // Persist the rename itself; not every platform can sync a directory
+        if let Ok(directory) = File::open(directory) {
+            let _ = directory.sync_all();
+        }
+        Ok(())
+    }
+
+    /// Read the content of `path`, verifying its checksum
+    pub fn read<T: StateCodec>(&self, path: &Path) -> Result<T, SqlError> {
+        let bytes = fs::read(path).map_err(|e| self.io_error(path, "read", e))?;
+        if bytes.len() < FILE_OVERHEAD || &bytes[..4] != self.magic {
+            return Err(self.corrupt(path, format!("not a {} file", self.kind)));
+        }
+        if bytes[4] != FORMAT_VERSION {
+            return Err(self.corrupt(path, format!("unsupported format version {}", bytes[4])));
+        }
+        let (content, sum) = bytes.split_at(bytes.len() - 8);
+        if checksum(content).to_be_bytes() != sum {
+            return Err(self.corrupt(path, "checksum mismatch"));
+        }
+        T::from_state_bytes(&content[5..]).map_err(|e| self.corrupt(path, e.to_string()))
+    }
+}
+
+/// Checkpoint files of one job in a directory
+#[derive(Debug, Clone)]
+pub struct CheckpointStore {
+    directory: PathBuf,
+}
+
+impl CheckpointStore {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+        }
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    fn path(&self, id: u64) -> PathBuf {
+        self.directory
+            .join(format!("{}{:020}{}", FILE_PREFIX, id, FILE_SUFFIX))
+    }
+
+    /// IDs of the stored checkpoints in ascending order
+    pub fn ids(&self) -> Result<Vec<u64>, SqlError> {
+        let entries = match fs::read_dir(&self.directory) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(CHECKPOINT_FILE.io_error(&self.directory, "list", e)),
+        };
+        let mut ids: Vec<u64> = entries
+            .filter_map(|entry| {
+                let name = entry.ok()?.file_name().into_string().ok()?;
+                name.strip_prefix(FILE_PREFIX)?
+                    .strip_suffix(FILE_SUFFIX)?
+                    .parse()
+                    .ok()
+            })
+            .collect();
+        ids.sort_unstable();
+        Ok(ids)
+    }
+
+    /// Write a checkpoint; it replaces nothing until it is complete on disk
+    pub fn write(&self, checkpoint: &Checkpoint) -> Result<PathBuf, SqlError> {
+        let path = self.path(checkpoint.id);
+        CHECKPOINT_FILE.write(&path, checkpoint)?;
+        Ok(path)
+    }
+
+    /// Read one checkpoint, verifying its checksum
+    pub fn read(&self, id: u64) -> Result<Checkpoint, SqlError> {
+        CHECKPOINT_FILE.read(&self.path(id))
+    }
+
+    /// The newest checkpoint that can be read, skipping damaged ones
+    pub fn latest(&self) -> Result<Option<Checkpoint>, SqlError> {
+        for id in self.ids()?.into_iter().rev() {
+            match self.read(id) {
+                Ok(checkpoint) => return Ok(Some(checkpoint)),
+                Err(e) => log::warn!("Skipping checkpoint {}: {}", id, e),
+            }
+        }
+        Ok(None)
+    }
+
+    /// Delete all but the newest `retained` checkpoints and leftover temporary files
+    pub fn prune(&self, retained: usize) -> Result<(), SqlError> {
+        let ids = self.ids()?;
+        for id in &ids[..ids.len().saturating_sub(retained)] {
+            let path = self.path(*id);
+            fs::remove_file(&path).map_err(|e| CHECKPOINT_FILE.io_error(&path, "delete", e))?;
+        }
+        for entry in fs::read_dir(&self.directory)
+            .map_err(|e| CHECKPOINT_FILE.io_error(&self.directory, "list", e))?
+            .flatten()
+        {
+            if entry.file_name().to_string_lossy().ends_with(TEMP_SUFFIX) {
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Seek the sources of a job to the positions recorded in a checkpoint or savepoint
+///
+/// A source that cannot seek keeps reading from where it starts, which is logged.
+pub async fn seek_sources(context: &mut ProcessorContext, offsets: HashMap<String, SourceOffset>) {
+    for (source, offset) in offsets {
+        if !context.data_readers.contains_key(&source) {
+            log::warn!("Checkpointed source '{}' is no longer read", source);
+            continue;
+        }
+        if let Err(e) = context.seek_source(&source, offset).await {
+            log::warn!(
+                "Source '{}' cannot resume from its checkpointed position: {}",
+                source,
+                e
+            );
+        }
+    }
+}
+
+#[derive(Debug)]
+struct CheckpointProgress {
+    next_id: u64,
+    last_checkpoint: Instant,
+    /// Source positions of the restored checkpoint, until the sources seek to them
+    pending_offsets: HashMap<String, SourceOffset>,
+}
+
+/// Takes the periodic checkpoints of a running job and restores it from the latest one
+#[derive(Debug)]
+pub struct Checkpointer {
+    config: CheckpointConfig,
+    store: CheckpointStore,
+    progress: Mutex<CheckpointProgress>,
+}
+
+impl Checkpointer {
+    /// Checkpointer of a job, continuing the numbering of its stored checkpoints
+    pub fn new(config: CheckpointConfig, job: &str) -> Result<Self, SqlError> {
+        let store = config.store(job);
+        let next_id = store.ids()?.last().map_or(1, |id| id + 1);
+        Ok(Self {
+            config,
+            store,
+            progress: Mutex::new(CheckpointProgress {
+                next_id,
+                last_checkpoint: Instant::now(),
+                pending_offsets: HashMap::new(),
+            }),
+        })
+    }
+
+    pub fn config(&self) -> &CheckpointConfig {
+        &self.config
+    }
+
+    pub fn store(&self) -> &CheckpointStore {
+        &self.store
+    }
+
+    fn progress(&self) -> std::sync::MutexGuard<'_, CheckpointProgress> {
+        self.progress.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Restore the engine from the latest checkpoint and keep its source positions for
+    /// [`resume_sources`](Self::resume_sources); returns the ID of the restored checkpoint
+    pub fn restore(&self, engine: &mut StreamExecutionEngine) -> Result<Option<u64>, SqlError> {
+        let Some(checkpoint) = self.store.latest()? else {
+            return Ok(None);
+        };
+        engine.restore_operator_state(&checkpoint.operators)?;
+        let mut progress = self.progress();
+        progress.next_id = progress.next_id.max(checkpoint.id + 1);
+        progress.pending_offsets = checkpoint.source_offsets;
+        Ok(Some(checkpoint.id))
+    }
+
+    /// Seek the sources of a job back to the positions of the restored checkpoint
+    ///
+    /// A source that cannot seek keeps reading from where it starts, which is logged.
+    pub async fn resume_sources(&self, context: &mut ProcessorContext) {
+        let offsets = std::mem::take(&mut self.progress().pending_offsets);
+        seek_sources(context, offsets).await;
+    }
+
+    /// Whether the checkpoint interval has passed since the last checkpoint
+    pub fn is_due(&self) -> bool {
+        self.progress().last_checkpoint.elapsed() >= self.config.interval
+    }
+
+    /// Write a checkpoint of the engine's operators and the positions of the sources,
+    /// deleting checkpoints beyond the retained number; returns its ID
+    ///
+    /// Call between batches, once every record read so far has been processed.
+    pub fn checkpoint(
+        &self,
+        engine: &StreamExecutionEngine,
+        source_positions: &HashMap<String, SourceOffset>,
+    ) -> Result<u64, SqlError> {
+        let mut progress = self.progress();
+        let checkpoint = Checkpoint {
+            id: progress.next_id,
+            created_at: chrono::Utc::now().timestamp_millis(),
+            operators: engine.snapshot_operator_state()?,
+            source_offsets: source_positions.clone(),
+        };
+        self.store.write(&checkpoint)?;
+        progress.next_id += 1;
+        progress.last_checkpoint = Instant::now();
+        drop(progress);
+
+        self.store.prune(self.config.retained)?;
+        Ok(checkpoint.id)
+    }
+}
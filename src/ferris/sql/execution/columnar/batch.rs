@@ -0,0 +1,338 @@
+//! Columnar record batches.
+
+use super::bitmap::Bitmap;
+use crate::ferris::sql::execution::types::{FieldValue, StreamRecord};
+use std::borrow::{Borrow, Cow};
+use std::collections::HashMap;
+
+/// One column of a batch: a typed vector of values with a validity bitmap
+///
+/// Rows whose field is missing or NULL are invalid; the value stored for them is a
+/// placeholder that kernels never read.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Column {
+    Integer {
+        values: Vec<i64>,
+        validity: Bitmap,
+    },
+    Float {
+        values: Vec<f64>,
+        validity: Bitmap,
+    },
+    Boolean {
+        values: Bitmap,
+        validity: Bitmap,
+    },
+    String {
+        values: Vec<String>,
+        validity: Bitmap,
+    },
+    /// Columns mixing types, holding only NULLs, or of a type without a typed
+    /// representation; kernels leave these to the row path
+    Values(Vec<FieldValue>),
+}
+
+impl Column {
+    /// Build a column from one optional value per row
+    pub fn from_values(values: &[Option<&FieldValue>]) -> Self {
+        let validity = Bitmap::from_bools(
+            values
+                .iter()
+                .map(|value| !matches!(value, None | Some(FieldValue::Null))),
+        );
+        let mut present = values
+            .iter()
+            .flatten()
+            .filter(|value| !matches!(value, FieldValue::Null));
+        let Some(first) = present.next() else {
+            return Self::generic(values);
+        };
+        if !present.all(|value| std::mem::discriminant(*value) == std::mem::discriminant(first)) {
+            return Self::generic(values);
+        }
+
+        match first {
+            FieldValue::Integer(_) => Self::Integer {
+                values: values
+                    .iter()
+                    .map(|value| match value {
+                        Some(FieldValue::Integer(value)) => *value,
+                        _ => 0,
+                    })
+                    .collect(),
+                validity,
+            },
+            FieldValue::Float(_) => Self::Float {
+                values: values
+                    .iter()
+                    .map(|value| match value {
+                        Some(FieldValue::Float(value)) => *value,
+                        _ => 0.0,
+                    })
+                    .collect(),
+                validity,
+            },
+            FieldValue::Boolean(_) => Self::Boolean {
+                values: Bitmap::from_bools(
+                    values
+                        .iter()
+                        .map(|value| matches!(value, Some(FieldValue::Boolean(true)))),
+                ),
+                validity,
+            },
+            FieldValue::String(_) => Self::String {
+                values: values
+                    .iter()
+                    .map(|value| match value {
+                        Some(FieldValue::String(value)) => value.clone(),
+                        _ => String::new(),
+                    })
+                    .collect(),
+                validity,
+            },
+            _ => Self::generic(values),
+        }
+    }
+
+    /// A boolean column without NULLs
+    pub fn from_bitmap(values: Bitmap) -> Self {
+        Self::Boolean {
+            validity: Bitmap::new(values.len(), true),
+            values,
+        }
+    }
+
+    fn generic(values: &[Option<&FieldValue>]) -> Self {
+        Self::Values(
+            values
+                .iter()
+                .map(|value| value.cloned().unwrap_or(FieldValue::Null))
+                .collect(),
+        )
+    }
+
+    /// Number of rows
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Integer { values, .. } => values.len(),
+            Self::Float { values, .. } => values.len(),
+            Self::Boolean { values, .. } => values.len(),
+            Self::String { values, .. } => values.len(),
+            Self::Values(values) => values.len(),
+        }
+    }
+
+    /// Whether the column has no rows
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The validity bitmap, or `None` for columns without a typed representation
+    pub fn validity(&self) -> Option<&Bitmap> {
+        match self {
+            Self::Integer { validity, .. }
+            | Self::Float { validity, .. }
+            | Self::Boolean { validity, .. }
+            | Self::String { validity, .. } => Some(validity),
+            Self::Values(_) => None,
+        }
+    }
+
+    /// Whether row `index` holds a non-NULL value
+    pub fn is_valid(&self, index: usize) -> bool {
+        match self {
+            Self::Values(values) => !matches!(values[index], FieldValue::Null),
+            _ => self.validity().is_some_and(|validity| validity.get(index)),
+        }
+    }
+
+    /// The value of row `index`
+    pub fn value(&self, index: usize) -> FieldValue {
+        if !self.is_valid(index) {
+            return FieldValue::Null;
+        }
+        match self {
+            Self::Integer { values, .. } => FieldValue::Integer(values[index]),
+            Self::Float { values, .. } => FieldValue::Float(values[index]),
+            Self::Boolean { values, .. } => FieldValue::Boolean(values.get(index)),
+            Self::String { values, .. } => FieldValue::String(values[index].clone()),
+            Self::Values(values) => values[index].clone(),
+        }
+    }
+
+    /// Keep the rows whose bit is set in `selection`
+    pub fn filter(&self, selection: &Bitmap) -> Self {
+        fn take<T: Clone>(values: &[T], selection: &Bitmap) -> Vec<T> {
+            selection
+                .iter_set()
+                .map(|row| values[row].clone())
+                .collect()
+        }
+        fn take_bits(bits: &Bitmap, selection: &Bitmap) -> Bitmap {
+            Bitmap::from_bools(selection.iter_set().map(|row| bits.get(row)))
+        }
+
+        match self {
+            Self::Integer { values, validity } => Self::Integer {
+                values: take(values, selection),
+                validity: take_bits(validity, selection),
+            },
+            Self::Float { values, validity } => Self::Float {
+                values: take(values, selection),
+                validity: take_bits(validity, selection),
+            },
+            Self::Boolean { values, validity } => Self::Boolean {
+                values: take_bits(values, selection),
+                validity: take_bits(validity, selection),
+            },
+            Self::String { values, validity } => Self::String {
+                values: take(values, selection),
+                validity: take_bits(validity, selection),
+            },
+            Self::Values(values) => Self::Values(take(values, selection)),
+        }
+    }
+}
+
+/// Records laid out column by column
+///
+/// A batch holds the named field columns it was built with plus the `_timestamp`,
+/// `_offset` and `_partition` system columns.
+#[derive(Debug, Clone)]
+pub struct ColumnarBatch {
+    len: usize,
+    columns: HashMap<String, Column>,
+    timestamps: Column,
+    offsets: Column,
+    partitions: Column,
+}
+
+impl ColumnarBatch {
+    /// Build a batch holding the named columns of `records`
+    ///
+    /// System column names and dotted names are skipped; use [`resolve`](Self::resolve) for
+    /// the former, the latter stay on the row path.
+    pub fn from_records<R: Borrow<StreamRecord>>(records: &[R], columns: &[&str]) -> Self {
+        let mut batch = Self {
+            len: records.len(),
+            columns: HashMap::new(),
+            timestamps: Self::system_column(records, |record| record.timestamp),
+            offsets: Self::system_column(records, |record| record.offset),
+            partitions: Self::system_column(records, |record| record.partition as i64),
+        };
+        let mut values = Vec::with_capacity(records.len());
+        for &name in columns {
+            if name.contains('.') || Self::is_system_column(name) {
+                continue;
+            }
+            if batch.columns.contains_key(name) {
+                continue;
+            }
+            values.clear();
+            values.extend(
+                records
+                    .iter()
+                    .map(|record| record.borrow().fields.get(name)),
+            );
+            batch
+                .columns
+                .insert(name.to_string(), Column::from_values(&values));
+        }
+        batch
+    }
+
+    fn system_column<R: Borrow<StreamRecord>>(
+        records: &[R],
+        value: impl Fn(&StreamRecord) -> i64,
+    ) -> Column {
+        Column::Integer {
+            values: records
+                .iter()
+                .map(|record| value(record.borrow()))
+                .collect(),
+            validity: Bitmap::new(records.len(), true),
+        }
+    }
+
+    fn is_system_column(name: &str) -> bool {
+        ["_TIMESTAMP", "_OFFSET", "_PARTITION"]
+            .iter()
+            .any(|system| name.eq_ignore_ascii_case(system))
+    }
+
+    /// Number of rows
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the batch has no rows
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// A field column the batch was built with
+    pub fn column(&self, name: &str) -> Option<&Column> {
+        self.columns.get(name)
+    }
+
+    /// A field column or, matched case-insensitively, a system column
+    pub fn resolve(&self, name: &str) -> Option<&Column> {
+        if name.eq_ignore_ascii_case("_TIMESTAMP") {
+            Some(&self.timestamps)
+        } else if name.eq_ignore_ascii_case("_OFFSET") {
+            Some(&self.offsets)
+        } else if name.eq_ignore_ascii_case("_PARTITION") {
+            Some(&self.partitions)
+        } else {
+            self.column(name)
+        }
+    }
+
+    /// The `_timestamp` system column
+    pub fn timestamps(&self) -> &Column {
+        &self.timestamps
+    }
+
+    /// The `_offset` system column
+    pub fn offsets(&self) -> &Column {
+        &self.offsets
+    }
+
+    /// The `_partition` system column
+    pub fn partitions(&self) -> &Column {
+        &self.partitions
+    }
+
+    /// Keep the rows whose bit is set in `selection`
+    pub fn filter(&self, selection: &Bitmap) -> Self {
+        Self {
+            len: selection.count_set(),
+            columns: self
+                .columns
+                .iter()
+                .map(|(name, column)| (name.clone(), column.filter(selection)))
+                .collect(),
+            timestamps: self.timestamps.filter(selection),
+            offsets: self.offsets.filter(selection),
+            partitions: self.partitions.filter(selection),
+        }
+    }
+}
+
+/// The result of evaluating an expression over a batch: a column, or one value for
+/// every row
+#[derive(Debug, Clone)]
+pub enum Datum<'a> {
+    Column(Cow<'a, Column>),
+    Scalar(Cow<'a, FieldValue>),
+}
+
+impl Datum<'_> {
+    /// The value of row `index`
+    pub fn value(&self, index: usize) -> FieldValue {
+        match self {
+            Datum::Column(column) => column.value(index),
+            Datum::Scalar(value) => value.as_ref().clone(),
+        }
+    }
+}
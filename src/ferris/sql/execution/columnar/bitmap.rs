@@ -0,0 +1,141 @@
+//! Packed bitmaps for validity and selection vectors.
+
+/// A fixed-length sequence of bits packed into 64-bit words
+///
+/// Columns use bitmaps to mark which rows hold a value (validity) and boolean columns use
+/// them for the values themselves; filters produce them as selection vectors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bitmap {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl Bitmap {
+    /// Create a bitmap of `len` bits, all set to `value`
+    pub fn new(len: usize, value: bool) -> Self {
+        let fill = if value { u64::MAX } else { 0 };
+        let mut bitmap = Self {
+            words: vec![fill; (len + 63) / 64],
+            len,
+        };
+        bitmap.clear_trailing_bits();
+        bitmap
+    }
+
+    /// Create a bitmap from one boolean per bit
+    pub fn from_bools(bits: impl IntoIterator<Item = bool>) -> Self {
+        let mut words = Vec::new();
+        let mut len = 0;
+        for bit in bits {
+            if len % 64 == 0 {
+                words.push(0);
+            }
+            if bit {
+                words[len / 64] |= 1 << (len % 64);
+            }
+            len += 1;
+        }
+        Self { words, len }
+    }
+
+    /// Number of bits
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the bitmap has no bits
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Whether bit `index` is set
+    #[inline]
+    pub fn get(&self, index: usize) -> bool {
+        debug_assert!(index < self.len);
+        self.words[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    /// Set bit `index` to `value`
+    #[inline]
+    pub fn set(&mut self, index: usize, value: bool) {
+        debug_assert!(index < self.len);
+        if value {
+            self.words[index / 64] |= 1 << (index % 64);
+        } else {
+            self.words[index / 64] &= !(1 << (index % 64));
+        }
+    }
+
+    /// Whether every bit is set
+    pub fn all_set(&self) -> bool {
+        self.count_set() == self.len
+    }
+
+    /// Number of set bits
+    pub fn count_set(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Bitwise AND of two bitmaps of the same length
+    pub fn and(&self, other: &Bitmap) -> Bitmap {
+        self.zip_words(other, |a, b| a & b)
+    }
+
+    /// Bitwise OR of two bitmaps of the same length
+    pub fn or(&self, other: &Bitmap) -> Bitmap {
+        self.zip_words(other, |a, b| a | b)
+    }
+
+    /// Bitwise NOT
+    pub fn not(&self) -> Bitmap {
+        let mut bitmap = Self {
+            words: self.words.iter().map(|word| !word).collect(),
+            len: self.len,
+        };
+        bitmap.clear_trailing_bits();
+        bitmap
+    }
+
+    /// Indices of the set bits, in ascending order
+    pub fn iter_set(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words
+            .iter()
+            .enumerate()
+            .flat_map(|(word_index, &word)| {
+                let mut remaining = word;
+                std::iter::from_fn(move || {
+                    if remaining == 0 {
+                        return None;
+                    }
+                    let bit = remaining.trailing_zeros() as usize;
+                    remaining &= remaining - 1;
+                    Some(word_index * 64 + bit)
+                })
+            })
+    }
+
+    fn zip_words(&self, other: &Bitmap, op: impl Fn(u64, u64) -> u64) -> Bitmap {
+        debug_assert_eq!(self.len, other.len);
+        Self {
+            words: self
+                .words
+                .iter()
+                .zip(&other.words)
+                .map(|(&a, &b)| op(a, b))
+                .collect(),
+            len: self.len,
+        }
+    }
+
+    /// Keep the bits past `len` clear so counts and NOT stay exact
+    fn clear_trailing_bits(&mut self) {
+        if self.len % 64 != 0 {
+            if let Some(last) = self.words.last_mut() {
+                *last &= (1 << (self.len % 64)) - 1;
+            }
+        }
+    }
+}
@@ -0,0 +1,484 @@
+//! Vectorized kernels over columnar batches.
+//!
+//! Each kernel mirrors the row evaluator's semantics for the operation it implements:
+//! comparisons follow `ExpressionEvaluator::compare_values` and `values_equal`, arithmetic
+//! follows `FieldValue::add` and friends, and NULL handling is the same as for a single
+//! record. A kernel returns `None` where a row would raise an error, or where an operand
+//! has no typed representation, so callers can fall back to evaluating the rows one by one.
+
+use super::batch::{Column, Datum};
+use super::bitmap::Bitmap;
+use crate::ferris::sql::ast::BinaryOperator;
+use crate::ferris::sql::execution::types::FieldValue;
+use regex::Regex;
+use std::cmp::Ordering;
+
+/// One row's value, borrowed from a typed column or a scalar
+#[derive(Debug, Clone, Copy)]
+enum Cell<'a> {
+    Null,
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+    String(&'a str),
+}
+
+impl<'a> Cell<'a> {
+    fn from_value(value: &'a FieldValue) -> Option<Self> {
+        match value {
+            FieldValue::Null => Some(Cell::Null),
+            FieldValue::Integer(value) => Some(Cell::Integer(*value)),
+            FieldValue::Float(value) => Some(Cell::Float(*value)),
+            FieldValue::Boolean(value) => Some(Cell::Boolean(*value)),
+            FieldValue::String(value) => Some(Cell::String(value)),
+            _ => None,
+        }
+    }
+
+    fn as_f64(self) -> Option<f64> {
+        match self {
+            Cell::Integer(value) => Some(value as f64),
+            Cell::Float(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn is_truthy(self) -> bool {
+        match self {
+            Cell::Null => false,
+            Cell::Integer(value) => value != 0,
+            Cell::Float(value) => value != 0.0,
+            Cell::Boolean(value) => value,
+            Cell::String(value) => !value.is_empty(),
+        }
+    }
+}
+
+/// Row access to a datum with a typed representation
+enum Cells<'a> {
+    Scalar(Cell<'a>),
+    Column(&'a Column),
+}
+
+impl<'a> Cells<'a> {
+    fn new(datum: &'a Datum<'_>) -> Option<Self> {
+        match datum {
+            Datum::Scalar(value) => Cell::from_value(value).map(Cells::Scalar),
+            Datum::Column(column) => match column.as_ref() {
+                Column::Values(_) => None,
+                column => Some(Cells::Column(column)),
+            },
+        }
+    }
+
+    #[inline]
+    fn get(&self, row: usize) -> Cell<'a> {
+        match self {
+            Cells::Scalar(cell) => *cell,
+            Cells::Column(column) => {
+                if !column.is_valid(row) {
+                    return Cell::Null;
+                }
+                match column {
+                    Column::Integer { values, .. } => Cell::Integer(values[row]),
+                    Column::Float { values, .. } => Cell::Float(values[row]),
+                    Column::Boolean { values, .. } => Cell::Boolean(values.get(row)),
+                    Column::String { values, .. } => Cell::String(&values[row]),
+                    Column::Values(_) => Cell::Null,
+                }
+            }
+        }
+    }
+
+    /// Whether every non-NULL row holds an integer
+    fn is_integer(&self) -> bool {
+        matches!(
+            self,
+            Cells::Scalar(Cell::Integer(_) | Cell::Null) | Cells::Column(Column::Integer { .. })
+        )
+    }
+
+    /// Whether every non-NULL row holds an integer or a float
+    fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            Cells::Scalar(Cell::Integer(_) | Cell::Float(_) | Cell::Null)
+                | Cells::Column(Column::Integer { .. } | Column::Float { .. })
+        )
+    }
+}
+
+fn cells_equal(left: Cell, right: Cell) -> bool {
+    match (left, right) {
+        (Cell::Null, Cell::Null) => true,
+        (Cell::Null, _) | (_, Cell::Null) => false,
+        (Cell::String(a), Cell::String(b)) => a == b,
+        (Cell::Integer(a), Cell::Integer(b)) => a == b,
+        (Cell::Float(a), Cell::Float(b)) => (a - b).abs() < f64::EPSILON,
+        (Cell::Boolean(a), Cell::Boolean(b)) => a == b,
+        (Cell::Integer(a), Cell::Float(b)) => (a as f64 - b).abs() < f64::EPSILON,
+        (Cell::Float(a), Cell::Integer(b)) => (a - b as f64).abs() < f64::EPSILON,
+        _ => false,
+    }
+}
+
+/// `Ok(None)` when either side is NULL, `Err` for types that cannot be compared
+fn compare_cells(left: Cell, right: Cell) -> Result<Option<Ordering>, ()> {
+    Ok(Some(match (left, right) {
+        (Cell::Null, _) | (_, Cell::Null) => return Ok(None),
+        (Cell::Integer(a), Cell::Integer(b)) => a.cmp(&b),
+        (Cell::String(a), Cell::String(b)) => a.cmp(b),
+        (left, right) => match (left.as_f64(), right.as_f64()) {
+            (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+            _ => return Err(()),
+        },
+    }))
+}
+
+/// The orderings an ordering comparison accepts, or `None` for other operators
+fn ordering_test(op: &BinaryOperator) -> Option<fn(Ordering) -> bool> {
+    Some(match op {
+        BinaryOperator::LessThan => |ordering| ordering == Ordering::Less,
+        BinaryOperator::LessThanOrEqual => |ordering| ordering != Ordering::Greater,
+        BinaryOperator::GreaterThan => |ordering| ordering == Ordering::Greater,
+        BinaryOperator::GreaterThanOrEqual => |ordering| ordering != Ordering::Less,
+        _ => return None,
+    })
+}
+
+/// The operator with its operands swapped: `a < b` is `b > a`
+fn flipped(op: &BinaryOperator) -> BinaryOperator {
+    match op {
+        BinaryOperator::LessThan => BinaryOperator::GreaterThan,
+        BinaryOperator::LessThanOrEqual => BinaryOperator::GreaterThanOrEqual,
+        BinaryOperator::GreaterThan => BinaryOperator::LessThan,
+        BinaryOperator::GreaterThanOrEqual => BinaryOperator::LessThanOrEqual,
+        other => other.clone(),
+    }
+}
+
+/// Comparison (`=`, `<>`, `<`, `<=`, `>`, `>=`) of two datums
+pub fn compare(op: &BinaryOperator, left: &Datum, right: &Datum, len: usize) -> Option<Bitmap> {
+    match (left, right) {
+        (Datum::Column(column), Datum::Scalar(scalar)) => {
+            if let Some(bits) = compare_column_scalar(op, column, scalar) {
+                return Some(bits);
+            }
+        }
+        (Datum::Scalar(scalar), Datum::Column(column)) => {
+            if let Some(bits) = compare_column_scalar(&flipped(op), column, scalar) {
+                return Some(bits);
+            }
+        }
+        _ => {}
+    }
+
+    let (left, right) = (Cells::new(left)?, Cells::new(right)?);
+    match op {
+        BinaryOperator::Equal => Some(Bitmap::from_bools(
+            (0..len).map(|row| cells_equal(left.get(row), right.get(row))),
+        )),
+        BinaryOperator::NotEqual => Some(Bitmap::from_bools(
+            (0..len).map(|row| !cells_equal(left.get(row), right.get(row))),
+        )),
+        _ => {
+            let accepts = ordering_test(op)?;
+            let mut bits = Bitmap::new(len, false);
+            for row in 0..len {
+                if let Some(ordering) = compare_cells(left.get(row), right.get(row)).ok()? {
+                    bits.set(row, accepts(ordering));
+                }
+            }
+            Some(bits)
+        }
+    }
+}
+
+/// Tight loops for a numeric column compared with a numeric literal, the common filter
+fn compare_column_scalar(
+    op: &BinaryOperator,
+    column: &Column,
+    scalar: &FieldValue,
+) -> Option<Bitmap> {
+    let valid = |validity: &Bitmap, row: usize| validity.get(row);
+    match (column, scalar) {
+        (Column::Integer { values, validity }, FieldValue::Integer(b)) => {
+            let b = *b;
+            Some(match op {
+                BinaryOperator::Equal => Bitmap::from_bools(
+                    values
+                        .iter()
+                        .enumerate()
+                        .map(|(row, &a)| valid(validity, row) && a == b),
+                ),
+                BinaryOperator::NotEqual => Bitmap::from_bools(
+                    values
+                        .iter()
+                        .enumerate()
+                        .map(|(row, &a)| !valid(validity, row) || a != b),
+                ),
+                op => {
+                    let accepts = ordering_test(op)?;
+                    Bitmap::from_bools(
+                        values
+                            .iter()
+                            .enumerate()
+                            .map(|(row, &a)| valid(validity, row) && accepts(a.cmp(&b))),
+                    )
+                }
+            })
+        }
+        (Column::Integer { values, validity }, FieldValue::Float(b)) => {
+            compare_floats(op, values.iter().map(|&a| a as f64), validity, *b)
+        }
+        (Column::Float { values, validity }, FieldValue::Float(b)) => {
+            compare_floats(op, values.iter().copied(), validity, *b)
+        }
+        (Column::Float { values, validity }, FieldValue::Integer(b)) => {
+            compare_floats(op, values.iter().copied(), validity, *b as f64)
+        }
+        _ => None,
+    }
+}
+
+fn compare_floats(
+    op: &BinaryOperator,
+    values: impl Iterator<Item = f64>,
+    validity: &Bitmap,
+    b: f64,
+) -> Option<Bitmap> {
+    let values = values.enumerate();
+    Some(match op {
+        BinaryOperator::Equal => Bitmap::from_bools(
+            values.map(|(row, a)| validity.get(row) && (a - b).abs() < f64::EPSILON),
+        ),
+        BinaryOperator::NotEqual => Bitmap::from_bools(
+            values.map(|(row, a)| !(validity.get(row) && (a - b).abs() < f64::EPSILON)),
+        ),
+        op => {
+            let accepts = ordering_test(op)?;
+            Bitmap::from_bools(values.map(|(row, a)| {
+                validity.get(row) && accepts(a.partial_cmp(&b).unwrap_or(Ordering::Equal))
+            }))
+        }
+    })
+}
+
+/// Arithmetic (`+`, `-`, `*`, `/`) on two numeric datums
+///
+/// Integer overflow and division by zero return `None`, leaving the error to the row path.
+pub fn arithmetic(op: &BinaryOperator, left: &Datum, right: &Datum, len: usize) -> Option<Column> {
+    let (left, right) = (Cells::new(left)?, Cells::new(right)?);
+    if !left.is_numeric() || !right.is_numeric() {
+        return None;
+    }
+    let mut validity = Bitmap::new(len, true);
+
+    if left.is_integer() && right.is_integer() && !matches!(op, BinaryOperator::Divide) {
+        let apply: fn(i64, i64) -> Option<i64> = match op {
+            BinaryOperator::Add => i64::checked_add,
+            BinaryOperator::Subtract => i64::checked_sub,
+            BinaryOperator::Multiply => i64::checked_mul,
+            _ => return None,
+        };
+        let mut values = Vec::with_capacity(len);
+        for row in 0..len {
+            match (left.get(row), right.get(row)) {
+                (Cell::Integer(a), Cell::Integer(b)) => values.push(apply(a, b)?),
+                _ => {
+                    validity.set(row, false);
+                    values.push(0);
+                }
+            }
+        }
+        return Some(Column::Integer { values, validity });
+    }
+
+    let apply: fn(f64, f64) -> f64 = match op {
+        BinaryOperator::Add => |a, b| a + b,
+        BinaryOperator::Subtract => |a, b| a - b,
+        BinaryOperator::Multiply => |a, b| a * b,
+        BinaryOperator::Divide => |a, b| a / b,
+        _ => return None,
+    };
+    let mut values = Vec::with_capacity(len);
+    for row in 0..len {
+        match (left.get(row).as_f64(), right.get(row).as_f64()) {
+            (Some(_), Some(b)) if b == 0.0 && matches!(op, BinaryOperator::Divide) => {
+                return None;
+            }
+            (Some(a), Some(b)) => values.push(apply(a, b)),
+            _ => {
+                validity.set(row, false);
+                values.push(0.0);
+            }
+        }
+    }
+    Some(Column::Float { values, validity })
+}
+
+/// The truth value of every row, NULL counting as false
+pub fn truthy(datum: &Datum, len: usize) -> Option<Bitmap> {
+    if let Datum::Column(column) = datum {
+        if let Column::Boolean { values, validity } = column.as_ref() {
+            return Some(values.and(validity));
+        }
+    }
+    let cells = Cells::new(datum)?;
+    Some(Bitmap::from_bools(
+        (0..len).map(|row| cells.get(row).is_truthy()),
+    ))
+}
+
+/// `IS NULL` for every row
+pub fn is_null(datum: &Datum, len: usize) -> Bitmap {
+    match datum {
+        Datum::Scalar(value) => Bitmap::new(len, matches!(value.as_ref(), FieldValue::Null)),
+        Datum::Column(column) => match column.validity() {
+            Some(validity) => validity.not(),
+            None => Bitmap::from_bools((0..len).map(|row| !column.is_valid(row))),
+        },
+    }
+}
+
+/// `BETWEEN`; rows with a NULL operand are false whether or not the test is negated
+pub fn between(
+    value: &Datum,
+    low: &Datum,
+    high: &Datum,
+    negated: bool,
+    len: usize,
+) -> Option<Bitmap> {
+    let (value, low, high) = (Cells::new(value)?, Cells::new(low)?, Cells::new(high)?);
+    let mut bits = Bitmap::new(len, false);
+    for row in 0..len {
+        let within = match (value.get(row), low.get(row), high.get(row)) {
+            (Cell::Null, _, _) | (_, Cell::Null, _) | (_, _, Cell::Null) => continue,
+            (Cell::Integer(e), Cell::Integer(l), Cell::Integer(h)) => e >= l && e <= h,
+            (Cell::String(e), Cell::String(l), Cell::String(h)) => e >= l && e <= h,
+            (e, l, h) => {
+                let (e, l, h) = (e.as_f64()?, l.as_f64()?, h.as_f64()?);
+                e >= l && e <= h
+            }
+        };
+        bits.set(row, within != negated);
+    }
+    Some(bits)
+}
+
+/// `IN` against a list of literal values
+///
+/// `null_is_false` makes a NULL left side false for both `IN` and `NOT IN`, as predicates
+/// evaluated with subquery support do.
+pub fn in_list(
+    value: &Datum,
+    list: &[&FieldValue],
+    negated: bool,
+    null_is_false: bool,
+    len: usize,
+) -> Option<Bitmap> {
+    let value = Cells::new(value)?;
+    let list = list
+        .iter()
+        .map(|item| Cell::from_value(item))
+        .collect::<Option<Vec<_>>>()?;
+    Some(Bitmap::from_bools((0..len).map(|row| {
+        let cell = value.get(row);
+        if null_is_false && matches!(cell, Cell::Null) {
+            return false;
+        }
+        list.iter().any(|item| cells_equal(cell, *item)) != negated
+    })))
+}
+
+/// `LIKE` against a pattern compiled once; `None` stands for an invalid pattern, which
+/// matches nothing. NULL text is false for both `LIKE` and `NOT LIKE`.
+pub fn like(text: &Datum, pattern: Option<&Regex>, negated: bool, len: usize) -> Option<Bitmap> {
+    let text = Cells::new(text)?;
+    let mut bits = Bitmap::new(len, false);
+    for row in 0..len {
+        match text.get(row) {
+            Cell::String(text) => {
+                let matched = pattern.is_some_and(|pattern| pattern.is_match(text));
+                bits.set(row, matched != negated);
+            }
+            Cell::Null => {}
+            _ => return None,
+        }
+    }
+    Some(bits)
+}
+
+/// Number of non-NULL values
+pub fn count(column: &Column) -> usize {
+    match column.validity() {
+        Some(validity) => validity.count_set(),
+        None => (0..column.len())
+            .filter(|&row| column.is_valid(row))
+            .count(),
+    }
+}
+
+/// Sum and number of the numeric values, skipping NULLs
+///
+/// Values are added in row order as `f64`, as `SUM` and `AVG` do for single records.
+/// Typed non-numeric columns hold no numeric values; `None` for untyped columns.
+pub fn sum(column: &Column) -> Option<(f64, usize)> {
+    match column {
+        Column::Integer { values, validity } => {
+            Some(validity.iter_set().fold((0.0, 0), |(sum, count), row| {
+                (sum + values[row] as f64, count + 1)
+            }))
+        }
+        Column::Float { values, validity } => Some(
+            validity
+                .iter_set()
+                .fold((0.0, 0), |(sum, count), row| (sum + values[row], count + 1)),
+        ),
+        Column::Boolean { .. } | Column::String { .. } => Some((0.0, 0)),
+        Column::Values(_) => None,
+    }
+}
+
+/// Smallest non-NULL value of a numeric column, NULL if there is none
+pub fn min(column: &Column) -> Option<FieldValue> {
+    extremum(column, Ordering::Less)
+}
+
+/// Largest non-NULL value of a numeric column, NULL if there is none
+pub fn max(column: &Column) -> Option<FieldValue> {
+    extremum(column, Ordering::Greater)
+}
+
+/// Keeps the first value and replaces it with every later one ordered `wanted` from it
+fn extremum(column: &Column, wanted: Ordering) -> Option<FieldValue> {
+    match column {
+        Column::Integer { values, validity } => Some(
+            validity
+                .iter_set()
+                .map(|row| values[row])
+                .reduce(|best, value| {
+                    if value.cmp(&best) == wanted {
+                        value
+                    } else {
+                        best
+                    }
+                })
+                .map_or(FieldValue::Null, FieldValue::Integer),
+        ),
+        Column::Float { values, validity } => Some(
+            validity
+                .iter_set()
+                .map(|row| values[row])
+                .reduce(|best, value| {
+                    if value.partial_cmp(&best) == Some(wanted) {
+                        value
+                    } else {
+                        best
+                    }
+                })
+                .map_or(FieldValue::Null, FieldValue::Float),
+        ),
+        _ => None,
+    }
+}
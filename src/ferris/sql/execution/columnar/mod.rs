@@ -0,0 +1,26 @@
+//! Columnar batch execution.
+//!
+//! Readers hand the engine `Vec<StreamRecord>` batches. This module lays such a batch out
+//! column by column so filters, projections and aggregates can run as tight loops over
+//! typed vectors instead of one record at a time:
+//!
+//! - [`ColumnarBatch`] - the named columns of a batch plus its system columns
+//! - [`Column`] - a typed vector with a validity [`Bitmap`] marking NULL rows
+//! - [`kernels`] - vectorized comparisons, boolean logic, arithmetic and aggregates
+//! - [`ColumnarPlan`] - a stateless SELECT or a GROUP BY aggregation run over a whole batch
+//!
+//! Compiled expressions ([`CompiledExpression::evaluate_batch`]) drive the kernels. Every
+//! kernel mirrors the row evaluator and declines what it cannot reproduce exactly, so the
+//! row path stays the fallback: a batch whose columns mix types, or whose expressions use
+//! functions, CASE or subqueries, is processed record by record as before.
+//!
+//! [`CompiledExpression::evaluate_batch`]: super::expression::CompiledExpression::evaluate_batch
+
+pub mod batch;
+pub mod bitmap;
+pub mod kernels;
+pub mod plan;
+
+pub use batch::{Column, ColumnarBatch, Datum};
+pub use bitmap::Bitmap;
+pub use plan::ColumnarPlan;
@@ -0,0 +1,527 @@
+//! Columnar execution of stateless SELECT queries and GROUP BY aggregations.
+
+use super::batch::ColumnarBatch;
+use super::bitmap::Bitmap;
+use super::kernels;
+use crate::ferris::sql::ast::{
+    EmitMode, Expr, LiteralValue, SelectField, StreamSource, StreamingQuery,
+};
+use crate::ferris::sql::execution::aggregation::GroupByStateManager;
+use crate::ferris::sql::execution::expression::{CompiledExpression, CompiledQuery, OutputLayout};
+use crate::ferris::sql::execution::internal::{GroupAccumulator, GroupByState};
+use crate::ferris::sql::execution::processors::SelectProcessor;
+use crate::ferris::sql::execution::types::{FieldValue, StreamRecord};
+use crate::ferris::sql::execution::RecordFields;
+use std::collections::HashMap;
+
+/// A SELECT prepared for columnar execution
+///
+/// Queries that treat every record on its own qualify, with no window, JOIN, HAVING or
+/// LIMIT and no subqueries: the WHERE clause and the SELECT expressions run as vectorized
+/// kernels, and the output records are the ones the row path would emit.
+///
+/// So do GROUP BY queries over columns whose SELECT takes COUNT, SUM, MIN and MAX of
+/// columns, emitting changes; see [`aggregate`](Self::aggregate).
+#[derive(Debug, Clone)]
+pub struct ColumnarPlan {
+    filter: Option<CompiledExpression>,
+    fields: Vec<PlannedField>,
+    /// Schema of the output records, unless a wildcard copies the input's fields
+    output: Option<OutputLayout>,
+    columns: Vec<String>,
+    aggregation: Option<PlannedAggregation>,
+}
+
+#[derive(Debug, Clone)]
+enum PlannedField {
+    Wildcard,
+    /// Copied from the record, so fields missing from it stay missing
+    Column {
+        column: String,
+        name: String,
+    },
+    Expression {
+        name: String,
+        expr: CompiledExpression,
+    },
+}
+
+/// The GROUP BY of an aggregating plan
+#[derive(Debug, Clone)]
+struct PlannedAggregation {
+    source: StreamSource,
+    group_by: Vec<Expr>,
+    /// The grouping columns, one per GROUP BY expression
+    keys: Vec<String>,
+    fields: Vec<SelectField>,
+    having: Option<Expr>,
+    aggregates: Vec<PlannedAggregate>,
+    /// The argument columns of the aggregates
+    columns: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+struct PlannedAggregate {
+    /// Accumulator entry of the aggregate, named as the row path names it
+    name: String,
+    function: AggregateFunction,
+    /// The argument column; `None` counts every row
+    column: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AggregateFunction {
+    Count,
+    Sum,
+    Min,
+    Max,
+}
+
+impl ColumnarPlan {
+    /// Plan a query, or return `None` if it needs the row path
+    ///
+    /// CREATE STREAM/TABLE statements are planned from their SELECT.
+    pub fn for_query(query: &StreamingQuery) -> Option<Self> {
+        CompiledQuery::new(query).columnar().cloned()
+    }
+
+    /// Plan a query from its compiled expressions
+    pub(crate) fn new(query: &StreamingQuery, compiled: &CompiledQuery) -> Option<Self> {
+        let StreamingQuery::Select {
+            fields,
+            from,
+            where_clause,
+            joins: None,
+            group_by,
+            having,
+            window: None,
+            limit: None,
+            emit_mode,
+            ..
+        } = Self::select(query)?
+        else {
+            return None;
+        };
+
+        let filter = match where_clause {
+            Some(_) => Some(compiled.where_clause()?.clone()),
+            None => None,
+        };
+        let aggregation = match group_by {
+            // EMIT FINAL without a window is an error of the row path
+            Some(_) if matches!(emit_mode, Some(EmitMode::Final)) => return None,
+            Some(group_by) => Some(PlannedAggregation::new(from, group_by, fields, having)?),
+            None if having.is_some() => return None,
+            None => None,
+        };
+        let fields = match &aggregation {
+            Some(_) => Vec::new(),
+            None => Self::plan_fields(fields, compiled)?,
+        };
+
+        let mut columns = Vec::new();
+        let expressions = filter
+            .iter()
+            .chain(fields.iter().filter_map(|field| match field {
+                PlannedField::Expression { expr, .. } => Some(expr),
+                _ => None,
+            }));
+        for expr in expressions {
+            for column in expr.columns() {
+                if !columns.iter().any(|known| known == column) {
+                    columns.push(column.to_string());
+                }
+            }
+        }
+
+        Some(Self {
+            filter,
+            fields,
+            output: compiled.output().cloned(),
+            columns,
+            aggregation,
+        })
+    }
+
+    fn plan_fields(fields: &[SelectField], compiled: &CompiledQuery) -> Option<Vec<PlannedField>> {
+        fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                Some(match field {
+                    SelectField::Wildcard => PlannedField::Wildcard,
+                    SelectField::Column(column) => PlannedField::Column {
+                        column: column.clone(),
+                        name: column.clone(),
+                    },
+                    SelectField::AliasedColumn { column, alias } => PlannedField::Column {
+                        column: column.clone(),
+                        name: alias.clone(),
+                    },
+                    SelectField::Expression { expr, alias } => PlannedField::Expression {
+                        name: alias
+                            .clone()
+                            .unwrap_or_else(|| SelectProcessor::get_expression_name(expr)),
+                        expr: compiled.field(index)?.clone(),
+                    },
+                })
+            })
+            .collect()
+    }
+
+    pub(crate) fn select(query: &StreamingQuery) -> Option<&StreamingQuery> {
+        match query {
+            StreamingQuery::Select { .. } => Some(query),
+            StreamingQuery::CreateStream { as_select, .. }
+            | StreamingQuery::CreateTable { as_select, .. }
+            | StreamingQuery::CreateStreamInto { as_select, .. }
+            | StreamingQuery::CreateTableInto { as_select, .. } => Self::select(as_select),
+            _ => None,
+        }
+    }
+
+    /// Run the plan over a batch of records, returning the output record of every input
+    /// record that passes the WHERE clause
+    ///
+    /// Returns `None` when a column type or a value needs the row path, for example a
+    /// comparison that fails for some record; nothing has been emitted in that case.
+    /// GROUP BY plans return `None` too, they run through [`aggregate`](Self::aggregate).
+    pub fn execute(&self, records: &[StreamRecord]) -> Option<Vec<StreamRecord>> {
+        if self.aggregation.is_some() {
+            return None;
+        }
+        let columns: Vec<&str> = self.columns.iter().map(String::as_str).collect();
+        let batch = ColumnarBatch::from_records(records, &columns);
+
+        let (selection, selected) = match &self.filter {
+            Some(filter) => {
+                let selection = filter.evaluate_batch_bool(&batch)?;
+                let selected = batch.filter(&selection);
+                (selection, selected)
+            }
+            None => (Bitmap::new(records.len(), true), batch),
+        };
+
+        let values = self
+            .fields
+            .iter()
+            .filter_map(|field| match field {
+                PlannedField::Expression { expr, .. } => Some(expr.evaluate_batch(&selected)),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let output = selection
+            .iter_set()
+            .enumerate()
+            .map(|(row, index)| {
+                let record = &records[index];
+                let mut fields = self
+                    .output
+                    .as_ref()
+                    .map_or_else(RecordFields::new, OutputLayout::record);
+                let mut values = values.iter();
+                for (position, field) in self.fields.iter().enumerate() {
+                    let (name, value) = match field {
+                        PlannedField::Wildcard if fields.is_empty() => {
+                            fields = record.fields.clone();
+                            continue;
+                        }
+                        PlannedField::Wildcard => {
+                            fields.extend(record.fields.clone());
+                            continue;
+                        }
+                        PlannedField::Column { column, name } => {
+                            match Self::column_value(record, column) {
+                                Some(value) => (name, value),
+                                None => continue,
+                            }
+                        }
+                        PlannedField::Expression { name, .. } => {
+                            let value = values.next().expect("one value per expression");
+                            (name, value.value(row))
+                        }
+                    };
+                    match &self.output {
+                        Some(layout) => layout.set(&mut fields, position, value),
+                        None => {
+                            fields.insert(name.clone(), value);
+                        }
+                    }
+                }
+                StreamRecord {
+                    fields,
+                    timestamp: record.timestamp,
+                    offset: record.offset,
+                    partition: record.partition,
+                    headers: record.headers.clone(),
+                }
+            })
+            .collect();
+        Some(output)
+    }
+
+    /// An empty GROUP BY state for the query of a GROUP BY plan
+    ///
+    /// The state has the namespace and GROUP BY expressions the row path gives the query,
+    /// so either path can carry on with groups the other one started.
+    pub fn group_state(&self) -> Option<GroupByState> {
+        let aggregation = self.aggregation.as_ref()?;
+        Some(GroupByState::new(
+            &aggregation.source,
+            aggregation.group_by.clone(),
+            aggregation.fields.clone(),
+            aggregation.having.clone(),
+        ))
+    }
+
+    /// Fold a batch of records into the groups of a GROUP BY plan, returning the changed
+    /// result of every group the batch updated that passes HAVING
+    ///
+    /// The aggregates of each group are computed over its records by the column kernels
+    /// and merged into its accumulator. A group emits one result per batch rather than
+    /// one per record, in the order of their last records; the result carries the
+    /// timestamp, offset and partition of that record. Float sums add up the values of a
+    /// batch before adding them to the group, so their last bits can differ from the row
+    /// path's.
+    ///
+    /// Returns `None` when the plan is not a GROUP BY or the batch needs the row path,
+    /// for example to report a SUM over text; `groups` is unchanged in that case.
+    pub fn aggregate(
+        &self,
+        records: &[StreamRecord],
+        groups: &mut HashMap<Vec<String>, GroupAccumulator>,
+    ) -> Option<Vec<StreamRecord>> {
+        let aggregation = self.aggregation.as_ref()?;
+        let selection = match &self.filter {
+            Some(filter) => {
+                let columns: Vec<&str> = self.columns.iter().map(String::as_str).collect();
+                filter.evaluate_batch_bool(&ColumnarBatch::from_records(records, &columns))?
+            }
+            None => Bitmap::new(records.len(), true),
+        };
+
+        // The rows of every group, in the order the groups first appear
+        let mut positions: HashMap<Vec<String>, usize> = HashMap::new();
+        let mut group_rows: Vec<(Vec<String>, Vec<usize>)> = Vec::new();
+        for index in selection.iter_set() {
+            let record = &records[index];
+            let key: Vec<String> = aggregation
+                .keys
+                .iter()
+                .map(|column| match record.fields.get(column) {
+                    Some(value) => GroupByStateManager::field_value_to_group_key(value),
+                    None => "NULL".to_string(),
+                })
+                .collect();
+            match positions.get(&key) {
+                Some(&position) => group_rows[position].1.push(index),
+                None => {
+                    positions.insert(key.clone(), group_rows.len());
+                    group_rows.push((key, vec![index]));
+                }
+            }
+        }
+
+        let columns: Vec<&str> = aggregation.columns.iter().map(String::as_str).collect();
+        let mut updated = Vec::with_capacity(group_rows.len());
+        let mut output = Vec::new();
+        for (key, rows) in group_rows {
+            let group: Vec<&StreamRecord> = rows.iter().map(|&row| &records[row]).collect();
+            let batch = ColumnarBatch::from_records(&group, &columns);
+            let mut accumulator = groups.get(&key).cloned().unwrap_or_default();
+            aggregation.accumulate(&mut accumulator, &group, &batch)?;
+
+            let last_row = *rows.last()?;
+            let last = &records[last_row];
+            let fields = SelectProcessor::group_result_fields(
+                &accumulator,
+                last,
+                &aggregation.group_by,
+                &aggregation.fields,
+            )
+            .ok()?;
+            let passes = match &aggregation.having {
+                Some(having) => SelectProcessor::evaluate_having_expression(
+                    having,
+                    &accumulator,
+                    &aggregation.fields,
+                )
+                .ok()?,
+                None => true,
+            };
+            if passes {
+                let record = StreamRecord {
+                    fields,
+                    timestamp: last.timestamp,
+                    offset: last.offset,
+                    partition: last.partition,
+                    headers: last.headers.clone(),
+                };
+                output.push((last_row, record));
+            }
+            updated.push((key, accumulator));
+        }
+
+        groups.extend(updated);
+        output.sort_by_key(|(last_row, _)| *last_row);
+        Some(output.into_iter().map(|(_, record)| record).collect())
+    }
+
+    fn column_value(record: &StreamRecord, column: &str) -> Option<FieldValue> {
+        match column.to_uppercase().as_str() {
+            "_TIMESTAMP" => Some(FieldValue::Integer(record.timestamp)),
+            "_OFFSET" => Some(FieldValue::Integer(record.offset)),
+            "_PARTITION" => Some(FieldValue::Integer(record.partition as i64)),
+            _ => record.fields.get(column).cloned(),
+        }
+    }
+}
+
+impl PlannedAggregation {
+    /// Plan a GROUP BY, or return `None` if it needs the row path
+    fn new(
+        source: &StreamSource,
+        group_by: &[Expr],
+        fields: &[SelectField],
+        having: &Option<Expr>,
+    ) -> Option<Self> {
+        let keys = group_by
+            .iter()
+            .map(|expr| match expr {
+                Expr::Column(column, _) => Some(column.clone()),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let mut aggregates = Vec::new();
+        for field in fields {
+            let SelectField::Expression { expr, alias } = field else {
+                continue;
+            };
+            let (name, args) = match expr {
+                Expr::Function { name, args, .. } => (name, args),
+                // Read from the latest record of the group, as the row path does
+                Expr::Column(_, _) => continue,
+                // Evaluated for every record by the row path, which reports their errors
+                _ => return None,
+            };
+            let function = match name.to_uppercase().as_str() {
+                "COUNT" => AggregateFunction::Count,
+                "SUM" => AggregateFunction::Sum,
+                "MIN" => AggregateFunction::Min,
+                "MAX" => AggregateFunction::Max,
+                _ => return None,
+            };
+            let column = match args.as_slice() {
+                // COUNT() reads the record count of the group
+                [] if function == AggregateFunction::Count => continue,
+                [Expr::Column(column, _)] => Some(column.clone()),
+                // COUNT(*) is parsed as COUNT(1)
+                [Expr::Literal(LiteralValue::Integer(_), _)]
+                    if function == AggregateFunction::Count =>
+                {
+                    None
+                }
+                _ => return None,
+            };
+            aggregates.push(PlannedAggregate {
+                name: alias
+                    .clone()
+                    .unwrap_or_else(|| SelectProcessor::get_expression_name(expr)),
+                function,
+                column,
+            });
+        }
+
+        let mut columns: Vec<String> = Vec::new();
+        for column in aggregates
+            .iter()
+            .filter_map(|aggregate| aggregate.column.as_ref())
+        {
+            if !columns.contains(column) {
+                columns.push(column.clone());
+            }
+        }
+
+        Some(Self {
+            source: source.clone(),
+            group_by: group_by.to_vec(),
+            keys,
+            fields: fields.to_vec(),
+            having: having.clone(),
+            aggregates,
+            columns,
+        })
+    }
+
+    /// Merge the records of a group, laid out as `batch`, into its accumulator as the row
+    /// path would record by record
+    fn accumulate(
+        &self,
+        accumulator: &mut GroupAccumulator,
+        records: &[&StreamRecord],
+        batch: &ColumnarBatch,
+    ) -> Option<()> {
+        // The row path counts every record twice, once per GROUP BY and once per aggregate
+        accumulator.count += 2 * records.len() as u64;
+        if accumulator.sample_record.is_none() {
+            accumulator.sample_record = Some(records[0].clone());
+        }
+        for column in &self.keys {
+            if !accumulator.first_values.contains_key(column) {
+                if let Some(value) = records.iter().find_map(|record| record.fields.get(column)) {
+                    accumulator
+                        .first_values
+                        .insert(column.clone(), value.clone());
+                }
+            }
+        }
+
+        for aggregate in &self.aggregates {
+            let name = aggregate.name.as_str();
+            let Some(column) = &aggregate.column else {
+                *accumulator
+                    .non_null_counts
+                    .entry(name.to_string())
+                    .or_insert(0) += records.len() as u64;
+                continue;
+            };
+            // System columns and dotted names are not in the batch, they need the row path
+            let column = batch.column(column)?;
+            let present = kernels::count(column);
+            match aggregate.function {
+                AggregateFunction::Count => {
+                    if present > 0 {
+                        *accumulator
+                            .non_null_counts
+                            .entry(name.to_string())
+                            .or_insert(0) += present as u64;
+                    }
+                }
+                AggregateFunction::Sum => match kernels::sum(column) {
+                    Some((sum, summed)) if summed > 0 => accumulator.add_sum(name, sum),
+                    _ if present == 0 => {}
+                    // Non-numeric values are an error of the row path
+                    _ => return None,
+                },
+                AggregateFunction::Min | AggregateFunction::Max => {
+                    let extremum = match aggregate.function {
+                        AggregateFunction::Min => kernels::min(column),
+                        _ => kernels::max(column),
+                    };
+                    match extremum {
+                        Some(FieldValue::Null) => {}
+                        Some(value) if aggregate.function == AggregateFunction::Min => {
+                            accumulator.update_min(name, value)
+                        }
+                        Some(value) => accumulator.update_max(name, value),
+                        None if present == 0 => {}
+                        None => return None,
+                    }
+                }
+            }
+        }
+        Some(())
+    }
+}
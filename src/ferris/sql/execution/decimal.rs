@@ -0,0 +1,352 @@
+//! Fixed-point DECIMAL(p, s) arithmetic.
+//!
+//! Exact decimals are stored as [`FieldValue::ScaledInteger`] (an `i64` unscaled value plus
+//! a scale). This module owns the rules that keep those values exact:
+//!
+//! - coercion to a declared `DECIMAL(p, s)` type, with a configurable [`RoundingMode`]
+//!   when digits are dropped and an overflow error when more than `p` digits are needed
+//! - SQL-standard result scales for arithmetic:
+//!
+//! | Operation | Result scale |
+//! |-----------|--------------|
+//! | `a + b`, `a - b` | `max(s1, s2)` |
+//! | `a * b` | `s1 + s2`, reduced (never below `max(s1, s2)`) only if the product would overflow |
+//! | `a / b` | `max(s1, s2, 6)` |
+//!
+//! Intermediate results are computed in `i128`, so arithmetic never silently wraps.
+
+use super::types::FieldValue;
+use crate::ferris::sql::ast::DecimalSpec;
+use crate::ferris::sql::error::SqlError;
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+/// Largest precision a `DECIMAL(p, s)` column can declare (bounded by the `i64` storage)
+pub const MAX_DECIMAL_PRECISION: u8 = 18;
+
+/// Minimum scale of a DECIMAL division result
+pub const MIN_DIVISION_SCALE: u8 = 6;
+
+/// How digits are dropped when a decimal is rescaled to fewer fractional digits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoundingMode {
+    /// Round half away from zero: 2.5 -> 3, -2.5 -> -3
+    #[default]
+    HalfUp,
+    /// Round half towards zero: 2.5 -> 2, -2.5 -> -2
+    HalfDown,
+    /// Round half to the nearest even digit (banker's rounding): 2.5 -> 2, 3.5 -> 4
+    HalfEven,
+    /// Round away from zero: 2.1 -> 3
+    Up,
+    /// Truncate towards zero: 2.9 -> 2
+    Down,
+    /// Round towards positive infinity: -2.9 -> -2
+    Ceiling,
+    /// Round towards negative infinity: 2.9 -> 2
+    Floor,
+}
+
+impl RoundingMode {
+    /// Parse a rounding mode name such as `HALF_EVEN` (case-insensitive)
+    pub fn parse(name: &str) -> Result<Self, SqlError> {
+        match name.trim().to_uppercase().as_str() {
+            "HALF_UP" => Ok(RoundingMode::HalfUp),
+            "HALF_DOWN" => Ok(RoundingMode::HalfDown),
+            "HALF_EVEN" => Ok(RoundingMode::HalfEven),
+            "UP" => Ok(RoundingMode::Up),
+            "DOWN" => Ok(RoundingMode::Down),
+            "CEILING" => Ok(RoundingMode::Ceiling),
+            "FLOOR" => Ok(RoundingMode::Floor),
+            _ => Err(SqlError::ExecutionError {
+                message: format!(
+                    "Unknown rounding mode '{}': expected HALF_UP, HALF_DOWN, HALF_EVEN, UP, DOWN, CEILING or FLOOR",
+                    name
+                ),
+                query: None,
+            }),
+        }
+    }
+}
+
+impl DecimalSpec {
+    /// Validate a declared `DECIMAL(precision, scale)`
+    pub fn new(precision: u32, scale: u32) -> Result<Self, String> {
+        if precision == 0 || precision > MAX_DECIMAL_PRECISION as u32 {
+            return Err(format!(
+                "DECIMAL precision must be between 1 and {}, got {}",
+                MAX_DECIMAL_PRECISION, precision
+            ));
+        }
+        if scale > precision {
+            return Err(format!(
+                "DECIMAL scale {} cannot exceed precision {}",
+                scale, precision
+            ));
+        }
+        Ok(DecimalSpec {
+            precision: precision as u8,
+            scale: scale as u8,
+        })
+    }
+
+    /// Parse a parameterised type name such as `DECIMAL(18,4)` or `NUMERIC(10)`
+    ///
+    /// Returns `Ok(None)` for anything that is not a parameterised DECIMAL/NUMERIC,
+    /// including the bare `DECIMAL` type name.
+    pub fn parse_type_name(type_name: &str) -> Result<Option<Self>, SqlError> {
+        let upper = type_name.trim().to_uppercase();
+        let Some(params) = upper
+            .strip_prefix("DECIMAL")
+            .or_else(|| upper.strip_prefix("NUMERIC"))
+            .map(str::trim_start)
+            .and_then(|rest| rest.strip_prefix('('))
+            .and_then(|rest| rest.strip_suffix(')'))
+        else {
+            return Ok(None);
+        };
+
+        let invalid = || SqlError::ExecutionError {
+            message: format!("Invalid DECIMAL type '{}'", type_name),
+            query: None,
+        };
+        let mut parts = params.split(',').map(|p| p.trim().parse::<u32>());
+        let precision = parts.next().ok_or_else(invalid)?.map_err(|_| invalid())?;
+        let scale = match parts.next() {
+            Some(scale) => scale.map_err(|_| invalid())?,
+            None => 0,
+        };
+        if parts.next().is_some() {
+            return Err(invalid());
+        }
+
+        DecimalSpec::new(precision, scale)
+            .map(Some)
+            .map_err(|message| SqlError::ExecutionError {
+                message,
+                query: None,
+            })
+    }
+
+    /// Coerce a value to this type: rescale to the declared scale, rounding with `mode`,
+    /// and reject values that need more digits than the declared precision
+    pub fn coerce(&self, value: FieldValue, mode: RoundingMode) -> Result<FieldValue, SqlError> {
+        let (unscaled, scale) = match exact_parts(&value, &self.to_string())? {
+            Some(parts) => parts,
+            None => return Ok(FieldValue::Null),
+        };
+
+        let overflow = || SqlError::ExecutionError {
+            message: format!("Value {} overflows {}", value.to_display_string(), self),
+            query: None,
+        };
+        let rescaled = rescale(unscaled, scale, self.scale, mode).ok_or_else(overflow)?;
+        let fits_precision = match 10_i128.checked_pow(self.precision as u32) {
+            Some(limit) => rescaled.abs() < limit,
+            None => true,
+        };
+        if !fits_precision {
+            return Err(overflow());
+        }
+        let unscaled = i64::try_from(rescaled).map_err(|_| overflow())?;
+        Ok(FieldValue::ScaledInteger(unscaled, self.scale))
+    }
+}
+
+impl std::fmt::Display for DecimalSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DECIMAL({},{})", self.precision, self.scale)
+    }
+}
+
+/// Split a numeric value into an exact unscaled value and scale
+///
+/// Returns `Ok(None)` for NULL.
+fn exact_parts(value: &FieldValue, target: &str) -> Result<Option<(i128, u8)>, SqlError> {
+    let from_decimal = |d: Decimal| (d.mantissa(), d.scale() as u8);
+    let cannot_cast = |shown: String| SqlError::ExecutionError {
+        message: format!("Cannot cast {} to {}", shown, target),
+        query: None,
+    };
+
+    let parts = match value {
+        FieldValue::Null => return Ok(None),
+        FieldValue::Integer(i) => (*i as i128, 0),
+        FieldValue::ScaledInteger(v, s) => (*v as i128, *s),
+        FieldValue::Decimal(d) => from_decimal(*d),
+        FieldValue::Boolean(b) => (*b as i128, 0),
+        FieldValue::Float(f) => {
+            if !f.is_finite() {
+                return Err(cannot_cast(format!("float {}", f)));
+            }
+            Decimal::from_str(&f.to_string())
+                .map(from_decimal)
+                .map_err(|_| cannot_cast(format!("float {}", f)))?
+        }
+        FieldValue::String(s) => Decimal::from_str(s.trim())
+            .map(from_decimal)
+            .map_err(|_| cannot_cast(format!("'{}'", s)))?,
+        other => return Err(cannot_cast(other.type_name().to_string())),
+    };
+    Ok(Some(parts))
+}
+
+/// Divide `numerator` by `divisor`, rounding the quotient with `mode`
+///
+/// `divisor` must be non-zero.
+pub fn div_round(numerator: i128, divisor: i128, mode: RoundingMode) -> i128 {
+    let (numerator, divisor) = if divisor < 0 {
+        (-numerator, -divisor)
+    } else {
+        (numerator, divisor)
+    };
+    let quotient = numerator / divisor;
+    let remainder = (numerator % divisor).abs();
+    if remainder == 0 {
+        return quotient;
+    }
+
+    // Compare the dropped remainder against half the divisor without overflowing
+    let half = divisor - remainder;
+    let round_away = match mode {
+        RoundingMode::HalfUp => remainder >= half,
+        RoundingMode::HalfDown => remainder > half,
+        RoundingMode::HalfEven => remainder > half || (remainder == half && quotient % 2 != 0),
+        RoundingMode::Up => true,
+        RoundingMode::Down => false,
+        RoundingMode::Ceiling => numerator > 0,
+        RoundingMode::Floor => numerator < 0,
+    };
+
+    if !round_away {
+        quotient
+    } else if numerator < 0 {
+        quotient - 1
+    } else {
+        quotient + 1
+    }
+}
+
+/// Rescale an unscaled value from `from_scale` to `to_scale`, rounding dropped digits
+///
+/// Returns `None` if the rescaled value does not fit in an `i128`.
+pub fn rescale(unscaled: i128, from_scale: u8, to_scale: u8, mode: RoundingMode) -> Option<i128> {
+    if to_scale >= from_scale {
+        10_i128
+            .checked_pow((to_scale - from_scale) as u32)?
+            .checked_mul(unscaled)
+    } else {
+        match 10_i128.checked_pow((from_scale - to_scale) as u32) {
+            Some(divisor) => Some(div_round(unscaled, divisor, mode)),
+            // The value is far smaller than one unit of the target scale
+            None => Some(div_round(unscaled.signum(), i128::MAX, mode)),
+        }
+    }
+}
+
+fn overflow_error(operation: &str) -> SqlError {
+    SqlError::ExecutionError {
+        message: format!("DECIMAL overflow in {}", operation),
+        query: None,
+    }
+}
+
+fn division_by_zero() -> SqlError {
+    SqlError::ExecutionError {
+        message: "Division by zero".to_string(),
+        query: None,
+    }
+}
+
+fn to_scaled(value: i128, scale: u8, operation: &str) -> Result<FieldValue, SqlError> {
+    i64::try_from(value)
+        .map(|v| FieldValue::ScaledInteger(v, scale))
+        .map_err(|_| overflow_error(operation))
+}
+
+/// `a + b` at scale `max(s1, s2)`
+pub fn add(a: i64, scale_a: u8, b: i64, scale_b: u8) -> Result<FieldValue, SqlError> {
+    let scale = scale_a.max(scale_b);
+    let a = rescale(a as i128, scale_a, scale, RoundingMode::Down);
+    let b = rescale(b as i128, scale_b, scale, RoundingMode::Down);
+    let sum = a
+        .zip(b)
+        .and_then(|(a, b)| a.checked_add(b))
+        .ok_or_else(|| overflow_error("addition"))?;
+    to_scaled(sum, scale, "addition")
+}
+
+/// `a - b` at scale `max(s1, s2)`
+pub fn subtract(a: i64, scale_a: u8, b: i64, scale_b: u8) -> Result<FieldValue, SqlError> {
+    let scale = scale_a.max(scale_b);
+    let a = rescale(a as i128, scale_a, scale, RoundingMode::Down);
+    let b = rescale(b as i128, scale_b, scale, RoundingMode::Down);
+    let difference = a
+        .zip(b)
+        .and_then(|(a, b)| a.checked_sub(b))
+        .ok_or_else(|| overflow_error("subtraction"))?;
+    to_scaled(difference, scale, "subtraction")
+}
+
+/// `a * b` at scale `s1 + s2`
+///
+/// When the exact product does not fit, fractional digits are dropped (HALF_UP) until it
+/// does, but never below `max(s1, s2)`.
+pub fn multiply(a: i64, scale_a: u8, b: i64, scale_b: u8) -> Result<FieldValue, SqlError> {
+    // |a * b| < 2^126, so the exact product always fits in an i128
+    let product = a as i128 * b as i128;
+    let exact_scale = scale_a as u32 + scale_b as u32;
+    let min_scale = scale_a.max(scale_b);
+
+    let mut scale = exact_scale.min(u8::MAX as u32) as u8;
+    loop {
+        let rescaled = if exact_scale > scale as u32 {
+            10_i128
+                .checked_pow(exact_scale - scale as u32)
+                .map(|divisor| div_round(product, divisor, RoundingMode::HalfUp))
+                .unwrap_or(0)
+        } else {
+            product
+        };
+        if let Ok(value) = i64::try_from(rescaled) {
+            return Ok(FieldValue::ScaledInteger(value, scale));
+        }
+        if scale <= min_scale {
+            return Err(overflow_error("multiplication"));
+        }
+        scale -= 1;
+    }
+}
+
+/// `a / b` at scale `max(s1, s2, 6)`, rounded HALF_UP
+pub fn divide(a: i64, scale_a: u8, b: i64, scale_b: u8) -> Result<FieldValue, SqlError> {
+    if b == 0 {
+        return Err(division_by_zero());
+    }
+    let scale = scale_a.max(scale_b).max(MIN_DIVISION_SCALE);
+
+    // a/10^s1 / (b/10^s2) = a * 10^(scale - s1 + s2) / b, in units of 10^-scale
+    let shift = scale as u32 + scale_b as u32 - scale_a as u32;
+    let numerator = 10_i128
+        .checked_pow(shift)
+        .and_then(|factor| factor.checked_mul(a as i128))
+        .ok_or_else(|| overflow_error("division"))?;
+    to_scaled(
+        div_round(numerator, b as i128, RoundingMode::HalfUp),
+        scale,
+        "division",
+    )
+}
+
+/// Convert a float operand to the scale of the decimal it is combined with
+pub fn float_to_unscaled(value: f64, scale: u8) -> Result<i64, SqlError> {
+    let scaled = (value * 10_f64.powi(scale as i32)).round();
+    if scaled.is_finite() && scaled.abs() < i64::MAX as f64 {
+        Ok(scaled as i64)
+    } else {
+        Err(SqlError::ExecutionError {
+            message: format!("Value {} overflows a DECIMAL with scale {}", value, scale),
+            query: None,
+        })
+    }
+}
@@ -12,6 +12,9 @@ The primary interface for executing SQL queries against streaming data:
 - [`StreamExecutionEngine`] - Main execution engine
 - [`StreamRecord`] - Input record format
 - [`FieldValue`] - Value type system
+- [`PreparedStatement`] - Queries with `$1` / `:name` parameters, see [`StreamExecutionEngine::prepare`]
+- [`LookupSource`] - Reference data for subqueries, see [`StreamExecutionEngine::register_lookup_source`]
+- [`StateBackend`](super::StateBackend) - Storage for GROUP BY, MERGE/UPSERT and window state, see [`StreamExecutionEngine::set_state_backends`]
 
 ## Usage
 
@@ -127,24 +130,42 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 */
 
 use super::aggregation::AggregateFunctions;
-use super::expression::ExpressionEvaluator;
+use super::algorithms::JoinStrategy;
+use super::checkpoint::{OperatorSnapshot, WindowSnapshot};
+use super::expression::{CompiledQuery, ExpressionEvaluator};
 use super::internal::{
-    ExecutionMessage, ExecutionState, GroupByState, QueryExecution, WindowState,
+    ExecutionMessage, ExecutionState, GroupAccumulator, GroupByState, QueryExecution, WindowState,
 };
+use super::prepared::PreparedStatement;
+use super::spill::{ExternalSorter, MemoryBudget, MemoryMetrics};
+use super::state::ttl::{EvictionOutput, StateKey, EVICTED_HEADER};
+use super::state::{
+    StateBackends, StateCodec, StateMetrics, StateOperator, StateTtl, StateTtlConfig, ValueState,
+};
+use super::subquery::{LookupSource, SubqueryCache, SubqueryRefreshPolicy};
 use super::types::{FieldValue, StreamRecord};
 // FieldValueConverter no longer needed since we use StreamRecord directly
 use crate::ferris::datasource::{create_sink, create_source, DataReader, DataWriter};
-use crate::ferris::sql::ast::{Expr, SelectField, StreamSource, StreamingQuery};
+use crate::ferris::sql::ast::{
+    Expr, InsertSource, SelectField, StreamSource, StreamingQuery, WindowSpec,
+};
 use crate::ferris::sql::error::SqlError;
+use crate::ferris::sql::parser::StreamingSqlParser;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::mpsc;
 // Processor imports for Phase 5B integration
+use super::processors::merge::{ChangeOperation, CHANGE_OPERATION_HEADER};
 use super::processors::{
     HeaderMutation as ProcessorHeaderMutation, HeaderOperation as ProcessorHeaderOperation,
-    JoinContext, ProcessorContext, QueryProcessor, SelectProcessor, WindowContext, WindowProcessor,
+    JoinContext, MergeProcessor, ProcessorContext, QueryProcessor, SelectProcessor, WindowContext,
+    WindowProcessor,
 };
 
+/// Query ID of the window state of queries run through
+/// [`execute_with_record`](StreamExecutionEngine::execute_with_record)
+pub(crate) const EXECUTE_QUERY_ID: &str = "execute_query";
+
 pub struct StreamExecutionEngine {
     active_queries: HashMap<String, QueryExecution>,
     message_sender: mpsc::UnboundedSender<ExecutionMessage>,
@@ -153,6 +174,29 @@ pub struct StreamExecutionEngine {
     record_count: u64,
     // Stateful GROUP BY support
     group_states: HashMap<String, GroupByState>,
+    // Current rows of MERGE/UPSERT targets, keyed by upsert key
+    merge_target_states: HashMap<String, HashMap<String, StreamRecord>>,
+    // JOIN strategies chosen by the query optimizer, by right source name
+    join_strategies: HashMap<String, JoinStrategy>,
+    // Compiled expressions of the query planned with plan_query
+    query_plan: Option<Arc<CompiledQuery>>,
+    // Reference data subqueries read, by source name
+    lookup_sources: HashMap<String, Arc<dyn LookupSource>>,
+    // Subquery plans and cached results of uncorrelated subqueries
+    subquery_cache: SubqueryCache,
+    subquery_refresh: SubqueryRefreshPolicy,
+    // State backends of stateful operators; operators without one keep state in the maps above
+    state_backends: StateBackends,
+    // Update times of keyed state that expires after a TTL
+    state_ttl: Option<StateTtl>,
+    // Records emitted for evicted keys, not yet published
+    evicted_records: Vec<StreamRecord>,
+    // Memory budget of the job's buffering operators, which spill to disk beyond it
+    memory_budget: Option<MemoryBudget>,
+    // Window buffers restored from a checkpoint whose queries have not started yet
+    restored_windows: HashMap<String, WindowSnapshot>,
+    // Whether execute_batch may run stateless SELECTs through the columnar kernels
+    columnar_execution: bool,
     // Performance monitoring
     performance_monitor:
         Option<Arc<crate::ferris::sql::execution::performance::PerformanceMonitor>>,
@@ -181,10 +225,476 @@ impl StreamExecutionEngine {
             output_sender,
             record_count: 0,
             group_states: HashMap::new(),
+            merge_target_states: HashMap::new(),
+            join_strategies: HashMap::new(),
+            query_plan: None,
+            lookup_sources: HashMap::new(),
+            subquery_cache: SubqueryCache::new(),
+            subquery_refresh: SubqueryRefreshPolicy::default(),
+            state_backends: StateBackends::new(),
+            state_ttl: None,
+            evicted_records: Vec::new(),
+            memory_budget: None,
+            restored_windows: HashMap::new(),
+            columnar_execution: false,
             performance_monitor: None,
         }
     }
 
+    /// Set the JOIN strategies chosen by the query optimizer, keyed by right source name
+    pub fn set_join_strategies(&mut self, join_strategies: HashMap<String, JoinStrategy>) {
+        self.join_strategies = join_strategies;
+    }
+
+    /// Register reference data that subqueries read as `FROM <name>`
+    pub fn register_lookup_source(
+        &mut self,
+        name: impl Into<String>,
+        source: Arc<dyn LookupSource>,
+    ) {
+        self.lookup_sources.insert(name.into(), source);
+    }
+
+    /// Set when cached results of subqueries without a `subquery.refresh` property are recomputed
+    pub fn set_subquery_refresh_policy(&mut self, policy: SubqueryRefreshPolicy) {
+        self.subquery_refresh = policy;
+    }
+
+    /// Keep operator state in these backends, usually opened from job properties with
+    /// [`StateBackendConfig::open`](super::StateBackendConfig::open)
+    pub fn set_state_backends(&mut self, state_backends: StateBackends) {
+        self.state_backends = state_backends;
+    }
+
+    /// State backends of stateful operators
+    pub fn state_backends(&self) -> &StateBackends {
+        &self.state_backends
+    }
+
+    /// Bound the memory windows, hash joins and sorts hold, spilling to disk beyond it,
+    /// see [`spill`](super::spill)
+    pub fn set_memory_budget(&mut self, budget: MemoryBudget) {
+        self.memory_budget = Some(budget);
+    }
+
+    /// Memory use and spilling of the job, when it has a budget
+    pub fn memory_metrics(&self) -> Option<MemoryMetrics> {
+        self.memory_budget.as_ref().map(MemoryBudget::metrics)
+    }
+
+    /// Evict GROUP BY groups, MERGE/UPSERT rows and window buffers not updated within the
+    /// TTL, see [`ttl`](super::state::ttl); keys already held start a full TTL now
+    pub fn set_state_ttl(&mut self, config: StateTtlConfig) -> Result<(), SqlError> {
+        self.state_ttl = Some(StateTtl::new(config));
+        self.track_existing_state()
+    }
+
+    /// Records emitted for keys evicted while executing through
+    /// [`execute_with_output`](Self::execute_with_output), which the caller publishes
+    pub fn take_evicted_records(&mut self) -> Vec<StreamRecord> {
+        std::mem::take(&mut self.evicted_records)
+    }
+
+    /// Keys held and keys evicted by every GROUP BY, MERGE/UPSERT target and windowed query
+    pub fn state_metrics(&self) -> Result<Vec<StateMetrics>, SqlError> {
+        let mut keys: HashMap<(StateOperator, String), usize> = HashMap::new();
+        for group_state in self.group_states.values() {
+            *keys
+                .entry((StateOperator::GroupBy, group_state.state_namespace.clone()))
+                .or_default() += group_state.groups.len();
+        }
+        for (target, rows) in &self.merge_target_states {
+            *keys
+                .entry((StateOperator::Merge, StateOperator::Merge.namespace(target)))
+                .or_default() += rows.len();
+        }
+        for query_id in Self::buffered_windows(&self.active_queries) {
+            *keys
+                .entry((
+                    StateOperator::Window,
+                    StateOperator::Window.namespace(query_id),
+                ))
+                .or_default() += 1;
+        }
+        for operator in StateOperator::KEYED {
+            if let Some(backend) = self.state_backends.get(operator) {
+                for namespace in backend.namespaces() {
+                    *keys.entry((operator, namespace.clone())).or_default() +=
+                        backend.scan_prefix(&namespace, &[])?.len();
+                }
+            }
+        }
+        if let Some(ttl) = &self.state_ttl {
+            for namespace in ttl.evicted_namespaces() {
+                keys.entry(namespace.clone()).or_default();
+            }
+        }
+
+        let mut metrics: Vec<StateMetrics> = keys
+            .into_iter()
+            .map(|((operator, namespace), keys)| StateMetrics {
+                evicted: self
+                    .state_ttl
+                    .as_ref()
+                    .map_or(0, |ttl| ttl.evicted(operator, &namespace)),
+                operator,
+                namespace,
+                keys,
+            })
+            .collect();
+        metrics.sort_by(|a, b| a.namespace.cmp(&b.namespace));
+        Ok(metrics)
+    }
+
+    /// Start the TTL of every key currently held
+    fn track_existing_state(&mut self) -> Result<(), SqlError> {
+        let Some(ttl) = &mut self.state_ttl else {
+            return Ok(());
+        };
+        ttl.clear();
+        for group_state in self.group_states.values() {
+            for key in group_state.groups.keys() {
+                ttl.track_restored(
+                    StateOperator::GroupBy,
+                    &group_state.state_namespace,
+                    &key.to_state_bytes(),
+                );
+            }
+        }
+        for (target, rows) in &self.merge_target_states {
+            let namespace = StateOperator::Merge.namespace(target);
+            for key in rows.keys() {
+                ttl.track_restored(StateOperator::Merge, &namespace, &key.to_state_bytes());
+            }
+        }
+        for query_id in Self::buffered_windows(&self.active_queries) {
+            ttl.track_restored(
+                StateOperator::Window,
+                &StateOperator::Window.namespace(query_id),
+                &query_id.to_string().to_state_bytes(),
+            );
+        }
+        for operator in StateOperator::KEYED {
+            if let Some(backend) = self.state_backends.get(operator) {
+                for namespace in backend.namespaces() {
+                    for (key, _) in backend.scan_prefix(&namespace, &[])? {
+                        ttl.track_restored(operator, &namespace, &key);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Evict the keys whose TTL ran out, keeping the records to emit for them
+    fn expire_state(&mut self) -> Result<(), SqlError> {
+        let Some(ttl) = &mut self.state_ttl else {
+            return Ok(());
+        };
+        let emit = ttl.config().emit;
+        for key in ttl.take_expired() {
+            let evicted = match key.0 {
+                StateOperator::GroupBy => self.evict_group(&key, emit)?,
+                StateOperator::Merge => self.evict_row(&key, emit)?,
+                StateOperator::Window => self.evict_window(&key, emit)?,
+            };
+            if let Some(mut record) = evicted {
+                record
+                    .headers
+                    .insert(EVICTED_HEADER.to_string(), "true".to_string());
+                self.evicted_records.push(record);
+            }
+        }
+        Ok(())
+    }
+
+    /// Drop an expired GROUP BY group; returns its final result or retraction
+    fn evict_group(
+        &mut self,
+        (_, namespace, key): &StateKey,
+        emit: EvictionOutput,
+    ) -> Result<Option<StreamRecord>, SqlError> {
+        let group_key = Vec::<String>::from_state_bytes(key)?;
+        let Some(group_state) = self
+            .group_states
+            .values_mut()
+            .find(|group_state| group_state.state_namespace == *namespace)
+        else {
+            return Ok(None);
+        };
+        let mut accumulator = group_state.groups.remove(&group_key);
+        if let Some(backend) = self.state_backends.get(StateOperator::GroupBy) {
+            let stored =
+                ValueState::<Vec<String>, GroupAccumulator>::new(Arc::clone(backend), namespace);
+            accumulator = accumulator.or(stored.get(&group_key)?);
+            stored.remove(&group_key)?;
+        }
+
+        let Some(accumulator) = accumulator else {
+            return Ok(None);
+        };
+        if emit == EvictionOutput::None {
+            return Ok(None);
+        }
+        let result = Self::group_result(
+            &group_state.select_fields,
+            &group_state.having_clause,
+            &accumulator,
+        )?;
+        Ok(result.map(|mut record| {
+            if emit == EvictionOutput::Retract {
+                record
+                    .fields
+                    .insert("__deleted".to_string(), FieldValue::Boolean(true));
+                record.headers.insert(
+                    CHANGE_OPERATION_HEADER.to_string(),
+                    ChangeOperation::Delete.as_str().to_string(),
+                );
+            }
+            record
+        }))
+    }
+
+    /// Drop an expired MERGE/UPSERT row; returns it or its tombstone
+    fn evict_row(
+        &mut self,
+        (_, namespace, key): &StateKey,
+        emit: EvictionOutput,
+    ) -> Result<Option<StreamRecord>, SqlError> {
+        let row_key = String::from_state_bytes(key)?;
+        let Some(target) = namespace.strip_prefix(&StateOperator::Merge.namespace("")) else {
+            return Ok(None);
+        };
+        let mut row = self
+            .merge_target_states
+            .get_mut(target)
+            .and_then(|rows| rows.remove(&row_key));
+        if let Some(backend) = self.state_backends.get(StateOperator::Merge) {
+            let stored = ValueState::<String, StreamRecord>::new(Arc::clone(backend), namespace);
+            row = row.or(stored.get(&row_key)?);
+            stored.remove(&row_key)?;
+        }
+        self.subquery_cache.invalidate_table(target);
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        Ok(match emit {
+            EvictionOutput::None => None,
+            EvictionOutput::Final => Some(MergeProcessor::changelog_record(
+                row.fields.clone(),
+                ChangeOperation::Update,
+                target,
+                Some(&row_key),
+                &row,
+            )),
+            EvictionOutput::Retract => {
+                let mut fields = row.fields.clone();
+                fields.insert("__deleted".to_string(), FieldValue::Boolean(true));
+                let mut tombstone = MergeProcessor::changelog_record(
+                    fields,
+                    ChangeOperation::Delete,
+                    target,
+                    Some(&row_key),
+                    &row,
+                );
+                tombstone
+                    .headers
+                    .insert("tombstone".to_string(), "true".to_string());
+                Some(tombstone)
+            }
+        })
+    }
+
+    /// Drop the buffer of a windowed query that received no records within the TTL;
+    /// returns the result of the buffered records for `final`. Windows emit nothing until
+    /// they close, so there is nothing to retract.
+    fn evict_window(
+        &mut self,
+        (_, _, key): &StateKey,
+        emit: EvictionOutput,
+    ) -> Result<Option<StreamRecord>, SqlError> {
+        let query_id = String::from_state_bytes(key)?;
+        let Some(QueryExecution {
+            query,
+            window_state: Some(window_state),
+            ..
+        }) = self.active_queries.get_mut(&query_id)
+        else {
+            return Ok(None);
+        };
+        let result = match emit {
+            EvictionOutput::Final => WindowProcessor::aggregate_window_state(query, window_state)?,
+            EvictionOutput::None | EvictionOutput::Retract => None,
+        };
+        window_state.retain_records(|_| false)?;
+        window_state.rebalance()?;
+        Ok(result)
+    }
+
+    /// Restart the TTL of a windowed query's buffer after `record` was added to it, then
+    /// evict whatever expired
+    fn touch_window(&mut self, query_id: &str, record: &StreamRecord) -> Result<(), SqlError> {
+        if let Some(ttl) = &mut self.state_ttl {
+            ttl.touch(
+                StateOperator::Window,
+                &StateOperator::Window.namespace(query_id),
+                &query_id.to_string().to_state_bytes(),
+                record.timestamp,
+            );
+        }
+        self.expire_state()
+    }
+
+    /// Queries whose window buffer holds records
+    fn buffered_windows(
+        active_queries: &HashMap<String, QueryExecution>,
+    ) -> impl Iterator<Item = &String> {
+        active_queries
+            .iter()
+            .filter(|(_, execution)| {
+                execution
+                    .window_state
+                    .as_ref()
+                    .is_some_and(|window_state| !window_state.is_empty())
+            })
+            .map(|(query_id, _)| query_id)
+    }
+
+    /// Snapshot of the state of every operator, written into a
+    /// [`Checkpoint`](super::checkpoint::Checkpoint)
+    pub fn snapshot_operator_state(&self) -> Result<OperatorSnapshot, SqlError> {
+        let mut snapshot = OperatorSnapshot::default();
+        for group_state in self.group_states.values() {
+            snapshot
+                .group_by
+                .entry(group_state.state_namespace.clone())
+                .or_default()
+                .extend(
+                    group_state
+                        .groups
+                        .iter()
+                        .map(|(key, accumulator)| (key.clone(), accumulator.clone())),
+                );
+        }
+
+        snapshot.windows = self.restored_windows.clone();
+        for (query_id, execution) in &self.active_queries {
+            if let Some(window_state) = &execution.window_state {
+                snapshot.windows.insert(
+                    query_id.clone(),
+                    WindowSnapshot {
+                        buffer: window_state.records()?,
+                        last_emit: window_state.last_emit,
+                    },
+                );
+            }
+        }
+
+        snapshot.merge_targets = self.merge_target_states.clone();
+
+        for operator in StateOperator::ALL {
+            if let Some(backend) = self.state_backends.get(operator) {
+                let namespaces = snapshot
+                    .backends
+                    .entry(operator.as_str().to_string())
+                    .or_default();
+                for namespace in backend.namespaces() {
+                    let entries = backend.scan_prefix(&namespace, &[])?;
+                    namespaces.insert(namespace, entries);
+                }
+            }
+        }
+        Ok(snapshot)
+    }
+
+    /// Replace the state of every operator with a snapshot from
+    /// [`snapshot_operator_state`](Self::snapshot_operator_state)
+    ///
+    /// GROUP BY groups and window buffers of queries that have not run yet are taken over
+    /// when they do.
+    pub fn restore_operator_state(&mut self, snapshot: &OperatorSnapshot) -> Result<(), SqlError> {
+        self.group_states = snapshot
+            .group_by
+            .iter()
+            .map(|(namespace, groups)| {
+                let groups = groups.iter().cloned().collect();
+                (
+                    namespace.clone(),
+                    GroupByState::restored(namespace.clone(), groups),
+                )
+            })
+            .collect();
+
+        self.restored_windows.clear();
+        for (query_id, window) in &snapshot.windows {
+            match self
+                .active_queries
+                .get_mut(query_id)
+                .and_then(|execution| execution.window_state.as_mut())
+            {
+                Some(window_state) => {
+                    window_state.set_records(window.buffer.clone())?;
+                    window_state.last_emit = window.last_emit;
+                }
+                None => {
+                    self.restored_windows
+                        .insert(query_id.clone(), window.clone());
+                }
+            }
+        }
+
+        for table in self
+            .merge_target_states
+            .keys()
+            .chain(snapshot.merge_targets.keys())
+        {
+            self.subquery_cache.invalidate_table(table);
+        }
+        self.merge_target_states = snapshot.merge_targets.clone();
+
+        for operator in StateOperator::ALL {
+            let Some(backend) = self.state_backends.get(operator) else {
+                continue;
+            };
+            for namespace in backend.namespaces() {
+                backend.clear(&namespace)?;
+            }
+            if let Some(namespaces) = snapshot.backends.get(operator.as_str()) {
+                for (namespace, entries) in namespaces {
+                    for (key, value) in entries {
+                        backend.put(namespace, key, value)?;
+                    }
+                }
+            }
+            backend.flush()?;
+        }
+        self.track_existing_state()
+    }
+
+    /// Window state of a query that starts now, kept in the window state backend if there
+    /// is one and resuming a restored buffer if there is one
+    fn new_window_state(
+        &mut self,
+        query_id: &str,
+        window_spec: &WindowSpec,
+    ) -> Result<WindowState, SqlError> {
+        let mut window_state = WindowState::new(window_spec.clone());
+        if let Some(backend) = self.state_backends.get(StateOperator::Window) {
+            window_state = window_state.with_backend(Arc::clone(backend), query_id)?;
+        }
+        if let Some(restored) = self.restored_windows.remove(query_id) {
+            window_state.set_records(restored.buffer)?;
+            window_state.last_emit = restored.last_emit;
+        }
+        Ok(window_state)
+    }
+
+    /// Enable or disable columnar batch execution in [`execute_batch`](Self::execute_batch)
+    pub fn set_columnar_execution(&mut self, enabled: bool) {
+        self.columnar_execution = enabled;
+    }
+
     /// Set performance monitor for tracking query execution metrics
     pub fn set_performance_monitor(
         &mut self,
@@ -210,6 +720,12 @@ impl StreamExecutionEngine {
         context.record_count = self.record_count;
         context.window_context = self.get_window_context_for_processors(query_id);
         context.join_context = JoinContext::new();
+        context.join_strategies = self.join_strategies.clone();
+        context.lookup_sources = self.lookup_sources.clone();
+        context.subquery_cache = self.subquery_cache.clone();
+        context.subquery_refresh = self.subquery_refresh;
+        context.state_backends = self.state_backends.clone();
+        context.memory_budget = self.memory_budget.clone();
         context.performance_monitor = self.performance_monitor.as_ref().map(Arc::clone);
 
         // Load window states efficiently (only for queries we're processing)
@@ -274,18 +790,24 @@ impl StreamExecutionEngine {
         record: &StreamRecord,
     ) -> Result<Option<StreamRecord>, SqlError> {
         // All queries now use the processor architecture
-        self.apply_query_with_processors(query, record)
+        let query_plan = self
+            .query_plan
+            .as_mut()
+            .map(|plan| Self::bound_plan(plan, record));
+        self.apply_query_with_processors(query, query_plan, record)
     }
 
     /// Step 3.1: Real processor-based query execution implementation
     fn apply_query_with_processors(
         &mut self,
         query: &StreamingQuery,
+        query_plan: Option<Arc<CompiledQuery>>,
         record: &StreamRecord,
     ) -> Result<Option<StreamRecord>, SqlError> {
         // Generate a query ID based on the query type and content
         let query_id = self.generate_query_id(query);
         let mut context = self.create_processor_context(&query_id);
+        context.query_plan = query_plan;
 
         // Set LIMIT in context if present
         if let StreamingQuery::Select { limit, .. } = query {
@@ -294,11 +816,18 @@ impl StreamExecutionEngine {
 
         // Share engine GROUP BY states with processor context
         context.group_by_states = self.group_states.clone();
+        // MERGE/UPSERT target rows move into the context and back, even on error
+        context.merge_target_states = std::mem::take(&mut self.merge_target_states);
+        context.state_ttl = self.state_ttl.take();
 
-        let result = QueryProcessor::process_query(query, record, &mut context)?;
+        let result = QueryProcessor::process_query(query, record, &mut context);
+        self.merge_target_states = std::mem::take(&mut context.merge_target_states);
+        self.state_ttl = context.state_ttl.take();
+        let result = result?;
 
         // Update engine state from context - sync back the GROUP BY states
         self.group_states = std::mem::take(&mut context.group_by_states);
+        self.expire_state()?;
 
         // NOTE: GROUP BY results emission moved to explicit triggers
         // Emitting after every record was causing performance issues and incorrect results
@@ -346,6 +875,8 @@ impl StreamExecutionEngine {
             StreamingQuery::CreateStreamInto { name, .. } => format!("create_stream_into_{}", name),
             StreamingQuery::CreateTableInto { name, .. } => format!("create_table_into_{}", name),
             StreamingQuery::Show { .. } => "show_query".to_string(),
+            StreamingQuery::Merge { target, .. } => format!("merge_{}", target),
+            StreamingQuery::Upsert { table_name, .. } => format!("upsert_{}", table_name),
             _ => "unknown_query".to_string(),
         }
     }
@@ -407,6 +938,113 @@ impl StreamExecutionEngine {
         self.execute_internal(query, stream_record).await
     }
 
+    /// Parse a query with `$1` or `:name` placeholders into a prepared statement
+    ///
+    /// Bind values with [`PreparedStatement::bind`] or [`PreparedStatement::bind_named`]
+    /// and execute the returned query as usual.
+    pub fn prepare(&self, sql: &str) -> Result<PreparedStatement, SqlError> {
+        PreparedStatement::new(StreamingSqlParser::new().parse(sql)?)
+    }
+
+    /// Execute a non-windowed query for one record and return its result
+    ///
+    /// Unlike [`execute_with_record`](Self::execute_with_record) the result is not published
+    /// on the output channel; job processors use this for MERGE and UPSERT, whose changelog
+    /// records are what gets written to the sink.
+    pub fn execute_with_output(
+        &mut self,
+        query: &StreamingQuery,
+        stream_record: &StreamRecord,
+    ) -> Result<Option<StreamRecord>, SqlError> {
+        self.apply_query(query, stream_record)
+    }
+
+    /// Plan the query that records are executed for from now on
+    ///
+    /// Compiles the query's WHERE clause and SELECT expressions once, and prepares its
+    /// columnar plan; [`execute_with_record`](Self::execute_with_record),
+    /// [`execute_with_output`](Self::execute_with_output) and
+    /// [`execute_batch`](Self::execute_batch) then evaluate the plan instead of interpreting
+    /// the query for every record. Every record executed until the next call must be for
+    /// this query. Queries executed without a plan are interpreted.
+    pub fn plan_query(&mut self, query: &StreamingQuery) {
+        self.query_plan = Some(Arc::new(CompiledQuery::new(query)));
+    }
+
+    /// The plan to evaluate for a record, bound to the record's schema
+    ///
+    /// Records of one source share their schema, so the plan is only rebound when the
+    /// layout of the records changes.
+    fn bound_plan(plan: &mut Arc<CompiledQuery>, record: &StreamRecord) -> Arc<CompiledQuery> {
+        let schema = record.fields.schema();
+        if !plan.is_bound_to(schema) {
+            Arc::make_mut(plan).bind(schema);
+        }
+        Arc::clone(plan)
+    }
+
+    /// Execute the query planned with [`plan_query`](Self::plan_query) over a whole batch
+    /// through the columnar kernels
+    ///
+    /// Results are published on the output channel like those of
+    /// [`execute_with_record`](Self::execute_with_record). Returns `Ok(false)` without
+    /// touching any state when columnar execution is disabled, no query was planned or the
+    /// batch needs the row path; the caller then executes the records one by one.
+    ///
+    /// A GROUP BY folds the batch into its groups and emits one result per updated group;
+    /// with a state TTL or a GROUP BY state backend it stays on the row path.
+    pub fn execute_batch(&mut self, records: &[StreamRecord]) -> Result<bool, SqlError> {
+        if !self.columnar_execution {
+            return Ok(false);
+        }
+        let Some(plan) = self.query_plan.as_deref().and_then(CompiledQuery::columnar) else {
+            return Ok(false);
+        };
+        let results = match plan.group_state() {
+            None => plan.execute(records),
+            // Groups kept in a state backend or under a TTL are updated record by record
+            Some(_)
+                if self.state_ttl.is_some()
+                    || self.state_backends.get(StateOperator::GroupBy).is_some() =>
+            {
+                None
+            }
+            Some(new_state) => {
+                // The state of the query sits under the key the row path gave it, or under
+                // its namespace when restored or started here, until the row path adopts it
+                let key = self
+                    .group_states
+                    .iter()
+                    .find(|(_, state)| {
+                        state.state_namespace == new_state.state_namespace
+                            && state.group_expressions == new_state.group_expressions
+                    })
+                    .map(|(key, _)| key.clone())
+                    .unwrap_or_else(|| new_state.state_namespace.clone());
+                match self.group_states.get_mut(&key) {
+                    Some(state) => plan.aggregate(records, &mut state.groups),
+                    None => {
+                        let mut state = new_state;
+                        let results = plan.aggregate(records, &mut state.groups);
+                        if results.is_some() {
+                            self.group_states.insert(key, state);
+                        }
+                        results
+                    }
+                }
+            }
+        };
+        let Some(results) = results else {
+            return Ok(false);
+        };
+
+        for result in results {
+            self.record_count += 1;
+            self.emit_result(result)?;
+        }
+        Ok(true)
+    }
+
     /// Internal execute method that does the actual query processing
     async fn execute_internal(
         &mut self,
@@ -422,18 +1060,15 @@ impl StreamExecutionEngine {
             // For windowed queries, we need to simulate the streaming execution model
 
             // Initialize window state if needed for this query
-            let query_id = "execute_query".to_string();
+            let query_id = EXECUTE_QUERY_ID.to_string();
             if !self.active_queries.contains_key(&query_id) {
-                let window_state = Some(WindowState {
-                    window_spec: window_spec.clone(),
-                    buffer: Vec::new(),
-                    last_emit: 0,
-                });
+                let window_state = Some(self.new_window_state(&query_id, window_spec)?);
 
                 let execution = QueryExecution {
                     query: query.clone(),
                     state: ExecutionState::Running,
                     window_state,
+                    plan: Arc::new(CompiledQuery::new(query)),
                 };
 
                 self.active_queries.insert(query_id.clone(), execution);
@@ -451,6 +1086,7 @@ impl StreamExecutionEngine {
 
                 // Efficiently persist only modified window states (zero-copy for unchanged states)
                 self.save_window_states_from_context(&context);
+                self.touch_window(&query_id, &stream_record)?;
 
                 result
             }
@@ -461,29 +1097,37 @@ impl StreamExecutionEngine {
 
         // Process result if any
         if let Some(result) = result {
-            // Send result through both channels - no conversion needed!
-            self.message_sender
-                .send(ExecutionMessage::QueryResult {
-                    query_id: "default".to_string(),
-                    result: result.clone(),
-                })
-                .map_err(|_| SqlError::ExecutionError {
-                    message: "Failed to send result".to_string(),
-                    query: None,
-                })?;
-
-            // Send result to output channel directly (no conversion needed)
-            self.output_sender
-                .send(result)
-                .map_err(|e| SqlError::ExecutionError {
-                    message: format!("Failed to send result to output channel: {}", e),
-                    query: None,
-                })?;
+            self.emit_result(result)?;
+        }
+        for evicted in self.take_evicted_records() {
+            self.emit_result(evicted)?;
         }
 
         Ok(())
     }
 
+    /// Publish a query result on the message and output channels
+    fn emit_result(&mut self, result: StreamRecord) -> Result<(), SqlError> {
+        // Send result through both channels - no conversion needed!
+        self.message_sender
+            .send(ExecutionMessage::QueryResult {
+                query_id: "default".to_string(),
+                result: result.clone(),
+            })
+            .map_err(|_| SqlError::ExecutionError {
+                message: "Failed to send result".to_string(),
+                query: None,
+            })?;
+
+        // Send result to output channel directly (no conversion needed)
+        self.output_sender
+            .send(result)
+            .map_err(|e| SqlError::ExecutionError {
+                message: format!("Failed to send result to output channel: {}", e),
+                query: None,
+            })
+    }
+
     /// Starts the execution engine's message processing loop.
     ///
     /// This method must be called to begin processing query execution messages.
@@ -526,20 +1170,20 @@ impl StreamExecutionEngine {
         query: StreamingQuery,
     ) -> Result<(), SqlError> {
         let window_state = match &query {
-            StreamingQuery::Select { window, .. } => {
-                window.as_ref().map(|window_spec| WindowState {
-                    window_spec: window_spec.clone(),
-                    buffer: Vec::new(),
-                    last_emit: 0,
-                })
-            }
+            StreamingQuery::Select { window, .. } => window
+                .as_ref()
+                .map(|window_spec| self.new_window_state(&query_id, window_spec))
+                .transpose()?,
             _ => None,
         };
 
+        // Compile the query once for all records it processes
+        let plan = Arc::new(CompiledQuery::new(&query));
         let execution = QueryExecution {
             query,
             state: ExecutionState::Running,
             window_state,
+            plan,
         };
 
         self.active_queries.insert(query_id, execution);
@@ -596,12 +1240,17 @@ impl StreamExecutionEngine {
 
                     // Persist modified states efficiently
                     self.save_window_states_from_context(&context);
+                    self.touch_window(&query_id, &record)?;
 
                     result
                 }
             } else {
                 // Use regular processing for non-windowed queries
-                self.apply_query(&query, &record)?
+                let query_plan = self
+                    .active_queries
+                    .get_mut(&query_id)
+                    .map(|execution| Self::bound_plan(&mut execution.plan, &record));
+                self.apply_query_with_processors(&query, query_plan, &record)?
             };
 
             if let Some(result_record) = result {
@@ -613,6 +1262,9 @@ impl StreamExecutionEngine {
             // Send result directly to output channel - no conversion needed!
             let _ = self.output_sender.send(result);
         }
+        for evicted in self.take_evicted_records() {
+            let _ = self.output_sender.send(evicted);
+        }
         Ok(())
     }
 
@@ -621,7 +1273,7 @@ impl StreamExecutionEngine {
     pub async fn flush_windows(&mut self) -> Result<(), SqlError> {
         // Create a trigger record with a very high timestamp to force window emission
         let trigger_record = StreamRecord {
-            fields: HashMap::new(),
+            fields: HashMap::new().into(),
             timestamp: i64::MAX, // Far future timestamp
             offset: 0,
             partition: 0,
@@ -681,7 +1333,9 @@ impl StreamExecutionEngine {
             StreamingQuery::CreateTableInto { as_select, .. } => {
                 self.query_matches_stream(as_select, stream_name)
             }
-            StreamingQuery::Show { .. } => false, // SHOW commands don't match streams
+            StreamingQuery::Alter { .. } => false, // ALTER commands don't match streams
+            StreamingQuery::Drop { .. } => false,  // DROP commands don't match streams
+            StreamingQuery::Show { .. } => false,  // SHOW commands don't match streams
             StreamingQuery::StartJob { query, .. } => {
                 // START JOB matches if the underlying query matches
                 self.query_matches_stream(query, stream_name)
@@ -706,6 +1360,19 @@ impl StreamExecutionEngine {
                 // DELETE matches the target table
                 table_name == stream_name
             }
+            StreamingQuery::Merge { source, .. } => match source {
+                // MERGE is driven by the records of its source
+                StreamSource::Stream(name) | StreamSource::Table(name) => name == stream_name,
+                StreamSource::Uri(uri) => uri == stream_name,
+                StreamSource::Subquery(_) => false,
+            },
+            StreamingQuery::Upsert {
+                table_name, source, ..
+            } => {
+                // UPSERT matches the target table or the source of its SELECT
+                table_name == stream_name
+                    || matches!(source, InsertSource::Select { query } if self.query_matches_stream(query, stream_name))
+            }
             StreamingQuery::Union { left, right, .. } => {
                 // UNION matches if either side matches the stream
                 self.query_matches_stream(left, stream_name)
@@ -740,165 +1407,185 @@ impl StreamExecutionEngine {
 
         // Iterate through all accumulated GROUP BY states and emit results
         for group_state in group_states.values() {
-            for accumulator in group_state.groups.values() {
-                // Generate result record for this group
-                let mut result_fields = HashMap::new();
-
-                // Evaluate SELECT fields using the accumulator
-                for field in fields {
-                    match field {
-                        SelectField::Expression { expr, alias } => {
-                            let field_name = alias
-                                .as_ref()
-                                .unwrap_or(&SelectProcessor::get_expression_name(expr))
-                                .clone();
-
-                            match expr {
-                                Expr::Function { name: _, args: _ } => {
-                                    // Delegate to AggregateFunctions module for proper handling
-                                    match AggregateFunctions::compute_field_aggregate_value(
-                                        &field_name,
-                                        expr,
-                                        accumulator,
-                                    ) {
-                                        Ok(value) => {
-                                            result_fields.insert(field_name, value);
-                                        }
-                                        Err(e) => {
-                                            log::warn!(
-                                                "Failed to compute aggregate for {}: {}",
-                                                field_name,
-                                                e
-                                            );
-                                            result_fields.insert(field_name, FieldValue::Null);
-                                        }
-                                    }
+            let mut accumulators: Vec<_> = group_state.groups.values().cloned().collect();
+            if let Some(backend) = self.state_backends.get(StateOperator::GroupBy) {
+                let stored = ValueState::<Vec<String>, GroupAccumulator>::new(
+                    Arc::clone(backend),
+                    &group_state.state_namespace,
+                );
+                accumulators.extend(stored.entries()?.into_iter().map(|(_, acc)| acc));
+            }
+            for accumulator in &accumulators {
+                if let Some(output_record) = Self::group_result(fields, having, accumulator)? {
+                    // Send output record directly - no conversion needed!
+                    if self.output_sender.send(output_record).is_err() {
+                        // Channel closed, continue processing
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Result record of one group, `None` when it does not pass HAVING
+    fn group_result(
+        fields: &[SelectField],
+        having: &Option<Expr>,
+        accumulator: &GroupAccumulator,
+    ) -> Result<Option<StreamRecord>, SqlError> {
+        // Generate result record for this group
+        let mut result_fields = HashMap::new();
+
+        // Evaluate SELECT fields using the accumulator
+        for field in fields {
+            match field {
+                SelectField::Expression { expr, alias } => {
+                    let field_name = alias
+                        .as_ref()
+                        .unwrap_or(&SelectProcessor::get_expression_name(expr))
+                        .clone();
+
+                    match expr {
+                        Expr::Function {
+                            name: _, args: _, ..
+                        } => {
+                            // Delegate to AggregateFunctions module for proper handling
+                            match AggregateFunctions::compute_field_aggregate_value(
+                                &field_name,
+                                expr,
+                                accumulator,
+                            ) {
+                                Ok(value) => {
+                                    result_fields.insert(field_name, value);
                                 }
-                                Expr::BinaryOp {
-                                    left: _,
-                                    op: _,
-                                    right: _,
-                                } => {
-                                    // This handles expressions like "amount > 150" in SELECT
-                                    // We need to evaluate the expression using the sample record
-                                    if let Some(sample_record) = &accumulator.sample_record {
-                                        match ExpressionEvaluator::evaluate_expression_value(
-                                            expr,
-                                            sample_record,
-                                        ) {
-                                            Ok(value) => {
-                                                result_fields.insert(field_name, value);
-                                            }
-                                            Err(_) => {
-                                                result_fields.insert(field_name, FieldValue::Null);
-                                            }
-                                        }
-                                    } else {
+                                Err(e) => {
+                                    log::warn!(
+                                        "Failed to compute aggregate for {}: {}",
+                                        field_name,
+                                        e
+                                    );
+                                    result_fields.insert(field_name, FieldValue::Null);
+                                }
+                            }
+                        }
+                        Expr::BinaryOp {
+                            left: _,
+                            op: _,
+                            right: _,
+                            ..
+                        } => {
+                            // This handles expressions like "amount > 150" in SELECT
+                            // We need to evaluate the expression using the sample record
+                            if let Some(sample_record) = &accumulator.sample_record {
+                                match ExpressionEvaluator::evaluate_expression_value(
+                                    expr,
+                                    sample_record,
+                                ) {
+                                    Ok(value) => {
+                                        result_fields.insert(field_name, value);
+                                    }
+                                    Err(_) => {
                                         result_fields.insert(field_name, FieldValue::Null);
                                     }
                                 }
-                                _ => {
-                                    // For other expressions, get first value
-                                    let value = accumulator
-                                        .first_values
-                                        .get(&field_name)
-                                        .cloned()
-                                        .unwrap_or(FieldValue::Null);
-                                    result_fields.insert(field_name, value);
-                                }
+                            } else {
+                                result_fields.insert(field_name, FieldValue::Null);
                             }
                         }
-                        SelectField::Column(name) => {
-                            let value = accumulator
-                                .first_values
-                                .get(name)
-                                .cloned()
-                                .unwrap_or(FieldValue::Null);
-                            result_fields.insert(name.clone(), value);
-                        }
-                        SelectField::AliasedColumn { column, alias } => {
+                        _ => {
+                            // For other expressions, get first value
                             let value = accumulator
                                 .first_values
-                                .get(column)
+                                .get(&field_name)
                                 .cloned()
                                 .unwrap_or(FieldValue::Null);
-                            result_fields.insert(alias.clone(), value);
-                        }
-                        SelectField::Wildcard => {
-                            // Add all fields from sample record
-                            if let Some(sample_record) = &accumulator.sample_record {
-                                result_fields.extend(sample_record.fields.clone());
-                            }
+                            result_fields.insert(field_name, value);
                         }
                     }
                 }
-
-                // Apply HAVING clause filter if present
-                if let Some(having_expr) = having {
-                    // Create temporary record to evaluate HAVING
-                    let temp_record = StreamRecord {
-                        fields: result_fields.clone(),
-                        timestamp: accumulator
-                            .sample_record
-                            .as_ref()
-                            .map(|r| r.timestamp)
-                            .unwrap_or(0),
-                        offset: accumulator
-                            .sample_record
-                            .as_ref()
-                            .map(|r| r.offset)
-                            .unwrap_or(0),
-                        partition: accumulator
-                            .sample_record
-                            .as_ref()
-                            .map(|r| r.partition)
-                            .unwrap_or(0),
-                        headers: accumulator
-                            .sample_record
-                            .as_ref()
-                            .map(|r| r.headers.clone())
-                            .unwrap_or_default(),
-                    };
-
-                    // Skip this group if it doesn't pass HAVING filter
-                    if !ExpressionEvaluator::evaluate_expression(having_expr, &temp_record)? {
-                        continue;
+                SelectField::Column(name) => {
+                    let value = accumulator
+                        .first_values
+                        .get(name)
+                        .cloned()
+                        .unwrap_or(FieldValue::Null);
+                    result_fields.insert(name.clone(), value);
+                }
+                SelectField::AliasedColumn { column, alias } => {
+                    let value = accumulator
+                        .first_values
+                        .get(column)
+                        .cloned()
+                        .unwrap_or(FieldValue::Null);
+                    result_fields.insert(alias.clone(), value);
+                }
+                SelectField::Wildcard => {
+                    // Add all fields from sample record
+                    if let Some(sample_record) = &accumulator.sample_record {
+                        result_fields.extend(sample_record.fields.clone());
                     }
                 }
+            }
+        }
 
-                // Create and emit result record
-                let output_record = StreamRecord {
-                    fields: result_fields,
-                    timestamp: accumulator
-                        .sample_record
-                        .as_ref()
-                        .map(|r| r.timestamp)
-                        .unwrap_or(0),
-                    offset: accumulator
-                        .sample_record
-                        .as_ref()
-                        .map(|r| r.offset)
-                        .unwrap_or(0),
-                    partition: accumulator
-                        .sample_record
-                        .as_ref()
-                        .map(|r| r.partition)
-                        .unwrap_or(0),
-                    headers: accumulator
-                        .sample_record
-                        .as_ref()
-                        .map(|r| r.headers.clone())
-                        .unwrap_or_default(),
-                };
+        // Apply HAVING clause filter if present
+        if let Some(having_expr) = having {
+            // Create temporary record to evaluate HAVING
+            let temp_record = StreamRecord {
+                fields: result_fields.clone().into(),
+                timestamp: accumulator
+                    .sample_record
+                    .as_ref()
+                    .map(|r| r.timestamp)
+                    .unwrap_or(0),
+                offset: accumulator
+                    .sample_record
+                    .as_ref()
+                    .map(|r| r.offset)
+                    .unwrap_or(0),
+                partition: accumulator
+                    .sample_record
+                    .as_ref()
+                    .map(|r| r.partition)
+                    .unwrap_or(0),
+                headers: accumulator
+                    .sample_record
+                    .as_ref()
+                    .map(|r| r.headers.clone())
+                    .unwrap_or_default(),
+            };
 
-                // Send output record directly - no conversion needed!
-                if self.output_sender.send(output_record).is_err() {
-                    // Channel closed, continue processing
-                }
+            // Skip this group if it doesn't pass HAVING filter
+            if !ExpressionEvaluator::evaluate_expression(having_expr, &temp_record)? {
+                return Ok(None);
             }
         }
 
-        Ok(())
+        // Create result record
+        Ok(Some(StreamRecord {
+            fields: result_fields.into(),
+            timestamp: accumulator
+                .sample_record
+                .as_ref()
+                .map(|r| r.timestamp)
+                .unwrap_or(0),
+            offset: accumulator
+                .sample_record
+                .as_ref()
+                .map(|r| r.offset)
+                .unwrap_or(0),
+            partition: accumulator
+                .sample_record
+                .as_ref()
+                .map(|r| r.partition)
+                .unwrap_or(0),
+            headers: accumulator
+                .sample_record
+                .as_ref()
+                .map(|r| r.headers.clone())
+                .unwrap_or_default(),
+        }))
     }
 
     // === PLUGGABLE DATA SOURCE SUPPORT ===
@@ -956,9 +1643,18 @@ impl StreamExecutionEngine {
         // Copy engine state to context
         context.record_count = self.record_count;
         context.group_by_states = self.group_states.clone();
+        context.merge_target_states = std::mem::take(&mut self.merge_target_states);
+        context.state_ttl = self.state_ttl.take();
+        context.join_strategies = self.join_strategies.clone();
+        context.lookup_sources = self.lookup_sources.clone();
+        context.subquery_cache = self.subquery_cache.clone();
+        context.subquery_refresh = self.subquery_refresh;
+        context.state_backends = self.state_backends.clone();
         context.performance_monitor = self.performance_monitor.as_ref().map(Arc::clone);
 
         // Process records from all sources
+        let mut query_plan = Arc::new(CompiledQuery::new(query));
+        let mut sorter = self.result_sorter(query);
         let source_names: Vec<String> = context.list_sources();
         for source_name in &source_names {
             context.set_active_reader(source_name)?;
@@ -972,13 +1668,19 @@ impl StreamExecutionEngine {
 
                 for record in batch {
                     // Apply query processing
+                    context.query_plan = None;
+                    context.query_plan = Some(Self::bound_plan(&mut query_plan, &record));
                     let result = QueryProcessor::process_query(query, &record, &mut context)?;
 
                     // Write result to all sinks if present
                     if let Some(output_record) = result.record {
-                        for sink_idx in 0..sink_uris.len() {
-                            let sink_name = format!("sink_{}", sink_idx);
-                            context.write_to(&sink_name, output_record.clone()).await?;
+                        if let Some(sorter) = sorter.as_mut() {
+                            sorter.push(output_record)?;
+                        } else {
+                            for sink_idx in 0..sink_uris.len() {
+                                let sink_name = format!("sink_{}", sink_idx);
+                                context.write_to(&sink_name, output_record.clone()).await?;
+                            }
                         }
                     }
 
@@ -993,6 +1695,17 @@ impl StreamExecutionEngine {
             context.commit_source(source_name).await?;
         }
 
+        // Write sorted results once all sources are read
+        if let Some(sorter) = sorter {
+            for output_record in sorter.finish()? {
+                let output_record = output_record?;
+                for sink_idx in 0..sink_uris.len() {
+                    let sink_name = format!("sink_{}", sink_idx);
+                    context.write_to(&sink_name, output_record.clone()).await?;
+                }
+            }
+        }
+
         // Flush and commit all sinks
         context.flush_all().await?;
         for sink_idx in 0..sink_uris.len() {
@@ -1002,6 +1715,8 @@ impl StreamExecutionEngine {
 
         // Sync state back to engine
         self.group_states = std::mem::take(&mut context.group_by_states);
+        self.merge_target_states = std::mem::take(&mut context.merge_target_states);
+        self.state_ttl = context.state_ttl.take();
         self.save_window_states_from_context(&context);
 
         Ok(())
@@ -1027,7 +1742,9 @@ impl StreamExecutionEngine {
             })?;
 
         let mut results = Vec::new();
+        let mut sorter = self.result_sorter(query);
         let query_id = self.generate_query_id(query);
+        let mut query_plan = Arc::new(CompiledQuery::new(query));
 
         // Process all records from source
         loop {
@@ -1042,12 +1759,21 @@ impl StreamExecutionEngine {
 
             for record in batch {
                 let mut context = self.create_processor_context(&query_id);
+                context.query_plan = Some(Self::bound_plan(&mut query_plan, &record));
                 context.group_by_states = self.group_states.clone();
+                context.merge_target_states = std::mem::take(&mut self.merge_target_states);
+                context.state_ttl = self.state_ttl.take();
 
-                let result = QueryProcessor::process_query(query, &record, &mut context)?;
+                let result = QueryProcessor::process_query(query, &record, &mut context);
+                self.merge_target_states = std::mem::take(&mut context.merge_target_states);
+                self.state_ttl = context.state_ttl.take();
+                let result = result?;
 
                 if let Some(output_record) = result.record {
-                    results.push(output_record);
+                    match sorter.as_mut() {
+                        Some(sorter) => sorter.push(output_record)?,
+                        None => results.push(output_record),
+                    }
                 }
 
                 // Sync state
@@ -1068,6 +1794,9 @@ impl StreamExecutionEngine {
                 query: None,
             })?;
 
+        if let Some(sorter) = sorter {
+            results = sorter.finish()?.collect::<Result<_, _>>()?;
+        }
         Ok(results)
     }
 
@@ -1079,6 +1808,8 @@ impl StreamExecutionEngine {
         mut writer: Box<dyn DataWriter>,
     ) -> Result<(), SqlError> {
         let query_id = self.generate_query_id(query);
+        let mut query_plan = Arc::new(CompiledQuery::new(query));
+        let mut sorter = self.result_sorter(query);
 
         // Stream processing loop
         loop {
@@ -1091,17 +1822,22 @@ impl StreamExecutionEngine {
 
                     for record in batch {
                         let mut context = self.create_processor_context(&query_id);
+                        context.query_plan = Some(Self::bound_plan(&mut query_plan, &record));
                         context.group_by_states = self.group_states.clone();
+                        context.merge_target_states = std::mem::take(&mut self.merge_target_states);
+                        context.state_ttl = self.state_ttl.take();
 
-                        let result = QueryProcessor::process_query(query, &record, &mut context)?;
+                        let result = QueryProcessor::process_query(query, &record, &mut context);
+                        self.merge_target_states = std::mem::take(&mut context.merge_target_states);
+                        self.state_ttl = context.state_ttl.take();
+                        let result = result?;
 
                         if let Some(output_record) = result.record {
-                            writer.write(output_record).await.map_err(|e| {
-                                SqlError::ExecutionError {
-                                    message: format!("Failed to write output: {}", e),
-                                    query: None,
-                                }
-                            })?;
+                            if let Some(sorter) = sorter.as_mut() {
+                                sorter.push(output_record)?;
+                            } else {
+                                Self::write_output(writer.as_mut(), output_record).await?;
+                            }
                         }
 
                         // Sync state
@@ -1122,6 +1858,13 @@ impl StreamExecutionEngine {
             }
         }
 
+        // Write sorted results once the reader is exhausted
+        if let Some(sorter) = sorter {
+            for output_record in sorter.finish()? {
+                Self::write_output(writer.as_mut(), output_record?).await?;
+            }
+        }
+
         // Commit reader and writer
         reader
             .commit()
@@ -1146,4 +1889,33 @@ impl StreamExecutionEngine {
 
         Ok(())
     }
+
+    async fn write_output(
+        writer: &mut dyn DataWriter,
+        output_record: StreamRecord,
+    ) -> Result<(), SqlError> {
+        writer
+            .write(output_record)
+            .await
+            .map_err(|e| SqlError::ExecutionError {
+                message: format!("Failed to write output: {}", e),
+                query: None,
+            })
+    }
+
+    /// Sorter for the results of a bounded query with ORDER BY, which sorts within the
+    /// job's memory budget and spills sorted runs beyond it. ORDER BY expressions are
+    /// evaluated on the output records.
+    fn result_sorter(&self, query: &StreamingQuery) -> Option<ExternalSorter> {
+        match query {
+            StreamingQuery::Select {
+                order_by: Some(order_by),
+                ..
+            } if !order_by.is_empty() => Some(ExternalSorter::new(
+                order_by.clone(),
+                self.memory_budget.as_ref(),
+            )),
+            _ => None,
+        }
+    }
 }
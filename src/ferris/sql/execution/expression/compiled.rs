@@ -0,0 +1,1061 @@
+//! Compiled expression evaluation.
+//!
+//! [`CompiledExpression`] turns an [`Expr`] into an evaluation plan once per query, so the
+//! per-record work no longer repeats what only depends on the query text:
+//!
+//! - Column references become slots whose lookup is prepared up front: system columns are
+//!   recognized at compile time, dotted names are split once, and plain names are bound to
+//!   their position in the [`RecordSchema`] of the records being processed
+//! - Column values and literals are borrowed instead of cloned for every record
+//! - Function calls are bound to their implementation instead of matched by name
+//! - LIKE patterns given as literals are translated to a regex once
+//! - REGEXP_* patterns given as literals are compiled once; patterns computed from records
+//!   are kept in a [`PatternCache`] of the plan
+//!
+//! Plans can also be evaluated over a [`ColumnarBatch`], where the comparisons, boolean
+//! logic, arithmetic, IN, BETWEEN, LIKE and NULL tests run as vectorized kernels.
+//!
+//! [`CompiledQuery`] holds the plans of one SELECT's WHERE clause and expressions, and the
+//! [`OutputLayout`] of its output records. The engine builds it when the query is planned
+//! and hands it to the processors with every record, so nothing is looked up or compiled
+//! per record.
+//!
+//! A plan gives the same results and errors as
+//! [`ExpressionEvaluator::evaluate_expression_value_with_subqueries`] for the expression it
+//! was compiled from. Expressions whose subqueries need a [`SubqueryExecutor`] are not
+//! compiled; callers keep using the interpreter for them.
+//!
+//! [`SubqueryExecutor`]: super::SubqueryExecutor
+
+use super::evaluator::ExpressionEvaluator;
+use super::functions::{BuiltinFunctions, FunctionImpl, RegexFunctionImpl};
+use super::regex_cache::{self, PatternCache};
+use crate::ferris::sql::ast::{
+    BinaryOperator, Expr, LiteralValue, SelectField, StreamingQuery, UnaryOperator,
+};
+use crate::ferris::sql::error::SqlError;
+use crate::ferris::sql::execution::columnar::{
+    kernels, Bitmap, Column, ColumnarBatch, ColumnarPlan, Datum,
+};
+use crate::ferris::sql::execution::processors::SelectProcessor;
+use crate::ferris::sql::execution::types::{FieldValue, StreamRecord};
+use crate::ferris::sql::execution::{RecordFields, RecordSchema};
+use regex::Regex;
+use rust_decimal::Decimal;
+use std::borrow::Cow;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// An expression compiled into an evaluation plan
+#[derive(Debug, Clone)]
+pub struct CompiledExpression {
+    root: Node,
+    slots: Vec<ColumnSlot>,
+}
+
+impl CompiledExpression {
+    /// Compile an expression, or return `None` if it contains subqueries that need a
+    /// subquery executor
+    pub fn compile(expr: &Expr) -> Option<Self> {
+        let mut compiler = Compiler { slots: Vec::new() };
+        let root = compiler.with_subqueries(expr)?;
+        Some(Self {
+            root,
+            slots: compiler.slots,
+        })
+    }
+
+    /// Evaluate the expression's value against a record
+    pub fn evaluate(&self, record: &StreamRecord) -> Result<FieldValue, SqlError> {
+        self.root.evaluate(record, &self.slots).map(Cow::into_owned)
+    }
+
+    /// Evaluate the expression as a predicate, e.g. a WHERE clause
+    pub fn evaluate_bool(&self, record: &StreamRecord) -> Result<bool, SqlError> {
+        let value = self.root.evaluate(record, &self.slots)?;
+        ExpressionEvaluator::field_value_to_bool(&value)
+    }
+
+    /// Evaluate the expression over every row of a batch
+    ///
+    /// Returns `None` when part of the expression has no vectorized kernel or a row would
+    /// fail; the rows then have to be evaluated one by one.
+    pub fn evaluate_batch<'a>(&'a self, batch: &'a ColumnarBatch) -> Option<Datum<'a>> {
+        self.root.evaluate_batch(batch, &self.slots)
+    }
+
+    /// Evaluate the expression as a predicate over every row of a batch
+    pub fn evaluate_batch_bool(&self, batch: &ColumnarBatch) -> Option<Bitmap> {
+        kernels::truthy(&self.evaluate_batch(batch)?, batch.len())
+    }
+
+    /// Names of the columns the expression reads, one per slot
+    pub fn columns(&self) -> impl Iterator<Item = &str> {
+        self.slots.iter().map(ColumnSlot::name)
+    }
+
+    /// Resolve column references to their position in `schema`
+    ///
+    /// Records laid out by that schema are then read by position; records with another
+    /// layout, like the output of a JOIN, are still read by name.
+    pub fn bind(&mut self, schema: &Arc<RecordSchema>) {
+        for slot in &mut self.slots {
+            if let ColumnSlot::Field { name, position } = slot {
+                *position = schema
+                    .index_of(name)
+                    .map(|index| (Arc::clone(schema), index));
+            }
+        }
+    }
+}
+
+/// The compiled WHERE clause and SELECT expressions of one query
+///
+/// CREATE STREAM/TABLE statements are compiled from their SELECT; other statements have
+/// nothing to compile. Expressions that need the subquery executor are left to the
+/// interpreter.
+#[derive(Debug, Clone, Default)]
+pub struct CompiledQuery {
+    where_clause: Option<CompiledExpression>,
+    /// One plan per SELECT field, `None` for columns and expressions left to the interpreter
+    fields: Vec<Option<CompiledExpression>>,
+    columnar: Option<ColumnarPlan>,
+    output: Option<OutputLayout>,
+    /// Schema the plans are bound to
+    schema: Option<Arc<RecordSchema>>,
+}
+
+/// The schema of a SELECT's output records, shared by all of them
+///
+/// Output records are filled by position: each SELECT field has its slot in the schema,
+/// and a later field with the same name takes the slot of the earlier one.
+#[derive(Debug, Clone)]
+pub struct OutputLayout {
+    schema: Arc<RecordSchema>,
+    /// Schema position of each SELECT field
+    positions: Vec<usize>,
+}
+
+impl OutputLayout {
+    /// Lay out the output of `fields`, or return `None` when a wildcard makes it depend on
+    /// the input records
+    pub fn new(fields: &[SelectField]) -> Option<Self> {
+        let names = fields
+            .iter()
+            .map(|field| match field {
+                SelectField::Wildcard => None,
+                SelectField::Column(name) => Some(name.clone()),
+                SelectField::AliasedColumn { alias, .. } => Some(alias.clone()),
+                SelectField::Expression { expr, alias } => Some(
+                    alias
+                        .clone()
+                        .unwrap_or_else(|| SelectProcessor::get_expression_name(expr)),
+                ),
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let schema = RecordSchema::new(names.iter().cloned());
+        let positions = names
+            .iter()
+            .map(|name| schema.index_of(name).expect("every name is in the schema"))
+            .collect();
+        Some(Self {
+            schema: Arc::new(schema),
+            positions,
+        })
+    }
+
+    /// An output record without values yet
+    pub fn record(&self) -> RecordFields {
+        RecordFields::with_schema(Arc::clone(&self.schema))
+    }
+
+    /// Set the value of the SELECT field at `index` in a record started by
+    /// [`record`](Self::record)
+    #[inline]
+    pub fn set(&self, fields: &mut RecordFields, index: usize, value: FieldValue) {
+        fields.insert_index(self.positions[index], value);
+    }
+}
+
+impl CompiledQuery {
+    /// Compile the expressions of a query
+    pub fn new(query: &StreamingQuery) -> Self {
+        let Some(StreamingQuery::Select {
+            fields: select_fields,
+            where_clause,
+            ..
+        }) = ColumnarPlan::select(query)
+        else {
+            return Self::default();
+        };
+
+        let where_clause = where_clause.as_ref().and_then(CompiledExpression::compile);
+        let fields = select_fields
+            .iter()
+            .map(|field| match field {
+                SelectField::Expression { expr, .. } => CompiledExpression::compile(expr),
+                _ => None,
+            })
+            .collect();
+        let mut compiled = Self {
+            where_clause,
+            fields,
+            columnar: None,
+            output: OutputLayout::new(select_fields),
+            schema: None,
+        };
+        compiled.columnar = ColumnarPlan::new(query, &compiled);
+        compiled
+    }
+
+    /// Plan of the WHERE clause
+    #[inline]
+    pub fn where_clause(&self) -> Option<&CompiledExpression> {
+        self.where_clause.as_ref()
+    }
+
+    /// Plan of the SELECT field at `index`
+    #[inline]
+    pub fn field(&self, index: usize) -> Option<&CompiledExpression> {
+        self.fields.get(index)?.as_ref()
+    }
+
+    /// Columnar plan, or `None` if the query needs the row path
+    pub fn columnar(&self) -> Option<&ColumnarPlan> {
+        self.columnar.as_ref()
+    }
+
+    /// Layout of the output records, or `None` if it depends on the input records
+    #[inline]
+    pub fn output(&self) -> Option<&OutputLayout> {
+        self.output.as_ref()
+    }
+
+    /// Whether the plans are bound to `schema`
+    #[inline]
+    pub fn is_bound_to(&self, schema: &Arc<RecordSchema>) -> bool {
+        self.schema
+            .as_ref()
+            .is_some_and(|bound| Arc::ptr_eq(bound, schema))
+    }
+
+    /// Bind the plans to the schema of the records being processed, see
+    /// [`CompiledExpression::bind`]
+    pub fn bind(&mut self, schema: &Arc<RecordSchema>) {
+        for expr in self
+            .where_clause
+            .iter_mut()
+            .chain(self.fields.iter_mut().flatten())
+        {
+            expr.bind(schema);
+        }
+        self.schema = Some(Arc::clone(schema));
+    }
+}
+
+/// Prepared lookup of a column reference, mirroring the interpreter's column resolution
+#[derive(Debug, Clone)]
+enum ColumnSlot {
+    Timestamp(String),
+    Offset(String),
+    Partition(String),
+    Field {
+        name: String,
+        /// Position in the schema the plan is bound to
+        position: Option<(Arc<RecordSchema>, usize)>,
+    },
+    /// A dotted name: a flat key (JOIN aliases), a path into a STRUCT/MAP field, or
+    /// `table.column`
+    Path {
+        name: String,
+        /// `(field key, remaining path)` candidates, longest prefix first
+        nested: Vec<(String, Vec<String>)>,
+        right_name: String,
+        column_name: String,
+    },
+}
+
+impl ColumnSlot {
+    fn new(name: &str) -> Self {
+        match name.to_uppercase().as_str() {
+            "_TIMESTAMP" => return Self::Timestamp(name.to_string()),
+            "_OFFSET" => return Self::Offset(name.to_string()),
+            "_PARTITION" => return Self::Partition(name.to_string()),
+            _ => {}
+        }
+        if !name.contains('.') {
+            return Self::Field {
+                name: name.to_string(),
+                position: None,
+            };
+        }
+
+        let segments: Vec<&str> = name.split('.').collect();
+        let mut nested = Vec::new();
+        for start in 0..segments.len().min(2) {
+            for end in (start + 1..segments.len()).rev() {
+                let path = segments[end..].iter().map(|s| s.to_string()).collect();
+                nested.push((segments[start..end].join("."), path));
+            }
+        }
+        let column_name = segments[segments.len() - 1].to_string();
+        Self::Path {
+            name: name.to_string(),
+            nested,
+            right_name: format!("right_{}", column_name),
+            column_name,
+        }
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            Self::Timestamp(name)
+            | Self::Offset(name)
+            | Self::Partition(name)
+            | Self::Field { name, .. }
+            | Self::Path { name, .. } => name,
+        }
+    }
+
+    fn resolve<'a>(&self, record: &'a StreamRecord) -> Cow<'a, FieldValue> {
+        let found = match self {
+            Self::Timestamp(_) => return Cow::Owned(FieldValue::Integer(record.timestamp)),
+            Self::Offset(_) => return Cow::Owned(FieldValue::Integer(record.offset)),
+            Self::Partition(_) => {
+                return Cow::Owned(FieldValue::Integer(record.partition as i64));
+            }
+            Self::Field {
+                position: Some((schema, index)),
+                ..
+            } if Arc::ptr_eq(schema, record.fields.schema()) => record.fields.get_index(*index),
+            Self::Field { name, .. } => record.fields.get(name),
+            Self::Path {
+                name,
+                nested,
+                right_name,
+                column_name,
+            } => {
+                if let Some(value) = record.fields.get(name) {
+                    return Cow::Borrowed(value);
+                }
+                for (key, path) in nested {
+                    if let Some(value @ (FieldValue::Struct(_) | FieldValue::Map(_))) =
+                        record.fields.get(key)
+                    {
+                        return path
+                            .iter()
+                            .try_fold(value, |current, field| match current {
+                                FieldValue::Struct(fields) | FieldValue::Map(fields) => {
+                                    fields.get(field)
+                                }
+                                _ => None,
+                            })
+                            .map_or(Cow::Owned(FieldValue::Null), Cow::Borrowed);
+                    }
+                }
+                record
+                    .fields
+                    .get(right_name)
+                    .or_else(|| record.fields.get(column_name))
+            }
+        };
+        found.map_or(Cow::Owned(FieldValue::Null), Cow::Borrowed)
+    }
+}
+
+/// LIKE pattern, translated once when it is a literal
+#[derive(Debug, Clone)]
+enum LikePattern {
+    /// `None` for patterns that are not a valid regex, which never match
+    Literal(Option<Regex>),
+    Dynamic(Box<Node>),
+}
+
+/// Pattern of a REGEXP_* function
+#[derive(Debug, Clone)]
+enum RegexPattern {
+    /// Pattern and flags given as literals, compiled with the plan
+    Literal(Regex),
+    /// Pattern or flags computed per record, compiled once per distinct value; clones of
+    /// the plan share the cache
+    Dynamic(Arc<Mutex<PatternCache>>),
+}
+
+impl RegexPattern {
+    fn compiled(&self, pattern: &str, flags: &str) -> Result<Regex, SqlError> {
+        match self {
+            RegexPattern::Literal(regex) => Ok(regex.clone()),
+            RegexPattern::Dynamic(cache) => cache
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .compiled(pattern, flags),
+        }
+    }
+}
+
+/// Node of an evaluation plan
+#[derive(Debug, Clone)]
+enum Node {
+    Column(usize),
+    Literal(FieldValue),
+    /// Binary operator other than LIKE and IN; `boolean` selects the boolean-context errors
+    Binary {
+        op: BinaryOperator,
+        left: Box<Node>,
+        right: Box<Node>,
+        boolean: bool,
+    },
+    Like {
+        text: Box<Node>,
+        pattern: LikePattern,
+        negated: bool,
+    },
+    /// `list` is `None` when the right side is not a list, which fails once evaluated
+    In {
+        expr: Box<Node>,
+        list: Option<Vec<Node>>,
+        negated: bool,
+        null_is_false: bool,
+    },
+    Not(Box<Node>),
+    IsNull {
+        expr: Box<Node>,
+        negated: bool,
+    },
+    Between {
+        expr: Box<Node>,
+        low: Box<Node>,
+        high: Box<Node>,
+        negated: bool,
+    },
+    Case {
+        when_clauses: Vec<(Node, Node)>,
+        else_clause: Option<Box<Node>>,
+    },
+    /// Bound built-in function; its arguments are evaluated by the function itself
+    Function {
+        function: FunctionImpl,
+        args: Vec<Expr>,
+    },
+    /// Bound REGEXP_* function with its pattern
+    Regex {
+        function: RegexFunctionImpl,
+        args: Vec<Expr>,
+        pattern: RegexPattern,
+    },
+    Subscript {
+        expr: Box<Node>,
+        index: Box<Node>,
+    },
+    FieldAccess {
+        expr: Box<Node>,
+        field: String,
+    },
+    /// Value converted to a boolean, for columns and literals in boolean context
+    ToBool(Box<Node>),
+    /// Left to the interpreter: window functions, lambdas and expressions that only fail
+    Interpreted {
+        expr: Expr,
+        boolean: bool,
+    },
+}
+
+impl Node {
+    fn evaluate<'a>(
+        &'a self,
+        record: &'a StreamRecord,
+        slots: &[ColumnSlot],
+    ) -> Result<Cow<'a, FieldValue>, SqlError> {
+        let value = match self {
+            Node::Column(slot) => return Ok(slots[*slot].resolve(record)),
+            Node::Literal(value) => return Ok(Cow::Borrowed(value)),
+            Node::Binary {
+                op,
+                left,
+                right,
+                boolean,
+            } => {
+                let left = left.evaluate(record, slots)?;
+                let right = right.evaluate(record, slots)?;
+                Self::binary(op, &left, &right, *boolean)?
+            }
+            Node::Like {
+                text,
+                pattern,
+                negated,
+            } => {
+                let text = text.evaluate(record, slots)?;
+                let matched = match pattern {
+                    LikePattern::Literal(regex) => match text.as_ref() {
+                        FieldValue::String(text) => {
+                            Some(regex.as_ref().is_some_and(|re| re.is_match(text)))
+                        }
+                        FieldValue::Null => None,
+                        _ => return Err(Self::like_type_error()),
+                    },
+                    LikePattern::Dynamic(pattern) => {
+                        match (text.as_ref(), pattern.evaluate(record, slots)?.as_ref()) {
+                            (FieldValue::String(text), FieldValue::String(pattern)) => Some(
+                                ExpressionEvaluator::like_regex(pattern)
+                                    .is_some_and(|re| re.is_match(text)),
+                            ),
+                            (FieldValue::Null, _) | (_, FieldValue::Null) => None,
+                            _ => return Err(Self::like_type_error()),
+                        }
+                    }
+                };
+                // NULL operands make both LIKE and NOT LIKE false
+                FieldValue::Boolean(matched.is_some_and(|matched| matched != *negated))
+            }
+            Node::In {
+                expr,
+                list,
+                negated,
+                null_is_false,
+            } => {
+                let value = expr.evaluate(record, slots)?;
+                if *null_is_false && matches!(value.as_ref(), FieldValue::Null) {
+                    return Ok(Cow::Owned(FieldValue::Boolean(false)));
+                }
+                let Some(list) = list else {
+                    let operator = if *negated { "NOT IN" } else { "IN" };
+                    return Err(SqlError::ExecutionError {
+                        message: format!(
+                            "{} operator requires a list or subquery on the right side",
+                            operator
+                        ),
+                        query: None,
+                    });
+                };
+                let mut found = false;
+                for item in list {
+                    if ExpressionEvaluator::values_equal(
+                        &value,
+                        item.evaluate(record, slots)?.as_ref(),
+                    ) {
+                        found = true;
+                        break;
+                    }
+                }
+                FieldValue::Boolean(found != *negated)
+            }
+            Node::Not(expr) => {
+                let value = expr.evaluate(record, slots)?;
+                FieldValue::Boolean(!ExpressionEvaluator::field_value_to_bool(&value)?)
+            }
+            Node::IsNull { expr, negated } => {
+                let is_null = matches!(expr.evaluate(record, slots)?.as_ref(), FieldValue::Null);
+                FieldValue::Boolean(is_null != *negated)
+            }
+            Node::Between {
+                expr,
+                low,
+                high,
+                negated,
+            } => ExpressionEvaluator::between_values(
+                expr.evaluate(record, slots)?.as_ref(),
+                low.evaluate(record, slots)?.as_ref(),
+                high.evaluate(record, slots)?.as_ref(),
+                *negated,
+            )?,
+            Node::Case {
+                when_clauses,
+                else_clause,
+            } => {
+                for (condition, result) in when_clauses {
+                    // Conditions are compiled in boolean context and always yield a BOOLEAN
+                    if matches!(
+                        condition.evaluate(record, slots)?.as_ref(),
+                        FieldValue::Boolean(true)
+                    ) {
+                        return result.evaluate(record, slots);
+                    }
+                }
+                match else_clause {
+                    Some(else_clause) => return else_clause.evaluate(record, slots),
+                    None => FieldValue::Null,
+                }
+            }
+            Node::Function { function, args } => function(args, record)?,
+            Node::Regex {
+                function,
+                args,
+                pattern,
+            } => function(args, record, &mut |text, flags| {
+                pattern.compiled(text, flags)
+            })?,
+            Node::Subscript { expr, index } => ExpressionEvaluator::subscript_value(
+                expr.evaluate(record, slots)?.as_ref(),
+                index.evaluate(record, slots)?.as_ref(),
+            )?,
+            Node::FieldAccess { expr, field } => ExpressionEvaluator::field_access_value(
+                expr.evaluate(record, slots)?.as_ref(),
+                field,
+            )?,
+            Node::ToBool(expr) => {
+                let value = expr.evaluate(record, slots)?;
+                FieldValue::Boolean(ExpressionEvaluator::field_value_to_bool(&value)?)
+            }
+            Node::Interpreted { expr, boolean } => {
+                if *boolean {
+                    FieldValue::Boolean(ExpressionEvaluator::evaluate_expression(expr, record)?)
+                } else {
+                    ExpressionEvaluator::evaluate_expression_value(expr, record)?
+                }
+            }
+        };
+        Ok(Cow::Owned(value))
+    }
+
+    /// Vectorized counterpart of [`evaluate`](Self::evaluate); `None` hands the batch back
+    /// to the row path
+    fn evaluate_batch<'a>(
+        &'a self,
+        batch: &'a ColumnarBatch,
+        slots: &[ColumnSlot],
+    ) -> Option<Datum<'a>> {
+        let len = batch.len();
+        let boolean = |bits: Bitmap| Some(Datum::Column(Cow::Owned(Column::from_bitmap(bits))));
+        match self {
+            Node::Column(slot) => {
+                let column = match &slots[*slot] {
+                    ColumnSlot::Timestamp(_) => batch.timestamps(),
+                    ColumnSlot::Offset(_) => batch.offsets(),
+                    ColumnSlot::Partition(_) => batch.partitions(),
+                    ColumnSlot::Field { name, .. } => batch.column(name)?,
+                    ColumnSlot::Path { .. } => return None,
+                };
+                Some(Datum::Column(Cow::Borrowed(column)))
+            }
+            Node::Literal(value) => Some(Datum::Scalar(Cow::Borrowed(value))),
+            Node::Binary {
+                op,
+                left,
+                right,
+                boolean: boolean_context,
+            } => {
+                let left = left.evaluate_batch(batch, slots)?;
+                let right = right.evaluate_batch(batch, slots)?;
+                match op {
+                    BinaryOperator::Equal
+                    | BinaryOperator::NotEqual
+                    | BinaryOperator::LessThan
+                    | BinaryOperator::LessThanOrEqual
+                    | BinaryOperator::GreaterThan
+                    | BinaryOperator::GreaterThanOrEqual => {
+                        boolean(kernels::compare(op, &left, &right, len)?)
+                    }
+                    BinaryOperator::And => {
+                        boolean(kernels::truthy(&left, len)?.and(&kernels::truthy(&right, len)?))
+                    }
+                    BinaryOperator::Or => {
+                        boolean(kernels::truthy(&left, len)?.or(&kernels::truthy(&right, len)?))
+                    }
+                    BinaryOperator::Add
+                    | BinaryOperator::Subtract
+                    | BinaryOperator::Multiply
+                    | BinaryOperator::Divide
+                        if !boolean_context =>
+                    {
+                        kernels::arithmetic(op, &left, &right, len)
+                            .map(|column| Datum::Column(Cow::Owned(column)))
+                    }
+                    _ => None,
+                }
+            }
+            Node::Like {
+                text,
+                pattern: LikePattern::Literal(regex),
+                negated,
+            } => boolean(kernels::like(
+                &text.evaluate_batch(batch, slots)?,
+                regex.as_ref(),
+                *negated,
+                len,
+            )?),
+            Node::In {
+                expr,
+                list: Some(list),
+                negated,
+                null_is_false,
+            } => {
+                let items = list
+                    .iter()
+                    .map(|item| match item {
+                        Node::Literal(value) => Some(value),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                boolean(kernels::in_list(
+                    &expr.evaluate_batch(batch, slots)?,
+                    &items,
+                    *negated,
+                    *null_is_false,
+                    len,
+                )?)
+            }
+            Node::Not(expr) => {
+                boolean(kernels::truthy(&expr.evaluate_batch(batch, slots)?, len)?.not())
+            }
+            Node::IsNull { expr, negated } => {
+                let is_null = kernels::is_null(&expr.evaluate_batch(batch, slots)?, len);
+                boolean(if *negated { is_null.not() } else { is_null })
+            }
+            Node::Between {
+                expr,
+                low,
+                high,
+                negated,
+            } => boolean(kernels::between(
+                &expr.evaluate_batch(batch, slots)?,
+                &low.evaluate_batch(batch, slots)?,
+                &high.evaluate_batch(batch, slots)?,
+                *negated,
+                len,
+            )?),
+            Node::ToBool(expr) => {
+                boolean(kernels::truthy(&expr.evaluate_batch(batch, slots)?, len)?)
+            }
+            _ => None,
+        }
+    }
+
+    fn binary(
+        op: &BinaryOperator,
+        left: &FieldValue,
+        right: &FieldValue,
+        boolean: bool,
+    ) -> Result<FieldValue, SqlError> {
+        let compare = |op: fn(i32) -> bool| {
+            ExpressionEvaluator::compare_values(left, right, op).map(FieldValue::Boolean)
+        };
+        match op {
+            BinaryOperator::Equal => Ok(FieldValue::Boolean(ExpressionEvaluator::values_equal(
+                left, right,
+            ))),
+            BinaryOperator::NotEqual => Ok(FieldValue::Boolean(
+                !ExpressionEvaluator::values_equal(left, right),
+            )),
+            BinaryOperator::LessThan => compare(|cmp| cmp < 0),
+            BinaryOperator::LessThanOrEqual => compare(|cmp| cmp <= 0),
+            BinaryOperator::GreaterThan => compare(|cmp| cmp > 0),
+            BinaryOperator::GreaterThanOrEqual => compare(|cmp| cmp >= 0),
+            BinaryOperator::And => Ok(FieldValue::Boolean(
+                ExpressionEvaluator::field_value_to_bool(left)?
+                    && ExpressionEvaluator::field_value_to_bool(right)?,
+            )),
+            BinaryOperator::Or => Ok(FieldValue::Boolean(
+                ExpressionEvaluator::field_value_to_bool(left)?
+                    || ExpressionEvaluator::field_value_to_bool(right)?,
+            )),
+            _ if boolean => Err(SqlError::ExecutionError {
+                message: format!("Unsupported binary operator: {:?}", op),
+                query: None,
+            }),
+            BinaryOperator::Add => left.add(right),
+            BinaryOperator::Subtract => left.subtract(right),
+            BinaryOperator::Multiply => left.multiply(right),
+            BinaryOperator::Divide => left.divide(right),
+            BinaryOperator::Concat => ExpressionEvaluator::concat_values(left, right),
+            _ => Err(SqlError::ExecutionError {
+                message: format!("Binary operator {:?} not supported in value context", op),
+                query: None,
+            }),
+        }
+    }
+
+    fn like_type_error() -> SqlError {
+        SqlError::TypeError {
+            expected: "string".to_string(),
+            actual: "non-string".to_string(),
+            value: None,
+        }
+    }
+}
+
+/// Builds plans, assigning one slot per distinct column name
+///
+/// The interpreter evaluates an expression in three contexts with slightly different rules,
+/// and each has its own method here: the subquery-aware layer used for WHERE and SELECT
+/// ([`Self::with_subqueries`]), plain values ([`Self::value`]) and plain booleans such as CASE
+/// conditions ([`Self::boolean`]).
+struct Compiler {
+    slots: Vec<ColumnSlot>,
+}
+
+impl Compiler {
+    /// Mirrors `evaluate_expression_value_with_subqueries`; `None` if a subquery needs an
+    /// executor
+    fn with_subqueries(&mut self, expr: &Expr) -> Option<Node> {
+        match expr {
+            Expr::Subquery { .. } => None,
+            Expr::BinaryOp {
+                left, op, right, ..
+            } => {
+                let left = Box::new(self.with_subqueries(left)?);
+                match (op, right.as_ref()) {
+                    (BinaryOperator::In | BinaryOperator::NotIn, Expr::Subquery { .. }) => None,
+                    (BinaryOperator::In | BinaryOperator::NotIn, right) => Some(Node::In {
+                        expr: left,
+                        list: match right {
+                            Expr::List(items, _) => Some(
+                                items
+                                    .iter()
+                                    .map(|item| self.with_subqueries(item))
+                                    .collect::<Option<_>>()?,
+                            ),
+                            _ => None,
+                        },
+                        negated: matches!(op, BinaryOperator::NotIn),
+                        null_is_false: true,
+                    }),
+                    (BinaryOperator::Like | BinaryOperator::NotLike, right) => {
+                        let pattern = self.like_pattern(right, Self::with_subqueries)?;
+                        Some(Node::Like {
+                            text: left,
+                            pattern,
+                            negated: matches!(op, BinaryOperator::NotLike),
+                        })
+                    }
+                    (_, right) => Some(Node::Binary {
+                        op: op.clone(),
+                        left,
+                        right: Box::new(self.with_subqueries(right)?),
+                        boolean: false,
+                    }),
+                }
+            }
+            _ => Some(self.value(expr)),
+        }
+    }
+
+    /// Mirrors `evaluate_expression_value`
+    fn value(&mut self, expr: &Expr) -> Node {
+        match expr {
+            Expr::Column(name, _) => Node::Column(self.slot(name)),
+            Expr::Literal(literal, _) => match Self::literal(literal) {
+                Some(value) => Node::Literal(value),
+                None => Self::interpreted(expr, false),
+            },
+            Expr::BinaryOp {
+                left, op, right, ..
+            } => {
+                let left = Box::new(self.value(left));
+                match op {
+                    BinaryOperator::In | BinaryOperator::NotIn => Node::In {
+                        expr: left,
+                        list: match right.as_ref() {
+                            Expr::List(items, _) => {
+                                Some(items.iter().map(|item| self.value(item)).collect())
+                            }
+                            _ => None,
+                        },
+                        negated: matches!(op, BinaryOperator::NotIn),
+                        null_is_false: false,
+                    },
+                    BinaryOperator::Like | BinaryOperator::NotLike => Node::Like {
+                        text: left,
+                        pattern: self
+                            .like_pattern(right, |compiler, expr| Some(compiler.value(expr)))
+                            .expect("value compilation always succeeds"),
+                        negated: matches!(op, BinaryOperator::NotLike),
+                    },
+                    _ => Node::Binary {
+                        op: op.clone(),
+                        left,
+                        right: Box::new(self.value(right)),
+                        boolean: false,
+                    },
+                }
+            }
+            Expr::Function { name, args, .. } => {
+                let name = name.to_uppercase();
+                if let Some((function, flags)) = BuiltinFunctions::lookup_regex_function(&name) {
+                    return Node::Regex {
+                        function,
+                        args: args.clone(),
+                        pattern: Self::regex_pattern(args, flags),
+                    };
+                }
+                match BuiltinFunctions::lookup_function(&name) {
+                    Some(function) => Node::Function {
+                        function,
+                        args: args.clone(),
+                    },
+                    None => Self::interpreted(expr, false),
+                }
+            }
+            Expr::UnaryOp {
+                op, expr: inner, ..
+            } => match op {
+                UnaryOperator::Not => Node::Not(Box::new(self.value(inner))),
+                UnaryOperator::IsNull | UnaryOperator::IsNotNull => Node::IsNull {
+                    expr: Box::new(self.value(inner)),
+                    negated: matches!(op, UnaryOperator::IsNotNull),
+                },
+                _ => Self::interpreted(expr, false),
+            },
+            Expr::Between {
+                expr,
+                low,
+                high,
+                negated,
+                ..
+            } => Node::Between {
+                expr: Box::new(self.value(expr)),
+                low: Box::new(self.value(low)),
+                high: Box::new(self.value(high)),
+                negated: *negated,
+            },
+            Expr::Case {
+                when_clauses,
+                else_clause,
+                ..
+            } => Node::Case {
+                when_clauses: when_clauses
+                    .iter()
+                    .map(|(condition, result)| (self.boolean(condition), self.value(result)))
+                    .collect(),
+                else_clause: else_clause
+                    .as_ref()
+                    .map(|else_clause| Box::new(self.value(else_clause))),
+            },
+            Expr::Subscript { expr, index, .. } => Node::Subscript {
+                expr: Box::new(self.value(expr)),
+                index: Box::new(self.value(index)),
+            },
+            Expr::FieldAccess { expr, field, .. } => Node::FieldAccess {
+                expr: Box::new(self.value(expr)),
+                field: field.clone(),
+            },
+            Expr::WindowFunction { .. }
+            | Expr::Subquery { .. }
+            | Expr::List(_, _)
+            | Expr::Lambda { .. }
+            | Expr::Parameter(_, _) => Self::interpreted(expr, false),
+        }
+    }
+
+    /// Mirrors `evaluate_expression`; the resulting node always yields a BOOLEAN
+    fn boolean(&mut self, expr: &Expr) -> Node {
+        match expr {
+            Expr::Column(_, _) | Expr::Subscript { .. } | Expr::FieldAccess { .. } => {
+                Node::ToBool(Box::new(self.value(expr)))
+            }
+            Expr::Literal(literal, _) if Self::literal(literal).is_some() => {
+                Node::ToBool(Box::new(self.value(expr)))
+            }
+            Expr::BinaryOp {
+                left, op, right, ..
+            } => {
+                let left = Box::new(self.value(left));
+                match op {
+                    BinaryOperator::In | BinaryOperator::NotIn => Node::In {
+                        expr: left,
+                        list: match right.as_ref() {
+                            Expr::List(items, _) => {
+                                Some(items.iter().map(|item| self.value(item)).collect())
+                            }
+                            _ => None,
+                        },
+                        negated: matches!(op, BinaryOperator::NotIn),
+                        null_is_false: true,
+                    },
+                    BinaryOperator::Like | BinaryOperator::NotLike => Node::Like {
+                        text: left,
+                        pattern: self
+                            .like_pattern(right, |compiler, expr| Some(compiler.value(expr)))
+                            .expect("value compilation always succeeds"),
+                        negated: matches!(op, BinaryOperator::NotLike),
+                    },
+                    _ => Node::Binary {
+                        op: op.clone(),
+                        left,
+                        right: Box::new(self.value(right)),
+                        boolean: true,
+                    },
+                }
+            }
+            Expr::UnaryOp {
+                op, expr: inner, ..
+            } => match op {
+                UnaryOperator::Not => Node::Not(Box::new(self.boolean(inner))),
+                UnaryOperator::IsNull | UnaryOperator::IsNotNull => Node::IsNull {
+                    expr: Box::new(self.value(inner)),
+                    negated: matches!(op, UnaryOperator::IsNotNull),
+                },
+                _ => Self::interpreted(expr, true),
+            },
+            Expr::Between { .. } => self.value(expr),
+            _ => Self::interpreted(expr, true),
+        }
+    }
+
+    fn like_pattern(
+        &mut self,
+        pattern: &Expr,
+        compile: fn(&mut Self, &Expr) -> Option<Node>,
+    ) -> Option<LikePattern> {
+        match pattern {
+            Expr::Literal(LiteralValue::String(pattern), _) => Some(LikePattern::Literal(
+                ExpressionEvaluator::like_regex(pattern),
+            )),
+            _ => Some(LikePattern::Dynamic(Box::new(compile(self, pattern)?))),
+        }
+    }
+
+    /// Compile the pattern of a REGEXP_* call when it and its flags are string literals;
+    /// invalid patterns are left to fail when evaluated, like other patterns
+    fn regex_pattern(args: &[Expr], flags: Option<usize>) -> RegexPattern {
+        fn literal(arg: Option<&Expr>) -> Option<&str> {
+            match arg {
+                Some(Expr::Literal(LiteralValue::String(text), _)) => Some(text),
+                _ => None,
+            }
+        }
+        let flags = match flags.map(|index| args.get(index)) {
+            None | Some(None) => Some(""),
+            Some(arg) => literal(arg),
+        };
+        match (literal(args.get(1)), flags) {
+            (Some(pattern), Some(flags)) => match regex_cache::compile(pattern, flags) {
+                Ok(regex) => RegexPattern::Literal(regex),
+                Err(_) => RegexPattern::Dynamic(Default::default()),
+            },
+            _ => RegexPattern::Dynamic(Default::default()),
+        }
+    }
+
+    fn slot(&mut self, name: &str) -> usize {
+        match self.slots.iter().position(|slot| slot.name() == name) {
+            Some(index) => index,
+            None => {
+                self.slots.push(ColumnSlot::new(name));
+                self.slots.len() - 1
+            }
+        }
+    }
+
+    /// Literal value, or `None` for invalid DECIMAL literals, which fail when evaluated
+    fn literal(literal: &LiteralValue) -> Option<FieldValue> {
+        Some(match literal {
+            LiteralValue::String(s) => FieldValue::String(s.clone()),
+            LiteralValue::Integer(i) => FieldValue::Integer(*i),
+            LiteralValue::Float(f) => FieldValue::Float(*f),
+            LiteralValue::Boolean(b) => FieldValue::Boolean(*b),
+            LiteralValue::Null => FieldValue::Null,
+            LiteralValue::Decimal(s) => FieldValue::Decimal(Decimal::from_str(s).ok()?),
+            LiteralValue::Interval { value, unit } => FieldValue::Interval {
+                value: *value,
+                unit: unit.clone(),
+            },
+        })
+    }
+
+    fn interpreted(expr: &Expr, boolean: bool) -> Node {
+        Node::Interpreted {
+            expr: expr.clone(),
+            boolean,
+        }
+    }
+}
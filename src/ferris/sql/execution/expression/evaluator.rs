@@ -22,45 +22,13 @@ impl ExpressionEvaluator {
     /// the expression result, with proper SQL NULL handling.
     pub fn evaluate_expression(expr: &Expr, record: &StreamRecord) -> Result<bool, SqlError> {
         match expr {
-            Expr::Column(name) => {
-                // Check for system columns first (case insensitive)
-                let field_value = match name.to_uppercase().as_str() {
-                    "_TIMESTAMP" => FieldValue::Integer(record.timestamp),
-                    "_OFFSET" => FieldValue::Integer(record.offset),
-                    "_PARTITION" => FieldValue::Integer(record.partition as i64),
-                    _ => {
-                        // Handle qualified column names (table.column)
-                        if name.contains('.') {
-                            // Try to find the field with the qualified name first (for JOIN aliases)
-                            if let Some(value) = record.fields.get(name) {
-                                value.clone()
-                            } else {
-                                let column_name = name.split('.').next_back().unwrap_or(name);
-                                // Try to find with the "right_" prefix (for non-aliased JOINs)
-                                let prefixed_name = format!("right_{}", column_name);
-                                if let Some(value) = record.fields.get(&prefixed_name) {
-                                    value.clone()
-                                } else {
-                                    // Fall back to just the column name (for FROM clause aliases like l.name -> name)
-                                    // Return NULL if not found instead of error
-                                    record
-                                        .fields
-                                        .get(column_name)
-                                        .cloned()
-                                        .unwrap_or(FieldValue::Null)
-                                }
-                            }
-                        } else {
-                            // Regular field lookup - return NULL if not found instead of error
-                            record.fields.get(name).cloned().unwrap_or(FieldValue::Null)
-                        }
-                    }
-                };
+            Expr::Column(name, _) => {
+                let field_value = Self::resolve_column(name, record);
 
                 // Convert to boolean
                 Self::field_value_to_bool(&field_value)
             }
-            Expr::Literal(literal) => {
+            Expr::Literal(literal, _) => {
                 let field_value = match literal {
                     LiteralValue::String(s) => FieldValue::String(s.clone()),
                     LiteralValue::Integer(i) => FieldValue::Integer(*i),
@@ -86,7 +54,9 @@ impl ExpressionEvaluator {
                 };
                 Self::field_value_to_bool(&field_value)
             }
-            Expr::BinaryOp { left, op, right } => {
+            Expr::BinaryOp {
+                left, op, right, ..
+            } => {
                 match op {
                     // Handle IN/NOT IN operators specially - they need the List structure preserved
                     BinaryOperator::In => {
@@ -98,7 +68,7 @@ impl ExpressionEvaluator {
                         }
 
                         match &**right {
-                            Expr::List(values) => {
+                            Expr::List(values, _) => {
                                 for value_expr in values {
                                     let value =
                                         Self::evaluate_expression_value(value_expr, record)?;
@@ -131,7 +101,7 @@ impl ExpressionEvaluator {
                         }
 
                         match &**right {
-                            Expr::List(values) => {
+                            Expr::List(values, _) => {
                                 for value_expr in values {
                                     let value =
                                         Self::evaluate_expression_value(value_expr, record)?;
@@ -216,6 +186,7 @@ impl ExpressionEvaluator {
             Expr::Subquery {
                 query: _,
                 subquery_type,
+                ..
             } => {
                 // Handle subqueries in boolean context
                 use crate::ferris::sql::ast::SubqueryType;
@@ -244,6 +215,7 @@ impl ExpressionEvaluator {
             Expr::UnaryOp {
                 op,
                 expr: inner_expr,
+                ..
             } => {
                 use crate::ferris::sql::ast::UnaryOperator;
                 match op {
@@ -268,6 +240,10 @@ impl ExpressionEvaluator {
                     }),
                 }
             }
+            Expr::Subscript { .. } | Expr::FieldAccess { .. } => {
+                let value = Self::evaluate_expression_value(expr, record)?;
+                Self::field_value_to_bool(&value)
+            }
             Expr::Between { .. } => {
                 // BETWEEN expressions always evaluate to boolean
                 let result = Self::evaluate_expression_value(expr, record)?;
@@ -295,42 +271,8 @@ impl ExpressionEvaluator {
         record: &StreamRecord,
     ) -> Result<FieldValue, SqlError> {
         match expr {
-            Expr::Column(name) => {
-                // Check for system columns first (case insensitive)
-                match name.to_uppercase().as_str() {
-                    "_TIMESTAMP" => Ok(FieldValue::Integer(record.timestamp)),
-                    "_OFFSET" => Ok(FieldValue::Integer(record.offset)),
-                    "_PARTITION" => Ok(FieldValue::Integer(record.partition as i64)),
-                    _ => {
-                        // Handle qualified column names (table.column)
-                        if name.contains('.') {
-                            // Try to find the field with the qualified name first (for JOIN aliases)
-                            if let Some(value) = record.fields.get(name) {
-                                Ok(value.clone())
-                            } else {
-                                let column_name = name.split('.').next_back().unwrap_or(name);
-                                // Try to find with the "right_" prefix (for non-aliased JOINs)
-                                let prefixed_name = format!("right_{}", column_name);
-                                if let Some(value) = record.fields.get(&prefixed_name) {
-                                    Ok(value.clone())
-                                } else {
-                                    // Fall back to just the column name (for FROM clause aliases like l.name -> name)
-                                    // Return NULL if not found instead of error
-                                    Ok(record
-                                        .fields
-                                        .get(column_name)
-                                        .cloned()
-                                        .unwrap_or(FieldValue::Null))
-                                }
-                            }
-                        } else {
-                            // Regular field lookup - return NULL if not found instead of error
-                            Ok(record.fields.get(name).cloned().unwrap_or(FieldValue::Null))
-                        }
-                    }
-                }
-            }
-            Expr::Literal(literal) => match literal {
+            Expr::Column(name, _) => Ok(Self::resolve_column(name, record)),
+            Expr::Literal(literal, _) => match literal {
                 LiteralValue::String(s) => Ok(FieldValue::String(s.clone())),
                 LiteralValue::Integer(i) => Ok(FieldValue::Integer(*i)),
                 LiteralValue::Float(f) => Ok(FieldValue::Float(*f)),
@@ -350,9 +292,13 @@ impl ExpressionEvaluator {
                     unit: unit.clone(),
                 }),
             },
-            Expr::BinaryOp { left, op, right } => {
+            Expr::BinaryOp { left, op, right, .. } => {
                 let left_val = Self::evaluate_expression_value(left, record)?;
-                let right_val = Self::evaluate_expression_value(right, record)?;
+                let right_val = match op {
+                    // The list on the right of IN/NOT IN is evaluated element by element below
+                    BinaryOperator::In | BinaryOperator::NotIn => FieldValue::Null,
+                    _ => Self::evaluate_expression_value(right, record)?,
+                };
 
                 match op {
                     // Arithmetic operators
@@ -362,110 +308,7 @@ impl ExpressionEvaluator {
                     BinaryOperator::Divide => left_val.divide(&right_val),
 
                     // String concatenation operator
-                    BinaryOperator::Concat => match (&left_val, &right_val) {
-                        // Handle NULL values first (SQL standard: concatenation with NULL returns NULL)
-                        (FieldValue::Null, _) | (_, FieldValue::Null) => Ok(FieldValue::Null),
-                        (FieldValue::String(s1), FieldValue::String(s2)) => {
-                            Ok(FieldValue::String(format!("{}{}", s1, s2)))
-                        }
-                        (FieldValue::String(s), other) | (other, FieldValue::String(s)) => {
-                            // Convert non-string to string representation and concatenate
-                            let other_str = match other {
-                                FieldValue::String(s) => s.clone(),
-                                FieldValue::Integer(i) => i.to_string(),
-                                FieldValue::Float(f) => f.to_string(),
-                                FieldValue::Boolean(b) => b.to_string(),
-                                FieldValue::ScaledInteger(value, scale) => {
-                                    // Format scaled integer as decimal
-                                    let divisor = 10_i64.pow(*scale as u32);
-                                    if divisor == 1 {
-                                        value.to_string()
-                                    } else {
-                                        format!(
-                                            "{:.prec$}",
-                                            *value as f64 / divisor as f64,
-                                            prec = *scale as usize
-                                        )
-                                    }
-                                }
-                                FieldValue::Timestamp(ts) => ts.to_string(),
-                                FieldValue::Date(d) => d.to_string(),
-                                FieldValue::Decimal(d) => d.to_string(),
-                                FieldValue::Array(arr) => format!("{:?}", arr),
-                                FieldValue::Map(map) => format!("{:?}", map),
-                                FieldValue::Struct(s) => format!("{:?}", s),
-                                FieldValue::Interval { value, unit } => {
-                                    format!("{} {:?}", value, unit)
-                                }
-                                FieldValue::Null => unreachable!("NULL values handled above"),
-                            };
-                            if left_val == *other {
-                                Ok(FieldValue::String(format!("{}{}", other_str, s)))
-                            } else {
-                                Ok(FieldValue::String(format!("{}{}", s, other_str)))
-                            }
-                        }
-                        (left, right) => {
-                            // Convert both non-string values to strings and concatenate
-                            let left_str = match left {
-                                FieldValue::String(s) => s.clone(),
-                                FieldValue::Integer(i) => i.to_string(),
-                                FieldValue::Float(f) => f.to_string(),
-                                FieldValue::Boolean(b) => b.to_string(),
-                                FieldValue::ScaledInteger(value, scale) => {
-                                    let divisor = 10_i64.pow(*scale as u32);
-                                    if divisor == 1 {
-                                        value.to_string()
-                                    } else {
-                                        format!(
-                                            "{:.prec$}",
-                                            *value as f64 / divisor as f64,
-                                            prec = *scale as usize
-                                        )
-                                    }
-                                }
-                                FieldValue::Timestamp(ts) => ts.to_string(),
-                                FieldValue::Date(d) => d.to_string(),
-                                FieldValue::Decimal(d) => d.to_string(),
-                                FieldValue::Array(arr) => format!("{:?}", arr),
-                                FieldValue::Map(map) => format!("{:?}", map),
-                                FieldValue::Struct(s) => format!("{:?}", s),
-                                FieldValue::Interval { value, unit } => {
-                                    format!("{} {:?}", value, unit)
-                                }
-                                FieldValue::Null => "".to_string(),
-                            };
-                            let right_str = match right {
-                                FieldValue::String(s) => s.clone(),
-                                FieldValue::Integer(i) => i.to_string(),
-                                FieldValue::Float(f) => f.to_string(),
-                                FieldValue::Boolean(b) => b.to_string(),
-                                FieldValue::ScaledInteger(value, scale) => {
-                                    let divisor = 10_i64.pow(*scale as u32);
-                                    if divisor == 1 {
-                                        value.to_string()
-                                    } else {
-                                        format!(
-                                            "{:.prec$}",
-                                            *value as f64 / divisor as f64,
-                                            prec = *scale as usize
-                                        )
-                                    }
-                                }
-                                FieldValue::Timestamp(ts) => ts.to_string(),
-                                FieldValue::Date(d) => d.to_string(),
-                                FieldValue::Decimal(d) => d.to_string(),
-                                FieldValue::Array(arr) => format!("{:?}", arr),
-                                FieldValue::Map(map) => format!("{:?}", map),
-                                FieldValue::Struct(s) => format!("{:?}", s),
-                                FieldValue::Interval { value, unit } => {
-                                    format!("{} {:?}", value, unit)
-                                }
-                                FieldValue::Null => "".to_string(),
-                            };
-                            Ok(FieldValue::String(format!("{}{}", left_str, right_str)))
-                        }
-                    },
+                    BinaryOperator::Concat => Self::concat_values(&left_val, &right_val),
 
                     // Comparison operators - return boolean values
                     BinaryOperator::Equal => Ok(FieldValue::Boolean(Self::values_equal(
@@ -533,7 +376,7 @@ impl ExpressionEvaluator {
                     BinaryOperator::In => {
                         // For IN operator, right side should be a list or subquery
                         match &**right {
-                            Expr::List(values) => {
+                            Expr::List(values, _) => {
                                 for value_expr in values {
                                     let value =
                                         Self::evaluate_expression_value(value_expr, record)?;
@@ -560,7 +403,7 @@ impl ExpressionEvaluator {
                     BinaryOperator::NotIn => {
                         // For NOT IN operator, right side should be a list or subquery
                         match &**right {
-                            Expr::List(values) => {
+                            Expr::List(values, _) => {
                                 for value_expr in values {
                                     let value =
                                         Self::evaluate_expression_value(value_expr, record)?;
@@ -594,7 +437,7 @@ impl ExpressionEvaluator {
             Expr::Function { .. } => BuiltinFunctions::evaluate_function(expr, record),
             Expr::Subquery {
                 query: _,
-                subquery_type,
+                subquery_type, ..
             } => {
                 // Handle subqueries based on their type
                 use crate::ferris::sql::ast::SubqueryType;
@@ -629,7 +472,7 @@ impl ExpressionEvaluator {
                     }),
                 }
             }
-            Expr::List(_) => {
+            Expr::List(_, _) => {
                 // List expressions are handled differently based on context
                 // For now, return an error for standalone list evaluation
                 Err(SqlError::ExecutionError {
@@ -639,7 +482,7 @@ impl ExpressionEvaluator {
             }
             Expr::UnaryOp {
                 op,
-                expr: inner_expr,
+                expr: inner_expr, ..
             } => {
                 use crate::ferris::sql::ast::UnaryOperator;
                 match op {
@@ -681,63 +524,16 @@ impl ExpressionEvaluator {
                 expr,
                 low,
                 high,
-                negated,
+                negated, ..
             } => {
                 let expr_val = Self::evaluate_expression_value(expr, record)?;
                 let low_val = Self::evaluate_expression_value(low, record)?;
                 let high_val = Self::evaluate_expression_value(high, record)?;
-
-                // Handle NULL values according to SQL standards
-                if matches!(expr_val, FieldValue::Null)
-                    || matches!(low_val, FieldValue::Null)
-                    || matches!(high_val, FieldValue::Null)
-                {
-                    return Ok(FieldValue::Boolean(false));
-                }
-
-                let result = match (&expr_val, &low_val, &high_val) {
-                    // Integer comparisons
-                    (FieldValue::Integer(e), FieldValue::Integer(l), FieldValue::Integer(h)) => {
-                        e >= l && e <= h
-                    }
-                    // Float comparisons
-                    (FieldValue::Float(e), FieldValue::Float(l), FieldValue::Float(h)) => {
-                        e >= l && e <= h
-                    }
-                    // ScaledInteger comparisons (financial precision)
-                    (
-                        FieldValue::ScaledInteger(e_val, e_scale),
-                        FieldValue::ScaledInteger(l_val, l_scale),
-                        FieldValue::ScaledInteger(h_val, h_scale),
-                    ) => {
-                        // Normalize scales for proper comparison
-                        let max_scale = (*e_scale).max(*l_scale).max(*h_scale);
-                        let e_normalized = *e_val * 10_i64.pow((max_scale - e_scale) as u32);
-                        let l_normalized = *l_val * 10_i64.pow((max_scale - l_scale) as u32);
-                        let h_normalized = *h_val * 10_i64.pow((max_scale - h_scale) as u32);
-
-                        e_normalized >= l_normalized && e_normalized <= h_normalized
-                    }
-                    // String comparisons (lexicographic)
-                    (FieldValue::String(e), FieldValue::String(l), FieldValue::String(h)) => {
-                        e >= l && e <= h
-                    }
-                    // Mixed type comparisons - convert to common type
-                    _ => {
-                        // Try to convert all to Float for comparison
-                        let e_float = Self::to_comparable_float(&expr_val)?;
-                        let l_float = Self::to_comparable_float(&low_val)?;
-                        let h_float = Self::to_comparable_float(&high_val)?;
-
-                        e_float >= l_float && e_float <= h_float
-                    }
-                };
-
-                Ok(FieldValue::Boolean(if *negated { !result } else { result }))
+                Self::between_values(&expr_val, &low_val, &high_val, *negated)
             }
             Expr::Case {
                 when_clauses,
-                else_clause,
+                else_clause, ..
             } => {
                 // Evaluate each WHEN condition until one is true
                 for (condition, result) in when_clauses {
@@ -757,7 +553,7 @@ impl ExpressionEvaluator {
             Expr::WindowFunction {
                 function_name,
                 args,
-                over_clause,
+                over_clause, ..
             } => {
                 // Delegate to window function evaluator
                 // For now, use empty window buffer - this should be populated by WindowProcessor
@@ -770,12 +566,314 @@ impl ExpressionEvaluator {
                     &empty_buffer,
                 )
             }
+            Expr::Subscript { expr, index, .. } => {
+                let value = Self::evaluate_expression_value(expr, record)?;
+                let index = Self::evaluate_expression_value(index, record)?;
+                Self::subscript_value(&value, &index)
+            }
+            Expr::FieldAccess { expr, field, .. } => {
+                let value = Self::evaluate_expression_value(expr, record)?;
+                Self::field_access_value(&value, field)
+            }
+            Expr::Lambda { .. } => Err(SqlError::ExecutionError {
+                message: "Lambda expressions can only be used as arguments to higher-order functions such as TRANSFORM or FILTER".to_string(),
+                query: None,
+            }),
+            Expr::Parameter(parameter, _) => Err(SqlError::ExecutionError {
+                message: format!(
+                    "Parameter {} is not bound - execute the query through a PreparedStatement",
+                    parameter
+                ),
+                query: None,
+            }),
+        }
+    }
+
+    /// Resolve a column reference against a record.
+    ///
+    /// Dotted names are tried as a flat key first (JOIN aliases), then as a path into a
+    /// STRUCT/MAP field (`payload.customer.id`, optionally qualified by a stream alias),
+    /// and finally as `table.column`. Unknown columns resolve to NULL.
+    fn resolve_column(name: &str, record: &StreamRecord) -> FieldValue {
+        // Check for system columns first (case insensitive)
+        match name.to_uppercase().as_str() {
+            "_TIMESTAMP" => return FieldValue::Integer(record.timestamp),
+            "_OFFSET" => return FieldValue::Integer(record.offset),
+            "_PARTITION" => return FieldValue::Integer(record.partition as i64),
+            _ => {}
+        }
+
+        if let Some(value) = record.fields.get(name) {
+            return value.clone();
+        }
+        if !name.contains('.') {
+            return FieldValue::Null;
+        }
+
+        // Nested field access: the longest prefix naming a STRUCT/MAP field, then walk the rest
+        let segments: Vec<&str> = name.split('.').collect();
+        for start in 0..segments.len().min(2) {
+            for end in (start + 1..segments.len()).rev() {
+                let key = segments[start..end].join(".");
+                if let Some(value @ (FieldValue::Struct(_) | FieldValue::Map(_))) =
+                    record.fields.get(&key)
+                {
+                    return segments[end..]
+                        .iter()
+                        .try_fold(value, |current, field| match current {
+                            FieldValue::Struct(fields) | FieldValue::Map(fields) => {
+                                fields.get(*field)
+                            }
+                            _ => None,
+                        })
+                        .cloned()
+                        .unwrap_or(FieldValue::Null);
+                }
+            }
+        }
+
+        // Qualified column names (table.column)
+        let column_name = name.split('.').next_back().unwrap_or(name);
+        // Try to find with the "right_" prefix (for non-aliased JOINs)
+        let prefixed_name = format!("right_{}", column_name);
+        if let Some(value) = record.fields.get(&prefixed_name) {
+            value.clone()
+        } else {
+            // Fall back to just the column name (for FROM clause aliases like l.name -> name)
+            record
+                .fields
+                .get(column_name)
+                .cloned()
+                .unwrap_or(FieldValue::Null)
+        }
+    }
+
+    /// Apply a subscript: zero-based array index (negative counts from the end) or map/struct key.
+    /// Missing elements and NULL inputs yield NULL.
+    pub fn subscript_value(value: &FieldValue, index: &FieldValue) -> Result<FieldValue, SqlError> {
+        match (value, index) {
+            (FieldValue::Null, _) | (_, FieldValue::Null) => Ok(FieldValue::Null),
+            (FieldValue::Array(items), FieldValue::Integer(i)) => {
+                let position = if *i < 0 { items.len() as i64 + i } else { *i };
+                Ok(usize::try_from(position)
+                    .ok()
+                    .and_then(|p| items.get(p))
+                    .cloned()
+                    .unwrap_or(FieldValue::Null))
+            }
+            (FieldValue::Map(fields) | FieldValue::Struct(fields), key) => Ok(fields
+                .get(&key.to_display_string())
+                .cloned()
+                .unwrap_or(FieldValue::Null)),
+            (FieldValue::Array(_), other) => Err(SqlError::TypeError {
+                expected: "INTEGER array index".to_string(),
+                actual: other.type_name().to_string(),
+                value: Some(other.to_display_string()),
+            }),
+            (other, _) => Err(SqlError::TypeError {
+                expected: "ARRAY, MAP or STRUCT for subscript".to_string(),
+                actual: other.type_name().to_string(),
+                value: None,
+            }),
+        }
+    }
+
+    /// Access a named field of a STRUCT/MAP value; missing fields and NULL yield NULL
+    pub fn field_access_value(value: &FieldValue, field: &str) -> Result<FieldValue, SqlError> {
+        match value {
+            FieldValue::Null => Ok(FieldValue::Null),
+            FieldValue::Struct(fields) | FieldValue::Map(fields) => {
+                Ok(fields.get(field).cloned().unwrap_or(FieldValue::Null))
+            }
+            other => Err(SqlError::TypeError {
+                expected: format!("STRUCT or MAP for field access '.{}'", field),
+                actual: other.type_name().to_string(),
+                value: None,
+            }),
         }
     }
 
     // Helper methods for comparison and conversion
 
-    fn field_value_to_bool(value: &FieldValue) -> Result<bool, SqlError> {
+    /// Concatenate two values with `||`: NULL on either side yields NULL, other values are
+    /// converted to their string form
+    pub(crate) fn concat_values(
+        left_val: &FieldValue,
+        right_val: &FieldValue,
+    ) -> Result<FieldValue, SqlError> {
+        match (left_val, right_val) {
+            // Handle NULL values first (SQL standard: concatenation with NULL returns NULL)
+            (FieldValue::Null, _) | (_, FieldValue::Null) => Ok(FieldValue::Null),
+            (FieldValue::String(s1), FieldValue::String(s2)) => {
+                Ok(FieldValue::String(format!("{}{}", s1, s2)))
+            }
+            (FieldValue::String(s), other) | (other, FieldValue::String(s)) => {
+                // Convert non-string to string representation and concatenate
+                let other_str = match other {
+                    FieldValue::String(s) => s.clone(),
+                    FieldValue::Integer(i) => i.to_string(),
+                    FieldValue::Float(f) => f.to_string(),
+                    FieldValue::Boolean(b) => b.to_string(),
+                    FieldValue::ScaledInteger(value, scale) => {
+                        // Format scaled integer as decimal
+                        let divisor = 10_i64.pow(*scale as u32);
+                        if divisor == 1 {
+                            value.to_string()
+                        } else {
+                            format!(
+                                "{:.prec$}",
+                                *value as f64 / divisor as f64,
+                                prec = *scale as usize
+                            )
+                        }
+                    }
+                    FieldValue::Timestamp(ts) => ts.to_string(),
+                    FieldValue::TimestampTz(ts) => ts.to_string(),
+                    FieldValue::Date(d) => d.to_string(),
+                    FieldValue::Decimal(d) => d.to_string(),
+                    FieldValue::Array(arr) => format!("{:?}", arr),
+                    FieldValue::Map(map) => format!("{:?}", map),
+                    FieldValue::Struct(s) => format!("{:?}", s),
+                    FieldValue::Interval { value, unit } => {
+                        format!("{} {:?}", value, unit)
+                    }
+                    FieldValue::Bytes(_) | FieldValue::Uuid(_) | FieldValue::Time(_) => {
+                        other.to_display_string()
+                    }
+                    FieldValue::Null => unreachable!("NULL values handled above"),
+                };
+                if left_val == other {
+                    Ok(FieldValue::String(format!("{}{}", other_str, s)))
+                } else {
+                    Ok(FieldValue::String(format!("{}{}", s, other_str)))
+                }
+            }
+            (left, right) => {
+                // Convert both non-string values to strings and concatenate
+                let left_str = match left {
+                    FieldValue::String(s) => s.clone(),
+                    FieldValue::Integer(i) => i.to_string(),
+                    FieldValue::Float(f) => f.to_string(),
+                    FieldValue::Boolean(b) => b.to_string(),
+                    FieldValue::ScaledInteger(value, scale) => {
+                        let divisor = 10_i64.pow(*scale as u32);
+                        if divisor == 1 {
+                            value.to_string()
+                        } else {
+                            format!(
+                                "{:.prec$}",
+                                *value as f64 / divisor as f64,
+                                prec = *scale as usize
+                            )
+                        }
+                    }
+                    FieldValue::Timestamp(ts) => ts.to_string(),
+                    FieldValue::TimestampTz(ts) => ts.to_string(),
+                    FieldValue::Date(d) => d.to_string(),
+                    FieldValue::Decimal(d) => d.to_string(),
+                    FieldValue::Array(arr) => format!("{:?}", arr),
+                    FieldValue::Map(map) => format!("{:?}", map),
+                    FieldValue::Struct(s) => format!("{:?}", s),
+                    FieldValue::Interval { value, unit } => {
+                        format!("{} {:?}", value, unit)
+                    }
+                    FieldValue::Bytes(_) | FieldValue::Uuid(_) | FieldValue::Time(_) => {
+                        left.to_display_string()
+                    }
+                    FieldValue::Null => "".to_string(),
+                };
+                let right_str = match right {
+                    FieldValue::String(s) => s.clone(),
+                    FieldValue::Integer(i) => i.to_string(),
+                    FieldValue::Float(f) => f.to_string(),
+                    FieldValue::Boolean(b) => b.to_string(),
+                    FieldValue::ScaledInteger(value, scale) => {
+                        let divisor = 10_i64.pow(*scale as u32);
+                        if divisor == 1 {
+                            value.to_string()
+                        } else {
+                            format!(
+                                "{:.prec$}",
+                                *value as f64 / divisor as f64,
+                                prec = *scale as usize
+                            )
+                        }
+                    }
+                    FieldValue::Timestamp(ts) => ts.to_string(),
+                    FieldValue::TimestampTz(ts) => ts.to_string(),
+                    FieldValue::Date(d) => d.to_string(),
+                    FieldValue::Decimal(d) => d.to_string(),
+                    FieldValue::Array(arr) => format!("{:?}", arr),
+                    FieldValue::Map(map) => format!("{:?}", map),
+                    FieldValue::Struct(s) => format!("{:?}", s),
+                    FieldValue::Interval { value, unit } => {
+                        format!("{} {:?}", value, unit)
+                    }
+                    FieldValue::Bytes(_) | FieldValue::Uuid(_) | FieldValue::Time(_) => {
+                        right.to_display_string()
+                    }
+                    FieldValue::Null => "".to_string(),
+                };
+                Ok(FieldValue::String(format!("{}{}", left_str, right_str)))
+            }
+        }
+    }
+
+    /// Evaluate `value [NOT] BETWEEN low AND high`; NULL operands yield false
+    pub(crate) fn between_values(
+        expr_val: &FieldValue,
+        low_val: &FieldValue,
+        high_val: &FieldValue,
+        negated: bool,
+    ) -> Result<FieldValue, SqlError> {
+        // Handle NULL values according to SQL standards
+        if matches!(expr_val, FieldValue::Null)
+            || matches!(low_val, FieldValue::Null)
+            || matches!(high_val, FieldValue::Null)
+        {
+            return Ok(FieldValue::Boolean(false));
+        }
+
+        let result = match (expr_val, low_val, high_val) {
+            // Integer comparisons
+            (FieldValue::Integer(e), FieldValue::Integer(l), FieldValue::Integer(h)) => {
+                e >= l && e <= h
+            }
+            // Float comparisons
+            (FieldValue::Float(e), FieldValue::Float(l), FieldValue::Float(h)) => e >= l && e <= h,
+            // ScaledInteger comparisons (financial precision)
+            (
+                FieldValue::ScaledInteger(e_val, e_scale),
+                FieldValue::ScaledInteger(l_val, l_scale),
+                FieldValue::ScaledInteger(h_val, h_scale),
+            ) => {
+                // Normalize scales for proper comparison
+                let max_scale = (*e_scale).max(*l_scale).max(*h_scale);
+                let e_normalized = *e_val * 10_i64.pow((max_scale - e_scale) as u32);
+                let l_normalized = *l_val * 10_i64.pow((max_scale - l_scale) as u32);
+                let h_normalized = *h_val * 10_i64.pow((max_scale - h_scale) as u32);
+
+                e_normalized >= l_normalized && e_normalized <= h_normalized
+            }
+            // String comparisons (lexicographic)
+            (FieldValue::String(e), FieldValue::String(l), FieldValue::String(h)) => {
+                e >= l && e <= h
+            }
+            // Mixed type comparisons - convert to common type
+            _ => {
+                // Try to convert all to Float for comparison
+                let e_float = Self::to_comparable_float(expr_val)?;
+                let l_float = Self::to_comparable_float(low_val)?;
+                let h_float = Self::to_comparable_float(high_val)?;
+
+                e_float >= l_float && e_float <= h_float
+            }
+        };
+
+        Ok(FieldValue::Boolean(if negated { !result } else { result }))
+    }
+
+    pub(crate) fn field_value_to_bool(value: &FieldValue) -> Result<bool, SqlError> {
         match value {
             FieldValue::Boolean(b) => Ok(*b),
             FieldValue::Integer(i) => Ok(*i != 0),
@@ -790,7 +888,7 @@ impl ExpressionEvaluator {
         }
     }
 
-    fn values_equal(left: &FieldValue, right: &FieldValue) -> bool {
+    pub(crate) fn values_equal(left: &FieldValue, right: &FieldValue) -> bool {
         match (left, right) {
             (FieldValue::Null, FieldValue::Null) => true,
             (FieldValue::Null, _) | (_, FieldValue::Null) => false,
@@ -800,6 +898,15 @@ impl ExpressionEvaluator {
             (FieldValue::Boolean(a), FieldValue::Boolean(b)) => a == b,
             (FieldValue::Integer(a), FieldValue::Float(b)) => (*a as f64 - b).abs() < f64::EPSILON,
             (FieldValue::Float(a), FieldValue::Integer(b)) => (a - *b as f64).abs() < f64::EPSILON,
+            (FieldValue::Date(a), FieldValue::Date(b)) => a == b,
+            (FieldValue::Time(a), FieldValue::Time(b)) => a == b,
+            (FieldValue::Bytes(a), FieldValue::Bytes(b)) => a == b,
+            (FieldValue::Uuid(a), FieldValue::Uuid(b)) => a == b,
+            // UUID literals are written as strings, so compare against the parsed form
+            (FieldValue::Uuid(a), FieldValue::String(b))
+            | (FieldValue::String(b), FieldValue::Uuid(a)) => {
+                uuid::Uuid::parse_str(b).is_ok_and(|b| *a == b)
+            }
             (FieldValue::Array(a), FieldValue::Array(b)) => {
                 // Arrays are equal if they have the same length and all elements are equal
                 if a.len() != b.len() {
@@ -836,7 +943,11 @@ impl ExpressionEvaluator {
         }
     }
 
-    fn compare_values<F>(left: &FieldValue, right: &FieldValue, op: F) -> Result<bool, SqlError>
+    pub(crate) fn compare_values<F>(
+        left: &FieldValue,
+        right: &FieldValue,
+        op: F,
+    ) -> Result<bool, SqlError>
     where
         F: Fn(i32) -> bool,
     {
@@ -854,7 +965,35 @@ impl ExpressionEvaluator {
                 a.partial_cmp(&(*b as f64))
                     .unwrap_or(std::cmp::Ordering::Equal) as i32
             }
+            // Exact decimal comparison: rescale both sides to the larger scale
+            (FieldValue::ScaledInteger(a, sa), FieldValue::ScaledInteger(b, sb)) => {
+                Self::compare_scaled(*a, *sa, *b, *sb) as i32
+            }
+            (FieldValue::ScaledInteger(a, sa), FieldValue::Integer(b)) => {
+                Self::compare_scaled(*a, *sa, *b, 0) as i32
+            }
+            (FieldValue::Integer(a), FieldValue::ScaledInteger(b, sb)) => {
+                Self::compare_scaled(*a, 0, *b, *sb) as i32
+            }
+            (FieldValue::ScaledInteger(a, sa), FieldValue::Float(b)) => {
+                (*a as f64 / 10f64.powi(*sa as i32))
+                    .partial_cmp(b)
+                    .unwrap_or(std::cmp::Ordering::Equal) as i32
+            }
+            (FieldValue::Float(a), FieldValue::ScaledInteger(b, sb)) => {
+                a.partial_cmp(&(*b as f64 / 10f64.powi(*sb as i32)))
+                    .unwrap_or(std::cmp::Ordering::Equal) as i32
+            }
             (FieldValue::String(a), FieldValue::String(b)) => a.cmp(b) as i32,
+            (FieldValue::Timestamp(a), FieldValue::Timestamp(b)) => a.cmp(b) as i32,
+            // Zone-aware timestamps compare by instant; naive timestamps are treated as UTC
+            (FieldValue::TimestampTz(a), FieldValue::TimestampTz(b)) => a.cmp(b) as i32,
+            (FieldValue::TimestampTz(a), FieldValue::Timestamp(b)) => a.naive_utc().cmp(b) as i32,
+            (FieldValue::Timestamp(a), FieldValue::TimestampTz(b)) => a.cmp(&b.naive_utc()) as i32,
+            (FieldValue::Date(a), FieldValue::Date(b)) => a.cmp(b) as i32,
+            (FieldValue::Time(a), FieldValue::Time(b)) => a.cmp(b) as i32,
+            (FieldValue::Bytes(a), FieldValue::Bytes(b)) => a.cmp(b) as i32,
+            (FieldValue::Uuid(a), FieldValue::Uuid(b)) => a.cmp(b) as i32,
             _ => {
                 return Err(SqlError::TypeError {
                     expected: "comparable types".to_string(),
@@ -866,13 +1005,30 @@ impl ExpressionEvaluator {
         Ok(op(cmp))
     }
 
+    fn compare_scaled(a: i64, scale_a: u8, b: i64, scale_b: u8) -> std::cmp::Ordering {
+        let scale = scale_a.max(scale_b);
+        let rescale = |v: i64, s: u8| {
+            10i128
+                .checked_pow((scale - s) as u32)
+                .and_then(|factor| (v as i128).checked_mul(factor))
+        };
+        match (rescale(a, scale_a), rescale(b, scale_b)) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            // Scales too far apart for i128 - fall back to floating point
+            _ => (a as f64 / 10f64.powi(scale_a as i32))
+                .partial_cmp(&(b as f64 / 10f64.powi(scale_b as i32)))
+                .unwrap_or(std::cmp::Ordering::Equal),
+        }
+    }
+
     fn match_pattern(text: &str, pattern: &str) -> bool {
-        let regex_pattern = pattern.replace('%', ".*").replace('_', ".");
+        Self::like_regex(pattern).is_some_and(|re| re.is_match(text))
+    }
 
-        match regex::Regex::new(&format!("^{}$", regex_pattern)) {
-            Ok(re) => re.is_match(text),
-            Err(_) => false,
-        }
+    /// Translate a LIKE pattern into an anchored regex; invalid patterns never match
+    pub(crate) fn like_regex(pattern: &str) -> Option<regex::Regex> {
+        let regex_pattern = pattern.replace('%', ".*").replace('_', ".");
+        regex::Regex::new(&format!("^{}$", regex_pattern)).ok()
     }
 
     /// Evaluates a boolean expression with subquery support
@@ -889,6 +1045,7 @@ impl ExpressionEvaluator {
             Expr::Subquery {
                 query,
                 subquery_type,
+                ..
             } => {
                 // Handle subqueries in boolean context
                 let result = evaluate_subquery_with_executor(
@@ -928,6 +1085,7 @@ impl ExpressionEvaluator {
             Expr::Subquery {
                 query,
                 subquery_type,
+                ..
             } => {
                 // Handle subqueries based on their type
                 evaluate_subquery_with_executor(
@@ -939,7 +1097,9 @@ impl ExpressionEvaluator {
                     None,
                 )
             }
-            Expr::BinaryOp { left, op, right } => {
+            Expr::BinaryOp {
+                left, op, right, ..
+            } => {
                 use crate::ferris::sql::ast::BinaryOperator;
 
                 // Handle IN/NOT IN operations that might involve subqueries
@@ -958,7 +1118,7 @@ impl ExpressionEvaluator {
                         }
 
                         match &**right {
-                            Expr::List(values) => {
+                            Expr::List(values, _) => {
                                 for value_expr in values {
                                     let value = Self::evaluate_expression_value_with_subqueries(
                                         value_expr,
@@ -999,7 +1159,7 @@ impl ExpressionEvaluator {
                         }
 
                         match &**right {
-                            Expr::List(values) => {
+                            Expr::List(values, _) => {
                                 for value_expr in values {
                                     let value = Self::evaluate_expression_value_with_subqueries(
                                         value_expr,
@@ -1127,6 +1287,7 @@ impl ExpressionEvaluator {
                                             }
                                         }
                                         FieldValue::Timestamp(ts) => ts.to_string(),
+                                        FieldValue::TimestampTz(ts) => ts.to_string(),
                                         FieldValue::Date(d) => d.to_string(),
                                         FieldValue::Decimal(d) => d.to_string(),
                                         FieldValue::Array(arr) => format!("{:?}", arr),
@@ -1135,6 +1296,9 @@ impl ExpressionEvaluator {
                                         FieldValue::Interval { value, unit } => {
                                             format!("{} {:?}", value, unit)
                                         }
+                                        FieldValue::Bytes(_)
+                                        | FieldValue::Uuid(_)
+                                        | FieldValue::Time(_) => other.to_display_string(),
                                         FieldValue::Null => return Ok(FieldValue::Null),
                                     };
                                     if left_val == *other {
@@ -1166,6 +1330,7 @@ impl ExpressionEvaluator {
                                             }
                                         }
                                         FieldValue::Timestamp(ts) => ts.to_string(),
+                                        FieldValue::TimestampTz(ts) => ts.to_string(),
                                         FieldValue::Date(d) => d.to_string(),
                                         FieldValue::Decimal(d) => d.to_string(),
                                         FieldValue::Array(arr) => format!("{:?}", arr),
@@ -1174,6 +1339,9 @@ impl ExpressionEvaluator {
                                         FieldValue::Interval { value, unit } => {
                                             format!("{} {:?}", value, unit)
                                         }
+                                        FieldValue::Bytes(_)
+                                        | FieldValue::Uuid(_)
+                                        | FieldValue::Time(_) => left.to_display_string(),
                                         FieldValue::Null => "".to_string(),
                                     };
                                     let right_str = match right {
@@ -1194,6 +1362,7 @@ impl ExpressionEvaluator {
                                             }
                                         }
                                         FieldValue::Timestamp(ts) => ts.to_string(),
+                                        FieldValue::TimestampTz(ts) => ts.to_string(),
                                         FieldValue::Date(d) => d.to_string(),
                                         FieldValue::Decimal(d) => d.to_string(),
                                         FieldValue::Array(arr) => format!("{:?}", arr),
@@ -1202,6 +1371,9 @@ impl ExpressionEvaluator {
                                         FieldValue::Interval { value, unit } => {
                                             format!("{} {:?}", value, unit)
                                         }
+                                        FieldValue::Bytes(_)
+                                        | FieldValue::Uuid(_)
+                                        | FieldValue::Time(_) => right.to_display_string(),
                                         FieldValue::Null => "".to_string(),
                                     };
                                     Ok(FieldValue::String(format!("{}{}", left_str, right_str)))
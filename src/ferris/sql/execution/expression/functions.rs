@@ -6,7 +6,7 @@
 //! - **Math Functions** (ABS, ROUND, CEIL, FLOOR) - High precision arithmetic  
 //! - **Enhanced Type Functions** (CAST, COALESCE, NULLIF) - Advanced type coercion and optimization
 //! - **Header Functions** (HEADER, HEADER_KEYS, HAS_HEADER) - Kafka message header access
-//! - **JSON Functions** (JSON_EXTRACT, JSON_VALUE) - High-performance JSON processing
+//! - **JSON Functions** (JSON_EXTRACT, JSON_VALUE, JSON_QUERY, PARSE_JSON, JSON_OBJECT) - JSONPath queries and construction
 //! - **Date/Time Functions** - Comprehensive temporal data handling
 //! - **Array Functions** - Complex data structure manipulation
 //!
@@ -17,14 +17,39 @@
 //! - **Performance Optimization**: Memory-efficient processing for high-throughput streaming
 //! - **Enhanced Error Handling**: Detailed context information for debugging
 
+use super::super::decimal::{self, RoundingMode};
 use super::super::types::{FieldValue, StreamRecord};
+use super::super::utils::field_value_comparator::FieldValueComparator;
 use super::evaluator::ExpressionEvaluator;
-use crate::ferris::sql::ast::{Expr, LiteralValue};
+use super::json_path::{self, JsonPath};
+use super::regex_cache;
+use crate::ferris::sql::ast::{BinaryOperator, DecimalSpec, Expr, LiteralValue, TimeUnit};
 use crate::ferris::sql::error::SqlError;
 use chrono::Utc;
+use regex::Regex;
 use serde_json;
 use std::collections::HashMap;
 
+/// Implementation of a built-in function, called with the unevaluated arguments
+pub(crate) type FunctionImpl = fn(&[Expr], &StreamRecord) -> Result<FieldValue, SqlError>;
+
+/// Compiles a REGEXP_* pattern from its text and flags
+pub(crate) type PatternCompiler<'a> = &'a mut dyn FnMut(&str, &str) -> Result<Regex, SqlError>;
+
+/// Implementation of a REGEXP_* function, called with the unevaluated arguments and the
+/// compiler of its pattern
+pub(crate) type RegexFunctionImpl =
+    fn(&[Expr], &StreamRecord, PatternCompiler) -> Result<FieldValue, SqlError>;
+
+/// Values selected by the `(json, path)` arguments of a JSON path function
+struct JsonPathMatches {
+    values: Vec<FieldValue>,
+    /// Whether the path can select at most one value
+    definite: bool,
+    /// The JSON text given, when the path is the root `$`
+    root_text: Option<String>,
+}
+
 /// Provides built-in SQL function implementations
 pub struct BuiltinFunctions;
 
@@ -35,7 +60,9 @@ impl BuiltinFunctions {
     /// This is the main entry point for all built-in function evaluation.
     pub fn evaluate_function(func: &Expr, record: &StreamRecord) -> Result<FieldValue, SqlError> {
         match func {
-            Expr::Function { name, args } => Self::evaluate_function_by_name(name, args, record),
+            Expr::Function { name, args, .. } => {
+                Self::evaluate_function_by_name(name, args, record)
+            }
             _ => Err(SqlError::ExecutionError {
                 message: "Expected function expression".to_string(),
                 query: None,
@@ -49,111 +76,172 @@ impl BuiltinFunctions {
         args: &[Expr],
         record: &StreamRecord,
     ) -> Result<FieldValue, SqlError> {
-        match name.to_uppercase().as_str() {
+        match Self::lookup_function(&name.to_uppercase()) {
+            Some(function) => function(args, record),
+            None => Err(SqlError::unknown_function_error(name)),
+        }
+    }
+
+    /// Look up a REGEXP_* function by its upper-case name, with the position of its flags
+    /// argument if it takes one; the pattern is always the second argument
+    ///
+    /// Compiled expressions bind these with a pattern compiled once for literal patterns
+    /// and a per-query cache for patterns computed from records.
+    pub(crate) fn lookup_regex_function(name: &str) -> Option<(RegexFunctionImpl, Option<usize>)> {
+        Some(match name {
+            "REGEXP_LIKE" => (Self::regexp_like as RegexFunctionImpl, Some(2)),
+            "REGEXP_EXTRACT" => (Self::regexp_extract, None),
+            "REGEXP_REPLACE" => (Self::regexp_replace, Some(3)),
+            "REGEXP_SPLIT" => (Self::regexp_split, None),
+            _ => return None,
+        })
+    }
+
+    /// Look up the implementation of a built-in function by its upper-case name
+    ///
+    /// Compiled expressions bind functions once through this instead of matching the name
+    /// for every record.
+    pub(crate) fn lookup_function(name: &str) -> Option<FunctionImpl> {
+        let function: FunctionImpl = match name {
             // Aggregate functions
-            "COUNT" => Self::count_function(args, record),
-            "SUM" => Self::sum_function(args, record),
-            "AVG" => Self::avg_function(args, record),
-            "MIN" => Self::min_function(args, record),
-            "MAX" => Self::max_function(args, record),
-            "APPROX_COUNT_DISTINCT" => Self::approx_count_distinct_function(args, record),
-            "FIRST_VALUE" => Self::first_value_function(args, record),
-            "LAST_VALUE" => Self::last_value_function(args, record),
-            "LISTAGG" => Self::listagg_function(args, record),
+            "COUNT" => Self::count_function,
+            "SUM" => Self::sum_function,
+            "AVG" => Self::avg_function,
+            "MIN" => Self::min_function,
+            "MAX" => Self::max_function,
+            "APPROX_COUNT_DISTINCT" => Self::approx_count_distinct_function,
+            "FIRST_VALUE" => Self::first_value_function,
+            "LAST_VALUE" => Self::last_value_function,
+            "LISTAGG" => Self::listagg_function,
 
             // Header functions
-            "HEADER" => Self::header_function(args, record),
-            "HEADER_KEYS" => Self::header_keys_function(args, record),
-            "HAS_HEADER" => Self::has_header_function(args, record),
+            "HEADER" => Self::header_function,
+            "HEADER_KEYS" => Self::header_keys_function,
+            "HAS_HEADER" => Self::has_header_function,
 
             // Math functions
-            "ABS" => Self::abs_function(args, record),
-            "ROUND" => Self::round_function(args, record),
-            "CEIL" | "CEILING" => Self::ceil_function(args, record),
-            "FLOOR" => Self::floor_function(args, record),
-            "SQRT" => Self::sqrt_function(args, record),
-            "POWER" | "POW" => Self::power_function(args, record),
-            "MOD" => Self::mod_function(args, record),
+            "ABS" => Self::abs_function,
+            "ROUND" => Self::round_function,
+            "CEIL" | "CEILING" => Self::ceil_function,
+            "FLOOR" => Self::floor_function,
+            "SQRT" => Self::sqrt_function,
+            "POWER" | "POW" => Self::power_function,
+            "MOD" => Self::mod_function,
 
             // String functions
-            "UPPER" => Self::upper_function(args, record),
-            "LOWER" => Self::lower_function(args, record),
-            "SUBSTRING" => Self::substring_function(args, record),
-            "REPLACE" => Self::replace_function(args, record),
-            "TRIM" => Self::trim_function(args, record),
-            "LTRIM" => Self::ltrim_function(args, record),
-            "RTRIM" => Self::rtrim_function(args, record),
-            "LENGTH" | "LEN" => Self::length_function(args, record),
-            "SPLIT" => Self::split_function(args, record),
-            "JOIN" => Self::join_function(args, record),
+            "UPPER" => Self::upper_function,
+            "LOWER" => Self::lower_function,
+            "SUBSTRING" => Self::substring_function,
+            "REPLACE" => Self::replace_function,
+            "TRIM" => Self::trim_function,
+            "LTRIM" => Self::ltrim_function,
+            "RTRIM" => Self::rtrim_function,
+            "LENGTH" | "LEN" => Self::length_function,
+            "SPLIT" => Self::split_function,
+            "JOIN" => Self::join_function,
+
+            // Regex, hashing and encoding functions
+            "REGEXP_LIKE" => Self::regexp_like_function,
+            "REGEXP_EXTRACT" => Self::regexp_extract_function,
+            "REGEXP_REPLACE" => Self::regexp_replace_function,
+            "REGEXP_SPLIT" => Self::regexp_split_function,
+            "MD5" => Self::md5_function,
+            "SHA256" => Self::sha256_function,
+            "XXHASH64" => Self::xxhash64_function,
+            "BASE64_ENCODE" => Self::base64_encode_function,
+            "BASE64_DECODE" => Self::base64_decode_function,
+            "URL_DECODE" => Self::url_decode_function,
+            "PARSE_URL" => Self::parse_url_function,
+            "UUID" => Self::uuid_function,
 
             // JSON functions
-            "JSON_EXTRACT" => Self::json_extract_function(args, record),
-            "JSON_VALUE" => Self::json_value_function(args, record),
+            "JSON_EXTRACT" => Self::json_extract_function,
+            "JSON_VALUE" => Self::json_value_function,
+            "JSON_QUERY" => Self::json_query_function,
+            "PARSE_JSON" => Self::parse_json_function,
+            "TO_JSON" => Self::to_json_function,
+            "JSON_OBJECT" => Self::json_object_function,
+            "JSON_ARRAY" => Self::json_array_function,
+            "JSON_ARRAYAGG" => Self::json_arrayagg_function,
+            "JSON_OBJECTAGG" => Self::json_objectagg_function,
 
             // Conversion functions
-            "CAST" => Self::cast_function(args, record),
+            "CAST" => Self::cast_function,
 
             // System functions
-            "TIMESTAMP" => Self::timestamp_function(args, record),
+            "TIMESTAMP" => Self::timestamp_function,
 
             // Advanced type functions
-            "ARRAY" => Self::array_function(args, record),
-            "STRUCT" => Self::struct_function(args, record),
-            "MAP" => Self::map_function(args, record),
-            "ARRAY_LENGTH" => Self::array_length_function(args, record),
-            "ARRAY_CONTAINS" => Self::array_contains_function(args, record),
-            "MAP_KEYS" => Self::map_keys_function(args, record),
-            "MAP_VALUES" => Self::map_values_function(args, record),
-            "CONCAT" => Self::concat_function(args, record),
-            "COALESCE" => Self::coalesce_function(args, record),
-            "NULLIF" => Self::nullif_function(args, record),
+            "ARRAY" => Self::array_function,
+            "STRUCT" => Self::struct_function,
+            "MAP" => Self::map_function,
+            "ARRAY_LENGTH" => Self::array_length_function,
+            "ARRAY_CONTAINS" => Self::array_contains_function,
+            "MAP_KEYS" => Self::map_keys_function,
+            "MAP_VALUES" => Self::map_values_function,
+            "TRANSFORM" => Self::transform_function,
+            "FILTER" => Self::filter_function,
+            "REDUCE" => Self::reduce_function,
+            "ARRAY_SORT" => Self::array_sort_function,
+            "ARRAY_DISTINCT" => Self::array_distinct_function,
+            "CONCAT" => Self::concat_function,
+            "COALESCE" => Self::coalesce_function,
+            "NULLIF" => Self::nullif_function,
 
-            "EXTRACT" => Self::extract_function(args, record),
+            "EXTRACT" => Self::extract_function,
 
-            "DATEDIFF" => Self::datediff_function(args, record),
+            "DATEDIFF" => Self::datediff_function,
 
-            "MEDIAN" => Self::median_function(args, record),
+            "MEDIAN" => Self::median_function,
 
-            "STDDEV" | "STDDEV_SAMP" => Self::stddev_function(args, record),
+            "STDDEV" | "STDDEV_SAMP" => Self::stddev_function,
 
-            "VARIANCE" | "VAR_SAMP" => Self::evaluate_variance(&args, record),
+            "VARIANCE" | "VAR_SAMP" => Self::evaluate_variance,
 
-            "VAR_POP" => Self::var_pop_function(args, record),
+            "VAR_POP" => Self::var_pop_function,
 
-            "STDDEV_POP" => Self::stddev_pop_function(args, record),
+            "STDDEV_POP" => Self::stddev_pop_function,
 
             // String manipulation functions
-            "LEFT" => Self::left_function(args, record),
-            "RIGHT" => Self::right_function(args, record),
+            "LEFT" => Self::left_function,
+            "RIGHT" => Self::right_function,
 
             // Date/Time functions
-            "NOW" => Self::now_function(args, record),
-            "CURRENT_TIMESTAMP" => Self::current_timestamp_function(args, record),
-            "DATE_FORMAT" => Self::date_format_function(args, record),
-            "FROM_UNIXTIME" => Self::from_unixtime_function(args, record),
-            "UNIX_TIMESTAMP" => Self::unix_timestamp_function(args, record),
+            "NOW" => Self::now_function,
+            "CURRENT_TIMESTAMP" => Self::current_timestamp_function,
+            "DATE_FORMAT" => Self::date_format_function,
+            "CONVERT_TZ" => Self::convert_tz_function,
+            "AT_TIME_ZONE" => Self::at_time_zone_function,
+            "FROM_UNIXTIME" => Self::from_unixtime_function,
+            "UNIX_TIMESTAMP" => Self::unix_timestamp_function,
+            "TO_TIMESTAMP" => Self::to_timestamp_function,
+            "TO_DATE" => Self::to_date_function,
+            "DATE_TRUNC" => Self::date_trunc_function,
+            "TIME_BUCKET" => Self::time_bucket_function,
+            "DATE_ADD" => |args, record| Self::date_add_function("DATE_ADD", args, record),
+            "DATE_SUB" => |args, record| Self::date_add_function("DATE_SUB", args, record),
 
             // Search functions
-            "POSITION" => Self::position_function(args, record),
+            "POSITION" => Self::position_function,
 
             // Comparison functions
-            "LEAST" => Self::least_function(args, record),
-            "GREATEST" => Self::greatest_function(args, record),
+            "LEAST" => Self::least_function,
+            "GREATEST" => Self::greatest_function,
 
             // Header manipulation functions
-            "SET_HEADER" => Self::set_header_function(args, record),
-            "REMOVE_HEADER" => Self::remove_header_function(args, record),
+            "SET_HEADER" => Self::set_header_function,
+            "REMOVE_HEADER" => Self::remove_header_function,
 
             // Additional aggregate functions
-            "STRING_AGG" => Self::string_agg_function(args, record),
-            "COUNT_DISTINCT" => Self::count_distinct_function(args, record),
+            "STRING_AGG" => Self::string_agg_function,
+            "COUNT_DISTINCT" => Self::count_distinct_function,
 
-            _ => Err(SqlError::unknown_function_error(name)),
-        }
+            _ => return None,
+        };
+        Some(function)
     }
 
-    fn evaluate_variance(args: &&[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
+    fn evaluate_variance(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
         // VARIANCE(column) - sample variance
         if args.len() != 1 {
             return Err(SqlError::ExecutionError {
@@ -209,7 +297,7 @@ impl BuiltinFunctions {
         let column_expr = &args[0];
 
         // Check if this is a literal 1 (which represents COUNT(*) from parser)
-        if let Expr::Literal(LiteralValue::Integer(1)) = column_expr {
+        if let Expr::Literal(LiteralValue::Integer(1), _) = column_expr {
             // This is actually COUNT(*) represented as COUNT(1)
             return Ok(FieldValue::Integer(1));
         }
@@ -472,14 +560,16 @@ impl BuiltinFunctions {
     }
 
     fn round_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
-        if args.is_empty() || args.len() > 2 {
+        if args.is_empty() || args.len() > 3 {
             return Err(SqlError::ExecutionError {
-                message: "ROUND requires 1 or 2 arguments: ROUND(number[, precision])".to_string(),
+                message:
+                    "ROUND requires 1 to 3 arguments: ROUND(number[, precision[, rounding_mode]])"
+                        .to_string(),
                 query: None,
             });
         }
         let value = ExpressionEvaluator::evaluate_expression_value(&args[0], record)?;
-        let precision = if args.len() == 2 {
+        let precision = if args.len() >= 2 {
             match ExpressionEvaluator::evaluate_expression_value(&args[1], record)? {
                 FieldValue::Integer(p) => p as i32,
                 FieldValue::Null => return Ok(FieldValue::Null), // If precision is NULL, result is NULL
@@ -493,12 +583,56 @@ impl BuiltinFunctions {
         } else {
             0
         };
+        let mode = match args.get(2) {
+            Some(arg) => Some(Self::rounding_mode_argument(arg, record)?),
+            None => None,
+        };
+
+        // Exact decimals round their unscaled value; the result keeps at most `precision` digits
+        let round_exact = |unscaled: i128, scale: u8| -> Result<(i128, u8), SqlError> {
+            if precision < 0 {
+                return Err(SqlError::ExecutionError {
+                    message: "ROUND precision must not be negative for DECIMAL values".to_string(),
+                    query: None,
+                });
+            }
+            let target = (precision as u8).min(scale);
+            let rounded = decimal::rescale(unscaled, scale, target, mode.unwrap_or_default())
+                .ok_or_else(|| SqlError::ExecutionError {
+                    message: "DECIMAL overflow in ROUND".to_string(),
+                    query: None,
+                })?;
+            Ok((rounded, target))
+        };
 
         match value {
-            FieldValue::Float(f) => {
+            FieldValue::Float(f) if mode.is_none() => {
                 let multiplier = 10_f64.powi(precision);
                 Ok(FieldValue::Float((f * multiplier).round() / multiplier))
             }
+            FieldValue::Float(f) => {
+                let exact =
+                    rust_decimal::Decimal::from_str_exact(&f.to_string()).map_err(|_| {
+                        SqlError::ExecutionError {
+                            message: format!("Cannot round {} with a rounding mode", f),
+                            query: None,
+                        }
+                    })?;
+                let (rounded, scale) = round_exact(exact.mantissa(), exact.scale() as u8)?;
+                Ok(FieldValue::Float(
+                    rounded as f64 / 10_f64.powi(scale as i32),
+                ))
+            }
+            FieldValue::ScaledInteger(v, scale) => {
+                let (rounded, scale) = round_exact(v as i128, scale)?;
+                Ok(FieldValue::ScaledInteger(rounded as i64, scale))
+            }
+            FieldValue::Decimal(d) => {
+                let (rounded, scale) = round_exact(d.mantissa(), d.scale() as u8)?;
+                Ok(FieldValue::Decimal(
+                    rust_decimal::Decimal::from_i128_with_scale(rounded, scale as u32),
+                ))
+            }
             FieldValue::Integer(i) => Ok(FieldValue::Integer(i)), // Integers don't need rounding
             FieldValue::Null => Ok(FieldValue::Null),
             _ => Err(SqlError::ExecutionError {
@@ -864,239 +998,829 @@ impl BuiltinFunctions {
         Ok(FieldValue::String(parts.first().unwrap_or(&"").to_string()))
     }
 
-    fn join_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
-        if args.len() < 2 {
+    // Regex, hashing and encoding functions
+
+    /// Evaluate an argument as text. NULL propagates as `None`; non-string scalars are
+    /// converted with their display form so that e.g. `MD5(user_id)` works on integers.
+    fn text_argument(arg: &Expr, record: &StreamRecord) -> Result<Option<String>, SqlError> {
+        match ExpressionEvaluator::evaluate_expression_value(arg, record)? {
+            FieldValue::Null => Ok(None),
+            FieldValue::String(s) => Ok(Some(s)),
+            other => Ok(Some(other.to_display_string())),
+        }
+    }
+
+    /// Evaluate an argument as raw bytes for hashing and encoding. BYTES values are used
+    /// as-is; every other value is hashed through its text form (see `text_argument`).
+    fn binary_argument(arg: &Expr, record: &StreamRecord) -> Result<Option<Vec<u8>>, SqlError> {
+        match ExpressionEvaluator::evaluate_expression_value(arg, record)? {
+            FieldValue::Null => Ok(None),
+            FieldValue::Bytes(bytes) => Ok(Some(bytes)),
+            FieldValue::String(s) => Ok(Some(s.into_bytes())),
+            other => Ok(Some(other.to_display_string().into_bytes())),
+        }
+    }
+
+    /// Evaluate the optional regex flags argument at `index` (defaults to no flags)
+    fn regex_flags_argument(
+        args: &[Expr],
+        index: usize,
+        record: &StreamRecord,
+    ) -> Result<String, SqlError> {
+        match args.get(index) {
+            Some(arg) => Ok(Self::text_argument(arg, record)?.unwrap_or_default()),
+            None => Ok(String::new()),
+        }
+    }
+
+    fn regexp_like_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
+        Self::regexp_like(args, record, &mut regex_cache::compile)
+    }
+
+    fn regexp_like(
+        args: &[Expr],
+        record: &StreamRecord,
+        compile: PatternCompiler,
+    ) -> Result<FieldValue, SqlError> {
+        if args.len() != 2 && args.len() != 3 {
             return Err(SqlError::ExecutionError {
-                message: "JOIN requires at least two arguments".to_string(),
+                message: "REGEXP_LIKE requires two or three arguments: REGEXP_LIKE(string, pattern[, flags])".to_string(),
                 query: None,
             });
         }
 
-        // Evaluate all arguments and join them with the first argument as delimiter
-        let delimiter_val = ExpressionEvaluator::evaluate_expression_value(&args[0], record)?;
-        let delimiter = match delimiter_val {
-            FieldValue::String(d) => d,
-            _ => {
-                return Err(SqlError::ExecutionError {
-                    message: "JOIN delimiter must be a string".to_string(),
-                    query: None,
-                });
-            }
+        let (Some(text), Some(pattern)) = (
+            Self::text_argument(&args[0], record)?,
+            Self::text_argument(&args[1], record)?,
+        ) else {
+            return Ok(FieldValue::Null);
         };
+        let flags = Self::regex_flags_argument(args, 2, record)?;
 
-        let mut parts = Vec::new();
-        for arg in &args[1..] {
-            let val = ExpressionEvaluator::evaluate_expression_value(arg, record)?;
-            let str_val = match val {
-                FieldValue::String(s) => s,
-                FieldValue::Integer(i) => i.to_string(),
-                FieldValue::Float(f) => f.to_string(),
-                FieldValue::Boolean(b) => b.to_string(),
-                FieldValue::Null => "NULL".to_string(),
-                FieldValue::Date(_)
-                | FieldValue::Timestamp(_)
-                | FieldValue::Decimal(_)
-                | FieldValue::ScaledInteger(_, _)
-                | FieldValue::Array(_)
-                | FieldValue::Map(_)
-                | FieldValue::Struct(_)
-                | FieldValue::Interval { .. } => val.to_display_string(),
-            };
-            parts.push(str_val);
-        }
-
-        Ok(FieldValue::String(parts.join(&delimiter)))
+        let regex = compile(&pattern, &flags)?;
+        Ok(FieldValue::Boolean(regex.is_match(&text)))
     }
 
-    // JSON Functions
+    fn regexp_extract_function(
+        args: &[Expr],
+        record: &StreamRecord,
+    ) -> Result<FieldValue, SqlError> {
+        Self::regexp_extract(args, record, &mut regex_cache::compile)
+    }
 
-    fn json_extract_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
-        if args.len() != 2 {
+    fn regexp_extract(
+        args: &[Expr],
+        record: &StreamRecord,
+        compile: PatternCompiler,
+    ) -> Result<FieldValue, SqlError> {
+        if args.len() != 2 && args.len() != 3 {
             return Err(SqlError::ExecutionError {
-                message:
-                    "JSON_EXTRACT requires exactly two arguments: JSON_EXTRACT(json_string, path)"
-                        .to_string(),
+                message: "REGEXP_EXTRACT requires two or three arguments: REGEXP_EXTRACT(string, pattern[, group])".to_string(),
                 query: None,
             });
         }
 
-        let json_val = ExpressionEvaluator::evaluate_expression_value(&args[0], record)?;
-        let path_val = ExpressionEvaluator::evaluate_expression_value(&args[1], record)?;
+        let (Some(text), Some(pattern)) = (
+            Self::text_argument(&args[0], record)?,
+            Self::text_argument(&args[1], record)?,
+        ) else {
+            return Ok(FieldValue::Null);
+        };
+        let group = match args.get(2) {
+            Some(arg) => ExpressionEvaluator::evaluate_expression_value(arg, record)?,
+            None => FieldValue::Integer(0),
+        };
 
-        let (json_string, path) = match (json_val, path_val) {
-            (FieldValue::String(j), FieldValue::String(p)) => (j, p),
+        let regex = compile(&pattern, "")?;
+        let Some(captures) = regex.captures(&text) else {
+            return Ok(FieldValue::Null);
+        };
+
+        // The group is either a capture index (0 = whole match) or a named group
+        let matched = match &group {
+            FieldValue::Integer(index)
+                if *index >= 0 && (*index as usize) < regex.captures_len() =>
+            {
+                captures.get(*index as usize)
+            }
+            FieldValue::String(name) if regex.capture_names().flatten().any(|n| n == name) => {
+                captures.name(name)
+            }
+            FieldValue::Null => return Ok(FieldValue::Null),
             _ => {
                 return Err(SqlError::ExecutionError {
-                    message: "JSON_EXTRACT requires string arguments".to_string(),
+                    message: format!(
+                        "REGEXP_EXTRACT group {} does not exist in pattern '{}'",
+                        group.to_display_string(),
+                        pattern
+                    ),
                     query: None,
-                });
+                })
             }
         };
 
-        Self::extract_json_value(&json_string, &path)
+        Ok(matched
+            .map(|m| FieldValue::String(m.as_str().to_string()))
+            .unwrap_or(FieldValue::Null))
     }
 
-    fn json_value_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
-        if args.len() != 2 {
+    fn regexp_replace_function(
+        args: &[Expr],
+        record: &StreamRecord,
+    ) -> Result<FieldValue, SqlError> {
+        Self::regexp_replace(args, record, &mut regex_cache::compile)
+    }
+
+    fn regexp_replace(
+        args: &[Expr],
+        record: &StreamRecord,
+        compile: PatternCompiler,
+    ) -> Result<FieldValue, SqlError> {
+        if args.len() != 3 && args.len() != 4 {
             return Err(SqlError::ExecutionError {
-                message: "JSON_VALUE requires exactly two arguments: JSON_VALUE(json_string, path)"
-                    .to_string(),
+                message: "REGEXP_REPLACE requires three or four arguments: REGEXP_REPLACE(string, pattern, replacement[, flags])".to_string(),
                 query: None,
             });
         }
 
-        let json_val = ExpressionEvaluator::evaluate_expression_value(&args[0], record)?;
-        let path_val = ExpressionEvaluator::evaluate_expression_value(&args[1], record)?;
-
-        let (json_string, path) = match (json_val, path_val) {
-            (FieldValue::String(j), FieldValue::String(p)) => (j, p),
-            _ => {
-                return Err(SqlError::ExecutionError {
-                    message: "JSON_VALUE requires string arguments".to_string(),
-                    query: None,
-                });
-            }
+        let (Some(text), Some(pattern), Some(replacement)) = (
+            Self::text_argument(&args[0], record)?,
+            Self::text_argument(&args[1], record)?,
+            Self::text_argument(&args[2], record)?,
+        ) else {
+            return Ok(FieldValue::Null);
         };
+        let flags = Self::regex_flags_argument(args, 3, record)?;
 
-        Self::extract_json_value(&json_string, &path)
+        // Replacements may reference groups as $1 or ${name}
+        let regex = compile(&pattern, &flags)?;
+        Ok(FieldValue::String(
+            regex.replace_all(&text, replacement.as_str()).into_owned(),
+        ))
     }
 
-    // Helper function for JSON extraction
-    fn extract_json_value(json_string: &str, path: &str) -> Result<FieldValue, SqlError> {
-        // Simplified JSON extraction - in real implementation would use proper JSON parsing
-        if path == "$" {
-            Ok(FieldValue::String(json_string.to_string()))
-        } else {
-            // For now, return null for any complex path
-            Ok(FieldValue::Null)
-        }
+    fn regexp_split_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
+        Self::regexp_split(args, record, &mut regex_cache::compile)
     }
 
-    // Conversion Functions
-
-    fn cast_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
+    fn regexp_split(
+        args: &[Expr],
+        record: &StreamRecord,
+        compile: PatternCompiler,
+    ) -> Result<FieldValue, SqlError> {
         if args.len() != 2 {
             return Err(SqlError::ExecutionError {
-                message: "CAST requires exactly two arguments: CAST(value, type)".to_string(),
+                message:
+                    "REGEXP_SPLIT requires exactly two arguments: REGEXP_SPLIT(string, pattern)"
+                        .to_string(),
                 query: None,
             });
         }
 
-        let value = ExpressionEvaluator::evaluate_expression_value(&args[0], record)?;
-        let target_type = match &args[1] {
-            Expr::Literal(LiteralValue::String(type_str)) => type_str.to_uppercase(),
-            _ => {
-                return Err(SqlError::ExecutionError {
-                    message: "CAST target type must be a string literal".to_string(),
-                    query: None,
-                });
-            }
+        let (Some(text), Some(pattern)) = (
+            Self::text_argument(&args[0], record)?,
+            Self::text_argument(&args[1], record)?,
+        ) else {
+            return Ok(FieldValue::Null);
         };
 
-        value.cast_to(&target_type)
+        let regex = compile(&pattern, "")?;
+        Ok(FieldValue::Array(
+            regex
+                .split(&text)
+                .map(|part| FieldValue::String(part.to_string()))
+                .collect(),
+        ))
     }
 
-    // System Functions
+    fn md5_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
+        use md5::{Digest, Md5};
 
-    fn timestamp_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
-        if !args.is_empty() {
+        if args.len() != 1 {
             return Err(SqlError::ExecutionError {
-                message: "TIMESTAMP() takes no arguments".to_string(),
+                message: "MD5 requires exactly one argument".to_string(),
                 query: None,
             });
         }
-        // Return current record timestamp
-        Ok(FieldValue::Integer(record.timestamp))
+
+        match Self::binary_argument(&args[0], record)? {
+            Some(bytes) => Ok(FieldValue::String(Self::hex(&Md5::digest(&bytes)))),
+            None => Ok(FieldValue::Null),
+        }
     }
 
-    // Advanced Type Functions
+    fn sha256_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
+        use sha2::{Digest, Sha256};
 
-    fn array_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
-        // ARRAY constructor function - creates an array from arguments
-        let mut array_values = Vec::new();
-        for arg in args {
-            let value = ExpressionEvaluator::evaluate_expression_value(arg, record)?;
-            array_values.push(value);
+        if args.len() != 1 {
+            return Err(SqlError::ExecutionError {
+                message: "SHA256 requires exactly one argument".to_string(),
+                query: None,
+            });
         }
-        Ok(FieldValue::Array(array_values))
-    }
 
-    fn struct_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
-        if args.is_empty() {
-            return Ok(FieldValue::Struct(HashMap::new()));
+        match Self::binary_argument(&args[0], record)? {
+            Some(bytes) => Ok(FieldValue::String(Self::hex(&Sha256::digest(&bytes)))),
+            None => Ok(FieldValue::Null),
         }
+    }
 
-        let mut struct_map = HashMap::new();
+    fn xxhash64_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
+        use std::hash::Hasher;
 
-        if args.len() % 2 == 0 {
-            // Even number of arguments - treat as name-value pairs
-            for chunk in args.chunks(2) {
-                let name_val = ExpressionEvaluator::evaluate_expression_value(&chunk[0], record)?;
-                let value_val = ExpressionEvaluator::evaluate_expression_value(&chunk[1], record)?;
+        if args.is_empty() || args.len() > 2 {
+            return Err(SqlError::ExecutionError {
+                message: "XXHASH64 requires one or two arguments: XXHASH64(value[, seed])"
+                    .to_string(),
+                query: None,
+            });
+        }
 
-                let name = match name_val {
-                    FieldValue::String(s) => s,
-                    _ => {
-                        return Err(SqlError::ExecutionError {
-                            message: "STRUCT field names must be strings".to_string(),
-                            query: None,
-                        });
-                    }
-                };
+        let Some(bytes) = Self::binary_argument(&args[0], record)? else {
+            return Ok(FieldValue::Null);
+        };
+        let seed = match args.get(1) {
+            Some(arg) => match ExpressionEvaluator::evaluate_expression_value(arg, record)? {
+                FieldValue::Integer(seed) => seed as u64,
+                other => {
+                    return Err(SqlError::TypeError {
+                        expected: "integer seed".to_string(),
+                        actual: other.type_name().to_string(),
+                        value: None,
+                    })
+                }
+            },
+            None => 0,
+        };
 
-                struct_map.insert(name, value_val);
-            }
-        } else {
-            // Odd number of arguments - treat as positional fields (field0, field1, field2...)
-            for (i, arg) in args.iter().enumerate() {
-                let value = ExpressionEvaluator::evaluate_expression_value(arg, record)?;
-                struct_map.insert(format!("field{}", i), value);
-            }
-        }
+        let mut hasher = twox_hash::XxHash64::with_seed(seed);
+        hasher.write(&bytes);
+        // The unsigned 64-bit hash is returned with the same bits as a signed BIGINT
+        Ok(FieldValue::Integer(hasher.finish() as i64))
+    }
 
-        Ok(FieldValue::Struct(struct_map))
+    /// Lowercase hex encoding of a digest
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
     }
 
-    fn map_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
-        if args.is_empty() {
-            return Ok(FieldValue::Map(HashMap::new()));
-        }
+    fn base64_encode_function(
+        args: &[Expr],
+        record: &StreamRecord,
+    ) -> Result<FieldValue, SqlError> {
+        use base64::Engine;
 
-        // MAP constructor - alternating keys and values
-        if args.len() % 2 != 0 {
-            return Err(SqlError::TypeError {
-                expected: "even number of arguments (key-value pairs)".to_string(),
-                actual: format!("{} arguments", args.len()),
-                value: None,
+        if args.len() != 1 {
+            return Err(SqlError::ExecutionError {
+                message: "BASE64_ENCODE requires exactly one argument".to_string(),
+                query: None,
             });
         }
 
-        let mut map = HashMap::new();
-        for chunk in args.chunks(2) {
-            let key_val = ExpressionEvaluator::evaluate_expression_value(&chunk[0], record)?;
-            let value_val = ExpressionEvaluator::evaluate_expression_value(&chunk[1], record)?;
+        match Self::binary_argument(&args[0], record)? {
+            Some(bytes) => Ok(FieldValue::String(
+                base64::engine::general_purpose::STANDARD.encode(bytes),
+            )),
+            None => Ok(FieldValue::Null),
+        }
+    }
 
-            let key = match key_val {
-                FieldValue::String(s) => s,
-                FieldValue::Integer(i) => i.to_string(),
-                _ => {
-                    return Err(SqlError::ExecutionError {
-                        message: "MAP keys must be strings or integers".to_string(),
-                        query: None,
-                    });
-                }
-            };
+    fn base64_decode_function(
+        args: &[Expr],
+        record: &StreamRecord,
+    ) -> Result<FieldValue, SqlError> {
+        use base64::Engine;
 
-            map.insert(key, value_val);
+        if args.len() != 1 {
+            return Err(SqlError::ExecutionError {
+                message: "BASE64_DECODE requires exactly one argument".to_string(),
+                query: None,
+            });
         }
 
-        Ok(FieldValue::Map(map))
+        let Some(text) = Self::text_argument(&args[0], record)? else {
+            return Ok(FieldValue::Null);
+        };
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(text.trim())
+            .map_err(|e| SqlError::ExecutionError {
+                message: format!("BASE64_DECODE: invalid base64 input: {}", e),
+                query: None,
+            })?;
+        String::from_utf8(bytes)
+            .map(FieldValue::String)
+            .map_err(|_| SqlError::ExecutionError {
+                message: "BASE64_DECODE: decoded bytes are not valid UTF-8 text".to_string(),
+                query: None,
+            })
     }
 
-    fn array_length_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
+    fn url_decode_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
         if args.len() != 1 {
             return Err(SqlError::ExecutionError {
-                message: "ARRAY_LENGTH requires exactly one argument".to_string(),
+                message: "URL_DECODE requires exactly one argument".to_string(),
+                query: None,
+            });
+        }
+
+        let Some(text) = Self::text_argument(&args[0], record)? else {
+            return Ok(FieldValue::Null);
+        };
+        // '+' is decoded as a space, matching form-encoded query strings
+        let text = text.replace('+', " ");
+        percent_encoding::percent_decode_str(&text)
+            .decode_utf8()
+            .map(|decoded| FieldValue::String(decoded.into_owned()))
+            .map_err(|_| SqlError::ExecutionError {
+                message: "URL_DECODE: decoded bytes are not valid UTF-8 text".to_string(),
+                query: None,
+            })
+    }
+
+    fn parse_url_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
+        if args.len() != 2 && args.len() != 3 {
+            return Err(SqlError::ExecutionError {
+                message:
+                    "PARSE_URL requires two or three arguments: PARSE_URL(url, part[, query_key])"
+                        .to_string(),
+                query: None,
+            });
+        }
+
+        let (Some(text), Some(part)) = (
+            Self::text_argument(&args[0], record)?,
+            Self::text_argument(&args[1], record)?,
+        ) else {
+            return Ok(FieldValue::Null);
+        };
+        let query_key = match args.get(2) {
+            Some(arg) => match Self::text_argument(arg, record)? {
+                Some(key) => Some(key),
+                None => return Ok(FieldValue::Null),
+            },
+            None => None,
+        };
+
+        // Malformed URLs yield NULL rather than failing the record
+        let Ok(url) = url::Url::parse(&text) else {
+            return Ok(FieldValue::Null);
+        };
+
+        let optional = |value: Option<&str>| {
+            value
+                .map(|v| FieldValue::String(v.to_string()))
+                .unwrap_or(FieldValue::Null)
+        };
+
+        let value = match part.to_uppercase().as_str() {
+            "PROTOCOL" => FieldValue::String(url.scheme().to_string()),
+            "HOST" => optional(url.host_str()),
+            "PORT" => url
+                .port_or_known_default()
+                .map(|p| FieldValue::Integer(p as i64))
+                .unwrap_or(FieldValue::Null),
+            "PATH" => FieldValue::String(url.path().to_string()),
+            "QUERY" => match query_key {
+                Some(key) => url
+                    .query_pairs()
+                    .find(|(k, _)| *k == key)
+                    .map(|(_, v)| FieldValue::String(v.into_owned()))
+                    .unwrap_or(FieldValue::Null),
+                None => optional(url.query()),
+            },
+            "REF" | "FRAGMENT" => optional(url.fragment()),
+            "FILE" => FieldValue::String(match url.query() {
+                Some(query) => format!("{}?{}", url.path(), query),
+                None => url.path().to_string(),
+            }),
+            "AUTHORITY" => {
+                let host = url.host_str().unwrap_or_default();
+                FieldValue::String(match url.port() {
+                    Some(port) => format!("{}:{}", host, port),
+                    None => host.to_string(),
+                })
+            }
+            "USERINFO" => match (url.username(), url.password()) {
+                ("", None) => FieldValue::Null,
+                (user, Some(password)) => FieldValue::String(format!("{}:{}", user, password)),
+                (user, None) => FieldValue::String(user.to_string()),
+            },
+            other => {
+                return Err(SqlError::ExecutionError {
+                    message: format!(
+                        "PARSE_URL: unknown part '{}', expected PROTOCOL, HOST, PORT, PATH, QUERY, REF, FILE, AUTHORITY or USERINFO",
+                        other
+                    ),
+                    query: None,
+                })
+            }
+        };
+        Ok(value)
+    }
+
+    fn uuid_function(args: &[Expr], _record: &StreamRecord) -> Result<FieldValue, SqlError> {
+        if !args.is_empty() {
+            return Err(SqlError::ExecutionError {
+                message: "UUID takes no arguments".to_string(),
+                query: None,
+            });
+        }
+        Ok(FieldValue::String(uuid::Uuid::new_v4().to_string()))
+    }
+
+    fn join_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
+        if args.len() < 2 {
+            return Err(SqlError::ExecutionError {
+                message: "JOIN requires at least two arguments".to_string(),
+                query: None,
+            });
+        }
+
+        // Evaluate all arguments and join them with the first argument as delimiter
+        let delimiter_val = ExpressionEvaluator::evaluate_expression_value(&args[0], record)?;
+        let delimiter = match delimiter_val {
+            FieldValue::String(d) => d,
+            _ => {
+                return Err(SqlError::ExecutionError {
+                    message: "JOIN delimiter must be a string".to_string(),
+                    query: None,
+                });
+            }
+        };
+
+        let mut parts = Vec::new();
+        for arg in &args[1..] {
+            let val = ExpressionEvaluator::evaluate_expression_value(arg, record)?;
+            let str_val = match val {
+                FieldValue::String(s) => s,
+                FieldValue::Integer(i) => i.to_string(),
+                FieldValue::Float(f) => f.to_string(),
+                FieldValue::Boolean(b) => b.to_string(),
+                FieldValue::Null => "NULL".to_string(),
+                FieldValue::Date(_)
+                | FieldValue::Timestamp(_)
+                | FieldValue::TimestampTz(_)
+                | FieldValue::Decimal(_)
+                | FieldValue::ScaledInteger(_, _)
+                | FieldValue::Array(_)
+                | FieldValue::Map(_)
+                | FieldValue::Struct(_)
+                | FieldValue::Interval { .. }
+                | FieldValue::Bytes(_)
+                | FieldValue::Uuid(_)
+                | FieldValue::Time(_) => val.to_display_string(),
+            };
+            parts.push(str_val);
+        }
+
+        Ok(FieldValue::String(parts.join(&delimiter)))
+    }
+
+    // JSON Functions
+
+    fn json_extract_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
+        let Some(matches) = Self::json_path_matches("JSON_EXTRACT", args, record)? else {
+            return Ok(FieldValue::Null);
+        };
+
+        // Scalars are returned as values, objects and arrays as JSON text; the root of
+        // JSON text is the text as given
+        match (matches.values.as_slice(), matches.definite) {
+            ([], _) => Ok(FieldValue::Null),
+            ([single], true) => match single {
+                FieldValue::Struct(_) | FieldValue::Map(_) | FieldValue::Array(_) => {
+                    match matches.root_text {
+                        Some(text) => Ok(FieldValue::String(text)),
+                        None => json_path::to_json_string(single).map(FieldValue::String),
+                    }
+                }
+                scalar => Ok(scalar.clone()),
+            },
+            _ => json_path::to_json_string(&FieldValue::Array(matches.values))
+                .map(FieldValue::String),
+        }
+    }
+
+    fn json_value_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
+        let Some(matches) = Self::json_path_matches("JSON_VALUE", args, record)? else {
+            return Ok(FieldValue::Null);
+        };
+
+        // JSON_VALUE only returns a single scalar; anything else is NULL
+        match matches.values.as_slice() {
+            [FieldValue::Struct(_) | FieldValue::Map(_) | FieldValue::Array(_)] => {
+                Ok(FieldValue::Null)
+            }
+            [single] => Ok(single.clone()),
+            _ => Ok(FieldValue::Null),
+        }
+    }
+
+    fn json_query_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
+        let Some(mut matches) = Self::json_path_matches("JSON_QUERY", args, record)? else {
+            return Ok(FieldValue::Null);
+        };
+
+        // Definite paths return the selected value, wildcards and filters an array of matches
+        if matches.definite {
+            Ok(matches.values.pop().unwrap_or(FieldValue::Null))
+        } else {
+            Ok(FieldValue::Array(matches.values))
+        }
+    }
+
+    /// Evaluate the `(json, path)` arguments shared by the JSON path functions.
+    ///
+    /// Returns `None` when the input is NULL or not valid JSON text.
+    fn json_path_matches(
+        name: &str,
+        args: &[Expr],
+        record: &StreamRecord,
+    ) -> Result<Option<JsonPathMatches>, SqlError> {
+        if args.len() != 2 {
+            return Err(SqlError::ExecutionError {
+                message: format!(
+                    "{} requires exactly two arguments: {}(json, path)",
+                    name, name
+                ),
+                query: None,
+            });
+        }
+
+        let json_val = ExpressionEvaluator::evaluate_expression_value(&args[0], record)?;
+        let path = match ExpressionEvaluator::evaluate_expression_value(&args[1], record)? {
+            FieldValue::String(p) => JsonPath::parse(&p)?,
+            FieldValue::Null => return Ok(None),
+            _ => {
+                return Err(SqlError::ExecutionError {
+                    message: format!("{} path must be a string", name),
+                    query: None,
+                });
+            }
+        };
+
+        let (document, text) = match json_val {
+            FieldValue::Null => return Ok(None),
+            // Malformed JSON text yields NULL rather than failing the record
+            FieldValue::String(text) => match json_path::parse_json(&text) {
+                Ok(document) => (document, Some(text)),
+                Err(_) => return Ok(None),
+            },
+            other => (other, None),
+        };
+
+        Ok(Some(JsonPathMatches {
+            values: path.select(&document).into_iter().cloned().collect(),
+            definite: path.is_definite(),
+            root_text: text.filter(|_| path.is_root()),
+        }))
+    }
+
+    fn parse_json_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
+        if args.len() != 1 {
+            return Err(SqlError::ExecutionError {
+                message: "PARSE_JSON requires exactly one argument: PARSE_JSON(json_string)"
+                    .to_string(),
+                query: None,
+            });
+        }
+
+        match ExpressionEvaluator::evaluate_expression_value(&args[0], record)? {
+            FieldValue::String(text) => json_path::parse_json(&text),
+            // Already structured values pass through unchanged
+            other => Ok(other),
+        }
+    }
+
+    fn to_json_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
+        if args.len() != 1 {
+            return Err(SqlError::ExecutionError {
+                message: "TO_JSON requires exactly one argument: TO_JSON(value)".to_string(),
+                query: None,
+            });
+        }
+
+        let value = ExpressionEvaluator::evaluate_expression_value(&args[0], record)?;
+        json_path::to_json_string(&value).map(FieldValue::String)
+    }
+
+    fn json_object_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
+        if args.len() % 2 != 0 {
+            return Err(SqlError::ExecutionError {
+                message: "JSON_OBJECT requires key/value pairs: JSON_OBJECT(key1, value1, ...)"
+                    .to_string(),
+                query: None,
+            });
+        }
+
+        let mut members = HashMap::new();
+        for pair in args.chunks(2) {
+            let key = match ExpressionEvaluator::evaluate_expression_value(&pair[0], record)? {
+                FieldValue::String(key) => key,
+                _ => {
+                    return Err(SqlError::ExecutionError {
+                        message: "JSON_OBJECT keys must be strings".to_string(),
+                        query: None,
+                    });
+                }
+            };
+            let value = ExpressionEvaluator::evaluate_expression_value(&pair[1], record)?;
+            members.insert(key, value);
+        }
+        Ok(FieldValue::Struct(members))
+    }
+
+    fn json_array_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
+        let values = args
+            .iter()
+            .map(|arg| ExpressionEvaluator::evaluate_expression_value(arg, record))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(FieldValue::Array(values))
+    }
+
+    fn json_arrayagg_function(
+        args: &[Expr],
+        record: &StreamRecord,
+    ) -> Result<FieldValue, SqlError> {
+        if args.len() != 1 {
+            return Err(SqlError::ExecutionError {
+                message: "JSON_ARRAYAGG requires exactly one argument: JSON_ARRAYAGG(value)"
+                    .to_string(),
+                query: None,
+            });
+        }
+
+        // For single record, wrap the value in an array
+        let value = ExpressionEvaluator::evaluate_expression_value(&args[0], record)?;
+        Ok(FieldValue::Array(vec![value]))
+    }
+
+    fn json_objectagg_function(
+        args: &[Expr],
+        record: &StreamRecord,
+    ) -> Result<FieldValue, SqlError> {
+        if args.len() != 2 {
+            return Err(SqlError::ExecutionError {
+                message:
+                    "JSON_OBJECTAGG requires exactly two arguments: JSON_OBJECTAGG(key, value)"
+                        .to_string(),
+                query: None,
+            });
+        }
+
+        // For single record, build a one-member object
+        Self::json_object_function(args, record)
+    }
+
+    // Conversion Functions
+
+    fn cast_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
+        if args.len() != 2 && args.len() != 3 {
+            return Err(SqlError::ExecutionError {
+                message: "CAST requires two or three arguments: CAST(value, type[, rounding_mode])"
+                    .to_string(),
+                query: None,
+            });
+        }
+
+        let value = ExpressionEvaluator::evaluate_expression_value(&args[0], record)?;
+        let target_type = match &args[1] {
+            Expr::Literal(LiteralValue::String(type_str), _) => type_str.to_uppercase(),
+            _ => {
+                return Err(SqlError::ExecutionError {
+                    message: "CAST target type must be a string literal".to_string(),
+                    query: None,
+                });
+            }
+        };
+
+        // The rounding mode only applies when narrowing to a declared DECIMAL(p, s)
+        if let Some(mode_arg) = args.get(2) {
+            let mode = Self::rounding_mode_argument(mode_arg, record)?;
+            return match DecimalSpec::parse_type_name(&target_type)? {
+                Some(spec) => spec.coerce(value, mode),
+                None => Err(SqlError::ExecutionError {
+                    message: format!(
+                        "CAST rounding mode requires a DECIMAL(p, s) target, got {}",
+                        target_type
+                    ),
+                    query: None,
+                }),
+            };
+        }
+
+        value.cast_to(&target_type)
+    }
+
+    /// Evaluate a rounding mode argument such as `'HALF_EVEN'`
+    fn rounding_mode_argument(arg: &Expr, record: &StreamRecord) -> Result<RoundingMode, SqlError> {
+        match Self::text_argument(arg, record)? {
+            Some(name) => RoundingMode::parse(&name),
+            None => Ok(RoundingMode::default()),
+        }
+    }
+
+    // System Functions
+
+    fn timestamp_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
+        if !args.is_empty() {
+            return Err(SqlError::ExecutionError {
+                message: "TIMESTAMP() takes no arguments".to_string(),
+                query: None,
+            });
+        }
+        // Return current record timestamp
+        Ok(FieldValue::Integer(record.timestamp))
+    }
+
+    // Advanced Type Functions
+
+    fn array_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
+        // ARRAY constructor function - creates an array from arguments
+        let mut array_values = Vec::new();
+        for arg in args {
+            let value = ExpressionEvaluator::evaluate_expression_value(arg, record)?;
+            array_values.push(value);
+        }
+        Ok(FieldValue::Array(array_values))
+    }
+
+    fn struct_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
+        if args.is_empty() {
+            return Ok(FieldValue::Struct(HashMap::new()));
+        }
+
+        let mut struct_map = HashMap::new();
+
+        if args.len() % 2 == 0 {
+            // Even number of arguments - treat as name-value pairs
+            for chunk in args.chunks(2) {
+                let name_val = ExpressionEvaluator::evaluate_expression_value(&chunk[0], record)?;
+                let value_val = ExpressionEvaluator::evaluate_expression_value(&chunk[1], record)?;
+
+                let name = match name_val {
+                    FieldValue::String(s) => s,
+                    _ => {
+                        return Err(SqlError::ExecutionError {
+                            message: "STRUCT field names must be strings".to_string(),
+                            query: None,
+                        });
+                    }
+                };
+
+                struct_map.insert(name, value_val);
+            }
+        } else {
+            // Odd number of arguments - treat as positional fields (field0, field1, field2...)
+            for (i, arg) in args.iter().enumerate() {
+                let value = ExpressionEvaluator::evaluate_expression_value(arg, record)?;
+                struct_map.insert(format!("field{}", i), value);
+            }
+        }
+
+        Ok(FieldValue::Struct(struct_map))
+    }
+
+    fn map_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
+        if args.is_empty() {
+            return Ok(FieldValue::Map(HashMap::new()));
+        }
+
+        // MAP constructor - alternating keys and values
+        if args.len() % 2 != 0 {
+            return Err(SqlError::TypeError {
+                expected: "even number of arguments (key-value pairs)".to_string(),
+                actual: format!("{} arguments", args.len()),
+                value: None,
+            });
+        }
+
+        let mut map = HashMap::new();
+        for chunk in args.chunks(2) {
+            let key_val = ExpressionEvaluator::evaluate_expression_value(&chunk[0], record)?;
+            let value_val = ExpressionEvaluator::evaluate_expression_value(&chunk[1], record)?;
+
+            let key = match key_val {
+                FieldValue::String(s) => s,
+                FieldValue::Integer(i) => i.to_string(),
+                _ => {
+                    return Err(SqlError::ExecutionError {
+                        message: "MAP keys must be strings or integers".to_string(),
+                        query: None,
+                    });
+                }
+            };
+
+            map.insert(key, value_val);
+        }
+
+        Ok(FieldValue::Map(map))
+    }
+
+    fn array_length_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
+        if args.len() != 1 {
+            return Err(SqlError::ExecutionError {
+                message: "ARRAY_LENGTH requires exactly one argument".to_string(),
                 query: None,
             });
         }
@@ -1272,6 +1996,7 @@ impl BuiltinFunctions {
             FieldValue::Boolean(b) => b.to_string(),
             FieldValue::Date(date) => date.format("%Y-%m-%d").to_string(),
             FieldValue::Timestamp(ts) => ts.format("%Y-%m-%d %H:%M:%S").to_string(),
+            FieldValue::TimestampTz(ts) => ts.to_rfc3339(),
             FieldValue::Decimal(dec) => dec.to_string(),
             FieldValue::ScaledInteger(value, scale) => {
                 // Use to_display_string for consistent formatting
@@ -1313,6 +2038,9 @@ impl BuiltinFunctions {
             FieldValue::Interval { value, unit } => {
                 format!("{} {:?}", value, unit)
             }
+            FieldValue::Bytes(_) | FieldValue::Uuid(_) | FieldValue::Time(_) => {
+                value.to_display_string()
+            }
         }
     }
 
@@ -1556,74 +2284,451 @@ impl BuiltinFunctions {
                     _ => Ok(FieldValue::Array(vec![])), // Not an object
                 }
             }
-            FieldValue::Null => Ok(FieldValue::Null),
-            _ => Ok(FieldValue::Array(vec![])), // Not a map/struct
+            FieldValue::Null => Ok(FieldValue::Null),
+            _ => Ok(FieldValue::Array(vec![])), // Not a map/struct
+        }
+    }
+
+    // Higher-order array functions
+
+    /// Evaluate the array argument of a higher-order function. NULL propagates as `None`.
+    fn array_argument(
+        function: &str,
+        arg: &Expr,
+        record: &StreamRecord,
+    ) -> Result<Option<Vec<FieldValue>>, SqlError> {
+        match ExpressionEvaluator::evaluate_expression_value(arg, record)? {
+            FieldValue::Array(values) => Ok(Some(values)),
+            FieldValue::Null => Ok(None),
+            other => Err(SqlError::TypeError {
+                expected: format!("array argument to {}", function),
+                actual: other.type_name().to_string(),
+                value: None,
+            }),
+        }
+    }
+
+    /// Split a lambda argument into its parameter names and body, checking that it
+    /// declares one of the accepted parameter counts.
+    fn lambda_argument<'a>(
+        function: &str,
+        arg: &'a Expr,
+        arities: &[usize],
+    ) -> Result<(&'a [String], &'a Expr), SqlError> {
+        match arg {
+            Expr::Lambda { params, body, .. } if arities.contains(&params.len()) => {
+                Ok((params.as_slice(), body.as_ref()))
+            }
+            Expr::Lambda { params, .. } => Err(SqlError::ExecutionError {
+                message: format!(
+                    "{} lambda takes {} parameter(s), got {}",
+                    function,
+                    arities
+                        .iter()
+                        .map(|n| n.to_string())
+                        .collect::<Vec<_>>()
+                        .join(" or "),
+                    params.len()
+                ),
+                query: None,
+            }),
+            _ => Err(SqlError::ExecutionError {
+                message: format!(
+                    "{} expects a lambda expression such as x -> x + 1",
+                    function
+                ),
+                query: None,
+            }),
+        }
+    }
+
+    /// Evaluate a lambda body against `scope` with its parameters bound to `values`.
+    /// Parameters shadow record fields of the same name.
+    fn apply_lambda(
+        params: &[String],
+        body: &Expr,
+        values: Vec<FieldValue>,
+        scope: &mut StreamRecord,
+    ) -> Result<FieldValue, SqlError> {
+        for (param, value) in params.iter().zip(values) {
+            scope.fields.insert(param.clone(), value);
+        }
+        ExpressionEvaluator::evaluate_expression_value(body, scope)
+    }
+
+    fn transform_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
+        if args.len() != 2 {
+            return Err(SqlError::ExecutionError {
+                message: "TRANSFORM requires exactly two arguments: TRANSFORM(array, x -> expr)"
+                    .to_string(),
+                query: None,
+            });
+        }
+
+        let Some(values) = Self::array_argument("TRANSFORM", &args[0], record)? else {
+            return Ok(FieldValue::Null);
+        };
+        let (params, body) = Self::lambda_argument("TRANSFORM", &args[1], &[1, 2])?;
+
+        let mut scope = record.clone();
+        let mut result = Vec::with_capacity(values.len());
+        for (index, value) in values.into_iter().enumerate() {
+            result.push(Self::apply_lambda(
+                params,
+                body,
+                vec![value, FieldValue::Integer(index as i64)],
+                &mut scope,
+            )?);
+        }
+        Ok(FieldValue::Array(result))
+    }
+
+    fn filter_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
+        if args.len() != 2 {
+            return Err(SqlError::ExecutionError {
+                message: "FILTER requires exactly two arguments: FILTER(array, x -> condition)"
+                    .to_string(),
+                query: None,
+            });
+        }
+
+        let Some(values) = Self::array_argument("FILTER", &args[0], record)? else {
+            return Ok(FieldValue::Null);
+        };
+        let (params, body) = Self::lambda_argument("FILTER", &args[1], &[1, 2])?;
+
+        let mut scope = record.clone();
+        let mut result = Vec::new();
+        for (index, value) in values.into_iter().enumerate() {
+            let keep = Self::apply_lambda(
+                params,
+                body,
+                vec![value.clone(), FieldValue::Integer(index as i64)],
+                &mut scope,
+            )?;
+            match keep {
+                FieldValue::Boolean(true) => result.push(value),
+                FieldValue::Boolean(false) | FieldValue::Null => {}
+                other => {
+                    return Err(SqlError::TypeError {
+                        expected: "boolean result from FILTER lambda".to_string(),
+                        actual: other.type_name().to_string(),
+                        value: None,
+                    })
+                }
+            }
+        }
+        Ok(FieldValue::Array(result))
+    }
+
+    fn reduce_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
+        if args.len() != 3 && args.len() != 4 {
+            return Err(SqlError::ExecutionError {
+                message: "REDUCE requires three or four arguments: REDUCE(array, initial, (acc, x) -> expr[, acc -> finish])".to_string(),
+                query: None,
+            });
+        }
+
+        let Some(values) = Self::array_argument("REDUCE", &args[0], record)? else {
+            return Ok(FieldValue::Null);
+        };
+        let (params, body) = Self::lambda_argument("REDUCE", &args[2], &[2])?;
+
+        let mut scope = record.clone();
+        let mut acc = ExpressionEvaluator::evaluate_expression_value(&args[1], record)?;
+        for value in values {
+            acc = Self::apply_lambda(params, body, vec![acc, value], &mut scope)?;
+        }
+
+        if let Some(finish) = args.get(3) {
+            let (params, body) = Self::lambda_argument("REDUCE", finish, &[1])?;
+            acc = Self::apply_lambda(params, body, vec![acc], &mut scope)?;
+        }
+        Ok(acc)
+    }
+
+    fn array_sort_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
+        if args.is_empty() || args.len() > 2 {
+            return Err(SqlError::ExecutionError {
+                message: "ARRAY_SORT requires one or two arguments: ARRAY_SORT(array[, (a, b) -> comparison])".to_string(),
+                query: None,
+            });
+        }
+
+        let Some(mut values) = Self::array_argument("ARRAY_SORT", &args[0], record)? else {
+            return Ok(FieldValue::Null);
+        };
+
+        match args.get(1) {
+            None => values.sort_by(Self::array_sort_order),
+            Some(comparator) => {
+                let (params, body) = Self::lambda_argument("ARRAY_SORT", comparator, &[2])?;
+                let mut scope = record.clone();
+                // sort_by cannot fail, so remember the first comparator error and report it afterwards
+                let mut error = None;
+                values.sort_by(|a, b| {
+                    if error.is_some() {
+                        return std::cmp::Ordering::Equal;
+                    }
+                    match Self::apply_lambda(params, body, vec![a.clone(), b.clone()], &mut scope) {
+                        Ok(FieldValue::Integer(n)) => n.cmp(&0),
+                        Ok(other) => {
+                            error = Some(SqlError::TypeError {
+                                expected: "integer result from ARRAY_SORT comparator".to_string(),
+                                actual: other.type_name().to_string(),
+                                value: None,
+                            });
+                            std::cmp::Ordering::Equal
+                        }
+                        Err(e) => {
+                            error = Some(e);
+                            std::cmp::Ordering::Equal
+                        }
+                    }
+                });
+                if let Some(e) = error {
+                    return Err(e);
+                }
+            }
+        }
+        Ok(FieldValue::Array(values))
+    }
+
+    /// Default ARRAY_SORT ordering: ascending, with NULLs last. Values of types that
+    /// cannot be compared with each other keep their relative order.
+    pub(crate) fn array_sort_order(a: &FieldValue, b: &FieldValue) -> std::cmp::Ordering {
+        use std::cmp::Ordering;
+
+        match (a, b) {
+            (FieldValue::Null, FieldValue::Null) => Ordering::Equal,
+            (FieldValue::Null, _) => Ordering::Greater,
+            (_, FieldValue::Null) => Ordering::Less,
+            (FieldValue::String(a), FieldValue::String(b)) => a.cmp(b),
+            (FieldValue::Boolean(a), FieldValue::Boolean(b)) => a.cmp(b),
+            (FieldValue::Date(a), FieldValue::Date(b)) => a.cmp(b),
+            (FieldValue::Timestamp(a), FieldValue::Timestamp(b)) => a.cmp(b),
+            (FieldValue::TimestampTz(a), FieldValue::TimestampTz(b)) => a.cmp(b),
+            (a, b) if a.is_numeric() && b.is_numeric() => {
+                if FieldValueComparator::compare_values_for_boolean(a, b, &BinaryOperator::LessThan)
+                    .unwrap_or(false)
+                {
+                    Ordering::Less
+                } else if FieldValueComparator::compare_values_for_boolean(
+                    a,
+                    b,
+                    &BinaryOperator::GreaterThan,
+                )
+                .unwrap_or(false)
+                {
+                    Ordering::Greater
+                } else {
+                    Ordering::Equal
+                }
+            }
+            _ => Ordering::Equal,
+        }
+    }
+
+    fn array_distinct_function(
+        args: &[Expr],
+        record: &StreamRecord,
+    ) -> Result<FieldValue, SqlError> {
+        if args.len() != 1 {
+            return Err(SqlError::ExecutionError {
+                message: "ARRAY_DISTINCT requires exactly one argument".to_string(),
+                query: None,
+            });
+        }
+
+        let Some(values) = Self::array_argument("ARRAY_DISTINCT", &args[0], record)? else {
+            return Ok(FieldValue::Null);
+        };
+
+        // Keep the first occurrence of each value, preserving input order
+        let mut result: Vec<FieldValue> = Vec::with_capacity(values.len());
+        for value in values {
+            if !result.iter().any(|seen| Self::values_equal(seen, &value)) {
+                result.push(value);
+            }
         }
+        Ok(FieldValue::Array(result))
     }
 
     fn extract_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
-        if args.len() != 2 {
+        if args.len() != 2 && args.len() != 3 {
             return Err(SqlError::ExecutionError {
-                message: "EXTRACT requires exactly two arguments: EXTRACT(part, timestamp)"
-                    .to_string(),
+                message:
+                    "EXTRACT requires two or three arguments: EXTRACT(part, timestamp[, time_zone])"
+                        .to_string(),
                 query: None,
             });
         }
 
         let part_val = ExpressionEvaluator::evaluate_expression_value(&args[0], record)?;
         let timestamp_val = ExpressionEvaluator::evaluate_expression_value(&args[1], record)?;
+        if matches!(timestamp_val, FieldValue::Null) {
+            return Ok(FieldValue::Null);
+        }
 
-        match (part_val, timestamp_val) {
-            (FieldValue::String(part), FieldValue::Integer(ts)) => {
-                use chrono::{Datelike, TimeZone, Timelike, Utc};
+        let part = match part_val {
+            FieldValue::String(part) => part,
+            _ => {
+                return Err(SqlError::ExecutionError {
+                    message: "EXTRACT requires part name (string) and timestamp".to_string(),
+                    query: None,
+                })
+            }
+        };
+        let dt = Self::zoned_timestamp_arg("EXTRACT", &timestamp_val, args.get(2), record)?;
+
+        use chrono::{Datelike, Offset, Timelike};
+        let offset_seconds = dt.offset().fix().local_minus_utc() as i64;
+
+        let result = match part.to_uppercase().as_str() {
+            "YEAR" => dt.year() as i64,
+            "MONTH" => dt.month() as i64,
+            "DAY" => dt.day() as i64,
+            "HOUR" => dt.hour() as i64,
+            "MINUTE" => dt.minute() as i64,
+            "SECOND" => dt.second() as i64,
+            "DOW" => {
+                // Day of week (0 = Sunday, 6 = Saturday)
+                dt.weekday().num_days_from_sunday() as i64
+            }
+            "DOY" => dt.ordinal() as i64, // Day of year (1-366)
+            "WEEK" => {
+                // Get ISO week number (1-53)
+                dt.iso_week().week() as i64
+            }
+            "EPOCH" => dt.timestamp(),
+            "QUARTER" => ((dt.month() - 1) / 3 + 1) as i64,
+            "MILLISECOND" => dt.timestamp_subsec_millis() as i64,
+            "MICROSECOND" => dt.timestamp_subsec_micros() as i64,
+            "NANOSECOND" => dt.timestamp_subsec_nanos() as i64,
+            // UTC offset of the zone in effect at this instant
+            "TIMEZONE" => offset_seconds,
+            "TIMEZONE_HOUR" => offset_seconds / 3600,
+            "TIMEZONE_MINUTE" => (offset_seconds % 3600) / 60,
+            _ => {
+                return Err(SqlError::ExecutionError {
+                    message: format!(
+                        "Unsupported EXTRACT part: {}. Supported parts: YEAR, MONTH, DAY, HOUR, MINUTE, SECOND, DOW, DOY, EPOCH, WEEK, QUARTER, MILLISECOND, MICROSECOND, NANOSECOND, TIMEZONE, TIMEZONE_HOUR, TIMEZONE_MINUTE",
+                        part
+                    ),
+                    query: None,
+                });
+            }
+        };
+        Ok(FieldValue::Integer(result))
+    }
 
-                let dt = Utc.timestamp_millis_opt(ts).single().ok_or_else(|| {
-                    SqlError::ExecutionError {
-                        message: format!("Invalid timestamp: {}", ts),
+    /// Resolve a timestamp argument (epoch millis, TIMESTAMP or TIMESTAMPTZ) in the zone it
+    /// should be viewed in: the explicit zone argument if given, otherwise the value's own
+    /// zone, otherwise UTC.
+    fn zoned_timestamp_arg(
+        function_name: &str,
+        value: &FieldValue,
+        zone_arg: Option<&Expr>,
+        record: &StreamRecord,
+    ) -> Result<chrono::DateTime<chrono_tz::Tz>, SqlError> {
+        let zone = match zone_arg {
+            Some(expr) => match ExpressionEvaluator::evaluate_expression_value(expr, record)? {
+                FieldValue::String(name) => FieldValue::parse_time_zone(&name)?,
+                other => {
+                    return Err(SqlError::ExecutionError {
+                        message: format!(
+                            "{} time zone must be a string, got {}",
+                            function_name,
+                            other.type_name()
+                        ),
                         query: None,
-                    }
-                })?;
+                    })
+                }
+            },
+            None => match value {
+                FieldValue::TimestampTz(ts) => ts.timezone(),
+                _ => chrono_tz::Tz::UTC,
+            },
+        };
 
-                let result = match part.to_uppercase().as_str() {
-                    "YEAR" => dt.year() as i64,
-                    "MONTH" => dt.month() as i64,
-                    "DAY" => dt.day() as i64,
-                    "HOUR" => dt.hour() as i64,
-                    "MINUTE" => dt.minute() as i64,
-                    "SECOND" => dt.second() as i64,
-                    "DOW" => {
-                        // Day of week (0 = Sunday, 6 = Saturday)
-                        dt.weekday().num_days_from_sunday() as i64
-                    }
-                    "DOY" => dt.ordinal() as i64, // Day of year (1-366)
-                    "WEEK" => {
-                        // Get ISO week number (1-53)
-                        dt.iso_week().week() as i64
-                    }
-                    "EPOCH" => dt.timestamp(),
-                    "QUARTER" => ((dt.month() - 1) / 3 + 1) as i64,
-                    "MILLISECOND" => dt.timestamp_subsec_millis() as i64,
-                    "MICROSECOND" => dt.timestamp_subsec_micros() as i64,
-                    "NANOSECOND" => dt.timestamp_subsec_nanos() as i64,
-                    _ => {
-                        return Err(SqlError::ExecutionError {
+        value
+            .to_zoned_timestamp(zone)
+            .ok_or_else(|| SqlError::ExecutionError {
+                message: format!(
+                    "{} requires a timestamp (integer millis, TIMESTAMP or TIMESTAMPTZ), got {}",
+                    function_name,
+                    value.type_name()
+                ),
+                query: None,
+            })
+    }
+
+    fn convert_tz_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
+        if args.len() != 3 {
+            return Err(SqlError::ExecutionError {
+                message:
+                    "CONVERT_TZ requires exactly 3 arguments: CONVERT_TZ(timestamp, from_tz, to_tz)"
+                        .to_string(),
+                query: None,
+            });
+        }
+
+        let timestamp_val = ExpressionEvaluator::evaluate_expression_value(&args[0], record)?;
+        let from_val = ExpressionEvaluator::evaluate_expression_value(&args[1], record)?;
+        let to_val = ExpressionEvaluator::evaluate_expression_value(&args[2], record)?;
+
+        match (timestamp_val, from_val, to_val) {
+            (FieldValue::Null, _, _) | (_, FieldValue::Null, _) | (_, _, FieldValue::Null) => {
+                Ok(FieldValue::Null)
+            }
+            (value, FieldValue::String(from_tz), FieldValue::String(to_tz)) => {
+                use chrono::TimeZone;
+
+                let from_zone = FieldValue::parse_time_zone(&from_tz)?;
+                let to_zone = FieldValue::parse_time_zone(&to_tz)?;
+
+                // A naive timestamp is wall-clock time in the source zone; anything that
+                // already identifies an instant is simply moved to the target zone.
+                let instant = match value {
+                    FieldValue::Timestamp(local) => from_zone
+                        .from_local_datetime(&local)
+                        .earliest()
+                        .ok_or_else(|| SqlError::ExecutionError {
                             message: format!(
-                                "Unsupported EXTRACT part: {}. Supported parts: YEAR, MONTH, DAY, HOUR, MINUTE, SECOND, DOW, DOY, EPOCH, WEEK, QUARTER, MILLISECOND, MICROSECOND, NANOSECOND",
-                                part
+                                "Local time {} does not exist in time zone {}",
+                                local, from_tz
                             ),
                             query: None,
-                        });
-                    }
+                        })?,
+                    other => Self::zoned_timestamp_arg("CONVERT_TZ", &other, None, record)?,
                 };
-                Ok(FieldValue::Integer(result))
+                Ok(FieldValue::TimestampTz(instant.with_timezone(&to_zone)))
             }
             _ => Err(SqlError::ExecutionError {
-                message: "EXTRACT requires part name (string) and timestamp (integer)".to_string(),
+                message: "CONVERT_TZ requires a timestamp and two time zone names (strings)"
+                    .to_string(),
                 query: None,
             }),
         }
     }
 
+    /// `ts AT TIME ZONE 'zone'` - the parser desugars the operator into this function
+    fn at_time_zone_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
+        if args.len() != 2 {
+            return Err(SqlError::ExecutionError {
+                message: "AT TIME ZONE requires a timestamp and a time zone".to_string(),
+                query: None,
+            });
+        }
+
+        let timestamp_val = ExpressionEvaluator::evaluate_expression_value(&args[0], record)?;
+        if matches!(timestamp_val, FieldValue::Null) {
+            return Ok(FieldValue::Null);
+        }
+        let dt = Self::zoned_timestamp_arg("AT TIME ZONE", &timestamp_val, Some(&args[1]), record)?;
+        Ok(FieldValue::TimestampTz(dt))
+    }
+
     fn datediff_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
         if args.len() != 3 {
             return Err(SqlError::ExecutionError {
@@ -2001,10 +3106,10 @@ impl BuiltinFunctions {
     }
 
     fn date_format_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
-        if args.len() != 2 {
+        if args.len() != 2 && args.len() != 3 {
             return Err(SqlError::ExecutionError {
                 message:
-                    "DATE_FORMAT requires exactly two arguments: DATE_FORMAT(timestamp, format)"
+                    "DATE_FORMAT requires two or three arguments: DATE_FORMAT(timestamp, format[, time_zone])"
                         .to_string(),
                 query: None,
             });
@@ -2014,23 +3119,14 @@ impl BuiltinFunctions {
         let format_val = ExpressionEvaluator::evaluate_expression_value(&args[1], record)?;
 
         match (timestamp_val, format_val) {
-            (FieldValue::Integer(ts), FieldValue::String(format)) => {
-                use chrono::{TimeZone, Utc};
-
-                let dt = Utc.timestamp_millis_opt(ts).single().ok_or_else(|| {
-                    SqlError::ExecutionError {
-                        message: format!("Invalid timestamp: {}", ts),
-                        query: None,
-                    }
-                })?;
-
+            (FieldValue::Null, _) | (_, FieldValue::Null) => Ok(FieldValue::Null),
+            (timestamp, FieldValue::String(format)) => {
+                let dt = Self::zoned_timestamp_arg("DATE_FORMAT", &timestamp, args.get(2), record)?;
                 let formatted = dt.format(&format).to_string();
                 Ok(FieldValue::String(formatted))
             }
-            (FieldValue::Null, _) | (_, FieldValue::Null) => Ok(FieldValue::Null),
             _ => Err(SqlError::ExecutionError {
-                message: "DATE_FORMAT requires timestamp (integer) and format (string) arguments"
-                    .to_string(),
+                message: "DATE_FORMAT requires timestamp and format (string) arguments".to_string(),
                 query: None,
             }),
         }
@@ -2098,6 +3194,7 @@ impl BuiltinFunctions {
                         let timestamp = dt.and_utc().timestamp();
                         Ok(FieldValue::Integer(timestamp))
                     }
+                    FieldValue::TimestampTz(dt) => Ok(FieldValue::Integer(dt.timestamp())),
                     FieldValue::Null => Ok(FieldValue::Null),
                     _ => Err(SqlError::ExecutionError {
                         message: "UNIX_TIMESTAMP with argument requires a timestamp value".to_string(),
@@ -2112,6 +3209,446 @@ impl BuiltinFunctions {
         }
     }
 
+    fn to_timestamp_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
+        if args.is_empty() || args.len() > 2 {
+            return Err(SqlError::ExecutionError {
+                message: "TO_TIMESTAMP requires 1 or 2 arguments: TO_TIMESTAMP(value[, format])"
+                    .to_string(),
+                query: None,
+            });
+        }
+
+        let value = ExpressionEvaluator::evaluate_expression_value(&args[0], record)?;
+        if args.len() == 1 {
+            return match value {
+                // Numeric values are Unix timestamps in seconds
+                FieldValue::Integer(_) => value.cast_to("TIMESTAMP"),
+                FieldValue::Float(secs) => {
+                    let millis = (secs * 1000.0).round() as i64;
+                    chrono::DateTime::from_timestamp_millis(millis)
+                        .map(|dt| FieldValue::Timestamp(dt.naive_utc()))
+                        .ok_or_else(|| SqlError::ExecutionError {
+                            message: format!("Invalid Unix timestamp: {}", secs),
+                            query: None,
+                        })
+                }
+                other => other.cast_to("TIMESTAMP"),
+            };
+        }
+
+        let format_val = ExpressionEvaluator::evaluate_expression_value(&args[1], record)?;
+        match (value, format_val) {
+            (FieldValue::Null, _) | (_, FieldValue::Null) => Ok(FieldValue::Null),
+            (FieldValue::String(input), FieldValue::String(format)) => {
+                Self::parse_datetime_with_format(&input, &format)
+                    .map(FieldValue::Timestamp)
+                    .ok_or_else(|| SqlError::ExecutionError {
+                        message: format!(
+                            "Cannot parse '{}' as TIMESTAMP with format '{}'",
+                            input, format
+                        ),
+                        query: None,
+                    })
+            }
+            _ => Err(SqlError::ExecutionError {
+                message: "TO_TIMESTAMP requires string value and format arguments".to_string(),
+                query: None,
+            }),
+        }
+    }
+
+    fn to_date_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
+        if args.is_empty() || args.len() > 2 {
+            return Err(SqlError::ExecutionError {
+                message: "TO_DATE requires 1 or 2 arguments: TO_DATE(value[, format])".to_string(),
+                query: None,
+            });
+        }
+
+        let value = ExpressionEvaluator::evaluate_expression_value(&args[0], record)?;
+        if args.len() == 1 {
+            return match value {
+                FieldValue::Integer(millis) => {
+                    Self::local_datetime_of(&FieldValue::Integer(millis))
+                        .map(|(dt, _)| FieldValue::Date(dt.date()))
+                }
+                FieldValue::TimestampTz(ts) => Ok(FieldValue::Date(ts.date_naive())),
+                other => other.cast_to("DATE"),
+            };
+        }
+
+        let format_val = ExpressionEvaluator::evaluate_expression_value(&args[1], record)?;
+        match (value, format_val) {
+            (FieldValue::Null, _) | (_, FieldValue::Null) => Ok(FieldValue::Null),
+            (FieldValue::String(input), FieldValue::String(format)) => {
+                Self::parse_datetime_with_format(&input, &format)
+                    .map(|dt| FieldValue::Date(dt.date()))
+                    .ok_or_else(|| SqlError::ExecutionError {
+                        message: format!(
+                            "Cannot parse '{}' as DATE with format '{}'",
+                            input, format
+                        ),
+                        query: None,
+                    })
+            }
+            _ => Err(SqlError::ExecutionError {
+                message: "TO_DATE requires string value and format arguments".to_string(),
+                query: None,
+            }),
+        }
+    }
+
+    /// Parse a date/time string with either a strftime format (`%Y-%m-%d`) or a SQL-style
+    /// template (`YYYY-MM-DD HH24:MI:SS`). Formats without a time part yield midnight and
+    /// formats with a UTC offset are normalized to UTC.
+    fn parse_datetime_with_format(input: &str, format: &str) -> Option<chrono::NaiveDateTime> {
+        let format = if format.contains('%') {
+            format.to_string()
+        } else {
+            Self::sql_format_to_strftime(format)
+        };
+        let input = input.trim();
+
+        chrono::NaiveDateTime::parse_from_str(input, &format)
+            .ok()
+            .or_else(|| {
+                chrono::DateTime::parse_from_str(input, &format)
+                    .ok()
+                    .map(|dt| dt.naive_utc())
+            })
+            .or_else(|| {
+                chrono::NaiveDate::parse_from_str(input, &format)
+                    .ok()
+                    .and_then(|d| d.and_hms_opt(0, 0, 0))
+            })
+    }
+
+    /// Translate a SQL-style format template (as used by TO_TIMESTAMP/TO_CHAR in other
+    /// databases) into a chrono strftime string. Text in double quotes is copied literally.
+    fn sql_format_to_strftime(format: &str) -> String {
+        const PATTERNS: &[(&str, &str)] = &[
+            ("TZH:TZM", "%:z"),
+            ("YYYY", "%Y"),
+            ("HH24", "%H"),
+            ("HH12", "%I"),
+            ("MONTH", "%B"),
+            ("YY", "%y"),
+            ("MON", "%b"),
+            ("MM", "%m"),
+            ("DD", "%d"),
+            ("DY", "%a"),
+            ("HH", "%I"),
+            ("MI", "%M"),
+            ("SS", "%S"),
+            ("MS", "%3f"),
+            ("US", "%6f"),
+            ("AM", "%p"),
+            ("PM", "%p"),
+        ];
+
+        let mut result = String::new();
+        let mut rest = format;
+        while let Some(c) = rest.chars().next() {
+            if c == '"' {
+                // Quoted literal text
+                let literal_end = rest[1..].find('"').map(|i| i + 1).unwrap_or(rest.len());
+                result.push_str(&rest[1..literal_end]);
+                rest = rest.get(literal_end + 1..).unwrap_or("");
+                continue;
+            }
+            let upper = rest.to_uppercase();
+            match PATTERNS
+                .iter()
+                .find(|(pattern, _)| upper.starts_with(pattern))
+            {
+                Some((pattern, replacement)) => {
+                    result.push_str(replacement);
+                    rest = &rest[pattern.len()..];
+                }
+                None => {
+                    result.push(c);
+                    rest = &rest[c.len_utf8()..];
+                }
+            }
+        }
+        result
+    }
+
+    /// Resolve a temporal value to its wall-clock time: epoch millis and TIMESTAMP are UTC,
+    /// TIMESTAMPTZ uses its own zone. The zone is returned so results can be mapped back.
+    fn local_datetime_of(
+        value: &FieldValue,
+    ) -> Result<(chrono::NaiveDateTime, Option<chrono_tz::Tz>), SqlError> {
+        match value {
+            FieldValue::Integer(millis) => chrono::DateTime::from_timestamp_millis(*millis)
+                .map(|dt| (dt.naive_utc(), None))
+                .ok_or_else(|| SqlError::ExecutionError {
+                    message: format!("Invalid timestamp: {}", millis),
+                    query: None,
+                }),
+            FieldValue::Timestamp(ts) => Ok((*ts, None)),
+            FieldValue::Date(d) => Ok((d.and_hms_opt(0, 0, 0).unwrap(), None)),
+            FieldValue::TimestampTz(ts) => Ok((ts.naive_local(), Some(ts.timezone()))),
+            other => Err(SqlError::ExecutionError {
+                message: format!(
+                    "Expected a timestamp (integer millis, DATE, TIMESTAMP or TIMESTAMPTZ), got {}",
+                    other.type_name()
+                ),
+                query: None,
+            }),
+        }
+    }
+
+    /// Apply a wall-clock transformation to a temporal value, keeping its type
+    fn map_local_datetime<F>(value: &FieldValue, f: F) -> Result<FieldValue, SqlError>
+    where
+        F: Fn(chrono::NaiveDateTime) -> Option<chrono::NaiveDateTime>,
+    {
+        use chrono::TimeZone;
+
+        let (local, zone) = Self::local_datetime_of(value)?;
+        let result = f(local).ok_or_else(|| SqlError::ExecutionError {
+            message: format!("Timestamp out of range: {}", local),
+            query: None,
+        })?;
+
+        match (value, zone) {
+            (FieldValue::Integer(_), _) => {
+                Ok(FieldValue::Integer(result.and_utc().timestamp_millis()))
+            }
+            (FieldValue::Date(_), _) => Ok(FieldValue::Date(result.date())),
+            (FieldValue::TimestampTz(_), Some(zone)) => zone
+                .from_local_datetime(&result)
+                .earliest()
+                .map(FieldValue::TimestampTz)
+                .ok_or_else(|| SqlError::ExecutionError {
+                    message: format!(
+                        "Local time {} does not exist in time zone {}",
+                        result,
+                        zone.name()
+                    ),
+                    query: None,
+                }),
+            _ => Ok(FieldValue::Timestamp(result)),
+        }
+    }
+
+    fn date_trunc_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
+        if args.len() != 2 {
+            return Err(SqlError::ExecutionError {
+                message: "DATE_TRUNC requires exactly 2 arguments: DATE_TRUNC(unit, timestamp)"
+                    .to_string(),
+                query: None,
+            });
+        }
+
+        let unit_val = ExpressionEvaluator::evaluate_expression_value(&args[0], record)?;
+        let value = ExpressionEvaluator::evaluate_expression_value(&args[1], record)?;
+
+        let unit = match unit_val {
+            FieldValue::String(unit) => unit.to_lowercase(),
+            _ => {
+                return Err(SqlError::ExecutionError {
+                    message: "DATE_TRUNC unit must be a string".to_string(),
+                    query: None,
+                })
+            }
+        };
+        if matches!(value, FieldValue::Null) {
+            return Ok(FieldValue::Null);
+        }
+
+        use chrono::{Datelike, NaiveDate, Timelike};
+        let truncate: fn(chrono::NaiveDateTime) -> Option<chrono::NaiveDateTime> =
+            match unit.trim_end_matches('s') {
+                "millisecond" => |dt| dt.with_nanosecond(dt.nanosecond() / 1_000_000 * 1_000_000),
+                "second" => |dt| dt.with_nanosecond(0),
+                "minute" => |dt| dt.date().and_hms_opt(dt.hour(), dt.minute(), 0),
+                "hour" => |dt| dt.date().and_hms_opt(dt.hour(), 0, 0),
+                "day" => |dt| dt.date().and_hms_opt(0, 0, 0),
+                "week" => |dt| {
+                    // ISO weeks start on Monday
+                    let days_from_monday = dt.weekday().num_days_from_monday() as i64;
+                    (dt.date() - chrono::Duration::days(days_from_monday)).and_hms_opt(0, 0, 0)
+                },
+                "month" => |dt| NaiveDate::from_ymd_opt(dt.year(), dt.month(), 1)?.and_hms_opt(0, 0, 0),
+                "quarter" => |dt| {
+                    let quarter_month = (dt.month() - 1) / 3 * 3 + 1;
+                    NaiveDate::from_ymd_opt(dt.year(), quarter_month, 1)?.and_hms_opt(0, 0, 0)
+                },
+                "year" => |dt| NaiveDate::from_ymd_opt(dt.year(), 1, 1)?.and_hms_opt(0, 0, 0),
+                _ => {
+                    return Err(SqlError::ExecutionError {
+                        message: format!(
+                            "Unsupported DATE_TRUNC unit: {}. Supported units: millisecond, second, minute, hour, day, week, month, quarter, year",
+                            unit
+                        ),
+                        query: None,
+                    })
+                }
+            };
+
+        Self::map_local_datetime(&value, truncate)
+    }
+
+    fn time_bucket_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
+        if args.len() != 2 && args.len() != 3 {
+            return Err(SqlError::ExecutionError {
+                message: "TIME_BUCKET requires 2 or 3 arguments: TIME_BUCKET(interval, timestamp[, origin])"
+                    .to_string(),
+                query: None,
+            });
+        }
+
+        let interval_val = ExpressionEvaluator::evaluate_expression_value(&args[0], record)?;
+        let value = ExpressionEvaluator::evaluate_expression_value(&args[1], record)?;
+        if matches!(value, FieldValue::Null) {
+            return Ok(FieldValue::Null);
+        }
+
+        let (size, unit) = match interval_val {
+            FieldValue::Interval { value, unit } if value > 0 => (value, unit),
+            _ => {
+                return Err(SqlError::ExecutionError {
+                    message: "TIME_BUCKET requires a positive INTERVAL as the bucket width"
+                        .to_string(),
+                    query: None,
+                })
+            }
+        };
+
+        // Default origin is Monday 2000-01-03 so weekly buckets start on Mondays; it is
+        // midnight-aligned so hourly and daily buckets match epoch alignment. Month and
+        // year buckets start on 2000-01-01 so they line up with calendar quarters/years.
+        let origin = match args.get(2) {
+            Some(expr) => {
+                let origin_val = ExpressionEvaluator::evaluate_expression_value(expr, record)?;
+                Self::local_datetime_of(&origin_val)?.0
+            }
+            None => {
+                let origin_day = if unit.is_calendar() { 1 } else { 3 };
+                chrono::NaiveDate::from_ymd_opt(2000, 1, origin_day)
+                    .unwrap()
+                    .and_hms_opt(0, 0, 0)
+                    .unwrap()
+            }
+        };
+
+        if unit.is_calendar() {
+            use chrono::Datelike;
+
+            let bucket_months = if unit == TimeUnit::Year {
+                size * 12
+            } else {
+                size
+            };
+            Self::map_local_datetime(&value, |dt| {
+                let mut months = (dt.year() as i64 - origin.year() as i64) * 12
+                    + (dt.month() as i64 - origin.month() as i64);
+                if FieldValue::add_interval_to_datetime(origin, months, &TimeUnit::Month).ok()? > dt
+                {
+                    months -= 1;
+                }
+                let bucket_start = months.div_euclid(bucket_months) * bucket_months;
+                FieldValue::add_interval_to_datetime(origin, bucket_start, &TimeUnit::Month).ok()
+            })
+        } else {
+            let bucket_ms =
+                FieldValue::Interval { value: size, unit }.add(&FieldValue::Integer(0))?;
+            let bucket_ms = match bucket_ms {
+                FieldValue::Integer(ms) => ms,
+                _ => unreachable!("interval + integer yields epoch millis"),
+            };
+            Self::map_local_datetime(&value, |dt| {
+                let offset = (dt - origin).num_milliseconds();
+                let bucket_start = offset.div_euclid(bucket_ms) * bucket_ms;
+                origin.checked_add_signed(chrono::Duration::milliseconds(bucket_start))
+            })
+        }
+    }
+
+    fn date_add_function(
+        name: &str,
+        args: &[Expr],
+        record: &StreamRecord,
+    ) -> Result<FieldValue, SqlError> {
+        let (value, interval) = match args.len() {
+            // DATE_ADD(timestamp, INTERVAL '1' MONTH)
+            2 => (
+                ExpressionEvaluator::evaluate_expression_value(&args[0], record)?,
+                ExpressionEvaluator::evaluate_expression_value(&args[1], record)?,
+            ),
+            // DATE_ADD('month', 1, timestamp)
+            3 => {
+                let unit_val = ExpressionEvaluator::evaluate_expression_value(&args[0], record)?;
+                let amount_val = ExpressionEvaluator::evaluate_expression_value(&args[1], record)?;
+                let value = ExpressionEvaluator::evaluate_expression_value(&args[2], record)?;
+                let interval = match (unit_val, amount_val) {
+                    (_, FieldValue::Null) => FieldValue::Null,
+                    (FieldValue::String(unit), FieldValue::Integer(amount)) => {
+                        let (multiplier, unit) = Self::parse_time_unit(&unit).ok_or_else(|| {
+                            SqlError::ExecutionError {
+                                message: format!("Unsupported {} unit: {}", name, unit),
+                                query: None,
+                            }
+                        })?;
+                        FieldValue::Interval {
+                            value: amount * multiplier,
+                            unit,
+                        }
+                    }
+                    _ => {
+                        return Err(SqlError::ExecutionError {
+                            message: format!(
+                                "{} requires unit name (string) and amount (integer)",
+                                name
+                            ),
+                            query: None,
+                        })
+                    }
+                };
+                (value, interval)
+            }
+            _ => {
+                return Err(SqlError::ExecutionError {
+                    message: format!(
+                        "{} requires 2 or 3 arguments: {}(timestamp, interval) or {}(unit, amount, timestamp)",
+                        name, name, name
+                    ),
+                    query: None,
+                })
+            }
+        };
+
+        match (&value, &interval) {
+            (FieldValue::Null, _) | (_, FieldValue::Null) => Ok(FieldValue::Null),
+            (_, FieldValue::Interval { .. }) if name == "DATE_SUB" => value.subtract(&interval),
+            (_, FieldValue::Interval { .. }) => value.add(&interval),
+            _ => Err(SqlError::ExecutionError {
+                message: format!("{} requires an INTERVAL argument", name),
+                query: None,
+            }),
+        }
+    }
+
+    /// Map a unit name to an interval unit and multiplier (quarters are 3 months)
+    fn parse_time_unit(unit: &str) -> Option<(i64, TimeUnit)> {
+        let unit = unit.to_lowercase();
+        let unit = match unit.trim_end_matches('s') {
+            "millisecond" => TimeUnit::Millisecond,
+            "second" => TimeUnit::Second,
+            "minute" => TimeUnit::Minute,
+            "hour" => TimeUnit::Hour,
+            "day" => TimeUnit::Day,
+            "week" => TimeUnit::Week,
+            "month" => TimeUnit::Month,
+            "quarter" => return Some((3, TimeUnit::Month)),
+            "year" => TimeUnit::Year,
+            _ => return None,
+        };
+        Some((1, unit))
+    }
+
     fn position_function(args: &[Expr], record: &StreamRecord) -> Result<FieldValue, SqlError> {
         if args.len() < 2 || args.len() > 3 {
             return Err(SqlError::ExecutionError {
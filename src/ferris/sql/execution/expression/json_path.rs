@@ -0,0 +1,554 @@
+//! JSON conversion and JSONPath evaluation for SQL JSON functions.
+//!
+//! JSON documents are represented as regular [`FieldValue`] trees: objects become
+//! `FieldValue::Struct`, arrays become `FieldValue::Array` and scalars map to the
+//! matching scalar variants. This lets `PARSE_JSON`, `JSON_QUERY` and `JSON_OBJECT`
+//! results flow into other functions and sinks without re-parsing.
+//!
+//! Supported JSONPath syntax:
+//!
+//! - `$` - the document root (optional, `order.total` is read as `$.order.total`)
+//! - `.name` / `['name']` - object member
+//! - `[n]` - array element, negative indexes count from the end
+//! - `.*` / `[*]` - all members of an object or all elements of an array
+//! - `..name` / `..*` - recursive descent
+//! - `[?(<filter>)]` - filter children, e.g. `[?(@.price > 10 && @.tags)]`
+//!
+//! Filters support `==`, `!=`, `<`, `<=`, `>`, `>=`, `&&`, `||`, `!`, parentheses,
+//! existence checks (`@.field`) and string, number, boolean and `null` literals.
+
+use super::super::types::FieldValue;
+use crate::ferris::serialization::helpers::field_value_to_json;
+use crate::ferris::sql::error::SqlError;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// Parse JSON text into a `FieldValue` tree
+pub fn parse_json(text: &str) -> Result<FieldValue, SqlError> {
+    serde_json::from_str::<serde_json::Value>(text)
+        .map(json_to_field_value)
+        .map_err(|e| SqlError::ExecutionError {
+            message: format!("Invalid JSON: {}", e),
+            query: None,
+        })
+}
+
+/// Convert a `serde_json::Value` into a `FieldValue`, mapping objects to structs
+pub fn json_to_field_value(value: serde_json::Value) -> FieldValue {
+    match value {
+        serde_json::Value::Null => FieldValue::Null,
+        serde_json::Value::Bool(b) => FieldValue::Boolean(b),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => FieldValue::Integer(i),
+            None => FieldValue::Float(n.as_f64().unwrap_or(f64::NAN)),
+        },
+        serde_json::Value::String(s) => FieldValue::String(s),
+        serde_json::Value::Array(items) => {
+            FieldValue::Array(items.into_iter().map(json_to_field_value).collect())
+        }
+        serde_json::Value::Object(members) => FieldValue::Struct(
+            members
+                .into_iter()
+                .map(|(k, v)| (k, json_to_field_value(v)))
+                .collect(),
+        ),
+    }
+}
+
+/// Serialize a `FieldValue` as compact JSON text (object keys are sorted)
+///
+/// Values are converted as the JSON sinks write them, so decimals keep every digit.
+pub fn to_json_string(value: &FieldValue) -> Result<String, SqlError> {
+    field_value_to_json(value)
+        .map(|json| json.to_string())
+        .map_err(|e| SqlError::ExecutionError {
+            message: format!("Cannot convert value to JSON: {}", e),
+            query: None,
+        })
+}
+
+/// A compiled JSONPath expression
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonPath {
+    segments: Vec<PathSegment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum PathSegment {
+    Member(String),
+    Index(i64),
+    Wildcard,
+    Descendant(String),
+    DescendantWildcard,
+    Filter(FilterExpr),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum FilterExpr {
+    Exists(FilterOperand),
+    Compare(FilterOperand, CompareOp, FilterOperand),
+    And(Box<FilterExpr>, Box<FilterExpr>),
+    Or(Box<FilterExpr>, Box<FilterExpr>),
+    Not(Box<FilterExpr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum FilterOperand {
+    /// `@...` - relative to the element being filtered
+    Current(JsonPath),
+    /// `$...` - relative to the document root
+    Root(JsonPath),
+    Literal(FieldValue),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CompareOp {
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+}
+
+impl JsonPath {
+    /// Parse a JSONPath expression
+    pub fn parse(path: &str) -> Result<Self, SqlError> {
+        let mut parser = PathParser {
+            chars: path.trim().chars().collect(),
+            pos: 0,
+            source: path,
+        };
+
+        match parser.peek() {
+            Some('$') => parser.pos += 1,
+            // Bare member paths (`order.total`) are relative to the root
+            Some('[') | Some('.') | None => {}
+            Some(_) => parser.chars.insert(0, '.'),
+        }
+
+        let path = parser.parse_segments()?;
+        if parser.pos < parser.chars.len() {
+            return Err(parser.error("unexpected character"));
+        }
+        Ok(path)
+    }
+
+    /// Whether the path can select at most one value (no wildcards, filters or descent)
+    pub fn is_definite(&self) -> bool {
+        self.segments
+            .iter()
+            .all(|s| matches!(s, PathSegment::Member(_) | PathSegment::Index(_)))
+    }
+
+    /// Whether the path is just `$`, selecting the whole document
+    pub fn is_root(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Select all values matching the path in document order
+    pub fn select<'a>(&self, root: &'a FieldValue) -> Vec<&'a FieldValue> {
+        self.select_from(root, root)
+    }
+
+    fn select_from<'a>(&self, start: &'a FieldValue, root: &'a FieldValue) -> Vec<&'a FieldValue> {
+        let mut current = vec![start];
+        for segment in &self.segments {
+            let mut next = Vec::new();
+            for node in current {
+                match segment {
+                    PathSegment::Member(name) => {
+                        if let Some(value) = member(node, name) {
+                            next.push(value);
+                        }
+                    }
+                    PathSegment::Index(index) => {
+                        if let FieldValue::Array(items) = node {
+                            let index = if *index < 0 {
+                                items.len() as i64 + index
+                            } else {
+                                *index
+                            };
+                            if let Some(value) =
+                                usize::try_from(index).ok().and_then(|i| items.get(i))
+                            {
+                                next.push(value);
+                            }
+                        }
+                    }
+                    PathSegment::Wildcard => next.extend(children(node)),
+                    PathSegment::Descendant(name) => {
+                        let mut nodes = vec![node];
+                        collect_descendants(node, &mut nodes);
+                        next.extend(nodes.into_iter().filter_map(|n| member(n, name)));
+                    }
+                    PathSegment::DescendantWildcard => collect_descendants(node, &mut next),
+                    PathSegment::Filter(filter) => next.extend(
+                        children(node)
+                            .into_iter()
+                            .filter(|child| filter.matches(child, root)),
+                    ),
+                }
+            }
+            current = next;
+        }
+        current
+    }
+}
+
+fn member<'a>(node: &'a FieldValue, name: &str) -> Option<&'a FieldValue> {
+    match node {
+        FieldValue::Struct(members) | FieldValue::Map(members) => members.get(name),
+        _ => None,
+    }
+}
+
+/// Direct children of an object (ordered by key) or array
+fn children(node: &FieldValue) -> Vec<&FieldValue> {
+    match node {
+        FieldValue::Array(items) => items.iter().collect(),
+        FieldValue::Struct(members) | FieldValue::Map(members) => sorted_values(members),
+        _ => Vec::new(),
+    }
+}
+
+fn sorted_values(members: &HashMap<String, FieldValue>) -> Vec<&FieldValue> {
+    let mut entries: Vec<_> = members.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    entries.into_iter().map(|(_, v)| v).collect()
+}
+
+fn collect_descendants<'a>(node: &'a FieldValue, out: &mut Vec<&'a FieldValue>) {
+    for child in children(node) {
+        out.push(child);
+        collect_descendants(child, out);
+    }
+}
+
+impl FilterExpr {
+    fn matches(&self, current: &FieldValue, root: &FieldValue) -> bool {
+        match self {
+            FilterExpr::Exists(operand) => !operand.resolve(current, root).is_empty(),
+            FilterExpr::Compare(left, op, right) => {
+                let left = left.resolve(current, root);
+                let right = right.resolve(current, root);
+                left.iter()
+                    .any(|l| right.iter().any(|r| compare(l, *op, r)))
+            }
+            FilterExpr::And(a, b) => a.matches(current, root) && b.matches(current, root),
+            FilterExpr::Or(a, b) => a.matches(current, root) || b.matches(current, root),
+            FilterExpr::Not(inner) => !inner.matches(current, root),
+        }
+    }
+}
+
+impl FilterOperand {
+    fn resolve<'a>(&'a self, current: &'a FieldValue, root: &'a FieldValue) -> Vec<&'a FieldValue> {
+        match self {
+            FilterOperand::Current(path) => path.select_from(current, root),
+            FilterOperand::Root(path) => path.select_from(root, root),
+            FilterOperand::Literal(value) => vec![value],
+        }
+    }
+}
+
+fn compare(left: &FieldValue, op: CompareOp, right: &FieldValue) -> bool {
+    let ordering = match (left, right) {
+        (FieldValue::Null, FieldValue::Null) => Some(Ordering::Equal),
+        (FieldValue::Boolean(a), FieldValue::Boolean(b)) => Some(a.cmp(b)),
+        (FieldValue::String(a), FieldValue::String(b)) => Some(a.cmp(b)),
+        (FieldValue::Integer(a), FieldValue::Integer(b)) => Some(a.cmp(b)),
+        (FieldValue::Integer(a), FieldValue::Float(b)) => (*a as f64).partial_cmp(b),
+        (FieldValue::Float(a), FieldValue::Integer(b)) => a.partial_cmp(&(*b as f64)),
+        (FieldValue::Float(a), FieldValue::Float(b)) => a.partial_cmp(b),
+        (a, b) => {
+            // Containers compare by value for equality only
+            return match op {
+                CompareOp::Eq => a == b,
+                CompareOp::NotEq => a != b,
+                _ => false,
+            };
+        }
+    };
+
+    match (ordering, op) {
+        (Some(o), CompareOp::Eq) => o == Ordering::Equal,
+        (Some(o), CompareOp::NotEq) => o != Ordering::Equal,
+        (Some(o), CompareOp::Lt) => o == Ordering::Less,
+        (Some(o), CompareOp::LtEq) => o != Ordering::Greater,
+        (Some(o), CompareOp::Gt) => o == Ordering::Greater,
+        (Some(o), CompareOp::GtEq) => o != Ordering::Less,
+        (None, CompareOp::NotEq) => true,
+        (None, _) => false,
+    }
+}
+
+struct PathParser<'a> {
+    chars: Vec<char>,
+    pos: usize,
+    source: &'a str,
+}
+
+impl PathParser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn error(&self, reason: &str) -> SqlError {
+        SqlError::ExecutionError {
+            message: format!(
+                "Invalid JSON path '{}': {} at position {}",
+                self.source, reason, self.pos
+            ),
+            query: None,
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), SqlError> {
+        self.skip_whitespace();
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", expected)))
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn starts_with(&self, s: &str) -> bool {
+        s.chars()
+            .enumerate()
+            .all(|(i, c)| self.peek_at(i) == Some(c))
+    }
+
+    /// Parse `.name`, `[...]` and `..name` segments until a character that cannot
+    /// continue a path (the end of input or an operator inside a filter)
+    fn parse_segments(&mut self) -> Result<JsonPath, SqlError> {
+        let mut segments = Vec::new();
+        loop {
+            match self.peek() {
+                Some('.') if self.peek_at(1) == Some('.') => {
+                    self.pos += 2;
+                    if self.peek() == Some('*') {
+                        self.pos += 1;
+                        segments.push(PathSegment::DescendantWildcard);
+                    } else {
+                        segments.push(PathSegment::Descendant(self.parse_name()?));
+                    }
+                }
+                Some('.') => {
+                    self.pos += 1;
+                    if self.peek() == Some('*') {
+                        self.pos += 1;
+                        segments.push(PathSegment::Wildcard);
+                    } else {
+                        segments.push(PathSegment::Member(self.parse_name()?));
+                    }
+                }
+                Some('[') => {
+                    self.pos += 1;
+                    segments.push(self.parse_bracket()?);
+                }
+                _ => return Ok(JsonPath { segments }),
+            }
+        }
+    }
+
+    fn parse_name(&mut self) -> Result<String, SqlError> {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == '$')
+        {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Err(self.error("expected member name"));
+        }
+        Ok(self.chars[start..self.pos].iter().collect())
+    }
+
+    fn parse_quoted(&mut self) -> Result<String, SqlError> {
+        let quote = self.peek().ok_or_else(|| self.error("expected quote"))?;
+        self.pos += 1;
+        let mut value = String::new();
+        loop {
+            match self.peek() {
+                None => return Err(self.error("unterminated string")),
+                Some('\\') => {
+                    self.pos += 1;
+                    if let Some(c) = self.peek() {
+                        value.push(c);
+                        self.pos += 1;
+                    }
+                }
+                Some(c) if c == quote => {
+                    self.pos += 1;
+                    return Ok(value);
+                }
+                Some(c) => {
+                    value.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<FieldValue, SqlError> {
+        let start = self.pos;
+        if self.peek() == Some('-') {
+            self.pos += 1;
+        }
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+'))
+        {
+            self.pos += 1;
+        }
+        let text: String = self.chars[start..self.pos].iter().collect();
+        if let Ok(i) = text.parse::<i64>() {
+            Ok(FieldValue::Integer(i))
+        } else {
+            text.parse::<f64>()
+                .map(FieldValue::Float)
+                .map_err(|_| self.error("invalid number"))
+        }
+    }
+
+    /// Parse the contents of `[...]` after the opening bracket
+    fn parse_bracket(&mut self) -> Result<PathSegment, SqlError> {
+        self.skip_whitespace();
+        let segment = match self.peek() {
+            Some('*') => {
+                self.pos += 1;
+                PathSegment::Wildcard
+            }
+            Some('\'') | Some('"') => PathSegment::Member(self.parse_quoted()?),
+            Some('?') => {
+                self.pos += 1;
+                self.expect('(')?;
+                let filter = self.parse_or()?;
+                self.expect(')')?;
+                PathSegment::Filter(filter)
+            }
+            Some(c) if c == '-' || c.is_ascii_digit() => match self.parse_number()? {
+                FieldValue::Integer(i) => PathSegment::Index(i),
+                _ => return Err(self.error("array index must be an integer")),
+            },
+            _ => return Err(self.error("expected index, quoted name, '*' or filter")),
+        };
+        self.expect(']')?;
+        Ok(segment)
+    }
+
+    fn parse_or(&mut self) -> Result<FilterExpr, SqlError> {
+        let mut left = self.parse_and()?;
+        loop {
+            self.skip_whitespace();
+            if !self.starts_with("||") {
+                return Ok(left);
+            }
+            self.pos += 2;
+            left = FilterExpr::Or(Box::new(left), Box::new(self.parse_and()?));
+        }
+    }
+
+    fn parse_and(&mut self) -> Result<FilterExpr, SqlError> {
+        let mut left = self.parse_unary()?;
+        loop {
+            self.skip_whitespace();
+            if !self.starts_with("&&") {
+                return Ok(left);
+            }
+            self.pos += 2;
+            left = FilterExpr::And(Box::new(left), Box::new(self.parse_unary()?));
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<FilterExpr, SqlError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('!') if self.peek_at(1) != Some('=') => {
+                self.pos += 1;
+                Ok(FilterExpr::Not(Box::new(self.parse_unary()?)))
+            }
+            Some('(') => {
+                self.pos += 1;
+                let inner = self.parse_or()?;
+                self.expect(')')?;
+                Ok(inner)
+            }
+            _ => self.parse_comparison(),
+        }
+    }
+
+    fn parse_comparison(&mut self) -> Result<FilterExpr, SqlError> {
+        let left = self.parse_operand()?;
+        self.skip_whitespace();
+
+        let op = [
+            ("==", CompareOp::Eq),
+            ("!=", CompareOp::NotEq),
+            ("<=", CompareOp::LtEq),
+            (">=", CompareOp::GtEq),
+            ("<", CompareOp::Lt),
+            (">", CompareOp::Gt),
+        ]
+        .into_iter()
+        .find(|(token, _)| self.starts_with(token));
+
+        match op {
+            Some((token, op)) => {
+                self.pos += token.len();
+                let right = self.parse_operand()?;
+                Ok(FilterExpr::Compare(left, op, right))
+            }
+            None if matches!(left, FilterOperand::Literal(_)) => {
+                Err(self.error("expected comparison operator"))
+            }
+            None => Ok(FilterExpr::Exists(left)),
+        }
+    }
+
+    fn parse_operand(&mut self) -> Result<FilterOperand, SqlError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('@') => {
+                self.pos += 1;
+                Ok(FilterOperand::Current(self.parse_segments()?))
+            }
+            Some('$') => {
+                self.pos += 1;
+                Ok(FilterOperand::Root(self.parse_segments()?))
+            }
+            Some('\'') | Some('"') => Ok(FilterOperand::Literal(FieldValue::String(
+                self.parse_quoted()?,
+            ))),
+            Some(c) if c == '-' || c.is_ascii_digit() => {
+                Ok(FilterOperand::Literal(self.parse_number()?))
+            }
+            _ => {
+                for (keyword, value) in [
+                    ("true", FieldValue::Boolean(true)),
+                    ("false", FieldValue::Boolean(false)),
+                    ("null", FieldValue::Null),
+                ] {
+                    if self.starts_with(keyword) {
+                        self.pos += keyword.len();
+                        return Ok(FilterOperand::Literal(value));
+                    }
+                }
+                Err(self.error("expected '@', '$' or a literal"))
+            }
+        }
+    }
+}
@@ -4,6 +4,9 @@
 //! - Expression evaluation (boolean and value expressions)
 //! - Built-in function implementations (math, string, date functions)
 //! - Window functions (LAG, LEAD, ROW_NUMBER, RANK, etc.)
+//! - JSON conversion and JSONPath evaluation
+//! - Compiled-pattern cache for the REGEXP_* functions
+//! - Expressions compiled once per query into evaluation plans
 //!
 //! The expression evaluation system supports:
 //! - Column references and literals
@@ -13,12 +16,17 @@
 //! - Type coercion and casting
 //! - NULL handling according to SQL semantics
 
+pub mod compiled;
 pub mod evaluator;
 pub mod functions;
+pub mod json_path;
+pub mod regex_cache;
 pub mod subquery_executor;
 pub mod window_functions;
 
 // Re-export the main API
+pub use compiled::{CompiledExpression, CompiledQuery, OutputLayout};
 pub use evaluator::ExpressionEvaluator;
+pub use json_path::JsonPath;
 pub use subquery_executor::SubqueryExecutor;
 pub use window_functions::WindowFunctions;
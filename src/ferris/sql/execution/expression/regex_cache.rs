@@ -0,0 +1,108 @@
+//! Compiled patterns for the REGEXP_* functions.
+//!
+//! Built-in functions are evaluated once per record, so compiling the pattern on every
+//! call would dominate the cost of log-parsing queries. A [`CompiledQuery`] compiles
+//! patterns given as literals once, when the query is planned. Patterns computed from
+//! record data are kept in a [`PatternCache`] owned by the plan, so a query compiles each
+//! distinct pattern once and queries never share or evict each other's patterns.
+//! Expressions left to the interpreter compile their pattern on every call.
+//!
+//! The cache is bounded so that patterns built from record data cannot grow it without
+//! limit. When it is full the least recently used pattern is evicted, so the patterns a
+//! query keeps using stay cached.
+//!
+//! [`CompiledQuery`]: super::CompiledQuery
+
+use crate::ferris::sql::error::SqlError;
+use regex::{Regex, RegexBuilder};
+use std::collections::HashMap;
+
+/// Maximum number of compiled patterns kept by one cache
+pub const MAX_CACHED_PATTERNS: usize = 256;
+
+type PatternKey = (String, String);
+
+/// Compiled patterns of one query with the tick of their last use
+#[derive(Debug, Default)]
+pub struct PatternCache {
+    patterns: HashMap<PatternKey, (Regex, u64)>,
+    tick: u64,
+}
+
+impl PatternCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the compiled form of `pattern`, compiling and caching it on first use
+    pub fn compiled(&mut self, pattern: &str, flags: &str) -> Result<Regex, SqlError> {
+        self.tick += 1;
+        let key = (pattern.to_string(), flags.to_string());
+        if let Some((regex, last_used)) = self.patterns.get_mut(&key) {
+            *last_used = self.tick;
+            return Ok(regex.clone());
+        }
+
+        let regex = compile(pattern, flags)?;
+        if self.patterns.len() >= MAX_CACHED_PATTERNS {
+            let least_recent = self
+                .patterns
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(least_recent) = least_recent {
+                self.patterns.remove(&least_recent);
+            }
+        }
+        self.patterns.insert(key, (regex.clone(), self.tick));
+        Ok(regex)
+    }
+
+    /// Whether `pattern` with `flags` is currently cached
+    pub fn contains(&self, pattern: &str, flags: &str) -> bool {
+        self.patterns
+            .contains_key(&(pattern.to_string(), flags.to_string()))
+    }
+
+    /// Number of patterns currently cached
+    pub fn len(&self) -> usize {
+        self.patterns.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+}
+
+/// Compile `pattern` with `flags`
+///
+/// `flags` is a string of single-letter modifiers:
+/// - `i` - case-insensitive matching
+/// - `m` - `^` and `$` match at line boundaries
+/// - `s` - `.` also matches newlines
+/// - `x` - ignore whitespace and allow `#` comments in the pattern
+pub fn compile(pattern: &str, flags: &str) -> Result<Regex, SqlError> {
+    let mut builder = RegexBuilder::new(pattern);
+    for flag in flags.chars() {
+        match flag {
+            'i' => builder.case_insensitive(true),
+            'm' => builder.multi_line(true),
+            's' => builder.dot_matches_new_line(true),
+            'x' => builder.ignore_whitespace(true),
+            other => {
+                return Err(SqlError::ExecutionError {
+                    message: format!(
+                        "Unsupported regular expression flag '{}', expected one of i, m, s, x",
+                        other
+                    ),
+                    query: None,
+                })
+            }
+        };
+    }
+
+    builder.build().map_err(|e| SqlError::ExecutionError {
+        message: format!("Invalid regular expression '{}': {}", pattern, e),
+        query: None,
+    })
+}
@@ -4,11 +4,36 @@
 //! These types support the internal operation of the execution engine including
 //! GROUP BY state management, execution messaging, and query lifecycle management.
 
+use super::expression::CompiledQuery;
+use super::spill::{record_size, MemoryBudget, MemoryReservation, SpillRun};
+use super::state::{ListState, StateBackend, StateOperator};
 use super::types::{FieldValue, StreamRecord};
-use crate::ferris::sql::ast::{Expr, SelectField, StreamingQuery, WindowSpec};
+use crate::ferris::sql::ast::{Expr, SelectField, StreamSource, StreamingQuery, WindowSpec};
+use crate::ferris::sql::SqlError;
 use hyperloglogplus::{HyperLogLog, HyperLogLogPlus};
-use std::collections::hash_map::RandomState;
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::hash::BuildHasher;
+use std::sync::Arc;
+use twox_hash::XxHash64;
+
+/// Hasher for APPROX_COUNT_DISTINCT sketches
+///
+/// The seed is fixed so a sketch written to a state backend keeps counting correctly
+/// after it is read back, also in another process.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct SketchHasher;
+
+impl BuildHasher for SketchHasher {
+    type Hasher = XxHash64;
+
+    fn build_hasher(&self) -> XxHash64 {
+        XxHash64::with_seed(0)
+    }
+}
+
+/// HyperLogLog sketch behind APPROX_COUNT_DISTINCT
+pub type ApproxDistinctEstimator = HyperLogLogPlus<String, SketchHasher>;
 
 /// State for tracking GROUP BY aggregations across streaming records
 ///
@@ -24,20 +49,46 @@ pub struct GroupByState {
     pub select_fields: Vec<SelectField>,
     /// Optional HAVING clause
     pub having_clause: Option<Expr>,
+    /// Namespace of the groups in a GROUP BY state backend, named after the source
+    pub state_namespace: String,
 }
 
 impl GroupByState {
-    /// Create a new GroupByState for the given expressions and fields
+    /// Create a new GroupByState for a GROUP BY over `source`
+    ///
+    /// The groups are filed under the source in state backends and checkpoints, like the
+    /// stable operator ID of the GROUP BY in savepoints, so editing the projection or
+    /// HAVING of the query keeps them.
     pub fn new(
+        source: &StreamSource,
         group_expressions: Vec<Expr>,
         select_fields: Vec<SelectField>,
         having_clause: Option<Expr>,
     ) -> Self {
+        let state_namespace = StateOperator::GroupBy.namespace(&source.to_string());
         Self {
             groups: HashMap::new(),
             group_expressions,
             select_fields,
             having_clause,
+            state_namespace,
+        }
+    }
+
+    /// GROUP BY state restored from a checkpoint, waiting under its namespace for its query
+    ///
+    /// The query takes over the groups when it next runs, see
+    /// [`StreamExecutionEngine::restore_operator_state`](super::StreamExecutionEngine::restore_operator_state).
+    pub fn restored(
+        state_namespace: String,
+        groups: HashMap<Vec<String>, GroupAccumulator>,
+    ) -> Self {
+        Self {
+            groups,
+            group_expressions: Vec::new(),
+            select_fields: Vec::new(),
+            having_clause: None,
+            state_namespace,
         }
     }
 
@@ -86,10 +137,14 @@ pub struct GroupAccumulator {
     pub last_values: HashMap<String, FieldValue>,
     /// String values for STRING_AGG
     pub string_values: HashMap<String, Vec<String>>,
+    /// Collected values for JSON_ARRAYAGG
+    pub json_array_values: HashMap<String, Vec<FieldValue>>,
+    /// Collected key/value pairs for JSON_OBJECTAGG
+    pub json_object_entries: HashMap<String, Vec<(String, FieldValue)>>,
     /// Distinct values for COUNT_DISTINCT
     pub distinct_values: HashMap<String, HashSet<String>>,
     /// HyperLogLog estimators for APPROX_COUNT_DISTINCT
-    pub approx_distinct_values: HashMap<String, HyperLogLogPlus<String, RandomState>>,
+    pub approx_distinct_values: HashMap<String, ApproxDistinctEstimator>,
     /// Sample record for non-aggregate fields (takes first record's values)
     pub sample_record: Option<StreamRecord>,
 }
@@ -107,6 +162,8 @@ impl GroupAccumulator {
             first_values: HashMap::new(),
             last_values: HashMap::new(),
             string_values: HashMap::new(),
+            json_array_values: HashMap::new(),
+            json_object_entries: HashMap::new(),
             distinct_values: HashMap::new(),
             approx_distinct_values: HashMap::new(),
             sample_record: None,
@@ -268,7 +325,7 @@ impl GroupAccumulator {
         let hll = self
             .approx_distinct_values
             .entry(field_name.to_string())
-            .or_insert_with(|| HyperLogLogPlus::new(10, RandomState::new()).unwrap()); // 10-bit precision
+            .or_insert_with(|| HyperLogLogPlus::new(10, SketchHasher).unwrap()); // 10-bit precision
         hll.insert(&value_str);
     }
 
@@ -391,15 +448,18 @@ pub struct QueryExecution {
     pub state: ExecutionState,
     /// Window state for windowed queries
     pub window_state: Option<WindowState>,
+    /// Compiled expressions of the query
+    pub plan: Arc<CompiledQuery>,
 }
 
 impl QueryExecution {
     /// Create a new query execution context
     pub fn new(query: StreamingQuery) -> Self {
         Self {
-            query,
             state: ExecutionState::Running,
             window_state: None,
+            plan: Arc::new(CompiledQuery::new(&query)),
+            query,
         }
     }
 
@@ -441,6 +501,10 @@ pub enum ExecutionState {
 ///
 /// This structure maintains the buffered records and timing information
 /// needed for window-based query processing.
+///
+/// Under a job [memory budget](super::spill) older records of the buffer may sit in
+/// spill runs; they precede the records in `buffer`. A window kept in a
+/// [state backend](super::state) holds its records in a [`ListState`] instead of both.
 #[derive(Debug, Clone)]
 pub struct WindowState {
     /// Window specification from the query
@@ -449,6 +513,38 @@ pub struct WindowState {
     pub buffer: Vec<StreamRecord>,
     /// Timestamp of the last window emission
     pub last_emit: i64,
+    /// Records spilled to disk, oldest first; shared by clones of the state
+    pub spilled: Vec<Arc<SpillRun>>,
+    /// Memory the buffer holds of the job's budget; shared by clones of the state
+    memory: Option<Arc<MemoryReservation>>,
+    /// Records kept in a state backend; shared by clones of the state
+    stored: Option<StoredBuffer>,
+}
+
+/// Window records kept in a [`ListState`] under the id of their query
+#[derive(Debug, Clone)]
+struct StoredBuffer {
+    list: ListState<String, StreamRecord>,
+    query_id: String,
+    len: usize,
+}
+
+impl StoredBuffer {
+    fn add(&mut self, record: &StreamRecord) -> Result<(), SqlError> {
+        self.list.add(&self.query_id, record)?;
+        self.len += 1;
+        Ok(())
+    }
+
+    fn records(&self) -> Result<Vec<StreamRecord>, SqlError> {
+        self.list.get(&self.query_id)
+    }
+
+    fn replace(&mut self, records: &[StreamRecord]) -> Result<(), SqlError> {
+        self.list.clear(&self.query_id)?;
+        self.len = 0;
+        records.iter().try_for_each(|record| self.add(record))
+    }
 }
 
 impl WindowState {
@@ -458,22 +554,189 @@ impl WindowState {
             window_spec,
             buffer: Vec::new(),
             last_emit: 0,
+            spilled: Vec::new(),
+            memory: None,
+            stored: None,
         }
     }
 
+    /// Keep the records of the window of `query_id` in `backend`, taking over the records
+    /// the backend already holds for it
+    pub fn with_backend(
+        mut self,
+        backend: Arc<dyn StateBackend>,
+        query_id: &str,
+    ) -> Result<Self, SqlError> {
+        let list = ListState::new(backend, StateOperator::Window.namespace(query_id));
+        let query_id = query_id.to_string();
+        let len = list.len(&query_id)? as usize;
+        let mut stored = StoredBuffer {
+            list,
+            query_id,
+            len,
+        };
+        for record in self.buffer.drain(..) {
+            stored.add(&record)?;
+        }
+        self.stored = Some(stored);
+        Ok(self)
+    }
+
     /// Add a record to the window buffer
-    pub fn add_record(&mut self, record: StreamRecord) {
+    pub fn add_record(&mut self, record: StreamRecord) -> Result<(), SqlError> {
+        match &mut self.stored {
+            Some(stored) => stored.add(&record),
+            None => {
+                self.buffer.push(record);
+                Ok(())
+            }
+        }
+    }
+
+    /// Add a record to the window buffer, spilling the buffer to disk once the job is
+    /// over its memory budget; a window in a state backend is not accounted
+    pub fn add_record_within(
+        &mut self,
+        record: StreamRecord,
+        budget: &MemoryBudget,
+    ) -> Result<(), SqlError> {
+        if self.stored.is_some() {
+            return self.add_record(record);
+        }
+        let size = record_size(&record);
         self.buffer.push(record);
+        let memory = self
+            .memory
+            .get_or_insert_with(|| Arc::new(budget.reservation()));
+        if !memory.grow(size) {
+            self.spill()?;
+        }
+        Ok(())
+    }
+
+    /// Budget the buffer is accounted against, once a record was added within one
+    pub fn memory_budget(&self) -> Option<&MemoryBudget> {
+        self.memory.as_ref().map(|memory| memory.budget())
+    }
+
+    /// Move the buffer to a spill run
+    fn spill(&mut self) -> Result<(), SqlError> {
+        let Some(memory) = &self.memory else {
+            return Ok(());
+        };
+        if !self.buffer.is_empty() {
+            let run = SpillRun::write(memory.budget(), &self.buffer)?;
+            self.spilled.push(Arc::new(run));
+            self.buffer.clear();
+        }
+        memory.free();
+        Ok(())
+    }
+
+    /// Visit every buffered record, oldest first, reading spilled runs one record at a
+    /// time rather than back into memory
+    pub fn for_each_record(
+        &self,
+        mut visit: impl FnMut(&StreamRecord) -> Result<(), SqlError>,
+    ) -> Result<(), SqlError> {
+        if let Some(stored) = &self.stored {
+            return stored.records()?.iter().try_for_each(visit);
+        }
+        for run in &self.spilled {
+            for record in run.read()? {
+                visit(&record?)?;
+            }
+        }
+        self.buffer.iter().try_for_each(visit)
+    }
+
+    /// Keep the buffered records `keep` returns true for; spilled runs are rewritten as
+    /// they are read
+    pub fn retain_records(
+        &mut self,
+        mut keep: impl FnMut(&StreamRecord) -> bool,
+    ) -> Result<(), SqlError> {
+        if let Some(stored) = &mut self.stored {
+            let mut records = stored.records()?;
+            let len = records.len();
+            records.retain(|record| keep(record));
+            return if records.len() == len {
+                Ok(())
+            } else {
+                stored.replace(&records)
+            };
+        }
+        self.buffer.retain(|record| keep(record));
+        let Some(memory) = &self.memory else {
+            return Ok(());
+        };
+        let mut spilled = Vec::with_capacity(self.spilled.len());
+        for run in &self.spilled {
+            let retained = run.retain(memory.budget(), &mut keep)?;
+            if !retained.is_empty() {
+                spilled.push(Arc::new(retained));
+            }
+        }
+        self.spilled = spilled;
+        Ok(())
+    }
+
+    /// Account the buffer anew after records were removed from it, spilling it again
+    /// while the job is over its budget
+    pub fn rebalance(&mut self) -> Result<(), SqlError> {
+        match &self.memory {
+            Some(memory) if !memory.resize(self.buffer.iter().map(record_size).sum()) => {
+                self.spill()
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// All buffered records, spilled ones included, oldest first
+    pub fn records(&self) -> Result<Vec<StreamRecord>, SqlError> {
+        if let Some(stored) = &self.stored {
+            return stored.records();
+        }
+        let mut records = Vec::new();
+        for run in &self.spilled {
+            records.extend(run.read_all()?);
+        }
+        records.extend(self.buffer.iter().cloned());
+        Ok(records)
+    }
+
+    /// Replace all buffered records, dropping spilled ones
+    pub fn set_records(&mut self, records: Vec<StreamRecord>) -> Result<(), SqlError> {
+        if let Some(stored) = &mut self.stored {
+            return stored.replace(&records);
+        }
+        self.spilled.clear();
+        self.buffer = records;
+        if let Some(memory) = &self.memory {
+            memory.resize(self.buffer.iter().map(record_size).sum());
+        }
+        Ok(())
+    }
+
+    /// Whether no record is buffered, in memory, on disk or in a state backend
+    pub fn is_empty(&self) -> bool {
+        match &self.stored {
+            Some(stored) => stored.len == 0,
+            None => self.buffer.is_empty() && self.spilled.is_empty(),
+        }
     }
 
     /// Clear the window buffer
-    pub fn clear_buffer(&mut self) {
-        self.buffer.clear();
+    pub fn clear_buffer(&mut self) -> Result<(), SqlError> {
+        self.set_records(Vec::new())
     }
 
     /// Get the number of records in the buffer
     pub fn buffer_size(&self) -> usize {
-        self.buffer.len()
+        match &self.stored {
+            Some(stored) => stored.len,
+            None => self.buffer.len() + self.spilled.iter().map(|run| run.len()).sum::<usize>(),
+        }
     }
 
     /// Update the last emit timestamp
@@ -39,18 +39,36 @@
 
 pub mod aggregation;
 pub mod algorithms;
+pub mod checkpoint;
+pub mod columnar;
+pub mod decimal;
 pub mod engine;
 pub mod expression;
 pub mod internal;
 pub mod performance;
+pub mod prepared;
 pub mod processors;
+pub mod record_fields;
+pub mod savepoint;
+pub mod schema_enforcement;
+pub mod spill;
+pub mod state;
+pub mod subquery;
+pub mod table_constraints;
 #[cfg(test)]
 pub mod test_data_sources;
 pub mod types;
 pub mod utils;
 
 // Re-export public API only
+pub use checkpoint::{CheckpointConfig, Checkpointer};
 pub use engine::StreamExecutionEngine;
+pub use prepared::{PreparedParameter, PreparedStatement};
+pub use record_fields::{RecordFields, RecordSchema, RecordSchemaCache};
+pub use savepoint::Savepoint;
+pub use spill::{MemoryBudget, MemoryBudgetConfig, MemoryMetrics};
+pub use state::{StateBackend, StateBackendConfig, StateBackends, StateMetrics, StateTtlConfig};
+pub use subquery::{InMemoryLookupSource, LookupSource, SubqueryRefreshPolicy};
 pub use types::{FieldValue, StreamRecord};
 
 // Re-export internal types for testing
@@ -0,0 +1,501 @@
+//! Prepared statements with typed parameters.
+//!
+//! Queries take values through placeholders instead of string concatenation:
+//!
+//! ```sql
+//! SELECT * FROM orders WHERE amount > $1 AND status = $2
+//! SELECT * FROM orders WHERE customer_id = :customer AND amount > :min_amount
+//! ```
+//!
+//! [`StreamExecutionEngine::prepare`](super::StreamExecutionEngine::prepare) parses such a
+//! query into a [`PreparedStatement`], which lists its parameters and binds
+//! [`FieldValue`]s to them. Binding gives back a plain [`StreamingQuery`] with every
+//! placeholder replaced by its value, ready for the engine.
+//!
+//! A parameter is typed by, in order:
+//! - a declaration, see [`PreparedStatement::with_parameter_type`] and the
+//!   `-- Parameter:` comments of SQL applications
+//! - what it is compared or combined with: `amount > $1` gives `$1` the type of `amount`
+//!   when the source schema is known ([`PreparedStatement::with_schema_catalog`]),
+//!   `$1 AND x` makes it BOOLEAN, `name LIKE $1` a STRING
+//!
+//! Bound values are checked against that type and coerced to it (an INTEGER bound to a
+//! DECIMAL(10,2) parameter becomes a DECIMAL with scale 2); parameters whose type is
+//! unknown accept any value. NULL can be bound to every parameter.
+
+use super::types::FieldValue;
+use crate::ferris::sql::ast::{
+    DataType, Expr, InsertSource, LiteralValue, MergeClause, MergeMatchedAction, NodeSpan,
+    QueryParameter, SelectField, StreamSource, StreamingQuery, TableConstraint,
+};
+use crate::ferris::sql::error::SqlError;
+use crate::ferris::sql::semantic_analyzer::{SchemaCatalog, SemanticAnalyzer};
+use std::collections::HashMap;
+
+/// A parameter of a prepared statement
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreparedParameter {
+    pub parameter: QueryParameter,
+    /// Declared or inferred type, `None` when the parameter accepts any value
+    pub data_type: Option<DataType>,
+}
+
+/// A parsed query with `$1` or `:name` placeholders, bound to values before execution
+#[derive(Debug, Clone)]
+pub struct PreparedStatement {
+    query: StreamingQuery,
+    catalog: SchemaCatalog,
+    declared_types: HashMap<QueryParameter, DataType>,
+    /// Positional parameters by number, or named ones in order of first use
+    parameters: Vec<PreparedParameter>,
+}
+
+impl PreparedStatement {
+    /// Prepare a parsed query; a query cannot mix positional and named parameters
+    pub fn new(mut query: StreamingQuery) -> Result<Self, SqlError> {
+        let mut placeholders: Vec<QueryParameter> = Vec::new();
+        for_each_expr(&mut query, &mut |expr| {
+            if let Expr::Parameter(parameter, _) = expr {
+                if !placeholders.contains(parameter) {
+                    placeholders.push(parameter.clone());
+                }
+            }
+        });
+
+        let positional = |p: &QueryParameter| matches!(p, QueryParameter::Positional(_));
+        if placeholders.iter().any(positional) && !placeholders.iter().all(positional) {
+            return Err(SqlError::parse_error(
+                "A query cannot mix positional ($1) and named (:name) parameters",
+                None,
+            ));
+        }
+        placeholders.sort_by_key(|p| match p {
+            QueryParameter::Positional(number) => *number,
+            QueryParameter::Named(_) => 0,
+        });
+
+        let mut statement = Self {
+            query,
+            catalog: SchemaCatalog::new(),
+            declared_types: HashMap::new(),
+            parameters: placeholders
+                .into_iter()
+                .map(|parameter| PreparedParameter {
+                    parameter,
+                    data_type: None,
+                })
+                .collect(),
+        };
+        statement.infer_types();
+        Ok(statement)
+    }
+
+    /// Infer parameter types from the schemas of the sources the query reads
+    pub fn with_schema_catalog(mut self, catalog: &SchemaCatalog) -> Self {
+        self.catalog = catalog.clone();
+        self.infer_types();
+        self
+    }
+
+    /// Declare the type of a parameter, overriding the inferred one
+    pub fn with_parameter_type(mut self, parameter: QueryParameter, data_type: DataType) -> Self {
+        self.declared_types.insert(parameter, data_type);
+        self.infer_types();
+        self
+    }
+
+    /// The query with its placeholders
+    pub fn query(&self) -> &StreamingQuery {
+        &self.query
+    }
+
+    /// The parameters of the query: positional ones by number, named ones in order of use
+    pub fn parameters(&self) -> &[PreparedParameter] {
+        &self.parameters
+    }
+
+    /// Bind values to `$1`, `$2`, ... in order
+    pub fn bind(&self, values: &[FieldValue]) -> Result<StreamingQuery, SqlError> {
+        if let Some(named) = self
+            .parameters
+            .iter()
+            .find(|p| matches!(p.parameter, QueryParameter::Named(_)))
+        {
+            return Err(SqlError::execution_error(
+                format!(
+                    "Query has named parameters such as {}, bind them by name",
+                    named.parameter
+                ),
+                None,
+            ));
+        }
+        let expected = self
+            .parameters
+            .iter()
+            .filter_map(|p| match p.parameter {
+                QueryParameter::Positional(number) => Some(number),
+                QueryParameter::Named(_) => None,
+            })
+            .max()
+            .unwrap_or(0);
+        if values.len() != expected {
+            return Err(SqlError::execution_error(
+                format!(
+                    "Query takes {} parameter value(s), {} given",
+                    expected,
+                    values.len()
+                ),
+                None,
+            ));
+        }
+        self.bind_with(|parameter| match parameter {
+            QueryParameter::Positional(number) => values.get(number - 1),
+            QueryParameter::Named(_) => None,
+        })
+    }
+
+    /// Bind values to `:name` parameters; every parameter needs a value and every value a
+    /// parameter
+    pub fn bind_named(
+        &self,
+        values: &HashMap<String, FieldValue>,
+    ) -> Result<StreamingQuery, SqlError> {
+        if let Some(unknown) = values.keys().find(|name| {
+            !self
+                .parameters
+                .iter()
+                .any(|p| p.parameter == QueryParameter::Named(name.to_string()))
+        }) {
+            return Err(SqlError::execution_error(
+                format!("Query has no parameter :{}", unknown),
+                None,
+            ));
+        }
+        self.bind_with(|parameter| match parameter {
+            QueryParameter::Named(name) => values.get(name),
+            QueryParameter::Positional(_) => None,
+        })
+    }
+
+    fn bind_with<'v>(
+        &self,
+        value_of: impl Fn(&QueryParameter) -> Option<&'v FieldValue>,
+    ) -> Result<StreamingQuery, SqlError> {
+        let mut bound = HashMap::new();
+        for prepared in &self.parameters {
+            let value = value_of(&prepared.parameter).ok_or_else(|| {
+                SqlError::execution_error(
+                    format!("No value bound to parameter {}", prepared.parameter),
+                    None,
+                )
+            })?;
+            let value = check_value(prepared, value.clone())?;
+            bound.insert(
+                prepared.parameter.clone(),
+                value_expr(&prepared.parameter, value)?,
+            );
+        }
+
+        let mut query = self.query.clone();
+        for_each_expr(&mut query, &mut |expr| {
+            if let Expr::Parameter(parameter, _) = expr {
+                if let Some(value) = bound.get(parameter) {
+                    *expr = value.clone();
+                }
+            }
+        });
+        Ok(query)
+    }
+
+    fn infer_types(&mut self) {
+        let analysis = SemanticAnalyzer::new(&self.catalog)
+            .with_parameter_types(self.declared_types.clone())
+            .analyze(&self.query);
+        for prepared in &mut self.parameters {
+            prepared.data_type = analysis.parameter_types.get(&prepared.parameter).cloned();
+        }
+    }
+}
+
+/// Check a bound value against the type of its parameter and coerce it to that type
+fn check_value(prepared: &PreparedParameter, value: FieldValue) -> Result<FieldValue, SqlError> {
+    let Some(data_type) = &prepared.data_type else {
+        return Ok(value);
+    };
+    if matches!(value, FieldValue::Null) {
+        return Ok(value);
+    }
+
+    let accepted = match data_type {
+        DataType::SmallInt | DataType::Integer | DataType::BigInt => {
+            matches!(value, FieldValue::Integer(_))
+        }
+        DataType::Real | DataType::Float | DataType::Decimal(_) => matches!(
+            value,
+            FieldValue::Integer(_)
+                | FieldValue::Float(_)
+                | FieldValue::Decimal(_)
+                | FieldValue::ScaledInteger(..)
+        ),
+        DataType::String => matches!(value, FieldValue::String(_)),
+        DataType::Boolean => matches!(value, FieldValue::Boolean(_)),
+        DataType::Bytes => matches!(value, FieldValue::Bytes(_)),
+        DataType::Uuid => matches!(value, FieldValue::Uuid(_)),
+        DataType::Date => matches!(value, FieldValue::Date(_)),
+        DataType::Time => matches!(value, FieldValue::Time(_)),
+        DataType::Timestamp | DataType::TimestampTz => matches!(
+            value,
+            FieldValue::Date(_) | FieldValue::Timestamp(_) | FieldValue::TimestampTz(_)
+        ),
+        DataType::Array(_) => matches!(value, FieldValue::Array(_)),
+        DataType::Map(..) => matches!(value, FieldValue::Map(_)),
+        DataType::Struct(_) => matches!(value, FieldValue::Struct(_)),
+    };
+    if !accepted {
+        return Err(SqlError::type_error(
+            format!("{} for parameter {}", data_type, prepared.parameter),
+            value.type_name(),
+            Some(value.to_display_string()),
+        ));
+    }
+
+    match data_type {
+        DataType::Array(_) | DataType::Map(..) | DataType::Struct(_) => Ok(value),
+        scalar => value.cast_to(&scalar.to_string()),
+    }
+}
+
+/// The expression a bound value replaces its placeholder with
+fn value_expr(parameter: &QueryParameter, value: FieldValue) -> Result<Expr, SqlError> {
+    let literal = |literal| Ok(Expr::Literal(literal, NodeSpan::default()));
+    // Values without a literal form are written as a CAST of their text
+    let cast = |value: &FieldValue| {
+        Ok(Expr::Function {
+            name: "CAST".to_string(),
+            args: vec![
+                Expr::Literal(
+                    LiteralValue::String(value.to_display_string()),
+                    NodeSpan::default(),
+                ),
+                Expr::Literal(
+                    LiteralValue::String(value.type_name().to_string()),
+                    NodeSpan::default(),
+                ),
+            ],
+            span: NodeSpan::default(),
+        })
+    };
+    match value {
+        FieldValue::Integer(i) => literal(LiteralValue::Integer(i)),
+        FieldValue::Float(f) => literal(LiteralValue::Float(f)),
+        FieldValue::String(s) => literal(LiteralValue::String(s)),
+        FieldValue::Boolean(b) => literal(LiteralValue::Boolean(b)),
+        FieldValue::Null => literal(LiteralValue::Null),
+        FieldValue::Decimal(d) => literal(LiteralValue::Decimal(d.to_string())),
+        FieldValue::ScaledInteger(..) => literal(LiteralValue::Decimal(value.to_display_string())),
+        FieldValue::Interval { value, unit } => literal(LiteralValue::Interval { value, unit }),
+        FieldValue::Bytes(_) => Ok(Expr::Function {
+            name: "BASE64_DECODE".to_string(),
+            args: vec![Expr::Literal(
+                LiteralValue::String(value.to_display_string()),
+                NodeSpan::default(),
+            )],
+            span: NodeSpan::default(),
+        }),
+        FieldValue::Date(_)
+        | FieldValue::Time(_)
+        | FieldValue::Timestamp(_)
+        | FieldValue::TimestampTz(_)
+        | FieldValue::Uuid(_) => cast(&value),
+        FieldValue::Array(_) | FieldValue::Map(_) | FieldValue::Struct(_) => {
+            Err(SqlError::execution_error(
+                format!(
+                    "A {} value cannot be bound to parameter {}",
+                    value.type_name(),
+                    parameter
+                ),
+                None,
+            ))
+        }
+    }
+}
+
+/// Apply `f` to every expression of a query, subqueries included, parents before children
+pub(crate) fn for_each_expr(query: &mut StreamingQuery, f: &mut dyn FnMut(&mut Expr)) {
+    match query {
+        StreamingQuery::Select {
+            fields,
+            from,
+            joins,
+            where_clause,
+            group_by,
+            having,
+            order_by,
+            ..
+        } => {
+            for field in fields.iter_mut() {
+                if let SelectField::Expression { expr, .. } = field {
+                    walk_expr(expr, f);
+                }
+            }
+            walk_source(from, f);
+            for join in joins.iter_mut().flatten() {
+                walk_source(&mut join.right_source, f);
+                walk_expr(&mut join.condition, f);
+            }
+            for expr in where_clause
+                .iter_mut()
+                .chain(group_by.iter_mut().flatten())
+                .chain(having.iter_mut())
+            {
+                walk_expr(expr, f);
+            }
+            for order in order_by.iter_mut().flatten() {
+                walk_expr(&mut order.expr, f);
+            }
+        }
+        StreamingQuery::CreateStream { as_select, .. }
+        | StreamingQuery::CreateStreamInto { as_select, .. } => for_each_expr(as_select, f),
+        StreamingQuery::CreateTable {
+            as_select,
+            constraints,
+            ..
+        }
+        | StreamingQuery::CreateTableInto {
+            as_select,
+            constraints,
+            ..
+        } => {
+            for constraint in constraints.iter_mut() {
+                if let TableConstraint::Check { expr, .. } = constraint {
+                    walk_expr(expr, f);
+                }
+            }
+            for_each_expr(as_select, f)
+        }
+        StreamingQuery::StartJob { query, .. } | StreamingQuery::DeployJob { query, .. } => {
+            for_each_expr(query, f)
+        }
+        StreamingQuery::Union { left, right, .. } => {
+            for_each_expr(left, f);
+            for_each_expr(right, f);
+        }
+        StreamingQuery::InsertInto { source, .. } | StreamingQuery::Upsert { source, .. } => {
+            match source {
+                InsertSource::Values { rows } => {
+                    for expr in rows.iter_mut().flatten() {
+                        walk_expr(expr, f);
+                    }
+                }
+                InsertSource::Select { query } => for_each_expr(query, f),
+            }
+        }
+        StreamingQuery::Update {
+            assignments,
+            where_clause,
+            ..
+        } => {
+            for (_, expr) in assignments.iter_mut() {
+                walk_expr(expr, f);
+            }
+            for expr in where_clause.iter_mut() {
+                walk_expr(expr, f);
+            }
+        }
+        StreamingQuery::Delete { where_clause, .. } => {
+            for expr in where_clause.iter_mut() {
+                walk_expr(expr, f);
+            }
+        }
+        StreamingQuery::Merge {
+            source,
+            on,
+            clauses,
+            ..
+        } => {
+            walk_source(source, f);
+            walk_expr(on, f);
+            for clause in clauses.iter_mut() {
+                match clause {
+                    MergeClause::Matched { condition, action } => {
+                        for expr in condition.iter_mut() {
+                            walk_expr(expr, f);
+                        }
+                        if let MergeMatchedAction::Update { assignments } = action {
+                            for (_, expr) in assignments.iter_mut() {
+                                walk_expr(expr, f);
+                            }
+                        }
+                    }
+                    MergeClause::NotMatched {
+                        condition, values, ..
+                    } => {
+                        for expr in condition.iter_mut().chain(values.iter_mut()) {
+                            walk_expr(expr, f);
+                        }
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn walk_source(source: &mut StreamSource, f: &mut dyn FnMut(&mut Expr)) {
+    if let StreamSource::Subquery(query) = source {
+        for_each_expr(query, f);
+    }
+}
+
+fn walk_expr(expr: &mut Expr, f: &mut dyn FnMut(&mut Expr)) {
+    f(expr);
+    match expr {
+        Expr::Column(_, _) | Expr::Literal(_, _) | Expr::Parameter(_, _) => {}
+        Expr::BinaryOp { left, right, .. } => {
+            walk_expr(left, f);
+            walk_expr(right, f);
+        }
+        Expr::UnaryOp { expr, .. } | Expr::FieldAccess { expr, .. } => walk_expr(expr, f),
+        Expr::Subscript { expr, index, .. } => {
+            walk_expr(expr, f);
+            walk_expr(index, f);
+        }
+        Expr::Function { args, .. } | Expr::List(args, _) => {
+            for arg in args.iter_mut() {
+                walk_expr(arg, f);
+            }
+        }
+        Expr::WindowFunction {
+            args, over_clause, ..
+        } => {
+            for arg in args.iter_mut() {
+                walk_expr(arg, f);
+            }
+            for order in over_clause.order_by.iter_mut() {
+                walk_expr(&mut order.expr, f);
+            }
+        }
+        Expr::Case {
+            when_clauses,
+            else_clause,
+            ..
+        } => {
+            for (condition, result) in when_clauses.iter_mut() {
+                walk_expr(condition, f);
+                walk_expr(result, f);
+            }
+            if let Some(else_expr) = else_clause {
+                walk_expr(else_expr, f);
+            }
+        }
+        Expr::Subquery { query, .. } => for_each_expr(query, f),
+        Expr::Between {
+            expr, low, high, ..
+        } => {
+            walk_expr(expr, f);
+            walk_expr(low, f);
+            walk_expr(high, f);
+        }
+        Expr::Lambda { body, .. } => walk_expr(body, f),
+    }
+}
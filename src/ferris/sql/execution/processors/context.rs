@@ -1,9 +1,16 @@
 //! Processor execution context and state management
 
-use crate::ferris::datasource::{DataReader, DataWriter, SourceOffset};
+use crate::ferris::datasource::{DataReader, DataWriter, PushdownFailure, SourceOffset};
 use crate::ferris::schema::{Schema, StreamHandle};
+use crate::ferris::sql::execution::algorithms::JoinStrategy;
+use crate::ferris::sql::execution::expression::CompiledQuery;
 use crate::ferris::sql::execution::internal::WindowState;
 use crate::ferris::sql::execution::performance::PerformanceMonitor;
+use crate::ferris::sql::execution::spill::MemoryBudget;
+use crate::ferris::sql::execution::state::{
+    StateBackends, StateCodec, StateOperator, StateTtl, ValueState,
+};
+use crate::ferris::sql::execution::subquery::{LookupSource, SubqueryCache, SubqueryRefreshPolicy};
 use crate::ferris::sql::execution::StreamRecord;
 use crate::ferris::sql::SqlError;
 use std::collections::HashMap;
@@ -36,6 +43,12 @@ pub struct ProcessorContext {
     /// Data sources for subquery execution
     /// Maps table/stream name to available records for querying
     pub data_sources: HashMap<String, Vec<StreamRecord>>,
+    /// Lookup sources subqueries read reference data from, by name
+    pub lookup_sources: HashMap<String, Arc<dyn LookupSource>>,
+    /// Subquery plans and cached results of uncorrelated subqueries, shared with the engine
+    pub subquery_cache: SubqueryCache,
+    /// Refresh policy of cached subquery results without a `subquery.refresh` property
+    pub subquery_refresh: SubqueryRefreshPolicy,
 
     // === PLUGGABLE DATA SOURCE SUPPORT ===
     /// Multiple input data readers (e.g., Kafka + S3 + File)
@@ -50,7 +63,7 @@ pub struct ProcessorContext {
     /// Active sink for current write operation
     /// Enables context.write() to work without specifying sink each time
     pub active_writer: Option<String>,
-    /// Source positions/offsets for commit/seek operations
+    /// Source positions/offsets for commit/seek operations, updated after every read
     pub source_positions: HashMap<String, SourceOffset>,
 
     // === HIGH-PERFORMANCE WINDOW STATE MANAGEMENT ===
@@ -61,6 +74,23 @@ pub struct ProcessorContext {
     pub dirty_window_states: u32,
     /// Generic metadata storage for processors (e.g., job management)
     pub metadata: HashMap<String, String>,
+    /// Current rows of MERGE/UPSERT targets: target name -> upsert key -> row
+    ///
+    /// With a merge state backend the rows live in the backend and the map of a target
+    /// only records that it was written to.
+    pub merge_target_states: HashMap<String, HashMap<String, StreamRecord>>,
+    /// State backends of stateful operators, shared with the engine
+    pub state_backends: StateBackends,
+    /// Update times of keyed state when it expires after a `state.ttl`, moved in from
+    /// the engine for the record
+    pub state_ttl: Option<StateTtl>,
+    /// Memory budget of the job's buffering operators, shared with the engine
+    pub memory_budget: Option<MemoryBudget>,
+    /// JOIN strategies chosen by the query optimizer, by right source name
+    pub join_strategies: HashMap<String, JoinStrategy>,
+    /// Compiled WHERE and SELECT expressions of the query being processed, built when the
+    /// query was planned; `None` interprets the query
+    pub query_plan: Option<Arc<CompiledQuery>>,
 
     // === PERFORMANCE MONITORING ===
     /// Optional performance monitor for query tracking
@@ -89,6 +119,9 @@ impl ProcessorContext {
             schemas: HashMap::new(),
             stream_handles: HashMap::new(),
             data_sources: HashMap::new(),
+            lookup_sources: HashMap::new(),
+            subquery_cache: SubqueryCache::new(),
+            subquery_refresh: SubqueryRefreshPolicy::default(),
             data_readers: HashMap::new(),
             data_writers: HashMap::new(),
             active_reader: None,
@@ -97,6 +130,12 @@ impl ProcessorContext {
             persistent_window_states: Vec::new(),
             dirty_window_states: 0,
             metadata: HashMap::new(),
+            merge_target_states: HashMap::new(),
+            state_backends: StateBackends::new(),
+            state_ttl: None,
+            memory_budget: None,
+            join_strategies: HashMap::new(),
+            query_plan: None,
             performance_monitor: None,
         }
     }
@@ -206,6 +245,48 @@ impl ProcessorContext {
         self.dirty_window_states = 0;
     }
 
+    // === OPERATOR STATE METHODS ===
+
+    /// Value state of an operator in its configured backend, `None` without one
+    pub fn value_state<K: StateCodec, V: StateCodec>(
+        &self,
+        operator: StateOperator,
+        namespace: &str,
+    ) -> Option<ValueState<K, V>> {
+        self.state_backends
+            .get(operator)
+            .map(|backend| ValueState::new(Arc::clone(backend), namespace))
+    }
+
+    /// Record that `record` updated a key of keyed state, restarting its TTL
+    pub fn touch_state<K: StateCodec>(
+        &mut self,
+        operator: StateOperator,
+        namespace: &str,
+        key: &K,
+        record: &StreamRecord,
+    ) {
+        if let Some(ttl) = &mut self.state_ttl {
+            ttl.touch(operator, namespace, &key.to_state_bytes(), record.timestamp);
+        }
+    }
+
+    /// Current rows of a MERGE/UPSERT target, `None` when nothing was written to it
+    pub fn materialized_rows(&self, table: &str) -> Result<Option<Vec<StreamRecord>>, SqlError> {
+        let rows = self.merge_target_states.get(table);
+        match self.value_state::<String, StreamRecord>(
+            StateOperator::Merge,
+            &StateOperator::Merge.namespace(table),
+        ) {
+            Some(state) => {
+                let stored: Vec<StreamRecord> =
+                    state.entries()?.into_iter().map(|(_, row)| row).collect();
+                Ok((rows.is_some() || !stored.is_empty()).then_some(stored))
+            }
+            None => Ok(rows.map(|rows| rows.values().cloned().collect())),
+        }
+    }
+
     // === HETEROGENEOUS DATA SOURCE METHODS ===
 
     /// Register a data reader for a specific source
@@ -236,10 +317,25 @@ impl ProcessorContext {
                     query: None,
                 })?;
 
-        reader.read().await.map_err(|e| SqlError::ExecutionError {
+        let records = reader.read().await.map_err(|e| SqlError::ExecutionError {
             message: format!("Failed to read from source '{}': {}", source_name, e),
             query: None,
-        })
+        })?;
+
+        // Checkpoints record how far each source was read
+        if let Some(position) = reader.position() {
+            self.source_positions
+                .insert(source_name.to_string(), position);
+        }
+        Ok(records)
+    }
+
+    /// Take the records the pushed-down filter of a source could not be evaluated on
+    pub fn take_pushdown_failures(&mut self, source_name: &str) -> Vec<PushdownFailure> {
+        self.data_readers
+            .get_mut(source_name)
+            .map(|reader| reader.take_pushdown_failures())
+            .unwrap_or_default()
     }
 
     /// Write to a specific data sink
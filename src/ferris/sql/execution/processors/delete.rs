@@ -104,7 +104,7 @@ impl DeleteProcessor {
         );
 
         let tombstone_record = StreamRecord {
-            fields: tombstone_fields,
+            fields: tombstone_fields.into(),
             timestamp: chrono::Utc::now().timestamp_millis(), // New timestamp for delete event
             offset: original_record.offset + 1, // Increment offset to show this is a new event
             partition: original_record.partition,
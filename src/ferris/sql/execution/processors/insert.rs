@@ -112,7 +112,7 @@ impl InsertProcessor {
 
             // Create new record for insertion
             let insert_record = StreamRecord {
-                fields,
+                fields: fields.into(),
                 timestamp: input_record.timestamp, // Preserve original timestamp
                 offset: input_record.offset + (row_index as i64), // Increment offset for each row
                 partition: input_record.partition,
@@ -234,7 +234,7 @@ impl InsertProcessor {
 
         // Step 4: Create INSERT record with proper metadata
         Ok(StreamRecord {
-            fields: insert_fields,
+            fields: insert_fields.into(),
             headers: input_record.headers.clone(), // Preserve original headers
             timestamp: select_record.timestamp.max(input_record.timestamp), // Use latest timestamp
             offset: input_record.offset,           // Preserve INSERT context offset
@@ -2,13 +2,16 @@
 # Job Management Processor
 
 Handles streaming job lifecycle operations including START, STOP, PAUSE, RESUME, and DEPLOY.
-This processor manages continuous query execution in streaming environments.
+This processor manages continuous query execution in streaming environments. Savepoints
+are written, listed and restored by the job server, see
+[`StreamJobServer::execute_statement`](crate::ferris::server::StreamJobServer::execute_statement).
 */
 
 use super::ProcessorContext;
 use crate::ferris::sql::ast::{DeploymentStrategy, StreamingQuery};
 use crate::ferris::sql::error::SqlError;
 use crate::ferris::sql::execution::types::{FieldValue, StreamRecord};
+use crate::ferris::sql::formatter::format_query;
 use std::collections::HashMap;
 
 /// Processor for handling job management operations
@@ -66,7 +69,7 @@ impl JobProcessor {
         context.set_metadata("last_job_started", name);
 
         Ok(Some(StreamRecord {
-            fields,
+            fields: fields.into(),
             timestamp: record.timestamp,
             offset: record.offset,
             partition: record.partition,
@@ -103,7 +106,7 @@ impl JobProcessor {
         context.set_metadata("last_job_stopped", name);
 
         Ok(Some(StreamRecord {
-            fields,
+            fields: fields.into(),
             timestamp: record.timestamp,
             offset: record.offset,
             partition: record.partition,
@@ -141,7 +144,7 @@ impl JobProcessor {
         context.set_metadata("last_job_paused", name);
 
         Ok(Some(StreamRecord {
-            fields,
+            fields: fields.into(),
             timestamp: record.timestamp,
             offset: record.offset,
             partition: record.partition,
@@ -179,7 +182,7 @@ impl JobProcessor {
         context.set_metadata("last_job_resumed", name);
 
         Ok(Some(StreamRecord {
-            fields,
+            fields: fields.into(),
             timestamp: record.timestamp,
             offset: record.offset,
             partition: record.partition,
@@ -192,7 +195,7 @@ impl JobProcessor {
         &self,
         name: &str,
         version: &str,
-        _query: &StreamingQuery,
+        query: &StreamingQuery,
         properties: &HashMap<String, String>,
         strategy: &DeploymentStrategy,
         context: &mut ProcessorContext,
@@ -237,6 +240,7 @@ impl JobProcessor {
             "strategy".to_string(),
             FieldValue::String(strategy_name.to_string()),
         );
+        fields.insert("query".to_string(), FieldValue::String(format_query(query)));
 
         if !properties.is_empty() {
             fields.insert(
@@ -250,7 +254,7 @@ impl JobProcessor {
         context.set_metadata("last_deployment_version", version);
 
         Ok(Some(StreamRecord {
-            fields,
+            fields: fields.into(),
             timestamp: record.timestamp,
             offset: record.offset,
             partition: record.partition,
@@ -270,18 +274,39 @@ impl JobProcessor {
                 name,
                 query,
                 properties,
-            } => self.process_start_job(name, query, properties, context, record),
-            StreamingQuery::StopJob { name, force } => {
+                savepoint, ..
+            } => {
+                let mut result =
+                    self.process_start_job(name, query, properties, context, record)?;
+                if let (Some(result), Some(path)) = (result.as_mut(), savepoint) {
+                    result
+                        .fields
+                        .insert("savepoint", FieldValue::String(path.clone()));
+                }
+                Ok(result)
+            }
+            StreamingQuery::StopJob {
+                name,
+                savepoint: true,
+                ..
+            } => Err(SqlError::ExecutionError {
+                message: format!(
+                    "[SAVEPOINT-005] STOP JOB {} WITH SAVEPOINT must be executed by the job server, see StreamJobServer::execute_statement",
+                    name
+                ),
+                query: None,
+            }),
+            StreamingQuery::StopJob { name, force, .. } => {
                 self.process_stop_job(name, force, context, record)
             }
-            StreamingQuery::PauseJob { name } => self.process_pause_job(name, context, record),
-            StreamingQuery::ResumeJob { name } => self.process_resume_job(name, context, record),
+            StreamingQuery::PauseJob { name, .. } => self.process_pause_job(name, context, record),
+            StreamingQuery::ResumeJob { name, .. } => self.process_resume_job(name, context, record),
             StreamingQuery::DeployJob {
                 name,
                 version,
                 query,
                 properties,
-                strategy,
+                strategy, ..
             } => {
                 self.process_deploy_job(name, version, query, properties, strategy, context, record)
             }
@@ -296,6 +321,7 @@ impl JobProcessor {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ferris::sql::ast::NodeSpan;
 
     #[test]
     fn test_job_processor_creation() {
@@ -310,7 +336,7 @@ mod tests {
         let mut context = ProcessorContext::new("test_job");
 
         let record = StreamRecord {
-            fields: HashMap::new(),
+            fields: HashMap::new().into(),
             timestamp: 1000,
             offset: 0,
             partition: 0,
@@ -320,6 +346,7 @@ mod tests {
         let query = StreamingQuery::Select {
             fields: vec![],
             from: crate::ferris::sql::ast::StreamSource::Stream("test_stream".to_string()),
+            from_alias: None,
             joins: None,
             where_clause: None,
             group_by: None,
@@ -329,6 +356,7 @@ mod tests {
             limit: None,
             emit_mode: None,
             properties: None,
+            span: NodeSpan::default(),
         };
 
         let properties = HashMap::new();
@@ -354,7 +382,7 @@ mod tests {
         let mut context = ProcessorContext::new("test_job");
 
         let record = StreamRecord {
-            fields: HashMap::new(),
+            fields: HashMap::new().into(),
             timestamp: 1000,
             offset: 0,
             partition: 0,
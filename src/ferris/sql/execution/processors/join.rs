@@ -44,8 +44,11 @@ impl JoinProcessor {
         // Get right records for the join
         let right_records = Self::get_right_records_batch(&join_clause.right_source, context)?;
 
-        // Determine join strategy based on data sizes
-        let strategy = Self::select_join_strategy(left_records.len(), right_records.len());
+        // Use the strategy the optimizer chose from join statistics, else decide by data sizes
+        let strategy = Self::source_name(&join_clause.right_source)
+            .and_then(|name| context.join_strategies.get(name).cloned())
+            .filter(|strategy| *strategy != JoinStrategy::Auto)
+            .unwrap_or_else(|| Self::select_join_strategy(left_records.len(), right_records.len()));
 
         match strategy {
             JoinStrategy::HashJoin => {
@@ -58,6 +61,21 @@ impl JoinProcessor {
         }
     }
 
+    /// Name of a stream, table or URI source, looking through subqueries
+    fn source_name(source: &StreamSource) -> Option<&str> {
+        use crate::ferris::sql::ast::StreamingQuery;
+
+        match source {
+            StreamSource::Stream(name) | StreamSource::Table(name) | StreamSource::Uri(name) => {
+                Some(name)
+            }
+            StreamSource::Subquery(query) => match query.as_ref() {
+                StreamingQuery::Select { from, .. } => Self::source_name(from),
+                _ => None,
+            },
+        }
+    }
+
     /// Select optimal join strategy based on cardinalities
     fn select_join_strategy(left_size: usize, right_size: usize) -> JoinStrategy {
         // Use hash join if one side is significantly larger
@@ -78,10 +96,12 @@ impl JoinProcessor {
         join_clause: &JoinClause,
         context: &mut ProcessorContext,
     ) -> Result<Vec<StreamRecord>, SqlError> {
-        // Build hash join executor
-        let mut executor = HashJoinBuilder::new()
-            .with_strategy(JoinStrategy::HashJoin)
-            .build(join_clause.clone())?;
+        // Build hash join executor, spilling within the job's memory budget
+        let mut builder = HashJoinBuilder::new().with_strategy(JoinStrategy::HashJoin);
+        if let Some(budget) = &context.memory_budget {
+            builder = builder.with_memory_budget(budget.clone());
+        }
+        let mut executor = builder.build(join_clause.clone())?;
 
         // Execute hash join
         executor.execute(left_records, right_records, context)
@@ -513,19 +533,26 @@ impl JoinProcessor {
 
         // Execute the subquery against available data sources
         match subquery {
-            StreamingQuery::Select { .. } => {
+            StreamingQuery::Select { where_clause, .. } => {
                 // Get data source for the subquery
                 if let Some(data_sources) =
                     Self::get_subquery_data_source_for_join(subquery, context)
                 {
-                    // For JOIN operations, return the first matching record
-                    // In a full implementation, this would apply WHERE conditions,
-                    // GROUP BY, and other query clauses
-                    if let Some(first_record) = data_sources.first() {
-                        Ok(Some(first_record.clone()))
-                    } else {
-                        Ok(None)
+                    // For JOIN operations, return the first record matching the WHERE clause
+                    // In a full implementation, this would also apply GROUP BY
+                    // and other query clauses
+                    for record in data_sources {
+                        let matches = match where_clause {
+                            Some(condition) => {
+                                ExpressionEvaluator::evaluate_expression(condition, &record)?
+                            }
+                            None => true,
+                        };
+                        if matches {
+                            return Ok(Some(record));
+                        }
                     }
+                    Ok(None)
                 } else {
                     // No data source available - this is expected for some test scenarios
                     // or when the subquery references tables not available in context
@@ -127,7 +127,7 @@ impl JoinContext {
         }
 
         Ok(Some(crate::ferris::sql::execution::StreamRecord {
-            fields,
+            fields: fields.into(),
             headers: std::collections::HashMap::new(),
             timestamp: 1640995200000,
             offset: 1,
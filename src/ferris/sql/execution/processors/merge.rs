@@ -0,0 +1,500 @@
+/*!
+# MERGE and UPSERT Processor
+
+Handles `MERGE INTO` and `UPSERT INTO`, which maintain keyed rows of a target table.
+
+## Supported Operations
+
+1. **MERGE**: `MERGE INTO target t USING source s ON t.id = s.id WHEN MATCHED ... WHEN NOT MATCHED ...`
+2. **UPSERT with VALUES**: `UPSERT INTO table (id, qty) VALUES (s.id, s.qty)`
+3. **UPSERT with SELECT**: `UPSERT INTO table KEY (id) SELECT id, qty FROM source`
+
+## Streaming Semantics
+
+Each source record is one MERGE or UPSERT against the current rows of the target,
+which are kept by upsert key in the processor context, or in the merge state backend
+when the job configures one (see [`state`](crate::ferris::sql::execution::state)):
+- MERGE keys come from the `ON` clause, which must be an AND of equalities between
+  target and source columns; a source record with a NULL key never matches
+- The first `WHEN` clause whose kind and `AND` condition apply wins; records that
+  no clause applies to produce nothing
+- UPSERT inserts the row, or overlays the listed columns on the existing row
+- Every change is emitted as a changelog record with `operation` (`INSERT`, `UPDATE`
+  or `DELETE`), `table_name` and `upsert.key` headers; deletes are tombstones that
+  carry only the key columns and `__deleted`
+- With a `state.ttl`, rows not written within the TTL are evicted, see
+  [`ttl`](crate::ferris::sql::execution::state::ttl)
+
+Sinks that support upserts apply changelog records with `DataWriter::update` and
+`DataWriter::delete` on the `upsert.key`; other sinks receive the changelog as appends.
+
+## Examples
+
+```sql
+MERGE INTO inventory t
+USING stock_movements s
+ON t.sku = s.sku
+WHEN MATCHED AND s.qty = 0 THEN DELETE
+WHEN MATCHED THEN UPDATE SET qty = t.qty + s.qty
+WHEN NOT MATCHED THEN INSERT (sku, qty) VALUES (s.sku, s.qty);
+
+UPSERT INTO prices (sku, price) VALUES (sku, price);
+```
+*/
+
+use crate::ferris::sql::ast::{
+    BinaryOperator, Expr, InsertSource, MergeClause, MergeMatchedAction, StreamSource,
+    StreamingQuery,
+};
+use crate::ferris::sql::error::SqlError;
+use crate::ferris::sql::execution::expression::evaluator::ExpressionEvaluator;
+use crate::ferris::sql::execution::state::{StateCodec, StateOperator, StateTtl, ValueState};
+use crate::ferris::sql::execution::table_constraints::encode_upsert_key;
+use crate::ferris::sql::execution::{FieldValue, RecordFields, StreamRecord};
+use std::collections::HashMap;
+
+use super::{InsertProcessor, ProcessorContext};
+
+/// Header carrying the change type of a changelog record (`INSERT`, `UPDATE` or `DELETE`)
+pub const CHANGE_OPERATION_HEADER: &str = "operation";
+
+/// Header carrying the upsert key of a changelog record
+pub const CHANGE_KEY_HEADER: &str = "upsert.key";
+
+/// Rows of one MERGE/UPSERT target, in the context or in the merge state backend
+enum TargetStore<'a> {
+    Memory(&'a mut HashMap<String, StreamRecord>),
+    Backend(ValueState<String, StreamRecord>),
+}
+
+/// Rows of one MERGE/UPSERT target, restarting the TTL of every row a record writes
+struct TargetRows<'a> {
+    store: TargetStore<'a>,
+    ttl: Option<&'a mut StateTtl>,
+    namespace: String,
+    event_time: i64,
+}
+
+impl<'a> TargetRows<'a> {
+    fn open(context: &'a mut ProcessorContext, target: &str, record: &StreamRecord) -> Self {
+        let namespace = StateOperator::Merge.namespace(target);
+        let state = context.value_state(StateOperator::Merge, &namespace);
+        // The context entry also marks the target as materialized for subqueries
+        let rows = context
+            .merge_target_states
+            .entry(target.to_string())
+            .or_default();
+        let store = match state {
+            Some(state) => TargetStore::Backend(state),
+            None => TargetStore::Memory(rows),
+        };
+        TargetRows {
+            store,
+            ttl: context.state_ttl.as_mut(),
+            namespace,
+            event_time: record.timestamp,
+        }
+    }
+
+    fn get(&self, key: &str) -> Result<Option<StreamRecord>, SqlError> {
+        match &self.store {
+            TargetStore::Memory(rows) => Ok(rows.get(key).cloned()),
+            TargetStore::Backend(state) => state.get(&key.to_string()),
+        }
+    }
+
+    fn insert(&mut self, key: &str, row: &StreamRecord) -> Result<(), SqlError> {
+        match &mut self.store {
+            TargetStore::Memory(rows) => {
+                rows.insert(key.to_string(), row.clone());
+            }
+            TargetStore::Backend(state) => state.set(&key.to_string(), row)?,
+        }
+        if let Some(ttl) = &mut self.ttl {
+            let key = key.to_string().to_state_bytes();
+            ttl.touch(StateOperator::Merge, &self.namespace, &key, self.event_time);
+        }
+        Ok(())
+    }
+
+    fn remove(&mut self, key: &str) -> Result<(), SqlError> {
+        match &mut self.store {
+            TargetStore::Memory(rows) => {
+                rows.remove(key);
+            }
+            TargetStore::Backend(state) => state.remove(&key.to_string())?,
+        }
+        if let Some(ttl) = &mut self.ttl {
+            let key = key.to_string().to_state_bytes();
+            ttl.forget(StateOperator::Merge, &self.namespace, &key);
+        }
+        Ok(())
+    }
+}
+
+/// Change applied to a keyed target row
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeOperation {
+    Insert,
+    Update,
+    Delete,
+}
+
+impl ChangeOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeOperation::Insert => "INSERT",
+            ChangeOperation::Update => "UPDATE",
+            ChangeOperation::Delete => "DELETE",
+        }
+    }
+}
+
+/// Processor for MERGE INTO and UPSERT INTO operations
+pub struct MergeProcessor;
+
+impl MergeProcessor {
+    /// Process a MERGE statement for one source record
+    pub fn process_merge(
+        query: &StreamingQuery,
+        input_record: &StreamRecord,
+        context: &mut ProcessorContext,
+    ) -> Result<Option<StreamRecord>, SqlError> {
+        let StreamingQuery::Merge {
+            target,
+            target_alias,
+            source,
+            source_alias,
+            on,
+            clauses,
+            ..
+        } = query
+        else {
+            return Err(SqlError::ExecutionError {
+                message: "MergeProcessor can only process MERGE statements".to_string(),
+                query: None,
+            });
+        };
+        log::debug!("Processing MERGE INTO {} statement", target);
+
+        let target_qualifier = target_alias.as_deref().unwrap_or(target);
+        let source_name = match source {
+            StreamSource::Stream(name) | StreamSource::Table(name) | StreamSource::Uri(name) => {
+                Some(name.as_str())
+            }
+            StreamSource::Subquery(_) => None,
+        };
+        let source_qualifier = source_alias.as_deref().or(source_name).unwrap_or("");
+        let key_pairs = Self::key_pairs(on, [target, target_qualifier], source_qualifier)?;
+
+        // Source key, in the order of the ON equalities
+        let source_key: Option<Vec<FieldValue>> = key_pairs
+            .iter()
+            .map(
+                |(_, source_column)| match input_record.fields.get(source_column) {
+                    None | Some(FieldValue::Null) => None,
+                    Some(value) => Some(value.clone()),
+                },
+            )
+            .collect();
+        let key = source_key.as_deref().and_then(encode_upsert_key);
+
+        context.subquery_cache.invalidate_table(target);
+        let mut target_rows = TargetRows::open(context, target, input_record);
+        let existing = match &key {
+            Some(key) => target_rows.get(key)?,
+            None => None,
+        };
+
+        // Source columns resolve unqualified and qualified, target columns qualified
+        let mut eval_record = input_record.clone();
+        for (name, value) in &input_record.fields {
+            eval_record
+                .fields
+                .insert(format!("{}.{}", source_qualifier, name), value.clone());
+        }
+        if let Some(row) = &existing {
+            for (name, value) in &row.fields {
+                eval_record
+                    .fields
+                    .insert(format!("{}.{}", target_qualifier, name), value.clone());
+            }
+        }
+
+        for clause in clauses {
+            let (condition, matched) = match clause {
+                MergeClause::Matched { condition, .. } => (condition, true),
+                MergeClause::NotMatched { condition, .. } => (condition, false),
+            };
+            if matched != existing.is_some() {
+                continue;
+            }
+            if let Some(condition) = condition {
+                if !ExpressionEvaluator::evaluate_expression(condition, &eval_record)? {
+                    continue;
+                }
+            }
+
+            return match (clause, existing) {
+                (
+                    MergeClause::Matched {
+                        action: MergeMatchedAction::Update { assignments },
+                        ..
+                    },
+                    Some(mut row),
+                ) => {
+                    // Assignments are evaluated against the row before the update
+                    for (column, expr) in assignments {
+                        if key_pairs.iter().any(|(key_column, _)| key_column == column) {
+                            return Err(SqlError::ExecutionError {
+                                message: format!(
+                                    "MERGE cannot update key column '{}' of '{}'",
+                                    column, target
+                                ),
+                                query: None,
+                            });
+                        }
+                        let value =
+                            ExpressionEvaluator::evaluate_expression_value(expr, &eval_record)?;
+                        row.fields.insert(column.clone(), value);
+                    }
+                    let key = key.unwrap_or_default();
+                    target_rows.insert(&key, &row)?;
+                    Ok(Some(Self::changelog_record(
+                        row.fields,
+                        ChangeOperation::Update,
+                        target,
+                        Some(&key),
+                        input_record,
+                    )))
+                }
+                (
+                    MergeClause::Matched {
+                        action: MergeMatchedAction::Delete,
+                        ..
+                    },
+                    Some(row),
+                ) => {
+                    let key = key.unwrap_or_default();
+                    target_rows.remove(&key)?;
+                    let mut fields: RecordFields = key_pairs
+                        .iter()
+                        .filter_map(|(column, _)| {
+                            row.fields.get(column).map(|v| (column.clone(), v.clone()))
+                        })
+                        .collect();
+                    fields.insert("__deleted".to_string(), FieldValue::Boolean(true));
+                    let mut tombstone = Self::changelog_record(
+                        fields,
+                        ChangeOperation::Delete,
+                        target,
+                        Some(&key),
+                        input_record,
+                    );
+                    tombstone
+                        .headers
+                        .insert("tombstone".to_string(), "true".to_string());
+                    Ok(Some(tombstone))
+                }
+                (
+                    MergeClause::NotMatched {
+                        columns, values, ..
+                    },
+                    _,
+                ) => {
+                    let mut fields = RecordFields::new();
+                    for (column, expr) in columns.iter().zip(values) {
+                        let value =
+                            ExpressionEvaluator::evaluate_expression_value(expr, &eval_record)?;
+                        fields.insert(column.clone(), value);
+                    }
+                    // Key columns left out of the INSERT take the matching source value
+                    for (key_column, source_column) in &key_pairs {
+                        if !fields.contains_key(key_column) {
+                            let value = input_record
+                                .fields
+                                .get(source_column)
+                                .cloned()
+                                .unwrap_or(FieldValue::Null);
+                            fields.insert(key_column.clone(), value);
+                        }
+                    }
+
+                    let row_key = Self::key_of(&fields, key_pairs.iter().map(|(t, _)| t));
+                    if let Some(row_key) = &row_key {
+                        target_rows.insert(row_key, &Self::row(&fields, input_record))?;
+                    }
+                    Ok(Some(Self::changelog_record(
+                        fields,
+                        ChangeOperation::Insert,
+                        target,
+                        row_key.as_deref(),
+                        input_record,
+                    )))
+                }
+                (MergeClause::Matched { .. }, None) => unreachable!("checked above"),
+            };
+        }
+
+        log::debug!("No MERGE clause applies to the record, skipping");
+        Ok(None)
+    }
+
+    /// Process an UPSERT statement for one source record
+    pub fn process_upsert(
+        table_name: &str,
+        columns: &Option<Vec<String>>,
+        key_columns: &[String],
+        source: &InsertSource,
+        input_record: &StreamRecord,
+        context: &mut ProcessorContext,
+    ) -> Result<Option<StreamRecord>, SqlError> {
+        log::debug!("Processing UPSERT INTO {} statement", table_name);
+
+        let Some(inserted) =
+            InsertProcessor::process_insert(table_name, columns, source, input_record)?
+                .into_iter()
+                .next()
+        else {
+            return Ok(None);
+        };
+
+        let key = Self::key_of(&inserted.fields, key_columns.iter()).ok_or_else(|| {
+            SqlError::ExecutionError {
+                message: format!(
+                    "UPSERT INTO {} requires non-NULL values for key columns {}",
+                    table_name,
+                    key_columns.join(", ")
+                ),
+                query: None,
+            }
+        })?;
+
+        context.subquery_cache.invalidate_table(table_name);
+        let mut target_rows = TargetRows::open(context, table_name, input_record);
+        let (fields, operation) = match target_rows.get(&key)? {
+            Some(existing) => {
+                // Columns not listed keep their current values
+                let mut fields = existing.fields.clone();
+                fields.extend(inserted.fields);
+                (fields, ChangeOperation::Update)
+            }
+            None => (inserted.fields, ChangeOperation::Insert),
+        };
+        target_rows.insert(&key, &Self::row(&fields, input_record))?;
+
+        Ok(Some(Self::changelog_record(
+            fields,
+            operation,
+            table_name,
+            Some(&key),
+            input_record,
+        )))
+    }
+
+    /// Split a MERGE ON clause into `(target column, source column)` key pairs
+    fn key_pairs(
+        on: &Expr,
+        target_qualifiers: [&str; 2],
+        source_qualifier: &str,
+    ) -> Result<Vec<(String, String)>, SqlError> {
+        let invalid = || SqlError::ExecutionError {
+            message: "MERGE ON must be an AND of equalities between target and source columns, \
+                      e.g. ON t.id = s.id"
+                .to_string(),
+            query: None,
+        };
+
+        match on {
+            Expr::BinaryOp {
+                left,
+                op: BinaryOperator::And,
+                right,
+                ..
+            } => {
+                let mut pairs = Self::key_pairs(left, target_qualifiers, source_qualifier)?;
+                pairs.extend(Self::key_pairs(right, target_qualifiers, source_qualifier)?);
+                Ok(pairs)
+            }
+            Expr::BinaryOp {
+                left,
+                op: BinaryOperator::Equal,
+                right,
+                ..
+            } => {
+                let (Expr::Column(left, _), Expr::Column(right, _)) =
+                    (left.as_ref(), right.as_ref())
+                else {
+                    return Err(invalid());
+                };
+                let side = |column: &str| -> Option<(bool, String)> {
+                    let (qualifier, name) = column.split_once('.')?;
+                    if target_qualifiers.contains(&qualifier) {
+                        Some((true, name.to_string()))
+                    } else if qualifier == source_qualifier {
+                        Some((false, name.to_string()))
+                    } else {
+                        None
+                    }
+                };
+                match (side(left), side(right)) {
+                    (Some((true, target)), Some((false, source)))
+                    | (Some((false, source)), Some((true, target))) => Ok(vec![(target, source)]),
+                    _ => Err(invalid()),
+                }
+            }
+            _ => Err(invalid()),
+        }
+    }
+
+    /// Upsert key of a row, or `None` when a key column is NULL
+    fn key_of<'a>(
+        fields: &RecordFields,
+        key_columns: impl Iterator<Item = &'a String>,
+    ) -> Option<String> {
+        let values: Option<Vec<FieldValue>> = key_columns
+            .map(|column| match fields.get(column) {
+                None | Some(FieldValue::Null) => None,
+                Some(value) => Some(value.clone()),
+            })
+            .collect();
+        values.as_deref().and_then(encode_upsert_key)
+    }
+
+    fn row(fields: &RecordFields, input_record: &StreamRecord) -> StreamRecord {
+        StreamRecord {
+            fields: fields.clone(),
+            timestamp: input_record.timestamp,
+            offset: input_record.offset,
+            partition: input_record.partition,
+            headers: HashMap::new(),
+        }
+    }
+
+    /// Build the changelog record emitted for a change to a target row
+    pub fn changelog_record(
+        fields: RecordFields,
+        operation: ChangeOperation,
+        table_name: &str,
+        key: Option<&str>,
+        input_record: &StreamRecord,
+    ) -> StreamRecord {
+        let mut headers = input_record.headers.clone();
+        headers.insert(
+            CHANGE_OPERATION_HEADER.to_string(),
+            operation.as_str().to_string(),
+        );
+        headers.insert("table_name".to_string(), table_name.to_string());
+        if let Some(key) = key {
+            headers.insert(CHANGE_KEY_HEADER.to_string(), key.to_string());
+        }
+
+        StreamRecord {
+            fields,
+            timestamp: input_record.timestamp,
+            offset: input_record.offset,
+            partition: input_record.partition,
+            headers,
+        }
+    }
+}
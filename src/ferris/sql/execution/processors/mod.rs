@@ -5,6 +5,7 @@
 //! - Window processing
 //! - JOIN processing
 //! - LIMIT processing
+//! - MERGE/UPSERT processing
 //! - SHOW/DESCRIBE processing
 
 use crate::ferris::sql::execution::StreamRecord;
@@ -55,6 +56,7 @@ impl QueryProcessor {
                 table_name,
                 columns,
                 source,
+                ..
             } => {
                 // Extract parameters and delegate to InsertProcessor
                 let insert_records =
@@ -71,6 +73,7 @@ impl QueryProcessor {
                 table_name,
                 assignments,
                 where_clause,
+                ..
             } => {
                 // Extract parameters and delegate to UpdateProcessor
                 let updated_record =
@@ -84,6 +87,7 @@ impl QueryProcessor {
             StreamingQuery::Delete {
                 table_name,
                 where_clause,
+                ..
             } => {
                 // Extract parameters and delegate to DeleteProcessor
                 let tombstone_record =
@@ -94,6 +98,36 @@ impl QueryProcessor {
                     should_count: true,
                 })
             }
+            StreamingQuery::Merge { .. } => {
+                // Delegate to MergeProcessor, which keeps the target rows in the context
+                let change_record = MergeProcessor::process_merge(query, record, context)?;
+                Ok(ProcessorResult {
+                    record: change_record,
+                    header_mutations: Vec::new(),
+                    should_count: true,
+                })
+            }
+            StreamingQuery::Upsert {
+                table_name,
+                columns,
+                key_columns,
+                source,
+                ..
+            } => {
+                let change_record = MergeProcessor::process_upsert(
+                    table_name,
+                    columns,
+                    key_columns,
+                    source,
+                    record,
+                    context,
+                )?;
+                Ok(ProcessorResult {
+                    record: change_record,
+                    header_mutations: Vec::new(),
+                    should_count: true,
+                })
+            }
             _ => {
                 // For other query types, use simplified implementation
                 Ok(ProcessorResult {
@@ -193,6 +227,7 @@ pub use self::delete::DeleteProcessor;
 pub use self::insert::InsertProcessor;
 pub use self::join::JoinProcessor;
 pub use self::limit::LimitProcessor;
+pub use self::merge::MergeProcessor;
 pub use self::select::SelectProcessor;
 pub use self::show::ShowProcessor;
 pub use self::update::UpdateProcessor;
@@ -205,6 +240,7 @@ pub mod job;
 pub mod join;
 pub mod join_context;
 pub mod limit;
+pub mod merge;
 pub mod select;
 pub mod show;
 pub mod update;
@@ -23,6 +23,7 @@ impl ShowProcessor {
         if let StreamingQuery::Show {
             resource_type,
             pattern,
+            ..
         } = query
         {
             let results = Self::generate_show_results(resource_type, pattern, context)?;
@@ -40,7 +41,7 @@ impl ShowProcessor {
                 // In a full implementation, we might want to support multiple result records
                 let first_result = &results[0];
                 let result_record = StreamRecord {
-                    fields: first_result.clone(),
+                    fields: first_result.clone().into(),
                     timestamp: record.timestamp,
                     offset: record.offset,
                     partition: record.partition,
@@ -90,6 +91,7 @@ impl ShowProcessor {
             ShowResourceType::JobStatus { name } => Self::show_job_status(name),
             ShowResourceType::JobVersions { name } => Self::show_job_versions(name),
             ShowResourceType::JobMetrics { name } => Self::show_job_metrics(name),
+            ShowResourceType::Savepoints { name } => Self::show_savepoints(name),
             ShowResourceType::Partitions { name } => {
                 Self::show_partitions(name, &context.stream_handles)
             }
@@ -174,7 +176,7 @@ impl ShowProcessor {
     }
 
     fn show_functions(
-        _pattern: &Option<String>,
+        pattern: &Option<String>,
     ) -> Result<Vec<HashMap<String, FieldValue>>, SqlError> {
         // Built-in functions available in the system
         let builtin_functions = vec![
@@ -192,10 +194,45 @@ impl ShowProcessor {
             ("DENSE_RANK", "Window", "Dense rank within partition"),
             ("LAG", "Window", "Previous row value"),
             ("LEAD", "Window", "Next row value"),
+            (
+                "REGEXP_LIKE",
+                "String",
+                "Tests whether a string matches a regular expression",
+            ),
+            (
+                "REGEXP_EXTRACT",
+                "String",
+                "Extracts the whole match or a capture group",
+            ),
+            (
+                "REGEXP_REPLACE",
+                "String",
+                "Replaces all matches, with $1/${name} group references",
+            ),
+            (
+                "REGEXP_SPLIT",
+                "String",
+                "Splits a string into an array on a regular expression",
+            ),
+            ("MD5", "Hash", "MD5 digest as lowercase hex"),
+            ("SHA256", "Hash", "SHA-256 digest as lowercase hex"),
+            ("XXHASH64", "Hash", "64-bit xxHash as a BIGINT"),
+            ("BASE64_ENCODE", "Encoding", "Encodes text as base64"),
+            ("BASE64_DECODE", "Encoding", "Decodes base64 to text"),
+            ("URL_DECODE", "Encoding", "Decodes percent-encoded text"),
+            (
+                "PARSE_URL",
+                "Encoding",
+                "Extracts a URL part or query parameter",
+            ),
+            ("UUID", "Utility", "Generates a random version 4 UUID"),
         ];
 
         let mut results = Vec::new();
         for (name, category, description) in builtin_functions {
+            if !Self::matches_pattern(name, pattern) {
+                continue;
+            }
             let mut fields = HashMap::new();
             fields.insert(
                 "function_name".to_string(),
@@ -387,6 +424,16 @@ impl ShowProcessor {
         Ok(vec![fields])
     }
 
+    fn show_savepoints(
+        _name: &Option<String>,
+    ) -> Result<Vec<HashMap<String, FieldValue>>, SqlError> {
+        // Savepoints live in the job server's savepoint directory, which the engine does not know
+        Err(SqlError::ExecutionError {
+            message: "[SAVEPOINT-005] SHOW SAVEPOINTS must be executed by the job server, see StreamJobServer::execute_statement".to_string(),
+            query: None,
+        })
+    }
+
     fn show_partitions(
         _name: &str,
         _streams: &HashMap<String, StreamHandle>,
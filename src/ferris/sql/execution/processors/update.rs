@@ -36,7 +36,7 @@ WHERE user_id IN (SELECT user_id FROM active_sessions);
 use crate::ferris::sql::ast::Expr;
 use crate::ferris::sql::error::SqlError;
 use crate::ferris::sql::execution::expression::evaluator::ExpressionEvaluator;
-use crate::ferris::sql::execution::{FieldValue, StreamRecord};
+use crate::ferris::sql::execution::{RecordFields, StreamRecord};
 use std::collections::HashMap;
 
 /// Processor for UPDATE operations
@@ -191,7 +191,7 @@ impl UpdateProcessor {
     pub fn apply_assignments(
         assignments: &[(String, Expr)],
         record: &StreamRecord,
-    ) -> Result<HashMap<String, FieldValue>, SqlError> {
+    ) -> Result<RecordFields, SqlError> {
         let mut updated_fields = record.fields.clone();
 
         for (column_name, value_expr) in assignments {
@@ -3,11 +3,14 @@
 //! Handles windowed query processing including tumbling, sliding, and session windows.
 
 use super::{ProcessorContext, WindowContext};
-use crate::ferris::sql::ast::WindowSpec;
+use crate::ferris::sql::ast::{BinaryOperator, Expr, LiteralValue, SelectField, WindowSpec};
 use crate::ferris::sql::execution::expression::ExpressionEvaluator;
 use crate::ferris::sql::execution::internal::WindowState;
 use crate::ferris::sql::execution::{FieldValue, StreamRecord};
 use crate::ferris::sql::{SqlError, StreamingQuery};
+use chrono::{DateTime, Offset, TimeZone, Utc};
+use chrono_tz::Tz;
+use std::cmp::Ordering;
 use std::collections::HashMap;
 
 /// Window processing utilities
@@ -31,10 +34,14 @@ impl WindowProcessor {
 
                 // Get or create window state using high-performance context management
                 // This is thread-safe and avoids locks entirely
+                let budget = context.memory_budget.clone();
                 let window_state = context.get_or_create_window_state(query_id, window_spec);
 
-                // Add record to buffer (pre-allocated for performance)
-                window_state.add_record(record.clone());
+                // Add record to buffer, spilling it to disk when the job is over its budget
+                match &budget {
+                    Some(budget) => window_state.add_record_within(record.clone(), budget)?,
+                    None => window_state.add_record(record.clone())?,
+                }
 
                 // Check if window should emit using optimized timing logic
                 if Self::should_emit_window_state(window_state, event_time, window_spec) {
@@ -70,62 +77,40 @@ impl WindowProcessor {
         event_time: i64,
     ) -> Result<Option<StreamRecord>, SqlError> {
         let last_emit_time = window_state.last_emit;
-        let buffer = window_state.buffer.clone();
-
-        // Filter buffer for current window
-        let windowed_buffer = match window_spec {
-            WindowSpec::Tumbling { size, .. } => {
-                let window_size_ms = size.as_millis() as i64;
-                let completed_window_start = if last_emit_time == 0 {
-                    0 // First window: 0 to window_size_ms
-                } else {
-                    last_emit_time
-                };
-                let completed_window_end = completed_window_start + window_size_ms;
-
-                // Filter records that belong to the completed window
-                buffer
-                    .iter()
-                    .filter(|r| {
-                        let record_time = Self::extract_event_time(r, window_spec.time_column());
-                        record_time >= completed_window_start && record_time < completed_window_end
-                    })
-                    .cloned()
-                    .collect()
-            }
-            _ => buffer, // For other window types, use all buffered records
-        };
-
-        // Execute aggregation on filtered records
-        let result_option = match Self::execute_windowed_aggregation_impl(query, &windowed_buffer) {
-            Ok(result) => Some(result),
-            Err(SqlError::ExecutionError { message, .. })
-                if message == "No records after filtering" =>
-            {
-                None
-            }
-            Err(SqlError::ExecutionError { message, .. })
-                if message == "HAVING clause not satisfied" =>
-            {
-                None
-            }
-            Err(SqlError::ExecutionError { message, .. })
-                if message == "No groups satisfied HAVING clause" =>
-            {
-                None
-            }
-            Err(e) => return Err(e),
-        };
+        let completed_window = Self::completed_window_bounds(window_spec, last_emit_time);
+
+        // Aggregate the records of the completed window in one pass; spilled records are
+        // read from disk one at a time rather than back into memory
+        let mut aggregation = WindowAggregation::new(query)?;
+        window_state.for_each_record(|record| {
+            if Self::in_window(record, window_spec, completed_window) {
+                aggregation.push(record)?;
+            }
+            Ok(())
+        })?;
+        let result_option = aggregation.finish()?;
 
         // Update window state after aggregation
         Self::update_window_state_direct(window_state, window_spec, event_time);
 
         // Clear or adjust buffer based on window type
-        Self::cleanup_window_buffer_direct(window_state, window_spec, last_emit_time);
+        Self::cleanup_window_buffer_direct(window_state, window_spec, last_emit_time)?;
+        window_state.rebalance()?;
 
         Ok(result_option)
     }
 
+    /// Result of every record a windowed query has buffered, for a window whose state
+    /// expires before it closes
+    pub fn aggregate_window_state(
+        query: &StreamingQuery,
+        window_state: &WindowState,
+    ) -> Result<Option<StreamRecord>, SqlError> {
+        let mut aggregation = WindowAggregation::new(query)?;
+        window_state.for_each_record(|record| aggregation.push(record))?;
+        aggregation.finish()
+    }
+
     /// Process window emission when triggered
     fn process_window_emission(
         query: &StreamingQuery,
@@ -134,52 +119,16 @@ impl WindowProcessor {
         event_time: i64,
     ) -> Result<Option<StreamRecord>, SqlError> {
         let last_emit_time = window_context.last_emit;
-        let buffer = window_context.buffer.clone();
-
-        // Filter buffer for current window
-        let windowed_buffer = match window_spec {
-            WindowSpec::Tumbling { size, .. } => {
-                let window_size_ms = size.as_millis() as i64;
-                let completed_window_start = if last_emit_time == 0 {
-                    0 // First window: 0 to window_size_ms
-                } else {
-                    last_emit_time
-                };
-                let completed_window_end = completed_window_start + window_size_ms;
-
-                // Filter records that belong to the completed window
-                buffer
-                    .iter()
-                    .filter(|r| {
-                        let record_time = Self::extract_event_time(r, window_spec.time_column());
-                        record_time >= completed_window_start && record_time < completed_window_end
-                    })
-                    .cloned()
-                    .collect()
-            }
-            _ => buffer, // For other window types, use all buffered records
-        };
+        let completed_window = Self::completed_window_bounds(window_spec, last_emit_time);
 
-        // Execute aggregation on filtered records
-        let result_option = match Self::execute_windowed_aggregation_impl(query, &windowed_buffer) {
-            Ok(result) => Some(result),
-            Err(SqlError::ExecutionError { message, .. })
-                if message == "No records after filtering" =>
-            {
-                None
-            }
-            Err(SqlError::ExecutionError { message, .. })
-                if message == "HAVING clause not satisfied" =>
-            {
-                None
-            }
-            Err(SqlError::ExecutionError { message, .. })
-                if message == "No groups satisfied HAVING clause" =>
-            {
-                None
-            }
-            Err(e) => return Err(e),
-        };
+        // Execute aggregation on the records of the completed window
+        let mut aggregation = WindowAggregation::new(query)?;
+        for record in &window_context.buffer {
+            if Self::in_window(record, window_spec, completed_window) {
+                aggregation.push(record)?;
+            }
+        }
+        let result_option = aggregation.finish()?;
 
         // Update window state after aggregation
         Self::update_window_state(window_context, window_spec, event_time);
@@ -190,6 +139,37 @@ impl WindowProcessor {
         Ok(result_option)
     }
 
+    /// `[start, end)` of the tumbling window completed since `last_emit`; other window
+    /// types aggregate every buffered record
+    fn completed_window_bounds(window_spec: &WindowSpec, last_emit: i64) -> Option<(i64, i64)> {
+        match window_spec {
+            WindowSpec::Tumbling {
+                size, time_zone, ..
+            } => {
+                // The first window starts at 0 while last_emit == 0
+                let (_, end) = Self::tumbling_window_bounds(
+                    last_emit,
+                    size.as_millis() as i64,
+                    time_zone.as_deref(),
+                );
+                Some((last_emit, end))
+            }
+            _ => None,
+        }
+    }
+
+    /// Check if a record belongs to the window being emitted
+    fn in_window(
+        record: &StreamRecord,
+        window_spec: &WindowSpec,
+        bounds: Option<(i64, i64)>,
+    ) -> bool {
+        bounds.map_or(true, |(start, end)| {
+            let record_time = Self::extract_event_time(record, window_spec.time_column());
+            record_time >= start && record_time < end
+        })
+    }
+
     /// Extract event time from record
     pub fn extract_event_time(record: &StreamRecord, time_column: Option<&str>) -> i64 {
         if let Some(column_name) = time_column {
@@ -197,6 +177,7 @@ impl WindowProcessor {
                 match field_value {
                     FieldValue::Integer(ts) => *ts,
                     FieldValue::Timestamp(ts) => ts.and_utc().timestamp_millis(),
+                    FieldValue::TimestampTz(ts) => ts.timestamp_millis(),
                     FieldValue::String(s) => s.parse::<i64>().unwrap_or(record.timestamp),
                     _ => record.timestamp,
                 }
@@ -208,6 +189,51 @@ impl WindowProcessor {
         }
     }
 
+    /// Compute the `[start, end)` bounds of the tumbling window containing `time`.
+    ///
+    /// Without a time zone windows are aligned to the Unix epoch. With one they are aligned to
+    /// local wall-clock time, so a 1-day window runs from local midnight to local midnight
+    /// (23 or 25 hours long on daylight-saving transition days).
+    pub fn tumbling_window_bounds(
+        time: i64,
+        window_size_ms: i64,
+        time_zone: Option<&str>,
+    ) -> (i64, i64) {
+        let zone = match time_zone.and_then(|name| name.parse::<Tz>().ok()) {
+            Some(zone) => zone,
+            None => {
+                let start = (time / window_size_ms) * window_size_ms;
+                return (start, start.saturating_add(window_size_ms));
+            }
+        };
+
+        let Some(instant) = Utc.timestamp_millis_opt(time).single() else {
+            let start = (time / window_size_ms) * window_size_ms;
+            return (start, start.saturating_add(window_size_ms));
+        };
+        let offset_ms = instant
+            .with_timezone(&zone)
+            .offset()
+            .fix()
+            .local_minus_utc() as i64
+            * 1000;
+
+        // Align in local time, then map the local boundaries back to UTC instants
+        let local_start = (time + offset_ms).div_euclid(window_size_ms) * window_size_ms;
+        let to_instant = |local_ms: i64| -> i64 {
+            DateTime::from_timestamp_millis(local_ms)
+                .and_then(|local| zone.from_local_datetime(&local.naive_utc()).earliest())
+                .map(|dt| dt.timestamp_millis())
+                // Boundary falls into a DST gap: keep the offset of the original instant
+                .unwrap_or(local_ms - offset_ms)
+        };
+
+        (
+            to_instant(local_start),
+            to_instant(local_start + window_size_ms),
+        )
+    }
+
     /// Check if window should emit based on proper window logic from legacy method
     pub fn should_emit_window(
         window_context: &WindowContext,
@@ -242,7 +268,7 @@ impl WindowProcessor {
         window_spec: &WindowSpec,
     ) -> bool {
         // Check if we have any buffered records
-        if window_state.buffer.is_empty() {
+        if window_state.is_empty() {
             return false;
         }
 
@@ -250,16 +276,16 @@ impl WindowProcessor {
         let last_emit = window_state.last_emit;
 
         match window_spec {
-            WindowSpec::Tumbling { size, .. } => {
+            WindowSpec::Tumbling {
+                size, time_zone, ..
+            } => {
                 let window_size_ms = size.as_millis() as i64;
 
-                // If this is the first window (last_emit == 0), check if we have enough time elapsed
-                if last_emit == 0 {
-                    return event_time >= window_size_ms;
-                }
-
-                // Otherwise, check if a full window period has elapsed
-                event_time >= last_emit + window_size_ms
+                // Emit once the event time passes the end of the current window
+                // (the first window starts at 0 while last_emit == 0)
+                let (_, window_end) =
+                    Self::tumbling_window_bounds(last_emit, window_size_ms, time_zone.as_deref());
+                event_time >= window_end
             }
             WindowSpec::Sliding { advance, .. } => {
                 let advance_ms = advance.as_millis() as i64;
@@ -274,184 +300,11 @@ impl WindowProcessor {
             WindowSpec::Session { .. } => {
                 // Session windows use enhanced logic with proper gap detection and overflow safety
                 // For now, emit after we have at least one record to enable basic functionality
-                !window_state.buffer.is_empty()
+                !window_state.is_empty()
             }
         }
     }
 
-    /// Execute windowed aggregation implementation using logic from legacy method
-    fn execute_windowed_aggregation_impl(
-        query: &StreamingQuery,
-        windowed_buffer: &[StreamRecord],
-    ) -> Result<StreamRecord, SqlError> {
-        use crate::ferris::sql::execution::expression::evaluator::ExpressionEvaluator;
-
-        if windowed_buffer.is_empty() {
-            return Err(SqlError::ExecutionError {
-                message: "No records after filtering".to_string(),
-                query: None,
-            });
-        }
-
-        if let StreamingQuery::Select {
-            fields,
-            where_clause,
-            group_by: _,
-            having,
-            ..
-        } = query
-        {
-            // Step 1: Filter records by WHERE clause
-            let filtered_records: Vec<&StreamRecord> = windowed_buffer
-                .iter()
-                .filter(|record| {
-                    if let Some(where_expr) = where_clause {
-                        ExpressionEvaluator::evaluate_expression(where_expr, record)
-                            .unwrap_or(false)
-                    } else {
-                        true
-                    }
-                })
-                .collect();
-
-            if filtered_records.is_empty() {
-                return Err(SqlError::ExecutionError {
-                    message: "No records after filtering".to_string(),
-                    query: None,
-                });
-            }
-
-            // Step 2: For windowed queries, create aggregated result
-            let mut result_fields = HashMap::new();
-
-            // Simple aggregation logic - process the first record as representative
-            if let Some(first_record) = filtered_records.first() {
-                // Process SELECT fields
-                for field in fields {
-                    match field {
-                        crate::ferris::sql::ast::SelectField::Wildcard => {
-                            // For windowed aggregations, add basic aggregate info instead of all fields
-                            result_fields.insert(
-                                "window_size".to_string(),
-                                FieldValue::Integer(filtered_records.len() as i64),
-                            );
-                        }
-                        crate::ferris::sql::ast::SelectField::Expression { expr, alias } => {
-                            let field_name = alias
-                                .clone()
-                                .unwrap_or_else(|| format!("field_{}", result_fields.len()));
-
-                            // Handle aggregate functions properly for windowed queries
-                            let value =
-                                Self::evaluate_aggregate_expression(expr, &filtered_records)?;
-                            result_fields.insert(field_name, value);
-                        }
-                        crate::ferris::sql::ast::SelectField::Column(column_name) => {
-                            // Simple column reference
-                            if let Some(value) = first_record.fields.get(column_name) {
-                                result_fields.insert(column_name.clone(), value.clone());
-                            }
-                        }
-                        crate::ferris::sql::ast::SelectField::AliasedColumn { column, alias } => {
-                            // Aliased column reference
-                            if let Some(value) = first_record.fields.get(column) {
-                                result_fields.insert(alias.clone(), value.clone());
-                            }
-                        }
-                    }
-                }
-            }
-
-            // Always include window metadata
-            result_fields.insert(
-                "_window_record_count".to_string(),
-                FieldValue::Integer(filtered_records.len() as i64),
-            );
-
-            // Step 3: Apply HAVING clause filtering
-            if let Some(having_expr) = having {
-                // Create a temporary record for HAVING evaluation
-                let temp_record = StreamRecord {
-                    fields: result_fields.clone(),
-                    timestamp: 0,
-                    offset: 0,
-                    partition: 0,
-                    headers: HashMap::new(),
-                };
-
-                // For HAVING clauses with aggregates, we need special handling
-                // Check if the HAVING expression contains aggregates
-                let having_result = match having_expr {
-                    crate::ferris::sql::ast::Expr::BinaryOp { left, op, right } => {
-                        // Handle HAVING COUNT(*) >= 2 style expressions
-                        let left_value =
-                            Self::evaluate_aggregate_expression(left, &filtered_records)?;
-                        let right_value = if let Ok(value) =
-                            ExpressionEvaluator::evaluate_expression_value(right, &temp_record)
-                        {
-                            value
-                        } else {
-                            FieldValue::Null
-                        };
-
-                        // Apply comparison
-                        match (left_value, right_value, op) {
-                            (
-                                FieldValue::Integer(left_int),
-                                FieldValue::Integer(right_int),
-                                crate::ferris::sql::ast::BinaryOperator::GreaterThanOrEqual,
-                            ) => left_int >= right_int,
-                            (
-                                FieldValue::Integer(left_int),
-                                FieldValue::Float(right_float),
-                                crate::ferris::sql::ast::BinaryOperator::GreaterThanOrEqual,
-                            ) => (left_int as f64) >= right_float,
-                            (
-                                FieldValue::Float(left_float),
-                                FieldValue::Integer(right_int),
-                                crate::ferris::sql::ast::BinaryOperator::GreaterThanOrEqual,
-                            ) => left_float >= (right_int as f64),
-                            (
-                                FieldValue::Float(left_float),
-                                FieldValue::Float(right_float),
-                                crate::ferris::sql::ast::BinaryOperator::GreaterThanOrEqual,
-                            ) => left_float >= right_float,
-                            _ => false,
-                        }
-                    }
-                    _ => {
-                        // For non-binary ops, use regular evaluation
-                        ExpressionEvaluator::evaluate_expression(having_expr, &temp_record)
-                            .unwrap_or(false)
-                    }
-                };
-
-                if !having_result {
-                    return Err(SqlError::ExecutionError {
-                        message: "No records after filtering".to_string(),
-                        query: None,
-                    });
-                }
-            }
-
-            // Use timestamp from the last record in the window
-            let timestamp = filtered_records.last().map(|r| r.timestamp).unwrap_or(0);
-
-            Ok(StreamRecord {
-                fields: result_fields,
-                timestamp,
-                offset: 0,
-                partition: 0,
-                headers: HashMap::new(),
-            })
-        } else {
-            Err(SqlError::ExecutionError {
-                message: "Invalid query type for windowed aggregation".to_string(),
-                query: None,
-            })
-        }
-    }
-
     /// Update window state after processing (high-performance version for WindowState)
     fn update_window_state_direct(
         window_state: &mut WindowState,
@@ -459,9 +312,13 @@ impl WindowProcessor {
         event_time: i64,
     ) {
         match window_spec {
-            WindowSpec::Tumbling { size, .. } => {
+            WindowSpec::Tumbling {
+                size, time_zone, ..
+            } => {
                 let window_size_ms = size.as_millis() as i64;
-                window_state.last_emit = (event_time / window_size_ms) * window_size_ms;
+                window_state.last_emit =
+                    Self::tumbling_window_bounds(event_time, window_size_ms, time_zone.as_deref())
+                        .0;
             }
             WindowSpec::Sliding { .. } => {
                 window_state.last_emit = event_time;
@@ -479,9 +336,13 @@ impl WindowProcessor {
         event_time: i64,
     ) {
         match window_spec {
-            WindowSpec::Tumbling { size, .. } => {
+            WindowSpec::Tumbling {
+                size, time_zone, ..
+            } => {
                 let window_size_ms = size.as_millis() as i64;
-                window_context.last_emit = (event_time / window_size_ms) * window_size_ms;
+                window_context.last_emit =
+                    Self::tumbling_window_bounds(event_time, window_size_ms, time_zone.as_deref())
+                        .0;
             }
             WindowSpec::Sliding { .. } => {
                 window_context.last_emit = event_time;
@@ -497,82 +358,31 @@ impl WindowProcessor {
         window_state: &mut WindowState,
         window_spec: &WindowSpec,
         old_last_emit: i64,
-    ) {
+    ) -> Result<(), SqlError> {
+        let time_column = window_spec.time_column();
         match window_spec {
-            WindowSpec::Tumbling { size, .. } => {
-                let window_size_ms = size.as_millis() as i64;
-                let completed_window_start = if old_last_emit == 0 { 0 } else { old_last_emit };
-                let completed_window_end = completed_window_start + window_size_ms;
-                let time_column = window_spec.time_column();
+            WindowSpec::Tumbling { .. } => {
+                let completed_window = Self::completed_window_bounds(window_spec, old_last_emit);
 
                 // Only remove records that were part of the completed window
-                window_state.buffer.retain(|r| {
-                    let record_time = if let Some(column_name) = time_column {
-                        if let Some(field_value) = r.fields.get(column_name) {
-                            match field_value {
-                                FieldValue::Integer(ts) => *ts,
-                                FieldValue::Timestamp(ts) => ts.and_utc().timestamp_millis(),
-                                FieldValue::String(s) => s.parse::<i64>().unwrap_or(r.timestamp),
-                                _ => r.timestamp,
-                            }
-                        } else {
-                            r.timestamp
-                        }
-                    } else {
-                        r.timestamp
-                    };
-                    // Keep records that are NOT in the completed window
-                    !(record_time >= completed_window_start && record_time < completed_window_end)
-                });
+                window_state.retain_records(|r| !Self::in_window(r, window_spec, completed_window))
             }
             WindowSpec::Sliding { size, .. } => {
                 let window_size_ms = size.as_millis() as i64;
                 let cutoff_time = window_state.last_emit.saturating_sub(window_size_ms);
-                let time_column = window_spec.time_column();
 
                 // Remove records older than the sliding window
-                window_state.buffer.retain(|r| {
-                    let record_time = if let Some(column_name) = time_column {
-                        if let Some(field_value) = r.fields.get(column_name) {
-                            match field_value {
-                                FieldValue::Integer(ts) => *ts,
-                                FieldValue::Timestamp(ts) => ts.and_utc().timestamp_millis(),
-                                FieldValue::String(s) => s.parse::<i64>().unwrap_or(r.timestamp),
-                                _ => r.timestamp,
-                            }
-                        } else {
-                            r.timestamp
-                        }
-                    } else {
-                        r.timestamp
-                    };
-                    record_time >= cutoff_time
-                });
+                window_state
+                    .retain_records(|r| Self::extract_event_time(r, time_column) >= cutoff_time)
             }
             WindowSpec::Session { gap, .. } => {
                 // For session windows, we would need more complex logic
                 // For now, keep recent records within the gap time
                 let gap_ms = gap.as_millis() as i64;
                 let cutoff_time = window_state.last_emit.saturating_sub(gap_ms);
-                let time_column = window_spec.time_column();
 
-                window_state.buffer.retain(|r| {
-                    let record_time = if let Some(column_name) = time_column {
-                        if let Some(field_value) = r.fields.get(column_name) {
-                            match field_value {
-                                FieldValue::Integer(ts) => *ts,
-                                FieldValue::Timestamp(ts) => ts.and_utc().timestamp_millis(),
-                                FieldValue::String(s) => s.parse::<i64>().unwrap_or(r.timestamp),
-                                _ => r.timestamp,
-                            }
-                        } else {
-                            r.timestamp
-                        }
-                    } else {
-                        r.timestamp
-                    };
-                    record_time >= cutoff_time
-                });
+                window_state
+                    .retain_records(|r| Self::extract_event_time(r, time_column) >= cutoff_time)
             }
         }
     }
@@ -584,10 +394,16 @@ impl WindowProcessor {
         old_last_emit: i64,
     ) {
         match window_spec {
-            WindowSpec::Tumbling { size, .. } => {
+            WindowSpec::Tumbling {
+                size, time_zone, ..
+            } => {
                 let window_size_ms = size.as_millis() as i64;
                 let completed_window_start = if old_last_emit == 0 { 0 } else { old_last_emit };
-                let completed_window_end = completed_window_start + window_size_ms;
+                let (_, completed_window_end) = Self::tumbling_window_bounds(
+                    completed_window_start,
+                    window_size_ms,
+                    time_zone.as_deref(),
+                );
                 let time_column = window_spec.time_column();
 
                 // Only remove records that were part of the completed window
@@ -597,6 +413,7 @@ impl WindowProcessor {
                             match field_value {
                                 FieldValue::Integer(ts) => *ts,
                                 FieldValue::Timestamp(ts) => ts.and_utc().timestamp_millis(),
+                                FieldValue::TimestampTz(ts) => ts.timestamp_millis(),
                                 FieldValue::String(s) => s.parse::<i64>().unwrap_or(r.timestamp),
                                 _ => r.timestamp,
                             }
@@ -622,6 +439,7 @@ impl WindowProcessor {
                             match field_value {
                                 FieldValue::Integer(ts) => *ts,
                                 FieldValue::Timestamp(ts) => ts.and_utc().timestamp_millis(),
+                                FieldValue::TimestampTz(ts) => ts.timestamp_millis(),
                                 FieldValue::String(s) => s.parse::<i64>().unwrap_or(r.timestamp),
                                 _ => r.timestamp,
                             }
@@ -647,6 +465,7 @@ impl WindowProcessor {
                             match field_value {
                                 FieldValue::Integer(ts) => *ts,
                                 FieldValue::Timestamp(ts) => ts.and_utc().timestamp_millis(),
+                                FieldValue::TimestampTz(ts) => ts.timestamp_millis(),
                                 FieldValue::String(s) => s.parse::<i64>().unwrap_or(r.timestamp),
                                 _ => r.timestamp,
                             }
@@ -675,183 +494,322 @@ impl WindowProcessor {
             should_emit: false,
         }
     }
+}
 
-    /// Evaluate aggregate expression across multiple records in a window
-    fn evaluate_aggregate_expression(
-        expr: &crate::ferris::sql::ast::Expr,
-        records: &[&StreamRecord],
-    ) -> Result<FieldValue, SqlError> {
-        use crate::ferris::sql::ast::Expr;
-
-        match expr {
-            Expr::Function { name, args } => {
-                match name.to_uppercase().as_str() {
-                    "COUNT" => {
-                        if args.is_empty()
-                            || (args.len() == 1
-                                && matches!(
-                                    args[0],
-                                    Expr::Literal(crate::ferris::sql::ast::LiteralValue::Integer(
-                                        1
-                                    ))
-                                ))
-                        {
-                            // COUNT(*) or COUNT(1) - count all records
-                            Ok(FieldValue::Integer(records.len() as i64))
-                        } else {
-                            // COUNT(column) - count non-null values
-                            let mut count = 0i64;
-                            for record in records {
-                                if let Ok(value) =
-                                    ExpressionEvaluator::evaluate_expression_value(&args[0], record)
-                                {
-                                    if !matches!(value, FieldValue::Null) {
-                                        count += 1;
-                                    }
-                                }
-                            }
-                            Ok(FieldValue::Integer(count))
-                        }
-                    }
-                    "SUM" => {
-                        let mut sum = 0.0;
-                        for record in records {
-                            if let Ok(value) =
-                                ExpressionEvaluator::evaluate_expression_value(&args[0], record)
-                            {
-                                match value {
-                                    FieldValue::Integer(i) => sum += i as f64,
-                                    FieldValue::Float(f) => sum += f,
-                                    _ => {} // Skip non-numeric values
-                                }
-                            }
-                        }
-                        Ok(FieldValue::Float(sum))
+/// Single-pass aggregation of the records of a window being emitted, so buffered and
+/// spilled records can be streamed through without collecting them first
+struct WindowAggregation<'q> {
+    fields: &'q [SelectField],
+    where_clause: Option<&'q Expr>,
+    having: Option<&'q Expr>,
+    /// One aggregate per `SelectField::Expression`, in field order
+    aggregates: Vec<WindowAggregate<'q>>,
+    /// Aggregate for the left side of a `HAVING <aggregate> <op> <value>` clause
+    having_aggregate: Option<WindowAggregate<'q>>,
+    first: Option<StreamRecord>,
+    count: i64,
+    timestamp: i64,
+}
+
+impl<'q> WindowAggregation<'q> {
+    fn new(query: &'q StreamingQuery) -> Result<Self, SqlError> {
+        let StreamingQuery::Select {
+            fields,
+            where_clause,
+            having,
+            ..
+        } = query
+        else {
+            return Err(SqlError::ExecutionError {
+                message: "Invalid query type for windowed aggregation".to_string(),
+                query: None,
+            });
+        };
+
+        let aggregates = fields
+            .iter()
+            .filter_map(|field| match field {
+                SelectField::Expression { expr, .. } => Some(WindowAggregate::new(expr)),
+                _ => None,
+            })
+            .collect();
+        let having_aggregate = match having {
+            Some(Expr::BinaryOp { left, .. }) => Some(WindowAggregate::new(left)),
+            _ => None,
+        };
+
+        Ok(Self {
+            fields,
+            where_clause: where_clause.as_ref(),
+            having: having.as_ref(),
+            aggregates,
+            having_aggregate,
+            first: None,
+            count: 0,
+            timestamp: 0,
+        })
+    }
+
+    /// Add a record of the window, skipping it if it fails the WHERE clause
+    fn push(&mut self, record: &StreamRecord) -> Result<(), SqlError> {
+        if let Some(where_expr) = self.where_clause {
+            if !ExpressionEvaluator::evaluate_expression(where_expr, record).unwrap_or(false) {
+                return Ok(());
+            }
+        }
+
+        if self.first.is_none() {
+            self.first = Some(record.clone());
+        }
+        self.count += 1;
+        // Use timestamp from the last record in the window
+        self.timestamp = record.timestamp;
+
+        for aggregate in self
+            .aggregates
+            .iter_mut()
+            .chain(self.having_aggregate.as_mut())
+        {
+            aggregate.push(record)?;
+        }
+        Ok(())
+    }
+
+    /// The aggregated result, or None if no records passed the WHERE clause or the
+    /// HAVING clause was not satisfied
+    fn finish(self) -> Result<Option<StreamRecord>, SqlError> {
+        let Some(first_record) = self.first else {
+            return Ok(None);
+        };
+
+        let mut result_fields = HashMap::new();
+        let mut aggregates = self.aggregates.into_iter();
+        for field in self.fields {
+            match field {
+                SelectField::Wildcard => {
+                    // For windowed aggregations, add basic aggregate info instead of all fields
+                    result_fields
+                        .insert("window_size".to_string(), FieldValue::Integer(self.count));
+                }
+                SelectField::Expression { alias, .. } => {
+                    let field_name = alias
+                        .clone()
+                        .unwrap_or_else(|| format!("field_{}", result_fields.len()));
+                    if let Some(aggregate) = aggregates.next() {
+                        let value = aggregate.finish(&first_record, self.count)?;
+                        result_fields.insert(field_name, value);
                     }
-                    "AVG" => {
-                        let mut sum = 0.0;
-                        let mut count = 0i64;
-                        for record in records {
-                            if let Ok(value) =
-                                ExpressionEvaluator::evaluate_expression_value(&args[0], record)
-                            {
-                                match value {
-                                    FieldValue::Integer(i) => {
-                                        sum += i as f64;
-                                        count += 1;
-                                    }
-                                    FieldValue::Float(f) => {
-                                        sum += f;
-                                        count += 1;
-                                    }
-                                    _ => {} // Skip non-numeric values
-                                }
-                            }
-                        }
-                        if count > 0 {
-                            Ok(FieldValue::Float(sum / count as f64))
-                        } else {
-                            Ok(FieldValue::Null)
-                        }
+                }
+                SelectField::Column(column_name) => {
+                    if let Some(value) = first_record.fields.get(column_name) {
+                        result_fields.insert(column_name.clone(), value.clone());
                     }
-                    "MIN" => {
-                        let mut min_val: Option<FieldValue> = None;
-                        for record in records {
-                            if let Ok(value) =
-                                ExpressionEvaluator::evaluate_expression_value(&args[0], record)
-                            {
-                                match &min_val {
-                                    None => min_val = Some(value),
-                                    Some(current_min) => {
-                                        // Compare values - simplified comparison for numeric types
-                                        match (&value, current_min) {
-                                            (FieldValue::Integer(i1), FieldValue::Integer(i2)) => {
-                                                if i1 < i2 {
-                                                    min_val = Some(value);
-                                                }
-                                            }
-                                            (FieldValue::Float(f1), FieldValue::Float(f2)) => {
-                                                if f1 < f2 {
-                                                    min_val = Some(value);
-                                                }
-                                            }
-                                            (FieldValue::Integer(i), FieldValue::Float(f)) => {
-                                                if (*i as f64) < *f {
-                                                    min_val = Some(value);
-                                                }
-                                            }
-                                            (FieldValue::Float(f), FieldValue::Integer(i)) => {
-                                                if *f < (*i as f64) {
-                                                    min_val = Some(value);
-                                                }
-                                            }
-                                            _ => {} // Skip non-comparable types
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        Ok(min_val.unwrap_or(FieldValue::Null))
+                }
+                SelectField::AliasedColumn { column, alias } => {
+                    if let Some(value) = first_record.fields.get(column) {
+                        result_fields.insert(alias.clone(), value.clone());
                     }
-                    "MAX" => {
-                        let mut max_val: Option<FieldValue> = None;
-                        for record in records {
-                            if let Ok(value) =
-                                ExpressionEvaluator::evaluate_expression_value(&args[0], record)
-                            {
-                                match &max_val {
-                                    None => max_val = Some(value),
-                                    Some(current_max) => {
-                                        // Compare values - simplified comparison for numeric types
-                                        match (&value, current_max) {
-                                            (FieldValue::Integer(i1), FieldValue::Integer(i2)) => {
-                                                if i1 > i2 {
-                                                    max_val = Some(value);
-                                                }
-                                            }
-                                            (FieldValue::Float(f1), FieldValue::Float(f2)) => {
-                                                if f1 > f2 {
-                                                    max_val = Some(value);
-                                                }
-                                            }
-                                            (FieldValue::Integer(i), FieldValue::Float(f)) => {
-                                                if (*i as f64) > *f {
-                                                    max_val = Some(value);
-                                                }
-                                            }
-                                            (FieldValue::Float(f), FieldValue::Integer(i)) => {
-                                                if *f > (*i as f64) {
-                                                    max_val = Some(value);
-                                                }
-                                            }
-                                            _ => {} // Skip non-comparable types
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        Ok(max_val.unwrap_or(FieldValue::Null))
+                }
+            }
+        }
+
+        // Always include window metadata
+        result_fields.insert(
+            "_window_record_count".to_string(),
+            FieldValue::Integer(self.count),
+        );
+
+        if let Some(having_expr) = self.having {
+            // Create a temporary record for HAVING evaluation
+            let temp_record = StreamRecord {
+                fields: result_fields.clone().into(),
+                timestamp: 0,
+                offset: 0,
+                partition: 0,
+                headers: HashMap::new(),
+            };
+
+            let having_result = match (having_expr, self.having_aggregate) {
+                // Handle HAVING COUNT(*) >= 2 style expressions
+                (Expr::BinaryOp { right, op, .. }, Some(left)) => {
+                    let left_value = left.finish(&first_record, self.count)?;
+                    let right_value =
+                        ExpressionEvaluator::evaluate_expression_value(right, &temp_record)
+                            .unwrap_or(FieldValue::Null);
+                    *op == BinaryOperator::GreaterThanOrEqual
+                        && compare_numeric(&left_value, &right_value)
+                            .is_some_and(|ordering| ordering != Ordering::Less)
+                }
+                // For non-binary ops, use regular evaluation
+                _ => ExpressionEvaluator::evaluate_expression(having_expr, &temp_record)
+                    .unwrap_or(false),
+            };
+            if !having_result {
+                return Ok(None);
+            }
+        }
+
+        Ok(Some(StreamRecord {
+            fields: result_fields.into(),
+            timestamp: self.timestamp,
+            offset: 0,
+            partition: 0,
+            headers: HashMap::new(),
+        }))
+    }
+}
+
+/// Running state of one aggregate expression over a window
+enum WindowAggregate<'q> {
+    /// COUNT(*) or COUNT(1), taken from the window's record count
+    CountAll,
+    /// COUNT(column) - count non-null values
+    Count(&'q Expr, i64),
+    Sum(&'q Expr, f64),
+    Avg(&'q Expr, f64, i64),
+    Min(&'q Expr, Option<FieldValue>),
+    Max(&'q Expr, Option<FieldValue>),
+    Median(&'q Expr, Vec<f64>),
+    ArrayAgg(&'q Expr, Vec<FieldValue>),
+    ObjectAgg(&'q [Expr], HashMap<String, FieldValue>),
+    /// Non-aggregate expressions are evaluated on the window's first record
+    FirstRecord(&'q Expr),
+}
+
+impl<'q> WindowAggregate<'q> {
+    fn new(expr: &'q Expr) -> Self {
+        let Expr::Function { name, args, .. } = expr else {
+            return Self::FirstRecord(expr);
+        };
+        match (name.to_uppercase().as_str(), args.as_slice()) {
+            ("COUNT", []) | ("COUNT", [Expr::Literal(LiteralValue::Integer(1), _)]) => {
+                Self::CountAll
+            }
+            ("COUNT", [arg, ..]) => Self::Count(arg, 0),
+            ("SUM", [arg, ..]) => Self::Sum(arg, 0.0),
+            ("AVG", [arg, ..]) => Self::Avg(arg, 0.0, 0),
+            ("MIN", [arg, ..]) => Self::Min(arg, None),
+            ("MAX", [arg, ..]) => Self::Max(arg, None),
+            ("MEDIAN", [arg, ..]) => Self::Median(arg, Vec::new()),
+            ("JSON_ARRAYAGG", [arg, ..]) => Self::ArrayAgg(arg, Vec::new()),
+            ("JSON_OBJECTAGG", _) => Self::ObjectAgg(args, HashMap::new()),
+            _ => Self::FirstRecord(expr),
+        }
+    }
+
+    fn push(&mut self, record: &StreamRecord) -> Result<(), SqlError> {
+        let evaluate = |expr| ExpressionEvaluator::evaluate_expression_value(expr, record);
+        match self {
+            Self::CountAll | Self::FirstRecord(_) => {}
+            Self::Count(arg, count) => {
+                if matches!(evaluate(arg), Ok(value) if !matches!(value, FieldValue::Null)) {
+                    *count += 1;
+                }
+            }
+            Self::Sum(arg, sum) => {
+                if let Some(value) = numeric(evaluate(arg)) {
+                    *sum += value;
+                }
+            }
+            Self::Avg(arg, sum, count) => {
+                if let Some(value) = numeric(evaluate(arg)) {
+                    *sum += value;
+                    *count += 1;
+                }
+            }
+            Self::Min(arg, min_val) => {
+                if let Ok(value) = evaluate(arg) {
+                    let replace = min_val.as_ref().map_or(true, |current| {
+                        compare_numeric(&value, current) == Some(Ordering::Less)
+                    });
+                    if replace {
+                        *min_val = Some(value);
                     }
-                    _ => {
-                        // For non-aggregate functions, just evaluate on first record
-                        if let Some(first_record) = records.first() {
-                            ExpressionEvaluator::evaluate_expression_value(expr, first_record)
-                        } else {
-                            Ok(FieldValue::Null)
-                        }
+                }
+            }
+            Self::Max(arg, max_val) => {
+                if let Ok(value) = evaluate(arg) {
+                    let replace = max_val.as_ref().map_or(true, |current| {
+                        compare_numeric(&value, current) == Some(Ordering::Greater)
+                    });
+                    if replace {
+                        *max_val = Some(value);
                     }
                 }
             }
-            _ => {
-                // For non-function expressions, evaluate on first record
-                if let Some(first_record) = records.first() {
-                    ExpressionEvaluator::evaluate_expression_value(expr, first_record)
-                } else {
-                    Ok(FieldValue::Null)
+            Self::Median(arg, values) => {
+                // Skip NULL and non-numeric values
+                if let Some(value) = numeric(evaluate(arg)) {
+                    values.push(value);
+                }
+            }
+            Self::ArrayAgg(arg, values) => values.push(evaluate(arg)?),
+            Self::ObjectAgg(args, members) => {
+                let [key_expr, value_expr] = *args else {
+                    return Err(SqlError::ExecutionError {
+                        message: "JSON_OBJECTAGG requires exactly two arguments: JSON_OBJECTAGG(key, value)".to_string(),
+                        query: None,
+                    });
+                };
+                let key = evaluate(key_expr)?;
+                if !matches!(key, FieldValue::Null) {
+                    members.insert(key.to_display_string(), evaluate(value_expr)?);
                 }
             }
         }
+        Ok(())
+    }
+
+    fn finish(self, first_record: &StreamRecord, count: i64) -> Result<FieldValue, SqlError> {
+        Ok(match self {
+            Self::CountAll => FieldValue::Integer(count),
+            Self::Count(_, count) => FieldValue::Integer(count),
+            Self::Sum(_, sum) => FieldValue::Float(sum),
+            Self::Avg(_, _, 0) => FieldValue::Null,
+            Self::Avg(_, sum, count) => FieldValue::Float(sum / count as f64),
+            Self::Min(_, value) | Self::Max(_, value) => value.unwrap_or(FieldValue::Null),
+            Self::Median(_, values) => median(values).map_or(FieldValue::Null, FieldValue::Float),
+            Self::ArrayAgg(_, values) => FieldValue::Array(values),
+            Self::ObjectAgg(_, members) => FieldValue::Struct(members),
+            Self::FirstRecord(expr) => {
+                ExpressionEvaluator::evaluate_expression_value(expr, first_record)?
+            }
+        })
+    }
+}
+
+fn numeric(value: Result<FieldValue, SqlError>) -> Option<f64> {
+    match value {
+        Ok(FieldValue::Integer(i)) => Some(i as f64),
+        Ok(FieldValue::Float(f)) => Some(f),
+        _ => None,
+    }
+}
+
+/// Compare numeric values; other types are not comparable
+fn compare_numeric(left: &FieldValue, right: &FieldValue) -> Option<Ordering> {
+    match (left, right) {
+        (FieldValue::Integer(l), FieldValue::Integer(r)) => Some(l.cmp(r)),
+        (FieldValue::Float(l), FieldValue::Float(r)) => l.partial_cmp(r),
+        (FieldValue::Integer(l), FieldValue::Float(r)) => (*l as f64).partial_cmp(r),
+        (FieldValue::Float(l), FieldValue::Integer(r)) => l.partial_cmp(&(*r as f64)),
+        _ => None,
+    }
+}
+
+/// The middle value, or the mean of the two middle values, selected in place
+fn median(mut values: Vec<f64>) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    let len = values.len();
+    let (lower, upper, _) = values.select_nth_unstable_by(len / 2, f64::total_cmp);
+    let upper = *upper;
+    if len % 2 == 1 {
+        return Some(upper);
     }
+    let lower = lower.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    Some((lower + upper) / 2.0)
 }
 
 // Extension trait to get time column from WindowSpec
@@ -0,0 +1,431 @@
+//! Schema-indexed record fields.
+//!
+//! A [`StreamRecord`](super::StreamRecord) keeps its field values in a `Vec` indexed by
+//! position in a [`RecordSchema`] shared through an `Arc`, so records read from the same
+//! source share one copy of their field names instead of each owning a `HashMap` of them:
+//!
+//! - [`RecordSchema`] - the field names of a record layout, with a name-to-position index
+//! - [`RecordFields`] - the values of one record; keeps the `HashMap`-style API (`get`,
+//!   `insert`, `contains_key`, iteration) as a compatibility layer over the layout
+//! - [`RecordSchemaCache`] - lets readers and codecs fill records directly, reusing the
+//!   schema of the previous record whenever the next one has the same fields
+
+use super::types::FieldValue;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Index;
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// The field names of a record layout
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecordSchema {
+    names: Vec<String>,
+    index: HashMap<String, usize>,
+}
+
+impl RecordSchema {
+    /// Create a schema from field names; repeated names keep their first position
+    pub fn new<I, S>(names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut schema = Self::default();
+        for name in names {
+            schema.push(name.into());
+        }
+        schema
+    }
+
+    /// Position of a field
+    #[inline]
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.index.get(name).copied()
+    }
+
+    /// Field names in position order
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Number of fields
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Whether the schema has no fields
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Append a field unless it already exists, returning its position
+    fn push(&mut self, name: String) -> usize {
+        if let Some(index) = self.index_of(&name) {
+            return index;
+        }
+        let index = self.names.len();
+        self.index.insert(name.clone(), index);
+        self.names.push(name);
+        index
+    }
+}
+
+/// The field values of a record, laid out by a shared [`RecordSchema`]
+///
+/// Every position of the schema holds a value or is absent, so a record can lack fields
+/// of its schema the same way a `HashMap` lacks keys. Inserting a field the schema does
+/// not have extends a private copy of the schema; records sharing the original are not
+/// affected.
+#[derive(Clone, Default)]
+pub struct RecordFields {
+    schema: Arc<RecordSchema>,
+    values: Vec<Option<FieldValue>>,
+    len: usize,
+}
+
+impl RecordFields {
+    /// Create a record without fields
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a record with one value per field of `schema`, in position order
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of values differs from the number of fields.
+    pub fn from_values(schema: Arc<RecordSchema>, values: Vec<FieldValue>) -> Self {
+        assert_eq!(
+            schema.len(),
+            values.len(),
+            "one value per schema field expected"
+        );
+        Self {
+            len: values.len(),
+            values: values.into_iter().map(Some).collect(),
+            schema,
+        }
+    }
+
+    /// Create a record laid out by `schema` without values, to be filled by position
+    pub fn with_schema(schema: Arc<RecordSchema>) -> Self {
+        Self {
+            values: vec![None; schema.len()],
+            len: 0,
+            schema,
+        }
+    }
+
+    /// The schema the values are laid out by
+    pub fn schema(&self) -> &Arc<RecordSchema> {
+        &self.schema
+    }
+
+    /// The value of a field
+    #[inline]
+    pub fn get(&self, name: &str) -> Option<&FieldValue> {
+        let index = self.schema.index_of(name)?;
+        self.values[index].as_ref()
+    }
+
+    /// The value of a field, mutably
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut FieldValue> {
+        let index = self.schema.index_of(name)?;
+        self.values[index].as_mut()
+    }
+
+    /// The value at a schema position
+    #[inline]
+    pub fn get_index(&self, index: usize) -> Option<&FieldValue> {
+        self.values.get(index)?.as_ref()
+    }
+
+    /// Whether the record has a value for a field
+    pub fn contains_key(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Set a field, returning its previous value
+    ///
+    /// The name is only copied into the schema when the schema lacks the field.
+    pub fn insert<K>(&mut self, name: K, value: FieldValue) -> Option<FieldValue>
+    where
+        K: AsRef<str> + Into<String>,
+    {
+        let index = match self.schema.index_of(name.as_ref()) {
+            Some(index) => index,
+            None => {
+                let index = Arc::make_mut(&mut self.schema).push(name.into());
+                self.values.push(None);
+                index
+            }
+        };
+        let previous = self.values[index].replace(value);
+        if previous.is_none() {
+            self.len += 1;
+        }
+        previous
+    }
+
+    /// Set the value at a schema position, returning its previous value
+    ///
+    /// # Panics
+    ///
+    /// Panics if the schema has no field at `index`.
+    pub fn insert_index(&mut self, index: usize, value: FieldValue) -> Option<FieldValue> {
+        let previous = self.values[index].replace(value);
+        if previous.is_none() {
+            self.len += 1;
+        }
+        previous
+    }
+
+    /// Remove a field, returning its value
+    pub fn remove(&mut self, name: &str) -> Option<FieldValue> {
+        let index = self.schema.index_of(name)?;
+        let previous = self.values[index].take();
+        if previous.is_some() {
+            self.len -= 1;
+        }
+        previous
+    }
+
+    /// Keep only the fields for which `keep` returns true
+    pub fn retain(&mut self, mut keep: impl FnMut(&String, &mut FieldValue) -> bool) {
+        for (name, slot) in self.schema.names.iter().zip(self.values.iter_mut()) {
+            if let Some(value) = slot {
+                if !keep(name, value) {
+                    *slot = None;
+                    self.len -= 1;
+                }
+            }
+        }
+    }
+
+    /// Number of fields with a value
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the record has no field values
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Fields and values in schema order
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &FieldValue)> + '_ {
+        self.schema
+            .names
+            .iter()
+            .zip(&self.values)
+            .filter_map(|(name, value)| Some((name, value.as_ref()?)))
+    }
+
+    /// Fields and mutable values in schema order
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&String, &mut FieldValue)> + '_ {
+        self.schema
+            .names
+            .iter()
+            .zip(&mut self.values)
+            .filter_map(|(name, value)| Some((name, value.as_mut()?)))
+    }
+
+    /// Names of the fields with a value
+    pub fn keys(&self) -> impl Iterator<Item = &String> + '_ {
+        self.iter().map(|(name, _)| name)
+    }
+
+    /// Field values in schema order
+    pub fn values(&self) -> impl Iterator<Item = &FieldValue> + '_ {
+        self.values.iter().flatten()
+    }
+
+    /// Copy the fields into a `HashMap`
+    pub fn to_map(&self) -> HashMap<String, FieldValue> {
+        self.iter()
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect()
+    }
+}
+
+impl fmt::Debug for RecordFields {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl PartialEq for RecordFields {
+    fn eq(&self, other: &Self) -> bool {
+        if Arc::ptr_eq(&self.schema, &other.schema) {
+            return self.values == other.values;
+        }
+        self.len == other.len
+            && self
+                .iter()
+                .all(|(name, value)| other.get(name) == Some(value))
+    }
+}
+
+impl PartialEq<HashMap<String, FieldValue>> for RecordFields {
+    fn eq(&self, other: &HashMap<String, FieldValue>) -> bool {
+        self.len == other.len()
+            && self
+                .iter()
+                .all(|(name, value)| other.get(name) == Some(value))
+    }
+}
+
+impl Index<&str> for RecordFields {
+    type Output = FieldValue;
+
+    fn index(&self, name: &str) -> &FieldValue {
+        self.get(name)
+            .unwrap_or_else(|| panic!("record has no field '{}'", name))
+    }
+}
+
+impl From<HashMap<String, FieldValue>> for RecordFields {
+    fn from(fields: HashMap<String, FieldValue>) -> Self {
+        fields.into_iter().collect()
+    }
+}
+
+impl From<RecordFields> for HashMap<String, FieldValue> {
+    fn from(fields: RecordFields) -> Self {
+        fields.into_iter().collect()
+    }
+}
+
+impl<K: AsRef<str> + Into<String>> FromIterator<(K, FieldValue)> for RecordFields {
+    fn from_iter<I: IntoIterator<Item = (K, FieldValue)>>(iter: I) -> Self {
+        let mut fields = Self::new();
+        fields.extend(iter);
+        fields
+    }
+}
+
+impl<K: AsRef<str> + Into<String>> Extend<(K, FieldValue)> for RecordFields {
+    fn extend<I: IntoIterator<Item = (K, FieldValue)>>(&mut self, iter: I) {
+        for (name, value) in iter {
+            self.insert(name, value);
+        }
+    }
+}
+
+impl IntoIterator for RecordFields {
+    type Item = (String, FieldValue);
+    type IntoIter = std::iter::FilterMap<
+        std::iter::Zip<std::vec::IntoIter<String>, std::vec::IntoIter<Option<FieldValue>>>,
+        fn((String, Option<FieldValue>)) -> Option<(String, FieldValue)>,
+    >;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let names = Arc::try_unwrap(self.schema)
+            .map(|schema| schema.names)
+            .unwrap_or_else(|schema| schema.names.clone());
+        fn present((name, value): (String, Option<FieldValue>)) -> Option<(String, FieldValue)> {
+            Some((name, value?))
+        }
+        names
+            .into_iter()
+            .zip(self.values)
+            .filter_map(present as fn(_) -> _)
+    }
+}
+
+impl<'a> IntoIterator for &'a RecordFields {
+    type Item = (&'a String, &'a FieldValue);
+    type IntoIter = Box<dyn Iterator<Item = (&'a String, &'a FieldValue)> + 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.iter())
+    }
+}
+
+/// The schema shared by the records a reader or codec builds
+///
+/// Clones share the same schema, like the readers and codecs of one source do. Records
+/// are built with a [`RecordFieldsBuilder`]: fields the schema already has fill their
+/// slot without copying the name, in any order. A record bringing new fields extends the
+/// schema for the records after it; a record using less than half of the schema gets a
+/// schema of its own fields, which then becomes the shared one.
+#[derive(Debug, Clone, Default)]
+pub struct RecordSchemaCache {
+    current: Arc<Mutex<Arc<RecordSchema>>>,
+}
+
+impl RecordSchemaCache {
+    /// Create a cache without a schema
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The schema the next record is laid out by
+    pub fn current(&self) -> Arc<RecordSchema> {
+        self.current
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Start a record laid out by the current schema
+    pub fn builder(&self) -> RecordFieldsBuilder<'_> {
+        RecordFieldsBuilder {
+            cache: self,
+            fields: RecordFields::with_schema(self.current()),
+        }
+    }
+
+    /// Lay out a `HashMap` record by the current schema
+    pub fn fields_from_map(&self, fields: HashMap<String, FieldValue>) -> RecordFields {
+        let mut builder = self.builder();
+        for (name, value) in fields {
+            builder.push(name, value);
+        }
+        builder.finish()
+    }
+}
+
+/// Fills one record field by field, see [`RecordSchemaCache`]
+pub struct RecordFieldsBuilder<'a> {
+    cache: &'a RecordSchemaCache,
+    fields: RecordFields,
+}
+
+impl RecordFieldsBuilder<'_> {
+    /// Set a field; a field set twice keeps the later value
+    pub fn push<K>(&mut self, name: K, value: FieldValue)
+    where
+        K: AsRef<str> + Into<String>,
+    {
+        self.fields.insert(name, value);
+    }
+
+    /// Set every field of another record, moving its values
+    pub fn append(&mut self, fields: RecordFields) {
+        for (name, value) in fields.schema.names.iter().zip(fields.values) {
+            if let Some(value) = value {
+                self.fields.insert(name.as_str(), value);
+            }
+        }
+    }
+
+    /// Finish the record
+    pub fn finish(self) -> RecordFields {
+        let mut fields = self.fields;
+        let sparse = fields.len * 2 < fields.schema.len();
+        if sparse {
+            fields = fields.into_iter().collect();
+        }
+
+        let mut current = self
+            .cache
+            .current
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if sparse || !Arc::ptr_eq(&current, &fields.schema) {
+            *current = fields.schema.clone();
+        }
+        fields
+    }
+}
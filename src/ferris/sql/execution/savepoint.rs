@@ -0,0 +1,558 @@
+//! Savepoints for stopping, upgrading and resuming jobs.
+//!
+//! A [`Savepoint`] is the state of a stopped job: what its operators accumulated and the
+//! position each source was read up to. `STOP JOB totals WITH SAVEPOINT` waits for the
+//! batch in progress, stops the job and writes the savepoint to
+//! `<savepoint directory>/<job>/savepoint-<created>.svpt`; `SHOW SAVEPOINTS` lists them.
+//! `START JOB totals FROM SAVEPOINT '<path>' AS ...` starts a job, usually with a changed
+//! query, from that state.
+//!
+//! Checkpoints store state the way the running engine keys it, which changes with almost
+//! any edit of the query. A savepoint keys it by stable operator IDs instead, derived only
+//! from the parts of the query that give the state its meaning:
+//!
+//! | Operator | ID | Signature | Restores into |
+//! |----------|----|-----------|---------------|
+//! | GROUP BY | `group_by:<source>` | GROUP BY expressions and aggregates | the same GROUP BY, with a subset of the aggregates |
+//! | Window | `window:<source>` | window specification | the same window |
+//! | MERGE / UPSERT | `merge:<target>` | ON condition or key columns | the same condition or keys |
+//!
+//! Everything else - non-aggregate columns of the projection, WHERE, HAVING, ORDER BY,
+//! properties - can change freely, so adding a column to the projection keeps all state.
+//! A query that drops a stateful operator of the savepoint, changes its signature or needs
+//! an aggregate the savepoint holds no accumulator for is rejected with a diff of the
+//! signatures. Operators of the query the savepoint has no state for start empty.
+//!
+//! ```sql
+//! STOP JOB customer_totals WITH SAVEPOINT;
+//!
+//! START JOB customer_totals
+//! FROM SAVEPOINT '/var/lib/ferris/savepoints/customer_totals/savepoint-00000001700000000000.svpt'
+//! AS SELECT customer_id, region, COUNT(*) AS orders FROM orders GROUP BY customer_id;
+//! ```
+
+use super::aggregation::AggregateFunctions;
+use super::checkpoint::{OperatorSnapshot, StateFile};
+use super::engine::EXECUTE_QUERY_ID;
+use super::internal::GroupByState;
+use super::state::{StateCodec, StateOperator};
+use super::StreamExecutionEngine;
+use crate::ferris::datasource::SourceOffset;
+use crate::ferris::sql::ast::{InsertSource, SelectField, StreamingQuery};
+use crate::ferris::sql::error::SqlError;
+use crate::ferris::sql::formatter::format_query;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Job property with the directory savepoints of the job are written to
+pub const SAVEPOINT_DIR_PROPERTY: &str = "savepoint.dir";
+
+const FILE_PREFIX: &str = "savepoint-";
+const FILE_SUFFIX: &str = ".svpt";
+
+const SAVEPOINT_FILE: StateFile = StateFile {
+    kind: "savepoint",
+    magic: b"FSVP",
+};
+
+/// Source positions a running job shares with the job server, updated after every batch
+pub type SharedSourcePositions = Arc<Mutex<HashMap<String, SourceOffset>>>;
+
+/// Kind of state an operator keeps
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OperatorKind {
+    /// Accumulators of a GROUP BY, one per group
+    GroupBy,
+    /// Records buffered by a window
+    Window,
+    /// Current rows of a MERGE or UPSERT target
+    Merge,
+}
+
+impl OperatorKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::GroupBy => "group_by",
+            Self::Window => "window",
+            Self::Merge => "merge",
+        }
+    }
+}
+
+/// An operator of a query that keeps state across records
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatefulOperator {
+    /// Stable ID, unchanged by query edits that keep the state meaningful
+    pub id: String,
+    pub kind: OperatorKind,
+    /// Parts of the query that give the state its meaning, one line each
+    ///
+    /// For GROUP BY the first line holds the GROUP BY expressions and the others the
+    /// aggregates, sorted.
+    pub signature: Vec<String>,
+    /// Key of the state in an [`OperatorSnapshot`] of an engine running the query; the
+    /// operator ID in a savepoint
+    state_key: String,
+}
+
+impl StatefulOperator {
+    /// Namespace of the operator's state in state backends
+    fn backend_namespace(&self) -> Option<String> {
+        match self.kind {
+            // The GROUP BY state key is its namespace
+            OperatorKind::GroupBy => Some(self.state_key.clone()),
+            OperatorKind::Merge => Some(StateOperator::Merge.namespace(&self.state_key)),
+            OperatorKind::Window => None,
+        }
+    }
+
+    /// Move the operator's state out of an engine snapshot into savepoint state, where
+    /// every key is the operator ID
+    fn save(&self, engine: &mut OperatorSnapshot, saved: &mut OperatorSnapshot) {
+        let namespace = self.backend_namespace();
+        transfer(
+            self.kind,
+            (engine, &self.state_key, namespace.as_deref()),
+            (saved, &self.id, Some(&self.id)),
+        );
+    }
+
+    /// Move the state saved for this operator's ID into an engine snapshot
+    fn load(&self, saved: &mut OperatorSnapshot, engine: &mut OperatorSnapshot) {
+        let namespace = self.backend_namespace();
+        transfer(
+            self.kind,
+            (saved, &self.id, Some(&self.id)),
+            (engine, &self.state_key, namespace.as_deref()),
+        );
+    }
+
+    /// Whether this operator of a query can continue from the state of `saved`
+    fn accepts(&self, saved: &StatefulOperator) -> bool {
+        match self.kind {
+            // Accumulators of aggregates the query no longer computes are ignored
+            OperatorKind::GroupBy => {
+                self.signature.first() == saved.signature.first()
+                    && self.signature[1..]
+                        .iter()
+                        .all(|aggregate| saved.signature[1..].contains(aggregate))
+            }
+            OperatorKind::Window | OperatorKind::Merge => self.signature == saved.signature,
+        }
+    }
+}
+
+/// State of an operator in a snapshot: its key and its namespace in state backends
+type StateLocation<'a> = (&'a mut OperatorSnapshot, &'a str, Option<&'a str>);
+
+/// Move the state of an operator of `kind` from one location to another
+fn transfer(kind: OperatorKind, from: StateLocation<'_>, to: StateLocation<'_>) {
+    fn move_entry<V>(
+        from: &mut HashMap<String, V>,
+        from_key: &str,
+        to: &mut HashMap<String, V>,
+        to_key: &str,
+    ) {
+        if let Some(value) = from.remove(from_key) {
+            to.insert(to_key.to_string(), value);
+        }
+    }
+
+    let (from, from_key, from_namespace) = from;
+    let (to, to_key, to_namespace) = to;
+    let operator = match kind {
+        OperatorKind::GroupBy => {
+            move_entry(&mut from.group_by, from_key, &mut to.group_by, to_key);
+            StateOperator::GroupBy
+        }
+        OperatorKind::Merge => {
+            move_entry(
+                &mut from.merge_targets,
+                from_key,
+                &mut to.merge_targets,
+                to_key,
+            );
+            StateOperator::Merge
+        }
+        OperatorKind::Window => {
+            move_entry(&mut from.windows, from_key, &mut to.windows, to_key);
+            return;
+        }
+    };
+
+    if let (Some(from_namespace), Some(to_namespace)) = (from_namespace, to_namespace) {
+        if let Some(namespaces) = from.backends.get_mut(operator.as_str()) {
+            let to_namespaces = to
+                .backends
+                .entry(operator.as_str().to_string())
+                .or_default();
+            move_entry(namespaces, from_namespace, to_namespaces, to_namespace);
+        }
+    }
+}
+
+/// The stateful operators of a query, in the order they appear
+pub fn stateful_operators(query: &StreamingQuery) -> Vec<StatefulOperator> {
+    let mut operators = Vec::new();
+    collect_operators(query, &mut operators);
+    operators
+}
+
+fn collect_operators(query: &StreamingQuery, operators: &mut Vec<StatefulOperator>) {
+    match query {
+        StreamingQuery::Select {
+            fields,
+            from,
+            group_by,
+            having,
+            window,
+            ..
+        } => {
+            // Windowed queries aggregate the window buffer and keep no GROUP BY state
+            if let Some(window) = window {
+                operators.push(StatefulOperator {
+                    id: format!("{}:{}", OperatorKind::Window.as_str(), from),
+                    kind: OperatorKind::Window,
+                    signature: vec![format!("WINDOW {}", window)],
+                    state_key: EXECUTE_QUERY_ID.to_string(),
+                });
+            } else if let Some(group_by) = group_by {
+                let keys: Vec<String> = group_by.iter().map(|expr| expr.to_string()).collect();
+                let mut aggregates: Vec<String> = fields
+                    .iter()
+                    .filter(|field| {
+                        matches!(field, SelectField::Expression { expr, .. }
+                            if AggregateFunctions::is_aggregate_function(expr))
+                    })
+                    .map(|field| field.to_string())
+                    .collect();
+                aggregates.sort();
+
+                let mut signature = vec![format!("GROUP BY {}", keys.join(", "))];
+                signature.extend(aggregates);
+                operators.push(StatefulOperator {
+                    id: format!("{}:{}", OperatorKind::GroupBy.as_str(), from),
+                    kind: OperatorKind::GroupBy,
+                    signature,
+                    // The namespace the SELECT processor files the groups under
+                    state_key: GroupByState::new(
+                        from,
+                        group_by.clone(),
+                        fields.clone(),
+                        having.clone(),
+                    )
+                    .state_namespace,
+                });
+            }
+        }
+        StreamingQuery::CreateStream { as_select, .. }
+        | StreamingQuery::CreateTable { as_select, .. }
+        | StreamingQuery::CreateStreamInto { as_select, .. }
+        | StreamingQuery::CreateTableInto { as_select, .. } => {
+            collect_operators(as_select, operators)
+        }
+        StreamingQuery::StartJob { query, .. } | StreamingQuery::DeployJob { query, .. } => {
+            collect_operators(query, operators)
+        }
+        StreamingQuery::InsertInto {
+            source: InsertSource::Select { query },
+            ..
+        } => collect_operators(query, operators),
+        StreamingQuery::Upsert {
+            table_name,
+            key_columns,
+            source,
+            ..
+        } => {
+            if let InsertSource::Select { query } = source {
+                collect_operators(query, operators);
+            }
+            operators.push(StatefulOperator {
+                id: format!("{}:{}", OperatorKind::Merge.as_str(), table_name),
+                kind: OperatorKind::Merge,
+                signature: vec![format!("KEY ({})", key_columns.join(", "))],
+                state_key: table_name.clone(),
+            });
+        }
+        StreamingQuery::Merge { target, on, .. } => operators.push(StatefulOperator {
+            id: format!("{}:{}", OperatorKind::Merge.as_str(), target),
+            kind: OperatorKind::Merge,
+            signature: vec![format!("ON {}", on)],
+            state_key: target.clone(),
+        }),
+        _ => {}
+    }
+}
+
+/// State of a stopped job, keyed by stable operator IDs
+#[derive(Debug, Clone)]
+pub struct Savepoint {
+    pub job: String,
+    pub version: String,
+    /// Creation time in milliseconds since the epoch
+    pub created_at: i64,
+    /// Canonical text of the query the job ran, see [`format_query`]
+    pub query: String,
+    pub operators: Vec<StatefulOperator>,
+    /// State of the operators, every key being an operator ID
+    pub state: OperatorSnapshot,
+    /// Position each source was read up to, by source name
+    pub source_offsets: HashMap<String, SourceOffset>,
+}
+
+/// A savepoint on disk, as listed by [`list_savepoints`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavepointSummary {
+    pub path: PathBuf,
+    pub job: String,
+    pub version: String,
+    pub created_at: i64,
+    /// IDs of the operators with state
+    pub operators: Vec<String>,
+}
+
+impl StateCodec for OperatorKind {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(match self {
+            Self::GroupBy => 0,
+            Self::Window => 1,
+            Self::Merge => 2,
+        });
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, SqlError> {
+        match u8::decode(input)? {
+            0 => Ok(Self::GroupBy),
+            1 => Ok(Self::Window),
+            2 => Ok(Self::Merge),
+            tag => Err(SqlError::ExecutionError {
+                message: format!("Unknown operator kind tag {} in savepoint", tag),
+                query: None,
+            }),
+        }
+    }
+}
+
+impl StateCodec for StatefulOperator {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.id.encode(out);
+        self.kind.encode(out);
+        self.signature.encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, SqlError> {
+        let id = String::decode(input)?;
+        Ok(Self {
+            kind: OperatorKind::decode(input)?,
+            signature: Vec::decode(input)?,
+            state_key: id.clone(),
+            id,
+        })
+    }
+}
+
+impl StateCodec for Savepoint {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.job.encode(out);
+        self.version.encode(out);
+        self.created_at.encode(out);
+        self.query.encode(out);
+        self.operators.encode(out);
+        self.state.encode(out);
+        self.source_offsets.encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, SqlError> {
+        Ok(Self {
+            job: String::decode(input)?,
+            version: String::decode(input)?,
+            created_at: i64::decode(input)?,
+            query: String::decode(input)?,
+            operators: Vec::decode(input)?,
+            state: OperatorSnapshot::decode(input)?,
+            source_offsets: HashMap::decode(input)?,
+        })
+    }
+}
+
+impl Savepoint {
+    /// Savepoint of a job whose engine runs `query` and whose sources were read up to
+    /// `source_positions`
+    ///
+    /// Take it once the job has stopped, so the state and the positions agree.
+    pub fn take(
+        job: &str,
+        version: &str,
+        query: &StreamingQuery,
+        engine: &StreamExecutionEngine,
+        source_positions: &HashMap<String, SourceOffset>,
+    ) -> Result<Self, SqlError> {
+        let mut snapshot = engine.snapshot_operator_state()?;
+        let mut state = OperatorSnapshot::default();
+        let operators: Vec<StatefulOperator> = stateful_operators(query)
+            .into_iter()
+            .map(|operator| {
+                operator.save(&mut snapshot, &mut state);
+                StatefulOperator {
+                    state_key: operator.id.clone(),
+                    ..operator
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            job: job.to_string(),
+            version: version.to_string(),
+            created_at: chrono::Utc::now().timestamp_millis(),
+            query: format_query(query),
+            operators,
+            state,
+            source_offsets: source_positions.clone(),
+        })
+    }
+
+    /// Write the savepoint to `<directory>/<job>/savepoint-<created>.svpt`; returns its path
+    pub fn write(&self, directory: &Path) -> Result<PathBuf, SqlError> {
+        let path = directory.join(&self.job).join(format!(
+            "{}{:020}{}",
+            FILE_PREFIX, self.created_at, FILE_SUFFIX
+        ));
+        SAVEPOINT_FILE.write(&path, self)?;
+        Ok(path)
+    }
+
+    /// Read a savepoint, verifying its checksum
+    pub fn read(path: &Path) -> Result<Self, SqlError> {
+        SAVEPOINT_FILE.read(path)
+    }
+
+    /// Check that `query` can continue from this savepoint; returns each of its operators
+    /// paired with the saved operator whose state it takes over
+    ///
+    /// The error lists every operator that does not fit: `-` lines are signatures of the
+    /// savepoint, `+` lines those of the query.
+    pub fn check_compatible<'a>(
+        &'a self,
+        query: &StreamingQuery,
+    ) -> Result<Vec<(StatefulOperator, &'a StatefulOperator)>, SqlError> {
+        let operators = stateful_operators(query);
+        let mut pairs = Vec::new();
+        let mut diff = Vec::new();
+        for saved in &self.operators {
+            match operators.iter().find(|operator| operator.id == saved.id) {
+                Some(operator) if operator.accepts(saved) => pairs.push((operator.clone(), saved)),
+                Some(operator) => {
+                    diff.push(format!("  {}: state does not fit the query", saved.id));
+                    diff.extend(
+                        saved
+                            .signature
+                            .iter()
+                            .filter(|line| !operator.signature.contains(line))
+                            .map(|line| format!("    - {}", line)),
+                    );
+                    diff.extend(
+                        operator
+                            .signature
+                            .iter()
+                            .filter(|line| !saved.signature.contains(line))
+                            .map(|line| format!("    + {}", line)),
+                    );
+                }
+                None => {
+                    diff.push(format!(
+                        "  {}: not in the query, its state would be lost",
+                        saved.id
+                    ));
+                    diff.extend(saved.signature.iter().map(|line| format!("    - {}", line)));
+                }
+            }
+        }
+
+        if diff.is_empty() {
+            return Ok(pairs);
+        }
+        Err(SqlError::ExecutionError {
+            message: format!(
+                "[SAVEPOINT-002] Query of job '{}' cannot start from its savepoint of {}:\n{}",
+                self.job,
+                chrono::DateTime::from_timestamp_millis(self.created_at)
+                    .map(|created| created.to_rfc3339())
+                    .unwrap_or_else(|| self.created_at.to_string()),
+                diff.join("\n")
+            ),
+            query: Some(format_query(query)),
+        })
+    }
+
+    /// Restore the state of the operators of `query` into a new engine, after checking
+    /// that the query fits the savepoint
+    ///
+    /// The sources seek to [`source_offsets`](Self::source_offsets) once they are open.
+    pub fn restore(
+        &self,
+        query: &StreamingQuery,
+        engine: &mut StreamExecutionEngine,
+    ) -> Result<(), SqlError> {
+        let mut saved = self.state.clone();
+        let mut snapshot = OperatorSnapshot::default();
+        for (operator, _) in self.check_compatible(query)? {
+            operator.load(&mut saved, &mut snapshot);
+        }
+        engine.restore_operator_state(&snapshot)
+    }
+}
+
+/// Savepoints under `directory`, of one job or of all, oldest first
+pub fn list_savepoints(
+    directory: &Path,
+    job: Option<&str>,
+) -> Result<Vec<SavepointSummary>, SqlError> {
+    let list_error = |path: &Path, e: std::io::Error| SqlError::ResourceError {
+        resource: "savepoint".to_string(),
+        message: format!("Failed to list savepoints in '{}': {}", path.display(), e),
+    };
+    let job_directories: Vec<PathBuf> = match job {
+        Some(job) => vec![directory.join(job)],
+        None => match fs::read_dir(directory) {
+            Ok(entries) => entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.is_dir())
+                .collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(list_error(directory, e)),
+        },
+    };
+
+    let mut savepoints = Vec::new();
+    for job_directory in job_directories {
+        let entries = match fs::read_dir(&job_directory) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(list_error(&job_directory, e)),
+        };
+        for path in entries.flatten().map(|entry| entry.path()) {
+            let is_savepoint = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(FILE_PREFIX) && name.ends_with(FILE_SUFFIX));
+            if !is_savepoint {
+                continue;
+            }
+            match Savepoint::read(&path) {
+                Ok(savepoint) => savepoints.push(SavepointSummary {
+                    path,
+                    job: savepoint.job,
+                    version: savepoint.version,
+                    created_at: savepoint.created_at,
+                    operators: savepoint.operators.into_iter().map(|op| op.id).collect(),
+                }),
+                Err(e) => log::warn!("Skipping savepoint '{}': {}", path.display(), e),
+            }
+        }
+    }
+    savepoints.sort_by(|a, b| (a.created_at, &a.job).cmp(&(b.created_at, &b.job)));
+    Ok(savepoints)
+}
@@ -0,0 +1,358 @@
+//! Enforcement of declared CREATE STREAM schemas at the source boundary.
+//!
+//! `CREATE STREAM orders (id BIGINT NOT NULL, amount DECIMAL(18,4)) AS ...` declares the
+//! shape of the records the stream accepts. [`StreamSchemaEnforcer`] checks every record read
+//! from a source against those column definitions before it reaches the query:
+//!
+//! - a missing column is NULL; a missing or NULL value in a `NOT NULL` column is a violation
+//! - a field that is not declared in the schema is a violation
+//! - a value of the wrong type is a violation
+//!
+//! The stream's `schema.enforcement` property picks what happens to a violating record:
+//!
+//! | Policy | Behaviour |
+//! |--------|-----------|
+//! | `coerce` | Cast values with [`FieldValue::cast_to`] and drop undeclared fields; records that still violate the schema are rejected |
+//! | `dlq` (default) | Reject the record to the dead letter queue with the violation attached |
+//! | `fail` | Stop the job with the violation |
+//!
+//! Lossless widenings (an integer into a FLOAT, REAL or DECIMAL column) are applied under
+//! every policy.
+
+use super::types::{FieldValue, StreamRecord};
+use crate::ferris::sql::ast::{ColumnDef, DataType, StreamingQuery};
+use crate::ferris::sql::error::SqlError;
+use std::collections::HashMap;
+
+/// Stream property that selects the [`SchemaEnforcementPolicy`]
+pub const SCHEMA_ENFORCEMENT_PROPERTY: &str = "schema.enforcement";
+
+/// What to do with a record that does not match the declared stream schema
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SchemaEnforcementPolicy {
+    /// Cast values to the declared types, dropping undeclared fields
+    Coerce,
+    /// Reject the record to the dead letter queue
+    #[default]
+    DeadLetter,
+    /// Fail the job
+    Fail,
+}
+
+impl SchemaEnforcementPolicy {
+    /// Parse a `schema.enforcement` property value (case-insensitive)
+    pub fn parse(value: &str) -> Result<Self, SqlError> {
+        match value.trim().to_lowercase().as_str() {
+            "coerce" => Ok(SchemaEnforcementPolicy::Coerce),
+            "dlq" | "reject" | "dead_letter" => Ok(SchemaEnforcementPolicy::DeadLetter),
+            "fail" => Ok(SchemaEnforcementPolicy::Fail),
+            _ => Err(SqlError::ConfigurationError {
+                message: format!(
+                    "Invalid {} '{}': expected 'coerce', 'dlq' or 'fail'",
+                    SCHEMA_ENFORCEMENT_PROPERTY, value
+                ),
+            }),
+        }
+    }
+}
+
+/// Why a record does not match the declared schema
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaViolation {
+    /// Column (or undeclared field) that caused the violation
+    pub column: String,
+    /// Human-readable reason
+    pub reason: String,
+}
+
+impl std::fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "column '{}': {}", self.column, self.reason)
+    }
+}
+
+/// Result of checking one record
+#[derive(Debug, Clone)]
+pub enum EnforcementOutcome {
+    /// The record conforms (possibly after coercion) and may enter the query
+    Accepted(StreamRecord),
+    /// The record was rejected; it is returned unchanged together with the reason
+    Rejected {
+        record: StreamRecord,
+        violation: SchemaViolation,
+    },
+}
+
+/// Checks source records against the column definitions of a CREATE STREAM statement
+#[derive(Debug, Clone)]
+pub struct StreamSchemaEnforcer {
+    stream_name: String,
+    columns: Vec<ColumnDef>,
+    policy: SchemaEnforcementPolicy,
+}
+
+impl StreamSchemaEnforcer {
+    pub fn new(
+        stream_name: impl Into<String>,
+        columns: Vec<ColumnDef>,
+        policy: SchemaEnforcementPolicy,
+    ) -> Self {
+        Self {
+            stream_name: stream_name.into(),
+            columns,
+            policy,
+        }
+    }
+
+    /// Build the enforcer for a CREATE STREAM statement with column definitions
+    ///
+    /// Returns `Ok(None)` for queries that declare no stream schema.
+    pub fn from_query(query: &StreamingQuery) -> Result<Option<Self>, SqlError> {
+        let (name, columns, properties, as_select) = match query {
+            StreamingQuery::CreateStream {
+                name,
+                columns: Some(columns),
+                properties,
+                as_select,
+                ..
+            } => (name, columns, properties, as_select),
+            StreamingQuery::CreateStreamInto {
+                name,
+                columns: Some(columns),
+                properties,
+                as_select,
+                ..
+            } => (name, columns, &properties.inline_properties, as_select),
+            _ => return Ok(None),
+        };
+
+        // A trailing WITH clause may have been attached to the SELECT
+        let select_properties = match as_select.as_ref() {
+            StreamingQuery::Select {
+                properties: Some(properties),
+                ..
+            } => Some(properties),
+            _ => None,
+        };
+        let policy = match properties
+            .get(SCHEMA_ENFORCEMENT_PROPERTY)
+            .or_else(|| select_properties.and_then(|p| p.get(SCHEMA_ENFORCEMENT_PROPERTY)))
+        {
+            Some(value) => SchemaEnforcementPolicy::parse(value)?,
+            None => SchemaEnforcementPolicy::default(),
+        };
+
+        Ok(Some(Self::new(name.clone(), columns.clone(), policy)))
+    }
+
+    pub fn stream_name(&self) -> &str {
+        &self.stream_name
+    }
+
+    pub fn policy(&self) -> SchemaEnforcementPolicy {
+        self.policy
+    }
+
+    /// Check a record against the declared schema
+    ///
+    /// Under the `fail` policy a violation is returned as [`SqlError::SchemaError`];
+    /// otherwise it is reported as [`EnforcementOutcome::Rejected`].
+    pub fn enforce(&self, record: StreamRecord) -> Result<EnforcementOutcome, SqlError> {
+        match self.conform(record) {
+            Ok(record) => Ok(EnforcementOutcome::Accepted(record)),
+            Err((_, violation)) if self.policy == SchemaEnforcementPolicy::Fail => {
+                Err(SqlError::SchemaError {
+                    message: format!(
+                        "Record violates the schema of stream '{}': {}",
+                        self.stream_name, violation
+                    ),
+                    column: Some(violation.column),
+                })
+            }
+            Err((record, violation)) => Ok(EnforcementOutcome::Rejected { record, violation }),
+        }
+    }
+
+    /// Validate the record without mutating it, then apply the collected conversions
+    fn conform(
+        &self,
+        mut record: StreamRecord,
+    ) -> Result<StreamRecord, (StreamRecord, SchemaViolation)> {
+        let coerce = self.policy == SchemaEnforcementPolicy::Coerce;
+
+        let mut undeclared: Vec<&String> = record
+            .fields
+            .keys()
+            .filter(|key| !self.columns.iter().any(|c| &c.name == *key))
+            .collect();
+        undeclared.sort();
+        if let (false, Some(field)) = (coerce, undeclared.first()) {
+            let violation = SchemaViolation {
+                column: field.to_string(),
+                reason: "field is not declared in the stream schema".to_string(),
+            };
+            return Err((record, violation));
+        }
+        let undeclared: Vec<String> = undeclared.into_iter().cloned().collect();
+
+        let mut replacements: Vec<(&str, FieldValue)> = Vec::new();
+        for column in &self.columns {
+            let value = record.fields.get(&column.name).unwrap_or(&FieldValue::Null);
+            let conformed = match conform_value(value, &column.data_type, coerce) {
+                Ok(conformed) => conformed,
+                Err(reason) => {
+                    let violation = SchemaViolation {
+                        column: column.name.clone(),
+                        reason,
+                    };
+                    return Err((record, violation));
+                }
+            };
+
+            let is_null = match &conformed {
+                Some(v) => matches!(v, FieldValue::Null),
+                None => matches!(value, FieldValue::Null),
+            };
+            if is_null && !column.nullable {
+                let reason = if record.fields.contains_key(&column.name) {
+                    "NULL value in NOT NULL column"
+                } else {
+                    "NOT NULL column is missing"
+                };
+                let violation = SchemaViolation {
+                    column: column.name.clone(),
+                    reason: reason.to_string(),
+                };
+                return Err((record, violation));
+            }
+
+            match conformed {
+                Some(v) => replacements.push((&column.name, v)),
+                None if !record.fields.contains_key(&column.name) => {
+                    replacements.push((&column.name, FieldValue::Null))
+                }
+                None => {}
+            }
+        }
+
+        for field in undeclared {
+            record.fields.remove(&field);
+        }
+        for (name, value) in replacements {
+            record.fields.insert(name.to_string(), value);
+        }
+        Ok(record)
+    }
+}
+
+/// Conform a value to a declared type
+///
+/// Returns `Ok(None)` when the value already matches, `Ok(Some(converted))` for a widening
+/// or coercion, and `Err(reason)` for a mismatch.
+fn conform_value(
+    value: &FieldValue,
+    data_type: &DataType,
+    coerce: bool,
+) -> Result<Option<FieldValue>, String> {
+    if matches!(value, FieldValue::Null) || value_matches(value, data_type) {
+        return Ok(None);
+    }
+
+    // Lossless widenings are accepted under every policy
+    match (value, data_type) {
+        (FieldValue::Integer(i), DataType::Float) => return Ok(Some(FieldValue::Float(*i as f64))),
+        (FieldValue::Integer(_), DataType::Decimal(_))
+        | (FieldValue::Integer(_), DataType::Real) => {
+            if let Some(widened) = value
+                .clone()
+                .cast_to(&data_type.to_string())
+                .ok()
+                .filter(|widened| !lossy_float(value, widened))
+            {
+                return Ok(Some(widened));
+            }
+        }
+        _ => {}
+    }
+
+    if coerce && is_scalar(data_type) {
+        return value
+            .clone()
+            .cast_to(&data_type.to_string())
+            .map(Some)
+            .map_err(|e| {
+                format!(
+                    "cannot coerce {} to {}: {}",
+                    value.type_name(),
+                    data_type,
+                    e
+                )
+            });
+    }
+
+    Err(format!("expected {}, got {}", data_type, value.type_name()))
+}
+
+/// An integer widened to REAL must survive the trip through `f32`
+fn lossy_float(original: &FieldValue, widened: &FieldValue) -> bool {
+    match (original, widened) {
+        (FieldValue::Integer(i), FieldValue::Float(f)) => *f as i64 != *i,
+        _ => false,
+    }
+}
+
+fn is_scalar(data_type: &DataType) -> bool {
+    !matches!(
+        data_type,
+        DataType::Array(_) | DataType::Map(_, _) | DataType::Struct(_)
+    )
+}
+
+/// Whether a non-NULL value already has the declared type
+fn value_matches(value: &FieldValue, data_type: &DataType) -> bool {
+    match (value, data_type) {
+        (FieldValue::Null, _) => true,
+        (FieldValue::Integer(i), DataType::SmallInt) => i16::try_from(*i).is_ok(),
+        // INTEGER is 64-bit like BIGINT; only SMALLINT is range-checked
+        (FieldValue::Integer(_), DataType::Integer | DataType::BigInt) => true,
+        (FieldValue::Float(f), DataType::Real) => (*f as f32) as f64 == *f || f.is_nan(),
+        (FieldValue::Float(_), DataType::Float) => true,
+        (FieldValue::String(_), DataType::String) => true,
+        (FieldValue::Boolean(_), DataType::Boolean) => true,
+        (FieldValue::Bytes(_), DataType::Bytes) => true,
+        (FieldValue::Uuid(_), DataType::Uuid) => true,
+        (FieldValue::Date(_), DataType::Date) => true,
+        (FieldValue::Time(_), DataType::Time) => true,
+        (FieldValue::Timestamp(_), DataType::Timestamp) => true,
+        (FieldValue::TimestampTz(_), DataType::TimestampTz) => true,
+        (FieldValue::Decimal(_) | FieldValue::ScaledInteger(..), DataType::Decimal(None)) => true,
+        (FieldValue::ScaledInteger(v, scale), DataType::Decimal(Some(spec))) => {
+            *scale == spec.scale
+                && 10_i128
+                    .checked_pow(spec.precision as u32)
+                    .map_or(true, |limit| (*v as i128).abs() < limit)
+        }
+        (FieldValue::Array(items), DataType::Array(inner)) => {
+            items.iter().all(|item| value_matches(item, inner))
+        }
+        (FieldValue::Map(entries), DataType::Map(_, value_type)) => {
+            entries.values().all(|v| value_matches(v, value_type))
+        }
+        (FieldValue::Struct(fields) | FieldValue::Map(fields), DataType::Struct(declared)) => {
+            struct_matches(fields, declared)
+        }
+        _ => false,
+    }
+}
+
+fn struct_matches(
+    fields: &HashMap<String, FieldValue>,
+    declared: &[crate::ferris::sql::ast::StructField],
+) -> bool {
+    declared.iter().all(|field| match fields.get(&field.name) {
+        None | Some(FieldValue::Null) => field.nullable,
+        Some(value) => value_matches(value, &field.data_type),
+    }) && fields
+        .keys()
+        .all(|key| declared.iter().any(|field| &field.name == key))
+}
@@ -0,0 +1,653 @@
+//! Job memory budget and spilling of operator buffers to disk.
+//!
+//! Window buffers, hash join tables and sorts hold records in memory for as long as they
+//! need them, so a burst of input or a large join can take the whole server down with one
+//! job. With `memory.budget.mb` set, these operators account the estimated size of the
+//! records they hold against one [`MemoryBudget`] per job, and once the job is over it they
+//! move records to spill files on local disk:
+//!
+//! - a window writes its buffered records to a run and keeps buffering in memory; when the
+//!   window fires the runs are streamed through its aggregation one record at a time, and
+//!   rewritten without the records of the closed window
+//! - a hash join whose build side, estimated with
+//!   [`JoinStatistics::estimate_memory_usage`](super::algorithms::hash_join::JoinStatistics::estimate_memory_usage),
+//!   does not fit what is left of the budget partitions both sides by join key into runs
+//!   and joins one partition at a time
+//! - an [`ExternalSorter`], which sorts the results of bounded queries with `ORDER BY`,
+//!   sorts what fits into a run and merges the sorted runs as they are read
+//!
+//! Runs are files under `<memory.spill.dir>/<job>`, deleted once their operator no longer
+//! needs them. Sizes are estimates of the in-memory size of records, not measurements of
+//! the allocator.
+//!
+//! ## Configuration
+//!
+//! | Property | Default | |
+//! |----------|---------|-|
+//! | `memory.budget.mb` | none | Memory in MiB the buffering operators of a job may hold; nothing spills without it |
+//! | `memory.spill.dir` | system temp directory | Root directory of spill files |
+//!
+//! ```sql
+//! CREATE STREAM hourly_totals AS
+//! SELECT customer_id, SUM(amount) AS total FROM orders
+//! GROUP BY customer_id WINDOW TUMBLING(1h)
+//! WITH ('memory.budget.mb' = '256', 'memory.spill.dir' = '/var/tmp/ferris');
+//! ```
+
+use super::expression::functions::BuiltinFunctions;
+use super::expression::ExpressionEvaluator;
+use super::state::StateCodec;
+use super::{FieldValue, StreamRecord};
+use crate::ferris::sql::ast::{OrderByExpr, OrderDirection};
+use crate::ferris::sql::error::SqlError;
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::mem::size_of;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Job property with the memory budget of a job in MiB; operators never spill without it
+pub const MEMORY_BUDGET_PROPERTY: &str = "memory.budget.mb";
+
+/// Job property with the root directory of spill files
+pub const SPILL_DIR_PROPERTY: &str = "memory.spill.dir";
+
+const RUN_PREFIX: &str = "run-";
+const RUN_SUFFIX: &str = ".spill";
+
+/// Memory budget settings of a job
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemoryBudgetConfig {
+    /// Bytes the buffering operators of a job may hold
+    pub limit: usize,
+    /// Root directory of spill files, holding one subdirectory per job
+    pub directory: PathBuf,
+}
+
+impl MemoryBudgetConfig {
+    /// A budget of `limit` bytes spilling to the system temp directory
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            directory: std::env::temp_dir().join("ferris-spill"),
+        }
+    }
+
+    pub fn with_directory(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.directory = directory.into();
+        self
+    }
+
+    /// Read `memory.budget.mb` and `memory.spill.dir` from job properties; `None` when
+    /// the job has no budget
+    pub fn from_properties(properties: &HashMap<String, String>) -> Result<Option<Self>, SqlError> {
+        let Some(value) = properties.get(MEMORY_BUDGET_PROPERTY) else {
+            if properties.contains_key(SPILL_DIR_PROPERTY) {
+                return Err(SqlError::ConfigurationError {
+                    message: format!(
+                        "'{}' requires '{}'",
+                        SPILL_DIR_PROPERTY, MEMORY_BUDGET_PROPERTY
+                    ),
+                });
+            }
+            return Ok(None);
+        };
+
+        let megabytes = value
+            .trim()
+            .parse::<usize>()
+            .ok()
+            .filter(|megabytes| *megabytes > 0)
+            .ok_or_else(|| SqlError::ConfigurationError {
+                message: format!(
+                    "Invalid {} '{}': expected a positive number of MiB",
+                    MEMORY_BUDGET_PROPERTY, value
+                ),
+            })?;
+        let mut config = Self::new(megabytes.saturating_mul(1024 * 1024));
+        if let Some(directory) = properties.get(SPILL_DIR_PROPERTY) {
+            config.directory = PathBuf::from(directory);
+        }
+        Ok(Some(config))
+    }
+
+    /// The budget of one job, spilling to `<directory>/<job>`
+    pub fn open(&self, job: &str) -> MemoryBudget {
+        MemoryBudget::new(self.limit, self.directory.join(job))
+    }
+}
+
+struct BudgetState {
+    limit: usize,
+    directory: PathBuf,
+    used: AtomicUsize,
+    peak: AtomicUsize,
+    spills: AtomicU64,
+    spilled_bytes: AtomicU64,
+    next_run: AtomicU64,
+}
+
+/// Memory shared by the buffering operators of a job, see the [module docs](self)
+///
+/// Clones share one budget, so the engine and its processor contexts account against the
+/// same limit.
+#[derive(Clone)]
+pub struct MemoryBudget {
+    state: Arc<BudgetState>,
+}
+
+impl fmt::Debug for MemoryBudget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemoryBudget")
+            .field("limit", &self.state.limit)
+            .field("used", &self.used())
+            .field("directory", &self.state.directory)
+            .finish()
+    }
+}
+
+impl MemoryBudget {
+    /// A budget of `limit` bytes spilling to files in `directory`
+    pub fn new(limit: usize, directory: impl Into<PathBuf>) -> Self {
+        Self {
+            state: Arc::new(BudgetState {
+                limit,
+                directory: directory.into(),
+                used: AtomicUsize::new(0),
+                peak: AtomicUsize::new(0),
+                spills: AtomicU64::new(0),
+                spilled_bytes: AtomicU64::new(0),
+                next_run: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.state.limit
+    }
+
+    /// Bytes held by the job's operators
+    pub fn used(&self) -> usize {
+        self.state.used.load(Ordering::Relaxed)
+    }
+
+    /// Bytes left before the job goes over its budget
+    pub fn available(&self) -> usize {
+        self.state.limit.saturating_sub(self.used())
+    }
+
+    pub fn is_exceeded(&self) -> bool {
+        self.used() > self.state.limit
+    }
+
+    /// Directory spill files of the job go to
+    pub fn directory(&self) -> &Path {
+        &self.state.directory
+    }
+
+    /// Account `bytes` more; `false` when the job is now over its budget
+    pub fn reserve(&self, bytes: usize) -> bool {
+        let used = self.state.used.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        self.state.peak.fetch_max(used, Ordering::Relaxed);
+        used <= self.state.limit
+    }
+
+    /// Give back `bytes` accounted with [`reserve`](Self::reserve)
+    pub fn release(&self, bytes: usize) {
+        let _ = self
+            .state
+            .used
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| {
+                Some(used.saturating_sub(bytes))
+            });
+    }
+
+    /// An empty reservation of one operator
+    pub fn reservation(&self) -> MemoryReservation {
+        MemoryReservation {
+            budget: self.clone(),
+            bytes: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn metrics(&self) -> MemoryMetrics {
+        MemoryMetrics {
+            limit: self.state.limit,
+            used: self.used(),
+            peak: self.state.peak.load(Ordering::Relaxed),
+            spills: self.state.spills.load(Ordering::Relaxed),
+            spilled_bytes: self.state.spilled_bytes.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Path of a new spill file
+    fn run_path(&self) -> Result<PathBuf, SqlError> {
+        let directory = self.directory();
+        fs::create_dir_all(directory).map_err(|e| io_error(directory, "create", e))?;
+        let run = self.state.next_run.fetch_add(1, Ordering::Relaxed);
+        Ok(directory.join(format!(
+            "{}{}-{}{}",
+            RUN_PREFIX,
+            std::process::id(),
+            run,
+            RUN_SUFFIX
+        )))
+    }
+}
+
+/// Memory use and spilling of a job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MemoryMetrics {
+    pub limit: usize,
+    /// Bytes held now
+    pub used: usize,
+    /// Most bytes held at once
+    pub peak: usize,
+    /// Runs written to disk
+    pub spills: u64,
+    /// Bytes written to disk
+    pub spilled_bytes: u64,
+}
+
+/// The part of a [`MemoryBudget`] one operator holds, given back when dropped
+#[derive(Debug)]
+pub struct MemoryReservation {
+    budget: MemoryBudget,
+    bytes: AtomicUsize,
+}
+
+impl MemoryReservation {
+    pub fn budget(&self) -> &MemoryBudget {
+        &self.budget
+    }
+
+    /// Bytes held
+    pub fn size(&self) -> usize {
+        self.bytes.load(Ordering::Relaxed)
+    }
+
+    /// Hold `bytes` more; `false` when the job is now over its budget
+    pub fn grow(&self, bytes: usize) -> bool {
+        self.bytes.fetch_add(bytes, Ordering::Relaxed);
+        self.budget.reserve(bytes)
+    }
+
+    /// Hold `bytes` in all; `false` when the job is over its budget
+    pub fn resize(&self, bytes: usize) -> bool {
+        let held = self.bytes.swap(bytes, Ordering::Relaxed);
+        if bytes >= held {
+            self.budget.reserve(bytes - held)
+        } else {
+            self.budget.release(held - bytes);
+            !self.budget.is_exceeded()
+        }
+    }
+
+    /// Give back everything held
+    pub fn free(&self) {
+        self.resize(0);
+    }
+}
+
+impl Drop for MemoryReservation {
+    fn drop(&mut self) {
+        self.budget.release(self.size());
+    }
+}
+
+/// Estimated bytes a record takes in memory
+pub fn record_size(record: &StreamRecord) -> usize {
+    let fields: usize = record
+        .fields
+        .iter()
+        .map(|(name, value)| size_of::<String>() + name.len() + value_size(value))
+        .sum();
+    let headers: usize = record
+        .headers
+        .iter()
+        .map(|(key, value)| 2 * size_of::<String>() + key.len() + value.len())
+        .sum();
+    size_of::<StreamRecord>() + fields + headers
+}
+
+fn value_size(value: &FieldValue) -> usize {
+    size_of::<FieldValue>()
+        + match value {
+            FieldValue::String(s) => s.len(),
+            FieldValue::Bytes(bytes) => bytes.len(),
+            FieldValue::Array(values) => values.iter().map(value_size).sum(),
+            FieldValue::Map(entries) | FieldValue::Struct(entries) => entries
+                .iter()
+                .map(|(key, value)| size_of::<String>() + key.len() + value_size(value))
+                .sum(),
+            _ => 0,
+        }
+}
+
+fn io_error(path: &Path, action: &str, error: std::io::Error) -> SqlError {
+    SqlError::ResourceError {
+        resource: "spill".to_string(),
+        message: format!(
+            "Failed to {} spill file '{}': {}",
+            action,
+            path.display(),
+            error
+        ),
+    }
+}
+
+/// Records written to a spill file, which is deleted when the run is dropped
+///
+/// Each record is its [`StateCodec`] encoding behind a `u32` length.
+#[derive(Debug)]
+pub struct SpillRun {
+    path: PathBuf,
+    records: usize,
+}
+
+impl SpillRun {
+    /// Write `records` to a new file in the budget's spill directory
+    pub fn write<'a>(
+        budget: &MemoryBudget,
+        records: impl IntoIterator<Item = &'a StreamRecord>,
+    ) -> Result<Self, SqlError> {
+        Self::write_results(budget, records.into_iter().map(Ok))
+    }
+
+    /// A new run of the records of this one `keep` returns true for, written as they are
+    /// read
+    pub fn retain(
+        &self,
+        budget: &MemoryBudget,
+        mut keep: impl FnMut(&StreamRecord) -> bool,
+    ) -> Result<Self, SqlError> {
+        let records = self
+            .read()?
+            .filter(|record| record.as_ref().map_or(true, &mut keep));
+        Self::write_results(budget, records)
+    }
+
+    fn write_results<R: Borrow<StreamRecord>>(
+        budget: &MemoryBudget,
+        records: impl Iterator<Item = Result<R, SqlError>>,
+    ) -> Result<Self, SqlError> {
+        let path = budget.run_path()?;
+        let file = File::create(&path).map_err(|e| io_error(&path, "create", e))?;
+        // From here on the file is removed again if writing fails
+        let mut run = Self { path, records: 0 };
+        let mut writer = BufWriter::new(file);
+        let mut encoded = Vec::new();
+        let mut bytes = 0u64;
+        for record in records {
+            encoded.clear();
+            record?.borrow().encode(&mut encoded);
+            writer
+                .write_all(&(encoded.len() as u32).to_be_bytes())
+                .and_then(|()| writer.write_all(&encoded))
+                .map_err(|e| io_error(&run.path, "write", e))?;
+            run.records += 1;
+            bytes += 4 + encoded.len() as u64;
+        }
+        writer
+            .flush()
+            .map_err(|e| io_error(&run.path, "write", e))?;
+
+        budget.state.spills.fetch_add(1, Ordering::Relaxed);
+        budget
+            .state
+            .spilled_bytes
+            .fetch_add(bytes, Ordering::Relaxed);
+        Ok(run)
+    }
+
+    /// Number of records in the run
+    pub fn len(&self) -> usize {
+        self.records
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records == 0
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Read the records back in the order they were written
+    pub fn read(&self) -> Result<SpillReader, SqlError> {
+        let file = File::open(&self.path).map_err(|e| io_error(&self.path, "open", e))?;
+        Ok(SpillReader {
+            path: self.path.clone(),
+            reader: BufReader::new(file),
+            remaining: self.records,
+        })
+    }
+
+    pub fn read_all(&self) -> Result<Vec<StreamRecord>, SqlError> {
+        self.read()?.collect()
+    }
+}
+
+impl Drop for SpillRun {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Records of a [`SpillRun`], read one at a time
+#[derive(Debug)]
+pub struct SpillReader {
+    path: PathBuf,
+    reader: BufReader<File>,
+    remaining: usize,
+}
+
+impl Iterator for SpillReader {
+    type Item = Result<StreamRecord, SqlError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        let mut len = [0u8; 4];
+        let mut read = || -> Result<StreamRecord, SqlError> {
+            self.reader
+                .read_exact(&mut len)
+                .map_err(|e| io_error(&self.path, "read", e))?;
+            let mut encoded = vec![0u8; u32::from_be_bytes(len) as usize];
+            self.reader
+                .read_exact(&mut encoded)
+                .map_err(|e| io_error(&self.path, "read", e))?;
+            StreamRecord::from_state_bytes(&encoded)
+        };
+        let record = read();
+        if record.is_err() {
+            self.remaining = 0;
+        }
+        Some(record)
+    }
+}
+
+/// Sort key of a record: its ORDER BY values, NULL where an expression fails
+fn sort_key(order_by: &[OrderByExpr], record: &StreamRecord) -> Vec<FieldValue> {
+    order_by
+        .iter()
+        .map(|order| {
+            ExpressionEvaluator::evaluate_expression_value(&order.expr, record)
+                .unwrap_or(FieldValue::Null)
+        })
+        .collect()
+}
+
+fn compare_keys(
+    order_by: &[OrderByExpr],
+    a: &[FieldValue],
+    b: &[FieldValue],
+) -> std::cmp::Ordering {
+    for ((order, a), b) in order_by.iter().zip(a).zip(b) {
+        let ordering = BuiltinFunctions::array_sort_order(a, b);
+        let ordering = match order.direction {
+            OrderDirection::Asc => ordering,
+            OrderDirection::Desc => ordering.reverse(),
+        };
+        if ordering.is_ne() {
+            return ordering;
+        }
+    }
+    std::cmp::Ordering::Equal
+}
+
+/// Sorts records by ORDER BY expressions within a memory budget
+///
+/// Records are held in memory until the job goes over its budget; they are then sorted
+/// into a [`SpillRun`] and [`finish`](Self::finish) merges the runs, holding one record
+/// per run at a time. The sort is stable and puts NULLs last in ascending order, as
+/// `ARRAY_SORT` does.
+pub struct ExternalSorter {
+    order_by: Vec<OrderByExpr>,
+    buffer: Vec<StreamRecord>,
+    runs: Vec<SpillRun>,
+    reservation: Option<MemoryReservation>,
+}
+
+impl ExternalSorter {
+    /// A sorter that never spills without a budget
+    pub fn new(order_by: Vec<OrderByExpr>, budget: Option<&MemoryBudget>) -> Self {
+        Self {
+            order_by,
+            buffer: Vec::new(),
+            runs: Vec::new(),
+            reservation: budget.map(MemoryBudget::reservation),
+        }
+    }
+
+    pub fn push(&mut self, record: StreamRecord) -> Result<(), SqlError> {
+        let size = record_size(&record);
+        self.buffer.push(record);
+        match &self.reservation {
+            Some(reservation) if !reservation.grow(size) => self.spill(),
+            _ => Ok(()),
+        }
+    }
+
+    /// Runs written to disk so far
+    pub fn spilled_runs(&self) -> usize {
+        self.runs.len()
+    }
+
+    fn sorted_buffer(&mut self) -> Vec<(Vec<FieldValue>, StreamRecord)> {
+        let mut keyed: Vec<_> = self
+            .buffer
+            .drain(..)
+            .map(|record| (sort_key(&self.order_by, &record), record))
+            .collect();
+        keyed.sort_by(|(a, _), (b, _)| compare_keys(&self.order_by, a, b));
+        keyed
+    }
+
+    fn spill(&mut self) -> Result<(), SqlError> {
+        let Some(reservation) = &self.reservation else {
+            return Ok(());
+        };
+        let budget = reservation.budget().clone();
+        let sorted = self.sorted_buffer();
+        let run = SpillRun::write(&budget, sorted.iter().map(|(_, record)| record))?;
+        self.runs.push(run);
+        if let Some(reservation) = &self.reservation {
+            reservation.free();
+        }
+        Ok(())
+    }
+
+    /// The records in sort order
+    pub fn finish(mut self) -> Result<SortedRecords, SqlError> {
+        let memory = self.sorted_buffer();
+        let mut sources = self
+            .runs
+            .iter()
+            .map(|run| run.read().map(SortedSource::Run))
+            .collect::<Result<Vec<_>, _>>()?;
+        sources.push(SortedSource::Memory(memory.into_iter()));
+
+        let mut sorted = SortedRecords {
+            heads: Vec::with_capacity(sources.len()),
+            sources,
+            order_by: std::mem::take(&mut self.order_by),
+            _runs: std::mem::take(&mut self.runs),
+            _reservation: self.reservation.take(),
+        };
+        for index in 0..sorted.sources.len() {
+            let head = sorted.next_of(index)?;
+            sorted.heads.push(head);
+        }
+        Ok(sorted)
+    }
+}
+
+enum SortedSource {
+    Run(SpillReader),
+    Memory(std::vec::IntoIter<(Vec<FieldValue>, StreamRecord)>),
+}
+
+/// Records of an [`ExternalSorter`] in sort order, merged from its runs as they are read
+pub struct SortedRecords {
+    order_by: Vec<OrderByExpr>,
+    sources: Vec<SortedSource>,
+    /// Next record of every source
+    heads: Vec<Option<(Vec<FieldValue>, StreamRecord)>>,
+    /// Spill files stay until the records are read
+    _runs: Vec<SpillRun>,
+    _reservation: Option<MemoryReservation>,
+}
+
+impl SortedRecords {
+    fn next_of(
+        &mut self,
+        index: usize,
+    ) -> Result<Option<(Vec<FieldValue>, StreamRecord)>, SqlError> {
+        match &mut self.sources[index] {
+            SortedSource::Run(reader) => reader
+                .next()
+                .transpose()
+                .map(|record| record.map(|record| (sort_key(&self.order_by, &record), record))),
+            SortedSource::Memory(records) => Ok(records.next()),
+        }
+    }
+}
+
+impl Iterator for SortedRecords {
+    type Item = Result<StreamRecord, SqlError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Earlier sources win ties, which keeps the sort stable
+        let mut smallest: Option<usize> = None;
+        for (index, head) in self.heads.iter().enumerate() {
+            let Some((key, _)) = head else {
+                continue;
+            };
+            let is_smaller = smallest.map_or(true, |current| {
+                let (current_key, _) = self.heads[current].as_ref().expect("head is set");
+                compare_keys(&self.order_by, key, current_key).is_lt()
+            });
+            if is_smaller {
+                smallest = Some(index);
+            }
+        }
+
+        let index = smallest?;
+        let (_, record) = self.heads[index].take().expect("head is set");
+        match self.next_of(index) {
+            Ok(head) => {
+                self.heads[index] = head;
+                Some(Ok(record))
+            }
+            Err(error) => Some(Err(error)),
+        }
+    }
+}
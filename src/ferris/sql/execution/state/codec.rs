@@ -0,0 +1,548 @@
+//! Binary encoding of keys and values held in a [`StateBackend`](super::StateBackend).
+//!
+//! Encodings are self-delimiting: strings, byte strings and collections carry a length
+//! prefix, so the encoding of a key is never a prefix of the encoding of a different key.
+//! [`MapState`](super::MapState) and [`ListState`](super::ListState) rely on this to find all
+//! entries of one key with a prefix scan.
+
+use crate::ferris::datasource::SourceOffset;
+use crate::ferris::sql::ast::TimeUnit;
+use crate::ferris::sql::error::SqlError;
+use crate::ferris::sql::execution::internal::{ApproxDistinctEstimator, GroupAccumulator};
+use crate::ferris::sql::execution::{FieldValue, RecordFields, StreamRecord};
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike};
+use chrono_tz::Tz;
+use rust_decimal::Decimal;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use uuid::Uuid;
+
+/// A type that can be stored in a state backend
+pub trait StateCodec: Sized {
+    /// Append the encoding of `self` to `out`
+    fn encode(&self, out: &mut Vec<u8>);
+
+    /// Decode a value from the front of `input`, advancing it past the value
+    fn decode(input: &mut &[u8]) -> Result<Self, SqlError>;
+
+    /// The encoding of `self` as a new buffer
+    fn to_state_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.encode(&mut out);
+        out
+    }
+
+    /// Decode a value that must span all of `bytes`
+    fn from_state_bytes(bytes: &[u8]) -> Result<Self, SqlError> {
+        let mut input = bytes;
+        let value = Self::decode(&mut input)?;
+        if !input.is_empty() {
+            return Err(codec_error(format!(
+                "{} trailing bytes after state value",
+                input.len()
+            )));
+        }
+        Ok(value)
+    }
+}
+
+fn codec_error(message: impl Into<String>) -> SqlError {
+    SqlError::ExecutionError {
+        message: format!("[STATE-CODEC-001] Corrupt state value: {}", message.into()),
+        query: None,
+    }
+}
+
+fn take<'a>(input: &mut &'a [u8], len: usize) -> Result<&'a [u8], SqlError> {
+    if input.len() < len {
+        return Err(codec_error(format!(
+            "expected {} bytes, found {}",
+            len,
+            input.len()
+        )));
+    }
+    let (head, rest) = input.split_at(len);
+    *input = rest;
+    Ok(head)
+}
+
+fn take_array<const N: usize>(input: &mut &[u8]) -> Result<[u8; N], SqlError> {
+    let mut bytes = [0u8; N];
+    bytes.copy_from_slice(take(input, N)?);
+    Ok(bytes)
+}
+
+fn encode_len(len: usize, out: &mut Vec<u8>) {
+    (len as u32).encode(out);
+}
+
+fn decode_len(input: &mut &[u8]) -> Result<usize, SqlError> {
+    Ok(u32::decode(input)? as usize)
+}
+
+macro_rules! fixed_width_codec {
+    ($($ty:ty),*) => {
+        $(
+            impl StateCodec for $ty {
+                fn encode(&self, out: &mut Vec<u8>) {
+                    // Big-endian keeps the byte order of non-negative keys equal to their numeric order
+                    out.extend_from_slice(&self.to_be_bytes());
+                }
+
+                fn decode(input: &mut &[u8]) -> Result<Self, SqlError> {
+                    Ok(<$ty>::from_be_bytes(take_array(input)?))
+                }
+            }
+        )*
+    };
+}
+
+fixed_width_codec!(u8, u32, u64, i32, i64, f64);
+
+impl StateCodec for bool {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(u8::from(*self));
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, SqlError> {
+        match u8::decode(input)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            other => Err(codec_error(format!("invalid boolean byte {}", other))),
+        }
+    }
+}
+
+impl StateCodec for String {
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_len(self.len(), out);
+        out.extend_from_slice(self.as_bytes());
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, SqlError> {
+        let len = decode_len(input)?;
+        String::from_utf8(take(input, len)?.to_vec())
+            .map_err(|e| codec_error(format!("invalid UTF-8 string: {}", e)))
+    }
+}
+
+impl<T: StateCodec> StateCodec for Option<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            None => out.push(0),
+            Some(value) => {
+                out.push(1);
+                value.encode(out);
+            }
+        }
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, SqlError> {
+        match u8::decode(input)? {
+            0 => Ok(None),
+            1 => Ok(Some(T::decode(input)?)),
+            other => Err(codec_error(format!("invalid option tag {}", other))),
+        }
+    }
+}
+
+impl<T: StateCodec> StateCodec for Vec<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_len(self.len(), out);
+        for item in self {
+            item.encode(out);
+        }
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, SqlError> {
+        let len = decode_len(input)?;
+        (0..len).map(|_| T::decode(input)).collect()
+    }
+}
+
+impl<A: StateCodec, B: StateCodec> StateCodec for (A, B) {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.0.encode(out);
+        self.1.encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, SqlError> {
+        Ok((A::decode(input)?, B::decode(input)?))
+    }
+}
+
+impl<K: StateCodec + Eq + Hash, V: StateCodec> StateCodec for HashMap<K, V> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_len(self.len(), out);
+        for (key, value) in self {
+            key.encode(out);
+            value.encode(out);
+        }
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, SqlError> {
+        let len = decode_len(input)?;
+        (0..len)
+            .map(|_| Ok((K::decode(input)?, V::decode(input)?)))
+            .collect()
+    }
+}
+
+impl<T: StateCodec + Eq + Hash> StateCodec for HashSet<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_len(self.len(), out);
+        for item in self {
+            item.encode(out);
+        }
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, SqlError> {
+        let len = decode_len(input)?;
+        (0..len).map(|_| T::decode(input)).collect()
+    }
+}
+
+impl StateCodec for NaiveDate {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.num_days_from_ce().encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, SqlError> {
+        let days = i32::decode(input)?;
+        NaiveDate::from_num_days_from_ce_opt(days)
+            .ok_or_else(|| codec_error(format!("date out of range: {} days", days)))
+    }
+}
+
+impl StateCodec for NaiveTime {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.num_seconds_from_midnight().encode(out);
+        self.nanosecond().encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, SqlError> {
+        let seconds = u32::decode(input)?;
+        let nanos = u32::decode(input)?;
+        NaiveTime::from_num_seconds_from_midnight_opt(seconds, nanos)
+            .ok_or_else(|| codec_error(format!("time out of range: {}s {}ns", seconds, nanos)))
+    }
+}
+
+impl StateCodec for NaiveDateTime {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.date().encode(out);
+        self.time().encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, SqlError> {
+        Ok(NaiveDateTime::new(
+            NaiveDate::decode(input)?,
+            NaiveTime::decode(input)?,
+        ))
+    }
+}
+
+impl StateCodec for DateTime<Tz> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.naive_utc().encode(out);
+        self.timezone().name().to_string().encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, SqlError> {
+        let utc = NaiveDateTime::decode(input)?;
+        let zone = String::decode(input)?;
+        let tz: Tz = zone
+            .parse()
+            .map_err(|_| codec_error(format!("unknown time zone '{}'", zone)))?;
+        Ok(tz.from_utc_datetime(&utc))
+    }
+}
+
+impl StateCodec for TimeUnit {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(match self {
+            TimeUnit::Millisecond => 0,
+            TimeUnit::Second => 1,
+            TimeUnit::Minute => 2,
+            TimeUnit::Hour => 3,
+            TimeUnit::Day => 4,
+            TimeUnit::Week => 5,
+            TimeUnit::Month => 6,
+            TimeUnit::Year => 7,
+        });
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, SqlError> {
+        Ok(match u8::decode(input)? {
+            0 => TimeUnit::Millisecond,
+            1 => TimeUnit::Second,
+            2 => TimeUnit::Minute,
+            3 => TimeUnit::Hour,
+            4 => TimeUnit::Day,
+            5 => TimeUnit::Week,
+            6 => TimeUnit::Month,
+            7 => TimeUnit::Year,
+            other => return Err(codec_error(format!("invalid time unit tag {}", other))),
+        })
+    }
+}
+
+impl StateCodec for FieldValue {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            FieldValue::Null => out.push(0),
+            FieldValue::Integer(value) => {
+                out.push(1);
+                value.encode(out);
+            }
+            FieldValue::Float(value) => {
+                out.push(2);
+                value.encode(out);
+            }
+            FieldValue::String(value) => {
+                out.push(3);
+                value.encode(out);
+            }
+            FieldValue::Boolean(value) => {
+                out.push(4);
+                value.encode(out);
+            }
+            FieldValue::Date(value) => {
+                out.push(5);
+                value.encode(out);
+            }
+            FieldValue::Timestamp(value) => {
+                out.push(6);
+                value.encode(out);
+            }
+            FieldValue::TimestampTz(value) => {
+                out.push(7);
+                value.encode(out);
+            }
+            FieldValue::Decimal(value) => {
+                out.push(8);
+                out.extend_from_slice(&value.serialize());
+            }
+            FieldValue::ScaledInteger(value, scale) => {
+                out.push(9);
+                value.encode(out);
+                scale.encode(out);
+            }
+            FieldValue::Array(values) => {
+                out.push(10);
+                values.encode(out);
+            }
+            FieldValue::Map(entries) => {
+                out.push(11);
+                entries.encode(out);
+            }
+            FieldValue::Struct(entries) => {
+                out.push(12);
+                entries.encode(out);
+            }
+            FieldValue::Interval { value, unit } => {
+                out.push(13);
+                value.encode(out);
+                unit.encode(out);
+            }
+            FieldValue::Bytes(bytes) => {
+                out.push(14);
+                encode_len(bytes.len(), out);
+                out.extend_from_slice(bytes);
+            }
+            FieldValue::Uuid(value) => {
+                out.push(15);
+                out.extend_from_slice(value.as_bytes());
+            }
+            FieldValue::Time(value) => {
+                out.push(16);
+                value.encode(out);
+            }
+        }
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, SqlError> {
+        Ok(match u8::decode(input)? {
+            0 => FieldValue::Null,
+            1 => FieldValue::Integer(i64::decode(input)?),
+            2 => FieldValue::Float(f64::decode(input)?),
+            3 => FieldValue::String(String::decode(input)?),
+            4 => FieldValue::Boolean(bool::decode(input)?),
+            5 => FieldValue::Date(NaiveDate::decode(input)?),
+            6 => FieldValue::Timestamp(NaiveDateTime::decode(input)?),
+            7 => FieldValue::TimestampTz(DateTime::<Tz>::decode(input)?),
+            8 => FieldValue::Decimal(Decimal::deserialize(take_array(input)?)),
+            9 => FieldValue::ScaledInteger(i64::decode(input)?, u8::decode(input)?),
+            10 => FieldValue::Array(Vec::decode(input)?),
+            11 => FieldValue::Map(HashMap::decode(input)?),
+            12 => FieldValue::Struct(HashMap::decode(input)?),
+            13 => FieldValue::Interval {
+                value: i64::decode(input)?,
+                unit: TimeUnit::decode(input)?,
+            },
+            14 => {
+                let len = decode_len(input)?;
+                FieldValue::Bytes(take(input, len)?.to_vec())
+            }
+            15 => FieldValue::Uuid(Uuid::from_bytes(take_array(input)?)),
+            16 => FieldValue::Time(NaiveTime::decode(input)?),
+            other => return Err(codec_error(format!("invalid field value tag {}", other))),
+        })
+    }
+}
+
+impl StateCodec for StreamRecord {
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_len(self.fields.len(), out);
+        for (name, value) in &self.fields {
+            name.encode(out);
+            value.encode(out);
+        }
+        self.timestamp.encode(out);
+        self.offset.encode(out);
+        self.partition.encode(out);
+        self.headers.encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, SqlError> {
+        let len = decode_len(input)?;
+        let mut fields = RecordFields::new();
+        for _ in 0..len {
+            let name = String::decode(input)?;
+            fields.insert(name, FieldValue::decode(input)?);
+        }
+        Ok(StreamRecord {
+            fields,
+            timestamp: i64::decode(input)?,
+            offset: i64::decode(input)?,
+            partition: i32::decode(input)?,
+            headers: HashMap::decode(input)?,
+        })
+    }
+}
+
+impl StateCodec for SourceOffset {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            SourceOffset::Kafka { partition, offset } => {
+                out.push(0);
+                partition.encode(out);
+                offset.encode(out);
+            }
+            SourceOffset::KafkaPartitions { offsets } => {
+                out.push(5);
+                offsets.encode(out);
+            }
+            SourceOffset::File {
+                path,
+                byte_offset,
+                line_number,
+            } => {
+                out.push(1);
+                path.encode(out);
+                byte_offset.encode(out);
+                line_number.encode(out);
+            }
+            SourceOffset::S3 {
+                bucket,
+                key,
+                byte_offset,
+            } => {
+                out.push(2);
+                bucket.encode(out);
+                key.encode(out);
+                byte_offset.encode(out);
+            }
+            SourceOffset::Database { table, cursor } => {
+                out.push(3);
+                table.encode(out);
+                cursor.encode(out);
+            }
+            SourceOffset::Generic(offset) => {
+                out.push(4);
+                offset.encode(out);
+            }
+        }
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, SqlError> {
+        Ok(match u8::decode(input)? {
+            0 => SourceOffset::Kafka {
+                partition: i32::decode(input)?,
+                offset: i64::decode(input)?,
+            },
+            1 => SourceOffset::File {
+                path: String::decode(input)?,
+                byte_offset: u64::decode(input)?,
+                line_number: u64::decode(input)?,
+            },
+            2 => SourceOffset::S3 {
+                bucket: String::decode(input)?,
+                key: String::decode(input)?,
+                byte_offset: u64::decode(input)?,
+            },
+            3 => SourceOffset::Database {
+                table: String::decode(input)?,
+                cursor: HashMap::decode(input)?,
+            },
+            4 => SourceOffset::Generic(String::decode(input)?),
+            5 => SourceOffset::KafkaPartitions {
+                offsets: HashMap::decode(input)?,
+            },
+            other => return Err(codec_error(format!("invalid source offset tag {}", other))),
+        })
+    }
+}
+
+impl StateCodec for ApproxDistinctEstimator {
+    fn encode(&self, out: &mut Vec<u8>) {
+        // The sketch keeps its registers in several representations; serde covers them all
+        let bytes = serde_json::to_vec(self).expect("HyperLogLog sketches serialize to JSON");
+        encode_len(bytes.len(), out);
+        out.extend_from_slice(&bytes);
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, SqlError> {
+        let len = decode_len(input)?;
+        serde_json::from_slice(take(input, len)?)
+            .map_err(|e| codec_error(format!("invalid HyperLogLog sketch: {}", e)))
+    }
+}
+
+impl StateCodec for GroupAccumulator {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.count.encode(out);
+        self.non_null_counts.encode(out);
+        self.sums.encode(out);
+        self.mins.encode(out);
+        self.maxs.encode(out);
+        self.numeric_values.encode(out);
+        self.first_values.encode(out);
+        self.last_values.encode(out);
+        self.string_values.encode(out);
+        self.json_array_values.encode(out);
+        self.json_object_entries.encode(out);
+        self.distinct_values.encode(out);
+        self.approx_distinct_values.encode(out);
+        self.sample_record.encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, SqlError> {
+        Ok(GroupAccumulator {
+            count: u64::decode(input)?,
+            non_null_counts: HashMap::decode(input)?,
+            sums: HashMap::decode(input)?,
+            mins: HashMap::decode(input)?,
+            maxs: HashMap::decode(input)?,
+            numeric_values: HashMap::decode(input)?,
+            first_values: HashMap::decode(input)?,
+            last_values: HashMap::decode(input)?,
+            string_values: HashMap::decode(input)?,
+            json_array_values: HashMap::decode(input)?,
+            json_object_entries: HashMap::decode(input)?,
+            distinct_values: HashMap::decode(input)?,
+            approx_distinct_values: HashMap::decode(input)?,
+            sample_record: Option::decode(input)?,
+        })
+    }
+}
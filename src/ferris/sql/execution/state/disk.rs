@@ -0,0 +1,509 @@
+//! Embedded log-structured state backend on the local filesystem
+//!
+//! Every write appends a record to `state.log` in the backend directory:
+//!
+//! ```text
+//! op: u8 | namespace_len: u32 | key_len: u32 | value_len: u32 | namespace | key | value | checksum: u64
+//! ```
+//!
+//! `op` is a put, a delete or the clearing of a namespace, and the checksum is the xxHash64 of
+//! the bytes before it. Only keys and the position of their latest value are kept in memory;
+//! values are read back from the log. Opening a directory replays the log through a buffered
+//! reader to rebuild that index, checksumming values without holding them, and drops a torn
+//! record at the end. Once superseded records outweigh live ones the
+//! log is rewritten with only the live values.
+
+use super::memory::prefix_range;
+use super::{StateBackend, StateBackendKind, StateEntry};
+use crate::ferris::sql::error::SqlError;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File, OpenOptions};
+use std::hash::Hasher;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use twox_hash::XxHash64;
+
+const LOG_FILE: &str = "state.log";
+const COMPACTING_FILE: &str = "state.log.compacting";
+
+const OP_PUT: u8 = 1;
+const OP_DELETE: u8 = 2;
+const OP_CLEAR: u8 = 3;
+
+/// op and three lengths
+const HEADER_LEN: u64 = 1 + 4 + 4 + 4;
+/// Header and the trailing checksum
+const RECORD_OVERHEAD: u64 = HEADER_LEN + 8;
+/// Chunk in which values are checksummed while replaying the log
+const REPLAY_CHUNK: usize = 64 * 1024;
+
+/// Default log size below which the log is never compacted
+pub const DEFAULT_COMPACTION_THRESHOLD_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Where the latest value of a key sits in the log
+#[derive(Debug, Clone, Copy)]
+struct ValuePointer {
+    /// Offset of the value bytes
+    offset: u64,
+    len: u32,
+    /// Size of the whole record, counted as garbage once superseded
+    record_len: u64,
+}
+
+/// Header and key of a record replayed from the log; values are left on disk
+struct LogRecord {
+    op: u8,
+    namespace: String,
+    key: Vec<u8>,
+    value_len: u32,
+}
+
+impl LogRecord {
+    /// Offset of the value from the start of the record
+    fn value_start(&self) -> u64 {
+        HEADER_LEN + self.namespace.len() as u64 + self.key.len() as u64
+    }
+
+    /// Size of the whole record
+    fn len(&self) -> u64 {
+        self.value_start() + self.value_len as u64 + 8
+    }
+}
+
+struct LogState {
+    writer: BufWriter<File>,
+    reader: File,
+    /// Writes in `writer` not yet visible to `reader`
+    unflushed: bool,
+    index: HashMap<String, BTreeMap<Vec<u8>, ValuePointer>>,
+    log_len: u64,
+    live_bytes: u64,
+}
+
+/// A [`StateBackend`] that appends every write to a log file and keeps only keys in memory
+pub struct LogStateBackend {
+    directory: PathBuf,
+    compaction_threshold: u64,
+    state: Mutex<LogState>,
+}
+
+impl std::fmt::Debug for LogStateBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LogStateBackend")
+            .field("directory", &self.directory)
+            .finish()
+    }
+}
+
+fn io_error(directory: &Path, action: &str, error: std::io::Error) -> SqlError {
+    SqlError::ResourceError {
+        resource: "state".to_string(),
+        message: format!(
+            "Failed to {} state log in '{}': {}",
+            action,
+            directory.display(),
+            error
+        ),
+    }
+}
+
+fn checksum(bytes: &[u8]) -> u64 {
+    let mut hasher = XxHash64::with_seed(0);
+    hasher.write(bytes);
+    hasher.finish()
+}
+
+/// Fill `buf`, returning false if the log ends first
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> std::io::Result<bool> {
+    match reader.read_exact(buf) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+fn encode_record(op: u8, namespace: &str, key: &[u8], value: &[u8]) -> Vec<u8> {
+    let mut record =
+        Vec::with_capacity(RECORD_OVERHEAD as usize + namespace.len() + key.len() + value.len());
+    record.push(op);
+    record.extend_from_slice(&(namespace.len() as u32).to_be_bytes());
+    record.extend_from_slice(&(key.len() as u32).to_be_bytes());
+    record.extend_from_slice(&(value.len() as u32).to_be_bytes());
+    record.extend_from_slice(namespace.as_bytes());
+    record.extend_from_slice(key);
+    record.extend_from_slice(value);
+    let sum = checksum(&record);
+    record.extend_from_slice(&sum.to_be_bytes());
+    record
+}
+
+impl LogState {
+    fn open(log_path: &Path, directory: &Path) -> Result<Self, SqlError> {
+        let mut state_index: HashMap<String, BTreeMap<Vec<u8>, ValuePointer>> = HashMap::new();
+        let mut live_bytes = 0u64;
+        let mut position = 0u64;
+        let mut log_len = 0u64;
+        if log_path.exists() {
+            let file = File::open(log_path).map_err(|e| io_error(directory, "read", e))?;
+            log_len = file
+                .metadata()
+                .map_err(|e| io_error(directory, "read", e))?
+                .len();
+            let mut reader = BufReader::new(file);
+            while let Some(record) = Self::replay_record(&mut reader, log_len - position)
+                .map_err(|e| io_error(directory, "read", e))?
+            {
+                let record_len = record.len();
+                let entries = state_index.entry(record.namespace.clone()).or_default();
+                match record.op {
+                    OP_PUT => {
+                        let pointer = ValuePointer {
+                            offset: position + record.value_start(),
+                            len: record.value_len,
+                            record_len,
+                        };
+                        if let Some(old) = entries.insert(record.key, pointer) {
+                            live_bytes -= old.record_len;
+                        }
+                        live_bytes += record_len;
+                    }
+                    OP_DELETE => {
+                        if let Some(old) = entries.remove(&record.key) {
+                            live_bytes -= old.record_len;
+                        }
+                    }
+                    _ => {
+                        live_bytes -= entries.values().map(|p| p.record_len).sum::<u64>();
+                        entries.clear();
+                    }
+                }
+                if entries.is_empty() {
+                    state_index.remove(&record.namespace);
+                }
+                position += record_len;
+            }
+        }
+
+        if position < log_len {
+            log::warn!(
+                "Dropping {} bytes of a torn record at the end of the state log in '{}'",
+                log_len - position,
+                directory.display()
+            );
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(log_path)
+            .map_err(|e| io_error(directory, "open", e))?;
+        file.set_len(position)
+            .map_err(|e| io_error(directory, "truncate", e))?;
+        let reader = File::open(log_path).map_err(|e| io_error(directory, "open", e))?;
+
+        Ok(Self {
+            writer: BufWriter::new(file),
+            reader,
+            unflushed: false,
+            index: state_index,
+            log_len: position,
+            live_bytes,
+        })
+    }
+
+    /// Decode the next record of at most `remaining` bytes, checksumming its value without
+    /// keeping it; `None` at the end of the log or at a torn record
+    fn replay_record(reader: &mut impl Read, remaining: u64) -> std::io::Result<Option<LogRecord>> {
+        let mut header = [0u8; HEADER_LEN as usize];
+        if remaining < RECORD_OVERHEAD || !read_full(reader, &mut header)? {
+            return Ok(None);
+        }
+        let read_len = |at: usize| u32::from_be_bytes(header[at..at + 4].try_into().unwrap());
+        let (namespace_len, key_len, value_len) = (read_len(1), read_len(5), read_len(9));
+        let record_len = RECORD_OVERHEAD + namespace_len as u64 + key_len as u64 + value_len as u64;
+        if record_len > remaining {
+            return Ok(None);
+        }
+
+        let mut hasher = XxHash64::with_seed(0);
+        hasher.write(&header);
+        let mut namespace = vec![0u8; namespace_len as usize];
+        let mut key = vec![0u8; key_len as usize];
+        if !read_full(reader, &mut namespace)? || !read_full(reader, &mut key)? {
+            return Ok(None);
+        }
+        hasher.write(&namespace);
+        hasher.write(&key);
+        let mut chunk = vec![0u8; REPLAY_CHUNK.min(value_len as usize)];
+        let mut unread = value_len as usize;
+        while unread > 0 {
+            let len = unread.min(chunk.len());
+            if !read_full(reader, &mut chunk[..len])? {
+                return Ok(None);
+            }
+            hasher.write(&chunk[..len]);
+            unread -= len;
+        }
+        let mut stored = [0u8; 8];
+        if !read_full(reader, &mut stored)?
+            || u64::from_be_bytes(stored) != hasher.finish()
+            || !matches!(header[0], OP_PUT | OP_DELETE | OP_CLEAR)
+        {
+            return Ok(None);
+        }
+        let Ok(namespace) = String::from_utf8(namespace) else {
+            return Ok(None);
+        };
+        Ok(Some(LogRecord {
+            op: header[0],
+            namespace,
+            key,
+            value_len,
+        }))
+    }
+
+    /// Append a record, returning the offset of its value
+    fn append(&mut self, record: &[u8], directory: &Path) -> Result<u64, SqlError> {
+        self.writer
+            .write_all(record)
+            .map_err(|e| io_error(directory, "append to", e))?;
+        self.unflushed = true;
+        let start = self.log_len;
+        self.log_len += record.len() as u64;
+        Ok(start)
+    }
+
+    fn read_value(&mut self, pointer: ValuePointer, directory: &Path) -> Result<Vec<u8>, SqlError> {
+        if self.unflushed {
+            self.writer
+                .flush()
+                .map_err(|e| io_error(directory, "write", e))?;
+            self.unflushed = false;
+        }
+        let mut value = vec![0u8; pointer.len as usize];
+        self.reader
+            .seek(SeekFrom::Start(pointer.offset))
+            .and_then(|_| self.reader.read_exact(&mut value))
+            .map_err(|e| io_error(directory, "read", e))?;
+        Ok(value)
+    }
+}
+
+impl LogStateBackend {
+    /// Open the log in `directory`, creating the directory when missing
+    pub fn open(directory: impl Into<PathBuf>) -> Result<Self, SqlError> {
+        let directory = directory.into();
+        fs::create_dir_all(&directory).map_err(|e| io_error(&directory, "create", e))?;
+        let state = LogState::open(&directory.join(LOG_FILE), &directory)?;
+        Ok(Self {
+            directory,
+            compaction_threshold: DEFAULT_COMPACTION_THRESHOLD_BYTES,
+            state: Mutex::new(state),
+        })
+    }
+
+    /// Compact only once the log is larger than `bytes`
+    pub fn with_compaction_threshold(mut self, bytes: u64) -> Self {
+        self.compaction_threshold = bytes;
+        self
+    }
+
+    /// Directory holding the log
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Current size of the log file in bytes
+    pub fn log_size(&self) -> u64 {
+        self.state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .log_len
+    }
+
+    /// Rewrite the log with only the latest value of every live key
+    pub fn compact(&self) -> Result<(), SqlError> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        self.compact_locked(&mut state)
+    }
+
+    fn compact_locked(&self, state: &mut LogState) -> Result<(), SqlError> {
+        let compacting_path = self.directory.join(COMPACTING_FILE);
+        let mut compacted = BufWriter::new(
+            File::create(&compacting_path).map_err(|e| io_error(&self.directory, "create", e))?,
+        );
+        let mut index: HashMap<String, BTreeMap<Vec<u8>, ValuePointer>> = HashMap::new();
+        let mut position = 0u64;
+
+        let namespaces: Vec<(String, Vec<_>)> = state
+            .index
+            .iter()
+            .map(|(namespace, entries)| {
+                let entries = entries.iter().map(|(k, p)| (k.clone(), *p)).collect();
+                (namespace.clone(), entries)
+            })
+            .collect();
+        for (namespace, entries) in namespaces {
+            let target = index.entry(namespace.clone()).or_default();
+            for (key, pointer) in entries {
+                let value = state.read_value(pointer, &self.directory)?;
+                let record = encode_record(OP_PUT, &namespace, &key, &value);
+                compacted
+                    .write_all(&record)
+                    .map_err(|e| io_error(&self.directory, "write", e))?;
+                let record_len = record.len() as u64;
+                target.insert(
+                    key,
+                    ValuePointer {
+                        offset: position + record_len - 8 - pointer.len as u64,
+                        len: pointer.len,
+                        record_len,
+                    },
+                );
+                position += record_len;
+            }
+        }
+        compacted
+            .into_inner()
+            .map_err(|e| io_error(&self.directory, "write", e.into_error()))?
+            .sync_all()
+            .map_err(|e| io_error(&self.directory, "sync", e))?;
+
+        let log_path = self.directory.join(LOG_FILE);
+        fs::rename(&compacting_path, &log_path)
+            .map_err(|e| io_error(&self.directory, "replace", e))?;
+        let writer = OpenOptions::new()
+            .append(true)
+            .open(&log_path)
+            .map_err(|e| io_error(&self.directory, "open", e))?;
+        state.reader = File::open(&log_path).map_err(|e| io_error(&self.directory, "open", e))?;
+        state.writer = BufWriter::new(writer);
+        state.unflushed = false;
+        state.index = index;
+        state.log_len = position;
+        state.live_bytes = position;
+        Ok(())
+    }
+
+    fn maybe_compact(&self, state: &mut LogState) -> Result<(), SqlError> {
+        let garbage = state.log_len - state.live_bytes;
+        if state.log_len > self.compaction_threshold && garbage > state.live_bytes {
+            log::debug!(
+                "Compacting state log in '{}' ({} of {} bytes superseded)",
+                self.directory.display(),
+                garbage,
+                state.log_len
+            );
+            self.compact_locked(state)?;
+        }
+        Ok(())
+    }
+}
+
+impl StateBackend for LogStateBackend {
+    fn kind(&self) -> StateBackendKind {
+        StateBackendKind::Disk
+    }
+
+    fn get(&self, namespace: &str, key: &[u8]) -> Result<Option<Vec<u8>>, SqlError> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let Some(pointer) = state.index.get(namespace).and_then(|e| e.get(key)).copied() else {
+            return Ok(None);
+        };
+        state.read_value(pointer, &self.directory).map(Some)
+    }
+
+    fn put(&self, namespace: &str, key: &[u8], value: &[u8]) -> Result<(), SqlError> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let record = encode_record(OP_PUT, namespace, key, value);
+        let start = state.append(&record, &self.directory)?;
+        let record_len = record.len() as u64;
+        let pointer = ValuePointer {
+            offset: start + record_len - 8 - value.len() as u64,
+            len: value.len() as u32,
+            record_len,
+        };
+        let old = state
+            .index
+            .entry(namespace.to_string())
+            .or_default()
+            .insert(key.to_vec(), pointer);
+        state.live_bytes += record_len;
+        if let Some(old) = old {
+            state.live_bytes -= old.record_len;
+        }
+        self.maybe_compact(&mut state)
+    }
+
+    fn delete(&self, namespace: &str, key: &[u8]) -> Result<(), SqlError> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let Some(entries) = state.index.get_mut(namespace) else {
+            return Ok(());
+        };
+        let Some(old) = entries.remove(key) else {
+            return Ok(());
+        };
+        if entries.is_empty() {
+            state.index.remove(namespace);
+        }
+        state.live_bytes -= old.record_len;
+        state.append(
+            &encode_record(OP_DELETE, namespace, key, &[]),
+            &self.directory,
+        )?;
+        self.maybe_compact(&mut state)
+    }
+
+    fn scan_prefix(&self, namespace: &str, prefix: &[u8]) -> Result<Vec<StateEntry>, SqlError> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let pointers: Vec<(Vec<u8>, ValuePointer)> = match state.index.get(namespace) {
+            Some(entries) => prefix_range(entries, prefix)
+                .map(|(key, pointer)| (key.clone(), *pointer))
+                .collect(),
+            None => return Ok(Vec::new()),
+        };
+        pointers
+            .into_iter()
+            .map(|(key, pointer)| Ok((key, state.read_value(pointer, &self.directory)?)))
+            .collect()
+    }
+
+    fn clear(&self, namespace: &str) -> Result<(), SqlError> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let Some(entries) = state.index.remove(namespace) else {
+            return Ok(());
+        };
+        state.live_bytes -= entries.values().map(|p| p.record_len).sum::<u64>();
+        state.append(
+            &encode_record(OP_CLEAR, namespace, &[], &[]),
+            &self.directory,
+        )?;
+        self.maybe_compact(&mut state)
+    }
+
+    fn namespaces(&self) -> Vec<String> {
+        let state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut names: Vec<String> = state.index.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    fn flush(&self) -> Result<(), SqlError> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state
+            .writer
+            .flush()
+            .and_then(|_| state.writer.get_ref().sync_data())
+            .map_err(|e| io_error(&self.directory, "sync", e))?;
+        state.unflushed = false;
+        Ok(())
+    }
+}
+
+impl Drop for LogStateBackend {
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            log::warn!("{}", e);
+        }
+    }
+}
@@ -0,0 +1,106 @@
+//! Heap-backed state backend
+
+use super::{StateBackend, StateBackendKind, StateEntry};
+use crate::ferris::sql::error::SqlError;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Bound;
+use std::sync::{PoisonError, RwLock};
+
+/// Entries of one namespace in key order
+type Namespace = BTreeMap<Vec<u8>, Vec<u8>>;
+
+/// A [`StateBackend`] that keeps every namespace in an ordered map on the heap
+///
+/// Fast, but bounded by RAM and lost when the process exits.
+#[derive(Debug, Default)]
+pub struct MemoryStateBackend {
+    namespaces: RwLock<HashMap<String, Namespace>>,
+}
+
+impl MemoryStateBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Entries of an ordered map whose keys start with `prefix`
+pub(super) fn prefix_range<'a, V>(
+    map: &'a BTreeMap<Vec<u8>, V>,
+    prefix: &'a [u8],
+) -> impl Iterator<Item = (&'a Vec<u8>, &'a V)> + 'a {
+    map.range::<[u8], _>((Bound::Included(prefix), Bound::Unbounded))
+        .take_while(move |(key, _)| key.starts_with(prefix))
+}
+
+impl StateBackend for MemoryStateBackend {
+    fn kind(&self) -> StateBackendKind {
+        StateBackendKind::Memory
+    }
+
+    fn get(&self, namespace: &str, key: &[u8]) -> Result<Option<Vec<u8>>, SqlError> {
+        let namespaces = self
+            .namespaces
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+        Ok(namespaces
+            .get(namespace)
+            .and_then(|entries| entries.get(key))
+            .cloned())
+    }
+
+    fn put(&self, namespace: &str, key: &[u8], value: &[u8]) -> Result<(), SqlError> {
+        let mut namespaces = self
+            .namespaces
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        namespaces
+            .entry(namespace.to_string())
+            .or_default()
+            .insert(key.to_vec(), value.to_vec());
+        Ok(())
+    }
+
+    fn delete(&self, namespace: &str, key: &[u8]) -> Result<(), SqlError> {
+        let mut namespaces = self
+            .namespaces
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(entries) = namespaces.get_mut(namespace) {
+            entries.remove(key);
+            if entries.is_empty() {
+                namespaces.remove(namespace);
+            }
+        }
+        Ok(())
+    }
+
+    fn scan_prefix(&self, namespace: &str, prefix: &[u8]) -> Result<Vec<StateEntry>, SqlError> {
+        let namespaces = self
+            .namespaces
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+        Ok(namespaces.get(namespace).map_or_else(Vec::new, |entries| {
+            prefix_range(entries, prefix)
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect()
+        }))
+    }
+
+    fn clear(&self, namespace: &str) -> Result<(), SqlError> {
+        self.namespaces
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(namespace);
+        Ok(())
+    }
+
+    fn namespaces(&self) -> Vec<String> {
+        let namespaces = self
+            .namespaces
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+        let mut names: Vec<String> = namespaces.keys().cloned().collect();
+        names.sort();
+        names
+    }
+}
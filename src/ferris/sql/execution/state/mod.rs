@@ -0,0 +1,502 @@
+//! Pluggable backends for keyed operator state.
+//!
+//! GROUP BY accumulators, the rows of MERGE/UPSERT tables and the record buffers of
+//! windowed queries can be kept in a [`StateBackend`] instead of the operator's own maps and
+//! vectors. A backend stores opaque bytes under `(namespace, key)`; operators use the typed
+//! [`ValueState`], [`ListState`] and [`MapState`] handles on top of it, with keys and values
+//! encoded by [`StateCodec`].
+//!
+//! ## Backends
+//!
+//! | `state.backend` | Backend | Keys | Values |
+//! |-----------------|---------|------|--------|
+//! | `memory` | [`MemoryStateBackend`] | heap | heap |
+//! | `disk` | [`LogStateBackend`], an append-only log under `state.dir` | heap | disk |
+//!
+//! The disk backend holds only keys and value positions in memory, so aggregations over
+//! many large groups are bounded by disk rather than RAM, and its state survives a restart.
+//! Operators without a configured backend keep their state in plain maps.
+//!
+//! ## Configuration
+//!
+//! Jobs pick backends through their properties. `state.backend` applies to every operator
+//! and `state.backend.<operator>` overrides it for one of them (`group_by`, `merge`,
+//! `window`); the
+//! disk backend of each operator lives in `<state.dir>/<job>/<operator>`:
+//!
+//! ```sql
+//! CREATE STREAM customer_totals AS
+//! SELECT customer_id, COUNT(*) AS orders, SUM(amount) AS total
+//! FROM orders
+//! GROUP BY customer_id
+//! WITH ('state.backend.group_by' = 'disk', 'state.dir' = '/var/lib/ferris/state');
+//! ```
+//!
+//! A window buffer in a backend is a [`ListState`] under its query and takes the place of
+//! spilling under the job's memory budget. Hash joins build their table for each batch and
+//! drop it afterwards, so there is no join state to back.
+//! Keys of both kinds of operator state can expire after a `state.ttl`, see [`ttl`].
+
+pub mod codec;
+mod disk;
+mod memory;
+pub mod ttl;
+
+pub use codec::StateCodec;
+pub use disk::{LogStateBackend, DEFAULT_COMPACTION_THRESHOLD_BYTES};
+pub use memory::MemoryStateBackend;
+pub use ttl::{StateMetrics, StateTtl, StateTtlConfig};
+
+use crate::ferris::sql::error::SqlError;
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Key and value bytes of one entry of a namespace
+pub type StateEntry = (Vec<u8>, Vec<u8>);
+
+/// Job property selecting the backend of every stateful operator
+pub const STATE_BACKEND_PROPERTY: &str = "state.backend";
+
+/// Job property naming the directory of disk backends
+pub const STATE_DIR_PROPERTY: &str = "state.dir";
+
+/// Storage for operator state: opaque values under `(namespace, key)`
+///
+/// Backends are shared between the engine and the processor context of every record, so
+/// writes go through `&self`. Keys of a namespace are kept in byte order.
+pub trait StateBackend: Send + Sync {
+    /// Which kind of backend this is
+    fn kind(&self) -> StateBackendKind;
+
+    /// Value stored under `key`
+    fn get(&self, namespace: &str, key: &[u8]) -> Result<Option<Vec<u8>>, SqlError>;
+
+    /// Store `value` under `key`, replacing any previous value
+    fn put(&self, namespace: &str, key: &[u8], value: &[u8]) -> Result<(), SqlError>;
+
+    /// Remove `key`; removing a missing key is not an error
+    fn delete(&self, namespace: &str, key: &[u8]) -> Result<(), SqlError>;
+
+    /// Entries whose key starts with `prefix`, in key order
+    fn scan_prefix(&self, namespace: &str, prefix: &[u8]) -> Result<Vec<StateEntry>, SqlError>;
+
+    /// Remove every entry of a namespace
+    fn clear(&self, namespace: &str) -> Result<(), SqlError>;
+
+    /// Namespaces holding at least one entry, sorted
+    fn namespaces(&self) -> Vec<String>;
+
+    /// Make every write so far durable
+    fn flush(&self) -> Result<(), SqlError> {
+        Ok(())
+    }
+}
+
+/// Available state backends
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StateBackendKind {
+    /// [`MemoryStateBackend`]
+    Memory,
+    /// [`LogStateBackend`]
+    Disk,
+}
+
+impl StateBackendKind {
+    /// Parse a `state.backend` property value
+    pub fn parse(value: &str) -> Result<Self, SqlError> {
+        match value.trim().to_lowercase().as_str() {
+            "memory" | "heap" => Ok(Self::Memory),
+            "disk" | "log" => Ok(Self::Disk),
+            _ => Err(SqlError::ConfigurationError {
+                message: format!(
+                    "Invalid state backend '{}': expected 'memory' or 'disk'",
+                    value
+                ),
+            }),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Memory => "memory",
+            Self::Disk => "disk",
+        }
+    }
+}
+
+/// Stateful operators, whose keys expire under a [state TTL](ttl)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StateOperator {
+    /// GROUP BY accumulators, one value per group
+    GroupBy,
+    /// Current rows of MERGE and UPSERT targets, one value per upsert key
+    Merge,
+    /// Records buffered by a windowed query, one key per query
+    Window,
+}
+
+impl StateOperator {
+    /// Operators that can keep their state in a backend
+    pub const ALL: [StateOperator; 3] = [
+        StateOperator::GroupBy,
+        StateOperator::Merge,
+        StateOperator::Window,
+    ];
+
+    /// Operators whose backend holds one entry per state key; a window buffer is one key
+    /// stored as many list entries
+    pub const KEYED: [StateOperator; 2] = [StateOperator::GroupBy, StateOperator::Merge];
+
+    /// Name used in `state.backend.<operator>` and in state directories
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::GroupBy => "group_by",
+            Self::Merge => "merge",
+            Self::Window => "window",
+        }
+    }
+
+    /// The `state.backend.<operator>` property
+    pub fn property(&self) -> String {
+        format!("{}.{}", STATE_BACKEND_PROPERTY, self.as_str())
+    }
+
+    /// Namespace of one instance of the operator, e.g. the MERGE target `inventory`
+    pub fn namespace(&self, instance: &str) -> String {
+        format!("{}/{}", self.as_str(), instance)
+    }
+}
+
+/// Backend choice of every stateful operator of a job
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StateBackendConfig {
+    /// Backend of operators without their own setting; `None` keeps state in plain maps
+    pub default: Option<StateBackendKind>,
+    /// Per-operator overrides
+    pub operators: HashMap<StateOperator, StateBackendKind>,
+    /// Root directory of disk backends
+    pub directory: Option<PathBuf>,
+}
+
+impl StateBackendConfig {
+    /// Read `state.backend`, `state.backend.<operator>` and `state.dir` from job properties
+    pub fn from_properties(properties: &HashMap<String, String>) -> Result<Self, SqlError> {
+        let mut config = Self {
+            default: properties
+                .get(STATE_BACKEND_PROPERTY)
+                .map(|value| StateBackendKind::parse(value))
+                .transpose()?,
+            operators: HashMap::new(),
+            directory: properties.get(STATE_DIR_PROPERTY).map(PathBuf::from),
+        };
+        for operator in StateOperator::ALL {
+            if let Some(value) = properties.get(&operator.property()) {
+                config
+                    .operators
+                    .insert(operator, StateBackendKind::parse(value)?);
+            }
+        }
+        if let Some(key) = properties.keys().find(|key| {
+            key.starts_with(&format!("{}.", STATE_BACKEND_PROPERTY))
+                && !StateOperator::ALL.iter().any(|op| **key == op.property())
+        }) {
+            return Err(SqlError::ConfigurationError {
+                message: format!(
+                    "Unknown state backend property '{}': operators are {}",
+                    key,
+                    StateOperator::ALL.map(|op| op.as_str()).join(", ")
+                ),
+            });
+        }
+        if config.directory.is_none()
+            && StateOperator::ALL
+                .iter()
+                .any(|op| config.backend_for(*op) == Some(StateBackendKind::Disk))
+        {
+            return Err(SqlError::ConfigurationError {
+                message: format!("The disk state backend requires '{}'", STATE_DIR_PROPERTY),
+            });
+        }
+        Ok(config)
+    }
+
+    /// Backend kind an operator uses, `None` for plain maps
+    pub fn backend_for(&self, operator: StateOperator) -> Option<StateBackendKind> {
+        self.operators.get(&operator).copied().or(self.default)
+    }
+
+    /// Open the backends of a job; disk backends live under `<state.dir>/<job>/<operator>`
+    pub fn open(&self, job: &str) -> Result<StateBackends, SqlError> {
+        let mut backends = StateBackends::new();
+        for operator in StateOperator::ALL {
+            let backend: Arc<dyn StateBackend> = match self.backend_for(operator) {
+                None => continue,
+                Some(StateBackendKind::Memory) => Arc::new(MemoryStateBackend::new()),
+                Some(StateBackendKind::Disk) => {
+                    let root =
+                        self.directory
+                            .as_ref()
+                            .ok_or_else(|| SqlError::ConfigurationError {
+                                message: format!(
+                                    "The disk state backend requires '{}'",
+                                    STATE_DIR_PROPERTY
+                                ),
+                            })?;
+                    Arc::new(LogStateBackend::open(
+                        root.join(job).join(operator.as_str()),
+                    )?)
+                }
+            };
+            backends = backends.with_backend(operator, backend);
+        }
+        Ok(backends)
+    }
+}
+
+/// The backends of a job's stateful operators, shared by the engine and its processor contexts
+#[derive(Clone, Default)]
+pub struct StateBackends {
+    backends: HashMap<StateOperator, Arc<dyn StateBackend>>,
+}
+
+impl fmt::Debug for StateBackends {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.backends.iter().map(|(op, b)| (op, b.kind())))
+            .finish()
+    }
+}
+
+impl StateBackends {
+    /// No backends: every operator keeps its state in plain maps
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use `backend` for `operator`
+    pub fn with_backend(mut self, operator: StateOperator, backend: Arc<dyn StateBackend>) -> Self {
+        self.backends.insert(operator, backend);
+        self
+    }
+
+    /// Backend of an operator, if it has one
+    pub fn get(&self, operator: StateOperator) -> Option<&Arc<dyn StateBackend>> {
+        self.backends.get(&operator)
+    }
+
+    /// Make every write of every backend durable
+    pub fn flush(&self) -> Result<(), SqlError> {
+        self.backends
+            .values()
+            .try_for_each(|backend| backend.flush())
+    }
+}
+
+/// One value per key
+pub struct ValueState<K, V> {
+    backend: Arc<dyn StateBackend>,
+    namespace: String,
+    _types: PhantomData<fn(K) -> V>,
+}
+
+impl<K: StateCodec, V: StateCodec> ValueState<K, V> {
+    pub fn new(backend: Arc<dyn StateBackend>, namespace: impl Into<String>) -> Self {
+        Self {
+            backend,
+            namespace: namespace.into(),
+            _types: PhantomData,
+        }
+    }
+
+    pub fn get(&self, key: &K) -> Result<Option<V>, SqlError> {
+        self.backend
+            .get(&self.namespace, &key.to_state_bytes())?
+            .map(|bytes| V::from_state_bytes(&bytes))
+            .transpose()
+    }
+
+    pub fn set(&self, key: &K, value: &V) -> Result<(), SqlError> {
+        self.backend.put(
+            &self.namespace,
+            &key.to_state_bytes(),
+            &value.to_state_bytes(),
+        )
+    }
+
+    pub fn remove(&self, key: &K) -> Result<(), SqlError> {
+        self.backend.delete(&self.namespace, &key.to_state_bytes())
+    }
+
+    /// Every key and value, in key byte order
+    pub fn entries(&self) -> Result<Vec<(K, V)>, SqlError> {
+        self.backend
+            .scan_prefix(&self.namespace, &[])?
+            .into_iter()
+            .map(|(key, value)| Ok((K::from_state_bytes(&key)?, V::from_state_bytes(&value)?)))
+            .collect()
+    }
+
+    /// Whether the namespace holds any value
+    pub fn is_empty(&self) -> Result<bool, SqlError> {
+        Ok(self.backend.scan_prefix(&self.namespace, &[])?.is_empty())
+    }
+
+    pub fn clear(&self) -> Result<(), SqlError> {
+        self.backend.clear(&self.namespace)
+    }
+}
+
+/// An append-only list of values per key, stored one entry per element
+#[derive(Clone)]
+pub struct ListState<K, V> {
+    backend: Arc<dyn StateBackend>,
+    namespace: String,
+    _types: PhantomData<fn(K) -> V>,
+}
+
+/// Suffix of the entry holding a list's length
+const LIST_LENGTH_TAG: u8 = 0;
+/// Suffix of element entries, followed by the element's big-endian position
+const LIST_ELEMENT_TAG: u8 = 1;
+
+impl<K, V> fmt::Debug for ListState<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ListState")
+            .field("backend", &self.backend.kind())
+            .field("namespace", &self.namespace)
+            .finish()
+    }
+}
+
+impl<K: StateCodec, V: StateCodec> ListState<K, V> {
+    pub fn new(backend: Arc<dyn StateBackend>, namespace: impl Into<String>) -> Self {
+        Self {
+            backend,
+            namespace: namespace.into(),
+            _types: PhantomData,
+        }
+    }
+
+    fn length_key(key: &K) -> Vec<u8> {
+        let mut bytes = key.to_state_bytes();
+        bytes.push(LIST_LENGTH_TAG);
+        bytes
+    }
+
+    pub fn len(&self, key: &K) -> Result<u64, SqlError> {
+        self.backend
+            .get(&self.namespace, &Self::length_key(key))?
+            .map_or(Ok(0), |bytes| u64::from_state_bytes(&bytes))
+    }
+
+    pub fn is_empty(&self, key: &K) -> Result<bool, SqlError> {
+        Ok(self.len(key)? == 0)
+    }
+
+    /// Append a value without reading the existing elements
+    pub fn add(&self, key: &K, value: &V) -> Result<(), SqlError> {
+        let position = self.len(key)?;
+        let mut element_key = key.to_state_bytes();
+        element_key.push(LIST_ELEMENT_TAG);
+        position.encode(&mut element_key);
+        self.backend
+            .put(&self.namespace, &element_key, &value.to_state_bytes())?;
+        self.backend.put(
+            &self.namespace,
+            &Self::length_key(key),
+            &(position + 1).to_state_bytes(),
+        )
+    }
+
+    /// Elements in insertion order
+    pub fn get(&self, key: &K) -> Result<Vec<V>, SqlError> {
+        let mut prefix = key.to_state_bytes();
+        prefix.push(LIST_ELEMENT_TAG);
+        self.backend
+            .scan_prefix(&self.namespace, &prefix)?
+            .into_iter()
+            .map(|(_, value)| V::from_state_bytes(&value))
+            .collect()
+    }
+
+    pub fn clear(&self, key: &K) -> Result<(), SqlError> {
+        for (entry_key, _) in self
+            .backend
+            .scan_prefix(&self.namespace, &key.to_state_bytes())?
+        {
+            self.backend.delete(&self.namespace, &entry_key)?;
+        }
+        Ok(())
+    }
+}
+
+/// A map of values per key
+pub struct MapState<K, MK, V> {
+    backend: Arc<dyn StateBackend>,
+    namespace: String,
+    _types: PhantomData<fn(K, MK) -> V>,
+}
+
+impl<K: StateCodec, MK: StateCodec, V: StateCodec> MapState<K, MK, V> {
+    pub fn new(backend: Arc<dyn StateBackend>, namespace: impl Into<String>) -> Self {
+        Self {
+            backend,
+            namespace: namespace.into(),
+            _types: PhantomData,
+        }
+    }
+
+    fn entry_key(key: &K, map_key: &MK) -> Vec<u8> {
+        let mut bytes = key.to_state_bytes();
+        map_key.encode(&mut bytes);
+        bytes
+    }
+
+    pub fn get(&self, key: &K, map_key: &MK) -> Result<Option<V>, SqlError> {
+        self.backend
+            .get(&self.namespace, &Self::entry_key(key, map_key))?
+            .map(|bytes| V::from_state_bytes(&bytes))
+            .transpose()
+    }
+
+    pub fn put(&self, key: &K, map_key: &MK, value: &V) -> Result<(), SqlError> {
+        self.backend.put(
+            &self.namespace,
+            &Self::entry_key(key, map_key),
+            &value.to_state_bytes(),
+        )
+    }
+
+    pub fn remove(&self, key: &K, map_key: &MK) -> Result<(), SqlError> {
+        self.backend
+            .delete(&self.namespace, &Self::entry_key(key, map_key))
+    }
+
+    /// Entries of one key, in map key byte order
+    pub fn entries(&self, key: &K) -> Result<Vec<(MK, V)>, SqlError> {
+        let prefix = key.to_state_bytes();
+        self.backend
+            .scan_prefix(&self.namespace, &prefix)?
+            .into_iter()
+            .map(|(entry_key, value)| {
+                Ok((
+                    MK::from_state_bytes(&entry_key[prefix.len()..])?,
+                    V::from_state_bytes(&value)?,
+                ))
+            })
+            .collect()
+    }
+
+    pub fn clear(&self, key: &K) -> Result<(), SqlError> {
+        for (entry_key, _) in self
+            .backend
+            .scan_prefix(&self.namespace, &key.to_state_bytes())?
+        {
+            self.backend.delete(&self.namespace, &entry_key)?;
+        }
+        Ok(())
+    }
+}
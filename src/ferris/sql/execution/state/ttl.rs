@@ -0,0 +1,303 @@
+//! Time-to-live of keyed operator state.
+//!
+//! GROUP BY accumulators and MERGE/UPSERT rows are kept per key for as long as the job
+//! runs, so keys such as user or session IDs grow state without bound. With `state.ttl`
+//! set, a key that was not updated within the TTL is evicted: its accumulator or row is
+//! dropped from the operator's map and from its [state backend](super). A record for an
+//! evicted key starts it again from scratch.
+//!
+//! UPSERT keeps the latest row per key, so it also serves as the deduplication state of a
+//! job and expires the same way. The record buffer of a windowed query is one key: when
+//! no record reached the query within the TTL, its buffered records are dropped, and
+//! `final` emits their result; a window emits nothing before it closes, so `retract`
+//! emits nothing for it. Hash joins build their table from the right side's current
+//! records for each batch and drop it afterwards, so no join buffer outlives a batch.
+//!
+//! ## Configuration
+//!
+//! | Property | Default | |
+//! |----------|---------|-|
+//! | `state.ttl` | none | Time a key lives without updates, e.g. `30m`; state never expires without it |
+//! | `state.ttl.time` | `processing` | `processing`: wall-clock time of the update; `event`: record timestamps, measured against the latest one seen |
+//! | `state.ttl.emit` | `none` | `final`: emit the last result of an evicted key; `retract`: emit a `DELETE` changelog record for it |
+//!
+//! Expired keys are found as records arrive, at most every quarter of the TTL, so a key
+//! may outlive its TTL by that much. Emitted records carry the `state.evicted` header.
+//! Checkpoints and savepoints do not store update times; restored keys start a full TTL
+//! when the job resumes.
+//!
+//! ```sql
+//! CREATE STREAM session_totals AS
+//! SELECT session_id, COUNT(*) AS clicks FROM clicks GROUP BY session_id
+//! WITH ('state.ttl' = '30m', 'state.ttl.time' = 'event', 'state.ttl.emit' = 'final');
+//! ```
+
+use super::StateOperator;
+use crate::ferris::sql::error::SqlError;
+use crate::ferris::sql::execution::subquery::parse_interval;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Job property with the time a key of keyed state lives without updates
+pub const STATE_TTL_PROPERTY: &str = "state.ttl";
+
+/// Job property choosing processing or event time for the TTL
+pub const STATE_TTL_TIME_PROPERTY: &str = "state.ttl.time";
+
+/// Job property choosing what is emitted for an evicted key
+pub const STATE_TTL_EMIT_PROPERTY: &str = "state.ttl.emit";
+
+/// Header marking records emitted for an evicted key
+pub const EVICTED_HEADER: &str = "state.evicted";
+
+/// Clock the TTL of state is measured with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TtlTimeDomain {
+    /// Wall-clock time at which a key is updated
+    #[default]
+    Processing,
+    /// Timestamp of the record updating a key, against the latest timestamp seen
+    Event,
+}
+
+/// Output for a key when it is evicted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EvictionOutput {
+    /// Drop the key silently
+    #[default]
+    None,
+    /// Emit the last result of the key
+    Final,
+    /// Emit a `DELETE` changelog record withdrawing the key's result
+    Retract,
+}
+
+/// TTL settings of a job
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateTtlConfig {
+    pub ttl: Duration,
+    pub time: TtlTimeDomain,
+    pub emit: EvictionOutput,
+}
+
+impl StateTtlConfig {
+    /// Evict keys not updated within `ttl` of processing time, emitting nothing
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            time: TtlTimeDomain::default(),
+            emit: EvictionOutput::default(),
+        }
+    }
+
+    pub fn with_time(mut self, time: TtlTimeDomain) -> Self {
+        self.time = time;
+        self
+    }
+
+    pub fn with_emit(mut self, emit: EvictionOutput) -> Self {
+        self.emit = emit;
+        self
+    }
+
+    /// Read `state.ttl`, `state.ttl.time` and `state.ttl.emit` from job properties;
+    /// `None` when state does not expire
+    pub fn from_properties(properties: &HashMap<String, String>) -> Result<Option<Self>, SqlError> {
+        let Some(ttl) = properties.get(STATE_TTL_PROPERTY) else {
+            if let Some(property) = [STATE_TTL_TIME_PROPERTY, STATE_TTL_EMIT_PROPERTY]
+                .into_iter()
+                .find(|property| properties.contains_key(*property))
+            {
+                return Err(SqlError::ConfigurationError {
+                    message: format!("'{}' requires '{}'", property, STATE_TTL_PROPERTY),
+                });
+            }
+            return Ok(None);
+        };
+
+        let ttl = parse_interval(&ttl.trim().to_lowercase())
+            .filter(|ttl| !ttl.is_zero())
+            .ok_or_else(|| SqlError::ConfigurationError {
+                message: format!(
+                    "Invalid {} '{}': expected a positive duration such as '30m'",
+                    STATE_TTL_PROPERTY, ttl
+                ),
+            })?;
+        let mut config = Self::new(ttl);
+        if let Some(value) = properties.get(STATE_TTL_TIME_PROPERTY) {
+            config.time = match value.trim().to_lowercase().as_str() {
+                "processing" => TtlTimeDomain::Processing,
+                "event" => TtlTimeDomain::Event,
+                _ => {
+                    return Err(SqlError::ConfigurationError {
+                        message: format!(
+                            "Invalid {} '{}': expected 'processing' or 'event'",
+                            STATE_TTL_TIME_PROPERTY, value
+                        ),
+                    })
+                }
+            };
+        }
+        if let Some(value) = properties.get(STATE_TTL_EMIT_PROPERTY) {
+            config.emit = match value.trim().to_lowercase().as_str() {
+                "none" => EvictionOutput::None,
+                "final" => EvictionOutput::Final,
+                "retract" => EvictionOutput::Retract,
+                _ => {
+                    return Err(SqlError::ConfigurationError {
+                        message: format!(
+                            "Invalid {} '{}': expected 'none', 'final' or 'retract'",
+                            STATE_TTL_EMIT_PROPERTY, value
+                        ),
+                    })
+                }
+            };
+        }
+        Ok(Some(config))
+    }
+}
+
+/// A key of keyed state: its operator, namespace and encoded key
+pub type StateKey = (StateOperator, String, Vec<u8>);
+
+/// Last update of every key of keyed state, which finds the keys whose TTL ran out
+///
+/// Keys are tracked by operator, [namespace](StateOperator::namespace) and their
+/// [`StateCodec`](super::StateCodec) encoding, as in state backends.
+#[derive(Debug, Clone)]
+pub struct StateTtl {
+    config: StateTtlConfig,
+    /// Update time by namespace and key; `None` for restored keys, whose TTL starts at
+    /// the next sweep
+    updated: HashMap<(StateOperator, String), HashMap<Vec<u8>, Option<i64>>>,
+    /// Latest event time seen
+    watermark: i64,
+    /// Time of the next sweep for expired keys
+    next_sweep: i64,
+    /// Keys evicted so far, by namespace
+    evicted: HashMap<(StateOperator, String), u64>,
+}
+
+impl StateTtl {
+    pub fn new(config: StateTtlConfig) -> Self {
+        Self {
+            config,
+            updated: HashMap::new(),
+            watermark: i64::MIN,
+            next_sweep: i64::MIN,
+            evicted: HashMap::new(),
+        }
+    }
+
+    pub fn config(&self) -> &StateTtlConfig {
+        &self.config
+    }
+
+    /// Current time of the TTL clock in milliseconds
+    pub fn now(&self) -> i64 {
+        match self.config.time {
+            TtlTimeDomain::Processing => chrono::Utc::now().timestamp_millis(),
+            TtlTimeDomain::Event => self.watermark,
+        }
+    }
+
+    /// Record an update of a key by a record with timestamp `event_time`
+    pub fn touch(&mut self, operator: StateOperator, namespace: &str, key: &[u8], event_time: i64) {
+        self.watermark = self.watermark.max(event_time);
+        let updated_at = match self.config.time {
+            TtlTimeDomain::Processing => self.now(),
+            TtlTimeDomain::Event => event_time,
+        };
+        let keys = self.namespace_keys(operator, namespace);
+        let last = keys.entry(key.to_vec()).or_insert(Some(updated_at));
+        // Late records do not shorten the life of a key
+        *last = Some(last.map_or(updated_at, |last| last.max(updated_at)));
+    }
+
+    /// Track a key restored from a checkpoint, or present before the TTL was set
+    pub fn track_restored(&mut self, operator: StateOperator, namespace: &str, key: &[u8]) {
+        self.namespace_keys(operator, namespace)
+            .entry(key.to_vec())
+            .or_insert(None);
+    }
+
+    /// Stop tracking a key its operator removed
+    pub fn forget(&mut self, operator: StateOperator, namespace: &str, key: &[u8]) {
+        if let Some(keys) = self.updated.get_mut(&(operator, namespace.to_string())) {
+            keys.remove(key);
+        }
+    }
+
+    /// Stop tracking every key
+    pub fn clear(&mut self) {
+        self.updated.clear();
+    }
+
+    /// Keys whose TTL ran out, once a sweep is due; they are no longer tracked and count
+    /// as evicted
+    pub fn take_expired(&mut self) -> Vec<StateKey> {
+        let now = self.now();
+        if now < self.next_sweep {
+            return Vec::new();
+        }
+        let ttl = self.config.ttl.as_millis().min(i64::MAX as u128) as i64;
+        self.next_sweep = now.saturating_add((ttl / 4).max(1));
+
+        let mut expired = Vec::new();
+        for ((operator, namespace), keys) in &mut self.updated {
+            keys.retain(|key, updated_at| match updated_at {
+                None => {
+                    *updated_at = Some(now);
+                    true
+                }
+                Some(updated_at) if now.saturating_sub(*updated_at) >= ttl => {
+                    expired.push((*operator, namespace.clone(), key.clone()));
+                    false
+                }
+                Some(_) => true,
+            });
+        }
+        for (operator, namespace, _) in &expired {
+            *self
+                .evicted
+                .entry((*operator, namespace.clone()))
+                .or_default() += 1;
+        }
+        expired
+    }
+
+    /// Keys evicted so far from a namespace
+    pub fn evicted(&self, operator: StateOperator, namespace: &str) -> u64 {
+        self.evicted
+            .get(&(operator, namespace.to_string()))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Namespaces keys were evicted from
+    pub fn evicted_namespaces(&self) -> impl Iterator<Item = &(StateOperator, String)> {
+        self.evicted.keys()
+    }
+
+    fn namespace_keys(
+        &mut self,
+        operator: StateOperator,
+        namespace: &str,
+    ) -> &mut HashMap<Vec<u8>, Option<i64>> {
+        self.updated
+            .entry((operator, namespace.to_string()))
+            .or_default()
+    }
+}
+
+/// Size of the keyed state of one operator instance, see
+/// [`StreamExecutionEngine::state_metrics`](crate::ferris::sql::execution::StreamExecutionEngine::state_metrics)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateMetrics {
+    pub operator: StateOperator,
+    pub namespace: String,
+    /// Keys currently held
+    pub keys: usize,
+    /// Keys evicted by the TTL so far
+    pub evicted: u64,
+}
@@ -0,0 +1,939 @@
+//! Subqueries evaluated against materialized table state and lookup sources.
+//!
+//! `IN (SELECT ...)`, `EXISTS (...)`, scalar, `ANY` and `ALL` subqueries read the rows of the
+//! source named in their FROM clause, resolved in this order:
+//!
+//! 1. a materialized table: the current rows of a MERGE or UPSERT target
+//! 2. a [`LookupSource`] registered with
+//!    [`StreamExecutionEngine::register_lookup_source`](super::StreamExecutionEngine::register_lookup_source)
+//! 3. records preloaded into [`ProcessorContext::data_sources`]
+//!
+//! Every job on the [`StreamJobServer`](crate::ferris::server::StreamJobServer) has its own
+//! engine, so its subqueries read the tables the job itself materializes and the lookup
+//! sources registered with the server; a deploy whose subqueries read anything else fails,
+//! see [`subquery_sources`].
+//!
+//! A subquery without FROM evaluates its fields once against the outer record.
+//!
+//! ## Correlation
+//!
+//! Columns qualified with a name other than the subquery's own source or alias are
+//! correlation variables, bound from the outer record:
+//!
+//! ```sql
+//! SELECT * FROM orders o
+//! WHERE o.amount > (SELECT AVG(amount) FROM order_history h WHERE h.customer_id = o.customer_id)
+//! ```
+//!
+//! Unqualified columns resolve against the subquery's source. A correlated subquery is
+//! evaluated again for every outer record; when its WHERE clause equates a source column with
+//! a correlation variable, a lookup source indexed on that column is probed with
+//! [`LookupSource::lookup`] instead of scanned.
+//!
+//! ## Uncorrelated subqueries
+//!
+//! An uncorrelated subquery is evaluated once and its result kept in the [`SubqueryCache`]:
+//! `IN` and `EXISTS` become hash semi-joins against the cached values, `NOT IN` and
+//! `NOT EXISTS` anti-joins. The subquery's `subquery.refresh` property picks when the cached
+//! result is recomputed:
+//!
+//! | Policy | Behaviour |
+//! |--------|-----------|
+//! | `on_change` (default) | After a write to the materialized table, or when [`LookupSource::version`] changes |
+//! | `<duration>`, e.g. `30s` | When the result is older than the duration, for sources that do not report changes |
+//! | `once` | Never; the first result is kept for the life of the query |
+//!
+//! ```sql
+//! SELECT * FROM payments
+//! WHERE account_id NOT IN (SELECT account_id FROM blocked_accounts WITH ('subquery.refresh' = '5m'))
+//! ```
+
+use super::aggregation::{AccumulatorManager, AggregateFunctions, GroupByStateManager};
+use super::expression::{ExpressionEvaluator, SubqueryExecutor};
+use super::internal::GroupAccumulator;
+use super::prepared::for_each_expr;
+use super::processors::ProcessorContext;
+use super::types::{FieldValue, StreamRecord};
+use crate::ferris::sql::ast::{
+    BinaryOperator, Expr, NodeSpan, SelectField, StreamSource, StreamingQuery, SubqueryType,
+};
+use crate::ferris::sql::error::SqlError;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+use std::time::{Duration, Instant};
+
+/// Subquery property that selects the [`SubqueryRefreshPolicy`]
+pub const SUBQUERY_REFRESH_PROPERTY: &str = "subquery.refresh";
+
+/// Plans kept before the cache is cleared, bounding memory for ad-hoc query workloads
+const MAX_CACHED_SUBQUERIES: usize = 256;
+
+/// When the cached result of an uncorrelated subquery is recomputed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SubqueryRefreshPolicy {
+    /// When the source changes
+    #[default]
+    OnChange,
+    /// When the cached result is older than the interval
+    Interval(Duration),
+    /// Never
+    Once,
+}
+
+impl SubqueryRefreshPolicy {
+    /// Parse a `subquery.refresh` property value (case-insensitive)
+    pub fn parse(value: &str) -> Result<Self, SqlError> {
+        let normalized = value.trim().to_lowercase();
+        match normalized.as_str() {
+            "on_change" | "change" => Ok(SubqueryRefreshPolicy::OnChange),
+            "once" | "never" => Ok(SubqueryRefreshPolicy::Once),
+            _ => parse_interval(&normalized)
+                .map(SubqueryRefreshPolicy::Interval)
+                .ok_or_else(|| SqlError::ConfigurationError {
+                    message: format!(
+                        "Invalid {} '{}': expected 'on_change', 'once' or a duration such as '30s'",
+                        SUBQUERY_REFRESH_PROPERTY, value
+                    ),
+                }),
+        }
+    }
+}
+
+/// Parse a duration such as `500ms`, `30s`, `5m`, `2h` or `1d`; a bare number is in seconds
+pub(crate) fn parse_interval(value: &str) -> Option<Duration> {
+    let digits = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(digits);
+    let amount: u64 = amount.parse().ok()?;
+    let seconds = match unit.trim() {
+        "ms" => return Some(Duration::from_millis(amount)),
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        _ => return None,
+    };
+    Some(Duration::from_secs(amount.checked_mul(seconds)?))
+}
+
+/// Reference data a subquery can read, such as a table loaded from a database or a file
+pub trait LookupSource: Send + Sync {
+    /// Every row of the source
+    fn scan(&self) -> Result<Vec<StreamRecord>, SqlError>;
+
+    /// Rows whose `column` equals `key`, or `None` when the source has no index on `column`
+    fn lookup(
+        &self,
+        column: &str,
+        key: &FieldValue,
+    ) -> Result<Option<Vec<StreamRecord>>, SqlError> {
+        let _ = (column, key);
+        Ok(None)
+    }
+
+    /// Changes whenever the rows change, so cached `on_change` results are recomputed
+    fn version(&self) -> u64 {
+        0
+    }
+}
+
+/// A [`LookupSource`] over rows held in memory, with hash indexes on chosen columns
+#[derive(Debug, Default)]
+pub struct InMemoryLookupSource {
+    rows: RwLock<IndexedRows>,
+    version: AtomicU64,
+}
+
+#[derive(Debug, Default)]
+struct IndexedRows {
+    rows: Vec<StreamRecord>,
+    /// Column -> value key -> positions of the rows holding that value
+    indexes: HashMap<String, HashMap<String, Vec<usize>>>,
+}
+
+impl IndexedRows {
+    fn reindex(&mut self) {
+        for (column, index) in self.indexes.iter_mut() {
+            index.clear();
+            for (position, row) in self.rows.iter().enumerate() {
+                match row.fields.get(column) {
+                    None | Some(FieldValue::Null) => {}
+                    Some(value) => index.entry(value_key(value)).or_default().push(position),
+                }
+            }
+        }
+    }
+}
+
+impl InMemoryLookupSource {
+    pub fn new(rows: Vec<StreamRecord>) -> Self {
+        Self {
+            rows: RwLock::new(IndexedRows {
+                rows,
+                indexes: HashMap::new(),
+            }),
+            version: AtomicU64::new(0),
+        }
+    }
+
+    /// Index `column`, so correlated subqueries probe it instead of scanning every row
+    pub fn with_index(self, column: &str) -> Self {
+        {
+            let mut rows = self.rows.write().unwrap_or_else(PoisonError::into_inner);
+            rows.indexes.insert(column.to_string(), HashMap::new());
+            rows.reindex();
+        }
+        self
+    }
+
+    /// Replace the rows, invalidating cached results of subqueries that read them
+    pub fn replace(&self, rows: Vec<StreamRecord>) {
+        let mut indexed = self.rows.write().unwrap_or_else(PoisonError::into_inner);
+        indexed.rows = rows;
+        indexed.reindex();
+        self.version.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+impl LookupSource for InMemoryLookupSource {
+    fn scan(&self) -> Result<Vec<StreamRecord>, SqlError> {
+        Ok(self
+            .rows
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .rows
+            .clone())
+    }
+
+    fn lookup(
+        &self,
+        column: &str,
+        key: &FieldValue,
+    ) -> Result<Option<Vec<StreamRecord>>, SqlError> {
+        let indexed = self.rows.read().unwrap_or_else(PoisonError::into_inner);
+        let Some(index) = indexed.indexes.get(column) else {
+            return Ok(None);
+        };
+        let rows = index
+            .get(&value_key(key))
+            .into_iter()
+            .flatten()
+            .map(|&position| &indexed.rows[position])
+            .filter(|row| {
+                row.fields
+                    .get(column)
+                    .is_some_and(|value| ExpressionEvaluator::values_equal(value, key))
+            })
+            .cloned()
+            .collect();
+        Ok(Some(rows))
+    }
+
+    fn version(&self) -> u64 {
+        self.version.load(Ordering::SeqCst)
+    }
+}
+
+/// Hash key of a value; values with equal keys are compared with `values_equal`
+fn value_key(value: &FieldValue) -> String {
+    GroupByStateManager::field_value_to_group_key(value)
+}
+
+/// How a subquery is evaluated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubqueryStrategy {
+    /// Uncorrelated IN or EXISTS: a hash semi-join against the cached result
+    SemiJoin,
+    /// Uncorrelated NOT IN or NOT EXISTS: a hash anti-join against the cached result
+    AntiJoin,
+    /// Uncorrelated scalar, ANY or ALL: compared with the cached values
+    CachedValues,
+    /// Evaluated again for every outer record
+    Correlated,
+}
+
+/// Where a subquery reads its rows from and which outer columns it binds
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubqueryPlan {
+    /// FROM source, `None` for `SELECT <expr>` without FROM
+    source: Option<String>,
+    /// Qualified columns bound from the outer record
+    outer_references: Vec<String>,
+    /// Source column equated with an outer reference in the WHERE clause
+    correlation_key: Option<(String, String)>,
+    /// Whether the subquery selects an aggregate and returns a single row
+    aggregate: bool,
+    /// Whether the subquery selects exactly one column
+    single_column: bool,
+    refresh: Option<SubqueryRefreshPolicy>,
+}
+
+impl SubqueryPlan {
+    /// Plan a subquery; it must be a SELECT from a named source without JOIN or GROUP BY
+    pub fn new(query: &StreamingQuery) -> Result<Self, SqlError> {
+        let StreamingQuery::Select {
+            fields,
+            from,
+            from_alias,
+            joins,
+            where_clause,
+            group_by,
+            having,
+            window,
+            properties,
+            ..
+        } = query
+        else {
+            return Err(plan_error(
+                "SUBQ-PLAN-001",
+                "Subquery must be a SELECT statement",
+            ));
+        };
+        if joins.as_ref().is_some_and(|joins| !joins.is_empty()) {
+            return Err(plan_error(
+                "SUBQ-PLAN-002",
+                "JOIN inside a subquery is not supported",
+            ));
+        }
+        if group_by.is_some() || having.is_some() || window.is_some() {
+            return Err(plan_error(
+                "SUBQ-PLAN-003",
+                "GROUP BY, HAVING and WINDOW inside a subquery are not supported",
+            ));
+        }
+        let source = match from {
+            StreamSource::Stream(name) | StreamSource::Table(name) if name.is_empty() => None,
+            StreamSource::Stream(name) | StreamSource::Table(name) => Some(name.clone()),
+            StreamSource::Uri(_) | StreamSource::Subquery(_) => {
+                return Err(plan_error(
+                    "SUBQ-PLAN-004",
+                    "Subquery must read a named table or lookup source",
+                ))
+            }
+        };
+        let refresh = properties
+            .as_ref()
+            .and_then(|properties| properties.get(SUBQUERY_REFRESH_PROPERTY))
+            .map(|value| SubqueryRefreshPolicy::parse(value))
+            .transpose()?;
+
+        let mut local: Vec<&str> = source.iter().map(String::as_str).collect();
+        local.extend(from_alias.as_deref());
+        let mut outer_references = Vec::new();
+        for field in fields {
+            match field {
+                SelectField::Column(column) | SelectField::AliasedColumn { column, .. } => {
+                    collect_column(column, &local, source.is_none(), &mut outer_references)
+                }
+                SelectField::Expression { expr, .. } => {
+                    collect_outer_references(expr, &local, source.is_none(), &mut outer_references)
+                }
+                SelectField::Wildcard => {}
+            }
+        }
+        if let Some(condition) = where_clause {
+            collect_outer_references(condition, &local, source.is_none(), &mut outer_references);
+        }
+
+        let correlation_key = match (&source, where_clause) {
+            (Some(_), Some(condition)) => correlation_key(condition, &local, &outer_references),
+            _ => None,
+        };
+        let aggregate = matches!(
+            fields.first(),
+            Some(SelectField::Expression { expr, .. }) if AggregateFunctions::is_aggregate_function(expr)
+        );
+
+        Ok(Self {
+            source,
+            outer_references,
+            correlation_key,
+            aggregate,
+            single_column: fields.len() == 1 && !matches!(fields[0], SelectField::Wildcard),
+            refresh,
+        })
+    }
+
+    /// The FROM source, `None` for a subquery without FROM
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
+    /// Columns bound from the outer record
+    pub fn outer_references(&self) -> &[String] {
+        &self.outer_references
+    }
+
+    /// Whether the result depends on the outer record
+    pub fn is_correlated(&self) -> bool {
+        !self.outer_references.is_empty()
+    }
+
+    /// The `subquery.refresh` policy of the subquery, if it sets one
+    pub fn refresh_policy(&self) -> Option<SubqueryRefreshPolicy> {
+        self.refresh
+    }
+
+    /// How a subquery of the given type is evaluated
+    pub fn strategy(&self, subquery_type: &SubqueryType) -> SubqueryStrategy {
+        if self.is_correlated() {
+            return SubqueryStrategy::Correlated;
+        }
+        match subquery_type {
+            SubqueryType::In | SubqueryType::Exists => SubqueryStrategy::SemiJoin,
+            SubqueryType::NotIn | SubqueryType::NotExists => SubqueryStrategy::AntiJoin,
+            SubqueryType::Scalar | SubqueryType::Any | SubqueryType::All => {
+                SubqueryStrategy::CachedValues
+            }
+        }
+    }
+
+    /// Values of the outer references for an outer record
+    fn bindings(&self, outer: &StreamRecord) -> Result<Vec<(String, FieldValue)>, SqlError> {
+        self.outer_references
+            .iter()
+            .map(|reference| {
+                let value = ExpressionEvaluator::evaluate_expression_value(
+                    &Expr::Column(reference.clone(), NodeSpan::default()),
+                    outer,
+                )?;
+                Ok((reference.clone(), value))
+            })
+            .collect()
+    }
+
+    /// Rows of the source that may match, narrowed by the correlation key when indexed
+    fn candidate_rows(
+        &self,
+        outer: &StreamRecord,
+        bindings: &[(String, FieldValue)],
+        context: &ProcessorContext,
+    ) -> Result<Vec<StreamRecord>, SqlError> {
+        let Some(source) = &self.source else {
+            return Ok(vec![outer.clone()]);
+        };
+        if let Some(rows) = context.materialized_rows(source)? {
+            return Ok(rows);
+        }
+        if let Some(lookup) = context.lookup_sources.get(source) {
+            let key = self
+                .correlation_key
+                .as_ref()
+                .and_then(|(column, reference)| {
+                    bindings
+                        .iter()
+                        .find(|(name, _)| name == reference)
+                        .map(|(_, value)| (column, value))
+                });
+            if let Some((column, value)) = key {
+                if matches!(value, FieldValue::Null) {
+                    return Ok(Vec::new());
+                }
+                if let Some(rows) = lookup.lookup(column, value)? {
+                    return Ok(rows);
+                }
+            }
+            return lookup.scan();
+        }
+        if let Some(rows) = context.data_sources.get(source) {
+            return Ok(rows.clone());
+        }
+        Err(SqlError::ExecutionError {
+            message: format!(
+                "[SUBQ-SOURCE-001] Subquery source '{}' is neither a materialized table nor a registered lookup source",
+                source
+            ),
+            query: None,
+        })
+    }
+
+    /// Version of the source the cached result was computed from
+    fn source_version(&self, context: &ProcessorContext) -> u64 {
+        match &self.source {
+            Some(source) if context.merge_target_states.contains_key(source) => {
+                context.subquery_cache.table_version(source)
+            }
+            Some(source) => context
+                .lookup_sources
+                .get(source)
+                .map_or(0, |lookup| lookup.version()),
+            None => 0,
+        }
+    }
+}
+
+/// Names of the sources the subqueries of a query read, nested subqueries included
+pub fn subquery_sources(query: &StreamingQuery) -> Vec<String> {
+    let mut sources = Vec::new();
+    for_each_expr(&mut query.clone(), &mut |expr| {
+        if let Expr::Subquery { query, .. } = expr {
+            if let StreamingQuery::Select {
+                from: StreamSource::Stream(name) | StreamSource::Table(name),
+                ..
+            } = query.as_ref()
+            {
+                if !name.is_empty() && !sources.contains(name) {
+                    sources.push(name.clone());
+                }
+            }
+        }
+    });
+    sources
+}
+
+/// The MERGE or UPSERT target whose current rows a query materializes
+pub fn materialized_table(query: &StreamingQuery) -> Option<&str> {
+    match query {
+        StreamingQuery::Merge { target, .. } => Some(target),
+        StreamingQuery::Upsert { table_name, .. } => Some(table_name),
+        StreamingQuery::StartJob { query, .. } | StreamingQuery::DeployJob { query, .. } => {
+            materialized_table(query)
+        }
+        _ => None,
+    }
+}
+
+fn plan_error(code: &str, message: &str) -> SqlError {
+    SqlError::ExecutionError {
+        message: format!("[{}] {}", code, message),
+        query: None,
+    }
+}
+
+/// Record `column` as an outer reference when it does not name a column of the subquery
+fn collect_column(column: &str, local: &[&str], unqualified_is_outer: bool, out: &mut Vec<String>) {
+    let is_outer = match column.split_once('.') {
+        Some((qualifier, _)) => !local.contains(&qualifier),
+        None => unqualified_is_outer,
+    };
+    if is_outer && !out.iter().any(|reference| reference == column) {
+        out.push(column.to_string());
+    }
+}
+
+fn collect_outer_references(
+    expr: &Expr,
+    local: &[&str],
+    unqualified_is_outer: bool,
+    out: &mut Vec<String>,
+) {
+    let mut walk = |expr: &Expr| collect_outer_references(expr, local, unqualified_is_outer, out);
+    match expr {
+        Expr::Column(column, _) => collect_column(column, local, unqualified_is_outer, out),
+        Expr::Literal(_, _) | Expr::Parameter(_, _) => {}
+        Expr::BinaryOp { left, right, .. } => {
+            walk(left);
+            walk(right);
+        }
+        Expr::UnaryOp { expr, .. } | Expr::FieldAccess { expr, .. } => walk(expr),
+        Expr::Subscript { expr, index, .. } => {
+            walk(expr);
+            walk(index);
+        }
+        Expr::Function { args, .. } | Expr::List(args, _) => args.iter().for_each(walk),
+        Expr::WindowFunction {
+            args, over_clause, ..
+        } => {
+            args.iter().for_each(&mut walk);
+            over_clause
+                .order_by
+                .iter()
+                .for_each(|order| walk(&order.expr));
+        }
+        Expr::Case {
+            when_clauses,
+            else_clause,
+            ..
+        } => {
+            for (condition, result) in when_clauses {
+                walk(condition);
+                walk(result);
+            }
+            if let Some(else_expr) = else_clause {
+                walk(else_expr);
+            }
+        }
+        Expr::Between {
+            expr, low, high, ..
+        } => {
+            walk(expr);
+            walk(low);
+            walk(high);
+        }
+        Expr::Lambda { params, body, .. } => {
+            let mut body_references = Vec::new();
+            collect_outer_references(body, local, unqualified_is_outer, &mut body_references);
+            for reference in body_references {
+                if !params.contains(&reference) && !out.contains(&reference) {
+                    out.push(reference);
+                }
+            }
+        }
+        Expr::Subquery { query, .. } => {
+            // Names of a nested subquery's own source are local to it
+            let StreamingQuery::Select {
+                fields,
+                from,
+                from_alias,
+                where_clause,
+                ..
+            } = query.as_ref()
+            else {
+                return;
+            };
+            let mut nested_local = local.to_vec();
+            let has_source = match from {
+                StreamSource::Stream(name) | StreamSource::Table(name) if !name.is_empty() => {
+                    nested_local.push(name);
+                    true
+                }
+                _ => false,
+            };
+            nested_local.extend(from_alias.as_deref());
+            let nested_unqualified_is_outer = unqualified_is_outer && !has_source;
+            for field in fields {
+                match field {
+                    SelectField::Column(column) | SelectField::AliasedColumn { column, .. } => {
+                        collect_column(column, &nested_local, nested_unqualified_is_outer, out)
+                    }
+                    SelectField::Expression { expr, .. } => collect_outer_references(
+                        expr,
+                        &nested_local,
+                        nested_unqualified_is_outer,
+                        out,
+                    ),
+                    SelectField::Wildcard => {}
+                }
+            }
+            if let Some(condition) = where_clause {
+                collect_outer_references(
+                    condition,
+                    &nested_local,
+                    nested_unqualified_is_outer,
+                    out,
+                );
+            }
+        }
+    }
+}
+
+/// A `source_column = outer.column` conjunct of the WHERE clause
+fn correlation_key(
+    condition: &Expr,
+    local: &[&str],
+    outer_references: &[String],
+) -> Option<(String, String)> {
+    match condition {
+        Expr::BinaryOp {
+            left,
+            op: BinaryOperator::And,
+            right,
+            ..
+        } => correlation_key(left, local, outer_references)
+            .or_else(|| correlation_key(right, local, outer_references)),
+        Expr::BinaryOp {
+            left,
+            op: BinaryOperator::Equal,
+            right,
+            ..
+        } => match (left.as_ref(), right.as_ref()) {
+            (Expr::Column(a, _), Expr::Column(b, _)) => {
+                let local_column = |column: &str| match column.split_once('.') {
+                    Some((qualifier, name)) if local.contains(&qualifier) => Some(name.to_string()),
+                    Some(_) => None,
+                    None => Some(column.to_string()),
+                };
+                let is_outer = |column: &str| outer_references.iter().any(|r| r == column);
+                if is_outer(b) {
+                    local_column(a).map(|column| (column, b.clone()))
+                } else if is_outer(a) {
+                    local_column(b).map(|column| (column, a.clone()))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Values a subquery returned: its first column, one value per row
+#[derive(Debug, Default)]
+pub struct SubqueryResult {
+    values: Vec<FieldValue>,
+    single_column: bool,
+    /// Value key -> positions of the values, for hash semi- and anti-joins
+    index: HashMap<String, Vec<usize>>,
+}
+
+impl SubqueryResult {
+    fn new(values: Vec<FieldValue>, single_column: bool) -> Self {
+        let mut index: HashMap<String, Vec<usize>> = HashMap::new();
+        for (position, value) in values.iter().enumerate() {
+            if !matches!(value, FieldValue::Null) {
+                index.entry(value_key(value)).or_default().push(position);
+            }
+        }
+        Self {
+            values,
+            single_column,
+            index,
+        }
+    }
+
+    /// One value per returned row
+    pub fn values(&self) -> &[FieldValue] {
+        &self.values
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Whether the subquery selects exactly one column, as IN, ANY, ALL and scalar subqueries must
+    pub fn is_single_column(&self) -> bool {
+        self.single_column
+    }
+
+    /// Whether a returned value equals `value`; NULL equals nothing
+    pub fn contains(&self, value: &FieldValue) -> bool {
+        if matches!(value, FieldValue::Null) {
+            return false;
+        }
+        self.index.get(&value_key(value)).is_some_and(|positions| {
+            positions
+                .iter()
+                .any(|&position| ExpressionEvaluator::values_equal(&self.values[position], value))
+        })
+    }
+}
+
+/// Subquery plans and the cached results of uncorrelated subqueries, shared across records
+///
+/// Clones share the same cache, so the engine hands a clone to every processor context.
+#[derive(Debug, Clone, Default)]
+pub struct SubqueryCache {
+    state: Arc<Mutex<CacheState>>,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    entries: Vec<CacheEntry>,
+    /// Write counts of materialized tables
+    table_versions: HashMap<String, u64>,
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    query: StreamingQuery,
+    plan: Arc<SubqueryPlan>,
+    result: Option<CachedResult>,
+}
+
+#[derive(Debug)]
+struct CachedResult {
+    result: Arc<SubqueryResult>,
+    source_version: u64,
+    computed_at: Instant,
+}
+
+impl SubqueryCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a write to a materialized table, invalidating cached results that read it
+    pub fn invalidate_table(&self, table: &str) {
+        let mut state = self.lock();
+        *state.table_versions.entry(table.to_string()).or_insert(0) += 1;
+    }
+
+    /// Number of subqueries with a cached result
+    pub fn cached_results(&self) -> usize {
+        self.lock()
+            .entries
+            .iter()
+            .filter(|entry| entry.result.is_some())
+            .count()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn table_version(&self, table: &str) -> u64 {
+        self.lock().table_versions.get(table).copied().unwrap_or(0)
+    }
+
+    /// Get the plan of a subquery, planning it on first use
+    fn plan(&self, query: &StreamingQuery) -> Result<Arc<SubqueryPlan>, SqlError> {
+        let mut state = self.lock();
+        if let Some(entry) = state.entries.iter().find(|entry| &entry.query == query) {
+            return Ok(Arc::clone(&entry.plan));
+        }
+        let plan = Arc::new(SubqueryPlan::new(query)?);
+        if state.entries.len() >= MAX_CACHED_SUBQUERIES {
+            state.entries.clear();
+        }
+        state.entries.push(CacheEntry {
+            query: query.clone(),
+            plan: Arc::clone(&plan),
+            result: None,
+        });
+        Ok(plan)
+    }
+
+    fn cached_result(
+        &self,
+        query: &StreamingQuery,
+        policy: SubqueryRefreshPolicy,
+        source_version: u64,
+    ) -> Option<Arc<SubqueryResult>> {
+        let state = self.lock();
+        let cached = state
+            .entries
+            .iter()
+            .find(|entry| &entry.query == query)?
+            .result
+            .as_ref()?;
+        let fresh = match policy {
+            SubqueryRefreshPolicy::OnChange => cached.source_version == source_version,
+            SubqueryRefreshPolicy::Interval(interval) => cached.computed_at.elapsed() < interval,
+            SubqueryRefreshPolicy::Once => true,
+        };
+        fresh.then(|| Arc::clone(&cached.result))
+    }
+
+    fn store_result(
+        &self,
+        query: &StreamingQuery,
+        result: Arc<SubqueryResult>,
+        source_version: u64,
+    ) {
+        let mut state = self.lock();
+        if let Some(entry) = state.entries.iter_mut().find(|entry| &entry.query == query) {
+            entry.result = Some(CachedResult {
+                result,
+                source_version,
+                computed_at: Instant::now(),
+            });
+        }
+    }
+}
+
+/// Evaluate a subquery for an outer record, reusing the cached result when it is uncorrelated
+pub fn evaluate_subquery<T: SubqueryExecutor>(
+    executor: &T,
+    query: &StreamingQuery,
+    outer: &StreamRecord,
+    context: &ProcessorContext,
+) -> Result<Arc<SubqueryResult>, SqlError> {
+    let cache = &context.subquery_cache;
+    let plan = cache.plan(query)?;
+    if plan.is_correlated() {
+        return execute(executor, query, &plan, outer, context).map(Arc::new);
+    }
+
+    let policy = plan.refresh.unwrap_or(context.subquery_refresh);
+    let source_version = plan.source_version(context);
+    if let Some(result) = cache.cached_result(query, policy, source_version) {
+        return Ok(result);
+    }
+    let result = Arc::new(execute(executor, query, &plan, outer, context)?);
+    cache.store_result(query, Arc::clone(&result), source_version);
+    Ok(result)
+}
+
+fn execute<T: SubqueryExecutor>(
+    executor: &T,
+    query: &StreamingQuery,
+    plan: &SubqueryPlan,
+    outer: &StreamRecord,
+    context: &ProcessorContext,
+) -> Result<SubqueryResult, SqlError> {
+    let StreamingQuery::Select {
+        fields,
+        where_clause,
+        limit,
+        ..
+    } = query
+    else {
+        return Err(plan_error(
+            "SUBQ-PLAN-001",
+            "Subquery must be a SELECT statement",
+        ));
+    };
+
+    let bindings = plan.bindings(outer)?;
+    let mut matched = Vec::new();
+    for mut row in plan.candidate_rows(outer, &bindings, context)? {
+        if plan.source.is_some() {
+            for (reference, value) in &bindings {
+                // A qualifier naming a STRUCT column of the row is nested access, not the outer record
+                let qualifier = reference.split('.').next().unwrap_or(reference);
+                if !row.fields.contains_key(qualifier) {
+                    row.fields.insert(reference.clone(), value.clone());
+                }
+            }
+        }
+        if let Some(condition) = where_clause {
+            if !ExpressionEvaluator::evaluate_expression_with_subqueries(
+                condition, &row, executor, context,
+            )? {
+                continue;
+            }
+        }
+        matched.push(row);
+        if !plan.aggregate && limit.is_some_and(|limit| matched.len() as u64 >= limit) {
+            break;
+        }
+    }
+
+    let values = if plan.aggregate {
+        vec![aggregate_value(&fields[0], &matched)?]
+    } else {
+        matched
+            .iter()
+            .map(|row| first_column(fields, row, executor, context))
+            .collect::<Result<_, _>>()?
+    };
+    Ok(SubqueryResult::new(values, plan.single_column))
+}
+
+fn first_column<T: SubqueryExecutor>(
+    fields: &[SelectField],
+    row: &StreamRecord,
+    executor: &T,
+    context: &ProcessorContext,
+) -> Result<FieldValue, SqlError> {
+    match fields.first() {
+        Some(SelectField::Column(column)) | Some(SelectField::AliasedColumn { column, .. }) => {
+            ExpressionEvaluator::evaluate_expression_value(
+                &Expr::Column(column.clone(), NodeSpan::default()),
+                row,
+            )
+        }
+        Some(SelectField::Expression { expr, .. }) => {
+            ExpressionEvaluator::evaluate_expression_value_with_subqueries(
+                expr, row, executor, context,
+            )
+        }
+        // Only EXISTS reads `SELECT *`, and it only counts rows
+        Some(SelectField::Wildcard) | None => Ok(FieldValue::Boolean(true)),
+    }
+}
+
+fn aggregate_value(field: &SelectField, rows: &[StreamRecord]) -> Result<FieldValue, SqlError> {
+    let SelectField::Expression { expr, .. } = field else {
+        return Ok(FieldValue::Null);
+    };
+    let aggregates = [("value".to_string(), expr.clone())];
+    let mut accumulator = GroupAccumulator::new();
+    for row in rows {
+        AccumulatorManager::process_record_into_accumulator(&mut accumulator, row, &aggregates)?;
+    }
+    AggregateFunctions::compute_field_aggregate_value("value", expr, &accumulator)
+}
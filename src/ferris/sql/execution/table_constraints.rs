@@ -0,0 +1,309 @@
+//! Enforcement of CREATE TABLE constraints on every row written to a table.
+//!
+//! ```sql
+//! CREATE TABLE account_balances (
+//!     account_id STRING PRIMARY KEY,
+//!     currency STRING NOT NULL,
+//!     balance DECIMAL(18, 2) NOT NULL,
+//!     CONSTRAINT positive_balance CHECK (balance >= 0)
+//! ) AS SELECT ... WITH ('constraint.violation' = 'fail');
+//! ```
+//!
+//! - `PRIMARY KEY (cols)` is the upsert key of the table; its columns are implicitly `NOT NULL`
+//! - `NOT NULL` columns must be present and non-NULL
+//! - `CHECK (expr)` must not evaluate to FALSE; comparisons with NULL are FALSE in this engine,
+//!   so checks on nullable columns are written `CHECK (col IS NULL OR col >= 0)`
+//!
+//! The table's `constraint.violation` property picks what happens to a violating row:
+//!
+//! | Policy | Behaviour |
+//! |--------|-----------|
+//! | `skip` | Drop the row with a warning |
+//! | `dlq` (default) | Send the row to the dead letter queue with the violation attached |
+//! | `fail` | Stop the job with the violation |
+
+use super::expression::ExpressionEvaluator;
+use super::types::{FieldValue, StreamRecord};
+use crate::ferris::sql::ast::{Expr, StreamingQuery, TableConstraint};
+use crate::ferris::sql::error::SqlError;
+
+/// Table property that selects the [`ConstraintViolationPolicy`]
+pub const CONSTRAINT_VIOLATION_PROPERTY: &str = "constraint.violation";
+
+/// What to do with a row that violates a table constraint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConstraintViolationPolicy {
+    /// Drop the row
+    Skip,
+    /// Send the row to the dead letter queue
+    #[default]
+    DeadLetter,
+    /// Fail the job
+    Fail,
+}
+
+impl ConstraintViolationPolicy {
+    /// Parse a `constraint.violation` property value (case-insensitive)
+    pub fn parse(value: &str) -> Result<Self, SqlError> {
+        match value.trim().to_lowercase().as_str() {
+            "skip" | "drop" => Ok(ConstraintViolationPolicy::Skip),
+            "dlq" | "reject" | "dead_letter" => Ok(ConstraintViolationPolicy::DeadLetter),
+            "fail" => Ok(ConstraintViolationPolicy::Fail),
+            _ => Err(SqlError::ConfigurationError {
+                message: format!(
+                    "Invalid {} '{}': expected 'skip', 'dlq' or 'fail'",
+                    CONSTRAINT_VIOLATION_PROPERTY, value
+                ),
+            }),
+        }
+    }
+}
+
+/// Why a row violates the table constraints
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstraintViolation {
+    /// Name of the violated constraint (generated for unnamed constraints)
+    pub constraint: String,
+    /// Column involved, for NOT NULL and PRIMARY KEY violations
+    pub column: Option<String>,
+    /// Human-readable reason
+    pub reason: String,
+}
+
+impl std::fmt::Display for ConstraintViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "constraint '{}': {}", self.constraint, self.reason)
+    }
+}
+
+/// Result of checking one row
+#[derive(Debug, Clone)]
+pub enum ConstraintOutcome {
+    /// The row satisfies every constraint
+    Accepted(StreamRecord),
+    /// The row was rejected; it is returned together with the reason
+    Rejected {
+        record: StreamRecord,
+        violation: ConstraintViolation,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct CheckConstraint {
+    name: String,
+    expr: Expr,
+}
+
+/// Encode key values as the key passed to [`DataWriter::update`] and [`DataWriter::delete`]
+///
+/// Single-column keys use the display form of the value; composite keys are
+/// encoded as a JSON array of the display forms so that they cannot collide.
+/// Returns `None` for an empty key.
+///
+/// [`DataWriter::update`]: crate::ferris::datasource::DataWriter::update
+/// [`DataWriter::delete`]: crate::ferris::datasource::DataWriter::delete
+pub fn encode_upsert_key(values: &[FieldValue]) -> Option<String> {
+    match values {
+        [] => None,
+        [value] => Some(value.to_display_string()),
+        _ => Some(
+            serde_json::Value::from(
+                values
+                    .iter()
+                    .map(FieldValue::to_display_string)
+                    .collect::<Vec<_>>(),
+            )
+            .to_string(),
+        ),
+    }
+}
+
+/// Checks rows against the constraints of a CREATE TABLE statement
+#[derive(Debug, Clone)]
+pub struct TableConstraintEnforcer {
+    table_name: String,
+    primary_key: Vec<String>,
+    not_null: Vec<String>,
+    checks: Vec<CheckConstraint>,
+    policy: ConstraintViolationPolicy,
+}
+
+impl TableConstraintEnforcer {
+    /// Build the enforcer for a CREATE TABLE statement
+    ///
+    /// Returns `Ok(None)` for queries that declare no primary key, NOT NULL column or CHECK.
+    pub fn from_query(query: &StreamingQuery) -> Result<Option<Self>, SqlError> {
+        let (name, columns, constraints, policy_value) = match query {
+            StreamingQuery::CreateTable {
+                name,
+                columns,
+                constraints,
+                properties,
+                ..
+            } => (
+                name,
+                columns,
+                constraints,
+                properties.get(CONSTRAINT_VIOLATION_PROPERTY),
+            ),
+            StreamingQuery::CreateTableInto {
+                name,
+                columns,
+                constraints,
+                properties,
+                ..
+            } => (
+                name,
+                columns,
+                constraints,
+                properties
+                    .inline_properties
+                    .get(CONSTRAINT_VIOLATION_PROPERTY),
+            ),
+            _ => return Ok(None),
+        };
+
+        let not_null: Vec<String> = columns
+            .iter()
+            .flatten()
+            .filter(|c| !c.nullable)
+            .map(|c| c.name.clone())
+            .collect();
+        if constraints.is_empty() && not_null.is_empty() {
+            return Ok(None);
+        }
+
+        let mut primary_key = Vec::new();
+        let mut checks = Vec::new();
+        for constraint in constraints {
+            match constraint {
+                TableConstraint::PrimaryKey { columns, .. } => primary_key = columns.clone(),
+                TableConstraint::Check {
+                    name: check_name,
+                    expr,
+                } => {
+                    // Unnamed checks get PostgreSQL-style generated names
+                    let check_name = check_name.clone().unwrap_or_else(|| match checks.len() {
+                        0 => format!("{}_check", name),
+                        n => format!("{}_check{}", name, n),
+                    });
+                    checks.push(CheckConstraint {
+                        name: check_name,
+                        expr: expr.clone(),
+                    });
+                }
+            }
+        }
+
+        // A trailing WITH clause may have been attached to the SELECT
+        let select_policy = match query {
+            StreamingQuery::CreateTable { as_select, .. }
+            | StreamingQuery::CreateTableInto { as_select, .. } => match as_select.as_ref() {
+                StreamingQuery::Select {
+                    properties: Some(properties),
+                    ..
+                } => properties.get(CONSTRAINT_VIOLATION_PROPERTY),
+                _ => None,
+            },
+            _ => None,
+        };
+        let policy = match policy_value.or(select_policy) {
+            Some(value) => ConstraintViolationPolicy::parse(value)?,
+            None => ConstraintViolationPolicy::default(),
+        };
+
+        Ok(Some(Self {
+            table_name: name.clone(),
+            primary_key,
+            not_null,
+            checks,
+            policy,
+        }))
+    }
+
+    pub fn table_name(&self) -> &str {
+        &self.table_name
+    }
+
+    pub fn policy(&self) -> ConstraintViolationPolicy {
+        self.policy
+    }
+
+    /// Columns of the declared PRIMARY KEY (empty if none)
+    pub fn primary_key(&self) -> &[String] {
+        &self.primary_key
+    }
+
+    /// The upsert key of a row, or `None` if the table has no primary key
+    ///
+    /// See [`encode_upsert_key`] for the encoding.
+    pub fn key_of(&self, record: &StreamRecord) -> Option<String> {
+        let values: Vec<FieldValue> = self
+            .primary_key
+            .iter()
+            .map(|column| {
+                record
+                    .fields
+                    .get(column)
+                    .cloned()
+                    .unwrap_or(FieldValue::Null)
+            })
+            .collect();
+        encode_upsert_key(&values)
+    }
+
+    /// Check a row against the table constraints
+    ///
+    /// Under the `fail` policy a violation is returned as [`SqlError::SchemaError`];
+    /// otherwise it is reported as [`ConstraintOutcome::Rejected`].
+    pub fn enforce(&self, record: StreamRecord) -> Result<ConstraintOutcome, SqlError> {
+        match self.violation(&record) {
+            None => Ok(ConstraintOutcome::Accepted(record)),
+            Some(violation) if self.policy == ConstraintViolationPolicy::Fail => {
+                Err(SqlError::SchemaError {
+                    message: format!("Row violates {} of table '{}'", violation, self.table_name),
+                    column: violation.column,
+                })
+            }
+            Some(violation) => Ok(ConstraintOutcome::Rejected { record, violation }),
+        }
+    }
+
+    fn violation(&self, record: &StreamRecord) -> Option<ConstraintViolation> {
+        let is_null =
+            |column: &String| matches!(record.fields.get(column), None | Some(FieldValue::Null));
+
+        if let Some(column) = self.primary_key.iter().find(|c| is_null(c)) {
+            return Some(ConstraintViolation {
+                constraint: format!("{}_pkey", self.table_name),
+                column: Some(column.clone()),
+                reason: format!("NULL value in PRIMARY KEY column '{}'", column),
+            });
+        }
+        if let Some(column) = self.not_null.iter().find(|c| is_null(c)) {
+            return Some(ConstraintViolation {
+                constraint: format!("{}_not_null", column),
+                column: Some(column.clone()),
+                reason: format!("NULL value in NOT NULL column '{}'", column),
+            });
+        }
+
+        for check in &self.checks {
+            let reason = match ExpressionEvaluator::evaluate_expression_value(&check.expr, record) {
+                Ok(FieldValue::Boolean(true)) | Ok(FieldValue::Null) => continue,
+                Ok(FieldValue::Boolean(false)) => "CHECK condition is false".to_string(),
+                Ok(other) => format!(
+                    "CHECK condition returned {} instead of BOOLEAN",
+                    other.type_name()
+                ),
+                Err(e) => format!("CHECK condition could not be evaluated: {}", e),
+            };
+            return Some(ConstraintViolation {
+                constraint: check.name.clone(),
+                column: None,
+                reason,
+            });
+        }
+        None
+    }
+}
@@ -62,7 +62,7 @@ fn create_config_table_data() -> Vec<StreamRecord> {
     config_fields_1.insert("max_value".to_string(), FieldValue::Integer(100));
 
     config_records.push(StreamRecord {
-        fields: config_fields_1,
+        fields: config_fields_1.into(),
         headers: HashMap::new(),
         timestamp: 1640995200000,
         offset: 1,
@@ -82,7 +82,7 @@ fn create_config_table_data() -> Vec<StreamRecord> {
     config_fields_2.insert("max_value".to_string(), FieldValue::Integer(200));
 
     config_records.push(StreamRecord {
-        fields: config_fields_2,
+        fields: config_fields_2.into(),
         headers: HashMap::new(),
         timestamp: 1640995200001,
         offset: 2,
@@ -105,7 +105,7 @@ fn create_active_configs_table_data() -> Vec<StreamRecord> {
     );
 
     vec![StreamRecord {
-        fields: active_config_fields,
+        fields: active_config_fields.into(),
         headers: HashMap::new(),
         timestamp: 1640995200000,
         offset: 1,
@@ -128,7 +128,7 @@ fn create_products_table_data() -> Vec<StreamRecord> {
     );
 
     products_records.push(StreamRecord {
-        fields: product_fields_1,
+        fields: product_fields_1.into(),
         headers: HashMap::new(),
         timestamp: 1640995200000,
         offset: 1,
@@ -146,7 +146,7 @@ fn create_products_table_data() -> Vec<StreamRecord> {
     );
 
     products_records.push(StreamRecord {
-        fields: product_fields_2,
+        fields: product_fields_2.into(),
         headers: HashMap::new(),
         timestamp: 1640995200001,
         offset: 2,
@@ -178,7 +178,7 @@ fn create_users_table_data() -> Vec<StreamRecord> {
     );
 
     users_records.push(StreamRecord {
-        fields: user_fields_1,
+        fields: user_fields_1.into(),
         headers: HashMap::new(),
         timestamp: 1640995200000,
         offset: 1,
@@ -203,7 +203,7 @@ fn create_users_table_data() -> Vec<StreamRecord> {
     );
 
     users_records.push(StreamRecord {
-        fields: user_fields_2,
+        fields: user_fields_2.into(),
         headers: HashMap::new(),
         timestamp: 1640995200001,
         offset: 2,
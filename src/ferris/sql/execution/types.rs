@@ -4,12 +4,17 @@
 //! - [`FieldValue`] - The value type system supporting SQL data types
 //! - [`StreamRecord`] - The record format for streaming data processing
 
-use crate::ferris::sql::ast::TimeUnit;
+use super::decimal::{self, RoundingMode};
+use super::record_fields::RecordFields;
+use crate::ferris::sql::ast::{DecimalSpec, TimeUnit};
 use crate::ferris::sql::error::SqlError;
-use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use base64::Engine;
+use chrono::{DateTime, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
 use rust_decimal::Decimal;
 use std::collections::HashMap;
 use std::str::FromStr;
+use uuid::Uuid;
 
 /// A value in a SQL record field
 ///
@@ -32,6 +37,8 @@ pub enum FieldValue {
     Date(NaiveDate),
     /// Timestamp type (YYYY-MM-DD HH:MM:SS[.nnn])
     Timestamp(NaiveDateTime),
+    /// Timestamp with time zone - an instant plus the IANA zone it is presented in
+    TimestampTz(DateTime<Tz>),
     /// Decimal type for precise arithmetic
     Decimal(Decimal),
     /// Scaled integer for financial precision (value, *scale) where value is scaled by 10^*scale
@@ -44,6 +51,12 @@ pub enum FieldValue {
     Struct(HashMap<String, FieldValue>),
     /// Time interval (value, unit)
     Interval { value: i64, unit: TimeUnit },
+    /// Raw binary data (BYTES / VARBINARY)
+    Bytes(Vec<u8>),
+    /// Universally unique identifier
+    Uuid(Uuid),
+    /// Time of day without a date or zone (HH:MM:SS[.nnn])
+    Time(NaiveTime),
 }
 
 impl FieldValue {
@@ -60,12 +73,16 @@ impl FieldValue {
             FieldValue::Null => "NULL",
             FieldValue::Date(_) => "DATE",
             FieldValue::Timestamp(_) => "TIMESTAMP",
+            FieldValue::TimestampTz(_) => "TIMESTAMPTZ",
             FieldValue::Decimal(_) => "DECIMAL",
             FieldValue::ScaledInteger(_, _) => "SCALED_INTEGER",
             FieldValue::Array(_) => "ARRAY",
             FieldValue::Map(_) => "MAP",
             FieldValue::Struct(_) => "STRUCT",
             FieldValue::Interval { .. } => "INTERVAL",
+            FieldValue::Bytes(_) => "BYTES",
+            FieldValue::Uuid(_) => "UUID",
+            FieldValue::Time(_) => "TIME",
         }
     }
 
@@ -97,6 +114,11 @@ impl FieldValue {
             FieldValue::Null => "NULL".to_string(),
             FieldValue::Date(d) => d.format("%Y-%m-%d").to_string(),
             FieldValue::Timestamp(ts) => ts.format("%Y-%m-%d %H:%M:%S%.3f").to_string(),
+            FieldValue::TimestampTz(ts) => format!(
+                "{} {}",
+                ts.format("%Y-%m-%d %H:%M:%S%.3f"),
+                ts.timezone().name()
+            ),
             FieldValue::Decimal(dec) => dec.to_string(),
             FieldValue::ScaledInteger(value, scale) => {
                 let divisor = 10_i64.pow(*scale as u32);
@@ -137,6 +159,9 @@ impl FieldValue {
             FieldValue::Interval { value, unit } => {
                 format!("INTERVAL {} {:?}", value, unit)
             }
+            FieldValue::Bytes(bytes) => base64::engine::general_purpose::STANDARD.encode(bytes),
+            FieldValue::Uuid(uuid) => uuid.hyphenated().to_string(),
+            FieldValue::Time(t) => t.format("%H:%M:%S%.3f").to_string(),
         }
     }
 
@@ -152,43 +177,33 @@ impl FieldValue {
     /// * `Ok(FieldValue)` - The converted value
     /// * `Err(SqlError)` - If the conversion is not supported or fails
     pub fn cast_to(self, target_type: &str) -> Result<FieldValue, SqlError> {
+        // DECIMAL(p, s) coerces to the declared scale with the default rounding mode
+        if let Some(spec) = DecimalSpec::parse_type_name(target_type)? {
+            return spec.coerce(self, RoundingMode::default());
+        }
+
         match target_type {
-            "INTEGER" | "INT" => match self {
-                FieldValue::Integer(i) => Ok(FieldValue::Integer(i)),
-                FieldValue::Float(f) => Ok(FieldValue::Integer(f as i64)),
-                FieldValue::String(s) => s.parse::<i64>().map(FieldValue::Integer).map_err(|_| {
-                    SqlError::ExecutionError {
-                        message: format!("Cannot cast '{}' to INTEGER", s),
+            "INTEGER" | "INT" => self.cast_to_integer("INTEGER"),
+            "BIGINT" | "LONG" => self.cast_to_integer("BIGINT"),
+            "SMALLINT" => match self.cast_to_integer("SMALLINT")? {
+                FieldValue::Integer(i) if i16::try_from(i).is_err() => {
+                    Err(SqlError::ExecutionError {
+                        message: format!("Value {} is out of range for SMALLINT", i),
                         query: None,
-                    }
-                }),
-                FieldValue::Boolean(b) => Ok(FieldValue::Integer(if b { 1 } else { 0 })),
-                FieldValue::Decimal(d) => {
-                    // Convert decimal to integer, truncating fractional part
-                    let int_part = d.trunc();
-                    match int_part.to_string().parse::<i64>() {
-                        Ok(i) => Ok(FieldValue::Integer(i)),
-                        Err(_) => Err(SqlError::ExecutionError {
-                            message: format!("Cannot cast DECIMAL {} to INTEGER", d),
-                            query: None,
-                        }),
-                    }
+                    })
                 }
-                FieldValue::ScaledInteger(value, scale) => {
-                    // Convert scaled integer to regular integer by dividing by scale
-                    let divisor = 10_i64.pow(scale as u32);
-                    Ok(FieldValue::Integer(value / divisor))
+                other => Ok(other),
+            },
+            "REAL" => match self.cast_to("FLOAT")? {
+                // REAL values keep single precision even though they are stored as f64
+                FieldValue::Float(f) if f.is_finite() && (f as f32).is_infinite() => {
+                    Err(SqlError::ExecutionError {
+                        message: format!("Value {} is out of range for REAL", f),
+                        query: None,
+                    })
                 }
-                FieldValue::Null => Ok(FieldValue::Null),
-                FieldValue::Date(_)
-                | FieldValue::Timestamp(_)
-                | FieldValue::Array(_)
-                | FieldValue::Map(_)
-                | FieldValue::Struct(_)
-                | FieldValue::Interval { .. } => Err(SqlError::ExecutionError {
-                    message: format!("Cannot cast {} to INTEGER", self.type_name()),
-                    query: None,
-                }),
+                FieldValue::Float(f) => Ok(FieldValue::Float(f as f32 as f64)),
+                other => Ok(other),
             },
             "FLOAT" | "DOUBLE" => match self {
                 FieldValue::Integer(i) => Ok(FieldValue::Float(i as f64)),
@@ -220,10 +235,14 @@ impl FieldValue {
                 FieldValue::Null => Ok(FieldValue::Null),
                 FieldValue::Date(_)
                 | FieldValue::Timestamp(_)
+                | FieldValue::TimestampTz(_)
                 | FieldValue::Array(_)
                 | FieldValue::Map(_)
                 | FieldValue::Struct(_)
-                | FieldValue::Interval { .. } => Err(SqlError::ExecutionError {
+                | FieldValue::Interval { .. }
+                | FieldValue::Bytes(_)
+                | FieldValue::Uuid(_)
+                | FieldValue::Time(_) => Err(SqlError::ExecutionError {
                     message: format!("Cannot cast {} to FLOAT", self.type_name()),
                     query: None,
                 }),
@@ -234,8 +253,20 @@ impl FieldValue {
                 FieldValue::String(s) => Ok(FieldValue::String(s)),
                 FieldValue::Boolean(b) => Ok(FieldValue::String(b.to_string())),
                 FieldValue::Null => Ok(FieldValue::String("NULL".to_string())),
+                FieldValue::Bytes(bytes) => {
+                    String::from_utf8(bytes).map(FieldValue::String).map_err(|_| {
+                        SqlError::ExecutionError {
+                            message: "Cannot cast BYTES to STRING: data is not valid UTF-8, use BASE64_ENCODE instead".to_string(),
+                            query: None,
+                        }
+                    })
+                }
+                FieldValue::Uuid(_) | FieldValue::Time(_) => {
+                    Ok(FieldValue::String(self.to_display_string()))
+                }
                 FieldValue::Date(_)
                 | FieldValue::Timestamp(_)
+                | FieldValue::TimestampTz(_)
                 | FieldValue::Decimal(_)
                 | FieldValue::ScaledInteger(_, _)
                 | FieldValue::Array(_)
@@ -259,11 +290,15 @@ impl FieldValue {
                 FieldValue::Null => Ok(FieldValue::Null),
                 FieldValue::Date(_)
                 | FieldValue::Timestamp(_)
+                | FieldValue::TimestampTz(_)
                 | FieldValue::Decimal(_)
                 | FieldValue::Array(_)
                 | FieldValue::Map(_)
                 | FieldValue::Struct(_)
-                | FieldValue::Interval { .. } => Err(SqlError::ExecutionError {
+                | FieldValue::Interval { .. }
+                | FieldValue::Bytes(_)
+                | FieldValue::Uuid(_)
+                | FieldValue::Time(_) => Err(SqlError::ExecutionError {
                     message: format!("Cannot cast {} to BOOLEAN", self.type_name()),
                     query: None,
                 }),
@@ -283,6 +318,7 @@ impl FieldValue {
                         query: None,
                     }),
                 FieldValue::Timestamp(ts) => Ok(FieldValue::Date(ts.date())),
+                FieldValue::TimestampTz(ts) => Ok(FieldValue::Date(ts.date_naive())),
                 FieldValue::Null => Ok(FieldValue::Null),
                 _ => Err(SqlError::ExecutionError {
                     message: format!("Cannot cast {} to DATE", self.type_name()),
@@ -291,6 +327,8 @@ impl FieldValue {
             },
             "TIMESTAMP" | "DATETIME" => match self {
                 FieldValue::Timestamp(ts) => Ok(FieldValue::Timestamp(ts)),
+                // Zone-aware timestamps are normalized to UTC
+                FieldValue::TimestampTz(ts) => Ok(FieldValue::Timestamp(ts.naive_utc())),
                 FieldValue::Date(d) => Ok(FieldValue::Timestamp(d.and_hms_opt(0, 0, 0).unwrap())),
                 FieldValue::String(s) => {
                     // Try various timestamp formats
@@ -325,6 +363,94 @@ impl FieldValue {
                     query: None,
                 }),
             },
+            "TIMESTAMPTZ" | "TIMESTAMP WITH TIME ZONE" => match self {
+                FieldValue::TimestampTz(ts) => Ok(FieldValue::TimestampTz(ts)),
+                // Timestamps without a zone are interpreted as UTC
+                FieldValue::Timestamp(ts) => Ok(FieldValue::TimestampTz(
+                    Tz::UTC.from_utc_datetime(&ts),
+                )),
+                FieldValue::Date(d) => Ok(FieldValue::TimestampTz(
+                    Tz::UTC.from_utc_datetime(&d.and_hms_opt(0, 0, 0).unwrap()),
+                )),
+                FieldValue::String(s) => Self::parse_timestamp_tz(&s)
+                    .map(FieldValue::TimestampTz)
+                    .ok_or_else(|| SqlError::ExecutionError {
+                        message: format!(
+                            "Cannot cast '{}' to TIMESTAMPTZ. Expected format: YYYY-MM-DD HH:MM:SS[.fff] <zone> or RFC 3339",
+                            s
+                        ),
+                        query: None,
+                    }),
+                FieldValue::Integer(i) => {
+                    // Treat as Unix timestamp (seconds), same as TIMESTAMP
+                    let dt =
+                        DateTime::from_timestamp(i, 0).ok_or_else(|| SqlError::ExecutionError {
+                            message: format!("Invalid Unix timestamp: {}", i),
+                            query: None,
+                        })?;
+                    Ok(FieldValue::TimestampTz(dt.with_timezone(&Tz::UTC)))
+                }
+                FieldValue::Null => Ok(FieldValue::Null),
+                _ => Err(SqlError::ExecutionError {
+                    message: format!("Cannot cast {} to TIMESTAMPTZ", self.type_name()),
+                    query: None,
+                }),
+            },
+            "TIME" => match self {
+                FieldValue::Time(t) => Ok(FieldValue::Time(t)),
+                FieldValue::Timestamp(ts) => Ok(FieldValue::Time(ts.time())),
+                // Zone-aware timestamps keep the wall-clock time of their zone
+                FieldValue::TimestampTz(ts) => Ok(FieldValue::Time(ts.naive_local().time())),
+                FieldValue::String(s) => NaiveTime::parse_from_str(s.trim(), "%H:%M:%S%.f")
+                    .or_else(|_| NaiveTime::parse_from_str(s.trim(), "%H:%M"))
+                    .map(FieldValue::Time)
+                    .map_err(|_| SqlError::ExecutionError {
+                        message: format!(
+                            "Cannot cast '{}' to TIME. Expected format: HH:MM[:SS[.fff]]",
+                            s
+                        ),
+                        query: None,
+                    }),
+                FieldValue::Null => Ok(FieldValue::Null),
+                _ => Err(SqlError::ExecutionError {
+                    message: format!("Cannot cast {} to TIME", self.type_name()),
+                    query: None,
+                }),
+            },
+            "BYTES" | "VARBINARY" | "BINARY" => match self {
+                FieldValue::Bytes(bytes) => Ok(FieldValue::Bytes(bytes)),
+                // Strings are taken as their UTF-8 encoding; use BASE64_DECODE for encoded data
+                FieldValue::String(s) => Ok(FieldValue::Bytes(s.into_bytes())),
+                FieldValue::Uuid(uuid) => Ok(FieldValue::Bytes(uuid.as_bytes().to_vec())),
+                FieldValue::Null => Ok(FieldValue::Null),
+                _ => Err(SqlError::ExecutionError {
+                    message: format!("Cannot cast {} to BYTES", self.type_name()),
+                    query: None,
+                }),
+            },
+            "UUID" => match self {
+                FieldValue::Uuid(uuid) => Ok(FieldValue::Uuid(uuid)),
+                FieldValue::String(s) => Uuid::parse_str(s.trim()).map(FieldValue::Uuid).map_err(
+                    |_| SqlError::ExecutionError {
+                        message: format!("Cannot cast '{}' to UUID", s),
+                        query: None,
+                    },
+                ),
+                FieldValue::Bytes(bytes) => Uuid::from_slice(&bytes)
+                    .map(FieldValue::Uuid)
+                    .map_err(|_| SqlError::ExecutionError {
+                        message: format!(
+                            "Cannot cast {} bytes to UUID, expected exactly 16",
+                            bytes.len()
+                        ),
+                        query: None,
+                    }),
+                FieldValue::Null => Ok(FieldValue::Null),
+                _ => Err(SqlError::ExecutionError {
+                    message: format!("Cannot cast {} to UUID", self.type_name()),
+                    query: None,
+                }),
+            },
             "DECIMAL" | "NUMERIC" => {
                 match self {
                     FieldValue::Decimal(d) => Ok(FieldValue::Decimal(d)),
@@ -409,6 +535,107 @@ impl FieldValue {
         }
     }
 
+    /// Cast to a 64-bit integer, as INTEGER and BIGINT are stored
+    ///
+    /// SMALLINT values are stored as `FieldValue::Integer` too and range-checked by the caller.
+    fn cast_to_integer(self, type_name: &str) -> Result<FieldValue, SqlError> {
+        match self {
+            FieldValue::Integer(i) => Ok(FieldValue::Integer(i)),
+            FieldValue::Float(f) => Ok(FieldValue::Integer(f as i64)),
+            FieldValue::String(s) => {
+                s.parse::<i64>()
+                    .map(FieldValue::Integer)
+                    .map_err(|_| SqlError::ExecutionError {
+                        message: format!("Cannot cast '{}' to {}", s, type_name),
+                        query: None,
+                    })
+            }
+            FieldValue::Boolean(b) => Ok(FieldValue::Integer(if b { 1 } else { 0 })),
+            FieldValue::Decimal(d) => {
+                // Convert decimal to integer, truncating fractional part
+                let int_part = d.trunc();
+                match int_part.to_string().parse::<i64>() {
+                    Ok(i) => Ok(FieldValue::Integer(i)),
+                    Err(_) => Err(SqlError::ExecutionError {
+                        message: format!("Cannot cast DECIMAL {} to {}", d, type_name),
+                        query: None,
+                    }),
+                }
+            }
+            FieldValue::ScaledInteger(value, scale) => {
+                // Convert scaled integer to regular integer by dividing by scale
+                let divisor = 10_i64.pow(scale as u32);
+                Ok(FieldValue::Integer(value / divisor))
+            }
+            FieldValue::Null => Ok(FieldValue::Null),
+            FieldValue::Date(_)
+            | FieldValue::Timestamp(_)
+            | FieldValue::TimestampTz(_)
+            | FieldValue::Array(_)
+            | FieldValue::Map(_)
+            | FieldValue::Struct(_)
+            | FieldValue::Interval { .. }
+            | FieldValue::Bytes(_)
+            | FieldValue::Uuid(_)
+            | FieldValue::Time(_) => Err(SqlError::ExecutionError {
+                message: format!("Cannot cast {} to {}", self.type_name(), type_name),
+                query: None,
+            }),
+        }
+    }
+
+    /// Parse a zone-aware timestamp string
+    ///
+    /// Accepts RFC 3339 (`2024-03-08T09:00:00+09:00`, normalized to UTC), a local
+    /// timestamp followed by an IANA zone name (`2024-03-08 09:00:00 Asia/Tokyo`), or a
+    /// plain timestamp which is interpreted as UTC.
+    fn parse_timestamp_tz(s: &str) -> Option<DateTime<Tz>> {
+        let s = s.trim();
+        if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+            return Some(dt.with_timezone(&Tz::UTC));
+        }
+
+        let (local_part, zone) = match s.rsplit_once(' ') {
+            Some((local, zone)) if zone.parse::<Tz>().is_ok() => (local, zone.parse::<Tz>().ok()?),
+            _ => (s, Tz::UTC),
+        };
+
+        let local = NaiveDateTime::parse_from_str(local_part, "%Y-%m-%d %H:%M:%S%.f")
+            .or_else(|_| NaiveDateTime::parse_from_str(local_part, "%Y-%m-%dT%H:%M:%S%.f"))
+            .or_else(|_| {
+                NaiveDate::parse_from_str(local_part, "%Y-%m-%d")
+                    .map(|d| d.and_hms_opt(0, 0, 0).unwrap())
+            })
+            .ok()?;
+        zone.from_local_datetime(&local).earliest()
+    }
+
+    /// Resolve an IANA time zone name (e.g. `America/New_York`, `UTC`)
+    pub fn parse_time_zone(name: &str) -> Result<Tz, SqlError> {
+        name.trim()
+            .parse::<Tz>()
+            .map_err(|_| SqlError::ExecutionError {
+                message: format!("Unknown time zone: '{}'", name),
+                query: None,
+            })
+    }
+
+    /// Interpret this value as a point in time in the given zone
+    ///
+    /// Integers are epoch milliseconds and naive timestamps are treated as UTC, matching
+    /// how the rest of the engine handles event times. Returns `None` for non-temporal values.
+    pub fn to_zoned_timestamp(&self, zone: Tz) -> Option<DateTime<Tz>> {
+        match self {
+            FieldValue::TimestampTz(ts) => Some(ts.with_timezone(&zone)),
+            FieldValue::Timestamp(ts) => Some(zone.from_utc_datetime(ts)),
+            FieldValue::Integer(millis) => Utc
+                .timestamp_millis_opt(*millis)
+                .single()
+                .map(|dt| dt.with_timezone(&zone)),
+            _ => None,
+        }
+    }
+
     /// Add two FieldValue instances with proper type coercion
     ///
     /// Supports addition between numeric types (Integer, Float) with automatic
@@ -422,49 +649,41 @@ impl FieldValue {
             (FieldValue::Integer(a), FieldValue::Float(b)) => Ok(FieldValue::Float(*a as f64 + b)),
             (FieldValue::Float(a), FieldValue::Integer(b)) => Ok(FieldValue::Float(a + *b as f64)),
 
-            // ScaledInteger arithmetic - exact precision
+            // ScaledInteger arithmetic - exact precision at scale max(s1, s2)
             (FieldValue::ScaledInteger(a, scale_a), FieldValue::ScaledInteger(b, scale_b)) => {
-                if *scale_a == *scale_b {
-                    // Same *scale, can add directly
-                    Ok(FieldValue::ScaledInteger(*a + *b, *scale_a))
-                } else {
-                    // Different *scales, normalize to higher precision
-                    let max_scale = (*scale_a).max(*scale_b);
-                    let factor_a = 10_i64.pow((max_scale - *scale_a) as u32);
-                    let factor_b = 10_i64.pow((max_scale - *scale_b) as u32);
-                    Ok(FieldValue::ScaledInteger(
-                        *a * factor_a + *b * factor_b,
-                        max_scale,
-                    ))
-                }
+                decimal::add(*a, *scale_a, *b, *scale_b)
             }
             (FieldValue::ScaledInteger(a, scale), FieldValue::Integer(b)) => {
-                let scaled_b = *b * 10_i64.pow(*scale as u32);
-                Ok(FieldValue::ScaledInteger(*a + scaled_b, *scale))
+                decimal::add(*a, *scale, *b, 0)
             }
             (FieldValue::Integer(a), FieldValue::ScaledInteger(b, scale)) => {
-                let scaled_a = *a * 10_i64.pow(*scale as u32);
-                Ok(FieldValue::ScaledInteger(scaled_a + *b, *scale))
+                decimal::add(*a, 0, *b, *scale)
             }
             (FieldValue::ScaledInteger(a, scale), FieldValue::Float(b)) => {
-                let scaled_b = (b * 10_i64.pow(*scale as u32) as f64).round() as i64;
-                Ok(FieldValue::ScaledInteger(*a + scaled_b, *scale))
+                decimal::add(*a, *scale, decimal::float_to_unscaled(*b, *scale)?, *scale)
             }
             (FieldValue::Float(a), FieldValue::ScaledInteger(b, scale)) => {
-                let scaled_a = (a * 10_i64.pow(*scale as u32) as f64).round() as i64;
-                Ok(FieldValue::ScaledInteger(scaled_a + *b, *scale))
+                decimal::add(decimal::float_to_unscaled(*a, *scale)?, *scale, *b, *scale)
             }
 
             // Interval + Timestamp arithmetic: timestamp + interval
             (FieldValue::Integer(timestamp), FieldValue::Interval { value, unit }) => {
-                let interval_millis = Self::interval_to_millis(*value, unit);
-                Ok(FieldValue::Integer(timestamp + interval_millis))
+                Self::add_interval_to_epoch_millis(*timestamp, *value, unit)
+                    .map(FieldValue::Integer)
             }
 
             // Interval + Timestamp arithmetic: interval + timestamp
             (FieldValue::Interval { value, unit }, FieldValue::Integer(timestamp)) => {
-                let interval_millis = Self::interval_to_millis(*value, unit);
-                Ok(FieldValue::Integer(interval_millis + timestamp))
+                Self::add_interval_to_epoch_millis(*timestamp, *value, unit)
+                    .map(FieldValue::Integer)
+            }
+
+            // Interval + TIMESTAMP/TIMESTAMPTZ/DATE, in either order
+            (temporal, FieldValue::Interval { value, unit })
+            | (FieldValue::Interval { value, unit }, temporal)
+                if temporal.is_temporal() =>
+            {
+                temporal.add_interval_to_temporal(*value, unit)
             }
 
             // Interval + Interval arithmetic
@@ -507,45 +726,41 @@ impl FieldValue {
             (FieldValue::Integer(a), FieldValue::Float(b)) => Ok(FieldValue::Float(*a as f64 - b)),
             (FieldValue::Float(a), FieldValue::Integer(b)) => Ok(FieldValue::Float(a - *b as f64)),
 
-            // ScaledInteger arithmetic - exact precision
+            // ScaledInteger arithmetic - exact precision at scale max(s1, s2)
             (FieldValue::ScaledInteger(a, scale_a), FieldValue::ScaledInteger(b, scale_b)) => {
-                if *scale_a == *scale_b {
-                    // Same *scale, can subtract directly
-                    Ok(FieldValue::ScaledInteger(a - b, *scale_a))
-                } else {
-                    // Different *scales, normalize to higher precision
-                    let max_scale = (*scale_a).max(*scale_b);
-                    let factor_a = 10_i64.pow((max_scale - *scale_a) as u32);
-                    let factor_b = 10_i64.pow((max_scale - *scale_b) as u32);
-                    Ok(FieldValue::ScaledInteger(
-                        a * factor_a - b * factor_b,
-                        max_scale,
-                    ))
-                }
+                decimal::subtract(*a, *scale_a, *b, *scale_b)
             }
             (FieldValue::ScaledInteger(a, scale), FieldValue::Integer(b)) => {
-                let scaled_b = *b * 10_i64.pow(*scale as u32);
-                Ok(FieldValue::ScaledInteger(a - scaled_b, *scale))
+                decimal::subtract(*a, *scale, *b, 0)
             }
             (FieldValue::Integer(a), FieldValue::ScaledInteger(b, scale)) => {
-                let scaled_a = *a * 10_i64.pow(*scale as u32);
-                Ok(FieldValue::ScaledInteger(scaled_a - b, *scale))
+                decimal::subtract(*a, 0, *b, *scale)
             }
             (FieldValue::ScaledInteger(a, scale), FieldValue::Float(b)) => {
-                let scaled_b = (b * 10_i64.pow(*scale as u32) as f64).round() as i64;
-                Ok(FieldValue::ScaledInteger(a - scaled_b, *scale))
+                decimal::subtract(*a, *scale, decimal::float_to_unscaled(*b, *scale)?, *scale)
             }
             (FieldValue::Float(a), FieldValue::ScaledInteger(b, scale)) => {
-                let scaled_a = (a * 10_i64.pow(*scale as u32) as f64).round() as i64;
-                Ok(FieldValue::ScaledInteger(scaled_a - b, *scale))
+                decimal::subtract(decimal::float_to_unscaled(*a, *scale)?, *scale, *b, *scale)
             }
 
             // Interval arithmetic: timestamp - interval
             (FieldValue::Integer(timestamp), FieldValue::Interval { value, unit }) => {
-                let interval_millis = Self::interval_to_millis(*value, unit);
-                Ok(FieldValue::Integer(timestamp - interval_millis))
+                Self::add_interval_to_epoch_millis(*timestamp, -*value, unit)
+                    .map(FieldValue::Integer)
+            }
+            (temporal, FieldValue::Interval { value, unit }) if temporal.is_temporal() => {
+                temporal.add_interval_to_temporal(-*value, unit)
             }
 
+            // Timestamp arithmetic: timestamp - timestamp yields an interval
+            (a, b) if a.is_temporal() && b.is_temporal() => self
+                .timestamp_difference(other)
+                .ok_or_else(|| SqlError::TypeError {
+                    expected: "matching timestamp types".to_string(),
+                    actual: format!("{} - {}", self.type_name(), other.type_name()),
+                    value: None,
+                }),
+
             // Interval arithmetic: interval - interval
             (
                 FieldValue::Interval {
@@ -577,6 +792,8 @@ impl FieldValue {
     ///
     /// Supports multiplication between numeric types (Integer, Float) with automatic
     /// type promotion. Returns appropriate SQL error for incompatible types.
+    /// ScaledInteger products follow the result-scale rules in [`decimal`] and
+    /// fail instead of wrapping on overflow.
     pub fn multiply(&self, other: &FieldValue) -> Result<FieldValue, SqlError> {
         match (self, other) {
             (FieldValue::Integer(a), FieldValue::Integer(b)) => Ok(FieldValue::Integer(a * b)),
@@ -584,26 +801,21 @@ impl FieldValue {
             (FieldValue::Integer(a), FieldValue::Float(b)) => Ok(FieldValue::Float(*a as f64 * b)),
             (FieldValue::Float(a), FieldValue::Integer(b)) => Ok(FieldValue::Float(a * *b as f64)),
 
-            // ScaledInteger multiplication - result *scale is sum of *scales
+            // ScaledInteger multiplication - result scale is the sum of the scales
             (FieldValue::ScaledInteger(a, scale_a), FieldValue::ScaledInteger(b, scale_b)) => {
-                let result_scale = *scale_a + *scale_b;
-                Ok(FieldValue::ScaledInteger(a * b, result_scale))
+                decimal::multiply(*a, *scale_a, *b, *scale_b)
             }
             (FieldValue::ScaledInteger(a, scale), FieldValue::Integer(b)) => {
-                Ok(FieldValue::ScaledInteger(a * b, *scale))
+                decimal::multiply(*a, *scale, *b, 0)
             }
             (FieldValue::Integer(a), FieldValue::ScaledInteger(b, scale)) => {
-                Ok(FieldValue::ScaledInteger(a * b, *scale))
+                decimal::multiply(*a, 0, *b, *scale)
             }
             (FieldValue::ScaledInteger(a, scale), FieldValue::Float(b)) => {
-                let scaled_b = (b * 10_i64.pow(*scale as u32) as f64).round() as i64;
-                let result_scale = *scale + *scale; // Double *scale since we scaled b
-                Ok(FieldValue::ScaledInteger(a * scaled_b, result_scale))
+                decimal::multiply(*a, *scale, decimal::float_to_unscaled(*b, *scale)?, *scale)
             }
             (FieldValue::Float(a), FieldValue::ScaledInteger(b, scale)) => {
-                let scaled_a = (a * 10_i64.pow(*scale as u32) as f64).round() as i64;
-                let result_scale = *scale + *scale; // Double *scale since we scaled a
-                Ok(FieldValue::ScaledInteger(scaled_a * b, result_scale))
+                decimal::multiply(decimal::float_to_unscaled(*a, *scale)?, *scale, *b, *scale)
             }
             (FieldValue::Null, _) | (_, FieldValue::Null) => Ok(FieldValue::Null),
             (FieldValue::Interval { .. }, _) | (_, FieldValue::Interval { .. }) => {
@@ -626,6 +838,7 @@ impl FieldValue {
     /// Supports division between numeric types (Integer, Float) with automatic
     /// type promotion. Handles division by zero appropriately.
     /// Returns appropriate SQL error for incompatible types.
+    /// ScaledInteger quotients are rounded HALF_UP at scale `max(s1, s2, 6)`.
     pub fn divide(&self, other: &FieldValue) -> Result<FieldValue, SqlError> {
         match (self, other) {
             (FieldValue::Integer(a), FieldValue::Integer(b)) => {
@@ -669,78 +882,21 @@ impl FieldValue {
                 }
             }
 
-            // ScaledInteger division - preserve precision by scaling numerator
+            // ScaledInteger division - result scale is max(s1, s2, 6)
             (FieldValue::ScaledInteger(a, scale_a), FieldValue::ScaledInteger(b, scale_b)) => {
-                if *b == 0 {
-                    Err(SqlError::ExecutionError {
-                        message: "Division by zero".to_string(),
-                        query: None,
-                    })
-                } else {
-                    // Scale the numerator by the target precision to maintain precision
-                    let target_scale = (*scale_a).max(*scale_b);
-                    let extra_precision = 4; // Add extra precision for division
-                    let scale_factor = 10_i64.pow((target_scale + extra_precision) as u32);
-                    let scaled_numerator = a * scale_factor;
-                    let result = scaled_numerator / b;
-                    // Adjust *scale to account for the extra scaling
-                    let result_scale = *scale_a + extra_precision - *scale_b;
-                    Ok(FieldValue::ScaledInteger(result, result_scale))
-                }
+                decimal::divide(*a, *scale_a, *b, *scale_b)
             }
             (FieldValue::ScaledInteger(a, scale), FieldValue::Integer(b)) => {
-                if *b == 0 {
-                    Err(SqlError::ExecutionError {
-                        message: "Division by zero".to_string(),
-                        query: None,
-                    })
-                } else {
-                    Ok(FieldValue::ScaledInteger(a / b, *scale))
-                }
+                decimal::divide(*a, *scale, *b, 0)
             }
             (FieldValue::Integer(a), FieldValue::ScaledInteger(b, scale)) => {
-                if *b == 0 {
-                    Err(SqlError::ExecutionError {
-                        message: "Division by zero".to_string(),
-                        query: None,
-                    })
-                } else {
-                    // Scale the integer numerator to match the denominator's *scale, then add extra precision
-                    let extra_precision = 4;
-                    let scale_factor = 10_i64.pow((*scale + extra_precision) as u32);
-                    let scaled_numerator = a * scale_factor;
-                    let result = scaled_numerator / b;
-                    Ok(FieldValue::ScaledInteger(result, extra_precision))
-                }
+                decimal::divide(*a, 0, *b, *scale)
             }
             (FieldValue::ScaledInteger(a, scale), FieldValue::Float(b)) => {
-                if *b == 0.0 {
-                    Err(SqlError::ExecutionError {
-                        message: "Division by zero".to_string(),
-                        query: None,
-                    })
-                } else {
-                    let scaled_b = (b * 10_i64.pow(*scale as u32) as f64).round() as i64;
-                    if scaled_b == 0 {
-                        Err(SqlError::ExecutionError {
-                            message: "Division by zero".to_string(),
-                            query: None,
-                        })
-                    } else {
-                        Ok(FieldValue::ScaledInteger(a / scaled_b, *scale))
-                    }
-                }
+                decimal::divide(*a, *scale, decimal::float_to_unscaled(*b, *scale)?, *scale)
             }
             (FieldValue::Float(a), FieldValue::ScaledInteger(b, scale)) => {
-                if *b == 0 {
-                    Err(SqlError::ExecutionError {
-                        message: "Division by zero".to_string(),
-                        query: None,
-                    })
-                } else {
-                    let scaled_a = (a * 10_i64.pow(*scale as u32) as f64).round() as i64;
-                    Ok(FieldValue::ScaledInteger(scaled_a / b, *scale))
-                }
+                decimal::divide(decimal::float_to_unscaled(*a, *scale)?, *scale, *b, *scale)
             }
             (FieldValue::Null, _) | (_, FieldValue::Null) => Ok(FieldValue::Null),
             (FieldValue::Interval { .. }, _) | (_, FieldValue::Interval { .. }) => {
@@ -766,9 +922,166 @@ impl FieldValue {
             TimeUnit::Minute => value * 60 * 1000,
             TimeUnit::Hour => value * 60 * 60 * 1000,
             TimeUnit::Day => value * 24 * 60 * 60 * 1000,
+            TimeUnit::Week => value * 7 * 24 * 60 * 60 * 1000,
+            // Calendar units have no fixed length; used only when no timestamp is involved
+            TimeUnit::Month => value * 30 * 24 * 60 * 60 * 1000,
+            TimeUnit::Year => value * 365 * 24 * 60 * 60 * 1000,
         }
     }
 
+    /// Add an interval to a wall-clock timestamp
+    ///
+    /// Month and year intervals use calendar arithmetic, clamping to the last day of the
+    /// month (Jan 31 + 1 month = Feb 29/28). All other units are fixed durations.
+    pub fn add_interval_to_datetime(
+        ts: NaiveDateTime,
+        value: i64,
+        unit: &TimeUnit,
+    ) -> Result<NaiveDateTime, SqlError> {
+        let months = match unit {
+            TimeUnit::Month => Some(value),
+            TimeUnit::Year => value.checked_mul(12),
+            _ => None,
+        };
+
+        let result = match months {
+            Some(months) => {
+                let magnitude = Months::new(months.unsigned_abs().min(u32::MAX as u64) as u32);
+                if months >= 0 {
+                    ts.checked_add_months(magnitude)
+                } else {
+                    ts.checked_sub_months(magnitude)
+                }
+            }
+            None if unit.is_calendar() => None,
+            None => ts.checked_add_signed(chrono::Duration::milliseconds(
+                Self::interval_to_millis(value, unit),
+            )),
+        };
+
+        result.ok_or_else(|| SqlError::ExecutionError {
+            message: format!(
+                "Timestamp out of range: {} + INTERVAL {} {:?}",
+                ts, value, unit
+            ),
+            query: None,
+        })
+    }
+
+    /// Add an interval to epoch milliseconds, using calendar arithmetic (in UTC) for
+    /// month and year intervals
+    fn add_interval_to_epoch_millis(
+        timestamp: i64,
+        value: i64,
+        unit: &TimeUnit,
+    ) -> Result<i64, SqlError> {
+        if !unit.is_calendar() {
+            return Ok(timestamp + Self::interval_to_millis(value, unit));
+        }
+        let ts = DateTime::from_timestamp_millis(timestamp)
+            .ok_or_else(|| SqlError::ExecutionError {
+                message: format!("Invalid timestamp: {}", timestamp),
+                query: None,
+            })?
+            .naive_utc();
+        Ok(Self::add_interval_to_datetime(ts, value, unit)?
+            .and_utc()
+            .timestamp_millis())
+    }
+
+    /// Add an interval to any temporal value, keeping the value's type
+    ///
+    /// Dates stay dates for whole-day intervals. Zone-aware timestamps apply day and
+    /// calendar units in local time so that "+ 1 day" keeps the wall-clock time across
+    /// daylight-saving changes.
+    fn add_interval_to_temporal(
+        &self,
+        value: i64,
+        unit: &TimeUnit,
+    ) -> Result<FieldValue, SqlError> {
+        match self {
+            FieldValue::Integer(ts) => {
+                Self::add_interval_to_epoch_millis(*ts, value, unit).map(FieldValue::Integer)
+            }
+            FieldValue::Timestamp(ts) => {
+                Self::add_interval_to_datetime(*ts, value, unit).map(FieldValue::Timestamp)
+            }
+            FieldValue::Date(d) => {
+                let ts = d.and_hms_opt(0, 0, 0).unwrap();
+                Self::add_interval_to_datetime(ts, value, unit).map(|result| match unit {
+                    TimeUnit::Day | TimeUnit::Week | TimeUnit::Month | TimeUnit::Year => {
+                        FieldValue::Date(result.date())
+                    }
+                    _ => FieldValue::Timestamp(result),
+                })
+            }
+            FieldValue::TimestampTz(ts) => match unit {
+                TimeUnit::Day | TimeUnit::Week | TimeUnit::Month | TimeUnit::Year => {
+                    let zone = ts.timezone();
+                    Self::add_interval_to_datetime(ts.naive_local(), value, unit).and_then(
+                        |local| {
+                            zone.from_local_datetime(&local)
+                                .earliest()
+                                .map(FieldValue::TimestampTz)
+                                .ok_or_else(|| SqlError::ExecutionError {
+                                    message: format!(
+                                        "Local time {} does not exist in time zone {}",
+                                        local,
+                                        zone.name()
+                                    ),
+                                    query: None,
+                                })
+                        },
+                    )
+                }
+                _ => ts
+                    .checked_add_signed(chrono::Duration::milliseconds(Self::interval_to_millis(
+                        value, unit,
+                    )))
+                    .map(FieldValue::TimestampTz)
+                    .ok_or_else(|| SqlError::ExecutionError {
+                        message: "Timestamp out of range".to_string(),
+                        query: None,
+                    }),
+            },
+            _ => Err(SqlError::TypeError {
+                expected: "timestamp".to_string(),
+                actual: self.type_name().to_string(),
+                value: None,
+            }),
+        }
+    }
+
+    /// Check if this value is a DATE, TIMESTAMP or TIMESTAMPTZ
+    pub fn is_temporal(&self) -> bool {
+        matches!(
+            self,
+            FieldValue::Date(_) | FieldValue::Timestamp(_) | FieldValue::TimestampTz(_)
+        )
+    }
+
+    /// Difference between two timestamps as a millisecond interval
+    fn timestamp_difference(&self, other: &FieldValue) -> Option<FieldValue> {
+        let as_utc = |value: &FieldValue| match value {
+            FieldValue::Timestamp(ts) => Some(*ts),
+            FieldValue::TimestampTz(ts) => Some(ts.naive_utc()),
+            _ => None,
+        };
+        let millis = match (self, other) {
+            (FieldValue::Date(a), FieldValue::Date(b)) => {
+                return Some(FieldValue::Interval {
+                    value: (*a - *b).num_days(),
+                    unit: TimeUnit::Day,
+                });
+            }
+            _ => (as_utc(self)? - as_utc(other)?).num_milliseconds(),
+        };
+        Some(FieldValue::Interval {
+            value: millis,
+            unit: TimeUnit::Millisecond,
+        })
+    }
+
     /// Create a ScaledInteger from an f64 with specified decimal places
     ///
     /// This is the preferred way to create financial values from floating point numbers.
@@ -828,8 +1141,8 @@ impl FieldValue {
 /// timing within the stream.
 #[derive(Debug, Clone)]
 pub struct StreamRecord {
-    /// The actual field data for this record
-    pub fields: HashMap<String, FieldValue>,
+    /// The actual field data for this record, laid out by a shared schema
+    pub fields: RecordFields,
     /// Timestamp when this record was created (milliseconds since epoch)
     pub timestamp: i64,
     /// Offset of this record within its partition
@@ -847,9 +1160,9 @@ impl StreamRecord {
     ///
     /// This constructor creates a record with the specified field data and
     /// default values for metadata fields (timestamp=0, offset=0, partition=0, no headers).
-    pub fn new(fields: HashMap<String, FieldValue>) -> Self {
+    pub fn new(fields: impl Into<RecordFields>) -> Self {
         Self {
-            fields,
+            fields: fields.into(),
             timestamp: 0,
             offset: 0,
             partition: 0,
@@ -861,14 +1174,14 @@ impl StreamRecord {
     ///
     /// This constructor allows setting all record metadata along with the field data.
     pub fn with_metadata(
-        fields: HashMap<String, FieldValue>,
+        fields: impl Into<RecordFields>,
         timestamp: i64,
         offset: i64,
         partition: i32,
         headers: HashMap<String, String>,
     ) -> Self {
         Self {
-            fields,
+            fields: fields.into(),
             timestamp,
             offset,
             partition,
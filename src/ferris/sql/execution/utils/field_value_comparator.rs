@@ -24,7 +24,11 @@ impl FieldValueComparator {
             (FieldValue::String(a), FieldValue::String(b)) => a == b,
             (FieldValue::Boolean(a), FieldValue::Boolean(b)) => a == b,
             (FieldValue::Date(a), FieldValue::Date(b)) => a == b,
+            (FieldValue::Time(a), FieldValue::Time(b)) => a == b,
+            (FieldValue::Bytes(a), FieldValue::Bytes(b)) => a == b,
+            (FieldValue::Uuid(a), FieldValue::Uuid(b)) => a == b,
             (FieldValue::Timestamp(a), FieldValue::Timestamp(b)) => a == b,
+            (FieldValue::TimestampTz(a), FieldValue::TimestampTz(b)) => a == b,
             (FieldValue::Decimal(a), FieldValue::Decimal(b)) => a == b,
             (
                 FieldValue::Interval {
@@ -69,7 +73,11 @@ impl FieldValueComparator {
             (FieldValue::String(a), FieldValue::String(b)) => a == b,
             (FieldValue::Boolean(a), FieldValue::Boolean(b)) => a == b,
             (FieldValue::Date(a), FieldValue::Date(b)) => a == b,
+            (FieldValue::Time(a), FieldValue::Time(b)) => a == b,
+            (FieldValue::Bytes(a), FieldValue::Bytes(b)) => a == b,
+            (FieldValue::Uuid(a), FieldValue::Uuid(b)) => a == b,
             (FieldValue::Timestamp(a), FieldValue::Timestamp(b)) => a == b,
+            (FieldValue::TimestampTz(a), FieldValue::TimestampTz(b)) => a == b,
             (FieldValue::Decimal(a), FieldValue::Decimal(b)) => a == b,
             (
                 FieldValue::Interval {
@@ -17,6 +17,7 @@ impl TimeExtractor {
                     FieldValue::Integer(ts) => *ts,
                     FieldValue::Float(ts) => *ts as i64,
                     FieldValue::Timestamp(ts) => ts.and_utc().timestamp_millis(),
+                    FieldValue::TimestampTz(ts) => ts.timestamp_millis(),
                     FieldValue::String(s) => {
                         // Try to parse as timestamp
                         s.parse::<i64>().unwrap_or(record.timestamp)
@@ -0,0 +1,1236 @@
+//! SQL Formatter
+//!
+//! This module turns parsed queries back into SQL text. Every AST node implements
+//! [`Display`](fmt::Display) as compact single-line SQL, and [`format_query`] lays a
+//! statement out in the canonical multi-line form that `sql-validator --format` writes,
+//! running jobs store and EXPLAIN prints.
+//!
+//! Both forms are canonical: keywords are upper case, WITH properties are sorted by key,
+//! durations use their largest exact unit and expressions get only the parentheses their
+//! precedence needs. Parsing the output gives back an equal AST, so statements that parse
+//! to the same AST format to the same text.
+//!
+//! The round trip holds for every statement the parser produces. Nodes it never builds -
+//! UPDATE, DELETE, INSERT ... VALUES, TABLE and subquery sources, LIKE, `%` and unary
+//! operators other than NOT - are printed as standard SQL.
+//!
+//! ## Scripts
+//!
+//! [`format_script`] formats every statement of a `.sql` application and keeps the comments
+//! between statements. Comments inside a statement are not part of the AST, so such
+//! statements are kept as written, as are statements with `${VAR}` references, which the
+//! parser replaces by their values.
+
+use crate::ferris::sql::ast::{
+    AlterOperation, BinaryOperator, ColumnDef, DeploymentStrategy, EmitMode, Expr, FrameBound,
+    FrameType, InsertSource, JoinClause, JoinType, LiteralValue, MergeClause, MergeMatchedAction,
+    NodeSpan, OrderByExpr, OrderDirection, OverClause, SelectField, ShowResourceType, StreamSource,
+    StreamingQuery, SubqueryType, TableConstraint, TimeUnit, UnaryOperator, WindowFrame,
+    WindowSpec,
+};
+use crate::ferris::sql::error::diagnostics::Diagnostic;
+use crate::ferris::sql::parser::StreamingSqlParser;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Widest line [`format_query`] keeps a SELECT list, column list or WITH clause on
+const MAX_LINE_WIDTH: usize = 80;
+
+/// Indentation of list items that [`format_query`] puts on their own lines
+const INDENT: &str = "    ";
+
+/// Format a statement in the canonical multi-line layout
+///
+/// Every clause starts a new line; SELECT lists, column lists and WITH properties that
+/// do not fit on one line get one item per line.
+///
+/// ```rust
+/// use ferrisstreams::ferris::sql::formatter::format_query;
+/// use ferrisstreams::ferris::sql::parser::StreamingSqlParser;
+///
+/// let query = StreamingSqlParser::new()
+///     .parse("select id, amount*2 as double from orders where amount>10 limit 5")
+///     .unwrap();
+/// assert_eq!(
+///     format_query(&query),
+///     "SELECT id, amount * 2 AS double\nFROM orders\nWHERE amount > 10\nLIMIT 5"
+/// );
+/// ```
+pub fn format_query(query: &StreamingQuery) -> String {
+    Layout::Pretty.statement(query)
+}
+
+/// Format every `;`-separated statement of a script, keeping the comments between them
+///
+/// Statements are separated by a blank line and end with `;`. A comment on the line of a
+/// statement's `;` stays there; other comments keep their own lines above the statement
+/// that follows them. Nothing is formatted unless every statement parses, in which case
+/// the diagnostics of all statements are returned.
+pub fn format_script(sql: &str) -> Result<String, Vec<Diagnostic>> {
+    let parser = StreamingSqlParser::new();
+    let statements = parser.parse_script(sql);
+    let errors: Vec<Diagnostic> = statements
+        .iter()
+        .filter_map(|statement| statement.result.as_ref().err())
+        .flatten()
+        .cloned()
+        .collect();
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let mut script = ScriptWriter::default();
+    let mut position = 0;
+    for statement in &statements {
+        let Ok(query) = &statement.result else {
+            continue;
+        };
+        script.gap(&sql[position..statement.span.start]);
+        let text = &sql[statement.span.start..statement.span.end];
+        script.statement(&format_statement(&parser, text, query));
+        position = statement.span.end;
+    }
+    script.gap(&sql[position..]);
+    Ok(script.finish())
+}
+
+/// Canonical text of a script statement, or its text as written when formatting it would
+/// lose something
+fn format_statement(parser: &StreamingSqlParser, text: &str, query: &StreamingQuery) -> String {
+    if has_comment(text) || text.contains("${") {
+        return text.to_string();
+    }
+    let formatted = format_query(query);
+    match parser.parse(&formatted) {
+        Ok(reparsed) if &reparsed == query => formatted,
+        _ => text.to_string(),
+    }
+}
+
+/// Whether `text` has a `--` or `/* */` comment outside of string literals
+fn has_comment(text: &str) -> bool {
+    let mut quote = None;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match quote {
+            Some(open) if c == open => quote = None,
+            Some(_) => {}
+            None if c == '\'' || c == '"' => quote = Some(c),
+            None if matches!((c, chars.peek()), ('-', Some('-')) | ('/', Some('*'))) => {
+                return true
+            }
+            None => {}
+        }
+    }
+    false
+}
+
+/// Output of [`format_script`], built from statements and the comments around them
+#[derive(Debug, Default)]
+struct ScriptWriter {
+    text: String,
+    /// Line breaks in the input since the last statement or comment
+    newlines: usize,
+    /// Nothing but same-line comments has been written since the last statement
+    after_statement: bool,
+}
+
+impl ScriptWriter {
+    /// Keep the comments of the text between two statements
+    fn gap(&mut self, gap: &str) {
+        let mut rest = gap;
+        loop {
+            let trimmed = rest.trim_start_matches(|c: char| c.is_whitespace() || c == ';');
+            self.newlines += rest[..rest.len() - trimmed.len()].matches('\n').count();
+            rest = trimmed;
+
+            let end = if rest.starts_with("--") {
+                rest.find('\n').unwrap_or(rest.len())
+            } else if rest.starts_with("/*") {
+                rest.find("*/").map_or(rest.len(), |end| end + 2)
+            } else {
+                return;
+            };
+            self.push(rest[..end].trim_end(), false);
+            rest = &rest[end..];
+        }
+    }
+
+    fn statement(&mut self, statement: &str) {
+        self.push(&format!("{};", statement), true);
+    }
+
+    fn push(&mut self, piece: &str, is_statement: bool) {
+        if !self.text.is_empty() {
+            if !is_statement && self.newlines == 0 {
+                self.text.push(' ');
+            } else {
+                // Statements are separated by a blank line, comments keep theirs
+                self.text.push('\n');
+                if self.after_statement || self.newlines >= 2 {
+                    self.text.push('\n');
+                }
+                self.after_statement = false;
+            }
+        }
+        self.text.push_str(piece);
+        self.newlines = 0;
+        self.after_statement |= is_statement;
+    }
+
+    fn finish(mut self) -> String {
+        if !self.text.is_empty() {
+            self.text.push('\n');
+        }
+        self.text
+    }
+}
+
+/// Line layout of a statement
+#[derive(Debug, Clone, Copy)]
+enum Layout {
+    /// Everything on one line
+    Compact,
+    /// One clause per line, long lists one item per line
+    Pretty,
+}
+
+impl Layout {
+    fn clauses(self, clauses: Vec<String>) -> String {
+        clauses.join(match self {
+            Layout::Compact => " ",
+            Layout::Pretty => "\n",
+        })
+    }
+
+    /// `open`, the comma-separated `items` and `close`
+    fn list(self, open: &str, items: &[String], close: &str) -> String {
+        let line = format!("{}{}{}", open, items.join(", "), close);
+        let fits = line.chars().count() <= MAX_LINE_WIDTH && !line.contains('\n');
+        if matches!(self, Layout::Compact) || fits {
+            return line;
+        }
+
+        let mut text = open.trim_end().to_string();
+        for (i, item) in items.iter().enumerate() {
+            text.push_str(if i == 0 { "\n" } else { ",\n" });
+            text.push_str(&indent(item));
+        }
+        if !close.is_empty() {
+            text.push('\n');
+            text.push_str(close);
+        }
+        text
+    }
+
+    /// `keyword ('key' = 'value', ...)` with the properties sorted by key
+    fn properties(self, keyword: &str, properties: &HashMap<String, String>) -> String {
+        let items: Vec<String> = properties
+            .iter()
+            .collect::<BTreeMap<_, _>>()
+            .into_iter()
+            .map(|(key, value)| format!("{} = {}", quote(key), quote(value)))
+            .collect();
+        self.list(&format!("{} (", keyword), &items, ")")
+    }
+
+    fn statement(self, query: &StreamingQuery) -> String {
+        match query {
+            StreamingQuery::Select { .. } => self.clauses(self.select_clauses(query, None)),
+            StreamingQuery::CreateStream {
+                name,
+                columns,
+                as_select,
+                properties,
+                emit_mode,
+                ..
+            } => {
+                let mut clauses = vec![
+                    self.create_head("STREAM", name, columns.as_deref(), &[]),
+                    self.statement(as_select),
+                ];
+                self.push_properties(&mut clauses, properties);
+                clauses.extend(emit_mode.iter().map(|mode| format!("EMIT {}", mode)));
+                self.clauses(clauses)
+            }
+            StreamingQuery::CreateTable {
+                name,
+                columns,
+                constraints,
+                as_select,
+                properties,
+                emit_mode,
+                ..
+            } => {
+                let mut clauses = vec![
+                    self.create_head("TABLE", name, columns.as_deref(), constraints),
+                    self.statement(as_select),
+                ];
+                self.push_properties(&mut clauses, properties);
+                clauses.extend(emit_mode.iter().map(|mode| format!("EMIT {}", mode)));
+                self.clauses(clauses)
+            }
+            StreamingQuery::CreateStreamInto {
+                name,
+                columns,
+                as_select,
+                into_clause,
+                properties,
+                emit_mode,
+                ..
+            } => {
+                let mut clauses = vec![
+                    self.create_head("STREAM", name, columns.as_deref(), &[]),
+                    self.statement(as_select),
+                    format!("INTO {}", name_or_string(&into_clause.sink_name)),
+                ];
+                self.push_properties(&mut clauses, &properties.clone().into_legacy_format());
+                clauses.extend(emit_mode.iter().map(|mode| format!("EMIT {}", mode)));
+                self.clauses(clauses)
+            }
+            StreamingQuery::CreateTableInto {
+                name,
+                columns,
+                constraints,
+                as_select,
+                into_clause,
+                properties,
+                emit_mode,
+                ..
+            } => {
+                let mut clauses = vec![
+                    self.create_head("TABLE", name, columns.as_deref(), constraints),
+                    self.statement(as_select),
+                    format!("INTO {}", name_or_string(&into_clause.sink_name)),
+                ];
+                self.push_properties(&mut clauses, &properties.clone().into_legacy_format());
+                clauses.extend(emit_mode.iter().map(|mode| format!("EMIT {}", mode)));
+                self.clauses(clauses)
+            }
+            StreamingQuery::Alter {
+                object_type,
+                name,
+                operation,
+                ..
+            } => {
+                let head = format!("ALTER {} {}", object_type.to_string().to_uppercase(), name);
+                match operation {
+                    AlterOperation::SetProperties { properties } => {
+                        self.properties(&format!("{} SET", head), properties)
+                    }
+                    AlterOperation::AddColumn { .. } => format!("{} {}", head, operation),
+                }
+            }
+            StreamingQuery::Drop {
+                object_type,
+                name,
+                if_exists,
+                ..
+            } => format!(
+                "DROP {} {}{}",
+                object_type.to_string().to_uppercase(),
+                if *if_exists { "IF EXISTS " } else { "" },
+                name
+            ),
+            StreamingQuery::Show {
+                resource_type,
+                pattern,
+                ..
+            } => {
+                let mut text = match resource_type {
+                    ShowResourceType::Describe { .. } => resource_type.to_string(),
+                    _ => format!("SHOW {}", resource_type),
+                };
+                if let Some(pattern) = pattern {
+                    text.push_str(&format!(" LIKE {}", quote(pattern)));
+                }
+                text
+            }
+            StreamingQuery::StartJob {
+                name,
+                query,
+                properties,
+                savepoint,
+                ..
+            } => {
+                let start = match savepoint {
+                    Some(path) => format!("START JOB {} FROM SAVEPOINT {} AS", name, quote(path)),
+                    None => format!("START JOB {} AS", name),
+                };
+                let mut clauses = vec![start, self.statement(query)];
+                self.push_properties(&mut clauses, properties);
+                self.clauses(clauses)
+            }
+            StreamingQuery::StopJob {
+                name,
+                force,
+                savepoint,
+                ..
+            } => format!(
+                "STOP JOB {}{}{}",
+                name,
+                if *force { " FORCE" } else { "" },
+                if *savepoint { " WITH SAVEPOINT" } else { "" }
+            ),
+            StreamingQuery::PauseJob { name, .. } => format!("PAUSE JOB {}", name),
+            StreamingQuery::ResumeJob { name, .. } => format!("RESUME JOB {}", name),
+            StreamingQuery::DeployJob {
+                name,
+                version,
+                query,
+                properties,
+                strategy,
+                ..
+            } => {
+                let mut clauses = vec![
+                    format!("DEPLOY JOB {} VERSION {} AS", name, quote(version)),
+                    self.statement(query),
+                ];
+                self.push_properties(&mut clauses, properties);
+                clauses.push(format!("STRATEGY {}", strategy));
+                self.clauses(clauses)
+            }
+            StreamingQuery::RollbackJob {
+                name,
+                target_version,
+                ..
+            } => match target_version {
+                Some(version) => format!("ROLLBACK JOB {} VERSION {}", name, quote(version)),
+                None => format!("ROLLBACK JOB {}", name),
+            },
+            StreamingQuery::InsertInto {
+                table_name,
+                columns,
+                source,
+                ..
+            } => match (columns, source) {
+                // The parser's only INSERT form: SELECT ... INTO target [WITH (...)]
+                (None, InsertSource::Select { query })
+                    if matches!(query.as_ref(), StreamingQuery::Select { .. }) =>
+                {
+                    self.clauses(self.select_clauses(query, Some(table_name)))
+                }
+                _ => {
+                    let mut head = format!("INSERT INTO {}", name_or_string(table_name));
+                    if let Some(columns) = columns {
+                        head.push_str(&format!(" ({})", columns.join(", ")));
+                    }
+                    self.insert_source(head, source)
+                }
+            },
+            StreamingQuery::Update {
+                table_name,
+                assignments,
+                where_clause,
+                ..
+            } => {
+                let assignments: Vec<String> = assignments
+                    .iter()
+                    .map(|(column, value)| format!("{} = {}", column, value))
+                    .collect();
+                let mut clauses = vec![
+                    format!("UPDATE {}", name_or_string(table_name)),
+                    format!("SET {}", assignments.join(", ")),
+                ];
+                clauses.extend(where_clause.iter().map(|c| format!("WHERE {}", c)));
+                self.clauses(clauses)
+            }
+            StreamingQuery::Delete {
+                table_name,
+                where_clause,
+                ..
+            } => {
+                let mut clauses = vec![format!("DELETE FROM {}", name_or_string(table_name))];
+                clauses.extend(where_clause.iter().map(|c| format!("WHERE {}", c)));
+                self.clauses(clauses)
+            }
+            StreamingQuery::Merge {
+                target,
+                target_alias,
+                source,
+                source_alias,
+                on,
+                clauses: merge_clauses,
+                properties,
+                ..
+            } => {
+                let mut clauses = vec![
+                    format!("MERGE INTO {}{}", target, alias(target_alias)),
+                    format!("USING {}{}", source, alias(source_alias)),
+                    format!("ON {}", on),
+                ];
+                clauses.extend(merge_clauses.iter().map(ToString::to_string));
+                if let Some(properties) = properties {
+                    self.push_properties(&mut clauses, properties);
+                }
+                self.clauses(clauses)
+            }
+            StreamingQuery::Upsert {
+                table_name,
+                columns,
+                key_columns,
+                source,
+                ..
+            } => {
+                let mut head = format!("UPSERT INTO {}", table_name);
+                if let Some(columns) = columns {
+                    head.push_str(&format!(" ({})", columns.join(", ")));
+                }
+                // The key defaults to the first listed column
+                let default_key = columns.as_ref().and_then(|c| c.first());
+                if key_columns.len() != 1 || default_key != key_columns.first() {
+                    head.push_str(&format!(" KEY ({})", key_columns.join(", ")));
+                }
+                self.insert_source(head, source)
+            }
+            StreamingQuery::Union {
+                left, right, all, ..
+            } => self.clauses(vec![
+                self.statement(left),
+                if *all { "UNION ALL" } else { "UNION" }.to_string(),
+                self.statement(right),
+            ]),
+        }
+    }
+
+    /// Clauses of a SELECT, with an INTO target between its EMIT and WITH clauses
+    fn select_clauses(self, query: &StreamingQuery, into: Option<&str>) -> Vec<String> {
+        let StreamingQuery::Select {
+            fields,
+            from,
+            from_alias,
+            joins,
+            where_clause,
+            group_by,
+            having,
+            window,
+            order_by,
+            limit,
+            emit_mode,
+            properties,
+            ..
+        } = query
+        else {
+            return vec![self.statement(query)];
+        };
+
+        let fields: Vec<String> = fields.iter().map(ToString::to_string).collect();
+        let mut clauses = vec![self.list("SELECT ", &fields, "")];
+        // An empty stream name stands for a SELECT without FROM
+        if !matches!(from, StreamSource::Stream(name) if name.is_empty()) {
+            clauses.push(format!("FROM {}{}", from, alias(from_alias)));
+        }
+        clauses.extend(joins.iter().flatten().map(ToString::to_string));
+        if let Some(condition) = where_clause {
+            clauses.push(format!("WHERE {}", condition));
+        }
+        if let Some(expressions) = group_by {
+            clauses.push(format!("GROUP BY {}", comma_separated(expressions)));
+        }
+        if let Some(condition) = having {
+            clauses.push(format!("HAVING {}", condition));
+        }
+        if let Some(window) = window {
+            clauses.push(format!("WINDOW {}", window));
+        }
+        if let Some(order_by) = order_by {
+            clauses.push(format!("ORDER BY {}", comma_separated(order_by)));
+        }
+        if let Some(limit) = limit {
+            clauses.push(format!("LIMIT {}", limit));
+        }
+        if let Some(mode) = emit_mode {
+            clauses.push(format!("EMIT {}", mode));
+        }
+        if let Some(target) = into {
+            clauses.push(format!("INTO {}", name_or_string(target)));
+        }
+        if let Some(properties) = properties {
+            self.push_properties(&mut clauses, properties);
+        }
+        clauses
+    }
+
+    /// `CREATE STREAM|TABLE name [(columns, constraints)] AS`
+    fn create_head(
+        self,
+        kind: &str,
+        name: &str,
+        columns: Option<&[ColumnDef]>,
+        constraints: &[TableConstraint],
+    ) -> String {
+        let head = format!("CREATE {} {}", kind, name);
+        if columns.is_none() && constraints.is_empty() {
+            return format!("{} AS", head);
+        }
+        let items: Vec<String> = columns
+            .into_iter()
+            .flatten()
+            .map(ToString::to_string)
+            .chain(constraints.iter().map(ToString::to_string))
+            .collect();
+        self.list(&format!("{} (", head), &items, ") AS")
+    }
+
+    fn insert_source(self, head: String, source: &InsertSource) -> String {
+        match source {
+            InsertSource::Values { .. } => format!("{} {}", head, source),
+            InsertSource::Select { query } => self.clauses(vec![head, self.statement(query)]),
+        }
+    }
+
+    fn push_properties(self, clauses: &mut Vec<String>, properties: &HashMap<String, String>) {
+        if !properties.is_empty() {
+            clauses.push(self.properties("WITH", properties));
+        }
+    }
+}
+
+fn indent(text: &str) -> String {
+    text.lines()
+        .map(|line| format!("{}{}", INDENT, line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn comma_separated<T: fmt::Display>(items: &[T]) -> String {
+    items
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// ` alias`, or nothing without an alias
+fn alias(alias: &Option<String>) -> String {
+    alias
+        .as_ref()
+        .map(|alias| format!(" {}", alias))
+        .unwrap_or_default()
+}
+
+/// String literal for `value`
+///
+/// The tokenizer has no escapes, so the value is quoted with the quote it does not contain.
+fn quote(value: &str) -> String {
+    if value.contains('\'') && !value.contains('"') {
+        format!("\"{}\"", value)
+    } else {
+        format!("'{}'", value)
+    }
+}
+
+/// A stream, sink or table name where the parser also accepts a string: the name itself
+/// when it reads back as an identifier, a string literal otherwise
+fn name_or_string(name: &str) -> String {
+    let mut chars = name.chars();
+    let is_identifier = chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+        && !keywords().contains(&name.to_uppercase());
+    match is_identifier {
+        true => name.to_string(),
+        false => quote(name),
+    }
+}
+
+fn keywords() -> &'static HashSet<String> {
+    static KEYWORDS: OnceLock<HashSet<String>> = OnceLock::new();
+    KEYWORDS.get_or_init(|| {
+        StreamingSqlParser::new()
+            .keywords()
+            .into_iter()
+            .map(str::to_string)
+            .collect()
+    })
+}
+
+/// Duration literal of WINDOW and WITHIN clauses in its largest exact unit, e.g. `90s`
+fn duration(duration: &Duration) -> String {
+    const UNITS: [(u128, &str); 7] = [
+        (86_400_000_000_000, "d"),
+        (3_600_000_000_000, "h"),
+        (60_000_000_000, "m"),
+        (1_000_000_000, "s"),
+        (1_000_000, "ms"),
+        (1_000, "us"),
+        (1, "ns"),
+    ];
+    let nanos = duration.as_nanos();
+    if nanos == 0 {
+        return "0s".to_string();
+    }
+    let (unit_nanos, unit) = UNITS
+        .into_iter()
+        .find(|(unit_nanos, _)| nanos % unit_nanos == 0)
+        .unwrap_or((1, "ns"));
+    format!("{}{}", nanos / unit_nanos, unit)
+}
+
+impl fmt::Display for StreamingQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&Layout::Compact.statement(self))
+    }
+}
+
+impl fmt::Display for SelectField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SelectField::Column(column) => f.write_str(column),
+            SelectField::AliasedColumn { column, alias } => write!(f, "{} AS {}", column, alias),
+            SelectField::Expression { expr, alias } => match alias {
+                Some(alias) => write!(f, "{} AS {}", expr, alias),
+                None => write!(f, "{}", expr),
+            },
+            SelectField::Wildcard => f.write_str("*"),
+        }
+    }
+}
+
+impl fmt::Display for StreamSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamSource::Stream(name) | StreamSource::Table(name) => {
+                f.write_str(&name_or_string(name))
+            }
+            StreamSource::Uri(uri) => f.write_str(&quote(uri)),
+            StreamSource::Subquery(query) => write!(f, "({})", query),
+        }
+    }
+}
+
+impl fmt::Display for JoinType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            JoinType::Inner => "INNER JOIN",
+            JoinType::Left => "LEFT JOIN",
+            JoinType::Right => "RIGHT JOIN",
+            JoinType::FullOuter => "FULL OUTER JOIN",
+        })
+    }
+}
+
+impl fmt::Display for JoinClause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}{} ON {}",
+            self.join_type,
+            self.right_source,
+            alias(&self.right_alias),
+            self.condition
+        )?;
+        match &self.window {
+            Some(window) => write!(f, " WITHIN {}", duration(&window.time_window)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for WindowSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WindowSpec::Tumbling {
+                size, time_zone, ..
+            } => match time_zone {
+                Some(zone) => write!(f, "TUMBLING({}, {})", duration(size), quote(zone)),
+                None => write!(f, "TUMBLING({})", duration(size)),
+            },
+            WindowSpec::Sliding { size, advance, .. } => {
+                write!(f, "SLIDING({}, {})", duration(size), duration(advance))
+            }
+            WindowSpec::Session { gap, .. } => write!(f, "SESSION({})", duration(gap)),
+        }
+    }
+}
+
+impl fmt::Display for EmitMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            EmitMode::Changes => "CHANGES",
+            EmitMode::Final => "FINAL",
+        })
+    }
+}
+
+impl fmt::Display for OrderDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            OrderDirection::Asc => "ASC",
+            OrderDirection::Desc => "DESC",
+        })
+    }
+}
+
+impl fmt::Display for OrderByExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // ASC is the default and left out
+        match self.direction {
+            OrderDirection::Asc => write!(f, "{}", self.expr),
+            OrderDirection::Desc => write!(f, "{} {}", self.expr, self.direction),
+        }
+    }
+}
+
+impl fmt::Display for OverClause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if !self.partition_by.is_empty() {
+            parts.push(format!("PARTITION BY {}", self.partition_by.join(", ")));
+        }
+        if !self.order_by.is_empty() {
+            parts.push(format!("ORDER BY {}", comma_separated(&self.order_by)));
+        }
+        if let Some(frame) = &self.window_frame {
+            parts.push(frame.to_string());
+        }
+        write!(f, "OVER ({})", parts.join(" "))
+    }
+}
+
+impl fmt::Display for FrameType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FrameType::Rows => "ROWS",
+            FrameType::Range => "RANGE",
+        })
+    }
+}
+
+impl fmt::Display for WindowFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.end_bound {
+            Some(end) => write!(
+                f,
+                "{} BETWEEN {} AND {}",
+                self.frame_type, self.start_bound, end
+            ),
+            None => write!(f, "{} {}", self.frame_type, self.start_bound),
+        }
+    }
+}
+
+impl fmt::Display for FrameBound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameBound::UnboundedPreceding => f.write_str("UNBOUNDED PRECEDING"),
+            FrameBound::Preceding(offset) => write!(f, "{} PRECEDING", offset),
+            FrameBound::CurrentRow => f.write_str("CURRENT ROW"),
+            FrameBound::Following(offset) => write!(f, "{} FOLLOWING", offset),
+            FrameBound::UnboundedFollowing => f.write_str("UNBOUNDED FOLLOWING"),
+        }
+    }
+}
+
+impl fmt::Display for ColumnDef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.name, self.data_type)?;
+        if !self.nullable {
+            f.write_str(" NOT NULL")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TableConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(name) = self.name() {
+            write!(f, "CONSTRAINT {} ", name)?;
+        }
+        match self {
+            TableConstraint::PrimaryKey { columns, .. } => {
+                write!(f, "PRIMARY KEY ({})", columns.join(", "))
+            }
+            TableConstraint::Check { expr, .. } => write!(f, "CHECK ({})", expr),
+        }
+    }
+}
+
+impl fmt::Display for AlterOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlterOperation::AddColumn {
+                column,
+                if_not_exists,
+            } => write!(
+                f,
+                "ADD COLUMN {}{}",
+                if *if_not_exists { "IF NOT EXISTS " } else { "" },
+                column
+            ),
+            AlterOperation::SetProperties { properties } => {
+                f.write_str(&Layout::Compact.properties("SET", properties))
+            }
+        }
+    }
+}
+
+impl fmt::Display for ShowResourceType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let optional_name = |name: &Option<String>| alias(name);
+        match self {
+            ShowResourceType::Streams => f.write_str("STREAMS"),
+            ShowResourceType::Tables => f.write_str("TABLES"),
+            ShowResourceType::Topics => f.write_str("TOPICS"),
+            ShowResourceType::Functions => f.write_str("FUNCTIONS"),
+            ShowResourceType::Schema { name } => write!(f, "SCHEMA {}", name),
+            ShowResourceType::Properties {
+                resource_type,
+                name,
+            } => write!(f, "PROPERTIES {} {}", resource_type, name),
+            ShowResourceType::Jobs => f.write_str("JOBS"),
+            ShowResourceType::JobStatus { name } => write!(f, "STATUS{}", optional_name(name)),
+            ShowResourceType::JobVersions { name } => write!(f, "VERSIONS {}", name),
+            ShowResourceType::JobMetrics { name } => write!(f, "METRICS{}", optional_name(name)),
+            ShowResourceType::Savepoints { name } => {
+                write!(f, "SAVEPOINTS{}", optional_name(name))
+            }
+            ShowResourceType::Partitions { name } => write!(f, "PARTITIONS {}", name),
+            // DESCRIBE is a statement of its own rather than a SHOW resource
+            ShowResourceType::Describe { name } => write!(f, "DESCRIBE {}", name),
+        }
+    }
+}
+
+impl fmt::Display for DeploymentStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeploymentStrategy::BlueGreen => f.write_str("BLUE_GREEN"),
+            DeploymentStrategy::Canary { percentage } => write!(f, "CANARY({})", percentage),
+            DeploymentStrategy::Rolling => f.write_str("ROLLING"),
+            DeploymentStrategy::Replace => f.write_str("REPLACE"),
+        }
+    }
+}
+
+impl fmt::Display for InsertSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InsertSource::Values { rows } => {
+                let rows: Vec<String> = rows
+                    .iter()
+                    .map(|row| format!("({})", comma_separated(row)))
+                    .collect();
+                write!(f, "VALUES {}", rows.join(", "))
+            }
+            InsertSource::Select { query } => write!(f, "{}", query),
+        }
+    }
+}
+
+impl fmt::Display for MergeClause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (matched, condition) = match self {
+            MergeClause::Matched { condition, .. } => ("MATCHED", condition),
+            MergeClause::NotMatched { condition, .. } => ("NOT MATCHED", condition),
+        };
+        write!(f, "WHEN {}", matched)?;
+        if let Some(condition) = condition {
+            write!(f, " AND {}", condition)?;
+        }
+        match self {
+            MergeClause::Matched {
+                action: MergeMatchedAction::Update { assignments },
+                ..
+            } => {
+                let assignments: Vec<String> = assignments
+                    .iter()
+                    .map(|(column, value)| format!("{} = {}", column, value))
+                    .collect();
+                write!(f, " THEN UPDATE SET {}", assignments.join(", "))
+            }
+            MergeClause::Matched {
+                action: MergeMatchedAction::Delete,
+                ..
+            } => f.write_str(" THEN DELETE"),
+            MergeClause::NotMatched {
+                columns, values, ..
+            } => write!(
+                f,
+                " THEN INSERT ({}) VALUES ({})",
+                columns.join(", "),
+                comma_separated(values)
+            ),
+        }
+    }
+}
+
+/// Binding strength of an expression in the parser's grammar, loosest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Precedence {
+    /// Lambdas only appear as whole function arguments
+    Lambda,
+    Or,
+    And,
+    /// Comparisons, IS [NOT] NULL, [NOT] IN and [NOT] BETWEEN
+    Comparison,
+    Concat,
+    Additive,
+    Multiplicative,
+    AtTimeZone,
+    /// Subscripts and field accesses
+    Postfix,
+    Primary,
+}
+
+/// Precedence of a binary operator and the least its left and right operands need
+fn binary_precedence(op: &BinaryOperator) -> (Precedence, Precedence, Precedence) {
+    use Precedence::*;
+    match op {
+        BinaryOperator::Or => (Or, Or, And),
+        BinaryOperator::And => (And, And, Comparison),
+        BinaryOperator::Equal
+        | BinaryOperator::NotEqual
+        | BinaryOperator::LessThan
+        | BinaryOperator::LessThanOrEqual
+        | BinaryOperator::GreaterThan
+        | BinaryOperator::GreaterThanOrEqual
+        | BinaryOperator::Like
+        | BinaryOperator::NotLike
+        | BinaryOperator::In
+        | BinaryOperator::NotIn => (Comparison, Comparison, Additive),
+        BinaryOperator::Concat => (Concat, Concat, Additive),
+        BinaryOperator::Add | BinaryOperator::Subtract => (Additive, Additive, Multiplicative),
+        BinaryOperator::Multiply | BinaryOperator::Divide | BinaryOperator::Modulo => {
+            (Multiplicative, Multiplicative, AtTimeZone)
+        }
+    }
+}
+
+fn precedence(expr: &Expr) -> Precedence {
+    match expr {
+        Expr::BinaryOp { op, .. } => binary_precedence(op).0,
+        Expr::UnaryOp {
+            op: UnaryOperator::IsNull | UnaryOperator::IsNotNull,
+            ..
+        }
+        | Expr::Between { .. } => Precedence::Comparison,
+        Expr::Function { .. } if is_at_time_zone(expr) => Precedence::AtTimeZone,
+        Expr::Subscript { .. } | Expr::FieldAccess { .. } => Precedence::Postfix,
+        Expr::Lambda { .. } => Precedence::Lambda,
+        _ => Precedence::Primary,
+    }
+}
+
+/// `expr AT TIME ZONE zone`, which the parser turns into an AT_TIME_ZONE(expr, zone) call
+fn is_at_time_zone(expr: &Expr) -> bool {
+    matches!(expr, Expr::Function { name, args, .. } if name == "AT_TIME_ZONE" && args.len() == 2)
+}
+
+/// An operand that is parenthesized unless it binds at least as tightly as required
+struct Operand<'a>(&'a Expr, Precedence);
+
+impl fmt::Display for Operand<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Operand(expr, required) = *self;
+        if precedence(expr) < required {
+            write!(f, "({})", expr)
+        } else {
+            write!(f, "{}", expr)
+        }
+    }
+}
+
+/// Arguments of a function call; COUNT(*) is parsed as COUNT(1) and printed back as written
+fn write_arguments(f: &mut fmt::Formatter<'_>, name: &str, args: &[Expr]) -> fmt::Result {
+    if name.eq_ignore_ascii_case("COUNT")
+        && args == [Expr::Literal(LiteralValue::Integer(1), NodeSpan::default())]
+    {
+        return f.write_str("*");
+    }
+    f.write_str(&comma_separated(args))
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use Precedence::*;
+        match self {
+            Expr::Column(name, _) => f.write_str(name),
+            Expr::Literal(value, _) => write!(f, "{}", value),
+            Expr::Parameter(parameter, _) => write!(f, "{}", parameter),
+            Expr::BinaryOp {
+                left, op, right, ..
+            } => {
+                let (_, left_required, right_required) = binary_precedence(op);
+                write!(f, "{} {} ", Operand(left, left_required), op)?;
+                match (op, right.as_ref()) {
+                    // IN takes a parenthesized list or subquery
+                    (BinaryOperator::In | BinaryOperator::NotIn, Expr::List(_, _)) => {
+                        write!(f, "{}", right)
+                    }
+                    (BinaryOperator::In | BinaryOperator::NotIn, Expr::Subquery { query, .. }) => {
+                        write!(f, "({})", query)
+                    }
+                    (BinaryOperator::In | BinaryOperator::NotIn, _) => write!(f, "({})", right),
+                    _ => write!(f, "{}", Operand(right, right_required)),
+                }
+            }
+            Expr::UnaryOp { op, expr, .. } => match op {
+                // NOT applies to the primary expression after it
+                UnaryOperator::Not => write!(f, "NOT {}", Operand(expr, Primary)),
+                UnaryOperator::Minus => write!(f, "-{}", Operand(expr, Primary)),
+                UnaryOperator::Plus => write!(f, "+{}", Operand(expr, Primary)),
+                UnaryOperator::IsNull => write!(f, "{} IS NULL", Operand(expr, Comparison)),
+                UnaryOperator::IsNotNull => {
+                    write!(f, "{} IS NOT NULL", Operand(expr, Comparison))
+                }
+            },
+            Expr::Function { name, args, .. } if is_at_time_zone(self) => write!(
+                f,
+                "{} AT TIME ZONE {}",
+                Operand(&args[0], AtTimeZone),
+                Operand(&args[1], Primary)
+            ),
+            // CURRENT_TIMESTAMP is written without parentheses
+            Expr::Function { name, args, .. } if name == "CURRENT_TIMESTAMP" && args.is_empty() => {
+                f.write_str(name)
+            }
+            Expr::Function { name, args, .. } => {
+                write!(f, "{}(", name)?;
+                write_arguments(f, name, args)?;
+                f.write_str(")")
+            }
+            Expr::WindowFunction {
+                function_name,
+                args,
+                over_clause,
+                ..
+            } => {
+                write!(f, "{}(", function_name)?;
+                write_arguments(f, function_name, args)?;
+                write!(f, ") {}", over_clause)
+            }
+            Expr::Case {
+                when_clauses,
+                else_clause,
+                ..
+            } => {
+                f.write_str("CASE")?;
+                for (condition, result) in when_clauses {
+                    write!(f, " WHEN {} THEN {}", condition, result)?;
+                }
+                if let Some(result) = else_clause {
+                    write!(f, " ELSE {}", result)?;
+                }
+                f.write_str(" END")
+            }
+            Expr::List(items, _) => {
+                let items: Vec<String> = items
+                    .iter()
+                    .map(|item| Operand(item, Additive).to_string())
+                    .collect();
+                write!(f, "({})", items.join(", "))
+            }
+            Expr::Subquery {
+                query,
+                subquery_type,
+                ..
+            } => match subquery_type {
+                SubqueryType::Exists => write!(f, "EXISTS ({})", query),
+                SubqueryType::NotExists => write!(f, "NOT EXISTS ({})", query),
+                SubqueryType::Any => write!(f, "ANY ({})", query),
+                SubqueryType::All => write!(f, "ALL ({})", query),
+                SubqueryType::Scalar | SubqueryType::In | SubqueryType::NotIn => {
+                    write!(f, "({})", query)
+                }
+            },
+            Expr::Between {
+                expr,
+                low,
+                high,
+                negated,
+                ..
+            } => write!(
+                f,
+                "{} {}BETWEEN {} AND {}",
+                Operand(expr, Comparison),
+                if *negated { "NOT " } else { "" },
+                Operand(low, Additive),
+                Operand(high, Additive)
+            ),
+            Expr::Subscript { expr, index, .. } => {
+                write!(f, "{}[{}]", Operand(expr, Postfix), index)
+            }
+            // A field of a plain column would read back as a longer column name
+            Expr::FieldAccess { expr, field, .. } => match expr.as_ref() {
+                Expr::Column(_, _) => write!(f, "({}).{}", expr, field),
+                _ => write!(f, "{}.{}", Operand(expr, Postfix), field),
+            },
+            Expr::Lambda { params, body, .. } => match params.as_slice() {
+                [param] => write!(f, "{} -> {}", param, body),
+                _ => write!(f, "({}) -> {}", params.join(", "), body),
+            },
+        }
+    }
+}
+
+impl fmt::Display for BinaryOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            BinaryOperator::Add => "+",
+            BinaryOperator::Subtract => "-",
+            BinaryOperator::Multiply => "*",
+            BinaryOperator::Divide => "/",
+            BinaryOperator::Modulo => "%",
+            BinaryOperator::Equal => "=",
+            BinaryOperator::NotEqual => "!=",
+            BinaryOperator::LessThan => "<",
+            BinaryOperator::LessThanOrEqual => "<=",
+            BinaryOperator::GreaterThan => ">",
+            BinaryOperator::GreaterThanOrEqual => ">=",
+            BinaryOperator::And => "AND",
+            BinaryOperator::Or => "OR",
+            BinaryOperator::Like => "LIKE",
+            BinaryOperator::NotLike => "NOT LIKE",
+            BinaryOperator::Concat => "||",
+            BinaryOperator::In => "IN",
+            BinaryOperator::NotIn => "NOT IN",
+        })
+    }
+}
+
+impl fmt::Display for UnaryOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            UnaryOperator::Not => "NOT",
+            UnaryOperator::Minus => "-",
+            UnaryOperator::Plus => "+",
+            UnaryOperator::IsNull => "IS NULL",
+            UnaryOperator::IsNotNull => "IS NOT NULL",
+        })
+    }
+}
+
+impl fmt::Display for LiteralValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LiteralValue::String(value) => f.write_str(&quote(value)),
+            LiteralValue::Integer(value) => write!(f, "{}", value),
+            // Debug output always has a decimal point or exponent, so it reads back as a float
+            LiteralValue::Float(value) => write!(f, "{:?}", value),
+            LiteralValue::Boolean(true) => f.write_str("TRUE"),
+            LiteralValue::Boolean(false) => f.write_str("FALSE"),
+            LiteralValue::Null => f.write_str("NULL"),
+            LiteralValue::Decimal(value) => f.write_str(value),
+            LiteralValue::Interval { value, unit } => {
+                write!(f, "INTERVAL '{}' {}", value, unit)
+            }
+        }
+    }
+}
+
+impl fmt::Display for TimeUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TimeUnit::Millisecond => "MILLISECOND",
+            TimeUnit::Second => "SECOND",
+            TimeUnit::Minute => "MINUTE",
+            TimeUnit::Hour => "HOUR",
+            TimeUnit::Day => "DAY",
+            TimeUnit::Week => "WEEK",
+            TimeUnit::Month => "MONTH",
+            TimeUnit::Year => "YEAR",
+        })
+    }
+}
@@ -7,9 +7,12 @@ pub mod config;
 pub mod context;
 pub mod error;
 pub mod execution;
+pub mod formatter;
 // Legacy multi_job modules removed - functionality moved to src/ferris/server/processors/
+pub mod optimizer;
 pub mod parser;
 pub mod query_analyzer;
+pub mod semantic_analyzer;
 pub mod validator;
 
 // Re-export main API
@@ -17,7 +20,9 @@ pub use app_parser::{SqlApplication, SqlApplicationParser};
 pub use ast::{DataType, StreamingQuery};
 pub use error::SqlError;
 pub use execution::{FieldValue, StreamExecutionEngine};
+pub use optimizer::{OptimizedQuery, QueryOptimizer};
 pub use parser::StreamingSqlParser;
+pub use semantic_analyzer::{SchemaCatalog, SemanticAnalyzer};
 pub use validator::SqlValidator;
 
 // Version and feature info
@@ -28,13 +33,34 @@ pub const FEATURES: &[&str] = &[
     "window_functions", // LAG, LEAD, ROW_NUMBER, RANK, DENSE_RANK with OVER clause
     "time_functions",   // DATEDIFF for time calculations
     "stream_registration",
-    "math_functions",      // ABS, ROUND, CEIL, FLOOR, MOD, POWER, SQRT
-    "string_functions",    // CONCAT, LENGTH, TRIM, UPPER, LOWER, REPLACE, LEFT, RIGHT, POSITION
-    "date_functions",      // NOW, CURRENT_TIMESTAMP, DATE_FORMAT, EXTRACT, DATEDIFF
-    "utility_functions",   // COALESCE, NULLIF
-    "json_processing",     // JSON_VALUE, JSON_EXTRACT
-    "header_functions",    // HEADER, HAS_HEADER, HEADER_KEYS
-    "system_columns",      // _timestamp, _offset, _partition
+    "math_functions",          // ABS, ROUND, CEIL, FLOOR, MOD, POWER, SQRT
+    "string_functions",        // CONCAT, LENGTH, TRIM, UPPER, LOWER, REPLACE, LEFT, RIGHT, POSITION
+    "regex_functions",         // REGEXP_LIKE, REGEXP_EXTRACT, REGEXP_REPLACE, REGEXP_SPLIT
+    "hash_encoding_functions", // MD5, SHA256, XXHASH64, BASE64_ENCODE/DECODE, URL_DECODE, PARSE_URL, UUID
+    "date_functions", // NOW, DATE_FORMAT, EXTRACT, DATEDIFF, CONVERT_TZ, TO_TIMESTAMP, DATE_TRUNC, TIME_BUCKET
+    "time_zones",     // TIMESTAMPTZ, AT TIME ZONE, zone-aligned tumbling windows
+    "extended_types", // BYTES, UUID, DATE, TIME, SMALLINT/INT/BIGINT, REAL
+    "decimal_precision", // DECIMAL(p, s), rounding modes, overflow checks, SQL result scales
+    "schema_enforcement", // CREATE STREAM column types and NOT NULL with coerce/dlq/fail policies
+    "table_constraints", // PRIMARY KEY upsert keys, CHECK and NOT NULL on CREATE TABLE
+    "alter_drop",     // ALTER STREAM/TABLE ADD COLUMN and SET, DROP STREAM/TABLE/JOB [IF EXISTS]
+    "merge_upsert",   // MERGE INTO ... WHEN [NOT] MATCHED, UPSERT INTO, keyed sink updates/deletes
+    "semantic_analysis", // column resolution and type checking against source schemas
+    "sql_formatter", // canonical format_query/format_script, Display for AST nodes, sql-validator --format
+    "prepared_statements", // $1 and :name parameters, PreparedStatement binding, -- Parameter: declarations
+    "state_backed_subqueries", // subqueries over materialized tables and lookup sources, cached semi/anti-joins
+    "state_backends",          // pluggable memory / on-disk state for GROUP BY, MERGE and window operators
+    "checkpoints", // periodic operator-state and source-position checkpoints, restore on deploy
+    "savepoints",  // STOP JOB ... WITH SAVEPOINT, START JOB ... FROM SAVEPOINT, SHOW SAVEPOINTS
+    "state_ttl",   // 'state.ttl' evicts idle GROUP BY and MERGE/UPSERT keys and window buffers
+    "memory_budget", // 'memory.budget.mb' spills windows, hash joins and ORDER BY sorts to disk
+    "query_optimizer", // constant folding, predicate/projection pushdown, CAST simplification
+    "utility_functions", // COALESCE, NULLIF
+    "json_processing", // JSON_VALUE, JSON_EXTRACT, JSON_QUERY, PARSE_JSON, JSON_OBJECT, JSON_ARRAYAGG
+    "nested_access",   // payload.customer.id, items[0].price, attributes['key']
+    "higher_order_functions", // TRANSFORM, FILTER, REDUCE, ARRAY_SORT, ARRAY_DISTINCT with lambdas
+    "header_functions", // HEADER, HAS_HEADER, HEADER_KEYS
+    "system_columns",  // _timestamp, _offset, _partition
     "aggregate_functions", // COUNT, SUM, AVG, MIN, MAX, LISTAGG
-    "having_clause",       // Post-aggregation filtering with HAVING
+    "having_clause",   // Post-aggregation filtering with HAVING
 ];
@@ -0,0 +1,1133 @@
+//! Rule-Based Query Optimizer
+//!
+//! This module rewrites parsed queries between parsing and execution. Every rule preserves
+//! the results of the query; when a rule cannot prove a rewrite safe, the query is left as
+//! written.
+//!
+//! ## Rules
+//!
+//! - **Constant folding**: literal-only expressions such as `60 * 60 * 1000` or
+//!   `UPPER('eu')` are evaluated once, `AND`/`OR` with a constant side are simplified, and a
+//!   WHERE clause that folds to `true` is removed
+//! - **CAST simplification**: nested casts to the same type collapse, and casts of a column
+//!   to the type declared for it in `CREATE STREAM` are dropped
+//! - **Predicate pushdown**: WHERE conjuncts that only read the aliased right side of an
+//!   INNER JOIN move below the join, and conjuncts that only read the FROM source are handed
+//!   to the source readers
+//! - **Projection pruning**: unless the query selects `*`, readers only decode the fields
+//!   the query references
+//! - **Join strategy selection**: [`JoinStatistics`] registered for a right source choose
+//!   between nested loop and hash join
+//!
+//! Reader pushdown goes through [`DataReader::push_down`]. Readers that cannot apply it
+//! keep receiving every field, and the query keeps evaluating its full WHERE clause, see
+//! [`OptimizedQuery::push_down_into`]. Only the reader of the FROM source receives the
+//! pushdown; readers of joined sources are never filtered or pruned. Streams with declared columns are not pushed down,
+//! because their records are checked against the declared schema after they are read.
+
+use crate::ferris::datasource::{DataReader, ReaderPushdown};
+use crate::ferris::sql::ast::{
+    BinaryOperator, ColumnDef, DataType, Expr, InsertSource, JoinClause, JoinType, LiteralValue,
+    MergeClause, MergeMatchedAction, NodeSpan, SelectField, StreamSource, StreamingQuery,
+    WindowSpec,
+};
+use crate::ferris::sql::execution::aggregation::AggregateFunctions;
+use crate::ferris::sql::execution::algorithms::hash_join::JoinStatistics;
+use crate::ferris::sql::execution::algorithms::JoinStrategy;
+use crate::ferris::sql::execution::expression::ExpressionEvaluator;
+use crate::ferris::sql::execution::{FieldValue, StreamRecord};
+use crate::ferris::sql::semantic_analyzer::{cast_target_type, SchemaCatalog};
+use std::collections::{HashMap, HashSet};
+
+/// Functions whose result only depends on their arguments, so calls on literals can be folded
+const FOLDABLE_FUNCTIONS: &[&str] = &[
+    "ABS",
+    "ROUND",
+    "CEIL",
+    "CEILING",
+    "FLOOR",
+    "SQRT",
+    "POWER",
+    "POW",
+    "MOD",
+    "UPPER",
+    "LOWER",
+    "SUBSTRING",
+    "REPLACE",
+    "TRIM",
+    "LTRIM",
+    "RTRIM",
+    "LENGTH",
+    "LEN",
+    "LEFT",
+    "RIGHT",
+    "POSITION",
+    "CONCAT",
+    "COALESCE",
+    "NULLIF",
+    "CAST",
+    "MD5",
+    "SHA256",
+    "BASE64_ENCODE",
+];
+
+/// Aggregates not covered by `AggregateFunctions::is_aggregate_function`
+const OTHER_AGGREGATES: &[&str] = &[
+    "LISTAGG",
+    "MEDIAN",
+    "STDDEV_SAMP",
+    "STDDEV_POP",
+    "VAR_SAMP",
+    "VAR_POP",
+    "FIRST_VALUE",
+    "LAST_VALUE",
+];
+
+/// Functions reading message headers, which a JOIN merges from both sides
+const HEADER_FUNCTIONS: &[&str] = &["HEADER", "HAS_HEADER", "HEADER_KEYS"];
+
+/// System columns taken from the left record when joining
+const LEFT_SYSTEM_COLUMNS: &[&str] = &["_OFFSET", "_PARTITION"];
+
+/// Rewrite applied by the optimizer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptimizationRule {
+    /// Literal-only expressions evaluated at planning time
+    ConstantFolding,
+    /// Redundant CASTs removed
+    CastSimplification,
+    /// WHERE conjuncts moved below an INNER JOIN into its right source
+    JoinPredicatePushdown,
+    /// WHERE conjuncts handed to the source readers
+    ReaderFilterPushdown,
+    /// Source readers restricted to the referenced fields
+    ProjectionPruning,
+    /// JOIN strategy chosen from registered statistics
+    JoinStrategySelection,
+}
+
+/// Result of optimizing a query
+#[derive(Debug, Clone)]
+pub struct OptimizedQuery {
+    /// The rewritten query, still evaluating every WHERE conjunct itself
+    pub query: StreamingQuery,
+    /// Projection and filter for the source readers
+    pub pushdown: ReaderPushdown,
+    /// JOIN strategies by right source name
+    pub join_strategies: HashMap<String, JoinStrategy>,
+    /// Rules that changed the query, in the order they first applied
+    pub applied_rules: Vec<OptimizationRule>,
+    /// `query` without the conjuncts in `pushdown.filter`
+    residual_query: StreamingQuery,
+    /// Name of the FROM source the pushdown applies to
+    source: Option<String>,
+}
+
+impl OptimizedQuery {
+    /// Check if a rule changed the query
+    pub fn applied(&self, rule: OptimizationRule) -> bool {
+        self.applied_rules.contains(&rule)
+    }
+
+    /// The query to run once every source reader applies `pushdown`
+    pub fn residual_query(&self) -> &StreamingQuery {
+        &self.residual_query
+    }
+
+    /// Hand the pushdown to the reader of the FROM source and return the query to execute
+    ///
+    /// Readers of joined sources are left untouched, since the pushed-down filter and
+    /// projection only describe the FROM side. The filter is only removed from the query
+    /// when the FROM reader accepted it; if no reader, or more than one reader as in a
+    /// self-join, belongs to the FROM source, nothing is pushed down.
+    pub fn push_down_into(
+        &self,
+        readers: &mut HashMap<String, Box<dyn DataReader>>,
+    ) -> StreamingQuery {
+        let Some(source) = self.source.as_deref() else {
+            return self.query.clone();
+        };
+        if self.pushdown.is_empty() {
+            return self.query.clone();
+        }
+
+        let mut from_readers = readers
+            .iter_mut()
+            .filter(|(key, _)| reader_source_name(key) == source)
+            .map(|(_, reader)| reader);
+        let (Some(reader), None) = (from_readers.next(), from_readers.next()) else {
+            return self.query.clone();
+        };
+
+        if reader.push_down(self.pushdown.clone()) {
+            self.residual_query.clone()
+        } else {
+            self.query.clone()
+        }
+    }
+}
+
+/// Side of a JOIN a WHERE conjunct reads from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Left,
+    Right(usize),
+    Mixed,
+}
+
+/// Rule-based optimizer run between parsing and execution
+#[derive(Debug, Clone, Default)]
+pub struct QueryOptimizer {
+    schema_catalog: SchemaCatalog,
+    join_statistics: HashMap<String, JoinStatistics>,
+}
+
+impl QueryOptimizer {
+    /// Create an optimizer without schema or join statistics
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use source schemas to prove which side of a JOIN a column comes from
+    pub fn with_schema_catalog(mut self, schema_catalog: SchemaCatalog) -> Self {
+        self.schema_catalog = schema_catalog;
+        self
+    }
+
+    /// Register statistics for joins against the given right source
+    pub fn with_join_statistics(
+        mut self,
+        right_source: impl Into<String>,
+        statistics: JoinStatistics,
+    ) -> Self {
+        self.join_statistics.insert(right_source.into(), statistics);
+        self
+    }
+
+    /// Optimize a parsed query
+    pub fn optimize(&self, query: &StreamingQuery) -> OptimizedQuery {
+        let mut query = query.clone();
+        let mut applied_rules = Vec::new();
+        let declared_columns = declared_columns(&query).cloned();
+
+        // Declared columns are enforced on read, so their types are known for CAST simplification
+        let column_types: HashMap<String, DataType> = match main_select(&query) {
+            Some(StreamingQuery::Select { joins: None, .. }) => declared_columns
+                .iter()
+                .flatten()
+                .map(|column| (column.name.clone(), column.data_type.clone()))
+                .collect(),
+            _ => HashMap::new(),
+        };
+        self.simplify_query(&mut query, &column_types, &mut applied_rules);
+
+        let mut join_strategies = HashMap::new();
+        if let Some(select) = main_select_mut(&mut query) {
+            self.select_join_strategies(select, &mut join_strategies, &mut applied_rules);
+            self.push_below_joins(select, &mut applied_rules);
+        }
+
+        let mut pushdown = ReaderPushdown::default();
+        let mut residual_query = query.clone();
+        if declared_columns.is_none() {
+            if let Some(select) = main_select_mut(&mut residual_query) {
+                pushdown.filter = self.take_reader_filter(select);
+                if pushdown.filter.is_some() {
+                    note(&mut applied_rules, OptimizationRule::ReaderFilterPushdown);
+                }
+            }
+            pushdown.projection = main_select(&query).and_then(projection);
+            if pushdown.projection.is_some() {
+                note(&mut applied_rules, OptimizationRule::ProjectionPruning);
+            }
+        }
+
+        let source = match main_select(&query) {
+            Some(StreamingQuery::Select { from, .. }) => source_name(from).map(str::to_string),
+            _ => None,
+        };
+
+        OptimizedQuery {
+            query,
+            pushdown,
+            join_strategies,
+            applied_rules,
+            residual_query,
+            source,
+        }
+    }
+
+    /// Fold constants and simplify CASTs in every expression of a query
+    fn simplify_query(
+        &self,
+        query: &mut StreamingQuery,
+        column_types: &HashMap<String, DataType>,
+        applied: &mut Vec<OptimizationRule>,
+    ) {
+        let no_types = HashMap::new();
+        match query {
+            StreamingQuery::Select {
+                fields,
+                from,
+                joins,
+                where_clause,
+                group_by,
+                having,
+                order_by,
+                ..
+            } => {
+                self.simplify_source(from, applied);
+                for join in joins.iter_mut().flatten() {
+                    self.simplify_source(&mut join.right_source, applied);
+                    self.simplify_expr(&mut join.condition, column_types, applied);
+                }
+                for field in fields.iter_mut() {
+                    if let SelectField::Expression { expr, alias } = field {
+                        if alias.is_some() {
+                            self.simplify_expr(expr, column_types, applied);
+                        } else {
+                            // Unaliased output columns are named after the expression itself
+                            self.simplify_children(expr, column_types, applied);
+                        }
+                    }
+                }
+                for expr in group_by.iter_mut().flatten() {
+                    self.simplify_expr(expr, column_types, applied);
+                }
+                for expr in having.iter_mut() {
+                    self.simplify_expr(expr, column_types, applied);
+                }
+                for order in order_by.iter_mut().flatten() {
+                    self.simplify_expr(&mut order.expr, column_types, applied);
+                }
+                if let Some(condition) = where_clause {
+                    self.simplify_expr(condition, column_types, applied);
+                    if matches!(condition, Expr::Literal(LiteralValue::Boolean(true), _)) {
+                        *where_clause = None;
+                    }
+                }
+            }
+            StreamingQuery::CreateStream { as_select, .. }
+            | StreamingQuery::CreateStreamInto { as_select, .. }
+            | StreamingQuery::CreateTable { as_select, .. }
+            | StreamingQuery::CreateTableInto { as_select, .. } => {
+                self.simplify_query(as_select, column_types, applied)
+            }
+            StreamingQuery::StartJob { query, .. } | StreamingQuery::DeployJob { query, .. } => {
+                self.simplify_query(query, column_types, applied)
+            }
+            StreamingQuery::Union { left, right, .. } => {
+                self.simplify_query(left, &no_types, applied);
+                self.simplify_query(right, &no_types, applied);
+            }
+            StreamingQuery::InsertInto { source, .. } | StreamingQuery::Upsert { source, .. } => {
+                match source {
+                    InsertSource::Values { rows } => {
+                        for expr in rows.iter_mut().flatten() {
+                            self.simplify_expr(expr, &no_types, applied);
+                        }
+                    }
+                    InsertSource::Select { query } => {
+                        self.simplify_query(query, &no_types, applied)
+                    }
+                }
+            }
+            StreamingQuery::Update {
+                assignments,
+                where_clause,
+                ..
+            } => {
+                for (_, expr) in assignments.iter_mut() {
+                    self.simplify_expr(expr, &no_types, applied);
+                }
+                for expr in where_clause.iter_mut() {
+                    self.simplify_expr(expr, &no_types, applied);
+                }
+            }
+            StreamingQuery::Delete { where_clause, .. } => {
+                for expr in where_clause.iter_mut() {
+                    self.simplify_expr(expr, &no_types, applied);
+                }
+            }
+            StreamingQuery::Merge {
+                source,
+                on,
+                clauses,
+                ..
+            } => {
+                self.simplify_source(source, applied);
+                self.simplify_expr(on, &no_types, applied);
+                for clause in clauses.iter_mut() {
+                    match clause {
+                        MergeClause::Matched { condition, action } => {
+                            for expr in condition.iter_mut() {
+                                self.simplify_expr(expr, &no_types, applied);
+                            }
+                            if let MergeMatchedAction::Update { assignments } = action {
+                                for (_, expr) in assignments.iter_mut() {
+                                    self.simplify_expr(expr, &no_types, applied);
+                                }
+                            }
+                        }
+                        MergeClause::NotMatched {
+                            condition, values, ..
+                        } => {
+                            for expr in condition.iter_mut().chain(values.iter_mut()) {
+                                self.simplify_expr(expr, &no_types, applied);
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Simplify the query of a subquery source
+    fn simplify_source(&self, source: &mut StreamSource, applied: &mut Vec<OptimizationRule>) {
+        if let StreamSource::Subquery(query) = source {
+            self.simplify_query(query, &HashMap::new(), applied);
+        }
+    }
+
+    /// Simplify an expression bottom-up
+    fn simplify_expr(
+        &self,
+        expr: &mut Expr,
+        column_types: &HashMap<String, DataType>,
+        applied: &mut Vec<OptimizationRule>,
+    ) {
+        self.simplify_children(expr, column_types, applied);
+
+        if let Some(simplified) = simplify_cast(expr, column_types) {
+            *expr = simplified;
+            note(applied, OptimizationRule::CastSimplification);
+        }
+        if let Some(simplified) = simplify_boolean(expr) {
+            *expr = simplified;
+            note(applied, OptimizationRule::ConstantFolding);
+        } else if let Some(literal) = fold_constant(expr) {
+            *expr = Expr::Literal(literal, NodeSpan::default());
+            note(applied, OptimizationRule::ConstantFolding);
+        }
+    }
+
+    /// Simplify the operands of an expression, leaving the expression itself as written
+    fn simplify_children(
+        &self,
+        expr: &mut Expr,
+        column_types: &HashMap<String, DataType>,
+        applied: &mut Vec<OptimizationRule>,
+    ) {
+        match expr {
+            Expr::Column(_, _) | Expr::Literal(_, _) | Expr::Parameter(_, _) => {}
+            Expr::BinaryOp { left, right, .. } => {
+                self.simplify_expr(left, column_types, applied);
+                self.simplify_expr(right, column_types, applied);
+            }
+            Expr::UnaryOp { expr, .. } | Expr::FieldAccess { expr, .. } => {
+                self.simplify_expr(expr, column_types, applied);
+            }
+            Expr::Subscript { expr, index, .. } => {
+                self.simplify_expr(expr, column_types, applied);
+                self.simplify_expr(index, column_types, applied);
+            }
+            Expr::Function { args, .. } | Expr::List(args, _) => {
+                for arg in args.iter_mut() {
+                    self.simplify_expr(arg, column_types, applied);
+                }
+            }
+            Expr::WindowFunction {
+                args, over_clause, ..
+            } => {
+                for arg in args.iter_mut() {
+                    self.simplify_expr(arg, column_types, applied);
+                }
+                for order in over_clause.order_by.iter_mut() {
+                    self.simplify_expr(&mut order.expr, column_types, applied);
+                }
+            }
+            Expr::Case {
+                when_clauses,
+                else_clause,
+                ..
+            } => {
+                for (condition, result) in when_clauses.iter_mut() {
+                    self.simplify_expr(condition, column_types, applied);
+                    self.simplify_expr(result, column_types, applied);
+                }
+                if let Some(else_expr) = else_clause {
+                    self.simplify_expr(else_expr, column_types, applied);
+                }
+            }
+            Expr::Subquery { query, .. } => self.simplify_query(query, &HashMap::new(), applied),
+            Expr::Between {
+                expr, low, high, ..
+            } => {
+                self.simplify_expr(expr, column_types, applied);
+                self.simplify_expr(low, column_types, applied);
+                self.simplify_expr(high, column_types, applied);
+            }
+            // Lambda parameters may shadow columns, so their types are unknown
+            Expr::Lambda { body, .. } => self.simplify_expr(body, &HashMap::new(), applied),
+        }
+    }
+
+    /// Choose a strategy for every join with registered statistics
+    fn select_join_strategies(
+        &self,
+        select: &StreamingQuery,
+        join_strategies: &mut HashMap<String, JoinStrategy>,
+        applied: &mut Vec<OptimizationRule>,
+    ) {
+        let StreamingQuery::Select {
+            joins: Some(joins), ..
+        } = select
+        else {
+            return;
+        };
+
+        for join in joins {
+            let Some(name) = source_name(&join.right_source) else {
+                continue;
+            };
+            if let Some(statistics) = self.join_statistics.get(name) {
+                join_strategies.insert(name.to_string(), statistics.select_strategy());
+                note(applied, OptimizationRule::JoinStrategySelection);
+            }
+        }
+    }
+
+    /// Move WHERE conjuncts reading only the right side of an INNER JOIN into that side
+    fn push_below_joins(&self, select: &mut StreamingQuery, applied: &mut Vec<OptimizationRule>) {
+        let StreamingQuery::Select {
+            joins: Some(joins),
+            where_clause,
+            ..
+        } = select
+        else {
+            return;
+        };
+        let Some(condition) = where_clause.take() else {
+            return;
+        };
+
+        let mut remaining = Vec::new();
+        for conjunct in split_conjuncts(condition) {
+            let pushed = match self.conjunct_side(&conjunct, joins) {
+                Side::Right(index) if joins[index].join_type == JoinType::Inner => {
+                    push_into_right_source(&mut joins[index], &conjunct)
+                }
+                _ => false,
+            };
+            if pushed {
+                note(applied, OptimizationRule::JoinPredicatePushdown);
+            } else {
+                remaining.push(conjunct);
+            }
+        }
+        *where_clause = combine_conjuncts(remaining);
+    }
+
+    /// Remove the WHERE conjuncts the source readers can evaluate and return them
+    fn take_reader_filter(&self, select: &mut StreamingQuery) -> Option<Expr> {
+        let StreamingQuery::Select {
+            from,
+            joins,
+            where_clause,
+            ..
+        } = select
+        else {
+            return None;
+        };
+        // Readers of a subquery source produce the subquery's input, not its output
+        if matches!(from, StreamSource::Subquery(_)) {
+            return None;
+        }
+        let condition = where_clause.take()?;
+
+        let joins = joins.as_deref().unwrap_or(&[]);
+        let (pushed, kept): (Vec<Expr>, Vec<Expr>) = split_conjuncts(condition)
+            .into_iter()
+            .partition(|conjunct| self.conjunct_side(conjunct, joins) == Side::Left);
+        *where_clause = combine_conjuncts(kept);
+        combine_conjuncts(pushed)
+    }
+
+    /// Side of the joins a WHERE conjunct reads from
+    fn conjunct_side(&self, conjunct: &Expr, joins: &[JoinClause]) -> Side {
+        if !is_row_local(conjunct, !joins.is_empty()) {
+            return Side::Mixed;
+        }
+        if joins.is_empty() {
+            return Side::Left;
+        }
+
+        let mut columns = Vec::new();
+        collect_expr_columns(conjunct, &mut columns);
+        let mut sides = columns.iter().map(|column| self.column_side(column, joins));
+        match sides.next() {
+            Some(first) if sides.all(|side| side == first) => first,
+            _ => Side::Mixed,
+        }
+    }
+
+    /// Side of the joins a column reference resolves to in the joined record
+    fn column_side(&self, column: &str, joins: &[JoinClause]) -> Side {
+        let upper = column.to_uppercase();
+        if upper == "_TIMESTAMP" {
+            // The joined record carries the later timestamp of both sides
+            return Side::Mixed;
+        }
+        if LEFT_SYSTEM_COLUMNS.contains(&upper.as_str()) {
+            return Side::Left;
+        }
+
+        if let Some((qualifier, _)) = column.split_once('.') {
+            if let Some(index) = joins
+                .iter()
+                .position(|join| join.right_alias.as_deref() == Some(qualifier))
+            {
+                return Side::Right(index);
+            }
+        }
+
+        // Unaliased right sources add their fields unprefixed, replacing left fields
+        // of the same name, unless their schema shows they have no such field
+        let first = column.split('.').next().unwrap_or(column);
+        let last = column.rsplit('.').next().unwrap_or(column);
+        let shadowed = joins.iter().any(|join| {
+            join.right_alias.is_none()
+                && source_name(&join.right_source)
+                    .and_then(|name| self.schema_catalog.get(name))
+                    .map_or(true, |schema| {
+                        schema.has_field(first) || schema.has_field(last)
+                    })
+        });
+        if shadowed {
+            Side::Mixed
+        } else {
+            Side::Left
+        }
+    }
+}
+
+/// Record a rule once
+fn note(applied: &mut Vec<OptimizationRule>, rule: OptimizationRule) {
+    if !applied.contains(&rule) {
+        applied.push(rule);
+    }
+}
+
+/// The SELECT a statement reads its sources with
+fn main_select(query: &StreamingQuery) -> Option<&StreamingQuery> {
+    match query {
+        StreamingQuery::Select { .. } => Some(query),
+        StreamingQuery::CreateStream { as_select, .. }
+        | StreamingQuery::CreateStreamInto { as_select, .. }
+        | StreamingQuery::CreateTable { as_select, .. }
+        | StreamingQuery::CreateTableInto { as_select, .. } => main_select(as_select),
+        StreamingQuery::StartJob { query, .. } | StreamingQuery::DeployJob { query, .. } => {
+            main_select(query)
+        }
+        _ => None,
+    }
+}
+
+fn main_select_mut(query: &mut StreamingQuery) -> Option<&mut StreamingQuery> {
+    match query {
+        StreamingQuery::Select { .. } => Some(query),
+        StreamingQuery::CreateStream { as_select, .. }
+        | StreamingQuery::CreateStreamInto { as_select, .. }
+        | StreamingQuery::CreateTable { as_select, .. }
+        | StreamingQuery::CreateTableInto { as_select, .. } => main_select_mut(as_select),
+        StreamingQuery::StartJob { query, .. } | StreamingQuery::DeployJob { query, .. } => {
+            main_select_mut(query)
+        }
+        _ => None,
+    }
+}
+
+/// Column definitions declared by a CREATE STREAM / CREATE TABLE statement
+fn declared_columns(query: &StreamingQuery) -> Option<&Vec<ColumnDef>> {
+    match query {
+        StreamingQuery::CreateStream { columns, .. }
+        | StreamingQuery::CreateStreamInto { columns, .. }
+        | StreamingQuery::CreateTable { columns, .. }
+        | StreamingQuery::CreateTableInto { columns, .. } => columns.as_ref(),
+        StreamingQuery::StartJob { query, .. } | StreamingQuery::DeployJob { query, .. } => {
+            declared_columns(query)
+        }
+        _ => None,
+    }
+}
+
+/// Name of a stream, table or URI source, looking through subqueries
+fn source_name(source: &StreamSource) -> Option<&str> {
+    match source {
+        StreamSource::Stream(name) | StreamSource::Table(name) | StreamSource::Uri(name) => {
+            Some(name)
+        }
+        StreamSource::Subquery(query) => match query.as_ref() {
+            StreamingQuery::Select { from, .. } => source_name(from),
+            _ => None,
+        },
+    }
+}
+
+/// Source name of a reader keyed `source_<index>_<name>`, as the job server creates them
+fn reader_source_name(key: &str) -> &str {
+    key.strip_prefix("source_")
+        .and_then(|rest| rest.split_once('_'))
+        .filter(|(index, _)| index.parse::<usize>().is_ok())
+        .map_or(key, |(_, name)| name)
+}
+
+/// Split a condition into its AND conjuncts
+fn split_conjuncts(condition: Expr) -> Vec<Expr> {
+    match condition {
+        Expr::BinaryOp {
+            left,
+            op: BinaryOperator::And,
+            right,
+            ..
+        } => {
+            let mut conjuncts = split_conjuncts(*left);
+            conjuncts.extend(split_conjuncts(*right));
+            conjuncts
+        }
+        other => vec![other],
+    }
+}
+
+/// AND conjuncts back together
+fn combine_conjuncts(conjuncts: Vec<Expr>) -> Option<Expr> {
+    conjuncts.into_iter().reduce(|left, right| Expr::BinaryOp {
+        left: Box::new(left),
+        op: BinaryOperator::And,
+        right: Box::new(right),
+        span: NodeSpan::default(),
+    })
+}
+
+/// Check if a conjunct only depends on the record it is evaluated on
+fn is_row_local(expr: &Expr, has_joins: bool) -> bool {
+    match expr {
+        Expr::Column(_, _) | Expr::Literal(_, _) | Expr::Parameter(_, _) => true,
+        Expr::Subquery { .. } | Expr::WindowFunction { .. } => false,
+        Expr::Function { name, args, .. } => {
+            let upper = name.to_uppercase();
+            let reads_joined_headers = has_joins && HEADER_FUNCTIONS.contains(&upper.as_str());
+            !AggregateFunctions::is_aggregate_function(expr)
+                && !OTHER_AGGREGATES.contains(&upper.as_str())
+                && !reads_joined_headers
+                && args.iter().all(|arg| is_row_local(arg, has_joins))
+        }
+        Expr::BinaryOp { left, right, .. } => {
+            is_row_local(left, has_joins) && is_row_local(right, has_joins)
+        }
+        Expr::UnaryOp { expr, .. } | Expr::FieldAccess { expr, .. } => {
+            is_row_local(expr, has_joins)
+        }
+        Expr::Subscript { expr, index, .. } => {
+            is_row_local(expr, has_joins) && is_row_local(index, has_joins)
+        }
+        Expr::List(items, _) => items.iter().all(|item| is_row_local(item, has_joins)),
+        Expr::Case {
+            when_clauses,
+            else_clause,
+            ..
+        } => {
+            when_clauses.iter().all(|(condition, result)| {
+                is_row_local(condition, has_joins) && is_row_local(result, has_joins)
+            }) && else_clause
+                .as_ref()
+                .map_or(true, |else_expr| is_row_local(else_expr, has_joins))
+        }
+        Expr::Between {
+            expr, low, high, ..
+        } => {
+            is_row_local(expr, has_joins)
+                && is_row_local(low, has_joins)
+                && is_row_local(high, has_joins)
+        }
+        Expr::Lambda { body, .. } => is_row_local(body, has_joins),
+    }
+}
+
+/// Add a conjunct over the right side of a join to the WHERE clause of that side
+fn push_into_right_source(join: &mut JoinClause, conjunct: &Expr) -> bool {
+    let Some(alias) = &join.right_alias else {
+        return false;
+    };
+    // Right-side records carry their fields without the alias prefix
+    let prefix = format!("{}.", alias);
+    let mut predicate = conjunct.clone();
+    rename_columns(&mut predicate, &|name| {
+        name.strip_prefix(&prefix).map(str::to_string)
+    });
+
+    match &mut join.right_source {
+        StreamSource::Subquery(query) => match query.as_mut() {
+            StreamingQuery::Select {
+                fields,
+                where_clause,
+                group_by: None,
+                having: None,
+                window: None,
+                limit: None,
+                ..
+            } if fields.as_slice() == [SelectField::Wildcard] => {
+                *where_clause =
+                    combine_conjuncts(where_clause.take().into_iter().chain([predicate]).collect());
+                true
+            }
+            _ => false,
+        },
+        source => {
+            let from = std::mem::replace(source, StreamSource::Stream(String::new()));
+            *source = StreamSource::Subquery(Box::new(StreamingQuery::Select {
+                fields: vec![SelectField::Wildcard],
+                from,
+                from_alias: None,
+                joins: None,
+                where_clause: Some(predicate),
+                group_by: None,
+                having: None,
+                window: None,
+                order_by: None,
+                limit: None,
+                emit_mode: None,
+                properties: None,
+                span: NodeSpan::default(),
+            }));
+            true
+        }
+    }
+}
+
+/// Rename the column references of an expression
+fn rename_columns(expr: &mut Expr, rename: &dyn Fn(&str) -> Option<String>) {
+    match expr {
+        Expr::Column(name, _) => {
+            if let Some(renamed) = rename(name) {
+                *name = renamed;
+            }
+        }
+        Expr::Literal(_, _)
+        | Expr::Parameter(_, _)
+        | Expr::Subquery { .. }
+        | Expr::WindowFunction { .. } => {}
+        Expr::BinaryOp { left, right, .. } => {
+            rename_columns(left, rename);
+            rename_columns(right, rename);
+        }
+        Expr::UnaryOp { expr, .. } | Expr::FieldAccess { expr, .. } => rename_columns(expr, rename),
+        Expr::Subscript { expr, index, .. } => {
+            rename_columns(expr, rename);
+            rename_columns(index, rename);
+        }
+        Expr::Function { args, .. } | Expr::List(args, _) => {
+            for arg in args.iter_mut() {
+                rename_columns(arg, rename);
+            }
+        }
+        Expr::Case {
+            when_clauses,
+            else_clause,
+            ..
+        } => {
+            for (condition, result) in when_clauses.iter_mut() {
+                rename_columns(condition, rename);
+                rename_columns(result, rename);
+            }
+            if let Some(else_expr) = else_clause {
+                rename_columns(else_expr, rename);
+            }
+        }
+        Expr::Between {
+            expr, low, high, ..
+        } => {
+            rename_columns(expr, rename);
+            rename_columns(low, rename);
+            rename_columns(high, rename);
+        }
+        Expr::Lambda { body, .. } => rename_columns(body, rename),
+    }
+}
+
+/// Collapse `CAST(CAST(x, 'T'), 'T')` and drop casts of a column to its declared type
+fn simplify_cast(expr: &Expr, column_types: &HashMap<String, DataType>) -> Option<Expr> {
+    let (operand, target) = cast_parts(expr)?;
+    match operand {
+        Expr::Column(name, _) => {
+            let declared = column_types.get(name)?;
+            (cast_target_type(target)? == *declared).then(|| operand.clone())
+        }
+        inner => {
+            let (_, inner_target) = cast_parts(inner)?;
+            same_cast_type(inner_target, target).then(|| inner.clone())
+        }
+    }
+}
+
+/// Operand and target type name of a CAST call
+fn cast_parts(expr: &Expr) -> Option<(&Expr, &str)> {
+    match expr {
+        Expr::Function { name, args, .. }
+            if name.eq_ignore_ascii_case("CAST") && args.len() == 2 =>
+        {
+            match &args[1] {
+                Expr::Literal(LiteralValue::String(target), _) => Some((&args[0], target.as_str())),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Check if two CAST target names denote the same type
+fn same_cast_type(left: &str, right: &str) -> bool {
+    let normalize = |name: &str| name.split_whitespace().collect::<String>().to_uppercase();
+    normalize(left) == normalize(right)
+        || cast_target_type(left).is_some_and(|left| cast_target_type(right) == Some(left))
+}
+
+/// Simplify AND/OR with a boolean literal operand
+fn simplify_boolean(expr: &Expr) -> Option<Expr> {
+    let Expr::BinaryOp {
+        left, op, right, ..
+    } = expr
+    else {
+        return None;
+    };
+    let literal = |expr: &Expr| match expr {
+        Expr::Literal(LiteralValue::Boolean(value), _) => Some(*value),
+        _ => None,
+    };
+
+    match (op, literal(left), literal(right)) {
+        (BinaryOperator::And, Some(true), _) => Some(right.as_ref().clone()),
+        (BinaryOperator::And, _, Some(true)) => Some(left.as_ref().clone()),
+        (BinaryOperator::And, Some(false), _) | (BinaryOperator::And, _, Some(false)) => Some(
+            Expr::Literal(LiteralValue::Boolean(false), NodeSpan::default()),
+        ),
+        (BinaryOperator::Or, Some(false), _) => Some(right.as_ref().clone()),
+        (BinaryOperator::Or, _, Some(false)) => Some(left.as_ref().clone()),
+        (BinaryOperator::Or, Some(true), _) | (BinaryOperator::Or, _, Some(true)) => Some(
+            Expr::Literal(LiteralValue::Boolean(true), NodeSpan::default()),
+        ),
+        _ => None,
+    }
+}
+
+/// Evaluate a literal-only expression; expressions that fail to evaluate are left to fail at runtime
+fn fold_constant(expr: &Expr) -> Option<LiteralValue> {
+    if matches!(expr, Expr::Literal(_, _)) || !is_constant(expr) {
+        return None;
+    }
+    let empty = StreamRecord::new(HashMap::new());
+    match ExpressionEvaluator::evaluate_expression_value(expr, &empty).ok()? {
+        FieldValue::Integer(value) => Some(LiteralValue::Integer(value)),
+        FieldValue::Float(value) if value.is_finite() => Some(LiteralValue::Float(value)),
+        FieldValue::String(value) => Some(LiteralValue::String(value)),
+        FieldValue::Boolean(value) => Some(LiteralValue::Boolean(value)),
+        FieldValue::Null => Some(LiteralValue::Null),
+        FieldValue::Decimal(value) => Some(LiteralValue::Decimal(value.to_string())),
+        FieldValue::Interval { value, unit } => Some(LiteralValue::Interval { value, unit }),
+        _ => None,
+    }
+}
+
+/// Check if an expression only combines literals with deterministic operations
+fn is_constant(expr: &Expr) -> bool {
+    match expr {
+        Expr::Literal(_, _) => true,
+        Expr::Column(_, _)
+        | Expr::Subquery { .. }
+        | Expr::WindowFunction { .. }
+        | Expr::Lambda { .. }
+        | Expr::List(_, _)
+        | Expr::Parameter(_, _) => false,
+        Expr::Function { name, args, .. } => {
+            FOLDABLE_FUNCTIONS.contains(&name.to_uppercase().as_str())
+                && args.iter().all(is_constant)
+        }
+        Expr::BinaryOp { left, right, .. } => is_constant(left) && is_constant(right),
+        Expr::UnaryOp { expr, .. } | Expr::FieldAccess { expr, .. } => is_constant(expr),
+        Expr::Subscript { expr, index, .. } => is_constant(expr) && is_constant(index),
+        Expr::Case {
+            when_clauses,
+            else_clause,
+            ..
+        } => {
+            when_clauses
+                .iter()
+                .all(|(condition, result)| is_constant(condition) && is_constant(result))
+                && else_clause.as_deref().map_or(true, is_constant)
+        }
+        Expr::Between {
+            expr, low, high, ..
+        } => is_constant(expr) && is_constant(low) && is_constant(high),
+    }
+}
+
+/// Top-level fields a SELECT reads, or `None` if it needs every field
+fn projection(select: &StreamingQuery) -> Option<HashSet<String>> {
+    let StreamingQuery::Select { fields, .. } = select else {
+        return None;
+    };
+    let selects_all = fields.iter().any(|field| match field {
+        SelectField::Wildcard => true,
+        SelectField::Column(name) | SelectField::AliasedColumn { column: name, .. } => {
+            name.ends_with('*')
+        }
+        SelectField::Expression { .. } => false,
+    });
+    if selects_all {
+        return None;
+    }
+
+    let mut columns = Vec::new();
+    collect_query_columns(select, &mut columns);
+
+    // A dotted reference may name a field, a STRUCT path or a qualified column,
+    // so every contiguous run of its segments is kept
+    let mut projection = HashSet::new();
+    for column in columns {
+        let segments: Vec<&str> = column.split('.').collect();
+        for start in 0..segments.len() {
+            for end in start + 1..=segments.len() {
+                projection.insert(segments[start..end].join("."));
+            }
+        }
+    }
+    Some(projection)
+}
+
+/// Collect every column a query references
+fn collect_query_columns(query: &StreamingQuery, columns: &mut Vec<String>) {
+    match query {
+        StreamingQuery::Select {
+            fields,
+            from,
+            joins,
+            where_clause,
+            group_by,
+            having,
+            window,
+            order_by,
+            ..
+        } => {
+            for field in fields {
+                match field {
+                    SelectField::Column(name) | SelectField::AliasedColumn { column: name, .. } => {
+                        columns.push(name.clone())
+                    }
+                    SelectField::Expression { expr, .. } => collect_expr_columns(expr, columns),
+                    SelectField::Wildcard => {}
+                }
+            }
+            if let StreamSource::Subquery(subquery) = from {
+                collect_query_columns(subquery, columns);
+            }
+            for join in joins.iter().flatten() {
+                if let StreamSource::Subquery(subquery) = &join.right_source {
+                    collect_query_columns(subquery, columns);
+                }
+                collect_expr_columns(&join.condition, columns);
+            }
+            let exprs = where_clause
+                .iter()
+                .chain(group_by.iter().flatten())
+                .chain(having.iter())
+                .chain(order_by.iter().flatten().map(|order| &order.expr));
+            for expr in exprs {
+                collect_expr_columns(expr, columns);
+            }
+            match window {
+                Some(WindowSpec::Tumbling { time_column, .. })
+                | Some(WindowSpec::Sliding { time_column, .. }) => {
+                    columns.extend(time_column.iter().cloned())
+                }
+                Some(WindowSpec::Session { partition_by, .. }) => {
+                    columns.extend(partition_by.iter().cloned())
+                }
+                None => {}
+            }
+        }
+        StreamingQuery::Union { left, right, .. } => {
+            collect_query_columns(left, columns);
+            collect_query_columns(right, columns);
+        }
+        _ => {
+            if let Some(select) = main_select(query) {
+                collect_query_columns(select, columns);
+            }
+        }
+    }
+}
+
+/// Collect every column an expression references, lambda parameters excluded
+fn collect_expr_columns(expr: &Expr, columns: &mut Vec<String>) {
+    match expr {
+        Expr::Column(name, _) => columns.push(name.clone()),
+        Expr::Literal(_, _) | Expr::Parameter(_, _) => {}
+        Expr::BinaryOp { left, right, .. } => {
+            collect_expr_columns(left, columns);
+            collect_expr_columns(right, columns);
+        }
+        Expr::UnaryOp { expr, .. } | Expr::FieldAccess { expr, .. } => {
+            collect_expr_columns(expr, columns)
+        }
+        Expr::Subscript { expr, index, .. } => {
+            collect_expr_columns(expr, columns);
+            collect_expr_columns(index, columns);
+        }
+        Expr::Function { args, .. } | Expr::List(args, _) => {
+            for arg in args {
+                collect_expr_columns(arg, columns);
+            }
+        }
+        Expr::WindowFunction {
+            args, over_clause, ..
+        } => {
+            for arg in args {
+                collect_expr_columns(arg, columns);
+            }
+            columns.extend(over_clause.partition_by.iter().cloned());
+            for order in &over_clause.order_by {
+                collect_expr_columns(&order.expr, columns);
+            }
+        }
+        Expr::Case {
+            when_clauses,
+            else_clause,
+            ..
+        } => {
+            for (condition, result) in when_clauses {
+                collect_expr_columns(condition, columns);
+                collect_expr_columns(result, columns);
+            }
+            if let Some(else_expr) = else_clause {
+                collect_expr_columns(else_expr, columns);
+            }
+        }
+        Expr::Subquery { query, .. } => collect_query_columns(query, columns),
+        Expr::Between {
+            expr, low, high, ..
+        } => {
+            collect_expr_columns(expr, columns);
+            collect_expr_columns(low, columns);
+            collect_expr_columns(high, columns);
+        }
+        Expr::Lambda { params, body, .. } => {
+            let mut body_columns = Vec::new();
+            collect_expr_columns(body, &mut body_columns);
+            columns.extend(body_columns.into_iter().filter(|column| {
+                let root = column.split('.').next().unwrap_or(column);
+                !params.iter().any(|param| param == root)
+            }));
+        }
+    }
+}
@@ -96,9 +96,16 @@ The parser provides detailed error messages with position information:
 - Missing required clauses
 
 All errors implement the `SqlError` type for consistent error handling throughout the system.
+
+[`StreamingSqlParser::parse`] stops at the first error. [`StreamingSqlParser::parse_script`]
+and [`StreamingSqlParser::parse_with_diagnostics`] keep going: they resynchronize at the next
+statement (`;`) and, within a statement, at the next clause keyword (FROM, WHERE, GROUP BY,
+...), and return every error as a [`Diagnostic`] with its span and "did you mean"
+suggestions for misspelled keywords, ready to be rendered with a source excerpt.
 */
 
 use crate::ferris::sql::ast::*;
+use crate::ferris::sql::error::diagnostics::{closest_match, Diagnostic, Span};
 use crate::ferris::sql::error::SqlError;
 use std::collections::HashMap;
 use std::time::Duration;
@@ -190,6 +197,10 @@ enum TokenType {
     Versions,   // VERSIONS
     Metrics,    // METRICS
     Describe,   // DESCRIBE
+    Alter,      // ALTER
+    Drop,       // DROP
+    Merge,      // MERGE
+    Upsert,     // UPSERT
 
     // Emit Mode Keywords
     Emit,    // EMIT
@@ -201,13 +212,17 @@ enum TokenType {
     String,     // String literals ('hello', "world")
     Null,       // NULL literal
     Number,     // Numeric literals (42, 3.14)
+    Parameter,  // Query parameter placeholders ($1, :name)
 
     // Punctuation
-    LeftParen,  // (
-    RightParen, // )
-    Comma,      // ,
-    Asterisk,   // * (wildcard or multiplication)
-    Dot,        // . (qualified names)
+    LeftParen,    // (
+    RightParen,   // )
+    Comma,        // ,
+    Asterisk,     // * (wildcard or multiplication)
+    Dot,          // . (qualified names)
+    LeftBracket,  // [ (array index / map subscript)
+    RightBracket, // ]
+    Arrow,        // -> (lambda expressions)
 
     // Arithmetic Operators
     Plus,  // +
@@ -283,6 +298,35 @@ struct Token {
     value: String,
     /// Character position in the original SQL string (for error reporting)
     position: usize,
+    /// Character position just past the token's text, quotes included
+    end: usize,
+}
+
+/// Clauses a query is resynchronized at after an error, see [`StreamingSqlParser::parse_script`]
+const CLAUSE_KEYWORDS: &[TokenType] = &[
+    TokenType::Select,
+    TokenType::From,
+    TokenType::Where,
+    TokenType::GroupBy,
+    TokenType::Having,
+    TokenType::Window,
+    TokenType::OrderBy,
+    TokenType::Limit,
+    TokenType::Emit,
+    TokenType::Into,
+    TokenType::With,
+];
+
+/// Errors reported for one statement before giving up on it
+const MAX_ERRORS_PER_STATEMENT: usize = 16;
+
+/// One statement of a multi-statement script
+#[derive(Debug, Clone)]
+pub struct ParsedStatement {
+    /// Where the statement is in the script, without its terminating `;`
+    pub span: Span,
+    /// The parsed statement, or every error found in it
+    pub result: Result<StreamingQuery, Vec<Diagnostic>>,
 }
 
 impl StreamingSqlParser {
@@ -345,6 +389,10 @@ impl StreamingSqlParser {
         keywords.insert("VERSIONS".to_string(), TokenType::Versions);
         keywords.insert("METRICS".to_string(), TokenType::Metrics);
         keywords.insert("DESCRIBE".to_string(), TokenType::Describe);
+        keywords.insert("ALTER".to_string(), TokenType::Alter);
+        keywords.insert("DROP".to_string(), TokenType::Drop);
+        keywords.insert("MERGE".to_string(), TokenType::Merge);
+        keywords.insert("UPSERT".to_string(), TokenType::Upsert);
 
         // Emit Mode Keywords
         keywords.insert("EMIT".to_string(), TokenType::Emit);
@@ -450,11 +498,23 @@ impl StreamingSqlParser {
     }
 
     fn tokenize(&self, sql: &str) -> Result<Vec<Token>, SqlError> {
+        let (tokens, mut errors) = self.tokenize_with_errors(sql);
+        match errors.is_empty() {
+            true => Ok(tokens),
+            false => Err(errors.remove(0)),
+        }
+    }
+
+    /// Tokenize the whole text, skipping characters that start no token
+    fn tokenize_with_errors(&self, sql: &str) -> (Vec<Token>, Vec<SqlError>) {
         let mut tokens = Vec::new();
+        let mut errors = Vec::new();
         let mut chars = sql.chars().peekable();
         let mut position = 0;
 
         while let Some(&ch) = chars.peek() {
+            let start = position;
+            let token_count = tokens.len();
             match ch {
                 ' ' | '\t' | '\n' | '\r' => {
                     chars.next();
@@ -465,6 +525,7 @@ impl StreamingSqlParser {
                         token_type: TokenType::LeftParen,
                         value: "(".to_string(),
                         position,
+                        end: position,
                     });
                     chars.next();
                     position += 1;
@@ -474,6 +535,7 @@ impl StreamingSqlParser {
                         token_type: TokenType::RightParen,
                         value: ")".to_string(),
                         position,
+                        end: position,
                     });
                     chars.next();
                     position += 1;
@@ -483,6 +545,7 @@ impl StreamingSqlParser {
                         token_type: TokenType::Comma,
                         value: ",".to_string(),
                         position,
+                        end: position,
                     });
                     chars.next();
                     position += 1;
@@ -492,6 +555,7 @@ impl StreamingSqlParser {
                         token_type: TokenType::Semicolon,
                         value: ";".to_string(),
                         position,
+                        end: position,
                     });
                     chars.next();
                     position += 1;
@@ -503,6 +567,27 @@ impl StreamingSqlParser {
                         token_type: TokenType::Asterisk,
                         value: "*".to_string(),
                         position,
+                        end: position,
+                    });
+                    chars.next();
+                    position += 1;
+                }
+                '[' => {
+                    tokens.push(Token {
+                        token_type: TokenType::LeftBracket,
+                        value: "[".to_string(),
+                        position,
+                        end: position,
+                    });
+                    chars.next();
+                    position += 1;
+                }
+                ']' => {
+                    tokens.push(Token {
+                        token_type: TokenType::RightBracket,
+                        value: "]".to_string(),
+                        position,
+                        end: position,
                     });
                     chars.next();
                     position += 1;
@@ -512,6 +597,7 @@ impl StreamingSqlParser {
                         token_type: TokenType::Dot,
                         value: ".".to_string(),
                         position,
+                        end: position,
                     });
                     chars.next();
                     position += 1;
@@ -521,6 +607,7 @@ impl StreamingSqlParser {
                         token_type: TokenType::Plus,
                         value: "+".to_string(),
                         position,
+                        end: position,
                     });
                     chars.next();
                     position += 1;
@@ -540,12 +627,23 @@ impl StreamingSqlParser {
                                 break;
                             }
                         }
+                    } else if let Some(&'>') = chars.peek() {
+                        // Lambda arrow "->"
+                        chars.next();
+                        position += 1;
+                        tokens.push(Token {
+                            token_type: TokenType::Arrow,
+                            value: "->".to_string(),
+                            position: position - 2,
+                            end: position,
+                        });
                     } else {
                         // Regular minus token
                         tokens.push(Token {
                             token_type: TokenType::Minus,
                             value: "-".to_string(),
                             position: position - 1, // Adjust position since we already advanced
+                            end: position,
                         });
                     }
                 }
@@ -571,7 +669,7 @@ impl StreamingSqlParser {
                             }
                         }
                         if !found_end {
-                            return Err(SqlError::ParseError {
+                            errors.push(SqlError::ParseError {
                                 message: "Unterminated multi-line comment".to_string(),
                                 position: Some(position),
                             });
@@ -582,6 +680,7 @@ impl StreamingSqlParser {
                             token_type: TokenType::Divide,
                             value: "/".to_string(),
                             position: position - 1, // Adjust position since we already advanced
+                            end: position,
                         });
                     }
                 }
@@ -590,6 +689,7 @@ impl StreamingSqlParser {
                         token_type: TokenType::Equal,
                         value: "=".to_string(),
                         position,
+                        end: position,
                     });
                     chars.next();
                     position += 1;
@@ -602,6 +702,7 @@ impl StreamingSqlParser {
                             token_type: TokenType::LessThanOrEqual,
                             value: "<=".to_string(),
                             position: position - 1,
+                            end: position,
                         });
                         chars.next();
                         position += 1;
@@ -610,6 +711,7 @@ impl StreamingSqlParser {
                             token_type: TokenType::NotEqual,
                             value: "<>".to_string(),
                             position: position - 1,
+                            end: position,
                         });
                         chars.next();
                         position += 1;
@@ -618,6 +720,7 @@ impl StreamingSqlParser {
                             token_type: TokenType::LessThan,
                             value: "<".to_string(),
                             position: position - 1,
+                            end: position,
                         });
                     }
                 }
@@ -629,6 +732,7 @@ impl StreamingSqlParser {
                             token_type: TokenType::GreaterThanOrEqual,
                             value: ">=".to_string(),
                             position: position - 1,
+                            end: position,
                         });
                         chars.next();
                         position += 1;
@@ -637,6 +741,7 @@ impl StreamingSqlParser {
                             token_type: TokenType::GreaterThan,
                             value: ">".to_string(),
                             position: position - 1,
+                            end: position,
                         });
                     }
                 }
@@ -648,11 +753,12 @@ impl StreamingSqlParser {
                             token_type: TokenType::NotEqual,
                             value: "!=".to_string(),
                             position: position - 1,
+                            end: position,
                         });
                         chars.next();
                         position += 1;
                     } else {
-                        return Err(SqlError::ParseError {
+                        errors.push(SqlError::ParseError {
                             message: "Unexpected character '!' - did you mean '!='?".to_string(),
                             position: Some(position - 1),
                         });
@@ -666,11 +772,12 @@ impl StreamingSqlParser {
                             token_type: TokenType::Concat,
                             value: "||".to_string(),
                             position: position - 1,
+                            end: position,
                         });
                         chars.next();
                         position += 1;
                     } else {
-                        return Err(SqlError::ParseError {
+                        errors.push(SqlError::ParseError {
                             message:
                                 "Unexpected character '|' - did you mean '||' for concatenation?"
                                     .to_string(),
@@ -699,6 +806,7 @@ impl StreamingSqlParser {
                         token_type: TokenType::String,
                         value,
                         position,
+                        end: position,
                     });
                 }
                 '0'..='9' => {
@@ -741,8 +849,46 @@ impl StreamingSqlParser {
                         token_type: TokenType::Number,
                         value,
                         position,
+                        end: position,
                     });
                 }
+                '$' | ':' => {
+                    // Query parameter placeholder: $1 or :name
+                    chars.next();
+                    position += 1;
+                    let mut value = ch.to_string();
+                    while let Some(&next_ch) = chars.peek() {
+                        let accepted = match ch {
+                            '$' => next_ch.is_ascii_digit(),
+                            _ => next_ch.is_alphanumeric() || next_ch == '_',
+                        };
+                        if !accepted {
+                            break;
+                        }
+                        value.push(next_ch);
+                        chars.next();
+                        position += 1;
+                    }
+
+                    if value.len() == 1 {
+                        errors.push(SqlError::ParseError {
+                            message: match ch {
+                                '$' => {
+                                    "Expected a parameter number after '$', as in $1".to_string()
+                                }
+                                _ => "Expected a parameter name after ':', as in :name".to_string(),
+                            },
+                            position: Some(start),
+                        });
+                    } else {
+                        tokens.push(Token {
+                            token_type: TokenType::Parameter,
+                            value,
+                            position,
+                            end: position,
+                        });
+                    }
+                }
                 _ if ch.is_alphabetic() || ch == '_' => {
                     let mut value = String::new();
                     while let Some(&next_ch) = chars.peek() {
@@ -765,24 +911,35 @@ impl StreamingSqlParser {
                         token_type,
                         value,
                         position,
+                        end: position,
                     });
                 }
                 _ => {
-                    return Err(SqlError::ParseError {
+                    errors.push(SqlError::ParseError {
                         message: format!("Unexpected character '{}' at position {}", ch, position),
                         position: Some(position),
                     });
+                    chars.next();
+                    position += 1;
                 }
             }
+
+            // Arms push their token before or after consuming its text, so record both
+            // ends once the text is consumed
+            if let Some(token) = tokens.get_mut(token_count) {
+                token.position = start;
+                token.end = position;
+            }
         }
 
         tokens.push(Token {
             token_type: TokenType::Eof,
             value: String::new(),
             position,
+            end: position,
         });
 
-        Ok(tokens)
+        (tokens, errors)
     }
 
     fn parse_tokens_with_context(
@@ -790,21 +947,267 @@ impl StreamingSqlParser {
         tokens: Vec<Token>,
         sql_text: &str,
     ) -> Result<StreamingQuery, SqlError> {
-        let mut parser = TokenParser::new(tokens, sql_text);
-
-        match parser.current_token().token_type {
-            TokenType::Select => parser.parse_select(),
-            TokenType::Create => parser.parse_create(),
-            TokenType::Show | TokenType::List => parser.parse_show(),
-            TokenType::Start => parser.parse_start_job(),
-            TokenType::Stop => parser.parse_stop_job(),
-            TokenType::Pause => parser.parse_pause_job(),
-            TokenType::Resume => parser.parse_resume_job(),
-            TokenType::Deploy => parser.parse_deploy_job(),
-            TokenType::Rollback => parser.parse_rollback_job(),
-            TokenType::Describe => parser.parse_describe(),
-            _ => Err(parser.create_parse_error("Expected SELECT, CREATE, SHOW, LIST, START, STOP, PAUSE, RESUME, DEPLOY, ROLLBACK, or DESCRIBE statement"))
+        TokenParser::new(tokens, sql_text).parse_statement()
+    }
+
+    /// Parse a script of `;`-separated statements, reporting every error found
+    ///
+    /// A statement with errors is skipped up to the next `;`. Within a statement, parsing
+    /// resumes at the next clause keyword after an error so that later clauses are checked
+    /// too; the clause in error is dropped, or replaced by `*` / a placeholder source when
+    /// the statement needs it.
+    pub fn parse_script(&self, sql: &str) -> Vec<ParsedStatement> {
+        let (tokens, tokenize_errors) = self.tokenize_with_errors(sql);
+        let mut tokenize_diagnostics: Vec<Diagnostic> = tokenize_errors
+            .iter()
+            .map(|error| self.diagnostic(error, &tokens, sql))
+            .collect();
+
+        let mut statements = Vec::new();
+        let mut statement: Vec<Token> = Vec::new();
+        for token in tokens {
+            let ends_statement = matches!(token.token_type, TokenType::Semicolon | TokenType::Eof);
+            if !ends_statement {
+                statement.push(token);
+                continue;
+            }
+            if statement.is_empty() {
+                continue;
+            }
+
+            let span = Span::from_char_range(
+                sql,
+                statement[0].position,
+                statement[statement.len() - 1].end,
+            );
+            statement.push(Token {
+                token_type: TokenType::Eof,
+                value: String::new(),
+                position: token.position,
+                end: token.position,
+            });
+            let mut result =
+                self.parse_statement_with_recovery(std::mem::take(&mut statement), sql);
+
+            // Characters that start no token belong to the statement they appear in
+            let (inside, rest): (Vec<_>, Vec<_>) = tokenize_diagnostics
+                .into_iter()
+                .partition(|diagnostic| diagnostic.span.is_some_and(|s| s.start < span.end));
+            tokenize_diagnostics = rest;
+            if !inside.is_empty() {
+                let mut diagnostics = inside;
+                if let Err(errors) = result {
+                    diagnostics.extend(errors);
+                    diagnostics.sort_by_key(|diagnostic| diagnostic.span.map(|s| s.start));
+                }
+                result = Err(diagnostics);
+            }
+            statements.push(ParsedStatement { span, result });
+        }
+
+        if !tokenize_diagnostics.is_empty() {
+            statements.push(ParsedStatement {
+                span: Span::new(sql.len(), sql.len()),
+                result: Err(tokenize_diagnostics),
+            });
+        }
+        statements
+    }
+
+    /// Parse a single statement, reporting every error found in it
+    pub fn parse_with_diagnostics(&self, sql: &str) -> Result<StreamingQuery, Vec<Diagnostic>> {
+        let (tokens, tokenize_errors) = self.tokenize_with_errors(sql);
+        let mut diagnostics: Vec<Diagnostic> = tokenize_errors
+            .iter()
+            .map(|error| self.diagnostic(error, &tokens, sql))
+            .collect();
+
+        match self.parse_statement_with_recovery(tokens, sql) {
+            Ok(query) if diagnostics.is_empty() => Ok(query),
+            Ok(_) => Err(diagnostics),
+            Err(errors) => {
+                diagnostics.extend(errors);
+                diagnostics.sort_by_key(|diagnostic| diagnostic.span.map(|s| s.start));
+                Err(diagnostics)
+            }
+        }
+    }
+
+    /// Names of the SQL keywords, statement and clause keywords first
+    pub fn keywords(&self) -> Vec<&str> {
+        let mut keywords: Vec<&str> = self.keywords.keys().map(String::as_str).collect();
+        keywords.sort_by_key(|keyword| {
+            let token_type = &self.keywords[*keyword];
+            let is_clause = CLAUSE_KEYWORDS.contains(token_type);
+            (!is_clause, *keyword)
+        });
+        keywords
+    }
+
+    /// Parse the tokens of one statement, resuming after errors at clause boundaries
+    fn parse_statement_with_recovery(
+        &self,
+        mut tokens: Vec<Token>,
+        sql: &str,
+    ) -> Result<StreamingQuery, Vec<Diagnostic>> {
+        let mut diagnostics = Vec::new();
+        let mut last_error = None;
+
+        while diagnostics.len() < MAX_ERRORS_PER_STATEMENT {
+            let mut parser = TokenParser::new(tokens.clone(), sql);
+            parser.enhance_errors = false;
+            let error = match parser.parse_statement() {
+                Ok(query) => {
+                    parser.consume_semicolon();
+                    let trailing = parser.current_token();
+                    if trailing.token_type != TokenType::Eof {
+                        parser.create_parse_error(format!(
+                            "Unexpected '{}' after the end of the statement",
+                            trailing.value
+                        ))
+                    } else if diagnostics.is_empty() {
+                        return Ok(query);
+                    } else {
+                        break;
+                    }
+                }
+                Err(error) => error,
+            };
+
+            // An error at or before the previous one comes from the repair, not the text
+            let position = match &error {
+                SqlError::ParseError { position, .. } => *position,
+                _ => None,
+            };
+            if let (Some(position), Some(last)) = (position, last_error) {
+                if position <= last {
+                    break;
+                }
+            }
+            diagnostics.push(self.diagnostic(&error, &tokens, sql));
+
+            match position.and_then(|position| Self::skip_clause(&tokens, position)) {
+                Some(repaired) => {
+                    tokens = repaired;
+                    last_error = position;
+                }
+                None => break,
+            }
         }
+        Err(diagnostics)
+    }
+
+    /// Drop or stub out the clause containing the error at `position`, so that parsing
+    /// can go on with the next clause
+    fn skip_clause(tokens: &[Token], position: usize) -> Option<Vec<Token>> {
+        let error_index = tokens.iter().position(|token| token.position >= position)?;
+
+        // Clause keywords outside parentheses, from the query's SELECT on
+        let mut boundaries = Vec::new();
+        let mut depth = 0usize;
+        let mut in_query = false;
+        for (index, token) in tokens.iter().enumerate() {
+            match token.token_type {
+                TokenType::LeftParen => depth += 1,
+                TokenType::RightParen => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            if depth == 0 && CLAUSE_KEYWORDS.contains(&token.token_type) {
+                in_query |= token.token_type == TokenType::Select;
+                if in_query {
+                    boundaries.push(index);
+                }
+            }
+        }
+
+        let start = boundaries
+            .iter()
+            .copied()
+            .rev()
+            .find(|&index| index < error_index)?;
+        let end = boundaries
+            .iter()
+            .copied()
+            .find(|&index| index >= error_index)
+            .unwrap_or(tokens.len() - 1);
+
+        let keyword = &tokens[start];
+        let placeholder = |token_type: TokenType, value: &str| Token {
+            token_type,
+            value: value.to_string(),
+            position: keyword.position,
+            end: keyword.end,
+        };
+        let replacement = match keyword.token_type {
+            // A query needs its field list and source; the rest is optional
+            TokenType::Select => vec![keyword.clone(), placeholder(TokenType::Asterisk, "*")],
+            TokenType::From => vec![
+                keyword.clone(),
+                placeholder(TokenType::Identifier, "recovered_source"),
+            ],
+            _ => Vec::new(),
+        };
+
+        let mut repaired = tokens[..start].to_vec();
+        repaired.extend(replacement);
+        repaired.extend_from_slice(&tokens[end..]);
+        Some(repaired)
+    }
+
+    /// Describe a parse error with the span of the offending token
+    fn diagnostic(&self, error: &SqlError, tokens: &[Token], sql: &str) -> Diagnostic {
+        let (message, position) = match error {
+            SqlError::ParseError { message, position } => (message.clone(), *position),
+            other => (other.to_string(), None),
+        };
+        let mut diagnostic = Diagnostic::error(message);
+        let Some(position) = position else {
+            return diagnostic;
+        };
+
+        let token = tokens.iter().find(|token| token.position == position);
+        match token {
+            Some(token) if token.token_type == TokenType::Eof => {
+                // Point just past the last token rather than at trailing whitespace
+                let end = tokens
+                    .iter()
+                    .rev()
+                    .find(|t| t.token_type != TokenType::Eof && t.end <= position)
+                    .map_or(position, |t| t.end);
+                diagnostic = diagnostic
+                    .with_span(Span::from_char_range(sql, end, end))
+                    .with_label("unexpected end of statement");
+            }
+            Some(token) => {
+                diagnostic =
+                    diagnostic.with_span(Span::from_char_range(sql, token.position, token.end));
+                let index = tokens
+                    .iter()
+                    .position(|t| t.position == position)
+                    .unwrap_or(0);
+                let keywords = self.keywords();
+                let misspelled_keyword = |token: &Token| match token.token_type {
+                    TokenType::Identifier => closest_match(&token.value, keywords.iter().copied()),
+                    _ => None,
+                };
+                if let Some(keyword) = misspelled_keyword(token) {
+                    diagnostic = diagnostic.with_suggestion(keyword);
+                } else if let Some(previous) = index.checked_sub(1).map(|i| &tokens[i]) {
+                    // A misspelled keyword before the error was taken for a name or alias
+                    if let Some(keyword) = misspelled_keyword(previous) {
+                        diagnostic = diagnostic.with_help(format!(
+                            "did you mean `{}` instead of `{}`?",
+                            keyword, previous.value
+                        ));
+                    }
+                }
+            }
+            None => {
+                diagnostic =
+                    diagnostic.with_span(Span::from_char_range(sql, position, position + 1));
+            }
+        }
+        diagnostic
     }
 }
 
@@ -812,6 +1215,10 @@ struct TokenParser<'a> {
     tokens: Vec<Token>,
     current: usize,
     sql_text: &'a str,
+    /// Byte offset of each character of non-ASCII text, as tokens count characters
+    char_offsets: Option<Vec<usize>>,
+    /// Whether errors embed the location and a snippet of the SQL text in their message
+    enhance_errors: bool,
 }
 
 impl<'a> TokenParser<'a> {
@@ -820,9 +1227,34 @@ impl<'a> TokenParser<'a> {
             tokens,
             current: 0,
             sql_text,
+            char_offsets: (!sql_text.is_ascii())
+                .then(|| sql_text.char_indices().map(|(offset, _)| offset).collect()),
+            enhance_errors: true,
         }
     }
 
+    fn parse_statement(&mut self) -> Result<StreamingQuery, SqlError> {
+        let start = self.current;
+        let query = match self.current_token().token_type {
+            TokenType::Select => self.parse_select(),
+            TokenType::Create => self.parse_create(),
+            TokenType::Show | TokenType::List => self.parse_show(),
+            TokenType::Start => self.parse_start_job(),
+            TokenType::Stop => self.parse_stop_job(),
+            TokenType::Pause => self.parse_pause_job(),
+            TokenType::Resume => self.parse_resume_job(),
+            TokenType::Deploy => self.parse_deploy_job(),
+            TokenType::Rollback => self.parse_rollback_job(),
+            TokenType::Describe => self.parse_describe(),
+            TokenType::Alter => self.parse_alter(),
+            TokenType::Drop => self.parse_drop(),
+            TokenType::Merge => self.parse_merge(),
+            TokenType::Upsert => self.parse_upsert(),
+            _ => Err(self.create_parse_error("Expected SELECT, CREATE, ALTER, DROP, MERGE, UPSERT, SHOW, LIST, START, STOP, PAUSE, RESUME, DEPLOY, ROLLBACK, or DESCRIBE statement"))
+        }?;
+        Ok(query.with_span(self.span_from(start)))
+    }
+
     /// Create an enhanced parse error with context
     fn create_parse_error(&self, message: impl Into<String>) -> SqlError {
         let position = if self.current < self.tokens.len() {
@@ -833,7 +1265,27 @@ impl<'a> TokenParser<'a> {
             None
         };
 
-        SqlError::parse_error_with_context(message, position, Some(self.sql_text))
+        match self.enhance_errors {
+            true => SqlError::parse_error_with_context(message, position, Some(self.sql_text)),
+            false => SqlError::parse_error(message, position),
+        }
+    }
+
+    /// The text from the token at `start` through the last token consumed
+    fn span_from(&self, start: usize) -> Span {
+        let first = self.tokens.get(start).map_or(0, |token| token.position);
+        let last = match self.current.checked_sub(1) {
+            Some(previous) if previous >= start => self.tokens[previous].end,
+            _ => first,
+        };
+        match &self.char_offsets {
+            Some(offsets) => {
+                let byte_offset =
+                    |chars: usize| offsets.get(chars).copied().unwrap_or(self.sql_text.len());
+                Span::new(byte_offset(first), byte_offset(last))
+            }
+            None => Span::new(first, last),
+        }
     }
 
     fn current_token(&self) -> &Token {
@@ -844,6 +1296,7 @@ impl<'a> TokenParser<'a> {
                 token_type: TokenType::Eof,
                 value: String::new(),
                 position: 0,
+                end: 0,
             };
             &EOF_TOKEN
         }
@@ -878,12 +1331,13 @@ impl<'a> TokenParser<'a> {
     }
 
     fn parse_select(&mut self) -> Result<StreamingQuery, SqlError> {
+        let start = self.current;
         self.expect(TokenType::Select)?;
 
         let fields = self.parse_select_fields()?;
 
         // FROM clause is optional (for scalar subqueries like SELECT 1)
-        let from_stream = if self.current_token().token_type == TokenType::From {
+        let (from_stream, from_alias) = if self.current_token().token_type == TokenType::From {
             self.advance(); // consume FROM
 
             // Support both identifiers and URI strings (FR-047)
@@ -908,7 +1362,7 @@ impl<'a> TokenParser<'a> {
             };
 
             // Parse optional alias for FROM clause (e.g., "FROM events s" or "FROM 'file://data.csv' f")
-            let _from_alias = if self.current_token().token_type == TokenType::Identifier {
+            let from_alias = if self.current_token().token_type == TokenType::Identifier {
                 let alias = self.current_token().value.clone();
                 self.advance();
                 Some(alias)
@@ -916,10 +1370,10 @@ impl<'a> TokenParser<'a> {
                 None
             };
 
-            stream_name
+            (stream_name, from_alias)
         } else {
             // No FROM clause - use a dummy stream name for scalar queries
-            "".to_string()
+            ("".to_string(), None)
         };
 
         // Parse JOIN clauses
@@ -1039,6 +1493,7 @@ impl<'a> TokenParser<'a> {
             let select_query = StreamingQuery::Select {
                 fields,
                 from: from_source,
+                from_alias,
                 joins,
                 where_clause,
                 group_by,
@@ -1048,6 +1503,7 @@ impl<'a> TokenParser<'a> {
                 limit,
                 emit_mode,
                 properties: _properties,
+                span: self.span_from(start).into(),
             };
 
             // Consume optional semicolon
@@ -1060,6 +1516,7 @@ impl<'a> TokenParser<'a> {
                 source: InsertSource::Select {
                     query: Box::new(select_query),
                 },
+                span: self.span_from(start).into(),
             });
         }
 
@@ -1083,6 +1540,7 @@ impl<'a> TokenParser<'a> {
         let select_query = StreamingQuery::Select {
             fields,
             from: from_source,
+            from_alias,
             joins,
             where_clause,
             group_by,
@@ -1092,6 +1550,7 @@ impl<'a> TokenParser<'a> {
             limit,
             emit_mode,
             properties,
+            span: self.span_from(start).into(),
         };
 
         // Check for UNION after SELECT
@@ -1113,6 +1572,7 @@ impl<'a> TokenParser<'a> {
                 left: Box::new(select_query),
                 right: Box::new(right_query),
                 all,
+                span: self.span_from(start).into(),
             })
         } else {
             Ok(select_query)
@@ -1120,12 +1580,13 @@ impl<'a> TokenParser<'a> {
     }
 
     fn parse_select_no_with(&mut self) -> Result<StreamingQuery, SqlError> {
+        let start = self.current;
         self.expect(TokenType::Select)?;
 
         let fields = self.parse_select_fields()?;
 
         // FROM clause is optional (for scalar subqueries like SELECT 1)
-        let from_stream = if self.current_token().token_type == TokenType::From {
+        let (from_stream, from_alias) = if self.current_token().token_type == TokenType::From {
             self.advance(); // consume FROM
 
             // Support both identifiers and URI strings (FR-047)
@@ -1150,7 +1611,7 @@ impl<'a> TokenParser<'a> {
             };
 
             // Parse optional alias for FROM clause (e.g., "FROM events s" or "FROM 'file://data.csv' f")
-            let _from_alias = if self.current_token().token_type == TokenType::Identifier {
+            let from_alias = if self.current_token().token_type == TokenType::Identifier {
                 let alias = self.current_token().value.clone();
                 self.advance();
                 Some(alias)
@@ -1158,10 +1619,10 @@ impl<'a> TokenParser<'a> {
                 None
             };
 
-            stream_name
+            (stream_name, from_alias)
         } else {
             // No FROM clause - use a dummy stream name for scalar queries
-            "".to_string()
+            ("".to_string(), None)
         };
 
         // Parse JOIN clauses
@@ -1252,6 +1713,7 @@ impl<'a> TokenParser<'a> {
         let select_query = StreamingQuery::Select {
             fields,
             from: from_source,
+            from_alias,
             joins,
             where_clause,
             group_by,
@@ -1261,6 +1723,7 @@ impl<'a> TokenParser<'a> {
             limit,
             emit_mode,
             properties: None, // No WITH clause parsing in job contexts
+            span: self.span_from(start).into(),
         };
 
         // Check for UNION after SELECT
@@ -1282,6 +1745,7 @@ impl<'a> TokenParser<'a> {
                 left: Box::new(select_query),
                 right: Box::new(right_query),
                 all,
+                span: self.span_from(start).into(),
             })
         } else {
             Ok(select_query)
@@ -1385,7 +1849,7 @@ impl<'a> TokenParser<'a> {
 
         // Optional alias for the right source
         let right_alias = if self.current_token().token_type == TokenType::Identifier {
-            Some(self.current_token().value.clone())
+            Some(self.expect(TokenType::Identifier)?.value)
         } else if self.current_token().token_type == TokenType::As {
             self.advance();
             Some(self.expect(TokenType::Identifier)?.value)
@@ -1393,10 +1857,6 @@ impl<'a> TokenParser<'a> {
             None
         };
 
-        if right_alias.is_some() {
-            self.advance();
-        }
-
         // Parse ON condition
         self.expect(TokenType::On)?;
         let condition = self.parse_expression()?;
@@ -1428,6 +1888,7 @@ impl<'a> TokenParser<'a> {
     }
 
     fn parse_logical_or(&mut self) -> Result<Expr, SqlError> {
+        let start = self.current;
         let mut left = self.parse_logical_and()?;
 
         while self.current_token().token_type == TokenType::Or {
@@ -1437,6 +1898,7 @@ impl<'a> TokenParser<'a> {
                 left: Box::new(left),
                 op: BinaryOperator::Or,
                 right: Box::new(right),
+                span: self.span_from(start).into(),
             };
         }
 
@@ -1444,6 +1906,7 @@ impl<'a> TokenParser<'a> {
     }
 
     fn parse_logical_and(&mut self) -> Result<Expr, SqlError> {
+        let start = self.current;
         let mut left = self.parse_comparison()?;
 
         while self.current_token().token_type == TokenType::And {
@@ -1453,6 +1916,7 @@ impl<'a> TokenParser<'a> {
                 left: Box::new(left),
                 op: BinaryOperator::And,
                 right: Box::new(right),
+                span: self.span_from(start).into(),
             };
         }
 
@@ -1460,6 +1924,7 @@ impl<'a> TokenParser<'a> {
     }
 
     fn parse_comparison(&mut self) -> Result<Expr, SqlError> {
+        let start = self.current;
         let mut left = self.parse_concatenative()?;
 
         while matches!(
@@ -1487,6 +1952,7 @@ impl<'a> TokenParser<'a> {
                         left = Expr::UnaryOp {
                             op: UnaryOperator::IsNotNull,
                             expr: Box::new(left),
+                            span: self.span_from(start).into(),
                         };
                     } else {
                         return Err(SqlError::ParseError {
@@ -1499,6 +1965,7 @@ impl<'a> TokenParser<'a> {
                     left = Expr::UnaryOp {
                         op: UnaryOperator::IsNull,
                         expr: Box::new(left),
+                        span: self.span_from(start).into(),
                     };
                 } else {
                     return Err(SqlError::ParseError {
@@ -1514,6 +1981,7 @@ impl<'a> TokenParser<'a> {
                         position: Some(self.current_token().position),
                     });
                 }
+                let list_start = self.current;
                 self.advance(); // consume '('
 
                 // Check if this is a subquery
@@ -1526,7 +1994,9 @@ impl<'a> TokenParser<'a> {
                         right: Box::new(Expr::Subquery {
                             query: Box::new(subquery),
                             subquery_type: crate::ferris::sql::ast::SubqueryType::In,
+                            span: self.span_from(list_start).into(),
                         }),
+                        span: self.span_from(start).into(),
                     };
                 } else {
                     // Regular IN list
@@ -1550,7 +2020,8 @@ impl<'a> TokenParser<'a> {
                     left = Expr::BinaryOp {
                         left: Box::new(left),
                         op: BinaryOperator::In,
-                        right: Box::new(Expr::List(list_items)),
+                        right: Box::new(Expr::List(list_items, self.span_from(list_start).into())),
+                        span: self.span_from(start).into(),
                     };
                 }
             } else if op_token.token_type == TokenType::Not {
@@ -1565,6 +2036,7 @@ impl<'a> TokenParser<'a> {
                             position: Some(self.current_token().position),
                         });
                     }
+                    let list_start = self.current;
                     self.advance(); // consume '('
 
                     // Check if this is a subquery
@@ -1577,7 +2049,9 @@ impl<'a> TokenParser<'a> {
                             right: Box::new(Expr::Subquery {
                                 query: Box::new(subquery),
                                 subquery_type: crate::ferris::sql::ast::SubqueryType::NotIn,
+                                span: self.span_from(list_start).into(),
                             }),
+                            span: self.span_from(start).into(),
                         };
                     } else {
                         // Regular NOT IN list
@@ -1601,7 +2075,11 @@ impl<'a> TokenParser<'a> {
                         left = Expr::BinaryOp {
                             left: Box::new(left),
                             op: BinaryOperator::NotIn,
-                            right: Box::new(Expr::List(list_items)),
+                            right: Box::new(Expr::List(
+                                list_items,
+                                self.span_from(list_start).into(),
+                            )),
+                            span: self.span_from(start).into(),
                         };
                     }
                 } else if self.current_token().token_type == TokenType::Between {
@@ -1625,6 +2103,7 @@ impl<'a> TokenParser<'a> {
                         low: Box::new(low),
                         high: Box::new(high),
                         negated: true,
+                        span: self.span_from(start).into(),
                     };
                 } else {
                     return Err(SqlError::ParseError {
@@ -1651,6 +2130,7 @@ impl<'a> TokenParser<'a> {
                     low: Box::new(low),
                     high: Box::new(high),
                     negated: false,
+                    span: self.span_from(start).into(),
                 };
             } else {
                 let right = self.parse_additive()?;
@@ -1669,6 +2149,7 @@ impl<'a> TokenParser<'a> {
                     left: Box::new(left),
                     op,
                     right: Box::new(right),
+                    span: self.span_from(start).into(),
                 };
             }
         }
@@ -1677,6 +2158,7 @@ impl<'a> TokenParser<'a> {
     }
 
     fn parse_concatenative(&mut self) -> Result<Expr, SqlError> {
+        let start = self.current;
         let mut left = self.parse_additive()?;
 
         while self.current_token().token_type == TokenType::Concat {
@@ -1686,6 +2168,7 @@ impl<'a> TokenParser<'a> {
                 left: Box::new(left),
                 op: BinaryOperator::Concat,
                 right: Box::new(right),
+                span: self.span_from(start).into(),
             };
         }
 
@@ -1693,6 +2176,7 @@ impl<'a> TokenParser<'a> {
     }
 
     fn parse_additive(&mut self) -> Result<Expr, SqlError> {
+        let start = self.current;
         let mut left = self.parse_multiplicative()?;
 
         while matches!(
@@ -1713,6 +2197,7 @@ impl<'a> TokenParser<'a> {
                 left: Box::new(left),
                 op,
                 right: Box::new(right),
+                span: self.span_from(start).into(),
             };
         }
 
@@ -1720,7 +2205,8 @@ impl<'a> TokenParser<'a> {
     }
 
     fn parse_multiplicative(&mut self) -> Result<Expr, SqlError> {
-        let mut left = self.parse_primary()?;
+        let start = self.current;
+        let mut left = self.parse_at_time_zone()?;
 
         while matches!(
             self.current_token().token_type,
@@ -1728,7 +2214,7 @@ impl<'a> TokenParser<'a> {
         ) {
             let op_token = self.current_token().clone();
             self.advance();
-            let right = self.parse_primary()?;
+            let right = self.parse_at_time_zone()?;
 
             let op = match op_token.token_type {
                 TokenType::Asterisk => BinaryOperator::Multiply,
@@ -1740,13 +2226,147 @@ impl<'a> TokenParser<'a> {
                 left: Box::new(left),
                 op,
                 right: Box::new(right),
+                span: self.span_from(start).into(),
             };
         }
 
         Ok(left)
     }
 
+    /// Parse a primary expression followed by any number of `AT TIME ZONE <zone>` suffixes.
+    /// The operator is desugared into the AT_TIME_ZONE(expr, zone) function.
+    fn parse_at_time_zone(&mut self) -> Result<Expr, SqlError> {
+        let start = self.current;
+        let mut expr = self.parse_postfix()?;
+
+        while self.current_token().value.eq_ignore_ascii_case("AT")
+            && self
+                .peek_token(1)
+                .is_some_and(|t| t.value.eq_ignore_ascii_case("TIME"))
+        {
+            self.advance(); // consume AT
+            self.advance(); // consume TIME
+            self.expect_keyword("ZONE")?;
+            let zone = self.parse_primary()?;
+            expr = Expr::Function {
+                name: "AT_TIME_ZONE".to_string(),
+                args: vec![expr, zone],
+                span: self.span_from(start).into(),
+            };
+        }
+
+        Ok(expr)
+    }
+
+    /// Parse a primary expression followed by any number of `[index]` subscripts and
+    /// `.field` accesses, e.g. `items[0].price` or `attributes['region']`.
+    fn parse_postfix(&mut self) -> Result<Expr, SqlError> {
+        let start = self.current;
+        let mut expr = self.parse_primary()?;
+
+        loop {
+            match self.current_token().token_type {
+                TokenType::LeftBracket => {
+                    self.advance(); // consume '['
+                    let index = self.parse_expression()?;
+                    self.expect(TokenType::RightBracket)?;
+                    expr = Expr::Subscript {
+                        expr: Box::new(expr),
+                        index: Box::new(index),
+                        span: self.span_from(start).into(),
+                    };
+                }
+                TokenType::Dot => {
+                    self.advance(); // consume '.'
+                    let field = self.parse_field_name()?;
+                    expr = Expr::FieldAccess {
+                        expr: Box::new(expr),
+                        field,
+                        span: self.span_from(start).into(),
+                    };
+                }
+                _ => return Ok(expr),
+            }
+        }
+    }
+
+    /// Parse a field name after a dot. Keywords are allowed as field names in qualified expressions.
+    fn parse_field_name(&mut self) -> Result<String, SqlError> {
+        match self.current_token().token_type {
+            TokenType::Identifier
+            | TokenType::Status
+            | TokenType::Join
+            | TokenType::Left
+            | TokenType::Right
+            | TokenType::Inner
+            | TokenType::Full
+            | TokenType::Outer
+            | TokenType::On
+            | TokenType::Within
+            | TokenType::Versions
+            | TokenType::Metrics => {
+                let field_name = self.current_token().value.clone();
+                self.advance();
+                Ok(field_name)
+            }
+            _ => Err(SqlError::ParseError {
+                message: "Expected field name after dot".to_string(),
+                position: Some(self.current_token().position),
+            }),
+        }
+    }
+
+    /// Parse a function argument, which may be a lambda: `x -> expr` or `(acc, x) -> expr`
+    fn parse_function_argument(&mut self) -> Result<Expr, SqlError> {
+        let start = self.current;
+        let is_arrow = |parser: &Self, offset: usize| {
+            parser.peek_token(offset).map(|t| &t.token_type) == Some(&TokenType::Arrow)
+        };
+
+        if self.current_token().token_type == TokenType::Identifier && is_arrow(self, 1) {
+            let param = self.current_token().value.clone();
+            self.advance(); // consume parameter
+            self.advance(); // consume '->'
+            let body = self.parse_expression()?;
+            return Ok(Expr::Lambda {
+                params: vec![param],
+                body: Box::new(body),
+                span: self.span_from(start).into(),
+            });
+        }
+
+        if self.current_token().token_type == TokenType::LeftParen {
+            // Look ahead for `( ident [, ident]* ) ->`
+            let mut offset = 1;
+            let mut params = Vec::new();
+            while let Some(token) = self.peek_token(offset) {
+                if token.token_type != TokenType::Identifier {
+                    break;
+                }
+                params.push(token.value.clone());
+                match self.peek_token(offset + 1).map(|t| &t.token_type) {
+                    Some(TokenType::Comma) => offset += 2,
+                    Some(TokenType::RightParen) if is_arrow(self, offset + 2) => {
+                        for _ in 0..offset + 3 {
+                            self.advance();
+                        }
+                        let body = self.parse_expression()?;
+                        return Ok(Expr::Lambda {
+                            params,
+                            body: Box::new(body),
+                            span: self.span_from(start).into(),
+                        });
+                    }
+                    _ => break,
+                }
+            }
+        }
+
+        self.parse_expression()
+    }
+
     fn parse_primary(&mut self) -> Result<Expr, SqlError> {
+        let start = self.current;
         let token = self.current_token().clone();
         match token.token_type {
             TokenType::Identifier => {
@@ -1754,15 +2374,24 @@ impl<'a> TokenParser<'a> {
                 match token.value.to_uppercase().as_str() {
                     "TRUE" => {
                         self.advance();
-                        return Ok(Expr::Literal(LiteralValue::Boolean(true)));
+                        return Ok(Expr::Literal(
+                            LiteralValue::Boolean(true),
+                            self.span_from(start).into(),
+                        ));
                     }
                     "FALSE" => {
                         self.advance();
-                        return Ok(Expr::Literal(LiteralValue::Boolean(false)));
+                        return Ok(Expr::Literal(
+                            LiteralValue::Boolean(false),
+                            self.span_from(start).into(),
+                        ));
                     }
                     "NULL" => {
                         self.advance();
-                        return Ok(Expr::Literal(LiteralValue::Null));
+                        return Ok(Expr::Literal(
+                            LiteralValue::Null,
+                            self.span_from(start).into(),
+                        ));
                     }
                     _ => {}
                 }
@@ -1773,6 +2402,7 @@ impl<'a> TokenParser<'a> {
                     return Ok(Expr::Function {
                         name: "CURRENT_TIMESTAMP".to_string(),
                         args: Vec::new(),
+                        span: self.span_from(start).into(),
                     });
                 }
 
@@ -1786,10 +2416,14 @@ impl<'a> TokenParser<'a> {
                         loop {
                             if self.current_token().token_type == TokenType::Asterisk {
                                 // Handle COUNT(*) special case
+                                let asterisk = self.current;
                                 self.advance();
-                                args.push(Expr::Literal(LiteralValue::Integer(1)));
+                                args.push(Expr::Literal(
+                                    LiteralValue::Integer(1),
+                                    self.span_from(asterisk).into(),
+                                ));
                             } else {
-                                args.push(self.parse_expression()?);
+                                args.push(self.parse_function_argument()?);
                             }
 
                             if self.current_token().token_type == TokenType::Comma {
@@ -1810,47 +2444,26 @@ impl<'a> TokenParser<'a> {
                             function_name: token.value,
                             args,
                             over_clause,
+                            span: self.span_from(start).into(),
                         })
                     } else {
                         Ok(Expr::Function {
                             name: token.value,
                             args,
+                            span: self.span_from(start).into(),
                         })
                     }
                 } else if self.current_token().token_type == TokenType::Dot {
-                    self.advance();
-                    // Allow keywords to be used as field names in qualified expressions
-                    let field = match self.current_token().token_type {
-                        TokenType::Identifier => {
-                            let field_name = self.current_token().value.clone();
-                            self.advance();
-                            field_name
-                        }
-                        TokenType::Status
-                        | TokenType::Join
-                        | TokenType::Left
-                        | TokenType::Right
-                        | TokenType::Inner
-                        | TokenType::Full
-                        | TokenType::Outer
-                        | TokenType::On
-                        | TokenType::Within
-                        | TokenType::Versions
-                        | TokenType::Metrics => {
-                            let field_name = self.current_token().value.clone();
-                            self.advance();
-                            field_name
-                        }
-                        _ => {
-                            return Err(SqlError::ParseError {
-                                message: "Expected field name after dot".to_string(),
-                                position: Some(self.current_token().position),
-                            });
-                        }
-                    };
-                    Ok(Expr::Column(format!("{}.{}", token.value, field)))
+                    // Qualified or nested column: table.column, payload.customer.id
+                    let mut column_name = token.value;
+                    while self.current_token().token_type == TokenType::Dot {
+                        self.advance();
+                        column_name.push('.');
+                        column_name.push_str(&self.parse_field_name()?);
+                    }
+                    Ok(Expr::Column(column_name, self.span_from(start).into()))
                 } else {
-                    Ok(Expr::Column(token.value))
+                    Ok(Expr::Column(token.value, self.span_from(start).into()))
                 }
             }
             // Allow keywords to be used as column names or function names
@@ -1875,10 +2488,14 @@ impl<'a> TokenParser<'a> {
                         loop {
                             if self.current_token().token_type == TokenType::Asterisk {
                                 // Handle COUNT(*) special case
+                                let asterisk = self.current;
                                 self.advance();
-                                args.push(Expr::Literal(LiteralValue::Integer(1)));
+                                args.push(Expr::Literal(
+                                    LiteralValue::Integer(1),
+                                    self.span_from(asterisk).into(),
+                                ));
                             } else {
-                                args.push(self.parse_expression()?);
+                                args.push(self.parse_function_argument()?);
                             }
 
                             if self.current_token().token_type == TokenType::Comma {
@@ -1899,23 +2516,28 @@ impl<'a> TokenParser<'a> {
                             function_name,
                             args,
                             over_clause,
+                            span: self.span_from(start).into(),
                         })
                     } else {
                         Ok(Expr::Function {
                             name: function_name,
                             args,
+                            span: self.span_from(start).into(),
                         })
                     }
                 } else {
                     // This keyword is being used as a column name
                     let column_name = token.value;
                     self.advance();
-                    Ok(Expr::Column(column_name))
+                    Ok(Expr::Column(column_name, self.span_from(start).into()))
                 }
             }
             TokenType::String => {
                 self.advance();
-                Ok(Expr::Literal(LiteralValue::String(token.value)))
+                Ok(Expr::Literal(
+                    LiteralValue::String(token.value),
+                    self.span_from(start).into(),
+                ))
             }
             TokenType::Number => {
                 self.advance();
@@ -1923,7 +2545,10 @@ impl<'a> TokenParser<'a> {
                 if token.value.contains('.') || token.value.to_uppercase().contains('E') {
                     // Parse as Float for backward compatibility - users can cast to DECIMAL if needed
                     if let Ok(f) = token.value.parse::<f64>() {
-                        Ok(Expr::Literal(LiteralValue::Float(f)))
+                        Ok(Expr::Literal(
+                            LiteralValue::Float(f),
+                            self.span_from(start).into(),
+                        ))
                     } else {
                         Err(SqlError::ParseError {
                             message: format!("Invalid float literal: {}", token.value),
@@ -1931,9 +2556,15 @@ impl<'a> TokenParser<'a> {
                         })
                     }
                 } else if let Ok(i) = token.value.parse::<i64>() {
-                    Ok(Expr::Literal(LiteralValue::Integer(i)))
+                    Ok(Expr::Literal(
+                        LiteralValue::Integer(i),
+                        self.span_from(start).into(),
+                    ))
                 } else if let Ok(f) = token.value.parse::<f64>() {
-                    Ok(Expr::Literal(LiteralValue::Float(f)))
+                    Ok(Expr::Literal(
+                        LiteralValue::Float(f),
+                        self.span_from(start).into(),
+                    ))
                 } else {
                     Err(SqlError::ParseError {
                         message: format!("Invalid number: {}", token.value),
@@ -1943,7 +2574,30 @@ impl<'a> TokenParser<'a> {
             }
             TokenType::Null => {
                 self.advance();
-                Ok(Expr::Literal(LiteralValue::Null))
+                Ok(Expr::Literal(
+                    LiteralValue::Null,
+                    self.span_from(start).into(),
+                ))
+            }
+            TokenType::Parameter => {
+                self.advance();
+                let (sigil, name) = token.value.split_at(1);
+                let parameter = match sigil {
+                    "$" => match name.parse::<usize>() {
+                        Ok(number) if number > 0 => QueryParameter::Positional(number),
+                        _ => {
+                            return Err(SqlError::ParseError {
+                                message: format!(
+                                    "Invalid parameter {}, parameters are numbered from $1",
+                                    token.value
+                                ),
+                                position: Some(token.position),
+                            })
+                        }
+                    },
+                    _ => QueryParameter::Named(name.to_string()),
+                };
+                Ok(Expr::Parameter(parameter, self.span_from(start).into()))
             }
             TokenType::Interval => {
                 self.advance(); // consume INTERVAL
@@ -1984,6 +2638,9 @@ impl<'a> TokenParser<'a> {
                     "MINUTE" | "MINUTES" => TimeUnit::Minute,
                     "HOUR" | "HOURS" => TimeUnit::Hour,
                     "DAY" | "DAYS" => TimeUnit::Day,
+                    "WEEK" | "WEEKS" => TimeUnit::Week,
+                    "MONTH" | "MONTHS" => TimeUnit::Month,
+                    "YEAR" | "YEARS" => TimeUnit::Year,
                     _ => {
                         return Err(SqlError::ParseError {
                             message: format!("Invalid time unit: {}", unit_token.value),
@@ -1993,11 +2650,15 @@ impl<'a> TokenParser<'a> {
                 };
 
                 self.advance(); // consume time unit
-                Ok(Expr::Literal(LiteralValue::Interval { value, unit }))
+                Ok(Expr::Literal(
+                    LiteralValue::Interval { value, unit },
+                    self.span_from(start).into(),
+                ))
             }
             TokenType::Case => {
                 self.advance(); // consume CASE
-                self.parse_case_expression()
+                let expr = self.parse_case_expression()?;
+                Ok(expr.with_span(self.span_from(start)))
             }
             TokenType::Exists => {
                 self.advance(); // consume EXISTS
@@ -2007,6 +2668,7 @@ impl<'a> TokenParser<'a> {
                 Ok(Expr::Subquery {
                     query: Box::new(subquery),
                     subquery_type: crate::ferris::sql::ast::SubqueryType::Exists,
+                    span: self.span_from(start).into(),
                 })
             }
             TokenType::Not => {
@@ -2019,6 +2681,7 @@ impl<'a> TokenParser<'a> {
                     Ok(Expr::Subquery {
                         query: Box::new(subquery),
                         subquery_type: crate::ferris::sql::ast::SubqueryType::NotExists,
+                        span: self.span_from(start).into(),
                     })
                 } else {
                     // Other NOT expressions (like NOT column_name)
@@ -2026,6 +2689,7 @@ impl<'a> TokenParser<'a> {
                     Ok(Expr::UnaryOp {
                         op: crate::ferris::sql::ast::UnaryOperator::Not,
                         expr: Box::new(expr),
+                        span: self.span_from(start).into(),
                     })
                 }
             }
@@ -2040,6 +2704,7 @@ impl<'a> TokenParser<'a> {
                     Ok(Expr::Subquery {
                         query: Box::new(subquery),
                         subquery_type: crate::ferris::sql::ast::SubqueryType::Scalar,
+                        span: self.span_from(start).into(),
                     })
                 } else {
                     // Regular parenthesized expression
@@ -2052,12 +2717,14 @@ impl<'a> TokenParser<'a> {
                 self.advance(); // consume '-'
                 let expr = self.parse_primary()?;
                 match expr {
-                    Expr::Literal(LiteralValue::Integer(n)) => {
-                        Ok(Expr::Literal(LiteralValue::Integer(-n)))
-                    }
-                    Expr::Literal(LiteralValue::Float(f)) => {
-                        Ok(Expr::Literal(LiteralValue::Float(-f)))
-                    }
+                    Expr::Literal(LiteralValue::Integer(n), _) => Ok(Expr::Literal(
+                        LiteralValue::Integer(-n),
+                        self.span_from(start).into(),
+                    )),
+                    Expr::Literal(LiteralValue::Float(f), _) => Ok(Expr::Literal(
+                        LiteralValue::Float(-f),
+                        self.span_from(start).into(),
+                    )),
                     // Note: Decimal literals are no longer parsed directly as LiteralValue::Decimal
                     // They are parsed as Float for backward compatibility
                     _ => {
@@ -2111,6 +2778,7 @@ impl<'a> TokenParser<'a> {
         Ok(Expr::Case {
             when_clauses,
             else_clause,
+            span: NodeSpan::default(),
         })
     }
 
@@ -2120,12 +2788,28 @@ impl<'a> TokenParser<'a> {
                 self.advance();
                 self.expect(TokenType::LeftParen)?;
                 let duration_str = self.parse_duration_token()?;
+
+                // Optional time zone for local-time alignment: TUMBLING(1d, 'America/New_York')
+                let time_zone = if self.current_token().token_type == TokenType::Comma {
+                    self.advance();
+                    let zone_token = self.expect(TokenType::String)?;
+                    if zone_token.value.parse::<chrono_tz::Tz>().is_err() {
+                        return Err(SqlError::ParseError {
+                            message: format!("Unknown time zone: '{}'", zone_token.value),
+                            position: Some(zone_token.position),
+                        });
+                    }
+                    Some(zone_token.value)
+                } else {
+                    None
+                };
                 self.expect(TokenType::RightParen)?;
 
                 let size = self.parse_duration(&duration_str)?;
                 WindowSpec::Tumbling {
                     size,
                     time_column: None,
+                    time_zone,
                 }
             }
             "SLIDING" => {
@@ -2229,6 +2913,8 @@ impl<'a> TokenParser<'a> {
                     "SECONDS" | "SECOND" => "s",
                     "HOURS" | "HOUR" => "h",
                     "MILLISECONDS" | "MILLISECOND" => "ms",
+                    "DAYS" | "DAY" => "d",
+                    "WEEKS" | "WEEK" => "w",
                     _ => {
                         return Err(SqlError::ParseError {
                             message: format!("Unsupported time unit: {}", unit_token.value),
@@ -2346,7 +3032,16 @@ impl<'a> TokenParser<'a> {
 
         // Optional column definitions
         let columns = if self.current_token().token_type == TokenType::LeftParen {
-            Some(self.parse_column_definitions()?)
+            let position = self.current_token().position;
+            let (columns, constraints) = self.parse_column_definitions()?;
+            if !constraints.is_empty() {
+                return Err(SqlError::ParseError {
+                    message: "PRIMARY KEY and CHECK constraints are only supported on CREATE TABLE"
+                        .to_string(),
+                    position: Some(position),
+                });
+            }
+            Some(columns)
         } else {
             None
         };
@@ -2378,6 +3073,7 @@ impl<'a> TokenParser<'a> {
                 into_clause,
                 properties,
                 emit_mode,
+                span: NodeSpan::default(),
             });
         }
 
@@ -2400,6 +3096,7 @@ impl<'a> TokenParser<'a> {
             as_select,
             properties,
             emit_mode,
+            span: NodeSpan::default(),
         })
     }
 
@@ -2407,11 +3104,12 @@ impl<'a> TokenParser<'a> {
         self.expect(TokenType::Table)?;
         let name = self.expect(TokenType::Identifier)?.value;
 
-        // Optional column definitions
-        let columns = if self.current_token().token_type == TokenType::LeftParen {
-            Some(self.parse_column_definitions()?)
+        // Optional column definitions and constraints
+        let (columns, constraints) = if self.current_token().token_type == TokenType::LeftParen {
+            let (columns, constraints) = self.parse_column_definitions()?;
+            (Some(columns), constraints)
         } else {
-            None
+            (None, Vec::new())
         };
 
         self.expect(TokenType::As)?;
@@ -2437,10 +3135,12 @@ impl<'a> TokenParser<'a> {
             return Ok(StreamingQuery::CreateTableInto {
                 name,
                 columns,
+                constraints,
                 as_select,
                 into_clause,
                 properties,
                 emit_mode,
+                span: NodeSpan::default(),
             });
         }
 
@@ -2460,27 +3160,78 @@ impl<'a> TokenParser<'a> {
         Ok(StreamingQuery::CreateTable {
             name,
             columns,
+            constraints,
             as_select,
             properties,
             emit_mode,
+            span: NodeSpan::default(),
         })
     }
 
-    fn parse_column_definitions(&mut self) -> Result<Vec<ColumnDef>, SqlError> {
+    fn parse_column_definitions(
+        &mut self,
+    ) -> Result<(Vec<ColumnDef>, Vec<TableConstraint>), SqlError> {
         self.expect(TokenType::LeftParen)?;
         let mut columns = Vec::new();
+        let mut constraints = Vec::new();
 
         loop {
-            let name = self.expect(TokenType::Identifier)?.value;
-            let data_type = self.parse_data_type()?;
-            let nullable = !self.consume_if_matches("NOT");
+            // Table-level constraint: [CONSTRAINT name] PRIMARY KEY (...) | CHECK (...)
+            if self.is_constraint_start() {
+                let name = self.parse_constraint_name()?;
+                let constraint = if self.consume_if_matches("PRIMARY") {
+                    self.expect_keyword("KEY")?;
+                    self.expect(TokenType::LeftParen)?;
+                    let mut key_columns = vec![self.expect(TokenType::Identifier)?.value];
+                    while self.current_token().token_type == TokenType::Comma {
+                        self.advance();
+                        key_columns.push(self.expect(TokenType::Identifier)?.value);
+                    }
+                    self.expect(TokenType::RightParen)?;
+                    TableConstraint::PrimaryKey {
+                        name,
+                        columns: key_columns,
+                    }
+                } else {
+                    self.parse_check_constraint(name)?
+                };
+                constraints.push(constraint);
+            } else {
+                let name = self.expect(TokenType::Identifier)?.value;
+                let data_type = self.parse_data_type()?;
+                let mut nullable = true;
+
+                // Column-level clauses in any order
+                loop {
+                    if self.consume_if_matches("NOT") {
+                        // NOT NULL marks a required column
+                        self.expect(TokenType::Null)?;
+                        nullable = false;
+                    } else if self.consume_if_matches("NULL") {
+                        // A bare NULL is the (default) nullable form
+                    } else if self.is_constraint_start() {
+                        let constraint_name = self.parse_constraint_name()?;
+                        if self.consume_if_matches("PRIMARY") {
+                            self.expect_keyword("KEY")?;
+                            constraints.push(TableConstraint::PrimaryKey {
+                                name: constraint_name,
+                                columns: vec![name.clone()],
+                            });
+                        } else {
+                            constraints.push(self.parse_check_constraint(constraint_name)?);
+                        }
+                    } else {
+                        break;
+                    }
+                }
 
-            columns.push(ColumnDef {
-                name,
-                data_type,
-                nullable,
-                properties: HashMap::new(),
-            });
+                columns.push(ColumnDef {
+                    name,
+                    data_type,
+                    nullable,
+                    properties: HashMap::new(),
+                });
+            }
 
             if self.current_token().token_type == TokenType::Comma {
                 self.advance();
@@ -2490,19 +3241,129 @@ impl<'a> TokenParser<'a> {
         }
 
         self.expect(TokenType::RightParen)?;
-        Ok(columns)
+        Self::validate_table_constraints(&mut columns, &constraints)?;
+        Ok((columns, constraints))
+    }
+
+    fn is_constraint_start(&self) -> bool {
+        let value = &self.current_token().value;
+        ["CONSTRAINT", "PRIMARY", "CHECK"]
+            .iter()
+            .any(|keyword| value.eq_ignore_ascii_case(keyword))
+    }
+
+    /// Optional `CONSTRAINT name` prefix
+    fn parse_constraint_name(&mut self) -> Result<Option<String>, SqlError> {
+        if self.consume_if_matches("CONSTRAINT") {
+            Ok(Some(self.expect(TokenType::Identifier)?.value))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn parse_check_constraint(
+        &mut self,
+        name: Option<String>,
+    ) -> Result<TableConstraint, SqlError> {
+        self.expect_keyword("CHECK")?;
+        self.expect(TokenType::LeftParen)?;
+        let expr = self.parse_expression()?;
+        self.expect(TokenType::RightParen)?;
+        Ok(TableConstraint::Check { name, expr })
+    }
+
+    /// A table has at most one primary key over declared columns, which become NOT NULL
+    fn validate_table_constraints(
+        columns: &mut [ColumnDef],
+        constraints: &[TableConstraint],
+    ) -> Result<(), SqlError> {
+        let mut primary_keys = constraints.iter().filter_map(|c| match c {
+            TableConstraint::PrimaryKey { columns, .. } => Some(columns),
+            TableConstraint::Check { .. } => None,
+        });
+        let Some(key_columns) = primary_keys.next() else {
+            return Ok(());
+        };
+        if primary_keys.next().is_some() {
+            return Err(SqlError::ParseError {
+                message: "A table can only have one PRIMARY KEY".to_string(),
+                position: None,
+            });
+        }
+
+        for key_column in key_columns {
+            let column = columns
+                .iter_mut()
+                .find(|c| &c.name == key_column)
+                .ok_or_else(|| SqlError::ParseError {
+                    message: format!(
+                        "PRIMARY KEY column '{}' is not a declared column",
+                        key_column
+                    ),
+                    position: None,
+                })?;
+            column.nullable = false;
+        }
+        Ok(())
     }
 
     fn parse_data_type(&mut self) -> Result<DataType, SqlError> {
         let type_name = self.expect(TokenType::Identifier)?.value.to_uppercase();
 
         match type_name.as_str() {
+            "SMALLINT" => Ok(DataType::SmallInt),
             "INT" | "INTEGER" => Ok(DataType::Integer),
-            "FLOAT" | "DOUBLE" | "REAL" => Ok(DataType::Float),
+            "BIGINT" | "LONG" => Ok(DataType::BigInt),
+            "REAL" => Ok(DataType::Real),
+            "FLOAT" | "DOUBLE" => Ok(DataType::Float),
             "STRING" | "VARCHAR" | "TEXT" => Ok(DataType::String),
             "BOOLEAN" | "BOOL" => Ok(DataType::Boolean),
-            "TIMESTAMP" => Ok(DataType::Timestamp),
-            "DECIMAL" | "NUMERIC" => Ok(DataType::Decimal),
+            "BYTES" | "VARBINARY" | "BINARY" => Ok(DataType::Bytes),
+            "UUID" => Ok(DataType::Uuid),
+            "DATE" => Ok(DataType::Date),
+            "TIME" => Ok(DataType::Time),
+            "TIMESTAMP" => {
+                // TIMESTAMP WITH TIME ZONE / TIMESTAMP WITHOUT TIME ZONE
+                if self.current_token().token_type == TokenType::With
+                    && self
+                        .peek_token(1)
+                        .is_some_and(|t| t.value.eq_ignore_ascii_case("TIME"))
+                {
+                    self.advance(); // consume WITH
+                    self.advance(); // consume TIME
+                    self.expect_keyword("ZONE")?;
+                    Ok(DataType::TimestampTz)
+                } else if self.current_token().value.eq_ignore_ascii_case("WITHOUT") {
+                    self.advance(); // consume WITHOUT
+                    self.expect_keyword("TIME")?;
+                    self.expect_keyword("ZONE")?;
+                    Ok(DataType::Timestamp)
+                } else {
+                    Ok(DataType::Timestamp)
+                }
+            }
+            "TIMESTAMPTZ" => Ok(DataType::TimestampTz),
+            "DECIMAL" | "NUMERIC" => {
+                if self.current_token().token_type != TokenType::LeftParen {
+                    return Ok(DataType::Decimal(None));
+                }
+                self.advance(); // consume (
+                let precision = self.parse_type_parameter()?;
+                let scale = if self.current_token().token_type == TokenType::Comma {
+                    self.advance();
+                    self.parse_type_parameter()?
+                } else {
+                    0
+                };
+                self.expect(TokenType::RightParen)?;
+
+                let spec =
+                    DecimalSpec::new(precision, scale).map_err(|message| SqlError::ParseError {
+                        message,
+                        position: None,
+                    })?;
+                Ok(DataType::Decimal(Some(spec)))
+            }
             "ARRAY" => {
                 self.expect(TokenType::LeftParen)?;
                 let inner_type = self.parse_data_type()?;
@@ -2524,8 +3385,25 @@ impl<'a> TokenParser<'a> {
         }
     }
 
+    /// Parse an unsigned integer type parameter such as the precision in `DECIMAL(18, 4)`
+    fn parse_type_parameter(&mut self) -> Result<u32, SqlError> {
+        let token = self.expect(TokenType::Number)?;
+        token
+            .value
+            .parse::<u32>()
+            .map_err(|_| SqlError::ParseError {
+                message: format!("Invalid type parameter: {}", token.value),
+                position: Some(token.position),
+            })
+    }
+
     fn parse_with_properties(&mut self) -> Result<HashMap<String, String>, SqlError> {
         self.expect(TokenType::With)?;
+        self.parse_property_list()
+    }
+
+    /// Parse a parenthesized `('key' = 'value', ...)` list
+    fn parse_property_list(&mut self) -> Result<HashMap<String, String>, SqlError> {
         self.expect(TokenType::LeftParen)?;
 
         let mut properties = HashMap::new();
@@ -2835,6 +3713,21 @@ impl<'a> TokenParser<'a> {
                 };
                 ShowResourceType::JobMetrics { name }
             }
+            TokenType::Identifier
+                if self
+                    .current_token()
+                    .value
+                    .eq_ignore_ascii_case("SAVEPOINTS") =>
+            {
+                self.advance();
+                // Optional specific job name
+                let name = if self.current_token().token_type == TokenType::Identifier {
+                    Some(self.expect(TokenType::Identifier)?.value)
+                } else {
+                    None
+                };
+                ShowResourceType::Savepoints { name }
+            }
             TokenType::Schema => {
                 self.advance();
                 // Expect resource name after SCHEMA
@@ -2880,7 +3773,7 @@ impl<'a> TokenParser<'a> {
             }
             _ => {
                 return Err(SqlError::ParseError {
-                    message: "Expected STREAMS, TABLES, TOPICS, FUNCTIONS, SCHEMA, PROPERTIES, JOBS, STATUS, VERSIONS, METRICS, or PARTITIONS after SHOW/LIST".to_string(),
+                    message: "Expected STREAMS, TABLES, TOPICS, FUNCTIONS, SCHEMA, PROPERTIES, JOBS, STATUS, VERSIONS, METRICS, SAVEPOINTS, or PARTITIONS after SHOW/LIST".to_string(),
                     position: Some(self.current_token().position)
                 });
             }
@@ -2897,6 +3790,7 @@ impl<'a> TokenParser<'a> {
         Ok(StreamingQuery::Show {
             resource_type,
             pattern,
+            span: NodeSpan::default(),
         })
     }
 
@@ -2921,6 +3815,15 @@ impl<'a> TokenParser<'a> {
         // Get job name
         let name = self.expect(TokenType::Identifier)?.value;
 
+        // Optional FROM SAVEPOINT 'path' to start with the state of a stopped job
+        let savepoint = if self.current_token().token_type == TokenType::From {
+            self.advance();
+            self.expect_savepoint_keyword()?;
+            Some(self.expect(TokenType::String)?.value)
+        } else {
+            None
+        };
+
         // Expect AS keyword
         self.expect(TokenType::As)?;
 
@@ -2938,6 +3841,8 @@ impl<'a> TokenParser<'a> {
             name,
             query,
             properties,
+            savepoint,
+            span: NodeSpan::default(),
         })
     }
 
@@ -2970,7 +3875,37 @@ impl<'a> TokenParser<'a> {
             false
         };
 
-        Ok(StreamingQuery::StopJob { name, force })
+        // Optional WITH SAVEPOINT to keep the job's state for a later START JOB
+        let savepoint = if self.current_token().token_type == TokenType::With {
+            self.advance();
+            self.expect_savepoint_keyword()?;
+            true
+        } else {
+            false
+        };
+
+        Ok(StreamingQuery::StopJob {
+            name,
+            force,
+            savepoint,
+            span: NodeSpan::default(),
+        })
+    }
+
+    /// Consume SAVEPOINT, which is an identifier rather than a reserved word
+    fn expect_savepoint_keyword(&mut self) -> Result<(), SqlError> {
+        let token = self.current_token();
+        if token.token_type == TokenType::Identifier
+            && token.value.eq_ignore_ascii_case("SAVEPOINT")
+        {
+            self.advance();
+            Ok(())
+        } else {
+            Err(SqlError::ParseError {
+                message: format!("Expected SAVEPOINT, found '{}'", token.value),
+                position: Some(token.position),
+            })
+        }
     }
 
     fn parse_pause_job(&mut self) -> Result<StreamingQuery, SqlError> {
@@ -2994,7 +3929,10 @@ impl<'a> TokenParser<'a> {
         // Get job name
         let name = self.expect(TokenType::Identifier)?.value;
 
-        Ok(StreamingQuery::PauseJob { name })
+        Ok(StreamingQuery::PauseJob {
+            name,
+            span: NodeSpan::default(),
+        })
     }
 
     fn parse_resume_job(&mut self) -> Result<StreamingQuery, SqlError> {
@@ -3018,7 +3956,10 @@ impl<'a> TokenParser<'a> {
         // Get job name
         let name = self.expect(TokenType::Identifier)?.value;
 
-        Ok(StreamingQuery::ResumeJob { name })
+        Ok(StreamingQuery::ResumeJob {
+            name,
+            span: NodeSpan::default(),
+        })
     }
 
     fn parse_deploy_job(&mut self) -> Result<StreamingQuery, SqlError> {
@@ -3102,6 +4043,7 @@ impl<'a> TokenParser<'a> {
             query,
             properties,
             strategy,
+            span: NodeSpan::default(),
         })
     }
 
@@ -3126,9 +4068,373 @@ impl<'a> TokenParser<'a> {
         Ok(StreamingQuery::RollbackJob {
             name,
             target_version,
+            span: NodeSpan::default(),
+        })
+    }
+
+    fn parse_alter(&mut self) -> Result<StreamingQuery, SqlError> {
+        self.expect(TokenType::Alter)?;
+        let object_type = match self.current_token().token_type {
+            TokenType::Stream => ObjectType::Stream,
+            TokenType::Table => ObjectType::Table,
+            _ => {
+                return Err(SqlError::ParseError {
+                    message: "Expected STREAM or TABLE after ALTER".to_string(),
+                    position: Some(self.current_token().position),
+                })
+            }
+        };
+        self.advance();
+        let name = self.expect(TokenType::Identifier)?.value;
+
+        let operation = if self.consume_if_matches("ADD") {
+            self.consume_if_matches("COLUMN");
+            let if_not_exists = if self.consume_if_matches("IF") {
+                self.expect(TokenType::Not)?;
+                self.expect(TokenType::Exists)?;
+                true
+            } else {
+                false
+            };
+            let column_name = self.expect(TokenType::Identifier)?.value;
+            let data_type = self.parse_data_type()?;
+            let nullable = if self.current_token().token_type == TokenType::Not {
+                self.advance();
+                self.expect(TokenType::Null)?;
+                false
+            } else {
+                true
+            };
+            AlterOperation::AddColumn {
+                column: ColumnDef {
+                    name: column_name,
+                    data_type,
+                    nullable,
+                    properties: HashMap::new(),
+                },
+                if_not_exists,
+            }
+        } else if self.consume_if_matches("SET") {
+            AlterOperation::SetProperties {
+                properties: self.parse_property_list()?,
+            }
+        } else {
+            return Err(SqlError::ParseError {
+                message: format!(
+                    "Expected ADD COLUMN or SET after ALTER {} {}, found '{}'",
+                    object_type.to_string().to_uppercase(),
+                    name,
+                    self.current_token().value
+                ),
+                position: Some(self.current_token().position),
+            });
+        };
+
+        self.consume_semicolon();
+        self.expect(TokenType::Eof)?;
+        Ok(StreamingQuery::Alter {
+            object_type,
+            name,
+            operation,
+            span: NodeSpan::default(),
+        })
+    }
+
+    fn parse_drop(&mut self) -> Result<StreamingQuery, SqlError> {
+        self.expect(TokenType::Drop)?;
+        let object_type = match self.current_token().token_type {
+            TokenType::Stream => ObjectType::Stream,
+            TokenType::Table => ObjectType::Table,
+            TokenType::Job => ObjectType::Job,
+            _ => {
+                return Err(SqlError::ParseError {
+                    message: "Expected STREAM, TABLE or JOB after DROP".to_string(),
+                    position: Some(self.current_token().position),
+                })
+            }
+        };
+        self.advance();
+
+        let if_exists = if self.consume_if_matches("IF") {
+            self.expect(TokenType::Exists)?;
+            true
+        } else {
+            false
+        };
+        let name = self.expect(TokenType::Identifier)?.value;
+
+        self.consume_semicolon();
+        self.expect(TokenType::Eof)?;
+        Ok(StreamingQuery::Drop {
+            object_type,
+            name,
+            if_exists,
+            span: NodeSpan::default(),
+        })
+    }
+
+    fn parse_merge(&mut self) -> Result<StreamingQuery, SqlError> {
+        self.expect(TokenType::Merge)?;
+        self.expect(TokenType::Into)?;
+        let target = self.expect(TokenType::Identifier)?.value;
+        let target_alias = self.parse_merge_alias("USING")?;
+
+        self.expect_keyword("USING")?;
+        let source = match self.current_token().token_type {
+            TokenType::Identifier if self.current_token().value.contains("://") => {
+                StreamSource::Uri(self.current_token().value.clone())
+            }
+            TokenType::Identifier => StreamSource::Stream(self.current_token().value.clone()),
+            TokenType::String => StreamSource::Uri(self.current_token().value.clone()),
+            _ => {
+                return Err(SqlError::ParseError {
+                    message: "Expected source stream or URI after USING".to_string(),
+                    position: Some(self.current_token().position),
+                })
+            }
+        };
+        self.advance();
+        let source_alias = self.parse_merge_alias("ON")?;
+
+        self.expect(TokenType::On)?;
+        let on = self.parse_expression()?;
+
+        let mut clauses = Vec::new();
+        while self.current_token().token_type == TokenType::When {
+            self.advance();
+            let not_matched = if self.current_token().token_type == TokenType::Not {
+                self.advance();
+                true
+            } else {
+                false
+            };
+            self.expect_keyword("MATCHED")?;
+            let condition = if self.current_token().token_type == TokenType::And {
+                self.advance();
+                Some(self.parse_expression()?)
+            } else {
+                None
+            };
+            self.expect(TokenType::Then)?;
+
+            let clause = if not_matched {
+                self.expect_keyword("INSERT")?;
+                if self.current_token().token_type != TokenType::LeftParen {
+                    return Err(SqlError::ParseError {
+                        message: "WHEN NOT MATCHED THEN INSERT requires a column list".to_string(),
+                        position: Some(self.current_token().position),
+                    });
+                }
+                let columns = self.parse_column_list()?;
+                self.expect_keyword("VALUES")?;
+                let values = self.parse_value_row()?;
+                if columns.len() != values.len() {
+                    return Err(SqlError::ParseError {
+                        message: format!(
+                            "INSERT lists {} columns but {} values",
+                            columns.len(),
+                            values.len()
+                        ),
+                        position: None,
+                    });
+                }
+                MergeClause::NotMatched {
+                    condition,
+                    columns,
+                    values,
+                }
+            } else if self.consume_if_matches("UPDATE") {
+                self.expect_keyword("SET")?;
+                let mut assignments = Vec::new();
+                loop {
+                    let column =
+                        self.parse_merge_target_column(&target, target_alias.as_deref())?;
+                    self.expect(TokenType::Equal)?;
+                    assignments.push((column, self.parse_expression()?));
+                    if self.current_token().token_type != TokenType::Comma {
+                        break;
+                    }
+                    self.advance();
+                }
+                MergeClause::Matched {
+                    condition,
+                    action: MergeMatchedAction::Update { assignments },
+                }
+            } else if self.consume_if_matches("DELETE") {
+                MergeClause::Matched {
+                    condition,
+                    action: MergeMatchedAction::Delete,
+                }
+            } else {
+                return Err(SqlError::ParseError {
+                    message: format!(
+                        "Expected UPDATE or DELETE after WHEN MATCHED THEN, found '{}'",
+                        self.current_token().value
+                    ),
+                    position: Some(self.current_token().position),
+                });
+            };
+            clauses.push(clause);
+        }
+
+        if clauses.is_empty() {
+            return Err(SqlError::ParseError {
+                message: "MERGE requires at least one WHEN MATCHED or WHEN NOT MATCHED clause"
+                    .to_string(),
+                position: Some(self.current_token().position),
+            });
+        }
+
+        let properties = if self.current_token().token_type == TokenType::With {
+            Some(self.parse_with_properties()?)
+        } else {
+            None
+        };
+
+        self.consume_semicolon();
+        self.expect(TokenType::Eof)?;
+        Ok(StreamingQuery::Merge {
+            target,
+            target_alias,
+            source,
+            source_alias,
+            on,
+            clauses,
+            properties,
+            span: NodeSpan::default(),
+        })
+    }
+
+    /// Parse an optional `[AS] alias` that is not the keyword which follows it
+    fn parse_merge_alias(&mut self, next_keyword: &str) -> Result<Option<String>, SqlError> {
+        if self.current_token().token_type == TokenType::As {
+            self.advance();
+            return Ok(Some(self.expect(TokenType::Identifier)?.value));
+        }
+        let token = self.current_token();
+        if token.token_type == TokenType::Identifier
+            && !token.value.eq_ignore_ascii_case(next_keyword)
+        {
+            let alias = token.value.clone();
+            self.advance();
+            return Ok(Some(alias));
+        }
+        Ok(None)
+    }
+
+    /// Parse a SET column of `WHEN MATCHED THEN UPDATE`, dropping a target qualifier
+    fn parse_merge_target_column(
+        &mut self,
+        target: &str,
+        target_alias: Option<&str>,
+    ) -> Result<String, SqlError> {
+        let token = self.expect(TokenType::Identifier)?;
+        if self.current_token().token_type != TokenType::Dot {
+            return Ok(token.value);
+        }
+        if token.value != target && Some(token.value.as_str()) != target_alias {
+            return Err(SqlError::ParseError {
+                message: format!(
+                    "UPDATE SET column must belong to the MERGE target, found qualifier '{}'",
+                    token.value
+                ),
+                position: Some(token.position),
+            });
+        }
+        self.advance();
+        self.parse_field_name()
+    }
+
+    fn parse_upsert(&mut self) -> Result<StreamingQuery, SqlError> {
+        self.expect(TokenType::Upsert)?;
+        self.expect(TokenType::Into)?;
+        let table_name = self.expect(TokenType::Identifier)?.value;
+
+        let columns = if self.current_token().token_type == TokenType::LeftParen {
+            Some(self.parse_column_list()?)
+        } else {
+            None
+        };
+        let key_columns = if self.consume_if_matches("KEY") {
+            self.parse_column_list()?
+        } else if let Some(first) = columns.as_ref().and_then(|c| c.first()) {
+            // The first listed column is the key unless KEY (...) says otherwise
+            vec![first.clone()]
+        } else {
+            return Err(SqlError::ParseError {
+                message: "UPSERT INTO requires a column list or KEY (...) clause".to_string(),
+                position: Some(self.current_token().position),
+            });
+        };
+        if let Some(missing) = columns
+            .as_ref()
+            .and_then(|c| key_columns.iter().find(|k| !c.contains(k)))
+        {
+            return Err(SqlError::ParseError {
+                message: format!("UPSERT key column '{}' is not in the column list", missing),
+                position: None,
+            });
+        }
+
+        let source = if self.consume_if_matches("VALUES") {
+            let row = self.parse_value_row()?;
+            if self.current_token().token_type == TokenType::Comma {
+                return Err(SqlError::ParseError {
+                    message: "UPSERT INTO ... VALUES takes a single row".to_string(),
+                    position: Some(self.current_token().position),
+                });
+            }
+            self.consume_semicolon();
+            self.expect(TokenType::Eof)?;
+            InsertSource::Values { rows: vec![row] }
+        } else if self.current_token().token_type == TokenType::Select {
+            InsertSource::Select {
+                query: Box::new(self.parse_select()?),
+            }
+        } else {
+            return Err(SqlError::ParseError {
+                message: format!(
+                    "Expected VALUES or SELECT after UPSERT INTO {}, found '{}'",
+                    table_name,
+                    self.current_token().value
+                ),
+                position: Some(self.current_token().position),
+            });
+        };
+
+        Ok(StreamingQuery::Upsert {
+            table_name,
+            columns,
+            key_columns,
+            source,
+            span: NodeSpan::default(),
         })
     }
 
+    /// Parse a parenthesized `(col, ...)` list of column names
+    fn parse_column_list(&mut self) -> Result<Vec<String>, SqlError> {
+        self.expect(TokenType::LeftParen)?;
+        let mut columns = vec![self.expect(TokenType::Identifier)?.value];
+        while self.current_token().token_type == TokenType::Comma {
+            self.advance();
+            columns.push(self.expect(TokenType::Identifier)?.value);
+        }
+        self.expect(TokenType::RightParen)?;
+        Ok(columns)
+    }
+
+    /// Parse a parenthesized `(expr, ...)` row of values
+    fn parse_value_row(&mut self) -> Result<Vec<Expr>, SqlError> {
+        self.expect(TokenType::LeftParen)?;
+        let mut values = vec![self.parse_expression()?];
+        while self.current_token().token_type == TokenType::Comma {
+            self.advance();
+            values.push(self.parse_expression()?);
+        }
+        self.expect(TokenType::RightParen)?;
+        Ok(values)
+    }
+
     fn parse_describe(&mut self) -> Result<StreamingQuery, SqlError> {
         // Consume DESCRIBE token
         self.advance();
@@ -3139,31 +4445,36 @@ impl<'a> TokenParser<'a> {
         Ok(StreamingQuery::Show {
             resource_type: ShowResourceType::Describe { name },
             pattern: None,
+            span: NodeSpan::default(),
         })
     }
 
     fn parse_tokens_inner(&mut self) -> Result<StreamingQuery, SqlError> {
         // This is similar to the main parse_tokens but works on the current parser state
-        match self.current_token().token_type {
+        let start = self.current;
+        let query = match self.current_token().token_type {
             TokenType::Select => self.parse_select(),
             TokenType::Create => self.parse_create(),
             _ => Err(SqlError::ParseError {
                 message: "Expected SELECT or CREATE statement in START QUERY".to_string(),
                 position: Some(self.current_token().position),
             }),
-        }
+        }?;
+        Ok(query.with_span(self.span_from(start)))
     }
 
     fn parse_tokens_inner_no_with(&mut self) -> Result<StreamingQuery, SqlError> {
         // Similar to parse_tokens_inner but doesn't consume WITH clauses (for job contexts)
-        match self.current_token().token_type {
+        let start = self.current;
+        let query = match self.current_token().token_type {
             TokenType::Select => self.parse_select_no_with(),
             TokenType::Create => self.parse_create(),
             _ => Err(SqlError::ParseError {
                 message: "Expected SELECT or CREATE statement in job definition".to_string(),
                 position: Some(self.current_token().position),
             }),
-        }
+        }?;
+        Ok(query.with_span(self.span_from(start)))
     }
 
     /// Parse OVER clause for window functions
@@ -3199,7 +4510,7 @@ impl<'a> TokenParser<'a> {
 
             loop {
                 let column_name = self.expect(TokenType::Identifier)?.value;
-                let expr = Expr::Column(column_name);
+                let expr = Expr::Column(column_name, NodeSpan::default());
                 let direction = if self.current_token().token_type == TokenType::Asc {
                     self.advance();
                     OrderDirection::Asc
@@ -215,6 +215,9 @@ impl QueryAnalyzer {
                 // Analyze the INTO clause for sink requirements
                 self.analyze_into_clause(into_clause, &legacy_props, &mut analysis)?;
             }
+            StreamingQuery::Alter { .. } | StreamingQuery::Drop { .. } => {
+                // ALTER/DROP change the catalog and don't require consumers/producers
+            }
             StreamingQuery::Show { .. } => {
                 // SHOW queries don't require consumers/producers
             }
@@ -271,6 +274,46 @@ impl QueryAnalyzer {
                     }
                 }
             }
+            StreamingQuery::Merge {
+                target,
+                source,
+                properties,
+                ..
+            } => {
+                if let Some(props) = properties {
+                    for (key, value) in props {
+                        analysis.configuration.insert(key.clone(), value.clone());
+                    }
+                }
+                // The USING source is consumed and the target is written as a sink
+                self.analyze_from_clause(source, &mut analysis)?;
+                let config_clone = analysis.configuration.clone();
+                self.analyze_into_clause(
+                    &IntoClause {
+                        sink_name: target.clone(),
+                        sink_properties: HashMap::new(),
+                    },
+                    &config_clone,
+                    &mut analysis,
+                )?;
+            }
+            StreamingQuery::Upsert {
+                table_name, source, ..
+            } => {
+                if let InsertSource::Select { query } = source {
+                    let nested_analysis = self.analyze_with_context(query, &analysis)?;
+                    self.merge_analysis(&mut analysis, nested_analysis);
+                }
+                let config_clone = analysis.configuration.clone();
+                self.analyze_into_clause(
+                    &IntoClause {
+                        sink_name: table_name.clone(),
+                        sink_properties: HashMap::new(),
+                    },
+                    &config_clone,
+                    &mut analysis,
+                )?;
+            }
             StreamingQuery::Update { .. } => {
                 // UPDATE queries may require producers, but for now skip analysis
             }
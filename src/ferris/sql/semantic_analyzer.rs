@@ -0,0 +1,1494 @@
+//! Semantic Analysis with Schema-Aware Type Checking
+//!
+//! This module binds parsed SQL queries against the schemas of their sources before a job
+//! starts, so that misspelled columns and type mismatches are reported up front instead of
+//! record by record at runtime.
+//!
+//! Source schemas come from a [`SchemaCatalog`], which is filled from:
+//! - DDL: column definitions of `CREATE STREAM` / `CREATE TABLE` statements, and the output
+//!   columns of earlier statements in the same application
+//! - the schema registry, including `file://` sources described by `FileSchemaProvider`
+//! - schemas registered directly with [`SchemaCatalog::register_schema`]
+//!
+//! Sources without a known schema are treated as open: their columns cannot be checked,
+//! but expressions over known columns and literals are still type checked.
+//!
+//! ## Checks
+//!
+//! - every column reference resolves to a source column, a nested STRUCT field, a SELECT
+//!   alias (in HAVING and ORDER BY), a lambda parameter or a system column
+//! - comparisons, BETWEEN and IN do not mix numbers, strings and booleans
+//! - arithmetic operands are numeric (or temporal plus an interval)
+//! - WHERE, HAVING, JOIN ON and CASE WHEN conditions are boolean
+//! - every function call names a built-in function
+//! - numeric functions (SUM, AVG, ABS, ...) and string functions (UPPER, TRIM, ...) get
+//!   arguments of the right kind
+//!
+//! Misspelled columns and functions carry the closest known name as a suggestion, which
+//! [`SemanticError::to_diagnostic`] turns into a "did you mean" note.
+//!
+//! Expression and function return types are inferred along the way; the output columns of
+//! a query are returned in [`SemanticAnalysis::output_columns`].
+//!
+//! Query parameters (`$1`, `:name`) have the type declared with
+//! [`SemanticAnalyzer::with_parameter_types`], or else the type of what they are compared or
+//! combined with; both are returned in [`SemanticAnalysis::parameter_types`].
+
+use crate::ferris::schema::registry::SchemaRegistry;
+use crate::ferris::schema::{FieldDefinition, Schema};
+use crate::ferris::sql::ast::{
+    AlterOperation, BinaryOperator, ColumnDef, DataType, DecimalSpec, Expr, InsertSource,
+    LiteralValue, MergeClause, MergeMatchedAction, QueryParameter, SelectField, StreamSource,
+    StreamingQuery, SubqueryType, UnaryOperator,
+};
+use crate::ferris::sql::error::diagnostics::{closest_match, Diagnostic, Span};
+use crate::ferris::sql::error::BUILTIN_FUNCTIONS;
+use crate::ferris::sql::execution::expression::functions::BuiltinFunctions;
+use crate::ferris::sql::SqlError;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// Schemas of the streams, tables and URIs a query can read from, by name
+#[derive(Debug, Clone, Default)]
+pub struct SchemaCatalog {
+    schemas: HashMap<String, Schema>,
+}
+
+impl SchemaCatalog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register (or replace) the schema of a stream, table or source URI
+    pub fn register_schema(&mut self, name: impl Into<String>, schema: Schema) {
+        self.schemas.insert(name.into(), schema);
+    }
+
+    /// Register a schema from DDL column definitions
+    pub fn register_columns(&mut self, name: impl Into<String>, columns: &[ColumnDef]) {
+        self.register_schema(name, Self::schema_from_columns(columns));
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Schema> {
+        self.schemas.get(name)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.schemas.contains_key(name)
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Schema> {
+        self.schemas.remove(name)
+    }
+
+    /// Apply the catalog effects of an analyzed statement
+    ///
+    /// CREATE STREAM / CREATE TABLE register their declared columns, or otherwise the
+    /// inferred output columns when every column type is known. ALTER ... ADD COLUMN extends
+    /// a known schema and DROP removes it.
+    pub fn apply_statement(&mut self, query: &StreamingQuery, analysis: &SemanticAnalysis) {
+        match query {
+            StreamingQuery::CreateStream { name, columns, .. }
+            | StreamingQuery::CreateStreamInto { name, columns, .. }
+            | StreamingQuery::CreateTable { name, columns, .. }
+            | StreamingQuery::CreateTableInto { name, columns, .. } => {
+                if let Some(columns) = columns {
+                    self.register_columns(name.clone(), columns);
+                } else if let Some(output) = &analysis.output_columns {
+                    let fields: Option<Vec<FieldDefinition>> = output
+                        .iter()
+                        .map(|column| {
+                            column.data_type.clone().map(|data_type| {
+                                FieldDefinition::optional(column.name.clone(), data_type)
+                            })
+                        })
+                        .collect();
+                    if let Some(fields) = fields {
+                        self.register_schema(name.clone(), Schema::new(fields));
+                    }
+                }
+            }
+            StreamingQuery::Alter {
+                name,
+                operation: AlterOperation::AddColumn { column, .. },
+                ..
+            } => {
+                if let Some(schema) = self.schemas.get_mut(name) {
+                    if !schema.has_field(&column.name) {
+                        schema.fields.push(FieldDefinition::new(
+                            column.name.clone(),
+                            column.data_type.clone(),
+                            column.nullable,
+                        ));
+                    }
+                }
+            }
+            StreamingQuery::Drop { name, .. } => {
+                self.schemas.remove(name);
+            }
+            _ => {}
+        }
+    }
+
+    /// Discover the schemas of the URI sources of a query through the schema registry
+    ///
+    /// Sources the registry cannot describe stay unknown; they are not an error.
+    pub async fn discover_sources(&mut self, query: &StreamingQuery, registry: &SchemaRegistry) {
+        let mut uris = Vec::new();
+        collect_source_uris(query, &mut uris);
+        for uri in uris {
+            if self.contains(&uri) {
+                continue;
+            }
+            match registry.discover(&uri).await {
+                Ok(schema) => self.register_schema(uri, schema),
+                Err(e) => log::debug!("No schema discovered for '{}': {}", uri, e),
+            }
+        }
+    }
+
+    fn schema_from_columns(columns: &[ColumnDef]) -> Schema {
+        Schema::new(
+            columns
+                .iter()
+                .map(|c| FieldDefinition::new(c.name.clone(), c.data_type.clone(), c.nullable))
+                .collect(),
+        )
+    }
+}
+
+fn collect_source_uris(query: &StreamingQuery, uris: &mut Vec<String>) {
+    let from_source = |source: &StreamSource, uris: &mut Vec<String>| match source {
+        StreamSource::Uri(uri) => uris.push(uri.clone()),
+        StreamSource::Subquery(query) => collect_source_uris(query, uris),
+        StreamSource::Stream(_) | StreamSource::Table(_) => {}
+    };
+    match query {
+        StreamingQuery::Select { from, joins, .. } => {
+            from_source(from, uris);
+            for join in joins.iter().flatten() {
+                from_source(&join.right_source, uris);
+            }
+        }
+        StreamingQuery::Merge { source, .. } => from_source(source, uris),
+        StreamingQuery::CreateStream { as_select, .. }
+        | StreamingQuery::CreateStreamInto { as_select, .. }
+        | StreamingQuery::CreateTable { as_select, .. }
+        | StreamingQuery::CreateTableInto { as_select, .. } => collect_source_uris(as_select, uris),
+        StreamingQuery::StartJob { query, .. } | StreamingQuery::DeployJob { query, .. } => {
+            collect_source_uris(query, uris)
+        }
+        StreamingQuery::InsertInto {
+            source: InsertSource::Select { query },
+            ..
+        }
+        | StreamingQuery::Upsert {
+            source: InsertSource::Select { query },
+            ..
+        } => collect_source_uris(query, uris),
+        StreamingQuery::Union { left, right, .. } => {
+            collect_source_uris(left, uris);
+            collect_source_uris(right, uris);
+        }
+        _ => {}
+    }
+}
+
+/// Kind of problem found by semantic analysis
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SemanticErrorKind {
+    /// A column reference that no source, alias or system column provides
+    UnknownColumn,
+    /// Operands or conditions of incompatible types
+    TypeMismatch,
+    /// A function argument of the wrong kind
+    InvalidArgument,
+    /// A call to a function that is not built in
+    UnknownFunction,
+}
+
+/// A problem found by semantic analysis
+#[derive(Debug, Clone, PartialEq)]
+pub struct SemanticError {
+    pub kind: SemanticErrorKind,
+    pub message: String,
+    /// Column the error is about, if any
+    pub column: Option<String>,
+    /// The SQL text the error points at, when the text was given to the analyzer
+    pub span: Option<Span>,
+    /// Known name closest to a misspelled column or function
+    pub suggestion: Option<String>,
+}
+
+impl SemanticError {
+    /// The error as a source diagnostic, spanning the text it points at in the analyzed
+    /// `sql` text
+    pub fn to_diagnostic(&self, sql: &str) -> Diagnostic {
+        let mut diagnostic = Diagnostic::error(self.message.clone());
+        if let Some(span) = self.span.filter(|span| span.end <= sql.len()) {
+            diagnostic = diagnostic.with_span(span);
+        }
+        match &self.suggestion {
+            Some(suggestion) => diagnostic.with_suggestion(suggestion),
+            None => diagnostic,
+        }
+    }
+}
+
+impl std::fmt::Display for SemanticError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.span {
+            Some(span) => write!(f, "{} at position {}", self.message, span.start),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl From<SemanticError> for SqlError {
+    fn from(error: SemanticError) -> Self {
+        SqlError::SchemaError {
+            message: error.to_string(),
+            column: error.column,
+        }
+    }
+}
+
+/// An output column of an analyzed query with its inferred type (`None` when unknown)
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputColumn {
+    pub name: String,
+    pub data_type: Option<DataType>,
+}
+
+/// Result of semantic analysis
+#[derive(Debug, Clone, Default)]
+pub struct SemanticAnalysis {
+    /// Output columns of the query; `None` when they cannot be listed (e.g. `SELECT *` over
+    /// a source without a known schema) or the statement produces no rows
+    pub output_columns: Option<Vec<OutputColumn>>,
+    /// All problems found, in query order
+    pub errors: Vec<SemanticError>,
+    /// Types of the query parameters whose type is declared or could be inferred
+    pub parameter_types: HashMap<QueryParameter, DataType>,
+}
+
+impl SemanticAnalysis {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Turn the analysis into an error listing every problem, if there are any
+    pub fn into_result(self) -> Result<Self, SqlError> {
+        match self.errors.len() {
+            0 => Ok(self),
+            1 => Err(self.errors.into_iter().next().unwrap().into()),
+            n => Err(SqlError::SchemaError {
+                message: format!(
+                    "{} semantic errors: {}",
+                    n,
+                    self.errors
+                        .iter()
+                        .map(|e| e.to_string())
+                        .collect::<Vec<_>>()
+                        .join("; ")
+                ),
+                column: None,
+            }),
+        }
+    }
+}
+
+/// Broad type classes that decide which operands can be mixed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TypeClass {
+    Numeric,
+    String,
+    Boolean,
+    Temporal,
+    Other,
+}
+
+fn type_class(data_type: &DataType) -> TypeClass {
+    match data_type {
+        DataType::SmallInt
+        | DataType::Integer
+        | DataType::BigInt
+        | DataType::Real
+        | DataType::Float
+        | DataType::Decimal(_) => TypeClass::Numeric,
+        DataType::String => TypeClass::String,
+        DataType::Boolean => TypeClass::Boolean,
+        DataType::Date | DataType::Time | DataType::Timestamp | DataType::TimestampTz => {
+            TypeClass::Temporal
+        }
+        _ => TypeClass::Other,
+    }
+}
+
+/// Whether values of the two classes can be compared; only mixes of numbers, strings and
+/// booleans are rejected, which the runtime can never compare
+fn comparable(left: TypeClass, right: TypeClass) -> bool {
+    let strict = |class| {
+        matches!(
+            class,
+            TypeClass::Numeric | TypeClass::String | TypeClass::Boolean
+        )
+    };
+    left == right || !strict(left) || !strict(right)
+}
+
+/// Result type of arithmetic over two numeric types
+fn widen_numeric(left: &DataType, right: &DataType) -> DataType {
+    let is_float = |t: &DataType| matches!(t, DataType::Float | DataType::Real);
+    let is_decimal = |t: &DataType| matches!(t, DataType::Decimal(_));
+    if is_float(left) || is_float(right) {
+        DataType::Float
+    } else if is_decimal(left) || is_decimal(right) {
+        DataType::Decimal(None)
+    } else {
+        DataType::BigInt
+    }
+}
+
+/// Data type named by a CAST target such as `'BIGINT'` or `'DECIMAL(10,2)'`
+pub(crate) fn cast_target_type(type_name: &str) -> Option<DataType> {
+    let type_name = type_name.trim().to_uppercase();
+    if let Ok(Some(spec)) = DecimalSpec::parse_type_name(&type_name) {
+        return Some(DataType::Decimal(Some(spec)));
+    }
+    Some(match type_name.as_str() {
+        "BIGINT" | "LONG" => DataType::BigInt,
+        "INTEGER" | "INT" => DataType::Integer,
+        "SMALLINT" => DataType::SmallInt,
+        "REAL" => DataType::Real,
+        "FLOAT" | "DOUBLE" => DataType::Float,
+        "STRING" | "VARCHAR" | "TEXT" => DataType::String,
+        "BOOLEAN" | "BOOL" => DataType::Boolean,
+        "DATE" => DataType::Date,
+        "TIME" => DataType::Time,
+        "TIMESTAMP" | "DATETIME" => DataType::Timestamp,
+        "TIMESTAMPTZ" | "TIMESTAMP WITH TIME ZONE" => DataType::TimestampTz,
+        "BYTES" | "VARBINARY" | "BINARY" => DataType::Bytes,
+        "UUID" => DataType::Uuid,
+        "DECIMAL" | "NUMERIC" => DataType::Decimal(None),
+        _ => return None,
+    })
+}
+
+/// Kind of value a function requires for its first argument
+fn required_argument_class(function_name: &str) -> Option<TypeClass> {
+    match function_name {
+        "SUM" | "AVG" | "ABS" | "SQRT" | "ROUND" | "CEIL" | "CEILING" | "FLOOR" | "POWER"
+        | "POW" | "MOD" | "STDDEV" | "STDDEV_SAMP" | "STDDEV_POP" | "VARIANCE" | "VAR_SAMP"
+        | "VAR_POP" | "MEDIAN" => Some(TypeClass::Numeric),
+        "UPPER" | "LOWER" | "TRIM" | "LTRIM" | "RTRIM" => Some(TypeClass::String),
+        _ => None,
+    }
+}
+
+/// Return type of a function given its argument types, `None` when unknown
+fn function_return_type(
+    function_name: &str,
+    args: &[Expr],
+    arg_types: &[Option<DataType>],
+) -> Option<DataType> {
+    let first = arg_types.first().cloned().flatten();
+    match function_name {
+        "COUNT"
+        | "COUNT_DISTINCT"
+        | "APPROX_COUNT_DISTINCT"
+        | "LENGTH"
+        | "LEN"
+        | "POSITION"
+        | "ARRAY_LENGTH"
+        | "DATEDIFF"
+        | "UNIX_TIMESTAMP"
+        | "EXTRACT" => Some(DataType::BigInt),
+        "SUM" => first.map(|t| match t {
+            DataType::SmallInt | DataType::Integer | DataType::BigInt => DataType::BigInt,
+            other => other,
+        }),
+        "AVG" | "STDDEV" | "STDDEV_SAMP" | "STDDEV_POP" | "VARIANCE" | "VAR_SAMP" | "VAR_POP"
+        | "MEDIAN" | "SQRT" | "POWER" | "POW" => Some(DataType::Float),
+        "MIN" | "MAX" | "FIRST_VALUE" | "LAST_VALUE" | "ABS" | "ROUND" | "MOD" | "NULLIF" => first,
+        "COALESCE" | "LEAST" | "GREATEST" => arg_types.iter().flatten().next().cloned(),
+        "LISTAGG" | "STRING_AGG" | "UPPER" | "LOWER" | "SUBSTRING" | "REPLACE" | "TRIM"
+        | "LTRIM" | "RTRIM" | "CONCAT" | "LEFT" | "RIGHT" | "REGEXP_EXTRACT" | "REGEXP_REPLACE"
+        | "MD5" | "SHA256" | "BASE64_ENCODE" | "URL_DECODE" | "DATE_FORMAT" | "JSON_VALUE"
+        | "HEADER" | "TO_JSON" => Some(DataType::String),
+        "REGEXP_LIKE" | "HAS_HEADER" | "ARRAY_CONTAINS" => Some(DataType::Boolean),
+        "NOW" | "CURRENT_TIMESTAMP" | "TO_TIMESTAMP" | "FROM_UNIXTIME" | "DATE_TRUNC"
+        | "TIME_BUCKET" | "TIMESTAMP" => Some(DataType::Timestamp),
+        "TO_DATE" => Some(DataType::Date),
+        "CONVERT_TZ" | "AT_TIME_ZONE" => Some(DataType::TimestampTz),
+        "CAST" => match args.get(1) {
+            Some(Expr::Literal(LiteralValue::String(type_name), _)) => cast_target_type(type_name),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// One source visible to column references
+#[derive(Debug, Clone)]
+struct ScopeSource {
+    /// Names the source can be qualified with (name and alias)
+    names: Vec<String>,
+    /// Column types, or `None` when the schema is unknown
+    columns: Option<HashMap<String, DataType>>,
+}
+
+impl ScopeSource {
+    fn from_schema(names: Vec<String>, schema: Option<&Schema>) -> Self {
+        Self {
+            names,
+            columns: schema.map(|schema| {
+                schema
+                    .fields
+                    .iter()
+                    .map(|f| (f.name.clone(), f.data_type.clone()))
+                    .collect()
+            }),
+        }
+    }
+
+    fn from_output(names: Vec<String>, output: Option<&Vec<OutputColumn>>) -> Self {
+        Self {
+            names,
+            columns: output.map(|columns| {
+                columns
+                    .iter()
+                    .filter_map(|c| c.data_type.clone().map(|t| (c.name.clone(), t)))
+                    .collect()
+            }),
+        }
+    }
+}
+
+/// Names visible to the expressions of one query block
+#[derive(Debug, Clone, Default)]
+struct Scope {
+    sources: Vec<ScopeSource>,
+    /// Sources of enclosing queries, for correlated subqueries
+    outer: Vec<ScopeSource>,
+    /// SELECT aliases, visible in HAVING and ORDER BY
+    aliases: HashMap<String, Option<DataType>>,
+    /// Parameters of enclosing lambdas
+    lambda_params: Vec<String>,
+}
+
+impl Scope {
+    fn nested(&self, sources: Vec<ScopeSource>) -> Self {
+        let mut outer = self.sources.clone();
+        outer.extend(self.outer.iter().cloned());
+        Scope {
+            sources,
+            outer,
+            aliases: HashMap::new(),
+            lambda_params: self.lambda_params.clone(),
+        }
+    }
+}
+
+/// Outcome of resolving a column reference
+enum Resolution {
+    Found(Option<DataType>),
+    NotFound,
+}
+
+/// Binder and type checker for parsed queries
+pub struct SemanticAnalyzer<'a> {
+    catalog: &'a SchemaCatalog,
+    sql: Option<&'a str>,
+    /// Declared parameter types
+    parameter_types: HashMap<QueryParameter, DataType>,
+    /// Types of undeclared parameters, inferred during one analysis
+    inferred_parameters: RefCell<HashMap<QueryParameter, DataType>>,
+}
+
+impl<'a> SemanticAnalyzer<'a> {
+    pub fn new(catalog: &'a SchemaCatalog) -> Self {
+        Self {
+            catalog,
+            sql: None,
+            parameter_types: HashMap::new(),
+            inferred_parameters: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Attach the SQL text the query was parsed from, so errors point into it
+    pub fn with_sql(mut self, sql: &'a str) -> Self {
+        self.sql = Some(sql);
+        self
+    }
+
+    /// Declare the types of query parameters, instead of inferring them from their use
+    pub fn with_parameter_types(mut self, types: HashMap<QueryParameter, DataType>) -> Self {
+        self.parameter_types = types;
+        self
+    }
+
+    /// Resolve and type check a query
+    pub fn analyze(&self, query: &StreamingQuery) -> SemanticAnalysis {
+        self.inferred_parameters.borrow_mut().clear();
+        let mut errors = Vec::new();
+        let output_columns = self.analyze_query(query, &Scope::default(), None, &mut errors);
+        let mut parameter_types = self.inferred_parameters.take();
+        parameter_types.extend(self.parameter_types.clone());
+        SemanticAnalysis {
+            output_columns,
+            errors,
+            parameter_types,
+        }
+    }
+
+    fn analyze_query(
+        &self,
+        query: &StreamingQuery,
+        outer: &Scope,
+        declared_source: Option<&[ColumnDef]>,
+        errors: &mut Vec<SemanticError>,
+    ) -> Option<Vec<OutputColumn>> {
+        match query {
+            StreamingQuery::Select { .. } => {
+                self.analyze_select(query, outer, declared_source, errors)
+            }
+            // Declared CREATE STREAM columns describe the records read from the source
+            StreamingQuery::CreateStream {
+                columns, as_select, ..
+            }
+            | StreamingQuery::CreateStreamInto {
+                columns, as_select, ..
+            } => self.analyze_query(as_select, outer, columns.as_deref(), errors),
+            StreamingQuery::CreateTable { as_select, .. }
+            | StreamingQuery::CreateTableInto { as_select, .. } => {
+                self.analyze_query(as_select, outer, None, errors)
+            }
+            StreamingQuery::StartJob { query, .. } | StreamingQuery::DeployJob { query, .. } => {
+                self.analyze_query(query, outer, None, errors)
+            }
+            StreamingQuery::Union { left, right, .. } => {
+                let output = self.analyze_query(left, outer, None, errors);
+                self.analyze_query(right, outer, None, errors);
+                output
+            }
+            StreamingQuery::InsertInto {
+                table_name,
+                columns,
+                source,
+                ..
+            }
+            | StreamingQuery::Upsert {
+                table_name,
+                columns,
+                source,
+                ..
+            } => {
+                let target = self.named_source(table_name, None);
+                if let Some(columns) = columns {
+                    let scope = outer.nested(vec![target]);
+                    for column in columns {
+                        self.check_column(column, None, &scope, errors);
+                    }
+                }
+                match source {
+                    InsertSource::Select { query } => {
+                        self.analyze_query(query, outer, None, errors);
+                    }
+                    InsertSource::Values { rows } => {
+                        // VALUES expressions are evaluated against the incoming record
+                        for expr in rows.iter().flatten() {
+                            self.infer(expr, outer, errors);
+                        }
+                    }
+                }
+                None
+            }
+            StreamingQuery::Update {
+                table_name,
+                assignments,
+                where_clause,
+                ..
+            } => {
+                let scope = outer.nested(vec![self.named_source(table_name, None)]);
+                for (column, expr) in assignments {
+                    self.check_column(column, None, &scope, errors);
+                    self.infer(expr, &scope, errors);
+                }
+                if let Some(condition) = where_clause {
+                    self.check_condition(condition, "WHERE", &scope, errors);
+                }
+                None
+            }
+            StreamingQuery::Delete {
+                table_name,
+                where_clause,
+                ..
+            } => {
+                let scope = outer.nested(vec![self.named_source(table_name, None)]);
+                if let Some(condition) = where_clause {
+                    self.check_condition(condition, "WHERE", &scope, errors);
+                }
+                None
+            }
+            StreamingQuery::Merge {
+                target,
+                target_alias,
+                source,
+                source_alias,
+                on,
+                clauses,
+                ..
+            } => {
+                let target_source = self.named_source(target, target_alias.as_deref());
+                let source_source =
+                    self.stream_source(source, source_alias.as_deref(), outer, None, errors);
+                let scope = outer.nested(vec![target_source.clone(), source_source]);
+                self.check_condition(on, "MERGE ON", &scope, errors);
+                let target_scope = outer.nested(vec![target_source]);
+                for clause in clauses {
+                    match clause {
+                        MergeClause::Matched { condition, action } => {
+                            if let Some(condition) = condition {
+                                self.check_condition(condition, "WHEN MATCHED", &scope, errors);
+                            }
+                            if let MergeMatchedAction::Update { assignments } = action {
+                                for (column, expr) in assignments {
+                                    self.check_column(column, None, &target_scope, errors);
+                                    self.infer(expr, &scope, errors);
+                                }
+                            }
+                        }
+                        MergeClause::NotMatched {
+                            condition,
+                            columns,
+                            values,
+                        } => {
+                            if let Some(condition) = condition {
+                                self.check_condition(condition, "WHEN NOT MATCHED", &scope, errors);
+                            }
+                            for column in columns {
+                                self.check_column(column, None, &target_scope, errors);
+                            }
+                            for expr in values {
+                                self.infer(expr, &scope, errors);
+                            }
+                        }
+                    }
+                }
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn analyze_select(
+        &self,
+        query: &StreamingQuery,
+        outer: &Scope,
+        declared_source: Option<&[ColumnDef]>,
+        errors: &mut Vec<SemanticError>,
+    ) -> Option<Vec<OutputColumn>> {
+        let StreamingQuery::Select {
+            fields,
+            from,
+            from_alias,
+            joins,
+            where_clause,
+            group_by,
+            having,
+            window: _,
+            order_by,
+            ..
+        } = query
+        else {
+            return None;
+        };
+
+        let mut sources =
+            vec![self.stream_source(from, from_alias.as_deref(), outer, declared_source, errors)];
+        for join in joins.iter().flatten() {
+            sources.push(self.stream_source(
+                &join.right_source,
+                join.right_alias.as_deref(),
+                outer,
+                None,
+                errors,
+            ));
+        }
+        let mut scope = outer.nested(sources);
+
+        for join in joins.iter().flatten() {
+            self.check_condition(&join.condition, "JOIN ON", &scope, errors);
+        }
+        if let Some(condition) = where_clause {
+            self.check_condition(condition, "WHERE", &scope, errors);
+        }
+        for expr in group_by.iter().flatten() {
+            self.infer(expr, &scope, errors);
+        }
+
+        let mut output = Some(Vec::new());
+        for field in fields {
+            match field {
+                SelectField::Wildcard => {
+                    let mut expanded = Vec::new();
+                    for source in &scope.sources {
+                        match &source.columns {
+                            Some(columns) => {
+                                let mut names: Vec<_> = columns.iter().collect();
+                                names.sort_by(|a, b| a.0.cmp(b.0));
+                                expanded.extend(names.into_iter().map(|(name, t)| OutputColumn {
+                                    name: name.clone(),
+                                    data_type: Some(t.clone()),
+                                }));
+                            }
+                            None => {
+                                output = None;
+                                break;
+                            }
+                        }
+                    }
+                    if let Some(output) = output.as_mut() {
+                        output.extend(expanded);
+                    }
+                }
+                SelectField::Column(name) => {
+                    let data_type = self.check_column(name, None, &scope, errors);
+                    let output_name = name.rsplit('.').next().unwrap_or(name).to_string();
+                    if let Some(output) = output.as_mut() {
+                        output.push(OutputColumn {
+                            name: output_name,
+                            data_type,
+                        });
+                    }
+                }
+                SelectField::AliasedColumn { column, alias } => {
+                    let data_type = self.check_column(column, None, &scope, errors);
+                    scope.aliases.insert(alias.clone(), data_type.clone());
+                    if let Some(output) = output.as_mut() {
+                        output.push(OutputColumn {
+                            name: alias.clone(),
+                            data_type,
+                        });
+                    }
+                }
+                SelectField::Expression { expr, alias } => {
+                    let data_type = self.infer(expr, &scope, errors);
+                    let name = match (alias, expr) {
+                        (Some(alias), _) => alias.clone(),
+                        (None, Expr::Column(name, _)) => {
+                            name.rsplit('.').next().unwrap_or(name).to_string()
+                        }
+                        (None, _) => format!("{:?}", expr),
+                    };
+                    if let Some(alias) = alias {
+                        scope.aliases.insert(alias.clone(), data_type.clone());
+                    }
+                    if let Some(output) = output.as_mut() {
+                        output.push(OutputColumn { name, data_type });
+                    }
+                }
+            }
+        }
+
+        if let Some(condition) = having {
+            self.check_condition(condition, "HAVING", &scope, errors);
+        }
+        for order in order_by.iter().flatten() {
+            self.infer(&order.expr, &scope, errors);
+        }
+
+        output
+    }
+
+    /// Scope entry for a FROM or JOIN source
+    fn stream_source(
+        &self,
+        source: &StreamSource,
+        alias: Option<&str>,
+        outer: &Scope,
+        declared: Option<&[ColumnDef]>,
+        errors: &mut Vec<SemanticError>,
+    ) -> ScopeSource {
+        match source {
+            StreamSource::Stream(name) | StreamSource::Table(name) | StreamSource::Uri(name) => {
+                match declared {
+                    Some(columns) => {
+                        let mut names = vec![name.clone()];
+                        names.extend(alias.map(str::to_string));
+                        ScopeSource::from_schema(
+                            names,
+                            Some(&SchemaCatalog::schema_from_columns(columns)),
+                        )
+                    }
+                    None => self.named_source(name, alias),
+                }
+            }
+            StreamSource::Subquery(query) => {
+                let output = self.analyze_query(query, outer, declared, errors);
+                ScopeSource::from_output(
+                    alias.map(str::to_string).into_iter().collect(),
+                    output.as_ref(),
+                )
+            }
+        }
+    }
+
+    fn named_source(&self, name: &str, alias: Option<&str>) -> ScopeSource {
+        let mut names = vec![name.to_string()];
+        names.extend(alias.map(str::to_string));
+        ScopeSource::from_schema(names, self.catalog.get(name))
+    }
+
+    /// Resolve a column name, reporting it when no source provides it
+    fn check_column(
+        &self,
+        name: &str,
+        located: Option<Span>,
+        scope: &Scope,
+        errors: &mut Vec<SemanticError>,
+    ) -> Option<DataType> {
+        match self.resolve_column(name, scope) {
+            Resolution::Found(data_type) => data_type,
+            Resolution::NotFound => {
+                let known: Vec<String> = scope
+                    .sources
+                    .iter()
+                    .filter_map(|s| s.columns.as_ref())
+                    .flat_map(|columns| columns.keys().cloned())
+                    .collect::<std::collections::BTreeSet<_>>()
+                    .into_iter()
+                    .collect();
+                errors.push(SemanticError {
+                    kind: SemanticErrorKind::UnknownColumn,
+                    message: format!(
+                        "Unknown column '{}' (available columns: {})",
+                        name,
+                        known.join(", ")
+                    ),
+                    column: Some(name.to_string()),
+                    span: self.locate_node(located, name),
+                    suggestion: closest_match(name, known.iter().map(String::as_str))
+                        .map(str::to_string),
+                });
+                None
+            }
+        }
+    }
+
+    fn resolve_column(&self, name: &str, scope: &Scope) -> Resolution {
+        let first_segment = name.split('.').next().unwrap_or(name);
+        if scope.lambda_params.iter().any(|p| p == first_segment) {
+            return Resolution::Found(None);
+        }
+        match name.to_lowercase().as_str() {
+            "_timestamp" | "_offset" => return Resolution::Found(Some(DataType::BigInt)),
+            "_partition" => return Resolution::Found(Some(DataType::Integer)),
+            _ => {}
+        }
+        if let Some(data_type) = scope.aliases.get(name) {
+            return Resolution::Found(data_type.clone());
+        }
+
+        for sources in [&scope.sources, &scope.outer] {
+            if let Some(found) = Self::resolve_in_sources(name, sources) {
+                return found;
+            }
+        }
+        let all_known = scope
+            .sources
+            .iter()
+            .chain(scope.outer.iter())
+            .all(|s| s.columns.is_some());
+        if all_known {
+            Resolution::NotFound
+        } else {
+            // A source without a known schema may provide the column
+            Resolution::Found(None)
+        }
+    }
+
+    fn resolve_in_sources(name: &str, sources: &[ScopeSource]) -> Option<Resolution> {
+        // Exact field name (also covers field names containing dots)
+        for source in sources {
+            if let Some(data_type) = source.columns.as_ref().and_then(|c| c.get(name)) {
+                return Some(Resolution::Found(Some(data_type.clone())));
+            }
+        }
+        let (qualifier, rest) = name.split_once('.')?;
+
+        // Qualified by a source name or alias
+        if let Some(source) = sources
+            .iter()
+            .find(|s| s.names.iter().any(|n| n == qualifier))
+        {
+            return Some(match &source.columns {
+                None => Resolution::Found(None),
+                Some(columns) => match Self::resolve_path(columns, rest) {
+                    Some(data_type) => Resolution::Found(data_type),
+                    None => Resolution::NotFound,
+                },
+            });
+        }
+
+        // Nested access into a STRUCT or MAP column
+        for source in sources {
+            if let Some(columns) = &source.columns {
+                if columns.contains_key(qualifier) {
+                    return Some(match Self::resolve_path(columns, name) {
+                        Some(data_type) => Resolution::Found(data_type),
+                        None => Resolution::NotFound,
+                    });
+                }
+            }
+        }
+
+        // FROM aliases are not kept in the AST, so `s.amount` falls back to `amount`
+        let column = name.rsplit('.').next().unwrap_or(name);
+        sources.iter().find_map(|source| {
+            source
+                .columns
+                .as_ref()
+                .and_then(|c| c.get(column))
+                .map(|data_type| Resolution::Found(Some(data_type.clone())))
+        })
+    }
+
+    /// Walk a dotted path through STRUCT fields; `Some(None)` when the type is not known
+    fn resolve_path(columns: &HashMap<String, DataType>, path: &str) -> Option<Option<DataType>> {
+        if let Some(data_type) = columns.get(path) {
+            return Some(Some(data_type.clone()));
+        }
+        let mut segments = path.split('.');
+        let mut current = columns.get(segments.next()?)?.clone();
+        for segment in segments {
+            current = match current {
+                DataType::Struct(fields) => fields
+                    .into_iter()
+                    .find(|f| f.name == segment)
+                    .map(|f| f.data_type)?,
+                DataType::Map(_, value) => *value,
+                _ => return None,
+            };
+        }
+        Some(Some(current))
+    }
+
+    /// Type check a condition that must be boolean
+    fn check_condition(
+        &self,
+        expr: &Expr,
+        clause: &str,
+        scope: &Scope,
+        errors: &mut Vec<SemanticError>,
+    ) {
+        let data_type = self.infer(expr, scope, errors);
+        if let Some(data_type) =
+            data_type.or_else(|| self.infer_parameter(expr, &DataType::Boolean))
+        {
+            if data_type != DataType::Boolean {
+                self.type_error(
+                    format!("{} condition must be BOOLEAN, found {}", clause, data_type),
+                    expr,
+                    errors,
+                );
+            }
+        }
+    }
+
+    /// Infer the type of an expression, reporting problems on the way
+    fn infer(
+        &self,
+        expr: &Expr,
+        scope: &Scope,
+        errors: &mut Vec<SemanticError>,
+    ) -> Option<DataType> {
+        match expr {
+            Expr::Column(name, span) => self.check_column(name, span.get(), scope, errors),
+            Expr::Parameter(parameter, _) => self
+                .parameter_types
+                .get(parameter)
+                .or(self.inferred_parameters.borrow().get(parameter))
+                .cloned(),
+            Expr::Literal(literal, _) => match literal {
+                LiteralValue::String(_) => Some(DataType::String),
+                LiteralValue::Integer(_) => Some(DataType::BigInt),
+                LiteralValue::Float(_) => Some(DataType::Float),
+                LiteralValue::Boolean(_) => Some(DataType::Boolean),
+                LiteralValue::Decimal(_) => Some(DataType::Decimal(None)),
+                LiteralValue::Null | LiteralValue::Interval { .. } => None,
+            },
+            Expr::BinaryOp {
+                left, op, right, ..
+            } => self.infer_binary(expr, left, op, right, scope, errors),
+            Expr::UnaryOp {
+                op, expr: operand, ..
+            } => {
+                let operand_type = self.infer(operand, scope, errors);
+                match op {
+                    UnaryOperator::Not => {
+                        let operand_type = operand_type
+                            .or_else(|| self.infer_parameter(operand, &DataType::Boolean));
+                        if let Some(t) = operand_type.filter(|t| *t != DataType::Boolean) {
+                            self.type_error(
+                                format!("NOT requires a BOOLEAN operand, found {}", t),
+                                expr,
+                                errors,
+                            );
+                        }
+                        Some(DataType::Boolean)
+                    }
+                    UnaryOperator::Minus | UnaryOperator::Plus => {
+                        if let Some(t) = operand_type
+                            .as_ref()
+                            .filter(|t| type_class(t) != TypeClass::Numeric)
+                        {
+                            self.type_error(
+                                format!("Unary sign requires a numeric operand, found {}", t),
+                                expr,
+                                errors,
+                            );
+                        }
+                        operand_type
+                    }
+                    UnaryOperator::IsNull | UnaryOperator::IsNotNull => Some(DataType::Boolean),
+                }
+            }
+            Expr::Function { name, args, span } => {
+                let function_name = name.to_uppercase();
+                if !BUILTIN_FUNCTIONS.contains(&function_name.as_str())
+                    && BuiltinFunctions::lookup_function(&function_name).is_none()
+                {
+                    let suggestion = closest_match(name, BUILTIN_FUNCTIONS.iter().copied());
+                    errors.push(SemanticError {
+                        kind: SemanticErrorKind::UnknownFunction,
+                        message: format!("Unknown function '{}'", name),
+                        column: None,
+                        span: self.locate_node(
+                            span.get()
+                                .map(|call| Span::new(call.start, call.start + name.len())),
+                            name,
+                        ),
+                        suggestion: suggestion.map(str::to_string),
+                    });
+                }
+                let arg_types: Vec<Option<DataType>> = args
+                    .iter()
+                    .map(|arg| match arg {
+                        // CAST(x AS type) carries the type name as a string literal
+                        Expr::Literal(LiteralValue::String(_), _) if function_name == "CAST" => {
+                            None
+                        }
+                        // COUNT(*) is parsed with a `*` column
+                        Expr::Column(c, _) if c == "*" => None,
+                        _ => self.infer(arg, scope, errors),
+                    })
+                    .collect();
+                if let (Some(required), Some(Some(actual))) =
+                    (required_argument_class(&function_name), arg_types.first())
+                {
+                    if type_class(actual) != required {
+                        errors.push(SemanticError {
+                            kind: SemanticErrorKind::InvalidArgument,
+                            message: format!(
+                                "{} requires a {} argument, found {}",
+                                function_name,
+                                match required {
+                                    TypeClass::Numeric => "numeric",
+                                    _ => "string",
+                                },
+                                actual
+                            ),
+                            column: Self::first_column(&args[0]),
+                            span: self.locate_expr(&args[0]).or_else(|| self.locate(name)),
+                            suggestion: None,
+                        });
+                    }
+                }
+                function_return_type(&function_name, args, &arg_types)
+            }
+            Expr::WindowFunction {
+                function_name,
+                args,
+                over_clause,
+                ..
+            } => {
+                for column in &over_clause.partition_by {
+                    self.check_column(column, None, scope, errors);
+                }
+                for order in &over_clause.order_by {
+                    self.infer(&order.expr, scope, errors);
+                }
+                let arg_types: Vec<Option<DataType>> = args
+                    .iter()
+                    .map(|arg| self.infer(arg, scope, errors))
+                    .collect();
+                match function_name.to_uppercase().as_str() {
+                    "ROW_NUMBER" | "RANK" | "DENSE_RANK" | "NTILE" => Some(DataType::BigInt),
+                    "PERCENT_RANK" | "CUME_DIST" => Some(DataType::Float),
+                    "LAG" | "LEAD" | "NTH_VALUE" => arg_types.first().cloned().flatten(),
+                    other => function_return_type(other, args, &arg_types),
+                }
+            }
+            Expr::Case {
+                when_clauses,
+                else_clause,
+                ..
+            } => {
+                let mut result_type: Option<DataType> = None;
+                let results = when_clauses
+                    .iter()
+                    .map(|(condition, result)| {
+                        self.check_condition(condition, "CASE WHEN", scope, errors);
+                        result
+                    })
+                    .chain(else_clause.as_deref());
+                for result in results.collect::<Vec<_>>() {
+                    let Some(t) = self.infer(result, scope, errors) else {
+                        continue;
+                    };
+                    result_type = Some(match result_type {
+                        None => t,
+                        Some(current)
+                            if type_class(&current) == TypeClass::Numeric
+                                && type_class(&t) == TypeClass::Numeric =>
+                        {
+                            widen_numeric(&current, &t)
+                        }
+                        Some(current) => {
+                            if !comparable(type_class(&current), type_class(&t)) {
+                                self.type_error(
+                                    format!("CASE branches mix {} and {}", current, t),
+                                    result,
+                                    errors,
+                                );
+                            }
+                            current
+                        }
+                    });
+                }
+                result_type
+            }
+            Expr::List(items, _) => {
+                for item in items {
+                    self.infer(item, scope, errors);
+                }
+                None
+            }
+            Expr::Subquery {
+                query,
+                subquery_type,
+                ..
+            } => {
+                let output = self.analyze_query(query, scope, None, errors);
+                match subquery_type {
+                    SubqueryType::Scalar | SubqueryType::Any | SubqueryType::All => output
+                        .and_then(|columns| columns.into_iter().next())
+                        .and_then(|column| column.data_type),
+                    _ => Some(DataType::Boolean),
+                }
+            }
+            Expr::Between {
+                expr: value,
+                low,
+                high,
+                ..
+            } => {
+                let mut value_type = self.infer(value, scope, errors);
+                for bound in [low, high] {
+                    let mut bound_type = self.infer(bound, scope, errors);
+                    if let Some(t) = &value_type {
+                        bound_type = bound_type.or_else(|| self.infer_parameter(bound, t));
+                    }
+                    if let Some(t) = &bound_type {
+                        value_type = value_type.or_else(|| self.infer_parameter(value, t));
+                    }
+                    self.check_comparable(
+                        value_type.as_ref(),
+                        bound_type.as_ref(),
+                        "BETWEEN",
+                        expr,
+                        errors,
+                    );
+                }
+                Some(DataType::Boolean)
+            }
+            Expr::Subscript {
+                expr: base, index, ..
+            } => {
+                self.infer(index, scope, errors);
+                match self.infer(base, scope, errors) {
+                    Some(DataType::Array(element)) => Some(*element),
+                    Some(DataType::Map(_, value)) => Some(*value),
+                    _ => None,
+                }
+            }
+            Expr::FieldAccess {
+                expr: base, field, ..
+            } => match self.infer(base, scope, errors) {
+                Some(DataType::Struct(fields)) => fields
+                    .into_iter()
+                    .find(|f| &f.name == field)
+                    .map(|f| f.data_type),
+                Some(DataType::Map(_, value)) => Some(*value),
+                _ => None,
+            },
+            Expr::Lambda { params, body, .. } => {
+                let mut lambda_scope = scope.clone();
+                lambda_scope.lambda_params.extend(params.iter().cloned());
+                self.infer(body, &lambda_scope, errors);
+                None
+            }
+        }
+    }
+
+    fn infer_binary(
+        &self,
+        expr: &Expr,
+        left: &Expr,
+        op: &BinaryOperator,
+        right: &Expr,
+        scope: &Scope,
+        errors: &mut Vec<SemanticError>,
+    ) -> Option<DataType> {
+        let mut left_type = self.infer(left, scope, errors);
+        let mut right_type = match (op, right) {
+            // IN lists are checked item by item below
+            (BinaryOperator::In | BinaryOperator::NotIn, Expr::List(items, _)) => {
+                for item in items {
+                    let mut item_type = self.infer(item, scope, errors);
+                    if let Some(t) = &left_type {
+                        item_type = item_type.or_else(|| self.infer_parameter(item, t));
+                    }
+                    self.check_comparable(
+                        left_type.as_ref(),
+                        item_type.as_ref(),
+                        "IN",
+                        item,
+                        errors,
+                    );
+                }
+                None
+            }
+            _ => self.infer(right, scope, errors),
+        };
+
+        // A parameter takes the type its operator requires, or else that of the other operand
+        let required = match op {
+            BinaryOperator::And | BinaryOperator::Or => Some(DataType::Boolean),
+            BinaryOperator::Like | BinaryOperator::NotLike | BinaryOperator::Concat => {
+                Some(DataType::String)
+            }
+            _ => None,
+        };
+        if let Some(t) = required.as_ref().or(right_type.as_ref()) {
+            left_type = left_type.or_else(|| self.infer_parameter(left, t));
+        }
+        if let Some(t) = required.as_ref().or(left_type.as_ref()) {
+            right_type = right_type.or_else(|| self.infer_parameter(right, t));
+        }
+
+        match op {
+            BinaryOperator::And | BinaryOperator::Or => {
+                for (operand, operand_type) in [(left, &left_type), (right, &right_type)] {
+                    if let Some(t) = operand_type.as_ref().filter(|t| **t != DataType::Boolean) {
+                        self.type_error(
+                            format!("{:?} requires BOOLEAN operands, found {}", op, t)
+                                .to_uppercase(),
+                            operand,
+                            errors,
+                        );
+                    }
+                }
+                Some(DataType::Boolean)
+            }
+            BinaryOperator::Equal
+            | BinaryOperator::NotEqual
+            | BinaryOperator::LessThan
+            | BinaryOperator::LessThanOrEqual
+            | BinaryOperator::GreaterThan
+            | BinaryOperator::GreaterThanOrEqual
+            | BinaryOperator::In
+            | BinaryOperator::NotIn => {
+                self.check_comparable(
+                    left_type.as_ref(),
+                    right_type.as_ref(),
+                    "compare",
+                    expr,
+                    errors,
+                );
+                Some(DataType::Boolean)
+            }
+            BinaryOperator::Like | BinaryOperator::NotLike => Some(DataType::Boolean),
+            BinaryOperator::Concat => Some(DataType::String),
+            BinaryOperator::Add
+            | BinaryOperator::Subtract
+            | BinaryOperator::Multiply
+            | BinaryOperator::Divide
+            | BinaryOperator::Modulo => {
+                let (Some(l), Some(r)) = (&left_type, &right_type) else {
+                    // Temporal +/- INTERVAL keeps the temporal type
+                    let interval =
+                        |e: &Expr| matches!(e, Expr::Literal(LiteralValue::Interval { .. }, _));
+                    if interval(right) || interval(left) {
+                        return left_type.or(right_type);
+                    }
+                    self.check_arithmetic_operand(left_type.as_ref(), op, left, errors);
+                    self.check_arithmetic_operand(right_type.as_ref(), op, right, errors);
+                    return None;
+                };
+                match (type_class(l), type_class(r)) {
+                    (TypeClass::Numeric, TypeClass::Numeric) => Some(match op {
+                        BinaryOperator::Divide if widen_numeric(l, r) == DataType::BigInt => {
+                            DataType::Float
+                        }
+                        _ => widen_numeric(l, r),
+                    }),
+                    (TypeClass::Temporal, _) | (_, TypeClass::Temporal) => None,
+                    _ => {
+                        self.type_error(
+                            format!(
+                                "Operator {} cannot be applied to {} and {}",
+                                Self::operator_symbol(op),
+                                l,
+                                r
+                            ),
+                            expr,
+                            errors,
+                        );
+                        None
+                    }
+                }
+            }
+        }
+    }
+
+    /// Give an untyped parameter the type of the expression it is used with
+    fn infer_parameter(&self, expr: &Expr, data_type: &DataType) -> Option<DataType> {
+        let Expr::Parameter(parameter, _) = expr else {
+            return None;
+        };
+        let mut inferred = self.inferred_parameters.borrow_mut();
+        Some(
+            inferred
+                .entry(parameter.clone())
+                .or_insert_with(|| data_type.clone())
+                .clone(),
+        )
+    }
+
+    fn check_arithmetic_operand(
+        &self,
+        operand_type: Option<&DataType>,
+        op: &BinaryOperator,
+        operand: &Expr,
+        errors: &mut Vec<SemanticError>,
+    ) {
+        if let Some(t) = operand_type {
+            if matches!(type_class(t), TypeClass::String | TypeClass::Boolean) {
+                self.type_error(
+                    format!(
+                        "Operator {} cannot be applied to {}",
+                        Self::operator_symbol(op),
+                        t
+                    ),
+                    operand,
+                    errors,
+                );
+            }
+        }
+    }
+
+    fn check_comparable(
+        &self,
+        left: Option<&DataType>,
+        right: Option<&DataType>,
+        what: &str,
+        expr: &Expr,
+        errors: &mut Vec<SemanticError>,
+    ) {
+        if let (Some(l), Some(r)) = (left, right) {
+            if !comparable(type_class(l), type_class(r)) {
+                self.type_error(format!("Cannot {} {} with {}", what, l, r), expr, errors);
+            }
+        }
+    }
+
+    fn operator_symbol(op: &BinaryOperator) -> &'static str {
+        match op {
+            BinaryOperator::Add => "+",
+            BinaryOperator::Subtract => "-",
+            BinaryOperator::Multiply => "*",
+            BinaryOperator::Divide => "/",
+            BinaryOperator::Modulo => "%",
+            _ => "?",
+        }
+    }
+
+    fn type_error(&self, message: String, expr: &Expr, errors: &mut Vec<SemanticError>) {
+        errors.push(SemanticError {
+            kind: SemanticErrorKind::TypeMismatch,
+            message,
+            column: Self::first_column(expr),
+            span: self.locate_expr(expr),
+            suggestion: None,
+        });
+    }
+
+    /// First column referenced by an expression, used to anchor error positions
+    fn first_column(expr: &Expr) -> Option<String> {
+        expr.get_columns().into_iter().next()
+    }
+
+    /// Where an error about an expression points: the text it was parsed from, or else the
+    /// first occurrence of what it refers to
+    fn locate_expr(&self, expr: &Expr) -> Option<Span> {
+        self.sql?;
+        expr.span().or_else(|| match expr {
+            Expr::Literal(LiteralValue::String(s), _) => self.locate(&format!("'{}'", s)),
+            Expr::Literal(LiteralValue::Integer(i), _) => self.locate(&i.to_string()),
+            _ => Self::first_column(expr).and_then(|c| self.locate(&c)),
+        })
+    }
+
+    /// Where an error about `name` points: the node it was parsed from, or else the first
+    /// occurrence of the name
+    fn locate_node(&self, node: Option<Span>, name: &str) -> Option<Span> {
+        self.sql?;
+        node.or_else(|| self.locate(name))
+    }
+
+    /// First whole-word occurrence of `needle` outside string literals
+    fn locate(&self, needle: &str) -> Option<Span> {
+        let sql = self.sql?;
+        let is_word = |c: char| c.is_alphanumeric() || c == '_';
+        let needle_is_literal = needle.starts_with('\'');
+        let mut in_string = false;
+        for (index, ch) in sql.char_indices() {
+            if !in_string || needle_is_literal {
+                let rest = &sql[index..];
+                if rest.len() >= needle.len()
+                    && rest.is_char_boundary(needle.len())
+                    && rest[..needle.len()].eq_ignore_ascii_case(needle)
+                {
+                    let before_ok = sql[..index]
+                        .chars()
+                        .next_back()
+                        .map_or(true, |c| !is_word(c));
+                    let after_ok = rest[needle.len()..]
+                        .chars()
+                        .next()
+                        .map_or(true, |c| !is_word(c));
+                    if needle_is_literal || (before_ok && after_ok) {
+                        return Some(Span::new(index, index + needle.len()));
+                    }
+                }
+            }
+            if ch == '\'' {
+                in_string = !in_string;
+            }
+        }
+        None
+    }
+}
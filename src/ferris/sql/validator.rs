@@ -9,6 +9,7 @@ use crate::ferris::sql::{
     query_analyzer::{
         DataSinkRequirement, DataSinkType, DataSourceRequirement, DataSourceType, QueryAnalyzer,
     },
+    semantic_analyzer::{SchemaCatalog, SemanticAnalyzer},
 };
 use std::collections::HashMap;
 use std::fs;
@@ -35,6 +36,7 @@ pub struct QueryValidationResult {
     pub start_line: usize,
     pub is_valid: bool,
     pub parsing_errors: Vec<ValidationError>,
+    pub semantic_errors: Vec<ValidationError>,
     pub configuration_errors: Vec<ValidationError>,
     pub warnings: Vec<ValidationError>,
     pub sources_found: Vec<DataSourceRequirement>,
@@ -78,6 +80,7 @@ pub struct SqlValidator {
     parser: StreamingSqlParser,
     analyzer: QueryAnalyzer,
     with_clause_parser: WithClauseParser,
+    schema_catalog: SchemaCatalog,
     strict_mode: bool,
     check_performance: bool,
 }
@@ -89,6 +92,7 @@ impl SqlValidator {
             parser: StreamingSqlParser::new(),
             analyzer: QueryAnalyzer::new("sql-validator".to_string()),
             with_clause_parser: WithClauseParser::new(),
+            schema_catalog: SchemaCatalog::new(),
             strict_mode: false,
             check_performance: true,
         }
@@ -100,11 +104,20 @@ impl SqlValidator {
             parser: StreamingSqlParser::new(),
             analyzer: QueryAnalyzer::new("sql-validator".to_string()),
             with_clause_parser: WithClauseParser::new(),
+            schema_catalog: SchemaCatalog::new(),
             strict_mode: true,
             check_performance: true,
         }
     }
 
+    /// Check column references and types against known source schemas
+    ///
+    /// Streams and tables declared by the validated content itself are added on top.
+    pub fn with_schema_catalog(mut self, schema_catalog: SchemaCatalog) -> Self {
+        self.schema_catalog = schema_catalog;
+        self
+    }
+
     /// Validate SQL content from string - main integration point for StreamJobServer
     pub fn validate_sql_content(&self, content: &str) -> ApplicationValidationResult {
         let mut result = ApplicationValidationResult {
@@ -131,8 +144,12 @@ impl SqlValidator {
         let queries = self.split_sql_statements(content);
         result.total_queries = queries.len();
 
+        // DDL earlier in the application describes the sources of later statements
+        let mut schema_catalog = self.schema_catalog.clone();
+
         for (i, (query, start_line)) in queries.iter().enumerate() {
-            let query_result = self.validate_query(query, i, *start_line, content);
+            let query_result =
+                self.validate_query(query, i, *start_line, content, &mut schema_catalog);
 
             if query_result.is_valid {
                 result.valid_queries += 1;
@@ -193,6 +210,7 @@ impl SqlValidator {
         query_index: usize,
         start_line: usize,
         _full_content: &str,
+        schema_catalog: &mut SchemaCatalog,
     ) -> QueryValidationResult {
         let mut result = QueryValidationResult {
             query_text: query.to_string(),
@@ -200,6 +218,7 @@ impl SqlValidator {
             start_line,
             is_valid: true,
             parsing_errors: Vec::new(),
+            semantic_errors: Vec::new(),
             configuration_errors: Vec::new(),
             warnings: Vec::new(),
             sources_found: Vec::new(),
@@ -208,22 +227,67 @@ impl SqlValidator {
             missing_sink_configs: Vec::new(),
         };
 
-        // Try to parse the SQL statement
-        let parsed_query = match self.parser.parse(query) {
+        // Try to parse the SQL statement; positions are taken in the trimmed statement,
+        // which begins on `start_line`
+        let statement = query.trim_start();
+        let parsed_query = match self.parser.parse(statement) {
             Ok(q) => q,
             Err(e) => {
-                let (line, column, context) = self.extract_error_location(&e, query, start_line);
-                result.parsing_errors.push(ValidationError {
-                    message: format!("Parsing error: {}\n{}", e, context),
-                    line: Some(line),
-                    column: Some(column),
-                    severity: ErrorSeverity::Error,
-                });
+                // Report every error the parser recovers from, not just the first
+                let diagnostics = self.parser.parse_with_diagnostics(statement).err();
+                match diagnostics.filter(|diagnostics| !diagnostics.is_empty()) {
+                    Some(diagnostics) => {
+                        for diagnostic in diagnostics {
+                            let (line, column) =
+                                diagnostic.line_column(statement).unwrap_or((1, 1));
+                            result.parsing_errors.push(ValidationError {
+                                message: format!(
+                                    "Parsing error: {}",
+                                    diagnostic.render(statement, "<statement>")
+                                ),
+                                line: Some(start_line + line - 1),
+                                column: Some(column - 1),
+                                severity: ErrorSeverity::Error,
+                            });
+                        }
+                    }
+                    None => {
+                        let (line, column, context) =
+                            self.extract_error_location(&e, statement, start_line);
+                        result.parsing_errors.push(ValidationError {
+                            message: format!("Parsing error: {}\n{}", e, context),
+                            line: Some(line),
+                            column: Some(column),
+                            severity: ErrorSeverity::Error,
+                        });
+                    }
+                }
                 result.is_valid = false;
                 return result;
             }
         };
 
+        // Resolve columns and check types against the known source schemas
+        let semantic_analysis = SemanticAnalyzer::new(schema_catalog)
+            .with_sql(statement)
+            .analyze(&parsed_query);
+        for error in &semantic_analysis.errors {
+            let position = error.span.map_or(0, |span| span.start);
+            let (line_offset, column) = self.position_to_line_column(statement, position);
+            let diagnostic = error.to_diagnostic(statement);
+            result.semantic_errors.push(ValidationError {
+                message: format!(
+                    "Semantic error: {}",
+                    diagnostic.render(statement, "<statement>")
+                ),
+                line: Some(start_line + line_offset),
+                column: Some(column),
+                severity: ErrorSeverity::Error,
+            });
+            result.is_valid = false;
+        }
+        schema_catalog.apply_statement(&parsed_query, &semantic_analysis);
+
         // Analyze the query for data sources and sinks
         match self.analyzer.analyze(&parsed_query) {
             Ok(analysis) => {
@@ -318,6 +382,9 @@ impl SqlValidator {
             || trimmed.starts_with("UPDATE")
             || trimmed.starts_with("DELETE")
             || trimmed.starts_with("DROP")
+            || trimmed.starts_with("ALTER")
+            || trimmed.starts_with("MERGE")
+            || trimmed.starts_with("UPSERT")
     }
 
     fn extract_application_name(&self, content: &str) -> Option<String> {
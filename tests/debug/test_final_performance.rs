@@ -29,7 +29,7 @@ fn create_test_record(field_count: usize) -> StreamRecord {
     }
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         timestamp: chrono::Utc::now().timestamp_millis(),
         offset: i64::from(field_count as u32),
         partition: (field_count % 16) as i32,
@@ -25,7 +25,7 @@ fn create_test_record(field_count: usize) -> StreamRecord {
     }
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         timestamp: chrono::Utc::now().timestamp_millis(),
         offset: 0,
         partition: 0,
@@ -15,7 +15,7 @@ fn create_test_record(id: i64, amount: f64) -> StreamRecord {
     fields.insert("amount".to_string(), FieldValue::Float(amount));
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         timestamp: chrono::Utc::now().timestamp_millis(),
         offset: id,
         partition: 0,
@@ -31,7 +31,7 @@ fn create_market_data_record(
     fields.insert("timestamp".to_string(), FieldValue::Integer(timestamp));
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         timestamp,
         offset: 0,
         partition: 0,
@@ -284,7 +284,7 @@ async fn test_null_handling() {
     fields.insert("ask_price".to_string(), FieldValue::Float(100.0));
 
     let record = StreamRecord {
-        fields,
+        fields: fields.into(),
         timestamp: chrono::Utc::now().timestamp_millis(),
         offset: 0,
         partition: 0,
@@ -149,6 +149,7 @@ async fn test_simple_processor_multi_job_interface() {
         max_retries: 3,
         retry_backoff: Duration::from_millis(500),
         progress_interval: 10,
+        columnar_execution: false,
         log_progress: true,
     };
     
@@ -196,6 +197,7 @@ async fn test_transactional_processor_multi_job_interface() {
         max_retries: 5,
         retry_backoff: Duration::from_millis(1000),
         progress_interval: 5,
+        columnar_execution: false,
         log_progress: true,
     };
     
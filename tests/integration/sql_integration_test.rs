@@ -54,7 +54,7 @@ mod tests {
         fields.insert("timestamp".to_string(), FieldValue::Integer(safe_timestamp));
 
         StreamRecord {
-            fields,
+            fields: fields.into(),
             timestamp: safe_timestamp,
             offset: order_id,
             partition: 0,
@@ -72,7 +72,7 @@ mod tests {
         }
 
         StreamRecord {
-            fields,
+            fields: fields.into(),
             timestamp: 1000 + user_id * 1000,
             offset: user_id,
             partition: 0,
@@ -332,7 +332,7 @@ pub mod test_data {
             );
 
             records.push(StreamRecord {
-                fields,
+                fields: fields.into(),
                 timestamp: 1672531200000 + i as i64,
                 offset: i as i64,
                 partition: (i % 4) as i32,
@@ -388,7 +388,7 @@ pub mod test_data {
             );
 
             records.push(StreamRecord {
-                fields,
+                fields: fields.into(),
                 timestamp: 1672531200000 + i as i64 * 1000,
                 offset: i as i64,
                 partition: (i % 4) as i32,
@@ -315,7 +315,7 @@ fn create_benchmark_record(index: usize) -> StreamRecord {
     );
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         timestamp: 1672531200000 + index as i64 * 1000,
         offset: index as i64,
         partition: (index % 4) as i32,
@@ -6,7 +6,11 @@ These are lightweight tests - heavy benchmarks run in CI examples.
 */
 
 use ferrisstreams::ferris::sql::ast::StreamingQuery;
+use ferrisstreams::ferris::sql::execution::{FieldValue, StreamExecutionEngine, StreamRecord};
 use ferrisstreams::ferris::sql::parser::StreamingSqlParser;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
 
 #[test]
 fn test_query_parsing_memory_efficiency() {
@@ -179,3 +183,139 @@ fn test_complex_query_construction_performance() {
         }
     }
 }
+
+fn benchmark_records(count: usize) -> Vec<StreamRecord> {
+    let statuses = ["pending", "shipped", "cancelled", "refunded"];
+    (0..count)
+        .map(|i| {
+            let mut fields = HashMap::new();
+            fields.insert(
+                "customer_id".to_string(),
+                FieldValue::Integer(i as i64 % 97),
+            );
+            fields.insert(
+                "amount".to_string(),
+                FieldValue::Float((i % 500) as f64 * 1.5),
+            );
+            fields.insert(
+                "status".to_string(),
+                FieldValue::String(statuses[i % statuses.len()].to_string()),
+            );
+            fields.insert(
+                "region".to_string(),
+                FieldValue::String(format!("region-{}", i % 8)),
+            );
+            StreamRecord {
+                fields: fields.into(),
+                timestamp: i as i64,
+                offset: i as i64,
+                partition: 0,
+                headers: HashMap::new(),
+            }
+        })
+        .collect()
+}
+
+/// Run a query over records through an engine, planned with `plan_query` or interpreted,
+/// returning the results and the time it took
+async fn run_engine(
+    query: &StreamingQuery,
+    records: &[StreamRecord],
+    planned: bool,
+) -> (Vec<StreamRecord>, Duration) {
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    let mut engine = StreamExecutionEngine::new(sender);
+    if planned {
+        engine.plan_query(query);
+    }
+
+    let start = Instant::now();
+    for record in records {
+        engine
+            .execute_with_record(query, record.clone())
+            .await
+            .unwrap();
+    }
+    let duration = start.elapsed();
+
+    let mut results = Vec::new();
+    while let Ok(result) = receiver.try_recv() {
+        results.push(result);
+    }
+    (results, duration)
+}
+
+#[tokio::test]
+async fn test_compiled_filter_performance() {
+    // Filter-heavy jobs spend most of their time evaluating the WHERE clause
+    let query = StreamingSqlParser::new()
+        .parse(
+            "SELECT * FROM orders WHERE amount > 100 AND amount < 600 \
+             AND status NOT IN ('cancelled', 'refunded') \
+             AND UPPER(status) = 'SHIPPED' AND customer_id BETWEEN 10 AND 90",
+        )
+        .unwrap();
+    let records = benchmark_records(20_000);
+    let expected = (0..records.len())
+        .filter(|i| {
+            let amount = (i % 500) as f64 * 1.5;
+            let customer_id = i % 97;
+            amount > 100.0 && amount < 600.0 && i % 4 == 1 && (10..=90).contains(&customer_id)
+        })
+        .count();
+
+    let (interpreted_results, interpreted) = run_engine(&query, &records, false).await;
+    let (compiled_results, compiled_duration) = run_engine(&query, &records, true).await;
+
+    assert_eq!(compiled_results.len(), expected);
+    assert!(expected > 0);
+    for (compiled, interpreted) in compiled_results.iter().zip(&interpreted_results) {
+        assert_eq!(compiled.fields, interpreted.fields);
+        assert_eq!(compiled.offset, interpreted.offset);
+    }
+    assert_eq!(interpreted_results.len(), expected);
+    println!(
+        "WHERE over {} records: interpreted {:?}, compiled {:?} ({:.1}x)",
+        records.len(),
+        interpreted,
+        compiled_duration,
+        interpreted.as_secs_f64() / compiled_duration.as_secs_f64().max(f64::EPSILON)
+    );
+}
+
+#[tokio::test]
+async fn test_compiled_projection_performance() {
+    let query = StreamingSqlParser::new()
+        .parse(
+            "SELECT amount * 1.2 + 5 AS gross, \
+             CASE WHEN amount > 300 THEN 'large' WHEN amount > 100 THEN 'medium' ELSE 'small' END AS size, \
+             COALESCE(region, 'unknown') || ':' || status AS label FROM orders",
+        )
+        .unwrap();
+    let records = benchmark_records(20_000);
+
+    let (interpreted_results, interpreted) = run_engine(&query, &records, false).await;
+    let (compiled_results, compiled_duration) = run_engine(&query, &records, true).await;
+
+    assert_eq!(compiled_results.len(), records.len());
+    assert_eq!(interpreted_results.len(), records.len());
+    for (compiled, interpreted) in compiled_results.iter().zip(&interpreted_results) {
+        assert_eq!(compiled.fields, interpreted.fields);
+    }
+    let last = &compiled_results[records.len() - 1];
+    assert_eq!(
+        last.fields.get("size"),
+        Some(&FieldValue::String("large".to_string()))
+    );
+    assert_eq!(
+        last.fields.get("label"),
+        Some(&FieldValue::String("region-7:refunded".to_string()))
+    );
+    println!(
+        "SELECT expressions over {} records: interpreted {:?}, compiled {:?} ({:.1}x)",
+        records.len(),
+        interpreted,
+        compiled_duration,
+        interpreted.as_secs_f64() / compiled_duration.as_secs_f64().max(f64::EPSILON)
+    );
+}
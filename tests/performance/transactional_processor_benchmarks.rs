@@ -3,6 +3,7 @@
 //! Comprehensive benchmarks comparing SimpleJobProcessor vs TransactionalJobProcessor
 //! to validate transaction overhead and exactly-once semantics performance impact
 
+use ferrisstreams::ferris::sql::ast::NodeSpan;
 use ferrisstreams::ferris::{
     datasource::{DataReader, DataWriter},
     server::processors::{common::*, simple::*, transactional::*},
@@ -243,7 +244,7 @@ fn create_test_batches(record_count: usize, batch_size: usize) -> Vec<Vec<Stream
             );
 
             batch.push(StreamRecord {
-                fields,
+                fields: fields.into(),
                 timestamp: 1672531200000 + i as i64 * 1000,
                 offset: i as i64,
                 partition: (i % 4) as i32,
@@ -264,6 +265,7 @@ fn create_benchmark_query() -> StreamingQuery {
             SelectField::Column("volume".to_string()),
         ],
         from: StreamSource::Stream("benchmark_data".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         group_by: None,
@@ -273,6 +275,7 @@ fn create_benchmark_query() -> StreamingQuery {
         limit: None,
         emit_mode: Some(EmitMode::Changes),
         properties: None,
+        span: NodeSpan::default(),
     }
 }
 
@@ -2,6 +2,7 @@
 
 use ferrisstreams::ferris::datasource::file::{FileDataSource, FileSink};
 use ferrisstreams::ferris::datasource::kafka::{KafkaDataSink, KafkaDataSource};
+use ferrisstreams::ferris::datasource::DataSink;
 use std::collections::HashMap;
 
 #[cfg(test)]
@@ -148,6 +149,28 @@ mod properties_prefix_tests {
         assert_eq!(kafka_sink.topic(), "test_job_output");
     }
 
+    #[test]
+    fn test_kafka_data_sink_upsert_requires_compacted_topic() {
+        let mut props = HashMap::new();
+        props.insert("sink.topic".to_string(), "orders".to_string());
+
+        let kafka_sink = KafkaDataSink::from_properties(&props, "test_job");
+        assert!(!kafka_sink.supports_upsert());
+        assert!(!kafka_sink.metadata().supports_upsert);
+
+        props.insert("sink.cleanup.policy".to_string(), "delete".to_string());
+        let kafka_sink = KafkaDataSink::from_properties(&props, "test_job");
+        assert!(!kafka_sink.supports_upsert());
+
+        props.insert(
+            "sink.cleanup.policy".to_string(),
+            "compact,delete".to_string(),
+        );
+        let kafka_sink = KafkaDataSink::from_properties(&props, "test_job");
+        assert!(kafka_sink.supports_upsert());
+        assert!(kafka_sink.metadata().supports_upsert);
+    }
+
     #[test]
     fn test_file_data_source_with_source_prefix() {
         let mut props = HashMap::new();
@@ -25,7 +25,7 @@ fn create_test_record(id: i64, name: &str, amount: f64, timestamp: i64) -> Strea
     headers.insert("source".to_string(), "test".to_string());
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         headers,
         timestamp,
         offset: id,
@@ -45,7 +45,7 @@ fn create_financial_record(id: i64, price_cents: i64, quantity: i64) -> StreamRe
     fields.insert("key".to_string(), FieldValue::Integer(id));
 
     StreamRecord {
-        fields: fields,
+        fields: fields.into(),
         headers: HashMap::new(),
         timestamp: 1640995200000, // Fixed timestamp
         offset: id,
@@ -322,7 +322,7 @@ mod kafka_data_writer_tests {
 
         // Test empty record
         let empty_record = StreamRecord {
-            fields: HashMap::new(),
+            fields: HashMap::new().into(),
             headers: HashMap::new(),
             timestamp: 0,
             offset: 0,
@@ -370,13 +370,13 @@ mod avro_tests {
     fn test_edge_cases() {
         // Very large bytes array should not be interpreted as decimal
         let large_bytes = vec![0xFF; 20]; // 20 bytes
-        let avro_value = AvroValue::Bytes(large_bytes);
+        let avro_value = AvroValue::Bytes(large_bytes.clone());
         let field_result = avro_value_to_field_value(&avro_value).unwrap();
 
         match field_result {
-            FieldValue::String(_) => {} // Should fall back to hex string
+            FieldValue::Bytes(bytes) => assert_eq!(bytes, large_bytes), // Raw bytes preserved
             _ => panic!(
-                "Large bytes should fall back to string, got {:?}",
+                "Large bytes should fall back to raw bytes, got {:?}",
                 field_result
             ),
         }
@@ -387,9 +387,9 @@ mod avro_tests {
         let field_result = avro_value_to_field_value(&avro_value).unwrap();
 
         match field_result {
-            FieldValue::String(s) => assert_eq!(s, "", "Empty bytes should give empty string"),
+            FieldValue::Bytes(bytes) => assert!(bytes.is_empty(), "Empty bytes stay empty"),
             _ => panic!(
-                "Empty bytes should give empty string, got {:?}",
+                "Empty bytes should give empty bytes, got {:?}",
                 field_result
             ),
         }
@@ -122,7 +122,7 @@ mod avro_tests {
         fields.insert("score".to_string(), FieldValue::Float(87.3));
 
         let stream_record = StreamRecord {
-            fields: fields.clone(),
+            fields: fields.clone().into(),
             timestamp: 1234567890,
             offset: 100,
             partition: 0,
@@ -131,7 +131,7 @@ mod avro_tests {
 
         // Serialize the StreamRecord
         let serialized = format
-            .serialize_record(&stream_record.fields)
+            .serialize_record(&stream_record.fields.to_map())
             .expect("Serialization should succeed");
 
         // Deserialize back
@@ -476,4 +476,124 @@ mod codec_integration_tests {
             elapsed
         );
     }
+
+    #[test]
+    fn test_binary_uuid_and_temporal_types_round_trip() {
+        let uuid = uuid::Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+        let mut record = HashMap::new();
+        record.insert(
+            "payload".to_string(),
+            FieldValue::Bytes(vec![0x00, 0xff, 0x10, 0x80, 0x7f, 0x01, 0x02, 0x03]),
+        );
+        record.insert("device_id".to_string(), FieldValue::Uuid(uuid));
+        record.insert(
+            "event_date".to_string(),
+            FieldValue::Date(chrono::NaiveDate::from_ymd_opt(2024, 3, 15).unwrap()),
+        );
+        record.insert(
+            "event_time".to_string(),
+            FieldValue::Time(chrono::NaiveTime::from_hms_micro_opt(14, 30, 45, 123456).unwrap()),
+        );
+        record.insert("reading".to_string(), FieldValue::Integer(1200));
+        record.insert("checksum".to_string(), FieldValue::Bytes(vec![1, 2, 3, 4]));
+
+        // Avro: logical types and sized primitives must survive unchanged
+        let avro_codec = AvroCodec::new(
+            r#"{
+                "type": "record",
+                "name": "Telemetry",
+                "fields": [
+                    {"name": "payload", "type": "bytes"},
+                    {"name": "device_id", "type": {"type": "string", "logicalType": "uuid"}},
+                    {"name": "event_date", "type": {"type": "int", "logicalType": "date"}},
+                    {"name": "event_time", "type": ["null", {"type": "long", "logicalType": "time-micros"}]},
+                    {"name": "reading", "type": "int"},
+                    {"name": "checksum", "type": {"type": "fixed", "name": "Checksum", "size": 4}}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let bytes = avro_codec.serialize(&record).unwrap();
+        let restored = avro_codec.deserialize(&bytes).unwrap();
+        assert_eq!(restored, record);
+
+        // Out-of-range values for an Avro int are rejected rather than truncated
+        let mut too_large = record.clone();
+        too_large.insert("reading".to_string(), FieldValue::Integer(i64::MAX));
+        assert!(avro_codec.serialize(&too_large).is_err());
+
+        // Protobuf: dedicated oneof variants; dates keep their string encoding
+        let protobuf_codec = ProtobufCodec::new_with_default_schema();
+        let bytes = protobuf_codec.serialize(&record).unwrap();
+        let restored = protobuf_codec.deserialize(&bytes).unwrap();
+        let mut expected = record.clone();
+        expected.insert(
+            "event_date".to_string(),
+            FieldValue::String("2024-03-15".to_string()),
+        );
+        assert_eq!(restored, expected);
+
+        // JSON: base64 bytes and canonical text forms
+        assert_eq!(
+            field_value_to_json(&record["payload"]).unwrap(),
+            serde_json::Value::String("AP8QgH8BAgM=".to_string())
+        );
+        assert_eq!(
+            field_value_to_json(&record["device_id"]).unwrap(),
+            serde_json::Value::String("67e55044-10b1-426f-9247-bb680e5fe0c8".to_string())
+        );
+        assert_eq!(
+            field_value_to_json(&record["event_time"]).unwrap(),
+            serde_json::Value::String("14:30:45.123".to_string())
+        );
+    }
+
+    #[test]
+    fn test_avro_decimal_fields_keep_schema_scale() {
+        let avro_codec = AvroCodec::new(
+            r#"{
+                "type": "record",
+                "name": "Settlement",
+                "fields": [
+                    {"name": "amount", "type": {"type": "bytes", "logicalType": "decimal", "precision": 18, "scale": 4}},
+                    {"name": "fee", "type": ["null", {"type": "fixed", "name": "Fee", "size": 4, "logicalType": "decimal", "precision": 9, "scale": 2}]}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        // 12.34 (scale 2) and an integer fee are written at the declared scales
+        let mut record = HashMap::new();
+        record.insert("amount".to_string(), FieldValue::ScaledInteger(1234, 2));
+        record.insert("fee".to_string(), FieldValue::Integer(-3));
+        let bytes = avro_codec.serialize(&record).unwrap();
+        let restored = avro_codec.deserialize(&bytes).unwrap();
+        assert_eq!(
+            restored.get("amount"),
+            Some(&FieldValue::ScaledInteger(123400, 4))
+        );
+        assert_eq!(
+            restored.get("fee"),
+            Some(&FieldValue::ScaledInteger(-300, 2))
+        );
+
+        // Extra fractional digits are rounded HALF_UP to the schema scale
+        record.insert("amount".to_string(), FieldValue::ScaledInteger(1234565, 5));
+        record.insert("fee".to_string(), FieldValue::Null);
+        let restored = avro_codec
+            .deserialize(&avro_codec.serialize(&record).unwrap())
+            .unwrap();
+        assert_eq!(
+            restored.get("amount"),
+            Some(&FieldValue::ScaledInteger(123457, 4))
+        );
+        assert_eq!(restored.get("fee"), Some(&FieldValue::Null));
+
+        // Values exceeding the schema precision are rejected
+        record.insert(
+            "fee".to_string(),
+            FieldValue::ScaledInteger(10_000_000_000, 2),
+        );
+        assert!(avro_codec.serialize(&record).is_err());
+    }
 }
@@ -47,7 +47,7 @@ async fn test_json_from_execution_format() {
     fields.insert("test_null".to_string(), FieldValue::Null);
 
     let stream_record = StreamRecord {
-        fields: fields.clone(),
+        fields: fields.clone().into(),
         timestamp: 1234567890,
         offset: 100,
         partition: 0,
@@ -56,7 +56,7 @@ async fn test_json_from_execution_format() {
 
     // Serialize the StreamRecord's fields
     let serialized = format
-        .serialize_record(&stream_record.fields)
+        .serialize_record(&stream_record.fields.to_map())
         .expect("Serialization should succeed");
 
     // Deserialize back
@@ -33,7 +33,7 @@ mod protobuf_tests {
 
         // Create a StreamRecord
         let stream_record = StreamRecord {
-            fields: record.clone(),
+            fields: record.clone().into(),
             timestamp: 1234567890,
             offset: 100,
             partition: 0,
@@ -42,7 +42,7 @@ mod protobuf_tests {
 
         // Test serialization round-trip using StreamRecord's fields
         let serialized = format
-            .serialize_record(&stream_record.fields)
+            .serialize_record(&stream_record.fields.to_map())
             .expect("Serialization should succeed");
 
         let deserialized = format
@@ -71,7 +71,7 @@ mod protobuf_tests {
         fields.insert("rating".to_string(), FieldValue::Float(92.7));
 
         let stream_record = StreamRecord {
-            fields: fields.clone(),
+            fields: fields.clone().into(),
             timestamp: 1234567890,
             offset: 100,
             partition: 0,
@@ -80,7 +80,7 @@ mod protobuf_tests {
 
         // Serialize the StreamRecord's fields
         let serialized = format
-            .serialize_record(&stream_record.fields)
+            .serialize_record(&stream_record.fields.to_map())
             .expect("Serialization should succeed");
 
         // Deserialize back
@@ -182,6 +182,7 @@ async fn test_simple_processor_multi_source_processing() {
         max_retries: 2,
         retry_backoff: Duration::from_millis(50),
         progress_interval: 5,
+        columnar_execution: false,
         log_progress: true,
     };
 
@@ -254,6 +255,7 @@ async fn test_transactional_processor_multi_source_processing() {
         max_retries: 3,
         retry_backoff: Duration::from_millis(100),
         progress_interval: 2,
+        columnar_execution: false,
         log_progress: true,
     };
 
@@ -432,6 +434,7 @@ async fn test_error_handling_in_multi_source_processing() {
         max_retries: 2,
         retry_backoff: Duration::from_millis(25),
         progress_interval: 1,
+        columnar_execution: false,
         log_progress: true,
     };
 
@@ -489,6 +492,7 @@ async fn test_processor_configuration_handling() {
             max_retries: 1,
             retry_backoff: Duration::from_millis(5),
             progress_interval: 1,
+            columnar_execution: false,
             log_progress: false,
         },
         JobProcessingConfig {
@@ -499,6 +503,7 @@ async fn test_processor_configuration_handling() {
             max_retries: 5,
             retry_backoff: Duration::from_millis(500),
             progress_interval: 10,
+            columnar_execution: false,
             log_progress: true,
         },
     ];
@@ -1,4 +1,5 @@
 // Tests for SQL concatenation operator (||)
+use ferrisstreams::ferris::sql::ast::NodeSpan;
 use ferrisstreams::ferris::sql::{
     ast::{BinaryOperator, Expr, LiteralValue, SelectField, StreamSource, StreamingQuery},
     execution::{expression::evaluator::ExpressionEvaluator, types::FieldValue, StreamRecord},
@@ -57,9 +58,16 @@ fn test_evaluate_string_concatenation() {
 
     // Test string || string
     let expr = Expr::BinaryOp {
-        left: Box::new(Expr::Literal(LiteralValue::String("Hello".to_string()))),
+        left: Box::new(Expr::Literal(
+            LiteralValue::String("Hello".to_string()),
+            NodeSpan::default(),
+        )),
         op: BinaryOperator::Concat,
-        right: Box::new(Expr::Literal(LiteralValue::String(" World".to_string()))),
+        right: Box::new(Expr::Literal(
+            LiteralValue::String(" World".to_string()),
+            NodeSpan::default(),
+        )),
+        span: NodeSpan::default(),
     };
 
     let result = ExpressionEvaluator::evaluate_expression_value(&expr, &record).unwrap();
@@ -72,9 +80,16 @@ fn test_evaluate_mixed_type_concatenation() {
 
     // Test string || integer
     let expr = Expr::BinaryOp {
-        left: Box::new(Expr::Literal(LiteralValue::String("Value: ".to_string()))),
+        left: Box::new(Expr::Literal(
+            LiteralValue::String("Value: ".to_string()),
+            NodeSpan::default(),
+        )),
         op: BinaryOperator::Concat,
-        right: Box::new(Expr::Literal(LiteralValue::Integer(42))),
+        right: Box::new(Expr::Literal(
+            LiteralValue::Integer(42),
+            NodeSpan::default(),
+        )),
+        span: NodeSpan::default(),
     };
 
     let result = ExpressionEvaluator::evaluate_expression_value(&expr, &record).unwrap();
@@ -82,9 +97,16 @@ fn test_evaluate_mixed_type_concatenation() {
 
     // Test integer || string
     let expr2 = Expr::BinaryOp {
-        left: Box::new(Expr::Literal(LiteralValue::Integer(123))),
+        left: Box::new(Expr::Literal(
+            LiteralValue::Integer(123),
+            NodeSpan::default(),
+        )),
         op: BinaryOperator::Concat,
-        right: Box::new(Expr::Literal(LiteralValue::String(" items".to_string()))),
+        right: Box::new(Expr::Literal(
+            LiteralValue::String(" items".to_string()),
+            NodeSpan::default(),
+        )),
+        span: NodeSpan::default(),
     };
 
     let result2 = ExpressionEvaluator::evaluate_expression_value(&expr2, &record).unwrap();
@@ -97,9 +119,13 @@ fn test_evaluate_null_concatenation() {
 
     // Test string || null (should return null per SQL standards)
     let expr = Expr::BinaryOp {
-        left: Box::new(Expr::Literal(LiteralValue::String("Hello".to_string()))),
+        left: Box::new(Expr::Literal(
+            LiteralValue::String("Hello".to_string()),
+            NodeSpan::default(),
+        )),
         op: BinaryOperator::Concat,
-        right: Box::new(Expr::Literal(LiteralValue::Null)),
+        right: Box::new(Expr::Literal(LiteralValue::Null, NodeSpan::default())),
+        span: NodeSpan::default(),
     };
 
     let result = ExpressionEvaluator::evaluate_expression_value(&expr, &record).unwrap();
@@ -107,9 +133,13 @@ fn test_evaluate_null_concatenation() {
 
     // Test null || string (should also return null)
     let expr2 = Expr::BinaryOp {
-        left: Box::new(Expr::Literal(LiteralValue::Null)),
+        left: Box::new(Expr::Literal(LiteralValue::Null, NodeSpan::default())),
         op: BinaryOperator::Concat,
-        right: Box::new(Expr::Literal(LiteralValue::String("World".to_string()))),
+        right: Box::new(Expr::Literal(
+            LiteralValue::String("World".to_string()),
+            NodeSpan::default(),
+        )),
+        span: NodeSpan::default(),
     };
 
     let result2 = ExpressionEvaluator::evaluate_expression_value(&expr2, &record).unwrap();
@@ -122,9 +152,16 @@ fn test_evaluate_float_concatenation() {
 
     // Test string || float
     let expr = Expr::BinaryOp {
-        left: Box::new(Expr::Literal(LiteralValue::String("Pi is ".to_string()))),
+        left: Box::new(Expr::Literal(
+            LiteralValue::String("Pi is ".to_string()),
+            NodeSpan::default(),
+        )),
         op: BinaryOperator::Concat,
-        right: Box::new(Expr::Literal(LiteralValue::Float(std::f64::consts::PI))),
+        right: Box::new(Expr::Literal(
+            LiteralValue::Float(std::f64::consts::PI),
+            NodeSpan::default(),
+        )),
+        span: NodeSpan::default(),
     };
 
     let result = ExpressionEvaluator::evaluate_expression_value(&expr, &record).unwrap();
@@ -140,9 +177,16 @@ fn test_evaluate_boolean_concatenation() {
 
     // Test string || boolean
     let expr = Expr::BinaryOp {
-        left: Box::new(Expr::Literal(LiteralValue::String("Active: ".to_string()))),
+        left: Box::new(Expr::Literal(
+            LiteralValue::String("Active: ".to_string()),
+            NodeSpan::default(),
+        )),
         op: BinaryOperator::Concat,
-        right: Box::new(Expr::Literal(LiteralValue::Boolean(true))),
+        right: Box::new(Expr::Literal(
+            LiteralValue::Boolean(true),
+            NodeSpan::default(),
+        )),
+        span: NodeSpan::default(),
     };
 
     let result = ExpressionEvaluator::evaluate_expression_value(&expr, &record).unwrap();
@@ -155,15 +199,26 @@ fn test_evaluate_chained_concatenation() {
 
     // Test 'a' || 'b' || 'c' (should be parsed as ('a' || 'b') || 'c')
     let inner_concat = Expr::BinaryOp {
-        left: Box::new(Expr::Literal(LiteralValue::String("a".to_string()))),
+        left: Box::new(Expr::Literal(
+            LiteralValue::String("a".to_string()),
+            NodeSpan::default(),
+        )),
         op: BinaryOperator::Concat,
-        right: Box::new(Expr::Literal(LiteralValue::String("b".to_string()))),
+        right: Box::new(Expr::Literal(
+            LiteralValue::String("b".to_string()),
+            NodeSpan::default(),
+        )),
+        span: NodeSpan::default(),
     };
 
     let outer_concat = Expr::BinaryOp {
         left: Box::new(inner_concat),
         op: BinaryOperator::Concat,
-        right: Box::new(Expr::Literal(LiteralValue::String("c".to_string()))),
+        right: Box::new(Expr::Literal(
+            LiteralValue::String("c".to_string()),
+            NodeSpan::default(),
+        )),
+        span: NodeSpan::default(),
     };
 
     let result = ExpressionEvaluator::evaluate_expression_value(&outer_concat, &record).unwrap();
@@ -177,9 +232,16 @@ fn test_unix_timestamp_concatenation_example() {
     // Test the user's specific example: 'exported_' || UNIX_TIMESTAMP()
     // For this test, we'll simulate with a constant timestamp
     let expr = Expr::BinaryOp {
-        left: Box::new(Expr::Literal(LiteralValue::String("exported_".to_string()))),
+        left: Box::new(Expr::Literal(
+            LiteralValue::String("exported_".to_string()),
+            NodeSpan::default(),
+        )),
         op: BinaryOperator::Concat,
-        right: Box::new(Expr::Literal(LiteralValue::Integer(1693910400))), // Mock timestamp
+        right: Box::new(Expr::Literal(
+            LiteralValue::Integer(1693910400),
+            NodeSpan::default(),
+        )),
+        span: NodeSpan::default(), // Mock timestamp
     };
 
     let result = ExpressionEvaluator::evaluate_expression_value(&expr, &record).unwrap();
@@ -195,9 +257,16 @@ fn test_scaled_integer_concatenation() {
 
     // Test concatenation with ScaledInteger (financial precision type)
     let expr = Expr::BinaryOp {
-        left: Box::new(Expr::Literal(LiteralValue::String("Amount: $".to_string()))),
+        left: Box::new(Expr::Literal(
+            LiteralValue::String("Amount: $".to_string()),
+            NodeSpan::default(),
+        )),
         op: BinaryOperator::Concat,
-        right: Box::new(Expr::Literal(LiteralValue::Float(1234.50))), // $1234.50
+        right: Box::new(Expr::Literal(
+            LiteralValue::Float(1234.50),
+            NodeSpan::default(),
+        )),
+        span: NodeSpan::default(), // $1234.50
     };
 
     let result = ExpressionEvaluator::evaluate_expression_value(&expr, &record).unwrap();
@@ -211,9 +280,16 @@ fn test_non_string_concatenation() {
 
     // Test integer || integer (both get converted to strings)
     let expr = Expr::BinaryOp {
-        left: Box::new(Expr::Literal(LiteralValue::Integer(123))),
+        left: Box::new(Expr::Literal(
+            LiteralValue::Integer(123),
+            NodeSpan::default(),
+        )),
         op: BinaryOperator::Concat,
-        right: Box::new(Expr::Literal(LiteralValue::Integer(456))),
+        right: Box::new(Expr::Literal(
+            LiteralValue::Integer(456),
+            NodeSpan::default(),
+        )),
+        span: NodeSpan::default(),
     };
 
     let result = ExpressionEvaluator::evaluate_expression_value(&expr, &record).unwrap();
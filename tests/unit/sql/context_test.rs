@@ -1,6 +1,10 @@
-use ferrisstreams::ferris::schema::{FieldDefinition, Schema, StreamHandle};
+use ferrisstreams::ferris::schema::{
+    CompatibilityMode, FieldDefinition, Schema, SchemaMetadata, StreamHandle,
+};
 use ferrisstreams::ferris::sql::ast::DataType;
 use ferrisstreams::ferris::sql::context::StreamingSqlContext;
+use ferrisstreams::ferris::sql::parser::StreamingSqlParser;
+use ferrisstreams::ferris::sql::SqlError;
 
 #[cfg(test)]
 mod tests {
@@ -368,4 +372,159 @@ mod tests {
 
         assert!(!schema.validate_record(&wrong_type_record));
     }
+
+    fn context_with_orders() -> StreamingSqlContext {
+        let mut context = StreamingSqlContext::new();
+        context
+            .register_stream(
+                "orders".to_string(),
+                create_test_handle(),
+                create_test_schema(),
+            )
+            .unwrap();
+        context
+            .register_table(
+                "order_totals".to_string(),
+                create_test_handle(),
+                create_test_schema(),
+            )
+            .unwrap();
+        context
+    }
+
+    fn register_orders_job(context: &mut StreamingSqlContext) {
+        let query = StreamingSqlParser::new()
+            .parse("CREATE STREAM big_orders AS SELECT * FROM orders WHERE amount > 100")
+            .unwrap();
+        context
+            .register_job("big_orders_job".to_string(), &query)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_alter_add_column_follows_evolution_rules() {
+        let mut context = context_with_orders();
+
+        context
+            .execute_ddl("ALTER STREAM orders ADD COLUMN discount FLOAT")
+            .unwrap();
+        let discount = context
+            .get_stream_schema("orders")
+            .unwrap()
+            .get_field("discount")
+            .unwrap();
+        assert!(discount.nullable);
+
+        // A required column without a default breaks backward compatibility
+        let result = context.execute_ddl("ALTER STREAM orders ADD COLUMN region STRING NOT NULL");
+        assert!(matches!(result, Err(SqlError::SchemaError { .. })));
+        assert!(!context
+            .get_stream_schema("orders")
+            .unwrap()
+            .has_field("region"));
+
+        // Existing columns are rejected unless IF NOT EXISTS is given
+        assert!(context
+            .execute_ddl("ALTER STREAM orders ADD COLUMN discount FLOAT")
+            .is_err());
+        context
+            .execute_ddl("ALTER STREAM orders ADD COLUMN IF NOT EXISTS discount FLOAT")
+            .unwrap();
+
+        // ALTER TABLE must name a table
+        assert!(context
+            .execute_ddl("ALTER TABLE orders ADD COLUMN note STRING")
+            .is_err());
+        context
+            .execute_ddl("ALTER TABLE order_totals ADD COLUMN note STRING")
+            .unwrap();
+    }
+
+    #[test]
+    fn test_running_jobs_require_compatible_changes() {
+        let mut context = context_with_orders();
+        let unchecked = Schema::new(vec![FieldDefinition::required(
+            "id".to_string(),
+            DataType::Integer,
+        )])
+        .with_metadata(SchemaMetadata::default().with_compatibility(CompatibilityMode::None));
+        context
+            .register_stream("raw".to_string(), create_test_handle(), unchecked)
+            .unwrap();
+
+        // Without dependent jobs the schema's NONE mode allows any change
+        context
+            .execute_ddl("ALTER STREAM raw ADD COLUMN source STRING NOT NULL")
+            .unwrap();
+
+        let query = StreamingSqlParser::new()
+            .parse("SELECT * FROM raw")
+            .unwrap();
+        context.register_job("reader".to_string(), &query).unwrap();
+        assert!(context
+            .execute_ddl("ALTER STREAM raw ADD COLUMN region STRING NOT NULL")
+            .is_err());
+        context
+            .execute_ddl("ALTER STREAM raw ADD COLUMN region STRING")
+            .unwrap();
+    }
+
+    #[test]
+    fn test_alter_set_properties() {
+        let mut context = context_with_orders();
+
+        context
+            .execute_ddl("ALTER STREAM orders SET ('retention.ms' = '3600000')")
+            .unwrap();
+        context
+            .execute_ddl("ALTER STREAM orders SET ('schema.enforcement' = 'coerce')")
+            .unwrap();
+        let properties = context.get_properties("orders").unwrap();
+        assert_eq!(properties.len(), 2);
+        assert_eq!(properties.get("retention.ms").unwrap(), "3600000");
+
+        register_orders_job(&mut context);
+        match context.execute_ddl("ALTER STREAM orders SET ('retention.ms' = '60000')") {
+            Err(SqlError::ExecutionError { message, .. }) => {
+                assert!(message.contains("big_orders_job"), "{}", message)
+            }
+            other => panic!("Expected ExecutionError, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_drop_statements() {
+        let mut context = context_with_orders();
+        register_orders_job(&mut context);
+        assert_eq!(context.dependent_jobs("orders"), vec!["big_orders_job"]);
+        assert_eq!(context.dependent_jobs("big_orders"), vec!["big_orders_job"]);
+
+        // Dropping a stream that a running job reads from is rejected
+        assert!(context.execute_ddl("DROP STREAM orders").is_err());
+        assert!(context.list_streams().contains(&"orders".to_string()));
+
+        context.execute_ddl("DROP JOB big_orders_job").unwrap();
+        assert!(context.list_jobs().is_empty());
+        assert!(context.execute_ddl("DROP JOB big_orders_job").is_err());
+        context
+            .execute_ddl("DROP JOB IF EXISTS big_orders_job")
+            .unwrap();
+
+        context.execute_ddl("DROP STREAM orders").unwrap();
+        assert!(context.get_stream_schema("orders").is_none());
+        assert!(matches!(
+            context.execute_ddl("DROP STREAM orders"),
+            Err(SqlError::StreamError { .. })
+        ));
+        context.execute_ddl("DROP STREAM IF EXISTS orders").unwrap();
+
+        // DROP STREAM does not drop tables
+        assert!(context.execute_ddl("DROP STREAM order_totals").is_err());
+        context.execute_ddl("DROP TABLE order_totals").unwrap();
+        assert!(context.list_tables().is_empty());
+        assert!(matches!(
+            context.execute_ddl("DROP TABLE order_totals"),
+            Err(SqlError::TableNotFound { .. })
+        ));
+    }
 }
@@ -1,6 +1,6 @@
 //! Tests for aggregation accumulator management
 
-use ferrisstreams::ferris::sql::ast::{Expr, SelectField};
+use ferrisstreams::ferris::sql::ast::{Expr, NodeSpan, SelectField};
 use ferrisstreams::ferris::sql::execution::aggregation::AccumulatorManager;
 use ferrisstreams::ferris::sql::execution::internal::GroupAccumulator;
 use ferrisstreams::ferris::sql::execution::types::{FieldValue, StreamRecord};
@@ -13,7 +13,7 @@ fn create_test_record(fields: Vec<(&str, FieldValue)>) -> StreamRecord {
     }
 
     StreamRecord {
-        fields: field_map,
+        fields: field_map.into(),
         timestamp: 0,
         offset: 0,
         partition: 0,
@@ -42,22 +42,25 @@ fn test_is_aggregate_expression() {
     let count_expr = Expr::Function {
         name: "COUNT".to_string(),
         args: vec![],
+        span: NodeSpan::default(),
     };
     assert!(AccumulatorManager::is_aggregate_expression(&count_expr));
 
     let sum_expr = Expr::Function {
         name: "SUM".to_string(),
-        args: vec![Expr::Column("amount".to_string())],
+        args: vec![Expr::Column("amount".to_string(), NodeSpan::default())],
+        span: NodeSpan::default(),
     };
     assert!(AccumulatorManager::is_aggregate_expression(&sum_expr));
 
     let non_agg_expr = Expr::Function {
         name: "UPPER".to_string(),
-        args: vec![Expr::Column("name".to_string())],
+        args: vec![Expr::Column("name".to_string(), NodeSpan::default())],
+        span: NodeSpan::default(),
     };
     assert!(!AccumulatorManager::is_aggregate_expression(&non_agg_expr));
 
-    let identifier_expr = Expr::Column("name".to_string());
+    let identifier_expr = Expr::Column("name".to_string(), NodeSpan::default());
     assert!(!AccumulatorManager::is_aggregate_expression(
         &identifier_expr
     ));
@@ -68,6 +71,7 @@ fn test_generate_field_name() {
     let count_expr = Expr::Function {
         name: "COUNT".to_string(),
         args: vec![],
+        span: NodeSpan::default(),
     };
     assert_eq!(
         AccumulatorManager::generate_field_name(&count_expr),
@@ -76,7 +80,8 @@ fn test_generate_field_name() {
 
     let sum_expr = Expr::Function {
         name: "SUM".to_string(),
-        args: vec![Expr::Column("amount".to_string())],
+        args: vec![Expr::Column("amount".to_string(), NodeSpan::default())],
+        span: NodeSpan::default(),
     };
     assert_eq!(
         AccumulatorManager::generate_field_name(&sum_expr),
@@ -88,20 +93,22 @@ fn test_generate_field_name() {
 fn test_extract_aggregate_expressions() {
     let select_fields = vec![
         SelectField::Expression {
-            expr: Expr::Column("category".to_string()),
+            expr: Expr::Column("category".to_string(), NodeSpan::default()),
             alias: None,
         },
         SelectField::Expression {
             expr: Expr::Function {
                 name: "COUNT".to_string(),
                 args: vec![],
+                span: NodeSpan::default(),
             },
             alias: Some("total_count".to_string()),
         },
         SelectField::Expression {
             expr: Expr::Function {
                 name: "SUM".to_string(),
-                args: vec![Expr::Column("amount".to_string())],
+                args: vec![Expr::Column("amount".to_string(), NodeSpan::default())],
+                span: NodeSpan::default(),
             },
             alias: None,
         },
@@ -1,6 +1,6 @@
 //! Tests for aggregate function computation
 
-use ferrisstreams::ferris::sql::ast::{Expr, LiteralValue};
+use ferrisstreams::ferris::sql::ast::{Expr, LiteralValue, NodeSpan};
 use ferrisstreams::ferris::sql::execution::aggregation::AggregateFunctions;
 use ferrisstreams::ferris::sql::execution::internal::GroupAccumulator;
 use ferrisstreams::ferris::sql::execution::types::FieldValue;
@@ -60,7 +60,8 @@ fn test_compute_count_star() {
     let accumulator = create_test_accumulator();
     let count_expr = Expr::Function {
         name: "COUNT".to_string(),
-        args: vec![], // COUNT(*)
+        args: vec![],
+        span: NodeSpan::default(), // COUNT(*)
     };
 
     let result =
@@ -75,7 +76,8 @@ fn test_compute_count_column() {
     let accumulator = create_test_accumulator();
     let count_expr = Expr::Function {
         name: "COUNT".to_string(),
-        args: vec![Expr::Column("amount".to_string())],
+        args: vec![Expr::Column("amount".to_string(), NodeSpan::default())],
+        span: NodeSpan::default(),
     };
 
     let result =
@@ -90,7 +92,8 @@ fn test_compute_sum() {
     let accumulator = create_test_accumulator();
     let sum_expr = Expr::Function {
         name: "SUM".to_string(),
-        args: vec![Expr::Column("amount".to_string())],
+        args: vec![Expr::Column("amount".to_string(), NodeSpan::default())],
+        span: NodeSpan::default(),
     };
 
     let result =
@@ -105,7 +108,8 @@ fn test_compute_avg() {
     let accumulator = create_test_accumulator();
     let avg_expr = Expr::Function {
         name: "AVG".to_string(),
-        args: vec![Expr::Column("amount".to_string())],
+        args: vec![Expr::Column("amount".to_string(), NodeSpan::default())],
+        span: NodeSpan::default(),
     };
 
     let result =
@@ -122,7 +126,8 @@ fn test_compute_min_max() {
 
     let min_expr = Expr::Function {
         name: "MIN".to_string(),
-        args: vec![Expr::Column("amount".to_string())],
+        args: vec![Expr::Column("amount".to_string(), NodeSpan::default())],
+        span: NodeSpan::default(),
     };
     let result =
         AggregateFunctions::compute_field_aggregate_value("amount", &min_expr, &accumulator)
@@ -131,7 +136,8 @@ fn test_compute_min_max() {
 
     let max_expr = Expr::Function {
         name: "MAX".to_string(),
-        args: vec![Expr::Column("amount".to_string())],
+        args: vec![Expr::Column("amount".to_string(), NodeSpan::default())],
+        span: NodeSpan::default(),
     };
     let result =
         AggregateFunctions::compute_field_aggregate_value("amount", &max_expr, &accumulator)
@@ -145,7 +151,8 @@ fn test_compute_stddev_variance() {
 
     let stddev_expr = Expr::Function {
         name: "STDDEV".to_string(),
-        args: vec![Expr::Column("amount".to_string())],
+        args: vec![Expr::Column("amount".to_string(), NodeSpan::default())],
+        span: NodeSpan::default(),
     };
     let result =
         AggregateFunctions::compute_field_aggregate_value("amount", &stddev_expr, &accumulator)
@@ -165,7 +172,8 @@ fn test_compute_first_last() {
 
     let first_expr = Expr::Function {
         name: "FIRST".to_string(),
-        args: vec![Expr::Column("name".to_string())],
+        args: vec![Expr::Column("name".to_string(), NodeSpan::default())],
+        span: NodeSpan::default(),
     };
     let result =
         AggregateFunctions::compute_field_aggregate_value("name", &first_expr, &accumulator)
@@ -174,7 +182,8 @@ fn test_compute_first_last() {
 
     let last_expr = Expr::Function {
         name: "LAST".to_string(),
-        args: vec![Expr::Column("name".to_string())],
+        args: vec![Expr::Column("name".to_string(), NodeSpan::default())],
+        span: NodeSpan::default(),
     };
     let result =
         AggregateFunctions::compute_field_aggregate_value("name", &last_expr, &accumulator)
@@ -188,9 +197,10 @@ fn test_compute_string_agg() {
     let string_agg_expr = Expr::Function {
         name: "STRING_AGG".to_string(),
         args: vec![
-            Expr::Column("names".to_string()),
-            Expr::Literal(LiteralValue::String(";".to_string())),
+            Expr::Column("names".to_string(), NodeSpan::default()),
+            Expr::Literal(LiteralValue::String(";".to_string()), NodeSpan::default()),
         ],
+        span: NodeSpan::default(),
     };
 
     let result =
@@ -205,7 +215,8 @@ fn test_compute_count_distinct() {
     let accumulator = create_test_accumulator();
     let count_distinct_expr = Expr::Function {
         name: "COUNT_DISTINCT".to_string(),
-        args: vec![Expr::Column("category".to_string())],
+        args: vec![Expr::Column("category".to_string(), NodeSpan::default())],
+        span: NodeSpan::default(),
     };
 
     let result = AggregateFunctions::compute_field_aggregate_value(
@@ -223,16 +234,18 @@ fn test_is_aggregate_function() {
     let count_expr = Expr::Function {
         name: "COUNT".to_string(),
         args: vec![],
+        span: NodeSpan::default(),
     };
     assert!(AggregateFunctions::is_aggregate_function(&count_expr));
 
     let upper_expr = Expr::Function {
         name: "UPPER".to_string(),
-        args: vec![Expr::Column("name".to_string())],
+        args: vec![Expr::Column("name".to_string(), NodeSpan::default())],
+        span: NodeSpan::default(),
     };
     assert!(!AggregateFunctions::is_aggregate_function(&upper_expr));
 
-    let identifier_expr = Expr::Column("name".to_string());
+    let identifier_expr = Expr::Column("name".to_string(), NodeSpan::default());
     assert!(!AggregateFunctions::is_aggregate_function(&identifier_expr));
 }
 
@@ -23,7 +23,7 @@ mod tests {
         offset: i64,
     ) -> StreamRecord {
         StreamRecord {
-            fields,
+            fields: fields.into(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             offset,
             partition: 0,
@@ -51,7 +51,7 @@ mod tests {
             } => {
                 assert_eq!(group_exprs.len(), 1);
                 match &group_exprs[0] {
-                    Expr::Column(name) => assert_eq!(name, "customer_id"),
+                    Expr::Column(name, _) => assert_eq!(name, "customer_id"),
                     _ => panic!("Expected column expression"),
                 }
             }
@@ -74,7 +74,7 @@ mod tests {
             } => {
                 assert_eq!(group_exprs.len(), 2);
                 match (&group_exprs[0], &group_exprs[1]) {
-                    (Expr::Column(name1), Expr::Column(name2)) => {
+                    (Expr::Column(name1, _), Expr::Column(name2, _)) => {
                         assert_eq!(name1, "customer_id");
                         assert_eq!(name2, "region");
                     }
@@ -106,7 +106,7 @@ mod tests {
                 assert_eq!(order_exprs.len(), 1);
                 match &order_exprs[0] {
                     OrderByExpr {
-                        expr: Expr::Column(name),
+                        expr: Expr::Column(name, _),
                         direction,
                     } => {
                         assert_eq!(name, "amount");
@@ -135,7 +135,7 @@ mod tests {
                 assert_eq!(order_exprs.len(), 1);
                 match &order_exprs[0] {
                     OrderByExpr {
-                        expr: Expr::Column(name),
+                        expr: Expr::Column(name, _),
                         direction,
                     } => {
                         assert_eq!(name, "amount");
@@ -267,7 +267,7 @@ mod tests {
                 if i > 0 {
                     composite_key.push(',');
                 }
-                if let Some(key_value) = result.fields.get(*field) {
+                if let Some(key_value) = result.fields.get(field) {
                     let key_part = match key_value {
                         FieldValue::Float(n) => n.to_string(),
                         FieldValue::String(s) => s.clone(),
@@ -295,7 +295,7 @@ mod tests {
         fields1.insert("customer_id".to_string(), FieldValue::Float(1.0));
         fields1.insert("amount".to_string(), FieldValue::Float(100.0));
         let record1 = StreamRecord {
-            fields: fields1,
+            fields: fields1.into(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             offset: 1,
             partition: 0,
@@ -306,7 +306,7 @@ mod tests {
         fields2.insert("customer_id".to_string(), FieldValue::Float(1.0));
         fields2.insert("amount".to_string(), FieldValue::Float(200.0));
         let record2 = StreamRecord {
-            fields: fields2,
+            fields: fields2.into(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             offset: 2,
             partition: 0,
@@ -317,7 +317,7 @@ mod tests {
         fields3.insert("customer_id".to_string(), FieldValue::Float(2.0));
         fields3.insert("amount".to_string(), FieldValue::Float(150.0));
         let record3 = StreamRecord {
-            fields: fields3,
+            fields: fields3.into(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             offset: 3,
             partition: 0,
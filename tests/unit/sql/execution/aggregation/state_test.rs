@@ -1,6 +1,6 @@
 //! Tests for aggregation state management
 
-use ferrisstreams::ferris::sql::ast::Expr;
+use ferrisstreams::ferris::sql::ast::{Expr, NodeSpan};
 use ferrisstreams::ferris::sql::execution::aggregation::GroupByStateManager;
 use ferrisstreams::ferris::sql::execution::types::{FieldValue, StreamRecord};
 use std::collections::HashMap;
@@ -12,7 +12,7 @@ fn create_test_record(fields: Vec<(&str, FieldValue)>) -> StreamRecord {
     }
 
     StreamRecord {
-        fields: field_map,
+        fields: field_map.into(),
         timestamp: 0,
         offset: 0,
         partition: 0,
@@ -52,8 +52,8 @@ fn test_generate_group_key_simple() {
     ]);
 
     let expressions = vec![
-        Expr::Column("category".to_string()),
-        Expr::Column("priority".to_string()),
+        Expr::Column("category".to_string(), NodeSpan::default()),
+        Expr::Column("priority".to_string(), NodeSpan::default()),
     ];
 
     let result = GroupByStateManager::generate_group_key(&expressions, &record);
@@ -69,7 +69,7 @@ fn test_record_matches_group_key() {
         FieldValue::String("electronics".to_string()),
     )]);
 
-    let expressions = vec![Expr::Column("category".to_string())];
+    let expressions = vec![Expr::Column("category".to_string(), NodeSpan::default())];
     let target_key = vec!["electronics".to_string()];
 
     let result = GroupByStateManager::record_matches_group_key(&expressions, &record, &target_key);
@@ -96,7 +96,7 @@ fn test_extract_group_keys() {
         )]),
     ];
 
-    let expressions = vec![Expr::Column("category".to_string())];
+    let expressions = vec![Expr::Column("category".to_string(), NodeSpan::default())];
 
     let result = GroupByStateManager::extract_group_keys(&expressions, &records);
     assert!(result.is_ok());
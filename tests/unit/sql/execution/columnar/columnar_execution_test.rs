@@ -0,0 +1,456 @@
+/*!
+# Columnar Execution Tests
+
+Vectorized kernels must give the same results as the row evaluator, and decline whatever
+they cannot reproduce so the row path handles it.
+*/
+
+use ferrisstreams::ferris::sql::ast::{Expr, SelectField, StreamingQuery};
+use ferrisstreams::ferris::sql::execution::columnar::{
+    kernels, Bitmap, Column, ColumnarBatch, ColumnarPlan,
+};
+use ferrisstreams::ferris::sql::execution::expression::CompiledExpression;
+use ferrisstreams::ferris::sql::execution::state::StateTtlConfig;
+use ferrisstreams::ferris::sql::execution::{FieldValue, StreamExecutionEngine, StreamRecord};
+use ferrisstreams::ferris::sql::StreamingSqlParser;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+fn parse(sql: &str) -> StreamingQuery {
+    StreamingSqlParser::new()
+        .parse(sql)
+        .expect("query should parse")
+}
+
+fn parse_expr(expr: &str) -> Expr {
+    match parse(&format!("SELECT {} AS v FROM orders", expr)) {
+        StreamingQuery::Select { mut fields, .. } => match fields.remove(0) {
+            SelectField::Expression { expr, .. } => expr,
+            other => panic!("unexpected field {:?}", other),
+        },
+        other => panic!("unexpected query {:?}", other),
+    }
+}
+
+fn create_record(id: i64, amount: Option<f64>, status: Option<&str>) -> StreamRecord {
+    let mut fields = HashMap::new();
+    fields.insert("id".to_string(), FieldValue::Integer(id));
+    match amount {
+        Some(amount) => fields.insert("amount".to_string(), FieldValue::Float(amount)),
+        None => fields.insert("amount".to_string(), FieldValue::Null),
+    };
+    if let Some(status) = status {
+        fields.insert("status".to_string(), FieldValue::String(status.to_string()));
+    }
+
+    StreamRecord {
+        fields: fields.into(),
+        timestamp: 1_700_000_000_000 + id,
+        offset: id * 10,
+        partition: (id % 3) as i32,
+        headers: HashMap::new(),
+    }
+}
+
+fn create_batch() -> Vec<StreamRecord> {
+    vec![
+        create_record(1, Some(50.0), Some("new")),
+        create_record(2, Some(150.5), Some("shipped")),
+        create_record(3, None, Some("shipped")),
+        create_record(4, Some(999.0), None),
+        create_record(5, Some(100.0), Some("cancelled")),
+    ]
+}
+
+#[test]
+fn test_bitmap_operations() {
+    let bits = Bitmap::from_bools((0..130).map(|index| index % 3 == 0));
+    assert_eq!(bits.len(), 130);
+    assert_eq!(bits.count_set(), 44);
+    assert!(bits.get(129) && !bits.get(128));
+
+    let inverted = bits.not();
+    assert_eq!(inverted.count_set(), 86);
+    assert_eq!(bits.and(&inverted).count_set(), 0);
+    assert!(bits.or(&inverted).all_set());
+    assert_eq!(bits.iter_set().take(3).collect::<Vec<_>>(), vec![0, 3, 6]);
+
+    let mut all = Bitmap::new(70, true);
+    assert_eq!(all.count_set(), 70);
+    all.set(69, false);
+    assert_eq!(all.not().iter_set().collect::<Vec<_>>(), vec![69]);
+}
+
+#[test]
+fn test_columns_are_typed_with_validity() {
+    let records = create_batch();
+    let batch = ColumnarBatch::from_records(&records, &["id", "amount", "status", "_offset"]);
+    assert_eq!(batch.len(), 5);
+
+    assert!(matches!(batch.column("id"), Some(Column::Integer { .. })));
+    let amount = batch.column("amount").unwrap();
+    assert!(matches!(amount, Column::Float { .. }));
+    assert!(!amount.is_valid(2));
+    assert_eq!(amount.value(2), FieldValue::Null);
+    assert_eq!(amount.value(1), FieldValue::Float(150.5));
+
+    let status = batch.column("status").unwrap();
+    assert!(matches!(status, Column::String { .. }));
+    assert_eq!(status.validity().unwrap().count_set(), 4);
+
+    // System columns are always present and resolved case-insensitively
+    assert!(batch.column("_offset").is_none());
+    assert_eq!(
+        batch.resolve("_OFFSET").unwrap().value(3),
+        FieldValue::Integer(40)
+    );
+
+    // Columns mixing types keep their values for the row path
+    let mixed = Column::from_values(&[
+        Some(&FieldValue::Integer(1)),
+        Some(&FieldValue::String("a".to_string())),
+    ]);
+    assert!(matches!(mixed, Column::Values(_)));
+    assert!(mixed.validity().is_none());
+}
+
+#[test]
+fn test_batch_evaluation_matches_rows() {
+    let expressions = [
+        "amount > 100",
+        "amount >= 100 AND status = 'shipped'",
+        "id = 1 OR status IS NULL",
+        "100 < amount",
+        "amount * 2 + id",
+        "id - 1",
+        "amount IS NOT NULL",
+        "status LIKE 'ship%'",
+        "status LIKE '%e%'",
+        "status IN ('new', 'cancelled')",
+        "id NOT IN (1, 2)",
+        "amount BETWEEN 100 AND 200",
+        "id NOT BETWEEN 2 AND 4",
+        "NOT (id > 2)",
+        "_offset > 20 AND _partition = 1",
+        "status <> 'new'",
+    ];
+    let records = create_batch();
+
+    for sql in expressions {
+        let compiled = CompiledExpression::compile(&parse_expr(sql)).expect("no subqueries");
+        let columns: Vec<&str> = compiled.columns().collect();
+        let batch = ColumnarBatch::from_records(&records, &columns);
+
+        let values = compiled
+            .evaluate_batch(&batch)
+            .unwrap_or_else(|| panic!("{} should have a vectorized kernel", sql));
+        let predicate = compiled.evaluate_batch_bool(&batch).unwrap();
+        for (row, record) in records.iter().enumerate() {
+            assert_eq!(
+                Some(values.value(row)),
+                compiled.evaluate(record).ok(),
+                "value of {} for row {}",
+                sql,
+                row
+            );
+            assert_eq!(
+                Some(predicate.get(row)),
+                compiled.evaluate_bool(record).ok(),
+                "predicate {} for row {}",
+                sql,
+                row
+            );
+        }
+    }
+}
+
+#[test]
+fn test_batch_evaluation_declines_to_row_path() {
+    let records = create_batch();
+    for sql in [
+        // Functions and CASE have no kernels
+        "UPPER(status)",
+        "CASE WHEN id > 1 THEN 'a' ELSE 'b' END",
+        // Rows that would fail are left to the row path to report
+        "amount / 0",
+        "status > 1",
+        "id * 9223372036854775807",
+    ] {
+        let compiled = CompiledExpression::compile(&parse_expr(sql)).expect("no subqueries");
+        let columns: Vec<&str> = compiled.columns().collect();
+        let batch = ColumnarBatch::from_records(&records, &columns);
+        assert!(
+            compiled.evaluate_batch(&batch).is_none(),
+            "{} should need the row path",
+            sql
+        );
+    }
+}
+
+#[test]
+fn test_plan_accepts_stateless_selects_and_simple_aggregations() {
+    for sql in [
+        "SELECT * FROM orders",
+        "SELECT id, amount * 2 AS doubled FROM orders WHERE amount > 100",
+        "CREATE STREAM big AS SELECT id FROM orders WHERE amount > 100",
+        "SELECT status, COUNT(*) FROM orders GROUP BY status",
+        "SELECT status, SUM(amount) AS total FROM orders GROUP BY status HAVING COUNT(*) > 1",
+    ] {
+        assert!(
+            ColumnarPlan::for_query(&parse(sql)).is_some(),
+            "{} should be planned",
+            sql
+        );
+    }
+    for sql in [
+        "SELECT status, AVG(amount) FROM orders GROUP BY status",
+        "SELECT status, id + 1 AS next FROM orders GROUP BY status",
+        "SELECT COUNT(*) FROM orders GROUP BY UPPER(status)",
+        "SELECT status, COUNT(*) FROM orders GROUP BY status EMIT FINAL",
+        "SELECT status FROM orders HAVING COUNT(*) > 1",
+        "SELECT * FROM orders LIMIT 10",
+        "SELECT id FROM orders WINDOW TUMBLING(5m)",
+        "SELECT id FROM orders WHERE id IN (SELECT id FROM customers)",
+    ] {
+        assert!(
+            ColumnarPlan::for_query(&parse(sql)).is_none(),
+            "{} should need the row path",
+            sql
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_execute_batch_matches_row_path() {
+    let queries = [
+        "SELECT * FROM orders WHERE amount > 100",
+        "SELECT id, status AS state, amount * 2 AS doubled FROM orders WHERE status IS NOT NULL",
+        "SELECT id, _offset FROM orders WHERE id NOT IN (2, 3)",
+    ];
+    let records = create_batch();
+
+    for sql in queries {
+        let query = parse(sql);
+
+        let (row_sender, mut row_receiver) = mpsc::unbounded_channel();
+        let mut row_engine = StreamExecutionEngine::new(row_sender);
+        for record in &records {
+            row_engine
+                .execute_with_record(&query, record.clone())
+                .await
+                .unwrap();
+        }
+
+        let (batch_sender, mut batch_receiver) = mpsc::unbounded_channel();
+        let mut batch_engine = StreamExecutionEngine::new(batch_sender);
+        batch_engine.plan_query(&query);
+        assert!(!batch_engine.execute_batch(&records).unwrap());
+        batch_engine.set_columnar_execution(true);
+        assert!(batch_engine.execute_batch(&records).unwrap());
+
+        let mut schemas = Vec::new();
+        while let Ok(expected) = row_receiver.try_recv() {
+            let actual = batch_receiver.try_recv().expect("same number of results");
+            assert_eq!(actual.fields, expected.fields, "{}", sql);
+            assert_eq!(actual.offset, expected.offset, "{}", sql);
+            schemas.push(actual.fields.schema().clone());
+        }
+        assert!(!schemas.is_empty(), "{} should emit results", sql);
+        assert!(batch_receiver.try_recv().is_err(), "{}", sql);
+        // Projections fill one output schema shared by all their records
+        if !sql.contains('*') {
+            assert!(
+                schemas
+                    .windows(2)
+                    .all(|pair| Arc::ptr_eq(&pair[0], &pair[1])),
+                "{}",
+                sql
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_execute_batch_falls_back_without_emitting() {
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    let mut engine = StreamExecutionEngine::new(sender);
+    engine.set_columnar_execution(true);
+
+    // Nothing is executed before a query is planned
+    assert!(!engine.execute_batch(&create_batch()).unwrap());
+
+    engine.plan_query(&parse("SELECT id, amount / 0 AS broken FROM orders"));
+    assert!(!engine.execute_batch(&create_batch()).unwrap());
+    assert!(receiver.try_recv().is_err());
+}
+
+fn create_group_batch() -> Vec<StreamRecord> {
+    let mut records = create_batch();
+    records.extend([
+        create_record(6, Some(25.0), Some("new")),
+        create_record(7, Some(10.5), Some("shipped")),
+        create_record(8, None, None),
+    ]);
+    records
+}
+
+/// The latest result of every group, keyed by the GROUP BY column
+fn latest_by_group(
+    receiver: &mut mpsc::UnboundedReceiver<StreamRecord>,
+) -> HashMap<String, StreamRecord> {
+    let mut latest = HashMap::new();
+    while let Ok(result) = receiver.try_recv() {
+        let status = format!("{:?}", result.fields.get("status"));
+        latest.insert(status, result);
+    }
+    latest
+}
+
+#[tokio::test]
+async fn test_execute_batch_group_by_matches_row_path() {
+    let queries = [
+        "SELECT status, COUNT(*) AS orders, SUM(amount) AS total, MIN(amount) AS low, \
+         MAX(id) AS high FROM orders GROUP BY status",
+        "SELECT status, COUNT(amount) AS priced FROM orders WHERE id > 1 GROUP BY status",
+        "SELECT status, SUM(amount) AS total FROM orders GROUP BY status HAVING SUM(amount) > 100",
+    ];
+    let records = create_group_batch();
+
+    for sql in queries {
+        let query = parse(sql);
+
+        let (row_sender, mut row_receiver) = mpsc::unbounded_channel();
+        let mut row_engine = StreamExecutionEngine::new(row_sender);
+        for record in &records {
+            row_engine
+                .execute_with_record(&query, record.clone())
+                .await
+                .unwrap();
+        }
+
+        // Two batches, so groups carry over from one batch to the next
+        let (batch_sender, mut batch_receiver) = mpsc::unbounded_channel();
+        let mut batch_engine = StreamExecutionEngine::new(batch_sender);
+        batch_engine.plan_query(&query);
+        batch_engine.set_columnar_execution(true);
+        let (first, second) = records.split_at(4);
+        assert!(batch_engine.execute_batch(first).unwrap(), "{}", sql);
+        assert!(batch_engine.execute_batch(second).unwrap(), "{}", sql);
+
+        let expected = latest_by_group(&mut row_receiver);
+        let actual = latest_by_group(&mut batch_receiver);
+        assert!(!expected.is_empty(), "{} should emit results", sql);
+        assert_eq!(actual.len(), expected.len(), "{}", sql);
+        for (group, expected) in &expected {
+            let actual = &actual[group];
+            assert_eq!(actual.fields, expected.fields, "{} group {}", sql, group);
+            assert_eq!(actual.offset, expected.offset, "{} group {}", sql, group);
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_execute_batch_group_by_continues_row_path_groups() {
+    let query = parse("SELECT status, COUNT(*) AS orders FROM orders GROUP BY status");
+    let records = create_group_batch();
+    let (first, second) = records.split_at(3);
+
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    let mut engine = StreamExecutionEngine::new(sender);
+    engine.plan_query(&query);
+    engine.set_columnar_execution(true);
+    for record in first {
+        engine
+            .execute_with_record(&query, record.clone())
+            .await
+            .unwrap();
+    }
+    assert!(engine.execute_batch(second).unwrap());
+
+    // One result per group the batch updated, in the order of their last records
+    let results: Vec<StreamRecord> = std::iter::from_fn(|| receiver.try_recv().ok())
+        .skip(first.len())
+        .collect();
+    let counts: Vec<(Option<FieldValue>, Option<FieldValue>)> = results
+        .iter()
+        .map(|result| {
+            (
+                result.fields.get("status").cloned(),
+                result.fields.get("orders").cloned(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        counts,
+        vec![
+            (
+                Some(FieldValue::String("cancelled".to_string())),
+                Some(FieldValue::Integer(1))
+            ),
+            (
+                Some(FieldValue::String("new".to_string())),
+                Some(FieldValue::Integer(2))
+            ),
+            (
+                Some(FieldValue::String("shipped".to_string())),
+                Some(FieldValue::Integer(3))
+            ),
+            (Some(FieldValue::Null), Some(FieldValue::Integer(2))),
+        ]
+    );
+    assert_eq!(results[1].offset, 60);
+}
+
+#[tokio::test]
+async fn test_execute_batch_group_by_falls_back_without_changes() {
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    let mut engine = StreamExecutionEngine::new(sender);
+    engine.set_columnar_execution(true);
+
+    // SUM over text is an error the row path reports
+    engine.plan_query(&parse(
+        "SELECT id, SUM(status) AS total FROM orders GROUP BY id",
+    ));
+    assert!(!engine.execute_batch(&create_batch()).unwrap());
+    assert!(receiver.try_recv().is_err());
+    assert!(engine.state_metrics().unwrap().is_empty());
+
+    // Groups under a TTL are touched record by record
+    engine
+        .set_state_ttl(StateTtlConfig::new(Duration::from_secs(60)))
+        .unwrap();
+    engine.plan_query(&parse(
+        "SELECT status, COUNT(*) AS orders FROM orders GROUP BY status",
+    ));
+    assert!(!engine.execute_batch(&create_batch()).unwrap());
+    assert!(receiver.try_recv().is_err());
+}
+
+#[test]
+fn test_aggregate_kernels() {
+    let records = create_batch();
+    let batch = ColumnarBatch::from_records(&records, &["id", "amount", "status"]);
+    let amount = batch.column("amount").unwrap();
+
+    assert_eq!(kernels::count(amount), 4);
+    assert_eq!(kernels::sum(amount), Some((1299.5, 4)));
+    assert_eq!(kernels::min(amount), Some(FieldValue::Float(50.0)));
+    assert_eq!(kernels::max(amount), Some(FieldValue::Float(999.0)));
+    assert_eq!(
+        kernels::max(batch.column("id").unwrap()),
+        Some(FieldValue::Integer(5))
+    );
+
+    // Non-numeric columns have no extremum kernel
+    assert!(kernels::min(batch.column("status").unwrap()).is_none());
+
+    let empty = ColumnarBatch::from_records(&records, &["amount"])
+        .filter(&Bitmap::new(records.len(), false));
+    assert_eq!(kernels::count(empty.column("amount").unwrap()), 0);
+    assert_eq!(
+        kernels::min(empty.column("amount").unwrap()),
+        Some(FieldValue::Null)
+    );
+}
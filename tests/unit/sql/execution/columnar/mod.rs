@@ -0,0 +1,5 @@
+//! Columnar Execution Tests
+//!
+//! Tests for columnar batches, vectorized kernels and batch execution of stateless queries.
+
+pub mod columnar_execution_test;
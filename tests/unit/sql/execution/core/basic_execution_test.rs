@@ -7,7 +7,7 @@ simple SELECT queries, and basic field selection.
 
 use ferrisstreams::ferris::serialization::JsonFormat;
 use ferrisstreams::ferris::sql::ast::{
-    Expr, LiteralValue, SelectField, StreamSource, StreamingQuery,
+    Expr, LiteralValue, NodeSpan, SelectField, StreamSource, StreamingQuery,
 };
 use ferrisstreams::ferris::sql::execution::{FieldValue, StreamExecutionEngine, StreamRecord};
 use std::collections::HashMap;
@@ -35,7 +35,7 @@ fn create_test_record(
     );
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         timestamp: chrono::Utc::now().timestamp_millis(),
         offset: id,
         partition: 0,
@@ -60,6 +60,7 @@ async fn test_execute_simple_select() {
     let query = StreamingQuery::Select {
         fields: vec![SelectField::Wildcard],
         from: StreamSource::Stream("orders".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         window: None,
@@ -69,6 +70,7 @@ async fn test_execute_simple_select() {
         limit: None,
         emit_mode: None,
         properties: None,
+        span: NodeSpan::default(),
     };
 
     let record = create_test_record(1, 100, 299.99, Some("pending"));
@@ -89,15 +91,16 @@ async fn test_execute_specific_columns() {
     let query = StreamingQuery::Select {
         fields: vec![
             SelectField::Expression {
-                expr: Expr::Column("id".to_string()),
+                expr: Expr::Column("id".to_string(), NodeSpan::default()),
                 alias: None,
             },
             SelectField::Expression {
-                expr: Expr::Column("amount".to_string()),
+                expr: Expr::Column("amount".to_string(), NodeSpan::default()),
                 alias: Some("total".to_string()),
             },
         ],
         from: StreamSource::Stream("orders".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         window: None,
@@ -107,6 +110,7 @@ async fn test_execute_specific_columns() {
         limit: None,
         emit_mode: None,
         properties: None,
+        span: NodeSpan::default(),
     };
 
     let record = create_test_record(1, 100, 299.99, Some("pending"));
@@ -129,15 +133,19 @@ async fn test_execute_with_literals() {
     let query = StreamingQuery::Select {
         fields: vec![
             SelectField::Expression {
-                expr: Expr::Literal(LiteralValue::Integer(42)),
+                expr: Expr::Literal(LiteralValue::Integer(42), NodeSpan::default()),
                 alias: Some("constant".to_string()),
             },
             SelectField::Expression {
-                expr: Expr::Literal(LiteralValue::String("test".to_string())),
+                expr: Expr::Literal(
+                    LiteralValue::String("test".to_string()),
+                    NodeSpan::default(),
+                ),
                 alias: Some("message".to_string()),
             },
         ],
         from: StreamSource::Stream("orders".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         window: None,
@@ -147,6 +155,7 @@ async fn test_execute_with_literals() {
         limit: None,
         emit_mode: None,
         properties: None,
+        span: NodeSpan::default(),
     };
 
     let record = create_test_record(1, 100, 299.99, Some("pending"));
@@ -172,10 +181,11 @@ async fn test_missing_column_returns_null() {
 
     let query = StreamingQuery::Select {
         fields: vec![SelectField::Expression {
-            expr: Expr::Column("nonexistent_column".to_string()),
+            expr: Expr::Column("nonexistent_column".to_string(), NodeSpan::default()),
             alias: None,
         }],
         from: StreamSource::Stream("orders".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         window: None,
@@ -185,6 +195,7 @@ async fn test_missing_column_returns_null() {
         limit: None,
         emit_mode: None,
         properties: None,
+        span: NodeSpan::default(),
     };
 
     let record = create_test_record(1, 100, 299.99, Some("pending"));
@@ -208,6 +219,7 @@ async fn test_multiple_records_processing() {
     let query = StreamingQuery::Select {
         fields: vec![SelectField::Wildcard],
         from: StreamSource::Stream("orders".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         window: None,
@@ -217,6 +229,7 @@ async fn test_multiple_records_processing() {
         limit: None,
         emit_mode: None,
         properties: None,
+        span: NodeSpan::default(),
     };
 
     // Process multiple records
@@ -244,10 +257,11 @@ async fn test_null_value_handling() {
 
     let query = StreamingQuery::Select {
         fields: vec![SelectField::Expression {
-            expr: Expr::Column("status".to_string()),
+            expr: Expr::Column("status".to_string(), NodeSpan::default()),
             alias: None,
         }],
         from: StreamSource::Stream("orders".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         window: None,
@@ -257,6 +271,7 @@ async fn test_null_value_handling() {
         limit: None,
         emit_mode: None,
         properties: None,
+        span: NodeSpan::default(),
     };
 
     let record = create_test_record(1, 100, 299.99, None); // No status (null)
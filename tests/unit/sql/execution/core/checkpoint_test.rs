@@ -0,0 +1,376 @@
+/*!
+# Checkpoint Tests
+
+Tests for periodic checkpoints: configuration, the on-disk store with atomic writes,
+retention and corruption handling, and restoring GROUP BY, UPSERT, window and backend
+state into a new engine.
+*/
+
+use super::shared_test_utils::{drain, order, parse, properties};
+use ferrisstreams::ferris::datasource::file::{FileDataSource, FileFormat, FileSourceConfig};
+use ferrisstreams::ferris::datasource::kafka::reader::KafkaDataReader;
+use ferrisstreams::ferris::datasource::{BatchConfig, BatchStrategy, DataSource, SourceOffset};
+use ferrisstreams::ferris::sql::execution::checkpoint::{
+    Checkpoint, CheckpointStore, OperatorSnapshot,
+};
+use ferrisstreams::ferris::sql::execution::processors::ProcessorContext;
+use ferrisstreams::ferris::sql::execution::state::{MemoryStateBackend, StateOperator};
+use ferrisstreams::ferris::sql::execution::{
+    CheckpointConfig, Checkpointer, FieldValue, StateBackend, StateBackends, StreamExecutionEngine,
+};
+use ferrisstreams::ferris::sql::SqlError;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+use tokio::sync::mpsc;
+
+/// ID, creation time and source offsets, which identify a test checkpoint
+fn summary(checkpoint: &Checkpoint) -> (u64, i64, HashMap<String, SourceOffset>) {
+    (
+        checkpoint.id,
+        checkpoint.created_at,
+        checkpoint.source_offsets.clone(),
+    )
+}
+
+fn checkpoint(id: u64) -> Checkpoint {
+    Checkpoint {
+        id,
+        created_at: 1_700_000_000_000 + id as i64,
+        operators: OperatorSnapshot::default(),
+        source_offsets: HashMap::from([(
+            "orders".to_string(),
+            SourceOffset::File {
+                path: "/data/orders.csv".to_string(),
+                byte_offset: 100 * id,
+                line_number: 10 * id,
+            },
+        )]),
+    }
+}
+
+#[test]
+fn test_config_from_properties() {
+    assert_eq!(
+        CheckpointConfig::from_properties(&HashMap::new()).unwrap(),
+        None
+    );
+
+    let config = CheckpointConfig::from_properties(&properties(&[
+        ("checkpoint.dir", "/var/lib/ferris/checkpoints"),
+        ("checkpoint.interval", "30s"),
+        ("checkpoint.retained", "5"),
+    ]))
+    .unwrap()
+    .unwrap();
+    assert_eq!(config.interval, Duration::from_secs(30));
+    assert_eq!(config.retained, 5);
+
+    let defaults = CheckpointConfig::from_properties(&properties(&[("checkpoint.dir", "/tmp")]))
+        .unwrap()
+        .unwrap();
+    assert_eq!(defaults, CheckpointConfig::new("/tmp"));
+
+    for invalid in [
+        properties(&[("checkpoint.interval", "30s")]),
+        properties(&[("checkpoint.dir", "/tmp"), ("checkpoint.interval", "soon")]),
+        properties(&[("checkpoint.dir", "/tmp"), ("checkpoint.interval", "0s")]),
+        properties(&[("checkpoint.dir", "/tmp"), ("checkpoint.retained", "0")]),
+    ] {
+        assert!(
+            matches!(
+                CheckpointConfig::from_properties(&invalid),
+                Err(SqlError::ConfigurationError { .. })
+            ),
+            "{:?}",
+            invalid
+        );
+    }
+}
+
+#[test]
+fn test_store_write_read_and_prune() {
+    let dir = TempDir::new().unwrap();
+    let store = CheckpointStore::new(dir.path().join("job"));
+    assert!(store.ids().unwrap().is_empty());
+    assert!(store.latest().unwrap().is_none());
+
+    for id in 1..=4 {
+        store.write(&checkpoint(id)).unwrap();
+    }
+    // A temporary file left by an interrupted write is never read
+    std::fs::write(store.directory().join("checkpoint-5.ckpt.tmp"), b"partial").unwrap();
+    assert_eq!(store.ids().unwrap(), vec![1, 2, 3, 4]);
+    assert_eq!(summary(&store.read(2).unwrap()), summary(&checkpoint(2)));
+
+    store.prune(2).unwrap();
+    assert_eq!(store.ids().unwrap(), vec![3, 4]);
+    assert_eq!(std::fs::read_dir(store.directory()).unwrap().count(), 2);
+    assert_eq!(
+        store.latest().unwrap().map(|c| summary(&c)),
+        Some(summary(&checkpoint(4)))
+    );
+}
+
+#[test]
+fn test_corrupt_checkpoint_falls_back_to_previous() {
+    let dir = TempDir::new().unwrap();
+    let store = CheckpointStore::new(dir.path());
+    store.write(&checkpoint(1)).unwrap();
+    let path = store.write(&checkpoint(2)).unwrap();
+
+    let mut bytes = std::fs::read(&path).unwrap();
+    let middle = bytes.len() / 2;
+    bytes[middle] ^= 0xff;
+    std::fs::write(&path, bytes).unwrap();
+
+    let error = store.read(2).unwrap_err();
+    assert!(error.to_string().contains("CHECKPOINT-001"), "{}", error);
+    assert_eq!(
+        store.latest().unwrap().map(|c| summary(&c)),
+        Some(summary(&checkpoint(1)))
+    );
+}
+
+#[tokio::test]
+async fn test_group_by_and_upsert_state_restore_into_new_engine() {
+    let dir = TempDir::new().unwrap();
+    let checkpointer = Checkpointer::new(CheckpointConfig::new(dir.path()), "totals").unwrap();
+    let totals = parse(
+        "SELECT customer_id, COUNT(*) AS orders, SUM(amount) AS total \
+         FROM orders GROUP BY customer_id",
+    );
+    let upsert = parse("UPSERT INTO vip (customer_id) VALUES (customer_id)");
+    let vip_orders =
+        parse("SELECT amount FROM orders WHERE customer_id IN (SELECT customer_id FROM vip)");
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut engine = StreamExecutionEngine::new(tx);
+    for (customer_id, amount) in [(1, 10.0), (2, 5.0), (1, 2.5)] {
+        engine
+            .execute_with_record(&totals, order(customer_id, amount))
+            .await
+            .unwrap();
+    }
+    engine
+        .execute_with_record(&upsert, order(2, 0.0))
+        .await
+        .unwrap();
+    let positions = HashMap::from([(
+        "orders".to_string(),
+        SourceOffset::File {
+            path: "/data/orders.csv".to_string(),
+            byte_offset: 64,
+            line_number: 4,
+        },
+    )]);
+    assert_eq!(checkpointer.checkpoint(&engine, &positions).unwrap(), 1);
+    drop(engine);
+    drain(&mut rx);
+
+    // A new engine, with newly parsed queries, continues from the checkpoint
+    let checkpointer = Checkpointer::new(CheckpointConfig::new(dir.path()), "totals").unwrap();
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut engine = StreamExecutionEngine::new(tx);
+    assert_eq!(checkpointer.restore(&mut engine).unwrap(), Some(1));
+    assert_eq!(
+        checkpointer.store().read(1).unwrap().source_offsets,
+        positions
+    );
+
+    let totals = parse(
+        "SELECT customer_id, COUNT(*) AS orders, SUM(amount) AS total \
+         FROM orders GROUP BY customer_id",
+    );
+    engine
+        .execute_with_record(&totals, order(1, 7.5))
+        .await
+        .unwrap();
+    let latest = drain(&mut rx).pop().unwrap();
+    assert_eq!(latest.fields.get("orders"), Some(&FieldValue::Integer(3)));
+    assert_eq!(latest.fields.get("total"), Some(&FieldValue::Float(20.0)));
+
+    for customer_id in [1, 2] {
+        engine
+            .execute_with_record(&vip_orders, order(customer_id, customer_id as f64))
+            .await
+            .unwrap();
+    }
+    let amounts: Vec<_> = drain(&mut rx)
+        .iter()
+        .map(|r| r.fields.get("amount").cloned())
+        .collect();
+    assert_eq!(amounts, vec![Some(FieldValue::Float(2.0))]);
+
+    // Numbering continues after the restored checkpoint
+    assert_eq!(checkpointer.checkpoint(&engine, &positions).unwrap(), 2);
+}
+
+#[tokio::test]
+async fn test_window_buffer_restore() {
+    let dir = TempDir::new().unwrap();
+    let checkpointer = Checkpointer::new(CheckpointConfig::new(dir.path()), "windows").unwrap();
+    let sql = "SELECT customer_id, SUM(amount) AS total FROM orders \
+               GROUP BY customer_id WINDOW TUMBLING(5s)";
+    let at = |amount: f64, timestamp: i64| {
+        let mut record = order(1, amount);
+        record.timestamp = timestamp;
+        record
+    };
+
+    let (tx, _rx) = mpsc::unbounded_channel();
+    let mut engine = StreamExecutionEngine::new(tx);
+    engine
+        .start_query_execution("totals".to_string(), parse(sql))
+        .await
+        .unwrap();
+    for (amount, timestamp) in [(1.0, 1000), (2.0, 2000)] {
+        engine
+            .process_stream_record("orders", at(amount, timestamp))
+            .await
+            .unwrap();
+    }
+    checkpointer.checkpoint(&engine, &HashMap::new()).unwrap();
+    drop(engine);
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut engine = StreamExecutionEngine::new(tx);
+    checkpointer.restore(&mut engine).unwrap();
+    engine
+        .start_query_execution("totals".to_string(), parse(sql))
+        .await
+        .unwrap();
+    for (amount, timestamp) in [(4.0, 3000), (0.0, 12000)] {
+        let _ = engine
+            .process_stream_record("orders", at(amount, timestamp))
+            .await;
+    }
+    let totals: Vec<_> = drain(&mut rx)
+        .iter()
+        .map(|r| r.fields.get("total").cloned())
+        .collect();
+    assert_eq!(totals.first(), Some(&Some(FieldValue::Float(7.0))));
+}
+
+#[tokio::test]
+async fn test_backend_contents_restored() {
+    let dir = TempDir::new().unwrap();
+    let checkpointer = Checkpointer::new(CheckpointConfig::new(dir.path()), "vip").unwrap();
+    let upsert = parse("UPSERT INTO vip (customer_id) VALUES (customer_id)");
+    let namespace = StateOperator::Merge.namespace("vip");
+
+    let backend: Arc<dyn StateBackend> = Arc::new(MemoryStateBackend::new());
+    let (tx, _rx) = mpsc::unbounded_channel();
+    let mut engine = StreamExecutionEngine::new(tx);
+    engine.set_state_backends(
+        StateBackends::new().with_backend(StateOperator::Merge, backend.clone()),
+    );
+    for customer_id in [1, 2] {
+        engine
+            .execute_with_record(&upsert, order(customer_id, 0.0))
+            .await
+            .unwrap();
+    }
+    checkpointer.checkpoint(&engine, &HashMap::new()).unwrap();
+    let stored = backend.scan_prefix(&namespace, b"").unwrap();
+    assert_eq!(stored.len(), 2);
+
+    // Writes after the checkpoint are rolled back by the restore
+    engine
+        .execute_with_record(&upsert, order(3, 0.0))
+        .await
+        .unwrap();
+    let restored: Arc<dyn StateBackend> = Arc::new(MemoryStateBackend::new());
+    restored.put(&namespace, b"stale", b"entry").unwrap();
+    let (tx, _rx) = mpsc::unbounded_channel();
+    let mut engine = StreamExecutionEngine::new(tx);
+    engine.set_state_backends(
+        StateBackends::new().with_backend(StateOperator::Merge, restored.clone()),
+    );
+    checkpointer.restore(&mut engine).unwrap();
+    assert_eq!(restored.scan_prefix(&namespace, b"").unwrap(), stored);
+}
+
+#[tokio::test]
+async fn test_file_source_resumes_from_position() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("orders.csv");
+    std::fs::write(&path, "id,name\n1,Alice\n2,Bob\n3,Charlie\n4,David\n5,Eve").unwrap();
+    let batches = BatchConfig {
+        strategy: BatchStrategy::FixedSize(2),
+        ..BatchConfig::default()
+    };
+
+    let mut source = FileDataSource::new();
+    source
+        .initialize(
+            FileSourceConfig::new(path.to_string_lossy().to_string(), FileFormat::Csv).into(),
+        )
+        .await
+        .unwrap();
+    let mut reader = source
+        .create_reader_with_batch_config(batches.clone())
+        .await
+        .unwrap();
+    assert_eq!(reader.read().await.unwrap().len(), 2);
+    let position = reader.position().unwrap();
+    assert!(matches!(
+        position,
+        SourceOffset::File { line_number: 3, .. }
+    ));
+
+    // A new reader continues after the records read before the position was taken
+    let mut resumed = source
+        .create_reader_with_batch_config(batches)
+        .await
+        .unwrap();
+    resumed.seek(position).await.unwrap();
+    let mut ids = Vec::new();
+    while let Ok(batch) = resumed.read().await {
+        if batch.is_empty() {
+            break;
+        }
+        ids.extend(batch.iter().map(|r| r.fields.get("id").cloned()));
+    }
+    assert_eq!(
+        ids,
+        [3, 4, 5].map(|id| Some(FieldValue::Integer(id))).to_vec()
+    );
+}
+
+#[tokio::test]
+async fn test_kafka_source_resumes_from_checkpointed_offsets() {
+    let dir = TempDir::new().unwrap();
+    let checkpointer = Checkpointer::new(CheckpointConfig::new(dir.path()), "orders").unwrap();
+    let offsets = SourceOffset::KafkaPartitions {
+        offsets: HashMap::from([(0, 42), (1, 7), (2, 0)]),
+    };
+    checkpointer
+        .store()
+        .write(&Checkpoint {
+            id: 1,
+            created_at: 1_700_000_000_000,
+            operators: OperatorSnapshot::default(),
+            source_offsets: HashMap::from([("orders".to_string(), offsets.clone())]),
+        })
+        .unwrap();
+
+    // Creating the consumer and assigning partitions needs no running broker
+    let reader = KafkaDataReader::new_json("localhost:9092", "orders".to_string(), "resume", None)
+        .await
+        .unwrap();
+    let mut context = ProcessorContext::new("orders");
+    context
+        .data_readers
+        .insert("orders".to_string(), Box::new(reader));
+
+    let (tx, _rx) = mpsc::unbounded_channel();
+    let mut engine = StreamExecutionEngine::new(tx);
+    assert_eq!(checkpointer.restore(&mut engine).unwrap(), Some(1));
+    checkpointer.resume_sources(&mut context).await;
+
+    assert_eq!(context.get_source_position("orders"), Some(&offsets));
+    let reader = context.data_readers.get_mut("orders").unwrap();
+    assert_eq!(reader.position(), Some(offsets));
+}
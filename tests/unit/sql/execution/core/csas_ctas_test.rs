@@ -16,7 +16,7 @@ fn create_test_record(id: i64, amount: f64, status: &str) -> StreamRecord {
     fields.insert("status".to_string(), FieldValue::String(status.to_string()));
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         timestamp: chrono::Utc::now().timestamp_millis(),
         offset: id,
         partition: 0,
@@ -43,6 +43,7 @@ mod tests {
                 as_select,
                 properties,
                 emit_mode: _,
+                ..
             } => {
                 assert_eq!(name, "high_value_orders");
                 assert!(columns.is_none());
@@ -137,12 +138,15 @@ mod tests {
             StreamingQuery::CreateTable {
                 name,
                 columns,
+                constraints,
                 as_select,
                 properties,
                 emit_mode: _,
+                ..
             } => {
                 assert_eq!(name, "customer_totals");
                 assert!(columns.is_none());
+                assert!(constraints.is_empty());
                 assert!(properties.is_empty());
 
                 // Check the underlying SELECT query
@@ -467,6 +471,7 @@ mod tests {
                 into_clause,
                 properties,
                 emit_mode: _,
+                ..
             } => {
                 assert_eq!(name, "enriched_orders");
                 assert!(columns.is_none());
@@ -521,13 +526,16 @@ mod tests {
             StreamingQuery::CreateTableInto {
                 name,
                 columns,
+                constraints,
                 as_select,
                 into_clause,
                 properties,
                 emit_mode: _,
+                ..
             } => {
                 assert_eq!(name, "analytics_summary");
                 assert!(columns.is_none());
+                assert!(constraints.is_empty());
                 assert_eq!(into_clause.sink_name, "clickhouse_sink");
                 assert!(properties.source_config.is_none()); // Config is in SELECT WITH
 
@@ -7,7 +7,7 @@ invalid operations, and edge cases.
 
 use ferrisstreams::ferris::serialization::JsonFormat;
 use ferrisstreams::ferris::sql::ast::{
-    BinaryOperator, Expr, LiteralValue, SelectField, StreamSource, StreamingQuery,
+    BinaryOperator, Expr, LiteralValue, NodeSpan, SelectField, StreamSource, StreamingQuery,
 };
 use ferrisstreams::ferris::sql::execution::{FieldValue, StreamExecutionEngine, StreamRecord};
 use std::collections::HashMap;
@@ -33,7 +33,7 @@ fn create_test_record(
     );
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         timestamp: chrono::Utc::now().timestamp_millis(),
         offset: id,
         partition: 0,
@@ -49,13 +49,18 @@ async fn test_arithmetic_error_handling() {
     let query = StreamingQuery::Select {
         fields: vec![SelectField::Expression {
             expr: Expr::BinaryOp {
-                left: Box::new(Expr::Column("status".to_string())), // String field
+                left: Box::new(Expr::Column("status".to_string(), NodeSpan::default())), // String field
                 op: BinaryOperator::Add,
-                right: Box::new(Expr::Literal(LiteralValue::Integer(10))),
+                right: Box::new(Expr::Literal(
+                    LiteralValue::Integer(10),
+                    NodeSpan::default(),
+                )),
+                span: NodeSpan::default(),
             },
             alias: Some("invalid_operation".to_string()),
         }],
         from: StreamSource::Stream("orders".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         window: None,
@@ -65,6 +70,7 @@ async fn test_arithmetic_error_handling() {
         limit: None,
         emit_mode: None,
         properties: None,
+        span: NodeSpan::default(),
     };
 
     let record = create_test_record(1, 100, 299.99, Some("pending"));
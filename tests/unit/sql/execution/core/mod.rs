@@ -4,6 +4,17 @@
 //! basic query execution, error handling, and core features.
 
 pub mod basic_execution_test;
+pub mod checkpoint_test;
 pub mod csas_ctas_test;
 pub mod error_handling_test;
+pub mod prepared_statement_test;
+pub mod record_fields_test;
+pub mod savepoint_test;
+pub mod shared_test_utils;
+pub mod schema_enforcement_test;
+pub mod spill_test;
+pub mod state_backed_subquery_test;
+pub mod state_backend_test;
+pub mod state_ttl_test;
 pub mod subquery_test;
+pub mod table_constraints_test;
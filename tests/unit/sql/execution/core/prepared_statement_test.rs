@@ -0,0 +1,229 @@
+/*!
+# Prepared Statement Tests
+
+Tests for `$1` / `:name` placeholders, `PreparedStatement` binding with type checks,
+and `-- Parameter:` declarations in SQL application files.
+*/
+
+use ferrisstreams::ferris::schema::{FieldDefinition, Schema};
+use ferrisstreams::ferris::sql::app_parser::SqlApplicationParser;
+use ferrisstreams::ferris::sql::ast::{DataType, QueryParameter};
+use ferrisstreams::ferris::sql::execution::{
+    FieldValue, PreparedStatement, StreamExecutionEngine, StreamRecord,
+};
+use ferrisstreams::ferris::sql::formatter::format_query;
+use ferrisstreams::ferris::sql::semantic_analyzer::SchemaCatalog;
+use ferrisstreams::ferris::sql::{SqlError, StreamingSqlParser};
+use std::collections::HashMap;
+use tokio::sync::mpsc;
+
+fn orders_catalog() -> SchemaCatalog {
+    let mut catalog = SchemaCatalog::new();
+    catalog.register_schema(
+        "orders",
+        Schema::new(vec![
+            FieldDefinition::required("id".to_string(), DataType::BigInt),
+            FieldDefinition::required("customer".to_string(), DataType::String),
+            FieldDefinition::optional("amount".to_string(), DataType::Float),
+        ]),
+    );
+    catalog
+}
+
+fn prepare(sql: &str) -> PreparedStatement {
+    let query = StreamingSqlParser::new()
+        .parse(sql)
+        .expect("query should parse");
+    PreparedStatement::new(query)
+        .expect("query should prepare")
+        .with_schema_catalog(&orders_catalog())
+}
+
+fn order(id: i64, customer: &str, amount: f64) -> StreamRecord {
+    let mut fields = HashMap::new();
+    fields.insert("id".to_string(), FieldValue::Integer(id));
+    fields.insert(
+        "customer".to_string(),
+        FieldValue::String(customer.to_string()),
+    );
+    fields.insert("amount".to_string(), FieldValue::Float(amount));
+    StreamRecord {
+        fields: fields.into(),
+        headers: HashMap::new(),
+        timestamp: 1640995200000,
+        offset: 0,
+        partition: 0,
+    }
+}
+
+#[test]
+fn test_parse_and_format_placeholders() {
+    let statement = prepare("SELECT id FROM orders WHERE amount > $2 AND customer = $1");
+    assert_eq!(
+        statement
+            .parameters()
+            .iter()
+            .map(|p| (p.parameter.clone(), p.data_type.clone()))
+            .collect::<Vec<_>>(),
+        vec![
+            (QueryParameter::Positional(1), Some(DataType::String)),
+            (QueryParameter::Positional(2), Some(DataType::Float)),
+        ]
+    );
+    assert!(format_query(statement.query()).contains("amount > $2"));
+
+    let named = prepare("SELECT id FROM orders WHERE customer = :customer");
+    assert_eq!(
+        named.parameters()[0].parameter,
+        QueryParameter::Named("customer".to_string())
+    );
+
+    let parser = StreamingSqlParser::new();
+    assert!(parser.parse("SELECT id FROM orders WHERE id = $0").is_err());
+    assert!(parser.parse("SELECT id FROM orders WHERE id = $").is_err());
+
+    let mixed = parser
+        .parse("SELECT id FROM orders WHERE id = $1 AND customer = :customer")
+        .unwrap();
+    assert!(PreparedStatement::new(mixed).is_err());
+}
+
+#[test]
+fn test_bind_checks_types_and_counts() {
+    let statement = prepare("SELECT id FROM orders WHERE customer = $1 AND amount > $2");
+
+    let bound = statement
+        .bind(&[
+            FieldValue::String("acme".to_string()),
+            FieldValue::Integer(100),
+        ])
+        .expect("values should bind");
+    assert_eq!(
+        format_query(&bound),
+        format_query(
+            &StreamingSqlParser::new()
+                .parse("SELECT id FROM orders WHERE customer = 'acme' AND amount > 100.0")
+                .unwrap()
+        )
+    );
+
+    let mismatch = statement.bind(&[FieldValue::Integer(1), FieldValue::Float(1.0)]);
+    assert!(matches!(mismatch, Err(SqlError::TypeError { .. })));
+
+    assert!(statement
+        .bind(&[FieldValue::String("acme".to_string())])
+        .is_err());
+    assert!(statement
+        .bind_named(&HashMap::from([(
+            "customer".to_string(),
+            FieldValue::String("acme".to_string())
+        )]))
+        .is_err());
+
+    // NULL binds to any type
+    assert!(statement
+        .bind(&[FieldValue::Null, FieldValue::Null])
+        .is_ok());
+}
+
+#[test]
+fn test_declared_parameter_type_overrides_inference() {
+    let statement = prepare("SELECT id FROM orders WHERE id = :id")
+        .with_parameter_type(QueryParameter::Named("id".to_string()), DataType::String);
+    assert_eq!(statement.parameters()[0].data_type, Some(DataType::String));
+    assert!(statement
+        .bind_named(&HashMap::from([("id".to_string(), FieldValue::Integer(7))]))
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_execute_prepared_statement() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut engine = StreamExecutionEngine::new(tx);
+    let statement = engine
+        .prepare("SELECT id FROM orders WHERE amount > :min_amount")
+        .expect("query should prepare");
+
+    // An unbound placeholder fails at execution
+    assert!(engine
+        .execute_with_record(statement.query(), order(1, "acme", 50.0))
+        .await
+        .is_err());
+
+    let bound = statement
+        .bind_named(&HashMap::from([(
+            "min_amount".to_string(),
+            FieldValue::Float(100.0),
+        )]))
+        .unwrap();
+    engine
+        .execute_with_record(&bound, order(1, "acme", 50.0))
+        .await
+        .unwrap();
+    engine
+        .execute_with_record(&bound, order(2, "acme", 150.0))
+        .await
+        .unwrap();
+
+    let mut ids = Vec::new();
+    while let Ok(record) = rx.try_recv() {
+        ids.push(record.fields.get("id").cloned());
+    }
+    assert_eq!(ids, vec![Some(FieldValue::Integer(2))]);
+}
+
+#[test]
+fn test_application_parameter_declarations() {
+    let app = SqlApplicationParser::new()
+        .parse_application(
+            "-- SQL Application: Order Alerts\n\
+             -- Version: 1.0.0\n\
+             -- Parameter: min_amount DECIMAL(10,2)\n\
+             -- Parameter: region VARCHAR\n\
+             \n\
+             -- Name: big_orders\n\
+             SELECT id FROM orders WHERE amount > :min_amount AND region = :region;\n",
+        )
+        .expect("application should parse");
+    assert_eq!(app.metadata.parameters.len(), 2);
+    assert_eq!(app.metadata.parameters[0].name, "min_amount");
+    assert_eq!(app.metadata.parameters[0].data_type, "DECIMAL(10,2)");
+
+    let bound = app
+        .bind_parameters(&HashMap::from([
+            ("min_amount".to_string(), FieldValue::Integer(100)),
+            ("region".to_string(), FieldValue::String("eu".to_string())),
+        ]))
+        .expect("values should bind");
+    assert!(!bound.statements[0].sql.contains(':'));
+    assert!(bound.statements[0].sql.contains("'eu'"));
+
+    // Declared types are enforced and every parameter needs a value
+    assert!(app
+        .bind_parameters(&HashMap::from([
+            (
+                "min_amount".to_string(),
+                FieldValue::String("x".to_string())
+            ),
+            ("region".to_string(), FieldValue::String("eu".to_string())),
+        ]))
+        .is_err());
+    assert!(app
+        .bind_parameters(&HashMap::from([(
+            "region".to_string(),
+            FieldValue::String("eu".to_string())
+        )]))
+        .is_err());
+
+    let unknown_type = SqlApplicationParser::new().parse_application(
+        "-- SQL Application: Broken\n-- Parameter: limit MONEY\nSELECT id FROM orders;\n",
+    );
+    assert!(unknown_type.is_err());
+
+    let undeclared = SqlApplicationParser::new()
+        .parse_application("-- SQL Application: Open\nSELECT id FROM orders WHERE id = :id;\n")
+        .unwrap();
+    assert!(undeclared
+        .prepare_statement(&undeclared.statements[0])
+        .is_err());
+}
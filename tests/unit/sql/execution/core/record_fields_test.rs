@@ -0,0 +1,187 @@
+/*!
+# Tests for schema-indexed record fields
+
+Records built through a `RecordSchemaCache` share one copy of their field names, while
+`RecordFields` keeps behaving like the `HashMap` it replaces.
+*/
+
+use ferrisstreams::ferris::serialization::JsonCodec;
+use ferrisstreams::ferris::sql::execution::{
+    FieldValue, RecordFields, RecordSchema, RecordSchemaCache, StreamRecord,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+fn order(cache: &RecordSchemaCache, id: i64, status: &str) -> RecordFields {
+    let mut fields = cache.builder();
+    fields.push("id", FieldValue::Integer(id));
+    fields.push("status", FieldValue::String(status.to_string()));
+    fields.finish()
+}
+
+#[test]
+fn test_hashmap_compatible_access() {
+    let mut fields = RecordFields::new();
+    assert!(fields.is_empty());
+    assert_eq!(fields.insert("id", FieldValue::Integer(1)), None);
+    assert_eq!(
+        fields.insert("id".to_string(), FieldValue::Integer(2)),
+        Some(FieldValue::Integer(1))
+    );
+    fields.insert("name", FieldValue::String("a".to_string()));
+
+    assert_eq!(fields.len(), 2);
+    assert_eq!(fields["id"], FieldValue::Integer(2));
+    assert!(fields.contains_key("name"));
+    assert!(fields.get("missing").is_none());
+
+    if let Some(FieldValue::Integer(id)) = fields.get_mut("id") {
+        *id += 1;
+    }
+    assert_eq!(fields.get("id"), Some(&FieldValue::Integer(3)));
+
+    assert_eq!(
+        fields.remove("name"),
+        Some(FieldValue::String("a".to_string()))
+    );
+    assert!(!fields.contains_key("name"));
+    assert_eq!(fields.len(), 1);
+    // A removed field keeps its schema slot without counting as present
+    assert_eq!(fields.schema().len(), 2);
+    assert_eq!(fields.keys().collect::<Vec<_>>(), vec!["id"]);
+}
+
+#[test]
+fn test_iteration_follows_schema_order() {
+    let schema = Arc::new(RecordSchema::new(["c", "a", "b"]));
+    let mut fields = RecordFields::from_values(
+        schema,
+        vec![
+            FieldValue::Integer(3),
+            FieldValue::Integer(1),
+            FieldValue::Integer(2),
+        ],
+    );
+    fields.retain(|name, _| name != "a");
+
+    let names: Vec<&String> = fields.keys().collect();
+    assert_eq!(names, vec!["c", "b"]);
+    assert_eq!(
+        fields.values().cloned().collect::<Vec<_>>(),
+        vec![FieldValue::Integer(3), FieldValue::Integer(2)]
+    );
+    assert_eq!(
+        fields.into_iter().collect::<Vec<_>>(),
+        vec![
+            ("c".to_string(), FieldValue::Integer(3)),
+            ("b".to_string(), FieldValue::Integer(2)),
+        ]
+    );
+}
+
+#[test]
+fn test_equality_ignores_layout() {
+    let mut map = HashMap::new();
+    map.insert("id".to_string(), FieldValue::Integer(1));
+    map.insert("status".to_string(), FieldValue::String("new".to_string()));
+
+    let from_map = RecordFields::from(map.clone());
+    let cache = RecordSchemaCache::new();
+    cache.builder().finish();
+    let mut reversed = cache.builder();
+    reversed.push("status", FieldValue::String("new".to_string()));
+    reversed.push("id", FieldValue::Integer(1));
+    let reversed = reversed.finish();
+
+    assert_eq!(from_map, reversed);
+    assert_eq!(reversed, map);
+    assert_eq!(HashMap::from(reversed), map);
+    assert_ne!(from_map, order(&cache, 2, "new"));
+}
+
+#[test]
+fn test_builder_shares_schema_between_records() {
+    let cache = RecordSchemaCache::new();
+    let first = order(&cache, 1, "new");
+    let second = order(&cache, 2, "shipped");
+    assert!(Arc::ptr_eq(first.schema(), second.schema()));
+    assert!(Arc::ptr_eq(first.schema(), &cache.current()));
+
+    // Fields can arrive in any order and still fill the shared slots
+    let mut reordered = cache.builder();
+    reordered.push("status", FieldValue::String("new".to_string()));
+    reordered.push("id", FieldValue::Integer(3));
+    let reordered = reordered.finish();
+    assert!(Arc::ptr_eq(first.schema(), reordered.schema()));
+
+    // Clones of the cache share the schema too
+    let clone = cache.clone();
+    assert!(Arc::ptr_eq(
+        first.schema(),
+        order(&clone, 4, "new").schema()
+    ));
+}
+
+#[test]
+fn test_new_fields_extend_schema_without_touching_earlier_records() {
+    let cache = RecordSchemaCache::new();
+    let first = order(&cache, 1, "new");
+
+    let mut wider = cache.builder();
+    wider.push("id", FieldValue::Integer(2));
+    wider.push("status", FieldValue::String("new".to_string()));
+    wider.push("amount", FieldValue::Float(9.5));
+    let wider = wider.finish();
+
+    assert_eq!(first.schema().names(), ["id", "status"]);
+    assert_eq!(wider.schema().names(), ["id", "status", "amount"]);
+    assert!(Arc::ptr_eq(wider.schema(), &cache.current()));
+
+    // The narrower record is still dense enough to reuse the wider schema
+    let next = order(&cache, 3, "new");
+    assert!(Arc::ptr_eq(wider.schema(), next.schema()));
+    assert_eq!(next.len(), 2);
+    assert!(!next.contains_key("amount"));
+
+    // Inserting into one record copies the schema instead of changing a shared one
+    let mut edited = next.clone();
+    edited.insert("note", FieldValue::Null);
+    assert_eq!(next.schema().len(), 3);
+    assert_eq!(edited.schema().len(), 4);
+}
+
+#[test]
+fn test_sparse_record_gets_compact_schema() {
+    let cache = RecordSchemaCache::new();
+    let mut wide = cache.builder();
+    for name in ["a", "b", "c", "d", "e"] {
+        wide.push(name, FieldValue::Integer(0));
+    }
+    wide.finish();
+
+    let mut narrow = cache.builder();
+    narrow.push("b", FieldValue::Integer(1));
+    let narrow = narrow.finish();
+    assert_eq!(narrow.schema().names(), ["b"]);
+    assert!(Arc::ptr_eq(narrow.schema(), &cache.current()));
+}
+
+#[test]
+fn test_json_codec_decodes_into_shared_schema() {
+    let codec = JsonCodec::new();
+    let first = codec
+        .deserialize_fields(br#"{"id": 1, "status": "new"}"#)
+        .unwrap();
+    let second = codec
+        .deserialize_fields(br#"{"status": "shipped", "id": 2}"#)
+        .unwrap();
+
+    assert!(Arc::ptr_eq(first.schema(), second.schema()));
+    assert_eq!(second["id"], FieldValue::Integer(2));
+    assert_eq!(second["status"], FieldValue::String("shipped".to_string()));
+
+    let record = StreamRecord::new(second);
+    let bytes = codec.serialize_fields(&record.fields).unwrap();
+    let decoded = codec.deserialize_fields(&bytes).unwrap();
+    assert_eq!(decoded["id"], FieldValue::Integer(2));
+}
@@ -0,0 +1,181 @@
+/*!
+# Savepoint Tests
+
+Tests for savepoints: stable operator IDs and signatures, writing, reading and listing
+savepoints, restoring state into a changed but compatible query and rejecting
+incompatible queries with a diff.
+*/
+
+use super::shared_test_utils::{drain, order, parse};
+use ferrisstreams::ferris::datasource::SourceOffset;
+use ferrisstreams::ferris::sql::execution::savepoint::{
+    list_savepoints, stateful_operators, OperatorKind,
+};
+use ferrisstreams::ferris::sql::execution::{FieldValue, Savepoint, StreamExecutionEngine};
+use std::collections::HashMap;
+use tempfile::TempDir;
+use tokio::sync::mpsc;
+
+const TOTALS: &str = "SELECT customer_id, COUNT(*) AS orders, SUM(amount) AS total \
+                      FROM orders GROUP BY customer_id";
+
+fn positions() -> HashMap<String, SourceOffset> {
+    HashMap::from([(
+        "orders".to_string(),
+        SourceOffset::File {
+            path: "/data/orders.csv".to_string(),
+            byte_offset: 64,
+            line_number: 4,
+        },
+    )])
+}
+
+/// Savepoint of the TOTALS job after three orders of two customers
+async fn totals_savepoint() -> Savepoint {
+    let query = parse(TOTALS);
+    let (tx, _rx) = mpsc::unbounded_channel();
+    let mut engine = StreamExecutionEngine::new(tx);
+    for (customer_id, amount) in [(1, 10.0), (2, 5.0), (1, 2.5)] {
+        engine
+            .execute_with_record(&query, order(customer_id, amount))
+            .await
+            .unwrap();
+    }
+    Savepoint::take("totals", "1.0", &query, &engine, &positions()).unwrap()
+}
+
+#[test]
+fn test_operator_ids_ignore_projection_and_filters() {
+    let operators = stateful_operators(&parse(TOTALS));
+    assert_eq!(operators.len(), 1);
+    assert_eq!(operators[0].id, "group_by:orders");
+    assert_eq!(operators[0].kind, OperatorKind::GroupBy);
+
+    let changed = stateful_operators(&parse(
+        "SELECT customer_id, customer_id * 10 AS scaled, SUM(amount) AS total, \
+         COUNT(*) AS orders FROM orders WHERE amount > 0 GROUP BY customer_id",
+    ));
+    assert_eq!(
+        (&changed[0].id, &changed[0].signature),
+        (&operators[0].id, &operators[0].signature)
+    );
+
+    let windowed = stateful_operators(&parse(
+        "SELECT customer_id, SUM(amount) AS total FROM orders \
+         GROUP BY customer_id WINDOW TUMBLING(5s)",
+    ));
+    assert_eq!(windowed[0].id, "window:orders");
+    assert_eq!(windowed[0].kind, OperatorKind::Window);
+
+    let upsert = stateful_operators(&parse("UPSERT INTO vip (customer_id) VALUES (customer_id)"));
+    assert_eq!(upsert[0].id, "merge:vip");
+    assert!(stateful_operators(&parse("SELECT * FROM orders")).is_empty());
+}
+
+#[tokio::test]
+async fn test_write_read_and_list() {
+    let dir = TempDir::new().unwrap();
+    let savepoint = totals_savepoint().await;
+    let path = savepoint.write(dir.path()).unwrap();
+    assert!(path.starts_with(dir.path().join("totals")));
+
+    let read = Savepoint::read(&path).unwrap();
+    assert_eq!(
+        (&read.job, &read.version, read.created_at, &read.query),
+        (
+            &savepoint.job,
+            &savepoint.version,
+            savepoint.created_at,
+            &savepoint.query
+        )
+    );
+    assert_eq!(read.operators, savepoint.operators);
+    assert_eq!(read.source_offsets, positions());
+
+    let listed = list_savepoints(dir.path(), None).unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].path, path);
+    assert_eq!(listed[0].operators, vec!["group_by:orders".to_string()]);
+    assert!(list_savepoints(dir.path(), Some("other"))
+        .unwrap()
+        .is_empty());
+
+    let mut bytes = std::fs::read(&path).unwrap();
+    let middle = bytes.len() / 2;
+    bytes[middle] ^= 0xff;
+    std::fs::write(&path, bytes).unwrap();
+    let error = Savepoint::read(&path).unwrap_err();
+    assert!(error.to_string().contains("SAVEPOINT-001"), "{}", error);
+}
+
+#[tokio::test]
+async fn test_restore_after_adding_projection_column() {
+    let savepoint = totals_savepoint().await;
+    let upgraded = parse(
+        "SELECT customer_id, customer_id * 10 AS scaled, COUNT(*) AS orders, \
+         SUM(amount) AS total FROM orders GROUP BY customer_id",
+    );
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut engine = StreamExecutionEngine::new(tx);
+    savepoint.restore(&upgraded, &mut engine).unwrap();
+    engine
+        .execute_with_record(&upgraded, order(1, 7.5))
+        .await
+        .unwrap();
+
+    let latest = drain(&mut rx).pop().unwrap();
+    assert_eq!(latest.fields.get("scaled"), Some(&FieldValue::Integer(10)));
+    assert_eq!(latest.fields.get("orders"), Some(&FieldValue::Integer(3)));
+    assert_eq!(latest.fields.get("total"), Some(&FieldValue::Float(20.0)));
+}
+
+#[tokio::test]
+async fn test_incompatible_query_rejected_with_diff() {
+    let savepoint = totals_savepoint().await;
+
+    let regrouped = parse(
+        "SELECT region, COUNT(*) AS orders, SUM(amount) AS total \
+         FROM orders GROUP BY region",
+    );
+    let (tx, _rx) = mpsc::unbounded_channel();
+    let mut engine = StreamExecutionEngine::new(tx);
+    let message = savepoint
+        .restore(&regrouped, &mut engine)
+        .unwrap_err()
+        .to_string();
+    assert!(message.contains("SAVEPOINT-002"), "{}", message);
+    assert!(
+        message.contains("group_by:orders: state does not fit the query"),
+        "{}",
+        message
+    );
+    assert!(message.contains("- GROUP BY customer_id"), "{}", message);
+    assert!(message.contains("+ GROUP BY region"), "{}", message);
+
+    // An aggregate without a saved accumulator cannot continue from the savepoint
+    let new_aggregate = parse(
+        "SELECT customer_id, COUNT(*) AS orders, MAX(amount) AS biggest \
+         FROM orders GROUP BY customer_id",
+    );
+    let message = savepoint
+        .check_compatible(&new_aggregate)
+        .unwrap_err()
+        .to_string();
+    assert!(message.contains("+ MAX(amount) AS biggest"), "{}", message);
+
+    let stateless = parse("SELECT customer_id, amount FROM orders");
+    let message = savepoint
+        .check_compatible(&stateless)
+        .unwrap_err()
+        .to_string();
+    assert!(
+        message.contains("group_by:orders: not in the query, its state would be lost"),
+        "{}",
+        message
+    );
+
+    // Dropping an aggregate keeps the state of the others
+    let fewer = parse("SELECT customer_id, COUNT(*) AS orders FROM orders GROUP BY customer_id");
+    assert_eq!(savepoint.check_compatible(&fewer).unwrap().len(), 1);
+}
@@ -0,0 +1,298 @@
+/*!
+# Tests for CREATE STREAM schema enforcement
+
+Tests for checking source records against declared stream columns: NOT NULL, undeclared
+fields, type mismatches, widening, coercion and the `schema.enforcement` policies.
+*/
+
+use ferrisstreams::ferris::sql::execution::schema_enforcement::{
+    EnforcementOutcome, SchemaEnforcementPolicy, StreamSchemaEnforcer,
+};
+use ferrisstreams::ferris::sql::execution::{FieldValue, StreamRecord};
+use ferrisstreams::ferris::sql::parser::StreamingSqlParser;
+use ferrisstreams::ferris::sql::SqlError;
+use std::collections::HashMap;
+
+fn enforcer(sql: &str) -> StreamSchemaEnforcer {
+    let query = StreamingSqlParser::new()
+        .parse(sql)
+        .expect("CREATE STREAM should parse");
+    StreamSchemaEnforcer::from_query(&query)
+        .expect("policy should be valid")
+        .expect("stream declares columns")
+}
+
+fn orders_enforcer(policy: &str) -> StreamSchemaEnforcer {
+    enforcer(&format!(
+        "CREATE STREAM orders (
+            id BIGINT NOT NULL,
+            amount DECIMAL(10, 2),
+            region STRING
+        ) AS SELECT * FROM raw_orders WITH ('schema.enforcement' = '{}')",
+        policy
+    ))
+}
+
+fn source_record(fields: Vec<(&str, FieldValue)>) -> StreamRecord {
+    StreamRecord {
+        fields: fields
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect(),
+        headers: HashMap::new(),
+        timestamp: 1710513045000,
+        offset: 7,
+        partition: 0,
+    }
+}
+
+fn accepted(outcome: EnforcementOutcome) -> StreamRecord {
+    match outcome {
+        EnforcementOutcome::Accepted(record) => record,
+        EnforcementOutcome::Rejected { violation, .. } => {
+            panic!("Expected the record to be accepted, got: {}", violation)
+        }
+    }
+}
+
+fn rejection_reason(outcome: EnforcementOutcome) -> (String, String) {
+    match outcome {
+        EnforcementOutcome::Rejected { violation, .. } => (violation.column, violation.reason),
+        EnforcementOutcome::Accepted(record) => {
+            panic!("Expected the record to be rejected, got: {:?}", record)
+        }
+    }
+}
+
+#[test]
+fn test_policy_from_stream_properties() {
+    assert_eq!(
+        orders_enforcer("coerce").policy(),
+        SchemaEnforcementPolicy::Coerce
+    );
+    assert_eq!(
+        orders_enforcer("FAIL").policy(),
+        SchemaEnforcementPolicy::Fail
+    );
+    assert_eq!(
+        orders_enforcer("dlq").policy(),
+        SchemaEnforcementPolicy::DeadLetter
+    );
+
+    let default = enforcer("CREATE STREAM s (id BIGINT) AS SELECT * FROM src");
+    assert_eq!(default.policy(), SchemaEnforcementPolicy::DeadLetter);
+    assert_eq!(default.stream_name(), "s");
+
+    let query = StreamingSqlParser::new()
+        .parse("CREATE STREAM s (id BIGINT) AS SELECT * FROM src WITH ('schema.enforcement' = 'ignore')")
+        .unwrap();
+    assert!(matches!(
+        StreamSchemaEnforcer::from_query(&query),
+        Err(SqlError::ConfigurationError { .. })
+    ));
+
+    // Streams without column definitions are not enforced
+    let query = StreamingSqlParser::new()
+        .parse("CREATE STREAM s AS SELECT * FROM src")
+        .unwrap();
+    assert!(StreamSchemaEnforcer::from_query(&query).unwrap().is_none());
+}
+
+#[test]
+fn test_conforming_record_and_missing_nullable_column() {
+    let enforcer = orders_enforcer("dlq");
+
+    let record = accepted(
+        enforcer
+            .enforce(source_record(vec![
+                ("id", FieldValue::Integer(1)),
+                ("amount", FieldValue::ScaledInteger(1999, 2)),
+            ]))
+            .unwrap(),
+    );
+    assert_eq!(record.fields.get("region"), Some(&FieldValue::Null));
+    assert_eq!(
+        record.fields.get("amount"),
+        Some(&FieldValue::ScaledInteger(1999, 2))
+    );
+
+    // Integers widen losslessly into DECIMAL columns under every policy
+    let record = accepted(
+        enforcer
+            .enforce(source_record(vec![
+                ("id", FieldValue::Integer(2)),
+                ("amount", FieldValue::Integer(5)),
+            ]))
+            .unwrap(),
+    );
+    assert_eq!(
+        record.fields.get("amount"),
+        Some(&FieldValue::ScaledInteger(500, 2))
+    );
+}
+
+#[test]
+fn test_dead_letter_policy_rejections() {
+    let enforcer = orders_enforcer("dlq");
+
+    let (column, reason) = rejection_reason(
+        enforcer
+            .enforce(source_record(vec![(
+                "amount",
+                FieldValue::ScaledInteger(1, 2),
+            )]))
+            .unwrap(),
+    );
+    assert_eq!(column, "id");
+    assert_eq!(reason, "NOT NULL column is missing");
+
+    let (column, reason) = rejection_reason(
+        enforcer
+            .enforce(source_record(vec![("id", FieldValue::Null)]))
+            .unwrap(),
+    );
+    assert_eq!(column, "id");
+    assert_eq!(reason, "NULL value in NOT NULL column");
+
+    let (column, reason) = rejection_reason(
+        enforcer
+            .enforce(source_record(vec![
+                ("id", FieldValue::Integer(1)),
+                ("amount", FieldValue::String("12.50".to_string())),
+            ]))
+            .unwrap(),
+    );
+    assert_eq!(column, "amount");
+    assert!(reason.starts_with("expected DECIMAL(10,2)"), "{}", reason);
+
+    let (column, reason) = rejection_reason(
+        enforcer
+            .enforce(source_record(vec![
+                ("id", FieldValue::Integer(1)),
+                ("coupon", FieldValue::String("SPRING".to_string())),
+            ]))
+            .unwrap(),
+    );
+    assert_eq!(column, "coupon");
+    assert_eq!(reason, "field is not declared in the stream schema");
+}
+
+#[test]
+fn test_coerce_policy() {
+    let enforcer = orders_enforcer("coerce");
+
+    let record = accepted(
+        enforcer
+            .enforce(source_record(vec![
+                ("id", FieldValue::String("42".to_string())),
+                ("amount", FieldValue::String("12.345".to_string())),
+                ("region", FieldValue::Integer(3)),
+                ("coupon", FieldValue::String("SPRING".to_string())),
+            ]))
+            .unwrap(),
+    );
+    assert_eq!(record.fields.get("id"), Some(&FieldValue::Integer(42)));
+    assert_eq!(
+        record.fields.get("amount"),
+        Some(&FieldValue::ScaledInteger(1235, 2))
+    );
+    assert_eq!(
+        record.fields.get("region"),
+        Some(&FieldValue::String("3".to_string()))
+    );
+    assert!(!record.fields.contains_key("coupon"));
+
+    // Values that cannot be cast are still rejected
+    let (column, reason) = rejection_reason(
+        enforcer
+            .enforce(source_record(vec![(
+                "id",
+                FieldValue::String("abc".to_string()),
+            )]))
+            .unwrap(),
+    );
+    assert_eq!(column, "id");
+    assert!(reason.starts_with("cannot coerce"), "{}", reason);
+
+    // Coercion never invents a value for a NOT NULL column
+    assert!(matches!(
+        enforcer.enforce(source_record(vec![])).unwrap(),
+        EnforcementOutcome::Rejected { .. }
+    ));
+}
+
+#[test]
+fn test_fail_policy_returns_schema_error() {
+    let enforcer = orders_enforcer("fail");
+
+    assert!(matches!(
+        enforcer
+            .enforce(source_record(vec![("id", FieldValue::Integer(1))]))
+            .unwrap(),
+        EnforcementOutcome::Accepted(_)
+    ));
+
+    match enforcer.enforce(source_record(vec![("id", FieldValue::Boolean(true))])) {
+        Err(SqlError::SchemaError { message, column }) => {
+            assert_eq!(column.as_deref(), Some("id"));
+            assert!(message.contains("stream 'orders'"), "{}", message);
+        }
+        other => panic!("Expected SchemaError, got: {:?}", other),
+    }
+}
+
+#[test]
+fn test_range_and_nested_type_checks() {
+    let enforcer = enforcer(
+        "CREATE STREAM readings (
+            sensor SMALLINT,
+            samples ARRAY(INTEGER),
+            tags MAP(STRING, STRING)
+        ) AS SELECT * FROM raw_readings",
+    );
+
+    accepted(
+        enforcer
+            .enforce(source_record(vec![
+                ("sensor", FieldValue::Integer(12)),
+                (
+                    "samples",
+                    FieldValue::Array(vec![FieldValue::Integer(1), FieldValue::Integer(2)]),
+                ),
+            ]))
+            .unwrap(),
+    );
+
+    // INTEGER is 64-bit, so values beyond the i32 range conform
+    let record = accepted(
+        enforcer
+            .enforce(source_record(vec![(
+                "samples",
+                FieldValue::Array(vec![FieldValue::Integer(i32::MAX as i64 + 1)]),
+            )]))
+            .unwrap(),
+    );
+    assert_eq!(
+        record.fields.get("samples"),
+        Some(&FieldValue::Array(vec![FieldValue::Integer(
+            i32::MAX as i64 + 1
+        )]))
+    );
+
+    let (column, _) = rejection_reason(
+        enforcer
+            .enforce(source_record(vec![("sensor", FieldValue::Integer(70_000))]))
+            .unwrap(),
+    );
+    assert_eq!(column, "sensor");
+
+    let (column, _) = rejection_reason(
+        enforcer
+            .enforce(source_record(vec![(
+                "samples",
+                FieldValue::Array(vec![FieldValue::String("x".to_string())]),
+            )]))
+            .unwrap(),
+    );
+    assert_eq!(column, "samples");
+}
@@ -0,0 +1,43 @@
+//! Shared utilities for the state, checkpoint, savepoint, TTL and spill tests
+
+use ferrisstreams::ferris::sql::ast::StreamingQuery;
+use ferrisstreams::ferris::sql::execution::{FieldValue, StreamRecord};
+use ferrisstreams::ferris::sql::StreamingSqlParser;
+use std::collections::HashMap;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+pub fn parse(sql: &str) -> StreamingQuery {
+    StreamingSqlParser::new().parse(sql).unwrap()
+}
+
+/// Order record with a customer ID and an amount
+pub fn order(customer_id: i64, amount: f64) -> StreamRecord {
+    StreamRecord::new(HashMap::from([
+        ("customer_id".to_string(), FieldValue::Integer(customer_id)),
+        ("amount".to_string(), FieldValue::Float(amount)),
+    ]))
+}
+
+/// Order record with its event time set to `timestamp`
+pub fn order_at(customer_id: i64, amount: f64, timestamp: i64) -> StreamRecord {
+    let mut record = order(customer_id, amount);
+    record.timestamp = timestamp;
+    record
+}
+
+/// Records emitted so far, without waiting for more
+pub fn drain(rx: &mut UnboundedReceiver<StreamRecord>) -> Vec<StreamRecord> {
+    let mut results = Vec::new();
+    while let Ok(result) = rx.try_recv() {
+        results.push(result);
+    }
+    results
+}
+
+/// Job properties from key/value pairs
+pub fn properties(entries: &[(&str, &str)]) -> HashMap<String, String> {
+    entries
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
@@ -0,0 +1,397 @@
+/*!
+# Spill Tests
+
+Tests for the job memory budget: configuration, accounting, spill runs, the external
+sorter, and windows, hash joins and ORDER BY sorts spilling to disk with the same
+results as in memory.
+*/
+
+use super::shared_test_utils::{drain, order_at, parse, properties};
+use async_trait::async_trait;
+use ferrisstreams::ferris::datasource::file::{FileDataSource, FileFormat, FileSourceConfig};
+use ferrisstreams::ferris::datasource::{DataSource, DataWriter};
+use ferrisstreams::ferris::sql::ast::{
+    BinaryOperator, Expr, JoinClause, JoinType, NodeSpan, OrderByExpr, OrderDirection, StreamSource,
+};
+use ferrisstreams::ferris::sql::execution::algorithms::HashJoinBuilder;
+use ferrisstreams::ferris::sql::execution::processors::ProcessorContext;
+use ferrisstreams::ferris::sql::execution::spill::{ExternalSorter, SpillRun};
+use ferrisstreams::ferris::sql::execution::{
+    FieldValue, MemoryBudget, MemoryBudgetConfig, StreamExecutionEngine, StreamRecord,
+};
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tempfile::TempDir;
+use tokio::sync::mpsc;
+
+const WINDOWED: &str = "SELECT COUNT(*) AS orders, SUM(amount) AS total, MEDIAN(amount) AS median \
+     FROM orders WINDOW TUMBLING(1m)";
+
+fn spill_files(dir: &TempDir) -> usize {
+    std::fs::read_dir(dir.path())
+        .map(|entries| entries.count())
+        .unwrap_or(0)
+}
+
+/// Writer collecting the records written to it
+struct CollectingWriter(Arc<Mutex<Vec<StreamRecord>>>);
+
+#[async_trait]
+impl DataWriter for CollectingWriter {
+    async fn write(&mut self, record: StreamRecord) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.0.lock().unwrap().push(record);
+        Ok(())
+    }
+
+    async fn write_batch(
+        &mut self,
+        records: Vec<StreamRecord>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.0.lock().unwrap().extend(records);
+        Ok(())
+    }
+
+    async fn update(
+        &mut self,
+        _key: &str,
+        record: StreamRecord,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.write(record).await
+    }
+
+    async fn delete(&mut self, _key: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
+
+    async fn commit(&mut self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
+
+    async fn rollback(&mut self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_config_from_properties() {
+    assert_eq!(
+        MemoryBudgetConfig::from_properties(&HashMap::new()).unwrap(),
+        None
+    );
+    let config = MemoryBudgetConfig::from_properties(&properties(&[("memory.budget.mb", "64")]))
+        .unwrap()
+        .unwrap();
+    assert_eq!(config.limit, 64 * 1024 * 1024);
+    assert_eq!(
+        MemoryBudgetConfig::from_properties(&properties(&[
+            ("memory.budget.mb", "1"),
+            ("memory.spill.dir", "/var/tmp/ferris"),
+        ]))
+        .unwrap(),
+        Some(MemoryBudgetConfig::new(1024 * 1024).with_directory("/var/tmp/ferris"))
+    );
+    assert_eq!(
+        config.open("orders").directory(),
+        config.directory.join("orders")
+    );
+
+    for (entries, expected) in [
+        (
+            vec![("memory.spill.dir", "/tmp")],
+            "requires 'memory.budget.mb'",
+        ),
+        (
+            vec![("memory.budget.mb", "lots")],
+            "Invalid memory.budget.mb",
+        ),
+        (vec![("memory.budget.mb", "0")], "Invalid memory.budget.mb"),
+    ] {
+        let error = MemoryBudgetConfig::from_properties(&properties(&entries)).unwrap_err();
+        assert!(error.to_string().contains(expected), "{}", error);
+    }
+}
+
+#[test]
+fn test_budget_accounting() {
+    let budget = MemoryBudget::new(100, PathBuf::from("unused"));
+    assert!(budget.reserve(60));
+    let reservation = budget.reservation();
+    assert!(!reservation.grow(50));
+    assert!(budget.is_exceeded());
+    assert_eq!((budget.used(), budget.available()), (110, 0));
+
+    assert!(reservation.resize(20));
+    assert_eq!(budget.used(), 80);
+    drop(reservation);
+    budget.release(60);
+
+    let metrics = budget.metrics();
+    assert_eq!((metrics.limit, metrics.used, metrics.peak), (100, 0, 110));
+    assert_eq!(metrics.spills, 0);
+}
+
+#[test]
+fn test_spill_run_round_trip() {
+    let dir = TempDir::new().unwrap();
+    let budget = MemoryBudget::new(1024, dir.path());
+    let records: Vec<_> = (0..5)
+        .map(|i| order_at(i, i as f64 * 1.5, i * 10))
+        .collect();
+
+    let run = SpillRun::write(&budget, &records).unwrap();
+    assert_eq!(run.len(), 5);
+    assert!(run.path().exists());
+    let read = run.read_all().unwrap();
+    assert_eq!(read.len(), 5);
+    for (read, written) in read.iter().zip(&records) {
+        assert_eq!(read.fields, written.fields);
+        assert_eq!(read.timestamp, written.timestamp);
+    }
+    assert_eq!(budget.metrics().spills, 1);
+    assert!(budget.metrics().spilled_bytes > 0);
+
+    drop(run);
+    assert_eq!(spill_files(&dir), 0);
+}
+
+#[test]
+fn test_external_sort_merges_spilled_runs() {
+    let dir = TempDir::new().unwrap();
+    // Room for a few records at a time
+    let budget = MemoryBudget::new(1_000, dir.path());
+    let amounts = [7.0, 3.0, 9.0, 3.0, 1.0, 8.0, 2.0, 7.0, 5.0, 3.0, 6.0, 4.0];
+
+    for direction in [OrderDirection::Asc, OrderDirection::Desc] {
+        let order_by = vec![OrderByExpr {
+            expr: Expr::Column("amount".to_string(), NodeSpan::default()),
+            direction: direction.clone(),
+        }];
+        let mut sorter = ExternalSorter::new(order_by, Some(&budget));
+        for (id, amount) in amounts.iter().enumerate() {
+            sorter.push(order_at(id as i64, *amount, 0)).unwrap();
+        }
+        assert!(sorter.spilled_runs() > 1);
+
+        let sorted: Vec<_> = sorter
+            .finish()
+            .unwrap()
+            .map(|record| {
+                let record = record.unwrap();
+                match (
+                    record.fields.get("amount"),
+                    record.fields.get("customer_id"),
+                ) {
+                    (Some(FieldValue::Float(amount)), Some(FieldValue::Integer(id))) => {
+                        (*amount, *id)
+                    }
+                    other => panic!("unexpected fields {:?}", other),
+                }
+            })
+            .collect();
+
+        let mut expected: Vec<_> = amounts
+            .iter()
+            .enumerate()
+            .map(|(id, amount)| (*amount, id as i64))
+            .collect();
+        // Stable: equal amounts keep their arrival order in both directions
+        expected.sort_by(|a, b| match direction {
+            OrderDirection::Asc => a.0.total_cmp(&b.0),
+            OrderDirection::Desc => b.0.total_cmp(&a.0),
+        });
+        assert_eq!(sorted, expected);
+    }
+    assert_eq!(budget.used(), 0);
+    assert_eq!(spill_files(&dir), 0);
+}
+
+/// Run the windowed query over two windows, returning the emitted results
+async fn run_windowed(budget: Option<MemoryBudget>) -> Vec<StreamRecord> {
+    let query = parse(WINDOWED);
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut engine = StreamExecutionEngine::new(tx);
+    if let Some(budget) = budget {
+        engine.set_memory_budget(budget);
+    }
+    let amounts = [5.0, 1.0, 4.0, 2.0, 8.0, 3.0];
+    for (id, amount) in amounts.iter().enumerate() {
+        let record = order_at(id as i64, *amount, id as i64 * 1_000);
+        engine.execute_with_record(&query, record).await.unwrap();
+    }
+    // Closes the first window
+    engine
+        .execute_with_record(&query, order_at(99, 10.0, 61_000))
+        .await
+        .unwrap();
+    drain(&mut rx)
+}
+
+#[tokio::test]
+async fn test_window_spills_with_same_results() {
+    let in_memory = run_windowed(None).await;
+    assert_eq!(in_memory.len(), 1);
+    assert_eq!(
+        in_memory[0].fields.get("orders"),
+        Some(&FieldValue::Integer(6))
+    );
+    assert_eq!(
+        in_memory[0].fields.get("total"),
+        Some(&FieldValue::Float(23.0))
+    );
+    // Median of 1, 2, 3, 4, 5, 8
+    assert_eq!(
+        in_memory[0].fields.get("median"),
+        Some(&FieldValue::Float(3.5))
+    );
+
+    let dir = TempDir::new().unwrap();
+    let budget = MemoryBudget::new(1, dir.path());
+    let spilled = run_windowed(Some(budget.clone())).await;
+    assert_eq!(spilled.len(), 1);
+    assert_eq!(spilled[0].fields, in_memory[0].fields);
+
+    let metrics = budget.metrics();
+    assert!(metrics.spills > 0, "{:?}", metrics);
+    assert!(metrics.spilled_bytes > 0);
+}
+
+#[test]
+fn test_spill_run_retain_rewrites_run() {
+    let dir = TempDir::new().unwrap();
+    let budget = MemoryBudget::new(1024, dir.path());
+    let records: Vec<_> = (0..6).map(|i| order_at(i, i as f64, i * 10)).collect();
+    let run = SpillRun::write(&budget, &records).unwrap();
+
+    let kept = run
+        .retain(&budget, |record| record.timestamp >= 30)
+        .unwrap();
+    assert_eq!(kept.len(), 3);
+    let timestamps: Vec<_> = kept
+        .read_all()
+        .unwrap()
+        .iter()
+        .map(|r| r.timestamp)
+        .collect();
+    assert_eq!(timestamps, [30, 40, 50]);
+
+    drop(run);
+    drop(kept);
+    assert_eq!(spill_files(&dir), 0);
+}
+
+/// Run an ORDER BY query over a CSV file, returning the records written
+async fn run_sorted(budget: Option<MemoryBudget>) -> Vec<StreamRecord> {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("orders.csv");
+    let amounts = [17, 13, 19, 11, 18, 12, 15, 14, 16, 20, 22, 21];
+    let mut csv = "id,amount\n".to_string();
+    for (id, amount) in amounts.iter().enumerate() {
+        csv.push_str(&format!("{},{}\n", id, amount));
+    }
+    std::fs::write(&path, csv).unwrap();
+
+    let mut source = FileDataSource::new();
+    source
+        .initialize(
+            FileSourceConfig::new(path.to_string_lossy().to_string(), FileFormat::Csv).into(),
+        )
+        .await
+        .unwrap();
+    let reader = source.create_reader().await.unwrap();
+    let written = Arc::new(Mutex::new(Vec::new()));
+
+    let (tx, _rx) = mpsc::unbounded_channel();
+    let mut engine = StreamExecutionEngine::new(tx);
+    if let Some(budget) = budget {
+        engine.set_memory_budget(budget);
+    }
+    let query = parse("SELECT id, amount FROM orders ORDER BY amount DESC");
+    engine
+        .stream_process(
+            &query,
+            reader,
+            Box::new(CollectingWriter(Arc::clone(&written))),
+        )
+        .await
+        .unwrap();
+    let written = written.lock().unwrap().clone();
+    written
+}
+
+#[tokio::test]
+async fn test_order_by_spills_with_same_results() {
+    let amounts = |records: &[StreamRecord]| -> Vec<_> {
+        records
+            .iter()
+            .map(|record| record.fields.get("amount").cloned())
+            .collect()
+    };
+    let expected: Vec<_> = (11..=22)
+        .rev()
+        .map(|amount| Some(FieldValue::Integer(amount)))
+        .collect();
+
+    let in_memory = run_sorted(None).await;
+    assert_eq!(amounts(&in_memory), expected);
+
+    let dir = TempDir::new().unwrap();
+    let budget = MemoryBudget::new(1_000, dir.path());
+    let spilled = run_sorted(Some(budget.clone())).await;
+    assert_eq!(amounts(&spilled), expected);
+    assert!(budget.metrics().spills > 1, "{:?}", budget.metrics());
+    assert_eq!(budget.used(), 0);
+    assert_eq!(spill_files(&dir), 0);
+}
+
+#[test]
+fn test_hash_join_spills_partitions_with_same_results() {
+    let left: Vec<_> = (0..200).map(|i| order_at(i % 50, i as f64, 0)).collect();
+    let right: Vec<_> = (0..50).map(|i| order_at(i, -(i as f64), 0)).collect();
+    let join_clause = JoinClause {
+        join_type: JoinType::Inner,
+        right_source: StreamSource::Stream("customers".to_string()),
+        condition: Expr::BinaryOp {
+            left: Box::new(Expr::Column("customer_id".to_string(), NodeSpan::default())),
+            op: BinaryOperator::Equal,
+            right: Box::new(Expr::Column("customer_id".to_string(), NodeSpan::default())),
+            span: NodeSpan::default(),
+        },
+        right_alias: Some("c".to_string()),
+        window: None,
+    };
+    let ids = |results: Vec<StreamRecord>| {
+        let mut ids: Vec<_> = results
+            .iter()
+            .map(|record| format!("{:?}", record.fields.get("customer_id")))
+            .collect();
+        ids.sort();
+        ids
+    };
+
+    let mut context = ProcessorContext::new("join");
+    let mut in_memory = HashJoinBuilder::new().build(join_clause.clone()).unwrap();
+    let expected = in_memory
+        .execute(left.clone(), right.clone(), &mut context)
+        .unwrap();
+    assert_eq!(in_memory.spilled_partitions(), 0);
+    assert_eq!(expected.len(), 200);
+
+    let dir = TempDir::new().unwrap();
+    let budget = MemoryBudget::new(64 * 1024 * 1024, dir.path());
+    let mut spilling = HashJoinBuilder::new()
+        .with_memory_limit(1024)
+        .with_memory_budget(budget.clone())
+        .build(join_clause)
+        .unwrap();
+    let results = spilling.execute(left, right, &mut context).unwrap();
+    assert!(spilling.spilled_partitions() > 1);
+    assert_eq!(ids(results), ids(expected));
+    assert!(budget.metrics().spills > 0);
+    assert_eq!(spill_files(&dir), 0);
+}
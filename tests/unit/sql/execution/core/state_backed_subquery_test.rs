@@ -0,0 +1,279 @@
+/*!
+# State-Backed Subquery Tests
+
+Tests for subqueries evaluated against materialized MERGE/UPSERT tables and registered
+lookup sources: correlation variables, semi/anti-join planning, cached results and
+`subquery.refresh` policies.
+*/
+
+use ferrisstreams::ferris::sql::ast::{StreamingQuery, SubqueryType};
+use ferrisstreams::ferris::sql::execution::subquery::{SubqueryPlan, SubqueryStrategy};
+use ferrisstreams::ferris::sql::execution::{
+    FieldValue, InMemoryLookupSource, LookupSource, StreamExecutionEngine, StreamRecord,
+    SubqueryRefreshPolicy,
+};
+use ferrisstreams::ferris::sql::{SqlError, StreamingSqlParser};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+
+fn parse(sql: &str) -> StreamingQuery {
+    StreamingSqlParser::new().parse(sql).unwrap()
+}
+
+fn record(fields: Vec<(&str, FieldValue)>) -> StreamRecord {
+    StreamRecord::new(
+        fields
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect::<HashMap<_, _>>(),
+    )
+}
+
+fn payment(id: i64, account_id: i64, amount: f64) -> StreamRecord {
+    record(vec![
+        ("id", FieldValue::Integer(id)),
+        ("account_id", FieldValue::Integer(account_id)),
+        ("amount", FieldValue::Float(amount)),
+    ])
+}
+
+fn account(account_id: i64) -> StreamRecord {
+    record(vec![("account_id", FieldValue::Integer(account_id))])
+}
+
+fn drain(rx: &mut UnboundedReceiver<StreamRecord>) -> Vec<StreamRecord> {
+    let mut results = Vec::new();
+    while let Ok(result) = rx.try_recv() {
+        results.push(result);
+    }
+    results
+}
+
+/// Lookup source that counts full scans
+struct CountingSource {
+    inner: InMemoryLookupSource,
+    scans: AtomicUsize,
+}
+
+impl LookupSource for CountingSource {
+    fn scan(&self) -> Result<Vec<StreamRecord>, SqlError> {
+        self.scans.fetch_add(1, Ordering::SeqCst);
+        self.inner.scan()
+    }
+
+    fn version(&self) -> u64 {
+        self.inner.version()
+    }
+}
+
+#[test]
+fn test_plan_strategies_and_correlation() {
+    let plan_of = |sql: &str| SubqueryPlan::new(&parse(sql)).unwrap();
+
+    let uncorrelated = plan_of("SELECT account_id FROM blocked");
+    assert_eq!(uncorrelated.source(), Some("blocked"));
+    assert!(!uncorrelated.is_correlated());
+    assert_eq!(
+        uncorrelated.strategy(&SubqueryType::In),
+        SubqueryStrategy::SemiJoin
+    );
+    assert_eq!(
+        uncorrelated.strategy(&SubqueryType::NotExists),
+        SubqueryStrategy::AntiJoin
+    );
+    assert_eq!(
+        uncorrelated.strategy(&SubqueryType::Scalar),
+        SubqueryStrategy::CachedValues
+    );
+
+    let correlated = plan_of("SELECT 1 FROM history h WHERE h.account_id = p.account_id");
+    assert_eq!(correlated.outer_references(), ["p.account_id".to_string()]);
+    assert_eq!(
+        correlated.strategy(&SubqueryType::Exists),
+        SubqueryStrategy::Correlated
+    );
+
+    let joined = parse("SELECT a.id FROM a JOIN b ON a.id = b.id");
+    assert!(SubqueryPlan::new(&joined).is_err());
+}
+
+#[test]
+fn test_refresh_policy_parsing() {
+    assert_eq!(
+        SubqueryRefreshPolicy::parse("on_change").unwrap(),
+        SubqueryRefreshPolicy::OnChange
+    );
+    assert_eq!(
+        SubqueryRefreshPolicy::parse("once").unwrap(),
+        SubqueryRefreshPolicy::Once
+    );
+    assert_eq!(
+        SubqueryRefreshPolicy::parse("30s").unwrap(),
+        SubqueryRefreshPolicy::Interval(Duration::from_secs(30))
+    );
+    assert!(matches!(
+        SubqueryRefreshPolicy::parse("sometimes"),
+        Err(SqlError::ConfigurationError { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_correlated_scalar_aggregate_over_indexed_lookup() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut engine = StreamExecutionEngine::new(tx);
+    let history = InMemoryLookupSource::new(vec![
+        payment(1, 10, 100.0),
+        payment(2, 10, 300.0),
+        payment(3, 20, 50.0),
+    ])
+    .with_index("account_id");
+    engine.register_lookup_source("history", Arc::new(history));
+
+    let query = parse(
+        "SELECT id, (SELECT AVG(amount) FROM history h WHERE h.account_id = p.account_id) AS avg_amount \
+         FROM payments p",
+    );
+    engine
+        .execute_with_record(&query, payment(4, 10, 1.0))
+        .await
+        .unwrap();
+    engine
+        .execute_with_record(&query, payment(5, 20, 1.0))
+        .await
+        .unwrap();
+    engine
+        .execute_with_record(&query, payment(6, 30, 1.0))
+        .await
+        .unwrap();
+
+    let averages: Vec<_> = drain(&mut rx)
+        .iter()
+        .map(|r| r.fields.get("avg_amount").cloned())
+        .collect();
+    assert_eq!(
+        averages,
+        vec![
+            Some(FieldValue::Float(200.0)),
+            Some(FieldValue::Float(50.0)),
+            Some(FieldValue::Null),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_uncorrelated_result_is_cached_until_source_changes() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut engine = StreamExecutionEngine::new(tx);
+    let blocked = Arc::new(CountingSource {
+        inner: InMemoryLookupSource::new(vec![account(10)]),
+        scans: AtomicUsize::new(0),
+    });
+    engine.register_lookup_source("blocked", blocked.clone());
+
+    let query =
+        parse("SELECT id FROM payments WHERE account_id NOT IN (SELECT account_id FROM blocked)");
+    for (id, account_id) in [(1, 10), (2, 20), (3, 10)] {
+        engine
+            .execute_with_record(&query, payment(id, account_id, 1.0))
+            .await
+            .unwrap();
+    }
+    assert_eq!(drain(&mut rx).len(), 1);
+    assert_eq!(blocked.scans.load(Ordering::SeqCst), 1);
+
+    // A new version of the source refreshes the cached anti-join
+    blocked.inner.replace(vec![account(20)]);
+    engine
+        .execute_with_record(&query, payment(4, 10, 1.0))
+        .await
+        .unwrap();
+    engine
+        .execute_with_record(&query, payment(5, 20, 1.0))
+        .await
+        .unwrap();
+    let ids: Vec<_> = drain(&mut rx)
+        .iter()
+        .map(|r| r.fields.get("id").cloned())
+        .collect();
+    assert_eq!(ids, vec![Some(FieldValue::Integer(4))]);
+    assert_eq!(blocked.scans.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_once_policy_keeps_first_result() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut engine = StreamExecutionEngine::new(tx);
+    let blocked = Arc::new(InMemoryLookupSource::new(vec![account(10)]));
+    engine.register_lookup_source("blocked", blocked.clone());
+    engine.set_subquery_refresh_policy(SubqueryRefreshPolicy::Once);
+
+    let query =
+        parse("SELECT id FROM payments WHERE account_id IN (SELECT account_id FROM blocked)");
+    engine
+        .execute_with_record(&query, payment(1, 10, 1.0))
+        .await
+        .unwrap();
+    blocked.replace(vec![account(20)]);
+    engine
+        .execute_with_record(&query, payment(2, 10, 1.0))
+        .await
+        .unwrap();
+    assert_eq!(drain(&mut rx).len(), 2);
+}
+
+#[tokio::test]
+async fn test_subquery_reads_materialized_upsert_table() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut engine = StreamExecutionEngine::new(tx);
+    let upsert = parse("UPSERT INTO blocked (account_id) VALUES (account_id)");
+    let query =
+        parse("SELECT id FROM payments WHERE account_id IN (SELECT account_id FROM blocked)");
+
+    engine
+        .execute_with_record(&upsert, account(10))
+        .await
+        .unwrap();
+    engine
+        .execute_with_record(&query, payment(1, 20, 1.0))
+        .await
+        .unwrap();
+    engine
+        .execute_with_record(&query, payment(2, 10, 1.0))
+        .await
+        .unwrap();
+
+    // The write invalidates the cached semi-join result
+    engine
+        .execute_with_record(&upsert, account(20))
+        .await
+        .unwrap();
+    engine
+        .execute_with_record(&query, payment(3, 20, 1.0))
+        .await
+        .unwrap();
+
+    let ids: Vec<_> = drain(&mut rx)
+        .iter()
+        .filter(|r| r.fields.contains_key("id"))
+        .map(|r| r.fields.get("id").cloned())
+        .collect();
+    assert_eq!(
+        ids,
+        vec![Some(FieldValue::Integer(2)), Some(FieldValue::Integer(3))]
+    );
+}
+
+#[tokio::test]
+async fn test_unknown_source_is_an_error() {
+    let (tx, _rx) = mpsc::unbounded_channel();
+    let mut engine = StreamExecutionEngine::new(tx);
+    let query = parse("SELECT id FROM payments WHERE EXISTS (SELECT 1 FROM missing)");
+    let error = engine
+        .execute_with_record(&query, payment(1, 10, 1.0))
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("SUBQ-SOURCE-001"), "{}", error);
+}
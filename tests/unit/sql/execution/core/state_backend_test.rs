@@ -0,0 +1,483 @@
+/*!
+# State Backend Tests
+
+Tests for pluggable operator state: codecs, the memory and log-structured disk backends,
+typed value/list/map state, job configuration and GROUP BY / UPSERT / window state kept in
+a backend.
+*/
+
+use super::shared_test_utils::{drain, order, parse, properties};
+use ferrisstreams::ferris::sql::execution::internal::GroupAccumulator;
+use ferrisstreams::ferris::sql::execution::state::{
+    ListState, LogStateBackend, MapState, MemoryStateBackend, StateBackendKind, StateCodec,
+    StateOperator, ValueState,
+};
+use ferrisstreams::ferris::sql::execution::{
+    FieldValue, StateBackend, StateBackendConfig, StateBackends, StreamExecutionEngine,
+    StreamRecord,
+};
+use ferrisstreams::ferris::sql::SqlError;
+use hyperloglogplus::HyperLogLog;
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::sync::Arc;
+use tempfile::TempDir;
+use tokio::sync::mpsc;
+
+fn backends(dir: &TempDir) -> Vec<Arc<dyn StateBackend>> {
+    vec![
+        Arc::new(MemoryStateBackend::new()),
+        Arc::new(LogStateBackend::open(dir.path()).unwrap()),
+    ]
+}
+
+#[test]
+fn test_codec_round_trips() {
+    let value = FieldValue::Struct(HashMap::from([
+        (
+            "items".to_string(),
+            FieldValue::Array(vec![
+                FieldValue::Integer(-7),
+                FieldValue::Float(1.5),
+                FieldValue::Null,
+                FieldValue::ScaledInteger(12345, 2),
+            ]),
+        ),
+        (
+            "at".to_string(),
+            FieldValue::Timestamp(
+                chrono::NaiveDate::from_ymd_opt(2024, 3, 1)
+                    .unwrap()
+                    .and_hms_milli_opt(12, 30, 0, 250)
+                    .unwrap(),
+            ),
+        ),
+        ("raw".to_string(), FieldValue::Bytes(vec![0, 1, 255])),
+        ("name".to_string(), FieldValue::String("ferris".to_string())),
+    ]));
+    let bytes = value.to_state_bytes();
+    assert_eq!(FieldValue::from_state_bytes(&bytes).unwrap(), value);
+
+    let mut record = order(42, 9.5);
+    record.offset = 17;
+    record.partition = 3;
+    record
+        .headers
+        .insert("source".to_string(), "orders".to_string());
+    let decoded = StreamRecord::from_state_bytes(&record.to_state_bytes()).unwrap();
+    assert_eq!(
+        decoded.fields.get("customer_id"),
+        record.fields.get("customer_id")
+    );
+    assert_eq!(decoded.fields.get("amount"), record.fields.get("amount"));
+    assert_eq!(
+        (decoded.timestamp, decoded.offset, decoded.partition),
+        (record.timestamp, 17, 3)
+    );
+    assert_eq!(decoded.headers, record.headers);
+
+    // Trailing bytes mean the value was written with a different type
+    let mut padded = 5u64.to_state_bytes();
+    padded.push(0);
+    assert!(u64::from_state_bytes(&padded).is_err());
+}
+
+#[test]
+fn test_group_accumulator_round_trip() {
+    let mut accumulator = GroupAccumulator::new();
+    accumulator.increment_count();
+    accumulator.increment_count();
+    accumulator.add_sum("amount", 12.5);
+    accumulator.update_min("amount", FieldValue::Float(2.5));
+    accumulator.set_first_value("status", FieldValue::String("new".to_string()));
+    for user in ["a", "b", "c", "a"] {
+        accumulator.add_to_approx_set("user", FieldValue::String(user.to_string()));
+    }
+    accumulator.set_sample_record(order(1, 2.5));
+
+    let mut decoded = GroupAccumulator::from_state_bytes(&accumulator.to_state_bytes()).unwrap();
+    assert_eq!(decoded.count, 2);
+    assert_eq!(decoded.sums.get("amount"), Some(&12.5));
+    assert_eq!(decoded.mins.get("amount"), Some(&FieldValue::Float(2.5)));
+    assert_eq!(
+        decoded.first_values.get("status"),
+        Some(&FieldValue::String("new".to_string()))
+    );
+    assert!(decoded.sample_record.is_some());
+
+    // The sketch keeps counting after it is restored
+    let estimate = decoded
+        .approx_distinct_values
+        .get_mut("user")
+        .unwrap()
+        .count()
+        .round();
+    assert_eq!(estimate, 3.0);
+}
+
+#[test]
+fn test_backends_get_put_delete_scan() {
+    let dir = TempDir::new().unwrap();
+    for backend in backends(&dir) {
+        backend.put("ns", b"b/1", b"one").unwrap();
+        backend.put("ns", b"a/1", b"zero").unwrap();
+        backend.put("ns", b"b/2", b"two").unwrap();
+        backend.put("other", b"b/1", b"elsewhere").unwrap();
+        backend.put("ns", b"b/1", b"uno").unwrap();
+
+        assert_eq!(backend.get("ns", b"b/1").unwrap(), Some(b"uno".to_vec()));
+        assert_eq!(backend.get("ns", b"missing").unwrap(), None);
+        assert_eq!(
+            backend.scan_prefix("ns", b"b/").unwrap(),
+            vec![
+                (b"b/1".to_vec(), b"uno".to_vec()),
+                (b"b/2".to_vec(), b"two".to_vec())
+            ],
+            "{:?}",
+            backend.kind()
+        );
+
+        backend.delete("ns", b"b/1").unwrap();
+        assert_eq!(backend.get("ns", b"b/1").unwrap(), None);
+        assert_eq!(backend.namespaces(), vec!["ns", "other"]);
+
+        backend.clear("ns").unwrap();
+        assert!(backend.scan_prefix("ns", b"").unwrap().is_empty());
+        assert_eq!(backend.namespaces(), vec!["other"]);
+    }
+}
+
+#[test]
+fn test_typed_state_handles() {
+    let dir = TempDir::new().unwrap();
+    for backend in backends(&dir) {
+        let totals: ValueState<String, f64> = ValueState::new(backend.clone(), "totals");
+        totals.set(&"a".to_string(), &1.5).unwrap();
+        totals.set(&"b".to_string(), &2.5).unwrap();
+        assert_eq!(totals.get(&"a".to_string()).unwrap(), Some(1.5));
+        totals.remove(&"a".to_string()).unwrap();
+        assert_eq!(totals.entries().unwrap(), vec![("b".to_string(), 2.5)]);
+
+        let events: ListState<i64, String> = ListState::new(backend.clone(), "events");
+        for event in ["open", "click", "close"] {
+            events.add(&7, &event.to_string()).unwrap();
+        }
+        events.add(&8, &"other".to_string()).unwrap();
+        assert_eq!(events.len(&7).unwrap(), 3);
+        assert_eq!(events.get(&7).unwrap(), vec!["open", "click", "close"]);
+        events.clear(&7).unwrap();
+        assert!(events.is_empty(&7).unwrap());
+        assert_eq!(events.get(&8).unwrap(), vec!["other"]);
+
+        let seen: MapState<String, String, u64> = MapState::new(backend.clone(), "seen");
+        let user = "u1".to_string();
+        seen.put(&user, &"home".to_string(), &2).unwrap();
+        seen.put(&user, &"cart".to_string(), &1).unwrap();
+        seen.put(&"u2".to_string(), &"home".to_string(), &9)
+            .unwrap();
+        assert_eq!(seen.get(&user, &"home".to_string()).unwrap(), Some(2));
+        seen.remove(&user, &"home".to_string()).unwrap();
+        assert_eq!(seen.entries(&user).unwrap(), vec![("cart".to_string(), 1)]);
+        seen.clear(&user).unwrap();
+        assert!(seen.entries(&user).unwrap().is_empty());
+        assert_eq!(seen.entries(&"u2".to_string()).unwrap().len(), 1);
+    }
+}
+
+#[test]
+fn test_disk_backend_survives_reopen_and_torn_tail() {
+    let dir = TempDir::new().unwrap();
+    {
+        let backend = LogStateBackend::open(dir.path()).unwrap();
+        backend.put("ns", b"kept", b"value").unwrap();
+        backend.put("ns", b"large", &vec![7u8; 200_000]).unwrap();
+        backend.put("ns", b"dropped", b"value").unwrap();
+        backend.delete("ns", b"dropped").unwrap();
+        backend.flush().unwrap();
+    }
+
+    // A crash in the middle of an append leaves a partial record at the end of the log
+    let log = std::fs::read_dir(dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .find(|path| path.is_file())
+        .unwrap();
+    let mut file = OpenOptions::new().append(true).open(&log).unwrap();
+    file.write_all(&[1, 0, 0, 0, 2, 0]).unwrap();
+    drop(file);
+
+    let backend = LogStateBackend::open(dir.path()).unwrap();
+    assert_eq!(backend.get("ns", b"kept").unwrap(), Some(b"value".to_vec()));
+    assert_eq!(backend.get("ns", b"dropped").unwrap(), None);
+    assert_eq!(
+        backend.get("ns", b"large").unwrap(),
+        Some(vec![7u8; 200_000])
+    );
+
+    // Appends after recovery are readable on the next open
+    backend.put("ns", b"later", b"more").unwrap();
+    drop(backend);
+    let backend = LogStateBackend::open(dir.path()).unwrap();
+    assert_eq!(backend.scan_prefix("ns", b"").unwrap().len(), 3);
+}
+
+#[test]
+fn test_disk_backend_compaction_drops_garbage() {
+    let dir = TempDir::new().unwrap();
+    let backend = LogStateBackend::open(dir.path())
+        .unwrap()
+        .with_compaction_threshold(u64::MAX);
+    for round in 0..50u32 {
+        backend.put("ns", b"hot", &round.to_state_bytes()).unwrap();
+    }
+    let before = backend.log_size();
+    backend.compact().unwrap();
+    assert!(backend.log_size() < before / 10);
+    assert_eq!(
+        backend.get("ns", b"hot").unwrap(),
+        Some(49u32.to_state_bytes())
+    );
+    drop(backend);
+
+    let backend = LogStateBackend::open(dir.path()).unwrap();
+    assert_eq!(
+        backend.get("ns", b"hot").unwrap(),
+        Some(49u32.to_state_bytes())
+    );
+}
+
+#[test]
+fn test_config_from_properties() {
+    assert_eq!(
+        StateBackendConfig::from_properties(&HashMap::new()).unwrap(),
+        StateBackendConfig::default()
+    );
+
+    let config = StateBackendConfig::from_properties(&properties(&[
+        ("state.backend", "memory"),
+        ("state.backend.group_by", "disk"),
+        ("state.dir", "/tmp/state"),
+    ]))
+    .unwrap();
+    assert_eq!(
+        config.backend_for(StateOperator::GroupBy),
+        Some(StateBackendKind::Disk)
+    );
+    assert_eq!(
+        config.backend_for(StateOperator::Merge),
+        Some(StateBackendKind::Memory)
+    );
+
+    for invalid in [
+        properties(&[("state.backend", "rocks")]),
+        properties(&[("state.backend.join", "memory")]),
+        properties(&[("state.backend", "disk")]),
+    ] {
+        assert!(
+            matches!(
+                StateBackendConfig::from_properties(&invalid),
+                Err(SqlError::ConfigurationError { .. })
+            ),
+            "{:?}",
+            invalid
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_group_by_state_on_disk_backend() {
+    let dir = TempDir::new().unwrap();
+    let config = StateBackendConfig::from_properties(&properties(&[
+        ("state.backend.group_by", "disk"),
+        ("state.dir", dir.path().to_str().unwrap()),
+    ]))
+    .unwrap();
+    let query = parse(
+        "SELECT customer_id, COUNT(*) AS orders, SUM(amount) AS total \
+         FROM orders GROUP BY customer_id",
+    );
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut engine = StreamExecutionEngine::new(tx);
+    engine.set_state_backends(config.open("totals").unwrap());
+    for (customer_id, amount) in [(1, 10.0), (2, 5.0), (1, 2.5)] {
+        engine
+            .execute_with_record(&query, order(customer_id, amount))
+            .await
+            .unwrap();
+    }
+    let backend = engine
+        .state_backends()
+        .get(StateOperator::GroupBy)
+        .unwrap()
+        .clone();
+    assert_eq!(backend.kind(), StateBackendKind::Disk);
+    assert_eq!(backend.namespaces(), vec!["group_by/orders"]);
+    engine.state_backends().flush().unwrap();
+    drop(engine);
+    drop(backend);
+    drain(&mut rx);
+
+    // A new engine on the same directory continues from the stored accumulators, also
+    // after the projection of the query was edited
+    let query = parse(
+        "SELECT SUM(amount) AS total, COUNT(*) AS orders, customer_id \
+         FROM orders GROUP BY customer_id",
+    );
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut engine = StreamExecutionEngine::new(tx);
+    engine.set_state_backends(config.open("totals").unwrap());
+    engine
+        .execute_with_record(&query, order(1, 7.5))
+        .await
+        .unwrap();
+    let latest = drain(&mut rx).pop().unwrap();
+    assert_eq!(latest.fields.get("orders"), Some(&FieldValue::Integer(3)));
+    assert_eq!(latest.fields.get("total"), Some(&FieldValue::Float(20.0)));
+
+    engine.flush_group_by_results(&query).unwrap();
+    let mut totals: Vec<_> = drain(&mut rx)
+        .iter()
+        .map(|r| {
+            (
+                r.fields.get("customer_id").cloned(),
+                r.fields.get("orders").cloned(),
+            )
+        })
+        .collect();
+    totals.sort_by_key(|(customer, _)| format!("{:?}", customer));
+    assert_eq!(
+        totals,
+        vec![
+            (Some(FieldValue::Integer(1)), Some(FieldValue::Integer(3))),
+            (Some(FieldValue::Integer(2)), Some(FieldValue::Integer(1))),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_window_buffer_on_disk_backend() {
+    let dir = TempDir::new().unwrap();
+    let config = StateBackendConfig::from_properties(&properties(&[
+        ("state.backend.window", "disk"),
+        ("state.dir", dir.path().to_str().unwrap()),
+    ]))
+    .unwrap();
+    assert_eq!(
+        config.backend_for(StateOperator::Window),
+        Some(StateBackendKind::Disk)
+    );
+    let query =
+        parse("SELECT COUNT(*) AS orders, SUM(amount) AS total FROM orders WINDOW TUMBLING(1m)");
+    let timed_order = |customer_id, amount, timestamp| {
+        let mut record = order(customer_id, amount);
+        record.timestamp = timestamp;
+        record
+    };
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut engine = StreamExecutionEngine::new(tx);
+    engine.set_state_backends(config.open("windows").unwrap());
+    for (customer_id, amount, timestamp) in [(1, 10.0, 0), (2, 5.0, 1_000), (1, 2.5, 2_000)] {
+        engine
+            .execute_with_record(&query, timed_order(customer_id, amount, timestamp))
+            .await
+            .unwrap();
+    }
+    let backend = engine
+        .state_backends()
+        .get(StateOperator::Window)
+        .unwrap()
+        .clone();
+    let namespaces = backend.namespaces();
+    assert_eq!(namespaces.len(), 1);
+    assert!(namespaces[0].starts_with("window/"), "{:?}", namespaces);
+    let window: ListState<String, StreamRecord> = ListState::new(backend.clone(), &namespaces[0]);
+    let query_id = namespaces[0].trim_start_matches("window/").to_string();
+    assert_eq!(window.len(&query_id).unwrap(), 3);
+    engine.state_backends().flush().unwrap();
+    drop(engine);
+    drop(window);
+    drop(backend);
+    assert!(drain(&mut rx).is_empty());
+
+    // A new engine on the same directory continues the open window
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut engine = StreamExecutionEngine::new(tx);
+    engine.set_state_backends(config.open("windows").unwrap());
+    engine
+        .execute_with_record(&query, timed_order(2, 7.5, 3_000))
+        .await
+        .unwrap();
+    engine
+        .execute_with_record(&query, timed_order(3, 1.0, 61_000))
+        .await
+        .unwrap();
+    let results = drain(&mut rx);
+    assert_eq!(results.len(), 1);
+    assert_eq!(
+        results[0].fields.get("orders"),
+        Some(&FieldValue::Integer(4))
+    );
+    assert_eq!(
+        results[0].fields.get("total"),
+        Some(&FieldValue::Float(25.0))
+    );
+
+    // Only the record of the next window stays buffered
+    let backend = engine
+        .state_backends()
+        .get(StateOperator::Window)
+        .unwrap()
+        .clone();
+    let window: ListState<String, StreamRecord> =
+        ListState::new(backend, StateOperator::Window.namespace(&query_id));
+    let buffered = window.get(&query_id).unwrap();
+    assert_eq!(buffered.len(), 1);
+    assert_eq!(buffered[0].timestamp, 61_000);
+}
+
+#[tokio::test]
+async fn test_upsert_rows_in_backend_feed_subqueries() {
+    let backend: Arc<dyn StateBackend> = Arc::new(MemoryStateBackend::new());
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut engine = StreamExecutionEngine::new(tx);
+    engine.set_state_backends(
+        StateBackends::new().with_backend(StateOperator::Merge, backend.clone()),
+    );
+
+    let upsert = parse("UPSERT INTO vip (customer_id) VALUES (customer_id)");
+    let query =
+        parse("SELECT amount FROM orders WHERE customer_id IN (SELECT customer_id FROM vip)");
+    engine
+        .execute_with_record(&upsert, order(1, 0.0))
+        .await
+        .unwrap();
+    engine
+        .execute_with_record(&upsert, order(1, 0.0))
+        .await
+        .unwrap();
+    assert_eq!(
+        backend
+            .scan_prefix(&StateOperator::Merge.namespace("vip"), b"")
+            .unwrap()
+            .len(),
+        1
+    );
+    drain(&mut rx);
+
+    engine
+        .execute_with_record(&query, order(1, 3.0))
+        .await
+        .unwrap();
+    engine
+        .execute_with_record(&query, order(2, 4.0))
+        .await
+        .unwrap();
+    let amounts: Vec<_> = drain(&mut rx)
+        .iter()
+        .map(|r| r.fields.get("amount").cloned())
+        .collect();
+    assert_eq!(amounts, vec![Some(FieldValue::Float(3.0))]);
+}
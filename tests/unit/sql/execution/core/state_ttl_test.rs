@@ -0,0 +1,293 @@
+/*!
+# State TTL Tests
+
+Tests for the time-to-live of keyed state: job configuration, evicting idle GROUP BY
+groups, UPSERT rows and window buffers by event and processing time, final and retraction output for
+evicted keys, state metrics and restored keys starting a fresh TTL.
+*/
+
+use super::shared_test_utils::{drain, order_at, parse, properties};
+use ferrisstreams::ferris::sql::execution::state::ttl::{
+    EvictionOutput, TtlTimeDomain, EVICTED_HEADER,
+};
+use ferrisstreams::ferris::sql::execution::state::{MemoryStateBackend, StateOperator};
+use ferrisstreams::ferris::sql::execution::{
+    FieldValue, StateBackend, StateBackends, StateTtlConfig, StreamExecutionEngine, StreamRecord,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+
+const TOTALS: &str = "SELECT customer_id, COUNT(*) AS orders FROM orders GROUP BY customer_id";
+
+fn evicted(records: &[StreamRecord]) -> Vec<&StreamRecord> {
+    records
+        .iter()
+        .filter(|record| record.headers.contains_key(EVICTED_HEADER))
+        .collect()
+}
+
+/// Engine expiring keys 10s of event time after their last update
+fn event_time_engine(
+    emit: EvictionOutput,
+) -> (StreamExecutionEngine, UnboundedReceiver<StreamRecord>) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let mut engine = StreamExecutionEngine::new(tx);
+    engine
+        .set_state_ttl(
+            StateTtlConfig::new(Duration::from_secs(10))
+                .with_time(TtlTimeDomain::Event)
+                .with_emit(emit),
+        )
+        .unwrap();
+    (engine, rx)
+}
+
+#[test]
+fn test_config_from_properties() {
+    assert_eq!(
+        StateTtlConfig::from_properties(&HashMap::new()).unwrap(),
+        None
+    );
+    assert_eq!(
+        StateTtlConfig::from_properties(&properties(&[("state.ttl", "30m")])).unwrap(),
+        Some(StateTtlConfig::new(Duration::from_secs(1800)))
+    );
+    assert_eq!(
+        StateTtlConfig::from_properties(&properties(&[
+            ("state.ttl", "2h"),
+            ("state.ttl.time", "EVENT"),
+            ("state.ttl.emit", "retract"),
+        ]))
+        .unwrap(),
+        Some(
+            StateTtlConfig::new(Duration::from_secs(7200))
+                .with_time(TtlTimeDomain::Event)
+                .with_emit(EvictionOutput::Retract)
+        )
+    );
+
+    for (entries, expected) in [
+        (vec![("state.ttl.emit", "final")], "requires 'state.ttl'"),
+        (vec![("state.ttl", "soon")], "Invalid state.ttl"),
+        (vec![("state.ttl", "0s")], "Invalid state.ttl"),
+        (
+            vec![("state.ttl", "1m"), ("state.ttl.time", "ingest")],
+            "Invalid state.ttl.time",
+        ),
+        (
+            vec![("state.ttl", "1m"), ("state.ttl.emit", "all")],
+            "Invalid state.ttl.emit",
+        ),
+    ] {
+        let error = StateTtlConfig::from_properties(&properties(&entries)).unwrap_err();
+        assert!(error.to_string().contains(expected), "{}", error);
+    }
+}
+
+#[tokio::test]
+async fn test_idle_groups_evicted_by_event_time_with_final_result() {
+    let query = parse(TOTALS);
+    let (mut engine, mut rx) = event_time_engine(EvictionOutput::Final);
+    for (customer_id, timestamp) in [(1, 0), (1, 1_000), (2, 1_000)] {
+        engine
+            .execute_with_record(&query, order_at(customer_id, 1.0, timestamp))
+            .await
+            .unwrap();
+    }
+    assert!(evicted(&drain(&mut rx)).is_empty());
+
+    // Customer 1 was last updated 11s of event time earlier
+    engine
+        .execute_with_record(&query, order_at(2, 1.0, 12_000))
+        .await
+        .unwrap();
+    let results = drain(&mut rx);
+    let evicted = evicted(&results);
+    assert_eq!(evicted.len(), 1);
+    assert_eq!(
+        evicted[0].fields.get("customer_id"),
+        Some(&FieldValue::Integer(1))
+    );
+    assert_eq!(
+        evicted[0].fields.get("orders"),
+        Some(&FieldValue::Integer(2))
+    );
+
+    // An evicted key starts again from scratch
+    engine
+        .execute_with_record(&query, order_at(1, 1.0, 12_500))
+        .await
+        .unwrap();
+    let latest = drain(&mut rx).pop().unwrap();
+    assert_eq!(latest.fields.get("orders"), Some(&FieldValue::Integer(1)));
+}
+
+#[tokio::test]
+async fn test_idle_window_buffer_evicted_with_final_result() {
+    let window = parse("SELECT COUNT(*) AS orders FROM orders WINDOW TUMBLING(1h)");
+    let (mut engine, mut rx) = event_time_engine(EvictionOutput::Final);
+    for timestamp in [1_000, 2_000] {
+        engine
+            .execute_with_record(&window, order_at(1, 1.0, timestamp))
+            .await
+            .unwrap();
+    }
+    assert!(drain(&mut rx).is_empty());
+
+    // The hour-long window received nothing for 18s of event time
+    engine
+        .execute_with_record(&parse(TOTALS), order_at(2, 1.0, 20_000))
+        .await
+        .unwrap();
+    let results = drain(&mut rx);
+    let evicted = evicted(&results);
+    assert_eq!(evicted.len(), 1);
+    assert_eq!(
+        evicted[0].fields.get("orders"),
+        Some(&FieldValue::Integer(2))
+    );
+
+    let metrics = engine.state_metrics().unwrap();
+    let window_metrics = metrics
+        .iter()
+        .find(|metrics| metrics.operator == StateOperator::Window)
+        .unwrap();
+    assert_eq!((window_metrics.keys, window_metrics.evicted), (0, 1));
+}
+
+#[tokio::test]
+async fn test_evicted_groups_retracted() {
+    let query = parse(TOTALS);
+    let (mut engine, mut rx) = event_time_engine(EvictionOutput::Retract);
+    engine
+        .execute_with_record(&query, order_at(1, 1.0, 0))
+        .await
+        .unwrap();
+    engine
+        .execute_with_record(&query, order_at(2, 1.0, 15_000))
+        .await
+        .unwrap();
+
+    let results = drain(&mut rx);
+    let evicted = evicted(&results);
+    assert_eq!(evicted.len(), 1);
+    assert_eq!(
+        evicted[0].fields.get("__deleted"),
+        Some(&FieldValue::Boolean(true))
+    );
+    assert_eq!(
+        evicted[0].headers.get("operation").map(String::as_str),
+        Some("DELETE")
+    );
+}
+
+#[tokio::test]
+async fn test_upsert_rows_evicted_with_tombstones() {
+    let upsert = parse("UPSERT INTO vip (customer_id) VALUES (customer_id)");
+    let (mut engine, _rx) = event_time_engine(EvictionOutput::Retract);
+    engine
+        .execute_with_output(&upsert, &order_at(1, 1.0, 0))
+        .unwrap();
+    engine
+        .execute_with_output(&upsert, &order_at(2, 1.0, 8_000))
+        .unwrap();
+    assert!(engine.take_evicted_records().is_empty());
+
+    engine
+        .execute_with_output(&upsert, &order_at(2, 1.0, 11_000))
+        .unwrap();
+    let tombstones = engine.take_evicted_records();
+    assert_eq!(tombstones.len(), 1);
+    assert_eq!(
+        tombstones[0].fields.get("customer_id"),
+        Some(&FieldValue::Integer(1))
+    );
+    assert_eq!(
+        tombstones[0].headers.get("tombstone").map(String::as_str),
+        Some("true")
+    );
+    assert_eq!(
+        tombstones[0].headers.get("table_name").map(String::as_str),
+        Some("vip")
+    );
+
+    let metrics = engine.state_metrics().unwrap();
+    assert_eq!(metrics.len(), 1);
+    assert_eq!(metrics[0].operator, StateOperator::Merge);
+    assert_eq!(metrics[0].namespace, StateOperator::Merge.namespace("vip"));
+    assert_eq!((metrics[0].keys, metrics[0].evicted), (1, 1));
+}
+
+#[tokio::test]
+async fn test_processing_time_eviction_from_backend() {
+    let backend: Arc<dyn StateBackend> = Arc::new(MemoryStateBackend::new());
+    let query = parse(TOTALS);
+    let (tx, _rx) = mpsc::unbounded_channel();
+    let mut engine = StreamExecutionEngine::new(tx);
+    engine.set_state_backends(
+        StateBackends::new().with_backend(StateOperator::GroupBy, backend.clone()),
+    );
+    engine
+        .set_state_ttl(StateTtlConfig::new(Duration::from_millis(40)))
+        .unwrap();
+
+    engine
+        .execute_with_record(&query, order_at(1, 1.0, 0))
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(80)).await;
+    engine
+        .execute_with_record(&query, order_at(2, 1.0, 0))
+        .await
+        .unwrap();
+
+    let metrics = engine.state_metrics().unwrap();
+    assert_eq!(metrics.len(), 1);
+    assert_eq!(metrics[0].operator, StateOperator::GroupBy);
+    assert_eq!((metrics[0].keys, metrics[0].evicted), (1, 1));
+    assert_eq!(
+        backend
+            .scan_prefix(&metrics[0].namespace, b"")
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn test_restored_keys_start_full_ttl() {
+    let query = parse(TOTALS);
+    let (mut engine, _rx) = event_time_engine(EvictionOutput::Final);
+    engine
+        .execute_with_record(&query, order_at(1, 1.0, 0))
+        .await
+        .unwrap();
+    let snapshot = engine.snapshot_operator_state().unwrap();
+
+    let (mut restored, mut rx) = event_time_engine(EvictionOutput::Final);
+    restored.restore_operator_state(&snapshot).unwrap();
+    restored
+        .execute_with_record(&query, order_at(2, 1.0, 20_000))
+        .await
+        .unwrap();
+    // Customer 1 was updated long before, but its TTL starts with the restore
+    restored
+        .execute_with_record(&query, order_at(2, 1.0, 29_000))
+        .await
+        .unwrap();
+    assert!(evicted(&drain(&mut rx)).is_empty());
+
+    restored
+        .execute_with_record(&query, order_at(2, 1.0, 32_000))
+        .await
+        .unwrap();
+    let results = drain(&mut rx);
+    let evicted = evicted(&results);
+    assert_eq!(evicted.len(), 1);
+    assert_eq!(
+        evicted[0].fields.get("orders"),
+        Some(&FieldValue::Integer(1))
+    );
+}
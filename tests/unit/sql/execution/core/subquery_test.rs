@@ -7,10 +7,14 @@ Comprehensive tests for the newly implemented subquery functionality including:
 - NOT EXISTS subqueries: WHERE NOT EXISTS (SELECT 1 FROM table WHERE condition)
 - IN subqueries: WHERE column IN (SELECT id FROM table WHERE condition)
 - NOT IN subqueries: WHERE column NOT IN (SELECT id FROM table WHERE condition)
+
+Subqueries read the lookup sources registered by `create_lookup_sources`.
 */
 
 use ferrisstreams::ferris::serialization::JsonFormat;
-use ferrisstreams::ferris::sql::execution::{FieldValue, StreamExecutionEngine, StreamRecord};
+use ferrisstreams::ferris::sql::execution::{
+    FieldValue, InMemoryLookupSource, StreamExecutionEngine, StreamRecord,
+};
 use ferrisstreams::ferris::sql::parser::StreamingSqlParser;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -30,7 +34,7 @@ fn create_test_record() -> StreamRecord {
     headers.insert("test_source".to_string(), "subquery_tests".to_string());
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         headers,
         timestamp: 1640995200000, // 2022-01-01 00:00:00 UTC
         offset: 100,
@@ -38,13 +42,57 @@ fn create_test_record() -> StreamRecord {
     }
 }
 
-// Conversion function no longer needed - using StreamRecord directly
+fn lookup_row(fields: Vec<(&str, FieldValue)>) -> StreamRecord {
+    StreamRecord::new(
+        fields
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect::<HashMap<_, _>>(),
+    )
+}
+
+/// Reference data read by the subqueries: `config` holds an active row matching the test
+/// record (id 42) and an inactive one
+fn create_lookup_sources() -> Vec<(&'static str, InMemoryLookupSource)> {
+    let config = vec![
+        lookup_row(vec![
+            ("id", FieldValue::Integer(42)),
+            ("valid_id", FieldValue::Integer(42)),
+            ("blocked_id", FieldValue::Integer(42)),
+            ("valid_name", FieldValue::String("test_record".to_string())),
+            ("enabled", FieldValue::Boolean(true)),
+            ("active", FieldValue::Boolean(true)),
+        ]),
+        lookup_row(vec![
+            ("id", FieldValue::Integer(7)),
+            ("valid_id", FieldValue::Integer(7)),
+            ("blocked_id", FieldValue::Integer(7)),
+            ("valid_name", FieldValue::String("other".to_string())),
+            ("enabled", FieldValue::Boolean(false)),
+            ("active", FieldValue::Boolean(false)),
+        ]),
+    ];
+    let active_configs = vec![lookup_row(vec![(
+        "config_name",
+        FieldValue::String("production".to_string()),
+    )])];
+    let permissions = vec![lookup_row(vec![("user_id", FieldValue::Integer(42))])];
+
+    vec![
+        ("config", InMemoryLookupSource::new(config)),
+        ("active_configs", InMemoryLookupSource::new(active_configs)),
+        ("permissions", InMemoryLookupSource::new(permissions)),
+    ]
+}
 
 async fn execute_subquery_test(
     query: &str,
 ) -> Result<Vec<StreamRecord>, Box<dyn std::error::Error>> {
     let (tx, mut rx) = mpsc::unbounded_channel();
     let mut engine = StreamExecutionEngine::new(tx);
+    for (name, source) in create_lookup_sources() {
+        engine.register_lookup_source(name, Arc::new(source));
+    }
     let parser = StreamingSqlParser::new();
 
     let parsed_query = parser.parse(query)?;
@@ -66,7 +114,6 @@ async fn test_scalar_subquery_parsing() {
     let query = "SELECT id, (SELECT 100) as config_value FROM test_stream";
     let result = execute_subquery_test(query).await;
 
-    // Should parse successfully (the mock implementation returns 1 for scalar subqueries)
     assert!(result.is_ok(), "Scalar subquery should parse and execute");
 
     let results = result.unwrap();
@@ -76,10 +123,9 @@ async fn test_scalar_subquery_parsing() {
     assert!(results[0].fields.contains_key("id"));
     assert!(results[0].fields.contains_key("config_value"));
 
-    // Mock implementation should return 1 for scalar subqueries
     assert_eq!(
         results[0].fields.get("config_value"),
-        Some(&FieldValue::Integer(1))
+        Some(&FieldValue::Integer(100))
     );
 }
 
@@ -93,7 +139,7 @@ async fn test_exists_subquery() {
     assert!(result.is_ok(), "EXISTS subquery should parse and execute");
 
     let results = result.unwrap();
-    // Mock implementation returns true for EXISTS, so record should be included
+    // config has an active row, so the record is included
     assert_eq!(results.len(), 1);
     assert!(results[0].fields.contains_key("id"));
     assert!(results[0].fields.contains_key("name"));
@@ -111,26 +157,24 @@ async fn test_not_exists_subquery() {
     );
 
     let results = result.unwrap();
-    // Mock implementation returns true for EXISTS, so NOT EXISTS returns false
-    // This means the WHERE condition fails and no records should be returned
+    // config has an inactive row, so NOT EXISTS is false and the record is filtered out
     assert_eq!(
         results.len(),
         0,
-        "NOT EXISTS should filter out the record with mock implementation"
+        "NOT EXISTS should filter out the record when a matching row exists"
     );
 }
 
 #[tokio::test]
 async fn test_in_subquery_with_positive_value() {
-    // Test IN subquery with a positive integer (mock returns true for positive integers)
+    // Test IN subquery with a value present in the subquery result
     let query = "SELECT id, name FROM test_stream WHERE id IN (SELECT valid_id FROM config)";
     let result = execute_subquery_test(query).await;
 
     assert!(result.is_ok(), "IN subquery should parse and execute");
 
     let results = result.unwrap();
-    // Mock implementation returns true for positive integers in IN subqueries
-    // Since id = 42 (positive), it should match
+    // config.valid_id contains 42, the id of the record
     assert_eq!(results.len(), 1);
     assert_eq!(results[0].fields.get("id"), Some(&FieldValue::Integer(42)));
 }
@@ -144,12 +188,11 @@ async fn test_not_in_subquery() {
     assert!(result.is_ok(), "NOT IN subquery should parse and execute");
 
     let results = result.unwrap();
-    // Mock implementation returns true for positive integers in IN subqueries
-    // So NOT IN would return false for positive integers, filtering out the record
+    // config.blocked_id contains 42, so the record is filtered out
     assert_eq!(
         results.len(),
         0,
-        "NOT IN should filter out positive integers with mock implementation"
+        "NOT IN should filter out values present in the subquery result"
     );
 }
 
@@ -178,15 +221,15 @@ async fn test_complex_subquery_in_select() {
     assert!(results[0].fields.contains_key("config_type"));
     assert!(results[0].fields.contains_key("max_limit"));
 
-    // Verify subquery results (mock implementations)
+    // Verify subquery results
     assert_eq!(
         results[0].fields.get("config_type"),
-        Some(&FieldValue::Integer(1))
-    ); // Scalar subquery mock
+        Some(&FieldValue::String("default_config".to_string()))
+    );
     assert_eq!(
         results[0].fields.get("max_limit"),
-        Some(&FieldValue::Integer(1))
-    ); // Scalar subquery mock
+        Some(&FieldValue::Integer(999))
+    );
 }
 
 #[tokio::test]
@@ -209,7 +252,7 @@ async fn test_nested_subqueries() {
 
 #[tokio::test]
 async fn test_subquery_with_string_field() {
-    // Test IN subquery with string field (should return true for non-empty strings)
+    // Test IN subquery with string field
     let query = "SELECT id, name FROM test_stream WHERE name IN (SELECT valid_name FROM config)";
     let result = execute_subquery_test(query).await;
 
@@ -219,8 +262,7 @@ async fn test_subquery_with_string_field() {
     );
 
     let results = result.unwrap();
-    // Mock implementation returns true for non-empty strings
-    // Since name = "test_record" (non-empty), it should match
+    // config.valid_name contains "test_record", the name of the record
     assert_eq!(results.len(), 1);
     assert_eq!(
         results[0].fields.get("name"),
@@ -230,7 +272,7 @@ async fn test_subquery_with_string_field() {
 
 #[tokio::test]
 async fn test_subquery_with_boolean_field() {
-    // Test IN subquery with boolean field (should return true for true values)
+    // Test IN subquery with boolean field
     let query = "SELECT id, active FROM test_stream WHERE active IN (SELECT enabled FROM config)";
     let result = execute_subquery_test(query).await;
 
@@ -240,8 +282,7 @@ async fn test_subquery_with_boolean_field() {
     );
 
     let results = result.unwrap();
-    // Mock implementation returns the boolean value itself for IN subqueries
-    // Since active = true, it should match
+    // config.enabled contains true, the active flag of the record
     assert_eq!(results.len(), 1);
     assert_eq!(
         results[0].fields.get("active"),
@@ -316,9 +357,9 @@ async fn test_subquery_with_multiple_conditions() {
     );
 
     let results = result.unwrap();
-    // All conditions should pass with our test data and mock implementation:
+    // All conditions should pass with our test data:
     // - amount > 100: true (123.45 > 100)
-    // - EXISTS subqueries: true (mock implementation)
+    // - EXISTS subqueries: config has valid_id 42 and permissions has user_id 42
     assert_eq!(results.len(), 1);
 }
 
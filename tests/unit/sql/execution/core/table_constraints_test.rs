@@ -0,0 +1,242 @@
+/*!
+# Tests for CREATE TABLE constraints
+
+Tests for PRIMARY KEY, CHECK and NOT NULL: DDL parsing (column-level and table-level forms),
+upsert keys, constraint evaluation and the `constraint.violation` policies.
+*/
+
+use ferrisstreams::ferris::sql::ast::{StreamingQuery, TableConstraint};
+use ferrisstreams::ferris::sql::execution::table_constraints::{
+    ConstraintOutcome, ConstraintViolationPolicy, TableConstraintEnforcer,
+};
+use ferrisstreams::ferris::sql::execution::{FieldValue, StreamRecord};
+use ferrisstreams::ferris::sql::parser::StreamingSqlParser;
+use ferrisstreams::ferris::sql::SqlError;
+use std::collections::HashMap;
+
+const BALANCES_DDL: &str = "CREATE TABLE balances (
+        account_id STRING,
+        region STRING NOT NULL,
+        balance DECIMAL(18, 2) CHECK (balance >= 0),
+        currency STRING,
+        CONSTRAINT balances_pk PRIMARY KEY (account_id, region),
+        CONSTRAINT known_currency CHECK (currency IN ('USD', 'EUR'))
+    ) AS SELECT * FROM transactions";
+
+fn enforcer_for(sql: &str) -> TableConstraintEnforcer {
+    let query = StreamingSqlParser::new()
+        .parse(sql)
+        .expect("CREATE TABLE should parse");
+    TableConstraintEnforcer::from_query(&query)
+        .expect("policy should be valid")
+        .expect("table declares constraints")
+}
+
+fn row(fields: Vec<(&str, FieldValue)>) -> StreamRecord {
+    StreamRecord {
+        fields: fields
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect(),
+        headers: HashMap::new(),
+        timestamp: 1710513045000,
+        offset: 3,
+        partition: 0,
+    }
+}
+
+fn valid_balance() -> Vec<(&'static str, FieldValue)> {
+    vec![
+        ("account_id", FieldValue::String("acc-1".to_string())),
+        ("region", FieldValue::String("eu".to_string())),
+        ("balance", FieldValue::ScaledInteger(1050, 2)),
+        ("currency", FieldValue::String("EUR".to_string())),
+    ]
+}
+
+fn violated_constraint(outcome: ConstraintOutcome) -> String {
+    match outcome {
+        ConstraintOutcome::Rejected { violation, .. } => violation.constraint,
+        ConstraintOutcome::Accepted(record) => {
+            panic!("Expected the row to be rejected, got: {:?}", record)
+        }
+    }
+}
+
+#[test]
+fn test_parse_table_constraints() {
+    let query = StreamingSqlParser::new().parse(BALANCES_DDL).unwrap();
+    let StreamingQuery::CreateTable {
+        columns: Some(columns),
+        constraints,
+        ..
+    } = query
+    else {
+        panic!("Expected CreateTable with columns, got: {:?}", query);
+    };
+
+    assert_eq!(constraints.len(), 3);
+    assert!(matches!(
+        &constraints[0],
+        TableConstraint::Check { name: None, .. }
+    ));
+    assert_eq!(
+        constraints[1],
+        TableConstraint::PrimaryKey {
+            name: Some("balances_pk".to_string()),
+            columns: vec!["account_id".to_string(), "region".to_string()],
+        }
+    );
+    assert_eq!(constraints[2].name(), Some("known_currency"));
+
+    // Primary key columns are implicitly NOT NULL
+    let nullable: Vec<(String, bool)> = columns.into_iter().map(|c| (c.name, c.nullable)).collect();
+    assert_eq!(
+        nullable,
+        vec![
+            ("account_id".to_string(), false),
+            ("region".to_string(), false),
+            ("balance".to_string(), true),
+            ("currency".to_string(), true),
+        ]
+    );
+}
+
+#[test]
+fn test_column_level_primary_key_and_invalid_declarations() {
+    let parser = StreamingSqlParser::new();
+    let query = parser
+        .parse(
+            "CREATE TABLE users (id BIGINT PRIMARY KEY NOT NULL, name STRING) AS SELECT * FROM src",
+        )
+        .unwrap();
+    let StreamingQuery::CreateTable { constraints, .. } = query else {
+        panic!("Expected CreateTable");
+    };
+    assert_eq!(
+        constraints,
+        vec![TableConstraint::PrimaryKey {
+            name: None,
+            columns: vec!["id".to_string()],
+        }]
+    );
+
+    assert!(parser
+        .parse("CREATE TABLE t (id BIGINT PRIMARY KEY, v BIGINT PRIMARY KEY) AS SELECT * FROM src")
+        .is_err());
+    assert!(parser
+        .parse("CREATE TABLE t (id BIGINT, PRIMARY KEY (missing)) AS SELECT * FROM src")
+        .is_err());
+    // Constraints are a table concept
+    assert!(parser
+        .parse("CREATE STREAM s (id BIGINT PRIMARY KEY) AS SELECT * FROM src")
+        .is_err());
+}
+
+#[test]
+fn test_upsert_keys() {
+    let users = enforcer_for("CREATE TABLE users (id BIGINT PRIMARY KEY) AS SELECT * FROM src");
+    assert_eq!(users.primary_key(), ["id".to_string()]);
+    assert_eq!(
+        users.key_of(&row(vec![("id", FieldValue::Integer(42))])),
+        Some("42".to_string())
+    );
+
+    let balances = enforcer_for(BALANCES_DDL);
+    assert_eq!(
+        balances.key_of(&row(valid_balance())),
+        Some(r#"["acc-1","eu"]"#.to_string())
+    );
+
+    let no_key = enforcer_for("CREATE TABLE t (id BIGINT NOT NULL) AS SELECT * FROM src");
+    assert!(no_key.primary_key().is_empty());
+    assert_eq!(no_key.key_of(&row(valid_balance())), None);
+
+    // Tables without constraints have no enforcer
+    let query = StreamingSqlParser::new()
+        .parse("CREATE TABLE t (id BIGINT) AS SELECT * FROM src")
+        .unwrap();
+    assert!(TableConstraintEnforcer::from_query(&query)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn test_constraint_violations() {
+    let enforcer = enforcer_for(BALANCES_DDL);
+    assert_eq!(enforcer.policy(), ConstraintViolationPolicy::DeadLetter);
+
+    assert!(matches!(
+        enforcer.enforce(row(valid_balance())).unwrap(),
+        ConstraintOutcome::Accepted(_)
+    ));
+
+    let mut fields = valid_balance();
+    fields.retain(|(name, _)| *name != "account_id");
+    assert_eq!(
+        violated_constraint(enforcer.enforce(row(fields)).unwrap()),
+        "balances_pkey"
+    );
+
+    let mut fields = valid_balance();
+    fields[2].1 = FieldValue::ScaledInteger(-1, 2);
+    assert_eq!(
+        violated_constraint(enforcer.enforce(row(fields)).unwrap()),
+        "balances_check"
+    );
+
+    let mut fields = valid_balance();
+    fields[3].1 = FieldValue::String("GBP".to_string());
+    assert_eq!(
+        violated_constraint(enforcer.enforce(row(fields)).unwrap()),
+        "known_currency"
+    );
+
+    // Comparisons with NULL are false, so nullable columns opt out explicitly
+    let mut fields = valid_balance();
+    fields[2].1 = FieldValue::Null;
+    assert_eq!(
+        violated_constraint(enforcer.enforce(row(fields)).unwrap()),
+        "balances_check"
+    );
+    let lenient = enforcer_for(
+        "CREATE TABLE t (balance DECIMAL(18, 2) CHECK (balance IS NULL OR balance >= 0)) AS SELECT * FROM src",
+    );
+    assert!(matches!(
+        lenient
+            .enforce(row(vec![("balance", FieldValue::Null)]))
+            .unwrap(),
+        ConstraintOutcome::Accepted(_)
+    ));
+}
+
+#[test]
+fn test_violation_policies() {
+    let skip = enforcer_for(
+        "CREATE TABLE t (id BIGINT NOT NULL) AS SELECT * FROM src WITH ('constraint.violation' = 'skip')",
+    );
+    assert_eq!(skip.policy(), ConstraintViolationPolicy::Skip);
+    assert_eq!(
+        violated_constraint(skip.enforce(row(vec![])).unwrap()),
+        "id_not_null"
+    );
+
+    let fail = enforcer_for(
+        "CREATE TABLE t (id BIGINT NOT NULL) AS SELECT * FROM src WITH ('constraint.violation' = 'fail')",
+    );
+    match fail.enforce(row(vec![("id", FieldValue::Null)])) {
+        Err(SqlError::SchemaError { message, column }) => {
+            assert_eq!(column.as_deref(), Some("id"));
+            assert!(message.contains("table 't'"), "{}", message);
+        }
+        other => panic!("Expected SchemaError, got: {:?}", other),
+    }
+
+    let query = StreamingSqlParser::new()
+        .parse("CREATE TABLE t (id BIGINT NOT NULL) AS SELECT * FROM src WITH ('constraint.violation' = 'ignore')")
+        .unwrap();
+    assert!(matches!(
+        TableConstraintEnforcer::from_query(&query),
+        Err(SqlError::ConfigurationError { .. })
+    ));
+}
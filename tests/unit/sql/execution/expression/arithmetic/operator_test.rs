@@ -6,7 +6,7 @@ Tests for SQL operators including LIKE, NOT LIKE, and other specialized operator
 
 use ferrisstreams::ferris::serialization::JsonFormat;
 use ferrisstreams::ferris::sql::ast::{
-    BinaryOperator, Expr, LiteralValue, SelectField, StreamSource, StreamingQuery,
+    BinaryOperator, Expr, LiteralValue, NodeSpan, SelectField, StreamSource, StreamingQuery,
 };
 use ferrisstreams::ferris::sql::execution::{FieldValue, StreamExecutionEngine, StreamRecord};
 use std::collections::HashMap;
@@ -21,7 +21,7 @@ fn create_test_record(text_field: &str) -> StreamRecord {
     );
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         timestamp: chrono::Utc::now().timestamp_millis(),
         offset: 0,
         partition: 0,
@@ -42,7 +42,7 @@ fn create_edge_case_record() -> StreamRecord {
     );
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         timestamp: chrono::Utc::now().timestamp_millis(),
         offset: 0,
         partition: 0,
@@ -73,13 +73,18 @@ async fn test_like_operator() {
         let query = StreamingQuery::Select {
             fields: vec![SelectField::Expression {
                 expr: Expr::BinaryOp {
-                    left: Box::new(Expr::Column("text_field".to_string())),
+                    left: Box::new(Expr::Column("text_field".to_string(), NodeSpan::default())),
                     op: BinaryOperator::Like,
-                    right: Box::new(Expr::Literal(LiteralValue::String(pattern.to_string()))),
+                    right: Box::new(Expr::Literal(
+                        LiteralValue::String(pattern.to_string()),
+                        NodeSpan::default(),
+                    )),
+                    span: NodeSpan::default(),
                 },
                 alias: Some("like_result".to_string()),
             }],
             from: StreamSource::Stream("test".to_string()),
+            from_alias: None,
             where_clause: None,
             joins: None,
             group_by: None,
@@ -89,6 +94,7 @@ async fn test_like_operator() {
             limit: None,
             emit_mode: None,
             properties: None,
+            span: NodeSpan::default(),
         };
 
         let result = engine.execute_with_record(&query, record.clone()).await;
@@ -133,13 +139,18 @@ async fn test_not_like_operator() {
         let query = StreamingQuery::Select {
             fields: vec![SelectField::Expression {
                 expr: Expr::BinaryOp {
-                    left: Box::new(Expr::Column("text_field".to_string())),
+                    left: Box::new(Expr::Column("text_field".to_string(), NodeSpan::default())),
                     op: BinaryOperator::NotLike,
-                    right: Box::new(Expr::Literal(LiteralValue::String(pattern.to_string()))),
+                    right: Box::new(Expr::Literal(
+                        LiteralValue::String(pattern.to_string()),
+                        NodeSpan::default(),
+                    )),
+                    span: NodeSpan::default(),
                 },
                 alias: Some("not_like_result".to_string()),
             }],
             from: StreamSource::Stream("test".to_string()),
+            from_alias: None,
             where_clause: None,
             joins: None,
             group_by: None,
@@ -149,6 +160,7 @@ async fn test_not_like_operator() {
             limit: None,
             emit_mode: None,
             properties: None,
+            span: NodeSpan::default(),
         };
 
         let result = engine.execute_with_record(&query, record.clone()).await;
@@ -195,13 +207,18 @@ async fn test_like_operator_edge_cases() {
         let query = StreamingQuery::Select {
             fields: vec![SelectField::Expression {
                 expr: Expr::BinaryOp {
-                    left: Box::new(Expr::Column("text_field".to_string())),
+                    left: Box::new(Expr::Column("text_field".to_string(), NodeSpan::default())),
                     op: BinaryOperator::Like,
-                    right: Box::new(Expr::Literal(LiteralValue::String(pattern.to_string()))),
+                    right: Box::new(Expr::Literal(
+                        LiteralValue::String(pattern.to_string()),
+                        NodeSpan::default(),
+                    )),
+                    span: NodeSpan::default(),
                 },
                 alias: Some("like_result".to_string()),
             }],
             from: StreamSource::Stream("test".to_string()),
+            from_alias: None,
             where_clause: None,
             joins: None,
             group_by: None,
@@ -211,6 +228,7 @@ async fn test_like_operator_edge_cases() {
             limit: None,
             emit_mode: None,
             properties: None,
+            span: NodeSpan::default(),
         };
 
         let result = engine.execute_with_record(&query, record.clone()).await;
@@ -235,13 +253,18 @@ async fn test_like_operator_edge_cases() {
     let query = StreamingQuery::Select {
         fields: vec![SelectField::Expression {
             expr: Expr::BinaryOp {
-                left: Box::new(Expr::Column("null_field".to_string())),
+                left: Box::new(Expr::Column("null_field".to_string(), NodeSpan::default())),
                 op: BinaryOperator::Like,
-                right: Box::new(Expr::Literal(LiteralValue::String("%".to_string()))),
+                right: Box::new(Expr::Literal(
+                    LiteralValue::String("%".to_string()),
+                    NodeSpan::default(),
+                )),
+                span: NodeSpan::default(),
             },
             alias: Some("null_like_result".to_string()),
         }],
         from: StreamSource::Stream("test".to_string()),
+        from_alias: None,
         where_clause: None,
         joins: None,
         group_by: None,
@@ -251,6 +274,7 @@ async fn test_like_operator_edge_cases() {
         limit: None,
         emit_mode: None,
         properties: None,
+        span: NodeSpan::default(),
     };
 
     let result = engine.execute_with_record(&query, record.clone()).await;
@@ -269,13 +293,21 @@ async fn test_like_operator_edge_cases() {
     let query = StreamingQuery::Select {
         fields: vec![SelectField::Expression {
             expr: Expr::BinaryOp {
-                left: Box::new(Expr::Column("number_field".to_string())),
+                left: Box::new(Expr::Column(
+                    "number_field".to_string(),
+                    NodeSpan::default(),
+                )),
                 op: BinaryOperator::Like,
-                right: Box::new(Expr::Literal(LiteralValue::String("%3".to_string()))),
+                right: Box::new(Expr::Literal(
+                    LiteralValue::String("%3".to_string()),
+                    NodeSpan::default(),
+                )),
+                span: NodeSpan::default(),
             },
             alias: Some("number_like_result".to_string()),
         }],
         from: StreamSource::Stream("test".to_string()),
+        from_alias: None,
         where_clause: None,
         joins: None,
         group_by: None,
@@ -285,6 +317,7 @@ async fn test_like_operator_edge_cases() {
         limit: None,
         emit_mode: None,
         properties: None,
+        span: NodeSpan::default(),
     };
 
     let result = engine.execute_with_record(&query, record.clone()).await;
@@ -317,7 +350,7 @@ async fn test_in_operator_basic() {
     fields.insert("amount".to_string(), FieldValue::Float(150.0));
 
     let record = StreamRecord {
-        fields,
+        fields: fields.into(),
         timestamp: chrono::Utc::now().timestamp_millis(),
         offset: 0,
         partition: 0,
@@ -346,19 +379,21 @@ async fn test_in_operator_basic() {
     for (query_str, expected) in test_cases {
         let query = StreamingQuery::Select {
             fields: vec![SelectField::Expression {
-                expr: Expr::Column("id".to_string()), // Just select id to have some output
+                expr: Expr::Column("id".to_string(), NodeSpan::default()), // Just select id to have some output
                 alias: None,
             }],
             from: StreamSource::Stream("test".to_string()),
+            from_alias: None,
             where_clause: Some(Expr::BinaryOp {
                 left: Box::new(match query_str.split(" IN ").next().unwrap() {
-                    "id" => Expr::Column("id".to_string()),
-                    "name" => Expr::Column("name".to_string()),
-                    "amount" => Expr::Column("amount".to_string()),
-                    _ => Expr::Column("id".to_string()),
+                    "id" => Expr::Column("id".to_string(), NodeSpan::default()),
+                    "name" => Expr::Column("name".to_string(), NodeSpan::default()),
+                    "amount" => Expr::Column("amount".to_string(), NodeSpan::default()),
+                    _ => Expr::Column("id".to_string(), NodeSpan::default()),
                 }),
                 op: BinaryOperator::In,
                 right: Box::new(parse_in_list(query_str.split(" IN ").nth(1).unwrap())),
+                span: NodeSpan::default(),
             }),
             joins: None,
             group_by: None,
@@ -368,6 +403,7 @@ async fn test_in_operator_basic() {
             limit: None,
             emit_mode: None,
             properties: None,
+            span: NodeSpan::default(),
         };
 
         let result = engine.execute_with_record(&query, record.clone()).await;
@@ -395,7 +431,7 @@ async fn test_not_in_operator_basic() {
     fields.insert("name".to_string(), FieldValue::String("test".to_string()));
 
     let record = StreamRecord {
-        fields,
+        fields: fields.into(),
         timestamp: chrono::Utc::now().timestamp_millis(),
         offset: 0,
         partition: 0,
@@ -419,14 +455,16 @@ async fn test_not_in_operator_basic() {
 
         let query = StreamingQuery::Select {
             fields: vec![SelectField::Expression {
-                expr: Expr::Column("id".to_string()),
+                expr: Expr::Column("id".to_string(), NodeSpan::default()),
                 alias: None,
             }],
             from: StreamSource::Stream("test".to_string()),
+            from_alias: None,
             where_clause: Some(Expr::BinaryOp {
-                left: Box::new(Expr::Column(column.to_string())),
+                left: Box::new(Expr::Column(column.to_string(), NodeSpan::default())),
                 op: BinaryOperator::NotIn,
                 right: Box::new(parse_in_list(list_str)),
+                span: NodeSpan::default(),
             }),
             joins: None,
             group_by: None,
@@ -436,6 +474,7 @@ async fn test_not_in_operator_basic() {
             limit: None,
             emit_mode: None,
             properties: None,
+            span: NodeSpan::default(),
         };
 
         let result = engine.execute_with_record(&query, record.clone()).await;
@@ -462,7 +501,7 @@ async fn test_in_operator_with_null_values() {
     fields.insert("nullable_field".to_string(), FieldValue::Null);
 
     let record = StreamRecord {
-        fields,
+        fields: fields.into(),
         timestamp: chrono::Utc::now().timestamp_millis(),
         offset: 0,
         partition: 0,
@@ -472,18 +511,29 @@ async fn test_in_operator_with_null_values() {
     // Test NULL IN list (should never match)
     let query = StreamingQuery::Select {
         fields: vec![SelectField::Expression {
-            expr: Expr::Column("id".to_string()),
+            expr: Expr::Column("id".to_string(), NodeSpan::default()),
             alias: None,
         }],
         from: StreamSource::Stream("test".to_string()),
+        from_alias: None,
         where_clause: Some(Expr::BinaryOp {
-            left: Box::new(Expr::Column("nullable_field".to_string())),
+            left: Box::new(Expr::Column(
+                "nullable_field".to_string(),
+                NodeSpan::default(),
+            )),
             op: BinaryOperator::In,
-            right: Box::new(Expr::List(vec![
-                Expr::Literal(LiteralValue::Integer(1)),
-                Expr::Literal(LiteralValue::String("test".to_string())),
-                Expr::Literal(LiteralValue::Null),
-            ])),
+            right: Box::new(Expr::List(
+                vec![
+                    Expr::Literal(LiteralValue::Integer(1), NodeSpan::default()),
+                    Expr::Literal(
+                        LiteralValue::String("test".to_string()),
+                        NodeSpan::default(),
+                    ),
+                    Expr::Literal(LiteralValue::Null, NodeSpan::default()),
+                ],
+                NodeSpan::default(),
+            )),
+            span: NodeSpan::default(),
         }),
         joins: None,
         group_by: None,
@@ -493,6 +543,7 @@ async fn test_in_operator_with_null_values() {
         limit: None,
         emit_mode: None,
         properties: None,
+        span: NodeSpan::default(),
     };
 
     let result = engine.execute_with_record(&query, record.clone()).await;
@@ -505,17 +556,28 @@ async fn test_in_operator_with_null_values() {
     // Test NOT IN with NULL - should also not match
     let query_not_in = StreamingQuery::Select {
         fields: vec![SelectField::Expression {
-            expr: Expr::Column("id".to_string()),
+            expr: Expr::Column("id".to_string(), NodeSpan::default()),
             alias: None,
         }],
         from: StreamSource::Stream("test".to_string()),
+        from_alias: None,
         where_clause: Some(Expr::BinaryOp {
-            left: Box::new(Expr::Column("nullable_field".to_string())),
+            left: Box::new(Expr::Column(
+                "nullable_field".to_string(),
+                NodeSpan::default(),
+            )),
             op: BinaryOperator::NotIn,
-            right: Box::new(Expr::List(vec![
-                Expr::Literal(LiteralValue::Integer(1)),
-                Expr::Literal(LiteralValue::String("test".to_string())),
-            ])),
+            right: Box::new(Expr::List(
+                vec![
+                    Expr::Literal(LiteralValue::Integer(1), NodeSpan::default()),
+                    Expr::Literal(
+                        LiteralValue::String("test".to_string()),
+                        NodeSpan::default(),
+                    ),
+                ],
+                NodeSpan::default(),
+            )),
+            span: NodeSpan::default(),
         }),
         joins: None,
         group_by: None,
@@ -525,6 +587,7 @@ async fn test_in_operator_with_null_values() {
         limit: None,
         emit_mode: None,
         properties: None,
+        span: NodeSpan::default(),
     };
 
     let result_not_in = engine.execute_with_record(&query_not_in, record).await;
@@ -547,7 +610,7 @@ async fn test_in_operator_edge_cases() {
     fields.insert("name".to_string(), FieldValue::String("hello".to_string()));
 
     let record = StreamRecord {
-        fields,
+        fields: fields.into(),
         timestamp: chrono::Utc::now().timestamp_millis(),
         offset: 0,
         partition: 0,
@@ -559,18 +622,20 @@ async fn test_in_operator_edge_cases() {
 
     // Test large list
     let large_list = (1..=100)
-        .map(|i| Expr::Literal(LiteralValue::Integer(i)))
+        .map(|i| Expr::Literal(LiteralValue::Integer(i), NodeSpan::default()))
         .collect();
     let query_large = StreamingQuery::Select {
         fields: vec![SelectField::Expression {
-            expr: Expr::Column("id".to_string()),
+            expr: Expr::Column("id".to_string(), NodeSpan::default()),
             alias: None,
         }],
         from: StreamSource::Stream("test".to_string()),
+        from_alias: None,
         where_clause: Some(Expr::BinaryOp {
-            left: Box::new(Expr::Column("id".to_string())),
+            left: Box::new(Expr::Column("id".to_string(), NodeSpan::default())),
             op: BinaryOperator::In,
-            right: Box::new(Expr::List(large_list)),
+            right: Box::new(Expr::List(large_list, NodeSpan::default())),
+            span: NodeSpan::default(),
         }),
         joins: None,
         group_by: None,
@@ -580,6 +645,7 @@ async fn test_in_operator_edge_cases() {
         limit: None,
         emit_mode: None,
         properties: None,
+        span: NodeSpan::default(),
     };
 
     let result_large = engine
@@ -594,18 +660,23 @@ async fn test_in_operator_edge_cases() {
     // Test with duplicate values in list (should still work)
     let query_duplicates = StreamingQuery::Select {
         fields: vec![SelectField::Expression {
-            expr: Expr::Column("id".to_string()),
+            expr: Expr::Column("id".to_string(), NodeSpan::default()),
             alias: None,
         }],
         from: StreamSource::Stream("test".to_string()),
+        from_alias: None,
         where_clause: Some(Expr::BinaryOp {
-            left: Box::new(Expr::Column("id".to_string())),
+            left: Box::new(Expr::Column("id".to_string(), NodeSpan::default())),
             op: BinaryOperator::In,
-            right: Box::new(Expr::List(vec![
-                Expr::Literal(LiteralValue::Integer(5)),
-                Expr::Literal(LiteralValue::Integer(5)),
-                Expr::Literal(LiteralValue::Integer(5)),
-            ])),
+            right: Box::new(Expr::List(
+                vec![
+                    Expr::Literal(LiteralValue::Integer(5), NodeSpan::default()),
+                    Expr::Literal(LiteralValue::Integer(5), NodeSpan::default()),
+                    Expr::Literal(LiteralValue::Integer(5), NodeSpan::default()),
+                ],
+                NodeSpan::default(),
+            )),
+            span: NodeSpan::default(),
         }),
         joins: None,
         group_by: None,
@@ -615,6 +686,7 @@ async fn test_in_operator_edge_cases() {
         limit: None,
         emit_mode: None,
         properties: None,
+        span: NodeSpan::default(),
     };
 
     let result_duplicates = engine.execute_with_record(&query_duplicates, record).await;
@@ -643,18 +715,21 @@ fn parse_in_list(list_str: &str) -> Expr {
             if trimmed.starts_with('\'') && trimmed.ends_with('\'') {
                 // String literal
                 let content = &trimmed[1..trimmed.len() - 1];
-                Expr::Literal(LiteralValue::String(content.to_string()))
+                Expr::Literal(
+                    LiteralValue::String(content.to_string()),
+                    NodeSpan::default(),
+                )
             } else if trimmed.contains('.') {
                 // Float literal
                 let value: f64 = trimmed.parse().expect("Invalid float in test");
-                Expr::Literal(LiteralValue::Float(value))
+                Expr::Literal(LiteralValue::Float(value), NodeSpan::default())
             } else {
                 // Integer literal
                 let value: i64 = trimmed.parse().expect("Invalid integer in test");
-                Expr::Literal(LiteralValue::Integer(value))
+                Expr::Literal(LiteralValue::Integer(value), NodeSpan::default())
             }
         })
         .collect();
 
-    Expr::List(items)
+    Expr::List(items, NodeSpan::default())
 }
@@ -0,0 +1,241 @@
+/*!
+# Compiled Expression Tests
+
+Compiled plans must give the same results and errors as the interpreter they replace.
+*/
+
+use ferrisstreams::ferris::sql::ast::{Expr, SelectField, StreamingQuery};
+use ferrisstreams::ferris::sql::execution::expression::{
+    CompiledExpression, CompiledQuery, ExpressionEvaluator,
+};
+use ferrisstreams::ferris::sql::execution::processors::{ProcessorContext, SelectProcessor};
+use ferrisstreams::ferris::sql::execution::{FieldValue, StreamExecutionEngine, StreamRecord};
+use ferrisstreams::ferris::sql::StreamingSqlParser;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+fn parse_expr(expr: &str) -> Expr {
+    let query = StreamingSqlParser::new()
+        .parse(&format!("SELECT {} AS v FROM orders", expr))
+        .expect("expression should parse");
+    match query {
+        StreamingQuery::Select { mut fields, .. } => match fields.remove(0) {
+            SelectField::Expression { expr, .. } => expr,
+            other => panic!("unexpected field {:?}", other),
+        },
+        other => panic!("unexpected query {:?}", other),
+    }
+}
+
+fn create_record(status: Option<&str>) -> StreamRecord {
+    let mut fields = HashMap::new();
+    fields.insert("id".to_string(), FieldValue::Integer(7));
+    fields.insert("amount".to_string(), FieldValue::Float(150.5));
+    if let Some(status) = status {
+        fields.insert("status".to_string(), FieldValue::String(status.to_string()));
+    }
+    let mut shipping = HashMap::new();
+    shipping.insert("city".to_string(), FieldValue::String("Oslo".to_string()));
+    fields.insert("shipping".to_string(), FieldValue::Struct(shipping));
+
+    StreamRecord {
+        fields: fields.into(),
+        timestamp: 1_700_000_000_000,
+        offset: 42,
+        partition: 3,
+        headers: HashMap::new(),
+    }
+}
+
+#[test]
+fn test_compiled_matches_interpreter() {
+    let expressions = [
+        "amount * 2 + id",
+        "id > 1 AND status IS NOT NULL",
+        "status LIKE 'ship%'",
+        "id LIKE 'x%'",
+        "status LIKE status",
+        "status IN ('shipped', 'new')",
+        "status NOT IN ('new')",
+        "missing IN ('a', NULL)",
+        "NOT (missing IN ('a', NULL))",
+        "amount BETWEEN 100 AND 200",
+        "CASE WHEN amount > 1000 THEN 'big' WHEN status IN ('shipped') THEN 'shipped' ELSE 'other' END",
+        "CASE WHEN UPPER(status) THEN 1 ELSE 0 END",
+        "UPPER(status) || '-' || CAST(id, 'STRING')",
+        "COALESCE(missing, status, 'none')",
+        "shipping.city = 'Oslo'",
+        "o.amount > 100",
+        "_offset + _partition",
+        "_TIMESTAMP > 0",
+        "status + 1",
+        "UNKNOWN_FUNCTION(id)",
+        "REGEXP_LIKE(status, '^SH', 'i')",
+        "REGEXP_LIKE(status, status)",
+        "REGEXP_LIKE(status, '(unclosed')",
+        "REGEXP_LIKE(status, 'a', 'q')",
+        "REGEXP_EXTRACT(status, '(s)(h)', 2)",
+        "REGEXP_REPLACE(status, 'e', CAST(id, 'STRING'))",
+        "REGEXP_SPLIT(status, 'p')",
+    ];
+    let context = ProcessorContext::new("compiled_test");
+
+    for status in [Some("shipped"), Some("new"), None] {
+        let record = create_record(status);
+        for sql in expressions {
+            let expr = parse_expr(sql);
+            let compiled = CompiledExpression::compile(&expr).expect("no subqueries");
+
+            let interpreted = ExpressionEvaluator::evaluate_expression_value_with_subqueries(
+                &expr,
+                &record,
+                &SelectProcessor,
+                &context,
+            );
+            assert_eq!(
+                format!("{:?}", compiled.evaluate(&record)),
+                format!("{:?}", interpreted),
+                "value of {} with status {:?}",
+                sql,
+                status
+            );
+
+            let interpreted = ExpressionEvaluator::evaluate_expression_with_subqueries(
+                &expr,
+                &record,
+                &SelectProcessor,
+                &context,
+            );
+            assert_eq!(
+                format!("{:?}", compiled.evaluate_bool(&record)),
+                format!("{:?}", interpreted),
+                "predicate {} with status {:?}",
+                sql,
+                status
+            );
+        }
+    }
+}
+
+#[test]
+fn test_columns_share_slots() {
+    let compiled =
+        CompiledExpression::compile(&parse_expr("amount > 1 AND amount < 10 OR id = 2")).unwrap();
+    assert_eq!(compiled.columns().collect::<Vec<_>>(), vec!["amount", "id"]);
+}
+
+#[test]
+fn test_query_plan_compiles_where_and_fields() {
+    let query = StreamingSqlParser::new()
+        .parse(
+            "CREATE STREAM big AS SELECT id, amount * 2 AS doubled, \
+             EXISTS (SELECT id FROM customers WHERE tier > 1) AS vip \
+             FROM orders WHERE amount > 100",
+        )
+        .unwrap();
+    let plan = CompiledQuery::new(&query);
+    let record = create_record(Some("shipped"));
+
+    assert!(plan.where_clause().unwrap().evaluate_bool(&record).unwrap());
+    assert_eq!(
+        plan.field(0).unwrap().evaluate(&record).unwrap(),
+        FieldValue::Integer(7)
+    );
+    assert_eq!(
+        plan.field(1).unwrap().evaluate(&record).unwrap(),
+        FieldValue::Float(301.0)
+    );
+    // Subqueries need the executor, so the interpreter keeps handling them
+    assert!(plan.field(2).is_none());
+    assert!(plan.field(3).is_none());
+
+    // Wildcards and other statements have nothing to compile
+    let plan = CompiledQuery::new(
+        &StreamingSqlParser::new()
+            .parse("SELECT * FROM orders")
+            .unwrap(),
+    );
+    assert!(plan.where_clause().is_none() && plan.field(0).is_none());
+}
+
+#[test]
+fn test_bound_plan_reads_fields_by_position() {
+    let mut compiled = CompiledExpression::compile(&parse_expr("amount + id")).unwrap();
+    let record = create_record(None);
+    let schema = Arc::clone(record.fields.schema());
+    compiled.bind(&schema);
+    assert_eq!(
+        compiled.evaluate(&record).unwrap(),
+        FieldValue::Float(157.5)
+    );
+
+    // Records with another layout are read by name
+    let reordered = StreamRecord::new(HashMap::from([
+        ("shipping".to_string(), FieldValue::Null),
+        ("id".to_string(), FieldValue::Integer(1)),
+        ("amount".to_string(), FieldValue::Float(2.5)),
+    ]));
+    assert!(!Arc::ptr_eq(reordered.fields.schema(), &schema));
+    assert_eq!(
+        compiled.evaluate(&reordered).unwrap(),
+        FieldValue::Float(3.5)
+    );
+
+    let mut plan = CompiledQuery::new(
+        &StreamingSqlParser::new()
+            .parse("SELECT amount + id AS total FROM orders")
+            .unwrap(),
+    );
+    assert!(!plan.is_bound_to(&schema));
+    plan.bind(&schema);
+    assert!(plan.is_bound_to(&schema));
+    assert_eq!(
+        plan.field(0).unwrap().evaluate(&record).unwrap(),
+        FieldValue::Float(157.5)
+    );
+}
+
+#[tokio::test]
+async fn test_planned_query_matches_interpreted_query() {
+    let query = StreamingSqlParser::new()
+        .parse(
+            "SELECT id, amount * 2 AS doubled, UPPER(status) AS status_upper, \
+             shipping.city AS city FROM orders WHERE amount > 100 AND status IS NOT NULL",
+        )
+        .unwrap();
+    let records = [
+        create_record(Some("shipped")),
+        create_record(None),
+        create_record(Some("pending")),
+    ];
+
+    let mut outputs = Vec::new();
+    for planned in [false, true] {
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let mut engine = StreamExecutionEngine::new(sender);
+        if planned {
+            engine.plan_query(&query);
+        }
+        for record in &records {
+            engine
+                .execute_with_record(&query, record.clone())
+                .await
+                .unwrap();
+        }
+        let mut results = Vec::new();
+        while let Ok(result) = receiver.try_recv() {
+            results.push(result.fields);
+        }
+        outputs.push(results);
+    }
+
+    assert_eq!(outputs[0].len(), 2);
+    assert_eq!(outputs[0], outputs[1]);
+    // Planned output records share the query's output schema
+    assert!(Arc::ptr_eq(outputs[1][0].schema(), outputs[1][1].schema()));
+    assert_eq!(
+        outputs[1][0].get("status_upper"),
+        Some(&FieldValue::String("SHIPPED".to_string()))
+    );
+}
@@ -7,7 +7,7 @@ and complex nested expressions.
 
 use ferrisstreams::ferris::serialization::JsonFormat;
 use ferrisstreams::ferris::sql::ast::{
-    BinaryOperator, Expr, LiteralValue, SelectField, StreamSource, StreamingQuery,
+    BinaryOperator, Expr, LiteralValue, NodeSpan, SelectField, StreamSource, StreamingQuery,
 };
 use ferrisstreams::ferris::sql::execution::{FieldValue, StreamExecutionEngine, StreamRecord};
 use std::collections::HashMap;
@@ -33,7 +33,7 @@ fn create_test_record(
     );
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         timestamp: chrono::Utc::now().timestamp_millis(),
         offset: id,
         partition: 0,
@@ -49,13 +49,15 @@ async fn test_arithmetic_expressions() {
     let query = StreamingQuery::Select {
         fields: vec![SelectField::Expression {
             expr: Expr::BinaryOp {
-                left: Box::new(Expr::Column("amount".to_string())),
+                left: Box::new(Expr::Column("amount".to_string(), NodeSpan::default())),
                 op: BinaryOperator::Multiply,
-                right: Box::new(Expr::Literal(LiteralValue::Float(1.1))),
+                right: Box::new(Expr::Literal(LiteralValue::Float(1.1), NodeSpan::default())),
+                span: NodeSpan::default(),
             },
             alias: Some("amount_with_tax".to_string()),
         }],
         from: StreamSource::Stream("orders".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         window: None,
@@ -65,6 +67,7 @@ async fn test_arithmetic_expressions() {
         limit: None,
         emit_mode: None,
         properties: None,
+        span: NodeSpan::default(),
     };
 
     let record = create_test_record(1, 100, 100.0, Some("pending"));
@@ -88,13 +91,18 @@ async fn test_boolean_expressions() {
     let query = StreamingQuery::Select {
         fields: vec![SelectField::Expression {
             expr: Expr::BinaryOp {
-                left: Box::new(Expr::Column("amount".to_string())),
+                left: Box::new(Expr::Column("amount".to_string(), NodeSpan::default())),
                 op: BinaryOperator::GreaterThan,
-                right: Box::new(Expr::Literal(LiteralValue::Float(200.0))),
+                right: Box::new(Expr::Literal(
+                    LiteralValue::Float(200.0),
+                    NodeSpan::default(),
+                )),
+                span: NodeSpan::default(),
             },
             alias: Some("is_large_order".to_string()),
         }],
         from: StreamSource::Stream("orders".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         window: None,
@@ -104,6 +112,7 @@ async fn test_boolean_expressions() {
         limit: None,
         emit_mode: None,
         properties: None,
+        span: NodeSpan::default(),
     };
 
     let record = create_test_record(1, 100, 299.99, Some("pending"));
@@ -128,16 +137,22 @@ async fn test_complex_expression_evaluation() {
         fields: vec![SelectField::Expression {
             expr: Expr::BinaryOp {
                 left: Box::new(Expr::BinaryOp {
-                    left: Box::new(Expr::Column("amount".to_string())),
+                    left: Box::new(Expr::Column("amount".to_string(), NodeSpan::default())),
                     op: BinaryOperator::Multiply,
-                    right: Box::new(Expr::Literal(LiteralValue::Float(1.1))),
+                    right: Box::new(Expr::Literal(LiteralValue::Float(1.1), NodeSpan::default())),
+                    span: NodeSpan::default(),
                 }),
                 op: BinaryOperator::Add,
-                right: Box::new(Expr::Literal(LiteralValue::Integer(10))),
+                right: Box::new(Expr::Literal(
+                    LiteralValue::Integer(10),
+                    NodeSpan::default(),
+                )),
+                span: NodeSpan::default(),
             },
             alias: Some("complex_calc".to_string()),
         }],
         from: StreamSource::Stream("orders".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         window: None,
@@ -147,6 +162,7 @@ async fn test_complex_expression_evaluation() {
         limit: None,
         emit_mode: None,
         properties: None,
+        span: NodeSpan::default(),
     };
 
     let record = create_test_record(1, 100, 100.0, Some("pending"));
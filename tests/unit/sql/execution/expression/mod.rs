@@ -4,4 +4,5 @@
 //! function calls, and expression processing.
 
 pub mod arithmetic;
+pub mod compiled_expression_test;
 pub mod expression_evaluation_test;
@@ -11,6 +11,9 @@ pub mod expression;
 // Aggregation tests
 pub mod aggregation;
 
+// Columnar batch execution tests
+pub mod columnar;
+
 // Query processor tests
 pub mod processors;
 
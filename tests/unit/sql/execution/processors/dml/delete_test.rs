@@ -4,7 +4,7 @@
 Comprehensive test suite for DELETE operations.
 */
 
-use ferrisstreams::ferris::sql::ast::{Expr, LiteralValue};
+use ferrisstreams::ferris::sql::ast::{Expr, LiteralValue, NodeSpan};
 use ferrisstreams::ferris::sql::execution::processors::DeleteProcessor;
 use ferrisstreams::ferris::sql::execution::{FieldValue, StreamRecord};
 use std::collections::HashMap;
@@ -21,7 +21,7 @@ fn create_test_record() -> StreamRecord {
     );
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         headers: {
             let mut headers = HashMap::new();
             headers.insert("source".to_string(), "test".to_string());
@@ -38,9 +38,13 @@ fn create_test_record() -> StreamRecord {
 async fn test_delete_with_where_match() {
     let table_name = "users";
     let where_clause = Some(Expr::BinaryOp {
-        left: Box::new(Expr::Column("id".to_string())),
+        left: Box::new(Expr::Column("id".to_string(), NodeSpan::default())),
         op: ferrisstreams::ferris::sql::ast::BinaryOperator::Equal,
-        right: Box::new(Expr::Literal(LiteralValue::Integer(100))),
+        right: Box::new(Expr::Literal(
+            LiteralValue::Integer(100),
+            NodeSpan::default(),
+        )),
+        span: NodeSpan::default(),
     });
     let input_record = create_test_record();
 
@@ -90,9 +94,13 @@ async fn test_delete_with_where_match() {
 async fn test_delete_with_where_no_match() {
     let table_name = "users";
     let where_clause = Some(Expr::BinaryOp {
-        left: Box::new(Expr::Column("id".to_string())),
+        left: Box::new(Expr::Column("id".to_string(), NodeSpan::default())),
         op: ferrisstreams::ferris::sql::ast::BinaryOperator::Equal,
-        right: Box::new(Expr::Literal(LiteralValue::Integer(999))), // No match
+        right: Box::new(Expr::Literal(
+            LiteralValue::Integer(999),
+            NodeSpan::default(),
+        )),
+        span: NodeSpan::default(), // No match
     });
     let input_record = create_test_record();
 
@@ -131,16 +139,25 @@ async fn test_delete_with_complex_where() {
     // WHERE age > 18 AND status = 'pending'
     let where_clause = Some(Expr::BinaryOp {
         left: Box::new(Expr::BinaryOp {
-            left: Box::new(Expr::Column("age".to_string())),
+            left: Box::new(Expr::Column("age".to_string(), NodeSpan::default())),
             op: ferrisstreams::ferris::sql::ast::BinaryOperator::GreaterThan,
-            right: Box::new(Expr::Literal(LiteralValue::Integer(18))),
+            right: Box::new(Expr::Literal(
+                LiteralValue::Integer(18),
+                NodeSpan::default(),
+            )),
+            span: NodeSpan::default(),
         }),
         op: ferrisstreams::ferris::sql::ast::BinaryOperator::And,
         right: Box::new(Expr::BinaryOp {
-            left: Box::new(Expr::Column("status".to_string())),
+            left: Box::new(Expr::Column("status".to_string(), NodeSpan::default())),
             op: ferrisstreams::ferris::sql::ast::BinaryOperator::Equal,
-            right: Box::new(Expr::Literal(LiteralValue::String("pending".to_string()))),
+            right: Box::new(Expr::Literal(
+                LiteralValue::String("pending".to_string()),
+                NodeSpan::default(),
+            )),
+            span: NodeSpan::default(),
         }),
+        span: NodeSpan::default(),
     });
     let input_record = create_test_record(); // age=25, status='pending'
 
@@ -265,9 +282,10 @@ async fn test_delete_validation_empty_table() {
 async fn test_delete_validation_valid() {
     let table_name = "valid_table";
     let where_clause = Some(Expr::BinaryOp {
-        left: Box::new(Expr::Column("id".to_string())),
+        left: Box::new(Expr::Column("id".to_string(), NodeSpan::default())),
         op: ferrisstreams::ferris::sql::ast::BinaryOperator::GreaterThan,
-        right: Box::new(Expr::Literal(LiteralValue::Integer(0))),
+        right: Box::new(Expr::Literal(LiteralValue::Integer(0), NodeSpan::default())),
+        span: NodeSpan::default(),
     });
 
     let result = DeleteProcessor::validate_delete(table_name, &where_clause);
@@ -279,9 +297,13 @@ async fn test_delete_validation_valid() {
 #[tokio::test]
 async fn test_matches_where_clause() {
     let where_clause = Some(Expr::BinaryOp {
-        left: Box::new(Expr::Column("active".to_string())),
+        left: Box::new(Expr::Column("active".to_string(), NodeSpan::default())),
         op: ferrisstreams::ferris::sql::ast::BinaryOperator::Equal,
-        right: Box::new(Expr::Literal(LiteralValue::Boolean(true))),
+        right: Box::new(Expr::Literal(
+            LiteralValue::Boolean(true),
+            NodeSpan::default(),
+        )),
+        span: NodeSpan::default(),
     });
     let input_record = create_test_record(); // active = true
 
@@ -292,9 +314,13 @@ async fn test_matches_where_clause() {
 
     // Test no match
     let no_match_clause = Some(Expr::BinaryOp {
-        left: Box::new(Expr::Column("active".to_string())),
+        left: Box::new(Expr::Column("active".to_string(), NodeSpan::default())),
         op: ferrisstreams::ferris::sql::ast::BinaryOperator::Equal,
-        right: Box::new(Expr::Literal(LiteralValue::Boolean(false))),
+        right: Box::new(Expr::Literal(
+            LiteralValue::Boolean(false),
+            NodeSpan::default(),
+        )),
+        span: NodeSpan::default(),
     });
 
     let result2 = DeleteProcessor::matches_where_clause(&no_match_clause, &input_record);
@@ -308,12 +334,17 @@ async fn test_matches_where_clause() {
 async fn test_get_referenced_columns() {
     let where_clause = Some(Expr::BinaryOp {
         left: Box::new(Expr::BinaryOp {
-            left: Box::new(Expr::Column("age".to_string())),
+            left: Box::new(Expr::Column("age".to_string(), NodeSpan::default())),
             op: ferrisstreams::ferris::sql::ast::BinaryOperator::GreaterThan,
-            right: Box::new(Expr::Literal(LiteralValue::Integer(18))),
+            right: Box::new(Expr::Literal(
+                LiteralValue::Integer(18),
+                NodeSpan::default(),
+            )),
+            span: NodeSpan::default(),
         }),
         op: ferrisstreams::ferris::sql::ast::BinaryOperator::And,
-        right: Box::new(Expr::Column("status".to_string())),
+        right: Box::new(Expr::Column("status".to_string(), NodeSpan::default())),
+        span: NodeSpan::default(),
     });
 
     let columns = DeleteProcessor::get_referenced_columns(&where_clause);
@@ -382,13 +413,17 @@ async fn test_delete_with_in_condition() {
     let table_name = "users";
     // WHERE id IN (100, 200, 300)
     let where_clause = Some(Expr::BinaryOp {
-        left: Box::new(Expr::Column("id".to_string())),
+        left: Box::new(Expr::Column("id".to_string(), NodeSpan::default())),
         op: ferrisstreams::ferris::sql::ast::BinaryOperator::In,
-        right: Box::new(Expr::List(vec![
-            Expr::Literal(LiteralValue::Integer(100)),
-            Expr::Literal(LiteralValue::Integer(200)),
-            Expr::Literal(LiteralValue::Integer(300)),
-        ])),
+        right: Box::new(Expr::List(
+            vec![
+                Expr::Literal(LiteralValue::Integer(100), NodeSpan::default()),
+                Expr::Literal(LiteralValue::Integer(200), NodeSpan::default()),
+                Expr::Literal(LiteralValue::Integer(300), NodeSpan::default()),
+            ],
+            NodeSpan::default(),
+        )),
+        span: NodeSpan::default(),
     });
     let input_record = create_test_record(); // id = 100
 
@@ -412,7 +447,8 @@ async fn test_delete_with_not_condition() {
     // WHERE NOT active
     let where_clause = Some(Expr::UnaryOp {
         op: ferrisstreams::ferris::sql::ast::UnaryOperator::Not,
-        expr: Box::new(Expr::Column("active".to_string())),
+        expr: Box::new(Expr::Column("active".to_string(), NodeSpan::default())),
+        span: NodeSpan::default(),
     });
     let input_record = create_test_record(); // active = true
 
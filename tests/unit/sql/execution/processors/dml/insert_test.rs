@@ -4,7 +4,7 @@
 Comprehensive test suite for INSERT INTO operations.
 */
 
-use ferrisstreams::ferris::sql::ast::{Expr, InsertSource, LiteralValue, StreamingQuery};
+use ferrisstreams::ferris::sql::ast::{Expr, InsertSource, LiteralValue, NodeSpan, StreamingQuery};
 use ferrisstreams::ferris::sql::execution::processors::InsertProcessor;
 use ferrisstreams::ferris::sql::execution::{FieldValue, StreamRecord};
 use std::collections::HashMap;
@@ -18,7 +18,7 @@ fn create_test_record() -> StreamRecord {
     );
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         headers: HashMap::new(),
         timestamp: 1234567890000,
         offset: 1,
@@ -33,8 +33,11 @@ async fn test_insert_values_single_row() {
     let columns = Some(vec!["id".to_string(), "name".to_string()]);
     let values_source = InsertSource::Values {
         rows: vec![vec![
-            Expr::Literal(LiteralValue::Integer(100)),
-            Expr::Literal(LiteralValue::String("Alice".to_string())),
+            Expr::Literal(LiteralValue::Integer(100), NodeSpan::default()),
+            Expr::Literal(
+                LiteralValue::String("Alice".to_string()),
+                NodeSpan::default(),
+            ),
         ]],
     };
     let input_record = create_test_record();
@@ -68,14 +71,17 @@ async fn test_insert_values_multiple_rows() {
     let values_source = InsertSource::Values {
         rows: vec![
             vec![
-                Expr::Literal(LiteralValue::Integer(1)),
-                Expr::Literal(LiteralValue::String("Alice".to_string())),
-                Expr::Literal(LiteralValue::Boolean(true)),
+                Expr::Literal(LiteralValue::Integer(1), NodeSpan::default()),
+                Expr::Literal(
+                    LiteralValue::String("Alice".to_string()),
+                    NodeSpan::default(),
+                ),
+                Expr::Literal(LiteralValue::Boolean(true), NodeSpan::default()),
             ],
             vec![
-                Expr::Literal(LiteralValue::Integer(2)),
-                Expr::Literal(LiteralValue::String("Bob".to_string())),
-                Expr::Literal(LiteralValue::Boolean(false)),
+                Expr::Literal(LiteralValue::Integer(2), NodeSpan::default()),
+                Expr::Literal(LiteralValue::String("Bob".to_string()), NodeSpan::default()),
+                Expr::Literal(LiteralValue::Boolean(false), NodeSpan::default()),
             ],
         ],
     };
@@ -122,9 +128,12 @@ async fn test_insert_values_no_explicit_columns() {
     let columns = None;
     let values_source = InsertSource::Values {
         rows: vec![vec![
-            Expr::Literal(LiteralValue::Integer(101)),
-            Expr::Literal(LiteralValue::String("Widget".to_string())),
-            Expr::Literal(LiteralValue::Float(29.99)),
+            Expr::Literal(LiteralValue::Integer(101), NodeSpan::default()),
+            Expr::Literal(
+                LiteralValue::String("Widget".to_string()),
+                NodeSpan::default(),
+            ),
+            Expr::Literal(LiteralValue::Float(29.99), NodeSpan::default()),
         ]],
     };
     let input_record = create_test_record();
@@ -158,17 +167,20 @@ async fn test_insert_select() {
             ferrisstreams::ferris::sql::ast::SelectField::Expression {
                 expr: ferrisstreams::ferris::sql::ast::Expr::Literal(
                     ferrisstreams::ferris::sql::ast::LiteralValue::Integer(42),
+                    NodeSpan::default(),
                 ),
                 alias: Some("id".to_string()),
             },
             ferrisstreams::ferris::sql::ast::SelectField::Expression {
                 expr: ferrisstreams::ferris::sql::ast::Expr::Literal(
                     ferrisstreams::ferris::sql::ast::LiteralValue::String("test_name".to_string()),
+                    NodeSpan::default(),
                 ),
                 alias: Some("name".to_string()),
             },
         ],
         from: ferrisstreams::ferris::sql::ast::StreamSource::Table("source_table".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         group_by: None,
@@ -178,6 +190,7 @@ async fn test_insert_select() {
         limit: None,
         emit_mode: None,
         properties: None,
+        span: NodeSpan::default(),
     };
 
     let select_source = InsertSource::Select {
@@ -205,7 +218,7 @@ async fn test_insert_column_count_mismatch() {
     let columns = Some(vec!["id".to_string(), "name".to_string()]); // 2 columns
     let values_source = InsertSource::Values {
         rows: vec![vec![
-            Expr::Literal(LiteralValue::Integer(100)),
+            Expr::Literal(LiteralValue::Integer(100), NodeSpan::default()),
             // Missing second value - should cause error
         ]],
     };
@@ -225,7 +238,10 @@ async fn test_insert_validation_empty_table() {
     let table_name = "";
     let columns = Some(vec!["id".to_string()]);
     let values_source = InsertSource::Values {
-        rows: vec![vec![Expr::Literal(LiteralValue::Integer(100))]],
+        rows: vec![vec![Expr::Literal(
+            LiteralValue::Integer(100),
+            NodeSpan::default(),
+        )]],
     };
 
     let result = InsertProcessor::validate_insert(table_name, &columns, &values_source);
@@ -259,11 +275,14 @@ async fn test_insert_validation_inconsistent_rows() {
     let values_source = InsertSource::Values {
         rows: vec![
             vec![
-                Expr::Literal(LiteralValue::Integer(1)),
-                Expr::Literal(LiteralValue::String("Alice".to_string())),
+                Expr::Literal(LiteralValue::Integer(1), NodeSpan::default()),
+                Expr::Literal(
+                    LiteralValue::String("Alice".to_string()),
+                    NodeSpan::default(),
+                ),
             ],
             vec![
-                Expr::Literal(LiteralValue::Integer(2)),
+                Expr::Literal(LiteralValue::Integer(2), NodeSpan::default()),
                 // Missing second value - inconsistent with first row
             ],
         ],
@@ -286,9 +305,15 @@ async fn test_get_insert_columns_explicit() {
     ]);
     let values_source = InsertSource::Values {
         rows: vec![vec![
-            Expr::Literal(LiteralValue::Integer(1)),
-            Expr::Literal(LiteralValue::String("Test".to_string())),
-            Expr::Literal(LiteralValue::String("test@example.com".to_string())),
+            Expr::Literal(LiteralValue::Integer(1), NodeSpan::default()),
+            Expr::Literal(
+                LiteralValue::String("Test".to_string()),
+                NodeSpan::default(),
+            ),
+            Expr::Literal(
+                LiteralValue::String("test@example.com".to_string()),
+                NodeSpan::default(),
+            ),
         ]],
     };
 
@@ -305,9 +330,12 @@ async fn test_get_insert_columns_inferred() {
     let columns = None;
     let values_source = InsertSource::Values {
         rows: vec![vec![
-            Expr::Literal(LiteralValue::Integer(1)),
-            Expr::Literal(LiteralValue::String("Test".to_string())),
-            Expr::Literal(LiteralValue::Boolean(true)),
+            Expr::Literal(LiteralValue::Integer(1), NodeSpan::default()),
+            Expr::Literal(
+                LiteralValue::String("Test".to_string()),
+                NodeSpan::default(),
+            ),
+            Expr::Literal(LiteralValue::Boolean(true), NodeSpan::default()),
         ]],
     };
 
@@ -325,8 +353,11 @@ async fn test_insert_with_expressions() {
     let columns = Some(vec!["context_id".to_string(), "calculated".to_string()]);
     let values_source = InsertSource::Values {
         rows: vec![vec![
-            Expr::Column("context_id".to_string()), // Reference to input record field
-            Expr::Literal(LiteralValue::String("computed_value".to_string())),
+            Expr::Column("context_id".to_string(), NodeSpan::default()), // Reference to input record field
+            Expr::Literal(
+                LiteralValue::String("computed_value".to_string()),
+                NodeSpan::default(),
+            ),
         ]],
     };
     let input_record = create_test_record();
@@ -357,8 +388,8 @@ async fn test_insert_with_null_values() {
     let columns = Some(vec!["id".to_string(), "optional_field".to_string()]);
     let values_source = InsertSource::Values {
         rows: vec![vec![
-            Expr::Literal(LiteralValue::Integer(1)),
-            Expr::Literal(LiteralValue::Null),
+            Expr::Literal(LiteralValue::Integer(1), NodeSpan::default()),
+            Expr::Literal(LiteralValue::Null, NodeSpan::default()),
         ]],
     };
     let input_record = create_test_record();
@@ -385,8 +416,8 @@ async fn test_insert_performance_many_rows() {
     let mut rows = Vec::new();
     for i in 0..100 {
         rows.push(vec![
-            Expr::Literal(LiteralValue::Integer(i)),
-            Expr::Literal(LiteralValue::Integer(42)), // batch_id
+            Expr::Literal(LiteralValue::Integer(i), NodeSpan::default()),
+            Expr::Literal(LiteralValue::Integer(42), NodeSpan::default()), // batch_id
         ]);
     }
 
@@ -0,0 +1,214 @@
+/*!
+# MERGE and UPSERT Processor Tests
+
+Tests for keyed target rows, clause selection and the changelog records emitted by
+MERGE INTO and UPSERT INTO.
+*/
+
+use ferrisstreams::ferris::sql::ast::StreamingQuery;
+use ferrisstreams::ferris::sql::execution::processors::merge::{
+    CHANGE_KEY_HEADER, CHANGE_OPERATION_HEADER,
+};
+use ferrisstreams::ferris::sql::execution::processors::{ProcessorContext, QueryProcessor};
+use ferrisstreams::ferris::sql::execution::{FieldValue, StreamRecord};
+use ferrisstreams::ferris::sql::parser::StreamingSqlParser;
+use ferrisstreams::ferris::sql::SqlError;
+use std::collections::HashMap;
+
+const INVENTORY_MERGE: &str = "MERGE INTO inventory t
+    USING stock_movements s
+    ON t.sku = s.sku
+    WHEN MATCHED AND t.qty + s.qty = 0 THEN DELETE
+    WHEN MATCHED THEN UPDATE SET qty = t.qty + s.qty
+    WHEN NOT MATCHED AND s.qty > 0 THEN INSERT (sku, qty) VALUES (s.sku, s.qty)";
+
+fn parse(sql: &str) -> StreamingQuery {
+    StreamingSqlParser::new().parse(sql).unwrap()
+}
+
+fn movement(sku: Option<&str>, qty: i64) -> StreamRecord {
+    let mut fields = HashMap::new();
+    fields.insert(
+        "sku".to_string(),
+        sku.map_or(FieldValue::Null, |s| FieldValue::String(s.to_string())),
+    );
+    fields.insert("qty".to_string(), FieldValue::Integer(qty));
+    StreamRecord {
+        fields: fields.into(),
+        headers: HashMap::new(),
+        timestamp: 1710513045000,
+        offset: 5,
+        partition: 0,
+    }
+}
+
+fn apply(
+    query: &StreamingQuery,
+    record: &StreamRecord,
+    context: &mut ProcessorContext,
+) -> Result<Option<StreamRecord>, SqlError> {
+    QueryProcessor::process_query(query, record, context).map(|result| result.record)
+}
+
+fn operation(record: &StreamRecord) -> (&str, &str) {
+    (
+        record.headers.get(CHANGE_OPERATION_HEADER).unwrap(),
+        record.headers.get(CHANGE_KEY_HEADER).unwrap(),
+    )
+}
+
+#[test]
+fn test_merge_insert_update_delete() {
+    let query = parse(INVENTORY_MERGE);
+    let mut context = ProcessorContext::new("merge_test");
+
+    let inserted = apply(&query, &movement(Some("A-1"), 5), &mut context)
+        .unwrap()
+        .unwrap();
+    assert_eq!(operation(&inserted), ("INSERT", "A-1"));
+    assert_eq!(inserted.fields.get("qty"), Some(&FieldValue::Integer(5)));
+    assert_eq!(
+        inserted.headers.get("table_name"),
+        Some(&"inventory".to_string())
+    );
+    assert_eq!(inserted.offset, 5);
+
+    let updated = apply(&query, &movement(Some("A-1"), 3), &mut context)
+        .unwrap()
+        .unwrap();
+    assert_eq!(operation(&updated), ("UPDATE", "A-1"));
+    assert_eq!(updated.fields.get("qty"), Some(&FieldValue::Integer(8)));
+    assert_eq!(
+        updated.fields.get("sku"),
+        Some(&FieldValue::String("A-1".to_string()))
+    );
+
+    // The first applicable clause wins: the DELETE condition is checked before UPDATE
+    let deleted = apply(&query, &movement(Some("A-1"), -8), &mut context)
+        .unwrap()
+        .unwrap();
+    assert_eq!(operation(&deleted), ("DELETE", "A-1"));
+    assert_eq!(
+        deleted.fields.get("__deleted"),
+        Some(&FieldValue::Boolean(true))
+    );
+    assert_eq!(deleted.fields.len(), 2);
+    assert!(context.merge_target_states["inventory"].is_empty());
+
+    // No clause applies to a new key with a negative movement
+    assert!(apply(&query, &movement(Some("A-1"), -1), &mut context)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn test_merge_null_key_never_matches() {
+    let query = parse(
+        "MERGE INTO inventory t USING stock_movements s ON t.sku = s.sku \
+         WHEN MATCHED THEN DELETE \
+         WHEN NOT MATCHED THEN INSERT (sku, qty) VALUES (s.sku, s.qty)",
+    );
+    let mut context = ProcessorContext::new("merge_test");
+
+    let inserted = apply(&query, &movement(None, 1), &mut context)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        inserted.headers.get(CHANGE_OPERATION_HEADER),
+        Some(&"INSERT".to_string())
+    );
+    // Rows without a key are emitted but not tracked, so they are never matched
+    assert!(!inserted.headers.contains_key(CHANGE_KEY_HEADER));
+    assert!(context.merge_target_states["inventory"].is_empty());
+}
+
+#[test]
+fn test_merge_composite_key_fills_insert_columns() {
+    let query = parse(
+        "MERGE INTO balances USING ledger l ON balances.account = l.account AND l.region = balances.region \
+         WHEN NOT MATCHED THEN INSERT (balance) VALUES (l.amount)",
+    );
+    let mut record = movement(None, 0);
+    record.fields = HashMap::from([
+        (
+            "account".to_string(),
+            FieldValue::String("acc-1".to_string()),
+        ),
+        ("region".to_string(), FieldValue::String("eu".to_string())),
+        ("amount".to_string(), FieldValue::Integer(100)),
+    ])
+    .into();
+    let mut context = ProcessorContext::new("merge_test");
+
+    let inserted = apply(&query, &record, &mut context).unwrap().unwrap();
+    assert_eq!(
+        inserted.headers.get(CHANGE_KEY_HEADER),
+        Some(&r#"["acc-1","eu"]"#.to_string())
+    );
+    assert_eq!(
+        inserted.fields.get("region"),
+        Some(&FieldValue::String("eu".to_string()))
+    );
+    assert_eq!(
+        inserted.fields.get("balance"),
+        Some(&FieldValue::Integer(100))
+    );
+}
+
+#[test]
+fn test_merge_errors() {
+    let mut context = ProcessorContext::new("merge_test");
+
+    // ON must pair target and source columns
+    let query = parse(
+        "MERGE INTO inventory t USING stock_movements s ON t.sku = 'A-1' \
+         WHEN NOT MATCHED THEN INSERT (sku) VALUES (s.sku)",
+    );
+    assert!(matches!(
+        apply(&query, &movement(Some("A-1"), 1), &mut context),
+        Err(SqlError::ExecutionError { .. })
+    ));
+
+    // Key columns cannot be updated
+    let query = parse(
+        "MERGE INTO inventory t USING stock_movements s ON t.sku = s.sku \
+         WHEN MATCHED THEN UPDATE SET sku = 'B-2' \
+         WHEN NOT MATCHED THEN INSERT (sku, qty) VALUES (s.sku, s.qty)",
+    );
+    apply(&query, &movement(Some("A-1"), 1), &mut context).unwrap();
+    match apply(&query, &movement(Some("A-1"), 1), &mut context) {
+        Err(SqlError::ExecutionError { message, .. }) => {
+            assert!(message.contains("key column 'sku'"), "{}", message)
+        }
+        other => panic!("Expected ExecutionError, got: {:?}", other),
+    }
+}
+
+#[test]
+fn test_upsert_inserts_then_overlays() {
+    let mut context = ProcessorContext::new("upsert_test");
+    let full = parse("UPSERT INTO stock (sku, qty, warehouse) VALUES (sku, qty, 'north')");
+    let partial = parse("UPSERT INTO stock (sku, qty) VALUES (sku, qty * 10)");
+
+    let inserted = apply(&full, &movement(Some("A-1"), 2), &mut context)
+        .unwrap()
+        .unwrap();
+    assert_eq!(operation(&inserted), ("INSERT", "A-1"));
+
+    // Columns that are not listed keep their current values
+    let updated = apply(&partial, &movement(Some("A-1"), 3), &mut context)
+        .unwrap()
+        .unwrap();
+    assert_eq!(operation(&updated), ("UPDATE", "A-1"));
+    assert_eq!(updated.fields.get("qty"), Some(&FieldValue::Integer(30)));
+    assert_eq!(
+        updated.fields.get("warehouse"),
+        Some(&FieldValue::String("north".to_string()))
+    );
+
+    // The key must not be NULL
+    assert!(matches!(
+        apply(&partial, &movement(None, 3), &mut context),
+        Err(SqlError::ExecutionError { .. })
+    ));
+}
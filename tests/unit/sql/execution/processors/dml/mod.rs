@@ -8,6 +8,7 @@ Comprehensive tests for INSERT, UPDATE, DELETE operations in processors.
 - INSERT INTO operations (VALUES and SELECT sources)
 - UPDATE operations (conditional and unconditional)
 - DELETE operations (conditional and unconditional)
+- MERGE and UPSERT operations (keyed target rows and changelog records)
 - Error handling and validation
 - Streaming semantics (tombstones, timestamps, headers)
 - Edge cases and boundary conditions
@@ -19,4 +20,5 @@ Tests are organized by DML operation type with comprehensive scenarios for each.
 
 pub mod delete_test;
 pub mod insert_test;
+pub mod merge_test;
 pub mod update_test;
@@ -4,7 +4,7 @@
 Comprehensive test suite for UPDATE operations.
 */
 
-use ferrisstreams::ferris::sql::ast::{Expr, LiteralValue};
+use ferrisstreams::ferris::sql::ast::{Expr, LiteralValue, NodeSpan};
 use ferrisstreams::ferris::sql::execution::processors::UpdateProcessor;
 use ferrisstreams::ferris::sql::execution::{FieldValue, StreamRecord};
 use std::collections::HashMap;
@@ -18,7 +18,7 @@ fn create_test_record() -> StreamRecord {
     fields.insert("balance".to_string(), FieldValue::Float(1000.0));
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         headers: HashMap::new(),
         timestamp: 1234567890000,
         offset: 10,
@@ -33,14 +33,21 @@ async fn test_update_with_where_match() {
     let assignments = vec![
         (
             "name".to_string(),
-            Expr::Literal(LiteralValue::String("Bob".to_string())),
+            Expr::Literal(LiteralValue::String("Bob".to_string()), NodeSpan::default()),
+        ),
+        (
+            "age".to_string(),
+            Expr::Literal(LiteralValue::Integer(30), NodeSpan::default()),
         ),
-        ("age".to_string(), Expr::Literal(LiteralValue::Integer(30))),
     ];
     let where_clause = Some(Expr::BinaryOp {
-        left: Box::new(Expr::Column("id".to_string())),
+        left: Box::new(Expr::Column("id".to_string(), NodeSpan::default())),
         op: ferrisstreams::ferris::sql::ast::BinaryOperator::Equal,
-        right: Box::new(Expr::Literal(LiteralValue::Integer(100))),
+        right: Box::new(Expr::Literal(
+            LiteralValue::Integer(100),
+            NodeSpan::default(),
+        )),
+        span: NodeSpan::default(),
     });
     let input_record = create_test_record();
 
@@ -79,12 +86,16 @@ async fn test_update_with_where_no_match() {
     let table_name = "users";
     let assignments = vec![(
         "name".to_string(),
-        Expr::Literal(LiteralValue::String("Bob".to_string())),
+        Expr::Literal(LiteralValue::String("Bob".to_string()), NodeSpan::default()),
     )];
     let where_clause = Some(Expr::BinaryOp {
-        left: Box::new(Expr::Column("id".to_string())),
+        left: Box::new(Expr::Column("id".to_string(), NodeSpan::default())),
         op: ferrisstreams::ferris::sql::ast::BinaryOperator::Equal,
-        right: Box::new(Expr::Literal(LiteralValue::Integer(999))), // No match
+        right: Box::new(Expr::Literal(
+            LiteralValue::Integer(999),
+            NodeSpan::default(),
+        )),
+        span: NodeSpan::default(), // No match
     });
     let input_record = create_test_record();
 
@@ -102,7 +113,7 @@ async fn test_update_without_where() {
     let table_name = "products";
     let assignments = vec![(
         "active".to_string(),
-        Expr::Literal(LiteralValue::Boolean(false)),
+        Expr::Literal(LiteralValue::Boolean(false), NodeSpan::default()),
     )];
     let where_clause = None;
     let input_record = create_test_record();
@@ -131,18 +142,20 @@ async fn test_update_with_expression_assignment() {
         (
             "age".to_string(),
             Expr::BinaryOp {
-                left: Box::new(Expr::Column("age".to_string())),
+                left: Box::new(Expr::Column("age".to_string(), NodeSpan::default())),
                 op: ferrisstreams::ferris::sql::ast::BinaryOperator::Add,
-                right: Box::new(Expr::Literal(LiteralValue::Integer(1))),
+                right: Box::new(Expr::Literal(LiteralValue::Integer(1), NodeSpan::default())),
+                span: NodeSpan::default(),
             },
         ),
         // Double the balance
         (
             "balance".to_string(),
             Expr::BinaryOp {
-                left: Box::new(Expr::Column("balance".to_string())),
+                left: Box::new(Expr::Column("balance".to_string(), NodeSpan::default())),
                 op: ferrisstreams::ferris::sql::ast::BinaryOperator::Multiply,
-                right: Box::new(Expr::Literal(LiteralValue::Float(2.0))),
+                right: Box::new(Expr::Literal(LiteralValue::Float(2.0), NodeSpan::default())),
+                span: NodeSpan::default(),
             },
         ),
     ];
@@ -170,21 +183,33 @@ async fn test_update_with_complex_where() {
     let table_name = "users";
     let assignments = vec![(
         "name".to_string(),
-        Expr::Literal(LiteralValue::String("Updated".to_string())),
+        Expr::Literal(
+            LiteralValue::String("Updated".to_string()),
+            NodeSpan::default(),
+        ),
     )];
     // WHERE age > 18 AND active = true
     let where_clause = Some(Expr::BinaryOp {
         left: Box::new(Expr::BinaryOp {
-            left: Box::new(Expr::Column("age".to_string())),
+            left: Box::new(Expr::Column("age".to_string(), NodeSpan::default())),
             op: ferrisstreams::ferris::sql::ast::BinaryOperator::GreaterThan,
-            right: Box::new(Expr::Literal(LiteralValue::Integer(18))),
+            right: Box::new(Expr::Literal(
+                LiteralValue::Integer(18),
+                NodeSpan::default(),
+            )),
+            span: NodeSpan::default(),
         }),
         op: ferrisstreams::ferris::sql::ast::BinaryOperator::And,
         right: Box::new(Expr::BinaryOp {
-            left: Box::new(Expr::Column("active".to_string())),
+            left: Box::new(Expr::Column("active".to_string(), NodeSpan::default())),
             op: ferrisstreams::ferris::sql::ast::BinaryOperator::Equal,
-            right: Box::new(Expr::Literal(LiteralValue::Boolean(true))),
+            right: Box::new(Expr::Literal(
+                LiteralValue::Boolean(true),
+                NodeSpan::default(),
+            )),
+            span: NodeSpan::default(),
         }),
+        span: NodeSpan::default(),
     });
     let input_record = create_test_record(); // age=25, active=true
 
@@ -209,11 +234,14 @@ async fn test_update_create_new_field() {
     let assignments = vec![
         (
             "new_field".to_string(),
-            Expr::Literal(LiteralValue::String("new_value".to_string())),
+            Expr::Literal(
+                LiteralValue::String("new_value".to_string()),
+                NodeSpan::default(),
+            ),
         ),
         (
             "timestamp".to_string(),
-            Expr::Literal(LiteralValue::Integer(1699999999)),
+            Expr::Literal(LiteralValue::Integer(1699999999), NodeSpan::default()),
         ),
     ];
     let where_clause = None;
@@ -250,7 +278,10 @@ async fn test_update_validation_empty_table() {
     let table_name = "";
     let assignments = vec![(
         "name".to_string(),
-        Expr::Literal(LiteralValue::String("Test".to_string())),
+        Expr::Literal(
+            LiteralValue::String("Test".to_string()),
+            NodeSpan::default(),
+        ),
     )];
     let where_clause = None;
 
@@ -284,7 +315,10 @@ async fn test_update_validation_empty_column() {
     let assignments = vec![
         (
             "".to_string(),
-            Expr::Literal(LiteralValue::String("value".to_string())),
+            Expr::Literal(
+                LiteralValue::String("value".to_string()),
+                NodeSpan::default(),
+            ),
         ), // Empty column name
     ];
     let where_clause = None;
@@ -302,20 +336,28 @@ async fn test_update_validation_empty_column() {
 #[tokio::test]
 async fn test_get_affected_columns() {
     let assignments = vec![
-        ("name".to_string(), Expr::Column("old_name".to_string())),
+        (
+            "name".to_string(),
+            Expr::Column("old_name".to_string(), NodeSpan::default()),
+        ),
         (
             "age".to_string(),
             Expr::BinaryOp {
-                left: Box::new(Expr::Column("current_age".to_string())),
+                left: Box::new(Expr::Column("current_age".to_string(), NodeSpan::default())),
                 op: ferrisstreams::ferris::sql::ast::BinaryOperator::Add,
-                right: Box::new(Expr::Literal(LiteralValue::Integer(1))),
+                right: Box::new(Expr::Literal(LiteralValue::Integer(1), NodeSpan::default())),
+                span: NodeSpan::default(),
             },
         ),
     ];
     let where_clause = Some(Expr::BinaryOp {
-        left: Box::new(Expr::Column("status".to_string())),
+        left: Box::new(Expr::Column("status".to_string(), NodeSpan::default())),
         op: ferrisstreams::ferris::sql::ast::BinaryOperator::Equal,
-        right: Box::new(Expr::Literal(LiteralValue::String("active".to_string()))),
+        right: Box::new(Expr::Literal(
+            LiteralValue::String("active".to_string()),
+            NodeSpan::default(),
+        )),
+        span: NodeSpan::default(),
     });
 
     let columns = UpdateProcessor::get_affected_columns(&assignments, &where_clause);
@@ -335,9 +377,13 @@ async fn test_get_affected_columns() {
 #[tokio::test]
 async fn test_matches_where_clause() {
     let where_clause = Some(Expr::BinaryOp {
-        left: Box::new(Expr::Column("id".to_string())),
+        left: Box::new(Expr::Column("id".to_string(), NodeSpan::default())),
         op: ferrisstreams::ferris::sql::ast::BinaryOperator::Equal,
-        right: Box::new(Expr::Literal(LiteralValue::Integer(100))),
+        right: Box::new(Expr::Literal(
+            LiteralValue::Integer(100),
+            NodeSpan::default(),
+        )),
+        span: NodeSpan::default(),
     });
     let input_record = create_test_record(); // id = 100
 
@@ -348,9 +394,13 @@ async fn test_matches_where_clause() {
 
     // Test no match
     let no_match_clause = Some(Expr::BinaryOp {
-        left: Box::new(Expr::Column("id".to_string())),
+        left: Box::new(Expr::Column("id".to_string(), NodeSpan::default())),
         op: ferrisstreams::ferris::sql::ast::BinaryOperator::Equal,
-        right: Box::new(Expr::Literal(LiteralValue::Integer(999))),
+        right: Box::new(Expr::Literal(
+            LiteralValue::Integer(999),
+            NodeSpan::default(),
+        )),
+        span: NodeSpan::default(),
     });
 
     let result2 = UpdateProcessor::matches_where_clause(&no_match_clause, &input_record);
@@ -365,14 +415,18 @@ async fn test_apply_assignments() {
     let assignments = vec![
         (
             "name".to_string(),
-            Expr::Literal(LiteralValue::String("NewName".to_string())),
+            Expr::Literal(
+                LiteralValue::String("NewName".to_string()),
+                NodeSpan::default(),
+            ),
         ),
         (
             "age".to_string(),
             Expr::BinaryOp {
-                left: Box::new(Expr::Column("age".to_string())),
+                left: Box::new(Expr::Column("age".to_string(), NodeSpan::default())),
                 op: ferrisstreams::ferris::sql::ast::BinaryOperator::Add,
-                right: Box::new(Expr::Literal(LiteralValue::Integer(5))),
+                right: Box::new(Expr::Literal(LiteralValue::Integer(5), NodeSpan::default())),
+                span: NodeSpan::default(),
             },
         ),
     ];
@@ -401,9 +455,15 @@ async fn test_create_update_metadata() {
     let assignments = vec![
         (
             "name".to_string(),
-            Expr::Literal(LiteralValue::String("Updated".to_string())),
+            Expr::Literal(
+                LiteralValue::String("Updated".to_string()),
+                NodeSpan::default(),
+            ),
+        ),
+        (
+            "age".to_string(),
+            Expr::Literal(LiteralValue::Integer(30), NodeSpan::default()),
         ),
-        ("age".to_string(), Expr::Literal(LiteralValue::Integer(30))),
     ];
     let input_record = create_test_record();
 
@@ -430,8 +490,14 @@ async fn test_create_update_metadata() {
 async fn test_update_with_null_assignment() {
     let table_name = "users";
     let assignments = vec![
-        ("name".to_string(), Expr::Literal(LiteralValue::Null)),
-        ("balance".to_string(), Expr::Literal(LiteralValue::Null)),
+        (
+            "name".to_string(),
+            Expr::Literal(LiteralValue::Null, NodeSpan::default()),
+        ),
+        (
+            "balance".to_string(),
+            Expr::Literal(LiteralValue::Null, NodeSpan::default()),
+        ),
     ];
     let where_clause = None;
     let input_record = create_test_record();
@@ -459,7 +525,7 @@ async fn test_update_performance_many_assignments() {
     for i in 0..50 {
         assignments.push((
             format!("field_{}", i),
-            Expr::Literal(LiteralValue::Integer(i)),
+            Expr::Literal(LiteralValue::Integer(i), NodeSpan::default()),
         ));
     }
 
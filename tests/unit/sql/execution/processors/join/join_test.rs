@@ -17,7 +17,7 @@ fn create_test_record_for_join() -> StreamRecord {
     fields.insert("amount".to_string(), FieldValue::Float(100.0));
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         headers: HashMap::new(),
         timestamp: 1234567890000,
         offset: 1,
@@ -270,7 +270,7 @@ fn create_test_record_with_join_fields() -> StreamRecord {
     );
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         headers: HashMap::new(),
         timestamp: 1234567890000,
         offset: 1,
@@ -6,12 +6,17 @@ Comprehensive test suite for JOIN operations involving subqueries:
 - JOINs with subqueries in ON conditions
 - JOINs with EXISTS/NOT EXISTS in ON conditions
 - Complex combinations and error cases
+
+Subqueries read the lookup sources registered by `create_lookup_sources`.
 */
 
 use ferrisstreams::ferris::serialization::JsonFormat;
-use ferrisstreams::ferris::sql::execution::{FieldValue, StreamExecutionEngine, StreamRecord};
+use ferrisstreams::ferris::sql::execution::{
+    FieldValue, InMemoryLookupSource, StreamExecutionEngine, StreamRecord,
+};
 use ferrisstreams::ferris::sql::parser::StreamingSqlParser;
 use std::collections::HashMap;
+use std::sync::Arc;
 use tokio::sync::mpsc;
 
 fn create_test_record_for_subquery_join() -> StreamRecord {
@@ -30,7 +35,7 @@ fn create_test_record_for_subquery_join() -> StreamRecord {
     );
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         headers: HashMap::new(),
         timestamp: 1234567890000,
         offset: 1,
@@ -38,12 +43,51 @@ fn create_test_record_for_subquery_join() -> StreamRecord {
     }
 }
 
+fn lookup_row(fields: Vec<(&str, FieldValue)>) -> StreamRecord {
+    StreamRecord::new(
+        fields
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect::<HashMap<_, _>>(),
+    )
+}
+
+/// Reference data for the subqueries, keyed to the test record (id 1)
+fn create_lookup_sources() -> Vec<(&'static str, InMemoryLookupSource)> {
+    vec![
+        (
+            "orders",
+            InMemoryLookupSource::new(vec![
+                lookup_row(vec![("user_id", FieldValue::Integer(1))]),
+                lookup_row(vec![("user_id", FieldValue::Integer(1))]),
+            ]),
+        ),
+        (
+            "categories",
+            InMemoryLookupSource::new(vec![lookup_row(vec![
+                ("id", FieldValue::Integer(10)),
+                ("active", FieldValue::Boolean(true)),
+            ])]),
+        ),
+        (
+            "blocks",
+            InMemoryLookupSource::new(vec![lookup_row(vec![
+                ("user_id", FieldValue::Integer(2)),
+                ("resource", FieldValue::String("products".to_string())),
+            ])]),
+        ),
+    ]
+}
+
 async fn execute_subquery_join_test(
     query: &str,
 ) -> Result<Vec<StreamRecord>, Box<dyn std::error::Error>> {
     let (tx, mut rx) = mpsc::unbounded_channel();
     let serialization_format = std::sync::Arc::new(JsonFormat);
     let mut engine = StreamExecutionEngine::new(tx);
+    for (name, source) in create_lookup_sources() {
+        engine.register_lookup_source(name, Arc::new(source));
+    }
     let parser = StreamingSqlParser::new();
 
     let parsed_query = parser.parse(query)?;
@@ -201,16 +245,15 @@ async fn test_right_join_with_not_exists_in_on_condition() {
 
     let result = execute_subquery_join_test(query).await;
 
-    // NOT EXISTS in JOIN ON conditions are supported with mock implementation
-    // The execution engine successfully handles this with mock data
+    // NOT EXISTS in JOIN ON conditions are evaluated against the registered blocks source
     assert!(
         result.is_ok(),
-        "NOT EXISTS in JOIN ON conditions should work with mock implementation"
+        "NOT EXISTS in JOIN ON conditions should work"
     );
 
     // Verify we got results
     let results = result.unwrap();
-    assert!(!results.is_empty(), "Should have generated mock results");
+    assert!(!results.is_empty(), "Should have generated results");
 }
 
 // Test 7: FULL OUTER JOIN with correlated subquery
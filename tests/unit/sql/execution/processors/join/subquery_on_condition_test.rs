@@ -3,12 +3,16 @@
 
 Tests for JOIN operations with subqueries in the ON condition - these should work
 because the ExpressionEvaluator already supports EXISTS, NOT EXISTS, IN, NOT IN subqueries.
+Subqueries read the lookup sources registered by `create_lookup_sources`.
 */
 
 use ferrisstreams::ferris::serialization::JsonFormat;
-use ferrisstreams::ferris::sql::execution::{FieldValue, StreamExecutionEngine, StreamRecord};
+use ferrisstreams::ferris::sql::execution::{
+    FieldValue, InMemoryLookupSource, StreamExecutionEngine, StreamRecord,
+};
 use ferrisstreams::ferris::sql::parser::StreamingSqlParser;
 use std::collections::HashMap;
+use std::sync::Arc;
 use tokio::sync::mpsc;
 
 fn create_test_record_for_on_condition() -> StreamRecord {
@@ -26,7 +30,7 @@ fn create_test_record_for_on_condition() -> StreamRecord {
     );
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         headers: HashMap::new(),
         timestamp: 1234567890000,
         offset: 1,
@@ -34,12 +38,48 @@ fn create_test_record_for_on_condition() -> StreamRecord {
     }
 }
 
+fn lookup_row(fields: Vec<(&str, FieldValue)>) -> StreamRecord {
+    StreamRecord::new(
+        fields
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect::<HashMap<_, _>>(),
+    )
+}
+
+/// Reference data for the subqueries, keyed to the test record (id 42, status "active")
+fn create_lookup_sources() -> Vec<(&'static str, InMemoryLookupSource)> {
+    vec![
+        (
+            "valid_statuses",
+            InMemoryLookupSource::new(vec![lookup_row(vec![
+                ("status", FieldValue::String("active".to_string())),
+                ("active", FieldValue::Boolean(true)),
+            ])]),
+        ),
+        (
+            "permissions",
+            InMemoryLookupSource::new(vec![lookup_row(vec![("user_id", FieldValue::Integer(42))])]),
+        ),
+        (
+            "orders",
+            InMemoryLookupSource::new(vec![lookup_row(vec![
+                ("user_id", FieldValue::Integer(42)),
+                ("amount", FieldValue::Float(250.0)),
+            ])]),
+        ),
+    ]
+}
+
 async fn execute_on_condition_test(
     query: &str,
 ) -> Result<Vec<StreamRecord>, Box<dyn std::error::Error>> {
     let (tx, mut rx) = mpsc::unbounded_channel();
     let serialization_format = std::sync::Arc::new(JsonFormat);
     let mut engine = StreamExecutionEngine::new(tx);
+    for (name, source) in create_lookup_sources() {
+        engine.register_lookup_source(name, Arc::new(source));
+    }
     let parser = StreamingSqlParser::new();
 
     let parsed_query = parser.parse(query)?;
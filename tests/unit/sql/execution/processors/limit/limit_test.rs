@@ -84,7 +84,7 @@ mod tests {
             fields.insert("customer_id".to_string(), FieldValue::Integer(i));
             fields.insert("amount".to_string(), FieldValue::Float(100.0 * i as f64));
             let record = StreamRecord {
-                fields,
+                fields: fields.into(),
                 timestamp: chrono::Utc::now().timestamp_millis(),
                 offset: i,
                 partition: 0,
@@ -127,7 +127,7 @@ mod tests {
         fields1.insert("customer_id".to_string(), FieldValue::Integer(1));
         fields1.insert("amount".to_string(), FieldValue::Float(100.0));
         let record1 = StreamRecord {
-            fields: fields1,
+            fields: fields1.into(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             offset: 1,
             partition: 0,
@@ -139,7 +139,7 @@ mod tests {
         fields2.insert("customer_id".to_string(), FieldValue::Integer(2));
         fields2.insert("amount".to_string(), FieldValue::Float(200.0));
         let record2 = StreamRecord {
-            fields: fields2,
+            fields: fields2.into(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             offset: 2,
             partition: 0,
@@ -151,7 +151,7 @@ mod tests {
         fields3.insert("customer_id".to_string(), FieldValue::Integer(3));
         fields3.insert("amount".to_string(), FieldValue::Float(300.0));
         let record3 = StreamRecord {
-            fields: fields3,
+            fields: fields3.into(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             offset: 3,
             partition: 0,
@@ -196,7 +196,7 @@ mod tests {
         let mut fields = HashMap::new();
         fields.insert("customer_id".to_string(), FieldValue::Integer(1));
         let record = StreamRecord {
-            fields,
+            fields: fields.into(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             offset: 1,
             partition: 0,
@@ -228,7 +228,7 @@ mod tests {
         fields1.insert("customer_id".to_string(), FieldValue::Integer(1));
         fields1.insert("amount".to_string(), FieldValue::Float(100.0));
         let record1 = StreamRecord {
-            fields: fields1,
+            fields: fields1.into(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             offset: 1,
             partition: 0,
@@ -240,7 +240,7 @@ mod tests {
         fields2.insert("customer_id".to_string(), FieldValue::Integer(2));
         fields2.insert("amount".to_string(), FieldValue::Float(200.0));
         let record2 = StreamRecord {
-            fields: fields2,
+            fields: fields2.into(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             offset: 2,
             partition: 0,
@@ -281,7 +281,7 @@ mod tests {
         let mut fields1 = HashMap::new();
         fields1.insert("customer_id".to_string(), FieldValue::Integer(1));
         let record1 = StreamRecord {
-            fields: fields1,
+            fields: fields1.into(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             offset: 1,
             partition: 0,
@@ -292,7 +292,7 @@ mod tests {
         let mut fields2 = HashMap::new();
         fields2.insert("customer_id".to_string(), FieldValue::Integer(2));
         let record2 = StreamRecord {
-            fields: fields2,
+            fields: fields2.into(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             offset: 2,
             partition: 0,
@@ -337,7 +337,7 @@ mod tests {
         let mut fields1 = HashMap::new();
         fields1.insert("customer_id".to_string(), FieldValue::Integer(1));
         let record1 = StreamRecord {
-            fields: fields1,
+            fields: fields1.into(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             offset: 1,
             partition: 0,
@@ -348,7 +348,7 @@ mod tests {
         let mut fields2 = HashMap::new();
         fields2.insert("customer_id".to_string(), FieldValue::Integer(2));
         let record2 = StreamRecord {
-            fields: fields2,
+            fields: fields2.into(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             offset: 2,
             partition: 0,
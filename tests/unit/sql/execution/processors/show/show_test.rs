@@ -6,11 +6,14 @@ Comprehensive test suite for schema introspection operations.
 
 use ferrisstreams::ferris::schema::{FieldDefinition, Schema, StreamHandle};
 use ferrisstreams::ferris::sql::ast::DataType;
-use ferrisstreams::ferris::sql::ast::{ShowResourceType, StreamingQuery};
+use ferrisstreams::ferris::sql::ast::{NodeSpan, ShowResourceType, StreamingQuery};
 use ferrisstreams::ferris::sql::execution::processors::{
     JoinContext, ProcessorContext, QueryProcessor,
 };
-use ferrisstreams::ferris::sql::execution::{FieldValue, StreamRecord};
+use ferrisstreams::ferris::sql::execution::subquery::SubqueryCache;
+use ferrisstreams::ferris::sql::execution::{
+    FieldValue, StateBackends, StreamRecord, SubqueryRefreshPolicy,
+};
 use std::collections::HashMap;
 
 fn create_test_record() -> StreamRecord {
@@ -19,7 +22,7 @@ fn create_test_record() -> StreamRecord {
     fields.insert("name".to_string(), FieldValue::String("test".to_string()));
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         headers: HashMap::new(),
         timestamp: 1234567890000,
         offset: 0,
@@ -77,9 +80,18 @@ fn create_test_context_with_schemas() -> ProcessorContext {
         schemas,
         stream_handles,
         data_sources: HashMap::new(),
+        lookup_sources: HashMap::new(),
+        subquery_cache: SubqueryCache::new(),
+        subquery_refresh: SubqueryRefreshPolicy::default(),
         persistent_window_states: Vec::new(),
         dirty_window_states: 0,
         metadata: HashMap::new(),
+        merge_target_states: HashMap::new(),
+        state_backends: StateBackends::new(),
+        state_ttl: None,
+        memory_budget: None,
+        join_strategies: HashMap::new(),
+        query_plan: None,
         performance_monitor: None,
         // New heterogeneous data source fields
         data_readers: HashMap::new(),
@@ -96,6 +108,7 @@ async fn test_show_streams() {
     let query = StreamingQuery::Show {
         resource_type: ShowResourceType::Streams,
         pattern: None,
+        span: NodeSpan::default(),
     };
     let record = create_test_record();
     let mut context = create_test_context_with_schemas();
@@ -124,6 +137,7 @@ async fn test_show_streams_with_pattern() {
     let query = StreamingQuery::Show {
         resource_type: ShowResourceType::Streams,
         pattern: Some("user%".to_string()),
+        span: NodeSpan::default(),
     };
     let record = create_test_record();
     let mut context = create_test_context_with_schemas();
@@ -148,6 +162,7 @@ async fn test_show_tables() {
     let query = StreamingQuery::Show {
         resource_type: ShowResourceType::Tables,
         pattern: None,
+        span: NodeSpan::default(),
     };
     let record = create_test_record();
     let mut context = create_test_context_with_schemas();
@@ -176,6 +191,7 @@ async fn test_show_topics() {
     let query = StreamingQuery::Show {
         resource_type: ShowResourceType::Topics,
         pattern: None,
+        span: NodeSpan::default(),
     };
     let record = create_test_record();
     let mut context = create_test_context_with_schemas();
@@ -203,6 +219,7 @@ async fn test_show_functions() {
     let query = StreamingQuery::Show {
         resource_type: ShowResourceType::Functions,
         pattern: None,
+        span: NodeSpan::default(),
     };
     let record = create_test_record();
     let mut context = create_test_context_with_schemas();
@@ -219,6 +236,28 @@ async fn test_show_functions() {
     assert!(output_record.fields.contains_key("description"));
 }
 
+#[tokio::test]
+async fn test_show_functions_with_pattern() {
+    let query = StreamingQuery::Show {
+        resource_type: ShowResourceType::Functions,
+        pattern: Some("REGEXP%".to_string()),
+        span: NodeSpan::default(),
+    };
+    let record = create_test_record();
+    let mut context = create_test_context_with_schemas();
+
+    let result = QueryProcessor::process_query(&query, &record, &mut context).unwrap();
+    let output_record = result.record.unwrap();
+    assert_eq!(
+        output_record.fields.get("function_name"),
+        Some(&FieldValue::String("REGEXP_LIKE".to_string()))
+    );
+    assert_eq!(
+        output_record.fields.get("category"),
+        Some(&FieldValue::String("String".to_string()))
+    );
+}
+
 // Test 6: SHOW SCHEMA
 #[tokio::test]
 async fn test_show_schema() {
@@ -227,6 +266,7 @@ async fn test_show_schema() {
             name: "users".to_string(),
         },
         pattern: None,
+        span: NodeSpan::default(),
     };
     let record = create_test_record();
     let mut context = create_test_context_with_schemas();
@@ -251,6 +291,7 @@ async fn test_show_schema_not_found() {
             name: "nonexistent".to_string(),
         },
         pattern: None,
+        span: NodeSpan::default(),
     };
     let record = create_test_record();
     let mut context = create_test_context_with_schemas();
@@ -270,6 +311,7 @@ async fn test_describe_resource() {
             name: "users".to_string(),
         },
         pattern: None,
+        span: NodeSpan::default(),
     };
     let record = create_test_record();
     let mut context = create_test_context_with_schemas();
@@ -297,6 +339,7 @@ async fn test_show_properties_stream() {
             name: "users".to_string(),
         },
         pattern: None,
+        span: NodeSpan::default(),
     };
     let record = create_test_record();
     let mut context = create_test_context_with_schemas();
@@ -321,6 +364,7 @@ async fn test_show_properties_not_found() {
             name: "nonexistent".to_string(),
         },
         pattern: None,
+        span: NodeSpan::default(),
     };
     let record = create_test_record();
     let mut context = create_test_context_with_schemas();
@@ -338,6 +382,7 @@ async fn test_show_jobs() {
     let query = StreamingQuery::Show {
         resource_type: ShowResourceType::Jobs,
         pattern: None,
+        span: NodeSpan::default(),
     };
     let record = create_test_record();
     let mut context = create_test_context_with_schemas();
@@ -366,6 +411,7 @@ async fn test_show_job_status() {
             name: Some("test_job".to_string()),
         },
         pattern: None,
+        span: NodeSpan::default(),
     };
     let record = create_test_record();
     let mut context = create_test_context_with_schemas();
@@ -388,6 +434,7 @@ async fn test_show_partitions() {
             name: "users".to_string(),
         },
         pattern: None,
+        span: NodeSpan::default(),
     };
     let record = create_test_record();
     let mut context = create_test_context_with_schemas();
@@ -408,6 +455,7 @@ async fn test_show_streams_empty_context() {
     let query = StreamingQuery::Show {
         resource_type: ShowResourceType::Streams,
         pattern: None,
+        span: NodeSpan::default(),
     };
     let record = create_test_record();
     let mut context = ProcessorContext {
@@ -419,9 +467,18 @@ async fn test_show_streams_empty_context() {
         schemas: HashMap::new(),
         stream_handles: HashMap::new(),
         data_sources: HashMap::new(),
+        lookup_sources: HashMap::new(),
+        subquery_cache: SubqueryCache::new(),
+        subquery_refresh: SubqueryRefreshPolicy::default(),
         persistent_window_states: Vec::new(),
         dirty_window_states: 0,
         metadata: HashMap::new(),
+        merge_target_states: HashMap::new(),
+        state_backends: StateBackends::new(),
+        state_ttl: None,
+        memory_budget: None,
+        join_strategies: HashMap::new(),
+        query_plan: None,
         performance_monitor: None,
         // New heterogeneous data source fields
         data_readers: HashMap::new(),
@@ -446,6 +503,7 @@ async fn test_pattern_matching_edge_cases() {
     let query = StreamingQuery::Show {
         resource_type: ShowResourceType::Streams,
         pattern: Some("%order%".to_string()),
+        span: NodeSpan::default(),
     };
     let record = create_test_record();
     let mut context = create_test_context_with_schemas();
@@ -23,7 +23,7 @@ fn create_test_record(id: i64, amount: f64, timestamp: i64) -> StreamRecord {
     fields.insert("customer_id".to_string(), FieldValue::Integer(1));
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         headers: HashMap::new(),
         timestamp,
         offset: id,
@@ -13,7 +13,7 @@ Tests covered:
 
 use ferrisstreams::ferris::serialization::JsonFormat;
 use ferrisstreams::ferris::sql::ast::{
-    Expr, SelectField, StreamSource, StreamingQuery, WindowSpec,
+    Expr, NodeSpan, SelectField, StreamSource, StreamingQuery, WindowSpec,
 };
 use ferrisstreams::ferris::sql::execution::{FieldValue, StreamExecutionEngine, StreamRecord};
 use std::collections::HashMap;
@@ -57,7 +57,7 @@ fn create_test_record_with_timestamp(
     record.insert("timestamp".to_string(), FieldValue::Integer(timestamp));
 
     StreamRecord {
-        fields: record,
+        fields: record.into(),
         timestamp,
         offset: id,
         partition: 0,
@@ -74,16 +74,19 @@ async fn test_windowed_execution_tumbling() {
         fields: vec![SelectField::Expression {
             expr: Expr::Function {
                 name: "SUM".to_string(),
-                args: vec![Expr::Column("amount".to_string())],
+                args: vec![Expr::Column("amount".to_string(), NodeSpan::default())],
+                span: NodeSpan::default(),
             },
             alias: Some("total_amount".to_string()),
         }],
         from: StreamSource::Stream("orders".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         window: Some(WindowSpec::Tumbling {
             size: Duration::from_millis(1000), // 1 second
             time_column: Some("timestamp".to_string()),
+            time_zone: None,
         }),
         group_by: None,
         having: None,
@@ -91,6 +94,7 @@ async fn test_windowed_execution_tumbling() {
         limit: None,
         emit_mode: None,
         properties: None,
+        span: NodeSpan::default(),
     };
 
     // Create records with specific timestamps to trigger window emission
@@ -179,11 +183,13 @@ async fn test_sliding_window_execution() {
         fields: vec![SelectField::Expression {
             expr: Expr::Function {
                 name: "AVG".to_string(),
-                args: vec![Expr::Column("amount".to_string())],
+                args: vec![Expr::Column("amount".to_string(), NodeSpan::default())],
+                span: NodeSpan::default(),
             },
             alias: Some("avg_amount".to_string()),
         }],
         from: StreamSource::Stream("orders".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         window: Some(WindowSpec::Sliding {
@@ -197,6 +203,7 @@ async fn test_sliding_window_execution() {
         limit: None,
         emit_mode: None,
         properties: None,
+        span: NodeSpan::default(),
     };
 
     // Process multiple records to trigger sliding window output
@@ -236,11 +243,13 @@ async fn test_session_window_execution() {
         fields: vec![SelectField::Expression {
             expr: Expr::Function {
                 name: "COUNT".to_string(),
-                args: vec![Expr::Column("id".to_string())],
+                args: vec![Expr::Column("id".to_string(), NodeSpan::default())],
+                span: NodeSpan::default(),
             },
             alias: Some("session_count".to_string()),
         }],
         from: StreamSource::Stream("orders".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         window: Some(WindowSpec::Session {
@@ -253,6 +262,7 @@ async fn test_session_window_execution() {
         limit: None,
         emit_mode: None,
         properties: None,
+        span: NodeSpan::default(),
     };
 
     // Create records with controlled timestamps to test session windows properly
@@ -304,31 +314,37 @@ async fn test_aggregation_functions() {
                     name: "COUNT".to_string(),
                     args: vec![Expr::Literal(
                         ferrisstreams::ferris::sql::ast::LiteralValue::Integer(1),
+                        NodeSpan::default(),
                     )],
+                    span: NodeSpan::default(),
                 },
                 alias: Some("count".to_string()),
             },
             SelectField::Expression {
                 expr: Expr::Function {
                     name: "MAX".to_string(),
-                    args: vec![Expr::Column("amount".to_string())],
+                    args: vec![Expr::Column("amount".to_string(), NodeSpan::default())],
+                    span: NodeSpan::default(),
                 },
                 alias: Some("max_amount".to_string()),
             },
             SelectField::Expression {
                 expr: Expr::Function {
                     name: "MIN".to_string(),
-                    args: vec![Expr::Column("amount".to_string())],
+                    args: vec![Expr::Column("amount".to_string(), NodeSpan::default())],
+                    span: NodeSpan::default(),
                 },
                 alias: Some("min_amount".to_string()),
             },
         ],
         from: StreamSource::Stream("orders".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         window: Some(WindowSpec::Tumbling {
             size: Duration::from_millis(1000), // 1 second
             time_column: Some("timestamp".to_string()),
+            time_zone: None,
         }),
         group_by: None,
         having: None,
@@ -336,6 +352,7 @@ async fn test_aggregation_functions() {
         limit: None,
         emit_mode: None,
         properties: None,
+        span: NodeSpan::default(),
     };
 
     // Create records with specific timestamps to trigger window emission
@@ -392,3 +409,84 @@ async fn test_aggregation_functions() {
         println!("Window aggregation completed but didn't emit - this may be expected behavior");
     }
 }
+
+#[test]
+fn test_tumbling_window_bounds_utc() {
+    use ferrisstreams::ferris::sql::execution::processors::WindowProcessor;
+
+    // Without a time zone windows stay aligned to the epoch
+    assert_eq!(
+        WindowProcessor::tumbling_window_bounds(1_500, 1_000, None),
+        (1_000, 2_000)
+    );
+}
+
+#[test]
+fn test_tumbling_window_bounds_local_day() {
+    use chrono::{TimeZone, Utc};
+    use ferrisstreams::ferris::sql::execution::processors::WindowProcessor;
+
+    let day_ms = 24 * 3600 * 1000;
+
+    // 2024-03-08 20:00 UTC is 2024-03-09 05:00 in Tokyo, so the window is the Tokyo day
+    // 2024-03-09 00:00+09:00 .. 2024-03-10 00:00+09:00
+    let event = Utc
+        .with_ymd_and_hms(2024, 3, 8, 20, 0, 0)
+        .unwrap()
+        .timestamp_millis();
+    let (start, end) = WindowProcessor::tumbling_window_bounds(event, day_ms, Some("Asia/Tokyo"));
+    assert_eq!(
+        start,
+        Utc.with_ymd_and_hms(2024, 3, 8, 15, 0, 0)
+            .unwrap()
+            .timestamp_millis()
+    );
+    assert_eq!(end - start, day_ms);
+
+    // New York springs forward on 2024-03-10, so that local day is only 23 hours long
+    let event = Utc
+        .with_ymd_and_hms(2024, 3, 10, 18, 0, 0)
+        .unwrap()
+        .timestamp_millis();
+    let (start, end) =
+        WindowProcessor::tumbling_window_bounds(event, day_ms, Some("America/New_York"));
+    assert_eq!(
+        start,
+        Utc.with_ymd_and_hms(2024, 3, 10, 5, 0, 0)
+            .unwrap()
+            .timestamp_millis()
+    );
+    assert_eq!(
+        end,
+        Utc.with_ymd_and_hms(2024, 3, 11, 4, 0, 0)
+            .unwrap()
+            .timestamp_millis()
+    );
+}
+
+#[test]
+fn test_parse_tumbling_window_with_time_zone() {
+    use ferrisstreams::ferris::sql::parser::StreamingSqlParser;
+
+    let parser = StreamingSqlParser::new();
+    let query = parser
+        .parse("SELECT COUNT(*) FROM trades WINDOW TUMBLING(1d, 'Europe/London')")
+        .unwrap();
+
+    match query {
+        StreamingQuery::Select {
+            window: Some(WindowSpec::Tumbling {
+                size, time_zone, ..
+            }),
+            ..
+        } => {
+            assert_eq!(size, Duration::from_secs(24 * 3600));
+            assert_eq!(time_zone.as_deref(), Some("Europe/London"));
+        }
+        other => panic!("Expected tumbling window, got {:?}", other),
+    }
+
+    assert!(parser
+        .parse("SELECT COUNT(*) FROM trades WINDOW TUMBLING(1d, 'Mars/Olympus')")
+        .is_err());
+}
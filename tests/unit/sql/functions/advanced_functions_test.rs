@@ -21,6 +21,7 @@ mod tests {
             StreamingQuery::Show {
                 resource_type,
                 pattern,
+                ..
             } => {
                 assert_eq!(
                     resource_type,
@@ -61,7 +62,7 @@ mod tests {
                     assert_eq!(fields.len(), 1);
                     match &fields[0] {
                         SelectField::Expression { expr, .. } => match expr {
-                            Expr::Function { name, args } => {
+                            Expr::Function { name, args, .. } => {
                                 assert!(!name.is_empty());
                                 assert_eq!(args.len(), 1);
                             }
@@ -96,7 +97,7 @@ mod tests {
                     SelectField::Expression { expr, alias } => {
                         assert_eq!(alias, &Some("event_time".to_string()));
                         match expr {
-                            Expr::Function { name, args } => {
+                            Expr::Function { name, args, .. } => {
                                 assert_eq!(name.to_uppercase(), "TIMESTAMP");
                                 assert!(args.is_empty());
                             }
@@ -136,13 +137,13 @@ mod tests {
                     match &fields[0] {
                         SelectField::Expression { expr, .. } => {
                             match expr {
-                                Expr::Function { name, args } => {
+                                Expr::Function { name, args, .. } => {
                                     assert_eq!(name.to_uppercase(), "CAST");
                                     assert_eq!(args.len(), 2);
 
                                     // Check the target type argument
                                     match &args[1] {
-                                        Expr::Literal(LiteralValue::String(type_str)) => {
+                                        Expr::Literal(LiteralValue::String(type_str), _) => {
                                             assert_eq!(type_str, expected_type);
                                         }
                                         _ => panic!("Expected string literal for CAST target type"),
@@ -177,13 +178,13 @@ mod tests {
                     SelectField::Expression { expr, alias } => {
                         assert_eq!(alias, &Some("first_name".to_string()));
                         match expr {
-                            Expr::Function { name, args } => {
+                            Expr::Function { name, args, .. } => {
                                 assert_eq!(name.to_uppercase(), "SPLIT");
                                 assert_eq!(args.len(), 2);
 
                                 // Check the delimiter argument
                                 match &args[1] {
-                                    Expr::Literal(LiteralValue::String(delimiter)) => {
+                                    Expr::Literal(LiteralValue::String(delimiter), _) => {
                                         assert_eq!(delimiter, " ");
                                     }
                                     _ => panic!("Expected string literal for SPLIT delimiter"),
@@ -217,13 +218,13 @@ mod tests {
                     SelectField::Expression { expr, alias } => {
                         assert_eq!(alias, &Some("full_name".to_string()));
                         match expr {
-                            Expr::Function { name, args } => {
+                            Expr::Function { name, args, .. } => {
                                 assert_eq!(name.to_uppercase(), "JOIN");
                                 assert_eq!(args.len(), 3); // delimiter + 2 values
 
                                 // Check the delimiter argument
                                 match &args[0] {
-                                    Expr::Literal(LiteralValue::String(delimiter)) => {
+                                    Expr::Literal(LiteralValue::String(delimiter), _) => {
                                         assert_eq!(delimiter, ", ");
                                     }
                                     _ => panic!("Expected string literal for JOIN delimiter"),
@@ -365,7 +366,7 @@ mod tests {
                     SelectField::Expression { expr, alias } => {
                         assert_eq!(alias, &Some("port".to_string()));
                         match expr {
-                            Expr::Function { name, args } => {
+                            Expr::Function { name, args, .. } => {
                                 assert_eq!(name.to_uppercase(), "CAST");
                                 assert_eq!(args.len(), 2);
 
@@ -374,6 +375,7 @@ mod tests {
                                     Expr::Function {
                                         name: inner_name,
                                         args: inner_args,
+                                        ..
                                     } => {
                                         assert_eq!(inner_name.to_uppercase(), "SPLIT");
                                         assert_eq!(inner_args.len(), 2);
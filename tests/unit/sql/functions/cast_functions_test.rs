@@ -14,7 +14,7 @@ use std::str::FromStr;
 
 fn create_mock_record() -> ferrisstreams::ferris::sql::execution::StreamRecord {
     ferrisstreams::ferris::sql::execution::StreamRecord {
-        fields: HashMap::new(),
+        fields: HashMap::new().into(),
         timestamp: 1640995200000, // 2022-01-01 00:00:00 UTC
         offset: 0,
         partition: 0,
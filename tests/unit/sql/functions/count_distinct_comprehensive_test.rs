@@ -1,4 +1,4 @@
-use ferrisstreams::ferris::sql::ast::{Expr, LiteralValue};
+use ferrisstreams::ferris::sql::ast::{Expr, LiteralValue, NodeSpan};
 use ferrisstreams::ferris::sql::execution::{
     aggregation::{accumulator::AccumulatorManager, functions::AggregateFunctions},
     internal::GroupAccumulator,
@@ -22,9 +22,11 @@ fn test_count_distinct_exact_counting() {
         "customer_id",
         &Expr::Function {
             name: "COUNT_DISTINCT".to_string(),
-            args: vec![Expr::Literal(LiteralValue::String(
-                "customer_id".to_string(),
-            ))],
+            args: vec![Expr::Literal(
+                LiteralValue::String("customer_id".to_string()),
+                NodeSpan::default(),
+            )],
+            span: NodeSpan::default(),
         },
         &accumulator,
     )
@@ -57,9 +59,11 @@ fn test_approx_count_distinct_hyperloglog() {
         "customer_id",
         &Expr::Function {
             name: "APPROX_COUNT_DISTINCT".to_string(),
-            args: vec![Expr::Literal(LiteralValue::String(
-                "customer_id".to_string(),
-            ))],
+            args: vec![Expr::Literal(
+                LiteralValue::String("customer_id".to_string()),
+                NodeSpan::default(),
+            )],
+            span: NodeSpan::default(),
         },
         &accumulator,
     )
@@ -103,9 +107,11 @@ fn test_count_distinct_vs_approx_count_distinct_accuracy() {
         "test_field",
         &Expr::Function {
             name: "COUNT_DISTINCT".to_string(),
-            args: vec![Expr::Literal(LiteralValue::String(
-                "test_field".to_string(),
-            ))],
+            args: vec![Expr::Literal(
+                LiteralValue::String("test_field".to_string()),
+                NodeSpan::default(),
+            )],
+            span: NodeSpan::default(),
         },
         &accumulator,
     )
@@ -116,9 +122,11 @@ fn test_count_distinct_vs_approx_count_distinct_accuracy() {
         "test_field",
         &Expr::Function {
             name: "APPROX_COUNT_DISTINCT".to_string(),
-            args: vec![Expr::Literal(LiteralValue::String(
-                "test_field".to_string(),
-            ))],
+            args: vec![Expr::Literal(
+                LiteralValue::String("test_field".to_string()),
+                NodeSpan::default(),
+            )],
+            span: NodeSpan::default(),
         },
         &accumulator,
     )
@@ -176,7 +184,11 @@ fn test_count_distinct_with_different_field_types() {
         "strings",
         &Expr::Function {
             name: "COUNT_DISTINCT".to_string(),
-            args: vec![Expr::Literal(LiteralValue::String("strings".to_string()))],
+            args: vec![Expr::Literal(
+                LiteralValue::String("strings".to_string()),
+                NodeSpan::default(),
+            )],
+            span: NodeSpan::default(),
         },
         &accumulator,
     )
@@ -192,7 +204,11 @@ fn test_count_distinct_with_different_field_types() {
         "integers",
         &Expr::Function {
             name: "COUNT_DISTINCT".to_string(),
-            args: vec![Expr::Literal(LiteralValue::String("integers".to_string()))],
+            args: vec![Expr::Literal(
+                LiteralValue::String("integers".to_string()),
+                NodeSpan::default(),
+            )],
+            span: NodeSpan::default(),
         },
         &accumulator,
     )
@@ -208,7 +224,11 @@ fn test_count_distinct_with_different_field_types() {
         "scaled",
         &Expr::Function {
             name: "COUNT_DISTINCT".to_string(),
-            args: vec![Expr::Literal(LiteralValue::String("scaled".to_string()))],
+            args: vec![Expr::Literal(
+                LiteralValue::String("scaled".to_string()),
+                NodeSpan::default(),
+            )],
+            span: NodeSpan::default(),
         },
         &accumulator,
     )
@@ -233,9 +253,11 @@ fn test_count_distinct_empty_sets() {
         "nonexistent_field",
         &Expr::Function {
             name: "COUNT_DISTINCT".to_string(),
-            args: vec![Expr::Literal(LiteralValue::String(
-                "nonexistent_field".to_string(),
-            ))],
+            args: vec![Expr::Literal(
+                LiteralValue::String("nonexistent_field".to_string()),
+                NodeSpan::default(),
+            )],
+            span: NodeSpan::default(),
         },
         &accumulator,
     )
@@ -246,9 +268,11 @@ fn test_count_distinct_empty_sets() {
         "nonexistent_field",
         &Expr::Function {
             name: "APPROX_COUNT_DISTINCT".to_string(),
-            args: vec![Expr::Literal(LiteralValue::String(
-                "nonexistent_field".to_string(),
-            ))],
+            args: vec![Expr::Literal(
+                LiteralValue::String("nonexistent_field".to_string()),
+                NodeSpan::default(),
+            )],
+            span: NodeSpan::default(),
         },
         &accumulator,
     )
@@ -287,9 +311,11 @@ fn test_count_distinct_null_handling() {
         "test_field",
         &Expr::Function {
             name: "COUNT_DISTINCT".to_string(),
-            args: vec![Expr::Literal(LiteralValue::String(
-                "test_field".to_string(),
-            ))],
+            args: vec![Expr::Literal(
+                LiteralValue::String("test_field".to_string()),
+                NodeSpan::default(),
+            )],
+            span: NodeSpan::default(),
         },
         &accumulator,
     )
@@ -339,7 +365,11 @@ fn test_hyperloglog_performance_vs_exact() {
         "users",
         &Expr::Function {
             name: "COUNT_DISTINCT".to_string(),
-            args: vec![Expr::Literal(LiteralValue::String("users".to_string()))],
+            args: vec![Expr::Literal(
+                LiteralValue::String("users".to_string()),
+                NodeSpan::default(),
+            )],
+            span: NodeSpan::default(),
         },
         &exact_accumulator,
     )
@@ -349,7 +379,11 @@ fn test_hyperloglog_performance_vs_exact() {
         "users",
         &Expr::Function {
             name: "APPROX_COUNT_DISTINCT".to_string(),
-            args: vec![Expr::Literal(LiteralValue::String("users".to_string()))],
+            args: vec![Expr::Literal(
+                LiteralValue::String("users".to_string()),
+                NodeSpan::default(),
+            )],
+            span: NodeSpan::default(),
         },
         &approx_accumulator,
     )
@@ -402,7 +436,7 @@ fn create_test_record(customer_id: &str, amount: i64) -> StreamRecord {
     fields.insert("amount".to_string(), FieldValue::ScaledInteger(amount, 2));
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         timestamp: chrono::Utc::now().timestamp_millis(),
         offset: 0,
         partition: 0,
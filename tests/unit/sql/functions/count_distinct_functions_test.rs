@@ -1,4 +1,4 @@
-use ferrisstreams::ferris::sql::ast::{Expr, LiteralValue};
+use ferrisstreams::ferris::sql::ast::{Expr, LiteralValue, NodeSpan};
 use ferrisstreams::ferris::sql::execution::expression::functions::BuiltinFunctions;
 use ferrisstreams::ferris::sql::execution::types::{FieldValue, StreamRecord};
 use std::collections::HashMap;
@@ -7,7 +7,10 @@ use std::collections::HashMap;
 fn test_count_distinct_function_registration() {
     // Test that COUNT_DISTINCT is registered in the function registry
     let record = create_test_record();
-    let args = vec![Expr::Literal(LiteralValue::String("customer1".to_string()))];
+    let args = vec![Expr::Literal(
+        LiteralValue::String("customer1".to_string()),
+        NodeSpan::default(),
+    )];
 
     // Test COUNT_DISTINCT function call
     let result = BuiltinFunctions::evaluate_function_by_name("COUNT_DISTINCT", &args, &record);
@@ -27,7 +30,10 @@ fn test_count_distinct_function_registration() {
 fn test_approx_count_distinct_function_registration() {
     // Test that APPROX_COUNT_DISTINCT is registered in the function registry
     let record = create_test_record();
-    let args = vec![Expr::Literal(LiteralValue::String("customer1".to_string()))];
+    let args = vec![Expr::Literal(
+        LiteralValue::String("customer1".to_string()),
+        NodeSpan::default(),
+    )];
 
     // Test APPROX_COUNT_DISTINCT function call
     let result =
@@ -47,7 +53,7 @@ fn test_approx_count_distinct_function_registration() {
 #[test]
 fn test_count_distinct_null_handling() {
     let record = create_test_record();
-    let args = vec![Expr::Literal(LiteralValue::Null)];
+    let args = vec![Expr::Literal(LiteralValue::Null, NodeSpan::default())];
 
     let result = BuiltinFunctions::evaluate_function_by_name("COUNT_DISTINCT", &args, &record);
     assert!(result.is_ok(), "COUNT_DISTINCT should handle null values");
@@ -61,7 +67,7 @@ fn test_count_distinct_null_handling() {
 #[test]
 fn test_approx_count_distinct_null_handling() {
     let record = create_test_record();
-    let args = vec![Expr::Literal(LiteralValue::Null)];
+    let args = vec![Expr::Literal(LiteralValue::Null, NodeSpan::default())];
 
     let result =
         BuiltinFunctions::evaluate_function_by_name("APPROX_COUNT_DISTINCT", &args, &record);
@@ -89,8 +95,14 @@ fn test_function_argument_validation() {
 
     // Test too many arguments
     let args = vec![
-        Expr::Literal(LiteralValue::String("customer1".to_string())),
-        Expr::Literal(LiteralValue::String("customer2".to_string())),
+        Expr::Literal(
+            LiteralValue::String("customer1".to_string()),
+            NodeSpan::default(),
+        ),
+        Expr::Literal(
+            LiteralValue::String("customer2".to_string()),
+            NodeSpan::default(),
+        ),
     ];
     let result = BuiltinFunctions::evaluate_function_by_name("COUNT_DISTINCT", &args, &record);
     assert!(
@@ -112,7 +124,7 @@ fn create_test_record() -> StreamRecord {
     );
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         timestamp: chrono::Utc::now().timestamp_millis(),
         offset: 0,
         partition: 0,
@@ -124,9 +136,10 @@ fn create_test_record() -> StreamRecord {
 fn test_both_functions_produce_same_result() {
     // Since APPROX_COUNT_DISTINCT uses same logic as COUNT_DISTINCT for now
     let record = create_test_record();
-    let args = vec![Expr::Literal(LiteralValue::String(
-        "test_value".to_string(),
-    ))];
+    let args = vec![Expr::Literal(
+        LiteralValue::String("test_value".to_string()),
+        NodeSpan::default(),
+    )];
 
     let count_distinct_result =
         BuiltinFunctions::evaluate_function_by_name("COUNT_DISTINCT", &args, &record);
@@ -30,7 +30,7 @@ fn create_test_record_with_timestamps() -> StreamRecord {
     headers.insert("test_source".to_string(), "date_functions_test".to_string());
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         headers,
         timestamp: 1672576245123, // 2023-01-01 12:30:45.123 UTC
         offset: 1000,
@@ -441,7 +441,7 @@ fn create_test_record_for_timestamp_functions() -> StreamRecord {
     );
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         headers,
         timestamp: 1672576245000, // 2023-01-01 12:30:45 UTC in milliseconds
         offset: 1001,
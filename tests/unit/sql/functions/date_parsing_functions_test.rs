@@ -0,0 +1,298 @@
+/*!
+# Date Parsing and Bucketing Function Tests
+
+Tests for TO_TIMESTAMP, TO_DATE, DATE_TRUNC, TIME_BUCKET, DATE_ADD/DATE_SUB and
+calendar-aware interval arithmetic between timestamps.
+*/
+
+use chrono::{NaiveDate, NaiveDateTime, TimeZone};
+use chrono_tz::Tz;
+use ferrisstreams::ferris::sql::ast::TimeUnit;
+use ferrisstreams::ferris::sql::execution::{FieldValue, StreamExecutionEngine, StreamRecord};
+use ferrisstreams::ferris::sql::parser::StreamingSqlParser;
+use std::collections::HashMap;
+use tokio::sync::mpsc;
+
+fn datetime(y: i32, m: u32, d: u32, h: u32, mi: u32, s: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(y, m, d)
+        .unwrap()
+        .and_hms_opt(h, mi, s)
+        .unwrap()
+}
+
+fn create_test_record() -> StreamRecord {
+    let mut fields = HashMap::new();
+
+    fields.insert(
+        "legacy_ts".to_string(),
+        FieldValue::String("31/01/2024 17:45:12".to_string()),
+    );
+    fields.insert(
+        "trade_day".to_string(),
+        FieldValue::String("20240131".to_string()),
+    );
+    fields.insert(
+        "order_time".to_string(),
+        FieldValue::Timestamp(datetime(2024, 1, 31, 17, 45, 12)),
+    );
+    fields.insert(
+        "fill_time".to_string(),
+        FieldValue::Timestamp(datetime(2024, 1, 31, 17, 45, 14)),
+    );
+    // 2023-01-01 12:30:45.123 UTC
+    fields.insert("event_time".to_string(), FieldValue::Integer(1672576245123));
+    fields.insert(
+        "tokyo_time".to_string(),
+        FieldValue::TimestampTz(
+            Tz::Asia__Tokyo
+                .with_ymd_and_hms(2024, 1, 1, 8, 30, 0)
+                .unwrap(),
+        ),
+    );
+
+    StreamRecord {
+        fields: fields.into(),
+        headers: HashMap::new(),
+        timestamp: 1672576245123,
+        offset: 1,
+        partition: 0,
+    }
+}
+
+async fn execute_query(query: &str) -> Result<Vec<StreamRecord>, Box<dyn std::error::Error>> {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut engine = StreamExecutionEngine::new(tx);
+    let parser = StreamingSqlParser::new();
+
+    let parsed_query = parser.parse(query)?;
+    engine
+        .execute_with_record(&parsed_query, create_test_record())
+        .await?;
+
+    let mut results = Vec::new();
+    while let Ok(result) = rx.try_recv() {
+        results.push(result);
+    }
+    Ok(results)
+}
+
+#[tokio::test]
+async fn test_to_timestamp_with_sql_format() {
+    let results = execute_query(
+        "SELECT TO_TIMESTAMP(legacy_ts, 'DD/MM/YYYY HH24:MI:SS') as sql_fmt, \
+                TO_TIMESTAMP(legacy_ts, '%d/%m/%Y %H:%M:%S') as strftime_fmt, \
+                TO_TIMESTAMP('Jan 31 2024 05:45 PM', 'MON DD YYYY HH12:MI AM') as am_pm, \
+                TO_TIMESTAMP(1706723112) as from_epoch \
+         FROM test_stream",
+    )
+    .await
+    .unwrap();
+
+    let expected = FieldValue::Timestamp(datetime(2024, 1, 31, 17, 45, 12));
+    let fields = &results[0].fields;
+    assert_eq!(fields.get("sql_fmt"), Some(&expected));
+    assert_eq!(fields.get("strftime_fmt"), Some(&expected));
+    assert_eq!(fields.get("from_epoch"), Some(&expected));
+    assert_eq!(
+        fields.get("am_pm"),
+        Some(&FieldValue::Timestamp(datetime(2024, 1, 31, 17, 45, 0)))
+    );
+}
+
+#[tokio::test]
+async fn test_to_timestamp_invalid_input() {
+    let result =
+        execute_query("SELECT TO_TIMESTAMP(trade_day, 'DD/MM/YYYY') as bad FROM test_stream").await;
+
+    assert!(result.is_err());
+    assert!(result.unwrap_err().to_string().contains("Cannot parse"));
+}
+
+#[tokio::test]
+async fn test_to_date() {
+    let results = execute_query(
+        "SELECT TO_DATE(trade_day, 'YYYYMMDD') as compact, \
+                TO_DATE('2024-02-29') as iso \
+         FROM test_stream",
+    )
+    .await
+    .unwrap();
+
+    let fields = &results[0].fields;
+    assert_eq!(
+        fields.get("compact"),
+        Some(&FieldValue::Date(
+            NaiveDate::from_ymd_opt(2024, 1, 31).unwrap()
+        ))
+    );
+    assert_eq!(
+        fields.get("iso"),
+        Some(&FieldValue::Date(
+            NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()
+        ))
+    );
+}
+
+#[tokio::test]
+async fn test_date_trunc_units() {
+    let results = execute_query(
+        "SELECT DATE_TRUNC('hour', order_time) as hour, \
+                DATE_TRUNC('week', order_time) as week, \
+                DATE_TRUNC('quarter', order_time) as quarter, \
+                DATE_TRUNC('day', event_time) as epoch_day \
+         FROM test_stream",
+    )
+    .await
+    .unwrap();
+
+    let fields = &results[0].fields;
+    assert_eq!(
+        fields.get("hour"),
+        Some(&FieldValue::Timestamp(datetime(2024, 1, 31, 17, 0, 0)))
+    );
+    // 2024-01-31 is a Wednesday, the ISO week starts on Monday 2024-01-29
+    assert_eq!(
+        fields.get("week"),
+        Some(&FieldValue::Timestamp(datetime(2024, 1, 29, 0, 0, 0)))
+    );
+    assert_eq!(
+        fields.get("quarter"),
+        Some(&FieldValue::Timestamp(datetime(2024, 1, 1, 0, 0, 0)))
+    );
+    // Epoch milliseconds stay epoch milliseconds
+    assert_eq!(
+        fields.get("epoch_day"),
+        Some(&FieldValue::Integer(1672531200000))
+    );
+}
+
+#[tokio::test]
+async fn test_date_trunc_uses_local_time_of_timestamptz() {
+    let results = execute_query("SELECT DATE_TRUNC('day', tokyo_time) as day FROM test_stream")
+        .await
+        .unwrap();
+
+    // 08:30 in Tokyo is still the previous day in UTC, but truncation is local
+    assert_eq!(
+        results[0].fields.get("day").unwrap().to_display_string(),
+        "2024-01-01 00:00:00.000 Asia/Tokyo"
+    );
+}
+
+#[tokio::test]
+async fn test_time_bucket() {
+    let results = execute_query(
+        "SELECT TIME_BUCKET(INTERVAL '15' MINUTE, order_time) as quarter_hour, \
+                TIME_BUCKET(INTERVAL '1' WEEK, order_time) as week, \
+                TIME_BUCKET(INTERVAL '3' MONTHS, order_time) as quarter, \
+                TIME_BUCKET(INTERVAL '10' MINUTES, order_time, TO_TIMESTAMP('2024-01-01 00:07:00')) as shifted \
+         FROM test_stream",
+    )
+    .await
+    .unwrap();
+
+    let fields = &results[0].fields;
+    assert_eq!(
+        fields.get("quarter_hour"),
+        Some(&FieldValue::Timestamp(datetime(2024, 1, 31, 17, 45, 0)))
+    );
+    assert_eq!(
+        fields.get("week"),
+        Some(&FieldValue::Timestamp(datetime(2024, 1, 29, 0, 0, 0)))
+    );
+    assert_eq!(
+        fields.get("quarter"),
+        Some(&FieldValue::Timestamp(datetime(2024, 1, 1, 0, 0, 0)))
+    );
+    assert_eq!(
+        fields.get("shifted"),
+        Some(&FieldValue::Timestamp(datetime(2024, 1, 31, 17, 37, 0)))
+    );
+}
+
+#[tokio::test]
+async fn test_date_add_calendar_months() {
+    let results = execute_query(
+        "SELECT DATE_ADD(order_time, INTERVAL '1' MONTH) as next_month, \
+                DATE_ADD('quarter', 1, order_time) as next_quarter, \
+                DATE_SUB(order_time, INTERVAL '1' YEAR) as last_year, \
+                DATE_ADD(TO_DATE(trade_day, 'YYYYMMDD'), INTERVAL '2' DAYS) as settle_date \
+         FROM test_stream",
+    )
+    .await
+    .unwrap();
+
+    let fields = &results[0].fields;
+    // Jan 31 + 1 month clamps to the end of February (2024 is a leap year)
+    assert_eq!(
+        fields.get("next_month"),
+        Some(&FieldValue::Timestamp(datetime(2024, 2, 29, 17, 45, 12)))
+    );
+    assert_eq!(
+        fields.get("next_quarter"),
+        Some(&FieldValue::Timestamp(datetime(2024, 4, 30, 17, 45, 12)))
+    );
+    assert_eq!(
+        fields.get("last_year"),
+        Some(&FieldValue::Timestamp(datetime(2023, 1, 31, 17, 45, 12)))
+    );
+    assert_eq!(
+        fields.get("settle_date"),
+        Some(&FieldValue::Date(
+            NaiveDate::from_ymd_opt(2024, 2, 2).unwrap()
+        ))
+    );
+}
+
+#[tokio::test]
+async fn test_timestamp_interval_arithmetic() {
+    let results = execute_query(
+        "SELECT fill_time - order_time as latency, \
+                order_time + INTERVAL '1' MONTH as plus_month \
+         FROM test_stream",
+    )
+    .await
+    .unwrap();
+
+    let fields = &results[0].fields;
+    assert_eq!(
+        fields.get("latency"),
+        Some(&FieldValue::Interval {
+            value: 2000,
+            unit: TimeUnit::Millisecond
+        })
+    );
+    assert_eq!(
+        fields.get("plus_month"),
+        Some(&FieldValue::Timestamp(datetime(2024, 2, 29, 17, 45, 12)))
+    );
+}
+
+#[test]
+fn test_interval_arithmetic_on_timestamptz_across_dst() {
+    // Adding a day keeps the local wall-clock time across the New York spring-forward
+    let before = FieldValue::TimestampTz(
+        Tz::America__New_York
+            .with_ymd_and_hms(2024, 3, 9, 9, 30, 0)
+            .unwrap(),
+    );
+    let next_day = before
+        .add(&FieldValue::Interval {
+            value: 1,
+            unit: TimeUnit::Day,
+        })
+        .unwrap();
+    assert_eq!(
+        next_day.to_display_string(),
+        "2024-03-10 09:30:00.000 America/New_York"
+    );
+
+    // ...and the elapsed time is only 23 hours
+    assert_eq!(
+        next_day.subtract(&before).unwrap(),
+        FieldValue::Interval {
+            value: 23 * 60 * 60 * 1000,
+            unit: TimeUnit::Millisecond
+        }
+    );
+}
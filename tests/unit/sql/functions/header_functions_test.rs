@@ -25,7 +25,7 @@ fn create_test_record() -> StreamRecord {
     headers.insert("existing_header".to_string(), "existing_value".to_string());
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         headers,
         timestamp: 1734652800000,
         offset: 100,
@@ -25,7 +25,7 @@ fn create_test_record() -> StreamRecord {
     headers.insert("source".to_string(), "test-system".to_string());
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         headers,
         timestamp: 1734652800000, // 2024-12-20 00:00:00 UTC
         offset: 100,
@@ -80,6 +80,7 @@ async fn test_interval_literal_parsing() {
                         match expr {
                             ferrisstreams::ferris::sql::ast::Expr::Literal(
                                 ferrisstreams::ferris::sql::ast::LiteralValue::Interval { .. },
+                                _,
                             ) => {
                                 // Successfully parsed as interval literal
                             }
@@ -231,6 +232,10 @@ async fn test_interval_conversion_accuracy() {
                     TimeUnit::Minute => *value * 60 * 1000,
                     TimeUnit::Hour => *value * 60 * 60 * 1000,
                     TimeUnit::Day => *value * 24 * 60 * 60 * 1000,
+                    TimeUnit::Week => *value * 7 * 24 * 60 * 60 * 1000,
+                    TimeUnit::Month | TimeUnit::Year => {
+                        panic!("Calendar intervals have no fixed length: {:?}", unit)
+                    }
                 }
             }
             Some(other) => panic!("Unexpected field type for interval_value: {:?}", other),
@@ -0,0 +1,265 @@
+/*!
+# JSON Function Tests
+
+Tests for JSONPath queries (wildcards, recursive descent and filters), PARSE_JSON/TO_JSON,
+the JSON_OBJECT/JSON_ARRAY constructors and the JSON_ARRAYAGG/JSON_OBJECTAGG aggregates.
+*/
+
+use ferrisstreams::ferris::sql::execution::expression::JsonPath;
+use ferrisstreams::ferris::sql::execution::{FieldValue, StreamExecutionEngine, StreamRecord};
+use ferrisstreams::ferris::sql::parser::StreamingSqlParser;
+use std::collections::HashMap;
+use tokio::sync::mpsc;
+
+const ORDER_JSON: &str = r#"{
+    "order": {"id": "o-1", "total": 42.5},
+    "items": [
+        {"sku": "A", "qty": 1, "price": 5},
+        {"sku": "B", "qty": 3, "price": 12.5, "discount": true},
+        {"sku": "C", "qty": 2, "price": 7}
+    ],
+    "body": "{\"source\": \"web\", \"tags\": [\"new\", \"vip\"]}"
+}"#;
+
+fn create_record(fields: Vec<(&str, FieldValue)>) -> StreamRecord {
+    StreamRecord {
+        fields: fields
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v))
+            .collect(),
+        headers: HashMap::new(),
+        timestamp: 1672576245123,
+        offset: 1,
+        partition: 0,
+    }
+}
+
+async fn execute_query(query: &str) -> Result<Vec<StreamRecord>, Box<dyn std::error::Error>> {
+    execute_query_with_records(
+        query,
+        vec![create_record(vec![(
+            "payload",
+            FieldValue::String(ORDER_JSON.to_string()),
+        )])],
+    )
+    .await
+}
+
+async fn execute_query_with_records(
+    query: &str,
+    records: Vec<StreamRecord>,
+) -> Result<Vec<StreamRecord>, Box<dyn std::error::Error>> {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut engine = StreamExecutionEngine::new(tx);
+    let parser = StreamingSqlParser::new();
+
+    let parsed_query = parser.parse(query)?;
+    for record in records {
+        engine.execute_with_record(&parsed_query, record).await?;
+    }
+
+    let mut results = Vec::new();
+    while let Ok(result) = rx.try_recv() {
+        results.push(result);
+    }
+    Ok(results)
+}
+
+fn strings(values: &[&str]) -> FieldValue {
+    FieldValue::Array(
+        values
+            .iter()
+            .map(|s| FieldValue::String(s.to_string()))
+            .collect(),
+    )
+}
+
+#[tokio::test]
+async fn test_json_value_and_extract() {
+    let results = execute_query(
+        "SELECT JSON_VALUE(payload, '$.order.id') as order_id, \
+                JSON_VALUE(payload, '$.items[-1].qty') as last_qty, \
+                JSON_VALUE(payload, '$.items') as not_scalar, \
+                JSON_EXTRACT(payload, '$.order') as order_json, \
+                JSON_EXTRACT(payload, '$.items[*].sku') as skus_json \
+         FROM events",
+    )
+    .await
+    .unwrap();
+
+    let fields = &results[0].fields;
+    assert_eq!(
+        fields.get("order_id"),
+        Some(&FieldValue::String("o-1".to_string()))
+    );
+    assert_eq!(fields.get("last_qty"), Some(&FieldValue::Integer(2)));
+    assert_eq!(fields.get("not_scalar"), Some(&FieldValue::Null));
+    assert_eq!(
+        fields.get("order_json"),
+        Some(&FieldValue::String(
+            r#"{"id":"o-1","total":42.5}"#.to_string()
+        ))
+    );
+    assert_eq!(
+        fields.get("skus_json"),
+        Some(&FieldValue::String(r#"["A","B","C"]"#.to_string()))
+    );
+}
+
+#[tokio::test]
+async fn test_json_extract_root_and_exact_decimals() {
+    let results = execute_query_with_records(
+        "SELECT JSON_EXTRACT(payload, '$') as document, TO_JSON(price) as price_json \
+         FROM events",
+        vec![create_record(vec![
+            ("payload", FieldValue::String(ORDER_JSON.to_string())),
+            ("price", FieldValue::ScaledInteger(1234500, 4)),
+        ])],
+    )
+    .await
+    .unwrap();
+
+    let fields = &results[0].fields;
+    // The root of JSON text is returned as given, not re-serialized
+    assert_eq!(
+        fields.get("document"),
+        Some(&FieldValue::String(ORDER_JSON.to_string()))
+    );
+    // Decimals keep every digit
+    assert_eq!(
+        fields.get("price_json"),
+        Some(&FieldValue::String(r#""123.4500""#.to_string()))
+    );
+}
+
+#[tokio::test]
+async fn test_json_query_returns_nested_values() {
+    let results = execute_query(
+        "SELECT JSON_QUERY(payload, '$.order') as order_struct, \
+                JSON_QUERY(payload, '$.items[?(@.qty > 1)].sku') as bulk_skus, \
+                JSON_QUERY(payload, '$.items[?(@.discount || @.price < 6)].sku') as promo_skus, \
+                JSON_QUERY(payload, '$..price') as prices \
+         FROM events",
+    )
+    .await
+    .unwrap();
+
+    let fields = &results[0].fields;
+    match fields.get("order_struct") {
+        Some(FieldValue::Struct(order)) => {
+            assert_eq!(
+                order.get("id"),
+                Some(&FieldValue::String("o-1".to_string()))
+            );
+            assert_eq!(order.get("total"), Some(&FieldValue::Float(42.5)));
+        }
+        other => panic!("Expected STRUCT, got {:?}", other),
+    }
+    assert_eq!(fields.get("bulk_skus"), Some(&strings(&["B", "C"])));
+    assert_eq!(fields.get("promo_skus"), Some(&strings(&["A", "B"])));
+    assert_eq!(
+        fields.get("prices"),
+        Some(&FieldValue::Array(vec![
+            FieldValue::Integer(5),
+            FieldValue::Float(12.5),
+            FieldValue::Integer(7),
+        ]))
+    );
+}
+
+#[tokio::test]
+async fn test_parse_json_embedded_document() {
+    // `body` is a JSON document encoded as a string inside the payload
+    let results = execute_query(
+        "SELECT JSON_VALUE(PARSE_JSON(JSON_VALUE(payload, '$.body')), '$.source') as source, \
+                JSON_QUERY(PARSE_JSON(JSON_VALUE(payload, '$.body')), '$.tags') as tags \
+         FROM events",
+    )
+    .await
+    .unwrap();
+
+    let fields = &results[0].fields;
+    assert_eq!(
+        fields.get("source"),
+        Some(&FieldValue::String("web".to_string()))
+    );
+    assert_eq!(fields.get("tags"), Some(&strings(&["new", "vip"])));
+}
+
+#[tokio::test]
+async fn test_invalid_json_yields_null_and_parse_json_errors() {
+    let results = execute_query("SELECT JSON_VALUE('not json', '$.a') as missing FROM events")
+        .await
+        .unwrap();
+    assert_eq!(results[0].fields.get("missing"), Some(&FieldValue::Null));
+
+    let result = execute_query("SELECT PARSE_JSON('{broken') as bad FROM events").await;
+    assert!(result.is_err());
+    assert!(result.unwrap_err().to_string().contains("Invalid JSON"));
+}
+
+#[tokio::test]
+async fn test_json_constructors_and_to_json() {
+    let results = execute_query(
+        "SELECT TO_JSON(JSON_OBJECT('id', JSON_VALUE(payload, '$.order.id'), \
+                                    'skus', JSON_QUERY(payload, '$.items[*].sku'), \
+                                    'flags', JSON_ARRAY(true, NULL, 1.5))) as summary \
+         FROM events",
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        results[0].fields.get("summary"),
+        Some(&FieldValue::String(
+            r#"{"flags":[true,null,1.5],"id":"o-1","skus":["A","B","C"]}"#.to_string()
+        ))
+    );
+}
+
+#[tokio::test]
+async fn test_json_aggregates_with_group_by() {
+    let records = vec![
+        create_record(vec![
+            ("customer_id", FieldValue::Integer(1)),
+            ("sku", FieldValue::String("A".to_string())),
+            ("qty", FieldValue::Integer(2)),
+        ]),
+        create_record(vec![
+            ("customer_id", FieldValue::Integer(1)),
+            ("sku", FieldValue::String("B".to_string())),
+            ("qty", FieldValue::Integer(5)),
+        ]),
+    ];
+
+    let results = execute_query_with_records(
+        "SELECT customer_id, JSON_ARRAYAGG(sku) as skus, JSON_OBJECTAGG(sku, qty) as quantities \
+         FROM orders GROUP BY customer_id",
+        records,
+    )
+    .await
+    .unwrap();
+
+    let latest = results.last().expect("Expected GROUP BY output");
+    assert_eq!(latest.fields.get("skus"), Some(&strings(&["A", "B"])));
+    assert_eq!(
+        latest.fields.get("quantities"),
+        Some(&FieldValue::Struct(HashMap::from([
+            ("A".to_string(), FieldValue::Integer(2)),
+            ("B".to_string(), FieldValue::Integer(5)),
+        ])))
+    );
+}
+
+#[test]
+fn test_json_path_parsing() {
+    assert!(JsonPath::parse("$.a.b[0]").unwrap().is_definite());
+    assert!(JsonPath::parse("order.total").unwrap().is_definite());
+    assert!(!JsonPath::parse("$.items[*]").unwrap().is_definite());
+    assert!(!JsonPath::parse("$..price").unwrap().is_definite());
+    assert!(JsonPath::parse("$['odd key'][?(@.x == 'y' && !@.z)]").is_ok());
+
+    let err = JsonPath::parse("$.items[?(@.qty >)]").unwrap_err();
+    assert!(err.to_string().contains("Invalid JSON path"));
+    assert!(JsonPath::parse("$.items[").is_err());
+}
@@ -7,7 +7,7 @@ Tests both parsing and execution functionality.
 
 use ferrisstreams::ferris::serialization::JsonFormat;
 use ferrisstreams::ferris::sql::ast::{
-    Expr, LiteralValue, SelectField, StreamSource, StreamingQuery,
+    Expr, LiteralValue, NodeSpan, SelectField, StreamSource, StreamingQuery,
 };
 use ferrisstreams::ferris::sql::execution::{FieldValue, StreamExecutionEngine, StreamRecord};
 use std::collections::HashMap;
@@ -27,7 +27,7 @@ fn create_test_record() -> StreamRecord {
     fields.insert("null_value".to_string(), FieldValue::Null);
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         timestamp: chrono::Utc::now().timestamp_millis(),
         offset: 1,
         partition: 0,
@@ -55,11 +55,13 @@ async fn test_abs_function() {
             fields: vec![SelectField::Expression {
                 expr: Expr::Function {
                     name: "ABS".to_string(),
-                    args: vec![Expr::Column(column_name.to_string())],
+                    args: vec![Expr::Column(column_name.to_string(), NodeSpan::default())],
+                    span: NodeSpan::default(),
                 },
                 alias: Some("abs_result".to_string()),
             }],
             from: StreamSource::Stream("test".to_string()),
+            from_alias: None,
             joins: None,
             where_clause: None,
             window: None,
@@ -69,6 +71,7 @@ async fn test_abs_function() {
             limit: None,
             emit_mode: None,
             properties: None,
+            span: NodeSpan::default(),
         };
 
         let record = create_test_record();
@@ -102,11 +105,13 @@ async fn test_abs_function() {
         fields: vec![SelectField::Expression {
             expr: Expr::Function {
                 name: "ABS".to_string(),
-                args: vec![Expr::Column("null_value".to_string())],
+                args: vec![Expr::Column("null_value".to_string(), NodeSpan::default())],
+                span: NodeSpan::default(),
             },
             alias: Some("abs_result".to_string()),
         }],
         from: StreamSource::Stream("test".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         window: None,
@@ -116,6 +121,7 @@ async fn test_abs_function() {
         limit: None,
         emit_mode: None,
         properties: None,
+        span: NodeSpan::default(),
     };
 
     let record = create_test_record();
@@ -140,11 +146,13 @@ async fn test_round_function() {
         fields: vec![SelectField::Expression {
             expr: Expr::Function {
                 name: "ROUND".to_string(),
-                args: vec![Expr::Literal(LiteralValue::Float(3.7))],
+                args: vec![Expr::Literal(LiteralValue::Float(3.7), NodeSpan::default())],
+                span: NodeSpan::default(),
             },
             alias: Some("round_result".to_string()),
         }],
         from: StreamSource::Stream("test".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         window: None,
@@ -154,6 +162,7 @@ async fn test_round_function() {
         limit: None,
         emit_mode: None,
         properties: None,
+        span: NodeSpan::default(),
     };
 
     let record = create_test_record();
@@ -178,13 +187,15 @@ async fn test_round_function() {
             expr: Expr::Function {
                 name: "ROUND".to_string(),
                 args: vec![
-                    Expr::Literal(LiteralValue::Float(PI)),
-                    Expr::Literal(LiteralValue::Integer(2)),
+                    Expr::Literal(LiteralValue::Float(PI), NodeSpan::default()),
+                    Expr::Literal(LiteralValue::Integer(2), NodeSpan::default()),
                 ],
+                span: NodeSpan::default(),
             },
             alias: Some("round_result".to_string()),
         }],
         from: StreamSource::Stream("test".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         window: None,
@@ -194,6 +205,7 @@ async fn test_round_function() {
         limit: None,
         emit_mode: None,
         properties: None,
+        span: NodeSpan::default(),
     };
 
     let record = create_test_record();
@@ -231,11 +243,16 @@ async fn test_ceil_floor_functions() {
             fields: vec![SelectField::Expression {
                 expr: Expr::Function {
                     name: function.to_string(),
-                    args: vec![Expr::Literal(LiteralValue::Float(input))],
+                    args: vec![Expr::Literal(
+                        LiteralValue::Float(input),
+                        NodeSpan::default(),
+                    )],
+                    span: NodeSpan::default(),
                 },
                 alias: Some("result".to_string()),
             }],
             from: StreamSource::Stream("test".to_string()),
+            from_alias: None,
             joins: None,
             where_clause: None,
             window: None,
@@ -245,6 +262,7 @@ async fn test_ceil_floor_functions() {
             limit: None,
             emit_mode: None,
             properties: None,
+            span: NodeSpan::default(),
         };
 
         let record = create_test_record();
@@ -284,13 +302,15 @@ async fn test_mod_function() {
                 expr: Expr::Function {
                     name: "MOD".to_string(),
                     args: vec![
-                        Expr::Literal(LiteralValue::Integer(dividend)),
-                        Expr::Literal(LiteralValue::Integer(divisor)),
+                        Expr::Literal(LiteralValue::Integer(dividend), NodeSpan::default()),
+                        Expr::Literal(LiteralValue::Integer(divisor), NodeSpan::default()),
                     ],
+                    span: NodeSpan::default(),
                 },
                 alias: Some("mod_result".to_string()),
             }],
             from: StreamSource::Stream("test".to_string()),
+            from_alias: None,
             joins: None,
             where_clause: None,
             window: None,
@@ -300,6 +320,7 @@ async fn test_mod_function() {
             limit: None,
             emit_mode: None,
             properties: None,
+            span: NodeSpan::default(),
         };
 
         let record = create_test_record();
@@ -330,13 +351,15 @@ async fn test_mod_function() {
             expr: Expr::Function {
                 name: "MOD".to_string(),
                 args: vec![
-                    Expr::Literal(LiteralValue::Integer(10)),
-                    Expr::Literal(LiteralValue::Integer(0)),
+                    Expr::Literal(LiteralValue::Integer(10), NodeSpan::default()),
+                    Expr::Literal(LiteralValue::Integer(0), NodeSpan::default()),
                 ],
+                span: NodeSpan::default(),
             },
             alias: Some("mod_result".to_string()),
         }],
         from: StreamSource::Stream("test".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         window: None,
@@ -346,6 +369,7 @@ async fn test_mod_function() {
         limit: None,
         emit_mode: None,
         properties: None,
+        span: NodeSpan::default(),
     };
 
     let record = create_test_record();
@@ -377,13 +401,15 @@ async fn test_power_function() {
                     expr: Expr::Function {
                         name: function_name.to_string(),
                         args: vec![
-                            Expr::Literal(LiteralValue::Integer(base)),
-                            Expr::Literal(LiteralValue::Integer(exponent)),
+                            Expr::Literal(LiteralValue::Integer(base), NodeSpan::default()),
+                            Expr::Literal(LiteralValue::Integer(exponent), NodeSpan::default()),
                         ],
+                        span: NodeSpan::default(),
                     },
                     alias: Some("power_result".to_string()),
                 }],
                 from: StreamSource::Stream("test".to_string()),
+                from_alias: None,
                 joins: None,
                 where_clause: None,
                 window: None,
@@ -393,6 +419,7 @@ async fn test_power_function() {
                 limit: None,
                 emit_mode: None,
                 properties: None,
+                span: NodeSpan::default(),
             };
 
             let record = create_test_record();
@@ -453,11 +480,16 @@ async fn test_sqrt_function() {
             fields: vec![SelectField::Expression {
                 expr: Expr::Function {
                     name: "SQRT".to_string(),
-                    args: vec![Expr::Literal(LiteralValue::Integer(input))],
+                    args: vec![Expr::Literal(
+                        LiteralValue::Integer(input),
+                        NodeSpan::default(),
+                    )],
+                    span: NodeSpan::default(),
                 },
                 alias: Some("sqrt_result".to_string()),
             }],
             from: StreamSource::Stream("test".to_string()),
+            from_alias: None,
             joins: None,
             where_clause: None,
             window: None,
@@ -467,6 +499,7 @@ async fn test_sqrt_function() {
             limit: None,
             emit_mode: None,
             properties: None,
+            span: NodeSpan::default(),
         };
 
         let record = create_test_record();
@@ -493,11 +526,16 @@ async fn test_sqrt_function() {
         fields: vec![SelectField::Expression {
             expr: Expr::Function {
                 name: "SQRT".to_string(),
-                args: vec![Expr::Literal(LiteralValue::Integer(-4))],
+                args: vec![Expr::Literal(
+                    LiteralValue::Integer(-4),
+                    NodeSpan::default(),
+                )],
+                span: NodeSpan::default(),
             },
             alias: Some("sqrt_result".to_string()),
         }],
         from: StreamSource::Stream("test".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         window: None,
@@ -507,6 +545,7 @@ async fn test_sqrt_function() {
         limit: None,
         emit_mode: None,
         properties: None,
+        span: NodeSpan::default(),
     };
 
     let record = create_test_record();
@@ -525,7 +564,7 @@ async fn test_math_function_error_cases() {
     let error_cases = vec![
         // (function_name, args, expected_error_message_contains)
         ("ABS", vec![], "requires exactly one argument"),
-        ("ROUND", vec![], "requires 1 or 2 arguments"),
+        ("ROUND", vec![], "requires 1 to 3 arguments"),
         ("CEIL", vec![], "requires exactly one argument"),
         ("FLOOR", vec![], "requires exactly one argument"),
         ("MOD", vec![], "requires exactly two arguments"),
@@ -539,10 +578,12 @@ async fn test_math_function_error_cases() {
                 expr: Expr::Function {
                     name: function_name.to_string(),
                     args,
+                    span: NodeSpan::default(),
                 },
                 alias: Some("result".to_string()),
             }],
             from: StreamSource::Stream("test".to_string()),
+            from_alias: None,
             joins: None,
             where_clause: None,
             window: None,
@@ -552,6 +593,7 @@ async fn test_math_function_error_cases() {
             limit: None,
             emit_mode: None,
             properties: None,
+            span: NodeSpan::default(),
         };
 
         let record = create_test_record();
@@ -583,11 +625,13 @@ async fn test_math_function_null_handling() {
             fields: vec![SelectField::Expression {
                 expr: Expr::Function {
                     name: function_name.to_string(),
-                    args: vec![Expr::Column("null_value".to_string())],
+                    args: vec![Expr::Column("null_value".to_string(), NodeSpan::default())],
+                    span: NodeSpan::default(),
                 },
                 alias: Some("result".to_string()),
             }],
             from: StreamSource::Stream("test".to_string()),
+            from_alias: None,
             joins: None,
             where_clause: None,
             window: None,
@@ -597,6 +641,7 @@ async fn test_math_function_null_handling() {
             limit: None,
             emit_mode: None,
             properties: None,
+            span: NodeSpan::default(),
         };
 
         let record = create_test_record();
@@ -621,13 +666,15 @@ async fn test_math_function_null_handling() {
                 expr: Expr::Function {
                     name: function_name.to_string(),
                     args: vec![
-                        Expr::Column("null_value".to_string()),
-                        Expr::Literal(LiteralValue::Integer(2)),
+                        Expr::Column("null_value".to_string(), NodeSpan::default()),
+                        Expr::Literal(LiteralValue::Integer(2), NodeSpan::default()),
                     ],
+                    span: NodeSpan::default(),
                 },
                 alias: Some("result".to_string()),
             }],
             from: StreamSource::Stream("test".to_string()),
+            from_alias: None,
             joins: None,
             where_clause: None,
             window: None,
@@ -637,6 +684,7 @@ async fn test_math_function_null_handling() {
             limit: None,
             emit_mode: None,
             properties: None,
+            span: NodeSpan::default(),
         };
 
         let record = create_test_record();
@@ -660,13 +708,15 @@ async fn test_math_function_null_handling() {
                 expr: Expr::Function {
                     name: function_name.to_string(),
                     args: vec![
-                        Expr::Literal(LiteralValue::Integer(10)),
-                        Expr::Column("null_value".to_string()),
+                        Expr::Literal(LiteralValue::Integer(10), NodeSpan::default()),
+                        Expr::Column("null_value".to_string(), NodeSpan::default()),
                     ],
+                    span: NodeSpan::default(),
                 },
                 alias: Some("result".to_string()),
             }],
             from: StreamSource::Stream("test".to_string()),
+            from_alias: None,
             joins: None,
             where_clause: None,
             window: None,
@@ -676,6 +726,7 @@ async fn test_math_function_null_handling() {
             limit: None,
             emit_mode: None,
             properties: None,
+            span: NodeSpan::default(),
         };
 
         let record = create_test_record();
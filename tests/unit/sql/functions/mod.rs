@@ -4,12 +4,17 @@
 pub mod advanced_functions_test;
 pub mod cast_functions_test;
 pub mod date_functions_test;
+pub mod date_parsing_functions_test;
 pub mod header_functions_test;
 pub mod interval_test;
+pub mod json_functions_test;
 pub mod math_functions_test;
+pub mod nested_access_functions_test;
 pub mod new_functions_test;
+pub mod regex_hash_functions_test;
 pub mod statistical_functions_test;
 pub mod string_json_functions_test;
+pub mod timezone_functions_test;
 pub mod window_functions_test;
 // COUNT_DISTINCT and APPROX_COUNT_DISTINCT tests
 pub mod count_distinct_comprehensive_test;
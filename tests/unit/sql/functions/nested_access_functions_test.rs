@@ -0,0 +1,239 @@
+/*!
+# Nested Access and Higher-Order Function Tests
+
+Tests for dotted paths into STRUCT/MAP fields, `[index]` subscripts on arrays and maps,
+and the lambda-based TRANSFORM, FILTER, REDUCE, ARRAY_SORT and ARRAY_DISTINCT functions.
+*/
+
+use ferrisstreams::ferris::sql::execution::{FieldValue, StreamExecutionEngine, StreamRecord};
+use ferrisstreams::ferris::sql::parser::StreamingSqlParser;
+use std::collections::HashMap;
+use tokio::sync::mpsc;
+
+fn struct_of(fields: Vec<(&str, FieldValue)>) -> FieldValue {
+    FieldValue::Struct(
+        fields
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v))
+            .collect(),
+    )
+}
+
+fn item(sku: &str, price: f64) -> FieldValue {
+    struct_of(vec![
+        ("sku", FieldValue::String(sku.to_string())),
+        ("price", FieldValue::Float(price)),
+    ])
+}
+
+fn ints(values: &[i64]) -> FieldValue {
+    FieldValue::Array(values.iter().map(|v| FieldValue::Integer(*v)).collect())
+}
+
+fn create_order_record() -> StreamRecord {
+    let mut attributes = HashMap::new();
+    attributes.insert("region".to_string(), FieldValue::String("eu".to_string()));
+
+    let mut fields = HashMap::new();
+    fields.insert(
+        "payload".to_string(),
+        struct_of(vec![(
+            "customer",
+            struct_of(vec![
+                ("id", FieldValue::String("c-7".to_string())),
+                ("tier", FieldValue::String("gold".to_string())),
+            ]),
+        )]),
+    );
+    fields.insert(
+        "items".to_string(),
+        FieldValue::Array(vec![item("A", 5.0), item("B", 12.5), item("C", 7.0)]),
+    );
+    fields.insert("attributes".to_string(), FieldValue::Map(attributes));
+    fields.insert("scores".to_string(), ints(&[3, -1, 4, 1, -5, 4, 3]));
+    fields.insert("threshold".to_string(), FieldValue::Integer(2));
+
+    StreamRecord {
+        fields: fields.into(),
+        headers: HashMap::new(),
+        timestamp: 1672576245123,
+        offset: 1,
+        partition: 0,
+    }
+}
+
+async fn execute_query(query: &str) -> Result<Vec<StreamRecord>, Box<dyn std::error::Error>> {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut engine = StreamExecutionEngine::new(tx);
+    let parser = StreamingSqlParser::new();
+
+    let parsed_query = parser.parse(query)?;
+    engine
+        .execute_with_record(&parsed_query, create_order_record())
+        .await?;
+
+    let mut results = Vec::new();
+    while let Ok(result) = rx.try_recv() {
+        results.push(result);
+    }
+    Ok(results)
+}
+
+#[tokio::test]
+async fn test_dotted_paths_into_structs() {
+    let results = execute_query(
+        "SELECT payload.customer.id as customer_id, o.payload.customer.tier as tier \
+         FROM orders o WHERE payload.customer.tier = 'gold'",
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(results.len(), 1);
+    let fields = &results[0].fields;
+    assert_eq!(
+        fields.get("customer_id"),
+        Some(&FieldValue::String("c-7".to_string()))
+    );
+    assert_eq!(
+        fields.get("tier"),
+        Some(&FieldValue::String("gold".to_string()))
+    );
+}
+
+#[tokio::test]
+async fn test_array_and_map_subscripts() {
+    let results = execute_query(
+        "SELECT items[0].price as first_price, items[-1].sku as last_sku, \
+         items[10] as missing, attributes['region'] as region, \
+         attributes['unknown'] as unknown_key, scores[threshold] as by_column \
+         FROM orders",
+    )
+    .await
+    .unwrap();
+
+    let fields = &results[0].fields;
+    assert_eq!(fields.get("first_price"), Some(&FieldValue::Float(5.0)));
+    assert_eq!(
+        fields.get("last_sku"),
+        Some(&FieldValue::String("C".to_string()))
+    );
+    assert_eq!(fields.get("missing"), Some(&FieldValue::Null));
+    assert_eq!(
+        fields.get("region"),
+        Some(&FieldValue::String("eu".to_string()))
+    );
+    assert_eq!(fields.get("unknown_key"), Some(&FieldValue::Null));
+    assert_eq!(fields.get("by_column"), Some(&FieldValue::Integer(4)));
+}
+
+#[tokio::test]
+async fn test_subscript_in_where_clause() {
+    let results = execute_query("SELECT items[1].sku as sku FROM orders WHERE items[1].price > 10")
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 1);
+
+    let results = execute_query("SELECT items[1].sku as sku FROM orders WHERE items[0].price > 10")
+        .await
+        .unwrap();
+    assert!(results.is_empty());
+}
+
+#[tokio::test]
+async fn test_transform_and_filter() {
+    let results = execute_query(
+        "SELECT TRANSFORM(scores, x -> x * 2) as doubled, \
+         FILTER(scores, x -> x > threshold) as above, \
+         TRANSFORM(items, item -> item.sku) as skus, \
+         FILTER(scores, (x, i) -> i < 2) as first_two \
+         FROM orders",
+    )
+    .await
+    .unwrap();
+
+    let fields = &results[0].fields;
+    assert_eq!(
+        fields.get("doubled"),
+        Some(&ints(&[6, -2, 8, 2, -10, 8, 6]))
+    );
+    assert_eq!(fields.get("above"), Some(&ints(&[3, 4, 4, 3])));
+    assert_eq!(
+        fields.get("skus"),
+        Some(&FieldValue::Array(vec![
+            FieldValue::String("A".to_string()),
+            FieldValue::String("B".to_string()),
+            FieldValue::String("C".to_string()),
+        ]))
+    );
+    assert_eq!(fields.get("first_two"), Some(&ints(&[3, -1])));
+}
+
+#[tokio::test]
+async fn test_reduce() {
+    let results = execute_query(
+        "SELECT REDUCE(scores, 0, (acc, x) -> acc + x) as total, \
+         REDUCE(TRANSFORM(items, i -> i.price), 0.0, (acc, p) -> acc + p, acc -> acc * 2) as doubled_total \
+         FROM orders",
+    )
+    .await
+    .unwrap();
+
+    let fields = &results[0].fields;
+    assert_eq!(fields.get("total"), Some(&FieldValue::Integer(9)));
+    assert_eq!(fields.get("doubled_total"), Some(&FieldValue::Float(49.0)));
+}
+
+#[tokio::test]
+async fn test_array_sort_and_distinct() {
+    let results = execute_query(
+        "SELECT ARRAY_SORT(scores) as ascending, \
+         ARRAY_SORT(scores, (a, b) -> b - a) as descending, \
+         ARRAY_DISTINCT(scores) as distinct_scores, \
+         ARRAY_SORT(ARRAY_DISTINCT(scores)) as sorted_distinct \
+         FROM orders",
+    )
+    .await
+    .unwrap();
+
+    let fields = &results[0].fields;
+    assert_eq!(
+        fields.get("ascending"),
+        Some(&ints(&[-5, -1, 1, 3, 3, 4, 4]))
+    );
+    assert_eq!(
+        fields.get("descending"),
+        Some(&ints(&[4, 4, 3, 3, 1, -1, -5]))
+    );
+    assert_eq!(
+        fields.get("distinct_scores"),
+        Some(&ints(&[3, -1, 4, 1, -5]))
+    );
+    assert_eq!(
+        fields.get("sorted_distinct"),
+        Some(&ints(&[-5, -1, 1, 3, 4]))
+    );
+}
+
+#[tokio::test]
+async fn test_lambda_errors() {
+    assert!(
+        execute_query("SELECT TRANSFORM(scores, 5) as bad FROM orders")
+            .await
+            .is_err()
+    );
+    assert!(
+        execute_query("SELECT REDUCE(scores, 0, x -> x) as bad FROM orders")
+            .await
+            .is_err()
+    );
+    assert!(
+        execute_query("SELECT FILTER(scores, x -> x + 1) as bad FROM orders")
+            .await
+            .is_err()
+    );
+    assert!(
+        execute_query("SELECT COALESCE(x -> x, 1) as bad FROM orders")
+            .await
+            .is_err()
+    );
+}
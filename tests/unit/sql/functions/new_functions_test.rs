@@ -30,7 +30,7 @@ fn create_test_record() -> StreamRecord {
     headers.insert("version".to_string(), "1.0.0".to_string());
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         headers,
         timestamp: 1734652800000, // 2024-12-20 00:00:00 UTC
         offset: 100,
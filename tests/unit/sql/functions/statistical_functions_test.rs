@@ -14,7 +14,7 @@ In real windowed implementations, these would calculate actual statistics across
 
 use ferrisstreams::ferris::serialization::JsonFormat;
 use ferrisstreams::ferris::sql::ast::{
-    Expr, LiteralValue, SelectField, StreamSource, StreamingQuery,
+    Expr, LiteralValue, NodeSpan, SelectField, StreamSource, StreamingQuery,
 };
 use ferrisstreams::ferris::sql::execution::{FieldValue, StreamExecutionEngine, StreamRecord};
 use std::collections::HashMap;
@@ -32,7 +32,7 @@ fn create_test_record() -> StreamRecord {
     fields.insert("large_value".to_string(), FieldValue::Float(1000.5));
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         timestamp: chrono::Utc::now().timestamp_millis(),
         offset: 1,
         partition: 0,
@@ -52,11 +52,13 @@ async fn test_stddev_functions() {
             fields: vec![SelectField::Expression {
                 expr: Expr::Function {
                     name: function_name.to_string(),
-                    args: vec![Expr::Column("value_int".to_string())],
+                    args: vec![Expr::Column("value_int".to_string(), NodeSpan::default())],
+                    span: NodeSpan::default(),
                 },
                 alias: Some("stddev_result".to_string()),
             }],
             from: StreamSource::Stream("test".to_string()),
+            from_alias: None,
             joins: None,
             where_clause: None,
             window: None,
@@ -66,6 +68,7 @@ async fn test_stddev_functions() {
             limit: None,
             emit_mode: None,
             properties: None,
+            span: NodeSpan::default(),
         };
 
         let record = create_test_record();
@@ -97,11 +100,13 @@ async fn test_stddev_pop_function() {
         fields: vec![SelectField::Expression {
             expr: Expr::Function {
                 name: "STDDEV_POP".to_string(),
-                args: vec![Expr::Column("value_float".to_string())],
+                args: vec![Expr::Column("value_float".to_string(), NodeSpan::default())],
+                span: NodeSpan::default(),
             },
             alias: Some("stddev_pop_result".to_string()),
         }],
         from: StreamSource::Stream("test".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         window: None,
@@ -111,6 +116,7 @@ async fn test_stddev_pop_function() {
         limit: None,
         emit_mode: None,
         properties: None,
+        span: NodeSpan::default(),
     };
 
     let record = create_test_record();
@@ -143,11 +149,13 @@ async fn test_variance_functions() {
             fields: vec![SelectField::Expression {
                 expr: Expr::Function {
                     name: function_name.to_string(),
-                    args: vec![Expr::Column("large_value".to_string())],
+                    args: vec![Expr::Column("large_value".to_string(), NodeSpan::default())],
+                    span: NodeSpan::default(),
                 },
                 alias: Some("variance_result".to_string()),
             }],
             from: StreamSource::Stream("test".to_string()),
+            from_alias: None,
             joins: None,
             where_clause: None,
             window: None,
@@ -157,6 +165,7 @@ async fn test_variance_functions() {
             limit: None,
             emit_mode: None,
             properties: None,
+            span: NodeSpan::default(),
         };
 
         let record = create_test_record();
@@ -188,11 +197,16 @@ async fn test_var_pop_function() {
         fields: vec![SelectField::Expression {
             expr: Expr::Function {
                 name: "VAR_POP".to_string(),
-                args: vec![Expr::Column("negative_value".to_string())],
+                args: vec![Expr::Column(
+                    "negative_value".to_string(),
+                    NodeSpan::default(),
+                )],
+                span: NodeSpan::default(),
             },
             alias: Some("var_pop_result".to_string()),
         }],
         from: StreamSource::Stream("test".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         window: None,
@@ -202,6 +216,7 @@ async fn test_var_pop_function() {
         limit: None,
         emit_mode: None,
         properties: None,
+        span: NodeSpan::default(),
     };
 
     let record = create_test_record();
@@ -241,11 +256,13 @@ async fn test_median_function() {
             fields: vec![SelectField::Expression {
                 expr: Expr::Function {
                     name: "MEDIAN".to_string(),
-                    args: vec![Expr::Column(column_name.to_string())],
+                    args: vec![Expr::Column(column_name.to_string(), NodeSpan::default())],
+                    span: NodeSpan::default(),
                 },
                 alias: Some("median_result".to_string()),
             }],
             from: StreamSource::Stream("test".to_string()),
+            from_alias: None,
             joins: None,
             where_clause: None,
             window: None,
@@ -255,6 +272,7 @@ async fn test_median_function() {
             limit: None,
             emit_mode: None,
             properties: None,
+            span: NodeSpan::default(),
         };
 
         let record = create_test_record();
@@ -312,11 +330,13 @@ async fn test_statistical_function_null_handling() {
             fields: vec![SelectField::Expression {
                 expr: Expr::Function {
                     name: function_name.to_string(),
-                    args: vec![Expr::Column("null_value".to_string())],
+                    args: vec![Expr::Column("null_value".to_string(), NodeSpan::default())],
+                    span: NodeSpan::default(),
                 },
                 alias: Some("result".to_string()),
             }],
             from: StreamSource::Stream("test".to_string()),
+            from_alias: None,
             joins: None,
             where_clause: None,
             window: None,
@@ -326,6 +346,7 @@ async fn test_statistical_function_null_handling() {
             limit: None,
             emit_mode: None,
             properties: None,
+            span: NodeSpan::default(),
         };
 
         let record = create_test_record();
@@ -363,10 +384,12 @@ async fn test_statistical_function_error_cases() {
                 expr: Expr::Function {
                     name: function_name.to_string(),
                     args,
+                    span: NodeSpan::default(),
                 },
                 alias: Some("result".to_string()),
             }],
             from: StreamSource::Stream("test".to_string()),
+            from_alias: None,
             joins: None,
             where_clause: None,
             window: None,
@@ -376,6 +399,7 @@ async fn test_statistical_function_error_cases() {
             limit: None,
             emit_mode: None,
             properties: None,
+            span: NodeSpan::default(),
         };
 
         let record = create_test_record();
@@ -417,7 +441,7 @@ async fn test_statistical_functions_with_non_numeric_types() {
         FieldValue::String("not_a_number".to_string()),
     );
     let record = StreamRecord {
-        fields,
+        fields: fields.into(),
         timestamp: chrono::Utc::now().timestamp_millis(),
         offset: 1,
         partition: 0,
@@ -429,11 +453,16 @@ async fn test_statistical_functions_with_non_numeric_types() {
             fields: vec![SelectField::Expression {
                 expr: Expr::Function {
                     name: function_name.to_string(),
-                    args: vec![Expr::Column("string_field".to_string())],
+                    args: vec![Expr::Column(
+                        "string_field".to_string(),
+                        NodeSpan::default(),
+                    )],
+                    span: NodeSpan::default(),
                 },
                 alias: Some("result".to_string()),
             }],
             from: StreamSource::Stream("test".to_string()),
+            from_alias: None,
             joins: None,
             where_clause: None,
             window: None,
@@ -443,6 +472,7 @@ async fn test_statistical_functions_with_non_numeric_types() {
             limit: None,
             emit_mode: None,
             properties: None,
+            span: NodeSpan::default(),
         };
 
         let result = engine.execute_with_record(&query, record.clone()).await;
@@ -479,11 +509,13 @@ async fn test_statistical_functions_with_literal_values() {
             fields: vec![SelectField::Expression {
                 expr: Expr::Function {
                     name: function_name.to_string(),
-                    args: vec![Expr::Literal(literal_value.clone())],
+                    args: vec![Expr::Literal(literal_value.clone(), NodeSpan::default())],
+                    span: NodeSpan::default(),
                 },
                 alias: Some("result".to_string()),
             }],
             from: StreamSource::Stream("test".to_string()),
+            from_alias: None,
             joins: None,
             where_clause: None,
             window: None,
@@ -493,6 +525,7 @@ async fn test_statistical_functions_with_literal_values() {
             limit: None,
             emit_mode: None,
             properties: None,
+            span: NodeSpan::default(),
         };
 
         let record = create_test_record();
@@ -542,26 +575,30 @@ async fn test_multiple_statistical_functions_in_single_query() {
             SelectField::Expression {
                 expr: Expr::Function {
                     name: "STDDEV".to_string(),
-                    args: vec![Expr::Column("value_int".to_string())],
+                    args: vec![Expr::Column("value_int".to_string(), NodeSpan::default())],
+                    span: NodeSpan::default(),
                 },
                 alias: Some("stddev_result".to_string()),
             },
             SelectField::Expression {
                 expr: Expr::Function {
                     name: "VARIANCE".to_string(),
-                    args: vec![Expr::Column("value_int".to_string())],
+                    args: vec![Expr::Column("value_int".to_string(), NodeSpan::default())],
+                    span: NodeSpan::default(),
                 },
                 alias: Some("variance_result".to_string()),
             },
             SelectField::Expression {
                 expr: Expr::Function {
                     name: "MEDIAN".to_string(),
-                    args: vec![Expr::Column("value_int".to_string())],
+                    args: vec![Expr::Column("value_int".to_string(), NodeSpan::default())],
+                    span: NodeSpan::default(),
                 },
                 alias: Some("median_result".to_string()),
             },
         ],
         from: StreamSource::Stream("test".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         window: None,
@@ -571,6 +608,7 @@ async fn test_multiple_statistical_functions_in_single_query() {
         limit: None,
         emit_mode: None,
         properties: None,
+        span: NodeSpan::default(),
     };
 
     let record = create_test_record();
@@ -23,20 +23,20 @@ mod tests {
                     SelectField::Expression { expr, alias } => {
                         assert_eq!(alias, &Some("msg_prefix".to_string()));
                         match expr {
-                            Expr::Function { name, args } => {
+                            Expr::Function { name, args, .. } => {
                                 assert_eq!(name.to_uppercase(), "SUBSTRING");
                                 assert_eq!(args.len(), 3);
 
                                 // Check arguments
                                 match &args[1] {
-                                    Expr::Literal(LiteralValue::Integer(start)) => {
+                                    Expr::Literal(LiteralValue::Integer(start), _) => {
                                         assert_eq!(*start, 1);
                                     }
                                     _ => panic!("Expected integer literal for start position"),
                                 }
 
                                 match &args[2] {
-                                    Expr::Literal(LiteralValue::Integer(length)) => {
+                                    Expr::Literal(LiteralValue::Integer(length), _) => {
                                         assert_eq!(*length, 5);
                                     }
                                     _ => panic!("Expected integer literal for length"),
@@ -70,7 +70,7 @@ mod tests {
                     SelectField::Expression { expr, alias } => {
                         assert_eq!(alias, &Some("last_name".to_string()));
                         match expr {
-                            Expr::Function { name, args } => {
+                            Expr::Function { name, args, .. } => {
                                 assert_eq!(name.to_uppercase(), "SUBSTRING");
                                 assert_eq!(args.len(), 2); // Only string and start position
                             }
@@ -102,13 +102,13 @@ mod tests {
                     SelectField::Expression { expr, alias } => {
                         assert_eq!(alias, &Some("user_name".to_string()));
                         match expr {
-                            Expr::Function { name, args } => {
+                            Expr::Function { name, args, .. } => {
                                 assert_eq!(name.to_uppercase(), "JSON_EXTRACT");
                                 assert_eq!(args.len(), 2);
 
                                 // Check path argument
                                 match &args[1] {
-                                    Expr::Literal(LiteralValue::String(path)) => {
+                                    Expr::Literal(LiteralValue::String(path), _) => {
                                         assert_eq!(path, "$.user.name");
                                     }
                                     _ => panic!("Expected string literal for JSON path"),
@@ -142,13 +142,13 @@ mod tests {
                     SelectField::Expression { expr, alias } => {
                         assert_eq!(alias, &Some("order_total".to_string()));
                         match expr {
-                            Expr::Function { name, args } => {
+                            Expr::Function { name, args, .. } => {
                                 assert_eq!(name.to_uppercase(), "JSON_VALUE");
                                 assert_eq!(args.len(), 2);
 
                                 // Check path argument
                                 match &args[1] {
-                                    Expr::Literal(LiteralValue::String(path)) => {
+                                    Expr::Literal(LiteralValue::String(path), _) => {
                                         assert_eq!(path, "order.total");
                                     }
                                     _ => panic!("Expected string literal for JSON path"),
@@ -182,13 +182,13 @@ mod tests {
                     SelectField::Expression { expr, alias } => {
                         assert_eq!(alias, &Some("first_item_id".to_string()));
                         match expr {
-                            Expr::Function { name, args } => {
+                            Expr::Function { name, args, .. } => {
                                 assert_eq!(name.to_uppercase(), "JSON_EXTRACT");
                                 assert_eq!(args.len(), 2);
 
                                 // Check path argument with array access
                                 match &args[1] {
-                                    Expr::Literal(LiteralValue::String(path)) => {
+                                    Expr::Literal(LiteralValue::String(path), _) => {
                                         assert_eq!(path, "$.items[0].id");
                                     }
                                     _ => panic!("Expected string literal for JSON path"),
@@ -278,7 +278,7 @@ mod tests {
                     SelectField::Expression { expr, alias } => {
                         assert_eq!(alias, &Some("short_desc".to_string()));
                         match expr {
-                            Expr::Function { name, args } => {
+                            Expr::Function { name, args, .. } => {
                                 assert_eq!(name.to_uppercase(), "SUBSTRING");
                                 assert_eq!(args.len(), 3);
 
@@ -286,7 +286,7 @@ mod tests {
                                 match &args[0] {
                                     Expr::Function {
                                         name: inner_name,
-                                        args: inner_args,
+                                        args: inner_args, ..
                                     } => {
                                         assert_eq!(inner_name.to_uppercase(), "JSON_VALUE");
                                         assert_eq!(inner_args.len(), 2);
@@ -470,7 +470,7 @@ mod tests {
                     }
                     SelectField::Column(column) => column == "_timestamp" || column == "_partition",
                     SelectField::Expression { expr, .. } => match expr {
-                        Expr::Column(column) => column == "_timestamp" || column == "_partition",
+                        Expr::Column(column, _) => column == "_timestamp" || column == "_partition",
                         _ => false,
                     },
                     _ => false,
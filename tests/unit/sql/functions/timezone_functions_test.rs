@@ -0,0 +1,225 @@
+/*!
+# Time Zone Function Tests
+
+Tests for zone-aware timestamps: the TIMESTAMPTZ value type, `AT TIME ZONE`,
+CONVERT_TZ, and the optional time zone argument of EXTRACT and DATE_FORMAT.
+*/
+
+use chrono::{NaiveDate, TimeZone};
+use chrono_tz::Tz;
+use ferrisstreams::ferris::sql::execution::{FieldValue, StreamExecutionEngine, StreamRecord};
+use ferrisstreams::ferris::sql::parser::StreamingSqlParser;
+use std::collections::HashMap;
+use tokio::sync::mpsc;
+
+fn create_test_record() -> StreamRecord {
+    let mut fields = HashMap::new();
+
+    // 2023-01-01 12:30:45.123 UTC
+    fields.insert("event_time".to_string(), FieldValue::Integer(1672576245123));
+    fields.insert(
+        "naive_time".to_string(),
+        FieldValue::Timestamp(
+            NaiveDate::from_ymd_opt(2024, 3, 8)
+                .unwrap()
+                .and_hms_opt(9, 0, 0)
+                .unwrap(),
+        ),
+    );
+    // 2024-07-01 09:00:00 in London (BST, UTC+1)
+    fields.insert(
+        "london_open".to_string(),
+        FieldValue::TimestampTz(
+            Tz::Europe__London
+                .with_ymd_and_hms(2024, 7, 1, 9, 0, 0)
+                .unwrap(),
+        ),
+    );
+
+    StreamRecord {
+        fields: fields.into(),
+        headers: HashMap::new(),
+        timestamp: 1672576245123,
+        offset: 1,
+        partition: 0,
+    }
+}
+
+async fn execute_query(query: &str) -> Result<Vec<StreamRecord>, Box<dyn std::error::Error>> {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut engine = StreamExecutionEngine::new(tx);
+    let parser = StreamingSqlParser::new();
+
+    let parsed_query = parser.parse(query)?;
+    engine
+        .execute_with_record(&parsed_query, create_test_record())
+        .await?;
+
+    let mut results = Vec::new();
+    while let Ok(result) = rx.try_recv() {
+        results.push(result);
+    }
+    Ok(results)
+}
+
+#[tokio::test]
+async fn test_at_time_zone_on_epoch_millis() {
+    let results =
+        execute_query("SELECT event_time AT TIME ZONE 'Asia/Tokyo' as tokyo FROM test_stream")
+            .await
+            .unwrap();
+
+    assert_eq!(results.len(), 1);
+    match results[0].fields.get("tokyo") {
+        Some(FieldValue::TimestampTz(ts)) => {
+            assert_eq!(ts.timezone(), Tz::Asia__Tokyo);
+            assert_eq!(ts.timestamp_millis(), 1672576245123);
+        }
+        other => panic!("Expected TIMESTAMPTZ, got {:?}", other),
+    }
+    assert_eq!(
+        results[0].fields.get("tokyo").unwrap().to_display_string(),
+        "2023-01-01 21:30:45.123 Asia/Tokyo"
+    );
+}
+
+#[tokio::test]
+async fn test_at_time_zone_chained() {
+    // Converting a zone-aware value keeps the instant and changes the zone
+    let results = execute_query(
+        "SELECT london_open AT TIME ZONE 'Asia/Tokyo' AT TIME ZONE 'America/New_York' as ny FROM test_stream",
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        results[0].fields.get("ny").unwrap().to_display_string(),
+        "2024-07-01 04:00:00.000 America/New_York"
+    );
+}
+
+#[tokio::test]
+async fn test_extract_with_time_zone() {
+    let results = execute_query(
+        "SELECT EXTRACT('HOUR', event_time) as utc_hour, \
+                EXTRACT('HOUR', event_time, 'Asia/Tokyo') as tokyo_hour, \
+                EXTRACT('HOUR', event_time, 'America/New_York') as ny_hour, \
+                EXTRACT('TIMEZONE_HOUR', event_time, 'America/New_York') as ny_offset \
+         FROM test_stream",
+    )
+    .await
+    .unwrap();
+
+    let fields = &results[0].fields;
+    assert_eq!(fields.get("utc_hour"), Some(&FieldValue::Integer(12)));
+    assert_eq!(fields.get("tokyo_hour"), Some(&FieldValue::Integer(21)));
+    assert_eq!(fields.get("ny_hour"), Some(&FieldValue::Integer(7)));
+    assert_eq!(fields.get("ny_offset"), Some(&FieldValue::Integer(-5)));
+}
+
+#[tokio::test]
+async fn test_extract_uses_value_zone() {
+    let results = execute_query(
+        "SELECT EXTRACT('HOUR', london_open) as local_hour, \
+                EXTRACT('TIMEZONE', london_open) as offset_seconds \
+         FROM test_stream",
+    )
+    .await
+    .unwrap();
+
+    let fields = &results[0].fields;
+    assert_eq!(fields.get("local_hour"), Some(&FieldValue::Integer(9)));
+    assert_eq!(
+        fields.get("offset_seconds"),
+        Some(&FieldValue::Integer(3600))
+    );
+}
+
+#[tokio::test]
+async fn test_date_format_with_time_zone() {
+    let results = execute_query(
+        "SELECT DATE_FORMAT(event_time, '%Y-%m-%d %H:%M') as utc_fmt, \
+                DATE_FORMAT(event_time, '%Y-%m-%d %H:%M', 'Pacific/Auckland') as nz_fmt \
+         FROM test_stream",
+    )
+    .await
+    .unwrap();
+
+    let fields = &results[0].fields;
+    assert_eq!(
+        fields.get("utc_fmt"),
+        Some(&FieldValue::String("2023-01-01 12:30".to_string()))
+    );
+    // Auckland is UTC+13 in January, so this is already the next day
+    assert_eq!(
+        fields.get("nz_fmt"),
+        Some(&FieldValue::String("2023-01-02 01:30".to_string()))
+    );
+}
+
+#[tokio::test]
+async fn test_convert_tz_naive_timestamp() {
+    let results = execute_query(
+        "SELECT CONVERT_TZ(naive_time, 'Asia/Tokyo', 'Europe/London') as london FROM test_stream",
+    )
+    .await
+    .unwrap();
+
+    // 09:00 in Tokyo (UTC+9) is 00:00 in London (GMT in March)
+    assert_eq!(
+        results[0].fields.get("london").unwrap().to_display_string(),
+        "2024-03-08 00:00:00.000 Europe/London"
+    );
+}
+
+#[tokio::test]
+async fn test_convert_tz_unknown_zone() {
+    let result = execute_query(
+        "SELECT CONVERT_TZ(naive_time, 'Mars/Olympus', 'UTC') as bad FROM test_stream",
+    )
+    .await;
+
+    assert!(result.is_err());
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("Unknown time zone"));
+}
+
+#[test]
+fn test_cast_string_to_timestamptz() {
+    let value = FieldValue::String("2024-03-10 09:30:00 America/New_York".to_string())
+        .cast_to("TIMESTAMPTZ")
+        .unwrap();
+    match &value {
+        FieldValue::TimestampTz(ts) => {
+            assert_eq!(ts.timezone(), Tz::America__New_York);
+            // 09:30 EDT (after the 02:00 spring-forward) is 13:30 UTC
+            assert_eq!(
+                ts.naive_utc(),
+                NaiveDate::from_ymd_opt(2024, 3, 10)
+                    .unwrap()
+                    .and_hms_opt(13, 30, 0)
+                    .unwrap()
+            );
+        }
+        other => panic!("Expected TIMESTAMPTZ, got {:?}", other),
+    }
+
+    // RFC 3339 strings are accepted and normalized to UTC
+    let rfc = FieldValue::String("2024-03-08T09:00:00+09:00".to_string())
+        .cast_to("TIMESTAMP WITH TIME ZONE")
+        .unwrap();
+    assert_eq!(rfc.to_display_string(), "2024-03-08 00:00:00.000 UTC");
+
+    // Back to a naive TIMESTAMP gives the UTC wall-clock time
+    assert_eq!(
+        value.cast_to("TIMESTAMP").unwrap(),
+        FieldValue::Timestamp(
+            NaiveDate::from_ymd_opt(2024, 3, 10)
+                .unwrap()
+                .and_hms_opt(13, 30, 0)
+                .unwrap()
+        )
+    );
+}
@@ -22,7 +22,7 @@ fn create_test_record(id: i64, value: i64, name: &str) -> StreamRecord {
     headers.insert("source".to_string(), "test-system".to_string());
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         headers,
         timestamp: 1734652800000 + (id * 1000), // Sequential timestamps
         offset: id,
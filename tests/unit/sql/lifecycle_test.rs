@@ -18,7 +18,7 @@ mod tests {
         );
 
         match result.unwrap() {
-            StreamingQuery::PauseJob { name } => {
+            StreamingQuery::PauseJob { name, .. } => {
                 assert_eq!(name, "order_monitor");
             }
             _ => panic!("Expected PauseJob"),
@@ -38,7 +38,7 @@ mod tests {
         );
 
         match result.unwrap() {
-            StreamingQuery::ResumeJob { name } => {
+            StreamingQuery::ResumeJob { name, .. } => {
                 assert_eq!(name, "order_monitor");
             }
             _ => panic!("Expected ResumeJob"),
@@ -65,6 +65,7 @@ mod tests {
                 query,
                 properties,
                 strategy,
+                ..
             } => {
                 assert_eq!(name, "order_monitor");
                 assert_eq!(version, "1.2.0");
@@ -235,6 +236,7 @@ mod tests {
             StreamingQuery::RollbackJob {
                 name,
                 target_version,
+                ..
             } => {
                 assert_eq!(name, "order_monitor");
                 assert!(target_version.is_none()); // No specific version specified
@@ -259,6 +261,7 @@ mod tests {
             StreamingQuery::RollbackJob {
                 name,
                 target_version,
+                ..
             } => {
                 assert_eq!(name, "analytics");
                 assert_eq!(target_version, Some("1.5.0".to_string()));
@@ -290,10 +293,10 @@ mod tests {
             );
 
             match result.unwrap() {
-                StreamingQuery::PauseJob { name } if expected_type == "PauseJob" => {
+                StreamingQuery::PauseJob { name, .. } if expected_type == "PauseJob" => {
                     assert_eq!(name, "test");
                 }
-                StreamingQuery::ResumeJob { name } if expected_type == "ResumeJob" => {
+                StreamingQuery::ResumeJob { name, .. } if expected_type == "ResumeJob" => {
                     assert_eq!(name, "test");
                 }
                 _ => panic!("Expected {} for: {}", expected_type, query_str),
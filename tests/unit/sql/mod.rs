@@ -22,6 +22,12 @@ pub mod config;
 pub mod query_analyzer_explicit_type_test;
 pub mod query_analyzer_test;
 
+// Semantic analysis tests
+pub mod semantic_analyzer_test;
+
+// Query optimizer tests
+pub mod optimizer_test;
+
 // General SQL tests
 pub mod context_test;
 pub mod lifecycle_test;
@@ -0,0 +1,313 @@
+// Tests for QueryOptimizer - rule-based rewrites and reader pushdown
+use ferrisstreams::ferris::datasource::file::reader::FileReader;
+use ferrisstreams::ferris::datasource::file::{FileFormat, FileSourceConfig};
+use ferrisstreams::ferris::datasource::{BatchConfig, DataReader};
+use ferrisstreams::ferris::schema::{FieldDefinition, Schema};
+use ferrisstreams::ferris::sql::ast::{
+    BinaryOperator, DataType, Expr, LiteralValue, NodeSpan, SelectField, StreamSource,
+    StreamingQuery,
+};
+use ferrisstreams::ferris::sql::execution::algorithms::hash_join::JoinStatistics;
+use ferrisstreams::ferris::sql::execution::algorithms::JoinStrategy;
+use ferrisstreams::ferris::sql::execution::StreamRecord;
+use ferrisstreams::ferris::sql::optimizer::OptimizationRule;
+use ferrisstreams::ferris::sql::{QueryOptimizer, SchemaCatalog, StreamingSqlParser};
+use std::collections::HashMap;
+use std::io::Write;
+
+fn parse(sql: &str) -> StreamingQuery {
+    StreamingSqlParser::new()
+        .parse(sql)
+        .expect("query should parse")
+}
+
+fn where_clause(query: &StreamingQuery) -> Option<&Expr> {
+    match query {
+        StreamingQuery::Select { where_clause, .. } => where_clause.as_ref(),
+        StreamingQuery::CreateStream { as_select, .. } => where_clause(as_select),
+        _ => panic!("expected a SELECT"),
+    }
+}
+
+async fn read_all(reader: &mut Box<dyn DataReader>) -> Vec<StreamRecord> {
+    let mut records = Vec::new();
+    while reader.has_more().await.unwrap() {
+        records.extend(reader.read().await.unwrap());
+    }
+    records
+}
+
+#[test]
+fn test_constant_folding() {
+    let optimized = QueryOptimizer::new().optimize(&parse(
+        "SELECT id, amount * (60 * 60) AS hourly, UPPER('eu') AS region, 1 + 1 FROM orders \
+         WHERE 1 = 1 AND amount > 10 + 5",
+    ));
+
+    let expected = parse(
+        "SELECT id, amount * 3600 AS hourly, 'EU' AS region, 1 + 1 FROM orders WHERE amount > 15",
+    );
+    assert_eq!(optimized.query, expected);
+    assert!(optimized.applied(OptimizationRule::ConstantFolding));
+
+    // Expressions that fail to evaluate are left to fail at runtime
+    let division = QueryOptimizer::new().optimize(&parse("SELECT id FROM orders WHERE 1 / 0 > 1"));
+    assert!(!division.applied(OptimizationRule::ConstantFolding));
+}
+
+#[test]
+fn test_cast_simplification() {
+    let optimized = QueryOptimizer::new().optimize(&parse(
+        "CREATE STREAM payments (id BIGINT, amount DOUBLE) AS \
+         SELECT CAST(id, 'BIGINT') AS id, CAST(CAST(amount, 'STRING'), 'VARCHAR') AS label, \
+         CAST(amount, 'INTEGER') AS whole FROM raw_payments",
+    ));
+    assert!(optimized.applied(OptimizationRule::CastSimplification));
+
+    let StreamingQuery::CreateStream { as_select, .. } = &optimized.query else {
+        panic!("expected CREATE STREAM");
+    };
+    let StreamingQuery::Select { fields, .. } = as_select.as_ref() else {
+        panic!("expected SELECT");
+    };
+    let exprs: Vec<&Expr> = fields
+        .iter()
+        .map(|field| match field {
+            SelectField::Expression { expr, .. } => expr,
+            other => panic!("unexpected field {:?}", other),
+        })
+        .collect();
+    assert_eq!(
+        exprs[0],
+        &Expr::Column("id".to_string(), NodeSpan::default())
+    );
+    assert_eq!(
+        exprs[1],
+        &Expr::Function {
+            name: "CAST".to_string(),
+            args: vec![
+                Expr::Column("amount".to_string(), NodeSpan::default()),
+                Expr::Literal(
+                    LiteralValue::String("STRING".to_string()),
+                    NodeSpan::default()
+                ),
+            ],
+            span: NodeSpan::default()
+        }
+    );
+    // A cast to a different type is kept
+    assert!(matches!(exprs[2], Expr::Function { name, .. } if name == "CAST"));
+}
+
+#[test]
+fn test_predicate_pushdown_below_join() {
+    let optimized = QueryOptimizer::new().optimize(&parse(
+        "SELECT o.id, c.tier FROM orders o INNER JOIN customers c ON o.customer = c.name \
+         WHERE c.tier > 2 AND amount > 10 AND o.id = c.tier",
+    ));
+    assert!(optimized.applied(OptimizationRule::JoinPredicatePushdown));
+
+    let StreamingQuery::Select {
+        joins: Some(joins), ..
+    } = &optimized.query
+    else {
+        panic!("expected SELECT with joins");
+    };
+    let StreamSource::Subquery(right) = &joins[0].right_source else {
+        panic!("right source should be filtered by a subquery");
+    };
+    assert_eq!(
+        where_clause(right),
+        where_clause(&parse("SELECT * FROM customers WHERE tier > 2"))
+    );
+    assert_eq!(
+        where_clause(&optimized.query),
+        where_clause(&parse(
+            "SELECT * FROM t WHERE amount > 10 AND o.id = c.tier"
+        ))
+    );
+
+    // Left-side conjuncts go to the readers, the mixed conjunct stays in the query
+    assert_eq!(
+        optimized.pushdown.filter.as_ref(),
+        where_clause(&parse("SELECT * FROM t WHERE amount > 10"))
+    );
+    assert_eq!(
+        where_clause(optimized.residual_query()),
+        where_clause(&parse("SELECT * FROM t WHERE o.id = c.tier"))
+    );
+
+    // Outer joins keep the predicate above the join
+    let left_join = QueryOptimizer::new().optimize(&parse(
+        "SELECT o.id FROM orders o LEFT JOIN customers c ON o.customer = c.name WHERE c.tier > 2",
+    ));
+    assert!(!left_join.applied(OptimizationRule::JoinPredicatePushdown));
+}
+
+#[test]
+fn test_unaliased_join_needs_schema_for_reader_filter() {
+    let sql = "SELECT id FROM orders INNER JOIN customers ON customer = name WHERE amount > 10";
+    let unknown = QueryOptimizer::new().optimize(&parse(sql));
+    assert!(unknown.pushdown.filter.is_none());
+
+    let mut catalog = SchemaCatalog::new();
+    catalog.register_schema(
+        "customers",
+        Schema::new(vec![
+            FieldDefinition::required("name".to_string(), DataType::String),
+            FieldDefinition::optional("tier".to_string(), DataType::Integer),
+        ]),
+    );
+    let known = QueryOptimizer::new()
+        .with_schema_catalog(catalog)
+        .optimize(&parse(sql));
+    assert!(known.pushdown.filter.is_some());
+}
+
+#[test]
+fn test_projection_pruning() {
+    let optimized = QueryOptimizer::new().optimize(&parse(
+        "SELECT id, shipping.city, SUM(amount) AS total FROM orders WHERE paid = true \
+         GROUP BY id, shipping.city",
+    ));
+    let projection = optimized.pushdown.projection.expect("projection");
+    for field in ["id", "shipping", "shipping.city", "amount", "paid"] {
+        assert!(projection.contains(field), "missing {}", field);
+    }
+    assert!(!projection.contains("customer"));
+
+    let wildcard = QueryOptimizer::new().optimize(&parse("SELECT * FROM orders WHERE id > 1"));
+    assert!(wildcard.pushdown.projection.is_none());
+    assert!(wildcard.pushdown.filter.is_some());
+
+    // Declared columns are enforced after the read, so nothing is pushed down
+    let declared = QueryOptimizer::new().optimize(&parse(
+        "CREATE STREAM s (id BIGINT) AS SELECT id FROM raw WHERE id > 1",
+    ));
+    assert!(declared.pushdown.is_empty());
+}
+
+#[test]
+fn test_join_strategy_from_statistics() {
+    let optimized = QueryOptimizer::new()
+        .with_join_statistics(
+            "customers",
+            JoinStatistics {
+                left_cardinality: 1_000_000,
+                right_cardinality: 1_000,
+                available_memory: 64 * 1024 * 1024,
+                selectivity: 0.1,
+            },
+        )
+        .optimize(&parse(
+            "SELECT o.id FROM orders o INNER JOIN customers c ON o.customer = c.name",
+        ));
+    assert_eq!(
+        optimized.join_strategies.get("customers"),
+        Some(&JoinStrategy::HashJoin)
+    );
+    assert!(optimized.applied(OptimizationRule::JoinStrategySelection));
+}
+
+#[tokio::test]
+async fn test_file_reader_applies_pushdown() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("orders.jsonl");
+    let mut file = std::fs::File::create(&path).unwrap();
+    writeln!(
+        file,
+        r#"{{"id": 1, "amount": 5, "note": "small", "items": [1, 2, 3]}}"#
+    )
+    .unwrap();
+    writeln!(
+        file,
+        r#"{{"id": 2, "amount": 50, "note": "large", "items": [4]}}"#
+    )
+    .unwrap();
+
+    let optimized = QueryOptimizer::new().optimize(&parse(
+        "SELECT id, amount FROM orders WHERE amount > 10 AND 2 > 1",
+    ));
+    let reader = FileReader::new_with_batch_config(
+        FileSourceConfig::new(path.to_string_lossy().to_string(), FileFormat::JsonLines),
+        BatchConfig {
+            enable_batching: false,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    let mut readers: HashMap<String, Box<dyn DataReader>> = HashMap::new();
+    readers.insert("source_0_orders".to_string(), Box::new(reader));
+
+    let query = optimized.push_down_into(&mut readers);
+    assert!(where_clause(&query).is_none());
+
+    let reader = readers.get_mut("source_0_orders").unwrap();
+    let mut records = Vec::new();
+    while reader.has_more().await.unwrap() {
+        records.extend(reader.read().await.unwrap());
+    }
+    assert_eq!(records.len(), 1);
+    let mut fields: Vec<&String> = records[0].fields.keys().collect();
+    fields.sort();
+    assert_eq!(fields, vec!["amount", "id"]);
+
+    // The folded constant conjunct disappears instead of reaching the reader
+    assert!(matches!(
+        optimized.pushdown.filter,
+        Some(Expr::BinaryOp {
+            op: BinaryOperator::GreaterThan,
+            ..
+        })
+    ));
+}
+
+#[tokio::test]
+async fn test_pushdown_skips_joined_readers() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let orders_path = dir.path().join("orders.jsonl");
+    let mut file = std::fs::File::create(&orders_path).unwrap();
+    writeln!(file, r#"{{"id": 1, "amount": 5, "customer": "ann"}}"#).unwrap();
+    writeln!(file, r#"{{"id": 2, "amount": 50, "customer": "bob"}}"#).unwrap();
+    let customers_path = dir.path().join("customers.jsonl");
+    let mut file = std::fs::File::create(&customers_path).unwrap();
+    writeln!(file, r#"{{"name": "ann", "tier": "gold"}}"#).unwrap();
+    writeln!(file, r#"{{"name": "bob", "tier": "silver"}}"#).unwrap();
+
+    let optimized = QueryOptimizer::new().optimize(&parse(
+        "SELECT id, c.tier FROM orders o INNER JOIN customers c ON o.customer = c.name \
+         WHERE amount > 10",
+    ));
+    assert!(optimized.pushdown.filter.is_some());
+
+    let mut readers: HashMap<String, Box<dyn DataReader>> = HashMap::new();
+    for (key, path) in [
+        ("source_0_orders", &orders_path),
+        ("source_1_customers", &customers_path),
+    ] {
+        let reader = FileReader::new_with_batch_config(
+            FileSourceConfig::new(path.to_string_lossy().to_string(), FileFormat::JsonLines),
+            BatchConfig {
+                enable_batching: false,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        readers.insert(key.to_string(), Box::new(reader));
+    }
+
+    let query = optimized.push_down_into(&mut readers);
+    assert!(where_clause(&query).is_none());
+
+    let orders = read_all(readers.get_mut("source_0_orders").unwrap()).await;
+    assert_eq!(orders.len(), 1);
+
+    // The right side has no `amount` field; filtering it would have dropped every customer
+    let customers = read_all(readers.get_mut("source_1_customers").unwrap()).await;
+    assert_eq!(customers.len(), 2);
+    assert!(customers
+        .iter()
+        .all(|record| record.fields.contains_key("tier")));
+}
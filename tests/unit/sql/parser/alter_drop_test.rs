@@ -0,0 +1,116 @@
+/*!
+# Tests for ALTER and DROP statements
+
+Parsing of `ALTER STREAM/TABLE ... ADD COLUMN | SET (...)` and `DROP STREAM/TABLE/JOB [IF EXISTS]`.
+*/
+
+use ferrisstreams::ferris::sql::ast::{AlterOperation, DataType, ObjectType, StreamingQuery};
+use ferrisstreams::ferris::sql::parser::StreamingSqlParser;
+
+#[test]
+fn test_parse_alter_add_column() {
+    let parser = StreamingSqlParser::new();
+
+    let query = parser
+        .parse("ALTER STREAM orders ADD COLUMN discount DECIMAL(10, 2)")
+        .unwrap();
+    let StreamingQuery::Alter {
+        object_type,
+        name,
+        operation:
+            AlterOperation::AddColumn {
+                column,
+                if_not_exists,
+            },
+        ..
+    } = query
+    else {
+        panic!("Expected ALTER ... ADD COLUMN, got: {:?}", query);
+    };
+    assert_eq!(object_type, ObjectType::Stream);
+    assert_eq!(name, "orders");
+    assert_eq!(column.name, "discount");
+    assert_eq!(column.data_type.to_string(), "DECIMAL(10,2)");
+    assert!(column.nullable);
+    assert!(!if_not_exists);
+
+    // COLUMN is optional; IF NOT EXISTS and NOT NULL are recognized
+    let query = parser
+        .parse("ALTER TABLE totals ADD IF NOT EXISTS region STRING NOT NULL;")
+        .unwrap();
+    let StreamingQuery::Alter {
+        object_type: ObjectType::Table,
+        operation:
+            AlterOperation::AddColumn {
+                column,
+                if_not_exists: true,
+            },
+        ..
+    } = query
+    else {
+        panic!(
+            "Expected ALTER TABLE ... ADD IF NOT EXISTS, got: {:?}",
+            query
+        );
+    };
+    assert_eq!(column.data_type, DataType::String);
+    assert!(!column.nullable);
+}
+
+#[test]
+fn test_parse_alter_set_properties() {
+    let query = StreamingSqlParser::new()
+        .parse(
+            "ALTER STREAM orders SET ('retention.ms' = '3600000', 'schema.enforcement' = 'coerce')",
+        )
+        .unwrap();
+    let StreamingQuery::Alter {
+        operation: AlterOperation::SetProperties { properties },
+        ..
+    } = query
+    else {
+        panic!("Expected ALTER ... SET, got: {:?}", query);
+    };
+    assert_eq!(properties.len(), 2);
+    assert_eq!(properties.get("retention.ms").unwrap(), "3600000");
+    assert_eq!(properties.get("schema.enforcement").unwrap(), "coerce");
+}
+
+#[test]
+fn test_parse_drop() {
+    let parser = StreamingSqlParser::new();
+
+    for (sql, expected_type, expected_if_exists) in [
+        ("DROP STREAM orders", ObjectType::Stream, false),
+        ("DROP TABLE IF EXISTS orders", ObjectType::Table, true),
+        ("DROP JOB orders;", ObjectType::Job, false),
+    ] {
+        match parser.parse(sql).unwrap() {
+            StreamingQuery::Drop {
+                object_type,
+                name,
+                if_exists,
+                ..
+            } => {
+                assert_eq!(object_type, expected_type, "{}", sql);
+                assert_eq!(name, "orders", "{}", sql);
+                assert_eq!(if_exists, expected_if_exists, "{}", sql);
+            }
+            other => panic!("Expected DROP for '{}', got: {:?}", sql, other),
+        }
+    }
+}
+
+#[test]
+fn test_invalid_alter_and_drop() {
+    let parser = StreamingSqlParser::new();
+
+    assert!(parser.parse("ALTER JOB j SET ('a' = 'b')").is_err());
+    assert!(parser.parse("ALTER STREAM orders RENAME TO o").is_err());
+    assert!(parser
+        .parse("ALTER STREAM orders ADD COLUMN discount")
+        .is_err());
+    assert!(parser.parse("DROP VIEW orders").is_err());
+    assert!(parser.parse("DROP STREAM IF orders").is_err());
+    assert!(parser.parse("DROP STREAM orders CASCADE").is_err());
+}
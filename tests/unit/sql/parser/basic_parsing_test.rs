@@ -199,7 +199,7 @@ mod tests {
 
                 for field in &fields {
                     if let SelectField::Expression { expr, .. } = field {
-                        assert!(matches!(expr, Expr::Literal(LiteralValue::String(_))));
+                        assert!(matches!(expr, Expr::Literal(LiteralValue::String(_), _)));
                     }
                 }
             }
@@ -221,17 +221,17 @@ mod tests {
 
                 // Check first field (integer)
                 if let SelectField::Expression { expr, .. } = &fields[0] {
-                    assert!(matches!(expr, Expr::Literal(LiteralValue::Integer(42))));
+                    assert!(matches!(expr, Expr::Literal(LiteralValue::Integer(42), _)));
                 }
 
                 // Check second field (float - decimal numbers are parsed as Float for backward compatibility)
                 if let SelectField::Expression { expr, .. } = &fields[1] {
-                    assert!(matches!(expr, Expr::Literal(LiteralValue::Float(_))));
+                    assert!(matches!(expr, Expr::Literal(LiteralValue::Float(_), _)));
                 }
 
                 // Check third field (integer zero)
                 if let SelectField::Expression { expr, .. } = &fields[2] {
-                    assert!(matches!(expr, Expr::Literal(LiteralValue::Integer(0))));
+                    assert!(matches!(expr, Expr::Literal(LiteralValue::Integer(0), _)));
                 }
             }
             _ => panic!("Expected Select query"),
@@ -251,8 +251,8 @@ mod tests {
                 assert_eq!(fields.len(), 1);
 
                 if let SelectField::Expression { expr, .. } = &fields[0] {
-                    assert!(matches!(expr, Expr::Column(_)));
-                    if let Expr::Column(name) = expr {
+                    assert!(matches!(expr, Expr::Column(_, _)));
+                    if let Expr::Column(name, _) = expr {
                         assert_eq!(name, "customer_id");
                     }
                 }
@@ -274,8 +274,8 @@ mod tests {
                 assert_eq!(fields.len(), 1);
 
                 if let SelectField::Expression { expr, .. } = &fields[0] {
-                    assert!(matches!(expr, Expr::Column(_)));
-                    if let Expr::Column(name) = expr {
+                    assert!(matches!(expr, Expr::Column(_, _)));
+                    if let Expr::Column(name, _) = expr {
                         assert_eq!(name, "orders.customer_id");
                     }
                 }
@@ -33,7 +33,7 @@ fn create_test_record() -> StreamRecord {
     headers.insert("source".to_string(), "test-system".to_string());
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         headers,
         timestamp: 1734652800000,
         offset: 100,
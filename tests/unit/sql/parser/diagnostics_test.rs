@@ -0,0 +1,203 @@
+/*!
+# Tests for parser diagnostics
+
+`parse_script` and `parse_with_diagnostics` recover at statement and clause boundaries so
+that every error is reported, each with a span, a rustc-style excerpt and, for misspelled
+keywords, a "did you mean" suggestion.
+*/
+
+use ferrisstreams::ferris::sql::ast::{Expr, StreamingQuery};
+use ferrisstreams::ferris::sql::error::diagnostics::{
+    closest_match, edit_distance, Diagnostic, Span,
+};
+use ferrisstreams::ferris::sql::parser::StreamingSqlParser;
+
+fn errors(sql: &str) -> Vec<Diagnostic> {
+    StreamingSqlParser::new()
+        .parse_with_diagnostics(sql)
+        .expect_err("statement should not parse")
+}
+
+#[test]
+fn test_valid_statement_has_no_diagnostics() {
+    let parser = StreamingSqlParser::new();
+    let sql = "SELECT id, amount FROM orders WHERE amount > 10";
+    assert_eq!(
+        parser.parse_with_diagnostics(sql).unwrap(),
+        parser.parse(sql).unwrap()
+    );
+}
+
+#[test]
+fn test_reports_every_error_in_a_statement() {
+    let sql = "SELECT id, FROM orders WHERE amount > GROUP BY id";
+    let diagnostics = errors(sql);
+    assert_eq!(diagnostics.len(), 2, "{:?}", diagnostics);
+
+    // The first error is at FROM, the second where WHERE's operand is missing
+    let spans: Vec<&str> = diagnostics
+        .iter()
+        .map(|d| {
+            let span = d.span.unwrap();
+            &sql[span.start..span.end]
+        })
+        .collect();
+    assert_eq!(spans, vec!["FROM", "GROUP"]);
+}
+
+#[test]
+fn test_trailing_tokens_are_reported() {
+    let diagnostics = errors("SELECT * FROM orders WITH ('a' = 'b') WHERE id > 1");
+    assert_eq!(diagnostics.len(), 1);
+    assert!(diagnostics[0]
+        .message
+        .contains("Unexpected 'WHERE' after the end of the statement"));
+}
+
+#[test]
+fn test_keyword_suggestions() {
+    let diagnostics = errors("SELEC id FROM orders");
+    assert_eq!(diagnostics[0].help, vec!["did you mean `SELECT`?"]);
+
+    let diagnostics = errors("SELECT id FORM orders");
+    assert_eq!(diagnostics[0].help, vec!["did you mean `FROM`?"]);
+
+    // The keyword is misspelled one token before the parser notices
+    let diagnostics = errors("SELECT id FROM orders WHER id > 1");
+    assert_eq!(
+        diagnostics[0].help,
+        vec!["did you mean `WHERE` instead of `WHER`?"]
+    );
+}
+
+#[test]
+fn test_end_of_statement_is_labelled() {
+    let sql = "SELECT id FROM";
+    let diagnostics = errors(sql);
+    let diagnostic = &diagnostics[0];
+    assert_eq!(diagnostic.span, Some(Span::new(sql.len(), sql.len())));
+    assert_eq!(
+        diagnostic.label.as_deref(),
+        Some("unexpected end of statement")
+    );
+}
+
+#[test]
+fn test_tokenizer_errors_are_collected() {
+    // Characters that start no token are skipped, so the rest still parses
+    let sql = "SELECT id # FROM orders WHERE amount @> 1";
+    let diagnostics = errors(sql);
+    assert_eq!(diagnostics.len(), 2, "{:?}", diagnostics);
+    assert_eq!(diagnostics[0].span.unwrap().start, sql.find('#').unwrap());
+    assert_eq!(diagnostics[1].span.unwrap().start, sql.find('@').unwrap());
+}
+
+#[test]
+fn test_script_reports_errors_of_every_statement() {
+    let script = "\
+CREATE STREAM totals AS SELECT id, SUM(amount) AS total FROM orders GROUP BY id;
+
+SELECT * FORM totals;
+
+-- trailing comment
+SELECT id, FROM orders;
+SELECT 1 FROM orders";
+    let statements = StreamingSqlParser::new().parse_script(script);
+    assert_eq!(statements.len(), 4);
+
+    assert!(statements[0].result.is_ok());
+    assert!(script[statements[0].span.start..statements[0].span.end].starts_with("CREATE"));
+    assert!(statements[3].result.is_ok());
+
+    let second = statements[1].result.as_ref().unwrap_err();
+    assert_eq!(second[0].line_column(script), Some((3, 10)));
+    let third = statements[2].result.as_ref().unwrap_err();
+    assert_eq!(third[0].line_column(script), Some((6, 12)));
+    assert_eq!(
+        &script[statements[2].span.start..statements[2].span.end],
+        "SELECT id, FROM orders"
+    );
+}
+
+#[test]
+fn test_render_matches_rustc_layout() {
+    let script = "SELECT id\nFROM orders;\nSELECT * FORM orders";
+    let statements = StreamingSqlParser::new().parse_script(script);
+    let diagnostic = &statements[1].result.as_ref().unwrap_err()[0];
+
+    assert_eq!(
+        diagnostic.render(script, "app.sql"),
+        "error: Unexpected 'FORM' after the end of the statement\n \
+         --> app.sql:3:10\n  \
+         |\n\
+         3 | SELECT * FORM orders\n  \
+         |          ^^^^\n  \
+         |\n  \
+         = help: did you mean `FROM`?\n"
+    );
+}
+
+#[test]
+fn test_span_positions() {
+    let text = "héllo\nwörld";
+    let span = Span::from_char_range(text, 6, 11);
+    assert_eq!(&text[span.start..span.end], "wörld");
+    assert_eq!(span.line_column(text), (2, 1));
+    assert_eq!(Span::new(3, 4).offset(10), Span::new(13, 14));
+    assert_eq!(Span::new(5, 2), Span::new(5, 5));
+    assert!(Span::new(5, 5).is_empty());
+}
+
+#[test]
+fn test_parsed_nodes_carry_spans() {
+    let sql = "SELECT price * 2 AS doubled FROM orders WHERE city = 'Zürich' AND qty > 1";
+    let query = StreamingSqlParser::new().parse(sql).unwrap();
+    let text = |span: Option<Span>| {
+        let span = span.expect("parsed nodes have a span");
+        &sql[span.start..span.end]
+    };
+    assert_eq!(text(query.span()), sql);
+
+    let StreamingQuery::Select {
+        where_clause: Some(condition),
+        ..
+    } = &query
+    else {
+        panic!("expected a SELECT with a WHERE clause");
+    };
+    assert_eq!(text(condition.span()), "city = 'Zürich' AND qty > 1");
+    let Expr::BinaryOp { left, right, .. } = condition else {
+        panic!("expected AND");
+    };
+    assert_eq!(text(left.span()), "city = 'Zürich'");
+    assert_eq!(text(right.span()), "qty > 1");
+    let Expr::BinaryOp { left, .. } = right.as_ref() else {
+        panic!("expected a comparison");
+    };
+    assert_eq!(text(left.span()), "qty");
+
+    // Spans of later statements in a script are in the script's text
+    let script = "SELECT a FROM s; SELECT b FROM t";
+    let statements = StreamingSqlParser::new().parse_script(script);
+    let second = statements[1].result.as_ref().unwrap();
+    assert_eq!(second.span(), Some(statements[1].span));
+    let span = statements[1].span;
+    assert_eq!(&script[span.start..span.end], "SELECT b FROM t");
+}
+
+#[test]
+fn test_closest_match() {
+    let keywords = ["SELECT", "FROM", "WHERE", "WHEN"];
+    assert_eq!(closest_match("selct", keywords), Some("SELECT"));
+    assert_eq!(closest_match("FORM", keywords), Some("FROM"));
+    // Ties go to the earlier candidate
+    assert_eq!(closest_match("WHER", keywords), Some("WHERE"));
+    // Exact matches, far-off words and very short words get no suggestion
+    assert_eq!(closest_match("from", keywords), None);
+    assert_eq!(closest_match("GROUP", keywords), None);
+    assert_eq!(closest_match("WH", keywords), None);
+
+    assert_eq!(edit_distance("kitten", "sitting"), 3);
+    assert_eq!(edit_distance("form", "from"), 1);
+    assert_eq!(edit_distance("", "abc"), 3);
+}
@@ -0,0 +1,226 @@
+/*!
+# Tests for the SQL formatter
+
+Every AST node prints as SQL that parses back to the same AST, `format_query` lays
+statements out one clause per line, and `format_script` formats whole applications while
+keeping their comments.
+*/
+
+use ferrisstreams::ferris::sql::ast::{
+    BinaryOperator, Expr, LiteralValue, NodeSpan, StreamSource, StreamingQuery,
+};
+use ferrisstreams::ferris::sql::formatter::{format_query, format_script};
+use ferrisstreams::ferris::sql::parser::StreamingSqlParser;
+
+/// Statements covering every clause and expression form the parser accepts
+const ROUND_TRIP_QUERIES: &[&str] = &[
+    "SELECT * FROM orders",
+    "SELECT id, amount AS total FROM orders WHERE amount > 10 AND status = 'open' LIMIT 5",
+    "SELECT o.id, o.amount FROM orders o",
+    "SELECT * FROM 'file:///data/orders.json'",
+    "SELECT * FROM kafka_orders WHERE NOT cancelled OR amount <= 0",
+    "SELECT (a + b) * c, a - (b - c), a / (b * c), -5, 1.5, 1e10 FROM t",
+    "SELECT a || b || 'x', (a || b) = c FROM t",
+    "SELECT a = (b = c), (a < b) = TRUE FROM t",
+    "SELECT * FROM t WHERE (a OR b) AND (c OR d)",
+    "SELECT * FROM t WHERE NOT (a AND b)",
+    "SELECT * FROM t WHERE id IN (1, 2, 3) AND name NOT IN ('a', 'b')",
+    "SELECT * FROM t WHERE amount BETWEEN 1 AND 10 AND qty NOT BETWEEN 2 AND 3",
+    "SELECT * FROM t WHERE note IS NULL OR note IS NOT NULL",
+    "SELECT CASE WHEN a > 1 THEN 'big' WHEN a > 0 THEN 'small' ELSE 'none' END AS size FROM t",
+    "SELECT CAST(amount, 'DECIMAL(10,2)'), CAST(id, 'STRING') FROM t",
+    "SELECT COUNT(*), SUM(amount), AVG(amount) FROM t GROUP BY customer_id HAVING COUNT(*) > 2",
+    "SELECT customer_id, SUM(amount) FROM orders GROUP BY customer_id WINDOW TUMBLING(5m)",
+    "SELECT SUM(amount) FROM orders WINDOW SLIDING(10m, 1m)",
+    "SELECT SUM(amount) FROM orders WINDOW SESSION(30s)",
+    "SELECT SUM(amount) FROM orders WINDOW TUMBLING(1h) EMIT CHANGES",
+    "SELECT * FROM orders ORDER BY amount DESC, id LIMIT 10 EMIT FINAL",
+    "SELECT id, LAG(amount, 1) OVER (PARTITION BY customer_id ORDER BY ts) FROM orders",
+    "SELECT ROW_NUMBER() OVER (ORDER BY ts DESC ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW) FROM t",
+    "SELECT SUM(x) OVER (ORDER BY ts RANGE BETWEEN 2 PRECEDING AND 1 FOLLOWING) FROM t",
+    "SELECT ts AT TIME ZONE 'UTC', (a + b) AT TIME ZONE tz FROM t",
+    "SELECT NOW(), CURRENT_TIMESTAMP, INTERVAL '5' MINUTE, INTERVAL 2 HOUR FROM t",
+    "SELECT payload.customer.id, items[0].price, attributes['key'], (items).count FROM t",
+    "SELECT TRANSFORM(items, x -> x * 2), REDUCE(items, 0, (acc, x) -> acc + x) FROM t",
+    "SELECT * FROM t WHERE EXISTS (SELECT * FROM u WHERE u.id = 1)",
+    "SELECT * FROM t WHERE id IN (SELECT id FROM u)",
+    "SELECT (SELECT MAX(amount) FROM u) AS top FROM t",
+    "SELECT * FROM a UNION SELECT * FROM b UNION ALL SELECT * FROM c",
+    "SELECT o.id, c.name FROM orders o INNER JOIN customers c ON o.customer_id = c.id",
+    "SELECT * FROM orders o LEFT JOIN payments AS p ON o.id = p.order_id WITHIN 5m",
+    "SELECT * FROM a RIGHT JOIN b ON a.id = b.id FULL OUTER JOIN c ON b.id = c.id",
+    "SELECT \"it's\", 'say \"hi\"' FROM t",
+    "SELECT * FROM orders WITH ('orders.type' = 'kafka_source', 'orders.topic' = 'orders')",
+    "CREATE STREAM big_orders AS SELECT * FROM orders WHERE amount > 100",
+    "CREATE STREAM s (id INTEGER NOT NULL, amount DECIMAL(10,2)) AS SELECT * FROM orders",
+    "CREATE TABLE t (id INTEGER PRIMARY KEY, qty INTEGER, CHECK (qty > 0)) AS SELECT * FROM orders",
+    "CREATE TABLE totals AS SELECT customer_id, SUM(amount) FROM orders GROUP BY customer_id EMIT CHANGES",
+    "CREATE STREAM s AS SELECT * FROM src INTO sink WITH ('sink.type' = 'file_sink')",
+    "CREATE TABLE t AS SELECT * FROM src INTO sink",
+    "ALTER STREAM orders ADD COLUMN IF NOT EXISTS note STRING NOT NULL",
+    "ALTER TABLE totals SET ('retention.ms' = '1000')",
+    "DROP STREAM IF EXISTS orders",
+    "DROP TABLE totals",
+    "DROP JOB cleanup",
+    "SHOW STREAMS",
+    "SHOW TABLES LIKE 'ord%'",
+    "SHOW JOBS",
+    "DESCRIBE orders",
+    "START JOB etl AS SELECT * FROM orders WITH ('buffer' = '10')",
+    "STOP JOB etl FORCE",
+    "STOP JOB etl WITH SAVEPOINT",
+    "START JOB etl FROM SAVEPOINT '/savepoints/etl/savepoint-1.svpt' AS SELECT * FROM orders",
+    "SHOW SAVEPOINTS",
+    "SHOW SAVEPOINTS etl",
+    "PAUSE JOB etl",
+    "RESUME JOB etl",
+    "DEPLOY JOB etl VERSION '2.0' AS SELECT * FROM orders STRATEGY CANARY(10)",
+    "DEPLOY JOB etl VERSION '2.0' AS SELECT * FROM orders STRATEGY ROLLING",
+    "ROLLBACK JOB etl VERSION '1.0'",
+    "ROLLBACK JOB etl",
+    "MERGE INTO totals t USING updates u ON t.id = u.id WHEN MATCHED AND u.deleted THEN DELETE WHEN MATCHED THEN UPDATE SET amount = u.amount WHEN NOT MATCHED THEN INSERT (id, amount) VALUES (u.id, u.amount)",
+    "UPSERT INTO totals (id, amount) VALUES (1, 2.5)",
+    "UPSERT INTO totals (id, amount) KEY (amount) SELECT id, amount FROM orders",
+    "SELECT id FROM orders INTO sink",
+];
+
+fn parse(sql: &str) -> StreamingQuery {
+    StreamingSqlParser::new()
+        .parse(sql)
+        .unwrap_or_else(|e| panic!("failed to parse {sql}: {e}"))
+}
+
+#[test]
+fn test_display_round_trips() {
+    for sql in ROUND_TRIP_QUERIES {
+        let query = parse(sql);
+        let printed = query.to_string();
+        assert_eq!(parse(&printed), query, "{sql}\nprinted as\n{printed}");
+    }
+}
+
+#[test]
+fn test_format_query_round_trips() {
+    for sql in ROUND_TRIP_QUERIES {
+        let query = parse(sql);
+        let formatted = format_query(&query);
+        assert_eq!(parse(&formatted), query, "{sql}\nformatted as\n{formatted}");
+        assert_eq!(format_query(&parse(&formatted)), formatted);
+    }
+}
+
+#[test]
+fn test_equivalent_queries_format_identically() {
+    let a = parse("select  id,amount from orders where amount>10 window tumbling(60s)");
+    let b = parse("SELECT id, amount\nFROM orders WHERE (amount > 10) WINDOW TUMBLING (1m)");
+    assert_eq!(format_query(&a), format_query(&b));
+    assert_eq!(
+        format_query(&a),
+        "SELECT id, amount\nFROM orders\nWHERE amount > 10\nWINDOW TUMBLING(1m)"
+    );
+}
+
+#[test]
+fn test_only_needed_parentheses() {
+    let query = parse("SELECT ((a + b)) * c, a + (b * c), (a - b) - c, a - (b - c) FROM t");
+    assert_eq!(
+        query.to_string(),
+        "SELECT (a + b) * c, a + b * c, a - b - c, a - (b - c) FROM t"
+    );
+}
+
+#[test]
+fn test_parenthesizes_operands_built_without_parser() {
+    // (a OR b) AND c has no textual form without parentheses
+    let or = Expr::BinaryOp {
+        left: Box::new(Expr::Column("a".to_string(), NodeSpan::default())),
+        op: BinaryOperator::Or,
+        right: Box::new(Expr::Column("b".to_string(), NodeSpan::default())),
+        span: NodeSpan::default(),
+    };
+    let and = Expr::BinaryOp {
+        left: Box::new(or),
+        op: BinaryOperator::And,
+        right: Box::new(Expr::Literal(
+            LiteralValue::Boolean(true),
+            NodeSpan::default(),
+        )),
+        span: NodeSpan::default(),
+    };
+    assert_eq!(and.to_string(), "(a OR b) AND TRUE");
+}
+
+#[test]
+fn test_properties_sorted_and_long_lists_split() {
+    let query = parse(
+        "CREATE STREAM s AS SELECT id FROM src \
+         WITH ('src.type' = 'kafka_source', 'src.bootstrap.servers' = 'localhost:9092', 'src.topic' = 'orders')",
+    );
+    assert_eq!(
+        format_query(&query),
+        "CREATE STREAM s AS\n\
+         SELECT id\n\
+         FROM src\n\
+         WITH (\n    \
+         'src.bootstrap.servers' = 'localhost:9092',\n    \
+         'src.topic' = 'orders',\n    \
+         'src.type' = 'kafka_source'\n\
+         )"
+    );
+}
+
+#[test]
+fn test_from_alias_is_parsed() {
+    match parse("SELECT o.id FROM orders o WHERE o.amount > 1") {
+        StreamingQuery::Select {
+            from, from_alias, ..
+        } => {
+            assert_eq!(from, StreamSource::Stream("orders".to_string()));
+            assert_eq!(from_alias.as_deref(), Some("o"));
+        }
+        other => panic!("expected SELECT, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_format_script_keeps_comments() {
+    let script = "-- totals per customer\n\
+                  create stream totals as select customer_id, sum(amount) from orders group by customer_id; -- done\n\
+                  \n\
+                  \n\
+                  /* cleanup */\n\
+                  drop stream if exists old_totals;";
+    assert_eq!(
+        format_script(script).unwrap(),
+        "-- totals per customer\n\
+         CREATE STREAM totals AS\n\
+         SELECT customer_id, sum(amount)\n\
+         FROM orders\n\
+         GROUP BY customer_id; -- done\n\
+         \n\
+         /* cleanup */\n\
+         DROP STREAM IF EXISTS old_totals;\n"
+    );
+}
+
+#[test]
+fn test_format_script_keeps_statements_with_inner_comments_or_variables() {
+    let script = "SELECT id -- the key\nFROM orders;\n\n\
+                  SELECT * FROM orders WITH ('orders.topic' = '${TOPIC}');\n";
+    assert_eq!(format_script(script).unwrap(), script);
+}
+
+#[test]
+fn test_format_script_is_idempotent() {
+    let script =
+        "select a,b from t where a>1;select * from u -- trailing\n;\n\n-- last\nshow streams";
+    let formatted = format_script(script).unwrap();
+    assert_eq!(format_script(&formatted).unwrap(), formatted);
+}
+
+#[test]
+fn test_format_script_reports_parse_errors() {
+    let diagnostics = format_script("SELECT id FROM orders;\nSELEC id FROM orders;").unwrap_err();
+    assert_eq!(diagnostics.len(), 1);
+    assert!(diagnostics[0].span.is_some());
+}
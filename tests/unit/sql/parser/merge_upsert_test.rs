@@ -0,0 +1,172 @@
+/*!
+# Tests for MERGE and UPSERT statements
+
+Parsing of `MERGE INTO ... USING ... ON ... WHEN [NOT] MATCHED ...` and
+`UPSERT INTO ... [KEY (...)] VALUES | SELECT`.
+*/
+
+use ferrisstreams::ferris::sql::ast::{
+    InsertSource, MergeClause, MergeMatchedAction, StreamSource, StreamingQuery,
+};
+use ferrisstreams::ferris::sql::parser::StreamingSqlParser;
+
+#[test]
+fn test_parse_merge() {
+    let query = StreamingSqlParser::new()
+        .parse(
+            "MERGE INTO inventory AS t
+             USING stock_movements s
+             ON t.sku = s.sku
+             WHEN MATCHED AND s.qty = 0 THEN DELETE
+             WHEN MATCHED THEN UPDATE SET t.qty = t.qty + s.qty, updated_at = s.ts
+             WHEN NOT MATCHED THEN INSERT (sku, qty) VALUES (s.sku, s.qty)
+             WITH ('sink.topic' = 'inventory');",
+        )
+        .unwrap();
+    let StreamingQuery::Merge {
+        target,
+        target_alias,
+        source,
+        source_alias,
+        clauses,
+        properties,
+        ..
+    } = query
+    else {
+        panic!("Expected MERGE, got: {:?}", query);
+    };
+    assert_eq!(target, "inventory");
+    assert_eq!(target_alias.as_deref(), Some("t"));
+    assert_eq!(source, StreamSource::Stream("stock_movements".to_string()));
+    assert_eq!(source_alias.as_deref(), Some("s"));
+    assert_eq!(properties.unwrap().get("sink.topic").unwrap(), "inventory");
+
+    assert_eq!(clauses.len(), 3);
+    assert!(matches!(
+        &clauses[0],
+        MergeClause::Matched {
+            condition: Some(_),
+            action: MergeMatchedAction::Delete,
+        }
+    ));
+    let MergeClause::Matched {
+        condition: None,
+        action: MergeMatchedAction::Update { assignments },
+    } = &clauses[1]
+    else {
+        panic!("Expected WHEN MATCHED THEN UPDATE, got: {:?}", clauses[1]);
+    };
+    // The target qualifier is dropped from SET columns
+    let columns: Vec<&str> = assignments.iter().map(|(c, _)| c.as_str()).collect();
+    assert_eq!(columns, vec!["qty", "updated_at"]);
+    let MergeClause::NotMatched {
+        columns, values, ..
+    } = &clauses[2]
+    else {
+        panic!(
+            "Expected WHEN NOT MATCHED THEN INSERT, got: {:?}",
+            clauses[2]
+        );
+    };
+    assert_eq!(columns, &vec!["sku".to_string(), "qty".to_string()]);
+    assert_eq!(values.len(), 2);
+}
+
+#[test]
+fn test_parse_merge_without_aliases() {
+    let query = StreamingSqlParser::new()
+        .parse(
+            "MERGE INTO prices USING 'kafka://price_updates' ON prices.sku = price_updates.sku \
+             WHEN NOT MATCHED THEN INSERT (sku) VALUES (price_updates.sku)",
+        )
+        .unwrap();
+    let StreamingQuery::Merge {
+        target_alias,
+        source,
+        source_alias,
+        ..
+    } = query
+    else {
+        panic!("Expected MERGE, got: {:?}", query);
+    };
+    assert_eq!(target_alias, None);
+    assert_eq!(
+        source,
+        StreamSource::Uri("kafka://price_updates".to_string())
+    );
+    assert_eq!(source_alias, None);
+}
+
+#[test]
+fn test_parse_upsert() {
+    let parser = StreamingSqlParser::new();
+
+    // The first listed column is the key by default
+    let query = parser
+        .parse("UPSERT INTO prices (sku, price) VALUES (sku, price * 2)")
+        .unwrap();
+    let StreamingQuery::Upsert {
+        table_name,
+        columns,
+        key_columns,
+        source: InsertSource::Values { rows },
+        ..
+    } = query
+    else {
+        panic!("Expected UPSERT ... VALUES, got: {:?}", query);
+    };
+    assert_eq!(table_name, "prices");
+    assert_eq!(
+        columns.unwrap(),
+        vec!["sku".to_string(), "price".to_string()]
+    );
+    assert_eq!(key_columns, vec!["sku".to_string()]);
+    assert_eq!(rows.len(), 1);
+
+    let query = parser
+        .parse("UPSERT INTO balances KEY (account, region) SELECT account, region, balance FROM ledger")
+        .unwrap();
+    let StreamingQuery::Upsert {
+        columns: None,
+        key_columns,
+        source: InsertSource::Select { .. },
+        ..
+    } = query
+    else {
+        panic!("Expected UPSERT ... SELECT, got: {:?}", query);
+    };
+    assert_eq!(
+        key_columns,
+        vec!["account".to_string(), "region".to_string()]
+    );
+}
+
+#[test]
+fn test_invalid_merge_and_upsert() {
+    let parser = StreamingSqlParser::new();
+
+    // MERGE needs at least one clause and an INSERT column list
+    assert!(parser.parse("MERGE INTO t USING s ON t.id = s.id").is_err());
+    assert!(parser
+        .parse("MERGE INTO t USING s ON t.id = s.id WHEN NOT MATCHED THEN INSERT VALUES (s.id)")
+        .is_err());
+    assert!(parser
+        .parse("MERGE INTO t USING s ON t.id = s.id WHEN NOT MATCHED THEN INSERT (id, v) VALUES (s.id)")
+        .is_err());
+    assert!(parser
+        .parse("MERGE INTO t USING s ON t.id = s.id WHEN MATCHED THEN INSERT (id) VALUES (s.id)")
+        .is_err());
+    // SET columns belong to the target
+    assert!(parser
+        .parse("MERGE INTO t USING s ON t.id = s.id WHEN MATCHED THEN UPDATE SET s.v = 1")
+        .is_err());
+
+    // UPSERT needs a key, a single VALUES row and key columns from the column list
+    assert!(parser.parse("UPSERT INTO t VALUES (1, 2)").is_err());
+    assert!(parser
+        .parse("UPSERT INTO t (id, v) VALUES (1, 2), (3, 4)")
+        .is_err());
+    assert!(parser
+        .parse("UPSERT INTO t (id, v) KEY (other) VALUES (1, 2)")
+        .is_err());
+}
@@ -1,8 +1,12 @@
 // SQL Parser Tests
 // Tests for SQL parsing functionality
 
+pub mod alter_drop_test;
 pub mod basic_parsing_test;
 pub mod case_when_test;
 pub mod emit_mode_test;
+pub mod formatter_test;
 pub mod implicit_aggregation_test;
+pub mod merge_upsert_test;
 pub mod window_frame_test;
+pub mod diagnostics_test;
@@ -33,6 +33,7 @@ fn test_basic_over_clause_parsing() {
                             function_name,
                             args,
                             over_clause,
+                            ..
                         } => {
                             assert_eq!(function_name, "ROW_NUMBER");
                             assert_eq!(args.len(), 0);
@@ -133,7 +134,7 @@ fn test_order_by_parsing() {
                     assert!(over_clause.partition_by.is_empty());
                     assert_eq!(over_clause.order_by.len(), 1);
                     match &over_clause.order_by[0].expr {
-                        Expr::Column(col) => assert_eq!(col, "order_date"),
+                        Expr::Column(col, _) => assert_eq!(col, "order_date"),
                         _ => panic!("Expected column expression in ORDER BY"),
                     }
                     assert_eq!(over_clause.order_by[0].direction, OrderDirection::Asc);
@@ -371,7 +372,7 @@ fn test_complete_over_clause() {
                             // Verify ORDER BY
                             assert_eq!(over_clause.order_by.len(), 1);
                             match &over_clause.order_by[0].expr {
-                                Expr::Column(col) => assert_eq!(col, "order_date"),
+                                Expr::Column(col, _) => assert_eq!(col, "order_date"),
                                 _ => panic!("Expected column expression in ORDER BY"),
                             }
                             assert_eq!(over_clause.order_by[0].direction, OrderDirection::Desc);
@@ -419,14 +420,14 @@ fn test_multiple_order_by_columns() {
 
                             // First ORDER BY column
                             match &over_clause.order_by[0].expr {
-                                Expr::Column(col) => assert_eq!(col, "order_date"),
+                                Expr::Column(col, _) => assert_eq!(col, "order_date"),
                                 _ => panic!("Expected column expression in first ORDER BY"),
                             }
                             assert_eq!(over_clause.order_by[0].direction, OrderDirection::Asc);
 
                             // Second ORDER BY column
                             match &over_clause.order_by[1].expr {
-                                Expr::Column(col) => assert_eq!(col, "customer_id"),
+                                Expr::Column(col, _) => assert_eq!(col, "customer_id"),
                                 _ => panic!("Expected column expression in second ORDER BY"),
                             }
                             assert_eq!(over_clause.order_by[1].direction, OrderDirection::Desc);
@@ -470,13 +471,13 @@ fn test_window_function_with_arguments() {
 
                             // First argument should be a column
                             match &args[0] {
-                                Expr::Column(col) => assert_eq!(col, "amount"),
+                                Expr::Column(col, _) => assert_eq!(col, "amount"),
                                 _ => panic!("Expected column as first argument"),
                             }
 
                             // Second argument should be a literal 1
                             match &args[1] {
-                                Expr::Literal(LiteralValue::Integer(val)) => assert_eq!(*val, 1),
+                                Expr::Literal(LiteralValue::Integer(val), _) => assert_eq!(*val, 1),
                                 _ => panic!("Expected integer literal as second argument"),
                             }
                         }
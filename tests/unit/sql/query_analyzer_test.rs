@@ -1,4 +1,5 @@
 // Tests for QueryAnalyzer - SQL query analysis for resource requirements
+use ferrisstreams::ferris::sql::ast::NodeSpan;
 use ferrisstreams::ferris::sql::{
     ast::{
         ConfigProperties, IntoClause, SelectField, ShowResourceType, StreamSource, StreamingQuery,
@@ -72,6 +73,7 @@ fn test_select_query_analysis() {
     let query = StreamingQuery::Select {
         fields: vec![SelectField::Wildcard],
         from: StreamSource::Stream("orders_topic".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         group_by: None,
@@ -81,6 +83,7 @@ fn test_select_query_analysis() {
         limit: None,
         emit_mode: None,
         properties: Some(properties),
+        span: NodeSpan::default(),
     };
 
     let analysis = analyzer.analyze(&query).unwrap();
@@ -118,6 +121,7 @@ fn test_select_query_with_table_source() {
     let query = StreamingQuery::Select {
         fields: vec![SelectField::Wildcard],
         from: StreamSource::Table("transactions_table".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         group_by: None,
@@ -127,6 +131,7 @@ fn test_select_query_with_table_source() {
         limit: None,
         emit_mode: None,
         properties: Some(properties),
+        span: NodeSpan::default(),
     };
 
     let analysis = analyzer.analyze(&query).unwrap();
@@ -159,6 +164,7 @@ fn test_create_stream_analysis() {
     let select_query = StreamingQuery::Select {
         fields: vec![SelectField::Wildcard],
         from: StreamSource::Stream("orders".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         group_by: None,
@@ -168,6 +174,7 @@ fn test_create_stream_analysis() {
         limit: None,
         emit_mode: None,
         properties: Some(select_properties),
+        span: NodeSpan::default(),
     };
 
     let query = StreamingQuery::CreateStream {
@@ -176,6 +183,7 @@ fn test_create_stream_analysis() {
         as_select: Box::new(select_query),
         properties,
         emit_mode: None,
+        span: NodeSpan::default(),
     };
 
     let analysis = analyzer.analyze(&query).unwrap();
@@ -211,6 +219,7 @@ fn test_create_stream_into_analysis() {
     let select_query = StreamingQuery::Select {
         fields: vec![SelectField::Wildcard],
         from: StreamSource::Stream("input_stream".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         group_by: None,
@@ -220,6 +229,7 @@ fn test_create_stream_into_analysis() {
         limit: None,
         emit_mode: None,
         properties: Some(select_properties),
+        span: NodeSpan::default(),
     };
 
     let mut sink_properties = HashMap::new();
@@ -250,6 +260,7 @@ fn test_create_stream_into_analysis() {
         into_clause,
         properties: config_props,
         emit_mode: None,
+        span: NodeSpan::default(),
     };
 
     let analysis = analyzer.analyze(&query).unwrap();
@@ -272,6 +283,7 @@ fn test_show_query_analysis() {
     let query = StreamingQuery::Show {
         resource_type: ShowResourceType::Streams,
         pattern: None,
+        span: NodeSpan::default(),
     };
 
     let analysis = analyzer.analyze(&query).unwrap();
@@ -418,6 +430,7 @@ fn test_empty_query_analysis() {
     let query = StreamingQuery::Select {
         fields: vec![SelectField::Wildcard],
         from: StreamSource::Stream("empty_topic".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         group_by: None,
@@ -427,6 +440,7 @@ fn test_empty_query_analysis() {
         limit: None,
         emit_mode: None,
         properties: Some(properties),
+        span: NodeSpan::default(),
     };
 
     let analysis = analyzer.analyze(&query).unwrap();
@@ -452,6 +466,7 @@ fn test_subquery_analysis() {
     let inner_query = StreamingQuery::Select {
         fields: vec![SelectField::Wildcard],
         from: StreamSource::Stream("inner_stream".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         group_by: None,
@@ -461,11 +476,13 @@ fn test_subquery_analysis() {
         limit: None,
         emit_mode: None,
         properties: Some(inner_properties),
+        span: NodeSpan::default(),
     };
 
     let outer_query = StreamingQuery::Select {
         fields: vec![SelectField::Wildcard],
         from: StreamSource::Subquery(Box::new(inner_query)),
+        from_alias: None,
         joins: None,
         where_clause: None,
         group_by: None,
@@ -475,6 +492,7 @@ fn test_subquery_analysis() {
         limit: None,
         emit_mode: None,
         properties: None,
+        span: NodeSpan::default(),
     };
 
     let analysis = analyzer.analyze(&outer_query).unwrap();
@@ -502,6 +520,7 @@ fn test_analyzer_with_custom_group_id() {
     let query = StreamingQuery::Select {
         fields: vec![SelectField::Wildcard],
         from: StreamSource::Stream("test_topic".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         group_by: None,
@@ -511,6 +530,7 @@ fn test_analyzer_with_custom_group_id() {
         limit: None,
         emit_mode: None,
         properties: Some(properties),
+        span: NodeSpan::default(),
     };
 
     let analysis = analyzer.analyze(&query).unwrap();
@@ -545,6 +565,7 @@ fn test_file_source_inference() {
     let query = StreamingQuery::Select {
         fields: vec![SelectField::Wildcard],
         from: StreamSource::Stream("file:///data/test.csv".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         group_by: None,
@@ -554,6 +575,7 @@ fn test_file_source_inference() {
         limit: None,
         emit_mode: None,
         properties: Some(properties),
+        span: NodeSpan::default(),
     };
 
     let analysis = analyzer.analyze(&query).unwrap();
@@ -0,0 +1,248 @@
+// Tests for SemanticAnalyzer - column resolution and type checking against source schemas
+use ferrisstreams::ferris::schema::{FieldDefinition, Schema};
+use ferrisstreams::ferris::sql::{
+    ast::{DataType, StructField},
+    error::diagnostics::Span,
+    semantic_analyzer::{SchemaCatalog, SemanticAnalyzer, SemanticErrorKind},
+    SqlValidator, StreamingSqlParser,
+};
+
+fn orders_catalog() -> SchemaCatalog {
+    let mut catalog = SchemaCatalog::new();
+    catalog.register_schema(
+        "orders",
+        Schema::new(vec![
+            FieldDefinition::required("id".to_string(), DataType::BigInt),
+            FieldDefinition::required("customer".to_string(), DataType::String),
+            FieldDefinition::optional("amount".to_string(), DataType::Float),
+            FieldDefinition::optional("paid".to_string(), DataType::Boolean),
+            FieldDefinition::optional(
+                "shipping".to_string(),
+                DataType::Struct(vec![StructField {
+                    name: "city".to_string(),
+                    data_type: DataType::String,
+                    nullable: true,
+                }]),
+            ),
+        ]),
+    );
+    catalog.register_schema(
+        "customers",
+        Schema::new(vec![
+            FieldDefinition::required("name".to_string(), DataType::String),
+            FieldDefinition::optional("tier".to_string(), DataType::Integer),
+        ]),
+    );
+    catalog
+}
+
+fn analyze(
+    catalog: &SchemaCatalog,
+    sql: &str,
+) -> ferrisstreams::ferris::sql::semantic_analyzer::SemanticAnalysis {
+    let query = StreamingSqlParser::new()
+        .parse(sql)
+        .expect("query should parse");
+    SemanticAnalyzer::new(catalog).with_sql(sql).analyze(&query)
+}
+
+#[test]
+fn test_valid_query_infers_output_types() {
+    let catalog = orders_catalog();
+    let analysis = analyze(
+        &catalog,
+        "SELECT id, UPPER(customer) AS name, amount * 2 AS doubled, COUNT(*) AS n, shipping.city \
+         FROM orders WHERE paid = true AND amount > 10 GROUP BY id, customer, amount, shipping.city",
+    );
+
+    assert!(
+        analysis.is_valid(),
+        "unexpected errors: {:?}",
+        analysis.errors
+    );
+    let columns = analysis.output_columns.unwrap();
+    let types: Vec<_> = columns
+        .iter()
+        .map(|c| (c.name.as_str(), c.data_type.clone()))
+        .collect();
+    assert_eq!(
+        types,
+        vec![
+            ("id", Some(DataType::BigInt)),
+            ("name", Some(DataType::String)),
+            ("doubled", Some(DataType::Float)),
+            ("n", Some(DataType::BigInt)),
+            ("city", Some(DataType::String)),
+        ]
+    );
+}
+
+#[test]
+fn test_unknown_column_reports_position() {
+    let catalog = orders_catalog();
+    let sql = "SELECT id, amout FROM orders";
+    let analysis = analyze(&catalog, sql);
+
+    assert_eq!(analysis.errors.len(), 1);
+    let error = &analysis.errors[0];
+    assert_eq!(error.kind, SemanticErrorKind::UnknownColumn);
+    assert_eq!(error.column.as_deref(), Some("amout"));
+    let start = sql.find("amout").unwrap();
+    assert_eq!(error.span, Some(Span::new(start, start + "amout".len())));
+    assert!(error.to_string().contains("at position 11"));
+}
+
+#[test]
+fn test_errors_point_at_the_parsed_expression() {
+    let catalog = orders_catalog();
+    // `customer` is selected before the comparison that misuses it
+    let sql = "SELECT customer, id FROM orders WHERE customer > 100";
+    let analysis = analyze(&catalog, sql);
+
+    let span = analysis.errors[0].span.unwrap();
+    assert_eq!(&sql[span.start..span.end], "customer > 100");
+}
+
+#[test]
+fn test_misspelled_names_get_suggestions() {
+    let catalog = orders_catalog();
+    let sql = "SELECT id, amout, UPPR(customer) AS c, CONCAT(customer, 'x') AS d FROM orders";
+    let analysis = analyze(&catalog, sql);
+
+    assert_eq!(analysis.errors.len(), 2, "{:?}", analysis.errors);
+    let column = &analysis.errors[0];
+    assert_eq!(column.suggestion.as_deref(), Some("amount"));
+    let function = &analysis.errors[1];
+    assert_eq!(function.kind, SemanticErrorKind::UnknownFunction);
+    assert_eq!(function.message, "Unknown function 'UPPR'");
+    assert_eq!(function.suggestion.as_deref(), Some("UPPER"));
+
+    let diagnostic = function.to_diagnostic(sql);
+    let span = diagnostic.span.unwrap();
+    assert_eq!(&sql[span.start..span.end], "UPPR");
+    assert!(diagnostic
+        .render(sql, "query.sql")
+        .ends_with("= help: did you mean `UPPER`?\n"));
+
+    // Function names are not case sensitive
+    let analysis = analyze(
+        &catalog,
+        "SELECT id, upper(customer) AS c, from_unixtime(id) AS t FROM orders",
+    );
+    assert!(analysis.is_valid(), "{:?}", analysis.errors);
+}
+
+#[test]
+fn test_type_mismatches() {
+    let catalog = orders_catalog();
+
+    let comparison = analyze(&catalog, "SELECT id FROM orders WHERE customer > 100");
+    assert_eq!(comparison.errors[0].kind, SemanticErrorKind::TypeMismatch);
+
+    let arithmetic = analyze(&catalog, "SELECT customer + 1 AS x FROM orders");
+    assert_eq!(arithmetic.errors[0].kind, SemanticErrorKind::TypeMismatch);
+
+    let condition = analyze(&catalog, "SELECT id FROM orders WHERE amount");
+    assert!(condition.errors[0]
+        .message
+        .contains("WHERE condition must be BOOLEAN"));
+
+    let argument = analyze(&catalog, "SELECT SUM(customer) AS total FROM orders");
+    assert_eq!(argument.errors[0].kind, SemanticErrorKind::InvalidArgument);
+    assert_eq!(argument.errors[0].column.as_deref(), Some("customer"));
+}
+
+#[test]
+fn test_joins_aliases_and_system_columns() {
+    let catalog = orders_catalog();
+    let analysis = analyze(
+        &catalog,
+        "SELECT o.id, c.tier, _timestamp FROM orders o \
+         INNER JOIN customers c ON o.customer = c.name ORDER BY tier",
+    );
+    assert!(
+        analysis.is_valid(),
+        "unexpected errors: {:?}",
+        analysis.errors
+    );
+
+    let unknown = analyze(
+        &catalog,
+        "SELECT o.id FROM orders o INNER JOIN customers c ON o.customer = c.nme",
+    );
+    assert_eq!(unknown.errors[0].kind, SemanticErrorKind::UnknownColumn);
+
+    let having = analyze(
+        &catalog,
+        "SELECT customer, SUM(amount) AS total FROM orders GROUP BY customer HAVING total > 100",
+    );
+    assert!(having.is_valid(), "unexpected errors: {:?}", having.errors);
+}
+
+#[test]
+fn test_unknown_sources_are_not_checked() {
+    let catalog = SchemaCatalog::new();
+    let analysis = analyze(&catalog, "SELECT anything, SUM(other) AS s FROM events");
+    assert!(analysis.is_valid());
+    assert!(analysis.output_columns.unwrap()[0].data_type.is_none());
+
+    // Literals are still type checked
+    let literal = analyze(&catalog, "SELECT id FROM events WHERE 'a' = 1");
+    assert_eq!(literal.errors[0].kind, SemanticErrorKind::TypeMismatch);
+}
+
+#[test]
+fn test_ddl_feeds_the_catalog() {
+    let parser = StreamingSqlParser::new();
+    let mut catalog = SchemaCatalog::new();
+
+    let create =
+        "CREATE STREAM payments (id BIGINT, amount DECIMAL(10, 2)) AS SELECT id, amount FROM raw_payments";
+    let query = parser.parse(create).unwrap();
+    let analysis = SemanticAnalyzer::new(&catalog).analyze(&query);
+    assert!(
+        analysis.is_valid(),
+        "unexpected errors: {:?}",
+        analysis.errors
+    );
+    catalog.apply_statement(&query, &analysis);
+    assert!(catalog.contains("payments"));
+
+    let select = parser
+        .parse("SELECT id, amount, fee FROM payments")
+        .unwrap();
+    let analysis = SemanticAnalyzer::new(&catalog).analyze(&select);
+    assert_eq!(analysis.errors.len(), 1);
+    assert_eq!(analysis.errors[0].column.as_deref(), Some("fee"));
+
+    // Declared columns describe the records read from the source
+    let mismatch = parser
+        .parse("CREATE STREAM p2 (id BIGINT) AS SELECT id, amount FROM raw_payments")
+        .unwrap();
+    let analysis = SemanticAnalyzer::new(&catalog).analyze(&mismatch);
+    assert_eq!(analysis.errors[0].column.as_deref(), Some("amount"));
+}
+
+#[test]
+fn test_sql_validator_reports_semantic_errors() {
+    let content =
+        "CREATE STREAM trades (symbol STRING, price DOUBLE) AS SELECT * FROM trades_topic;\n\
+                   SELECT symbol, UPPER(price) AS p FROM trades;";
+    let result = SqlValidator::new().validate_sql_content(content);
+
+    assert!(!result.is_valid);
+    assert!(result.query_results[0].semantic_errors.is_empty());
+    let errors = &result.query_results[1].semantic_errors;
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0]
+        .message
+        .contains("UPPER requires a string argument"));
+    assert_eq!(errors[0].line, Some(2));
+
+    let result = SqlValidator::new()
+        .with_schema_catalog(orders_catalog())
+        .validate_sql_content("SELECT id, amout FROM orders;");
+    assert!(result.query_results[0].semantic_errors[0]
+        .message
+        .contains("Unknown column 'amout'"));
+}
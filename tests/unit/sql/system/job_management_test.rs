@@ -22,6 +22,7 @@ mod tests {
                 name,
                 query,
                 properties,
+                ..
             } => {
                 assert_eq!(name, "order_monitor");
                 assert!(properties.is_empty());
@@ -53,6 +54,7 @@ mod tests {
                 name,
                 query: _,
                 properties,
+                ..
             } => {
                 assert_eq!(name, "order_monitor");
                 assert_eq!(properties.len(), 2);
@@ -80,6 +82,7 @@ mod tests {
                 name,
                 query,
                 properties,
+                ..
             } => {
                 assert_eq!(name, "stream_creator");
                 assert!(properties.is_empty());
@@ -107,7 +110,7 @@ mod tests {
         );
 
         match result.unwrap() {
-            StreamingQuery::StopJob { name, force } => {
+            StreamingQuery::StopJob { name, force, .. } => {
                 assert_eq!(name, "order_monitor");
                 assert!(!force);
             }
@@ -128,7 +131,7 @@ mod tests {
         );
 
         match result.unwrap() {
-            StreamingQuery::StopJob { name, force } => {
+            StreamingQuery::StopJob { name, force, .. } => {
                 assert_eq!(name, "order_monitor");
                 assert!(force);
             }
@@ -136,6 +139,45 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_savepoint_syntax() {
+        let parser = StreamingSqlParser::new();
+
+        match parser
+            .parse("STOP JOB order_monitor FORCE WITH SAVEPOINT")
+            .unwrap()
+        {
+            StreamingQuery::StopJob {
+                name,
+                force,
+                savepoint,
+                ..
+            } => {
+                assert_eq!(name, "order_monitor");
+                assert!(force);
+                assert!(savepoint);
+            }
+            _ => panic!("Expected StopJob"),
+        }
+
+        match parser
+            .parse("START JOB order_monitor FROM SAVEPOINT '/savepoints/sp-1.svpt' AS SELECT * FROM orders")
+            .unwrap()
+        {
+            StreamingQuery::StartJob {
+                name, savepoint, ..
+            } => {
+                assert_eq!(name, "order_monitor");
+                assert_eq!(savepoint.as_deref(), Some("/savepoints/sp-1.svpt"));
+            }
+            _ => panic!("Expected StartJob"),
+        }
+
+        assert!(parser
+            .parse("START JOB order_monitor FROM SAVEPOINT AS SELECT * FROM orders")
+            .is_err());
+    }
+
     #[test]
     fn test_start_job_complex_select() {
         let parser = StreamingSqlParser::new();
@@ -153,6 +195,7 @@ mod tests {
                 name,
                 query,
                 properties: _,
+                ..
             } => {
                 assert_eq!(name, "complex_analytics");
 
@@ -198,6 +241,7 @@ mod tests {
                 name,
                 query,
                 properties: _,
+                ..
             } => {
                 assert_eq!(name, "windowed_aggregation");
 
@@ -21,6 +21,7 @@ mod tests {
             StreamingQuery::Show {
                 resource_type,
                 pattern,
+                ..
             } => {
                 assert_eq!(resource_type, ShowResourceType::Streams);
                 assert!(pattern.is_none());
@@ -45,6 +46,7 @@ mod tests {
             StreamingQuery::Show {
                 resource_type,
                 pattern,
+                ..
             } => {
                 assert_eq!(resource_type, ShowResourceType::Streams);
                 assert!(pattern.is_none());
@@ -65,6 +67,7 @@ mod tests {
             StreamingQuery::Show {
                 resource_type,
                 pattern,
+                ..
             } => {
                 assert_eq!(resource_type, ShowResourceType::Tables);
                 assert!(pattern.is_none());
@@ -85,6 +88,7 @@ mod tests {
             StreamingQuery::Show {
                 resource_type,
                 pattern,
+                ..
             } => {
                 assert_eq!(resource_type, ShowResourceType::Topics);
                 assert!(pattern.is_none());
@@ -105,6 +109,7 @@ mod tests {
             StreamingQuery::Show {
                 resource_type,
                 pattern,
+                ..
             } => {
                 assert_eq!(resource_type, ShowResourceType::Functions);
                 assert!(pattern.is_none());
@@ -125,6 +130,7 @@ mod tests {
             StreamingQuery::Show {
                 resource_type,
                 pattern,
+                ..
             } => {
                 assert_eq!(resource_type, ShowResourceType::Jobs);
                 assert!(pattern.is_none());
@@ -145,6 +151,7 @@ mod tests {
             StreamingQuery::Show {
                 resource_type,
                 pattern,
+                ..
             } => {
                 assert_eq!(
                     resource_type,
@@ -174,6 +181,7 @@ mod tests {
             StreamingQuery::Show {
                 resource_type,
                 pattern,
+                ..
             } => {
                 assert_eq!(
                     resource_type,
@@ -200,6 +208,7 @@ mod tests {
             StreamingQuery::Show {
                 resource_type,
                 pattern,
+                ..
             } => {
                 assert_eq!(
                     resource_type,
@@ -229,6 +238,7 @@ mod tests {
             StreamingQuery::Show {
                 resource_type,
                 pattern,
+                ..
             } => {
                 assert_eq!(resource_type, ShowResourceType::Streams);
                 assert_eq!(pattern, Some("order%".to_string()));
@@ -252,6 +262,7 @@ mod tests {
             StreamingQuery::Show {
                 resource_type,
                 pattern,
+                ..
             } => {
                 assert_eq!(resource_type, ShowResourceType::Tables);
                 assert_eq!(pattern, Some("user_*".to_string()));
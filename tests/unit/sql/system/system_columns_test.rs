@@ -12,7 +12,7 @@ fn create_test_record(id: i64, amount: f64) -> StreamRecord {
     fields.insert("amount".to_string(), FieldValue::Float(amount));
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         timestamp: 1609459200000, // Fixed timestamp for test consistency
         offset: id,
         partition: 0,
@@ -61,7 +61,7 @@ mod tests {
                 match &fields[0] {
                     SelectField::Expression { expr, alias } => {
                         match expr {
-                            Expr::Column(name) => assert_eq!(name, "_timestamp"),
+                            Expr::Column(name, _) => assert_eq!(name, "_timestamp"),
                             _ => panic!("Expected column expression"),
                         }
                         assert_eq!(alias.as_ref().unwrap(), "ts");
@@ -90,7 +90,7 @@ mod tests {
         fields.insert("customer_id".to_string(), FieldValue::Integer(123));
         fields.insert("amount".to_string(), FieldValue::Float(299.99));
         let record = StreamRecord {
-            fields,
+            fields: fields.into(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             offset: 0,
             partition: 0,
@@ -139,7 +139,7 @@ mod tests {
         let mut fields = HashMap::new();
         fields.insert("customer_id".to_string(), FieldValue::Integer(456));
         let record = StreamRecord {
-            fields,
+            fields: fields.into(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             offset: 0,
             partition: 0,
@@ -174,7 +174,7 @@ mod tests {
         let mut fields = HashMap::new();
         fields.insert("customer_id".to_string(), FieldValue::Integer(789));
         let record = StreamRecord {
-            fields,
+            fields: fields.into(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             offset: 0,
             partition: 0,
@@ -211,7 +211,7 @@ mod tests {
             FieldValue::String("active".to_string()),
         );
         let record = StreamRecord {
-            fields,
+            fields: fields.into(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             offset: 0,
             partition: 0,
@@ -248,7 +248,7 @@ mod tests {
         fields.insert("customer_id".to_string(), FieldValue::Integer(202));
         fields.insert("amount".to_string(), FieldValue::Float(149.99));
         let record = StreamRecord {
-            fields,
+            fields: fields.into(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             offset: 0,
             partition: 0,
@@ -289,7 +289,7 @@ mod tests {
             let mut fields = HashMap::new();
             fields.insert("test_id".to_string(), FieldValue::Integer(i as i64));
             let record = StreamRecord {
-                fields,
+                fields: fields.into(),
                 timestamp: chrono::Utc::now().timestamp_millis(),
                 offset: 0,
                 partition: 0,
@@ -329,7 +329,7 @@ mod tests {
         fields.insert("customer_id".to_string(), FieldValue::Integer(303));
         fields.insert("amount".to_string(), FieldValue::Float(75.50));
         let record = StreamRecord {
-            fields,
+            fields: fields.into(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             offset: 0,
             partition: 0,
@@ -396,7 +396,7 @@ mod tests {
             match result.unwrap() {
                 StreamingQuery::Select { fields, .. } => match &fields[0] {
                     SelectField::Expression { expr, .. } => match expr {
-                        Expr::Column(name) => assert_eq!(name, col),
+                        Expr::Column(name, _) => assert_eq!(name, col),
                         _ => panic!("System column should be parsed as column expression"),
                     },
                     _ => panic!("Expected expression field for {}", col),
@@ -44,7 +44,7 @@ fn create_test_record_with_advanced_types() -> StreamRecord {
     fields.insert("person".to_string(), FieldValue::Struct(struct_data));
 
     StreamRecord {
-        fields,
+        fields: fields.into(),
         headers: HashMap::new(),
         timestamp: 1234567890000,
         offset: 1,
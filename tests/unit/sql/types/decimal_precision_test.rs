@@ -0,0 +1,317 @@
+/*!
+# Tests for DECIMAL(p, s)
+
+Tests for declared precision and scale: DDL parsing, CAST coercion with rounding modes,
+overflow detection, exact comparison across scales and the SQL-standard result scales of
+ScaledInteger arithmetic.
+*/
+
+use ferrisstreams::ferris::sql::ast::{DataType, DecimalSpec, StreamingQuery};
+use ferrisstreams::ferris::sql::execution::decimal::RoundingMode;
+use ferrisstreams::ferris::sql::execution::expression::ExpressionEvaluator;
+use ferrisstreams::ferris::sql::execution::{FieldValue, StreamExecutionEngine, StreamRecord};
+use ferrisstreams::ferris::sql::parser::StreamingSqlParser;
+use std::collections::HashMap;
+use tokio::sync::mpsc;
+
+fn create_settlement_record() -> StreamRecord {
+    let mut fields = HashMap::new();
+    // 1234.56785 at scale 5
+    fields.insert(
+        "amount".to_string(),
+        FieldValue::ScaledInteger(123456785, 5),
+    );
+    fields.insert("quantity".to_string(), FieldValue::Integer(3));
+
+    StreamRecord {
+        fields: fields.into(),
+        headers: HashMap::new(),
+        timestamp: 1710513045000,
+        offset: 1,
+        partition: 0,
+    }
+}
+
+async fn execute_query(query: &str) -> Result<Vec<StreamRecord>, Box<dyn std::error::Error>> {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut engine = StreamExecutionEngine::new(tx);
+    let parser = StreamingSqlParser::new();
+
+    let parsed_query = parser.parse(query)?;
+    engine
+        .execute_with_record(&parsed_query, create_settlement_record())
+        .await?;
+
+    let mut results = Vec::new();
+    while let Ok(result) = rx.try_recv() {
+        results.push(result);
+    }
+    Ok(results)
+}
+
+fn spec(precision: u32, scale: u32) -> DecimalSpec {
+    DecimalSpec::new(precision, scale).unwrap()
+}
+
+#[test]
+fn test_decimal_precision_and_scale_ddl() {
+    let parser = StreamingSqlParser::new();
+    let query = parser
+        .parse(
+            "CREATE TABLE settlements (
+                amount DECIMAL(18, 4),
+                rate NUMERIC(10),
+                legacy DECIMAL
+            ) AS SELECT * FROM trades",
+        )
+        .expect("DECIMAL(p, s) should parse");
+    let StreamingQuery::CreateTable {
+        columns: Some(columns),
+        ..
+    } = query
+    else {
+        panic!("Expected CreateTable with columns, got: {:?}", query);
+    };
+
+    let types: Vec<DataType> = columns.into_iter().map(|c| c.data_type).collect();
+    assert_eq!(
+        types,
+        vec![
+            DataType::Decimal(Some(spec(18, 4))),
+            DataType::Decimal(Some(spec(10, 0))),
+            DataType::Decimal(None),
+        ]
+    );
+
+    // Precision is bounded by the 64-bit storage and the scale by the precision
+    assert!(parser
+        .parse("CREATE TABLE t (amount DECIMAL(19, 2)) AS SELECT * FROM trades")
+        .is_err());
+    assert!(parser
+        .parse("CREATE TABLE t (amount DECIMAL(4, 5)) AS SELECT * FROM trades")
+        .is_err());
+}
+
+#[test]
+fn test_cast_to_declared_decimal() {
+    assert_eq!(
+        FieldValue::String("12.345".to_string())
+            .cast_to("DECIMAL(10,2)")
+            .unwrap(),
+        FieldValue::ScaledInteger(1235, 2)
+    );
+    assert_eq!(
+        FieldValue::Integer(7).cast_to("NUMERIC(5, 3)").unwrap(),
+        FieldValue::ScaledInteger(7000, 3)
+    );
+    assert_eq!(
+        FieldValue::Float(-0.125).cast_to("DECIMAL(4,2)").unwrap(),
+        FieldValue::ScaledInteger(-13, 2)
+    );
+    assert_eq!(
+        FieldValue::Null.cast_to("DECIMAL(4,2)").unwrap(),
+        FieldValue::Null
+    );
+
+    // Too many integer digits for the declared precision
+    assert!(FieldValue::String("1234.5".to_string())
+        .cast_to("DECIMAL(5,2)")
+        .is_err());
+    // Rounding can push a value over the limit too
+    assert!(FieldValue::String("999.995".to_string())
+        .cast_to("DECIMAL(5,2)")
+        .is_err());
+    assert!(FieldValue::Integer(1).cast_to("DECIMAL(20,2)").is_err());
+
+    // Bare DECIMAL keeps its existing behavior
+    assert!(matches!(
+        FieldValue::Integer(1).cast_to("DECIMAL").unwrap(),
+        FieldValue::Decimal(_)
+    ));
+}
+
+#[test]
+fn test_rounding_modes() {
+    let round = |value: &str, mode: &str| match spec(5, 0)
+        .coerce(
+            FieldValue::String(value.to_string()),
+            RoundingMode::parse(mode).unwrap(),
+        )
+        .unwrap()
+    {
+        FieldValue::ScaledInteger(v, 0) => v,
+        other => panic!("Expected ScaledInteger, got {:?}", other),
+    };
+
+    let cases = [
+        // value, HALF_UP, HALF_DOWN, HALF_EVEN, UP, DOWN, CEILING, FLOOR
+        ("2.5", [3, 2, 2, 3, 2, 3, 2]),
+        ("3.5", [4, 3, 4, 4, 3, 4, 3]),
+        ("-2.5", [-3, -2, -2, -3, -2, -2, -3]),
+        ("2.4", [2, 2, 2, 3, 2, 3, 2]),
+        ("-2.6", [-3, -3, -3, -3, -2, -2, -3]),
+    ];
+    let modes = [
+        "HALF_UP",
+        "half_down",
+        "HALF_EVEN",
+        "UP",
+        "DOWN",
+        "CEILING",
+        "FLOOR",
+    ];
+    for (value, expected) in cases {
+        for (mode, want) in modes.iter().zip(expected) {
+            assert_eq!(round(value, mode), want, "{} rounded {}", value, mode);
+        }
+    }
+
+    assert!(RoundingMode::parse("SIDEWAYS").is_err());
+    assert_eq!(RoundingMode::default(), RoundingMode::HalfUp);
+}
+
+#[test]
+fn test_decimal_arithmetic_result_scales() {
+    let a = FieldValue::ScaledInteger(150, 2); // 1.50
+    let b = FieldValue::ScaledInteger(2250, 3); // 2.250
+
+    // add/subtract: max(s1, s2)
+    assert_eq!(a.add(&b).unwrap(), FieldValue::ScaledInteger(3750, 3));
+    assert_eq!(a.subtract(&b).unwrap(), FieldValue::ScaledInteger(-750, 3));
+
+    // multiply: s1 + s2
+    assert_eq!(
+        a.multiply(&b).unwrap(),
+        FieldValue::ScaledInteger(337500, 5)
+    );
+
+    // divide: max(s1, s2, 6), rounded HALF_UP
+    let one = FieldValue::ScaledInteger(100, 2);
+    let three = FieldValue::ScaledInteger(300, 2);
+    assert_eq!(
+        one.divide(&three).unwrap(),
+        FieldValue::ScaledInteger(333333, 6)
+    );
+    assert_eq!(
+        FieldValue::ScaledInteger(200, 2)
+            .divide(&FieldValue::ScaledInteger(300, 2))
+            .unwrap(),
+        FieldValue::ScaledInteger(666667, 6)
+    );
+    assert_eq!(
+        FieldValue::ScaledInteger(1000, 2)
+            .divide(&FieldValue::Integer(4))
+            .unwrap(),
+        FieldValue::ScaledInteger(2500000, 6)
+    );
+    assert_eq!(
+        FieldValue::ScaledInteger(1000, 2)
+            .divide(&FieldValue::Float(2.0))
+            .unwrap(),
+        FieldValue::ScaledInteger(5000000, 6)
+    );
+    assert!(one.divide(&FieldValue::ScaledInteger(0, 2)).is_err());
+}
+
+#[test]
+fn test_decimal_arithmetic_overflow() {
+    let big = FieldValue::ScaledInteger(i64::MAX - 1, 2);
+    assert!(big.add(&FieldValue::ScaledInteger(100, 2)).is_err());
+    assert!(big.subtract(&FieldValue::ScaledInteger(-100, 2)).is_err());
+    assert!(big.multiply(&FieldValue::Integer(2)).is_err());
+
+    // Products that only overflow in their fractional digits drop scale instead
+    let price = FieldValue::ScaledInteger(12_345_678_901, 6); // 12345.678901
+    assert_eq!(
+        price.multiply(&price).unwrap(),
+        FieldValue::ScaledInteger(1_524_157_875_265_965_678, 10)
+    );
+    // ...but never below max(s1, s2)
+    assert!(FieldValue::ScaledInteger(4_000_000_000_000_000, 6)
+        .multiply(&FieldValue::ScaledInteger(4_000_000_000_000_000, 6))
+        .is_err());
+}
+
+#[test]
+fn test_scaled_decimals_compare_exactly() {
+    let mut record = create_settlement_record();
+    // 1234.5679 at scale 4 and 1234.56785 at scale 7
+    record.fields.insert(
+        "ceiling".to_string(),
+        FieldValue::ScaledInteger(12345679, 4),
+    );
+    record.fields.insert(
+        "booked".to_string(),
+        FieldValue::ScaledInteger(12345678500, 7),
+    );
+
+    let parser = StreamingSqlParser::new();
+    let evaluate = |condition: &str| {
+        let sql = format!("SELECT * FROM settlements WHERE {}", condition);
+        match parser.parse(&sql).unwrap() {
+            StreamingQuery::Select {
+                where_clause: Some(expr),
+                ..
+            } => ExpressionEvaluator::evaluate_expression(&expr, &record).unwrap(),
+            other => panic!("Expected SELECT with WHERE, got {:?}", other),
+        }
+    };
+
+    // Scales are aligned before comparing, so nearby values keep their order
+    assert!(evaluate("amount < ceiling"));
+    assert!(evaluate("amount >= booked AND amount <= booked"));
+    // Against integers and floats
+    assert!(evaluate("amount > 1234 AND amount < 1235"));
+    assert!(evaluate("amount > 1234.5"));
+}
+
+#[tokio::test]
+async fn test_cast_and_round_with_rounding_mode_in_queries() {
+    let results = execute_query(
+        "SELECT CAST(amount, 'DECIMAL(18,4)') as half_up,
+                CAST(amount, 'DECIMAL(18,4)', 'HALF_EVEN') as half_even,
+                CAST(amount, 'DECIMAL(18,4)', 'DOWN') as truncated,
+                ROUND(amount, 2) as rounded,
+                ROUND(amount * quantity, 3, 'FLOOR') as total
+         FROM settlements",
+    )
+    .await
+    .unwrap();
+    assert_eq!(results.len(), 1);
+    let fields = &results[0].fields;
+
+    assert_eq!(
+        fields.get("half_up"),
+        Some(&FieldValue::ScaledInteger(12345679, 4))
+    );
+    assert_eq!(
+        fields.get("half_even"),
+        Some(&FieldValue::ScaledInteger(12345678, 4))
+    );
+    assert_eq!(
+        fields.get("truncated"),
+        Some(&FieldValue::ScaledInteger(12345678, 4))
+    );
+    assert_eq!(
+        fields.get("rounded"),
+        Some(&FieldValue::ScaledInteger(123457, 2))
+    );
+    // 3703.70355 floored to 3 digits
+    assert_eq!(
+        fields.get("total"),
+        Some(&FieldValue::ScaledInteger(3703703, 3))
+    );
+}
+
+#[tokio::test]
+async fn test_decimal_cast_errors_in_queries() {
+    let result = execute_query("SELECT CAST(amount, 'DECIMAL(5,2)') as a FROM settlements").await;
+    assert!(result.is_err(), "1234.57 does not fit in DECIMAL(5,2)");
+
+    let result =
+        execute_query("SELECT CAST(amount, 'FLOAT', 'HALF_EVEN') as a FROM settlements").await;
+    assert!(
+        result.is_err(),
+        "rounding modes only apply to DECIMAL(p, s)"
+    );
+}
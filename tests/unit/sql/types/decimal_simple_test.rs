@@ -36,8 +36,8 @@ mod decimal_simple_tests {
                     if let Some(cols) = columns {
                         assert_eq!(cols.len(), 3);
                         assert_eq!(cols[0].data_type, DataType::Integer);
-                        assert_eq!(cols[1].data_type, DataType::Decimal);
-                        assert_eq!(cols[2].data_type, DataType::Decimal);
+                        assert_eq!(cols[1].data_type, DataType::Decimal(None));
+                        assert_eq!(cols[2].data_type, DataType::Decimal(None));
                     } else {
                         panic!("Expected columns in CreateTable");
                     }
@@ -66,7 +66,7 @@ mod decimal_simple_tests {
                         match first_field {
                             SelectField::Expression { expr, .. } => {
                                 match expr {
-                                    Expr::Literal(LiteralValue::Float(value)) => {
+                                    Expr::Literal(LiteralValue::Float(value), _) => {
                                         assert!((value - 123.45).abs() < f64::EPSILON);
                                     }
                                     _ => panic!("Expected float literal (decimal numbers parse as Float), got: {:?}", expr),
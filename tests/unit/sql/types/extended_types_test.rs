@@ -0,0 +1,231 @@
+/*!
+# Tests for Extended Scalar Types
+
+Tests for BYTES, UUID, DATE, TIME and the SMALLINT/INT/BIGINT/REAL distinctions: DDL parsing,
+CAST range checks and conversions, comparisons and byte-aware hashing.
+*/
+
+use ferrisstreams::ferris::sql::ast::{DataType, StreamingQuery};
+use ferrisstreams::ferris::sql::execution::{FieldValue, StreamExecutionEngine, StreamRecord};
+use ferrisstreams::ferris::sql::parser::StreamingSqlParser;
+use std::collections::HashMap;
+use tokio::sync::mpsc;
+
+const DEVICE_ID: &str = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+
+fn create_telemetry_record() -> StreamRecord {
+    let mut fields = HashMap::new();
+    fields.insert(
+        "device_id".to_string(),
+        FieldValue::Uuid(uuid::Uuid::parse_str(DEVICE_ID).unwrap()),
+    );
+    fields.insert(
+        "payload".to_string(),
+        FieldValue::Bytes(vec![0xde, 0xad, 0xbe, 0xef]),
+    );
+    fields.insert(
+        "event_date".to_string(),
+        FieldValue::Date(chrono::NaiveDate::from_ymd_opt(2024, 3, 15).unwrap()),
+    );
+    fields.insert(
+        "reading_time".to_string(),
+        FieldValue::String("14:30:45.250".to_string()),
+    );
+    fields.insert("reading".to_string(), FieldValue::Integer(40000));
+
+    StreamRecord {
+        fields: fields.into(),
+        headers: HashMap::new(),
+        timestamp: 1710513045000,
+        offset: 1,
+        partition: 0,
+    }
+}
+
+async fn execute_query(query: &str) -> Result<Vec<StreamRecord>, Box<dyn std::error::Error>> {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut engine = StreamExecutionEngine::new(tx);
+    let parser = StreamingSqlParser::new();
+
+    let parsed_query = parser.parse(query)?;
+    engine
+        .execute_with_record(&parsed_query, create_telemetry_record())
+        .await?;
+
+    let mut results = Vec::new();
+    while let Ok(result) = rx.try_recv() {
+        results.push(result);
+    }
+    Ok(results)
+}
+
+#[test]
+fn test_extended_type_ddl_parsing() {
+    let parser = StreamingSqlParser::new();
+    let sql = "CREATE TABLE telemetry (
+        flags SMALLINT,
+        reading INT,
+        total BIGINT,
+        ratio REAL,
+        score DOUBLE,
+        payload VARBINARY,
+        device_id UUID,
+        event_date DATE,
+        reading_time TIME
+    ) AS SELECT * FROM readings";
+
+    let query = parser
+        .parse(sql)
+        .expect("DDL with extended types should parse");
+    let StreamingQuery::CreateTable {
+        columns: Some(columns),
+        ..
+    } = query
+    else {
+        panic!("Expected CreateTable with columns, got: {:?}", query);
+    };
+
+    let types: Vec<DataType> = columns.into_iter().map(|c| c.data_type).collect();
+    assert_eq!(
+        types,
+        vec![
+            DataType::SmallInt,
+            DataType::Integer,
+            DataType::BigInt,
+            DataType::Real,
+            DataType::Float,
+            DataType::Bytes,
+            DataType::Uuid,
+            DataType::Date,
+            DataType::Time,
+        ]
+    );
+}
+
+#[test]
+fn test_sized_integer_casts_enforce_range() {
+    assert_eq!(
+        FieldValue::Integer(32767).cast_to("SMALLINT").unwrap(),
+        FieldValue::Integer(32767)
+    );
+    assert!(FieldValue::Integer(40000).cast_to("SMALLINT").is_err());
+    assert!(FieldValue::Float(1e9).cast_to("SMALLINT").is_err());
+    // INTEGER stays 64-bit, like BIGINT
+    for target in ["INT", "BIGINT"] {
+        assert_eq!(
+            FieldValue::Integer(3_000_000_000).cast_to(target).unwrap(),
+            FieldValue::Integer(3_000_000_000)
+        );
+    }
+    assert_eq!(
+        FieldValue::String("-12".to_string())
+            .cast_to("SMALLINT")
+            .unwrap(),
+        FieldValue::Integer(-12)
+    );
+
+    // REAL keeps single precision
+    match FieldValue::Float(0.1).cast_to("REAL").unwrap() {
+        FieldValue::Float(f) => assert_eq!(f, 0.1_f32 as f64),
+        other => panic!("Expected Float, got {:?}", other),
+    }
+    assert!(FieldValue::Float(1e300).cast_to("REAL").is_err());
+}
+
+#[test]
+fn test_bytes_uuid_and_time_casts() {
+    let uuid = uuid::Uuid::parse_str(DEVICE_ID).unwrap();
+
+    assert_eq!(
+        FieldValue::String(DEVICE_ID.to_string())
+            .cast_to("UUID")
+            .unwrap(),
+        FieldValue::Uuid(uuid)
+    );
+    assert!(FieldValue::String("not-a-uuid".to_string())
+        .cast_to("UUID")
+        .is_err());
+
+    // UUID <-> 16 bytes round trip
+    let bytes = FieldValue::Uuid(uuid).cast_to("BYTES").unwrap();
+    assert_eq!(bytes, FieldValue::Bytes(uuid.as_bytes().to_vec()));
+    assert_eq!(bytes.cast_to("UUID").unwrap(), FieldValue::Uuid(uuid));
+
+    // BYTES -> STRING requires UTF-8
+    assert_eq!(
+        FieldValue::String("héllo".to_string())
+            .cast_to("VARBINARY")
+            .unwrap()
+            .cast_to("STRING")
+            .unwrap(),
+        FieldValue::String("héllo".to_string())
+    );
+    assert!(FieldValue::Bytes(vec![0xff, 0xfe])
+        .cast_to("STRING")
+        .is_err());
+
+    let time = FieldValue::String("08:15".to_string())
+        .cast_to("TIME")
+        .unwrap();
+    assert_eq!(
+        time,
+        FieldValue::Time(chrono::NaiveTime::from_hms_opt(8, 15, 0).unwrap())
+    );
+    assert_eq!(time.to_display_string(), "08:15:00.000");
+}
+
+#[tokio::test]
+async fn test_uuid_and_date_comparisons_in_where() {
+    let query = format!(
+        "SELECT device_id FROM telemetry WHERE device_id = '{}' AND event_date >= CAST('2024-01-01', 'DATE')",
+        DEVICE_ID
+    );
+    let results = execute_query(&query).await.unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(
+        results[0].fields.get("device_id"),
+        Some(&FieldValue::Uuid(uuid::Uuid::parse_str(DEVICE_ID).unwrap()))
+    );
+
+    let results = execute_query(
+        "SELECT device_id FROM telemetry WHERE event_date < CAST('2024-01-01', 'DATE')",
+    )
+    .await
+    .unwrap();
+    assert!(results.is_empty());
+}
+
+#[tokio::test]
+async fn test_time_casts_and_byte_hashing_in_queries() {
+    let results = execute_query(
+        "SELECT CAST(reading_time, 'TIME') as t, MD5(payload) as digest, BASE64_ENCODE(payload) as encoded FROM telemetry",
+    )
+    .await
+    .unwrap();
+    assert_eq!(results.len(), 1);
+    let fields = &results[0].fields;
+
+    assert_eq!(
+        fields.get("t"),
+        Some(&FieldValue::Time(
+            chrono::NaiveTime::from_hms_milli_opt(14, 30, 45, 250).unwrap()
+        ))
+    );
+    // Hashes and encodings operate on the raw bytes, not on a text rendering
+    assert_eq!(
+        fields.get("digest"),
+        Some(&FieldValue::String(
+            "2f249230a8e7c2bf6005ccd2679259ec".to_string()
+        ))
+    );
+    assert_eq!(
+        fields.get("encoded"),
+        Some(&FieldValue::String("3q2+7w==".to_string()))
+    );
+}
+
+#[tokio::test]
+async fn test_out_of_range_cast_in_query_fails() {
+    let result = execute_query("SELECT CAST(reading, 'SMALLINT') as r FROM telemetry").await;
+    assert!(result.is_err(), "40000 does not fit in SMALLINT");
+}
@@ -50,7 +50,7 @@ mod tests {
         fields.insert("customer_id".to_string(), FieldValue::Integer(123));
         fields.insert("amount".to_string(), FieldValue::Float(299.99));
         let record = StreamRecord {
-            fields,
+            fields: fields.into(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             offset: 0,
             partition: 0,
@@ -100,7 +100,7 @@ mod tests {
         let mut fields = HashMap::new();
         fields.insert("customer_id".to_string(), FieldValue::Integer(123));
         let record = StreamRecord {
-            fields,
+            fields: fields.into(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             offset: 0,
             partition: 0,
@@ -146,7 +146,7 @@ mod tests {
         let mut fields = HashMap::new();
         fields.insert("customer_id".to_string(), FieldValue::Integer(123));
         let record = StreamRecord {
-            fields,
+            fields: fields.into(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             offset: 0,
             partition: 0,
@@ -193,7 +193,7 @@ mod tests {
         let mut fields = HashMap::new();
         fields.insert("customer_id".to_string(), FieldValue::Integer(123));
         let record = StreamRecord {
-            fields,
+            fields: fields.into(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             offset: 0,
             partition: 0,
@@ -242,7 +242,7 @@ mod tests {
         let mut fields = HashMap::new();
         fields.insert("customer_id".to_string(), FieldValue::Integer(123));
         let record = StreamRecord {
-            fields,
+            fields: fields.into(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             offset: 0,
             partition: 0,
@@ -266,7 +266,7 @@ mod tests {
         let mut fields2 = HashMap::new();
         fields2.insert("customer_id".to_string(), FieldValue::Integer(123));
         let record2 = StreamRecord {
-            fields: fields2,
+            fields: fields2.into(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             offset: 0,
             partition: 0,
@@ -301,7 +301,7 @@ mod tests {
         fields.insert("customer_id".to_string(), FieldValue::Integer(456));
         fields.insert("amount".to_string(), FieldValue::Float(199.99));
         let record = StreamRecord {
-            fields,
+            fields: fields.into(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             offset: 0,
             partition: 0,
@@ -358,7 +358,7 @@ mod tests {
         fields.insert("customer_id".to_string(), FieldValue::Integer(789));
         fields.insert("amount".to_string(), FieldValue::Float(99.99));
         let record = StreamRecord {
-            fields,
+            fields: fields.into(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             offset: 0,
             partition: 0,
@@ -452,7 +452,7 @@ mod tests {
         let mut fields = HashMap::new();
         fields.insert("customer_id".to_string(), FieldValue::Integer(123));
         let record = StreamRecord {
-            fields,
+            fields: fields.into(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             offset: 0,
             partition: 0,
@@ -498,7 +498,7 @@ mod tests {
         let mut fields = HashMap::new();
         fields.insert("customer_id".to_string(), FieldValue::Integer(123));
         let record = StreamRecord {
-            fields,
+            fields: fields.into(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             offset: 0,
             partition: 0,
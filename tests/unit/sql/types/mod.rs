@@ -2,5 +2,7 @@
 // Tests for SQL data types and type handling
 
 pub mod advanced_types_test;
+pub mod decimal_precision_test;
 pub mod decimal_simple_test;
+pub mod extended_types_test;
 pub mod headers_test;
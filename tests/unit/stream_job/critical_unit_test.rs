@@ -6,8 +6,15 @@
 //! 3. Duplicate job name rejection
 //! 4. Input validation and error handling
 
+use ferrisstreams::ferris::schema::{FieldDefinition, Schema};
 use ferrisstreams::ferris::server::{JobStatus, StreamJobServer};
-use ferrisstreams::ferris::sql::SqlApplicationParser;
+use ferrisstreams::ferris::sql::ast::DataType;
+use ferrisstreams::ferris::sql::execution::processors::job::JobProcessor;
+use ferrisstreams::ferris::sql::execution::processors::{ProcessorContext, QueryProcessor};
+use ferrisstreams::ferris::sql::execution::{FieldValue, InMemoryLookupSource, StreamRecord};
+use ferrisstreams::ferris::sql::{SqlApplicationParser, StreamingSqlParser};
+use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Duration;
 
 // Test helper functions
@@ -610,3 +617,263 @@ async fn test_job_metrics_initialization() {
 
     println!("✅ Job metrics properly initialized");
 }
+
+#[tokio::test]
+async fn test_stop_job_with_savepoint_statement() {
+    println!("🧪 Testing STOP JOB WITH SAVEPOINT and SHOW SAVEPOINTS through the server");
+
+    let savepoints = tempfile::TempDir::new().unwrap();
+    let server = create_test_server(10)
+        .await
+        .with_savepoint_directory(savepoints.path());
+    server
+        .deploy_job(
+            "totals".to_string(),
+            "1.0".to_string(),
+            r#"SELECT customer_id, COUNT(*) AS orders FROM test_topic_source
+                GROUP BY customer_id
+                WITH (
+                    'test_topic_source.type' = 'kafka_source',
+                    'test_topic_source.bootstrap.servers' = 'localhost:9092',
+                    'test_topic_source.topic' = 'test_topic'
+                )"#
+            .to_string(),
+            "test_topic".to_string(),
+        )
+        .await
+        .unwrap();
+
+    let stopped = server
+        .execute_statement("STOP JOB totals WITH SAVEPOINT")
+        .await
+        .unwrap();
+    assert_eq!(stopped.len(), 1);
+    assert_eq!(
+        stopped[0].get("status"),
+        Some(&FieldValue::String("stopped".to_string()))
+    );
+    let Some(FieldValue::String(path)) = stopped[0].get("savepoint") else {
+        panic!("STOP JOB WITH SAVEPOINT should return the savepoint path");
+    };
+    assert!(std::path::Path::new(path).exists());
+    assert!(server.list_jobs().await.is_empty());
+
+    let listed = server
+        .execute_statement("SHOW SAVEPOINTS totals")
+        .await
+        .unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(
+        listed[0].get("path"),
+        Some(&FieldValue::String(path.clone()))
+    );
+    assert_eq!(
+        listed[0].get("job"),
+        Some(&FieldValue::String("totals".to_string()))
+    );
+    assert_eq!(
+        listed[0].get("operators"),
+        Some(&FieldValue::Array(vec![FieldValue::String(
+            "group_by:test_topic_source".to_string()
+        )]))
+    );
+    assert!(server
+        .execute_statement("SHOW SAVEPOINTS other")
+        .await
+        .unwrap()
+        .is_empty());
+
+    println!("✅ Savepoint written by STOP JOB and listed by SHOW SAVEPOINTS");
+}
+
+#[tokio::test]
+async fn test_stop_job_statement_errors() {
+    println!("🧪 Testing job management statements that cannot be executed");
+
+    let server = create_test_server(10).await;
+    assert!(server.execute_statement("STOP JOB missing").await.is_err());
+
+    // Without a savepoint directory the job keeps running
+    deploy_test_job(&server, "no_dir", "1.0").await.unwrap();
+    assert!(server
+        .execute_statement("STOP JOB no_dir WITH SAVEPOINT")
+        .await
+        .is_err());
+    assert_eq!(server.list_jobs().await.len(), 1);
+    let stopped = server.execute_statement("STOP JOB no_dir").await.unwrap();
+    assert!(!stopped[0].contains_key("savepoint"));
+    assert!(server.list_jobs().await.is_empty());
+
+    assert!(server
+        .execute_statement("SELECT * FROM orders")
+        .await
+        .is_err());
+
+    // The processors of a single query cannot write or list savepoints
+    let mut context = ProcessorContext::new("savepoints");
+    let record = StreamRecord::new(HashMap::new());
+    let stop = StreamingSqlParser::new()
+        .parse("STOP JOB totals WITH SAVEPOINT")
+        .unwrap();
+    assert!(JobProcessor::new()
+        .process(&stop, &mut context, &record)
+        .is_err());
+    let show = StreamingSqlParser::new().parse("SHOW SAVEPOINTS").unwrap();
+    assert!(QueryProcessor::process_query(&show, &record, &mut context).is_err());
+
+    println!("✅ Job management statements fail instead of doing nothing");
+}
+
+#[tokio::test]
+async fn test_alter_and_drop_checked_against_running_jobs() {
+    println!("🧪 Testing ALTER and DROP against the running jobs of the server");
+
+    let server = create_test_server(10).await;
+    server
+        .register_schema(
+            "orders",
+            Schema::new(vec![
+                FieldDefinition::required("customer_id".to_string(), DataType::BigInt),
+                FieldDefinition::optional("amount".to_string(), DataType::Float),
+            ]),
+        )
+        .await;
+    server
+        .deploy_job(
+            "totals".to_string(),
+            "1.0".to_string(),
+            r#"SELECT customer_id, amount FROM orders
+                WITH (
+                    'orders.type' = 'kafka_source',
+                    'orders.bootstrap.servers' = 'localhost:9092',
+                    'orders.topic' = 'orders'
+                )"#
+            .to_string(),
+            "orders".to_string(),
+        )
+        .await
+        .unwrap();
+
+    // The running job reads the stream
+    let error = server
+        .execute_statement("DROP STREAM orders")
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("totals"), "{}", error);
+    assert!(server
+        .execute_statement("ALTER STREAM orders SET ('retention.ms' = '1000')")
+        .await
+        .is_err());
+
+    // Optional columns keep the running job compatible
+    let altered = server
+        .execute_statement("ALTER STREAM orders ADD COLUMN region STRING")
+        .await
+        .unwrap();
+    assert_eq!(
+        altered[0].get("status"),
+        Some(&FieldValue::String("altered".to_string()))
+    );
+
+    let dropped = server.execute_statement("DROP JOB totals").await.unwrap();
+    assert_eq!(
+        dropped[0].get("status"),
+        Some(&FieldValue::String("dropped".to_string()))
+    );
+    assert!(server.list_jobs().await.is_empty());
+    assert!(server.execute_statement("DROP JOB totals").await.is_err());
+    assert!(server
+        .execute_statement("DROP JOB IF EXISTS totals")
+        .await
+        .unwrap()
+        .is_empty());
+
+    server
+        .execute_statement("ALTER STREAM orders SET ('retention.ms' = '1000')")
+        .await
+        .unwrap();
+    assert_eq!(
+        server
+            .object_properties("orders")
+            .await
+            .unwrap()
+            .get("retention.ms"),
+        Some(&"1000".to_string())
+    );
+    let dropped = server
+        .execute_statement("DROP STREAM orders")
+        .await
+        .unwrap();
+    assert_eq!(
+        dropped[0].get("status"),
+        Some(&FieldValue::String("dropped".to_string()))
+    );
+    assert!(server.object_properties("orders").await.is_none());
+    assert!(server
+        .execute_statement("DROP STREAM orders")
+        .await
+        .is_err());
+
+    println!("✅ ALTER and DROP refused while jobs depend on the stream, DROP JOB stops the job");
+}
+
+#[tokio::test]
+async fn test_deploy_resolves_subquery_sources() {
+    println!("🧪 Testing subquery sources resolved when a job is deployed");
+
+    let server = create_test_server(10).await;
+    let screened = |job: &str| {
+        server.deploy_job(
+            job.to_string(),
+            "1.0".to_string(),
+            r#"SELECT * FROM payments
+                WHERE account_id NOT IN (SELECT account_id FROM blocked_accounts)
+                WITH (
+                    'payments.type' = 'kafka_source',
+                    'payments.bootstrap.servers' = 'localhost:9092',
+                    'payments.topic' = 'payments'
+                )"#
+            .to_string(),
+            "payments".to_string(),
+        )
+    };
+
+    let error = screened("screened").await.unwrap_err().to_string();
+    assert!(error.contains("blocked_accounts"), "{}", error);
+    assert!(error.contains("lookup source"), "{}", error);
+
+    // A table materialized by another job lives in that job's engine
+    server
+        .deploy_job(
+            "blocklist".to_string(),
+            "1.0".to_string(),
+            r#"UPSERT INTO blocked_accounts KEY (account_id)
+                SELECT account_id, reason FROM blocks
+                WITH (
+                    'blocks.type' = 'kafka_source',
+                    'blocks.bootstrap.servers' = 'localhost:9092',
+                    'blocks.topic' = 'blocks',
+                    'blocked_accounts.type' = 'kafka_sink',
+                    'blocked_accounts.bootstrap.servers' = 'localhost:9092',
+                    'blocked_accounts.topic' = 'blocked_accounts'
+                )"#
+            .to_string(),
+            "blocks".to_string(),
+        )
+        .await
+        .unwrap();
+    let error = screened("screened").await.unwrap_err().to_string();
+    assert!(error.contains("job 'blocklist'"), "{}", error);
+    assert_eq!(server.list_jobs().await.len(), 1);
+
+    server
+        .register_lookup_source(
+            "blocked_accounts",
+            Arc::new(InMemoryLookupSource::new(Vec::new())),
+        )
+        .await;
+    screened("screened").await.unwrap();
+    assert_eq!(server.list_jobs().await.len(), 2);
+
+    println!("✅ Unresolved subquery sources fail the deploy");
+}
@@ -29,11 +29,12 @@ fn create_test_record(id: i64, name: &str, value: f64) -> StreamRecord {
 
 /// Helper function to create a simple test query
 fn create_simple_query() -> ferrisstreams::ferris::sql::ast::StreamingQuery {
-    use ferrisstreams::ferris::sql::ast::{SelectField, StreamSource, StreamingQuery};
+    use ferrisstreams::ferris::sql::ast::{NodeSpan, SelectField, StreamSource, StreamingQuery};
 
     StreamingQuery::Select {
         fields: vec![SelectField::Wildcard],
         from: StreamSource::Stream("test_stream".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         group_by: None,
@@ -43,6 +44,7 @@ fn create_simple_query() -> ferrisstreams::ferris::sql::ast::StreamingQuery {
         limit: None,
         emit_mode: None,
         properties: None,
+        span: NodeSpan::default(),
     }
 }
 
@@ -193,6 +195,31 @@ async fn test_process_batch_common_success() {
     assert!(result.error_details.is_empty());
 }
 
+#[tokio::test]
+async fn test_process_batch_with_output_reports_columnar_failures() {
+    let records = vec![
+        create_test_record(1, "alice", 100.0),
+        create_test_record(2, "bob", 200.0),
+    ];
+
+    // Results cannot be published once the output channel is closed
+    let (output_sender, output_receiver) = tokio::sync::mpsc::unbounded_channel();
+    drop(output_receiver);
+    let mut engine = StreamExecutionEngine::new(output_sender);
+    let query = create_simple_query();
+    engine.plan_query(&query);
+    engine.set_columnar_execution(true);
+    let engine = Arc::new(Mutex::new(engine));
+
+    let result = process_batch_with_output(records, &engine, &query, "test_columnar").await;
+
+    assert_eq!(result.records_processed, 0);
+    assert_eq!(result.records_failed, 2);
+    assert!(result.output_records.is_empty());
+    assert_eq!(result.error_details.len(), 2);
+    assert!(result.error_details[0].error_message.contains("channel"));
+}
+
 #[test]
 fn test_job_processing_config_default() {
     let config = JobProcessingConfig::default();
@@ -99,6 +99,7 @@ async fn test_your_processor_comprehensive_failure_scenarios() {
         max_retries: 1,
         retry_backoff: Duration::from_millis(50),
         progress_interval: 1,
+        columnar_execution: false,
         log_progress: true,
     };
     let log_continue_processor = YourJobProcessorWrapper::new(log_continue_config);
@@ -115,6 +116,7 @@ async fn test_your_processor_comprehensive_failure_scenarios() {
         max_retries: 2,
         retry_backoff: Duration::from_millis(50),
         progress_interval: 1,
+        columnar_execution: false,
         log_progress: true,
     };
     let retry_backoff_processor = YourJobProcessorWrapper::new(retry_backoff_config);
@@ -159,6 +161,7 @@ async fn test_your_processor_comprehensive_failure_scenarios() {
         max_retries: 1,
         retry_backoff: Duration::from_millis(50),
         progress_interval: 1,
+        columnar_execution: false,
         log_progress: true,
     };
     let fail_batch_processor = YourJobProcessorWrapper::new(fail_batch_config);
@@ -192,6 +195,7 @@ async fn test_your_processor_specific_behavior() {
         max_retries: 1,
         retry_backoff: Duration::from_millis(50),
         progress_interval: 1,
+        columnar_execution: false,
         log_progress: true,
     };
 
@@ -261,6 +265,7 @@ async fn test_streaming_processor_high_throughput() {
         max_retries: 0, // No retries for maximum throughput
         retry_backoff: Duration::from_millis(1),
         progress_interval: 100,
+        columnar_execution: false,
         log_progress: false, // Minimize logging overhead
     };
 
@@ -305,6 +310,7 @@ async fn test_batch_processor_timing() {
         max_retries: 1,
         retry_backoff: Duration::from_millis(100),
         progress_interval: 1,
+        columnar_execution: false,
         log_progress: true,
     };
 
@@ -22,6 +22,7 @@ use tokio::sync::{mpsc, Mutex};
 
 // Import test utilities
 use super::stream_job_test_utils::*;
+use ferrisstreams::ferris::sql::ast::NodeSpan;
 
 /// Mock datasource reader for testing
 pub struct MockDataReader {
@@ -215,6 +216,7 @@ fn create_test_query() -> StreamingQuery {
     StreamingQuery::Select {
         fields: vec![SelectField::Wildcard],
         from: StreamSource::Stream("test_stream".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         group_by: None,
@@ -224,6 +226,7 @@ fn create_test_query() -> StreamingQuery {
         limit: None,
         emit_mode: None,
         properties: None,
+        span: NodeSpan::default(),
     }
 }
 
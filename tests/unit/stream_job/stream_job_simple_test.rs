@@ -9,7 +9,9 @@ use super::stream_job_test_infrastructure::{
 };
 
 use async_trait::async_trait;
-use ferrisstreams::ferris::datasource::{DataReader, DataWriter, SourceOffset};
+use ferrisstreams::ferris::datasource::file::reader::FileReader;
+use ferrisstreams::ferris::datasource::file::{FileFormat, FileSourceConfig};
+use ferrisstreams::ferris::datasource::{BatchConfig, DataReader, DataWriter, SourceOffset};
 use ferrisstreams::ferris::server::processors::{
     common::{
         process_batch_with_output, DataSourceResult, FailureStrategy, JobExecutionStats,
@@ -17,8 +19,11 @@ use ferrisstreams::ferris::server::processors::{
     },
     simple::SimpleJobProcessor,
 };
+use ferrisstreams::ferris::sql::parser::StreamingSqlParser;
+use ferrisstreams::ferris::sql::QueryOptimizer;
 use ferrisstreams::ferris::sql::{
     ast::{SelectField, StreamSource, StreamingQuery},
+    error::recovery::DeadLetterQueue,
     execution::{
         engine::StreamExecutionEngine,
         types::{FieldValue, StreamRecord},
@@ -290,6 +295,7 @@ async fn test_simple_processor_comprehensive_failure_scenarios() {
         max_retries: 1,
         retry_backoff: Duration::from_millis(50),
         progress_interval: 1,
+        columnar_execution: false,
         log_progress: true,
     };
     let log_continue_processor = SimpleJobProcessorWrapper::new(log_continue_config);
@@ -317,6 +323,7 @@ async fn test_simple_processor_comprehensive_failure_scenarios() {
         max_retries: 2,
         retry_backoff: Duration::from_millis(50),
         progress_interval: 1,
+        columnar_execution: false,
         log_progress: true,
     };
     let retry_backoff_processor = SimpleJobProcessorWrapper::new(retry_backoff_config);
@@ -401,6 +408,7 @@ async fn test_simple_processor_comprehensive_failure_scenarios() {
         max_retries: 1,
         retry_backoff: Duration::from_millis(50),
         progress_interval: 1,
+        columnar_execution: false,
         log_progress: true,
     };
     let fail_batch_processor = SimpleJobProcessorWrapper::new(fail_batch_config);
@@ -440,6 +448,7 @@ async fn test_simple_processor_with_different_failure_strategies() {
             max_retries: 1,
             retry_backoff: Duration::from_millis(50),
             progress_interval: 1,
+            columnar_execution: false,
             log_progress: true,
         };
 
@@ -505,6 +514,7 @@ async fn test_simple_processor_transaction_detection() {
         max_retries: 1,
         retry_backoff: Duration::from_millis(50),
         progress_interval: 1,
+        columnar_execution: false,
         log_progress: true,
     };
 
@@ -565,6 +575,7 @@ async fn test_sink_failure_with_log_and_continue_strategy() {
         max_retries: 1,
         retry_backoff: Duration::from_millis(100),
         progress_interval: 1,
+        columnar_execution: false,
         log_progress: true,
     };
 
@@ -627,6 +638,7 @@ async fn test_sink_failure_with_retry_with_backoff_strategy() {
         max_retries: 2,
         retry_backoff: Duration::from_millis(50),
         progress_interval: 1,
+        columnar_execution: false,
         log_progress: true,
     };
 
@@ -683,6 +695,7 @@ async fn test_sink_failure_with_fail_batch_strategy() {
         max_retries: 1,
         retry_backoff: Duration::from_millis(50),
         progress_interval: 1,
+        columnar_execution: false,
         log_progress: true,
     };
 
@@ -757,6 +770,7 @@ async fn test_simple_processor_with_10000_records() {
         max_retries: 3,
         retry_backoff: Duration::from_millis(100),
         progress_interval: 10, // Log every 10 batches for visibility
+        columnar_execution: false,
         log_progress: true,
     };
 
@@ -833,3 +847,331 @@ async fn test_simple_processor_with_10000_records() {
         );
     }
 }
+
+fn schema_enforcement_config() -> JobProcessingConfig {
+    JobProcessingConfig {
+        use_transactions: false,
+        failure_strategy: FailureStrategy::LogAndContinue,
+        max_batch_size: 10,
+        batch_timeout: Duration::from_millis(100),
+        max_retries: 1,
+        retry_backoff: Duration::from_millis(10),
+        progress_interval: 1,
+        columnar_execution: false,
+        log_progress: false,
+    }
+}
+
+fn typed_stream_query(policy: &str) -> StreamingQuery {
+    StreamingSqlParser::new()
+        .parse(&format!(
+            "CREATE STREAM typed_orders (id BIGINT NOT NULL, value STRING) \
+             AS SELECT * FROM test_stream WITH ('schema.enforcement' = '{}')",
+            policy
+        ))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_schema_violations_are_sent_to_dead_letter_queue() {
+    let mut missing_id = create_test_record(2);
+    missing_id.fields.remove("id");
+    let test_batches = vec![vec![
+        create_test_record(1),
+        missing_id,
+        create_test_record(3),
+    ]];
+
+    let (output_tx, _output_rx) = mpsc::unbounded_channel();
+    let engine = Arc::new(Mutex::new(StreamExecutionEngine::new(output_tx)));
+    let dlq = Arc::new(DeadLetterQueue::new("typed_orders_dlq").await.unwrap());
+    let processor =
+        SimpleJobProcessor::new(schema_enforcement_config()).with_dead_letter_queue(dlq.clone());
+    let reader = Box::new(MockDataReader::new(test_batches)) as Box<dyn DataReader>;
+    let writer = Box::new(MockDataWriter::new(None)) as Box<dyn DataWriter>;
+    let (_shutdown_tx, shutdown_rx) = mpsc::channel(1);
+
+    let stats = processor
+        .process_job(
+            reader,
+            Some(writer),
+            engine,
+            typed_stream_query("dlq"),
+            "test_schema_dlq".to_string(),
+            shutdown_rx,
+        )
+        .await
+        .expect("rejections should not fail the job");
+
+    assert_eq!(stats.records_failed, 1);
+    assert_eq!(stats.records_processed, 2);
+
+    let rejected = dlq.dequeue(10).await.unwrap();
+    assert_eq!(rejected.len(), 1);
+    assert_eq!(rejected[0].id, "typed_orders-0-2");
+    assert!(
+        rejected[0]
+            .error_details
+            .contains("NOT NULL column is missing"),
+        "{}",
+        rejected[0].error_details
+    );
+    assert_eq!(
+        rejected[0].headers.get("schema.violation.column"),
+        Some(&"id".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_pushdown_filter_failures_are_sent_to_dead_letter_queue() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("orders.jsonl");
+    std::fs::write(
+        &path,
+        "{\"id\": 2, \"amount\": 50}\n{\"id\": 3, \"amount\": \"n/a\"}\n{\"id\": 4, \"amount\": 5}\n",
+    )
+    .unwrap();
+
+    let optimized = QueryOptimizer::new().optimize(
+        &StreamingSqlParser::new()
+            .parse("SELECT id FROM orders WHERE amount > 10")
+            .unwrap(),
+    );
+    let reader = FileReader::new_with_batch_config(
+        FileSourceConfig::new(path.to_string_lossy().to_string(), FileFormat::JsonLines),
+        BatchConfig {
+            enable_batching: false,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    let mut readers: HashMap<String, Box<dyn DataReader>> = HashMap::new();
+    readers.insert("source_0_orders".to_string(), Box::new(reader));
+    let query = optimized.push_down_into(&mut readers);
+    let reader = readers.remove("source_0_orders").unwrap();
+
+    let (output_tx, _output_rx) = mpsc::unbounded_channel();
+    let engine = Arc::new(Mutex::new(StreamExecutionEngine::new(output_tx)));
+    let dlq = Arc::new(DeadLetterQueue::new("orders_dlq").await.unwrap());
+    let processor =
+        SimpleJobProcessor::new(schema_enforcement_config()).with_dead_letter_queue(dlq.clone());
+    let writer = Box::new(MockDataWriter::new(None)) as Box<dyn DataWriter>;
+    let (_shutdown_tx, shutdown_rx) = mpsc::channel(1);
+
+    let stats = processor
+        .process_job(
+            reader,
+            Some(writer),
+            engine,
+            query,
+            "test_pushdown_dlq".to_string(),
+            shutdown_rx,
+        )
+        .await
+        .expect("filter failures should not fail the job");
+
+    // The string amount cannot be compared: it fails instead of being filtered out
+    assert_eq!(stats.records_failed, 1);
+    assert_eq!(stats.records_processed, 1);
+
+    let rejected = dlq.dequeue(10).await.unwrap();
+    assert_eq!(rejected.len(), 1);
+    assert!(rejected[0].original_data.contains("n/a"));
+    assert!(rejected[0].headers.contains_key("pushdown.filter.error"));
+}
+
+#[tokio::test]
+async fn test_schema_violation_fails_job_under_fail_policy() {
+    let mut bad_id = create_test_record(2);
+    bad_id
+        .fields
+        .insert("id".to_string(), FieldValue::String("two".to_string()));
+    let test_batches = vec![vec![create_test_record(1)], vec![bad_id]];
+
+    let processor = SimpleJobProcessor::new(schema_enforcement_config());
+    let reader = Box::new(MockDataReader::new(test_batches)) as Box<dyn DataReader>;
+    let writer = Box::new(MockDataWriter::new(None)) as Box<dyn DataWriter>;
+    let (_shutdown_tx, shutdown_rx) = mpsc::channel(1);
+
+    let result = tokio::time::timeout(
+        Duration::from_secs(5),
+        processor.process_job(
+            reader,
+            Some(writer),
+            create_test_engine(),
+            typed_stream_query("fail"),
+            "test_schema_fail".to_string(),
+            shutdown_rx,
+        ),
+    )
+    .await
+    .expect("a schema violation must stop the job instead of retrying");
+
+    let error = result.expect_err("the job should fail");
+    assert!(
+        error.to_string().contains("typed_orders"),
+        "unexpected error: {}",
+        error
+    );
+}
+
+/// Writer that records keyed upserts and deletes
+struct UpsertRecordingWriter {
+    upserts: Arc<Mutex<Vec<(String, StreamRecord)>>>,
+    deletes: Arc<Mutex<Vec<String>>>,
+}
+
+#[async_trait]
+impl DataWriter for UpsertRecordingWriter {
+    async fn write(
+        &mut self,
+        _record: StreamRecord,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Err("keyed tables must be written through update".into())
+    }
+
+    async fn write_batch(
+        &mut self,
+        _records: Vec<StreamRecord>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Err("keyed tables must be written through update".into())
+    }
+
+    async fn update(
+        &mut self,
+        key: &str,
+        record: StreamRecord,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.upserts.lock().await.push((key.to_string(), record));
+        Ok(())
+    }
+
+    async fn delete(&mut self, key: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.deletes.lock().await.push(key.to_string());
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    async fn commit(&mut self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    async fn rollback(&mut self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    fn supports_upsert(&self) -> bool {
+        true
+    }
+}
+
+#[tokio::test]
+async fn test_table_rows_are_upserted_by_primary_key_and_checked() {
+    let mut negative = create_test_record(2);
+    negative
+        .fields
+        .insert("id".to_string(), FieldValue::Integer(-2));
+    let test_batches = vec![vec![create_test_record(1), negative, create_test_record(3)]];
+
+    let (output_tx, _output_rx) = mpsc::unbounded_channel();
+    let engine = Arc::new(Mutex::new(StreamExecutionEngine::new(output_tx)));
+    let dlq = Arc::new(DeadLetterQueue::new("keyed_dlq").await.unwrap());
+    let upserts = Arc::new(Mutex::new(Vec::new()));
+    let processor =
+        SimpleJobProcessor::new(schema_enforcement_config()).with_dead_letter_queue(dlq.clone());
+    let reader = Box::new(MockDataReader::new(test_batches)) as Box<dyn DataReader>;
+    let writer = Box::new(UpsertRecordingWriter {
+        upserts: upserts.clone(),
+        deletes: Arc::new(Mutex::new(Vec::new())),
+    }) as Box<dyn DataWriter>;
+    let query = StreamingSqlParser::new()
+        .parse(
+            "CREATE TABLE latest_values (id BIGINT PRIMARY KEY CHECK (id > 0), value STRING) \
+             AS SELECT * FROM test_stream",
+        )
+        .unwrap();
+    let (_shutdown_tx, shutdown_rx) = mpsc::channel(1);
+
+    let stats = processor
+        .process_job(
+            reader,
+            Some(writer),
+            engine,
+            query,
+            "test_keyed_table".to_string(),
+            shutdown_rx,
+        )
+        .await
+        .unwrap();
+    assert_eq!(stats.records_failed, 1);
+
+    let keys: Vec<String> = upserts
+        .lock()
+        .await
+        .iter()
+        .map(|(key, _)| key.clone())
+        .collect();
+    assert_eq!(keys, vec!["1".to_string(), "3".to_string()]);
+
+    let rejected = dlq.dequeue(10).await.unwrap();
+    assert_eq!(rejected.len(), 1);
+    assert_eq!(
+        rejected[0].headers.get("constraint.violation.name"),
+        Some(&"latest_values_check".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_merge_changes_are_applied_as_sink_updates_and_deletes() {
+    // test_stream records carry id and value = 'test_<id>'; id 1 arrives twice
+    let test_batches = vec![vec![
+        create_test_record(1),
+        create_test_record(2),
+        create_test_record(1),
+    ]];
+
+    let (output_tx, _output_rx) = mpsc::unbounded_channel();
+    let engine = Arc::new(Mutex::new(StreamExecutionEngine::new(output_tx)));
+    let upserts = Arc::new(Mutex::new(Vec::new()));
+    let deletes = Arc::new(Mutex::new(Vec::new()));
+    let processor = SimpleJobProcessor::new(schema_enforcement_config());
+    let reader = Box::new(MockDataReader::new(test_batches)) as Box<dyn DataReader>;
+    let writer = Box::new(UpsertRecordingWriter {
+        upserts: upserts.clone(),
+        deletes: deletes.clone(),
+    }) as Box<dyn DataWriter>;
+    let query = StreamingSqlParser::new()
+        .parse(
+            "MERGE INTO latest_values t USING test_stream s ON t.id = s.id \
+             WHEN MATCHED THEN DELETE \
+             WHEN NOT MATCHED THEN INSERT (id, value) VALUES (s.id, s.value)",
+        )
+        .unwrap();
+    let (_shutdown_tx, shutdown_rx) = mpsc::channel(1);
+
+    let stats = processor
+        .process_job(
+            reader,
+            Some(writer),
+            engine,
+            query,
+            "test_merge_job".to_string(),
+            shutdown_rx,
+        )
+        .await
+        .unwrap();
+    assert_eq!(stats.records_failed, 0);
+
+    let upserts = upserts.lock().await;
+    let keys: Vec<&str> = upserts.iter().map(|(key, _)| key.as_str()).collect();
+    assert_eq!(keys, vec!["1", "2"]);
+    assert_eq!(
+        upserts[1].1.fields.get("value"),
+        Some(&FieldValue::String("test_2".to_string()))
+    );
+    assert_eq!(*deletes.lock().await, vec!["1".to_string()]);
+}
@@ -150,7 +150,7 @@ async fn test_process_datasource_with_shutdown() {
                     fields.insert("value".to_string(), FieldValue::String("test".to_string()));
 
                     records.push(StreamRecord {
-                        fields,
+                        fields: fields.into(),
                         timestamp: chrono::Utc::now().timestamp_millis(),
                         offset: 0,
                         partition: 0,
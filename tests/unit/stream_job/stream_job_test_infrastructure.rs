@@ -8,6 +8,7 @@ use ferrisstreams::ferris::datasource::{DataReader, DataWriter, SourceOffset};
 use ferrisstreams::ferris::server::processors::common::{
     FailureStrategy, JobExecutionStats, JobProcessingConfig,
 };
+use ferrisstreams::ferris::sql::ast::NodeSpan;
 use ferrisstreams::ferris::sql::{
     ast::{SelectField, StreamSource, StreamingQuery},
     execution::{
@@ -363,6 +364,7 @@ pub fn create_test_query() -> StreamingQuery {
     StreamingQuery::Select {
         fields: vec![SelectField::Wildcard],
         from: StreamSource::Stream("test_stream".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         group_by: None,
@@ -372,6 +374,7 @@ pub fn create_test_query() -> StreamingQuery {
         limit: None,
         emit_mode: None,
         properties: None,
+        span: NodeSpan::default(),
     }
 }
 
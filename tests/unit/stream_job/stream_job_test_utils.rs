@@ -8,6 +8,7 @@ use ferrisstreams::ferris::datasource::{DataReader, DataWriter};
 use ferrisstreams::ferris::server::processors::{
     common::*, simple::SimpleJobProcessor, transactional::TransactionalJobProcessor,
 };
+use ferrisstreams::ferris::sql::ast::NodeSpan;
 use ferrisstreams::ferris::sql::{
     ast::{SelectField, StreamSource, StreamingQuery},
     execution::types::{FieldValue, StreamRecord},
@@ -298,6 +299,7 @@ pub fn create_test_query() -> StreamingQuery {
     StreamingQuery::Select {
         fields: vec![SelectField::Wildcard],
         from: StreamSource::Stream("test_stream".to_string()),
+        from_alias: None,
         joins: None,
         where_clause: None,
         group_by: None,
@@ -307,6 +309,7 @@ pub fn create_test_query() -> StreamingQuery {
         limit: None,
         emit_mode: None,
         properties: None,
+        span: NodeSpan::default(),
     }
 }
 
@@ -87,6 +87,7 @@ async fn test_transactional_processor_comprehensive_failure_scenarios() {
         max_retries: 1,
         retry_backoff: Duration::from_millis(50),
         progress_interval: 1,
+        columnar_execution: false,
         log_progress: true,
     };
     let log_continue_processor = TransactionalJobProcessorWrapper::new(log_continue_config);
@@ -116,6 +117,7 @@ async fn test_transactional_processor_comprehensive_failure_scenarios() {
         max_retries: 2,
         retry_backoff: Duration::from_millis(50),
         progress_interval: 1,
+        columnar_execution: false,
         log_progress: true,
     };
     let retry_backoff_processor = TransactionalJobProcessorWrapper::new(retry_backoff_config);
@@ -196,6 +198,7 @@ async fn test_transactional_processor_comprehensive_failure_scenarios() {
         max_retries: 1,
         retry_backoff: Duration::from_millis(50),
         progress_interval: 1,
+        columnar_execution: false,
         log_progress: true,
     };
     let fail_batch_processor = TransactionalJobProcessorWrapper::new(fail_batch_config);
@@ -234,6 +237,7 @@ async fn test_transactional_processor_rollback_behavior() {
         max_retries: 1,
         retry_backoff: Duration::from_millis(50),
         progress_interval: 1,
+        columnar_execution: false,
         log_progress: true,
     };
 
@@ -282,6 +286,7 @@ async fn test_transactional_processor_vs_simple_processor_behavior() {
         max_retries: 1,
         retry_backoff: Duration::from_millis(50),
         progress_interval: 1,
+        columnar_execution: false,
         log_progress: true,
     };
 
@@ -349,6 +354,7 @@ async fn test_transactional_processor_with_non_transactional_datasources() {
         max_retries: 1,
         retry_backoff: Duration::from_millis(50),
         progress_interval: 1,
+        columnar_execution: false,
         log_progress: true,
     };
 
@@ -418,6 +424,7 @@ async fn test_transactional_processor_commit_ordering() {
         max_retries: 1,
         retry_backoff: Duration::from_millis(50),
         progress_interval: 1,
+        columnar_execution: false,
         log_progress: true,
     };
 
@@ -475,6 +482,7 @@ async fn test_transactional_processor_sink_commit_failure() {
         max_retries: 1,
         retry_backoff: Duration::from_millis(50),
         progress_interval: 1,
+        columnar_execution: false,
         log_progress: true,
     };
 